@@ -19,6 +19,7 @@ enum JobStatus {
     Failed,
     InProgress,
     Successful,
+    ArtifactCorrupted,
 }
 
 #[derive(Parser)]
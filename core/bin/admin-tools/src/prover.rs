@@ -72,7 +72,7 @@ pub fn print_stats(stats: &ProverStats, term_width: u32) -> Result<(), AppError>
                     ProverJobStatus::Successful(_) => &mut s,
                     ProverJobStatus::InProgress(_) => &mut i,
                     ProverJobStatus::Queued => &mut q,
-                    ProverJobStatus::Failed(_) => &mut f,
+                    ProverJobStatus::Failed(_) | ProverJobStatus::ArtifactCorrupted => &mut f,
                     ProverJobStatus::Skipped => &mut sk,
                     _ => unreachable!(),
                 })[ix] = true;
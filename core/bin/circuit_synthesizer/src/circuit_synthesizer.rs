@@ -18,11 +18,14 @@ use zksync_config::configs::prover_group::ProverGroupConfig;
 use zksync_config::ProverConfigs;
 use zksync_dal::ConnectionPool;
 use zksync_dal::gpu_prover_queue_dal::{GpuProverInstanceStatus, SocketAddress};
+use zksync_dal::job_queue_dal::JobStatus;
 use zksync_object_store::gcs_utils::prover_circuit_input_blob_url;
-use zksync_object_store::object_store::{create_object_store_from_env, PROVER_JOBS_BUCKET_PATH};
-use zksync_prover_utils::numeric_index_to_circuit_name;
-use zksync_prover_utils::region_fetcher::get_region;
-use zksync_queued_job_processor::{async_trait, JobProcessor};
+use zksync_object_store::object_store::{
+    create_object_store_from_env, ObjectStoreError, PROVER_JOBS_BUCKET_PATH,
+};
+use zksync_prover_utils::circuit_type::CircuitType;
+use zksync_prover_utils::region_fetcher::get_node_location;
+use zksync_queued_job_processor::{async_trait, worker_id, JobProcessor};
 use zksync_types::proofs::ProverJobMetadata;
 
 pub struct CircuitSynthesizer {
@@ -37,12 +40,13 @@ impl CircuitSynthesizer {
     pub fn synthesize(
         circuit: ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>,
     ) -> (ProvingAssembly, u8) {
+        let _log_context = vlog::with_circuit(circuit.numeric_circuit_type() as u32).enter();
         let circuit_synthesis_started_at = Instant::now();
         let mut assembly = Prover::new_proving_assembly();
         circuit
             .synthesize(&mut assembly)
             .expect("circuit synthesize failed");
-        let circuit_type = numeric_index_to_circuit_name(circuit.numeric_circuit_type()).unwrap();
+        let circuit_type = CircuitType::try_from(circuit.numeric_circuit_type()).unwrap();
         vlog::info!(
             "Finished circuit synthesis for circuit: {} took {:?} seconds",
             circuit_type,
@@ -51,7 +55,7 @@ impl CircuitSynthesizer {
         metrics::histogram!(
             "server.circuit_synthesizer.synthesize",
             circuit_synthesis_started_at.elapsed().as_secs() as f64,
-            "circuit_type" => circuit_type,
+            "circuit_type" => circuit_type.name(),
         );
 
         // we don't perform assembly finalization here since it increases the assembly size significantly due to padding.
@@ -61,7 +65,7 @@ impl CircuitSynthesizer {
 
 fn get_circuit(
     prover_job_metadata: ProverJobMetadata,
-) -> ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>> {
+) -> Result<ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>, ObjectStoreError> {
     let circuit_input_blob_url = prover_circuit_input_blob_url(
         prover_job_metadata.block_number,
         prover_job_metadata.sequence_number,
@@ -69,12 +73,11 @@ fn get_circuit(
         prover_job_metadata.aggregation_round,
     );
     let object_store = create_object_store_from_env();
-    let circuit_input = object_store
-        .get(PROVER_JOBS_BUCKET_PATH, circuit_input_blob_url)
-        .expect("Failed fetching prover jobs from GCS");
+    let circuit_input = object_store.get(PROVER_JOBS_BUCKET_PATH, circuit_input_blob_url)?;
 
-    bincode::deserialize::<ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>(&circuit_input)
-        .expect("Failed to deserialize circuit input")
+    bincode::deserialize::<ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>(&circuit_input).map_err(
+        |err| ObjectStoreError::Corrupted(format!("Failed to deserialize circuit input: {}", err)),
+    )
 }
 
 #[async_trait]
@@ -99,21 +102,34 @@ impl JobProcessor for CircuitSynthesizer {
         }
         vlog::info!("Fetching prover jobs for group: {} and circuits: {:?}", config.prover_group_id, circuit_ids);
         let circuit_types: Vec<String> = circuit_ids.iter()
-            .map(|&id| numeric_index_to_circuit_name(id).unwrap_or_else(|| panic!("unknown id :{}", id)).to_string())
+            .map(|&id| CircuitType::try_from(id).unwrap_or_else(|err| panic!("{}", err)).to_string())
             .collect();
         let prover_job = if circuit_types.is_empty() {
             connection_pool
                 .access_storage_blocking()
                 .prover_dal()
-                .get_next_prover_job(self.config.generation_timeout(), self.config.max_attempts)?
+                .get_next_prover_job(self.config.generation_timeout(), self.config.max_attempts, &worker_id())?
         } else {
             connection_pool
                 .access_storage_blocking()
                 .prover_dal()
-                .get_next_prover_job_by_circuit_types(self.config.generation_timeout(), self.config.max_attempts, circuit_types)?
+                .get_next_prover_job_by_circuit_types(self.config.generation_timeout(), self.config.max_attempts, circuit_types, &worker_id())?
         };
         let job_id = prover_job.id;
-        Some((job_id, get_circuit(prover_job)))
+        match get_circuit(prover_job) {
+            Ok(circuit) => Some((job_id, circuit)),
+            // Retrying the job won't help if its input is corrupted.
+            Err(ObjectStoreError::Corrupted(error)) => {
+                vlog::error!("Circuit input of prover job {} is corrupted: {}", job_id, error);
+                metrics::counter!("server.circuit_synthesizer.corrupted_artifacts", 1);
+                connection_pool
+                    .access_storage_blocking()
+                    .prover_dal()
+                    .mark_prover_job_as_artifact_corrupted(job_id, error);
+                None
+            }
+            Err(err) => panic!("Failed fetching prover job {} from GCS: {}", job_id, err),
+        }
     }
 
     async fn save_failure(
@@ -142,8 +158,8 @@ impl JobProcessor for CircuitSynthesizer {
         _started_at: Instant,
         artifacts: Self::JobArtifacts,
     ) {
-        let region = get_region().await;
-        vlog::info!("Finished circuit synthesis for job: {} in region: {}", job_id, region);
+        let location = get_node_location().await;
+        vlog::info!("Finished circuit synthesis for job: {} in region: {}", job_id, location.region);
         let config: CircuitSynthesizerConfig = CircuitSynthesizerConfig::from_env();
         let (assembly, circuit_id) = artifacts;
         let now = Instant::now();
@@ -152,7 +168,7 @@ impl JobProcessor for CircuitSynthesizer {
                 .clone()
                 .access_storage_blocking()
                 .gpu_prover_queue_dal()
-                .get_free_prover_instance(config.gpu_prover_queue_timeout(), config.prover_group_id, region.clone());
+                .get_free_prover_instance(config.gpu_prover_queue_timeout(), config.prover_group_id, location.region.clone(), location.zone.clone());
             match optional_prover_instance {
                 Some(address) => {
                     vlog::info!(
@@ -242,7 +258,7 @@ fn handle_successful_sent_assembly(job_id: u32, pool: ConnectionPool) {
     pool.clone()
         .access_storage_blocking()
         .prover_dal()
-        .update_status(job_id, "in_gpu_proof");
+        .update_status(job_id, JobStatus::InGpuProof);
 }
 
 fn handle_unreachable_prover_instance(job_id: u32, address: SocketAddress, pool: ConnectionPool) {
@@ -10,7 +10,7 @@ use tokio::time;
 
 use zksync_config::ContractVerifierConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_queued_job_processor::{async_trait, JobProcessor};
+use zksync_queued_job_processor::{async_trait, worker_id, JobProcessor};
 use zksync_types::explorer_api::{
     CompilationArtifacts, DeployContractCalldata, SourceCodeData, VerificationInfo,
     VerificationRequest,
@@ -287,7 +287,10 @@ impl JobProcessor for ContractVerifier {
         let job = connection
             .explorer()
             .contract_verification_dal()
-            .get_next_queued_verification_request(self.config.compilation_timeout() + TIME_OVERHEAD)
+            .get_next_queued_verification_request(
+                self.config.compilation_timeout() + TIME_OVERHEAD,
+                &worker_id(),
+            )
             .unwrap();
 
         job.map(|job| (job.id, job))
@@ -1,5 +1,8 @@
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::env;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use api::gpu_prover;
@@ -21,7 +24,10 @@ use zksync_dal::ConnectionPool;
 use crate::artifact_provider::ProverArtifactProvider;
 use crate::prover::ProverReporter;
 use crate::prover_params::ProverParams;
-use zksync_prover_utils::region_fetcher::get_region;
+use zksync_prover_utils::region_fetcher::get_node_location;
+use zksync_prover_utils::key_cleanup::{cleanup_obsolete_setup_keys, SetupKeysCleanup};
+use zksync_prover_utils::key_store::{create_setup_key_store, SetupKeyStoreMode};
+use zksync_prover_utils::{circuit_type::CircuitType, DownloadRetryPolicy};
 use crate::socket_listener::incoming_socket_listener;
 use crate::synthesized_circuit_provider::SynthesizedCircuitProvider;
 
@@ -31,6 +37,9 @@ mod prover_params;
 mod socket_listener;
 mod synthesized_circuit_provider;
 
+/// Max number of setup keys downloaded at the same time on startup.
+const MAX_CONCURRENT_SETUP_KEY_DOWNLOADS: usize = 4;
+
 pub async fn wait_for_tasks(task_futures: Vec<JoinHandle<()>>) {
     match future::select_all(task_futures).await.0 {
         Ok(_) => {
@@ -113,7 +122,7 @@ async fn main() {
         ),
         None => vlog::info!("No sentry url configured"),
     }
-    let region = get_region().await;
+    let location = get_node_location().await;
 
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
 
@@ -126,10 +135,18 @@ async fn main() {
         .expect("Error setting Ctrl+C handler");
     }
 
-    zksync_prover_utils::ensure_initial_setup_keys_present(
+    let setup_key_store = create_setup_key_store(SetupKeyStoreMode::from_config(
+        prover_config.setup_key_store_mode.as_deref(),
+    ))
+    .await;
+    zksync_prover_utils::ensure_initial_setup_keys_present_async(
+        setup_key_store.as_ref(),
         &prover_config.initial_setup_key_path,
         &prover_config.key_download_url,
-    );
+        prover_config.initial_setup_key_checksum.as_deref(),
+        DownloadRetryPolicy::from_config(&prover_config),
+    )
+    .await;
     env::set_var("CRS_FILE", prover_config.initial_setup_key_path.clone());
 
     let circuit_breaker_checker = CircuitBreakerChecker::new(
@@ -148,7 +165,52 @@ async fn main() {
     let circuit_ids = ProverGroupConfig::from_env()
         .get_circuit_ids_for_group_id(prover_config.specialized_prover_group_id);
 
-    vlog::info!("Starting proof generation for circuits: {:?} in region: {} with group-id: {}", circuit_ids, region, prover_config.specialized_prover_group_id);
+    vlog::info!("Starting proof generation for circuits: {:?} in region: {} with group-id: {}", circuit_ids, location.region, prover_config.specialized_prover_group_id);
+
+    if let Some(protocol_version) = prover_config.setup_keys_protocol_version {
+        let keys_path = Path::new(&prover_config.setup_keys_path);
+        assert_eq!(
+            keys_path.file_name(),
+            Some(OsStr::new(&format!("v{}", protocol_version))),
+            "setup_keys_path must point to the keys of protocol version {}",
+            protocol_version
+        );
+        let options = SetupKeysCleanup {
+            active_protocol_versions: prover_config
+                .setup_keys_active_protocol_versions
+                .clone()
+                .unwrap_or_default(),
+            archive_path: prover_config.setup_keys_archive_path.as_ref().map(PathBuf::from),
+            dry_run: prover_config.setup_keys_cleanup_dry_run.unwrap_or(false),
+        };
+        // Obsolete keys only take disk space, so failing to clean them up doesn't prevent the prover from starting.
+        if let Err(err) =
+            cleanup_obsolete_setup_keys(keys_path.parent().unwrap(), protocol_version, &options)
+        {
+            vlog::error!("Failed cleaning up obsolete setup keys: {}", err);
+        }
+    }
+
+    if let Some(setup_keys_download_url) = &prover_config.setup_keys_download_url {
+        // Provers without a specialized group handle all the circuits.
+        let circuit_types: Vec<CircuitType> = circuit_ids.as_ref().map_or_else(
+            || CircuitType::ALL.to_vec(),
+            |ids| {
+                ids.iter()
+                    .map(|&id| CircuitType::try_from(id).unwrap())
+                    .collect()
+            },
+        );
+        zksync_prover_utils::ensure_setup_keys_present_for_circuits(
+            setup_key_store.as_ref(),
+            &circuit_types,
+            &prover_config.setup_keys_path,
+            setup_keys_download_url,
+            MAX_CONCURRENT_SETUP_KEY_DOWNLOADS,
+            DownloadRetryPolicy::from_config(&prover_config),
+        )
+        .await;
+    }
     let mut tasks: Vec<JoinHandle<()>> = vec![];
 
     tasks.push(prometheus_exporter::run_prometheus_exporter(
@@ -179,7 +241,7 @@ async fn main() {
         producer,
         ConnectionPool::new(Some(1), true),
         prover_config.specialized_prover_group_id,
-        region
+        location
     )));
 
     let artifact_provider = ProverArtifactProvider {};
@@ -6,6 +6,7 @@ use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use zksync_dal::gpu_prover_queue_dal::{GpuProverInstanceStatus, SocketAddress};
 use zksync_dal::ConnectionPool;
+use zksync_prover_utils::region_fetcher::NodeLocation;
 
 pub async fn incoming_socket_listener(
     host: IpAddr,
@@ -14,7 +15,7 @@ pub async fn incoming_socket_listener(
     queue: SharedAssemblyQueue,
     pool: ConnectionPool,
     specialized_prover_group_id: u8,
-    region: String,
+    location: NodeLocation,
 ) {
     let listening_address = SocketAddr::new(host, port);
     vlog::info!(
@@ -33,7 +34,8 @@ pub async fn incoming_socket_listener(
             address.clone(),
             queue.lock().unwrap().capacity(),
             specialized_prover_group_id,
-            region
+            location.region,
+            location.zone,
         );
 
     loop {
@@ -10,6 +10,7 @@ use zkevm_test_harness::witness::vk_set_generator::circuits_for_vk_generation;
 use zksync_types::circuit::GEOMETRY_CONFIG;
 
 use zksync_config::ProverConfigs;
+use zksync_prover_utils::setup_key_filename;
 use zksync_types::circuit::{LEAF_SPLITTING_FACTOR, NODE_SPLITTING_FACTOR, SCHEDULER_UPPER_BOUND};
 pub fn get_setup_for_circuit_type(circuit_type: u8) -> Box<dyn Read> {
     let filepath = get_setup_key_file_path(circuit_type);
@@ -39,7 +40,7 @@ fn ensure_setup_key_exist() {
 
 pub fn get_setup_key_write_file_path(circuit_type: u8) -> String {
     let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| "/".into());
-    format!("{}/{}", zksync_home, get_setup_key_filename(circuit_type))
+    format!("{}/{}", zksync_home, setup_key_filename(circuit_type))
 }
 
 fn get_setup_key_file_path(circuit_type: u8) -> String {
@@ -47,10 +48,6 @@ fn get_setup_key_file_path(circuit_type: u8) -> String {
     format!(
         "{}/{}",
         prover_config.setup_keys_path,
-        get_setup_key_filename(circuit_type)
+        setup_key_filename(circuit_type)
     )
 }
-
-fn get_setup_key_filename(circuit_type: u8) -> String {
-    format!("setup_{}_key.bin", circuit_type)
-}
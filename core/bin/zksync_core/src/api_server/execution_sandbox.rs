@@ -1,24 +1,26 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::time::Instant;
 
 use super::tx_sender::SubmitTxError;
 use crate::api_server::web3::backend_jsonrpc::error::internal_error;
 use thiserror::Error;
 use tracing::{span, Level};
-use vm::oracles::tracer::{ValidationError, ValidationTracerParams};
+use vm::oracles::tracer::{TracerPlugin, ValidationReport, ValidationTracerParams};
 use zksync_types::api::BlockId;
 use zksync_types::utils::storage_key_for_eth_balance;
+use zksync_types::web3::types::{AccessList, AccessListItem};
 use zksync_types::{PUBLISH_BYTECODE_OVERHEAD, TRUSTED_ADDRESS_SLOTS, TRUSTED_TOKEN_SLOTS};
 
 use crate::db_storage_provider::DbStorageProvider;
+use crate::fork::{ForkError, ForkStorage};
 use vm::vm_with_bootloader::{
     derive_base_fee_and_gas_per_pubdata, init_vm, push_transaction_to_bootloader_memory,
     BlockContext, BlockContextMode, BootloaderJobType, DerivedBlockContext, TxExecutionMode,
 };
 use vm::zk_evm::block_properties::BlockProperties;
 use vm::{
-    storage::Storage, utils::ETH_CALL_GAS_LIMIT, TxRevertReason, VmBlockResult, VmExecutionResult,
-    VmInstance,
+    storage::Storage, utils::ETH_CALL_GAS_LIMIT, vm::VmTxExecutionResult, TxRevertReason,
+    VmBlockResult, VmExecutionResult, VmInstance,
 };
 use zksync_config::constants::ZKPORTER_IS_AVAILABLE;
 use zksync_contracts::BaseSystemContracts;
@@ -32,7 +34,8 @@ use zksync_types::{
     l2::L2Tx,
     storage_writes_deduplicator::StorageWritesDeduplicator,
     utils::{decompose_full_nonce, nonces_to_full_nonce},
-    AccountTreeId, MiniblockNumber, Nonce, Transaction, U256,
+    AccountTreeId, Address, MiniblockNumber, Nonce, StorageLogQuery, Transaction,
+    BOOTLOADER_ADDRESS, H256, U256,
 };
 use zksync_utils::bytecode::{bytecode_len_in_bytes, hash_bytecode, CompressedBytecodeInfo};
 use zksync_utils::time::millis_since_epoch;
@@ -53,8 +56,9 @@ pub enum SandboxExecutionError {
     FromIsNotAnAccount,
     #[error("Bootloader failure: {0}")]
     BootloaderFailure(String),
+    /// Revert with the reason and the revert data returned by the contract.
     #[error("Revert: {0}")]
-    Revert(String),
+    Revert(String, Vec<u8>),
     #[error("Failed to pay for the transaction: {0}")]
     FailedToPayForTransaction(String),
     #[error("Bootloader-based tx failed")]
@@ -66,17 +70,48 @@ pub enum SandboxExecutionError {
     UnexpectedVMBehavior(String),
     #[error("Transaction is unexecutable. Reason: {0}")]
     Unexecutable(String),
+    #[error("{0}")]
+    ForkSourceUnavailable(#[from] ForkError),
 }
 
+/// `l1_gas_price` and `fair_l2_gas_price` are only used for the pending block: the historical blocks
+/// are executed with the fee input they were produced with.
 pub fn execute_tx_eth_call(
     connection_pool: &ConnectionPool,
-    mut tx: L2Tx,
+    tx: L2Tx,
     block_id: api::BlockId,
     l1_gas_price: u64,
     fair_l2_gas_price: u64,
     enforced_base_fee: Option<u64>,
     base_system_contract: &BaseSystemContracts,
 ) -> Result<VmExecutionResult, Web3Error> {
+    execute_tx_eth_call_with_plugins(
+        connection_pool,
+        tx,
+        block_id,
+        l1_gas_price,
+        fair_l2_gas_price,
+        enforced_base_fee,
+        base_system_contract,
+        &mut [],
+    )?
+    .map_err(|err| SubmitTxError::from(err).into())
+}
+
+/// Same as `execute_tx_eth_call`, but additionally runs the provided tracer plugins.
+/// The execution error is returned as is, since the data collected by the plugins is meaningful
+/// even if the call has reverted.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_tx_eth_call_with_plugins(
+    connection_pool: &ConnectionPool,
+    mut tx: L2Tx,
+    block_id: api::BlockId,
+    l1_gas_price: u64,
+    fair_l2_gas_price: u64,
+    enforced_base_fee: Option<u64>,
+    base_system_contract: &BaseSystemContracts,
+    plugins: &mut [&mut dyn TracerPlugin],
+) -> Result<Result<VmExecutionResult, SandboxExecutionError>, Web3Error> {
     let mut storage = connection_pool.access_storage_blocking();
     let resolved_block_number = storage
         .blocks_web3_dal()
@@ -91,7 +126,7 @@ pub fn execute_tx_eth_call(
     // limiting the amount of gas the call can use.
     // We can't use BLOCK_ERGS_LIMIT here since the VM itself has some overhead.
     tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
-    let vm_result = execute_tx_in_sandbox(
+    let (_, vm_result) = execute_tx_in_sandbox(
         storage,
         tx.into(),
         TxExecutionMode::EthCall,
@@ -106,15 +141,140 @@ pub fn execute_tx_eth_call(
         fair_l2_gas_price,
         enforced_base_fee,
         base_system_contract,
-    )
-    .1
-    .map_err(|err| {
-        let submit_tx_error: SubmitTxError = err.into();
-        Web3Error::SubmitTransactionError(submit_tx_error.to_string())
-    })?;
+        plugins,
+    );
     Ok(vm_result)
 }
 
+/// Re-executes the transactions of the miniblock `miniblock_number` in the order they were included
+/// and runs the tracer plugins for the last `tracers.len()` of them, i.e. `tracers[0]` observes
+/// `txs[txs.len() - tracers.len()]`. `txs` must be a prefix of the miniblock transactions.
+///
+/// The transactions are executed on top of the state of the previous miniblock with the timestamp,
+/// the fee input and the base system contracts of the miniblock itself. The L1 batch number and the fee
+/// account are taken from the previous miniblock, so the replay may diverge for the first miniblock of a batch
+/// if these values are read by the traced contracts.
+pub fn replay_miniblock_transactions(
+    connection_pool: &ConnectionPool,
+    miniblock_number: MiniblockNumber,
+    txs: Vec<Transaction>,
+    validation_computational_gas_limit: u32,
+    tracers: &mut [&mut dyn TracerPlugin],
+) -> Result<Vec<Result<VmExecutionResult, SandboxExecutionError>>, Web3Error> {
+    assert!(tracers.len() <= txs.len(), "more tracers than transactions");
+    let first_tx = match txs.first() {
+        Some(tx) => tx.clone(),
+        None => return Ok(vec![]),
+    };
+    if miniblock_number.0 == 0 {
+        // Genesis miniblock has no transactions, but stay on the safe side.
+        return Err(Web3Error::NoBlock);
+    }
+
+    let mut connection = connection_pool.access_storage_blocking();
+    let header = connection
+        .blocks_dal()
+        .get_miniblock_header(miniblock_number)
+        .ok_or(Web3Error::NoBlock)?;
+    let base_system_contracts = connection.storage_dal().get_base_system_contracts(
+        header.base_system_contracts_hashes.bootloader,
+        header.base_system_contracts_hashes.default_aa,
+    );
+    let state_miniblock_number = MiniblockNumber(miniblock_number.0 - 1);
+    let traced_from = txs.len() - tracers.len();
+
+    let results = apply_vm_in_sandbox(
+        connection,
+        first_tx,
+        TxExecutionMode::VerifyExecute,
+        &base_system_contracts,
+        AccountTreeId::new(header.fee_account_address),
+        api::BlockId::Number(api::BlockNumber::Number(state_miniblock_number.0.into())),
+        state_miniblock_number,
+        Some(header.timestamp),
+        None,
+        U256::zero(),
+        SandboxFeeInput {
+            l1_gas_price: header.l1_gas_price,
+            fair_l2_gas_price: header.l2_fair_gas_price,
+            enforced_base_fee: Some(header.base_fee_per_gas),
+            enforce_for_historical_blocks: true,
+        },
+        |vm, _| {
+            let mut results = Vec::with_capacity(tracers.len());
+            for (i, tx) in txs.iter().enumerate() {
+                push_transaction_to_bootloader_memory(vm, tx, TxExecutionMode::VerifyExecute, None);
+                vm.save_current_vm_as_snapshot();
+                let gas_before = vm.gas_consumed();
+                let result = match i.checked_sub(traced_from) {
+                    Some(tracer_index) => vm.execute_next_tx_with_plugins(
+                        validation_computational_gas_limit,
+                        &mut [&mut *tracers[tracer_index]],
+                    ),
+                    None => vm.execute_next_tx(validation_computational_gas_limit),
+                };
+                let gas_used = vm.gas_consumed().saturating_sub(gas_before);
+                match &result {
+                    Ok(_) => vm.pop_snapshot_no_rollback(),
+                    // The transaction has been rejected, although it was included in the miniblock.
+                    // Roll back its changes so that the following transactions are executed on the original state.
+                    Err(_) => vm.rollback_to_latest_snapshot_popping(),
+                }
+                if i >= traced_from {
+                    results.push(replayed_tx_result(result, gas_used));
+                }
+            }
+            results
+        },
+    )
+    .map_err(SubmitTxError::from)?;
+    Ok(results)
+}
+
+fn replayed_tx_result(
+    result: Result<VmTxExecutionResult, TxRevertReason>,
+    gas_used: u32,
+) -> Result<VmExecutionResult, SandboxExecutionError> {
+    let result = result?.result;
+    Ok(VmExecutionResult {
+        events: result.logs.events,
+        storage_log_queries: result.logs.storage_logs,
+        used_contract_hashes: vec![],
+        l2_to_l1_logs: result.logs.l2_to_l1_logs,
+        return_data: vec![],
+        gas_used,
+        contracts_used: result.contracts_used,
+        revert_reason: None,
+        trace: Default::default(),
+        total_log_queries: result.logs.total_log_queries_count,
+        cycles_used: result.cycles_used,
+    })
+}
+
+/// Builds an access list out of the storage slots that were read or written during the VM execution.
+/// Bootloader slots are omitted, since they are accessed by every transaction.
+pub fn access_list_from_storage_logs(storage_logs: &[StorageLogQuery]) -> AccessList {
+    let mut touched_slots: BTreeMap<Address, BTreeSet<H256>> = BTreeMap::new();
+    for storage_log in storage_logs {
+        let address = storage_log.log_query.address;
+        if address == BOOTLOADER_ADDRESS {
+            continue;
+        }
+        touched_slots
+            .entry(address)
+            .or_default()
+            .insert(u256_to_h256(storage_log.log_query.key));
+    }
+
+    touched_slots
+        .into_iter()
+        .map(|(address, storage_keys)| AccessListItem {
+            address,
+            storage_keys: storage_keys.into_iter().collect(),
+        })
+        .collect()
+}
+
 fn get_pending_state(
     connection_pool: &ConnectionPool,
 ) -> (BlockId, StorageProcessor<'_>, MiniblockNumber) {
@@ -129,6 +289,17 @@ fn get_pending_state(
     (block_id, connection, resolved_block_number)
 }
 
+/// Converts the max fee per gas of the transaction to the base fee enforced in the sandbox.
+pub fn enforced_base_fee(max_fee_per_gas: U256) -> Result<u64, Web3Error> {
+    if max_fee_per_gas > U256::from(u64::MAX) {
+        return Err(Web3Error::InvalidFeeParams(format!(
+            "max fee per gas {} exceeds 2^64 - 1",
+            max_fee_per_gas
+        )));
+    }
+    Ok(max_fee_per_gas.as_u64())
+}
+
 #[tracing::instrument(skip(
     connection_pool,
     tx,
@@ -152,7 +323,53 @@ pub fn execute_tx_with_pending_state(
     TransactionExecutionMetrics,
     Result<VmExecutionResult, SandboxExecutionError>,
 ) {
-    let (block_id, connection, resolved_block_number) = get_pending_state(connection_pool);
+    execute_tx_at_block(
+        connection_pool,
+        tx,
+        api::BlockId::Number(api::BlockNumber::Pending),
+        operator_account,
+        execution_mode,
+        enforced_nonce,
+        added_balance,
+        l1_gas_price,
+        fair_l2_gas_price,
+        enforced_base_fee,
+        base_system_contracts,
+    )
+}
+
+/// Same as [`execute_tx_with_pending_state`], but executes the transaction on top of the state
+/// of the specified block, which must be present.
+#[tracing::instrument(skip(
+    connection_pool,
+    tx,
+    operator_account,
+    enforced_nonce,
+    base_system_contracts
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn execute_tx_at_block(
+    connection_pool: &ConnectionPool,
+    tx: Transaction,
+    block_id: api::BlockId,
+    operator_account: AccountTreeId,
+    execution_mode: TxExecutionMode,
+    enforced_nonce: Option<Nonce>,
+    added_balance: U256,
+    l1_gas_price: u64,
+    fair_l2_gas_price: u64,
+    enforced_base_fee: Option<u64>,
+    base_system_contracts: &BaseSystemContracts,
+) -> (
+    TransactionExecutionMetrics,
+    Result<VmExecutionResult, SandboxExecutionError>,
+) {
+    let mut connection = connection_pool.access_storage_blocking();
+    let resolved_block_number = connection
+        .blocks_web3_dal()
+        .resolve_block_id(block_id)
+        .unwrap()
+        .expect("Block should be present");
 
     // In order for execution to pass smoothlessly, we need to ensure that block's required gasPerPubdata will be
     // <= to the one in the transaction itself.
@@ -177,6 +394,7 @@ pub fn execute_tx_with_pending_state(
         fair_l2_gas_price,
         enforced_base_fee,
         base_system_contracts,
+        &mut [],
     )
 }
 
@@ -187,7 +405,7 @@ pub fn get_pubdata_for_factory_deps(
 ) -> u32 {
     let (_, connection, block_number) = get_pending_state(connection_pool);
     let db_storage_provider = DbStorageProvider::new(connection, block_number, false);
-    let mut storage_view = StorageView::new(db_storage_provider);
+    let mut storage_view = StorageView::new(ForkStorage::new(db_storage_provider));
 
     factory_deps
         .as_ref()
@@ -226,7 +444,7 @@ pub fn validate_tx_with_pending_state(
     enforced_base_fee: Option<u64>,
     base_system_contracts: &BaseSystemContracts,
     computational_gas_limit: u32,
-) -> Result<(), ValidationError> {
+) -> Result<ValidationReport, SandboxExecutionError> {
     let (block_id, connection, resolved_block_number) = get_pending_state(connection_pool);
 
     // In order for validation to pass smoothlessly, we need to ensure that block's required gasPerPubdata will be
@@ -285,7 +503,8 @@ pub(crate) fn adjust_l1_gas_price_for_tx(
     tx,
     operator_account,
     block_timestamp_s,
-    base_system_contract
+    base_system_contract,
+    plugins
 ))]
 fn execute_tx_in_sandbox(
     connection: StorageProcessor<'_>,
@@ -302,6 +521,7 @@ fn execute_tx_in_sandbox(
     fair_l2_gas_price: u64,
     enforced_base_fee: Option<u64>,
     base_system_contract: &BaseSystemContracts,
+    plugins: &mut [&mut dyn TracerPlugin],
 ) -> (
     TransactionExecutionMetrics,
     Result<VmExecutionResult, SandboxExecutionError>,
@@ -326,15 +546,18 @@ fn execute_tx_in_sandbox(
         block_timestamp_s,
         enforced_nonce,
         added_balance,
-        l1_gas_price,
-        fair_l2_gas_price,
-        enforced_base_fee,
+        SandboxFeeInput {
+            l1_gas_price,
+            fair_l2_gas_price,
+            enforced_base_fee,
+            enforce_for_historical_blocks: false,
+        },
         |vm, tx| {
             push_transaction_to_bootloader_memory(vm, &tx, execution_mode, None);
             let VmBlockResult {
                 full_result: result,
                 ..
-            } = vm.execute_till_block_end(job_type);
+            } = vm.execute_till_block_end_with_plugins(job_type, plugins);
 
             metrics::histogram!("api.web3.sandbox", stage_started_at.elapsed(), "stage" => "execution");
             span.exit();
@@ -342,6 +565,10 @@ fn execute_tx_in_sandbox(
             result
         },
     );
+    let execution_result = match execution_result {
+        Ok(result) => result,
+        Err(err) => return (TransactionExecutionMetrics::default(), Err(err)),
+    };
 
     let tx_execution_metrics = collect_tx_execution_metrics(total_factory_deps, &execution_result);
 
@@ -354,8 +581,20 @@ fn execute_tx_in_sandbox(
     )
 }
 
+/// Fee input of a sandboxed execution.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SandboxFeeInput {
+    pub l1_gas_price: u64,
+    pub fair_l2_gas_price: u64,
+    /// Base fee overriding the one derived from the gas prices.
+    pub enforced_base_fee: Option<u64>,
+    /// Whether the gas prices are used for the historical blocks as well. If not set, historical blocks
+    /// are executed with the fee input they were produced with.
+    pub enforce_for_historical_blocks: bool,
+}
+
 #[allow(clippy::too_many_arguments)]
-fn apply_vm_in_sandbox<T>(
+pub(crate) fn apply_vm_in_sandbox<T>(
     mut connection: StorageProcessor<'_>,
     tx: Transaction,
     execution_mode: TxExecutionMode,
@@ -366,11 +605,9 @@ fn apply_vm_in_sandbox<T>(
     block_timestamp_s: Option<u64>,
     enforced_nonce: Option<Nonce>,
     added_balance: U256,
-    l1_gas_price: u64,
-    fair_l2_gas_price: u64,
-    enforced_base_fee: Option<u64>,
+    fee_input: SandboxFeeInput,
     apply: impl FnOnce(&mut Box<VmInstance<'_>>, Transaction) -> T,
-) -> T {
+) -> Result<T, SandboxExecutionError> {
     let stage_started_at = Instant::now();
     let span = span!(Level::DEBUG, "initialization").entered();
 
@@ -405,9 +642,38 @@ fn apply_vm_in_sandbox<T>(
         }
     };
 
+    let SandboxFeeInput {
+        l1_gas_price,
+        fair_l2_gas_price,
+        enforced_base_fee,
+        enforce_for_historical_blocks,
+    } = fee_input;
+    // Historical calls are executed with the fee input the block was produced with rather than the current one,
+    // so that the pubdata price and the base fee match the original execution, unless the fee input is enforced.
+    let (l1_gas_price, fair_l2_gas_price) = match block_id {
+        _ if enforce_for_historical_blocks => (l1_gas_price, fair_l2_gas_price),
+        api::BlockId::Number(api::BlockNumber::Pending) => (l1_gas_price, fair_l2_gas_price),
+        _ => connection
+            .blocks_web3_dal()
+            .get_miniblock_fee_input(resolved_block_number)
+            .unwrap()
+            .unwrap_or((l1_gas_price, fair_l2_gas_price)),
+    };
+    // Same for the fee account, so that changing the configured one doesn't alter the results for the past blocks.
+    let operator_address = match block_id {
+        api::BlockId::Number(api::BlockNumber::Pending) => *operator_account.address(),
+        _ => connection
+            .blocks_web3_dal()
+            .get_miniblock_fee_account_address(resolved_block_number)
+            .unwrap()
+            .unwrap_or(*operator_account.address()),
+    };
+
     let db_storage_provider = DbStorageProvider::new(connection, state_block_number, false);
 
-    let mut storage_view = StorageView::new(db_storage_provider);
+    let fork_storage = ForkStorage::new(db_storage_provider);
+    let fork_errors = fork_storage.errors();
+    let mut storage_view = StorageView::new(fork_storage);
 
     let block_timestamp_ms = match block_id {
         api::BlockId::Number(api::BlockNumber::Pending) => millis_since_epoch(),
@@ -452,7 +718,7 @@ fn apply_vm_in_sandbox<T>(
             block_timestamp: (block_timestamp_ms / 1000) as u64,
             l1_gas_price,
             fair_l2_gas_price,
-            operator_address: *operator_account.address(),
+            operator_address,
         },
         base_fee: enforced_base_fee.unwrap_or_else(|| {
             derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price).0
@@ -504,7 +770,11 @@ fn apply_vm_in_sandbox<T>(
         );
     }
 
-    result
+    // The results obtained without a part of the forked state are meaningless.
+    match fork_errors.take() {
+        Some(err) => Err(err.into()),
+        None => Ok(result),
+    }
 }
 
 // Some slots can be marked as "trusted". That is needed for slots which can not be
@@ -579,7 +849,7 @@ fn validate_tx_in_sandbox(
     fair_l2_gas_price: u64,
     enforced_base_fee: Option<u64>,
     computational_gas_limit: u32,
-) -> Result<(), ValidationError> {
+) -> Result<ValidationReport, SandboxExecutionError> {
     let stage_started_at = Instant::now();
     let span = span!(Level::DEBUG, "validate_in_sandbox").entered();
     let validation_params = get_validation_params(&mut connection, &tx, computational_gas_limit);
@@ -597,15 +867,18 @@ fn validate_tx_in_sandbox(
         block_timestamp_s,
         enforced_nonce,
         added_balance,
-        l1_gas_price,
-        fair_l2_gas_price,
-        enforced_base_fee,
+        SandboxFeeInput {
+            l1_gas_price,
+            fair_l2_gas_price,
+            enforced_base_fee,
+            enforce_for_historical_blocks: false,
+        },
         |vm, tx| {
             let stage_started_at = Instant::now();
             let span = span!(Level::DEBUG, "validation").entered();
 
             push_transaction_to_bootloader_memory(vm, &tx, execution_mode, None);
-            let result = vm.execute_validation(validation_params);
+            let result = vm.execute_validation_with_report(validation_params);
 
             metrics::histogram!("api.web3.sandbox", stage_started_at.elapsed(), "stage" => "validation");
             span.exit();
@@ -663,8 +936,9 @@ fn collect_tx_execution_metrics(
 impl From<TxRevertReason> for SandboxExecutionError {
     fn from(reason: TxRevertReason) -> Self {
         match reason {
-            TxRevertReason::EthCall(reason) => SandboxExecutionError::Revert(reason.to_string()),
-            TxRevertReason::TxReverted(reason) => SandboxExecutionError::Revert(reason.to_string()),
+            TxRevertReason::EthCall(reason) | TxRevertReason::TxReverted(reason) => {
+                SandboxExecutionError::Revert(reason.to_string(), reason.encoded_message())
+            }
             TxRevertReason::FailedToChargeFee(reason) => {
                 SandboxExecutionError::FailedToChargeFee(reason.to_string())
             }
@@ -692,13 +966,13 @@ impl From<TxRevertReason> for SandboxExecutionError {
                 "The bootloader did not contain enough gas to execute the transaction".to_string(),
             ),
             revert_reason @ TxRevertReason::FailedToMarkFactoryDependencies(_) => {
-                SandboxExecutionError::Revert(revert_reason.to_string())
+                SandboxExecutionError::Revert(revert_reason.to_string(), vec![])
             }
             TxRevertReason::PayForTxFailed(reason) => {
                 SandboxExecutionError::FailedToPayForTransaction(reason.to_string())
             }
             TxRevertReason::TooBigGasLimit => {
-                SandboxExecutionError::Revert(TxRevertReason::TooBigGasLimit.to_string())
+                SandboxExecutionError::Revert(TxRevertReason::TooBigGasLimit.to_string(), vec![])
             }
         }
     }
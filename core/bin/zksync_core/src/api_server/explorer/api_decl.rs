@@ -1,5 +1,5 @@
 use zksync_config::ZkSyncConfig;
-use zksync_dal::connection::ConnectionPool;
+use zksync_dal::{connection::ConnectionPool, feature_flags_dal::FeatureFlagsReader};
 
 use actix_web::web;
 use futures::channel::mpsc;
@@ -12,6 +12,7 @@ pub struct RestApi {
     pub(super) master_connection_pool: ConnectionPool,
     pub(super) replica_connection_pool: ConnectionPool,
     pub(super) network_stats: SharedNetworkStats,
+    pub(super) feature_flags: FeatureFlagsReader,
     pub(super) config: ZkSyncConfig,
 }
 
@@ -23,6 +24,10 @@ impl RestApi {
     ) -> Self {
         Self {
             master_connection_pool,
+            feature_flags: FeatureFlagsReader::new(
+                replica_connection_pool.clone(),
+                FeatureFlagsReader::DEFAULT_TTL,
+            ),
             replica_connection_pool,
             network_stats: SharedNetworkStats::default(),
             config,
@@ -34,6 +39,8 @@ impl RestApi {
         web::scope("")
             .app_data(web::Data::new(self))
             .route("/network_stats", web::get().to(Self::network_stats))
+            .route("/debug/state", web::get().to(Self::debug_state))
+            .route("/health", web::get().to(Self::health))
             .route("/blocks", web::get().to(Self::block_pagination))
             .route("/block/{number}", web::get().to(Self::block_details))
             .route("/l1_batches", web::get().to(Self::l1_batch_pagination))
@@ -9,18 +9,41 @@ use serde::Serialize;
 use zksync_types::{
     explorer_api::{
         AccountDetails, AccountType, AddressDetails, BlocksQuery, ContractDetails, EventsQuery,
-        L1BatchesQuery, PaginationQuery, TransactionsQuery, VerificationIncomingRequest,
+        L1BatchesQuery, PaginationCursor, PaginationQuery, TransactionsQuery,
+        VerificationIncomingRequest,
     },
+    feature_flags::FeatureFlag,
     storage::L2_ETH_TOKEN_ADDRESS,
     Address, L1BatchNumber, MiniblockNumber, H256,
 };
 
 use super::api_decl::RestApi;
+use crate::chain_watchdog::check_chain_health;
+
+/// Internal state of the server exposed for debugging purposes.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DebugState {
+    feature_flags: Vec<FeatureFlag>,
+}
 
 fn ok_json(data: impl Serialize) -> ActixResult<HttpResponse> {
     Ok(HttpResponse::Ok().json(data))
 }
 
+/// Same as `ok_json`, but passes the cursor for the next page in the `X-Next-Cursor` header.
+/// Used for the endpoints that return plain lists.
+fn ok_json_with_cursor(
+    data: impl Serialize,
+    next_cursor: Option<PaginationCursor>,
+) -> ActixResult<HttpResponse> {
+    let mut response = HttpResponse::Ok();
+    if let Some(next_cursor) = next_cursor {
+        response.insert_header(("X-Next-Cursor", next_cursor.encode()));
+    }
+    Ok(response.json(data))
+}
+
 impl RestApi {
     #[tracing::instrument(skip(self_))]
     pub async fn network_stats(self_: web::Data<Self>) -> ActixResult<HttpResponse> {
@@ -32,6 +55,34 @@ impl RestApi {
         ok_json(stats)
     }
 
+    #[tracing::instrument(skip(self_))]
+    pub async fn debug_state(self_: web::Data<Self>) -> ActixResult<HttpResponse> {
+        let start = Instant::now();
+
+        let state = DebugState {
+            feature_flags: self_.feature_flags.snapshot(),
+        };
+
+        metrics::histogram!("api.explorer.call", start.elapsed(), "method" => "debug_state");
+        ok_json(state)
+    }
+
+    /// Returns `503 Service Unavailable` with the stalled stages if the chain has stopped making progress.
+    #[tracing::instrument(skip(self_))]
+    pub async fn health(self_: web::Data<Self>) -> ActixResult<HttpResponse> {
+        let start = Instant::now();
+
+        let mut storage = self_.replica_connection_pool.access_storage().await;
+        let health = check_chain_health(&mut storage, &self_.config.chain.watchdog);
+
+        metrics::histogram!("api.explorer.call", start.elapsed(), "method" => "health");
+        if health.is_healthy() {
+            ok_json(health)
+        } else {
+            Ok(HttpResponse::ServiceUnavailable().json(health))
+        }
+    }
+
     #[tracing::instrument(skip(self_))]
     pub async fn address_details(
         self_: web::Data<Self>,
@@ -190,6 +241,9 @@ impl RestApi {
                 self.config.api.explorer.req_entities_limit()
             )));
         }
+        if pagination.cursor.is_some() && pagination.offset != 0 {
+            return Err(HttpResponse::BadRequest().body("Can't use both `offset` and `cursor`"));
+        }
         if pagination.offset + pagination.limit > self.config.api.explorer.offset_limit() {
             return Err(HttpResponse::BadRequest().body(format!(
                 "(offset + limit) should not exceed {}",
@@ -309,17 +363,30 @@ impl RestApi {
             return Ok(res);
         }
 
-        let blocks = self_
-            .replica_connection_pool
-            .access_storage()
-            .await
+        let last_verified = self_.network_stats.read().await.last_verified;
+        let mut storage = self_.replica_connection_pool.access_storage().await;
+        let pagination = query.pagination;
+
+        let blocks = storage
             .explorer()
             .blocks_dal()
-            .get_blocks_page(query, self_.network_stats.read().await.last_verified)
+            .get_blocks_page(query, last_verified)
+            .unwrap();
+        let next_cursor = pagination
+            .next_cursor(
+                blocks.len(),
+                blocks.last().map(|block| (block.number.0, None)),
+                || {
+                    storage
+                        .blocks_web3_dal()
+                        .get_sealed_miniblock_number()
+                        .map(|number| number.0)
+                },
+            )
             .unwrap();
 
         metrics::histogram!("api.explorer.call", start.elapsed(), "method" => "block_pagination");
-        ok_json(blocks)
+        ok_json_with_cursor(blocks, next_cursor)
     }
 
     #[tracing::instrument(skip(self_))]
@@ -356,6 +423,7 @@ impl RestApi {
         }
         let last_verified_miniblock = self_.network_stats.read().await.last_verified;
         let mut storage = self_.replica_connection_pool.access_storage().await;
+        let pagination = query.pagination;
 
         let last_verified_l1_batch = storage
             .blocks_web3_dal()
@@ -368,9 +436,21 @@ impl RestApi {
             .blocks_dal()
             .get_l1_batches_page(query, last_verified_l1_batch)
             .unwrap();
+        let next_cursor = pagination
+            .next_cursor(
+                l1_batches.len(),
+                l1_batches.last().map(|l1_batch| (l1_batch.number.0, None)),
+                || {
+                    storage
+                        .blocks_web3_dal()
+                        .get_sealed_l1_batch_number()
+                        .map(|number| number.0)
+                },
+            )
+            .unwrap();
 
         metrics::histogram!("api.explorer.call", start.elapsed(), "method" => "l1_batch_pagination");
-        ok_json(l1_batches)
+        ok_json_with_cursor(l1_batches, next_cursor)
     }
 
     #[tracing::instrument(skip(self_))]
@@ -465,10 +545,8 @@ impl RestApi {
             return Ok(res);
         }
 
-        let events = self_
-            .replica_connection_pool
-            .access_storage()
-            .await
+        let mut storage = self_.replica_connection_pool.access_storage().await;
+        let events = storage
             .explorer()
             .events_dal()
             .get_events_page(query, self_.config.api.explorer.offset_limit())
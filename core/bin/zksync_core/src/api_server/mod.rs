@@ -1,5 +1,8 @@
 // Everywhere in this module the word "block" actually means "miniblock".
 pub mod execution_sandbox;
 pub mod explorer;
+pub mod prover_gateway;
+pub mod tls;
+pub mod tracers;
 pub mod tx_sender;
 pub mod web3;
@@ -0,0 +1,326 @@
+//! Prover gateway: REST API allowing the external provers (e.g. proving marketplaces) to fetch the inputs
+//! of the prover jobs and to submit the proofs back without direct access to the database.
+//!
+//! Circuit inputs are served from the `prover_jobs` bucket of the object store, the keys are returned
+//! in the job list, so that provers with access to the bucket can fetch them directly.
+//! Submitted proofs are verified against the verification key of the job circuit and then processed
+//! in the same way as the proofs generated by our own provers.
+//!
+//! The gateway can be served over TLS, optionally accepting only the provers with a client certificate
+//! signed by the configured CA in addition to the API key check.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::dev::Server;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Result as ActixResult};
+use futures::StreamExt;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use zksync_config::configs::api::ProverGateway as ProverGatewayConfig;
+use zksync_dal::ConnectionPool;
+use zksync_object_store::object_store::{
+    create_object_store_from_env, DynamicObjectStore, ObjectStoreError, PROVER_JOBS_BUCKET_PATH,
+};
+use zksync_prover_utils::circuit_type::CircuitType;
+use zksync_types::proofs::ExternalProverJob;
+use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::{
+    ZkSyncProof, ZkSyncVerificationKey,
+};
+use zksync_types::zkevm_test_harness::bellman::bn256::Bn256;
+use zksync_types::L1BatchNumber;
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+use zksync_verification_key_server::get_vk_for_circuit_type;
+
+use crate::api_server::tls::load_server_config;
+
+/// Value of the `proccesed_by` column for the proofs submitted via the gateway.
+const PROCESSED_BY: &str = "prover_gateway";
+/// Limit on the size of a submitted proof. Proofs of all the circuits consist of a fixed number
+/// of commitments and evaluations and are a few kilobytes long when serialized.
+const MAX_PROOF_SIZE: usize = 1 << 20;
+
+/// Checks that the serialized proof is a valid proof for the circuit of the job.
+fn verify_proof(job: &ExternalProverJob, proof: &[u8]) -> Result<(), String> {
+    let circuit_id = job.circuit_type.parse::<CircuitType>()?.numeric_index();
+    let proof: ZkSyncProof<Bn256> =
+        bincode::deserialize(proof).map_err(|err| format!("Cannot deserialize proof: {}", err))?;
+    if proof.numeric_circuit_type() != circuit_id {
+        return Err(format!(
+            "Proof is generated for circuit {}, while the job circuit is {}",
+            proof.numeric_circuit_type(),
+            circuit_id
+        ));
+    }
+
+    let vk = ZkSyncVerificationKey::from_verification_key_and_numeric_type(
+        circuit_id,
+        get_vk_for_circuit_type(circuit_id),
+    );
+    if !vk.verify_proof(&proof) {
+        return Err("Proof verification failed".to_owned());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct ProverGatewayApi {
+    config: ProverGatewayConfig,
+    connection_pool: ConnectionPool,
+    object_store: Arc<DynamicObjectStore>,
+}
+
+impl ProverGatewayApi {
+    fn into_scope(self) -> actix_web::Scope {
+        web::scope("")
+            .app_data(web::Data::new(self))
+            .route(
+                "/l1_batch/{number}/jobs",
+                web::get().to(Self::l1_batch_jobs),
+            )
+            .route("/job/{id}/input", web::get().to(Self::job_input))
+            .route("/job/{id}/proof", web::post().to(Self::submit_proof))
+    }
+
+    fn authorize(&self, request: &HttpRequest) -> Result<(), HttpResponse> {
+        let api_key = request
+            .headers()
+            .get("Authorization")
+            .and_then(|header| header.to_str().ok())
+            .and_then(|header| header.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if self.config.is_authorized(api_key) {
+            Ok(())
+        } else {
+            Err(HttpResponse::Unauthorized().finish())
+        }
+    }
+
+    /// Reads the proof from the request body. Must be called only after the request is authorized.
+    async fn read_proof(mut payload: web::Payload) -> Result<web::Bytes, HttpResponse> {
+        let mut proof = web::BytesMut::new();
+        while let Some(chunk) = payload.next().await {
+            let chunk = chunk.map_err(|err| HttpResponse::BadRequest().body(err.to_string()))?;
+            if proof.len() + chunk.len() > MAX_PROOF_SIZE {
+                return Err(HttpResponse::PayloadTooLarge().finish());
+            }
+            proof.extend_from_slice(&chunk);
+        }
+        Ok(proof.freeze())
+    }
+
+    #[tracing::instrument(skip(self_, request))]
+    async fn l1_batch_jobs(
+        self_: web::Data<Self>,
+        request: HttpRequest,
+        l1_batch_number: web::Path<L1BatchNumber>,
+    ) -> ActixResult<HttpResponse> {
+        if let Err(res) = self_.authorize(&request) {
+            return Ok(res);
+        }
+        let start = Instant::now();
+
+        let jobs = self_
+            .connection_pool
+            .access_storage()
+            .await
+            .prover_dal()
+            .get_external_prover_jobs_for_l1_batch(*l1_batch_number);
+
+        metrics::histogram!("api.prover_gateway.call", start.elapsed(), "method" => "l1_batch_jobs");
+        Ok(HttpResponse::Ok().json(jobs))
+    }
+
+    #[tracing::instrument(skip(self_, request))]
+    async fn job_input(
+        self_: web::Data<Self>,
+        request: HttpRequest,
+        job_id: web::Path<u32>,
+    ) -> ActixResult<HttpResponse> {
+        if let Err(res) = self_.authorize(&request) {
+            return Ok(res);
+        }
+        let start = Instant::now();
+
+        let job = self_
+            .connection_pool
+            .access_storage()
+            .await
+            .prover_dal()
+            .get_external_prover_job(*job_id);
+        let blob_url = match job {
+            Some(job) => job.circuit_input_blob_url,
+            None => return Ok(HttpResponse::NotFound().finish()),
+        };
+        let input = match blob_url {
+            Some(blob_url) => self_.object_store.get(PROVER_JOBS_BUCKET_PATH, blob_url),
+            None => return Ok(HttpResponse::Gone().body("Job input has been cleaned up")),
+        };
+
+        metrics::histogram!("api.prover_gateway.call", start.elapsed(), "method" => "job_input");
+        match input {
+            Ok(input) => Ok(HttpResponse::Ok()
+                .content_type("application/octet-stream")
+                .body(input)),
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                Ok(HttpResponse::Gone().body("Job input has been cleaned up"))
+            }
+            Err(ObjectStoreError::Corrupted(error)) => {
+                vlog::error!("Input of prover job {} is corrupted: {}", job_id, error);
+                self_
+                    .connection_pool
+                    .access_storage()
+                    .await
+                    .prover_dal()
+                    .mark_prover_job_as_artifact_corrupted(*job_id, error);
+                Ok(HttpResponse::Gone().body("Job input is corrupted"))
+            }
+            Err(err) => {
+                vlog::error!("Failed to fetch input of prover job {}: {}", job_id, err);
+                Ok(HttpResponse::InternalServerError().finish())
+            }
+        }
+    }
+
+    /// Accepts a bincode-serialized `ZkSyncProof` for the job.
+    #[tracing::instrument(skip(self_, request, payload))]
+    async fn submit_proof(
+        self_: web::Data<Self>,
+        request: HttpRequest,
+        job_id: web::Path<u32>,
+        payload: web::Payload,
+    ) -> ActixResult<HttpResponse> {
+        // The body is read only after the request is authorized.
+        if let Err(res) = self_.authorize(&request) {
+            return Ok(res);
+        }
+        let proof = match Self::read_proof(payload).await {
+            Ok(proof) => proof,
+            Err(res) => return Ok(res),
+        };
+        let start = Instant::now();
+
+        let mut connection = self_.connection_pool.access_storage().await;
+        let job = match connection.prover_dal().get_external_prover_job(*job_id) {
+            Some(job) => job,
+            None => return Ok(HttpResponse::NotFound().finish()),
+        };
+        if job.status == "successful" {
+            return Ok(HttpResponse::Conflict().body("Job is already proven"));
+        }
+        // Verification is CPU-heavy, so it's run outside of the server workers.
+        let verified = web::block({
+            let job = job.clone();
+            let proof = proof.clone();
+            move || verify_proof(&job, &proof)
+        })
+        .await?;
+        if let Err(err) = verified {
+            metrics::counter!("api.prover_gateway.rejected_proofs", 1);
+            return Ok(HttpResponse::BadRequest().body(err));
+        }
+
+        // Same as saving the proofs generated by our provers.
+        let mut transaction = connection.start_transaction().await;
+        transaction.prover_dal().lock_prover_jobs_table_exclusive();
+        // The job could've been proven while the proof was being verified.
+        let is_proven = transaction
+            .prover_dal()
+            .get_external_prover_job(job.id)
+            .map_or(false, |job| job.status == "successful");
+        if is_proven {
+            return Ok(HttpResponse::Conflict().body("Job is already proven"));
+        }
+        transaction.prover_dal().save_proof(
+            job.id,
+            Duration::from_secs(0),
+            proof.to_vec(),
+            PROCESSED_BY,
+        );
+        if let Some(next_round) = job.aggregation_round.next() {
+            let successful_proofs_count = transaction
+                .prover_dal()
+                .successful_proofs_count(job.l1_batch_number, job.aggregation_round);
+            let required_proofs_count = transaction
+                .witness_generator_dal()
+                .required_proofs_count(job.l1_batch_number, next_round);
+            if successful_proofs_count == required_proofs_count {
+                transaction
+                    .witness_generator_dal()
+                    .mark_witness_job_as_queued(job.l1_batch_number, next_round);
+            }
+        }
+        transaction.commit().await;
+
+        vlog::info!(
+            "Accepted external proof for job {} ({:?} of L1 batch {})",
+            job.id,
+            job.aggregation_round,
+            job.l1_batch_number
+        );
+        metrics::counter!("api.prover_gateway.accepted_proofs", 1);
+        metrics::histogram!("api.prover_gateway.call", start.elapsed(), "method" => "submit_proof");
+        Ok(HttpResponse::Ok().finish())
+    }
+}
+
+fn start_server(
+    api: ProverGatewayApi,
+    bind_to: SocketAddr,
+    tls_config: Option<rustls::ServerConfig>,
+) -> Server {
+    let server = HttpServer::new(move || App::new().service(api.clone().into_scope()));
+    let server = match tls_config {
+        Some(tls_config) => server.bind_rustls(bind_to, tls_config),
+        None => server.bind(bind_to),
+    };
+    server
+        .unwrap()
+        .shutdown_timeout(60)
+        .keep_alive(Duration::from_secs(10))
+        .client_request_timeout(Duration::from_secs(60))
+        .run()
+}
+
+/// Starts the prover gateway in a separate thread.
+pub fn start_server_thread_detached(
+    config: ProverGatewayConfig,
+    connection_pool: ConnectionPool,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> JoinHandle<()> {
+    let tls_config = config.tls_config().map(|tls_config| {
+        load_server_config(&tls_config)
+            .unwrap_or_else(|err| panic!("Invalid TLS configuration of prover gateway: {:#}", err))
+    });
+    let (handler, panic_sender) = spawn_panic_handler();
+
+    std::thread::Builder::new()
+        .name("prover-gateway".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender);
+
+            actix_rt::System::new().block_on(async move {
+                let bind_address = config.bind_addr();
+                let api = ProverGatewayApi {
+                    config,
+                    connection_pool,
+                    object_store: Arc::new(create_object_store_from_env()),
+                };
+
+                let server = start_server(api, bind_address, tls_config);
+                let close_handle = server.handle();
+                actix_rt::spawn(async move {
+                    if stop_receiver.changed().await.is_ok() {
+                        close_handle.stop(true).await;
+                        vlog::info!("Stop signal received, prover gateway is shutting down");
+                    }
+                });
+                server.await.expect("Prover gateway crashed");
+            });
+        })
+        .expect("Failed to spawn thread for prover gateway");
+
+    handler
+}
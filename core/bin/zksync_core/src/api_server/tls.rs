@@ -0,0 +1,62 @@
+//! TLS support for the internal API servers, which can't rely on being deployed to a trusted network.
+//!
+//! Currently only the prover gateway is served over TLS; its clients are external provers, which connect
+//! with their own HTTPS clients. The external node reaches the main node via its public JSON-RPC API,
+//! so TLS for this link is terminated in front of the main node, and the external node only has to be
+//! configured with an `https` URL of the main node.
+
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::Context as _;
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+
+use zksync_config::configs::api::ServerTlsConfig;
+
+fn load_certificates(path: &str) -> anyhow::Result<Vec<Certificate>> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path))?;
+    let certificates = rustls_pemfile::certs(&mut BufReader::new(file))
+        .with_context(|| format!("cannot parse certificates from {}", path))?;
+    anyhow::ensure!(!certificates.is_empty(), "no certificates in {}", path);
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &str) -> anyhow::Result<PrivateKey> {
+    let file = File::open(path).with_context(|| format!("cannot open {}", path))?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(file))
+        .with_context(|| format!("cannot parse private key from {}", path))?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::RSAKey(key)
+            | rustls_pemfile::Item::PKCS8Key(key)
+            | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .with_context(|| format!("no private key in {}", path))
+}
+
+/// Builds the server-side TLS configuration. If the client CA is configured,
+/// only the clients presenting a certificate signed by it are accepted.
+pub fn load_server_config(config: &ServerTlsConfig) -> anyhow::Result<ServerConfig> {
+    let certificates = load_certificates(&config.cert_path)?;
+    let private_key = load_private_key(&config.key_path)?;
+
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = match &config.client_ca_path {
+        Some(client_ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in load_certificates(client_ca_path)? {
+                roots
+                    .add(&certificate)
+                    .with_context(|| format!("invalid CA certificate in {}", client_ca_path))?;
+            }
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+        }
+        None => builder.with_no_client_auth(),
+    };
+    builder
+        .with_single_cert(certificates, private_key)
+        .context("invalid server certificate or private key")
+}
@@ -0,0 +1,254 @@
+use vm::oracles::tracer::{far_call_calldata, far_ret_returndata, TracerPlugin};
+use vm::zk_evm::{
+    abstractions::{AfterExecutionData, BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::{Opcode, RetOpcode},
+};
+use vm::{memory::SimpleMemory, VmExecutionResult};
+use zksync_config::constants::{BOOTLOADER_ADDRESS, MSG_VALUE_SIMULATOR_ADDRESS};
+use zksync_types::{
+    ethabi::{self, ParamType, Token},
+    vm_trace::{CallFrame, CallLog, CallType, DebugTraceResult, TracerConfig},
+    Address, Transaction, U256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use super::{sandbox_error, DebugTracer};
+use crate::api_server::execution_sandbox::SandboxExecutionError;
+
+/// Selector of the `Error(string)` revert reason.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+#[derive(Debug)]
+struct PendingCall {
+    caller: Address,
+    input: Vec<u8>,
+    depth: usize,
+}
+
+/// Builds the call tree of the transaction in the format of the geth `callTracer`.
+///
+/// The calls made by the bootloader and the system contracts to process the transaction
+/// (validation, fee payment, etc.) are not included: the trace starts with the call that
+/// the bootloader makes on behalf of the transaction initiator.
+#[derive(Debug)]
+pub struct CallTracer {
+    config: TracerConfig,
+    initiator: Address,
+    value: U256,
+    pending_call: Option<PendingCall>,
+    /// Frames that are being executed, together with the gas they have started with.
+    stack: Vec<(CallFrame, u32)>,
+    root: Option<CallFrame>,
+}
+
+impl CallTracer {
+    pub fn new(tx: &Transaction, config: TracerConfig) -> Self {
+        Self {
+            config,
+            initiator: tx.initiator_account(),
+            value: tx.execute.value,
+            pending_call: None,
+            stack: Vec::new(),
+            root: None,
+        }
+    }
+
+    /// Returns the top-level call frame. Fails if the call on behalf of the initiator
+    /// has not been made, e.g. because the transaction has been rejected by the bootloader.
+    pub(super) fn into_root(
+        self,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<(CallFrame, Result<VmExecutionResult, SandboxExecutionError>), Web3Error> {
+        match (self.root, result) {
+            (Some(root), result) => Ok((root, result)),
+            (None, Err(err)) => Err(sandbox_error(err)),
+            (None, Ok(_)) => Err(Web3Error::InternalError),
+        }
+    }
+
+    pub(super) fn is_recording(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    fn enter_frame(&mut self, state: &VmLocalStateData<'_>, pending_call: PendingCall) {
+        let frame = &state.vm_local_state.callstack.current;
+        let is_root = !self.is_recording();
+        if is_root {
+            let called_on_behalf_of_initiator = frame.msg_sender == self.initiator
+                && frame.code_address != MSG_VALUE_SIMULATOR_ADDRESS
+                && (pending_call.caller == BOOTLOADER_ADDRESS
+                    || pending_call.caller == MSG_VALUE_SIMULATOR_ADDRESS);
+            if self.root.is_some() || !called_on_behalf_of_initiator {
+                return;
+            }
+        }
+
+        let call_type = if frame.is_static {
+            CallType::StaticCall
+        } else if frame.code_address != frame.this_address {
+            CallType::DelegateCall
+        } else {
+            CallType::Call
+        };
+        let value = match call_type {
+            CallType::Call if is_root => Some(self.value),
+            CallType::Call => Some(frame.context_u128_value.into()),
+            CallType::DelegateCall | CallType::StaticCall => None,
+        };
+        let call_frame = CallFrame {
+            call_type,
+            from: if is_root {
+                frame.msg_sender
+            } else {
+                pending_call.caller
+            },
+            to: frame.code_address,
+            value,
+            gas: frame.ergs_remaining.into(),
+            gas_used: U256::zero(),
+            input: pending_call.input.into(),
+            output: None,
+            error: None,
+            revert_reason: None,
+            calls: vec![],
+            logs: vec![],
+        };
+        self.stack.push((call_frame, frame.ergs_remaining));
+    }
+
+    fn exit_frame(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory,
+        ret: RetOpcode,
+    ) {
+        let (mut call_frame, gas_before) = self.stack.pop().unwrap();
+        let gas_remaining = state.vm_local_state.callstack.current.ergs_remaining;
+        call_frame.gas_used = gas_before.saturating_sub(gas_remaining).into();
+        match ret {
+            RetOpcode::Ok => {
+                call_frame.output = Some(far_ret_returndata(state, data, memory).into());
+            }
+            RetOpcode::Revert => {
+                let output = far_ret_returndata(state, data, memory);
+                call_frame.error = Some("execution reverted".to_string());
+                call_frame.revert_reason = decode_revert_reason(&output);
+                call_frame.output = Some(output.into());
+            }
+            RetOpcode::Panic => {
+                call_frame.error = Some("panic".to_string());
+            }
+        }
+
+        match self.stack.last_mut() {
+            Some((parent, _)) => {
+                if !self.config.only_top_call {
+                    parent.calls.push(call_frame);
+                }
+            }
+            None => self.root = Some(call_frame),
+        }
+    }
+}
+
+impl TracerPlugin for CallTracer {
+    fn before_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        let current = &state.vm_local_state.callstack.current;
+        match data.opcode.variant.opcode {
+            Opcode::FarCall(_) => {
+                let may_start_recording = self.root.is_none()
+                    && (current.this_address == BOOTLOADER_ADDRESS
+                        || current.this_address == MSG_VALUE_SIMULATOR_ADDRESS);
+                if self.is_recording() || may_start_recording {
+                    self.pending_call = Some(PendingCall {
+                        caller: current.this_address,
+                        input: far_call_calldata(state, data, memory),
+                        depth: state.vm_local_state.callstack.inner.len(),
+                    });
+                }
+            }
+            Opcode::Ret(ret) if !current.is_local_frame && self.is_recording() => {
+                self.exit_frame(state, data, memory, ret);
+            }
+            _ => {}
+        }
+    }
+
+    fn after_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        _data: &AfterExecutionData,
+        _memory: &SimpleMemory,
+    ) {
+        if let Some(pending_call) = self.pending_call.take() {
+            // The far call may fail before the new frame is created.
+            if state.vm_local_state.callstack.inner.len() > pending_call.depth {
+                self.enter_frame(state, pending_call);
+            }
+        }
+    }
+}
+
+impl DebugTracer for CallTracer {
+    fn as_plugin(&mut self) -> &mut dyn TracerPlugin {
+        self
+    }
+
+    fn into_trace(
+        self: Box<Self>,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<DebugTraceResult, Web3Error> {
+        let with_log = self.config.with_log;
+        let (mut root, result) = self.into_root(result)?;
+
+        if let (true, Ok(result)) = (with_log, &result) {
+            // Events don't carry the information about the frame that has emitted them,
+            // so each event is attached to the first frame executed in the context of the emitter.
+            for event in &result.events {
+                let log = CallLog {
+                    address: event.address,
+                    topics: event.indexed_topics.clone(),
+                    data: event.value.clone().into(),
+                };
+                attach_log(&mut root, &log);
+            }
+        }
+        Ok(DebugTraceResult::CallTrace(root))
+    }
+}
+
+/// Returns the address in the context of which the frame is executed.
+fn context_address(frame: &CallFrame) -> Address {
+    match frame.call_type {
+        CallType::DelegateCall => frame.from,
+        CallType::Call | CallType::StaticCall => frame.to,
+    }
+}
+
+/// Returns `false` if there is no frame executed in the context of the log emitter.
+fn attach_log(frame: &mut CallFrame, log: &CallLog) -> bool {
+    if context_address(frame) == log.address {
+        frame.logs.push(log.clone());
+        return true;
+    }
+    frame.calls.iter_mut().any(|call| attach_log(call, log))
+}
+
+fn decode_revert_reason(output: &[u8]) -> Option<String> {
+    if output.len() < 4 || output[..4] != ERROR_SELECTOR {
+        return None;
+    }
+    match ethabi::decode(&[ParamType::String], &output[4..])
+        .ok()?
+        .pop()?
+    {
+        Token::String(reason) => Some(reason),
+        _ => None,
+    }
+}
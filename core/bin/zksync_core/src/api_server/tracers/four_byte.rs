@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use vm::oracles::tracer::TracerPlugin;
+use vm::zk_evm::abstractions::{AfterExecutionData, BeforeExecutionData, VmLocalStateData};
+use vm::{memory::SimpleMemory, VmExecutionResult};
+use zksync_config::constants::{
+    ECRECOVER_PRECOMPILE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
+};
+use zksync_types::{
+    vm_trace::{CallFrame, DebugTraceResult, TracerConfig},
+    Transaction,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use super::{CallTracer, DebugTracer};
+use crate::api_server::execution_sandbox::SandboxExecutionError;
+
+/// Counts the function selectors of the calls made by the transaction
+/// in the format of the geth `4byteTracer`.
+#[derive(Debug)]
+pub struct FourByteTracer {
+    calls: CallTracer,
+}
+
+impl FourByteTracer {
+    pub fn new(tx: &Transaction) -> Self {
+        Self {
+            calls: CallTracer::new(tx, TracerConfig::default()),
+        }
+    }
+}
+
+impl TracerPlugin for FourByteTracer {
+    fn before_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        self.calls.before_execution(state, data, memory);
+    }
+
+    fn after_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &AfterExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        self.calls.after_execution(state, data, memory);
+    }
+}
+
+impl DebugTracer for FourByteTracer {
+    fn as_plugin(&mut self) -> &mut dyn TracerPlugin {
+        self
+    }
+
+    fn into_trace(
+        self: Box<Self>,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<DebugTraceResult, Web3Error> {
+        let (root, _) = self.calls.into_root(result)?;
+        let mut selectors = BTreeMap::new();
+        count_selectors(&root, &mut selectors);
+        Ok(DebugTraceResult::FourByteTrace(selectors))
+    }
+}
+
+fn count_selectors(frame: &CallFrame, selectors: &mut BTreeMap<String, u64>) {
+    let is_precompile = [
+        ECRECOVER_PRECOMPILE_ADDRESS,
+        SHA256_PRECOMPILE_ADDRESS,
+        KECCAK256_PRECOMPILE_ADDRESS,
+    ]
+    .contains(&frame.to);
+    let input = &frame.input.0;
+    if !is_precompile && input.len() >= 4 {
+        let key = format!("0x{}-{}", hex::encode(&input[..4]), input.len() - 4);
+        *selectors.entry(key).or_default() += 1;
+    }
+    for call in &frame.calls {
+        count_selectors(call, selectors);
+    }
+}
@@ -0,0 +1,67 @@
+//! Tracers used by the `debug` namespace of the Web3 API.
+//!
+//! Each tracer is a VM tracer plugin that observes the transaction executed in the sandbox
+//! and turns the collected data into the response of the corresponding geth tracer.
+//! To add a new tracer, implement `DebugTracer` for it and register it in `create_tracer`.
+
+use vm::oracles::tracer::TracerPlugin;
+use vm::VmExecutionResult;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    vm_trace::{DebugTraceResult, SupportedTracer, TracerOptions},
+    Transaction,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::execution_sandbox::SandboxExecutionError;
+use crate::api_server::tx_sender::SubmitTxError;
+
+mod call;
+mod four_byte;
+mod prestate;
+mod security;
+mod struct_log;
+
+pub use self::{
+    call::CallTracer,
+    four_byte::FourByteTracer,
+    prestate::PrestateTracer,
+    security::SecurityTracer,
+    struct_log::{StructLogLimit, StructLogTracer},
+};
+
+pub trait DebugTracer: TracerPlugin {
+    fn as_plugin(&mut self) -> &mut dyn TracerPlugin;
+
+    /// Builds the trace once the execution is finished.
+    /// The execution result is an error if the transaction has failed.
+    fn into_trace(
+        self: Box<Self>,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<DebugTraceResult, Web3Error>;
+}
+
+/// Creates the tracer for the transaction. The struct log tracers of a request share `struct_log_limit`.
+/// `connection_pool` is used by the tracers that report the data not available from the execution (e.g., bytecodes).
+pub fn create_tracer(
+    options: &TracerOptions,
+    tx: &Transaction,
+    struct_log_limit: &StructLogLimit,
+    connection_pool: &ConnectionPool,
+) -> Result<Box<dyn DebugTracer>, Web3Error> {
+    Ok(match options.tracer {
+        SupportedTracer::StructLogger => {
+            Box::new(StructLogTracer::new(tx, struct_log_limit.clone()))
+        }
+        SupportedTracer::CallTracer => Box::new(CallTracer::new(tx, options.tracer_config)),
+        SupportedTracer::PrestateTracer => {
+            Box::new(PrestateTracer::new(tx, connection_pool.clone()))
+        }
+        SupportedTracer::FourByteTracer => Box::new(FourByteTracer::new(tx)),
+        SupportedTracer::SecurityTracer => Box::new(SecurityTracer::new(tx, options.tracer_config)),
+    })
+}
+
+fn sandbox_error(err: SandboxExecutionError) -> Web3Error {
+    SubmitTxError::from(err).into()
+}
@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, HashMap};
+
+use vm::oracles::tracer::TracerPlugin;
+use vm::zk_evm::abstractions::{AfterExecutionData, BeforeExecutionData, VmLocalStateData};
+use vm::{memory::SimpleMemory, VmExecutionResult};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    get_code_key, get_nonce_key,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
+    vm_trace::{CallFrame, DebugTraceResult, PrestateAccount, TracerConfig},
+    AccountTreeId, Address, StorageKey, Transaction, BOOTLOADER_ADDRESS, H256,
+};
+use zksync_utils::{h256_to_u256, u256_to_h256};
+use zksync_web3_decl::error::Web3Error;
+
+use super::{sandbox_error, CallTracer, DebugTracer};
+use crate::api_server::execution_sandbox::SandboxExecutionError;
+
+/// Collects the state accessed by the transaction as it was before the execution
+/// in the format of the geth `prestateTracer`.
+///
+/// Balances, nonces and code hashes are stored in the system contracts, so the corresponding slots
+/// are reported as the `balance`, `nonce` and `code` fields of the accounts participating in the calls.
+/// All the other accessed slots are reported as the storage of the contracts they belong to.
+#[derive(Debug)]
+pub struct PrestateTracer {
+    initiator: Address,
+    calls: CallTracer,
+    // Used to load the bytecodes of the called contracts by their hashes.
+    connection_pool: ConnectionPool,
+}
+
+impl PrestateTracer {
+    pub fn new(tx: &Transaction, connection_pool: ConnectionPool) -> Self {
+        Self {
+            initiator: tx.initiator_account(),
+            calls: CallTracer::new(tx, TracerConfig::default()),
+            connection_pool,
+        }
+    }
+}
+
+impl TracerPlugin for PrestateTracer {
+    fn before_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        self.calls.before_execution(state, data, memory);
+    }
+
+    fn after_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &AfterExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        self.calls.after_execution(state, data, memory);
+    }
+}
+
+impl DebugTracer for PrestateTracer {
+    fn as_plugin(&mut self) -> &mut dyn TracerPlugin {
+        self
+    }
+
+    fn into_trace(
+        self: Box<Self>,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<DebugTraceResult, Web3Error> {
+        let initiator = self.initiator;
+        let connection_pool = self.connection_pool;
+        let (root, result) = self.calls.into_root(result)?;
+        // Storage accesses are only returned for the successfully executed transactions.
+        let result = result.map_err(sandbox_error)?;
+
+        // The first access to each slot yields the value it had before the execution.
+        let mut initial_values: HashMap<StorageKey, H256> = HashMap::new();
+        for storage_log in &result.storage_log_queries {
+            let log_query = &storage_log.log_query;
+            if log_query.address == BOOTLOADER_ADDRESS {
+                continue;
+            }
+            let key = StorageKey::new(
+                AccountTreeId::new(log_query.address),
+                u256_to_h256(log_query.key),
+            );
+            initial_values
+                .entry(key)
+                .or_insert_with(|| u256_to_h256(log_query.read_value));
+        }
+
+        let mut accounts: BTreeMap<Address, PrestateAccount> = BTreeMap::new();
+        let mut participants = vec![initiator];
+        collect_participants(&root, &mut participants);
+        let mut storage = connection_pool.access_storage_blocking();
+        for address in participants {
+            let account = accounts.entry(address).or_default();
+            if let Some(balance) = initial_values.remove(&storage_key_for_eth_balance(&address)) {
+                account.balance = Some(h256_to_u256(balance));
+            }
+            if let Some(full_nonce) = initial_values.remove(&get_nonce_key(&address)) {
+                let (nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+                account.nonce = Some(nonce.as_u64());
+            }
+            // The code hash is read when the contract is called. It's zero for the accounts without code,
+            // including the contracts deployed by the transaction.
+            if let Some(code_hash) = initial_values.remove(&get_code_key(&address)) {
+                if !code_hash.is_zero() {
+                    let code = storage.storage_dal().get_factory_dep(code_hash);
+                    account.code = code.map(Into::into);
+                }
+            }
+        }
+        drop(storage);
+        for (key, value) in initial_values {
+            accounts
+                .entry(*key.address())
+                .or_default()
+                .storage
+                .insert(*key.key(), value);
+        }
+        Ok(DebugTraceResult::PrestateTrace(accounts))
+    }
+}
+
+fn collect_participants(frame: &CallFrame, participants: &mut Vec<Address>) {
+    participants.push(frame.from);
+    participants.push(frame.to);
+    for call in &frame.calls {
+        collect_participants(call, participants);
+    }
+}
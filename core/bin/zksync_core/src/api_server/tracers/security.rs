@@ -0,0 +1,234 @@
+use vm::oracles::tracer::TracerPlugin;
+use vm::zk_evm::{
+    abstractions::{AfterExecutionData, BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::{LogOpcode, Opcode},
+};
+use vm::{memory::SimpleMemory, VmExecutionResult};
+use zksync_types::{
+    vm_trace::{
+        CallFrame, CallType, DebugTraceResult, ReentrancyCycle, SecurityReport, StateChangeKind,
+        StaticCallViolation, TracerConfig,
+    },
+    Address, Transaction,
+};
+use zksync_utils::u256_to_h256;
+use zksync_web3_decl::error::Web3Error;
+
+use super::{CallTracer, DebugTracer};
+use crate::api_server::execution_sandbox::SandboxExecutionError;
+
+/// Upper bound of the kernel space, where the system contracts are deployed.
+const MAX_SYSTEM_CONTRACT_ADDRESS: u64 = 0xffff;
+
+/// Reports the patterns relevant for the security tooling:
+///
+/// - state changes attempted in the static call context. Such changes make the VM panic,
+///   so they are reported even though they don't make it into the state;
+/// - contracts re-entered while their earlier frame is still being executed. System contracts
+///   are excluded, since they are called by every transaction and are reentrant by design.
+///
+/// Only the calls made on behalf of the transaction initiator are analyzed, as in [`CallTracer`].
+#[derive(Debug)]
+pub struct SecurityTracer {
+    fail_on_findings: bool,
+    calls: CallTracer,
+    static_call_violations: Vec<StaticCallViolation>,
+}
+
+impl SecurityTracer {
+    pub fn new(tx: &Transaction, config: TracerConfig) -> Self {
+        Self {
+            fail_on_findings: config.fail_on_findings,
+            calls: CallTracer::new(tx, TracerConfig::default()),
+            static_call_violations: vec![],
+        }
+    }
+}
+
+impl TracerPlugin for SecurityTracer {
+    fn before_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        let current = &state.vm_local_state.callstack.current;
+        if current.is_static && self.calls.is_recording() {
+            let change = match data.opcode.variant.opcode {
+                Opcode::Log(LogOpcode::StorageWrite) => Some((
+                    StateChangeKind::StorageWrite,
+                    Some(u256_to_h256(data.src0_value.value)),
+                )),
+                Opcode::Log(LogOpcode::Event) => Some((StateChangeKind::Event, None)),
+                Opcode::Log(LogOpcode::ToL1Message) => Some((StateChangeKind::L1Message, None)),
+                _ => None,
+            };
+            if let Some((kind, key)) = change {
+                self.static_call_violations.push(StaticCallViolation {
+                    address: current.this_address,
+                    kind,
+                    key,
+                });
+            }
+        }
+        self.calls.before_execution(state, data, memory);
+    }
+
+    fn after_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &AfterExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        self.calls.after_execution(state, data, memory);
+    }
+}
+
+impl DebugTracer for SecurityTracer {
+    fn as_plugin(&mut self) -> &mut dyn TracerPlugin {
+        self
+    }
+
+    fn into_trace(
+        self: Box<Self>,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<DebugTraceResult, Web3Error> {
+        // Unlike the other tracers, the findings are reported for the failed transactions as well,
+        // since the violations often make the transaction fail.
+        let (root, _) = self.calls.into_root(result)?;
+        let mut reentrancy_cycles = vec![];
+        collect_reentrancy_cycles(&root, &mut vec![], &mut reentrancy_cycles);
+        let report = SecurityReport {
+            static_call_violations: self.static_call_violations,
+            reentrancy_cycles,
+        };
+
+        if self.fail_on_findings && !report.is_empty() {
+            return Err(Web3Error::SecurityCheckFailed(format!(
+                "{} state changes in static calls, {} reentrancy cycles",
+                report.static_call_violations.len(),
+                report.reentrancy_cycles.len()
+            )));
+        }
+        Ok(DebugTraceResult::SecurityTrace(report))
+    }
+}
+
+fn is_system_contract(address: Address) -> bool {
+    address <= Address::from_low_u64_be(MAX_SYSTEM_CONTRACT_ADDRESS)
+}
+
+/// Returns the address in the context of which the frame is executed.
+fn context_address(frame: &CallFrame) -> Address {
+    match frame.call_type {
+        CallType::DelegateCall => frame.from,
+        CallType::Call | CallType::StaticCall => frame.to,
+    }
+}
+
+/// Walks the call tree keeping the contexts of the frames being executed in `path`.
+fn collect_reentrancy_cycles(
+    frame: &CallFrame,
+    path: &mut Vec<Address>,
+    cycles: &mut Vec<ReentrancyCycle>,
+) {
+    let address = context_address(frame);
+    let is_tracked = !is_system_contract(address) && path.last() != Some(&address);
+    if is_tracked {
+        // Calls to the contract itself (e.g., `this.method()`) are not considered reentrancy,
+        // since the contract is the only one in control.
+        if let Some(start) = path.iter().position(|&entered| entered == address) {
+            let mut cycle_path = path[start..].to_vec();
+            cycle_path.push(address);
+            let cycle = ReentrancyCycle {
+                address,
+                call_type: frame.call_type,
+                path: cycle_path,
+            };
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+        }
+        path.push(address);
+    }
+
+    for call in &frame.calls {
+        collect_reentrancy_cycles(call, path, cycles);
+    }
+    if is_tracked {
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::U256;
+
+    fn frame(call_type: CallType, from: u64, to: u64, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            call_type,
+            from: Address::from_low_u64_be(from),
+            to: Address::from_low_u64_be(to),
+            value: None,
+            gas: U256::zero(),
+            gas_used: U256::zero(),
+            input: vec![].into(),
+            output: None,
+            error: None,
+            revert_reason: None,
+            calls,
+            logs: vec![],
+        }
+    }
+
+    #[test]
+    fn detecting_reentrancy_cycles() {
+        let (user, vault, attacker) = (0x10_0001, 0x10_0002, 0x10_0003);
+        // The vault pays out via the system contract, and the attacker re-enters it twice.
+        let reentrant_call = frame(CallType::Call, attacker, vault, vec![]);
+        let payout = frame(
+            CallType::Call,
+            0x8009,
+            attacker,
+            vec![
+                reentrant_call.clone(),
+                reentrant_call,
+                frame(CallType::StaticCall, attacker, vault, vec![]),
+            ],
+        );
+        let root = frame(
+            CallType::Call,
+            user,
+            vault,
+            vec![
+                frame(CallType::Call, vault, 0x8009, vec![payout]),
+                // Self-calls are not reported.
+                frame(CallType::Call, vault, vault, vec![]),
+            ],
+        );
+
+        let mut cycles = vec![];
+        collect_reentrancy_cycles(&root, &mut vec![], &mut cycles);
+        let path = vec![
+            Address::from_low_u64_be(vault),
+            Address::from_low_u64_be(attacker),
+            Address::from_low_u64_be(vault),
+        ];
+        assert_eq!(
+            cycles,
+            [
+                ReentrancyCycle {
+                    address: Address::from_low_u64_be(vault),
+                    call_type: CallType::Call,
+                    path: path.clone(),
+                },
+                ReentrancyCycle {
+                    address: Address::from_low_u64_be(vault),
+                    call_type: CallType::StaticCall,
+                    path,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use vm::oracles::tracer::TracerPlugin;
+use vm::zk_evm::{
+    abstractions::{AfterExecutionData, BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::Opcode,
+};
+use vm::{memory::SimpleMemory, VmExecutionResult};
+use zksync_types::{
+    vm_trace::{DebugTraceResult, StructLog, StructLogTrace, TracerConfig},
+    Transaction,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use super::{CallTracer, DebugTracer};
+use crate::api_server::execution_sandbox::SandboxExecutionError;
+
+/// Max number of opcodes in the traces of a single request. Traces of the heavy transactions don't fit
+/// into a response anyway, so the tracers stop collecting them to bound the memory used by the API server.
+const MAX_STRUCT_LOGS: usize = 1 << 20;
+
+/// Names of the opcodes, formatted once per opcode rather than for each executed one. The number of opcodes
+/// is bounded, so the names are never released.
+static OPCODE_NAMES: Lazy<Mutex<Vec<(Opcode, &'static str)>>> = Lazy::new(Mutex::default);
+
+fn opcode_name(opcode: Opcode) -> &'static str {
+    let mut names = OPCODE_NAMES.lock().unwrap();
+    if let Some(&(_, name)) = names.iter().find(|(known, _)| *known == opcode) {
+        return name;
+    }
+    let name: &'static str = Box::leak(format!("{:?}", opcode).into_boxed_str());
+    names.push((opcode, name));
+    name
+}
+
+/// Number of opcodes the struct log tracers may still record, shared by all the tracers of a request.
+#[derive(Debug, Clone)]
+pub struct StructLogLimit(Arc<AtomicUsize>);
+
+impl Default for StructLogLimit {
+    fn default() -> Self {
+        Self(Arc::new(AtomicUsize::new(MAX_STRUCT_LOGS)))
+    }
+}
+
+impl StructLogLimit {
+    /// Returns `false` if the limit is exhausted.
+    fn take_one(&self) -> bool {
+        self.0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .is_ok()
+    }
+}
+
+#[derive(Debug)]
+struct PendingStep {
+    index: usize,
+    callstack_depth: usize,
+}
+
+/// Records the opcodes executed by the transaction in the format of the geth struct logger.
+/// As in [`CallTracer`], only the opcodes executed on behalf of the transaction initiator are recorded.
+#[derive(Debug)]
+pub struct StructLogTracer {
+    calls: CallTracer,
+    /// Depth of the VM call stack in the top-level call frame.
+    root_depth: Option<usize>,
+    pending_step: Option<PendingStep>,
+    struct_logs: Vec<StructLog>,
+    limit: StructLogLimit,
+    is_truncated: bool,
+    /// Names of the opcodes executed so far, so that the shared names aren't looked up for each opcode.
+    opcode_names: Vec<(Opcode, &'static str)>,
+}
+
+impl StructLogTracer {
+    pub fn new(tx: &Transaction, limit: StructLogLimit) -> Self {
+        Self {
+            calls: CallTracer::new(tx, TracerConfig::default()),
+            root_depth: None,
+            pending_step: None,
+            struct_logs: vec![],
+            limit,
+            is_truncated: false,
+            opcode_names: vec![],
+        }
+    }
+
+    fn opcode_name(&mut self, opcode: Opcode) -> &'static str {
+        if let Some(&(_, name)) = self.opcode_names.iter().find(|(known, _)| *known == opcode) {
+            return name;
+        }
+        let name = opcode_name(opcode);
+        self.opcode_names.push((opcode, name));
+        name
+    }
+}
+
+impl TracerPlugin for StructLogTracer {
+    fn before_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        if self.calls.is_recording() && !self.is_truncated {
+            if self.limit.take_one() {
+                let callstack_depth = state.vm_local_state.callstack.inner.len();
+                let root_depth = *self.root_depth.get_or_insert(callstack_depth);
+                let current = &state.vm_local_state.callstack.current;
+                let op = self.opcode_name(data.opcode.variant.opcode);
+                self.pending_step = Some(PendingStep {
+                    index: self.struct_logs.len(),
+                    callstack_depth,
+                });
+                self.struct_logs.push(StructLog {
+                    pc: current.pc,
+                    op: op.into(),
+                    gas: current.ergs_remaining,
+                    gas_cost: 0,
+                    depth: callstack_depth.saturating_sub(root_depth) + 1,
+                    error: None,
+                });
+            } else {
+                self.is_truncated = true;
+            }
+        }
+        self.calls.before_execution(state, data, memory);
+    }
+
+    fn after_execution(
+        &mut self,
+        state: &VmLocalStateData<'_>,
+        data: &AfterExecutionData,
+        memory: &SimpleMemory,
+    ) {
+        if let Some(step) = self.pending_step.take() {
+            // The cost is only known for the opcodes that don't switch the frame; far calls and returns
+            // are reported with zero cost, since the gas is passed between the frames.
+            if state.vm_local_state.callstack.inner.len() == step.callstack_depth {
+                let struct_log = &mut self.struct_logs[step.index];
+                let gas_remaining = state.vm_local_state.callstack.current.ergs_remaining;
+                struct_log.gas_cost = struct_log.gas.saturating_sub(gas_remaining);
+            }
+        }
+        self.calls.after_execution(state, data, memory);
+    }
+}
+
+impl DebugTracer for StructLogTracer {
+    fn as_plugin(&mut self) -> &mut dyn TracerPlugin {
+        self
+    }
+
+    fn into_trace(
+        self: Box<Self>,
+        result: Result<VmExecutionResult, SandboxExecutionError>,
+    ) -> Result<DebugTraceResult, Web3Error> {
+        if self.is_truncated {
+            return Err(Web3Error::ResultLimitExceeded(MAX_STRUCT_LOGS));
+        }
+        let (root, _) = self.calls.into_root(result)?;
+        Ok(DebugTraceResult::StructLogTrace(StructLogTrace {
+            gas: root.gas_used,
+            failed: root.error.is_some(),
+            return_value: root.output.unwrap_or_default(),
+            struct_logs: self.struct_logs,
+        }))
+    }
+}
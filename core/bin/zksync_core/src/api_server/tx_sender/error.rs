@@ -1,8 +1,10 @@
 use crate::api_server::execution_sandbox::SandboxExecutionError;
+use crate::state_keeper::deployer_allowlist::DeploymentNotAllowed;
 use thiserror::Error;
 use vm::oracles::tracer::ValidationError;
 use zksync_types::l2::error::TxCheckError;
 use zksync_types::U256;
+use zksync_web3_decl::error::Web3Error;
 
 #[derive(Debug, Error)]
 pub enum SubmitTxError {
@@ -16,8 +18,13 @@ pub enum SubmitTxError {
     NotEnoughBalanceForFeeValue(U256, U256, U256),
     #[error("cannot estimate transaction: {0}.")]
     CannotEstimateTransaction(String),
+    /// The transaction reverted during the estimation; contains the reason and the revert data.
+    #[error("execution reverted: {0}")]
+    ExecutionReverted(String, Vec<u8>),
     #[error("exceeds block gas limit")]
     GasLimitIsTooBig,
+    #[error("gas limit {0} exceeds the max allowed transaction gas limit {1}")]
+    GasLimitExceedsCap(U256, u32),
     #[error("{0}")]
     Unexecutable(String),
     #[error("too many transactions")]
@@ -64,6 +71,18 @@ pub enum SubmitTxError {
     /// Error returned from main node
     #[error("{0}")]
     ProxyError(#[from] zksync_web3_decl::jsonrpsee::core::Error),
+    #[error("encrypted mempool is disabled")]
+    EncryptedMempoolDisabled,
+    #[error("encrypted transaction is too big. {0} bytes provided, while only {1} allowed")]
+    EncryptedTxTooBig(usize, usize),
+    #[error("malformed encrypted transaction: {0}")]
+    MalformedEncryptedTx(String),
+    #[error("{0}")]
+    DeploymentNotAllowed(#[from] DeploymentNotAllowed),
+    #[error("transaction expired at {0}, current timestamp: {1}")]
+    TxExpired(u64, u64),
+    #[error("{0}")]
+    ForkSourceUnavailable(String),
 }
 impl SubmitTxError {
     pub fn grafana_error_code(&self) -> &'static str {
@@ -73,7 +92,9 @@ impl SubmitTxError {
             SubmitTxError::IncorrectTx(_) => "incorrect-tx",
             SubmitTxError::NotEnoughBalanceForFeeValue(_, _, _) => "not-enough-balance-for-fee",
             SubmitTxError::CannotEstimateTransaction(_) => "cannot-estimate-transaction",
+            SubmitTxError::ExecutionReverted(_, _) => "execution-reverted",
             SubmitTxError::GasLimitIsTooBig => "gas-limit-is-too-big",
+            SubmitTxError::GasLimitExceedsCap(_, _) => "gas-limit-exceeds-cap",
             SubmitTxError::Unexecutable(_) => "unexecutable",
             SubmitTxError::RateLimitExceeded => "rate-limit-exceeded",
             SubmitTxError::BootloaderFailure(_) => "bootloader-failure",
@@ -94,6 +115,12 @@ impl SubmitTxError {
             SubmitTxError::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
             SubmitTxError::IntrinsicGas => "intrinsic-gas",
             SubmitTxError::ProxyError(_) => "proxy-error",
+            SubmitTxError::EncryptedMempoolDisabled => "encrypted-mempool-disabled",
+            SubmitTxError::EncryptedTxTooBig(_, _) => "encrypted-tx-too-big",
+            SubmitTxError::MalformedEncryptedTx(_) => "malformed-encrypted-tx",
+            SubmitTxError::DeploymentNotAllowed(_) => "deployment-not-allowed",
+            SubmitTxError::TxExpired(_, _) => "tx-expired",
+            SubmitTxError::ForkSourceUnavailable(_) => "fork-source-unavailable",
         }
     }
 }
@@ -101,8 +128,8 @@ impl SubmitTxError {
 impl From<SandboxExecutionError> for SubmitTxError {
     fn from(err: SandboxExecutionError) -> SubmitTxError {
         match err {
-            SandboxExecutionError::Revert(reason) => {
-                SubmitTxError::CannotEstimateTransaction(reason)
+            SandboxExecutionError::Revert(reason, data) => {
+                SubmitTxError::ExecutionReverted(reason, data)
             }
             SandboxExecutionError::BootloaderFailure(reason) => {
                 SubmitTxError::BootloaderFailure(reason)
@@ -130,6 +157,9 @@ impl From<SandboxExecutionError> for SubmitTxError {
                 SubmitTxError::FailedToChargeFee(reason)
             }
             SandboxExecutionError::Unexecutable(reason) => SubmitTxError::Unexecutable(reason),
+            SandboxExecutionError::ForkSourceUnavailable(err) => {
+                SubmitTxError::ForkSourceUnavailable(err.to_string())
+            }
         }
     }
 }
@@ -139,3 +169,15 @@ impl From<ValidationError> for SubmitTxError {
         Self::ValidationFailed(err.to_string())
     }
 }
+
+impl From<SubmitTxError> for Web3Error {
+    fn from(err: SubmitTxError) -> Self {
+        match err {
+            SubmitTxError::ExecutionReverted(reason, data) => Self::ExecutionReverted(reason, data),
+            SubmitTxError::GasLimitExceedsCap(gas_limit, cap) => {
+                Self::TxGasLimitExceedsCap(gas_limit, cap)
+            }
+            err => Self::SubmitTransactionError(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Fee parameters suggested to the users. All the API methods suggesting fees (`eth_gasPrice`,
+//! `eth_maxPriorityFeePerGas`, `eth_estimateGas` and `zks_estimateFee`) derive them from `FeeSuggestion`,
+//! so that the methods never disagree with each other.
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
+use zksync_dal::ConnectionPool;
+use zksync_types::U256;
+
+use crate::api_server::execution_sandbox::adjust_l1_gas_price_for_tx;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSuggestion {
+    pub l1_gas_price: u64,
+    pub fair_l2_gas_price: u64,
+    pub base_fee: u64,
+    pub gas_per_pubdata: u64,
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl FeeSuggestion {
+    pub fn new(l1_gas_price: u64, fair_l2_gas_price: u64, max_priority_fee_per_gas: U256) -> Self {
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
+        Self {
+            l1_gas_price,
+            fair_l2_gas_price,
+            base_fee,
+            gas_per_pubdata,
+            max_priority_fee_per_gas,
+        }
+    }
+
+    /// Lowers the L1 gas price, so that the required gas per pubdata byte fits into the limit of the transaction.
+    pub fn for_gas_per_pubdata_limit(self, gas_per_pubdata_limit: U256) -> Self {
+        let l1_gas_price = adjust_l1_gas_price_for_tx(
+            self.l1_gas_price,
+            self.fair_l2_gas_price,
+            gas_per_pubdata_limit,
+        );
+        Self::new(
+            l1_gas_price,
+            self.fair_l2_gas_price,
+            self.max_priority_fee_per_gas,
+        )
+    }
+
+    /// Suggested max fee per gas, which is also used as the gas price of the legacy transactions.
+    pub fn max_fee_per_gas(&self) -> U256 {
+        U256::from(self.base_fee) + self.max_priority_fee_per_gas
+    }
+}
+
+/// Provides the median priority fee paid by the recently included transactions.
+/// The value is reloaded from the database once it's older than the TTL. If the reload fails, the stale value
+/// (or zero, if nothing was loaded yet) is served, and the reload is retried on the next call.
+#[derive(Debug, Clone)]
+pub struct PriorityFeeOracle {
+    pool: ConnectionPool,
+    cache: Arc<RwLock<Option<(U256, Instant)>>>,
+}
+
+impl PriorityFeeOracle {
+    const TTL: Duration = Duration::from_secs(1);
+    /// Number of the last miniblocks the included transactions are taken from.
+    const MINIBLOCKS_COUNT: u32 = 100;
+
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self {
+            pool,
+            cache: Arc::default(),
+        }
+    }
+
+    pub fn get(&self) -> U256 {
+        let cached = *self.cache.read().unwrap();
+        if let Some((priority_fee, loaded_at)) = cached {
+            if loaded_at.elapsed() < Self::TTL {
+                return priority_fee;
+            }
+        }
+
+        let loaded = self
+            .pool
+            .access_storage_blocking()
+            .blocks_web3_dal()
+            .get_median_priority_fee(Self::MINIBLOCKS_COUNT);
+        match loaded {
+            Ok(priority_fee) => {
+                let priority_fee = priority_fee.unwrap_or_default();
+                *self.cache.write().unwrap() = Some((priority_fee, Instant::now()));
+                priority_fee
+            }
+            Err(err) => {
+                vlog::warn!("Failed to load the median priority fee: {}", err);
+                metrics::increment_counter!("api.web3.priority_fee_oracle.load_errors");
+                cached.map_or_else(U256::zero, |(priority_fee, _)| priority_fee)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_fee_includes_priority_fee() {
+        let suggestion = FeeSuggestion::new(1_000_000_000, 250_000_000, 5.into());
+        assert_eq!(
+            suggestion.max_fee_per_gas(),
+            U256::from(suggestion.base_fee + 5)
+        );
+    }
+
+    #[test]
+    fn suggestion_is_adjusted_for_gas_per_pubdata_limit() {
+        let suggestion = FeeSuggestion::new(100_000_000_000, 250_000_000, U256::zero());
+        let limit = suggestion.gas_per_pubdata / 2;
+
+        let adjusted = suggestion.for_gas_per_pubdata_limit(limit.into());
+        assert!(adjusted.gas_per_pubdata <= limit);
+        assert!(adjusted.l1_gas_price < suggestion.l1_gas_price);
+        assert_eq!(adjusted.max_priority_fee_per_gas, U256::zero());
+
+        // The suggestion that already fits into the limit is not changed.
+        assert_eq!(
+            suggestion.for_gas_per_pubdata_limit(suggestion.gas_per_pubdata.into()),
+            suggestion
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Recording of the lifecycle events of the submitted transactions.
+//!
+//! The events are written to the master DB in batches by a background task rather than on every submission,
+//! so that the DB load doesn't grow with the submission rate of the public API. As a consequence, the events
+//! become visible to `debug_getTransactionLifecycle` with a delay of up to the flush interval.
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::{api::TxLifecycleEvent, H256};
+
+/// Max number of the submissions whose events are waiting to be written. Events are dropped
+/// if the writer can't keep up.
+const SUBMISSIONS_BUFFER_SIZE: usize = 10_000;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+type SubmissionEvents = (H256, Vec<TxLifecycleEvent>);
+
+#[derive(Debug, Clone)]
+pub struct TxLifecycleRecorder {
+    sender: mpsc::Sender<SubmissionEvents>,
+}
+
+impl TxLifecycleRecorder {
+    /// Creates the recorder and spawns the task writing the recorded events with the given pool.
+    /// The task writes the remaining events and exits once all the clones of the recorder are dropped.
+    pub fn spawn(pool: ConnectionPool) -> Self {
+        let (sender, receiver) = mpsc::channel(SUBMISSIONS_BUFFER_SIZE);
+        tokio::spawn(write_events(pool, receiver));
+        Self { sender }
+    }
+
+    pub fn record(&self, tx_hash: H256, events: Vec<TxLifecycleEvent>) {
+        if self.sender.try_send((tx_hash, events)).is_err() {
+            metrics::counter!("api.tx_lifecycle.dropped_submissions", 1);
+        }
+    }
+}
+
+async fn write_events(pool: ConnectionPool, mut receiver: mpsc::Receiver<SubmissionEvents>) {
+    while let Some(submission) = receiver.recv().await {
+        let mut submissions = vec![submission];
+        while let Ok(submission) = receiver.try_recv() {
+            submissions.push(submission);
+        }
+        let events: Vec<_> = submissions
+            .into_iter()
+            .flat_map(|(tx_hash, events)| events.into_iter().map(move |event| (tx_hash, event)))
+            .collect();
+        pool.access_storage_blocking()
+            .tx_lifecycle_dal()
+            .insert_tx_lifecycle_events(&events);
+        metrics::counter!("api.tx_lifecycle.written_events", events.len() as u64);
+
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+    }
+}
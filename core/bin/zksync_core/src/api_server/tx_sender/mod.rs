@@ -9,6 +9,7 @@ use governor::middleware::NoOpMiddleware;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter};
 
+use vm::oracles::tracer::ValidationReport;
 use vm::vm_with_bootloader::{derive_base_fee_and_gas_per_pubdata, TxExecutionMode};
 use vm::zk_evm::zkevm_opcode_defs::system_params::MAX_PUBDATA_PER_BLOCK;
 use zksync_config::configs::chain::StateKeeperConfig;
@@ -28,6 +29,7 @@ use zksync_dal::ConnectionPool;
 
 use zksync_types::{
     api,
+    encrypted_tx::{check_payload, encrypted_tx_hash, ENCRYPTION_OVERHEAD},
     fee::Fee,
     get_code_key, get_intrinsic_constants,
     l2::error::TxCheckError::TxDuplication,
@@ -38,17 +40,18 @@ use zksync_types::{
 };
 
 use zksync_contracts::BaseSystemContracts;
-use zksync_utils::h256_to_u256;
+use zksync_utils::{h256_to_u256, time::seconds_since_epoch};
 
 // Local uses
 use crate::api_server::execution_sandbox::{
-    adjust_l1_gas_price_for_tx, execute_tx_with_pending_state, get_pubdata_for_factory_deps,
+    execute_tx_at_block, execute_tx_with_pending_state, get_pubdata_for_factory_deps,
     validate_tx_with_pending_state, SandboxExecutionError,
 };
 
 use crate::fee_ticker::{error::TickerError, FeeTicker, TokenPriceRequestType};
 use crate::gas_adjuster::GasAdjuster;
 use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
+use crate::state_keeper::deployer_allowlist::DeployerAllowlist;
 use crate::state_keeper::seal_criteria::{SealManager, SealResolution};
 
 pub mod error;
@@ -58,6 +61,12 @@ use vm::transaction_data::{derive_overhead, OverheadCoeficients};
 pub mod proxy;
 pub use proxy::TxProxy;
 
+pub mod fee_suggestion;
+pub use fee_suggestion::{FeeSuggestion, PriorityFeeOracle};
+
+pub mod lifecycle_recorder;
+pub use lifecycle_recorder::TxLifecycleRecorder;
+
 pub struct TxSenderInner {
     pub master_connection_pool: ConnectionPool,
     pub replica_connection_pool: ConnectionPool,
@@ -70,10 +79,15 @@ pub struct TxSenderInner {
         Option<RateLimiter<NotKeyed, InMemoryState, MonotonicClock, NoOpMiddleware<Instant>>>,
     // Used to keep track of gas prices for the fee ticker.
     pub gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
+    pub priority_fee_oracle: PriorityFeeOracle,
     pub state_keeper_config: StateKeeperConfig,
     pub playground_base_system_contracts: BaseSystemContracts,
     pub estimate_fee_base_system_contracts: BaseSystemContracts,
     pub proxy: Option<TxProxy>,
+    pub encrypted_mempool_enabled: bool,
+    pub max_tx_size: usize,
+    pub deployer_allowlist: Option<DeployerAllowlist>,
+    pub lifecycle_recorder: TxLifecycleRecorder,
 }
 
 #[derive(Clone)]
@@ -112,6 +126,12 @@ impl TxSender {
             .as_ref()
             .map(|url| TxProxy::new(url));
 
+        let priority_fee_oracle = PriorityFeeOracle::new(replica_connection_pool.clone());
+        // The allowlist is read from the master pool, so that the changes are visible without the replication lag.
+        let deployer_allowlist =
+            DeployerAllowlist::new(&config.chain.state_keeper, master_connection_pool.clone());
+        let lifecycle_recorder = TxLifecycleRecorder::spawn(master_connection_pool.clone());
+
         Self(Arc::new(TxSenderInner {
             chain_id: L2ChainId(config.chain.eth.zksync_network_id),
             master_connection_pool,
@@ -122,10 +142,15 @@ impl TxSender {
             max_single_tx_gas: config.chain.state_keeper.max_single_tx_gas,
             rate_limiter,
             gas_adjuster,
+            priority_fee_oracle,
             state_keeper_config: config.chain.state_keeper.clone(),
             playground_base_system_contracts,
             estimate_fee_base_system_contracts,
             proxy,
+            encrypted_mempool_enabled: config.api.web3_json_rpc.encrypted_mempool_enabled(),
+            max_tx_size: config.api.web3_json_rpc.max_tx_size,
+            deployer_allowlist,
+            lifecycle_recorder,
         }))
     }
 
@@ -136,6 +161,43 @@ impl TxSender {
                 return Err(SubmitTxError::RateLimitExceeded);
             }
         }
+        self.submit_tx_unlimited(tx)
+    }
+
+    /// Same as [`Self::submit_tx()`], but not subject to the rate limit. Used for the transactions from
+    /// the encrypted mempool, which have already been rate limited when their encrypted payloads were submitted.
+    pub fn submit_decrypted_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        self.submit_tx_unlimited(tx)
+    }
+
+    fn submit_tx_unlimited(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        let hash = tx.hash();
+        let mut lifecycle = vec![api::TxLifecycleEvent::new(
+            api::TxLifecycleEventKind::Received,
+        )];
+        let result = self.submit_tx_inner(tx, &mut lifecycle);
+        match &result {
+            Ok(L2TxSubmissionResult::Added) => lifecycle.push(api::TxLifecycleEvent::new(
+                api::TxLifecycleEventKind::InsertedToMempool,
+            )),
+            Ok(L2TxSubmissionResult::Replaced) => lifecycle.push(api::TxLifecycleEvent::new(
+                api::TxLifecycleEventKind::Replaced,
+            )),
+            Ok(_) => {}
+            Err(err) => lifecycle.push(api::TxLifecycleEvent::rejected(err.to_string())),
+        }
+        self.0.lifecycle_recorder.record(hash, lifecycle);
+
+        result
+    }
+
+    /// Validates the transaction and inserts it into the mempool.
+    /// Lifecycle events other than the final outcome are appended to `lifecycle`.
+    fn submit_tx_inner(
+        &self,
+        tx: L2Tx,
+        lifecycle: &mut Vec<api::TxLifecycleEvent>,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
         let mut stage_started_at = Instant::now();
 
         if tx.common_data.fee.gas_limit > U256::from(u32::MAX)
@@ -146,15 +208,17 @@ impl TxSender {
 
         let _maximal_allowed_overhead = 0;
 
-        if tx.common_data.fee.gas_limit
-            > U256::from(self.0.state_keeper_config.max_allowed_l2_tx_gas_limit)
-        {
+        let max_allowed_tx_gas_limit = self.0.state_keeper_config.max_allowed_tx_gas_limit();
+        if tx.common_data.fee.gas_limit > U256::from(max_allowed_tx_gas_limit) {
             vlog::info!(
-                "Submitted Tx is Unexecutable {:?} because of GasLimitIsTooBig {}",
+                "Submitted Tx is Unexecutable {:?} because of GasLimitExceedsCap {}",
                 tx.hash(),
                 tx.common_data.fee.gas_limit,
             );
-            return Err(SubmitTxError::GasLimitIsTooBig);
+            return Err(SubmitTxError::GasLimitExceedsCap(
+                tx.common_data.fee.gas_limit,
+                max_allowed_tx_gas_limit,
+            ));
         }
         if tx.common_data.fee.max_fee_per_gas < self.0.state_keeper_config.fair_l2_gas_price.into()
         {
@@ -179,6 +243,19 @@ impl TxSender {
                 MAX_NEW_FACTORY_DEPS,
             ));
         }
+        let now = seconds_since_epoch();
+        if tx.common_data.is_expired_at(now) {
+            return Err(SubmitTxError::TxExpired(
+                tx.common_data.valid_until.unwrap(),
+                now,
+            ));
+        }
+        if let Some(allowlist) = &self.0.deployer_allowlist {
+            allowlist.check(
+                tx.common_data.initiator_address,
+                tx.execute.contract_address,
+            )?;
+        }
 
         let l1_gas_price = self.0.gas_adjuster.estimate_effective_gas_price();
 
@@ -248,7 +325,8 @@ impl TxSender {
             self.0
                 .state_keeper_config
                 .validation_computational_gas_limit,
-        );
+        )?
+        .result;
 
         metrics::histogram!("api.web3.submit_tx", stage_started_at.elapsed(), "stage" => "3_verify_execute");
         stage_started_at = Instant::now();
@@ -258,6 +336,9 @@ impl TxSender {
         }
 
         self.ensure_tx_executable(&tx.clone().into(), &tx_metrics, true)?;
+        lifecycle.push(api::TxLifecycleEvent::new(
+            api::TxLifecycleEventKind::Validated,
+        ));
 
         if let Some(proxy) = &self.0.proxy {
             // We're running an external node: we have to proxy the transaction to the main node.
@@ -311,6 +392,71 @@ impl TxSender {
         submission_result
     }
 
+    /// Runs the account and paymaster validation of the transaction on top of the pending state,
+    /// same as on submission, but doesn't execute or submit it, so the nonce is not used.
+    pub fn validate_tx(&self, tx: &L2Tx) -> Result<ValidationReport, SubmitTxError> {
+        let l1_gas_price = self.0.gas_adjuster.estimate_effective_gas_price();
+        validate_tx_with_pending_state(
+            &self.0.replica_connection_pool,
+            tx.clone(),
+            AccountTreeId::new(self.0.fee_account_addr),
+            TxExecutionMode::VerifyExecute,
+            Some(tx.nonce()),
+            U256::zero(),
+            l1_gas_price,
+            self.0.state_keeper_config.fair_l2_gas_price,
+            Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
+            &self.0.playground_base_system_contracts,
+            self.0
+                .state_keeper_config
+                .validation_computational_gas_limit,
+        )
+        .map_err(SubmitTxError::from)
+    }
+
+    /// Stores the transaction encrypted to the decryption committee. It's only decrypted and validated
+    /// by the encrypted mempool decryptor, so the returned hash is the hash of the encrypted payload.
+    #[tracing::instrument(skip(self, payload))]
+    pub fn submit_encrypted_tx(&self, payload: Vec<u8>) -> Result<H256, SubmitTxError> {
+        if !self.0.encrypted_mempool_enabled {
+            return Err(SubmitTxError::EncryptedMempoolDisabled);
+        }
+        if let Some(rate_limiter) = &self.0.rate_limiter {
+            if rate_limiter.check().is_err() {
+                return Err(SubmitTxError::RateLimitExceeded);
+            }
+        }
+        let max_payload_size = self.0.max_tx_size + ENCRYPTION_OVERHEAD;
+        if payload.len() > max_payload_size {
+            return Err(SubmitTxError::EncryptedTxTooBig(
+                payload.len(),
+                max_payload_size,
+            ));
+        }
+        // Committee members can't produce decryption shares for malformed payloads.
+        check_payload(&payload)
+            .map_err(|err| SubmitTxError::MalformedEncryptedTx(err.to_string()))?;
+
+        if let Some(proxy) = &self.0.proxy {
+            let hash = proxy.submit_encrypted_tx(payload)?;
+            metrics::counter!("server.processed_txs", 1, "stage" => "encrypted_proxied");
+            return Ok(hash);
+        }
+
+        let hash = encrypted_tx_hash(&payload);
+        let inserted = self
+            .0
+            .master_connection_pool
+            .access_storage_blocking()
+            .encrypted_transactions_dal()
+            .insert_encrypted_transaction(hash, payload);
+        if !inserted {
+            return Err(SubmitTxError::IncorrectTx(TxDuplication(hash)));
+        }
+        metrics::counter!("server.processed_txs", 1, "stage" => "encrypted_mempool_added");
+        Ok(hash)
+    }
+
     fn validate_account_nonce(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
         let expected_nonce = self.get_expected_nonce(tx);
 
@@ -333,6 +479,27 @@ impl TxSender {
         }
     }
 
+    /// Stricter version of the nonce check, which doesn't allow nonce gaps: the transaction
+    /// must use exactly the next nonce of the account.
+    pub fn validate_account_nonce_strict(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
+        let expected_nonce = self.get_expected_nonce(tx);
+        if tx.nonce().0 < expected_nonce.0 {
+            Err(SubmitTxError::NonceIsTooLow(
+                expected_nonce.0,
+                expected_nonce.0,
+                tx.nonce().0,
+            ))
+        } else if tx.nonce().0 > expected_nonce.0 {
+            Err(SubmitTxError::NonceIsTooHigh(
+                expected_nonce.0,
+                expected_nonce.0,
+                tx.nonce().0,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     fn get_expected_nonce(&self, tx: &L2Tx) -> Nonce {
         self.0
             .replica_connection_pool
@@ -431,35 +598,26 @@ impl TxSender {
         h256_to_u256(balance)
     }
 
+    /// Estimates the fee of the transaction executed on top of the state of `block_id`, which must be present.
     pub fn get_txs_fee_in_wei(
         &self,
         mut tx: Transaction,
+        block_id: api::BlockId,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
-    ) -> Result<Fee, SubmitTxError> {
-        let l1_gas_price = {
-            let effective_gas_price = self.0.gas_adjuster.estimate_effective_gas_price();
-            let current_l1_gas_price =
-                ((effective_gas_price as f64) * self.0.gas_price_scale_factor) as u64;
-
-            // In order for execution to pass smoothly, we need to ensure that block's required gasPerPubdata will be
-            // <= to the one in the transaction itself.
-            adjust_l1_gas_price_for_tx(
-                current_l1_gas_price,
-                self.0.state_keeper_config.fair_l2_gas_price,
-                tx.gas_per_pubdata_byte_limit(),
-            )
-        };
-
-        let (base_fee, gas_per_pubdata_byte) = {
-            let (current_base_fee, gas_per_pubdata_byte) = derive_base_fee_and_gas_per_pubdata(
-                l1_gas_price,
-                self.0.state_keeper_config.fair_l2_gas_price,
-            );
-            let enforced_base_fee = std::cmp::min(tx.max_fee_per_gas().as_u64(), current_base_fee);
-
-            (enforced_base_fee, gas_per_pubdata_byte)
+    ) -> Result<api::FeeEstimate, SubmitTxError> {
+        // In order for execution to pass smoothly, we need to ensure that block's required gasPerPubdata will be
+        // <= to the one in the transaction itself.
+        let fee_suggestion = if tx.is_l1() {
+            self.l1_to_l2_fee_suggestion()
+        } else {
+            self.fee_suggestion()
         };
+        let fee_suggestion =
+            fee_suggestion.for_gas_per_pubdata_limit(tx.gas_per_pubdata_byte_limit());
+        let l1_gas_price = fee_suggestion.l1_gas_price;
+        let gas_per_pubdata_byte = fee_suggestion.gas_per_pubdata;
+        let base_fee = std::cmp::min(tx.max_fee_per_gas().as_u64(), fee_suggestion.base_fee);
 
         let hashed_key = get_code_key(&tx.initiator_account());
         // if the default account does not have enough funds
@@ -556,9 +714,10 @@ impl TxSender {
                 _ => U256::zero(),
             };
 
-            let (tx_metrics, exec_result) = execute_tx_with_pending_state(
+            let (tx_metrics, exec_result) = execute_tx_at_block(
                 &self.0.replica_connection_pool,
                 tx.clone(),
+                block_id,
                 AccountTreeId::new(self.0.fee_account_addr),
                 TxExecutionMode::EstimateFee,
                 enforced_nonce,
@@ -627,11 +786,19 @@ impl TxSender {
                         (x, _) => x,
                     };
 
-                Ok(Fee {
-                    max_fee_per_gas: base_fee.into(),
-                    max_priority_fee_per_gas: 0u32.into(),
-                    gas_limit: full_gas_limit.into(),
-                    gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+                Ok(api::FeeEstimate {
+                    fee: Fee {
+                        max_fee_per_gas: U256::from(base_fee)
+                            + fee_suggestion.max_priority_fee_per_gas,
+                        max_priority_fee_per_gas: fee_suggestion.max_priority_fee_per_gas,
+                        gas_limit: full_gas_limit.into(),
+                        gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
+                    },
+                    breakdown: api::FeeBreakdown {
+                        execution_gas: tx_body_gas_limit.into(),
+                        pubdata_gas: gas_for_bytecodes_pubdata.into(),
+                        overhead_gas: overhead.into(),
+                    },
                 })
             }
         }
@@ -647,14 +814,40 @@ impl TxSender {
         FeeTicker::get_l2_token_price(&mut tokens_web3_dal, request_type, &l2_token_address)
     }
 
-    pub fn gas_price(&self) -> u64 {
-        let gas_price = self.0.gas_adjuster.estimate_effective_gas_price();
+    /// Returns the fee parameters to be suggested to the users.
+    pub fn fee_suggestion(&self) -> FeeSuggestion {
+        let effective_gas_price = self.0.gas_adjuster.estimate_effective_gas_price();
+        FeeSuggestion::new(
+            (effective_gas_price as f64 * self.0.gas_price_scale_factor).round() as u64,
+            self.0.state_keeper_config.fair_l2_gas_price,
+            self.0.priority_fee_oracle.get(),
+        )
+    }
 
-        derive_base_fee_and_gas_per_pubdata(
+    /// Returns the fee parameters for L1->L2 transactions. The gas per pubdata byte is capped
+    /// if the cap is configured, with the operator covering the rest of the pubdata costs.
+    pub fn l1_to_l2_fee_suggestion(&self) -> FeeSuggestion {
+        let gas_price = self.0.gas_adjuster.estimate_l1_to_l2_gas_price();
+        let suggestion = FeeSuggestion::new(
             (gas_price as f64 * self.0.gas_price_scale_factor).round() as u64,
             self.0.state_keeper_config.fair_l2_gas_price,
-        )
-        .0
+            self.0.priority_fee_oracle.get(),
+        );
+        match self.0.gas_adjuster.l1_to_l2_max_gas_per_pubdata() {
+            Some(max_gas_per_pubdata) if suggestion.gas_per_pubdata > max_gas_per_pubdata => {
+                metrics::counter!("api.tx_sender.l1_to_l2_gas_per_pubdata_capped", 1);
+                suggestion.for_gas_per_pubdata_limit(max_gas_per_pubdata.into())
+            }
+            _ => suggestion,
+        }
+    }
+
+    pub fn gas_price(&self) -> U256 {
+        self.fee_suggestion().max_fee_per_gas()
+    }
+
+    pub fn max_priority_fee_per_gas(&self) -> U256 {
+        self.fee_suggestion().max_priority_fee_per_gas
     }
 
     fn ensure_tx_executable(
@@ -700,10 +893,11 @@ impl TxSender {
                 writes_metrics,
                 writes_metrics,
             );
-            if matches!(seal_resolution, SealResolution::Unexecutable(_)) {
+            if let SealResolution::Unexecutable(reason) = seal_resolution {
                 let message = format!(
-                    "Tx is Unexecutable because of {} with execution values {:?} and gas {:?}",
+                    "Tx is Unexecutable because of {} ({}) with execution values {:?} and gas {:?}",
                     sealer.prom_criterion_name(),
+                    reason,
                     execution_metrics,
                     tx_gas_count
                 );
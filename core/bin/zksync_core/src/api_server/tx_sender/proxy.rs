@@ -9,7 +9,7 @@ use zksync_types::{
 use zksync_web3_decl::{
     jsonrpsee::core::Error as JsonrpseeError,
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
-    namespaces::EthNamespaceClient,
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
 /// Used by external node to proxy transaction to the main node
@@ -46,6 +46,13 @@ impl TxProxy {
         async_std::task::block_on(self.client.send_raw_transaction(raw_tx))
     }
 
+    pub fn submit_encrypted_tx(&self, payload: Vec<u8>) -> Result<H256, JsonrpseeError> {
+        async_std::task::block_on(
+            self.client
+                .send_encrypted_transaction(zksync_types::Bytes(payload)),
+        )
+    }
+
     pub fn request_tx(&self, id: TransactionId) -> Result<Option<Transaction>, JsonrpseeError> {
         async_std::task::block_on(match id {
             TransactionId::Block(BlockId::Hash(block), index) => self
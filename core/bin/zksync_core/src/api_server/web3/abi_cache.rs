@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDateTime;
+
+use zksync_dal::ConnectionPool;
+use zksync_types::{ethabi::Contract, Address};
+
+#[derive(Debug, Default)]
+struct CachedAbis {
+    contracts: HashMap<Address, Arc<Contract>>,
+    /// Database time the cache was synced at.
+    synced_until: Option<NaiveDateTime>,
+    synced_at: Option<Instant>,
+}
+
+/// Cache of the ABIs of the verified contracts. The cache is fed by the contract verifier: once the
+/// cached data becomes older than the configured TTL, the ABIs of the contracts verified since
+/// the last sync are loaded from the database.
+#[derive(Debug, Clone)]
+pub struct VerifiedAbiCache {
+    pool: ConnectionPool,
+    ttl: Duration,
+    cache: Arc<RwLock<CachedAbis>>,
+    /// Held while the cache is refreshed, so that the concurrent readers don't refresh it at the same time.
+    refresh_lock: Arc<Mutex<()>>,
+}
+
+impl VerifiedAbiCache {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(10);
+    /// Contracts verified within this interval before the previous sync are loaded again, so that the verifications
+    /// timestamped before the sync but committed after it are not missed.
+    const SYNC_OVERLAP: Duration = Duration::from_secs(60);
+
+    pub fn new(pool: ConnectionPool, ttl: Duration) -> Self {
+        Self {
+            pool,
+            ttl,
+            cache: Arc::default(),
+            refresh_lock: Arc::default(),
+        }
+    }
+
+    /// Returns the ABI of the contract, or `None` if the contract isn't verified.
+    pub fn get(&self, address: Address) -> Option<Arc<Contract>> {
+        let (is_fresh, is_synced) = {
+            let cache = self.cache.read().unwrap();
+            let is_fresh = cache
+                .synced_at
+                .map_or(false, |synced_at| synced_at.elapsed() < self.ttl);
+            (is_fresh, cache.synced_at.is_some())
+        };
+        if !is_fresh {
+            if is_synced {
+                // If the cache is being refreshed by another reader, the stale data is served meanwhile.
+                if let Ok(_guard) = self.refresh_lock.try_lock() {
+                    self.refresh();
+                }
+            } else {
+                let _guard = self.refresh_lock.lock().unwrap();
+                self.refresh();
+            }
+        }
+        self.cache.read().unwrap().contracts.get(&address).cloned()
+    }
+
+    /// Loads the ABIs of the contracts verified since the last sync. Must be called with `refresh_lock` held.
+    /// The database is queried without locking the cache, so the readers aren't blocked meanwhile.
+    fn refresh(&self) {
+        let synced_until = {
+            let cache = self.cache.read().unwrap();
+            let is_fresh = cache
+                .synced_at
+                .map_or(false, |synced_at| synced_at.elapsed() < self.ttl);
+            if is_fresh {
+                // The cache was refreshed by another reader while this one was waiting for the lock.
+                return;
+            }
+            cache.synced_until
+        };
+
+        let (abis, synced_until) = self
+            .pool
+            .access_storage_blocking()
+            .explorer()
+            .contract_verification_dal()
+            .get_verified_abis_updated_after(synced_until, Self::SYNC_OVERLAP);
+        let contracts: Vec<_> = abis
+            .into_iter()
+            .filter_map(
+                |(contract_address, abi)| match serde_json::from_value::<Contract>(abi) {
+                    Ok(contract) => Some((contract_address, Arc::new(contract))),
+                    Err(err) => {
+                        vlog::warn!(
+                            "Failed to parse ABI of the verified contract {:?}: {}",
+                            contract_address,
+                            err
+                        );
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        let mut cache = self.cache.write().unwrap();
+        cache.contracts.extend(contracts);
+        cache.synced_until = Some(synced_until);
+        cache.synced_at = Some(Instant::now());
+    }
+}
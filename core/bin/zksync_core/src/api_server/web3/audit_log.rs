@@ -0,0 +1,481 @@
+//! Audit log of a sample of the API calls. Both API backends are audited: the `jsonrpc` one via
+//! [`ApiAuditLogMiddleware`], and the HTTP server of the `jsonrpsee` one via [`ApiAuditLayer`] and [`ApiAuditLogger`].
+
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use futures::future::Either;
+use jsonrpc_core::middleware::Middleware;
+use jsonrpc_core::{Call, ErrorCode, FutureOutput, FutureResponse, Metadata, Output, Response};
+use jsonrpc_http_server::hyper::{header::HeaderMap, Request};
+use rand::Rng;
+use tokio::sync::{mpsc, watch};
+use tokio::task::futures::TaskLocalFuture;
+use tower::{Layer, Service};
+
+use zksync_config::configs::api::Web3JsonRpc;
+use zksync_dal::api_audit_log_dal::ApiAuditRecord;
+use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_types::web3::signing::keccak256;
+use zksync_web3_decl::jsonrpsee::server::logger::{
+    HttpRequest, Logger, MethodKind, Params, TransportProtocol,
+};
+
+use super::extensions::{extensions_requested, NegotiatedExtensions};
+
+/// Max number of the records waiting to be written. Records are dropped if the writer can't keep up.
+const RECORDS_BUFFER_SIZE: usize = 10_000;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+const PARTITIONS_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+tokio::task_local! {
+    /// Sampled calls of the HTTP request handled by the `jsonrpsee` backend.
+    static AUDITED_REQUEST: AuditedRequest;
+}
+
+/// Returns the hash identifying the API key in the audit log and the usage records.
+pub fn api_key_hash(api_key: &str) -> String {
+    hex::encode(&keccak256(api_key.as_bytes())[..8])
+}
+
+/// Returns the client address from the `X-Forwarded-For` header appended to by `trusted_proxy_hops` proxies.
+/// The client can send the header with arbitrary entries, so only the entries appended by the trusted proxies
+/// are relied upon; the leftmost of them is the address of the client.
+fn client_address(forwarded_for: &str, trusted_proxy_hops: usize) -> Option<&str> {
+    if trusted_proxy_hops == 0 {
+        return None;
+    }
+    let entries: Vec<_> = forwarded_for.split(',').map(str::trim).collect();
+    let index = entries.len().checked_sub(trusted_proxy_hops)?;
+    Some(entries[index]).filter(|address| !address.is_empty())
+}
+
+/// Returns the key identifying the caller and the hash of the API key the request is made with.
+/// The caller is identified by the API key if provided by the front-end, and by the client address otherwise.
+/// Only a hash of the API key is recorded.
+fn identify_caller(
+    headers: &HeaderMap,
+    trusted_proxy_hops: usize,
+) -> (Option<String>, Option<String>) {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let api_key_hash = header(API_KEY_HEADER).map(api_key_hash);
+    let caller_key = if let Some(api_key_hash) = &api_key_hash {
+        Some(format!("key:{}", api_key_hash))
+    } else {
+        header(FORWARDED_FOR_HEADER)
+            .and_then(|addresses| client_address(addresses, trusted_proxy_hops))
+            .map(|address| format!("ip:{}", address))
+    };
+    (caller_key, api_key_hash)
+}
+
+/// Request metadata of the HTTP API.
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetadata {
+    caller_key: Option<String>,
+    api_key_hash: Option<String>,
+    extensions_enabled: bool,
+}
+
+impl Metadata for RequestMetadata {}
+
+impl RequestMetadata {
+    pub fn from_http_request(
+        request: &Request<jsonrpc_http_server::hyper::Body>,
+        trusted_proxy_hops: usize,
+    ) -> Self {
+        let (caller_key, api_key_hash) = identify_caller(request.headers(), trusted_proxy_hops);
+        Self {
+            caller_key,
+            api_key_hash,
+            extensions_enabled: extensions_requested(request),
+        }
+    }
+}
+
+/// Metadata the caller key can be extracted from.
+pub trait CallerKey {
+    fn caller_key(&self) -> Option<String>;
+
+    /// Hash of the API key the request is made with, identifying the tenant for the usage metering.
+    fn api_key_hash(&self) -> Option<String>;
+}
+
+impl CallerKey for RequestMetadata {
+    fn caller_key(&self) -> Option<String> {
+        self.caller_key.clone()
+    }
+
+    fn api_key_hash(&self) -> Option<String> {
+        self.api_key_hash.clone()
+    }
+}
+
+impl NegotiatedExtensions for RequestMetadata {
+    fn extensions_enabled(&self) -> bool {
+        self.extensions_enabled
+    }
+}
+
+impl CallerKey for Arc<jsonrpc_pubsub::Session> {
+    fn caller_key(&self) -> Option<String> {
+        None
+    }
+
+    fn api_key_hash(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Middleware recording a sample of the API calls into the audit log.
+#[derive(Debug, Clone, Default)]
+pub struct ApiAuditLogMiddleware {
+    sample_rate: f64,
+    sender: Option<mpsc::Sender<ApiAuditRecord>>,
+}
+
+impl ApiAuditLogMiddleware {
+    /// Creates the middleware and the writer of the records, if the audit log is enabled.
+    pub fn new(config: &Web3JsonRpc) -> (Self, Option<ApiAuditLogWriter>) {
+        let sample_rate = config.audit_log_sample_rate();
+        if sample_rate == 0.0 {
+            return (Self::default(), None);
+        }
+
+        let (sender, receiver) = mpsc::channel(RECORDS_BUFFER_SIZE);
+        let middleware = Self {
+            sample_rate,
+            sender: Some(sender),
+        };
+        let writer = ApiAuditLogWriter {
+            pool: ConnectionPool::new(Some(1), true),
+            receiver,
+            retention_days: config.audit_log_retention_days(),
+        };
+        (middleware, Some(writer))
+    }
+
+    /// Returns the logger auditing the calls of the `jsonrpsee` backend. The server must be built
+    /// with [`ApiAuditLayer`], so that the calls of a request can be matched with their results.
+    pub fn jsonrpsee_logger(&self) -> ApiAuditLogger {
+        ApiAuditLogger {
+            middleware: self.clone(),
+        }
+    }
+
+    fn sampled_sender(&self) -> Option<mpsc::Sender<ApiAuditRecord>> {
+        let sender = self.sender.as_ref()?;
+        rand::thread_rng()
+            .gen_bool(self.sample_rate)
+            .then(|| sender.clone())
+    }
+}
+
+fn send_record(sender: &mpsc::Sender<ApiAuditRecord>, record: ApiAuditRecord) {
+    if sender.try_send(record).is_err() {
+        metrics::counter!("api.audit_log.dropped_records", 1);
+    }
+}
+
+fn params_hash(params: &impl serde::Serialize) -> Vec<u8> {
+    let params = serde_json::to_vec(params).unwrap_or_default();
+    keccak256(&params).to_vec()
+}
+
+impl<M: Metadata + CallerKey> Middleware<M> for ApiAuditLogMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = FutureOutput;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: futures::Future<Output = Option<Output>> + Send + 'static,
+    {
+        let sender = match self.sampled_sender() {
+            Some(sender) => sender,
+            None => return Either::Right(next(call, meta)),
+        };
+        let (method, params_hash) = match &call {
+            Call::MethodCall(method_call) => {
+                (method_call.method.clone(), params_hash(&method_call.params))
+            }
+            _ => return Either::Right(next(call, meta)),
+        };
+        let caller_key = meta.caller_key();
+        let created_at = Utc::now().naive_utc();
+        let started_at = Instant::now();
+
+        let output = next(call, meta);
+        Either::Left(Box::pin(async move {
+            let output = output.await;
+            let result_code = match &output {
+                Some(Output::Failure(failure)) => failure.error.code.code(),
+                _ => 0,
+            };
+            let record = ApiAuditRecord {
+                method,
+                params_hash,
+                caller_key,
+                latency_ms: started_at.elapsed().as_millis() as u32,
+                result_code,
+                created_at,
+            };
+            send_record(&sender, record);
+            output
+        }))
+    }
+}
+
+/// Sampled call of the `jsonrpsee` backend waiting for its result.
+#[derive(Debug)]
+struct PendingCall {
+    sender: mpsc::Sender<ApiAuditRecord>,
+    method: String,
+    params_hash: Vec<u8>,
+    created_at: NaiveDateTime,
+    started_at: Instant,
+    /// Whether the call has succeeded and its latency, once it has completed.
+    outcome: Option<(bool, u32)>,
+}
+
+#[derive(Debug)]
+struct AuditedRequest {
+    caller_key: Option<String>,
+    calls: Mutex<Vec<PendingCall>>,
+}
+
+/// Tower layer identifying the caller of the HTTP request and collecting its calls for [`ApiAuditLogger`].
+#[derive(Debug, Clone, Copy)]
+pub struct ApiAuditLayer {
+    trusted_proxy_hops: usize,
+}
+
+impl ApiAuditLayer {
+    pub fn new(config: &Web3JsonRpc) -> Self {
+        Self {
+            trusted_proxy_hops: config.trusted_proxy_hops(),
+        }
+    }
+}
+
+impl<S> Layer<S> for ApiAuditLayer {
+    type Service = ApiAuditService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiAuditService {
+            inner,
+            trusted_proxy_hops: self.trusted_proxy_hops,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiAuditService<S> {
+    inner: S,
+    trusted_proxy_hops: usize,
+}
+
+impl<S, B> Service<Request<B>> for ApiAuditService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<AuditedRequest, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let (caller_key, _) = identify_caller(request.headers(), self.trusted_proxy_hops);
+        let audited_request = AuditedRequest {
+            caller_key,
+            calls: Mutex::default(),
+        };
+        AUDITED_REQUEST.scope(audited_request, self.inner.call(request))
+    }
+}
+
+/// `jsonrpsee` logger recording a sample of the method calls into the audit log. Calls are recorded
+/// once the response is sent, so that their JSON-RPC error codes are known.
+#[derive(Debug, Clone)]
+pub struct ApiAuditLogger {
+    middleware: ApiAuditLogMiddleware,
+}
+
+impl Logger for ApiAuditLogger {
+    type Instant = ();
+
+    fn on_connect(&self, _: SocketAddr, _: &HttpRequest, _: TransportProtocol) {}
+
+    fn on_request(&self, _: TransportProtocol) -> Self::Instant {}
+
+    fn on_call(&self, method_name: &str, params: Params, kind: MethodKind, _: TransportProtocol) {
+        if !matches!(kind, MethodKind::MethodCall) {
+            return;
+        }
+        let Some(sender) = self.middleware.sampled_sender() else {
+            return;
+        };
+        // Params are hashed in the same form as by the `jsonrpc` backend.
+        let params: serde_json::Value = params
+            .as_str()
+            .and_then(|params| serde_json::from_str(params).ok())
+            .unwrap_or_default();
+        let call = PendingCall {
+            sender,
+            method: method_name.to_owned(),
+            params_hash: params_hash(&params),
+            created_at: Utc::now().naive_utc(),
+            started_at: Instant::now(),
+            outcome: None,
+        };
+        let _ = AUDITED_REQUEST.try_with(|request| request.calls.lock().unwrap().push(call));
+    }
+
+    fn on_result(&self, method_name: &str, success: bool, _: Self::Instant, _: TransportProtocol) {
+        let _ = AUDITED_REQUEST.try_with(|request| {
+            let mut calls = request.calls.lock().unwrap();
+            let call = calls
+                .iter_mut()
+                .find(|call| call.outcome.is_none() && call.method == method_name);
+            if let Some(call) = call {
+                let latency_ms = call.started_at.elapsed().as_millis() as u32;
+                call.outcome = Some((success, latency_ms));
+            }
+        });
+    }
+
+    fn on_response(&self, result: &str, _: Self::Instant, _: TransportProtocol) {
+        let _ = AUDITED_REQUEST.try_with(|request| {
+            let calls = std::mem::take(&mut *request.calls.lock().unwrap());
+            if calls.is_empty() {
+                return;
+            }
+            // Failed calls are matched with the errors in the response in order.
+            let outputs = match serde_json::from_str(result) {
+                Ok(Response::Single(output)) => vec![output],
+                Ok(Response::Batch(outputs)) => outputs,
+                Err(_) => vec![],
+            };
+            let mut error_codes = outputs.into_iter().filter_map(|output| match output {
+                Output::Failure(failure) => Some(failure.error.code.code()),
+                Output::Success(_) => None,
+            });
+
+            for call in calls {
+                let Some((success, latency_ms)) = call.outcome else {
+                    continue;
+                };
+                let result_code = if success {
+                    0
+                } else {
+                    error_codes
+                        .next()
+                        .unwrap_or_else(|| ErrorCode::InternalError.code())
+                };
+                let record = ApiAuditRecord {
+                    method: call.method,
+                    params_hash: call.params_hash,
+                    caller_key: request.caller_key.clone(),
+                    latency_ms,
+                    result_code,
+                    created_at: call.created_at,
+                };
+                send_record(&call.sender, record);
+            }
+        });
+    }
+
+    fn on_disconnect(&self, _: SocketAddr, _: TransportProtocol) {}
+}
+
+/// Writes the sampled records into the audit log in batches and drops the partitions
+/// which are older than the retention period.
+#[derive(Debug)]
+pub struct ApiAuditLogWriter {
+    pool: ConnectionPool,
+    receiver: mpsc::Receiver<ApiAuditRecord>,
+    retention_days: u32,
+}
+
+impl ApiAuditLogWriter {
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) {
+        let mut partitions_maintained_at: Option<(Instant, NaiveDate)> = None;
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, API audit log writer is shutting down");
+                break;
+            }
+
+            let today = Utc::now().date_naive();
+            let needs_maintenance = partitions_maintained_at.map_or(true, |(at, day)| {
+                at.elapsed() >= PARTITIONS_MAINTENANCE_INTERVAL || day != today
+            });
+            if needs_maintenance {
+                match self.maintain_partitions(today) {
+                    Ok(()) => partitions_maintained_at = Some((Instant::now(), today)),
+                    Err(err) => {
+                        // Maintenance is retried on the next iteration.
+                        vlog::warn!("Failed to maintain API audit log partitions: {}", err);
+                        metrics::counter!("api.audit_log.partition_maintenance_failures", 1);
+                    }
+                }
+            }
+
+            let mut records = vec![];
+            while let Ok(record) = self.receiver.try_recv() {
+                records.push(record);
+            }
+            if !records.is_empty() {
+                self.pool
+                    .access_storage_blocking()
+                    .api_audit_log_dal()
+                    .insert_records(&records);
+                metrics::counter!("api.audit_log.written_records", records.len() as u64);
+            }
+
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+        }
+    }
+
+    /// Creates the partitions for today and tomorrow, so that the records are never left
+    /// without a partition around midnight, and drops the expired ones.
+    fn maintain_partitions(&self, today: NaiveDate) -> Result<(), SqlxError> {
+        let mut storage = self.pool.access_storage_blocking();
+        let mut dal = storage.api_audit_log_dal();
+        dal.create_partition(today)?;
+        dal.create_partition(today.succ_opt().expect("date out of range"))?;
+
+        let retention = chrono::Duration::days(self.retention_days.into());
+        let dropped = dal.drop_partitions_before(today - retention)?;
+        if !dropped.is_empty() {
+            vlog::info!("Dropped expired API audit log partitions: {:?}", dropped);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_address_is_taken_from_trusted_entries() {
+        let forwarded_for = "10.0.0.1, 192.168.1.1, 172.16.0.1";
+        assert_eq!(client_address(forwarded_for, 1), Some("172.16.0.1"));
+        assert_eq!(client_address(forwarded_for, 2), Some("192.168.1.1"));
+        assert_eq!(client_address(forwarded_for, 3), Some("10.0.0.1"));
+        // The header has fewer entries than the trusted proxies append, so it's not trusted.
+        assert_eq!(client_address(forwarded_for, 4), None);
+        assert_eq!(client_address(forwarded_for, 0), None);
+        assert_eq!(client_address("", 1), None);
+    }
+}
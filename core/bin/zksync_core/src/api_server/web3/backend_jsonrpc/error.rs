@@ -1,35 +1,15 @@
-use jsonrpc_core::{Error, ErrorCode};
-use serde_json::json;
+use jsonrpc_core::Error;
+use zksync_dal::{connection::is_statement_timeout, SqlxError};
 use zksync_web3_decl::error::Web3Error;
 
+use crate::api_server::web3::conformance;
+
 pub fn into_jsrpc_error(err: Web3Error) -> Error {
+    let error = conformance::error_object(&err);
     Error {
-        code: match err {
-            Web3Error::InternalError | Web3Error::NotImplemented => ErrorCode::InternalError,
-            Web3Error::NoBlock
-            | Web3Error::NoSuchFunction
-            | Web3Error::RLPError(_)
-            | Web3Error::InvalidTransactionData(_)
-            | Web3Error::TooManyTopics
-            | Web3Error::FilterNotFound
-            | Web3Error::InvalidFeeParams(_)
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams,
-            Web3Error::SubmitTransactionError(_) | Web3Error::SerializationError(_) => 3.into(),
-            Web3Error::PubSubTimeout => 4.into(),
-            Web3Error::RequestTimeout => 5.into(),
-        },
-        message: match err {
-            Web3Error::SubmitTransactionError(_) => err.to_string(),
-            _ => err.to_string(),
-        },
-        data: match err {
-            Web3Error::SubmitTransactionError(err) => json! ({
-                "code": 104,
-                "message": err
-            })
-            .into(),
-            _ => None,
-        },
+        code: error.code.into(),
+        message: error.message,
+        data: error.data,
     }
 }
 
@@ -43,3 +23,20 @@ pub fn internal_error(method_name: &str, error: impl ToString) -> Web3Error {
 
     Web3Error::InternalError
 }
+
+/// Reports the request whose DB queries haven't completed until its deadline.
+pub fn request_timeout_error(method_name: &str) -> Web3Error {
+    vlog::warn!("DB queries of method {} exceeded its deadline", method_name);
+    metrics::counter!("api.web3.timed_out_requests", 1, "method" => method_name.to_string());
+    Web3Error::RequestTimeout
+}
+
+/// Same as `internal_error`, but the DB queries cancelled because of the request deadline
+/// are reported as the request timeout.
+pub fn db_error(method_name: &str, error: SqlxError) -> Web3Error {
+    if is_statement_timeout(&error) {
+        request_timeout_error(method_name)
+    } else {
+        internal_error(method_name, error)
+    }
+}
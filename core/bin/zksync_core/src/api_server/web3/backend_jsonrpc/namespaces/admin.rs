@@ -0,0 +1,135 @@
+// Built-in uses
+
+// External uses
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+// Workspace uses
+use zksync_types::{
+    api::{AdminAuthorization, BlockRevertPlan, BootloaderUsageReport},
+    Address, L1BatchNumber, H256,
+};
+
+// Local uses
+use crate::web3::backend_jsonrpc::error::into_jsrpc_error;
+use crate::web3::namespaces::AdminNamespace;
+
+#[rpc]
+pub trait AdminNamespaceT {
+    #[rpc(name = "admin_getDeployerAllowlist", returns = "Vec<Address>")]
+    fn get_deployer_allowlist(&self) -> Result<Vec<Address>>;
+
+    #[rpc(name = "admin_addDeployerToAllowlist", returns = "bool")]
+    fn add_deployer_to_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> Result<bool>;
+
+    #[rpc(name = "admin_removeDeployerFromAllowlist", returns = "bool")]
+    fn remove_deployer_from_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> Result<bool>;
+
+    #[rpc(name = "admin_getQuarantinedTransactions", returns = "Vec<H256>")]
+    fn get_quarantined_transactions(&self) -> Result<Vec<H256>>;
+
+    #[rpc(name = "admin_releaseQuarantinedTransaction", returns = "bool")]
+    fn release_quarantined_transaction(
+        &self,
+        hash: H256,
+        authorization: AdminAuthorization,
+    ) -> Result<bool>;
+
+    #[rpc(name = "admin_getBlockRevertPlan", returns = "Option<BlockRevertPlan>")]
+    fn get_block_revert_plan(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BlockRevertPlan>>;
+
+    #[rpc(name = "admin_revertBlocks", returns = "BlockRevertPlan")]
+    fn revert_blocks(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stages: Vec<String>,
+        authorization: AdminAuthorization,
+    ) -> BoxFuture<Result<BlockRevertPlan>>;
+
+    #[rpc(
+        name = "admin_getBootloaderUsageReport",
+        returns = "Option<BootloaderUsageReport>"
+    )]
+    fn get_bootloader_usage_report(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BootloaderUsageReport>>;
+}
+
+impl AdminNamespaceT for AdminNamespace {
+    fn get_deployer_allowlist(&self) -> Result<Vec<Address>> {
+        Ok(self.get_deployer_allowlist_impl())
+    }
+
+    fn add_deployer_to_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> Result<bool> {
+        self.add_deployer_to_allowlist_impl(address, authorization)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn remove_deployer_from_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> Result<bool> {
+        self.remove_deployer_from_allowlist_impl(address, authorization)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_quarantined_transactions(&self) -> Result<Vec<H256>> {
+        Ok(self.get_quarantined_transactions_impl())
+    }
+
+    fn release_quarantined_transaction(
+        &self,
+        hash: H256,
+        authorization: AdminAuthorization,
+    ) -> Result<bool> {
+        self.release_quarantined_transaction_impl(hash, authorization)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_block_revert_plan(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BlockRevertPlan>> {
+        self.get_block_revert_plan_impl(l1_batch_number)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn revert_blocks(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stages: Vec<String>,
+        authorization: AdminAuthorization,
+    ) -> BoxFuture<Result<BlockRevertPlan>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .revert_blocks_impl(l1_batch_number, stages, authorization)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_bootloader_usage_report(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BootloaderUsageReport>> {
+        Ok(self.get_bootloader_usage_report_impl(l1_batch_number))
+    }
+}
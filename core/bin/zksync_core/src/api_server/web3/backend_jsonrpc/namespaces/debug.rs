@@ -0,0 +1,93 @@
+// Built-in uses
+
+// External uses
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+// Workspace uses
+use zksync_types::{
+    api::{BlockIdVariant, BlockNumber, TxLifecycleEvent},
+    transaction_request::CallRequest,
+    vm_trace::{DebugTraceResult, TracerOptions, TxTraceResult},
+    H256,
+};
+
+// Local uses
+use crate::web3::backend_jsonrpc::error::into_jsrpc_error;
+use crate::web3::namespaces::DebugNamespace;
+
+#[rpc]
+pub trait DebugNamespaceT {
+    #[rpc(name = "debug_traceCall", returns = "DebugTraceResult")]
+    fn trace_call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        options: TracerOptions,
+    ) -> Result<DebugTraceResult>;
+
+    #[rpc(
+        name = "debug_getTransactionLifecycle",
+        returns = "Vec<TxLifecycleEvent>"
+    )]
+    fn get_transaction_lifecycle(&self, hash: H256) -> Result<Vec<TxLifecycleEvent>>;
+
+    #[rpc(name = "debug_traceTransaction", returns = "Option<DebugTraceResult>")]
+    fn trace_transaction(
+        &self,
+        hash: H256,
+        options: Option<TracerOptions>,
+    ) -> BoxFuture<Result<Option<DebugTraceResult>>>;
+
+    #[rpc(name = "debug_traceBlockByNumber", returns = "Vec<TxTraceResult>")]
+    fn trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        options: Option<TracerOptions>,
+    ) -> BoxFuture<Result<Vec<TxTraceResult>>>;
+}
+
+impl DebugNamespaceT for DebugNamespace {
+    fn trace_call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        options: TracerOptions,
+    ) -> Result<DebugTraceResult> {
+        self.trace_call_impl(req, block.map(Into::into), options)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_transaction_lifecycle(&self, hash: H256) -> Result<Vec<TxLifecycleEvent>> {
+        self.get_transaction_lifecycle_impl(hash)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn trace_transaction(
+        &self,
+        hash: H256,
+        options: Option<TracerOptions>,
+    ) -> BoxFuture<Result<Option<DebugTraceResult>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .trace_transaction_impl(hash, options)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        options: Option<TracerOptions>,
+    ) -> BoxFuture<Result<Vec<TxTraceResult>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .trace_block_impl(block, options)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+}
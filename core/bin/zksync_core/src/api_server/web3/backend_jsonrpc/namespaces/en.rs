@@ -0,0 +1,23 @@
+// Built-in uses
+
+// External uses
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+// Workspace uses
+use zksync_types::api::NodeIdentity;
+
+// Local uses
+use crate::web3::namespaces::EnNamespace;
+
+#[rpc]
+pub trait EnNamespaceT {
+    #[rpc(name = "en_handshake", returns = "NodeIdentity")]
+    fn handshake(&self, peer: NodeIdentity) -> Result<NodeIdentity>;
+}
+
+impl EnNamespaceT for EnNamespace {
+    fn handshake(&self, peer: NodeIdentity) -> Result<NodeIdentity> {
+        Ok(self.handshake_impl(peer))
+    }
+}
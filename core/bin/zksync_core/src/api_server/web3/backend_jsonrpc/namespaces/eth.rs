@@ -1,14 +1,14 @@
 // Built-in uses
 
 // External uses
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 // Workspace uses
 use zksync_types::{
     api::{
-        BlockId, BlockIdVariant, BlockNumber, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        AccessListWithGasUsed, AccountInfo, BlockId, BlockIdVariant, BlockNumber, FeeHistory,
+        Transaction, TransactionId, TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
     web3::types::{Index, SyncState},
@@ -35,9 +35,27 @@ pub trait EthNamespaceT {
     #[rpc(name = "eth_estimateGas", returns = "U256")]
     fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> Result<U256>;
 
+    #[rpc(name = "eth_createAccessList", returns = "AccessListWithGasUsed")]
+    fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+    ) -> Result<AccessListWithGasUsed>;
+
     #[rpc(name = "eth_gasPrice", returns = "U256")]
     fn gas_price(&self) -> Result<U256>;
 
+    #[rpc(name = "eth_maxPriorityFeePerGas", returns = "U256")]
+    fn max_priority_fee_per_gas(&self) -> Result<U256>;
+
+    #[rpc(name = "eth_feeHistory", returns = "FeeHistory")]
+    fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> Result<FeeHistory>;
+
     #[rpc(name = "eth_newFilter", returns = "U256")]
     fn new_filter(&self, filter: Filter) -> Result<U256>;
 
@@ -51,13 +69,13 @@ pub trait EthNamespaceT {
     fn new_pending_transaction_filter(&self) -> Result<U256>;
 
     #[rpc(name = "eth_getLogs", returns = "Vec<Log>")]
-    fn get_logs(&self, filter: Filter) -> Result<Vec<Log>>;
+    fn get_logs(&self, filter: Filter) -> BoxFuture<Result<Vec<Log>>>;
 
     #[rpc(name = "eth_getFilterLogs", returns = "FilterChanges")]
-    fn get_filter_logs(&self, filter_index: U256) -> Result<FilterChanges>;
+    fn get_filter_logs(&self, filter_index: U256) -> BoxFuture<Result<FilterChanges>>;
 
     #[rpc(name = "eth_getFilterChanges", returns = "FilterChanges")]
-    fn get_filter_changes(&self, filter_index: U256) -> Result<FilterChanges>;
+    fn get_filter_changes(&self, filter_index: U256) -> BoxFuture<Result<FilterChanges>>;
 
     #[rpc(name = "eth_getBalance", returns = "U256")]
     fn get_balance(&self, address: Address, block: Option<BlockIdVariant>) -> Result<U256>;
@@ -105,15 +123,21 @@ pub trait EthNamespaceT {
         block: Option<BlockIdVariant>,
     ) -> Result<H256>;
 
+    #[rpc(name = "eth_getAccount", returns = "AccountInfo")]
+    fn get_account(&self, address: Address, block: Option<BlockIdVariant>) -> Result<AccountInfo>;
+
     #[rpc(name = "eth_getTransactionCount", returns = "U256")]
     fn get_transaction_count(
         &self,
         address: Address,
         block: Option<BlockIdVariant>,
-    ) -> Result<U256>;
+    ) -> BoxFuture<Result<U256>>;
 
     #[rpc(name = "eth_getTransactionByHash", returns = "Option<Transaction>")]
-    fn get_transaction_by_hash(&self, hash: H256) -> Result<Option<Transaction>>;
+    fn get_transaction_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Transaction>>>;
+
+    #[rpc(name = "eth_getRawTransactionByHash", returns = "Option<Bytes>")]
+    fn get_raw_transaction_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Bytes>>>;
 
     #[rpc(
         name = "eth_getTransactionByBlockHashAndIndex",
@@ -123,7 +147,7 @@ pub trait EthNamespaceT {
         &self,
         block_hash: H256,
         index: Index,
-    ) -> Result<Option<Transaction>>;
+    ) -> BoxFuture<Result<Option<Transaction>>>;
 
     #[rpc(
         name = "eth_getTransactionByBlockNumberAndIndex",
@@ -133,13 +157,22 @@ pub trait EthNamespaceT {
         &self,
         block_number: BlockNumber,
         index: Index,
-    ) -> Result<Option<Transaction>>;
+    ) -> BoxFuture<Result<Option<Transaction>>>;
 
     #[rpc(
         name = "eth_getTransactionReceipt",
         returns = "Option<TransactionReceipt>"
     )]
-    fn get_transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>>;
+    fn get_transaction_receipt(&self, hash: H256) -> BoxFuture<Result<Option<TransactionReceipt>>>;
+
+    #[rpc(
+        name = "eth_getBlockReceipts",
+        returns = "Option<Vec<TransactionReceipt>>"
+    )]
+    fn get_block_receipts(
+        &self,
+        block: BlockIdVariant,
+    ) -> BoxFuture<Result<Option<Vec<TransactionReceipt>>>>;
 
     #[rpc(name = "eth_protocolVersion", returns = "String")]
     fn protocol_version(&self) -> Result<String>;
@@ -175,7 +208,7 @@ pub trait EthNamespaceT {
     fn send_transaction(
         &self,
         transaction_request: zksync_types::web3::types::TransactionRequest,
-    ) -> Result<H256>;
+    ) -> BoxFuture<Result<H256>>;
 }
 
 impl EthNamespaceT for EthNamespace {
@@ -193,13 +226,38 @@ impl EthNamespaceT for EthNamespace {
     }
 
     fn estimate_gas(&self, req: CallRequest, block: Option<BlockNumber>) -> Result<U256> {
-        self.estimate_gas_impl(req, block).map_err(into_jsrpc_error)
+        self.estimate_gas_impl(req, block.map(BlockId::Number))
+            .map_err(into_jsrpc_error)
+    }
+
+    fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+    ) -> Result<AccessListWithGasUsed> {
+        self.create_access_list_impl(req, block.map(Into::into))
+            .map_err(into_jsrpc_error)
     }
 
     fn gas_price(&self) -> Result<U256> {
         self.gas_price_impl().map_err(into_jsrpc_error)
     }
 
+    fn max_priority_fee_per_gas(&self) -> Result<U256> {
+        self.max_priority_fee_per_gas_impl()
+            .map_err(into_jsrpc_error)
+    }
+
+    fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> Result<FeeHistory> {
+        self.fee_history_impl(block_count, newest_block, reward_percentiles)
+            .map_err(into_jsrpc_error)
+    }
+
     fn new_filter(&self, filter: Filter) -> Result<U256> {
         self.new_filter_impl(filter).map_err(into_jsrpc_error)
     }
@@ -216,18 +274,34 @@ impl EthNamespaceT for EthNamespace {
         Ok(self.new_pending_transaction_filter_impl())
     }
 
-    fn get_logs(&self, filter: Filter) -> Result<Vec<Log>> {
-        self.get_logs_impl(filter).map_err(into_jsrpc_error)
+    fn get_logs(&self, filter: Filter) -> BoxFuture<Result<Vec<Log>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_logs_impl(filter)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
-    fn get_filter_logs(&self, filter_index: U256) -> Result<FilterChanges> {
-        self.get_filter_logs_impl(filter_index)
-            .map_err(into_jsrpc_error)
+    fn get_filter_logs(&self, filter_index: U256) -> BoxFuture<Result<FilterChanges>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_filter_logs_impl(filter_index)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
-    fn get_filter_changes(&self, filter_index: U256) -> Result<FilterChanges> {
-        self.get_filter_changes_impl(filter_index)
-            .map_err(into_jsrpc_error)
+    fn get_filter_changes(&self, filter_index: U256) -> BoxFuture<Result<FilterChanges>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_filter_changes_impl(filter_index)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn get_balance(&self, address: Address, block: Option<BlockIdVariant>) -> Result<U256> {
@@ -281,41 +355,94 @@ impl EthNamespaceT for EthNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    fn get_account(&self, address: Address, block: Option<BlockIdVariant>) -> Result<AccountInfo> {
+        self.get_account_impl(address, block.map(Into::into))
+            .map_err(into_jsrpc_error)
+    }
+
     fn get_transaction_count(
         &self,
         address: Address,
         block: Option<BlockIdVariant>,
-    ) -> Result<U256> {
-        self.get_transaction_count_impl(address, block.map(Into::into))
-            .map_err(into_jsrpc_error)
-    }
-
-    fn get_transaction_by_hash(&self, hash: H256) -> Result<Option<Transaction>> {
-        self.get_transaction_impl(TransactionId::Hash(hash))
-            .map_err(into_jsrpc_error)
+    ) -> BoxFuture<Result<U256>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transaction_count_impl(address, block.map(Into::into))
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_transaction_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Transaction>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transaction_impl(TransactionId::Hash(hash))
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_raw_transaction_by_hash(&self, hash: H256) -> BoxFuture<Result<Option<Bytes>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_raw_transaction_by_hash_impl(hash)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn get_transaction_by_block_hash_and_index(
         &self,
         block_hash: H256,
         index: Index,
-    ) -> Result<Option<Transaction>> {
-        self.get_transaction_impl(TransactionId::Block(BlockId::Hash(block_hash), index))
-            .map_err(into_jsrpc_error)
+    ) -> BoxFuture<Result<Option<Transaction>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transaction_impl(TransactionId::Block(BlockId::Hash(block_hash), index))
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn get_transaction_by_block_number_and_index(
         &self,
         block_number: BlockNumber,
         index: Index,
-    ) -> Result<Option<Transaction>> {
-        self.get_transaction_impl(TransactionId::Block(BlockId::Number(block_number), index))
-            .map_err(into_jsrpc_error)
-    }
-
-    fn get_transaction_receipt(&self, hash: H256) -> Result<Option<TransactionReceipt>> {
-        self.get_transaction_receipt_impl(hash)
-            .map_err(into_jsrpc_error)
+    ) -> BoxFuture<Result<Option<Transaction>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transaction_impl(TransactionId::Block(BlockId::Number(block_number), index))
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_transaction_receipt(&self, hash: H256) -> BoxFuture<Result<Option<TransactionReceipt>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transaction_receipt_impl(hash)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_block_receipts(
+        &self,
+        block: BlockIdVariant,
+    ) -> BoxFuture<Result<Option<Vec<TransactionReceipt>>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_block_receipts_impl(block.into())
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn protocol_version(&self) -> Result<String> {
@@ -362,13 +489,19 @@ impl EthNamespaceT for EthNamespace {
     fn send_transaction(
         &self,
         _transaction_request: zksync_types::web3::types::TransactionRequest,
-    ) -> Result<H256> {
+    ) -> BoxFuture<Result<H256>> {
         #[cfg(feature = "openzeppelin_tests")]
-        return self
-            .send_transaction_impl(_transaction_request)
-            .map_err(into_jsrpc_error);
+        {
+            let namespace = self.clone();
+            return Box::pin(async move {
+                namespace
+                    .send_transaction_impl(_transaction_request)
+                    .await
+                    .map_err(into_jsrpc_error)
+            });
+        }
 
         #[cfg(not(feature = "openzeppelin_tests"))]
-        Err(into_jsrpc_error(Web3Error::NotImplemented))
+        Box::pin(async { Err(into_jsrpc_error(Web3Error::NotImplemented)) })
     }
 }
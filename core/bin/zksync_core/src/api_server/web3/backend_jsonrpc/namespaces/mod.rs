@@ -1,4 +1,8 @@
+pub mod admin;
+pub mod debug;
+pub mod en;
 pub mod eth;
 pub mod net;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
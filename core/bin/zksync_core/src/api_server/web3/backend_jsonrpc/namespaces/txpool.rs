@@ -0,0 +1,33 @@
+// Built-in uses
+
+// External uses
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+// Workspace uses
+use zksync_types::api::{TxPoolContent, TxPoolStatus};
+
+// Local uses
+use crate::web3::backend_jsonrpc::error::into_jsrpc_error;
+use crate::web3::namespaces::TxPoolNamespace;
+
+#[rpc]
+pub trait TxPoolNamespaceT {
+    #[rpc(name = "txpool_content", returns = "TxPoolContent")]
+    fn content(&self) -> BoxFuture<Result<TxPoolContent>>;
+
+    #[rpc(name = "txpool_status", returns = "TxPoolStatus")]
+    fn status(&self) -> BoxFuture<Result<TxPoolStatus>>;
+}
+
+impl TxPoolNamespaceT for TxPoolNamespace {
+    fn content(&self) -> BoxFuture<Result<TxPoolContent>> {
+        let namespace = self.clone();
+        Box::pin(async move { namespace.content_impl().await.map_err(into_jsrpc_error) })
+    }
+
+    fn status(&self) -> BoxFuture<Result<TxPoolStatus>> {
+        let namespace = self.clone();
+        Box::pin(async move { namespace.status_impl().await.map_err(into_jsrpc_error) })
+    }
+}
@@ -3,20 +3,29 @@ use std::collections::HashMap;
 
 // External uses
 use bigdecimal::BigDecimal;
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 
 // Workspace uses
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails},
+    abi_decoding::DecodedTransactionInput,
+    api::{
+        AccountStateAtTimestamp, AccountStorageProof, AccountSummary, AccountTransactionsPage,
+        AccountTxDirection, BlockAtTimestamp, BlockDependencyGraph, BlockIdVariant,
+        BridgeAddresses, ChainStats, ExpiredPriorityOp, FeeEstimate, L1BatchL1Event,
+        L2ToL1LogProof, L2TokenInfo, LogCursor, LogsPage, NodeCapabilities,
+        PendingTransactionsPage, PendingTxCursor, StateDiffPage, StateSnapshotDiffInfo,
+        SystemContext, TransactionDetails, TransactionReceipt, TransactionValidationResult,
+        TransferCursor, TransfersPage, TxCursor,
+    },
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    l1_calldata::{DecodedL1Calldata, L1BatchCommitmentData},
     transaction_request::CallRequest,
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace},
     Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
 use zksync_web3_decl::error::Web3Error;
-use zksync_web3_decl::types::Token;
+use zksync_web3_decl::types::{Filter, Token};
 
 // Local uses
 use crate::web3::backend_jsonrpc::error::into_jsrpc_error;
@@ -24,8 +33,8 @@ use crate::web3::namespaces::ZksNamespace;
 
 #[rpc]
 pub trait ZksNamespaceT {
-    #[rpc(name = "zks_estimateFee", returns = "Fee")]
-    fn estimate_fee(&self, req: CallRequest) -> Result<Fee>;
+    #[rpc(name = "zks_estimateFee", returns = "FeeEstimate")]
+    fn estimate_fee(&self, req: CallRequest) -> Result<FeeEstimate>;
 
     #[rpc(name = "zks_estimateGasL1ToL2", returns = "U256")]
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> Result<U256>;
@@ -45,6 +54,23 @@ pub trait ZksNamespaceT {
     #[rpc(name = "zks_getConfirmedTokens", returns = "Vec<Token>")]
     fn get_confirmed_tokens(&self, from: u32, limit: u8) -> Result<Vec<Token>>;
 
+    #[rpc(name = "zks_getBridgedTokens", returns = "Vec<Token>")]
+    fn get_bridged_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        after: Option<Address>,
+    ) -> Result<Vec<Token>>;
+
+    #[rpc(name = "zks_getBridgedTokenByL1Address", returns = "Option<Token>")]
+    fn get_bridged_token_by_l1_address(&self, l1_address: Address) -> Result<Option<Token>>;
+
+    #[rpc(name = "zks_getBridgedTokenByL2Address", returns = "Option<Token>")]
+    fn get_bridged_token_by_l2_address(&self, l2_address: Address) -> Result<Option<Token>>;
+
+    #[rpc(name = "zks_getTokenInfo", returns = "Option<L2TokenInfo>")]
+    fn get_token_info(&self, l2_address: Address) -> Result<Option<L2TokenInfo>>;
+
     #[rpc(name = "zks_getTokenPrice", returns = "BigDecimal")]
     fn get_token_price(&self, token_address: Address) -> Result<BigDecimal>;
 
@@ -83,6 +109,22 @@ pub trait ZksNamespaceT {
         index: Option<usize>,
     ) -> Result<Option<L2ToL1LogProof>>;
 
+    #[rpc(name = "zks_getStorageValues", returns = "Vec<H256>")]
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockIdVariant>,
+    ) -> Result<Vec<H256>>;
+
+    #[rpc(name = "zks_getProof", returns = "Option<AccountStorageProof>")]
+    fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<AccountStorageProof>>;
+
     #[rpc(name = "zks_L1BatchNumber", returns = "U64")]
     fn get_l1_batch_number(&self) -> Result<U64>;
 
@@ -99,7 +141,10 @@ pub trait ZksNamespaceT {
         name = "zks_getTransactionDetails",
         returns = "Option<TransactionDetails>"
     )]
-    fn get_transaction_details(&self, hash: H256) -> Result<Option<TransactionDetails>>;
+    fn get_transaction_details(&self, hash: H256) -> BoxFuture<Result<Option<TransactionDetails>>>;
+
+    #[rpc(name = "zks_getRawTransaction", returns = "Option<Bytes>")]
+    fn get_raw_transaction(&self, hash: H256) -> BoxFuture<Result<Option<Bytes>>>;
 
     #[rpc(
         name = "zks_getRawBlockTransactions",
@@ -108,14 +153,161 @@ pub trait ZksNamespaceT {
     fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
-    ) -> Result<Vec<zksync_types::Transaction>>;
+    ) -> BoxFuture<Result<Vec<zksync_types::Transaction>>>;
+
+    #[rpc(name = "zks_getBlockReceipts", returns = "Vec<TransactionReceipt>")]
+    fn get_block_receipts(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> BoxFuture<Result<Vec<TransactionReceipt>>>;
 
     #[rpc(name = "zks_getL1BatchDetails", returns = "Option<L1BatchDetails>")]
     fn get_l1_batch_details(&self, batch: L1BatchNumber) -> Result<Option<L1BatchDetails>>;
+
+    #[rpc(name = "zks_getNodeCapabilities", returns = "NodeCapabilities")]
+    fn get_node_capabilities(&self) -> Result<NodeCapabilities>;
+
+    #[rpc(name = "zks_decodeL1Calldata", returns = "Option<DecodedL1Calldata>")]
+    fn decode_l1_calldata(&self, tx_hash: H256) -> Result<Option<DecodedL1Calldata>>;
+
+    #[rpc(
+        name = "zks_getL1BatchCommitmentData",
+        returns = "Option<L1BatchCommitmentData>"
+    )]
+    fn get_l1_batch_commitment_data(
+        &self,
+        batch: L1BatchNumber,
+    ) -> Result<Option<L1BatchCommitmentData>>;
+
+    #[rpc(name = "zks_sendEncryptedTransaction", returns = "H256")]
+    fn send_encrypted_transaction(&self, payload: Bytes) -> Result<H256>;
+
+    #[rpc(name = "zks_getChainStats", returns = "Option<ChainStats>")]
+    fn get_chain_stats(&self) -> Result<Option<ChainStats>>;
+
+    #[rpc(
+        name = "zks_decodeTransactionInput",
+        returns = "Option<DecodedTransactionInput>"
+    )]
+    fn decode_transaction_input(
+        &self,
+        tx_hash: H256,
+        include_events: Option<bool>,
+    ) -> BoxFuture<Result<Option<DecodedTransactionInput>>>;
+
+    #[rpc(name = "zks_getAccountSummary", returns = "AccountSummary")]
+    fn get_account_summary(
+        &self,
+        address: Address,
+        tokens_limit: Option<u8>,
+    ) -> BoxFuture<Result<AccountSummary>>;
+
+    #[rpc(
+        name = "zks_getBlockDependencyGraph",
+        returns = "Option<BlockDependencyGraph>"
+    )]
+    fn get_block_dependency_graph(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockDependencyGraph>>;
+
+    #[rpc(
+        name = "zks_validateTransaction",
+        returns = "TransactionValidationResult"
+    )]
+    fn validate_transaction(&self, tx_bytes: Bytes) -> Result<TransactionValidationResult>;
+
+    #[rpc(name = "zks_getExpiredPriorityOps", returns = "Vec<ExpiredPriorityOp>")]
+    fn get_expired_priority_ops(&self, from: u64, limit: u8) -> Result<Vec<ExpiredPriorityOp>>;
+
+    #[rpc(name = "zks_getL1BatchL1Events", returns = "Vec<L1BatchL1Event>")]
+    fn get_l1_batch_l1_events(&self, batch: L1BatchNumber) -> Result<Vec<L1BatchL1Event>>;
+
+    #[rpc(name = "zks_getBytecodeByHash", returns = "Option<Vec<u8>>")]
+    fn get_bytecode_by_hash(&self, hash: H256) -> Result<Option<Vec<u8>>>;
+
+    #[rpc(name = "zks_getStateDiff", returns = "Option<StateDiffPage>")]
+    fn get_state_diff(
+        &self,
+        block_number: MiniblockNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<Option<StateDiffPage>>;
+
+    #[rpc(name = "zks_getL1BatchStateDiff", returns = "Option<StateDiffPage>")]
+    fn get_l1_batch_state_diff(
+        &self,
+        batch: L1BatchNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<Option<StateDiffPage>>;
+
+    #[rpc(
+        name = "zks_getStateSnapshotDiff",
+        returns = "Option<StateSnapshotDiffInfo>"
+    )]
+    fn get_state_snapshot_diff(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<StateSnapshotDiffInfo>>;
+
+    #[rpc(name = "zks_getLogsPaginated", returns = "LogsPage")]
+    fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        after: Option<LogCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<LogsPage>>;
+
+    #[rpc(name = "zks_getSystemContext", returns = "Option<SystemContext>")]
+    fn get_system_context(&self, block_number: MiniblockNumber) -> Result<Option<SystemContext>>;
+
+    #[rpc(
+        name = "zks_getTransactionsByAddress",
+        returns = "AccountTransactionsPage"
+    )]
+    fn get_transactions_by_address(
+        &self,
+        address: Address,
+        direction: Option<AccountTxDirection>,
+        before: Option<TxCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<AccountTransactionsPage>>;
+
+    #[rpc(
+        name = "zks_getPendingTransactions",
+        returns = "PendingTransactionsPage"
+    )]
+    fn get_pending_transactions(
+        &self,
+        after: Option<PendingTxCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<PendingTransactionsPage>>;
+
+    #[rpc(name = "zks_getTransfers", returns = "TransfersPage")]
+    fn get_transfers(
+        &self,
+        address: Address,
+        before: Option<TransferCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<TransfersPage>>;
+
+    #[rpc(name = "zks_getBlockByTimestamp", returns = "Option<BlockAtTimestamp>")]
+    fn get_block_by_timestamp(&self, timestamp: u64) -> Result<Option<BlockAtTimestamp>>;
+
+    #[rpc(
+        name = "zks_getAccountStateAtTimestamp",
+        returns = "Option<AccountStateAtTimestamp>"
+    )]
+    fn get_account_state_at_timestamp(
+        &self,
+        address: Address,
+        timestamp: u64,
+    ) -> Result<Option<AccountStateAtTimestamp>>;
 }
 
 impl ZksNamespaceT for ZksNamespace {
-    fn estimate_fee(&self, req: CallRequest) -> Result<Fee> {
+    fn estimate_fee(&self, req: CallRequest) -> Result<FeeEstimate> {
         self.estimate_fee_impl(req).map_err(into_jsrpc_error)
     }
 
@@ -150,6 +342,31 @@ impl ZksNamespaceT for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    fn get_bridged_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        after: Option<Address>,
+    ) -> Result<Vec<Token>> {
+        self.get_bridged_tokens_impl(from, limit, after)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_bridged_token_by_l1_address(&self, l1_address: Address) -> Result<Option<Token>> {
+        self.get_bridged_token_by_l1_address_impl(l1_address)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_bridged_token_by_l2_address(&self, l2_address: Address) -> Result<Option<Token>> {
+        self.get_bridged_token_by_l2_address_impl(l2_address)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_token_info(&self, l2_address: Address) -> Result<Option<L2TokenInfo>> {
+        self.get_token_info_impl(l2_address)
+            .map_err(into_jsrpc_error)
+    }
+
     fn get_token_price(&self, token_address: Address) -> Result<BigDecimal> {
         self.get_token_price_impl(token_address)
             .map_err(into_jsrpc_error)
@@ -196,6 +413,26 @@ impl ZksNamespaceT for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockIdVariant>,
+    ) -> Result<Vec<H256>> {
+        self.get_storage_values_impl(address, keys, block.map(Into::into))
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<AccountStorageProof>> {
+        self.get_proof_impl(address, keys, l1_batch_number)
+            .map_err(into_jsrpc_error)
+    }
+
     fn get_l1_batch_number(&self) -> Result<U64> {
         self.get_l1_batch_number_impl().map_err(into_jsrpc_error)
     }
@@ -205,9 +442,24 @@ impl ZksNamespaceT for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
-    fn get_transaction_details(&self, hash: H256) -> Result<Option<TransactionDetails>> {
-        self.get_transaction_details_impl(hash)
-            .map_err(into_jsrpc_error)
+    fn get_transaction_details(&self, hash: H256) -> BoxFuture<Result<Option<TransactionDetails>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transaction_details_impl(hash)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_raw_transaction(&self, hash: H256) -> BoxFuture<Result<Option<Bytes>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_raw_transaction_impl(hash)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn set_known_bytecode(&self, _bytecode: Bytes) -> Result<bool> {
@@ -221,13 +473,219 @@ impl ZksNamespaceT for ZksNamespace {
     fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
-    ) -> Result<Vec<zksync_types::Transaction>> {
-        self.get_raw_block_transactions_impl(block_number)
-            .map_err(into_jsrpc_error)
+    ) -> BoxFuture<Result<Vec<zksync_types::Transaction>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_raw_block_transactions_impl(block_number)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_block_receipts(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> BoxFuture<Result<Vec<TransactionReceipt>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_block_receipts_impl(block_number)
+                .await
+                .map_err(into_jsrpc_error)
+        })
     }
 
     fn get_l1_batch_details(&self, batch: L1BatchNumber) -> Result<Option<L1BatchDetails>> {
         self.get_l1_batch_details_impl(batch)
             .map_err(into_jsrpc_error)
     }
+
+    fn get_node_capabilities(&self) -> Result<NodeCapabilities> {
+        self.get_node_capabilities_impl().map_err(into_jsrpc_error)
+    }
+
+    fn decode_l1_calldata(&self, tx_hash: H256) -> Result<Option<DecodedL1Calldata>> {
+        self.decode_l1_calldata_impl(tx_hash)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_l1_batch_commitment_data(
+        &self,
+        batch: L1BatchNumber,
+    ) -> Result<Option<L1BatchCommitmentData>> {
+        self.get_l1_batch_commitment_data_impl(batch)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn send_encrypted_transaction(&self, payload: Bytes) -> Result<H256> {
+        self.send_encrypted_transaction_impl(payload)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_chain_stats(&self) -> Result<Option<ChainStats>> {
+        self.get_chain_stats_impl().map_err(into_jsrpc_error)
+    }
+
+    fn decode_transaction_input(
+        &self,
+        tx_hash: H256,
+        include_events: Option<bool>,
+    ) -> BoxFuture<Result<Option<DecodedTransactionInput>>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .decode_transaction_input_impl(tx_hash, include_events.unwrap_or(false))
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_account_summary(
+        &self,
+        address: Address,
+        tokens_limit: Option<u8>,
+    ) -> BoxFuture<Result<AccountSummary>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_account_summary_impl(address, tokens_limit)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_block_dependency_graph(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockDependencyGraph>> {
+        self.get_block_dependency_graph_impl(block_number)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn validate_transaction(&self, tx_bytes: Bytes) -> Result<TransactionValidationResult> {
+        self.validate_transaction_impl(tx_bytes)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_expired_priority_ops(&self, from: u64, limit: u8) -> Result<Vec<ExpiredPriorityOp>> {
+        self.get_expired_priority_ops_impl(from, limit)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_l1_batch_l1_events(&self, batch: L1BatchNumber) -> Result<Vec<L1BatchL1Event>> {
+        self.get_l1_batch_l1_events_impl(batch)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_bytecode_by_hash(&self, hash: H256) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_bytecode_by_hash_impl(hash))
+    }
+
+    fn get_state_diff(
+        &self,
+        block_number: MiniblockNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<Option<StateDiffPage>> {
+        self.get_state_diff_impl(block_number, after, limit)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_l1_batch_state_diff(
+        &self,
+        batch: L1BatchNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<Option<StateDiffPage>> {
+        self.get_l1_batch_state_diff_impl(batch, after, limit)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_state_snapshot_diff(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<StateSnapshotDiffInfo>> {
+        self.get_state_snapshot_diff_impl(l1_batch_number)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        after: Option<LogCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<LogsPage>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_logs_paginated_impl(filter, after, limit)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_system_context(&self, block_number: MiniblockNumber) -> Result<Option<SystemContext>> {
+        self.get_system_context_impl(block_number)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_transactions_by_address(
+        &self,
+        address: Address,
+        direction: Option<AccountTxDirection>,
+        before: Option<TxCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<AccountTransactionsPage>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transactions_by_address_impl(address, direction, before, limit)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_pending_transactions(
+        &self,
+        after: Option<PendingTxCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<PendingTransactionsPage>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_pending_transactions_impl(after, limit)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_transfers(
+        &self,
+        address: Address,
+        before: Option<TransferCursor>,
+        limit: Option<u16>,
+    ) -> BoxFuture<Result<TransfersPage>> {
+        let namespace = self.clone();
+        Box::pin(async move {
+            namespace
+                .get_transfers_impl(address, before, limit)
+                .await
+                .map_err(into_jsrpc_error)
+        })
+    }
+
+    fn get_block_by_timestamp(&self, timestamp: u64) -> Result<Option<BlockAtTimestamp>> {
+        self.get_block_by_timestamp_impl(timestamp)
+            .map_err(into_jsrpc_error)
+    }
+
+    fn get_account_state_at_timestamp(
+        &self,
+        address: Address,
+        timestamp: u64,
+    ) -> Result<Option<AccountStateAtTimestamp>> {
+        self.get_account_state_at_timestamp_impl(address, timestamp)
+            .map_err(into_jsrpc_error)
+    }
 }
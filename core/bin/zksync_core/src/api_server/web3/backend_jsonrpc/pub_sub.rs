@@ -5,7 +5,8 @@ use jsonrpc_derive::rpc;
 use jsonrpc_pubsub::typed;
 use jsonrpc_pubsub::{Session, SubscriptionId};
 
-use zksync_web3_decl::types::PubSubResult;
+use zksync_types::Address;
+use zksync_web3_decl::types::{AccountEvent, PubSubResult};
 
 use super::super::namespaces::EthSubscribe;
 
@@ -32,6 +33,29 @@ pub trait Web3PubSub {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> Result<bool>;
+
+    #[pubsub(
+        subscription = "zks_accountEvents",
+        subscribe,
+        name = "zks_subscribeAccountEvents"
+    )]
+    fn subscribe_account_events(
+        &self,
+        meta: Self::Metadata,
+        subscriber: typed::Subscriber<AccountEvent>,
+        addresses: Vec<Address>,
+    );
+
+    #[pubsub(
+        subscription = "zks_accountEvents",
+        unsubscribe,
+        name = "zks_unsubscribeAccountEvents"
+    )]
+    fn unsubscribe_account_events(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
 }
 
 impl Web3PubSub for EthSubscribe {
@@ -50,4 +74,21 @@ impl Web3PubSub for EthSubscribe {
     fn unsubscribe(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
         self.unsub(id)
     }
+
+    fn subscribe_account_events(
+        &self,
+        _meta: Self::Metadata,
+        subscriber: typed::Subscriber<AccountEvent>,
+        addresses: Vec<Address>,
+    ) {
+        self.sub_account_events(subscriber, addresses);
+    }
+
+    fn unsubscribe_account_events(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        self.unsub_account_events(id)
+    }
 }
@@ -3,3 +3,23 @@
 //! namespace structures defined in `zksync_core`.
 
 pub mod namespaces;
+
+use zksync_web3_decl::{
+    error::Web3Error,
+    jsonrpsee::{
+        core::Error,
+        types::error::{CallError, ErrorObject},
+    },
+};
+
+use super::conformance;
+
+pub fn into_jsrpsee_error(err: Web3Error) -> Error {
+    let error = conformance::error_object(&err);
+    CallError::Custom(ErrorObject::owned(
+        error.code as i32,
+        error.message,
+        error.data,
+    ))
+    .into()
+}
@@ -0,0 +1,75 @@
+use crate::api_server::web3::{
+    backend_jsonrpsee::into_jsrpsee_error, namespaces::admin::AdminNamespace,
+};
+use zksync_types::{
+    api::{AdminAuthorization, BlockRevertPlan, BootloaderUsageReport},
+    Address, L1BatchNumber, H256,
+};
+use zksync_web3_decl::{
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::admin::AdminNamespaceServer,
+};
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    fn get_deployer_allowlist(&self) -> RpcResult<Vec<Address>> {
+        Ok(self.get_deployer_allowlist_impl())
+    }
+
+    fn add_deployer_to_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<bool> {
+        self.add_deployer_to_allowlist_impl(address, authorization)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn remove_deployer_from_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<bool> {
+        self.remove_deployer_from_allowlist_impl(address, authorization)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_quarantined_transactions(&self) -> RpcResult<Vec<H256>> {
+        Ok(self.get_quarantined_transactions_impl())
+    }
+
+    fn release_quarantined_transaction(
+        &self,
+        hash: H256,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<bool> {
+        self.release_quarantined_transaction_impl(hash, authorization)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_block_revert_plan(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BlockRevertPlan>> {
+        self.get_block_revert_plan_impl(l1_batch_number)
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn revert_blocks(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stages: Vec<String>,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<BlockRevertPlan> {
+        self.revert_blocks_impl(l1_batch_number, stages, authorization)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_bootloader_usage_report(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BootloaderUsageReport>> {
+        Ok(self.get_bootloader_usage_report_impl(l1_batch_number))
+    }
+}
@@ -0,0 +1,53 @@
+use crate::api_server::web3::{
+    backend_jsonrpsee::into_jsrpsee_error, namespaces::debug::DebugNamespace,
+};
+
+use zksync_types::{
+    api::{BlockIdVariant, BlockNumber, TxLifecycleEvent},
+    transaction_request::CallRequest,
+    vm_trace::{DebugTraceResult, TracerOptions, TxTraceResult},
+    H256,
+};
+
+use zksync_web3_decl::{
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::debug::DebugNamespaceServer,
+};
+
+#[async_trait]
+impl DebugNamespaceServer for DebugNamespace {
+    fn trace_call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        options: TracerOptions,
+    ) -> RpcResult<DebugTraceResult> {
+        self.trace_call_impl(req, block.map(Into::into), options)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_transaction_lifecycle(&self, hash: H256) -> RpcResult<Vec<TxLifecycleEvent>> {
+        self.get_transaction_lifecycle_impl(hash)
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn trace_transaction(
+        &self,
+        hash: H256,
+        options: Option<TracerOptions>,
+    ) -> RpcResult<Option<DebugTraceResult>> {
+        self.trace_transaction_impl(hash, options)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        options: Option<TracerOptions>,
+    ) -> RpcResult<Vec<TxTraceResult>> {
+        self.trace_block_impl(block, options)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+}
@@ -0,0 +1,9 @@
+use crate::api_server::web3::namespaces::en::EnNamespace;
+use zksync_types::api::NodeIdentity;
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::en::EnNamespaceServer};
+
+impl EnNamespaceServer for EnNamespace {
+    fn handshake(&self, peer: NodeIdentity) -> RpcResult<NodeIdentity> {
+        Ok(self.handshake_impl(peer))
+    }
+}
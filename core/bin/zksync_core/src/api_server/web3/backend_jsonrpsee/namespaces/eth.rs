@@ -1,9 +1,11 @@
-use crate::api_server::web3::namespaces::eth::EthNamespace;
+use crate::api_server::web3::{
+    backend_jsonrpsee::into_jsrpsee_error, namespaces::eth::EthNamespace,
+};
 
 use zksync_types::{
     api::{
-        Block, BlockId, BlockIdVariant, BlockNumber, Log, Transaction, TransactionId,
-        TransactionReceipt, TransactionVariant,
+        AccessListWithGasUsed, AccountInfo, Block, BlockId, BlockIdVariant, BlockNumber,
+        FeeHistory, Log, Transaction, TransactionId, TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
     web3::types::{Index, SyncState},
@@ -11,15 +13,15 @@ use zksync_types::{
 };
 
 use zksync_web3_decl::{
-    jsonrpsee::{core::RpcResult, types::error::CallError},
+    jsonrpsee::core::{async_trait, RpcResult},
     namespaces::eth::EthNamespaceServer,
     types::{Filter, FilterChanges},
 };
 
+#[async_trait]
 impl EthNamespaceServer for EthNamespace {
     fn get_block_number(&self) -> RpcResult<U64> {
-        self.get_block_number_impl()
-            .map_err(|err| CallError::from_std_error(err).into())
+        self.get_block_number_impl().map_err(into_jsrpsee_error)
     }
 
     fn chain_id(&self) -> RpcResult<U64> {
@@ -28,27 +30,48 @@ impl EthNamespaceServer for EthNamespace {
 
     fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
         self.call_impl(req, block.map(Into::into))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn estimate_gas(&self, req: CallRequest, block: Option<BlockNumber>) -> RpcResult<U256> {
-        self.estimate_gas_impl(req, block)
-            .map_err(|err| CallError::from_std_error(err).into())
+        self.estimate_gas_impl(req, block.map(BlockId::Number))
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccessListWithGasUsed> {
+        self.create_access_list_impl(req, block.map(Into::into))
+            .map_err(into_jsrpsee_error)
     }
 
     fn gas_price(&self) -> RpcResult<U256> {
-        self.gas_price_impl()
-            .map_err(|err| CallError::from_std_error(err).into())
+        self.gas_price_impl().map_err(into_jsrpsee_error)
+    }
+
+    fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+        self.max_priority_fee_per_gas_impl()
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistory> {
+        self.fee_history_impl(block_count, newest_block, reward_percentiles)
+            .map_err(into_jsrpsee_error)
     }
 
     fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
-        self.new_filter_impl(filter)
-            .map_err(|err| CallError::from_std_error(err).into())
+        self.new_filter_impl(filter).map_err(into_jsrpsee_error)
     }
 
     fn new_block_filter(&self) -> RpcResult<U256> {
-        self.new_block_filter_impl()
-            .map_err(|err| CallError::from_std_error(err).into())
+        self.new_block_filter_impl().map_err(into_jsrpsee_error)
     }
 
     fn uninstall_filter(&self, idx: U256) -> RpcResult<bool> {
@@ -59,24 +82,25 @@ impl EthNamespaceServer for EthNamespace {
         Ok(self.new_pending_transaction_filter_impl())
     }
 
-    fn get_logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
-        self.get_logs_impl(filter)
-            .map_err(|err| CallError::from_std_error(err).into())
+    async fn get_logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
+        self.get_logs_impl(filter).await.map_err(into_jsrpsee_error)
     }
 
-    fn get_filter_logs(&self, filter_index: U256) -> RpcResult<FilterChanges> {
+    async fn get_filter_logs(&self, filter_index: U256) -> RpcResult<FilterChanges> {
         self.get_filter_logs_impl(filter_index)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_filter_changes(&self, filter_index: U256) -> RpcResult<FilterChanges> {
+    async fn get_filter_changes(&self, filter_index: U256) -> RpcResult<FilterChanges> {
         self.get_filter_changes_impl(filter_index)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_balance(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<U256> {
         self.get_balance_impl(address, block.map(Into::into))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_block_by_number(
@@ -85,7 +109,7 @@ impl EthNamespaceServer for EthNamespace {
         full_transactions: bool,
     ) -> RpcResult<Option<Block<TransactionVariant>>> {
         self.get_block_impl(BlockId::Number(block_number), full_transactions)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_block_by_hash(
@@ -94,7 +118,7 @@ impl EthNamespaceServer for EthNamespace {
         full_transactions: bool,
     ) -> RpcResult<Option<Block<TransactionVariant>>> {
         self.get_block_impl(BlockId::Hash(hash), full_transactions)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_block_transaction_count_by_number(
@@ -102,17 +126,17 @@ impl EthNamespaceServer for EthNamespace {
         block_number: BlockNumber,
     ) -> RpcResult<Option<U256>> {
         self.get_block_transaction_count_impl(BlockId::Number(block_number))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_block_transaction_count_by_hash(&self, block_hash: H256) -> RpcResult<Option<U256>> {
         self.get_block_transaction_count_impl(BlockId::Hash(block_hash))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_code(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
         self.get_code_impl(address, block.map(Into::into))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_storage_at(
@@ -122,44 +146,73 @@ impl EthNamespaceServer for EthNamespace {
         block: Option<BlockIdVariant>,
     ) -> RpcResult<H256> {
         self.get_storage_at_impl(address, idx, block.map(Into::into))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_transaction_count(
+    fn get_account(
+        &self,
+        address: Address,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccountInfo> {
+        self.get_account_impl(address, block.map(Into::into))
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_transaction_count(
         &self,
         address: Address,
         block: Option<BlockIdVariant>,
     ) -> RpcResult<U256> {
         self.get_transaction_count_impl(address, block.map(Into::into))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Transaction>> {
+    async fn get_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Transaction>> {
         self.get_transaction_impl(TransactionId::Hash(hash))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_transaction_by_block_hash_and_index(
+    async fn get_raw_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Bytes>> {
+        self.get_raw_transaction_by_hash_impl(hash)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_transaction_by_block_hash_and_index(
         &self,
         block_hash: H256,
         index: Index,
     ) -> RpcResult<Option<Transaction>> {
         self.get_transaction_impl(TransactionId::Block(BlockId::Hash(block_hash), index))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_transaction_by_block_number_and_index(
+    async fn get_transaction_by_block_number_and_index(
         &self,
         block_number: BlockNumber,
         index: Index,
     ) -> RpcResult<Option<Transaction>> {
         self.get_transaction_impl(TransactionId::Block(BlockId::Number(block_number), index))
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>> {
+    async fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>> {
         self.get_transaction_receipt_impl(hash)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_block_receipts(
+        &self,
+        block: BlockIdVariant,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>> {
+        self.get_block_receipts_impl(block.into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
     fn protocol_version(&self) -> RpcResult<String> {
@@ -168,7 +221,7 @@ impl EthNamespaceServer for EthNamespace {
 
     fn send_raw_transaction(&self, tx_bytes: Bytes) -> RpcResult<H256> {
         self.send_raw_transaction_impl(tx_bytes)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn syncing(&self) -> RpcResult<SyncState> {
@@ -1,5 +1,9 @@
+pub mod admin;
+pub mod debug;
+pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
@@ -0,0 +1,19 @@
+use crate::api_server::web3::{
+    backend_jsonrpsee::into_jsrpsee_error, namespaces::txpool::TxPoolNamespace,
+};
+use zksync_types::api::{TxPoolContent, TxPoolStatus};
+use zksync_web3_decl::{
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::txpool::TxPoolNamespaceServer,
+};
+
+#[async_trait]
+impl TxPoolNamespaceServer for TxPoolNamespace {
+    async fn content(&self) -> RpcResult<TxPoolContent> {
+        self.content_impl().await.map_err(into_jsrpsee_error)
+    }
+
+    async fn status(&self) -> RpcResult<TxPoolStatus> {
+        self.status_impl().await.map_err(into_jsrpsee_error)
+    }
+}
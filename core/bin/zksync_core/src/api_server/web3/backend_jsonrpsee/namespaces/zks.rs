@@ -1,29 +1,40 @@
-use crate::api_server::web3::namespaces::zks::ZksNamespace;
+use crate::api_server::web3::{
+    backend_jsonrpsee::into_jsrpsee_error, namespaces::zks::ZksNamespace,
+};
 use bigdecimal::BigDecimal;
 use std::collections::HashMap;
 use zksync_types::{
-    api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails, U64},
+    abi_decoding::DecodedTransactionInput,
+    api::{
+        AccountStateAtTimestamp, AccountStorageProof, AccountSummary, AccountTransactionsPage,
+        AccountTxDirection, BlockAtTimestamp, BlockDependencyGraph, BlockIdVariant,
+        BridgeAddresses, ChainStats, ExpiredPriorityOp, FeeEstimate, L1BatchL1Event,
+        L2ToL1LogProof, L2TokenInfo, LogCursor, LogsPage, NodeCapabilities,
+        PendingTransactionsPage, PendingTxCursor, StateDiffPage, StateSnapshotDiffInfo,
+        SystemContext, TransactionDetails, TransactionReceipt, TransactionValidationResult,
+        TransferCursor, TransfersPage, TxCursor, U64,
+    },
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    l1_calldata::{DecodedL1Calldata, L1BatchCommitmentData},
     transaction_request::CallRequest,
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace},
-    Address, L1BatchNumber, MiniblockNumber, H256, U256,
+    Address, Bytes, L1BatchNumber, MiniblockNumber, H256, U256,
 };
 use zksync_web3_decl::{
-    jsonrpsee::{core::RpcResult, types::error::CallError},
+    jsonrpsee::core::{async_trait, RpcResult},
     namespaces::zks::ZksNamespaceServer,
-    types::Token,
+    types::{Filter, Token},
 };
 
+#[async_trait]
 impl ZksNamespaceServer for ZksNamespace {
-    fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee> {
-        self.estimate_fee_impl(req)
-            .map_err(|err| CallError::from_std_error(err).into())
+    fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate> {
+        self.estimate_fee_impl(req).map_err(into_jsrpsee_error)
     }
 
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
         self.estimate_l1_to_l2_gas_impl(req)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_main_contract(&self) -> RpcResult<Address> {
@@ -44,12 +55,37 @@ impl ZksNamespaceServer for ZksNamespace {
 
     fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>> {
         self.get_confirmed_tokens_impl(from, limit)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_bridged_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        after: Option<Address>,
+    ) -> RpcResult<Vec<Token>> {
+        self.get_bridged_tokens_impl(from, limit, after)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_bridged_token_by_l1_address(&self, l1_address: Address) -> RpcResult<Option<Token>> {
+        self.get_bridged_token_by_l1_address_impl(l1_address)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_bridged_token_by_l2_address(&self, l2_address: Address) -> RpcResult<Option<Token>> {
+        self.get_bridged_token_by_l2_address_impl(l2_address)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_token_info(&self, l2_address: Address) -> RpcResult<Option<L2TokenInfo>> {
+        self.get_token_info_impl(l2_address)
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_token_price(&self, token_address: Address) -> RpcResult<BigDecimal> {
         self.get_token_price_impl(token_address)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn set_contract_debug_info(
@@ -73,7 +109,7 @@ impl ZksNamespaceServer for ZksNamespace {
 
     fn get_all_account_balances(&self, address: Address) -> RpcResult<HashMap<Address, U256>> {
         self.get_all_account_balances_impl(address)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_l2_to_l1_msg_proof(
@@ -84,7 +120,7 @@ impl ZksNamespaceServer for ZksNamespace {
         l2_log_position: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>> {
         self.get_l2_to_l1_msg_proof_impl(block, sender, msg, l2_log_position)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_l2_to_l1_log_proof(
@@ -93,35 +129,71 @@ impl ZksNamespaceServer for ZksNamespace {
         index: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>> {
         self.get_l2_to_l1_log_proof_impl(tx_hash, index)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<H256>> {
+        self.get_storage_values_impl(address, keys, block.map(Into::into))
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<AccountStorageProof>> {
+        self.get_proof_impl(address, keys, l1_batch_number)
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_l1_batch_number(&self) -> RpcResult<U64> {
-        self.get_l1_batch_number_impl()
-            .map_err(|err| CallError::from_std_error(err).into())
+        self.get_l1_batch_number_impl().map_err(into_jsrpsee_error)
     }
 
     fn get_miniblock_range(&self, batch: L1BatchNumber) -> RpcResult<Option<(U64, U64)>> {
         self.get_miniblock_range_impl(batch)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_block_details(&self, block_number: MiniblockNumber) -> RpcResult<Option<BlockDetails>> {
         self.get_block_details_impl(block_number)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>> {
+    async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>> {
         self.get_transaction_details_impl(hash)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
-    fn get_raw_block_transactions(
+    async fn get_raw_transaction(&self, hash: H256) -> RpcResult<Option<Bytes>> {
+        self.get_raw_transaction_impl(hash)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
     ) -> RpcResult<Vec<zksync_types::Transaction>> {
         self.get_raw_block_transactions_impl(block_number)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_block_receipts(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Vec<TransactionReceipt>> {
+        self.get_block_receipts_impl(block_number)
+            .await
+            .map_err(into_jsrpsee_error)
     }
 
     fn get_l1_batch_details(
@@ -129,6 +201,174 @@ impl ZksNamespaceServer for ZksNamespace {
         batch_number: L1BatchNumber,
     ) -> RpcResult<Option<L1BatchDetails>> {
         self.get_l1_batch_details_impl(batch_number)
-            .map_err(|err| CallError::from_std_error(err).into())
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_node_capabilities(&self) -> RpcResult<NodeCapabilities> {
+        self.get_node_capabilities_impl()
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn decode_l1_calldata(&self, tx_hash: H256) -> RpcResult<Option<DecodedL1Calldata>> {
+        self.decode_l1_calldata_impl(tx_hash)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_l1_batch_commitment_data(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchCommitmentData>> {
+        self.get_l1_batch_commitment_data_impl(batch_number)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn send_encrypted_transaction(&self, payload: Bytes) -> RpcResult<H256> {
+        self.send_encrypted_transaction_impl(payload)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_chain_stats(&self) -> RpcResult<Option<ChainStats>> {
+        self.get_chain_stats_impl().map_err(into_jsrpsee_error)
+    }
+
+    async fn decode_transaction_input(
+        &self,
+        tx_hash: H256,
+        include_events: Option<bool>,
+    ) -> RpcResult<Option<DecodedTransactionInput>> {
+        self.decode_transaction_input_impl(tx_hash, include_events.unwrap_or(false))
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_account_summary(
+        &self,
+        address: Address,
+        tokens_limit: Option<u8>,
+    ) -> RpcResult<AccountSummary> {
+        self.get_account_summary_impl(address, tokens_limit)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_block_dependency_graph(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<BlockDependencyGraph>> {
+        self.get_block_dependency_graph_impl(block_number)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn validate_transaction(&self, tx_bytes: Bytes) -> RpcResult<TransactionValidationResult> {
+        self.validate_transaction_impl(tx_bytes)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_expired_priority_ops(&self, from: u64, limit: u8) -> RpcResult<Vec<ExpiredPriorityOp>> {
+        self.get_expired_priority_ops_impl(from, limit)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_l1_batch_l1_events(&self, batch: L1BatchNumber) -> RpcResult<Vec<L1BatchL1Event>> {
+        self.get_l1_batch_l1_events_impl(batch)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
+        Ok(self.get_bytecode_by_hash_impl(hash))
+    }
+
+    fn get_state_diff(
+        &self,
+        block_number: MiniblockNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> RpcResult<Option<StateDiffPage>> {
+        self.get_state_diff_impl(block_number, after, limit)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_l1_batch_state_diff(
+        &self,
+        batch: L1BatchNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> RpcResult<Option<StateDiffPage>> {
+        self.get_l1_batch_state_diff_impl(batch, after, limit)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_state_snapshot_diff(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<StateSnapshotDiffInfo>> {
+        self.get_state_snapshot_diff_impl(l1_batch_number)
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        after: Option<LogCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<LogsPage> {
+        self.get_logs_paginated_impl(filter, after, limit)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_system_context(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<SystemContext>> {
+        self.get_system_context_impl(block_number)
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_transactions_by_address(
+        &self,
+        address: Address,
+        direction: Option<AccountTxDirection>,
+        before: Option<TxCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<AccountTransactionsPage> {
+        self.get_transactions_by_address_impl(address, direction, before, limit)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_pending_transactions(
+        &self,
+        after: Option<PendingTxCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<PendingTransactionsPage> {
+        self.get_pending_transactions_impl(after, limit)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    async fn get_transfers(
+        &self,
+        address: Address,
+        before: Option<TransferCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<TransfersPage> {
+        self.get_transfers_impl(address, before, limit)
+            .await
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_block_by_timestamp(&self, timestamp: u64) -> RpcResult<Option<BlockAtTimestamp>> {
+        self.get_block_by_timestamp_impl(timestamp)
+            .map_err(into_jsrpsee_error)
+    }
+
+    fn get_account_state_at_timestamp(
+        &self,
+        address: Address,
+        timestamp: u64,
+    ) -> RpcResult<Option<AccountStateAtTimestamp>> {
+        self.get_account_state_at_timestamp_impl(address, timestamp)
+            .map_err(into_jsrpsee_error)
     }
 }
@@ -0,0 +1,319 @@
+//! Mapping of the API errors to the JSON-RPC error objects shared by both backends.
+//!
+//! Codes follow [EIP-1474] and the [Ethereum execution APIs] specification, so that the client libraries
+//! can recognize the errors (e.g., reverts and exceeded limits) regardless of the handler returning them.
+//! Codes 4 and 5 are zkSync-specific and kept for compatibility; unauthorized calls use the [EIP-1193] code.
+//!
+//! [EIP-1474]: https://eips.ethereum.org/EIPS/eip-1474
+//! [EIP-1193]: https://eips.ethereum.org/EIPS/eip-1193
+//! [Ethereum execution APIs]: https://github.com/ethereum/execution-apis
+
+use serde_json::{json, Value};
+use zksync_web3_decl::error::Web3Error;
+
+/// JSON-RPC error codes returned by the API.
+pub mod codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+    pub const INVALID_INPUT: i64 = -32000;
+    pub const RESOURCE_NOT_FOUND: i64 = -32001;
+    pub const RESOURCE_UNAVAILABLE: i64 = -32002;
+    pub const TRANSACTION_REJECTED: i64 = -32003;
+    pub const METHOD_NOT_SUPPORTED: i64 = -32004;
+    pub const LIMIT_EXCEEDED: i64 = -32005;
+    /// Reverted execution; the revert data is returned as the error data.
+    pub const EXECUTION_REVERTED: i64 = 3;
+    pub const PUB_SUB_TIMEOUT: i64 = 4;
+    pub const REQUEST_TIMEOUT: i64 = 5;
+    /// The caller isn't authorized to call the method, e.g. an admin method with a bad signature.
+    pub const UNAUTHORIZED: i64 = 4100;
+
+    /// Code in the data of the transaction submission errors.
+    pub const SUBMIT_TX_FAILED: i64 = 104;
+    /// Code in the data of the errors caused by the gas limit exceeding the cap.
+    pub const TX_GAS_LIMIT_EXCEEDS_CAP: i64 = 105;
+}
+
+/// JSON-RPC error object as returned in the response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorObject {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+pub fn error_object(err: &Web3Error) -> ErrorObject {
+    let code = match err {
+        Web3Error::InternalError => codes::INTERNAL_ERROR,
+        Web3Error::NotImplemented => codes::METHOD_NOT_SUPPORTED,
+        Web3Error::NoBlock
+        | Web3Error::BlockReorged(_, _, _)
+        | Web3Error::NoSuchFunction
+        | Web3Error::RLPError(_)
+        | Web3Error::InvalidTransactionData(_)
+        | Web3Error::TooManyTopics
+        | Web3Error::FilterNotFound
+        | Web3Error::InvalidFeeParams(_) => codes::INVALID_PARAMS,
+        // Rejected transactions get the generic server error code, as in Geth (which the execution APIs
+        // test vectors are generated against); the zkSync-specific code is provided in the data.
+        Web3Error::SerializationError(_)
+        | Web3Error::BlockRevertRejected(_)
+        | Web3Error::SecurityCheckFailed(_)
+        | Web3Error::SubmitTransactionError(_)
+        | Web3Error::TxGasLimitExceedsCap(_, _) => codes::INVALID_INPUT,
+        Web3Error::LogsLimitExceeded(_, _, _)
+        | Web3Error::LogsBlockRangeExceeded(_)
+        | Web3Error::ResultLimitExceeded(_) => codes::LIMIT_EXCEEDED,
+        Web3Error::ExecutionReverted(_, _) => codes::EXECUTION_REVERTED,
+        Web3Error::PubSubTimeout => codes::PUB_SUB_TIMEOUT,
+        Web3Error::RequestTimeout => codes::REQUEST_TIMEOUT,
+        Web3Error::Unauthorized(_) => codes::UNAUTHORIZED,
+    };
+
+    let data = match err {
+        Web3Error::ExecutionReverted(_, data) if !data.is_empty() => {
+            Some(json!(format!("0x{}", hex::encode(data))))
+        }
+        Web3Error::SubmitTransactionError(message) => Some(json!({
+            "code": codes::SUBMIT_TX_FAILED,
+            "message": message,
+        })),
+        Web3Error::TxGasLimitExceedsCap(gas_limit, cap) => Some(json!({
+            "code": codes::TX_GAS_LIMIT_EXCEEDS_CAP,
+            "message": err.to_string(),
+            "gasLimit": gas_limit,
+            "maxAllowedGasLimit": cap,
+        })),
+        Web3Error::LogsLimitExceeded(limit, from_block, to_block) => Some(json!({
+            "limit": limit,
+            "fromBlock": format!("{:#x}", from_block),
+            "toBlock": format!("{:#x}", to_block),
+        })),
+        Web3Error::BlockReorged(hash, number, superseded_by) => Some(json!({
+            "reorged": true,
+            "blockHash": hash,
+            "blockNumber": format!("{:#x}", number.0),
+            "supersededBy": superseded_by,
+        })),
+        Web3Error::ResultLimitExceeded(limit) => Some(json!({ "limit": limit })),
+        Web3Error::LogsBlockRangeExceeded(limit) => Some(json!({ "limit": limit })),
+        _ => None,
+    };
+
+    ErrorObject {
+        code,
+        message: err.to_string(),
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use vm::TxRevertReason;
+    use zksync_types::{api::SerializationTransactionError, H256, U256};
+    use zksync_web3_decl::jsonrpsee::{core::Error as JsonrpseeError, types::error::CallError};
+
+    use crate::api_server::execution_sandbox::SandboxExecutionError;
+    use crate::api_server::tx_sender::SubmitTxError;
+    use crate::api_server::web3::backend_jsonrpc::error::into_jsrpc_error;
+    use crate::api_server::web3::backend_jsonrpsee::into_jsrpsee_error;
+
+    fn response_error(err: Web3Error) -> Value {
+        let error = error_object(&err);
+        let mut response = json!({ "code": error.code, "message": error.message });
+        if let Some(data) = error.data {
+            response["data"] = data;
+        }
+        response
+    }
+
+    /// Request / response exchange from an `.io` file of the execution APIs test suite.
+    #[derive(Debug)]
+    struct Exchange {
+        path: PathBuf,
+        request: Value,
+        response: Value,
+    }
+
+    /// Loads the execution APIs test vectors. By default, the vectors vendored in `etc/execution_apis_tests`
+    /// are used; `EXECUTION_APIS_TESTS_DIR` may point to the `tests` directory of a full checkout instead.
+    fn load_execution_apis_vectors() -> Vec<Exchange> {
+        let dir = std::env::var("EXECUTION_APIS_TESTS_DIR").map_or_else(
+            |_| {
+                let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
+                Path::new(&zksync_home).join("etc/execution_apis_tests")
+            },
+            PathBuf::from,
+        );
+        let mut exchanges = vec![];
+        load_vectors_from_dir(&dir, &mut exchanges);
+        exchanges
+    }
+
+    fn load_vectors_from_dir(dir: &Path, exchanges: &mut Vec<Exchange>) {
+        let entries = fs::read_dir(dir)
+            .unwrap_or_else(|err| panic!("cannot read test vectors in {}: {}", dir.display(), err));
+        for entry in entries {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                load_vectors_from_dir(&path, exchanges);
+            } else if path.extension().map_or(false, |ext| ext == "io") {
+                let contents = fs::read_to_string(&path).unwrap();
+                let mut request = None;
+                for line in contents.lines() {
+                    if let Some(line) = line.strip_prefix(">> ") {
+                        request = Some(serde_json::from_str(line).unwrap());
+                    } else if let Some(line) = line.strip_prefix("<< ") {
+                        exchanges.push(Exchange {
+                            path: path.clone(),
+                            request: request.take().expect("response without a request"),
+                            response: serde_json::from_str(line).unwrap(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the revert data of the exchange if it's applicable to zkSync, i.e. a reverted call
+    /// whose outcome only depends on the data returned by the contract.
+    fn revert_data(exchange: &Exchange) -> Option<Vec<u8>> {
+        let method = exchange.request["method"].as_str()?;
+        if !matches!(method, "eth_call" | "eth_estimateGas") {
+            return None;
+        }
+        let error = exchange.response.get("error")?;
+        if error["code"] != codes::EXECUTION_REVERTED {
+            return None;
+        }
+        let data = error.get("data").and_then(Value::as_str).unwrap_or("0x");
+        Some(hex::decode(data.trim_start_matches("0x")).unwrap())
+    }
+
+    /// Replays the revert data through the same conversions as the API does for a reverted call,
+    /// and returns the responses of both backends.
+    fn replay_revert(id: &Value, revert_data: &[u8]) -> [Value; 2] {
+        // The first byte of the bootloader output is the error code; 0 stands for a reverted call.
+        let bootloader_output = [&[0_u8] as &[u8], revert_data].concat();
+        let to_web3_error = || {
+            let reason = TxRevertReason::parse_error(&bootloader_output);
+            Web3Error::from(SubmitTxError::from(SandboxExecutionError::from(reason)))
+        };
+
+        let jsonrpc_response = jsonrpc_core::Failure {
+            jsonrpc: Some(jsonrpc_core::Version::V2),
+            error: into_jsrpc_error(to_web3_error()),
+            id: serde_json::from_value(id.clone()).unwrap(),
+        };
+        let jsonrpsee_error = match into_jsrpsee_error(to_web3_error()) {
+            JsonrpseeError::Call(CallError::Custom(error)) => error,
+            err => panic!("unexpected jsonrpsee error: {:?}", err),
+        };
+        let jsonrpsee_response = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": jsonrpsee_error,
+        });
+        [
+            serde_json::to_value(jsonrpc_response).unwrap(),
+            jsonrpsee_response,
+        ]
+    }
+
+    #[test]
+    fn execution_apis_vectors() {
+        let exchanges = load_execution_apis_vectors();
+        let mut replayed = 0;
+        for exchange in &exchanges {
+            let revert_data = match revert_data(exchange) {
+                Some(data) => data,
+                None => continue,
+            };
+            for response in replay_revert(&exchange.request["id"], &revert_data) {
+                assert_eq!(
+                    response,
+                    exchange.response,
+                    "response mismatch for {}",
+                    exchange.path.display()
+                );
+            }
+            replayed += 1;
+        }
+        assert!(
+            replayed > 0,
+            "no applicable vectors among {}",
+            exchanges.len()
+        );
+    }
+
+    #[test]
+    fn reverts_without_data() {
+        // Reverts without data have no `data` field, as in Geth.
+        let err = Web3Error::ExecutionReverted(String::new(), vec![]);
+        assert_eq!(
+            response_error(err),
+            json!({ "code": 3, "message": "execution reverted" })
+        );
+    }
+
+    #[test]
+    fn limit_errors_share_code() {
+        let errors = [
+            Web3Error::LogsLimitExceeded(10_000, 1, 2),
+            Web3Error::LogsBlockRangeExceeded(1_000),
+            Web3Error::ResultLimitExceeded(100),
+        ];
+        for err in errors {
+            let error = error_object(&err);
+            assert_eq!(error.code, codes::LIMIT_EXCEEDED);
+            assert!(error.data.unwrap()["limit"].is_number());
+        }
+    }
+
+    #[test]
+    fn error_data_payloads() {
+        let err = Web3Error::TxGasLimitExceedsCap(U256::from(100_000_000), 80_000_000);
+        let error = error_object(&err);
+        assert_eq!(error.code, codes::INVALID_INPUT);
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], codes::TX_GAS_LIMIT_EXCEEDS_CAP);
+        assert_eq!(data["maxAllowedGasLimit"], 80_000_000);
+
+        let err = Web3Error::BlockReorged(H256::zero(), 16.into(), None);
+        assert_eq!(response_error(err)["data"]["blockNumber"], json!("0x10"));
+
+        assert_eq!(
+            response_error(Web3Error::NotImplemented),
+            json!({ "code": -32004, "message": "Not implemented" })
+        );
+        assert_eq!(
+            error_object(&Web3Error::NoBlock).code,
+            codes::INVALID_PARAMS
+        );
+        assert_eq!(
+            error_object(&Web3Error::Unauthorized("deadline has passed".to_owned())).code,
+            codes::UNAUTHORIZED
+        );
+        let err = Web3Error::from(SerializationTransactionError::ToAddressIsNull);
+        assert_eq!(error_object(&err).code, codes::INVALID_INPUT);
+
+        let err = Web3Error::from(SubmitTxError::NonceIsTooLow(1, 2, 0));
+        assert_eq!(
+            response_error(err),
+            json!({
+                "code": -32000,
+                "message": "Failed to submit transaction: nonce too low. allowed nonce range: 1 - 2, actual: 0",
+                "data": {
+                    "code": codes::SUBMIT_TX_FAILED,
+                    "message": "nonce too low. allowed nonce range: 1 - 2, actual: 0",
+                },
+            })
+        );
+    }
+}
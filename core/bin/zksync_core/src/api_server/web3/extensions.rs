@@ -0,0 +1,191 @@
+//! Negotiation of the zkSync extensions of the Ethereum-compatible responses.
+//!
+//! Responses are strictly Ethereum-compatible by default. Clients aware of zkSync Era may request the extension
+//! fields (e.g. the L1 batch of a block, see [`BlockExtensions`]) per request, by sending the `x-zks-extensions: true`
+//! header or the `zks_extensions=true` query parameter with an HTTP API call. Both API backends support
+//! the negotiation: the `jsonrpc` one via [`ApiExtensionsMiddleware`] and the `jsonrpsee` one via
+//! [`ApiExtensionsLayer`]. Subscriptions and other calls made over WS always get the strict format.
+//!
+//! [`BlockExtensions`]: zksync_types::api::BlockExtensions
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::future::Either;
+use jsonrpc_core::middleware::Middleware;
+use jsonrpc_core::{Call, FutureOutput, FutureResponse, Metadata, Output};
+use jsonrpc_http_server::hyper::Request;
+use tokio::task::futures::TaskLocalFuture;
+use tower::{Layer, Service};
+
+pub const EXTENSIONS_HEADER: &str = "x-zks-extensions";
+pub const EXTENSIONS_QUERY_PARAM: &str = "zks_extensions";
+
+tokio::task_local! {
+    static EXTENSIONS_ENABLED: bool;
+}
+
+/// Returns `true` if the call being handled has requested the zkSync extensions.
+pub fn extensions_enabled() -> bool {
+    EXTENSIONS_ENABLED
+        .try_with(|enabled| *enabled)
+        .unwrap_or(false)
+}
+
+/// Returns `true` if the HTTP request asks for the zkSync extensions either with a header or a query parameter.
+pub fn extensions_requested<B>(request: &Request<B>) -> bool {
+    let header = request
+        .headers()
+        .get(EXTENSIONS_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let query_param = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| match pair.split_once('=') {
+                Some((name, value)) if name == EXTENSIONS_QUERY_PARAM => Some(value),
+                None if pair == EXTENSIONS_QUERY_PARAM => Some("true"),
+                _ => None,
+            })
+    });
+    header.or(query_param).map_or(false, is_enabled_value)
+}
+
+fn is_enabled_value(value: &str) -> bool {
+    matches!(value.trim(), "1" | "true" | "TRUE" | "True")
+}
+
+/// Metadata the negotiated extensions can be extracted from.
+pub trait NegotiatedExtensions {
+    fn extensions_enabled(&self) -> bool;
+}
+
+impl NegotiatedExtensions for Arc<jsonrpc_pubsub::Session> {
+    fn extensions_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// Middleware exposing the extensions negotiated by the request to the methods of the `jsonrpc` backend
+/// via [`extensions_enabled()`].
+///
+/// Methods of the `jsonrpc` backend are synchronous and are executed within `next`, before the returned
+/// future is polled, so the flag is set only for the duration of the call.
+#[derive(Debug, Clone, Default)]
+pub struct ApiExtensionsMiddleware;
+
+impl<M: Metadata + NegotiatedExtensions> Middleware<M> for ApiExtensionsMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = FutureOutput;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: futures::Future<Output = Option<Output>> + Send + 'static,
+    {
+        let enabled = meta.extensions_enabled();
+        Either::Right(EXTENSIONS_ENABLED.sync_scope(enabled, || next(call, meta)))
+    }
+}
+
+/// Tower layer exposing the extensions negotiated by the HTTP request to the methods of the `jsonrpsee` backend
+/// via [`extensions_enabled()`]. The flag is set while the request is being handled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiExtensionsLayer;
+
+impl<S> Layer<S> for ApiExtensionsLayer {
+    type Service = ApiExtensionsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiExtensionsService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiExtensionsService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for ApiExtensionsService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<bool, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let enabled = extensions_requested(&request);
+        EXTENSIONS_ENABLED.scope(enabled, self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpc_http_server::hyper::Body;
+
+    use super::*;
+
+    fn request(uri: &str, header: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(value) = header {
+            builder = builder.header(EXTENSIONS_HEADER, value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn extensions_negotiation() {
+        assert!(!extensions_requested(&request("http://localhost/", None)));
+        assert!(extensions_requested(&request(
+            "http://localhost/",
+            Some("true")
+        )));
+        assert!(!extensions_requested(&request(
+            "http://localhost/",
+            Some("false")
+        )));
+        assert!(extensions_requested(&request(
+            "http://localhost/?zks_extensions=1",
+            None
+        )));
+        assert!(extensions_requested(&request(
+            "http://localhost/?key=abc&zks_extensions",
+            None
+        )));
+        assert!(!extensions_requested(&request(
+            "http://localhost/?zks_extensions=0",
+            None
+        )));
+        assert!(!extensions_requested(&request(
+            "http://localhost/?other_zks_extensions=1",
+            None
+        )));
+
+        assert!(!extensions_enabled());
+        EXTENSIONS_ENABLED.sync_scope(true, || assert!(extensions_enabled()));
+        assert!(!extensions_enabled());
+    }
+
+    #[tokio::test]
+    async fn extensions_layer() {
+        let service = tower::service_fn(|_: Request<Body>| async {
+            Ok::<_, std::convert::Infallible>(extensions_enabled())
+        });
+        let mut service = ApiExtensionsLayer.layer(service);
+
+        let enabled = service
+            .call(request("http://localhost/?zks_extensions=true", None))
+            .await
+            .unwrap();
+        assert!(enabled);
+        let enabled = service
+            .call(request("http://localhost/", None))
+            .await
+            .unwrap();
+        assert!(!enabled);
+    }
+}
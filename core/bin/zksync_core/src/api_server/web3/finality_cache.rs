@@ -0,0 +1,43 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use zksync_dal::{ConnectionPool, SqlxError};
+use zksync_types::api::L1BatchFinalityWatermarks;
+
+/// Cache of the L1 batch finality watermarks, so that finality of the transactions can be computed
+/// from the number of their L1 batch without joining the Ethereum transactions for each request.
+/// The watermarks are reloaded from the database once they are older than the configured TTL.
+#[derive(Debug, Clone)]
+pub struct L1BatchFinalityCache {
+    pool: ConnectionPool,
+    ttl: Duration,
+    cache: Arc<RwLock<Option<(L1BatchFinalityWatermarks, Instant)>>>,
+}
+
+impl L1BatchFinalityCache {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(1);
+
+    pub fn new(pool: ConnectionPool, ttl: Duration) -> Self {
+        Self {
+            pool,
+            ttl,
+            cache: Arc::default(),
+        }
+    }
+
+    pub fn get(&self) -> Result<L1BatchFinalityWatermarks, SqlxError> {
+        if let Some((watermarks, loaded_at)) = *self.cache.read().unwrap() {
+            if loaded_at.elapsed() < self.ttl {
+                return Ok(watermarks);
+            }
+        }
+
+        let watermarks = self
+            .pool
+            .access_storage_blocking()
+            .blocks_web3_dal()
+            .get_l1_batch_finality_watermarks()?;
+        *self.cache.write().unwrap() = Some((watermarks, Instant::now()));
+        Ok(watermarks)
+    }
+}
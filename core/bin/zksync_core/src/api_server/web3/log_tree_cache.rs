@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use zksync_dal::{SqlxError, StorageProcessor};
+use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_object_store::gcs_utils::l2_to_l1_log_tree_blob_url;
+use zksync_object_store::object_store::{
+    create_object_store_from_env, DynamicObjectStore, ObjectStoreError,
+    L2_TO_L1_LOG_TREES_BUCKET_PATH,
+};
+use zksync_types::{
+    api::L2ToL1LogProof, commitment::CommitmentSerializable, l2_to_l1_log::L2ToL1Log, L1BatchNumber,
+};
+
+/// L2 to L1 logs of an L1 batch together with the Merkle tree built over them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct L2ToL1LogTree {
+    pub logs: Vec<L2ToL1Log>,
+    tree: MiniMerkleTree,
+}
+
+impl L2ToL1LogTree {
+    pub fn new(logs: Vec<L2ToL1Log>) -> Self {
+        let values: Vec<Vec<u8>> = logs.iter().cloned().map(|log| log.to_bytes()).collect();
+        let tree = MiniMerkleTree::new(
+            values,
+            L2ToL1Log::SERIALIZED_SIZE,
+            L2ToL1Log::limit_per_block(),
+        );
+        Self { logs, tree }
+    }
+
+    /// Returns the proof of inclusion of the log with the given index in the L1 batch.
+    pub fn proof(&self, index: usize) -> L2ToL1LogProof {
+        L2ToL1LogProof {
+            proof: self.tree.merkle_path(index),
+            root: self.tree.root_hash(),
+            id: index as u32,
+        }
+    }
+}
+
+/// Cache of the L2 to L1 log trees keyed by the L1 batch number, optionally backed by the object store.
+///
+/// Cached trees are only used if their root matches the root saved with the L1 batch metadata,
+/// so the trees of the reverted batches are never served. Trees of the batches without metadata
+/// aren't cached.
+#[derive(Debug, Clone)]
+pub struct L2ToL1LogTreeCache {
+    capacity: usize,
+    trees: Arc<Mutex<BTreeMap<L1BatchNumber, Arc<L2ToL1LogTree>>>>,
+    object_store: Option<Arc<Mutex<DynamicObjectStore>>>,
+}
+
+impl L2ToL1LogTreeCache {
+    pub fn new(capacity: usize, use_object_store: bool) -> Self {
+        Self {
+            capacity,
+            trees: Arc::default(),
+            object_store: use_object_store
+                .then(|| Arc::new(Mutex::new(create_object_store_from_env()))),
+        }
+    }
+
+    pub fn get(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Arc<L2ToL1LogTree>, SqlxError> {
+        let expected_root = storage
+            .blocks_web3_dal()
+            .get_l2_to_l1_logs_root(l1_batch_number)?;
+
+        if let Some(expected_root) = expected_root {
+            let cached = self.trees.lock().unwrap().get(&l1_batch_number).cloned();
+            if let Some(tree) = cached {
+                if tree.tree.root_hash() == expected_root {
+                    metrics::counter!("api.web3.l2_to_l1_log_tree_cache", 1, "result" => "hit");
+                    return Ok(tree);
+                }
+            }
+            if let Some(tree) = self.load_tree(l1_batch_number) {
+                if tree.tree.root_hash() == expected_root {
+                    metrics::counter!(
+                        "api.web3.l2_to_l1_log_tree_cache",
+                        1,
+                        "result" => "object_store_hit"
+                    );
+                    return Ok(self.insert(l1_batch_number, tree));
+                }
+            }
+        }
+        metrics::counter!("api.web3.l2_to_l1_log_tree_cache", 1, "result" => "miss");
+
+        let logs = storage
+            .blocks_web3_dal()
+            .get_l2_to_l1_logs(l1_batch_number)?;
+        let tree = L2ToL1LogTree::new(logs);
+        if expected_root == Some(tree.tree.root_hash()) {
+            self.store_tree(l1_batch_number, &tree);
+            Ok(self.insert(l1_batch_number, tree))
+        } else {
+            Ok(Arc::new(tree))
+        }
+    }
+
+    fn insert(&self, l1_batch_number: L1BatchNumber, tree: L2ToL1LogTree) -> Arc<L2ToL1LogTree> {
+        let tree = Arc::new(tree);
+        let mut trees = self.trees.lock().unwrap();
+        trees.insert(l1_batch_number, tree.clone());
+        // The oldest batches are evicted first.
+        while trees.len() > self.capacity {
+            let oldest = *trees.keys().next().unwrap();
+            trees.remove(&oldest);
+        }
+        tree
+    }
+
+    fn load_tree(&self, l1_batch_number: L1BatchNumber) -> Option<L2ToL1LogTree> {
+        let object_store = self.object_store.as_ref()?;
+        let result = object_store.lock().unwrap().get(
+            L2_TO_L1_LOG_TREES_BUCKET_PATH,
+            l2_to_l1_log_tree_blob_url(l1_batch_number),
+        );
+        let result = result.and_then(|serialized| {
+            bincode::deserialize(&serialized)
+                .map_err(|err| ObjectStoreError::Corrupted(err.to_string()))
+        });
+        match result {
+            Ok(tree) => Some(tree),
+            Err(ObjectStoreError::KeyNotFound(_)) => None,
+            Err(err) => {
+                vlog::warn!(
+                    "Failed to load L2 to L1 log tree of L1 batch {} from the object store: {}",
+                    l1_batch_number,
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    fn store_tree(&self, l1_batch_number: L1BatchNumber, tree: &L2ToL1LogTree) {
+        let object_store = match &self.object_store {
+            Some(object_store) => object_store,
+            None => return,
+        };
+        let serialized = bincode::serialize(tree).expect("cannot serialize L2 to L1 log tree");
+        let result = object_store.lock().unwrap().put(
+            L2_TO_L1_LOG_TREES_BUCKET_PATH,
+            l2_to_l1_log_tree_blob_url(l1_batch_number),
+            serialized,
+        );
+        if let Err(err) = result {
+            vlog::warn!(
+                "Failed to save L2 to L1 log tree of L1 batch {} to the object store: {}",
+                l1_batch_number,
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use tempfile::TempDir;
+
+use zksync_merkle_tree::{TreeEntryProof, TreeKey, ZkSyncTree};
+use zksync_storage::{db::Database, RocksDB};
+use zksync_types::{L1BatchNumber, H256};
+
+/// Read path of the Merkle tree maintained by the metadata calculator, used to build the storage proofs.
+///
+/// The tree is opened once as a secondary instance and is caught up with the instance used by the metadata
+/// calculator on every read, so that the reads observe the latest state of the tree without reopening it.
+/// If the tree keeps the history of its recent versions (see `DBConfig::merkle_tree_history_enabled`), the proofs
+/// can be built for any of the recent L1 batches processed by the tree; otherwise, only for the latest one.
+#[derive(Debug, Clone)]
+pub struct MerkleTreeReader {
+    path: String,
+    /// Secondary instance of the tree, opened on the first read and shared by the concurrent reads.
+    secondary: Arc<Mutex<Option<Arc<SecondaryTree>>>>,
+}
+
+#[derive(Debug)]
+struct SecondaryTree {
+    /// The instance is declared first, so that it's dropped before the directory.
+    db: RocksDB,
+    /// Directory for the logs of the secondary instance.
+    _dir: TempDir,
+}
+
+impl MerkleTreeReader {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            secondary: Arc::default(),
+        }
+    }
+
+    /// Returns the proofs of the values of the given keys after the L1 batch with the given root hash.
+    /// Returns `None` if the tree isn't available or can't be read at the L1 batch.
+    pub fn get_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        root_hash: H256,
+        keys: &[TreeKey],
+    ) -> Option<Vec<TreeEntryProof>> {
+        let secondary = self.secondary()?;
+        if let Err(err) = secondary.db.catch_up_with_primary() {
+            vlog::warn!(
+                "Failed to catch up with Merkle tree at {}: {}",
+                self.path,
+                err
+            );
+            // The tree may have been replaced, e.g. restored from a backup; it's reopened on the next read.
+            let mut current = self.secondary.lock().unwrap();
+            if current
+                .as_ref()
+                .map_or(false, |current| Arc::ptr_eq(current, &secondary))
+            {
+                *current = None;
+            }
+            return None;
+        }
+
+        // The block number of the tree is the number of the next L1 batch to be processed.
+        let block_number = l1_batch_number.0 + 1;
+        let (tree_root_hash, proofs) =
+            ZkSyncTree::read_proofs_at(&secondary.db, block_number, keys)?;
+        (tree_root_hash == root_hash.as_bytes()).then_some(proofs)
+    }
+
+    fn secondary(&self) -> Option<Arc<SecondaryTree>> {
+        if let Some(secondary) = self.secondary.lock().unwrap().as_ref() {
+            return Some(secondary.clone());
+        }
+        if !Path::new(&self.path).join("CURRENT").exists() {
+            return None;
+        }
+
+        // The instance is opened without holding the lock, so that it doesn't block the concurrent reads.
+        let dir = TempDir::new()
+            .map_err(|err| {
+                vlog::warn!(
+                    "Failed to create directory for secondary Merkle tree: {}",
+                    err
+                );
+            })
+            .ok()?;
+        let db = RocksDB::new_secondary(Database::MerkleTree, &self.path, dir.path())
+            .map_err(|err| {
+                vlog::warn!("Failed to open Merkle tree at {}: {}", self.path, err);
+            })
+            .ok()?;
+        metrics::increment_counter!("api.web3.merkle_tree_reader.opened");
+
+        let mut secondary = self.secondary.lock().unwrap();
+        let secondary = secondary.get_or_insert_with(|| Arc::new(SecondaryTree { db, _dir: dir }));
+        Some(secondary.clone())
+    }
+}
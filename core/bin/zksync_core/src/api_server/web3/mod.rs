@@ -4,42 +4,83 @@ use std::time::Duration;
 // External uses
 use futures::channel::oneshot;
 use futures::FutureExt;
-use jsonrpc_core::IoHandler;
+use jsonrpc_core::MetaIoHandler;
+use jsonrpc_http_server::hyper;
 use jsonrpc_pubsub::PubSubHandler;
 use once_cell::{self, sync::Lazy};
+use serde_json::value::RawValue;
 use tokio::sync::watch;
-use zksync_dal::ConnectionPool;
+use zksync_dal::{feature_flags_dal::FeatureFlagsReader, ConnectionPool};
 
 // Workspace uses
-use zksync_config::ZkSyncConfig;
+use zksync_config::{configs::api::NodePersonality, ZkSyncConfig};
 use zksync_eth_client::clients::http_client::EthereumClient;
 use zksync_eth_signer::{EthereumSigner, PrivateKeySigner};
-use zksync_types::H256;
+use zksync_types::{MiniblockNumber, H256};
 use zksync_web3_decl::{
-    jsonrpsee::{server::ServerBuilder, RpcModule},
-    namespaces::{EthNamespaceServer, NetNamespaceServer, Web3NamespaceServer, ZksNamespaceServer},
+    jsonrpsee::{
+        core::{server::rpc_module::Methods, traits::ToRpcParams},
+        server::ServerBuilder,
+        RpcModule,
+    },
+    namespaces::{
+        AdminNamespaceServer, DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer,
+        NetNamespaceServer, TxPoolNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+    },
 };
 
 use crate::gas_adjuster::GasAdjuster;
+use crate::state_keeper::sealed_miniblocks::SealedMiniblocksSender;
 
 // Local uses
 use super::tx_sender::TxSender;
+use abi_cache::VerifiedAbiCache;
+use audit_log::{ApiAuditLayer, ApiAuditLogMiddleware, RequestMetadata};
 use backend_jsonrpc::{
     namespaces::{
-        eth::EthNamespaceT, net::NetNamespaceT, web3::Web3NamespaceT, zks::ZksNamespaceT,
+        admin::AdminNamespaceT, debug::DebugNamespaceT, en::EnNamespaceT, eth::EthNamespaceT,
+        net::NetNamespaceT, txpool::TxPoolNamespaceT, web3::Web3NamespaceT, zks::ZksNamespaceT,
     },
     pub_sub::Web3PubSub,
 };
-use namespaces::{EthNamespace, EthSubscribe, NetNamespace, Web3Namespace, ZksNamespace};
-use pubsub_notifier::{notify_blocks, notify_logs, notify_txs};
+use extensions::{ApiExtensionsLayer, ApiExtensionsMiddleware};
+use finality_cache::L1BatchFinalityCache;
+use log_tree_cache::L2ToL1LogTreeCache;
+use merkle_tree_reader::MerkleTreeReader;
+use namespaces::{
+    AdminNamespace, DebugNamespace, EnNamespace, EthNamespace, EthSubscribe, NetNamespace,
+    TxPoolNamespace, Web3Namespace, ZksNamespace,
+};
+use pubsub_notifier::{notify_account_events, notify_blocks, notify_logs, notify_txs};
+use request_deadline::RequestDeadlines;
+use rocksdb_watermarks_reader::RocksdbWatermarksReader;
 use state::{Filters, RpcState};
+use usage_metering::{ApiKeyLayer, ApiUsageMeteringMiddleware};
 use zksync_contracts::{ESTIMATE_FEE_BLOCK_CODE, PLAYGROUND_BLOCK_BOOTLOADER_CODE};
 
+pub mod abi_cache;
+pub mod audit_log;
 pub mod backend_jsonrpc;
 pub mod backend_jsonrpsee;
+pub mod conformance;
+pub mod extensions;
+pub mod finality_cache;
+pub mod log_tree_cache;
+pub mod merkle_tree_reader;
 pub mod namespaces;
+pub mod pools;
 mod pubsub_notifier;
+pub mod request_deadline;
+pub mod rocksdb_watermarks_reader;
 pub mod state;
+pub mod usage_metering;
+
+/// Middlewares applied to the calls of the HTTP and WS APIs.
+pub type ApiMiddleware = (
+    ApiAuditLogMiddleware,
+    ApiUsageMeteringMiddleware,
+    ApiExtensionsMiddleware,
+);
 
 pub fn get_config() -> &'static ZkSyncConfig {
     static ZKSYNC_CONFIG: Lazy<ZkSyncConfig> = Lazy::new(ZkSyncConfig::from_env);
@@ -57,26 +98,21 @@ impl RpcState {
         gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
     ) -> Self {
         let config = get_config();
-        let mut storage = replica_connection_pool.access_storage_blocking();
-
-        let base_system_contracts = storage.storage_dal().get_base_system_contracts(
-            config.chain.state_keeper.bootloader_hash,
-            config.chain.state_keeper.default_aa_hash,
+        let earliest_miniblock = replica_connection_pool
+            .access_storage_blocking()
+            .blocks_web3_dal()
+            .get_earliest_miniblock_number()
+            .unwrap();
+        validate_node_personality(
+            config.api.web3_json_rpc.node_personality(),
+            earliest_miniblock,
         );
 
-        let mut playground_base_system_contracts = base_system_contracts.clone();
-        let mut estimate_fee_base_system_contracts = base_system_contracts;
-        playground_base_system_contracts.bootloader = PLAYGROUND_BLOCK_BOOTLOADER_CODE.clone();
-        estimate_fee_base_system_contracts.bootloader = ESTIMATE_FEE_BLOCK_CODE.clone();
-
-        drop(storage);
-        let tx_sender = TxSender::new(
+        let tx_sender = build_tx_sender(
             config,
             master_connection_pool,
             replica_connection_pool.clone(),
             gas_adjuster,
-            playground_base_system_contracts,
-            estimate_fee_base_system_contracts,
         );
 
         let accounts = if cfg!(feature = "openzeppelin_tests") {
@@ -94,7 +130,32 @@ impl RpcState {
         };
 
         RpcState {
-            installed_filters: Arc::new(RwLock::new(Filters::new(filters_limit))),
+            installed_filters: Arc::new(RwLock::new(Filters::new(
+                filters_limit,
+                config.api.web3_json_rpc.filters_ttl(),
+            ))),
+            feature_flags: FeatureFlagsReader::new(
+                replica_connection_pool.clone(),
+                FeatureFlagsReader::DEFAULT_TTL,
+            ),
+            abi_cache: VerifiedAbiCache::new(
+                replica_connection_pool.clone(),
+                VerifiedAbiCache::DEFAULT_TTL,
+            ),
+            finality_cache: L1BatchFinalityCache::new(
+                replica_connection_pool.clone(),
+                L1BatchFinalityCache::DEFAULT_TTL,
+            ),
+            log_tree_cache: L2ToL1LogTreeCache::new(
+                config.api.web3_json_rpc.l2_to_l1_log_trees_cache_size(),
+                config
+                    .api
+                    .web3_json_rpc
+                    .l2_to_l1_log_trees_in_object_store(),
+            ),
+            merkle_tree_reader: MerkleTreeReader::new(config.db.path()),
+            rocksdb_watermarks_reader: RocksdbWatermarksReader::new(&config.db),
+            request_deadlines: RequestDeadlines::new(&config.api.web3_json_rpc),
             connection_pool: replica_connection_pool,
             tx_sender,
             req_entities_limit,
@@ -106,35 +167,175 @@ impl RpcState {
     }
 }
 
+/// Creates the transaction sender with the base system contracts of the API sandbox.
+pub fn build_tx_sender(
+    config: &ZkSyncConfig,
+    master_connection_pool: ConnectionPool,
+    replica_connection_pool: ConnectionPool,
+    gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
+) -> TxSender {
+    let base_system_contracts = replica_connection_pool
+        .access_storage_blocking()
+        .storage_dal()
+        .get_base_system_contracts(
+            config.chain.state_keeper.bootloader_hash,
+            config.chain.state_keeper.default_aa_hash,
+        );
+
+    let mut playground_base_system_contracts = base_system_contracts.clone();
+    let mut estimate_fee_base_system_contracts = base_system_contracts;
+    playground_base_system_contracts.bootloader = PLAYGROUND_BLOCK_BOOTLOADER_CODE.clone();
+    estimate_fee_base_system_contracts.bootloader = ESTIMATE_FEE_BLOCK_CODE.clone();
+
+    TxSender::new(
+        config,
+        master_connection_pool,
+        replica_connection_pool,
+        gas_adjuster,
+        playground_base_system_contracts,
+        estimate_fee_base_system_contracts,
+    )
+}
+
+/// Methods that are only meaningful on the nodes keeping the history (traces, logs, receipts and state diffs
+/// of past blocks, account history). The minimal API nodes don't serve them at all, so that the clients
+/// get "method not found" instead of the results silently missing the data the node doesn't have.
+const HISTORICAL_METHODS: &[&str] = &[
+    "debug_traceTransaction",
+    "debug_traceBlockByNumber",
+    "eth_getLogs",
+    "eth_getFilterLogs",
+    "eth_getBlockReceipts",
+    "zks_getBlockReceipts",
+    "zks_getLogsPaginated",
+    "zks_getTransactionTrace",
+    "zks_getTransactionsByAddress",
+    "zks_getTransfers",
+    "zks_getStateDiff",
+    "zks_getL1BatchStateDiff",
+    "zks_getAccountStateAtTimestamp",
+    "zks_getBlockByTimestamp",
+    "zks_getBlockDependencyGraph",
+    "zks_getRawBlockTransactions",
+    "zks_getL1BatchL1Events",
+];
+
+fn is_method_served(personality: NodePersonality, method: &str) -> bool {
+    personality.serves_historical_queries() || !HISTORICAL_METHODS.contains(&method)
+}
+
+/// Leaves only the methods served by the node with the given personality. Used for the `jsonrpc` backend.
+fn served_methods<P>(
+    personality: NodePersonality,
+    methods: impl IntoIterator<Item = (String, P)>,
+) -> impl Iterator<Item = (String, P)> {
+    methods
+        .into_iter()
+        .filter(move |(name, _)| is_method_served(personality, name))
+}
+
+/// Leaves only the methods served by the node with the given personality. Used for the `jsonrpsee` backend:
+/// `RpcModule` doesn't allow to remove methods, so the served methods of a namespace that has unserved ones
+/// are registered anew in a separate module, forwarding the calls to the original one.
+fn served_module<T: Send + Sync + 'static>(
+    personality: NodePersonality,
+    module: RpcModule<T>,
+) -> RpcModule<()> {
+    let methods = Methods::from(module);
+    let mut served = RpcModule::new(());
+    if methods
+        .method_names()
+        .all(|name| is_method_served(personality, name))
+    {
+        served.merge(methods).expect("Can't merge served methods");
+        return served;
+    }
+
+    let mut forwarding = RpcModule::new(methods.clone());
+    for name in methods
+        .method_names()
+        .filter(|name| is_method_served(personality, name))
+    {
+        forwarding
+            .register_async_method(name, move |params, methods| async move {
+                let params = ForwardedParams(params.as_str().map(str::to_owned));
+                methods.call::<_, serde_json::Value>(name, params).await
+            })
+            .expect("Can't register served method");
+    }
+    served
+        .merge(forwarding)
+        .expect("Can't merge served methods");
+    served
+}
+
+/// Raw params of the call forwarded by [`served_module`].
+struct ForwardedParams(Option<String>);
+
+impl ToRpcParams for ForwardedParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, jsonrpsee::core::Error> {
+        self.0
+            .map(RawValue::from_string)
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+/// Checks that the data in the database is enough for the node to serve the queries its personality implies.
+fn validate_node_personality(
+    personality: NodePersonality,
+    earliest_miniblock: Option<MiniblockNumber>,
+) {
+    if personality.requires_full_history() && earliest_miniblock != Some(MiniblockNumber(0)) {
+        panic!(
+            "Node is configured as {:?}, but the database doesn't contain the full history: earliest miniblock is {:?}",
+            personality, earliest_miniblock
+        );
+    }
+    vlog::info!(
+        "Starting API as {:?} node, earliest available miniblock: {:?}",
+        personality,
+        earliest_miniblock
+    );
+}
+
 pub fn start_http_rpc_server_old(
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     _stop_receiver: watch::Receiver<bool>,
     gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
+    middleware: ApiMiddleware,
 ) -> tokio::task::JoinHandle<()> {
     let io_handler = build_http_io_handler(
         master_connection_pool,
         replica_connection_pool,
         config,
         gas_adjuster,
+        middleware,
     );
     let addr = config.api.web3_json_rpc.http_bind_addr();
-    let threads_per_server = config.api.web3_json_rpc.threads_per_server as usize;
+    let threads = config.api.web3_json_rpc.http_threads();
+    let trusted_proxy_hops = config.api.web3_json_rpc.trusted_proxy_hops();
 
     let (sender, recv) = oneshot::channel::<()>();
     std::thread::spawn(move || {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
-            .worker_threads(threads_per_server)
+            .worker_threads(threads)
             .build()
             .unwrap();
 
-        let server = jsonrpc_http_server::ServerBuilder::new(io_handler)
-            .threads(1)
-            .event_loop_executor(runtime.handle().clone())
-            .start_http(&addr)
-            .unwrap();
+        let server = jsonrpc_http_server::ServerBuilder::with_meta_extractor(
+            io_handler,
+            move |request: &hyper::Request<hyper::Body>| {
+                RequestMetadata::from_http_request(request, trusted_proxy_hops)
+            },
+        )
+        .threads(1)
+        .event_loop_executor(runtime.handle().clone())
+        .start_http(&addr)
+        .unwrap();
 
         server.wait();
         let _ = sender;
@@ -147,13 +348,24 @@ fn start_notifying_active_subs(
     pub_sub: EthSubscribe,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
+    resume_window: u32,
+    sealed_miniblocks: Option<SealedMiniblocksSender>,
     stop_receiver: watch::Receiver<bool>,
 ) -> Vec<tokio::task::JoinHandle<()>> {
+    // Subscribe before the notifiers load the last miniblock number, so that no miniblock is missed in between.
+    let block_stream = sealed_miniblocks
+        .as_ref()
+        .map(SealedMiniblocksSender::subscribe);
+    let log_stream = sealed_miniblocks
+        .as_ref()
+        .map(SealedMiniblocksSender::subscribe);
     vec![
         tokio::spawn(notify_blocks(
             pub_sub.active_block_subs,
             connection_pool.clone(),
             polling_interval,
+            resume_window,
+            block_stream,
             stop_receiver.clone(),
         )),
         tokio::spawn(notify_txs(
@@ -164,6 +376,14 @@ fn start_notifying_active_subs(
         )),
         tokio::spawn(notify_logs(
             pub_sub.active_log_subs,
+            connection_pool.clone(),
+            polling_interval,
+            resume_window,
+            log_stream,
+            stop_receiver.clone(),
+        )),
+        tokio::spawn(notify_account_events(
+            pub_sub.active_account_subs,
             connection_pool,
             polling_interval,
             stop_receiver,
@@ -174,15 +394,20 @@ fn start_notifying_active_subs(
 pub fn start_ws_rpc_server_old(
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
+    pubsub_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     stop_receiver: watch::Receiver<bool>,
     gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
+    middleware: ApiMiddleware,
+    sealed_miniblocks: Option<SealedMiniblocksSender>,
 ) -> Vec<tokio::task::JoinHandle<()>> {
     let pub_sub = EthSubscribe::default();
     let mut notify_handles = start_notifying_active_subs(
         pub_sub.clone(),
-        replica_connection_pool.clone(),
+        pubsub_connection_pool,
         config.api.web3_json_rpc.pubsub_interval(),
+        config.api.web3_json_rpc.pubsub_resume_window(),
+        sealed_miniblocks,
         stop_receiver.clone(),
     );
 
@@ -194,8 +419,14 @@ pub fn start_ws_rpc_server_old(
         config,
         gas_adjuster.clone(),
         pub_sub,
+        middleware,
     );
 
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .worker_threads(config.api.web3_json_rpc.ws_threads())
+        .build()
+        .unwrap();
     let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
         io,
         |context: &jsonrpc_ws_server::RequestContext| {
@@ -203,12 +434,15 @@ pub fn start_ws_rpc_server_old(
         },
     )
     .max_connections(config.api.web3_json_rpc.subscriptions_limit())
+    .event_loop_executor(runtime.handle().clone())
     .start(&addr)
     .unwrap();
     let close_handler = server.close_handle();
 
     std::thread::spawn(move || {
         server.wait().unwrap();
+        // The runtime is dropped outside of the async context once the server is stopped.
+        drop(runtime);
         let _ = sender;
     });
     let mut thread_stop_receiver = stop_receiver.clone();
@@ -230,6 +464,8 @@ pub fn start_http_rpc_server(
     replica_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
+    audit_log: ApiAuditLogMiddleware,
+    usage_metering: ApiUsageMeteringMiddleware,
 ) -> tokio::task::JoinHandle<()> {
     let rpc = build_rpc_module(
         master_connection_pool,
@@ -238,14 +474,15 @@ pub fn start_http_rpc_server(
         gas_adjuster,
     );
     let addr = config.api.web3_json_rpc.http_bind_addr();
-    let threads_per_server = config.api.web3_json_rpc.threads_per_server as usize;
+    let threads = config.api.web3_json_rpc.http_threads();
+    let audit_layer = ApiAuditLayer::new(&config.api.web3_json_rpc);
 
     // Start the server in a separate tokio runtime from a dedicated thread.
     let (sender, recv) = oneshot::channel::<()>();
     std::thread::spawn(move || {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
-            .worker_threads(threads_per_server)
+            .worker_threads(threads)
             .build()
             .unwrap();
 
@@ -253,6 +490,16 @@ pub fn start_http_rpc_server(
             let server = ServerBuilder::default()
                 .http_only()
                 .max_connections(5000)
+                .set_middleware(
+                    tower::ServiceBuilder::new()
+                        .layer(ApiExtensionsLayer)
+                        .layer(ApiKeyLayer)
+                        .layer(audit_layer),
+                )
+                .set_logger((
+                    usage_metering.jsonrpsee_logger(),
+                    audit_log.jsonrpsee_logger(),
+                ))
                 .build(addr)
                 .await
                 .expect("Can't start the HTTP JSON RPC server");
@@ -283,14 +530,14 @@ pub fn start_ws_rpc_server(
         gas_adjuster,
     );
     let addr = config.api.web3_json_rpc.ws_bind_addr();
-    let threads_per_server = config.api.web3_json_rpc.threads_per_server as usize;
+    let threads = config.api.web3_json_rpc.ws_threads();
 
     // Start the server in a separate tokio runtime from a dedicated thread.
     let (sender, recv) = oneshot::channel::<()>();
     std::thread::spawn(move || {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
-            .worker_threads(threads_per_server)
+            .worker_threads(threads)
             .build()
             .unwrap();
 
@@ -339,16 +586,37 @@ fn build_http_io_handler(
     replica_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
-) -> IoHandler {
+    middleware: ApiMiddleware,
+) -> MetaIoHandler<RequestMetadata, ApiMiddleware> {
     let rpc_state = build_rpc_state(
         master_connection_pool,
         replica_connection_pool,
         config,
         gas_adjuster,
     );
-    let mut io = IoHandler::new();
-    io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
-    io.extend_with(ZksNamespace::new(rpc_state).to_delegate());
+    let mut io = MetaIoHandler::with_middleware(middleware);
+    let personality = config.api.web3_json_rpc.node_personality();
+    io.extend_with(served_methods(
+        personality,
+        EthNamespace::new(rpc_state.clone()).to_delegate(),
+    ));
+    io.extend_with(served_methods(
+        personality,
+        ZksNamespace::new(rpc_state.clone()).to_delegate(),
+    ));
+    io.extend_with(EnNamespace::new(rpc_state.clone()).to_delegate());
+    if config.api.web3_json_rpc.admin_namespace_enabled() {
+        io.extend_with(AdminNamespace::new(rpc_state.clone()).to_delegate());
+    }
+    if config.api.web3_json_rpc.txpool_namespace_enabled() {
+        io.extend_with(TxPoolNamespace::new(rpc_state.clone()).to_delegate());
+    }
+    if config.api.web3_json_rpc.debug_namespace_enabled() {
+        io.extend_with(served_methods(
+            personality,
+            DebugNamespace::new(rpc_state).to_delegate(),
+        ));
+    }
     io.extend_with(Web3Namespace.to_delegate());
     io.extend_with(NetNamespace.to_delegate());
 
@@ -361,17 +629,38 @@ fn build_pubsub_io_handler(
     config: &ZkSyncConfig,
     gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
     pub_sub: EthSubscribe,
-) -> PubSubHandler<Arc<jsonrpc_pubsub::Session>> {
+    middleware: ApiMiddleware,
+) -> PubSubHandler<Arc<jsonrpc_pubsub::Session>, ApiMiddleware> {
     let rpc_state = build_rpc_state(
         master_connection_pool,
         replica_connection_pool,
         config,
         gas_adjuster,
     );
-    let mut io = PubSubHandler::default();
+    let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(middleware));
     io.extend_with(pub_sub.to_delegate());
-    io.extend_with(EthNamespace::new(rpc_state.clone()).to_delegate());
-    io.extend_with(ZksNamespace::new(rpc_state).to_delegate());
+    let personality = config.api.web3_json_rpc.node_personality();
+    io.extend_with(served_methods(
+        personality,
+        EthNamespace::new(rpc_state.clone()).to_delegate(),
+    ));
+    io.extend_with(served_methods(
+        personality,
+        ZksNamespace::new(rpc_state.clone()).to_delegate(),
+    ));
+    io.extend_with(EnNamespace::new(rpc_state.clone()).to_delegate());
+    if config.api.web3_json_rpc.admin_namespace_enabled() {
+        io.extend_with(AdminNamespace::new(rpc_state.clone()).to_delegate());
+    }
+    if config.api.web3_json_rpc.txpool_namespace_enabled() {
+        io.extend_with(TxPoolNamespace::new(rpc_state.clone()).to_delegate());
+    }
+    if config.api.web3_json_rpc.debug_namespace_enabled() {
+        io.extend_with(served_methods(
+            personality,
+            DebugNamespace::new(rpc_state).to_delegate(),
+        ));
+    }
     io.extend_with(Web3Namespace.to_delegate());
     io.extend_with(NetNamespace.to_delegate());
 
@@ -383,7 +672,7 @@ fn build_rpc_module(
     replica_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     gas_adjuster: Arc<GasAdjuster<EthereumClient>>,
-) -> RpcModule<EthNamespace> {
+) -> RpcModule<()> {
     let rpc_app = build_rpc_state(
         master_connection_pool,
         replica_connection_pool,
@@ -395,15 +684,110 @@ fn build_rpc_module(
     let eth = EthNamespace::new(rpc_app.clone());
     let net = NetNamespace;
     let web3 = Web3Namespace;
-    let zks = ZksNamespace::new(rpc_app);
-
-    // Collect all the methods into a single RPC module.
-    let mut rpc: RpcModule<_> = eth.into_rpc();
+    let zks = ZksNamespace::new(rpc_app.clone());
+    let en = EnNamespace::new(rpc_app.clone());
+    let admin = config
+        .api
+        .web3_json_rpc
+        .admin_namespace_enabled()
+        .then(|| AdminNamespace::new(rpc_app.clone()));
+    let txpool = config
+        .api
+        .web3_json_rpc
+        .txpool_namespace_enabled()
+        .then(|| TxPoolNamespace::new(rpc_app.clone()));
+    let debug = config
+        .api
+        .web3_json_rpc
+        .debug_namespace_enabled()
+        .then(|| DebugNamespace::new(rpc_app));
+
+    // Collect all the methods served by the node into a single RPC module.
+    let personality = config.api.web3_json_rpc.node_personality();
+    let mut rpc = served_module(personality, eth.into_rpc());
     rpc.merge(net.into_rpc())
         .expect("Can't merge net namespace");
     rpc.merge(web3.into_rpc())
         .expect("Can't merge web3 namespace");
-    rpc.merge(zks.into_rpc())
+    rpc.merge(served_module(personality, zks.into_rpc()))
         .expect("Can't merge zks namespace");
+    rpc.merge(en.into_rpc()).expect("Can't merge en namespace");
+    if let Some(debug) = debug {
+        rpc.merge(served_module(personality, debug.into_rpc()))
+            .expect("Can't merge debug namespace");
+    }
+    if let Some(admin) = admin {
+        rpc.merge(admin.into_rpc())
+            .expect("Can't merge admin namespace");
+    }
+    if let Some(txpool) = txpool {
+        rpc.merge(txpool.into_rpc())
+            .expect("Can't merge txpool namespace");
+    }
     rpc
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{IoDelegate, IoHandler, Value};
+
+    const GET_LOGS_REQUEST: &str = r#"{"jsonrpc":"2.0","method":"eth_getLogs","params":[],"id":1}"#;
+    const BLOCK_NUMBER_REQUEST: &str =
+        r#"{"jsonrpc":"2.0","method":"eth_blockNumber","params":[],"id":1}"#;
+
+    fn jsonrpc_handler(personality: NodePersonality) -> IoHandler {
+        let mut delegate = IoDelegate::<(), ()>::new(Arc::new(()));
+        for method in ["eth_getLogs", "eth_blockNumber"] {
+            delegate.add_method(method, |_, _| futures::future::ready(Ok(Value::Null)));
+        }
+        let mut io = IoHandler::new();
+        io.extend_with(served_methods(personality, delegate));
+        io
+    }
+
+    fn jsonrpsee_module(personality: NodePersonality) -> RpcModule<()> {
+        let mut rpc = RpcModule::new(());
+        for method in ["eth_getLogs", "eth_blockNumber"] {
+            rpc.register_method(method, |_, _| Ok(())).unwrap();
+        }
+        served_module(personality, rpc)
+    }
+
+    fn error_code(response: &str) -> Option<i64> {
+        let response: serde_json::Value = serde_json::from_str(response).unwrap();
+        response["error"]["code"].as_i64()
+    }
+
+    #[test]
+    fn minimal_api_node_doesnt_serve_historical_methods_via_jsonrpc() {
+        let io = jsonrpc_handler(NodePersonality::MinimalApi);
+        let response = io.handle_request_sync(GET_LOGS_REQUEST).unwrap();
+        assert_eq!(
+            error_code(&response),
+            Some(conformance::codes::METHOD_NOT_FOUND)
+        );
+        let response = io.handle_request_sync(BLOCK_NUMBER_REQUEST).unwrap();
+        assert_eq!(error_code(&response), None);
+
+        let io = jsonrpc_handler(NodePersonality::FullWithPruning);
+        let response = io.handle_request_sync(GET_LOGS_REQUEST).unwrap();
+        assert_eq!(error_code(&response), None);
+    }
+
+    #[tokio::test]
+    async fn minimal_api_node_doesnt_serve_historical_methods_via_jsonrpsee() {
+        let rpc = jsonrpsee_module(NodePersonality::MinimalApi);
+        let (response, _) = rpc.raw_json_request(GET_LOGS_REQUEST).await.unwrap();
+        assert_eq!(
+            error_code(&response),
+            Some(conformance::codes::METHOD_NOT_FOUND)
+        );
+        let (response, _) = rpc.raw_json_request(BLOCK_NUMBER_REQUEST).await.unwrap();
+        assert_eq!(error_code(&response), None);
+
+        let rpc = jsonrpsee_module(NodePersonality::Archive);
+        let (response, _) = rpc.raw_json_request(GET_LOGS_REQUEST).await.unwrap();
+        assert_eq!(error_code(&response), None);
+    }
+}
@@ -0,0 +1,471 @@
+//! Operator-only methods. The namespace is disabled by default and must not be exposed publicly.
+//! State-changing methods additionally require an [`AdminAuthorization`] signed by the admin keys from the config;
+//! the authorized calls are recorded into the append-only `admin_actions_log` table.
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde_json::json;
+
+use zksync_dal::{admin_actions_log_dal::AdminActionRecord, StorageProcessor};
+use zksync_object_store::object_store::create_object_store_from_env;
+use zksync_types::{
+    api::{AdminAuthorization, BlockRevertPlan, BootloaderUsageReport},
+    Address, L1BatchNumber, L2ChainId, H256,
+};
+use zksync_utils::time::seconds_since_epoch;
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{backend_jsonrpc::error::internal_error, state::RpcState};
+use crate::block_reverter::{
+    state_writing_components, BlockReverter, BlockReverterError, RevertStage,
+};
+use crate::schema_compatibility::HEARTBEAT_TTL;
+
+/// Max time until the deadline of an admin authorization, bounding the time a leaked authorization can be used for.
+const MAX_AUTHORIZATION_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct AdminNamespace {
+    pub state: RpcState,
+}
+
+impl AdminNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    /// Returns the accounts allowed to deploy contracts in the permissioned deployment mode.
+    #[tracing::instrument(skip(self))]
+    pub fn get_deployer_allowlist_impl(&self) -> Vec<Address> {
+        let start = Instant::now();
+        let endpoint_name = "get_deployer_allowlist";
+
+        let deployers = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .access_storage_blocking()
+            .deployer_allowlist_dal()
+            .get_allowlisted_deployers();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        deployers
+    }
+
+    /// Returns `false` if the account is already in the allowlist.
+    #[tracing::instrument(skip(self, authorization))]
+    pub fn add_deployer_to_allowlist_impl(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> Result<bool, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "add_deployer_to_allowlist";
+
+        let added = self.apply_authorized(
+            endpoint_name,
+            "addDeployerToAllowlist",
+            json!([address]),
+            &authorization,
+            |storage| storage.deployer_allowlist_dal().add_deployer(&address),
+        )?;
+        if added {
+            vlog::info!("Account {:?} was added to the deployer allowlist", address);
+        }
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(added)
+    }
+
+    /// Returns `false` if the account isn't in the allowlist.
+    #[tracing::instrument(skip(self, authorization))]
+    pub fn remove_deployer_from_allowlist_impl(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> Result<bool, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "remove_deployer_from_allowlist";
+
+        let removed = self.apply_authorized(
+            endpoint_name,
+            "removeDeployerFromAllowlist",
+            json!([address]),
+            &authorization,
+            |storage| storage.deployer_allowlist_dal().remove_deployer(&address),
+        )?;
+        if removed {
+            vlog::info!(
+                "Account {:?} was removed from the deployer allowlist",
+                address
+            );
+        }
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(removed)
+    }
+
+    /// Returns the transactions quarantined after repeatedly crashing the state keeper.
+    #[tracing::instrument(skip(self))]
+    pub fn get_quarantined_transactions_impl(&self) -> Vec<H256> {
+        let start = Instant::now();
+        let endpoint_name = "get_quarantined_transactions";
+
+        let hashes = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .access_storage_blocking()
+            .quarantined_transactions_dal()
+            .get_quarantined_transactions()
+            .into_iter()
+            .map(|tx| tx.hash)
+            .collect();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        hashes
+    }
+
+    /// Returns the reviewed transaction to the mempool. Returns `false` if the transaction isn't quarantined.
+    #[tracing::instrument(skip(self, authorization))]
+    pub fn release_quarantined_transaction_impl(
+        &self,
+        hash: H256,
+        authorization: AdminAuthorization,
+    ) -> Result<bool, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "release_quarantined_transaction";
+
+        let released = self.apply_authorized(
+            endpoint_name,
+            "releaseQuarantinedTransaction",
+            json!([hash]),
+            &authorization,
+            |storage| storage.quarantined_transactions_dal().release(&hash),
+        )?;
+        if released {
+            vlog::info!("Transaction {:?} was released from the quarantine", hash);
+        }
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(released)
+    }
+
+    /// Reports what reverting the node to the L1 batch would remove, without changing anything.
+    /// Returns `None` if the L1 batch isn't sealed.
+    #[tracing::instrument(skip(self))]
+    pub fn get_block_revert_plan_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<BlockRevertPlan>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_block_revert_plan";
+
+        let rocksdb_watermarks = self.state.rocksdb_watermarks_reader.read().map_err(|err| {
+            vlog::error!("Failed to read RocksDB watermarks: {:#}", err);
+            Web3Error::InternalError
+        })?;
+        let block_reverter = BlockReverter::new(
+            self.state.config.clone(),
+            self.state.tx_sender.0.master_connection_pool.clone(),
+            None,
+        );
+        let plan = match block_reverter.plan_with_watermarks(l1_batch_number, rocksdb_watermarks) {
+            Ok(plan) => Some(plan),
+            Err(BlockReverterError::UnknownL1Batch(_)) => None,
+            Err(err) => {
+                vlog::error!("Failed to build block revert plan: {}", err);
+                return Err(Web3Error::InternalError);
+            }
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(plan)
+    }
+
+    /// Reverts the requested stages (named as in `RevertStage::name()`) of the node state to the L1 batch
+    /// and returns the plan the revert was performed by. The revert is rejected while any running instance
+    /// has a component writing the reverted state, and for Postgres, while a RocksDB instance on this machine
+    /// is ahead of the L1 batch and isn't requested to be reverted as well, or isn't found on this machine.
+    ///
+    /// The action is recorded once the revert has finished, whether it has succeeded or not, so that
+    /// the authorization can't be reused afterwards; an interrupted revert is resumed by calling the method
+    /// with a new authorization. Since the revert is idempotent, concurrent calls with the same authorization
+    /// have the same effect as a single one.
+    #[tracing::instrument(skip(self, authorization))]
+    pub async fn revert_blocks_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stages: Vec<String>,
+        authorization: AdminAuthorization,
+    ) -> Result<BlockRevertPlan, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "revert_blocks";
+        let action = "revertBlocks";
+
+        let params = json!([l1_batch_number, stages]);
+        let stages = stages
+            .iter()
+            .map(|name| {
+                RevertStage::from_name(name).ok_or_else(|| {
+                    Web3Error::BlockRevertRejected(format!("unknown revert stage `{}`", name))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (signers, message_hash) = self.verify_authorization(action, &params, &authorization)?;
+        let authorization_used = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .access_storage_blocking()
+            .admin_actions_log_dal()
+            .contains_action(message_hash);
+        if authorization_used {
+            return Err(Web3Error::Unauthorized(
+                "authorization was already used".to_owned(),
+            ));
+        }
+
+        let removes_objects = stages.iter().any(|stage| {
+            matches!(
+                stage,
+                RevertStage::RocksdbBackups | RevertStage::StateSnapshotDiffs
+            )
+        });
+        let mut block_reverter = BlockReverter::new(
+            self.state.config.clone(),
+            self.state.tx_sender.0.master_connection_pool.clone(),
+            removes_objects.then(create_object_store_from_env),
+        );
+        self.check_state_writers_stopped()?;
+
+        // The revert blocks on Postgres and RocksDB, so it's run off the API server workers.
+        let runtime = tokio::runtime::Handle::current();
+        let revert_stages = stages.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            runtime.block_on(block_reverter.revert(l1_batch_number, &revert_stages, false))
+        })
+        .await
+        .map_err(|err| internal_error(endpoint_name, err))
+        .and_then(|result| {
+            result.map_err(|err| match err {
+                BlockReverterError::UnknownL1Batch(_)
+                | BlockReverterError::AlreadyExecuted { .. }
+                | BlockReverterError::UnrecoverableStage(_)
+                | BlockReverterError::UnknownRocksdbWatermark(_) => {
+                    Web3Error::BlockRevertRejected(err.to_string())
+                }
+                err => internal_error(endpoint_name, err),
+            })
+        });
+
+        let applied = result.as_ref().map_or(false, |plan| {
+            stages.iter().any(|stage| stage.is_pending(plan))
+        });
+        let record = AdminActionRecord {
+            action: action.to_owned(),
+            params,
+            signers,
+            message_hash,
+            applied,
+            created_at: Utc::now().naive_utc(),
+        };
+        Self::record_action(
+            &mut self
+                .state
+                .tx_sender
+                .0
+                .master_connection_pool
+                .access_storage_blocking(),
+            endpoint_name,
+            &record,
+        )?;
+        let plan = result?;
+        vlog::info!(
+            "Node state was reverted to L1 batch #{} via admin API: {:?}",
+            l1_batch_number,
+            plan
+        );
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(plan)
+    }
+
+    /// Returns `None` if the L1 batch isn't sealed or was sealed with the bootloader usage reports disabled.
+    #[tracing::instrument(skip(self))]
+    pub fn get_bootloader_usage_report_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<BootloaderUsageReport> {
+        let start = Instant::now();
+        let endpoint_name = "get_bootloader_usage_report";
+
+        let report = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .bootloader_usage_dal()
+            .get_report(l1_batch_number);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        report
+    }
+
+    /// Checks that the authorization is signed by enough admin keys for this chain and node, applies the action
+    /// and records it into the audit trail in a single DB transaction. Each authorization can only be used once.
+    fn apply_authorized(
+        &self,
+        endpoint_name: &str,
+        action: &str,
+        params: serde_json::Value,
+        authorization: &AdminAuthorization,
+        apply: impl FnOnce(&mut StorageProcessor<'_>) -> bool,
+    ) -> Result<bool, Web3Error> {
+        let (signers, message_hash) = self.verify_authorization(action, &params, authorization)?;
+
+        let mut storage = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .access_storage_blocking();
+        let mut transaction = storage.start_transaction_blocking();
+        let applied = apply(&mut transaction);
+        let record = AdminActionRecord {
+            action: action.to_owned(),
+            params,
+            signers,
+            message_hash,
+            applied,
+            created_at: Utc::now().naive_utc(),
+        };
+        // The transaction is rolled back on drop, so a replayed authorization has no effect.
+        Self::record_action(&mut transaction, endpoint_name, &record)?;
+        transaction.commit_blocking();
+        Ok(applied)
+    }
+
+    /// Checks that no running instance has a component writing the state reverted by the block reverter.
+    fn check_state_writers_stopped(&self) -> Result<(), Web3Error> {
+        let live_instances = self
+            .state
+            .tx_sender
+            .0
+            .master_connection_pool
+            .access_storage_blocking()
+            .instance_heartbeats_dal()
+            .get_live_instances(HEARTBEAT_TTL);
+        for instance in live_instances {
+            let components = state_writing_components(&instance.components);
+            if !components.is_empty() {
+                return Err(Web3Error::BlockRevertRejected(format!(
+                    "instance {} is running {}",
+                    instance.instance_id,
+                    components.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the authorization is signed by enough admin keys for this chain and node.
+    /// Returns the signers and the hash of the signed message.
+    fn verify_authorization(
+        &self,
+        action: &str,
+        params: &serde_json::Value,
+        authorization: &AdminAuthorization,
+    ) -> Result<(Vec<Address>, H256), Web3Error> {
+        let config = self.state.config;
+        let node_id =
+            config.api.web3_json_rpc.admin_node_id().ok_or_else(|| {
+                Web3Error::Unauthorized("no admin node ID is configured".to_owned())
+            })?;
+        let message = AdminAuthorization::message(
+            L2ChainId(config.chain.eth.zksync_network_id),
+            node_id,
+            action,
+            params,
+            authorization.deadline,
+        );
+        let message_hash = AdminAuthorization::message_hash(&message);
+        let signers = self.verify_signers(message_hash, authorization)?;
+        Ok((signers, message_hash))
+    }
+
+    /// Records the authorized action into the audit trail. Each authorization can only be used once.
+    fn record_action(
+        storage: &mut StorageProcessor<'_>,
+        endpoint_name: &str,
+        record: &AdminActionRecord,
+    ) -> Result<(), Web3Error> {
+        if !storage.admin_actions_log_dal().insert_action(record) {
+            return Err(Web3Error::Unauthorized(
+                "authorization was already used".to_owned(),
+            ));
+        }
+
+        vlog::info!(
+            "Admin action {} with params {} was authorized by {:?}",
+            record.action,
+            record.params,
+            record.signers
+        );
+        metrics::increment_counter!("api.web3.admin_actions", "method" => endpoint_name.to_owned());
+        Ok(())
+    }
+
+    /// Returns the distinct admin keys the authorization is signed by, if there's enough of them.
+    fn verify_signers(
+        &self,
+        message_hash: H256,
+        authorization: &AdminAuthorization,
+    ) -> Result<Vec<Address>, Web3Error> {
+        let config = &self.state.config.api.web3_json_rpc;
+        let admin_signers = config.admin_signers();
+        if admin_signers.is_empty() {
+            return Err(Web3Error::Unauthorized(
+                "no admin signers are configured".to_owned(),
+            ));
+        }
+
+        let now = seconds_since_epoch();
+        if authorization.deadline < now {
+            return Err(Web3Error::Unauthorized("deadline has passed".to_owned()));
+        }
+        if authorization.deadline > now + MAX_AUTHORIZATION_TTL.as_secs() {
+            return Err(Web3Error::Unauthorized(format!(
+                "deadline is more than {}s in the future",
+                MAX_AUTHORIZATION_TTL.as_secs()
+            )));
+        }
+
+        let mut signers = BTreeSet::new();
+        for signature in &authorization.signatures {
+            let signer = signature
+                .signature_recover_signer(&message_hash)
+                .map_err(|_| Web3Error::Unauthorized("malformed signature".to_owned()))?;
+            if !admin_signers.contains(&signer) {
+                return Err(Web3Error::Unauthorized(format!(
+                    "{:?} is not an admin signer",
+                    signer
+                )));
+            }
+            signers.insert(signer);
+        }
+        let threshold = config.admin_signatures_threshold();
+        if signers.len() < threshold {
+            return Err(Web3Error::Unauthorized(format!(
+                "{} distinct admin signatures are required, got {}",
+                threshold,
+                signers.len()
+            )));
+        }
+        Ok(signers.into_iter().collect())
+    }
+}
@@ -0,0 +1,252 @@
+use std::time::Instant;
+
+use zksync_types::{
+    api::{BlockId, BlockNumber, TransactionId, TxLifecycleEvent},
+    transaction_request::{l2_tx_from_call_req, CallRequest},
+    vm_trace::{DebugTraceResult, SupportedTracer, TracerConfig, TracerOptions, TxTraceResult},
+    L2ChainId, MiniblockNumber, Transaction, H256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::{
+    execution_sandbox::{
+        enforced_base_fee, execute_tx_eth_call_with_plugins, replay_miniblock_transactions,
+    },
+    tracers::{create_tracer, DebugTracer, StructLogLimit},
+    web3::{backend_jsonrpc::error::internal_error, state::RpcState},
+};
+
+#[derive(Debug, Clone)]
+pub struct DebugNamespace {
+    pub state: RpcState,
+}
+
+impl DebugNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    #[tracing::instrument(skip(self, request, block))]
+    pub fn trace_call_impl(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        options: TracerOptions,
+    ) -> Result<DebugTraceResult, Web3Error> {
+        let start = Instant::now();
+
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let tx = l2_tx_from_call_req(request, self.state.config.api.web3_json_rpc.max_tx_size)?;
+        let mut tracer = create_tracer(
+            &options,
+            &tx.clone().into(),
+            &StructLogLimit::default(),
+            &self.state.connection_pool,
+        )?;
+
+        let enforced_base_fee = Some(enforced_base_fee(tx.common_data.fee.max_fee_per_gas)?);
+        let result = execute_tx_eth_call_with_plugins(
+            &self.state.connection_pool,
+            tx,
+            block,
+            self.state
+                .tx_sender
+                .0
+                .gas_adjuster
+                .estimate_effective_gas_price(),
+            self.state.tx_sender.0.state_keeper_config.fair_l2_gas_price,
+            enforced_base_fee,
+            &self.state.tx_sender.0.playground_base_system_contracts,
+            &mut [tracer.as_plugin()],
+        )?;
+        let trace = tracer.into_trace(result);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "trace_call");
+        trace
+    }
+
+    /// Returns the recorded lifecycle of the transaction: submission, validation, mempool insertion,
+    /// execution or rejection. Events older than the retention period are not available.
+    #[tracing::instrument(skip(self))]
+    pub fn get_transaction_lifecycle_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Vec<TxLifecycleEvent>, Web3Error> {
+        let start = Instant::now();
+
+        let events = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .tx_lifecycle_dal()
+            .get_tx_lifecycle_events(hash);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "get_transaction_lifecycle");
+        Ok(events)
+    }
+
+    /// Re-executes the transaction on top of the state preceding it in its miniblock and traces it.
+    /// Returns `None` if the transaction is unknown or not included in a miniblock yet.
+    /// The struct logger is used if the tracer is not specified.
+    #[tracing::instrument(skip(self))]
+    pub async fn trace_transaction_impl(
+        &self,
+        hash: H256,
+        options: Option<TracerOptions>,
+    ) -> Result<Option<DebugTraceResult>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "trace_transaction";
+
+        let options = options.unwrap_or_else(default_tracer_options);
+        let mut storage = self.state.connection_pool.access_storage().await;
+        let tx = storage
+            .transactions_web3_dal()
+            .get_transaction(
+                TransactionId::Hash(hash),
+                L2ChainId(self.state.config.chain.eth.zksync_network_id),
+            )
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let (miniblock_number, index) = match tx
+            .and_then(|tx| Some((tx.block_number?, tx.transaction_index?)))
+        {
+            Some((miniblock_number, index)) => {
+                (MiniblockNumber(miniblock_number.as_u32()), index.as_usize())
+            }
+            None => {
+                metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+                return Ok(None);
+            }
+        };
+        let txs = storage
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock_number, Some(index + 1))
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        drop(storage);
+
+        let mut traces = self
+            .spawn_trace_miniblock_transactions(endpoint_name, miniblock_number, txs, 1, options)
+            .await?;
+        let trace = traces.pop().map(|trace| trace.result);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(trace)
+    }
+
+    /// Re-executes all the transactions of the block and traces each of them.
+    /// The struct logger is used if the tracer is not specified.
+    #[tracing::instrument(skip(self))]
+    pub async fn trace_block_impl(
+        &self,
+        block: BlockNumber,
+        options: Option<TracerOptions>,
+    ) -> Result<Vec<TxTraceResult>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "trace_block";
+
+        let options = options.unwrap_or_else(default_tracer_options);
+        let mut storage = self.state.connection_pool.access_storage().await;
+        let miniblock_number = storage
+            .blocks_web3_dal()
+            .resolve_block_id(BlockId::Number(block))
+            .map_err(|err| internal_error(endpoint_name, err))??;
+        // One extra transaction is requested to tell whether the block exceeds the limit.
+        let limit = self.state.req_entities_limit;
+        let txs = storage
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock_number, Some(limit + 1))
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        drop(storage);
+        if txs.len() > limit {
+            return Err(Web3Error::ResultLimitExceeded(limit));
+        }
+
+        let traced_count = txs.len();
+        let traces = self
+            .spawn_trace_miniblock_transactions(
+                endpoint_name,
+                miniblock_number,
+                txs,
+                traced_count,
+                options,
+            )
+            .await;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        traces
+    }
+
+    /// Runs [`Self::trace_miniblock_transactions()`] off the async runtime, since the replay executes
+    /// the transactions in the VM.
+    async fn spawn_trace_miniblock_transactions(
+        &self,
+        endpoint_name: &'static str,
+        miniblock_number: MiniblockNumber,
+        txs: Vec<Transaction>,
+        traced_count: usize,
+        options: TracerOptions,
+    ) -> Result<Vec<TxTraceResult>, Web3Error> {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || {
+            this.trace_miniblock_transactions(miniblock_number, txs, traced_count, &options)
+        })
+        .await
+        .map_err(|err| internal_error(endpoint_name, err))?
+    }
+
+    /// Replays the miniblock transactions and traces the last `traced_count` of them.
+    /// The struct log tracers share the limit of the recorded opcodes.
+    fn trace_miniblock_transactions(
+        &self,
+        miniblock_number: MiniblockNumber,
+        txs: Vec<Transaction>,
+        traced_count: usize,
+        options: &TracerOptions,
+    ) -> Result<Vec<TxTraceResult>, Web3Error> {
+        let traced_txs = &txs[txs.len() - traced_count..];
+        let tx_hashes: Vec<_> = traced_txs.iter().map(Transaction::hash).collect();
+        let struct_log_limit = StructLogLimit::default();
+        let mut tracers = traced_txs
+            .iter()
+            .map(|tx| create_tracer(options, tx, &struct_log_limit, &self.state.connection_pool))
+            .collect::<Result<Vec<Box<dyn DebugTracer>>, _>>()?;
+
+        let mut plugins: Vec<_> = tracers
+            .iter_mut()
+            .map(|tracer| tracer.as_plugin())
+            .collect();
+        let results = replay_miniblock_transactions(
+            &self.state.connection_pool,
+            miniblock_number,
+            txs,
+            self.state
+                .tx_sender
+                .0
+                .state_keeper_config
+                .validation_computational_gas_limit,
+            &mut plugins,
+        )?;
+        drop(plugins);
+
+        tracers
+            .into_iter()
+            .zip(results)
+            .zip(tx_hashes)
+            .map(|((tracer, result), tx_hash)| {
+                Ok(TxTraceResult {
+                    tx_hash,
+                    result: tracer.into_trace(result)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn default_tracer_options() -> TracerOptions {
+    TracerOptions {
+        tracer: SupportedTracer::StructLogger,
+        tracer_config: TracerConfig::default(),
+    }
+}
@@ -0,0 +1,56 @@
+//! Methods used by the external nodes to sync with the main node.
+
+use std::time::Instant;
+
+use zksync_types::{api::NodeIdentity, L2ChainId};
+
+use crate::api_server::web3::state::RpcState;
+use crate::sync_layer::handshake::{
+    check_compatibility, local_identity, reported_software_version,
+};
+
+#[derive(Debug, Clone)]
+pub struct EnNamespace {
+    pub state: RpcState,
+}
+
+impl EnNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    /// Returns the identity of this node. The external node decides whether it can sync from this node,
+    /// the identity of the external node is only checked to be reported.
+    #[tracing::instrument(skip(self))]
+    pub fn handshake_impl(&self, peer: NodeIdentity) -> NodeIdentity {
+        let start = Instant::now();
+        let endpoint_name = "handshake";
+
+        let local = local_identity(
+            &mut self.state.connection_pool.access_storage_blocking(),
+            L2ChainId(self.state.config.chain.eth.zksync_network_id),
+        );
+        // The negotiated protocol version is bounded by the versions supported by this node, and the software version
+        // reported by the client is sanitized, so that the client can't blow up the label cardinality.
+        let protocol_version = match check_compatibility(&local, &peer) {
+            Ok(protocol_version) => protocol_version.to_string(),
+            Err(err) => {
+                vlog::warn!(
+                    "External node with software version {} is incompatible with this node: {}",
+                    reported_software_version(&peer),
+                    err
+                );
+                "incompatible".to_owned()
+            }
+        };
+        metrics::counter!(
+            "api.en.handshakes",
+            1,
+            "software_version" => reported_software_version(&peer),
+            "protocol_version" => protocol_version
+        );
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        local
+    }
+}
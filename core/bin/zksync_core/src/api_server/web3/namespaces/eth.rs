@@ -4,28 +4,37 @@ use itertools::Itertools;
 
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        AccessListWithGasUsed, AccountInfo, BlockId, BlockNumber, FeeHistory, GetLogsFilter,
+        Transaction, TransactionId, TransactionReceipt, TransactionVariant,
     },
+    feature_flags, get_code_key, get_nonce_key,
     l2::{L2Tx, TransactionType},
+    protocol_version::ProtocolVersion,
     transaction_request::{l2_tx_from_call_req, CallRequest},
-    utils::decompose_full_nonce,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
     web3::types::SyncState,
     AccountTreeId, Bytes, L2ChainId, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS,
     MAX_GAS_PER_PUBDATA_BYTE, U256,
 };
 
+use zksync_dal::StorageProcessor;
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Block, Filter, FilterChanges, Log, TypedFilter, U64},
 };
 
 use crate::api_server::{
-    execution_sandbox::execute_tx_eth_call, web3::backend_jsonrpc::error::internal_error,
+    execution_sandbox::{
+        access_list_from_storage_logs, enforced_base_fee, execute_tx_eth_call,
+        SandboxExecutionError,
+    },
+    tx_sender::SubmitTxError,
+    web3::backend_jsonrpc::error::internal_error,
+    web3::extensions::extensions_enabled,
     web3::state::RpcState,
 };
 
-use zksync_utils::u256_to_h256;
+use zksync_utils::{h256_to_u256, u256_to_h256};
 
 #[cfg(feature = "openzeppelin_tests")]
 use zksync_utils::bytecode::hash_bytecode;
@@ -41,6 +50,8 @@ use {
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
 pub const PROTOCOL_VERSION: &str = "zks/1";
+/// Maximum number of miniblocks returned by `eth_feeHistory`; larger requests are truncated.
+pub const FEE_HISTORY_MAX_BLOCK_COUNT: u64 = 1024;
 
 #[derive(Debug, Clone)]
 pub struct EthNamespace {
@@ -106,6 +117,11 @@ impl EthNamespace {
         )?;
 
         let mut res_bytes = match result.revert_reason {
+            // OpenZeppelin tests expect the revert data to be returned as the call result.
+            Some(revert) if !cfg!(feature = "openzeppelin_tests") => {
+                let err = SandboxExecutionError::from(revert.revert_reason);
+                return Err(SubmitTxError::from(err).into());
+            }
             Some(result) => result.original_data,
             None => result
                 .return_data
@@ -132,9 +148,19 @@ impl EthNamespace {
     pub fn estimate_gas_impl(
         &self,
         request: CallRequest,
-        _block: Option<BlockNumber>,
+        block: Option<BlockId>,
     ) -> Result<U256, Web3Error> {
         let start = Instant::now();
+        let endpoint_name = "estimate_gas";
+
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        // Checks that the block is present before it's used for the estimation.
+        self.state
+            .connection_pool
+            .access_storage_blocking()
+            .blocks_web3_dal()
+            .resolve_block_id(block)
+            .map_err(|err| internal_error(endpoint_name, err))??;
 
         let is_eip712 = request.eip712_meta.is_some();
 
@@ -159,7 +185,7 @@ impl EthNamespace {
         // When we're estimating fee, we are trying to deduce values related to fee, so we should
         // not consider provided ones.
 
-        tx.common_data.fee.max_fee_per_gas = self.state.tx_sender.gas_price().into();
+        tx.common_data.fee.max_fee_per_gas = self.state.tx_sender.gas_price();
         tx.common_data.fee.max_priority_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
         tx.common_data.fee.gas_per_pubdata_limit = MAX_GAS_PER_PUBDATA_BYTE.into();
 
@@ -177,14 +203,62 @@ impl EthNamespace {
             .web3_json_rpc
             .estimate_gas_acceptable_overestimation;
 
-        let fee = self
-            .state
-            .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
-            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string()))?;
+        let fee = self.state.tx_sender.get_txs_fee_in_wei(
+            tx.into(),
+            block,
+            scale_factor,
+            acceptable_overestimation,
+        )?;
 
-        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_gas");
-        Ok(fee.gas_limit)
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(fee.fee.gas_limit)
+    }
+
+    #[tracing::instrument(skip(self, request, block))]
+    pub fn create_access_list_impl(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, Web3Error> {
+        let start = Instant::now();
+
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        #[cfg(not(feature = "openzeppelin_tests"))]
+        let tx = l2_tx_from_call_req(
+            request.clone(),
+            self.state.config.api.web3_json_rpc.max_tx_size,
+        )?;
+
+        #[cfg(feature = "openzeppelin_tests")]
+        let tx: L2Tx = self
+            .convert_evm_like_deploy_requests(tx_req_from_call_req(
+                request.clone(),
+                self.state.config.api.web3_json_rpc.max_tx_size,
+            )?)?
+            .try_into()?;
+
+        let enforced_base_fee = Some(enforced_base_fee(tx.common_data.fee.max_fee_per_gas)?);
+        let result = execute_tx_eth_call(
+            &self.state.connection_pool,
+            tx,
+            block,
+            self.state
+                .tx_sender
+                .0
+                .gas_adjuster
+                .estimate_effective_gas_price(),
+            self.state.tx_sender.0.state_keeper_config.fair_l2_gas_price,
+            enforced_base_fee,
+            &self.state.tx_sender.0.playground_base_system_contracts,
+        )?;
+        let access_list = access_list_from_storage_logs(&result.storage_log_queries);
+        let gas_used = self.estimate_gas_impl(request, Some(block))?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "create_access_list");
+        Ok(AccessListWithGasUsed {
+            access_list,
+            gas_used,
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -195,7 +269,105 @@ impl EthNamespace {
         let price = self.state.tx_sender.gas_price();
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
-        Ok(price.into())
+        Ok(price)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn max_priority_fee_per_gas_impl(&self) -> Result<U256, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "max_priority_fee_per_gas";
+
+        let priority_fee = self.state.tx_sender.max_priority_fee_per_gas();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(priority_fee)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn fee_history_impl(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> Result<FeeHistory, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "fee_history";
+
+        if let Some(percentiles) = &reward_percentiles {
+            let are_valid = percentiles
+                .iter()
+                .all(|percentile| (0.0..=100.0).contains(percentile))
+                && percentiles.windows(2).all(|pair| pair[0] <= pair[1]);
+            if !are_valid {
+                return Err(Web3Error::InvalidFeeParams(
+                    "reward percentiles must be non-decreasing values in [0, 100]".to_owned(),
+                ));
+            }
+        }
+        let block_count = block_count.as_u64().min(FEE_HISTORY_MAX_BLOCK_COUNT) as u32;
+        if block_count == 0 {
+            return Ok(FeeHistory::default());
+        }
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let newest_block = storage
+            .blocks_web3_dal()
+            .resolve_block_id(BlockId::Number(newest_block))
+            .map_err(|err| internal_error(endpoint_name, err))??;
+        let oldest_block = MiniblockNumber(newest_block.0.saturating_sub(block_count - 1));
+        let fractions: Vec<_> = reward_percentiles
+            .iter()
+            .flatten()
+            .map(|percentile| percentile / 100.0)
+            .collect();
+        let blocks = storage
+            .blocks_web3_dal()
+            .get_fee_history(oldest_block, newest_block, &fractions)
+            .map_err(|err| internal_error(endpoint_name, err))?;
+
+        // Same gas limits as reported for the blocks by `eth_getBlockBy*`.
+        let protocol_versions = storage
+            .protocol_versions_dal()
+            .get_protocol_versions_for_miniblocks(oldest_block, newest_block);
+        let default_block_gas_limit = self.state.config.chain.state_keeper.block_gas_limit;
+        let block_gas_limit = |number| {
+            let limit = ProtocolVersion::of_miniblock(&protocol_versions, number)
+                .map_or(default_block_gas_limit, |version| version.block_gas_limit);
+            U256::from(limit)
+        };
+        let mut base_fee_per_gas: Vec<_> =
+            blocks.iter().map(|block| block.base_fee_per_gas).collect();
+        if let Some(&last_base_fee) = base_fee_per_gas.last() {
+            base_fee_per_gas.push(last_base_fee);
+        }
+        let fee_history = FeeHistory {
+            oldest_block: blocks
+                .first()
+                .map_or(oldest_block, |block| block.number)
+                .0
+                .into(),
+            base_fee_per_gas,
+            gas_used_ratio: blocks
+                .iter()
+                .map(|block| gas_used_ratio(block.gas_used, block_gas_limit(block.number)))
+                .collect(),
+            // Blocks without L2 transactions have zero rewards at all percentiles, like in Ethereum clients.
+            reward: reward_percentiles.map(|percentiles| {
+                blocks
+                    .into_iter()
+                    .map(|block| {
+                        if block.rewards.is_empty() {
+                            vec![U256::zero(); percentiles.len()]
+                        } else {
+                            block.rewards
+                        }
+                    })
+                    .collect()
+            }),
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(fee_history)
     }
 
     #[tracing::instrument(skip(self))]
@@ -224,14 +396,15 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self, filter))]
-    pub fn get_logs_impl(&self, mut filter: Filter) -> Result<Vec<Log>, Web3Error> {
+    pub async fn get_logs_impl(&self, mut filter: Filter) -> Result<Vec<Log>, Web3Error> {
         let start = Instant::now();
 
-        let (from_block, to_block) = self.state.resolve_filter_block_range(&filter)?;
+        let (from_block, to_block) = self.state.resolve_logs_block_range(&filter)?;
 
         filter.to_block = Some(BlockNumber::Number(to_block.0.into()));
         let changes = self
-            .filter_changes(TypedFilter::Events(filter, from_block))?
+            .filter_changes("eth_getLogs", TypedFilter::Events(filter, from_block))
+            .await?
             .0;
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "get_logs");
@@ -242,7 +415,7 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_filter_logs_impl(&self, idx: U256) -> Result<FilterChanges, Web3Error> {
+    pub async fn get_filter_logs_impl(&self, idx: U256) -> Result<FilterChanges, Web3Error> {
         let start = Instant::now();
 
         let filter = match self
@@ -260,7 +433,7 @@ impl EthNamespace {
             _ => return Err(Web3Error::FilterNotFound),
         };
 
-        let logs = self.filter_changes(filter)?.0;
+        let logs = self.filter_changes("eth_getFilterLogs", filter).await?.0;
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "get_filter_logs");
         Ok(logs)
@@ -269,7 +442,7 @@ impl EthNamespace {
     #[tracing::instrument(skip(self))]
     pub fn get_block_impl(
         &self,
-        block: BlockId,
+        block_id: BlockId,
         full_transactions: bool,
     ) -> Result<Option<Block<TransactionVariant>>, Web3Error> {
         let start = Instant::now();
@@ -279,23 +452,57 @@ impl EthNamespace {
             "get_block"
         };
 
-        let block = self
-            .state
-            .connection_pool
-            .access_storage_blocking()
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let block = storage
             .blocks_web3_dal()
             .get_block_by_web3_block_id(
-                block,
+                block_id,
                 full_transactions,
                 L2ChainId(self.state.config.chain.eth.zksync_network_id),
+                self.state.config.chain.state_keeper.block_gas_limit,
             )
-            .map_err(|err| internal_error(endpoint_name, err));
+            .map_err(|err| internal_error(endpoint_name, err))
+            .and_then(|block| {
+                if block.is_none() {
+                    Self::ensure_block_not_reorged(&mut storage, block_id, endpoint_name)?;
+                }
+                Ok(block)
+            })
+            .map(|block| {
+                block.map(|mut block| {
+                    if !extensions_enabled() {
+                        block.extensions = None;
+                    }
+                    block
+                })
+            });
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
 
         block
     }
 
+    /// Block-by-hash lookups return an explicit error for the miniblocks which were reorged,
+    /// rather than treating them as unknown.
+    fn ensure_block_not_reorged(
+        storage: &mut StorageProcessor<'_>,
+        block_id: BlockId,
+        endpoint_name: &'static str,
+    ) -> Result<(), Web3Error> {
+        let hash = match block_id {
+            BlockId::Hash(hash) => hash,
+            BlockId::Number(_) => return Ok(()),
+        };
+        match storage
+            .blocks_web3_dal()
+            .missing_block_error(hash)
+            .map_err(|err| internal_error(endpoint_name, err))?
+        {
+            Web3Error::NoBlock => Ok(()),
+            err => Err(err),
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn get_block_transaction_count_impl(
         &self,
@@ -304,13 +511,17 @@ impl EthNamespace {
         let start = Instant::now();
         let endpoint_name = "get_block_transaction_count";
 
-        let tx_count = self
-            .state
-            .connection_pool
-            .access_storage_blocking()
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let tx_count = storage
             .blocks_web3_dal()
             .get_block_tx_count(block)
-            .map_err(|err| internal_error(endpoint_name, err));
+            .map_err(|err| internal_error(endpoint_name, err))
+            .and_then(|tx_count| {
+                if tx_count.is_none() {
+                    Self::ensure_block_not_reorged(&mut storage, block, endpoint_name)?;
+                }
+                Ok(tx_count)
+            });
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
         tx_count
@@ -370,9 +581,41 @@ impl EthNamespace {
         value
     }
 
+    /// Nonce, balance and code hash of the account, loaded with a single storage query.
+    #[tracing::instrument(skip(self))]
+    pub fn get_account_impl(
+        &self,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> Result<AccountInfo, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_account";
+
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let keys = [
+            get_nonce_key(&address),
+            storage_key_for_eth_balance(&address),
+            get_code_key(&address),
+        ];
+        let values = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .storage_web3_dal()
+            .get_historical_values(&keys, block)
+            .map_err(|err| internal_error(endpoint_name, err))??;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(AccountInfo {
+            nonce: decompose_full_nonce(h256_to_u256(values[0])).0,
+            balance: h256_to_u256(values[1]),
+            code_hash: values[2],
+        })
+    }
+
     /// Account nonce.
     #[tracing::instrument(skip(self))]
-    pub fn get_transaction_count_impl(
+    pub async fn get_transaction_count_impl(
         &self,
         address: Address,
         block: Option<BlockId>,
@@ -389,14 +632,17 @@ impl EthNamespace {
             BlockId::Number(BlockNumber::Pending) => self
                 .state
                 .connection_pool
-                .access_storage_blocking()
+                .access_storage()
+                .await
                 .transactions_web3_dal()
                 .next_nonce_by_initiator_account(address)
+                .await
                 .map_err(|err| internal_error(method_name, err)),
             _ => self
                 .state
                 .connection_pool
-                .access_storage_blocking()
+                .access_storage()
+                .await
                 .storage_web3_dal()
                 .get_address_historical_nonce(address, block)
                 .map_err(|err| internal_error(method_name, err))?,
@@ -409,7 +655,7 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_transaction_impl(
+    pub async fn get_transaction_impl(
         &self,
         id: TransactionId,
     ) -> Result<Option<Transaction>, Web3Error> {
@@ -419,9 +665,11 @@ impl EthNamespace {
         let mut transaction = self
             .state
             .connection_pool
-            .access_storage_blocking()
+            .access_storage()
+            .await
             .transactions_web3_dal()
             .get_transaction(id, L2ChainId(self.state.config.chain.eth.zksync_network_id))
+            .await
             .map_err(|err| internal_error(endpoint_name, err));
 
         if let Some(proxy) = &self.state.tx_sender.0.proxy {
@@ -451,23 +699,87 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_transaction_receipt_impl(
+    pub async fn get_raw_transaction_by_hash_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<Bytes>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_raw_transaction_by_hash";
+
+        let raw_tx = self
+            .state
+            .connection_pool
+            .access_storage()
+            .await
+            .transactions_web3_dal()
+            .get_raw_transaction_bytes(hash)
+            .await
+            .map(|bytes| bytes.map(Bytes))
+            .map_err(|err| internal_error(endpoint_name, err));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        raw_tx
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transaction_receipt_impl(
         &self,
         hash: H256,
     ) -> Result<Option<TransactionReceipt>, Web3Error> {
         let start = Instant::now();
         let endpoint_name = "get_transaction_receipt";
 
-        let res = self
+        let mut receipt = self
             .state
             .connection_pool
-            .access_storage_blocking()
+            .access_storage()
+            .await
             .transactions_web3_dal()
             .get_transaction_receipt(hash)
-            .map_err(|err| internal_error(endpoint_name, err));
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        if let Some(receipt) = &mut receipt {
+            self.state
+                .set_receipts_finality(std::slice::from_mut(receipt), endpoint_name)?;
+        }
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
-        res
+        Ok(receipt)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_receipts_impl(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<TransactionReceipt>>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_block_receipts";
+
+        let mut storage = self.state.connection_pool.access_storage().await;
+        let block_number = storage
+            .blocks_web3_dal()
+            .resolve_block_id(block_id)
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let block_number = match block_number {
+            Ok(number) => number,
+            Err(Web3Error::NoBlock) => {
+                Self::ensure_block_not_reorged(&mut storage, block_id, endpoint_name)?;
+                metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        let mut receipts = storage
+            .transactions_web3_dal()
+            .get_transaction_receipts_for_miniblock(block_number)
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        drop(storage);
+        self.state
+            .set_receipts_finality(&mut receipts, endpoint_name)?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(Some(receipts))
     }
 
     #[tracing::instrument(skip(self))]
@@ -533,7 +845,7 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_filter_changes_impl(&self, idx: U256) -> Result<FilterChanges, Web3Error> {
+    pub async fn get_filter_changes_impl(&self, idx: U256) -> Result<FilterChanges, Web3Error> {
         let start = Instant::now();
 
         let filter = match self
@@ -548,7 +860,7 @@ impl EthNamespace {
             None => return Err(Web3Error::FilterNotFound),
         };
 
-        let result = match self.filter_changes(filter) {
+        let result = match self.filter_changes("eth_getFilterChanges", filter).await {
             Ok((changes, updated_filter)) => {
                 self.state
                     .installed_filters
@@ -590,7 +902,17 @@ impl EthNamespace {
         let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
         tx.set_input(tx_bytes.0, hash);
 
-        let submit_res = match self.state.tx_sender.submit_tx(tx) {
+        let is_strict = self.state.feature_flags.is_enabled(
+            feature_flags::names::STRICT_TX_VALIDATION,
+            None,
+            Some(tx.initiator_account().as_bytes()),
+        );
+        let submit_res = if is_strict {
+            self.state.tx_sender.validate_account_nonce_strict(&tx)
+        } else {
+            Ok(())
+        };
+        let submit_res = match submit_res.and_then(|()| self.state.tx_sender.submit_tx(tx)) {
             Err(err) => {
                 vlog::debug!("Send raw transaction error {}", err);
                 metrics::counter!(
@@ -598,7 +920,7 @@ impl EthNamespace {
                     1,
                     "reason" => err.grafana_error_code()
                 );
-                Err(Web3Error::SubmitTransactionError(err.to_string()))
+                Err(err.into())
             }
             Ok(_) => Ok(hash),
         };
@@ -618,18 +940,18 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self, typed_filter))]
-    fn filter_changes(
+    async fn filter_changes(
         &self,
+        method_name: &'static str,
         typed_filter: TypedFilter,
     ) -> Result<(FilterChanges, TypedFilter), Web3Error> {
-        let method_name = "filter_changes";
-
         let res = match typed_filter {
             TypedFilter::Blocks(from_block) => {
                 let (block_hashes, last_block_number) = self
                     .state
                     .connection_pool
-                    .access_storage_blocking()
+                    .access_storage()
+                    .await
                     .blocks_web3_dal()
                     .get_block_hashes_after(from_block, self.state.req_entities_limit)
                     .map_err(|err| internal_error(method_name, err))?;
@@ -639,68 +961,65 @@ impl EthNamespace {
                 )
             }
             TypedFilter::PendingTransactions(from_timestamp) => {
-                let (tx_hashes, last_timestamp) = self
-                    .state
-                    .connection_pool
-                    .access_storage_blocking()
-                    .transactions_web3_dal()
-                    .get_pending_txs_hashes_after(
-                        from_timestamp,
-                        Some(self.state.req_entities_limit),
+                let (mut storage, deadline) =
+                    self.state.access_storage_with_deadline(method_name).await?;
+                let (tx_hashes, last_timestamp) = deadline
+                    .run(
+                        storage
+                            .transactions_web3_dal()
+                            .get_pending_txs_hashes_after(
+                                from_timestamp,
+                                Some(self.state.req_entities_limit),
+                            ),
                     )
-                    .map_err(|err| internal_error(method_name, err))?;
+                    .await?;
                 (
                     FilterChanges::Hashes(tx_hashes),
                     TypedFilter::PendingTransactions(last_timestamp.unwrap_or(from_timestamp)),
                 )
             }
             TypedFilter::Events(filter, from_block) => {
-                let addresses: Vec<_> = filter
-                    .address
-                    .clone()
-                    .into_iter()
-                    .flat_map(|v| v.0)
-                    .collect();
-                if let Some(topics) = filter.topics.as_ref() {
-                    if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
-                        return Err(Web3Error::TooManyTopics);
-                    }
-                }
-                let topics: Vec<_> = filter
-                    .topics
-                    .clone()
-                    .into_iter()
-                    .flatten()
-                    .enumerate()
-                    .filter_map(|(idx, topics)| topics.map(|topics| (idx as u32 + 1, topics.0)))
-                    .collect();
-                let get_logs_filter = GetLogsFilter {
-                    from_block,
-                    to_block: filter.to_block,
-                    addresses,
-                    topics,
-                };
+                let get_logs_filter = Self::get_logs_filter(&filter, from_block)?;
+                let logs_limit = self.state.config.api.web3_json_rpc.logs_result_limit();
 
-                let mut storage = self.state.connection_pool.access_storage_blocking();
+                let (mut storage, deadline) =
+                    self.state.access_storage_with_deadline(method_name).await?;
 
-                // Check if there are more than `req_entities_limit` logs that satisfies filter.
+                // Skip scanning the events if miniblock blooms exclude the requested addresses/topics.
+                if !deadline
+                    .run(storage.events_web3_dal().may_contain_logs(&get_logs_filter))
+                    .await?
+                {
+                    return Ok((
+                        FilterChanges::Logs(vec![]),
+                        TypedFilter::Events(filter, from_block),
+                    ));
+                }
+
+                // Check if there are more than `logs_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
-                if let Some(miniblock_number) = storage
-                    .events_web3_dal()
-                    .get_log_block_number(get_logs_filter.clone(), self.state.req_entities_limit)
-                    .map_err(|err| internal_error(method_name, err))?
+                if let Some(miniblock_number) = deadline
+                    .run(
+                        storage
+                            .events_web3_dal()
+                            .get_log_block_number(get_logs_filter.clone(), logs_limit),
+                    )
+                    .await?
                 {
                     return Err(Web3Error::LogsLimitExceeded(
-                        self.state.req_entities_limit,
+                        logs_limit,
                         from_block.0,
                         miniblock_number.0 - 1,
                     ));
                 }
 
-                let logs = storage
-                    .events_web3_dal()
-                    .get_logs(get_logs_filter, self.state.req_entities_limit)
-                    .map_err(|err| internal_error(method_name, err))?;
+                let logs = deadline
+                    .run(
+                        storage
+                            .events_web3_dal()
+                            .get_logs(get_logs_filter, logs_limit),
+                    )
+                    .await?;
                 let new_from_block = logs
                     .last()
                     .map(|log| MiniblockNumber(log.block_number.unwrap().as_u32()))
@@ -715,8 +1034,40 @@ impl EthNamespace {
         Ok(res)
     }
 
+    /// Converts the filter to the form used by the DAL, starting from `from_block`.
+    pub(super) fn get_logs_filter(
+        filter: &Filter,
+        from_block: MiniblockNumber,
+    ) -> Result<GetLogsFilter, Web3Error> {
+        if let Some(topics) = filter.topics.as_ref() {
+            if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+                return Err(Web3Error::TooManyTopics);
+            }
+        }
+        let addresses: Vec<_> = filter
+            .address
+            .clone()
+            .into_iter()
+            .flat_map(|v| v.0)
+            .collect();
+        let topics: Vec<_> = filter
+            .topics
+            .clone()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(idx, topics)| topics.map(|topics| (idx as u32 + 1, topics.0)))
+            .collect();
+        Ok(GetLogsFilter {
+            from_block,
+            to_block: filter.to_block,
+            addresses,
+            topics,
+        })
+    }
+
     #[cfg(feature = "openzeppelin_tests")]
-    pub fn send_transaction_impl(
+    pub async fn send_transaction_impl(
         &self,
         transaction_request: zksync_types::web3::types::TransactionRequest,
     ) -> Result<H256, Web3Error> {
@@ -725,9 +1076,11 @@ impl EthNamespace {
         } else {
             self.state
                 .connection_pool
-                .access_storage_blocking()
+                .access_storage()
+                .await
                 .transactions_web3_dal()
                 .next_nonce_by_initiator_account(transaction_request.from)
+                .await
                 .map_err(|err| internal_error("send_transaction", err))?
         };
         let mut eip712_meta = Eip712Meta::default();
@@ -879,3 +1232,13 @@ impl EthNamespace {
     // - `compile_solidity`.
     // - `compile_serpent`.
 }
+
+/// Ratio of the gas used by a block to its gas limit, as reported by `eth_feeHistory`.
+fn gas_used_ratio(gas_used: U256, block_gas_limit: U256) -> f64 {
+    if block_gas_limit.is_zero() {
+        return 0.0;
+    }
+    // Both values are scaled down the same way so that they fit into `u64` without changing the ratio much.
+    let shift = block_gas_limit.max(gas_used).bits().saturating_sub(64);
+    (gas_used >> shift).as_u64() as f64 / (block_gas_limit >> shift).as_u64() as f64
+}
@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
-use zksync_types::web3::types::H128;
-use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
+use zksync_types::{web3::types::H128, Address};
+use zksync_web3_decl::types::{
+    AccountEvent, LogsSubscriptionParams, PubSubFilter, PubSubResult, ResumableNotification,
+    ResumeParams, ResumeToken,
+};
 
 use jsonrpc_core::error::{Error, ErrorCode};
 use jsonrpc_pubsub::typed;
@@ -11,24 +14,63 @@ use super::eth::EVENT_TOPIC_NUMBER_LIMIT;
 
 pub type SubscriptionMap<T> = Arc<RwLock<HashMap<SubscriptionId, T>>>;
 
+/// Maximum number of accounts watched by a single `zks_subscribeAccountEvents` subscription.
+pub const ACCOUNT_EVENTS_ADDRESSES_LIMIT: usize = 100;
+
+/// Sink of a `newHeads` or `logs` subscription, which may be resumed after reconnecting.
+#[derive(Debug)]
+pub struct ResumableSink {
+    sink: typed::Sink<PubSubResult>,
+    resumable: bool,
+    /// Token of the last notification received by the resumed subscriber. The notifications following it
+    /// are replayed from the DB before the new ones are sent.
+    pub replay_from: Option<ResumeToken>,
+}
+
+impl ResumableSink {
+    fn new(sink: typed::Sink<PubSubResult>, params: ResumeParams) -> Self {
+        Self {
+            sink,
+            resumable: params.is_resumable(),
+            replay_from: params.resume_token,
+        }
+    }
+
+    /// Sends the notification, together with its resume token if the subscription is resumable.
+    pub fn notify(&self, result: PubSubResult, resume_token: ResumeToken) {
+        let result = if self.resumable {
+            PubSubResult::Resumable(ResumableNotification {
+                result: Box::new(result),
+                resume_token,
+            })
+        } else {
+            result
+        };
+        let _ = self.sink.notify(Ok(result));
+    }
+
+    pub fn notify_error(&self, error: Error) {
+        let _ = self.sink.notify(Err(error));
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct EthSubscribe {
-    pub active_block_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
+    pub active_block_subs: SubscriptionMap<ResumableSink>,
     pub active_tx_subs: SubscriptionMap<typed::Sink<PubSubResult>>,
-    pub active_log_subs: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+    pub active_log_subs: SubscriptionMap<(ResumableSink, PubSubFilter)>,
+    pub active_account_subs: SubscriptionMap<(typed::Sink<AccountEvent>, HashSet<Address>)>,
 }
 
 impl EthSubscribe {
-    fn assign_id(
-        subscriber: typed::Subscriber<PubSubResult>,
-    ) -> (typed::Sink<PubSubResult>, SubscriptionId) {
+    fn assign_id<T>(subscriber: typed::Subscriber<T>) -> (typed::Sink<T>, SubscriptionId) {
         let id = H128::random();
         let sub_id = SubscriptionId::String(format!("0x{}", hex::encode(id.0)));
         let sink = subscriber.assign_id(sub_id.clone()).unwrap();
         (sink, sub_id)
     }
 
-    fn reject(subscriber: typed::Subscriber<PubSubResult>) {
+    fn reject<T>(subscriber: typed::Subscriber<T>) {
         subscriber
             .reject(Error {
                 code: ErrorCode::InvalidParams,
@@ -49,19 +91,26 @@ impl EthSubscribe {
         let mut tx_subs = self.active_tx_subs.write().unwrap();
         let mut log_subs = self.active_log_subs.write().unwrap();
         match sub_type.as_str() {
-            "newHeads" => {
-                let (sink, id) = Self::assign_id(subscriber);
-                block_subs.insert(id, sink);
-            }
+            "newHeads" => match params
+                .filter(|params| !params.is_null())
+                .map(serde_json::from_value)
+                .transpose()
+            {
+                Ok(params) => {
+                    let (sink, id) = Self::assign_id(subscriber);
+                    block_subs.insert(id, ResumableSink::new(sink, params.unwrap_or_default()));
+                }
+                Err(_) => Self::reject(subscriber),
+            },
             "newPendingTransactions" => {
                 let (sink, id) = Self::assign_id(subscriber);
                 tx_subs.insert(id, sink);
             }
             "logs" => {
-                let filter = params.map(serde_json::from_value).transpose();
-                match filter {
-                    Ok(filter) => {
-                        let filter: PubSubFilter = filter.unwrap_or_default();
+                let params = params.map(serde_json::from_value).transpose();
+                match params {
+                    Ok(params) => {
+                        let LogsSubscriptionParams { filter, resume } = params.unwrap_or_default();
                         if filter
                             .topics
                             .as_ref()
@@ -72,7 +121,7 @@ impl EthSubscribe {
                             Self::reject(subscriber);
                         } else {
                             let (sink, id) = Self::assign_id(subscriber);
-                            log_subs.insert(id, (sink, filter));
+                            log_subs.insert(id, (ResumableSink::new(sink, resume), filter));
                         }
                     }
                     Err(_) => Self::reject(subscriber),
@@ -90,6 +139,37 @@ impl EthSubscribe {
         metrics::gauge!("api.web3.pubsub.active_subscribers", log_subs.len() as f64, "subscription_type" => "logs");
     }
 
+    #[tracing::instrument(skip(self, subscriber))]
+    pub fn sub_account_events(
+        &self,
+        subscriber: typed::Subscriber<AccountEvent>,
+        addresses: Vec<Address>,
+    ) {
+        let mut account_subs = self.active_account_subs.write().unwrap();
+        if addresses.is_empty() || addresses.len() > ACCOUNT_EVENTS_ADDRESSES_LIMIT {
+            Self::reject(subscriber);
+        } else {
+            let (sink, id) = Self::assign_id(subscriber);
+            account_subs.insert(id, (sink, addresses.into_iter().collect()));
+        }
+
+        metrics::gauge!("api.web3.pubsub.active_subscribers", account_subs.len() as f64, "subscription_type" => "account_events");
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn unsub_account_events(&self, id: SubscriptionId) -> Result<bool, Error> {
+        let removed = self.active_account_subs.write().unwrap().remove(&id);
+        if removed.is_some() {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid subscription.".into(),
+                data: None,
+            })
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn unsub(&self, id: SubscriptionId) -> Result<bool, Error> {
         let removed = self
@@ -97,15 +177,10 @@ impl EthSubscribe {
             .write()
             .unwrap()
             .remove(&id)
-            .or_else(|| self.active_tx_subs.write().unwrap().remove(&id))
-            .or_else(|| {
-                self.active_log_subs
-                    .write()
-                    .unwrap()
-                    .remove(&id)
-                    .map(|(sink, _)| sink)
-            });
-        if removed.is_some() {
+            .is_some()
+            || self.active_tx_subs.write().unwrap().remove(&id).is_some()
+            || self.active_log_subs.write().unwrap().remove(&id).is_some();
+        if removed {
             Ok(true)
         } else {
             Err(Error {
@@ -1,9 +1,13 @@
 //! Actual implementation of Web3 API namespaces logic, not tied to the backend
 //! used to create a JSON RPC server.
 
+pub mod admin;
+pub mod debug;
+pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
 
@@ -12,7 +16,8 @@ use zksync_types::U256;
 use zksync_utils::{biguint_to_u256, u256_to_biguint};
 
 pub use self::{
-    eth::EthNamespace, eth_subscribe::EthSubscribe, net::NetNamespace, web3::Web3Namespace,
+    admin::AdminNamespace, debug::DebugNamespace, en::EnNamespace, eth::EthNamespace,
+    eth_subscribe::EthSubscribe, net::NetNamespace, txpool::TxPoolNamespace, web3::Web3Namespace,
     zks::ZksNamespace,
 };
 
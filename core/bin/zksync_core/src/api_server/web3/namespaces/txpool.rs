@@ -0,0 +1,217 @@
+//! Inspection of the transactions waiting in the mempool. The namespace is disabled by default, since it exposes
+//! the pending transactions of all accounts.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use zksync_dal::transactions_web3_dal::MempoolTransaction;
+use zksync_mempool::L2TxFilter;
+use zksync_types::{
+    api::{TxPoolContent, TxPoolStatus, TxPoolStuckReason, TxPoolTransaction},
+    Address, U256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::state::RpcState;
+
+#[derive(Debug, Clone)]
+pub struct TxPoolNamespace {
+    pub state: RpcState,
+}
+
+impl TxPoolNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    /// Returns the mempool transactions of the accounts fitting into `req_entities_limit`. The transactions
+    /// of an account are never split; if some accounts are left out, the content is marked as truncated.
+    #[tracing::instrument(skip(self))]
+    pub async fn content_impl(&self) -> Result<TxPoolContent, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "txpool_content";
+
+        let (mut storage, deadline) = self
+            .state
+            .access_storage_with_deadline(endpoint_name)
+            .await?;
+        let mempool = deadline
+            .run(
+                storage
+                    .transactions_web3_dal()
+                    .get_mempool_transactions(Some(self.state.req_entities_limit)),
+            )
+            .await?;
+        let mut content = group_transactions(mempool.transactions, &mempool.nonces, &self.filter());
+        content.truncated = mempool.is_truncated;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(content)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn status_impl(&self) -> Result<TxPoolStatus, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "txpool_status";
+
+        let (mut storage, deadline) = self
+            .state
+            .access_storage_with_deadline(endpoint_name)
+            .await?;
+        let filter = self.filter();
+        let status = deadline
+            .run(
+                storage
+                    .transactions_web3_dal()
+                    .get_mempool_status(filter.fee_per_gas, filter.gas_per_pubdata.into()),
+            )
+            .await?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(status)
+    }
+
+    fn filter(&self) -> L2TxFilter {
+        let tx_sender = &self.state.tx_sender.0;
+        tx_sender
+            .gas_adjuster
+            .l2_tx_filter(tx_sender.state_keeper_config.fair_l2_gas_price)
+    }
+}
+
+/// Splits the mempool transactions ordered by initiator and nonce into the pending and queued ones.
+/// A transaction is queued if it's stuck itself or follows a stuck transaction of the same account.
+fn group_transactions(
+    transactions: Vec<MempoolTransaction>,
+    nonces: &HashMap<Address, u32>,
+    filter: &L2TxFilter,
+) -> TxPoolContent {
+    let mut content = TxPoolContent::default();
+    let mut current_account = None;
+    let mut expected_nonce = 0;
+    let mut is_blocked = false;
+    for tx in transactions {
+        if current_account != Some(tx.initiator_address) {
+            current_account = Some(tx.initiator_address);
+            expected_nonce = nonces.get(&tx.initiator_address).copied().unwrap_or(0);
+            is_blocked = false;
+        }
+        if tx.nonce < expected_nonce {
+            // The nonce is already used, so the transaction will be rejected by the state keeper.
+            continue;
+        }
+
+        let mut stuck_reasons = vec![];
+        if tx.nonce != expected_nonce {
+            stuck_reasons.push(TxPoolStuckReason::NonceGap);
+        }
+        if tx.max_fee_per_gas < U256::from(filter.fee_per_gas) {
+            stuck_reasons.push(TxPoolStuckReason::InsufficientFee);
+        }
+        if tx.gas_per_pubdata_limit < U256::from(filter.gas_per_pubdata) {
+            stuck_reasons.push(TxPoolStuckReason::InsufficientGasPerPubdata);
+        }
+        if tx.is_quarantined {
+            stuck_reasons.push(TxPoolStuckReason::Quarantined);
+        }
+        is_blocked |= !stuck_reasons.is_empty();
+        // Nonces of an account are unique, so no transaction may follow the one with the max nonce.
+        expected_nonce = tx.nonce.saturating_add(1);
+
+        let txs = if is_blocked {
+            &mut content.queued
+        } else {
+            &mut content.pending
+        };
+        txs.entry(tx.initiator_address).or_default().insert(
+            tx.nonce,
+            TxPoolTransaction {
+                hash: tx.hash,
+                nonce: tx.nonce,
+                max_fee_per_gas: tx.max_fee_per_gas,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+                gas_per_pubdata_limit: tx.gas_per_pubdata_limit,
+                received_at: tx.received_at,
+                stuck_reasons,
+            },
+        );
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::BTreeMap;
+    use zksync_types::H256;
+
+    type TxsByAccount = BTreeMap<Address, BTreeMap<u32, TxPoolTransaction>>;
+
+    fn mempool_tx(initiator: u64, nonce: u32, max_fee_per_gas: u64) -> MempoolTransaction {
+        MempoolTransaction {
+            hash: H256::from_low_u64_be((initiator << 32) | nonce as u64),
+            initiator_address: Address::from_low_u64_be(initiator),
+            nonce,
+            max_fee_per_gas: max_fee_per_gas.into(),
+            max_priority_fee_per_gas: U256::zero(),
+            gas_per_pubdata_limit: 800.into(),
+            received_at: Utc::now(),
+            is_quarantined: false,
+        }
+    }
+
+    fn account_nonces(content: &TxsByAccount, initiator: u64) -> Vec<u32> {
+        content
+            .get(&Address::from_low_u64_be(initiator))
+            .map_or_else(Vec::new, |txs| txs.keys().copied().collect())
+    }
+
+    #[test]
+    fn grouping_transactions() {
+        let filter = L2TxFilter {
+            l1_gas_price: 1,
+            fee_per_gas: 100,
+            gas_per_pubdata: 800,
+        };
+        let transactions = vec![
+            // Account 1: executable transactions followed by a gap.
+            mempool_tx(1, 5, 100),
+            mempool_tx(1, 6, 100),
+            mempool_tx(1, 8, 100),
+            mempool_tx(1, 9, 100),
+            // Account 2: underpriced transaction blocking the next one; stale transaction.
+            mempool_tx(2, 0, 100),
+            mempool_tx(2, 1, 100),
+            mempool_tx(2, 2, 50),
+            mempool_tx(2, 3, 100),
+            // Account 3: transaction with the max nonce.
+            mempool_tx(3, u32::MAX - 1, 100),
+            mempool_tx(3, u32::MAX, 100),
+        ];
+        let nonces = HashMap::from([
+            (Address::from_low_u64_be(1), 5),
+            (Address::from_low_u64_be(2), 1),
+            (Address::from_low_u64_be(3), u32::MAX - 1),
+        ]);
+
+        let content = group_transactions(transactions, &nonces, &filter);
+        assert_eq!(account_nonces(&content.pending, 1), [5, 6]);
+        assert_eq!(account_nonces(&content.queued, 1), [8, 9]);
+        assert_eq!(account_nonces(&content.pending, 2), [1]);
+        assert_eq!(account_nonces(&content.queued, 2), [2, 3]);
+        assert_eq!(
+            account_nonces(&content.pending, 3),
+            [u32::MAX - 1, u32::MAX]
+        );
+
+        let account = |initiator| &content.queued[&Address::from_low_u64_be(initiator)];
+        assert_eq!(account(1)[&8].stuck_reasons, [TxPoolStuckReason::NonceGap]);
+        assert!(account(1)[&9].stuck_reasons.is_empty());
+        assert_eq!(
+            account(2)[&2].stuck_reasons,
+            [TxPoolStuckReason::InsufficientFee]
+        );
+        assert!(account(2)[&3].stuck_reasons.is_empty());
+    }
+}
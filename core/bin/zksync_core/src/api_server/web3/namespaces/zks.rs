@@ -1,32 +1,49 @@
 use bigdecimal::{BigDecimal, Zero};
+use num::BigInt;
 use std::time::Instant;
-use std::{collections::HashMap, convert::TryInto};
+use std::{collections::HashMap, convert::TryInto, ops::RangeInclusive};
 
-use zksync_mini_merkle_tree::mini_merkle_tree_proof;
+use zksync_config::configs::api::PendingTxField;
+use zksync_dal::StorageProcessor;
 use zksync_types::{
-    api::{BridgeAddresses, GetLogsFilter, L2ToL1LogProof, TransactionDetails, U64},
-    commitment::CommitmentSerializable,
-    explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    abi_decoding::{decode_log, decode_transaction_input, DecodedTransactionInput},
+    api::{
+        AccountStateAtTimestamp, AccountStorageProof, AccountSummary, AccountTransactionsPage,
+        AccountTxDirection, AverageTps, BlockAtTimestamp, BlockDependencyGraph, BlockId,
+        BlockNumber, BridgeAddresses, ChainStats, ExpiredPriorityOp, FeeEstimate, GetLogsFilter,
+        L1BatchL1Event, L2ToL1LogProof, L2TokenInfo, LogCursor, LogsPage, NodeCapabilities,
+        PendingTransaction, PendingTransactionsPage, PendingTxCursor, StateDiffPage,
+        StateSnapshotDiffInfo, StorageProof, SystemContext, TransactionDetails, TransactionReceipt,
+        TransactionValidationResult, TransferCursor, TransfersPage, TxCursor, U64,
+    },
+    explorer_api::{BalanceItem, BlockDetails, L1BatchDetails},
     l1::L1Tx,
-    l2_to_l1_log::L2ToL1Log,
-    tokens::ETHEREUM_ADDRESS,
+    l1_calldata::{decode_l1_calldata, DecodedL1Calldata, L1BatchCommitmentData},
+    tokens::{TokenInfo, ETHEREUM_ADDRESS},
     transaction_request::{l2_tx_from_call_req, CallRequest},
+    utils::decompose_full_nonce,
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace},
-    L1BatchNumber, MiniblockNumber, Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
-    MAX_GAS_PER_PUBDATA_BYTE, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
+    AccountTreeId, Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, PriorityOpId, StorageKey,
+    Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, MAX_GAS_PER_PUBDATA_BYTE,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
 };
-use zksync_utils::address_to_h256;
+use zksync_utils::{address_to_h256, u256_to_biguint};
 use zksync_web3_decl::{
     error::Web3Error,
-    types::{Address, Token, H256},
+    types::{Address, Filter, Token, H256},
 };
 
-use crate::api_server::web3::{backend_jsonrpc::error::internal_error, RpcState};
+use vm::oracles::tracer::ValidationError;
+
+use crate::api_server::web3::{
+    backend_jsonrpc::error::internal_error, namespaces::eth::EthNamespace, RpcState,
+};
 use crate::fee_ticker::{error::TickerError, TokenPriceRequestType};
 
-#[cfg(feature = "openzeppelin_tests")]
-use zksync_types::Bytes;
+/// Time windows for which the average TPS is reported by `zks_getChainStats`: 1 hour, 1 day and 1 week.
+const CHAIN_STATS_TPS_WINDOWS_SEC: [u64; 3] = [60 * 60, 24 * 60 * 60, 7 * 24 * 60 * 60];
+/// Number of the token balances returned by `zks_getAccountSummary` if the limit isn't specified.
+const DEFAULT_ACCOUNT_SUMMARY_TOKENS_LIMIT: u8 = 10;
 
 #[derive(Debug, Clone)]
 pub struct ZksNamespace {
@@ -39,16 +56,15 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self, request))]
-    pub fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+    pub fn estimate_fee_impl(&self, request: CallRequest) -> Result<FeeEstimate, Web3Error> {
         let start = Instant::now();
 
         let mut tx = l2_tx_from_call_req(request, self.state.config.api.web3_json_rpc.max_tx_size)?;
 
         // When we're estimating fee, we are trying to deduce values related to fee, so we should
         // not consider provided ones.
-        let fair_l2_gas_price = self.state.tx_sender.0.state_keeper_config.fair_l2_gas_price;
-        tx.common_data.fee.max_fee_per_gas = fair_l2_gas_price.into();
-        tx.common_data.fee.max_priority_fee_per_gas = fair_l2_gas_price.into();
+        tx.common_data.fee.max_fee_per_gas = self.state.tx_sender.gas_price();
+        tx.common_data.fee.max_priority_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
         tx.common_data.fee.gas_per_pubdata_limit = MAX_GAS_PER_PUBDATA_BYTE.into();
 
         let fee = self.estimate_fee(tx.into())?;
@@ -74,10 +90,10 @@ impl ZksNamespace {
         let fee = self.estimate_fee(tx.into())?;
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => "estimate_gas_l1_to_l2");
-        Ok(fee.gas_limit)
+        Ok(fee.fee.gas_limit)
     }
 
-    fn estimate_fee(&self, tx: Transaction) -> Result<Fee, Web3Error> {
+    fn estimate_fee(&self, tx: Transaction) -> Result<FeeEstimate, Web3Error> {
         let scale_factor = self
             .state
             .config
@@ -91,11 +107,12 @@ impl ZksNamespace {
             .web3_json_rpc
             .estimate_gas_acceptable_overestimation;
 
-        let fee = self
-            .state
-            .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
-            .map_err(|err| Web3Error::SubmitTransactionError(err.to_string()))?;
+        let fee = self.state.tx_sender.get_txs_fee_in_wei(
+            tx,
+            BlockId::Number(BlockNumber::Pending),
+            scale_factor,
+            acceptable_overestimation,
+        )?;
 
         Ok(fee)
     }
@@ -138,19 +155,103 @@ impl ZksNamespace {
             .into_iter()
             .skip(from as usize)
             .take(limit.into())
-            .map(|token_info| Token {
-                l1_address: token_info.l1_address,
-                l2_address: token_info.l2_address,
-                name: token_info.metadata.name,
-                symbol: token_info.metadata.symbol,
-                decimals: token_info.metadata.decimals,
-            })
+            .map(token_from_info)
+            .collect();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(tokens)
+    }
+
+    /// Returns the bridged tokens ordered by the L1 address. Besides `from` (an offset), the page can be
+    /// selected by `after`, the L1 address of the last token of the previous page; unlike offsets, it stays
+    /// valid if new tokens are bridged mid-pagination.
+    #[tracing::instrument(skip(self))]
+    pub fn get_bridged_tokens_impl(
+        &self,
+        from: u32,
+        limit: u8,
+        after: Option<Address>,
+    ) -> Result<Vec<Token>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_bridged_tokens";
+
+        let tokens = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .tokens_web3_dal()
+            .get_bridged_tokens(from, limit.into(), after)
+            .map_err(|err| internal_error(endpoint_name, err))?
+            .into_iter()
+            .map(token_from_info)
             .collect();
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
         Ok(tokens)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_bridged_token_by_l1_address_impl(
+        &self,
+        l1_address: Address,
+    ) -> Result<Option<Token>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_bridged_token_by_l1_address";
+
+        let token = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .tokens_web3_dal()
+            .get_token_by_l1_address(&l1_address)
+            .map_err(|err| internal_error(endpoint_name, err))?
+            .map(token_from_info);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(token)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_bridged_token_by_l2_address_impl(
+        &self,
+        l2_address: Address,
+    ) -> Result<Option<Token>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_bridged_token_by_l2_address";
+
+        let token = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .tokens_web3_dal()
+            .get_token_by_l2_address(&l2_address)
+            .map_err(|err| internal_error(endpoint_name, err))?
+            .map(token_from_info);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(token)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_token_info_impl(
+        &self,
+        l2_address: Address,
+    ) -> Result<Option<L2TokenInfo>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_token_info";
+
+        let token_info = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .tokens_web3_dal()
+            .get_l2_token_info(&l2_address)
+            .map_err(|err| internal_error(endpoint_name, err));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        token_info
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn get_token_price_impl(&self, l2_token: Address) -> Result<BigDecimal, Web3Error> {
         let start = Instant::now();
@@ -286,30 +387,28 @@ impl ZksNamespace {
             .map_err(|err| internal_error(endpoint_name, err))?
             .expect("L1 batch should contain at least one miniblock");
 
-        let all_l1_logs_in_block = storage
-            .blocks_web3_dal()
-            .get_l2_to_l1_logs(l1_batch_number)
+        let log_tree = self
+            .state
+            .log_tree_cache
+            .get(&mut storage, l1_batch_number)
             .map_err(|err| internal_error(endpoint_name, err))?;
 
         // Position of l1 log in block relative to logs with identical data
         let l1_log_relative_position = if let Some(l2_log_position) = l2_log_position {
-            let pos = storage
-                .events_web3_dal()
-                .get_logs(
-                    GetLogsFilter {
-                        from_block: first_miniblock_of_l1_batch,
-                        to_block: Some(block_number.0.into()),
-                        addresses: vec![L1_MESSENGER_ADDRESS],
-                        topics: vec![(2, vec![address_to_h256(&sender)]), (3, vec![msg])],
-                    },
-                    self.state.req_entities_limit,
-                )
-                .map_err(|err| internal_error(endpoint_name, err))?
-                .iter()
-                .position(|event| {
-                    event.block_number == Some(block_number.0.into())
-                        && event.log_index == Some(l2_log_position.into())
-                });
+            let logs = async_std::task::block_on(storage.events_web3_dal().get_logs(
+                GetLogsFilter {
+                    from_block: first_miniblock_of_l1_batch,
+                    to_block: Some(block_number.0.into()),
+                    addresses: vec![L1_MESSENGER_ADDRESS],
+                    topics: vec![(2, vec![address_to_h256(&sender)]), (3, vec![msg])],
+                },
+                self.state.req_entities_limit,
+            ))
+            .map_err(|err| internal_error(endpoint_name, err))?;
+            let pos = logs.iter().position(|event| {
+                event.block_number == Some(block_number.0.into())
+                    && event.log_index == Some(l2_log_position.into())
+            });
             match pos {
                 Some(pos) => pos,
                 None => {
@@ -320,7 +419,8 @@ impl ZksNamespace {
             0
         };
 
-        let l1_log_index = match all_l1_logs_in_block
+        let l1_log_index = match log_tree
+            .logs
             .iter()
             .enumerate()
             .filter(|(_, log)| {
@@ -335,25 +435,7 @@ impl ZksNamespace {
                 return Ok(None);
             }
         };
-        let values: Vec<Vec<u8>> = all_l1_logs_in_block
-            .into_iter()
-            .map(|a| a.to_bytes())
-            .collect();
-        let mut proof: Vec<H256> = mini_merkle_tree_proof(
-            values,
-            l1_log_index,
-            L2ToL1Log::SERIALIZED_SIZE,
-            L2ToL1Log::limit_per_block(),
-        )
-        .into_iter()
-        .map(|elem| H256::from_slice(&elem))
-        .collect();
-        let root = proof.pop().unwrap();
-        let msg_proof = L2ToL1LogProof {
-            proof,
-            root,
-            id: l1_log_index as u32,
-        };
+        let msg_proof = log_tree.proof(l1_log_index);
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
         Ok(Some(msg_proof))
     }
@@ -377,12 +459,14 @@ impl ZksNamespace {
             None => return Ok(None),
         };
 
-        let all_l1_logs_in_block = storage
-            .blocks_web3_dal()
-            .get_l2_to_l1_logs(l1_batch_number)
+        let log_tree = self
+            .state
+            .log_tree_cache
+            .get(&mut storage, l1_batch_number)
             .map_err(|err| internal_error(endpoint_name, err))?;
 
-        let l1_log_index = match all_l1_logs_in_block
+        let l1_log_index = match log_tree
+            .logs
             .iter()
             .enumerate()
             .filter(|(_, log)| log.tx_number_in_block == l1_batch_tx_index)
@@ -394,29 +478,128 @@ impl ZksNamespace {
             }
         };
 
-        let values: Vec<Vec<u8>> = all_l1_logs_in_block
+        let msg_proof = log_tree.proof(l1_log_index);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(Some(msg_proof))
+    }
+
+    /// Batched `eth_getStorageAt`: returns the values of the storage slots of the account in the order of `keys`.
+    #[tracing::instrument(skip(self, keys))]
+    pub fn get_storage_values_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockId>,
+    ) -> Result<Vec<H256>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_storage_values";
+
+        if keys.len() > self.state.req_entities_limit {
+            return Err(Web3Error::ResultLimitExceeded(
+                self.state.req_entities_limit,
+            ));
+        }
+        let block = block.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let storage_keys: Vec<_> = keys
             .into_iter()
-            .map(|a| a.to_bytes())
+            .map(|key| StorageKey::new(AccountTreeId::new(address), key))
             .collect();
-        let mut proof: Vec<H256> = mini_merkle_tree_proof(
-            values,
-            l1_log_index,
-            L2ToL1Log::SERIALIZED_SIZE,
-            L2ToL1Log::limit_per_block(),
-        )
-        .into_iter()
-        .map(|elem| H256::from_slice(&elem))
-        .collect();
-        let root = proof.pop().unwrap();
-
-        let msg_proof = L2ToL1LogProof {
-            proof,
-            root,
-            id: l1_log_index as u32,
+        let values = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .storage_web3_dal()
+            .get_historical_values(&storage_keys, block)
+            .map_err(|err| internal_error(endpoint_name, err))?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        values
+    }
+
+    /// Returns the Merkle proofs of the values of the storage slots of the account after the L1 batch.
+    /// The proofs are only available on the nodes that have access to the state tree, and only for the latest
+    /// L1 batch processed by the tree, or for the recent ones if the tree keeps the history of its versions
+    /// (see `DBConfig::merkle_tree_history_enabled`); `None` is returned otherwise.
+    #[tracing::instrument(skip(self, keys))]
+    pub fn get_proof_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<AccountStorageProof>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_proof";
+
+        if keys.len() > self.state.req_entities_limit {
+            return Err(Web3Error::ResultLimitExceeded(
+                self.state.req_entities_limit,
+            ));
+        }
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let root_hash = match storage
+            .blocks_web3_dal()
+            .get_l1_batch_root_hash(l1_batch_number)
+            .map_err(|err| internal_error(endpoint_name, err))?
+        {
+            Some(root_hash) => root_hash,
+            None => return Ok(None),
+        };
+        let (_, last_miniblock_number) = match storage
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .map_err(|err| internal_error(endpoint_name, err))?
+        {
+            Some(range) => range,
+            None => return Ok(None),
         };
 
+        let storage_keys: Vec<_> = keys
+            .iter()
+            .map(|key| StorageKey::new(AccountTreeId::new(address), *key))
+            .collect();
+        let tree_keys: Vec<_> = storage_keys
+            .iter()
+            .map(StorageKey::hashed_key_u256)
+            .collect();
+        let proofs =
+            match self
+                .state
+                .merkle_tree_reader
+                .get_proofs(l1_batch_number, root_hash, &tree_keys)
+            {
+                Some(proofs) => proofs,
+                None => return Ok(None),
+            };
+
+        let storage_proof = storage_keys
+            .iter()
+            .zip(proofs)
+            .map(|(storage_key, proof)| {
+                let value = storage
+                    .storage_web3_dal()
+                    .get_historical_value_unchecked(storage_key, last_miniblock_number)
+                    .map_err(|err| internal_error(endpoint_name, err))?;
+                Ok(StorageProof {
+                    key: *storage_key.key(),
+                    value,
+                    index: proof.leaf_index,
+                    proof: proof
+                        .merkle_path
+                        .iter()
+                        .map(|hash| H256::from_slice(hash))
+                        .collect(),
+                })
+            })
+            .collect::<Result<_, Web3Error>>()?;
+
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
-        Ok(Some(msg_proof))
+        Ok(Some(AccountStorageProof {
+            address,
+            l1_batch_number,
+            root_hash,
+            storage_proof,
+        }))
     }
 
     #[tracing::instrument(skip(self))]
@@ -481,19 +664,24 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_raw_block_transactions_impl(
+    pub async fn get_raw_block_transactions_impl(
         &self,
         block_number: MiniblockNumber,
     ) -> Result<Vec<zksync_types::Transaction>, Web3Error> {
         let start = Instant::now();
         let endpoint_name = "get_raw_block_transactions";
 
+        // The external node fetcher syncs miniblocks via this method, so all the transactions of the miniblock
+        // are returned regardless of `req_entities_limit`; the number of transactions in a miniblock is bounded
+        // by the state keeper anyway.
         let transactions = self
             .state
             .connection_pool
-            .access_storage_blocking()
+            .access_storage()
+            .await
             .transactions_web3_dal()
-            .get_raw_miniblock_transactions(block_number)
+            .get_raw_miniblock_transactions(block_number, None)
+            .await
             .map_err(|err| internal_error(endpoint_name, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
@@ -502,7 +690,31 @@ impl ZksNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn get_transaction_details_impl(
+    pub async fn get_block_receipts_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<TransactionReceipt>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_block_receipts";
+
+        let mut receipts = self
+            .state
+            .connection_pool
+            .access_storage()
+            .await
+            .transactions_web3_dal()
+            .get_transaction_receipts_for_miniblock(block_number)
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        self.state
+            .set_receipts_finality(&mut receipts, endpoint_name)?;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(receipts)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transaction_details_impl(
         &self,
         hash: H256,
     ) -> Result<Option<TransactionDetails>, Web3Error> {
@@ -512,9 +724,11 @@ impl ZksNamespace {
         let tx_details = self
             .state
             .connection_pool
-            .access_storage_blocking()
+            .access_storage()
+            .await
             .transactions_web3_dal()
             .get_transaction_details(hash)
+            .await
             .map_err(|err| internal_error(endpoint_name, err));
 
         metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
@@ -522,6 +736,14 @@ impl ZksNamespace {
         tx_details
     }
 
+    /// Same as `eth_getRawTransactionByHash`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_raw_transaction_impl(&self, hash: H256) -> Result<Option<Bytes>, Web3Error> {
+        EthNamespace::new(self.state.clone())
+            .get_raw_transaction_by_hash_impl(hash)
+            .await
+    }
+
     #[tracing::instrument(skip(self))]
     pub fn get_l1_batch_details_impl(
         &self,
@@ -544,6 +766,791 @@ impl ZksNamespace {
         l1_batch
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_node_capabilities_impl(&self) -> Result<NodeCapabilities, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_node_capabilities";
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let earliest_block = storage
+            .blocks_web3_dal()
+            .get_earliest_miniblock_number()
+            .map_err(|err| internal_error(endpoint_name, err))?
+            .unwrap_or(MiniblockNumber(0));
+        let latest_block = storage
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let capabilities = NodeCapabilities::new(
+            self.state.config.api.web3_json_rpc.node_personality(),
+            earliest_block,
+            latest_block,
+        );
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(capabilities)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn decode_l1_calldata_impl(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<DecodedL1Calldata>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "decode_l1_calldata";
+
+        let eth_tx = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .eth_sender_dal()
+            .get_eth_tx_by_hash(tx_hash);
+        let decoded = eth_tx
+            .map(|eth_tx| decode_l1_calldata(&eth_tx.raw_tx))
+            .transpose()
+            .map_err(|err| internal_error(endpoint_name, err));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        decoded
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_l1_batch_commitment_data_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchCommitmentData>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_l1_batch_commitment_data";
+
+        // The genesis batch isn't committed to L1.
+        let commitment_data = match batch_number.0.checked_sub(1) {
+            Some(last_committed_batch_number) => {
+                let mut storage = self.state.connection_pool.access_storage_blocking();
+                let mut blocks_dal = storage.blocks_dal();
+                let last_committed_batch =
+                    blocks_dal.get_block_metadata(L1BatchNumber(last_committed_batch_number));
+                let batch = blocks_dal.get_block_metadata(batch_number);
+                match (last_committed_batch, batch) {
+                    (Some(last_committed_batch), Some(batch)) => {
+                        L1BatchCommitmentData::new(last_committed_batch, batch)
+                            .map(Some)
+                            .map_err(|err| internal_error(endpoint_name, err))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            None => Ok(None),
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        commitment_data
+    }
+
+    #[tracing::instrument(skip(self, payload))]
+    pub fn send_encrypted_transaction_impl(&self, payload: Bytes) -> Result<H256, Web3Error> {
+        let start = Instant::now();
+
+        let submit_res = self
+            .state
+            .tx_sender
+            .submit_encrypted_tx(payload.0)
+            .map_err(|err| {
+                vlog::debug!("Send encrypted transaction error {}", err);
+                metrics::counter!(
+                    "api.submit_tx_error",
+                    1,
+                    "reason" => err.grafana_error_code()
+                );
+                err.into()
+            });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => "send_encrypted_transaction");
+        submit_res
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_chain_stats_impl(&self) -> Result<Option<ChainStats>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_chain_stats";
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let totals = match storage.chain_stats_dal().get_last_chain_stats_checkpoint() {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(None),
+        };
+        let average_tps = CHAIN_STATS_TPS_WINDOWS_SEC
+            .iter()
+            .map(|&window_sec| {
+                let window_start = totals.timestamp.saturating_sub(window_sec);
+                let tps = storage
+                    .chain_stats_dal()
+                    .get_chain_stats_checkpoint_at(window_start)
+                    .map_or(0.0, |earlier| totals.tps_since(&earlier));
+                AverageTps { window_sec, tps }
+            })
+            .collect();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(Some(ChainStats {
+            totals,
+            average_tps,
+        }))
+    }
+
+    /// Decodes the calldata of the transaction using the ABI of the called contract. Returns `None`
+    /// if the transaction is not found, the contract isn't verified or the calldata doesn't match its ABI.
+    #[tracing::instrument(skip(self))]
+    pub async fn decode_transaction_input_impl(
+        &self,
+        tx_hash: H256,
+        include_events: bool,
+    ) -> Result<Option<DecodedTransactionInput>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "decode_transaction_input";
+
+        let mut storage = self.state.connection_pool.access_storage().await;
+        let transaction = storage
+            .transactions_web3_dal()
+            .get_transaction(
+                tx_hash.into(),
+                L2ChainId(self.state.config.chain.eth.zksync_network_id),
+            )
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let (contract_address, calldata) = match transaction {
+            Some(transaction) => match transaction.to {
+                Some(to) => (to, transaction.input),
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+        let contract = match self.state.abi_cache.get(contract_address) {
+            Some(contract) => contract,
+            None => return Ok(None),
+        };
+        let mut decoded = match decode_transaction_input(&contract, contract_address, &calldata.0) {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                vlog::debug!(
+                    "Failed to decode input of transaction {:?}: {}",
+                    tx_hash,
+                    err
+                );
+                return Ok(None);
+            }
+        };
+
+        if include_events {
+            let receipt = storage
+                .transactions_web3_dal()
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(|err| internal_error(endpoint_name, err))?;
+            let logs = receipt.map(|receipt| receipt.logs).unwrap_or_default();
+            let events = logs
+                .iter()
+                .filter_map(|log| {
+                    let contract = self.state.abi_cache.get(log.address)?;
+                    decode_log(&contract, log)
+                })
+                .collect();
+            decoded.events = Some(events);
+        }
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(Some(decoded))
+    }
+
+    /// Returns the account state needed by wallets on load in a single call.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_account_summary_impl(
+        &self,
+        address: Address,
+        tokens_limit: Option<u8>,
+    ) -> Result<AccountSummary, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_account_summary";
+        let tokens_limit = tokens_limit.unwrap_or(DEFAULT_ACCOUNT_SUMMARY_TOKENS_LIMIT) as usize;
+
+        let mut storage = self.state.connection_pool.access_storage().await;
+        let nonce = storage
+            .storage_web3_dal()
+            .get_address_historical_nonce(address, BlockId::Number(BlockNumber::Latest))
+            .map_err(|err| internal_error(endpoint_name, err))??;
+        let pending_nonce = storage
+            .transactions_web3_dal()
+            .next_nonce_by_initiator_account(address)
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let pending_transactions_count = storage
+            .transactions_web3_dal()
+            .get_pending_txs_count_by_initiator_account(address)
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let last_activity_timestamp = storage
+            .transactions_web3_dal()
+            .get_last_activity_timestamp_by_initiator_account(address)
+            .await
+            .map_err(|err| internal_error(endpoint_name, err))?;
+
+        let mut balances = storage
+            .explorer()
+            .accounts_dal()
+            .get_balances_for_address(address)
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let eth_balance = balances
+            .remove(&L2_ETH_TOKEN_ADDRESS)
+            .map_or_else(U256::zero, |item| item.balance);
+        // Tokens without a known price go last.
+        let mut token_balances: Vec<_> = balances
+            .into_iter()
+            .map(|(_, item)| (balance_usd_value(&item), item))
+            .collect();
+        token_balances.sort_by(|(value, item), (other_value, other_item)| {
+            other_value.cmp(value).then_with(|| {
+                item.token_info
+                    .l2_address
+                    .cmp(&other_item.token_info.l2_address)
+            })
+        });
+        let token_balances = token_balances
+            .into_iter()
+            .take(tokens_limit)
+            .map(|(_, item)| item)
+            .collect();
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(AccountSummary {
+            address,
+            nonce,
+            pending_nonce,
+            eth_balance,
+            token_balances,
+            pending_transactions_count,
+            last_activity_timestamp,
+        })
+    }
+
+    /// Returns `None` if the access sets weren't recorded for the miniblock.
+    #[tracing::instrument(skip(self))]
+    pub fn get_block_dependency_graph_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<BlockDependencyGraph>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_block_dependency_graph";
+
+        let access_sets = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .tx_access_sets_dal()
+            .get_access_sets(block_number);
+        let graph = (!access_sets.is_empty())
+            .then(|| BlockDependencyGraph::new(block_number, &access_sets));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(graph)
+    }
+
+    /// Runs the account and paymaster validation of a signed transaction without executing it,
+    /// so that the validation failures can be debugged without using the nonce.
+    #[tracing::instrument(skip(self, tx_bytes))]
+    pub fn validate_transaction_impl(
+        &self,
+        tx_bytes: Bytes,
+    ) -> Result<TransactionValidationResult, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "validate_transaction";
+
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+        let report = self.state.tx_sender.validate_tx(&tx)?;
+
+        let (revert_reason, violated_rule) = match &report.result {
+            Ok(()) => (None, None),
+            Err(ValidationError::FailedTx(revert_reason)) => {
+                (Some(revert_reason.revert_reason.to_string()), None)
+            }
+            Err(ValidationError::VioalatedRule(rule)) => (None, Some(rule.to_string())),
+        };
+        let has_paymaster = tx.common_data.paymaster_params.paymaster != Address::zero();
+        let result = TransactionValidationResult {
+            success: report.result.is_ok(),
+            revert_reason,
+            violated_rule,
+            gas_used: report.gas_used.into(),
+            computational_gas_used: report.computational_gas_used.into(),
+            computational_gas_limit: self
+                .state
+                .tx_sender
+                .0
+                .state_keeper_config
+                .validation_computational_gas_limit
+                .into(),
+            paymaster_context: has_paymaster.then(|| report.paymaster_context.into()),
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(result)
+    }
+
+    /// Returns the priority operations which weren't executed on L1 until their deadline,
+    /// starting from the serial ID `from`.
+    #[tracing::instrument(skip(self))]
+    pub fn get_expired_priority_ops_impl(
+        &self,
+        from: u64,
+        limit: u8,
+    ) -> Result<Vec<ExpiredPriorityOp>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_expired_priority_ops";
+
+        let ops = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .expired_priority_ops_dal()
+            .get_expired_priority_ops(PriorityOpId(from), limit.into())
+            .map_err(|err| internal_error(endpoint_name, err));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        ops
+    }
+
+    /// Returns the commit, prove and execute events of the L1 batch indexed from L1.
+    #[tracing::instrument(skip(self))]
+    pub fn get_l1_batch_l1_events_impl(
+        &self,
+        batch: L1BatchNumber,
+    ) -> Result<Vec<L1BatchL1Event>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_l1_batch_l1_events";
+
+        let events = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .l1_batch_l1_events_dal()
+            .get_l1_batch_l1_events(batch)
+            .map_err(|err| internal_error(endpoint_name, err));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        events
+    }
+
+    /// Returns the bytecode of a contract or another factory dependency known to the node by its hash.
+    #[tracing::instrument(skip(self))]
+    pub fn get_bytecode_by_hash_impl(&self, hash: H256) -> Option<Vec<u8>> {
+        let start = Instant::now();
+        let endpoint_name = "get_bytecode_by_hash";
+
+        let bytecode = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .storage_dal()
+            .get_factory_dep(hash);
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        bytecode
+    }
+
+    /// Returns the storage slots changed in the block, paginated by the hashed keys of the slots.
+    /// `limit` is capped by the max number of entities returned by a request.
+    #[tracing::instrument(skip(self))]
+    pub fn get_state_diff_impl(
+        &self,
+        block_number: MiniblockNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<Option<StateDiffPage>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_state_diff";
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let page = storage
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .map_err(|err| internal_error(endpoint_name, err))
+            .and_then(|sealed_miniblock| {
+                if block_number > sealed_miniblock {
+                    return Ok(None);
+                }
+                self.get_state_diff_page(
+                    endpoint_name,
+                    &mut storage,
+                    block_number..=block_number,
+                    after,
+                    limit,
+                )
+                .map(Some)
+            });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        page
+    }
+
+    /// Same as `get_state_diff_impl`, but for all the blocks of the L1 batch.
+    #[tracing::instrument(skip(self))]
+    pub fn get_l1_batch_state_diff_impl(
+        &self,
+        batch: L1BatchNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<Option<StateDiffPage>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_l1_batch_state_diff";
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let page = storage
+            .blocks_web3_dal()
+            .get_miniblock_range_of_l1_batch(batch)
+            .map_err(|err| internal_error(endpoint_name, err))
+            .and_then(|miniblock_range| {
+                let Some((first_miniblock, last_miniblock)) = miniblock_range else {
+                    return Ok(None);
+                };
+                self.get_state_diff_page(
+                    endpoint_name,
+                    &mut storage,
+                    first_miniblock..=last_miniblock,
+                    after,
+                    limit,
+                )
+                .map(Some)
+            });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        page
+    }
+
+    /// Returns the state snapshot diff moving the state of a node the furthest. `l1_batch_number` is the next
+    /// L1 batch to be processed by the node. Diffs past the last sealed L1 batch (e.g., after a revert) are not returned.
+    /// The node is expected to apply the returned diffs one by one until there are none left.
+    #[tracing::instrument(skip(self))]
+    pub fn get_state_snapshot_diff_impl(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<StateSnapshotDiffInfo>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_state_snapshot_diff";
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let max_l1_batch_number = storage.blocks_dal().get_sealed_block_number() + 1;
+        let diff = storage
+            .state_snapshot_diffs_dal()
+            .get_diff_to_apply(l1_batch_number, max_l1_batch_number)
+            .and_then(|diff| {
+                Some(StateSnapshotDiffInfo {
+                    base_l1_batch_number: diff.base_l1_batch_number,
+                    l1_batch_number: diff.l1_batch_number,
+                    first_miniblock_number: diff.first_miniblock_number,
+                    root_hash: diff.root_hash?,
+                    object_key: diff.object_key,
+                })
+            });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(diff)
+    }
+
+    /// Returns a page of the logs matching the filter, oldest first. Unlike `eth_getLogs`, the queries
+    /// matching too many logs are not rejected; the remaining logs are requested using the returned cursor.
+    #[tracing::instrument(skip(self, filter))]
+    pub async fn get_logs_paginated_impl(
+        &self,
+        mut filter: Filter,
+        after: Option<LogCursor>,
+        limit: Option<u16>,
+    ) -> Result<LogsPage, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_logs_paginated";
+
+        if let Some(watermark) = after.and_then(|after| after.watermark) {
+            // The range is bounded by the one resolved for the first page, so it doesn't grow mid-pagination.
+            filter.to_block = Some(BlockNumber::Number(match filter.to_block {
+                Some(BlockNumber::Number(number)) => number.min(watermark.0.into()),
+                _ => watermark.0.into(),
+            }));
+        }
+        let (from_block, to_block) = self.state.resolve_logs_block_range(&filter)?;
+        filter.to_block = Some(BlockNumber::Number(to_block.0.into()));
+        let get_logs_filter = EthNamespace::get_logs_filter(&filter, from_block)?;
+        let max_limit = self.state.config.api.web3_json_rpc.logs_result_limit();
+        let limit = limit.map_or(max_limit, |limit| usize::from(limit).min(max_limit));
+
+        let (mut storage, deadline) = self
+            .state
+            .access_storage_with_deadline("zks_getLogsPaginated")
+            .await?;
+        let may_contain_logs = deadline
+            .run(storage.events_web3_dal().may_contain_logs(&get_logs_filter))
+            .await?;
+        let logs = if may_contain_logs {
+            deadline
+                .run(
+                    storage
+                        .events_web3_dal()
+                        .get_logs_after(get_logs_filter, after, limit),
+                )
+                .await?
+        } else {
+            vec![]
+        };
+        let next_after = if logs.len() == limit {
+            logs.last().map(|log| LogCursor {
+                block_number: MiniblockNumber(log.block_number.unwrap().as_u32()),
+                log_index: log.log_index.unwrap().as_u32(),
+                watermark: Some(to_block),
+            })
+        } else {
+            None
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(LogsPage { logs, next_after })
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_system_context_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<SystemContext>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_system_context";
+
+        let system_context = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .blocks_web3_dal()
+            .get_miniblock_system_context(block_number)
+            .map_err(|err| internal_error(endpoint_name, err));
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        system_context
+    }
+
+    /// Returns a page of the transactions sent by and / or to the account, newest first.
+    /// All the transactions of the account are returned if `direction` isn't specified.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transactions_by_address_impl(
+        &self,
+        address: Address,
+        direction: Option<AccountTxDirection>,
+        before: Option<TxCursor>,
+        limit: Option<u16>,
+    ) -> Result<AccountTransactionsPage, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_transactions_by_address";
+
+        let max_limit = self.state.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| usize::from(limit).min(max_limit));
+        let (mut storage, deadline) = self
+            .state
+            .access_storage_with_deadline("zks_getTransactionsByAddress")
+            .await?;
+        let transactions = deadline
+            .run(storage.transactions_web3_dal().get_transactions_by_account(
+                address,
+                direction.unwrap_or(AccountTxDirection::All),
+                before,
+                limit,
+                L2ChainId(self.state.config.chain.eth.zksync_network_id),
+            ))
+            .await?;
+        let next_before = if transactions.len() == limit {
+            transactions.last().map(|tx| TxCursor {
+                block_number: MiniblockNumber(tx.block_number.unwrap().as_u32()),
+                index_in_block: tx.transaction_index.unwrap().as_u32(),
+            })
+        } else {
+            None
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(AccountTransactionsPage {
+            transactions,
+            next_before,
+        })
+    }
+
+    /// Returns the pending transactions in the order they were received, with the fields configured
+    /// by the operator redacted.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_pending_transactions_impl(
+        &self,
+        after: Option<PendingTxCursor>,
+        limit: Option<u16>,
+    ) -> Result<PendingTransactionsPage, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_pending_transactions";
+
+        let api_config = &self.state.config.api.web3_json_rpc;
+        if !api_config.pending_transactions_api_enabled() {
+            return Err(Web3Error::NotImplemented);
+        }
+        let max_limit = self.state.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| usize::from(limit).min(max_limit));
+        let (mut storage, deadline) = self
+            .state
+            .access_storage_with_deadline("zks_getPendingTransactions")
+            .await?;
+        let mut transactions = deadline
+            .run(
+                storage
+                    .transactions_web3_dal()
+                    .get_pending_transactions(after, limit),
+            )
+            .await?;
+        let redacted_fields = api_config.pending_transactions_redacted_fields();
+        for tx in &mut transactions {
+            redact_pending_transaction(tx, &redacted_fields);
+        }
+        let next_after = if transactions.len() == limit {
+            transactions.last().map(|tx| PendingTxCursor {
+                received_at: tx.received_at,
+                hash: tx.hash,
+            })
+        } else {
+            None
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(PendingTransactionsPage {
+            transactions,
+            next_after,
+        })
+    }
+
+    /// Returns a page of the ETH and ERC-20 token transfers from or to the account, newest first.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transfers_impl(
+        &self,
+        address: Address,
+        before: Option<TransferCursor>,
+        limit: Option<u16>,
+    ) -> Result<TransfersPage, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_transfers";
+
+        let max_limit = self.state.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| usize::from(limit).min(max_limit));
+        let (mut storage, deadline) = self
+            .state
+            .access_storage_with_deadline("zks_getTransfers")
+            .await?;
+        let transfers = deadline
+            .run(
+                storage
+                    .transfers_web3_dal()
+                    .get_transfers(address, before, limit),
+            )
+            .await?;
+        let next_before = if transfers.len() == limit {
+            transfers.last().map(|transfer| TransferCursor {
+                block_number: transfer.block_number,
+                log_index: transfer.log_index,
+            })
+        } else {
+            None
+        };
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(TransfersPage {
+            transfers,
+            next_before,
+        })
+    }
+
+    /// Returns the last miniblock sealed at or before `timestamp`.
+    #[tracing::instrument(skip(self))]
+    pub fn get_block_by_timestamp_impl(
+        &self,
+        timestamp: u64,
+    ) -> Result<Option<BlockAtTimestamp>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_block_by_timestamp";
+
+        let block = self
+            .state
+            .connection_pool
+            .access_storage_blocking()
+            .blocks_web3_dal()
+            .get_miniblock_by_timestamp(timestamp)
+            .map_err(|err| internal_error(endpoint_name, err))?
+            .map(|(number, timestamp)| BlockAtTimestamp { number, timestamp });
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(block)
+    }
+
+    /// Returns the nonce and the ETH balance of the account as of the last miniblock sealed
+    /// at or before `timestamp`.
+    #[tracing::instrument(skip(self))]
+    pub fn get_account_state_at_timestamp_impl(
+        &self,
+        address: Address,
+        timestamp: u64,
+    ) -> Result<Option<AccountStateAtTimestamp>, Web3Error> {
+        let start = Instant::now();
+        let endpoint_name = "get_account_state_at_timestamp";
+
+        let mut storage = self.state.connection_pool.access_storage_blocking();
+        let block = match storage
+            .blocks_web3_dal()
+            .get_miniblock_by_timestamp(timestamp)
+            .map_err(|err| internal_error(endpoint_name, err))?
+        {
+            Some((number, timestamp)) => BlockAtTimestamp { number, timestamp },
+            None => return Ok(None),
+        };
+        let block_id = BlockId::Number(BlockNumber::Number(block.number.0.into()));
+        let full_nonce = storage
+            .storage_web3_dal()
+            .get_address_historical_nonce(address, block_id)
+            .map_err(|err| internal_error(endpoint_name, err))??;
+        let eth_balance = storage
+            .storage_web3_dal()
+            .standard_token_historical_balance(
+                AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+                AccountTreeId::new(address),
+                block_id,
+            )
+            .map_err(|err| internal_error(endpoint_name, err))??;
+
+        metrics::histogram!("api.web3.call", start.elapsed(), "method" => endpoint_name);
+        Ok(Some(AccountStateAtTimestamp {
+            address,
+            block,
+            nonce: decompose_full_nonce(full_nonce).0,
+            eth_balance,
+        }))
+    }
+
+    fn get_state_diff_page(
+        &self,
+        endpoint_name: &'static str,
+        storage: &mut StorageProcessor<'_>,
+        miniblocks: RangeInclusive<MiniblockNumber>,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> Result<StateDiffPage, Web3Error> {
+        let max_limit = self.state.req_entities_limit;
+        let limit = limit.map_or(max_limit, |limit| usize::from(limit).min(max_limit));
+        let diffs = storage
+            .storage_web3_dal()
+            .get_state_diff(miniblocks, after, limit)
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        let next_after = if diffs.len() == limit {
+            diffs.last().map(|diff| diff.hashed_key)
+        } else {
+            None
+        };
+        Ok(StateDiffPage { diffs, next_after })
+    }
+
     #[cfg(feature = "openzeppelin_tests")]
     /// Saves contract bytecode to memory.
     pub fn set_known_bytecode_impl(&self, bytecode: Bytes) -> bool {
@@ -553,3 +1560,32 @@ impl ZksNamespace {
         true
     }
 }
+
+fn balance_usd_value(item: &BalanceItem) -> Option<BigDecimal> {
+    let usd_price = item.token_info.usd_price.as_ref()?;
+    let balance = BigDecimal::new(
+        BigInt::from(u256_to_biguint(item.balance)),
+        item.token_info.decimals as i64,
+    );
+    Some(balance * usd_price)
+}
+
+fn redact_pending_transaction(tx: &mut PendingTransaction, fields: &[PendingTxField]) {
+    for field in fields {
+        match field {
+            PendingTxField::To => tx.to = None,
+            PendingTxField::Value => tx.value = None,
+            PendingTxField::Calldata => tx.input = None,
+        }
+    }
+}
+
+fn token_from_info(token_info: TokenInfo) -> Token {
+    Token {
+        l1_address: token_info.l1_address,
+        l2_address: token_info.l2_address,
+        name: token_info.metadata.name,
+        symbol: token_info.metadata.symbol,
+        decimals: token_info.metadata.decimals,
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use zksync_config::configs::api::Web3JsonRpc;
+use zksync_dal::ConnectionPool;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Workload of the Web3 API that has a separate connection pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiWorkload {
+    Http,
+    Ws,
+    PubSub,
+    TxSender,
+}
+
+impl ApiWorkload {
+    /// Name of the pool in the `connection_pool_sizes` table and in the metrics.
+    pub fn pool_name(self) -> &'static str {
+        match self {
+            Self::Http => "api_http",
+            Self::Ws => "api_ws",
+            Self::PubSub => "api_pubsub",
+            Self::TxSender => "api_tx_sender",
+        }
+    }
+
+    fn configured_pool_size(self, config: &Web3JsonRpc) -> u32 {
+        match self {
+            Self::Http => config.http_pool_size(),
+            Self::Ws => config.ws_pool_size(),
+            Self::PubSub => config.pubsub_pool_size(),
+            Self::TxSender => config.tx_sender_pool_size(),
+        }
+    }
+
+    /// Only the transaction sender writes to the database.
+    fn connects_to_master(self) -> bool {
+        matches!(self, Self::TxSender)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WorkloadPool {
+    pool: ConnectionPool,
+    configured_size: u32,
+}
+
+/// Connection pools of the API workloads. Sizes of the pools are taken from the config
+/// and can be overridden at runtime via the `connection_pool_sizes` table.
+#[derive(Debug, Clone)]
+pub struct ApiConnectionPools {
+    pools: HashMap<ApiWorkload, WorkloadPool>,
+}
+
+impl ApiConnectionPools {
+    /// Creates the pools for the given workloads.
+    pub fn new(config: &Web3JsonRpc, workloads: &[ApiWorkload]) -> Self {
+        let pools = workloads
+            .iter()
+            .map(|&workload| {
+                let configured_size = workload.configured_pool_size(config);
+                let pool =
+                    ConnectionPool::new_resizable(configured_size, workload.connects_to_master());
+                (
+                    workload,
+                    WorkloadPool {
+                        pool,
+                        configured_size,
+                    },
+                )
+            })
+            .collect();
+        Self { pools }
+    }
+
+    /// Returns the pool of the workload. Panics if the pool wasn't created.
+    pub fn get(&self, workload: ApiWorkload) -> ConnectionPool {
+        self.pools
+            .get(&workload)
+            .unwrap_or_else(|| panic!("No connection pool for {:?} workload", workload))
+            .pool
+            .clone()
+    }
+
+    /// Returns a job that applies the overridden pool sizes and reports the saturation of the pools.
+    pub fn watcher(&self) -> ApiConnectionPoolsWatcher {
+        ApiConnectionPoolsWatcher {
+            pools: self.clone(),
+            sizes_reloaded_at: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ApiConnectionPoolsWatcher {
+    pools: ApiConnectionPools,
+    sizes_reloaded_at: Option<Instant>,
+}
+
+impl ApiConnectionPoolsWatcher {
+    const SIZES_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+    fn reload_sizes(&self, connection_pool: ConnectionPool) {
+        let overridden_sizes = connection_pool
+            .access_storage_blocking()
+            .connection_pool_sizes_dal()
+            .get_pool_sizes();
+
+        for (workload, workload_pool) in &self.pools.pools {
+            let name = workload.pool_name();
+            let size = overridden_sizes
+                .get(name)
+                .copied()
+                .unwrap_or(workload_pool.configured_size)
+                .max(1);
+            if workload_pool.pool.resize(size) {
+                vlog::info!("Resized `{}` connection pool to {} connections", name, size);
+            }
+        }
+    }
+
+    fn report_saturation(&self) {
+        for (workload, workload_pool) in &self.pools.pools {
+            let stats = match workload_pool.pool.stats() {
+                Some(stats) => stats,
+                None => continue,
+            };
+            let name = workload.pool_name();
+            metrics::gauge!("api.connection_pool.max_size", stats.max_size as f64, "pool" => name);
+            metrics::gauge!("api.connection_pool.in_use", stats.in_use() as f64, "pool" => name);
+            metrics::gauge!(
+                "api.connection_pool.saturation",
+                stats.in_use() as f64 / stats.max_size as f64,
+                "pool" => name
+            );
+        }
+    }
+}
+
+impl PeriodicJob for ApiConnectionPoolsWatcher {
+    const SERVICE_NAME: &'static str = "ApiConnectionPoolsWatcher";
+
+    fn run_routine_task(&mut self, connection_pool: ConnectionPool) {
+        let should_reload = self.sizes_reloaded_at.map_or(true, |reloaded_at| {
+            reloaded_at.elapsed() >= Self::SIZES_RELOAD_INTERVAL
+        });
+        if should_reload {
+            self.reload_sizes(connection_pool);
+            self.sizes_reloaded_at = Some(Instant::now());
+        }
+        self.report_saturation();
+    }
+}
@@ -1,18 +1,254 @@
-use jsonrpc_pubsub::typed;
-use tokio::sync::watch;
+//! Notifiers of the `eth_subscribe` and `zks_subscribeAccountEvents` subscriptions.
+//!
+//! The new miniblocks are detected by polling the DB. If the WS API runs in the same process as the state keeper
+//! (or the external IO on the external node), the block and log notifiers are driven by the stream of the sealed
+//! miniblocks instead: the headers are sent to the subscribers without loading them from the DB, and the logs
+//! are only queried for the miniblocks whose logs bloom may match the filter of a subscriber. Since the API reads
+//! from a replica, a streamed miniblock is only notified once the replica has it, so that the subscribers can query
+//! it right away. If a notifier lags behind the stream, it catches up from the DB, and if the stream is closed,
+//! it falls back to polling. The filters of the `logs` subscribers are evaluated by the notifier, and each log
+//! is only checked against the subscribers that may be interested in its address.
+//!
+//! The `newHeads` and `logs` subscriptions may be resumed after reconnecting, possibly to another API server:
+//! the notifications following the resume token of the subscriber are replayed from the DB before the new ones,
+//! as long as the token is within the resume window.
+//!
+//! The account events are detected by comparing the mempool queues of the watched accounts between the polls.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use jsonrpc_core::error::{Error, ErrorCode};
+use jsonrpc_pubsub::{typed, SubscriptionId};
+use tokio::sync::{broadcast, watch};
 use tokio::time::{interval, Duration, Instant};
 
+use zksync_dal::blocks_web3_dal::web3_block_header;
+use zksync_dal::transactions_web3_dal::MempoolTransaction;
 use zksync_dal::ConnectionPool;
-use zksync_types::MiniblockNumber;
-use zksync_web3_decl::types::{PubSubFilter, PubSubResult};
+use zksync_types::{
+    api::{BlockNumber, GetLogsFilter, LogCursor},
+    Address, MiniblockNumber, H256,
+};
+use zksync_web3_decl::types::{
+    AccountEvent, BlockHeader, Log, PubSubFilter, PubSubResult, ResumeToken,
+};
+
+use super::namespaces::eth_subscribe::{ResumableSink, SubscriptionMap};
+use crate::state_keeper::sealed_miniblocks::{SealedMiniblock, SealedMiniblocksReceiver};
+
+/// Max number of logs loaded from the DB at once while replaying the logs of a subscriber.
+const REPLAY_LOGS_PAGE_SIZE: usize = 1_000;
+
+#[derive(Debug)]
+enum StreamEvent {
+    /// The miniblock following the last processed one.
+    Next(SealedMiniblock),
+    /// Some miniblocks are missed by the notifier and must be loaded from the DB.
+    Gap,
+    /// The stop signal is received.
+    Stop,
+    /// The state keeper has stopped publishing the miniblocks.
+    Closed,
+}
+
+async fn next_stream_event(
+    sealed_miniblocks: &mut SealedMiniblocksReceiver,
+    last_block_number: MiniblockNumber,
+    stop_receiver: &mut watch::Receiver<bool>,
+) -> StreamEvent {
+    loop {
+        if *stop_receiver.borrow() {
+            return StreamEvent::Stop;
+        }
+        tokio::select! {
+            changed = stop_receiver.changed() => {
+                if changed.is_err() {
+                    return StreamEvent::Stop;
+                }
+            }
+            miniblock = sealed_miniblocks.recv() => match miniblock {
+                // The miniblocks sealed before the notifier has loaded the last miniblock number are skipped.
+                Ok(miniblock) if miniblock.header.number <= last_block_number => {}
+                Ok(miniblock) if miniblock.header.number == last_block_number + 1 => {
+                    return StreamEvent::Next(miniblock);
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => return StreamEvent::Gap,
+                Err(broadcast::error::RecvError::Closed) => return StreamEvent::Closed,
+            }
+        }
+    }
+}
+
+/// Waits until the miniblock with the given number is visible in the DB the API reads from.
+/// Returns `false` if the stop signal is received in the meantime.
+async fn wait_for_miniblock(
+    connection_pool: &ConnectionPool,
+    number: MiniblockNumber,
+    polling_interval: Duration,
+    stop_receiver: &mut watch::Receiver<bool>,
+) -> bool {
+    let started_at = Instant::now();
+    loop {
+        let last_block_number = connection_pool
+            .access_storage()
+            .await
+            .blocks_web3_dal()
+            .get_sealed_miniblock_number()
+            .unwrap();
+        if last_block_number >= number {
+            metrics::histogram!("api.web3.pubsub.replica_wait", started_at.elapsed());
+            return true;
+        }
+        if *stop_receiver.borrow() {
+            return false;
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(polling_interval) => {}
+            changed = stop_receiver.changed() => {
+                if changed.is_err() {
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+fn block_resume_token(block: &BlockHeader) -> ResumeToken {
+    ResumeToken {
+        miniblock_number: MiniblockNumber(block.number.unwrap().as_u32()),
+        log_index: None,
+    }
+}
+
+fn log_resume_token(log: &Log) -> ResumeToken {
+    ResumeToken {
+        miniblock_number: MiniblockNumber(log.block_number.unwrap().as_u32()),
+        log_index: Some(log.log_index.unwrap().as_u32()),
+    }
+}
+
+/// Takes the resume tokens of the subscribers waiting for a replay up to `last_block_number`.
+/// The subscribers with the tokens outside of the resume window are notified with an error and removed.
+fn take_replays<T>(
+    subscribers: &SubscriptionMap<T>,
+    resumable_sink: fn(&mut T) -> &mut ResumableSink,
+    last_block_number: MiniblockNumber,
+    resume_window: u32,
+) -> Vec<(SubscriptionId, ResumeToken)> {
+    let mut subscribers = subscribers.write().unwrap();
+    let mut replays = vec![];
+    let mut expired = vec![];
+    for (id, subscriber) in subscribers.iter_mut() {
+        let sink = resumable_sink(subscriber);
+        match sink.replay_from {
+            // The token was issued by a server ahead of this one; wait until the miniblock is loaded.
+            Some(token) if token.miniblock_number > last_block_number => {}
+            Some(token) if last_block_number.0 - token.miniblock_number.0 > resume_window => {
+                sink.notify_error(Error {
+                    code: ErrorCode::InvalidParams,
+                    message: "Resume token is outside of the resume window.".into(),
+                    data: None,
+                });
+                expired.push(id.clone());
+            }
+            Some(token) => {
+                sink.replay_from = None;
+                replays.push((id.clone(), token));
+            }
+            None => {}
+        }
+    }
+    for id in expired {
+        subscribers.remove(&id);
+    }
+    replays
+}
+
+/// Replays the headers following the resume tokens of the resumed subscribers up to `last_block_number`.
+async fn replay_blocks(
+    subscribers: &SubscriptionMap<ResumableSink>,
+    connection_pool: &ConnectionPool,
+    last_block_number: MiniblockNumber,
+    resume_window: u32,
+) {
+    let replays = take_replays(subscribers, |sink| sink, last_block_number, resume_window);
+    let Some(from_block) = replays.iter().map(|(_, token)| token.miniblock_number).min() else {
+        return;
+    };
+    let blocks = connection_pool
+        .access_storage()
+        .await
+        .blocks_web3_dal()
+        .get_block_headers_after(from_block)
+        .unwrap();
+    let subscribers = subscribers.read().unwrap();
+    for (id, token) in replays {
+        let Some(sink) = subscribers.get(&id) else {
+            continue;
+        };
+        for block in &blocks {
+            let resume_token = block_resume_token(block);
+            if resume_token.miniblock_number > token.miniblock_number
+                && resume_token.miniblock_number <= last_block_number
+            {
+                sink.notify(PubSubResult::Header(block.clone()), resume_token);
+                metrics::counter!("api.web3.pubsub.replay", 1, "subscription_type" => "blocks");
+            }
+        }
+    }
+}
+
+fn notify_block_subscribers(
+    subscribers: &SubscriptionMap<ResumableSink>,
+    new_blocks: &[BlockHeader],
+) {
+    let start = Instant::now();
+    for sink in subscribers.read().unwrap().values() {
+        // The resumed subscribers get the new blocks once the older ones are replayed.
+        if sink.replay_from.is_some() {
+            continue;
+        }
+        for block in new_blocks {
+            sink.notify(
+                PubSubResult::Header(block.clone()),
+                block_resume_token(block),
+            );
+            metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "blocks");
+        }
+    }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "blocks");
+}
 
-use super::namespaces::eth_subscribe::SubscriptionMap;
+/// Loads the miniblocks after `last_block_number` from the DB and notifies the subscribers.
+/// Returns the number of the last notified miniblock.
+async fn poll_blocks(
+    subscribers: &SubscriptionMap<ResumableSink>,
+    connection_pool: &ConnectionPool,
+    last_block_number: MiniblockNumber,
+) -> MiniblockNumber {
+    let start = Instant::now();
+    let new_blocks = connection_pool
+        .access_storage()
+        .await
+        .blocks_web3_dal()
+        .get_block_headers_after(last_block_number)
+        .unwrap();
+    metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "blocks");
+    let Some(last_block) = new_blocks.last() else {
+        return last_block_number;
+    };
+    let new_last_block_number = MiniblockNumber(last_block.number.unwrap().as_u32());
+    notify_block_subscribers(subscribers, &new_blocks);
+    new_last_block_number
+}
 
 pub async fn notify_blocks(
-    subscribers: SubscriptionMap<typed::Sink<PubSubResult>>,
+    subscribers: SubscriptionMap<ResumableSink>,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
-    stop_receiver: watch::Receiver<bool>,
+    resume_window: u32,
+    sealed_miniblocks: Option<SealedMiniblocksReceiver>,
+    mut stop_receiver: watch::Receiver<bool>,
 ) {
     let mut last_block_number = connection_pool
         .access_storage()
@@ -20,6 +256,68 @@ pub async fn notify_blocks(
         .blocks_web3_dal()
         .get_sealed_miniblock_number()
         .unwrap();
+
+    if let Some(mut sealed_miniblocks) = sealed_miniblocks {
+        loop {
+            match next_stream_event(
+                &mut sealed_miniblocks,
+                last_block_number,
+                &mut stop_receiver,
+            )
+            .await
+            {
+                StreamEvent::Next(SealedMiniblock { header, .. }) => {
+                    replay_blocks(
+                        &subscribers,
+                        &connection_pool,
+                        last_block_number,
+                        resume_window,
+                    )
+                    .await;
+                    let is_visible = wait_for_miniblock(
+                        &connection_pool,
+                        header.number,
+                        polling_interval,
+                        &mut stop_receiver,
+                    )
+                    .await;
+                    if !is_visible {
+                        vlog::info!("Stop signal received, pubsub_block_notifier is shutting down");
+                        return;
+                    }
+                    last_block_number = header.number;
+                    let block = web3_block_header(
+                        header.number,
+                        header.timestamp,
+                        header.hash,
+                        header.logs_bloom,
+                    );
+                    notify_block_subscribers(&subscribers, &[block]);
+                }
+                StreamEvent::Gap => {
+                    metrics::increment_counter!("api.web3.pubsub.stream_gaps", "subscription_type" => "blocks");
+                    replay_blocks(
+                        &subscribers,
+                        &connection_pool,
+                        last_block_number,
+                        resume_window,
+                    )
+                    .await;
+                    last_block_number =
+                        poll_blocks(&subscribers, &connection_pool, last_block_number).await;
+                }
+                StreamEvent::Stop => {
+                    vlog::info!("Stop signal received, pubsub_block_notifier is shutting down");
+                    return;
+                }
+                StreamEvent::Closed => {
+                    vlog::warn!("Sealed miniblocks stream is closed, pubsub_block_notifier falls back to polling");
+                    break;
+                }
+            }
+        }
+    }
+
     let mut timer = interval(polling_interval);
     loop {
         if *stop_receiver.borrow() {
@@ -28,27 +326,14 @@ pub async fn notify_blocks(
         }
 
         timer.tick().await;
-
-        let start = Instant::now();
-        let new_blocks = connection_pool
-            .access_storage()
-            .await
-            .blocks_web3_dal()
-            .get_block_headers_after(last_block_number)
-            .unwrap();
-        metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "blocks");
-        if !new_blocks.is_empty() {
-            last_block_number =
-                MiniblockNumber(new_blocks.last().unwrap().number.unwrap().as_u32());
-            let start = Instant::now();
-            for sink in subscribers.read().unwrap().values() {
-                for block in new_blocks.clone() {
-                    let _ = sink.notify(Ok(PubSubResult::Header(block)));
-                    metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "blocks");
-                }
-            }
-            metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "blocks");
-        }
+        replay_blocks(
+            &subscribers,
+            &connection_pool,
+            last_block_number,
+            resume_window,
+        )
+        .await;
+        last_block_number = poll_blocks(&subscribers, &connection_pool, last_block_number).await;
     }
 }
 
@@ -74,6 +359,7 @@ pub async fn notify_txs(
             .await
             .transactions_web3_dal()
             .get_pending_txs_hashes_after(last_time, None)
+            .await
             .unwrap();
         metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "txs");
         if let Some(new_last_time) = new_last_time {
@@ -90,11 +376,201 @@ pub async fn notify_txs(
     }
 }
 
+/// Converts the filter of the `logs` subscriber to the form used by the DAL, covering the logs following `token`
+/// up to `last_block_number`. Returns `None` if the filter can't match any log.
+fn replay_logs_filter(
+    filter: &PubSubFilter,
+    token: ResumeToken,
+    last_block_number: MiniblockNumber,
+) -> Option<(GetLogsFilter, Option<LogCursor>)> {
+    let addresses = match &filter.address {
+        Some(addresses) if addresses.0.is_empty() => return None,
+        Some(addresses) => addresses.0.clone(),
+        None => vec![],
+    };
+    let mut topics = vec![];
+    for (idx, expected_topics) in filter.topics.iter().flatten().enumerate() {
+        match expected_topics {
+            Some(expected_topics) if expected_topics.0.is_empty() => return None,
+            Some(expected_topics) => topics.push((idx as u32 + 1, expected_topics.0.clone())),
+            None => {}
+        }
+    }
+
+    // The token without a log index means that all the logs of the miniblock are delivered.
+    let (from_block, after) = match token.log_index {
+        Some(log_index) => {
+            let cursor = LogCursor {
+                block_number: token.miniblock_number,
+                log_index,
+                watermark: None,
+            };
+            (token.miniblock_number, Some(cursor))
+        }
+        None => (token.miniblock_number + 1, None),
+    };
+    let filter = GetLogsFilter {
+        from_block,
+        to_block: Some(BlockNumber::Number(last_block_number.0.into())),
+        addresses,
+        topics,
+    };
+    Some((filter, after))
+}
+
+/// Replays the logs following the resume tokens of the resumed subscribers up to `last_block_number`.
+/// The logs are filtered by the DB, so that only the logs matching the filter of a subscriber are loaded.
+async fn replay_logs(
+    subscribers: &SubscriptionMap<(ResumableSink, PubSubFilter)>,
+    connection_pool: &ConnectionPool,
+    last_block_number: MiniblockNumber,
+    resume_window: u32,
+) {
+    let replays = take_replays(
+        subscribers,
+        |(sink, _)| sink,
+        last_block_number,
+        resume_window,
+    );
+    if replays.is_empty() {
+        return;
+    }
+
+    let mut storage = connection_pool.access_storage().await;
+    for (id, token) in replays {
+        let filter = match subscribers.read().unwrap().get(&id) {
+            Some((_, filter)) => replay_logs_filter(filter, token, last_block_number),
+            None => continue,
+        };
+        let Some((filter, mut after)) = filter else {
+            continue;
+        };
+
+        loop {
+            let logs = storage
+                .events_web3_dal()
+                .get_logs_after(filter.clone(), after, REPLAY_LOGS_PAGE_SIZE)
+                .await
+                .unwrap();
+            let Some(last_log) = logs.last() else {
+                break;
+            };
+            let last_token = log_resume_token(last_log);
+            after = Some(LogCursor {
+                block_number: last_token.miniblock_number,
+                log_index: last_token.log_index.unwrap(),
+                watermark: None,
+            });
+            let is_last_page = logs.len() < REPLAY_LOGS_PAGE_SIZE;
+
+            let subscribers = subscribers.read().unwrap();
+            let Some((sink, _)) = subscribers.get(&id) else {
+                break;
+            };
+            for log in logs {
+                let resume_token = log_resume_token(&log);
+                sink.notify(PubSubResult::Log(log), resume_token);
+                metrics::counter!("api.web3.pubsub.replay", 1, "subscription_type" => "logs");
+            }
+            if is_last_page {
+                break;
+            }
+        }
+    }
+}
+
+/// Log subscribers indexed by the addresses in their filters, so that a log is only checked against
+/// the filters of the subscribers watching its address and the ones not filtering by address.
+#[derive(Debug)]
+struct LogSubscribersIndex<'a, T> {
+    by_address: HashMap<Address, Vec<(&'a T, &'a PubSubFilter)>>,
+    any_address: Vec<(&'a T, &'a PubSubFilter)>,
+}
+
+impl<'a, T> LogSubscribersIndex<'a, T> {
+    fn new(subscribers: impl Iterator<Item = (&'a T, &'a PubSubFilter)>) -> Self {
+        let mut by_address: HashMap<_, Vec<_>> = HashMap::new();
+        let mut any_address = vec![];
+        for (subscriber, filter) in subscribers {
+            match &filter.address {
+                Some(addresses) => {
+                    // Deduplicated so that the subscriber isn't notified twice about the same log.
+                    let addresses: HashSet<_> = addresses.0.iter().collect();
+                    for address in addresses {
+                        by_address
+                            .entry(*address)
+                            .or_default()
+                            .push((subscriber, filter));
+                    }
+                }
+                None => any_address.push((subscriber, filter)),
+            }
+        }
+        Self {
+            by_address,
+            any_address,
+        }
+    }
+
+    /// Returns the subscribers whose filters match the log.
+    fn matching<'s>(&'s self, log: &'s Log) -> impl Iterator<Item = &'a T> + 's {
+        let by_address = self.by_address.get(&log.address).into_iter().flatten();
+        self.any_address
+            .iter()
+            .chain(by_address)
+            .filter(move |(_, filter)| filter.matches(log))
+            .map(|(subscriber, _)| *subscriber)
+    }
+}
+
+/// Loads the logs emitted after `last_block_number` from the DB and notifies the subscribers.
+/// Returns the number of the miniblock of the last notified log.
+async fn poll_logs(
+    subscribers: &SubscriptionMap<(ResumableSink, PubSubFilter)>,
+    connection_pool: &ConnectionPool,
+    last_block_number: MiniblockNumber,
+) -> MiniblockNumber {
+    let start = Instant::now();
+    let new_logs = connection_pool
+        .access_storage()
+        .await
+        .events_web3_dal()
+        .get_all_logs(last_block_number)
+        .unwrap();
+    metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "logs");
+    let Some(last_log) = new_logs.last() else {
+        return last_block_number;
+    };
+    let new_last_block_number = MiniblockNumber(last_log.block_number.unwrap().as_u32());
+    let start = Instant::now();
+    {
+        let subscribers = subscribers.read().unwrap();
+        // The resumed subscribers get the new logs once the older ones are replayed.
+        let index = LogSubscribersIndex::new(
+            subscribers
+                .values()
+                .filter(|(sink, _)| sink.replay_from.is_none())
+                .map(|(sink, filter)| (sink, filter)),
+        );
+        for log in new_logs {
+            let resume_token = log_resume_token(&log);
+            for sink in index.matching(&log) {
+                sink.notify(PubSubResult::Log(log.clone()), resume_token);
+                metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "logs");
+            }
+        }
+    }
+    metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "logs");
+    new_last_block_number
+}
+
 pub async fn notify_logs(
-    subscribers: SubscriptionMap<(typed::Sink<PubSubResult>, PubSubFilter)>,
+    subscribers: SubscriptionMap<(ResumableSink, PubSubFilter)>,
     connection_pool: ConnectionPool,
     polling_interval: Duration,
-    stop_receiver: watch::Receiver<bool>,
+    resume_window: u32,
+    sealed_miniblocks: Option<SealedMiniblocksReceiver>,
+    mut stop_receiver: watch::Receiver<bool>,
 ) {
     let mut last_block_number = connection_pool
         .access_storage()
@@ -102,6 +578,74 @@ pub async fn notify_logs(
         .blocks_web3_dal()
         .get_sealed_miniblock_number()
         .unwrap();
+
+    if let Some(mut sealed_miniblocks) = sealed_miniblocks {
+        loop {
+            match next_stream_event(
+                &mut sealed_miniblocks,
+                last_block_number,
+                &mut stop_receiver,
+            )
+            .await
+            {
+                StreamEvent::Next(SealedMiniblock { header, .. }) => {
+                    replay_logs(
+                        &subscribers,
+                        &connection_pool,
+                        last_block_number,
+                        resume_window,
+                    )
+                    .await;
+                    let may_match = subscribers
+                        .read()
+                        .unwrap()
+                        .values()
+                        .any(|(_, filter)| filter.may_match_bloom(&header.logs_bloom));
+                    if may_match {
+                        let is_visible = wait_for_miniblock(
+                            &connection_pool,
+                            header.number,
+                            polling_interval,
+                            &mut stop_receiver,
+                        )
+                        .await;
+                        if !is_visible {
+                            vlog::info!(
+                                "Stop signal received, pubsub_logs_notifier is shutting down"
+                            );
+                            return;
+                        }
+                        last_block_number =
+                            poll_logs(&subscribers, &connection_pool, last_block_number).await;
+                    } else {
+                        metrics::increment_counter!("api.web3.pubsub.skipped_polls", "subscription_type" => "logs");
+                        last_block_number = header.number;
+                    }
+                }
+                StreamEvent::Gap => {
+                    metrics::increment_counter!("api.web3.pubsub.stream_gaps", "subscription_type" => "logs");
+                    replay_logs(
+                        &subscribers,
+                        &connection_pool,
+                        last_block_number,
+                        resume_window,
+                    )
+                    .await;
+                    last_block_number =
+                        poll_logs(&subscribers, &connection_pool, last_block_number).await;
+                }
+                StreamEvent::Stop => {
+                    vlog::info!("Stop signal received, pubsub_logs_notifier is shutting down");
+                    return;
+                }
+                StreamEvent::Closed => {
+                    vlog::warn!("Sealed miniblocks stream is closed, pubsub_logs_notifier falls back to polling");
+                    break;
+                }
+            }
+        }
+    }
+
     let mut timer = interval(polling_interval);
     loop {
         if *stop_receiver.borrow() {
@@ -109,29 +653,405 @@ pub async fn notify_logs(
             break;
         }
 
+        timer.tick().await;
+        replay_logs(
+            &subscribers,
+            &connection_pool,
+            last_block_number,
+            resume_window,
+        )
+        .await;
+        last_block_number = poll_logs(&subscribers, &connection_pool, last_block_number).await;
+    }
+}
+
+/// Mempool transactions of an account with the nonces not used in the latest sealed state.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct AccountQueue {
+    next_nonce: u32,
+    txs: BTreeMap<u32, H256>,
+}
+
+impl AccountQueue {
+    /// Groups the mempool transactions by the accounts from `nonces`.
+    fn group(
+        transactions: Vec<MempoolTransaction>,
+        nonces: &HashMap<Address, u32>,
+    ) -> HashMap<Address, Self> {
+        let mut queues: HashMap<_, _> = nonces
+            .iter()
+            .map(|(&account, &next_nonce)| {
+                let queue = Self {
+                    next_nonce,
+                    txs: BTreeMap::new(),
+                };
+                (account, queue)
+            })
+            .collect();
+        for tx in transactions {
+            if let Some(queue) = queues.get_mut(&tx.initiator_address) {
+                // Transactions with the used nonces will be rejected by the state keeper.
+                if tx.nonce >= queue.next_nonce {
+                    queue.txs.insert(tx.nonce, tx.hash);
+                }
+            }
+        }
+        queues
+    }
+
+    /// Returns the first missing nonce and the nonce of the first transaction following it, if any.
+    fn gap(&self) -> Option<(u32, u32)> {
+        let mut expected_nonce = self.next_nonce;
+        for &nonce in self.txs.keys() {
+            if nonce != expected_nonce {
+                return Some((expected_nonce, nonce));
+            }
+            // No transaction can follow the one with the maximum nonce.
+            expected_nonce = expected_nonce.checked_add(1)?;
+        }
+        None
+    }
+}
+
+/// Returns the events describing the change of the account queue since the previous poll.
+/// If there was no previous poll, only the current nonce gap is reported.
+fn account_events(
+    account: Address,
+    old: Option<&AccountQueue>,
+    new: &AccountQueue,
+) -> Vec<AccountEvent> {
+    let mut events = vec![];
+    let new_gap = new.gap();
+    let old_gap = old.and_then(AccountQueue::gap);
+    if let Some(old) = old {
+        let new_hashes: HashSet<_> = new.txs.values().collect();
+        for (&nonce, &hash) in &old.txs {
+            if new_hashes.contains(&hash) {
+                continue;
+            }
+            match new.txs.get(&nonce) {
+                Some(&replaced_by) => events.push(AccountEvent::TxReplaced {
+                    account,
+                    hash,
+                    nonce,
+                    replaced_by,
+                }),
+                // The nonce is used in the sealed state, so the transaction is executed.
+                None if nonce < new.next_nonce => {}
+                None => events.push(AccountEvent::TxDropped {
+                    account,
+                    hash,
+                    nonce,
+                }),
+            }
+        }
+    }
+
+    if let Some((expected_nonce, first_queued_nonce)) = new_gap {
+        if old_gap != new_gap {
+            events.push(AccountEvent::NonceGap {
+                account,
+                expected_nonce,
+                first_queued_nonce,
+            });
+        }
+    }
+
+    if let (Some(old), Some((old_expected_nonce, _))) = (old, old_gap) {
+        // The transactions following the gap were blocked; the ones before the new gap can be executed.
+        let executable_until = new_gap.map_or(u32::MAX, |(expected_nonce, _)| expected_nonce);
+        for (&nonce, &hash) in &new.txs {
+            let was_blocked = nonce >= old_expected_nonce && old.txs.get(&nonce) == Some(&hash);
+            if was_blocked && nonce < executable_until {
+                events.push(AccountEvent::TxExecutable {
+                    account,
+                    hash,
+                    nonce,
+                });
+            }
+        }
+    }
+    events
+}
+
+pub async fn notify_account_events(
+    subscribers: SubscriptionMap<(typed::Sink<AccountEvent>, HashSet<Address>)>,
+    connection_pool: ConnectionPool,
+    polling_interval: Duration,
+    stop_receiver: watch::Receiver<bool>,
+) {
+    let mut queues = HashMap::new();
+    let mut notified_subscribers = HashSet::new();
+    let mut timer = interval(polling_interval);
+    loop {
+        if *stop_receiver.borrow() {
+            vlog::info!("Stop signal received, pubsub_account_events_notifier is shutting down");
+            break;
+        }
+
         timer.tick().await;
 
+        let accounts: HashSet<_> = subscribers
+            .read()
+            .unwrap()
+            .values()
+            .flat_map(|(_, accounts)| accounts.iter().copied())
+            .collect();
+        if accounts.is_empty() {
+            queues.clear();
+            notified_subscribers.clear();
+            continue;
+        }
+        let accounts: Vec<_> = accounts.into_iter().collect();
+
         let start = Instant::now();
-        let new_logs = connection_pool
+        let (transactions, nonces) = connection_pool
             .access_storage()
             .await
-            .events_web3_dal()
-            .get_all_logs(last_block_number)
+            .transactions_web3_dal()
+            .get_mempool_transactions_by_initiators(&accounts)
+            .await
             .unwrap();
-        metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "logs");
-        if !new_logs.is_empty() {
-            last_block_number =
-                MiniblockNumber(new_logs.last().unwrap().block_number.unwrap().as_u32());
-            let start = Instant::now();
-            for (sink, filter) in subscribers.read().unwrap().values() {
-                for log in new_logs.clone() {
-                    if filter.matches(&log) {
-                        let _ = sink.notify(Ok(PubSubResult::Log(log)));
-                        metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "logs");
-                    }
+        metrics::histogram!("api.web3.pubsub.db_poll_latency", start.elapsed(), "subscription_type" => "account_events");
+        let new_queues = AccountQueue::group(transactions, &nonces);
+
+        let start = Instant::now();
+        let active_subscribers = subscribers.read().unwrap();
+        for (id, (sink, accounts)) in active_subscribers.iter() {
+            let is_new_subscriber = !notified_subscribers.contains(id);
+            for account in accounts {
+                // The account is subscribed to after the queues have been loaded.
+                let Some(new_queue) = new_queues.get(account) else {
+                    continue;
+                };
+                let old_queue = if is_new_subscriber {
+                    None
+                } else {
+                    queues.get(account)
+                };
+                for event in account_events(*account, old_queue, new_queue) {
+                    let _ = sink.notify(Ok(event));
+                    metrics::counter!("api.web3.pubsub.notify", 1, "subscription_type" => "account_events");
                 }
             }
-            metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "logs");
         }
+        notified_subscribers = active_subscribers.keys().cloned().collect();
+        drop(active_subscribers);
+        metrics::histogram!("api.web3.pubsub.notify_subscribers_latency", start.elapsed(), "subscription_type" => "account_events");
+        queues = new_queues;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_web3_decl::types::ValueOrArray;
+
+    fn queue(next_nonce: u32, txs: &[(u32, u64)]) -> AccountQueue {
+        AccountQueue {
+            next_nonce,
+            txs: txs
+                .iter()
+                .map(|&(nonce, hash)| (nonce, H256::from_low_u64_be(hash)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn detecting_account_events() {
+        let account = Address::repeat_byte(1);
+        let hash = H256::from_low_u64_be;
+
+        // The initial state only reports the current gap.
+        let gapped = queue(5, &[(5, 1), (7, 2), (8, 3)]);
+        assert_eq!(
+            account_events(account, None, &gapped),
+            [AccountEvent::NonceGap {
+                account,
+                expected_nonce: 6,
+                first_queued_nonce: 7,
+            }]
+        );
+        assert!(account_events(account, Some(&gapped), &gapped).is_empty());
+
+        // Nonce 5 is executed and the gap is filled, so the queued transactions become executable.
+        let filled = queue(6, &[(6, 4), (7, 2), (8, 3)]);
+        assert_eq!(
+            account_events(account, Some(&gapped), &filled),
+            [
+                AccountEvent::TxExecutable {
+                    account,
+                    hash: hash(2),
+                    nonce: 7,
+                },
+                AccountEvent::TxExecutable {
+                    account,
+                    hash: hash(3),
+                    nonce: 8,
+                },
+            ]
+        );
+
+        // Nonce 6 is replaced and nonce 7 is dropped, which opens a new gap.
+        let replaced = queue(6, &[(6, 5), (8, 3)]);
+        assert_eq!(
+            account_events(account, Some(&filled), &replaced),
+            [
+                AccountEvent::TxReplaced {
+                    account,
+                    hash: hash(4),
+                    nonce: 6,
+                    replaced_by: hash(5),
+                },
+                AccountEvent::TxDropped {
+                    account,
+                    hash: hash(2),
+                    nonce: 7,
+                },
+                AccountEvent::NonceGap {
+                    account,
+                    expected_nonce: 7,
+                    first_queued_nonce: 8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn grouping_account_queues() {
+        let account = Address::repeat_byte(1);
+        let idle_account = Address::repeat_byte(2);
+        let tx = |nonce: u32| MempoolTransaction {
+            hash: H256::from_low_u64_be(nonce as u64),
+            initiator_address: account,
+            nonce,
+            max_fee_per_gas: 0.into(),
+            max_priority_fee_per_gas: 0.into(),
+            gas_per_pubdata_limit: 0.into(),
+            received_at: chrono::Utc::now(),
+            is_quarantined: false,
+        };
+        let nonces = HashMap::from([(account, 1), (idle_account, 3)]);
+
+        let queues = AccountQueue::group(vec![tx(0), tx(1), tx(3)], &nonces);
+        assert_eq!(queues[&account], queue(1, &[(1, 1), (3, 3)]));
+        assert_eq!(queues[&account].gap(), Some((2, 3)));
+        assert_eq!(queues[&idle_account], queue(3, &[]));
+        assert_eq!(queues[&idle_account].gap(), None);
+    }
+
+    #[test]
+    fn no_gap_after_max_nonce() {
+        assert_eq!(queue(u32::MAX, &[(u32::MAX, 1)]).gap(), None);
+        assert_eq!(
+            queue(u32::MAX - 1, &[(u32::MAX, 1)]).gap(),
+            Some((u32::MAX - 1, u32::MAX))
+        );
+    }
+
+    fn log(address: Address, topics: Vec<H256>) -> Log {
+        Log {
+            address,
+            topics,
+            data: Default::default(),
+            block_hash: None,
+            block_number: None,
+            l1_batch_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn indexing_log_subscribers() {
+        let address = Address::repeat_byte(1);
+        let other_address = Address::repeat_byte(2);
+        let topic = H256::repeat_byte(3);
+        let filters = vec![
+            (0, PubSubFilter::default()),
+            (
+                1,
+                PubSubFilter {
+                    address: Some(ValueOrArray(vec![address, address])),
+                    topics: None,
+                },
+            ),
+            (
+                2,
+                PubSubFilter {
+                    address: Some(ValueOrArray(vec![other_address])),
+                    topics: None,
+                },
+            ),
+            (
+                3,
+                PubSubFilter {
+                    address: None,
+                    topics: Some(vec![Some(ValueOrArray(vec![topic]))]),
+                },
+            ),
+        ];
+        let index = LogSubscribersIndex::new(filters.iter().map(|(id, filter)| (id, filter)));
+
+        let matching: Vec<_> = index.matching(&log(address, vec![])).copied().collect();
+        assert_eq!(matching, [0, 1]);
+        let matching: Vec<_> = index
+            .matching(&log(other_address, vec![topic]))
+            .copied()
+            .collect();
+        assert_eq!(matching, [0, 3, 2]);
+        let matching: Vec<_> = index
+            .matching(&log(Address::repeat_byte(4), vec![]))
+            .copied()
+            .collect();
+        assert_eq!(matching, [0]);
+    }
+
+    #[test]
+    fn converting_replay_filters() {
+        let address = Address::repeat_byte(1);
+        let topic = H256::repeat_byte(2);
+        let filter = PubSubFilter {
+            address: Some(ValueOrArray(vec![address])),
+            topics: Some(vec![None, Some(ValueOrArray(vec![topic]))]),
+        };
+        let token = ResumeToken {
+            miniblock_number: MiniblockNumber(5),
+            log_index: Some(3),
+        };
+        let (logs_filter, after) = replay_logs_filter(&filter, token, MiniblockNumber(10)).unwrap();
+        assert_eq!(logs_filter.from_block, MiniblockNumber(5));
+        assert_eq!(
+            logs_filter.to_block,
+            Some(BlockNumber::Number(10_u32.into()))
+        );
+        assert_eq!(logs_filter.addresses, [address]);
+        assert_eq!(logs_filter.topics, [(2, vec![topic])]);
+        let after = after.unwrap();
+        assert_eq!(
+            (after.block_number, after.log_index),
+            (MiniblockNumber(5), 3)
+        );
+
+        // All the logs of the miniblock of the token without a log index are delivered.
+        let token = ResumeToken {
+            log_index: None,
+            ..token
+        };
+        let (logs_filter, after) =
+            replay_logs_filter(&PubSubFilter::default(), token, MiniblockNumber(10)).unwrap();
+        assert_eq!(logs_filter.from_block, MiniblockNumber(6));
+        assert!(logs_filter.addresses.is_empty() && logs_filter.topics.is_empty());
+        assert!(after.is_none());
+
+        let filter = PubSubFilter {
+            address: Some(ValueOrArray(vec![])),
+            topics: None,
+        };
+        assert!(replay_logs_filter(&filter, token, MiniblockNumber(10)).is_none());
     }
 }
@@ -0,0 +1,204 @@
+//! Deadlines of the heavy DB queries made to serve the API requests.
+//!
+//! Such queries are run in a transaction with the statement timeout set to the deadline of the request,
+//! so that Postgres cancels them even if the API server doesn't get to it. The timeout is scoped
+//! to these queries only: their errors are returned to the client as the request timeout, while
+//! the rest of the API queries unwrap the DB errors and thus are never cancelled.
+//!
+//! A query still running at the deadline, or once the request is dropped (e.g., because the client
+//! has disconnected), is additionally cancelled via `pg_cancel_backend`, so that the abandoned queries
+//! don't keep loading the database. Cancellations are sent by a single background task reusing
+//! its connections, and are rate-limited, so that a burst of the disconnected clients doesn't turn
+//! into a burst of the new DB connections.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+
+use zksync_config::configs::api::Web3JsonRpc;
+use zksync_dal::{
+    connection::{BackendTransaction, QueryCanceller},
+    ConnectionPool, SqlxError, StorageProcessor,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use super::backend_jsonrpc::error::{db_error, internal_error, request_timeout_error};
+
+/// Max number of the cancellations waiting to be sent. Cancellations are dropped once it's reached,
+/// leaving the queries to the statement timeout.
+const CANCELLATIONS_BUFFER_SIZE: usize = 1_000;
+/// Min interval between the cancellations, limiting their rate.
+const MIN_CANCELLATION_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Query to be cancelled by [`run_query_canceller()`].
+#[derive(Debug)]
+struct Cancellation {
+    method_name: &'static str,
+    pool: ConnectionPool,
+    backend_transaction: BackendTransaction,
+}
+
+/// Sends the cancellations until all the senders are dropped.
+async fn run_query_canceller(mut receiver: mpsc::Receiver<Cancellation>) {
+    let mut canceller = QueryCanceller::default();
+    while let Some(cancellation) = receiver.recv().await {
+        let result = canceller
+            .cancel_query(&cancellation.pool, cancellation.backend_transaction)
+            .await;
+        if let Err(err) = result {
+            vlog::warn!(
+                "Failed to cancel DB query of method {}: {}",
+                cancellation.method_name,
+                err
+            );
+        }
+        tokio::time::sleep(MIN_CANCELLATION_INTERVAL).await;
+    }
+}
+
+/// Deadlines of the API methods.
+#[derive(Debug, Clone, Default)]
+pub struct RequestDeadlines {
+    default: Option<Duration>,
+    overrides: Arc<HashMap<String, Duration>>,
+    /// Sender of the cancellations to the background task; no queries are cancelled if not set.
+    cancellations: Option<mpsc::Sender<Cancellation>>,
+}
+
+impl RequestDeadlines {
+    /// Creates the deadlines and spawns the task cancelling the queries, which runs until
+    /// the deadlines and all their clones are dropped.
+    pub fn new(config: &Web3JsonRpc) -> Self {
+        let (sender, receiver) = mpsc::channel(CANCELLATIONS_BUFFER_SIZE);
+        tokio::spawn(run_query_canceller(receiver));
+        Self {
+            default: config.request_deadline(),
+            overrides: Arc::new(config.request_deadline_overrides()),
+            cancellations: Some(sender),
+        }
+    }
+
+    fn get(&self, method_name: &str) -> Option<Duration> {
+        self.overrides.get(method_name).copied().or(self.default)
+    }
+
+    /// Acquires a connection for the heavy queries of the method, which have to be run via
+    /// [`QueryDeadline::run()`]. The deadline is counted from this call.
+    pub async fn access_storage(
+        &self,
+        pool: &ConnectionPool,
+        method_name: &'static str,
+    ) -> Result<(StorageProcessor<'static>, QueryDeadline), Web3Error> {
+        let timeout = self.get(method_name);
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let (storage, backend_transaction) = pool
+            .access_cancellable_storage(timeout)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let deadline = QueryDeadline {
+            method_name,
+            deadline,
+            pool: pool.clone(),
+            backend_transaction,
+            cancellations: self.cancellations.clone(),
+        };
+        Ok((storage, deadline))
+    }
+}
+
+/// Deadline of the queries made over a connection acquired with [`RequestDeadlines::access_storage()`].
+#[derive(Debug)]
+pub struct QueryDeadline {
+    method_name: &'static str,
+    deadline: Option<Instant>,
+    pool: ConnectionPool,
+    /// Transaction of the Postgres backend of the connection.
+    backend_transaction: BackendTransaction,
+    cancellations: Option<mpsc::Sender<Cancellation>>,
+}
+
+impl QueryDeadline {
+    /// Runs the query until the deadline. The query is cancelled if it's still running at the deadline,
+    /// or if the returned future is dropped.
+    pub async fn run<T>(
+        &self,
+        query: impl Future<Output = Result<T, SqlxError>>,
+    ) -> Result<T, Web3Error> {
+        let mut guard = CancelOnDrop {
+            deadline: self,
+            armed: true,
+        };
+        let output = match self.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), query).await,
+            None => Ok(query.await),
+        };
+        guard.armed = false;
+        match output {
+            Ok(output) => output.map_err(|err| db_error(self.method_name, err)),
+            Err(_) => {
+                self.cancel();
+                Err(request_timeout_error(self.method_name))
+            }
+        }
+    }
+
+    /// Schedules the cancellation of the running query without waiting for it to be sent.
+    fn cancel(&self) {
+        let Some(cancellations) = &self.cancellations else {
+            return;
+        };
+        let cancellation = Cancellation {
+            method_name: self.method_name,
+            pool: self.pool.clone(),
+            backend_transaction: self.backend_transaction,
+        };
+        if cancellations.try_send(cancellation).is_ok() {
+            metrics::increment_counter!("api.web3.cancelled_queries", "method" => self.method_name);
+        } else {
+            metrics::increment_counter!("api.web3.dropped_query_cancellations", "method" => self.method_name);
+        }
+    }
+}
+
+/// Cancels the running query if the future running it is dropped.
+#[derive(Debug)]
+struct CancelOnDrop<'a> {
+    deadline: &'a QueryDeadline,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            // The cancellation is sent asynchronously, so the connection may be reused by another request
+            // by then; it's only cancelled if it's still in the transaction of this request.
+            self.deadline.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadlines_are_overridden_per_method() {
+        let deadlines = RequestDeadlines {
+            default: Some(Duration::from_secs(5)),
+            overrides: Arc::new(HashMap::from([(
+                "eth_getLogs".to_owned(),
+                Duration::from_secs(20),
+            )])),
+            cancellations: None,
+        };
+        assert_eq!(deadlines.get("eth_getLogs"), Some(Duration::from_secs(20)));
+        assert_eq!(
+            deadlines.get("zks_getTransfers"),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(RequestDeadlines::default().get("eth_getLogs"), None);
+    }
+}
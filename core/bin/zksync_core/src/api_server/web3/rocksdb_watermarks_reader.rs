@@ -0,0 +1,136 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context as _;
+use tempfile::TempDir;
+
+use zksync_config::DBConfig;
+use zksync_storage::{db::Database, RocksDB};
+use zksync_types::L1BatchNumber;
+
+use crate::block_reverter::{read_l1_batch_number, RocksdbWatermarks};
+
+/// Reads the L1 batches the RocksDB instances on this machine are at, used to build the block revert plans.
+///
+/// Each instance is opened once as a secondary instance and is caught up with the instance used by the node
+/// on every read, so that the reads observe the latest state of the instances without reopening them.
+#[derive(Debug, Clone)]
+pub struct RocksdbWatermarksReader {
+    tree: Arc<FollowedInstance>,
+    lightweight_tree: Arc<FollowedInstance>,
+    state_keeper_cache: Arc<FollowedInstance>,
+}
+
+impl RocksdbWatermarksReader {
+    pub fn new(config: &DBConfig) -> Self {
+        Self {
+            tree: Arc::new(FollowedInstance::new(Database::MerkleTree, config.path())),
+            lightweight_tree: Arc::new(FollowedInstance::new(
+                Database::MerkleTree,
+                config.merkle_tree_fast_ssd_path(),
+            )),
+            state_keeper_cache: Arc::new(FollowedInstance::new(
+                Database::StateKeeper,
+                config.state_keeper_db_path(),
+            )),
+        }
+    }
+
+    pub fn read(&self) -> anyhow::Result<RocksdbWatermarks> {
+        Ok(RocksdbWatermarks {
+            tree: self.tree.read_l1_batch_number()?,
+            lightweight_tree: self.lightweight_tree.read_l1_batch_number()?,
+            state_keeper_cache: self.state_keeper_cache.read_l1_batch_number()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FollowedInstance {
+    database: Database,
+    path: String,
+    /// Secondary instance together with the directory for its logs, opened on the first read.
+    /// The instance is declared first, so that it's dropped before the directory.
+    secondary: Mutex<Option<(RocksDB, TempDir)>>,
+}
+
+impl FollowedInstance {
+    fn new(database: Database, path: &str) -> Self {
+        Self {
+            database,
+            path: path.to_owned(),
+            secondary: Mutex::default(),
+        }
+    }
+
+    /// Returns `None` if the instance doesn't exist.
+    fn read_l1_batch_number(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        if !Path::new(&self.path).join("CURRENT").exists() {
+            return Ok(None);
+        }
+
+        let mut secondary = self.secondary.lock().unwrap();
+        if secondary.is_none() {
+            let secondary_dir = TempDir::new()
+                .context("failed to create directory for the secondary RocksDB instance")?;
+            let db = RocksDB::new_secondary(self.database, &self.path, secondary_dir.path())
+                .with_context(|| {
+                    format!(
+                        "failed to open secondary RocksDB instance for {}",
+                        self.path
+                    )
+                })?;
+            *secondary = Some((db, secondary_dir));
+            metrics::increment_counter!("api.web3.rocksdb_watermarks_reader.opened");
+        }
+
+        let (db, _) = secondary.as_ref().unwrap();
+        let l1_batch_number = db
+            .catch_up_with_primary()
+            .and_then(|()| read_l1_batch_number(self.database, db));
+        if l1_batch_number.is_err() {
+            // The instance may have been replaced, e.g. restored from a backup; it's reopened on the next read.
+            *secondary = None;
+        }
+        l1_batch_number
+            .map(Some)
+            .with_context(|| format!("failed to read RocksDB instance at {}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_state::secondary_storage::SecondaryStateStorage;
+
+    #[test]
+    fn reader_follows_state_keeper_cache() {
+        let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+        let state_keeper_db_path = temp_dir.path().join("state_keeper");
+        let config = DBConfig {
+            path: temp_dir.path().join("tree").to_str().unwrap().to_owned(),
+            state_keeper_db_path: state_keeper_db_path.to_str().unwrap().to_owned(),
+            merkle_tree_fast_ssd_path: temp_dir
+                .path()
+                .join("lightweight")
+                .to_str()
+                .unwrap()
+                .to_owned(),
+            ..DBConfig::default()
+        };
+        let reader = RocksdbWatermarksReader::new(&config);
+        assert_eq!(reader.read().unwrap(), RocksdbWatermarks::default());
+
+        let db = RocksDB::new(Database::StateKeeper, &state_keeper_db_path, false);
+        let mut state = SecondaryStateStorage::new(db);
+        state.save(L1BatchNumber(3));
+        let watermarks = reader.read().unwrap();
+        assert_eq!(watermarks.state_keeper_cache, Some(L1BatchNumber(3)));
+        assert_eq!(watermarks.tree, None);
+
+        // The secondary instance observes the changes without being reopened.
+        state.save(L1BatchNumber(5));
+        let watermarks = reader.read().unwrap();
+        assert_eq!(watermarks.state_keeper_cache, Some(L1BatchNumber(5)));
+    }
+}
@@ -3,17 +3,24 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use std::sync::RwLock;
 
 use crate::api_server::tx_sender::TxSender;
+use crate::api_server::web3::abi_cache::VerifiedAbiCache;
 use crate::api_server::web3::backend_jsonrpc::error::internal_error;
+use crate::api_server::web3::finality_cache::L1BatchFinalityCache;
+use crate::api_server::web3::log_tree_cache::L2ToL1LogTreeCache;
+use crate::api_server::web3::merkle_tree_reader::MerkleTreeReader;
+use crate::api_server::web3::request_deadline::{QueryDeadline, RequestDeadlines};
+use crate::api_server::web3::rocksdb_watermarks_reader::RocksdbWatermarksReader;
 
 use zksync_config::ZkSyncConfig;
-use zksync_dal::ConnectionPool;
+use zksync_dal::{feature_flags_dal::FeatureFlagsReader, ConnectionPool, StorageProcessor};
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::api::{self, TransactionRequest};
-use zksync_types::{l2::L2Tx, Address, MiniblockNumber, H256, U256, U64};
+use zksync_types::{l2::L2Tx, Address, L1BatchNumber, MiniblockNumber, H256, U256, U64};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Filter, TypedFilter},
@@ -28,6 +35,13 @@ pub struct RpcState {
     pub req_entities_limit: usize,
     pub config: &'static ZkSyncConfig,
     pub accounts: HashMap<Address, PrivateKeySigner>,
+    pub feature_flags: FeatureFlagsReader,
+    pub abi_cache: VerifiedAbiCache,
+    pub finality_cache: L1BatchFinalityCache,
+    pub log_tree_cache: L2ToL1LogTreeCache,
+    pub merkle_tree_reader: MerkleTreeReader,
+    pub rocksdb_watermarks_reader: RocksdbWatermarksReader,
+    pub request_deadlines: RequestDeadlines,
     #[cfg(feature = "openzeppelin_tests")]
     pub known_bytecodes: Arc<RwLock<HashSet<Vec<u8>>>>,
 }
@@ -44,6 +58,42 @@ impl RpcState {
         Ok((tx_request.try_into()?, hash))
     }
 
+    /// Fills in the finality of the receipts from the cached L1 batch finality watermarks.
+    pub fn set_receipts_finality(
+        &self,
+        receipts: &mut [api::TransactionReceipt],
+        endpoint_name: &'static str,
+    ) -> Result<(), Web3Error> {
+        if receipts.is_empty() {
+            return Ok(());
+        }
+        let watermarks = self
+            .finality_cache
+            .get()
+            .map_err(|err| internal_error(endpoint_name, err))?;
+        for receipt in receipts {
+            receipt.finality = Some(
+                watermarks.finality(
+                    receipt.block_number.is_some(),
+                    receipt
+                        .l1_batch_number
+                        .map(|number| L1BatchNumber(number.as_u32())),
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// Acquires a connection for the heavy queries of the API method, bounded by the method deadline.
+    pub async fn access_storage_with_deadline(
+        &self,
+        method_name: &'static str,
+    ) -> Result<(StorageProcessor<'static>, QueryDeadline), Web3Error> {
+        self.request_deadlines
+            .access_storage(&self.connection_pool, method_name)
+            .await
+    }
+
     pub fn u64_to_block_number(n: U64) -> MiniblockNumber {
         if n.as_u64() > u32::MAX as u64 {
             MiniblockNumber(u32::MAX)
@@ -86,6 +136,22 @@ impl RpcState {
         Ok((from_block, to_block))
     }
 
+    /// Same as `resolve_filter_block_range`, but also checks the range against the block range limit
+    /// of the logs queries.
+    pub fn resolve_logs_block_range(
+        &self,
+        filter: &Filter,
+    ) -> Result<(MiniblockNumber, MiniblockNumber), Web3Error> {
+        let (from_block, to_block) = self.resolve_filter_block_range(filter)?;
+        if let Some(limit) = self.config.api.web3_json_rpc.logs_block_range_limit() {
+            let block_count = to_block.0.saturating_sub(from_block.0).saturating_add(1);
+            if block_count > limit {
+                return Err(Web3Error::LogsBlockRangeExceeded(limit));
+            }
+        }
+        Ok((from_block, to_block))
+    }
+
     /// Returns initial `from_block` for filter.
     /// It is equal to max(filter.from_block, PENDING_BLOCK).
     pub fn get_filter_from_block(&self, filter: &Filter) -> Result<MiniblockNumber, Web3Error> {
@@ -108,59 +174,133 @@ impl RpcState {
     }
 }
 
+#[derive(Debug, Clone)]
+struct InstalledFilter {
+    filter: TypedFilter,
+    last_polled_at: Instant,
+}
+
 /// Contains mapping from index to `Filter` with optional location.
-#[derive(Default, Debug, Clone)]
+/// Filters that haven't been polled for `ttl` are considered uninstalled. Once the max capacity
+/// is reached, the least recently polled filter is evicted to make room for the new one.
+#[derive(Debug, Clone)]
 pub struct Filters {
-    state: HashMap<U256, TypedFilter>,
+    state: HashMap<U256, InstalledFilter>,
     max_cap: usize,
+    ttl: Duration,
 }
 
 impl Filters {
-    /// Instantiates `Filters` with given max capacity.
-    pub fn new(max_cap: usize) -> Self {
+    /// Instantiates `Filters` with given max capacity and time-to-live of the filters.
+    pub fn new(max_cap: usize, ttl: Duration) -> Self {
         Self {
             state: Default::default(),
             max_cap,
+            ttl,
         }
     }
 
     /// Adds filter to the state and returns its key.
     pub fn add(&mut self, filter: TypedFilter) -> U256 {
+        self.remove_expired();
+        // Check if we reached max capacity
+        if self.state.len() >= self.max_cap {
+            let least_recently_polled = self
+                .state
+                .iter()
+                .min_by_key(|(_, installed)| installed.last_polled_at)
+                .map(|(idx, _)| *idx);
+            if let Some(idx) = least_recently_polled {
+                self.remove(idx);
+            }
+        }
+
         let idx = loop {
             let val = H256::random().to_fixed_bytes().into();
             if !self.state.contains_key(&val) {
                 break val;
             }
         };
-        self.state.insert(idx, filter);
-
-        // Check if we reached max capacity
-        if self.state.len() > self.max_cap {
-            if let Some(first) = self.state.keys().next().cloned() {
-                self.remove(first);
-            }
-        }
-
+        self.state.insert(
+            idx,
+            InstalledFilter {
+                filter,
+                last_polled_at: Instant::now(),
+            },
+        );
+        metrics::gauge!("api.web3.filters", self.state.len() as f64);
         idx
     }
 
-    /// Retrieves filter from the state.
+    /// Retrieves filter from the state. Returns `None` if the filter has expired.
     pub fn get(&self, index: U256) -> Option<&TypedFilter> {
-        self.state.get(&index)
+        self.state
+            .get(&index)
+            .filter(|installed| installed.last_polled_at.elapsed() < self.ttl)
+            .map(|installed| &installed.filter)
     }
 
-    /// Updates filter in the state.
+    /// Updates filter in the state and prolongs its lifetime. Expired filters are not revived:
+    /// they are removed, and `false` is returned.
     pub fn update(&mut self, index: U256, new_filter: TypedFilter) -> bool {
-        if let Some(typed_filter) = self.state.get_mut(&index) {
-            *typed_filter = new_filter;
-            true
-        } else {
-            false
+        let Some(installed) = self.state.get_mut(&index) else {
+            return false;
+        };
+        if installed.last_polled_at.elapsed() >= self.ttl {
+            self.remove(index);
+            return false;
         }
+        installed.filter = new_filter;
+        installed.last_polled_at = Instant::now();
+        true
     }
 
     /// Removes filter from the map.
     pub fn remove(&mut self, index: U256) -> bool {
-        self.state.remove(&index).is_some()
+        let removed = self.state.remove(&index).is_some();
+        metrics::gauge!("api.web3.filters", self.state.len() as f64);
+        removed
+    }
+
+    fn remove_expired(&mut self) {
+        let ttl = self.ttl;
+        self.state
+            .retain(|_, installed| installed.last_polled_at.elapsed() < ttl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_are_evicted_by_ttl() {
+        let mut filters = Filters::new(10, Duration::from_millis(50));
+        let idx = filters.add(TypedFilter::Blocks(MiniblockNumber(1)));
+        assert!(filters.get(idx).is_some());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(filters.get(idx).is_none());
+        // Expired filters are removed once a new filter is installed.
+        let other_idx = filters.add(TypedFilter::Blocks(MiniblockNumber(1)));
+        assert!(!filters.remove(idx));
+
+        // Expired filters can't be revived by an update.
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(!filters.update(other_idx, TypedFilter::Blocks(MiniblockNumber(2))));
+        assert!(filters.get(other_idx).is_none());
+    }
+
+    #[test]
+    fn least_recently_polled_filter_is_evicted() {
+        let mut filters = Filters::new(2, Duration::from_secs(60));
+        let first = filters.add(TypedFilter::Blocks(MiniblockNumber(1)));
+        let second = filters.add(TypedFilter::Blocks(MiniblockNumber(1)));
+        assert!(filters.update(first, TypedFilter::Blocks(MiniblockNumber(2))));
+
+        let third = filters.add(TypedFilter::Blocks(MiniblockNumber(1)));
+        assert!(filters.get(first).is_some());
+        assert!(filters.get(second).is_none());
+        assert!(filters.get(third).is_some());
     }
 }
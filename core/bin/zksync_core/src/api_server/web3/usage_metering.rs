@@ -0,0 +1,428 @@
+//! Metering of the API usage per API key, for the operators billing the access to their nodes.
+//!
+//! Requests made with the API keys of the configured tenants are counted per key and method, and weighted
+//! with the compute units of the method, which reflect how expensive the method is to serve. The usage
+//! is aggregated in memory and periodically exported either to the `api_usage_records` table or to a webhook.
+//! Both API backends are metered: the `jsonrpc` one via [`ApiUsageMeteringMiddleware`], and the HTTP server
+//! of the `jsonrpsee` one via [`ApiKeyLayer`] and [`ApiUsageLogger`].
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::future::Either;
+use jsonrpc_core::middleware::Middleware;
+use jsonrpc_core::{Call, FutureOutput, FutureResponse, Metadata, Output};
+use jsonrpc_http_server::hyper::Request;
+use reqwest::Client;
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::task::futures::TaskLocalFuture;
+use tower::{Layer, Service};
+
+use zksync_web3_decl::jsonrpsee::server::logger::{
+    HttpRequest, Logger, MethodKind, Params, TransportProtocol,
+};
+
+use zksync_config::configs::api::Web3JsonRpc;
+use zksync_dal::api_usage_dal::ApiUsageRecord;
+use zksync_dal::ConnectionPool;
+
+use super::audit_log::{api_key_hash, CallerKey, API_KEY_HEADER};
+
+/// Compute units charged for the methods which don't have an explicit weight.
+const DEFAULT_COMPUTE_UNITS: u32 = 10;
+/// Max number of the (API key, method) pairs metered in a period. Once it's reached, the calls
+/// of the methods not metered for the key yet are recorded under [`OTHER_METHODS`], so that the calls
+/// of the arbitrary (e.g., non-existing) methods can't grow the usage unboundedly.
+const MAX_USAGE_ENTRIES: usize = 10_000;
+const OTHER_METHODS: &str = "other";
+
+tokio::task_local! {
+    /// Hash of the API key of the HTTP request handled by the `jsonrpsee` backend.
+    static API_KEY_HASH: Option<String>;
+}
+
+fn default_compute_units(method: &str) -> u32 {
+    match method {
+        "eth_chainId" | "eth_blockNumber" | "net_version" | "web3_clientVersion" => 1,
+        "eth_sendRawTransaction" | "zks_getStorageValues" => 30,
+        "eth_call" | "eth_estimateGas" | "zks_estimateFee" | "zks_estimateGasL1ToL2" => 50,
+        "eth_getLogs" | "eth_getFilterLogs" | "zks_getL2ToL1LogProof" | "zks_getProof" => 75,
+        "debug_traceCall"
+        | "debug_traceTransaction"
+        | "debug_traceBlockByNumber"
+        | "debug_traceBlockByHash" => 100,
+        _ => DEFAULT_COMPUTE_UNITS,
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Usage {
+    requests: u64,
+    compute_units: u64,
+}
+
+#[derive(Debug)]
+struct UsagePeriod {
+    started_at: NaiveDateTime,
+    /// Usage per (API key hash, method).
+    usage: HashMap<(String, String), Usage>,
+}
+
+#[derive(Debug)]
+struct UsageMeter {
+    /// Hashes of the API keys whose usage is metered.
+    api_key_hashes: HashSet<String>,
+    compute_units: HashMap<String, u32>,
+    period: Mutex<UsagePeriod>,
+}
+
+impl UsageMeter {
+    fn new(api_keys: &[String], compute_units: HashMap<String, u32>, now: NaiveDateTime) -> Self {
+        Self {
+            api_key_hashes: api_keys.iter().map(|key| api_key_hash(key)).collect(),
+            compute_units,
+            period: Mutex::new(UsagePeriod {
+                started_at: now,
+                usage: HashMap::new(),
+            }),
+        }
+    }
+
+    fn compute_units(&self, method: &str) -> u32 {
+        self.compute_units
+            .get(method)
+            .copied()
+            .unwrap_or_else(|| default_compute_units(method))
+    }
+
+    fn record(&self, api_key_hash: String, method: &str) {
+        if !self.api_key_hashes.contains(&api_key_hash) {
+            return;
+        }
+        let compute_units = self.compute_units(method);
+        let mut period = self.period.lock().unwrap();
+        let mut key = (api_key_hash, method.to_owned());
+        if period.usage.len() >= MAX_USAGE_ENTRIES && !period.usage.contains_key(&key) {
+            key.1 = OTHER_METHODS.to_owned();
+        }
+        let usage = period.usage.entry(key).or_default();
+        usage.requests += 1;
+        usage.compute_units += u64::from(compute_units);
+    }
+
+    /// Ends the current period at `now` and returns its usage.
+    fn take_records(&self, now: NaiveDateTime) -> Vec<ApiUsageRecord> {
+        let mut period = self.period.lock().unwrap();
+        let period_start = mem::replace(&mut period.started_at, now);
+        let usage = mem::take(&mut period.usage);
+        drop(period);
+
+        usage
+            .into_iter()
+            .map(|((api_key_hash, method), usage)| ApiUsageRecord {
+                api_key_hash,
+                method,
+                requests: usage.requests,
+                compute_units: usage.compute_units,
+                period_start,
+                period_end: now,
+            })
+            .collect()
+    }
+}
+
+/// Middleware counting the requests made with an API key.
+#[derive(Debug, Clone, Default)]
+pub struct ApiUsageMeteringMiddleware {
+    meter: Option<Arc<UsageMeter>>,
+}
+
+impl ApiUsageMeteringMiddleware {
+    /// Creates the middleware and the exporter of the usage, if the metering is enabled.
+    pub fn new(config: &Web3JsonRpc) -> (Self, Option<ApiUsageExporter>) {
+        if !config.usage_metering_enabled() {
+            return (Self::default(), None);
+        }
+
+        let meter = Arc::new(UsageMeter::new(
+            config.usage_api_keys.as_deref().unwrap_or_default(),
+            config.usage_compute_units(),
+            Utc::now().naive_utc(),
+        ));
+        let middleware = Self {
+            meter: Some(meter.clone()),
+        };
+        let exporter = ApiUsageExporter {
+            meter,
+            pool: ConnectionPool::new(Some(1), true),
+            client: Client::new(),
+            webhook_url: config.usage_webhook_url.clone(),
+            interval: config.usage_export_interval(),
+        };
+        (middleware, Some(exporter))
+    }
+
+    /// Returns the logger metering the calls of the `jsonrpsee` backend. The server must be built
+    /// with [`ApiKeyLayer`], so that the API keys of the calls are known.
+    pub fn jsonrpsee_logger(&self) -> ApiUsageLogger {
+        ApiUsageLogger {
+            meter: self.meter.clone(),
+        }
+    }
+}
+
+impl<M: Metadata + CallerKey> Middleware<M> for ApiUsageMeteringMiddleware {
+    type Future = FutureResponse;
+    type CallFuture = FutureOutput;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: futures::Future<Output = Option<Output>> + Send + 'static,
+    {
+        if let (Some(meter), Call::MethodCall(method_call)) = (&self.meter, &call) {
+            if let Some(api_key_hash) = meta.api_key_hash() {
+                meter.record(api_key_hash, &method_call.method);
+            }
+        }
+        Either::Right(next(call, meta))
+    }
+}
+
+/// Tower layer exposing the API key of the HTTP request to [`ApiUsageLogger`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiKeyLayer;
+
+impl<S> Layer<S> for ApiKeyLayer {
+    type Service = ApiKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for ApiKeyService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<Option<String>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let hash = request
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(api_key_hash);
+        API_KEY_HASH.scope(hash, self.inner.call(request))
+    }
+}
+
+/// `jsonrpsee` logger metering the method calls made with an API key. Calls of the unknown methods
+/// aren't metered.
+#[derive(Debug, Clone)]
+pub struct ApiUsageLogger {
+    meter: Option<Arc<UsageMeter>>,
+}
+
+impl Logger for ApiUsageLogger {
+    type Instant = ();
+
+    fn on_connect(&self, _: SocketAddr, _: &HttpRequest, _: TransportProtocol) {}
+
+    fn on_request(&self, _: TransportProtocol) -> Self::Instant {}
+
+    fn on_call(&self, method_name: &str, _: Params, kind: MethodKind, _: TransportProtocol) {
+        let Some(meter) = &self.meter else {
+            return;
+        };
+        if !matches!(kind, MethodKind::MethodCall) {
+            return;
+        }
+        if let Ok(Some(api_key_hash)) = API_KEY_HASH.try_with(Clone::clone) {
+            meter.record(api_key_hash, method_name);
+        }
+    }
+
+    fn on_result(&self, _: &str, _: bool, _: Self::Instant, _: TransportProtocol) {}
+
+    fn on_response(&self, _: &str, _: Self::Instant, _: TransportProtocol) {}
+
+    fn on_disconnect(&self, _: SocketAddr, _: TransportProtocol) {}
+}
+
+/// Usage record posted to the webhook.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookUsageRecord<'a> {
+    api_key_hash: &'a str,
+    method: &'a str,
+    requests: u64,
+    compute_units: u64,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+}
+
+impl<'a> From<&'a ApiUsageRecord> for WebhookUsageRecord<'a> {
+    fn from(record: &'a ApiUsageRecord) -> Self {
+        Self {
+            api_key_hash: &record.api_key_hash,
+            method: &record.method,
+            requests: record.requests,
+            compute_units: record.compute_units,
+            period_start: DateTime::<Utc>::from_utc(record.period_start, Utc),
+            period_end: DateTime::<Utc>::from_utc(record.period_end, Utc),
+        }
+    }
+}
+
+/// Periodically exports the metered usage.
+#[derive(Debug)]
+pub struct ApiUsageExporter {
+    meter: Arc<UsageMeter>,
+    pool: ConnectionPool,
+    client: Client,
+    webhook_url: Option<String>,
+    interval: Duration,
+}
+
+impl ApiUsageExporter {
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) {
+        let mut timer = tokio::time::interval(self.interval);
+        // The first tick completes immediately.
+        timer.tick().await;
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, API usage exporter is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            self.export().await;
+        }
+        // Export the usage of the last (incomplete) period, so that it's not lost.
+        self.export().await;
+    }
+
+    async fn export(&self) {
+        let records = self.meter.take_records(Utc::now().naive_utc());
+        if records.is_empty() {
+            return;
+        }
+
+        if let Some(webhook_url) = &self.webhook_url {
+            let payload: Vec<_> = records.iter().map(WebhookUsageRecord::from).collect();
+            let response = self.client.post(webhook_url).json(&payload).send().await;
+            match response.and_then(|response| response.error_for_status()) {
+                Ok(_) => {
+                    metrics::counter!("api.usage_metering.exported_records", records.len() as u64, "sink" => "webhook");
+                    return;
+                }
+                Err(err) => {
+                    // Usage is written to the DB instead, so that it isn't lost.
+                    vlog::warn!("Failed to post API usage to the webhook: {}", err);
+                    metrics::counter!("api.usage_metering.webhook_failures", 1);
+                }
+            }
+        }
+
+        self.pool
+            .access_storage()
+            .await
+            .api_usage_dal()
+            .insert_records(&records);
+        metrics::counter!("api.usage_metering.exported_records", records.len() as u64, "sink" => "db");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_is_aggregated_per_key_and_method() {
+        let start = NaiveDateTime::from_timestamp_opt(1_000, 0).unwrap();
+        let overrides = HashMap::from([("eth_call".to_owned(), 20)]);
+        let meter = UsageMeter::new(&["key1".to_owned(), "key2".to_owned()], overrides, start);
+        let (key1, key2) = (api_key_hash("key1"), api_key_hash("key2"));
+        meter.record(key1.clone(), "eth_call");
+        meter.record(key1.clone(), "eth_call");
+        meter.record(key1.clone(), "eth_getLogs");
+        meter.record(key2.clone(), "eth_call");
+        // Requests made with unknown keys aren't metered.
+        meter.record(api_key_hash("key3"), "eth_call");
+
+        let end = NaiveDateTime::from_timestamp_opt(1_060, 0).unwrap();
+        let mut records = meter.take_records(end);
+        records.sort_by(|a, b| (&a.api_key_hash, &a.method).cmp(&(&b.api_key_hash, &b.method)));
+        let usage: Vec<_> = records
+            .iter()
+            .map(|record| {
+                (
+                    record.api_key_hash.as_str(),
+                    record.method.as_str(),
+                    record.requests,
+                    record.compute_units,
+                )
+            })
+            .collect();
+        let mut expected = [
+            (key1.as_str(), "eth_call", 2, 40),
+            (key1.as_str(), "eth_getLogs", 1, 75),
+            (key2.as_str(), "eth_call", 1, 20),
+        ];
+        expected.sort_unstable();
+        assert_eq!(usage, expected);
+        assert!(records
+            .iter()
+            .all(|record| record.period_start == start && record.period_end == end));
+
+        // The next period starts where the previous one has ended.
+        meter.record(key1, "eth_chainId");
+        let records = meter.take_records(NaiveDateTime::from_timestamp_opt(1_120, 0).unwrap());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].period_start, end);
+        assert_eq!(records[0].compute_units, 1);
+    }
+
+    #[test]
+    fn metered_usage_is_bounded() {
+        let start = NaiveDateTime::from_timestamp_opt(1_000, 0).unwrap();
+        let meter = UsageMeter::new(&["key".to_owned()], HashMap::new(), start);
+        let key = api_key_hash("key");
+        for i in 0..MAX_USAGE_ENTRIES + 10 {
+            meter.record(key.clone(), &format!("method_{}", i));
+        }
+        // Already metered methods are still metered separately.
+        meter.record(key.clone(), "method_0");
+
+        let records = meter.take_records(start);
+        assert_eq!(records.len(), MAX_USAGE_ENTRIES + 1);
+        let other = records
+            .iter()
+            .find(|record| record.method == OTHER_METHODS)
+            .unwrap();
+        assert_eq!(other.requests, 10);
+        let method_0 = records
+            .iter()
+            .find(|record| record.method == "method_0")
+            .unwrap();
+        assert_eq!(method_0.requests, 2);
+    }
+}
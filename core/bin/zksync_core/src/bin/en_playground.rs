@@ -2,15 +2,24 @@
 //! It's temporary and once a PoC is ready, this file will be replaced by the real EN entrypoint.
 use zksync_config::ZkSyncConfig;
 use zksync_core::{
-    state_keeper::{seal_criteria::SealManager, ZkSyncStateKeeper},
+    state_keeper::{
+        seal_criteria::SealManager, sealed_miniblocks::SealedMiniblocksSender, ZkSyncStateKeeper,
+    },
     sync_layer::{
-        batch_status_updater::run_batch_status_updater, external_io::ExternalIO,
-        fetcher::MainNodeFetcher, genesis::perform_genesis_if_needed,
-        mock_batch_executor::MockBatchExecutorBuilder, ActionQueue, ExternalNodeSealer,
+        batch_status_updater::run_batch_status_updater,
+        external_io::ExternalIO,
+        fetcher::MainNodeFetcher,
+        genesis::perform_genesis_if_needed,
+        handshake::{local_identity, perform_handshake},
+        mock_batch_executor::MockBatchExecutorBuilder,
+        snapshot_diff::{catch_up_via_snapshot_diffs, CatchUpError},
+        ActionQueue, ExternalNodeSealer, SyncStart,
     },
 };
 use zksync_dal::ConnectionPool;
-use zksync_types::{Address, L1BatchNumber, MiniblockNumber};
+use zksync_object_store::object_store::create_object_store_from_env;
+use zksync_types::{Address, L2ChainId};
+use zksync_web3_decl::jsonrpsee::http_client::HttpClientBuilder;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -21,6 +30,38 @@ async fn main() -> anyhow::Result<()> {
     vlog::info!("Started the EN playground");
 
     perform_genesis_if_needed(&mut connection_pool.access_storage().await, &config).await;
+    let identity = local_identity(
+        &mut connection_pool.access_storage().await,
+        L2ChainId(config.chain.eth.zksync_network_id),
+    );
+    let session = perform_handshake(
+        config.api.web3_json_rpc.main_node_url.as_ref().unwrap(),
+        &identity,
+    )
+    .await;
+
+    let main_node_url = config.api.web3_json_rpc.main_node_url.clone().unwrap();
+    let main_node_client = HttpClientBuilder::default().build(&main_node_url)?;
+    // Instead of replaying every miniblock missed while the node was offline, catch up using the state snapshot diffs.
+    let sync_start = match catch_up_via_snapshot_diffs(
+        &main_node_client,
+        create_object_store_from_env(),
+        &connection_pool,
+        config.db.state_keeper_db_path(),
+    )
+    .await
+    {
+        Ok(sync_start) => sync_start,
+        Err(CatchUpError::NoDiffs(l1_batch_number)) => {
+            vlog::info!(
+                "No state snapshot diffs are applicable to L1 batch #{}, replaying the missed miniblocks",
+                l1_batch_number
+            );
+            SyncStart::resume(&main_node_client, l1_batch_number).await?
+        }
+        Err(CatchUpError::Internal(err)) => return Err(err),
+    };
+    vlog::info!("Starting to sync from {:?}", sync_start);
 
     let action_queue = ActionQueue::new();
     let en_sealer = ExternalNodeSealer::new(action_queue.clone());
@@ -33,7 +74,14 @@ async fn main() -> anyhow::Result<()> {
 
     let mock_batch_executor_base = Box::new(MockBatchExecutorBuilder);
 
-    let io = Box::new(ExternalIO::new(Address::default(), action_queue.clone()));
+    let io = Box::new(ExternalIO::new(
+        Address::default(),
+        sync_start.l1_batch_number,
+        sync_start.miniblock_number,
+        action_queue.clone(),
+        // The playground doesn't serve the WS API, so there are no subscribers to the sealed miniblocks.
+        SealedMiniblocksSender::new(),
+    ));
     let (_stop_sender, stop_receiver) = tokio::sync::watch::channel::<bool>(false);
 
     let state_keeper = ZkSyncStateKeeper::new(stop_receiver, io, mock_batch_executor_base, sealer);
@@ -49,12 +97,12 @@ async fn main() -> anyhow::Result<()> {
     // let main_node_url = "https://zksync2-mainnet.zksync.io:443";
 
     let fetcher = MainNodeFetcher::new(
-        &config.api.web3_json_rpc.main_node_url.unwrap(),
-        L1BatchNumber(0),
-        MiniblockNumber(1),
-        L1BatchNumber(0),
-        L1BatchNumber(0),
-        L1BatchNumber(0),
+        session,
+        sync_start.last_l1_batch_number(),
+        sync_start.miniblock_number,
+        sync_start.last_l1_batch_number(),
+        sync_start.last_l1_batch_number(),
+        sync_start.last_l1_batch_number(),
         action_queue.clone(),
     );
 
@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use zksync_config::ZkSyncConfig;
+use zksync_contracts::{read_zbin_bytecode, BaseSystemContracts, SystemContractCode};
+use zksync_core::fee_repricing::{FeeModelProposal, FeeRepricer};
+use zksync_dal::ConnectionPool;
+use zksync_types::MiniblockNumber;
+use zksync_utils::bytecode::hash_bytecode;
+use zksync_utils::bytes_to_be_words;
+
+#[derive(StructOpt, Debug)]
+#[structopt(
+    name = "fee repricing tool",
+    about = "Replays historical transactions under a proposed fee model and reports fee deltas"
+)]
+struct Opt {
+    /// First miniblock to sample transactions from.
+    #[structopt(long)]
+    from_miniblock: u32,
+    /// Last miniblock (inclusive) to sample transactions from.
+    #[structopt(long)]
+    to_miniblock: u32,
+    /// Maximum number of transactions to replay.
+    #[structopt(long, default_value = "1000")]
+    sample_size: usize,
+    /// Proposed fair L2 gas price. The historical value of each miniblock is used if not set.
+    #[structopt(long)]
+    fair_l2_gas_price: Option<u64>,
+    /// Factor applied to the historical L1 gas price.
+    #[structopt(long, default_value = "1.0")]
+    l1_gas_price_scale_factor: f64,
+    /// Path to the `.zbin` bootloader with the proposed gas schedule, relative to `$ZKSYNC_HOME`.
+    /// The current proved block bootloader is used if not set.
+    #[structopt(long)]
+    bootloader: Option<PathBuf>,
+}
+
+fn main() {
+    let _sentry_guard = vlog::init();
+    let opt = Opt::from_args();
+    let config = ZkSyncConfig::from_env();
+
+    let mut base_system_contracts = BaseSystemContracts::load_from_disk();
+    if let Some(bootloader) = &opt.bootloader {
+        let bytecode = read_zbin_bytecode(bootloader);
+        base_system_contracts.bootloader = SystemContractCode {
+            hash: hash_bytecode(&bytecode),
+            code: bytes_to_be_words(bytecode),
+        };
+    }
+    let proposal = FeeModelProposal {
+        fair_l2_gas_price: opt.fair_l2_gas_price,
+        l1_gas_price_scale_factor: opt.l1_gas_price_scale_factor,
+        base_system_contracts,
+    };
+
+    let connection_pool = ConnectionPool::new(Some(1), false);
+    let repricer = FeeRepricer::new(
+        connection_pool,
+        proposal,
+        config.chain.state_keeper.validation_computational_gas_limit,
+    );
+    let report = repricer.run(
+        MiniblockNumber(opt.from_miniblock),
+        MiniblockNumber(opt.to_miniblock),
+        opt.sample_size,
+    );
+    println!("{}", report);
+}
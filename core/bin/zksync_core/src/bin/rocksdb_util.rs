@@ -1,5 +1,8 @@
 use structopt::StructOpt;
 use zksync_config::DBConfig;
+use zksync_core::rocksdb_backup::{restore_from_object_store, BackupTarget};
+use zksync_dal::ConnectionPool;
+use zksync_object_store::object_store::create_object_store_from_env;
 use zksync_storage::rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
 use zksync_storage::rocksdb::{Error, Options, DB};
 
@@ -14,6 +17,16 @@ enum Opt {
 
     #[structopt(name = "restore-from-backup", about = "Restores rocksdb from backup")]
     Restore,
+
+    #[structopt(
+        name = "restore-from-object-store",
+        about = "Restores rocksdb from the latest backup in the object store consistent with Postgres"
+    )]
+    RestoreFromObjectStore {
+        /// Instance to restore: `tree`, `tree_lightweight` or `state_keeper_cache`.
+        #[structopt(long)]
+        target: String,
+    },
 }
 
 fn create_backup(config: &DBConfig) -> Result<(), Error> {
@@ -39,6 +52,19 @@ fn main() {
     match Opt::from_args() {
         Opt::Backup => create_backup(&config).unwrap(),
         Opt::Restore => restore_from_latest_backup(&config).unwrap(),
+        Opt::RestoreFromObjectStore { target } => {
+            let target = BackupTarget::from_name(&target)
+                .unwrap_or_else(|| panic!("unknown backup target {}", target));
+            let pool = ConnectionPool::new(Some(1), true);
+            let l1_batch_number =
+                restore_from_object_store(&config, target, &create_object_store_from_env(), &pool)
+                    .unwrap();
+            println!(
+                "Restored {}, next L1 batch to process is #{}",
+                target.name(),
+                l1_batch_number
+            );
+        }
     }
 }
 
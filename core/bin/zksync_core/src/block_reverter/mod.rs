@@ -0,0 +1,713 @@
+//! Reverting the node state to an earlier L1 batch.
+//!
+//! The node state is reverted in stages, each touching a single storage. Stages are applied in a fixed order:
+//! the RocksDB instances are reverted using the storage logs in Postgres, so they must be reverted before
+//! Postgres. Every stage checks whether its storage is ahead of the target L1 batch and is skipped otherwise,
+//! so an interrupted revert can be resumed by simply running it again. `BlockReverter::plan()` reports
+//! what each stage would revert without changing anything; processes which can't open the RocksDB instances
+//! on each call (e.g., the API server) read the [`RocksdbWatermarks`] themselves and pass them to
+//! `BlockReverter::plan_with_watermarks()`.
+//!
+//! Reverts are run either by the `block_reverter` binary or via `admin_revertBlocks`; in both cases,
+//! the components listed in [`STATE_WRITING_COMPONENTS`] must be stopped on all instances.
+
+use std::path::Path;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use zksync_config::{DBConfig, ZkSyncConfig};
+use zksync_contracts::zksync_contract;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::clients::http_client::{EthInterface, EthereumClient};
+use zksync_merkle_tree::{TreeError, ZkSyncTree};
+use zksync_object_store::object_store::{
+    DynamicObjectStore, ROCKSDB_BACKUPS_BUCKET_PATH, STATE_SNAPSHOT_DIFFS_BUCKET_PATH,
+};
+use zksync_state::secondary_storage::SecondaryStateStorage;
+use zksync_storage::db::Database;
+use zksync_storage::{rocksdb, RocksDB};
+use zksync_types::aggregated_operations::AggregatedActionType;
+use zksync_types::api::BlockRevertPlan;
+use zksync_types::ethabi::Token;
+use zksync_types::web3::contract::Options;
+use zksync_types::{L1BatchNumber, H256, U256};
+
+/// Part of the node state reverted separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevertStage {
+    /// Eth txs sent for the reverted L1 batches.
+    EthSender,
+    /// Full and lightweight Merkle trees.
+    MerkleTree,
+    StateKeeperCache,
+    Postgres,
+    /// Records of the RocksDB backups taken after the target L1 batch, together with the backed up files.
+    RocksdbBackups,
+    /// Records of the state snapshot diffs covering the reverted L1 batches, together with the diff files.
+    StateSnapshotDiffs,
+}
+
+impl RevertStage {
+    /// All stages in the order they are applied.
+    pub const ALL: [Self; 6] = [
+        Self::EthSender,
+        Self::MerkleTree,
+        Self::StateKeeperCache,
+        Self::Postgres,
+        Self::RocksdbBackups,
+        Self::StateSnapshotDiffs,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::EthSender => "eth_sender",
+            Self::MerkleTree => "merkle_tree",
+            Self::StateKeeperCache => "state_keeper_cache",
+            Self::Postgres => "postgres",
+            Self::RocksdbBackups => "rocksdb_backups",
+            Self::StateSnapshotDiffs => "state_snapshot_diffs",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|stage| stage.name() == name)
+    }
+
+    /// Checks whether the stage has anything to revert.
+    pub fn is_pending(self, plan: &BlockRevertPlan) -> bool {
+        let next_l1_batch_number = plan.last_l1_batch_to_keep + 1;
+        let is_ahead = |l1_batch_number: Option<L1BatchNumber>| {
+            l1_batch_number.map_or(false, |number| number > next_l1_batch_number)
+        };
+        match self {
+            Self::EthSender => plan.eth_txs_to_remove > 0 || plan.l1_batches_to_unlink > 0,
+            Self::MerkleTree => {
+                is_ahead(plan.tree_l1_batch_number)
+                    || is_ahead(plan.lightweight_tree_l1_batch_number)
+            }
+            Self::StateKeeperCache => is_ahead(plan.state_keeper_cache_l1_batch_number),
+            Self::Postgres => plan.l1_batches_to_remove > 0 || plan.miniblocks_to_remove > 0,
+            Self::RocksdbBackups => plan.rocksdb_backups_to_remove > 0,
+            Self::StateSnapshotDiffs => plan.state_snapshot_diffs_to_remove > 0,
+        }
+    }
+}
+
+/// Components (as named in `Components`) writing the state reverted by the block reverter.
+pub const STATE_WRITING_COMPONENTS: [&str; 9] = [
+    "Tree",
+    "TreeLightweight",
+    "TreeBackup",
+    "EthWatcher",
+    "EthTxAggregator",
+    "EthTxManager",
+    "StateKeeper",
+    "RocksdbBackup",
+    "StateSnapshotDiffs",
+];
+
+/// Returns the components from [`STATE_WRITING_COMPONENTS`] mentioned in the components description
+/// (e.g., `Components([HttpApi, StateKeeper])`) advertised by a running instance.
+pub fn state_writing_components(components: &str) -> Vec<&'static str> {
+    let names: Vec<_> = components
+        .split(|char: char| !char.is_ascii_alphanumeric())
+        .collect();
+    STATE_WRITING_COMPONENTS
+        .iter()
+        .copied()
+        .filter(|component| names.contains(component))
+        .collect()
+}
+
+#[derive(Debug, Error)]
+pub enum BlockReverterError {
+    #[error("L1 batch #{0} is not in Postgres")]
+    UnknownL1Batch(L1BatchNumber),
+    #[error("L1 batch #{requested} can't be reverted to, L1 batches up to #{last_executed} are executed on L1")]
+    AlreadyExecuted {
+        requested: L1BatchNumber,
+        last_executed: L1BatchNumber,
+    },
+    #[error(
+        "{} is ahead of the L1 batch and can only be reverted before Postgres; \
+         reverting Postgres alone would require rebuilding it from scratch",
+        .0.name()
+    )]
+    UnrecoverableStage(RevertStage),
+    #[error(
+        "{} is not found on this machine, so it's unknown whether it's ahead of the L1 batch; \
+         Postgres must be reverted on the machine hosting it",
+        .0.name()
+    )]
+    UnknownRocksdbWatermark(RevertStage),
+    #[error(
+        "root hash of the reverted tree {tree:?} doesn't match the one in Postgres {postgres:?}"
+    )]
+    TreeRootHashMismatch { tree: H256, postgres: H256 },
+    #[error("root hash of L1 batch #{0} is not in Postgres")]
+    MissingRootHash(L1BatchNumber),
+    #[error("failed to save the reverted tree: {0}")]
+    TreeSave(#[from] TreeError),
+    #[error("failed to read RocksDB instance at {path}: {source}")]
+    Rocksdb {
+        path: String,
+        source: rocksdb::Error,
+    },
+}
+
+/// L1 batches the RocksDB instances on this machine are at, i.e., the numbers of the next L1 batches
+/// the instances would process. `None` if the instance doesn't exist.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RocksdbWatermarks {
+    pub tree: Option<L1BatchNumber>,
+    pub lightweight_tree: Option<L1BatchNumber>,
+    pub state_keeper_cache: Option<L1BatchNumber>,
+}
+
+impl RocksdbWatermarks {
+    /// Reads the watermarks by opening the instances in the read-only mode.
+    pub fn read(config: &DBConfig) -> Result<Self, BlockReverterError> {
+        Ok(Self {
+            tree: read_watermark(Database::MerkleTree, config.path())?,
+            lightweight_tree: read_watermark(
+                Database::MerkleTree,
+                config.merkle_tree_fast_ssd_path(),
+            )?,
+            state_keeper_cache: read_watermark(
+                Database::StateKeeper,
+                config.state_keeper_db_path(),
+            )?,
+        })
+    }
+}
+
+/// Reads the number of the next L1 batch to be processed by the instance.
+pub fn read_l1_batch_number(
+    database: Database,
+    db: &RocksDB,
+) -> Result<L1BatchNumber, rocksdb::Error> {
+    match database {
+        Database::MerkleTree => ZkSyncTree::read_block_number(db).map(L1BatchNumber),
+        Database::StateKeeper => SecondaryStateStorage::read_l1_batch_number(db),
+    }
+}
+
+fn read_watermark(
+    database: Database,
+    path: &str,
+) -> Result<Option<L1BatchNumber>, BlockReverterError> {
+    if !Path::new(path).join("CURRENT").exists() {
+        return Ok(None);
+    }
+    let to_error = |source| BlockReverterError::Rocksdb {
+        path: path.to_owned(),
+        source,
+    };
+    let db = RocksDB::try_new_read_only(database, path).map_err(to_error)?;
+    read_l1_batch_number(database, &db)
+        .map(Some)
+        .map_err(to_error)
+}
+
+/// Returns the RocksDB stage whose instance isn't found on this machine. The lightweight tree is optional
+/// as long as the full one is present, and vice versa.
+fn unknown_rocksdb_watermark(plan: &BlockRevertPlan) -> Option<RevertStage> {
+    if plan.tree_l1_batch_number.is_none() && plan.lightweight_tree_l1_batch_number.is_none() {
+        Some(RevertStage::MerkleTree)
+    } else if plan.state_keeper_cache_l1_batch_number.is_none() {
+        Some(RevertStage::StateKeeperCache)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockReverter {
+    config: ZkSyncConfig,
+    connection_pool: ConnectionPool,
+    /// Store with the RocksDB backups and the state snapshot diffs. If not set, only their records are removed.
+    object_store: Option<DynamicObjectStore>,
+}
+
+impl BlockReverter {
+    pub fn new(
+        config: ZkSyncConfig,
+        connection_pool: ConnectionPool,
+        object_store: Option<DynamicObjectStore>,
+    ) -> Self {
+        Self {
+            config,
+            connection_pool,
+            object_store,
+        }
+    }
+
+    /// Reports what would be reverted by rolling the node back to `last_l1_batch_to_keep`. The RocksDB instances
+    /// are opened read-only, so the plan can be obtained while the node is running.
+    pub fn plan(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> Result<BlockRevertPlan, BlockReverterError> {
+        let rocksdb_watermarks = RocksdbWatermarks::read(&self.config.db)?;
+        self.plan_with_watermarks(last_l1_batch_to_keep, rocksdb_watermarks)
+    }
+
+    /// Same as [`Self::plan()`], but with the RocksDB instances at the provided L1 batches.
+    pub fn plan_with_watermarks(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        rocksdb_watermarks: RocksdbWatermarks,
+    ) -> Result<BlockRevertPlan, BlockReverterError> {
+        if rocksdb_watermarks.state_keeper_cache.is_none() {
+            vlog::warn!(
+                "State keeper cache is not found at {}, so it's not reverted",
+                self.config.db.state_keeper_db_path()
+            );
+        }
+
+        let mut storage = self.connection_pool.access_storage_blocking();
+        let (_, last_miniblock_to_keep) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .ok_or(BlockReverterError::UnknownL1Batch(last_l1_batch_to_keep))?;
+        let sealed_l1_batch_number = storage.blocks_dal().get_sealed_block_number();
+        let sealed_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number();
+        let (eth_txs_to_remove, l1_batches_to_unlink) = storage
+            .eth_sender_dal()
+            .get_eth_txs_revert_stats(last_l1_batch_to_keep);
+
+        Ok(BlockRevertPlan {
+            last_l1_batch_to_keep,
+            last_miniblock_to_keep,
+            eth_txs_to_remove,
+            l1_batches_to_unlink,
+            tree_l1_batch_number: rocksdb_watermarks.tree,
+            lightweight_tree_l1_batch_number: rocksdb_watermarks.lightweight_tree,
+            state_keeper_cache_l1_batch_number: rocksdb_watermarks.state_keeper_cache,
+            l1_batches_to_remove: sealed_l1_batch_number
+                .0
+                .saturating_sub(last_l1_batch_to_keep.0),
+            miniblocks_to_remove: sealed_miniblock_number
+                .0
+                .saturating_sub(last_miniblock_to_keep.0),
+            transactions_to_reset: storage
+                .transactions_dal()
+                .get_tx_count_after_miniblock(last_miniblock_to_keep),
+            rocksdb_backups_to_remove: storage
+                .rocksdb_backups_dal()
+                .get_backups_after(last_l1_batch_to_keep)
+                .len(),
+            state_snapshot_diffs_to_remove: storage
+                .state_snapshot_diffs_dal()
+                .get_diffs_after(last_l1_batch_to_keep)
+                .len(),
+        })
+    }
+
+    /// Reverts the requested stages of the node state to `last_l1_batch_to_keep`. Stages with nothing
+    /// to revert are skipped. All node components must be stopped. Returns the plan the revert was performed by.
+    ///
+    /// Reverting Postgres makes it impossible to revert the RocksDB instances later, so unless
+    /// `allow_unrecoverable` is set, it's refused while a RocksDB instance ahead of the L1 batch isn't reverted,
+    /// or while the Merkle tree or the state keeper cache isn't found on this machine, as it can't be checked then.
+    pub async fn revert(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        stages: &[RevertStage],
+        allow_unrecoverable: bool,
+    ) -> Result<BlockRevertPlan, BlockReverterError> {
+        let last_executed = self
+            .l1_batch_number_on_contract(AggregatedActionType::ExecuteBlocks)
+            .await;
+        if last_l1_batch_to_keep < last_executed {
+            return Err(BlockReverterError::AlreadyExecuted {
+                requested: last_l1_batch_to_keep,
+                last_executed,
+            });
+        }
+
+        let plan = self.plan(last_l1_batch_to_keep)?;
+        let pending_stages: Vec<_> = RevertStage::ALL
+            .iter()
+            .copied()
+            .filter(|stage| stages.contains(stage) && stage.is_pending(&plan))
+            .collect();
+        if pending_stages.contains(&RevertStage::Postgres) && !allow_unrecoverable {
+            let skipped_stage = [RevertStage::MerkleTree, RevertStage::StateKeeperCache]
+                .iter()
+                .copied()
+                .find(|stage| stage.is_pending(&plan) && !pending_stages.contains(stage));
+            if let Some(stage) = skipped_stage {
+                return Err(BlockReverterError::UnrecoverableStage(stage));
+            }
+            if let Some(stage) = unknown_rocksdb_watermark(&plan) {
+                return Err(BlockReverterError::UnknownRocksdbWatermark(stage));
+            }
+        }
+
+        for stage in pending_stages {
+            vlog::info!(
+                "Reverting {} to L1 batch #{}",
+                stage.name(),
+                last_l1_batch_to_keep
+            );
+            match stage {
+                RevertStage::EthSender => self.revert_eth_sender(last_l1_batch_to_keep),
+                RevertStage::MerkleTree => self.revert_trees(&plan)?,
+                RevertStage::StateKeeperCache => self.revert_state_keeper_cache(&plan),
+                RevertStage::Postgres => self.revert_postgres(&plan).await,
+                RevertStage::RocksdbBackups => self.revert_rocksdb_backups(last_l1_batch_to_keep),
+                RevertStage::StateSnapshotDiffs => {
+                    self.revert_state_snapshot_diffs(last_l1_batch_to_keep)
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    fn revert_eth_sender(&self, last_l1_batch_to_keep: L1BatchNumber) {
+        let mut storage = self.connection_pool.access_storage_blocking();
+        let mut transaction = storage.start_transaction_blocking();
+        transaction
+            .eth_sender_dal()
+            .revert_eth_txs(last_l1_batch_to_keep);
+        transaction.commit_blocking();
+    }
+
+    /// Returns the storage logs which revert the state to `last_l1_batch_to_keep`, with the keys in the tree format.
+    fn logs_for_revert(&self, last_l1_batch_to_keep: L1BatchNumber) -> Vec<(H256, Option<H256>)> {
+        self.connection_pool
+            .access_storage_blocking()
+            .storage_logs_dedup_dal()
+            .get_storage_logs_for_revert(last_l1_batch_to_keep)
+    }
+
+    fn revert_trees(&self, plan: &BlockRevertPlan) -> Result<(), BlockReverterError> {
+        let logs = self.logs_for_revert(plan.last_l1_batch_to_keep);
+        let trees = [
+            (self.config.db.path(), plan.tree_l1_batch_number),
+            (
+                self.config.db.merkle_tree_fast_ssd_path(),
+                plan.lightweight_tree_l1_batch_number,
+            ),
+        ];
+        for (path, l1_batch_number) in trees {
+            if l1_batch_number.map_or(false, |number| number > plan.last_l1_batch_to_keep + 1) {
+                vlog::info!("Reverting Merkle tree at {}", path);
+                self.revert_tree(plan.last_l1_batch_to_keep, logs.clone(), path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn revert_tree(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        logs: Vec<(H256, Option<H256>)>,
+        path: &str,
+    ) -> Result<(), BlockReverterError> {
+        let db = RocksDB::new(Database::MerkleTree, path, true);
+        let mut tree = ZkSyncTree::new(db);
+
+        // Convert H256 -> U256, note that tree keys are encoded using little endianness.
+        let logs: Vec<_> = logs
+            .into_iter()
+            .map(|(key, value)| (U256::from_little_endian(&key.to_fixed_bytes()), value))
+            .collect();
+        tree.revert_logs(last_l1_batch_to_keep, logs);
+
+        let postgres_root_hash = self
+            .connection_pool
+            .access_storage_blocking()
+            .blocks_dal()
+            .get_merkle_state_root(last_l1_batch_to_keep)
+            .ok_or(BlockReverterError::MissingRootHash(last_l1_batch_to_keep))?;
+        let tree_root_hash = H256::from_slice(&tree.root_hash());
+        if tree_root_hash != postgres_root_hash {
+            return Err(BlockReverterError::TreeRootHashMismatch {
+                tree: tree_root_hash,
+                postgres: postgres_root_hash,
+            });
+        }
+        tree.save()?;
+        Ok(())
+    }
+
+    fn revert_state_keeper_cache(&self, plan: &BlockRevertPlan) {
+        let logs = self.logs_for_revert(plan.last_l1_batch_to_keep);
+        let mut storage = self.connection_pool.access_storage_blocking();
+        let contracts = storage
+            .storage_dal()
+            .get_contracts_for_revert(plan.last_miniblock_to_keep);
+        let factory_deps = storage
+            .storage_dal()
+            .get_factory_deps_for_revert(plan.last_miniblock_to_keep);
+
+        let db = RocksDB::new(
+            Database::StateKeeper,
+            self.config.db.state_keeper_db_path(),
+            true,
+        );
+        let mut cache = SecondaryStateStorage::new(db);
+        cache.rollback(logs, contracts, factory_deps, plan.last_l1_batch_to_keep);
+    }
+
+    async fn revert_postgres(&self, plan: &BlockRevertPlan) {
+        let last_l1_batch_to_keep = plan.last_l1_batch_to_keep;
+        let last_miniblock_to_keep = plan.last_miniblock_to_keep;
+        let mut storage = self.connection_pool.access_storage().await;
+        let mut transaction = storage.start_transaction().await;
+
+        transaction
+            .transactions_dal()
+            .reset_transactions_state(last_miniblock_to_keep);
+        transaction
+            .events_dal()
+            .rollback_events(last_miniblock_to_keep);
+        transaction
+            .events_dal()
+            .rollback_l2_to_l1_logs(last_miniblock_to_keep);
+        transaction
+            .tokens_dal()
+            .rollback_tokens(last_miniblock_to_keep);
+        transaction
+            .storage_dal()
+            .rollback_factory_deps(last_miniblock_to_keep);
+        transaction
+            .storage_logs_dal()
+            .rollback_storage(last_miniblock_to_keep);
+        transaction
+            .storage_logs_dal()
+            .rollback_storage_logs(last_miniblock_to_keep);
+        transaction
+            .storage_logs_dedup_dal()
+            .rollback_storage_logs(last_l1_batch_to_keep);
+        transaction
+            .blocks_dal()
+            .delete_l1_batches(last_l1_batch_to_keep);
+        transaction
+            .blocks_dal()
+            .delete_miniblocks(last_miniblock_to_keep);
+
+        transaction.commit().await;
+    }
+
+    fn revert_rocksdb_backups(&mut self, last_l1_batch_to_keep: L1BatchNumber) {
+        let mut storage = self.connection_pool.access_storage_blocking();
+        let backups = storage
+            .rocksdb_backups_dal()
+            .get_backups_after(last_l1_batch_to_keep);
+        for backup in backups {
+            if let Some(object_store) = &mut self.object_store {
+                for key in backup.object_keys {
+                    if let Err(err) = object_store.remove(ROCKSDB_BACKUPS_BUCKET_PATH, key) {
+                        vlog::warn!("Failed to remove reverted backup file: {}", err);
+                    }
+                }
+            }
+            // The record is removed even if some files are left, so that the backup is never restored.
+            storage.rocksdb_backups_dal().delete_backup(backup.id);
+        }
+    }
+
+    fn revert_state_snapshot_diffs(&mut self, last_l1_batch_to_keep: L1BatchNumber) {
+        let mut storage = self.connection_pool.access_storage_blocking();
+        let diffs = storage
+            .state_snapshot_diffs_dal()
+            .get_diffs_after(last_l1_batch_to_keep);
+        for diff in diffs {
+            if let Some(object_store) = &mut self.object_store {
+                if let Err(err) =
+                    object_store.remove(STATE_SNAPSHOT_DIFFS_BUCKET_PATH, diff.object_key)
+                {
+                    vlog::warn!(
+                        "Failed to remove reverted state snapshot diff file: {}",
+                        err
+                    );
+                }
+            }
+            // As with the backups, the record is removed even if the file is left, so that external nodes
+            // never apply the diff.
+            storage.state_snapshot_diffs_dal().delete_diff(diff.id);
+        }
+    }
+
+    /// Sends the transaction reverting the L1 batches after `last_l1_batch_to_keep` on L1 and waits
+    /// for it to be mined.
+    pub async fn send_ethereum_revert_transaction(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        priority_fee_per_gas: U256,
+        nonce: u64,
+    ) {
+        let eth_gateway = EthereumClient::from_config(&self.config);
+        let revert_blocks = zksync_contract()
+            .functions
+            .get("revertBlocks")
+            .cloned()
+            .expect("revertBlocks function not found")
+            .pop()
+            .expect("revertBlocks function entry not found");
+        let args = vec![Token::Uint(U256::from(last_l1_batch_to_keep.0))];
+        let raw_tx = revert_blocks
+            .encode_input(&args)
+            .expect("Failed to encode transaction data.")
+            .to_vec();
+        let signed_tx = eth_gateway
+            .sign_prepared_tx_for_addr(
+                raw_tx.clone(),
+                self.config.contracts.validator_timelock_addr,
+                Options::with(|opt| {
+                    opt.gas = Some(5_000_000.into());
+                    opt.max_priority_fee_per_gas = Some(priority_fee_per_gas);
+                    opt.nonce = Some(nonce.into());
+                }),
+                "block-reverter",
+            )
+            .await
+            .expect("Failed to sign transaction");
+        let tx_hash = eth_gateway
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+            .expect("failed to send revert transaction to L1");
+
+        loop {
+            match eth_gateway
+                .get_tx_status(tx_hash, "block reverter")
+                .await
+                .expect("Failed to get tx status from eth node")
+            {
+                Some(status) => {
+                    assert!(status.success, "revert transaction has failed");
+                    vlog::info!("Revert transaction {:?} has completed", tx_hash);
+                    return;
+                }
+                None => {
+                    vlog::info!("Waiting for L1 transaction confirmation...");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of the last L1 batch committed, proven or executed on L1.
+    pub async fn l1_batch_number_on_contract(&self, op: AggregatedActionType) -> L1BatchNumber {
+        let function_name = match op {
+            AggregatedActionType::CommitBlocks => "getTotalBlocksCommitted",
+            AggregatedActionType::PublishProofBlocksOnchain => "getTotalBlocksVerified",
+            AggregatedActionType::ExecuteBlocks => "getTotalBlocksExecuted",
+        };
+        let eth_gateway = EthereumClient::from_config(&self.config);
+        let block_number: U256 = eth_gateway
+            .call_main_contract_function(function_name, (), None, Options::default(), None)
+            .await
+            .unwrap();
+        L1BatchNumber(block_number.as_u32())
+    }
+
+    /// Returns the nonce to be used for the revert transaction.
+    pub async fn suggested_nonce(&self) -> u64 {
+        EthereumClient::from_config(&self.config)
+            .pending_nonce("reverter")
+            .await
+            .unwrap()
+            .as_u64()
+    }
+
+    /// Removes the failed eth txs together with all eth txs created after them.
+    pub fn clear_failed_l1_transactions(&self) {
+        self.connection_pool
+            .access_storage_blocking()
+            .eth_sender_dal()
+            .clear_failed_transactions();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::MiniblockNumber;
+
+    fn plan() -> BlockRevertPlan {
+        BlockRevertPlan {
+            last_l1_batch_to_keep: L1BatchNumber(5),
+            last_miniblock_to_keep: MiniblockNumber(10),
+            eth_txs_to_remove: 0,
+            l1_batches_to_unlink: 0,
+            tree_l1_batch_number: Some(L1BatchNumber(6)),
+            lightweight_tree_l1_batch_number: None,
+            state_keeper_cache_l1_batch_number: Some(L1BatchNumber(6)),
+            l1_batches_to_remove: 0,
+            miniblocks_to_remove: 0,
+            transactions_to_reset: 0,
+            rocksdb_backups_to_remove: 0,
+            state_snapshot_diffs_to_remove: 0,
+        }
+    }
+
+    #[test]
+    fn reverted_plan_has_no_pending_stages() {
+        let plan = plan();
+        for stage in RevertStage::ALL {
+            assert!(!stage.is_pending(&plan), "{:?}", stage);
+        }
+    }
+
+    #[test]
+    fn pending_stages() {
+        let mut plan = plan();
+        plan.lightweight_tree_l1_batch_number = Some(L1BatchNumber(8));
+        plan.miniblocks_to_remove = 3;
+        plan.l1_batches_to_unlink = 1;
+        plan.state_snapshot_diffs_to_remove = 2;
+        let pending: Vec<_> = RevertStage::ALL
+            .iter()
+            .copied()
+            .filter(|stage| stage.is_pending(&plan))
+            .collect();
+        assert_eq!(
+            pending,
+            [
+                RevertStage::EthSender,
+                RevertStage::MerkleTree,
+                RevertStage::Postgres,
+                RevertStage::StateSnapshotDiffs
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_rocksdb_watermarks() {
+        let mut plan = plan();
+        assert_eq!(unknown_rocksdb_watermark(&plan), None);
+        plan.tree_l1_batch_number = None;
+        assert_eq!(
+            unknown_rocksdb_watermark(&plan),
+            Some(RevertStage::MerkleTree)
+        );
+        plan.lightweight_tree_l1_batch_number = Some(L1BatchNumber(6));
+        assert_eq!(unknown_rocksdb_watermark(&plan), None);
+        plan.state_keeper_cache_l1_batch_number = None;
+        assert_eq!(
+            unknown_rocksdb_watermark(&plan),
+            Some(RevertStage::StateKeeperCache)
+        );
+    }
+
+    #[test]
+    fn state_writing_components_are_recognized() {
+        assert_eq!(
+            state_writing_components("Components([HttpApi, TreeLightweight, StateKeeper])"),
+            ["TreeLightweight", "StateKeeper"]
+        );
+        assert!(state_writing_components("Components([HttpApi, WsApi])").is_empty());
+    }
+
+    #[test]
+    fn stage_names() {
+        for stage in RevertStage::ALL {
+            assert_eq!(RevertStage::from_name(stage.name()), Some(stage));
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Watchdog detecting that the chain has stopped making progress.
+//!
+//! The watchdog compares the wall-clock age of the last sealed miniblock and of the last
+//! confirmed commit / execute operations on L1 with the configured thresholds. Once a stage
+//! is stalled, it is reported via metrics and logs, and optionally posted to a webhook.
+//! The same check is used by the health check endpoint of the explorer API.
+
+use std::collections::HashSet;
+
+use reqwest::Client;
+use serde::Serialize;
+use tokio::sync::watch;
+
+use zksync_config::configs::chain::ChainWatchdogConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::aggregated_operations::AggregatedActionType;
+use zksync_utils::time::seconds_since_epoch;
+
+/// Stage of the chain progress tracked by the watchdog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainStage {
+    MiniblockSealing,
+    L1BatchCommit,
+    L1BatchExecution,
+}
+
+impl ChainStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MiniblockSealing => "miniblock_sealing",
+            Self::L1BatchCommit => "l1_batch_commit",
+            Self::L1BatchExecution => "l1_batch_execution",
+        }
+    }
+
+    fn threshold_sec(self, config: &ChainWatchdogConfig) -> u64 {
+        match self {
+            Self::MiniblockSealing => config.miniblock_stall_threshold_sec,
+            Self::L1BatchCommit => config.commit_stall_threshold_sec,
+            Self::L1BatchExecution => config.execute_stall_threshold_sec,
+        }
+    }
+}
+
+/// Age of the last progress made by a stage.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct StageAge {
+    pub stage: ChainStage,
+    pub age_sec: u64,
+    pub threshold_sec: u64,
+}
+
+impl StageAge {
+    pub fn is_stalled(&self) -> bool {
+        self.age_sec > self.threshold_sec
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainHealth {
+    pub stalled_stages: Vec<StageAge>,
+}
+
+impl ChainHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.stalled_stages.is_empty()
+    }
+}
+
+/// Returns the ages of the stages that have made any progress so far.
+pub fn get_stage_ages(
+    storage: &mut StorageProcessor<'_>,
+    config: &ChainWatchdogConfig,
+) -> Vec<StageAge> {
+    let last_miniblock_timestamp = storage
+        .blocks_dal()
+        .get_last_sealed_miniblock_header()
+        .map(|header| header.timestamp);
+    let last_commit_timestamp = storage
+        .eth_sender_dal()
+        .get_last_confirmation_timestamp(AggregatedActionType::CommitBlocks);
+    let last_execute_timestamp = storage
+        .eth_sender_dal()
+        .get_last_confirmation_timestamp(AggregatedActionType::ExecuteBlocks);
+
+    let now = seconds_since_epoch();
+    vec![
+        (ChainStage::MiniblockSealing, last_miniblock_timestamp),
+        (ChainStage::L1BatchCommit, last_commit_timestamp),
+        (ChainStage::L1BatchExecution, last_execute_timestamp),
+    ]
+    .into_iter()
+    .filter_map(|(stage, timestamp)| {
+        Some(StageAge {
+            stage,
+            age_sec: now.saturating_sub(timestamp?),
+            threshold_sec: stage.threshold_sec(config),
+        })
+    })
+    .collect()
+}
+
+/// Checks the chain progress once. Used by the health checks.
+pub fn check_chain_health(
+    storage: &mut StorageProcessor<'_>,
+    config: &ChainWatchdogConfig,
+) -> ChainHealth {
+    let stalled_stages = get_stage_ages(storage, config)
+        .into_iter()
+        .filter(StageAge::is_stalled)
+        .collect();
+    ChainHealth { stalled_stages }
+}
+
+/// Alert sent to the webhook once a stage gets stalled or recovers.
+#[derive(Debug, Serialize)]
+struct WebhookAlert {
+    stage: ChainStage,
+    stalled: bool,
+    age_sec: u64,
+    threshold_sec: u64,
+}
+
+#[derive(Debug)]
+pub struct ChainWatchdog {
+    config: ChainWatchdogConfig,
+    pool: ConnectionPool,
+    client: Client,
+    stalled_stages: HashSet<ChainStage>,
+}
+
+impl ChainWatchdog {
+    pub fn new(config: ChainWatchdogConfig, pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            pool,
+            client: Client::new(),
+            stalled_stages: HashSet::new(),
+        }
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) {
+        let mut timer = tokio::time::interval(self.config.check_interval());
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, chain watchdog is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            self.run_iter().await;
+        }
+    }
+
+    async fn run_iter(&mut self) {
+        let mut storage = self.pool.access_storage().await;
+        let stage_ages = get_stage_ages(&mut storage, &self.config);
+        drop(storage);
+
+        for stage_age in stage_ages {
+            let stage = stage_age.stage;
+            let is_stalled = stage_age.is_stalled();
+            metrics::gauge!("server.chain_watchdog.stage_age", stage_age.age_sec as f64, "stage" => stage.as_str());
+            metrics::gauge!("server.chain_watchdog.stalled", is_stalled as u8 as f64, "stage" => stage.as_str());
+
+            let was_stalled = self.stalled_stages.contains(&stage);
+            if is_stalled == was_stalled {
+                continue;
+            }
+            if is_stalled {
+                vlog::error!(
+                    "Chain stage {} is stalled: no progress for {}s (threshold is {}s)",
+                    stage.as_str(),
+                    stage_age.age_sec,
+                    stage_age.threshold_sec
+                );
+                self.stalled_stages.insert(stage);
+            } else {
+                vlog::info!("Chain stage {} has recovered", stage.as_str());
+                self.stalled_stages.remove(&stage);
+            }
+            self.send_alert(stage_age).await;
+        }
+    }
+
+    async fn send_alert(&self, stage_age: StageAge) {
+        let webhook_url = match &self.config.webhook_url {
+            Some(url) => url,
+            None => return,
+        };
+        let alert = WebhookAlert {
+            stage: stage_age.stage,
+            stalled: stage_age.is_stalled(),
+            age_sec: stage_age.age_sec,
+            threshold_sec: stage_age.threshold_sec,
+        };
+        let response = self.client.post(webhook_url).json(&alert).send().await;
+        if let Err(err) = response.and_then(|response| response.error_for_status()) {
+            vlog::warn!("Failed to send chain watchdog alert: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_is_stalled_only_after_threshold() {
+        let stage_age = |age_sec| StageAge {
+            stage: ChainStage::L1BatchCommit,
+            age_sec,
+            threshold_sec: 60,
+        };
+        assert!(!stage_age(0).is_stalled());
+        assert!(!stage_age(60).is_stalled());
+        assert!(stage_age(61).is_stalled());
+    }
+}
@@ -5,6 +5,8 @@
 //!   Data of this fetcher is used to calculate fees.
 //! - Token trading volume fetcher, which updates trading volumes for tokens.
 //!   Data of this fetcher is used to decide whether we are going to accept fees in this token.
+//! - Token metadata fetcher, which reads the name, symbol and decimals of the L2 tokens from the token contracts.
+//!   Unlike the others, it doesn't use a third-party API, but calls the contracts via the API sandbox.
 //!
 //! Every data fetcher is represented by an autonomic routine, which spend most of the time sleeping;
 //! once in the configurable interval it fetches the data from an API and store it into the database.
@@ -16,6 +18,7 @@ use zksync_dal::ConnectionPool;
 
 pub mod error;
 pub mod token_list;
+pub mod token_metadata;
 pub mod token_price;
 pub mod token_trading_volume;
 
@@ -27,10 +30,12 @@ pub fn run_data_fetchers(
     let list_fetcher = token_list::TokenListFetcher::new(config.clone());
     let price_fetcher = token_price::TokenPriceFetcher::new(config.clone());
     let volume_fetcher = token_trading_volume::TradingVolumeFetcher::new(config.clone());
+    let metadata_fetcher = token_metadata::TokenMetadataFetcher::new(config.clone());
 
     vec![
         tokio::spawn(list_fetcher.run(pool.clone(), stop_receiver.clone())),
         tokio::spawn(price_fetcher.run(pool.clone(), stop_receiver.clone())),
-        tokio::spawn(volume_fetcher.run(pool, stop_receiver)),
+        tokio::spawn(volume_fetcher.run(pool.clone(), stop_receiver.clone())),
+        tokio::spawn(metadata_fetcher.run(pool, stop_receiver)),
     ]
 }
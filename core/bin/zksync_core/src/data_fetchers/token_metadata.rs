@@ -0,0 +1,256 @@
+//! Token metadata fetcher reads `name()`, `symbol()` and `decimals()` of the L2 tokens via the API sandbox.
+//!
+//! Unlike the token list fetcher, it doesn't depend on a third-party API, so it covers the tokens deployed
+//! directly on L2 as well. New tokens are observed by their ERC20 `Transfer` events. The metadata is read
+//! again once in the refresh interval, since upgradeable token contracts are able to change it.
+//!
+//! Both the sandbox and the DAL block the thread, so the fetcher does all its work outside of the async runtime.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+use zksync_config::ZkSyncConfig;
+use zksync_contracts::{BaseSystemContracts, PLAYGROUND_BLOCK_BOOTLOADER_CODE};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::{BlockId, BlockNumber},
+    ethabi::{self, ParamType},
+    transaction_request::{l2_tx_from_call_req, CallRequest},
+    Address, MiniblockNumber,
+};
+
+use crate::api_server::execution_sandbox::execute_tx_eth_call;
+
+/// Max number of miniblocks scanned for the new tokens by a single query,
+/// so that catching up with the chain doesn't result in long-running queries.
+const MAX_MINIBLOCKS_PER_QUERY: u32 = 1000;
+/// Max number of tokens which metadata is read in a single iteration.
+const MAX_TOKENS_PER_ITERATION: usize = 100;
+
+#[derive(Debug)]
+pub struct TokenMetadataFetcher {
+    config: ZkSyncConfig,
+}
+
+impl TokenMetadataFetcher {
+    pub fn new(config: ZkSyncConfig) -> Self {
+        Self { config }
+    }
+
+    pub async fn run(self, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) {
+        let this = Arc::new(self);
+        let (base_system_contracts, mut next_miniblock) = {
+            let this = this.clone();
+            let pool = pool.clone();
+            tokio::task::spawn_blocking(move || this.load_initial_state(&pool))
+                .await
+                .expect("token metadata fetcher panicked")
+        };
+        let base_system_contracts = Arc::new(base_system_contracts);
+
+        let mut fetching_interval =
+            tokio::time::interval(this.config.fetcher.token_metadata.fetching_interval());
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, token_metadata_fetcher is shutting down");
+                break;
+            }
+
+            fetching_interval.tick().await;
+            let fetcher = this.clone();
+            let pool = pool.clone();
+            let base_system_contracts = base_system_contracts.clone();
+            next_miniblock = tokio::task::spawn_blocking(move || {
+                let next_miniblock = fetcher.observe_new_tokens(&pool, next_miniblock);
+                fetcher.refresh_metadata(&pool, &base_system_contracts);
+                next_miniblock
+            })
+            .await
+            .expect("token metadata fetcher panicked");
+        }
+    }
+
+    /// Returns the base system contracts to call the tokens with, and the first miniblock to scan for new tokens.
+    fn load_initial_state(&self, pool: &ConnectionPool) -> (BaseSystemContracts, MiniblockNumber) {
+        let mut storage = pool.access_storage_blocking();
+        let mut base_system_contracts = storage.storage_dal().get_base_system_contracts(
+            self.config.chain.state_keeper.bootloader_hash,
+            self.config.chain.state_keeper.default_aa_hash,
+        );
+        // Same as for `eth_call`: the playground bootloader doesn't charge the fee.
+        base_system_contracts.bootloader = PLAYGROUND_BLOCK_BOOTLOADER_CODE.clone();
+        // Tokens observed in the last processed miniblock are skipped by `ON CONFLICT`, so it's safe to rescan it.
+        let next_miniblock = storage
+            .tokens_dal()
+            .get_last_l2_token_observed_miniblock()
+            .unwrap_or(MiniblockNumber(0));
+        (base_system_contracts, next_miniblock)
+    }
+
+    /// Scans the sealed miniblocks starting from `from` and returns the first miniblock to be scanned next time.
+    fn observe_new_tokens(&self, pool: &ConnectionPool, from: MiniblockNumber) -> MiniblockNumber {
+        let mut storage = pool.access_storage_blocking();
+        let sealed_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number();
+
+        let mut from = from;
+        while from <= sealed_miniblock_number {
+            let to = std::cmp::min(
+                sealed_miniblock_number,
+                from + (MAX_MINIBLOCKS_PER_QUERY - 1),
+            );
+            let observed_tokens = storage.tokens_dal().observe_l2_tokens(from, to);
+            if observed_tokens > 0 {
+                vlog::info!(
+                    "Observed {} new L2 tokens in miniblocks {}..={}",
+                    observed_tokens,
+                    from,
+                    to
+                );
+            }
+            metrics::counter!(
+                "server.token_metadata_fetcher.observed_tokens",
+                observed_tokens as u64
+            );
+            from = to + 1;
+        }
+        from
+    }
+
+    fn refresh_metadata(&self, pool: &ConnectionPool, base_system_contracts: &BaseSystemContracts) {
+        let tokens = pool
+            .access_storage_blocking()
+            .tokens_dal()
+            .get_l2_tokens_for_metadata_refresh(
+                self.config.fetcher.token_metadata.refresh_interval(),
+                MAX_TOKENS_PER_ITERATION,
+            );
+
+        for token in tokens {
+            let name = self
+                .call_getter(pool, base_system_contracts, token, "name")
+                .and_then(|output| decode_string(&output));
+            let symbol = self
+                .call_getter(pool, base_system_contracts, token, "symbol")
+                .and_then(|output| decode_string(&output));
+            let decimals = self
+                .call_getter(pool, base_system_contracts, token, "decimals")
+                .and_then(|output| decode_decimals(&output));
+
+            let outcome = if name.is_some() && symbol.is_some() && decimals.is_some() {
+                "complete"
+            } else {
+                "partial"
+            };
+            metrics::increment_counter!(
+                "server.token_metadata_fetcher.refreshed_tokens",
+                "outcome" => outcome
+            );
+
+            pool.access_storage_blocking()
+                .tokens_dal()
+                .set_l2_token_metadata(&token, name.as_deref(), symbol.as_deref(), decimals);
+        }
+    }
+
+    /// Calls a getter without arguments on top of the latest sealed miniblock.
+    /// Returns `None` if the call has reverted or failed.
+    fn call_getter(
+        &self,
+        pool: &ConnectionPool,
+        base_system_contracts: &BaseSystemContracts,
+        token: Address,
+        getter: &str,
+    ) -> Option<Vec<u8>> {
+        let request = CallRequest {
+            to: Some(token),
+            data: Some(ethabi::short_signature(getter, &[]).to_vec().into()),
+            ..CallRequest::default()
+        };
+        let tx = l2_tx_from_call_req(request, self.config.api.web3_json_rpc.max_tx_size).ok()?;
+        // The fee input doesn't affect view calls; the base fee is enforced to match the transaction,
+        // same as for `eth_call`.
+        let enforced_base_fee = Some(tx.common_data.fee.max_fee_per_gas.as_u64());
+        let fair_l2_gas_price = self.config.chain.state_keeper.fair_l2_gas_price;
+
+        let result = execute_tx_eth_call(
+            pool,
+            tx,
+            BlockId::Number(BlockNumber::Latest),
+            fair_l2_gas_price,
+            fair_l2_gas_price,
+            enforced_base_fee,
+            base_system_contracts,
+        );
+        match result {
+            Ok(result) if result.revert_reason.is_none() => Some(
+                result
+                    .return_data
+                    .into_iter()
+                    .flat_map(|val| {
+                        let bytes: [u8; 32] = val.into();
+                        bytes.to_vec()
+                    })
+                    .collect(),
+            ),
+            Ok(_) => None,
+            Err(err) => {
+                vlog::warn!(
+                    "Failed to call `{}()` of token {:?}: {}",
+                    getter,
+                    token,
+                    err
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Decodes a string returned by `name()` or `symbol()`. Some of the older tokens return `bytes32` instead.
+fn decode_string(output: &[u8]) -> Option<String> {
+    if let Ok(mut tokens) = ethabi::decode(&[ParamType::String], output) {
+        return tokens.pop()?.into_string();
+    }
+    if output.len() != 32 {
+        return None;
+    }
+    let len = output.iter().position(|&byte| byte == 0).unwrap_or(32);
+    String::from_utf8(output[..len].to_vec()).ok()
+}
+
+fn decode_decimals(output: &[u8]) -> Option<u8> {
+    let decimals = ethabi::decode(&[ParamType::Uint(256)], output)
+        .ok()?
+        .pop()?
+        .into_uint()?;
+    (decimals <= u8::MAX.into()).then(|| decimals.as_u32() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::U256;
+
+    #[test]
+    fn strings_are_decoded() {
+        let output = ethabi::encode(&[ethabi::Token::String("Test token".to_owned())]);
+        assert_eq!(decode_string(&output).as_deref(), Some("Test token"));
+
+        let mut bytes32_output = [0_u8; 32];
+        bytes32_output[..3].copy_from_slice(b"MKR");
+        assert_eq!(decode_string(&bytes32_output).as_deref(), Some("MKR"));
+
+        assert_eq!(decode_string(&[]), None);
+    }
+
+    #[test]
+    fn decimals_are_decoded() {
+        let output = ethabi::encode(&[ethabi::Token::Uint(18.into())]);
+        assert_eq!(decode_decimals(&output), Some(18));
+
+        let output = ethabi::encode(&[ethabi::Token::Uint(U256::from(256))]);
+        assert_eq!(decode_decimals(&output), None);
+        assert_eq!(decode_decimals(&[]), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! DB maintenance component tracking the bloat of the hot tables.
+//!
+//! Dead tuples accumulate in the frequently updated tables faster than autovacuum reclaims them,
+//! which degrades the latency of the queries served by the API. The component reports the dead tuples
+//! ratio and the table / index sizes as metrics, and optionally runs `VACUUM (ANALYZE)` for the tables
+//! exceeding their thresholds within the configured low-traffic window.
+
+use chrono::{Timelike, Utc};
+use tokio::sync::watch;
+
+use zksync_config::configs::DBMaintenanceConfig;
+use zksync_dal::db_maintenance_dal::TableStats;
+use zksync_dal::ConnectionPool;
+
+#[derive(Debug)]
+pub struct DBMaintenance {
+    config: DBMaintenanceConfig,
+    table_thresholds: Vec<(String, f64)>,
+    pool: ConnectionPool,
+}
+
+impl DBMaintenance {
+    /// Creates the component, failing if the monitored tables are misconfigured.
+    pub fn new(config: DBMaintenanceConfig, pool: ConnectionPool) -> anyhow::Result<Self> {
+        let table_thresholds = config
+            .table_thresholds()
+            .map_err(|err| anyhow::anyhow!("Invalid DB maintenance config: {}", err))?;
+        Ok(Self {
+            table_thresholds,
+            config,
+            pool,
+        })
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) {
+        let mut timer = tokio::time::interval(self.config.check_interval());
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, DB maintenance is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            self.run_iter(&stop_receiver).await;
+        }
+    }
+
+    async fn run_iter(&self, stop_receiver: &watch::Receiver<bool>) {
+        let tables: Vec<_> = self
+            .table_thresholds
+            .iter()
+            .map(|(table, _)| table.clone())
+            .collect();
+        let mut storage = self.pool.access_storage().await;
+        let table_stats = storage.db_maintenance_dal().get_table_stats(&tables);
+
+        let in_vacuum_window = self.config.is_in_vacuum_window(Utc::now().hour());
+        for stats in table_stats {
+            report_table_stats(&stats);
+
+            let threshold = self
+                .table_thresholds
+                .iter()
+                .find_map(|(table, threshold)| (*table == stats.table_name).then_some(*threshold))
+                .expect("Stats are only requested for the monitored tables");
+            let dead_tuples_ratio = stats.dead_tuples_ratio();
+            if dead_tuples_ratio <= threshold {
+                continue;
+            }
+
+            vlog::warn!(
+                "Table {} is bloated: dead tuples ratio is {:.3} (threshold is {}), last vacuum at {:?}",
+                stats.table_name,
+                dead_tuples_ratio,
+                threshold,
+                stats.last_vacuum_at
+            );
+            if self.config.vacuum_enabled && in_vacuum_window {
+                // Vacuuming a large table takes a while, so the stop signal is checked before each one.
+                if *stop_receiver.borrow() {
+                    vlog::info!("Stop signal received, skipping VACUUM for the remaining tables");
+                    return;
+                }
+                vlog::info!("Running VACUUM (ANALYZE) for table {}", stats.table_name);
+                let started_at = std::time::Instant::now();
+                storage
+                    .db_maintenance_dal()
+                    .vacuum_analyze_table(&stats.table_name);
+                metrics::histogram!("server.db_maintenance.vacuum_latency", started_at.elapsed(), "table" => stats.table_name.clone());
+            }
+        }
+    }
+}
+
+fn report_table_stats(stats: &TableStats) {
+    let table = stats.table_name.clone();
+    metrics::gauge!("server.db_maintenance.dead_tuples_ratio", stats.dead_tuples_ratio(), "table" => table.clone());
+    metrics::gauge!("server.db_maintenance.dead_tuples", stats.dead_tuples as f64, "table" => table.clone());
+    metrics::gauge!("server.db_maintenance.table_size", stats.table_size_bytes as f64, "table" => table.clone());
+    metrics::gauge!("server.db_maintenance.indexes_size", stats.indexes_size_bytes as f64, "table" => table.clone());
+    if let Some(last_vacuum_at) = stats.last_vacuum_at {
+        let age_sec = (Utc::now() - last_vacuum_at).num_seconds().max(0);
+        metrics::gauge!("server.db_maintenance.last_vacuum_age", age_sec as f64, "table" => table);
+    }
+}
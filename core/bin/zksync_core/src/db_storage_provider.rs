@@ -1,6 +1,8 @@
 use zksync_dal::StorageProcessor;
 use zksync_types::{Address, MiniblockNumber, StorageKey, StorageValue, ZkSyncReadStorage, H256};
 
+use crate::fork::LocalStorage;
+
 #[derive(Debug)]
 pub struct DbStorageProvider<'a> {
     connection: StorageProcessor<'a>,
@@ -51,3 +53,12 @@ impl<'a> ZkSyncReadStorage for DbStorageProvider<'a> {
             .unwrap()
     }
 }
+
+impl<'a> LocalStorage for DbStorageProvider<'a> {
+    fn read_local_value(&mut self, key: &StorageKey) -> Option<StorageValue> {
+        self.connection
+            .storage_web3_dal()
+            .get_historical_value_opt(key, self.block_number)
+            .unwrap()
+    }
+}
@@ -0,0 +1,229 @@
+//! Components of the experimental encrypted mempool.
+//!
+//! Encrypted transactions are stored as-is. Each member of the decryption committee runs
+//! [`EncryptedTxShareProvider`] with its share of the committee key, publishing a decryption share
+//! for every pending transaction. [`EncryptedTxDecryptor`] decrypts the transactions in rounds once the threshold
+//! number of shares is available and passes them to the regular mempool in the order they have been received,
+//! so their contents are not known to the sequencer before they are queued for the execution.
+//!
+//! The decryptor doesn't hold any key material, and no single host holds the committee key: decrypting
+//! a transaction requires the cooperation of the threshold number of members. Decrypted transactions go through
+//! the same validation as the ones submitted via `eth_sendRawTransaction` before they are inserted into the mempool.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+use zksync_config::configs::EncryptedMempoolConfig;
+use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
+use zksync_types::{
+    encrypted_tx::{combine_and_decrypt, decryption_share, DecryptionShare, EncryptedTransaction},
+    l2::L2Tx,
+    transaction_request::TransactionRequest,
+    H256,
+};
+
+use crate::api_server::tx_sender::TxSender;
+
+/// Max number of encrypted transactions to process in a single round.
+const DECRYPTION_BATCH_SIZE: usize = 100;
+
+#[derive(Debug)]
+pub struct EncryptedTxDecryptor {
+    config: EncryptedMempoolConfig,
+    pool: ConnectionPool,
+    tx_sender: TxSender,
+}
+
+impl EncryptedTxDecryptor {
+    pub fn new(config: EncryptedMempoolConfig, pool: ConnectionPool, tx_sender: TxSender) -> Self {
+        Self {
+            config,
+            pool,
+            tx_sender,
+        }
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) {
+        let this = Arc::new(self);
+        let mut timer = tokio::time::interval(this.config.decryption_interval());
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, encrypted mempool decryptor is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            // Validation executes the transactions in the VM, so it's not run on the async runtime.
+            let decryptor = this.clone();
+            tokio::task::spawn_blocking(move || decryptor.decrypt_pending())
+                .await
+                .unwrap();
+        }
+    }
+
+    /// Decrypts the pending encrypted transactions and submits them to the mempool. Transactions that
+    /// can't be decrypted or parsed, or that fail the validation, are marked as rejected.
+    ///
+    /// Transactions are processed in the order they have been received, so the round stops at the first
+    /// transaction without enough decryption shares; it's retried in the next round.
+    fn decrypt_pending(&self) {
+        let mut storage = self.pool.access_storage_blocking();
+        let encrypted_txs = storage
+            .encrypted_transactions_dal()
+            .get_pending_encrypted_transactions(DECRYPTION_BATCH_SIZE);
+        let hashes: Vec<_> = encrypted_txs.iter().map(|tx| tx.hash).collect();
+        let mut shares = storage
+            .encrypted_transactions_dal()
+            .get_decryption_shares(&hashes);
+        drop(storage);
+
+        for encrypted_tx in encrypted_txs {
+            let tx_shares = shares.remove(&encrypted_tx.hash).unwrap_or_default();
+            if tx_shares.len() < self.config.decryption_threshold {
+                break;
+            }
+
+            let result = self
+                .decrypt(&encrypted_tx, &tx_shares)
+                .and_then(|tx| self.submit(tx));
+            let mut storage = self.pool.access_storage_blocking();
+            match result {
+                Ok(tx_hash) => {
+                    metrics::increment_counter!("server.encrypted_mempool.txs", "status" => "decrypted");
+                    storage
+                        .encrypted_transactions_dal()
+                        .mark_encrypted_transaction_as_decrypted(encrypted_tx.hash, tx_hash);
+                }
+                Err(err) => {
+                    vlog::warn!(
+                        "encrypted transaction {:?} is rejected with error {}",
+                        encrypted_tx.hash,
+                        err
+                    );
+                    metrics::increment_counter!("server.encrypted_mempool.txs", "status" => "rejected");
+                    storage
+                        .encrypted_transactions_dal()
+                        .mark_encrypted_transaction_as_rejected(encrypted_tx.hash, &err);
+                }
+            }
+        }
+    }
+
+    /// Validates the decrypted transaction and inserts it into the mempool, returning its hash.
+    fn submit(&self, tx: L2Tx) -> Result<H256, String> {
+        let tx_hash = tx.hash();
+        match self.tx_sender.submit_decrypted_tx(tx) {
+            Ok(
+                L2TxSubmissionResult::Added
+                | L2TxSubmissionResult::Replaced
+                | L2TxSubmissionResult::Proxied,
+            ) => Ok(tx_hash),
+            Ok(other) => Err(format!("failed to insert into the mempool: {}", other)),
+            Err(err) => Err(format!("failed validation: {}", err)),
+        }
+    }
+
+    fn decrypt(
+        &self,
+        encrypted_tx: &EncryptedTransaction,
+        shares: &[DecryptionShare],
+    ) -> Result<L2Tx, String> {
+        let raw_tx = combine_and_decrypt(
+            shares,
+            self.config.decryption_threshold,
+            &encrypted_tx.payload,
+        )
+        .map_err(|err| format!("failed to decrypt: {}", err))?;
+        let (tx_request, hash) = TransactionRequest::from_bytes(
+            &raw_tx,
+            self.tx_sender.0.chain_id.0,
+            self.tx_sender.0.max_tx_size,
+        )
+        .map_err(|err| format!("failed to parse: {}", err))?;
+        let mut tx: L2Tx = tx_request
+            .try_into()
+            .map_err(|err| format!("failed to parse: {}", err))?;
+        tx.set_input(raw_tx, hash);
+        Ok(tx)
+    }
+}
+
+/// Publishes the decryption shares of a committee member for the pending encrypted transactions.
+/// The only component provided with a key share.
+#[derive(Debug)]
+pub struct EncryptedTxShareProvider {
+    member_index: u32,
+    key_share: H256,
+    config: EncryptedMempoolConfig,
+    pool: ConnectionPool,
+}
+
+impl EncryptedTxShareProvider {
+    /// Creates the component, failing if the host isn't configured as a committee member.
+    pub fn new(config: EncryptedMempoolConfig, pool: ConnectionPool) -> anyhow::Result<Self> {
+        let (member_index, key_share) = config.member().ok_or_else(|| {
+            anyhow::anyhow!(
+                "ENCRYPTED_MEMPOOL_MEMBER_INDEX and ENCRYPTED_MEMPOOL_MEMBER_KEY_SHARE must be set \
+                 to publish decryption shares"
+            )
+        })?;
+        anyhow::ensure!(member_index > 0, "Committee member indices start from 1");
+        Ok(Self {
+            member_index,
+            key_share,
+            config,
+            pool,
+        })
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) {
+        let mut timer = tokio::time::interval(self.config.decryption_interval());
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!(
+                    "Stop signal received, encrypted mempool share provider is shutting down"
+                );
+                break;
+            }
+
+            timer.tick().await;
+            self.publish_shares().await;
+        }
+    }
+
+    async fn publish_shares(&self) {
+        let mut storage = self.pool.access_storage().await;
+        let encrypted_txs = storage
+            .encrypted_transactions_dal()
+            .get_pending_encrypted_transactions_without_share(
+                self.member_index,
+                DECRYPTION_BATCH_SIZE,
+            );
+        for encrypted_tx in encrypted_txs {
+            // Payloads are checked when they are submitted, so this is not expected to fail.
+            match decryption_share(self.member_index, &self.key_share, &encrypted_tx.payload) {
+                Ok(share) => {
+                    storage
+                        .encrypted_transactions_dal()
+                        .insert_decryption_share(encrypted_tx.hash, &share);
+                    metrics::increment_counter!("server.encrypted_mempool.decryption_shares");
+                }
+                Err(err) => {
+                    vlog::warn!(
+                        "can't produce decryption share for encrypted transaction {:?}: {}",
+                        encrypted_tx.hash,
+                        err
+                    );
+                    storage
+                        .encrypted_transactions_dal()
+                        .mark_encrypted_transaction_as_rejected(
+                            encrypted_tx.hash,
+                            &format!("failed to decrypt: {}", err),
+                        );
+                }
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::eth_sender::block_publish_criterion::{
     BlockNumberCriterion, BlockPublishCriterion, DataSizeCriterion, GasCriterion,
     TimestampDeadlineCriterion,
 };
+use crate::eth_sender::priority_ops_check::take_blocks_with_consistent_priority_ops;
 use zksync_config::configs::eth_sender::{ProofSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
@@ -163,6 +164,8 @@ impl Aggregator {
                     panic!("Blocks are not sequential")
                 }
             });
+        let ready_for_commit_blocks =
+            take_blocks_with_consistent_priority_ops(storage, ready_for_commit_blocks);
 
         let blocks = extract_ready_subrange(
             storage,
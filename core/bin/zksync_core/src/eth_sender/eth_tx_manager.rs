@@ -36,12 +36,14 @@ struct OperatorNonce {
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to ethereum.
 /// Based on eth_tx_history queue the component can mark txs as stuck and create the new attempt
-/// with higher gas price
+/// with higher gas price.
+/// In the simulation mode, txs are only executed via `eth_call` and never sent.
 #[derive(Debug)]
 pub struct EthTxManager<E> {
     ethereum_gateway: E,
     config: SenderConfig,
     gas_adjuster: Arc<GasAdjuster<E>>,
+    last_simulated_eth_tx_id: Option<u32>,
 }
 
 impl<E: EthInterface + Sync> EthTxManager<E> {
@@ -54,6 +56,7 @@ impl<E: EthInterface + Sync> EthTxManager<E> {
             ethereum_gateway,
             config,
             gas_adjuster,
+            last_simulated_eth_tx_id: None,
         }
     }
 
@@ -510,7 +513,9 @@ impl<E: EthInterface + Sync> EthTxManager<E> {
     }
 
     pub async fn run(mut self, pool: ConnectionPool, stop_receiver: watch::Receiver<bool>) {
-        {
+        if self.config.simulation_mode {
+            vlog::warn!("eth_tx_manager is running in the simulation mode, L1 txs won't be sent");
+        } else {
             let current_block = L1BlockNumber(
                 self.ethereum_gateway
                     .block_number("etx_tx_manager")
@@ -569,6 +574,59 @@ impl<E: EthInterface + Sync> EthTxManager<E> {
         }
     }
 
+    /// Simulates the eth txs created since the last call, returning the number of simulated txs.
+    /// Simulated txs don't change the L1 state, so a tx depending on a previously simulated one
+    /// (e.g. a commit of the next blocks) is expected to revert.
+    pub(super) async fn simulate_new_eth_txs(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<usize, ETHSenderError> {
+        let new_eth_txs = match self.last_simulated_eth_tx_id {
+            Some(eth_tx_id) => storage
+                .eth_sender_dal()
+                .get_eth_txs_after(eth_tx_id, self.config.max_txs_in_flight),
+            None => storage
+                .eth_sender_dal()
+                .get_new_eth_txs(self.config.max_txs_in_flight),
+        };
+
+        for tx in &new_eth_txs {
+            let result = self
+                .ethereum_gateway
+                .simulate_tx(
+                    tx.raw_tx.clone(),
+                    tx.contract_address,
+                    self.config.max_aggregated_tx_gas.into(),
+                    "eth_tx_manager",
+                )
+                .await?;
+            match result.revert_reason {
+                None => {
+                    vlog::info!(
+                        "Simulated eth_tx {} for {} succeeded. Estimated gas: {:?}, predicted gas: {}",
+                        tx.id,
+                        tx.tx_type.to_string(),
+                        result.estimated_gas,
+                        tx.predicted_gas_cost
+                    );
+                    metrics::increment_counter!("server.eth_sender.simulated_txs", "status" => "success", "type" => tx.tx_type.to_string());
+                }
+                Some(revert_reason) => {
+                    vlog::warn!(
+                        "Simulated eth_tx {} for {} reverted: {}. Trace: {:?}",
+                        tx.id,
+                        tx.tx_type.to_string(),
+                        revert_reason,
+                        result.trace
+                    );
+                    metrics::increment_counter!("server.eth_sender.simulated_txs", "status" => "reverted", "type" => tx.tx_type.to_string());
+                }
+            }
+            self.last_simulated_eth_tx_id = Some(tx.id);
+        }
+        Ok(new_eth_txs.len())
+    }
+
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(
         &mut self,
@@ -582,6 +640,11 @@ impl<E: EthInterface + Sync> EthTxManager<E> {
                 .as_u32(),
         );
 
+        if self.config.simulation_mode {
+            self.simulate_new_eth_txs(storage).await?;
+            return Ok(current_block);
+        }
+
         self.send_new_eth_txs(storage, current_block).await;
 
         if current_block <= previous_block {
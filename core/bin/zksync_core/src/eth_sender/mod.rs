@@ -5,6 +5,7 @@ mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
 mod grafana_metrics;
+mod priority_ops_check;
 mod zksync_functions;
 
 #[cfg(test)]
@@ -0,0 +1,242 @@
+//! Cross-check of the priority operations processed in the L1 batches before the batches are committed.
+//!
+//! The `priorityOperationsHash` committed to L1 is computed from the priority ops data of the batch header.
+//! If the batch has missed or double-counted a priority operation, the hash doesn't match the priority queue
+//! of the L1 contract and the commit transaction is reverted. To catch it before committing, the hash is
+//! recomputed from the L1 transactions executed in the batch, and their serial IDs are checked to continue
+//! the IDs of the previous batches without gaps or duplicates.
+
+use std::fmt;
+
+use zksync_dal::StorageProcessor;
+use zksync_types::block::{priority_ops_rolling_hash, L1BatchHeader};
+use zksync_types::commitment::BlockWithMetadata;
+use zksync_types::{PriorityOpId, H256};
+
+#[derive(Debug, PartialEq)]
+pub(super) enum PriorityOpsMismatch {
+    TxCount {
+        l1_tx_count: usize,
+        executed: usize,
+    },
+    NonSequentialId {
+        expected: PriorityOpId,
+        actual: PriorityOpId,
+    },
+    Hash {
+        committed: H256,
+        executed: H256,
+    },
+    StoredHash {
+        committed: H256,
+        stored: H256,
+    },
+}
+
+impl fmt::Display for PriorityOpsMismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TxCount {
+                l1_tx_count,
+                executed,
+            } => write!(
+                formatter,
+                "L1 tx count is {}, but {} priority ops are executed",
+                l1_tx_count, executed
+            ),
+            Self::NonSequentialId { expected, actual } => write!(
+                formatter,
+                "expected priority op #{}, got #{}",
+                expected.0, actual.0
+            ),
+            Self::Hash {
+                committed,
+                executed,
+            } => write!(
+                formatter,
+                "committed priority ops hash {:?} doesn't match the hash {:?} of the executed priority ops",
+                committed, executed
+            ),
+            Self::StoredHash { committed, stored } => write!(
+                formatter,
+                "committed priority ops hash {:?} doesn't match the hash {:?} recorded when the batch was sealed",
+                committed, stored
+            ),
+        }
+    }
+}
+
+/// Checks the priority ops of the batch, where `priority_ops` are the serial IDs and hashes of the executed
+/// priority operations, and `next_priority_op_id` is the ID the batch must start with (if known).
+/// Returns the ID the next batch must start with.
+pub(super) fn check_priority_ops(
+    header: &L1BatchHeader,
+    stored_hash: Option<H256>,
+    priority_ops: &[(PriorityOpId, H256)],
+    mut next_priority_op_id: Option<PriorityOpId>,
+) -> Result<Option<PriorityOpId>, PriorityOpsMismatch> {
+    if priority_ops.len() != header.l1_tx_count as usize {
+        return Err(PriorityOpsMismatch::TxCount {
+            l1_tx_count: header.l1_tx_count as usize,
+            executed: priority_ops.len(),
+        });
+    }
+    for &(op_id, _) in priority_ops {
+        match next_priority_op_id {
+            Some(expected) if expected != op_id => {
+                return Err(PriorityOpsMismatch::NonSequentialId {
+                    expected,
+                    actual: op_id,
+                });
+            }
+            _ => next_priority_op_id = Some(op_id + 1),
+        }
+    }
+
+    let committed = header.priority_ops_onchain_data_hash();
+    let executed = priority_ops_rolling_hash(priority_ops.iter().map(|&(_, hash)| hash));
+    if committed != executed {
+        return Err(PriorityOpsMismatch::Hash {
+            committed,
+            executed,
+        });
+    }
+    match stored_hash {
+        Some(stored) if stored != committed => {
+            Err(PriorityOpsMismatch::StoredHash { committed, stored })
+        }
+        _ => Ok(next_priority_op_id),
+    }
+}
+
+/// Returns the prefix of the blocks with consistent priority ops. Blocks starting from the first inconsistent one
+/// are held back, since committing them would be reverted by L1.
+pub(super) fn take_blocks_with_consistent_priority_ops(
+    storage: &mut StorageProcessor<'_>,
+    blocks: Vec<BlockWithMetadata>,
+) -> Vec<BlockWithMetadata> {
+    let Some(first_block) = blocks.first() else {
+        return blocks;
+    };
+    let mut next_priority_op_id = storage
+        .transactions_dal()
+        .last_priority_id_before_l1_batch(first_block.header.number)
+        .map(|op_id| op_id + 1);
+
+    let mut consistent_blocks = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let number = block.header.number;
+        let stored_hash = storage.blocks_dal().get_l1_batch_priority_ops_hash(number);
+        let priority_ops = storage.transactions_dal().get_l1_batch_priority_ops(number);
+        match check_priority_ops(
+            &block.header,
+            stored_hash,
+            &priority_ops,
+            next_priority_op_id,
+        ) {
+            Ok(next_op_id) => next_priority_op_id = next_op_id,
+            Err(err) => {
+                vlog::error!(
+                    "Priority ops of L1 batch {} are inconsistent, holding back its commit: {}",
+                    number,
+                    err
+                );
+                metrics::increment_counter!("server.eth_sender.priority_ops_mismatch");
+                break;
+            }
+        }
+        consistent_blocks.push(block);
+    }
+    consistent_blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::priority_op_onchain_data::PriorityOpOnchainData;
+    use zksync_types::{Address, L1BatchNumber};
+
+    use super::*;
+
+    fn header_with_priority_ops(hashes: &[H256]) -> L1BatchHeader {
+        let mut header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        header.l1_tx_count = hashes.len() as u16;
+        header.priority_ops_onchain_data = hashes
+            .iter()
+            .map(|&onchain_data_hash| PriorityOpOnchainData {
+                layer_2_tip_fee: 0.into(),
+                onchain_data_hash,
+            })
+            .collect();
+        header
+    }
+
+    #[test]
+    fn consistent_priority_ops() {
+        let hashes = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        let header = header_with_priority_ops(&hashes);
+        let ops = [(PriorityOpId(5), hashes[0]), (PriorityOpId(6), hashes[1])];
+
+        let stored_hash = Some(header.priority_ops_onchain_data_hash());
+        let next_op_id = check_priority_ops(&header, stored_hash, &ops, Some(PriorityOpId(5)));
+        assert_eq!(next_op_id, Ok(Some(PriorityOpId(7))));
+        // The first ID isn't checked if it's unknown.
+        let next_op_id = check_priority_ops(&header, None, &ops, None);
+        assert_eq!(next_op_id, Ok(Some(PriorityOpId(7))));
+
+        let empty_header = header_with_priority_ops(&[]);
+        let next_op_id = check_priority_ops(&empty_header, None, &[], Some(PriorityOpId(7)));
+        assert_eq!(next_op_id, Ok(Some(PriorityOpId(7))));
+    }
+
+    #[test]
+    fn inconsistent_priority_ops() {
+        let hashes = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        let header = header_with_priority_ops(&hashes);
+
+        let missing_op = [(PriorityOpId(5), hashes[0])];
+        let err = check_priority_ops(&header, None, &missing_op, None).unwrap_err();
+        assert!(
+            matches!(err, PriorityOpsMismatch::TxCount { .. }),
+            "{}",
+            err
+        );
+
+        let skipped_op = [(PriorityOpId(5), hashes[0]), (PriorityOpId(7), hashes[1])];
+        let err = check_priority_ops(&header, None, &skipped_op, None).unwrap_err();
+        assert_eq!(
+            err,
+            PriorityOpsMismatch::NonSequentialId {
+                expected: PriorityOpId(6),
+                actual: PriorityOpId(7),
+            }
+        );
+
+        let double_counted_op = [(PriorityOpId(4), hashes[0]), (PriorityOpId(5), hashes[1])];
+        let err = check_priority_ops(&header, None, &double_counted_op, Some(PriorityOpId(5)))
+            .unwrap_err();
+        assert!(
+            matches!(err, PriorityOpsMismatch::NonSequentialId { .. }),
+            "{}",
+            err
+        );
+
+        let reordered_ops = [(PriorityOpId(5), hashes[1]), (PriorityOpId(6), hashes[0])];
+        let err = check_priority_ops(&header, None, &reordered_ops, None).unwrap_err();
+        assert!(matches!(err, PriorityOpsMismatch::Hash { .. }), "{}", err);
+
+        let ops = [(PriorityOpId(5), hashes[0]), (PriorityOpId(6), hashes[1])];
+        let stored_hash = Some(H256::zero());
+        let err = check_priority_ops(&header, stored_hash, &ops, None).unwrap_err();
+        assert!(
+            matches!(err, PriorityOpsMismatch::StoredHash { .. }),
+            "{}",
+            err
+        );
+    }
+}
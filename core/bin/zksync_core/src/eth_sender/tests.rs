@@ -395,3 +395,54 @@ async fn failed_eth_tx(connection_pool: ConnectionPool) {
         .await
         .unwrap();
 }
+
+// Tests that in the simulation mode txs are simulated only once and never sent.
+#[db_test]
+async fn simulation_mode(connection_pool: ConnectionPool) -> anyhow::Result<()> {
+    let mut tester = EthSenderTester::new(connection_pool.clone(), vec![10; 100]).await;
+    tester.manager = EthTxManager::new(
+        SenderConfig {
+            simulation_mode: true,
+            ..ETHSenderConfig::from_env().sender
+        },
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+    );
+
+    for _ in 0..3 {
+        tester
+            .aggregator
+            .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+            .await?;
+    }
+    let simulated_txs = tester
+        .manager
+        .simulate_new_eth_txs(&mut tester.storage().await)
+        .await?;
+    assert_eq!(simulated_txs, 3);
+    let simulated_txs = tester
+        .manager
+        .simulate_new_eth_txs(&mut tester.storage().await)
+        .await?;
+    assert_eq!(simulated_txs, 0);
+
+    tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &DUMMY_OPERATION)
+        .await?;
+    let simulated_txs = tester
+        .manager
+        .simulate_new_eth_txs(&mut tester.storage().await)
+        .await?;
+    assert_eq!(simulated_txs, 1);
+
+    assert!(tester.gateway.sent_txs.read().unwrap().is_empty());
+    assert!(tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_inflight_txs()
+        .is_empty());
+
+    Ok(())
+}
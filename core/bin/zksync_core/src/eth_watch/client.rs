@@ -1,3 +1,4 @@
+use futures::future::{BoxFuture, FutureExt};
 use itertools::Itertools;
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display};
@@ -9,13 +10,14 @@ use zksync_types::ethabi::{Contract, Hash};
 
 use zksync_contracts::zksync_contract;
 use zksync_types::{
+    api::{L1BatchL1Event, L1BatchL1EventKind},
     l1::L1Tx,
     web3::{
         self,
         contract::Options,
         types::{BlockNumber, FilterBuilder, Log},
     },
-    Address, Nonce, H160,
+    Address, L1BatchNumber, Nonce, H160, H256, U256,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -31,17 +33,75 @@ pub enum Error {
 #[derive(Debug)]
 struct ContractTopics {
     new_priority_request: Hash,
+    block_commit: Hash,
+    blocks_verification: Hash,
+    block_execution: Hash,
 }
 
 impl ContractTopics {
     fn new(zksync_contract: &Contract) -> Self {
-        Self {
-            new_priority_request: zksync_contract
-                .event("NewPriorityRequest")
+        let signature = |event: &str| {
+            zksync_contract
+                .event(event)
                 .expect("main contract abi error")
-                .signature(),
+                .signature()
+        };
+        Self {
+            new_priority_request: signature("NewPriorityRequest"),
+            block_commit: signature("BlockCommit"),
+            blocks_verification: signature("BlocksVerification"),
+            block_execution: signature("BlockExecution"),
         }
     }
+
+    /// Parses a `BlockCommit`, `BlocksVerification` or `BlockExecution` event. All their parameters are indexed.
+    /// `BlocksVerification` reports the range of the proven batches, so it's expanded into an event per batch.
+    fn parse_l1_batch_events(&self, log: &Log) -> Result<Vec<L1BatchL1Event>, Error> {
+        let (l1_tx_hash, l1_block_number) = match (log.transaction_hash, log.block_number) {
+            (Some(tx_hash), Some(block_number)) => (tx_hash, block_number),
+            _ => return Err(Error::LogParse(format!("log {:?} is not mined", log))),
+        };
+        let topic = |index: usize| {
+            log.topics
+                .get(index)
+                .copied()
+                .ok_or_else(|| Error::LogParse(format!("log {:?} has no topic #{}", log, index)))
+        };
+        let batch_number =
+            |topic: H256| L1BatchNumber(U256::from_big_endian(topic.as_bytes()).as_u32());
+        let event = |l1_batch_number, kind, block_hash, commitment| L1BatchL1Event {
+            l1_batch_number,
+            kind,
+            l1_tx_hash,
+            l1_block_number: l1_block_number.as_u64(),
+            block_hash,
+            commitment,
+        };
+
+        let signature = topic(0)?;
+        let events = if signature == self.blocks_verification {
+            let previous_last_verified = batch_number(topic(1)?);
+            let current_last_verified = batch_number(topic(2)?);
+            (previous_last_verified.0 + 1..=current_last_verified.0)
+                .map(|number| event(L1BatchNumber(number), L1BatchL1EventKind::Prove, None, None))
+                .collect()
+        } else {
+            let kind = if signature == self.block_commit {
+                L1BatchL1EventKind::Commit
+            } else if signature == self.block_execution {
+                L1BatchL1EventKind::Execute
+            } else {
+                return Err(Error::LogParse(format!("unexpected event {:?}", signature)));
+            };
+            vec![event(
+                batch_number(topic(1)?),
+                kind,
+                Some(topic(2)?),
+                Some(topic(3)?),
+            )]
+        };
+        Ok(events)
+    }
 }
 
 #[async_trait::async_trait]
@@ -52,7 +112,16 @@ pub trait EthClient {
         to: BlockNumber,
         retries_left: usize,
     ) -> Result<Vec<L1Tx>, Error>;
+    /// Returns the commit, prove and execute events of the L1 batches.
+    async fn get_l1_batch_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> Result<Vec<L1BatchL1Event>, Error>;
     async fn block_number(&self) -> Result<u64, Error>;
+    /// Returns the timestamp of the block, `None` if the block is not known.
+    async fn block_timestamp(&self, block: u64) -> Result<Option<u64>, Error>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> Result<Vec<u8>, Error>;
     async fn get_auth_fact_reset_time(&self, address: Address, nonce: Nonce) -> Result<u64, Error>;
 }
@@ -103,92 +172,124 @@ impl EthHttpClient {
             .map(|log| T::try_from(log).map_err(|err| Error::LogParse(format!("{}", err))))
             .collect()
     }
-}
 
-#[async_trait::async_trait]
-impl EthClient for EthHttpClient {
-    async fn get_priority_op_events(
+    /// Fetches the logs with the given topics, splitting the block range in half if the provider
+    /// returns too many results, and retrying on the transient errors.
+    fn get_logs_with_retries(
         &self,
         from: BlockNumber,
         to: BlockNumber,
+        topics: Vec<Hash>,
         retries_left: usize,
-    ) -> Result<Vec<L1Tx>, Error> {
-        let start = Instant::now();
-
-        let mut result = self
-            .get_filter_logs(from, to, vec![self.topics.new_priority_request])
-            .await;
-
-        // This code is compatible with both Infura and Alchemy API providers.
-        // Note: we don't handle rate-limits here - assumption is that we're never going to hit them.
-        if let Err(Error::EthClient(http_client::Error::EthereumGateway(err))) = &result {
-            vlog::warn!("Provider returned error message: {:?}", err);
-            let err_message = err.to_string();
-            let err_code = if let web3::Error::Rpc(err) = err {
-                Some(err.code.code())
-            } else {
-                None
-            };
-
-            let should_retry = |err_code, err_message: String| {
-                // All of these can be emitted by either API provider.
-                err_code == Some(-32603)             // Internal error
-                || err_message.contains("failed")    // Server error
-                || err_message.contains("timed out") // Time-out error
-            };
+    ) -> BoxFuture<'_, Result<Vec<Log>, Error>> {
+        async move {
+            let mut result = self.get_filter_logs(from, to, topics.clone()).await;
 
-            // check whether the error is related to having too many results
-            if err_message.contains(TOO_MANY_RESULTS_INFURA)
-                || err_message.contains(TOO_MANY_RESULTS_ALCHEMY)
-            {
-                // get the numeric block ids
-                let from_number = match from {
-                    BlockNumber::Number(num) => num,
-                    _ => {
-                        // invalid variant
-                        return result;
-                    }
+            // This code is compatible with both Infura and Alchemy API providers.
+            // Note: we don't handle rate-limits here - assumption is that we're never going to hit them.
+            if let Err(Error::EthClient(http_client::Error::EthereumGateway(err))) = &result {
+                vlog::warn!("Provider returned error message: {:?}", err);
+                let err_message = err.to_string();
+                let err_code = if let web3::Error::Rpc(err) = err {
+                    Some(err.code.code())
+                } else {
+                    None
                 };
-                let to_number = match to {
-                    BlockNumber::Number(num) => num,
-                    BlockNumber::Latest => self.client.block_number("watch").await?,
-                    _ => {
-                        // invalid variant
-                        return result;
-                    }
+
+                let should_retry = |err_code, err_message: String| {
+                    // All of these can be emitted by either API provider.
+                    err_code == Some(-32603)             // Internal error
+                    || err_message.contains("failed")    // Server error
+                    || err_message.contains("timed out") // Time-out error
                 };
 
-                // divide range into two halves and recursively fetch them
-                let mid = (from_number + to_number) / 2;
+                // check whether the error is related to having too many results
+                if err_message.contains(TOO_MANY_RESULTS_INFURA)
+                    || err_message.contains(TOO_MANY_RESULTS_ALCHEMY)
+                {
+                    // get the numeric block ids
+                    let from_number = match from {
+                        BlockNumber::Number(num) => num,
+                        _ => {
+                            // invalid variant
+                            return result;
+                        }
+                    };
+                    let to_number = match to {
+                        BlockNumber::Number(num) => num,
+                        BlockNumber::Latest => self.client.block_number("watch").await?,
+                        _ => {
+                            // invalid variant
+                            return result;
+                        }
+                    };
+
+                    // divide range into two halves and recursively fetch them
+                    let mid = (from_number + to_number) / 2;
 
-                // safety check to prevent infinite recursion (quite unlikely)
-                if from_number >= mid {
-                    return Err(Error::InfiniteRecursion);
+                    // safety check to prevent infinite recursion (quite unlikely)
+                    if from_number >= mid {
+                        return Err(Error::InfiniteRecursion);
+                    }
+                    vlog::warn!(
+                        "Splitting block range in half: {:?} - {:?} - {:?}",
+                        from,
+                        mid,
+                        to
+                    );
+                    let mut first_half = self
+                        .get_logs_with_retries(
+                            from,
+                            BlockNumber::Number(mid),
+                            topics.clone(),
+                            RETRY_LIMIT,
+                        )
+                        .await?;
+                    let mut second_half = self
+                        .get_logs_with_retries(
+                            BlockNumber::Number(mid + 1u64),
+                            to,
+                            topics,
+                            RETRY_LIMIT,
+                        )
+                        .await?;
+
+                    first_half.append(&mut second_half);
+                    result = Ok(first_half);
+                } else if should_retry(err_code, err_message) && retries_left > 0 {
+                    vlog::warn!("Retrying. Retries left: {:?}", retries_left);
+                    result = self
+                        .get_logs_with_retries(from, to, topics, retries_left - 1)
+                        .await;
                 }
-                vlog::warn!(
-                    "Splitting block range in half: {:?} - {:?} - {:?}",
-                    from,
-                    mid,
-                    to
-                );
-                let mut first_half = self
-                    .get_priority_op_events(from, BlockNumber::Number(mid), RETRY_LIMIT)
-                    .await?;
-                let mut second_half = self
-                    .get_priority_op_events(BlockNumber::Number(mid + 1u64), to, RETRY_LIMIT)
-                    .await?;
-
-                first_half.append(&mut second_half);
-                result = Ok(first_half);
-            } else if should_retry(err_code, err_message) && retries_left > 0 {
-                vlog::warn!("Retrying. Retries left: {:?}", retries_left);
-                result = self
-                    .get_priority_op_events(from, to, retries_left - 1)
-                    .await;
             }
+            result
         }
+        .boxed()
+    }
+}
+
+#[async_trait::async_trait]
+impl EthClient for EthHttpClient {
+    async fn get_priority_op_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> Result<Vec<L1Tx>, Error> {
+        let start = Instant::now();
 
-        let events: Vec<L1Tx> = result?
+        let events: Vec<L1Tx> = self
+            .get_logs_with_retries(
+                from,
+                to,
+                vec![self.topics.new_priority_request],
+                retries_left,
+            )
+            .await?
+            .into_iter()
+            .map(|log| L1Tx::try_from(log).map_err(|err| Error::LogParse(err.to_string())))
+            .collect::<Result<Vec<_>, _>>()?
             .into_iter()
             .sorted_by_key(|event| event.serial_id())
             .collect();
@@ -197,10 +298,42 @@ impl EthClient for EthHttpClient {
         Ok(events)
     }
 
+    async fn get_l1_batch_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        retries_left: usize,
+    ) -> Result<Vec<L1BatchL1Event>, Error> {
+        let start = Instant::now();
+
+        let topics = vec![
+            self.topics.block_commit,
+            self.topics.blocks_verification,
+            self.topics.block_execution,
+        ];
+        let mut events = vec![];
+        for log in self
+            .get_logs_with_retries(from, to, topics, retries_left)
+            .await?
+        {
+            events.extend(self.topics.parse_l1_batch_events(&log)?);
+        }
+
+        metrics::histogram!("eth_watcher.get_l1_batch_events", start.elapsed());
+        Ok(events)
+    }
+
     async fn block_number(&self) -> Result<u64, Error> {
         Ok(self.client.block_number("watch").await?.as_u64())
     }
 
+    async fn block_timestamp(&self, block: u64) -> Result<Option<u64>, Error> {
+        Ok(self
+            .client
+            .block_timestamp(BlockNumber::Number(block.into()), "watch")
+            .await?)
+    }
+
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> Result<Vec<u8>, Error> {
         Ok(self
             .client
@@ -1,5 +1,7 @@
 //! Ethereum watcher polls the Ethereum node for PriorityQueue events.
 //! New events are accepted to the zkSync network once they have the sufficient amount of L1 confirmations.
+//! The commit, prove and execute events of the L1 batches are indexed as well, so that the L1 state of the batches
+//! can be read from the database.
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
 //! Number of confirmations is configured using the `CONFIRMATIONS_FOR_ETH_EVENT` environment variable.
@@ -140,6 +142,14 @@ impl<W: EthClient> EthWatch<W> {
         let new_ops = self
             .get_new_priority_ops(self.state.last_processed_ethereum_block, to_block)
             .await?;
+        let l1_batch_events = self
+            .client
+            .get_l1_batch_events(
+                Web3BlockNumber::Number(self.state.last_processed_ethereum_block.into()),
+                Web3BlockNumber::Number(to_block.into()),
+                RETRY_LIMIT,
+            )
+            .await?;
 
         self.state.last_processed_ethereum_block = to_block;
 
@@ -171,9 +181,53 @@ impl<W: EthClient> EthWatch<W> {
             }
             metrics::histogram!("eth_watcher.poll_eth_node", stage_start.elapsed(), "stage" => "persist");
         }
+
+        if !l1_batch_events.is_empty() {
+            metrics::counter!(
+                "server.eth_watcher.l1_batch_events",
+                l1_batch_events.len() as u64
+            );
+            storage
+                .l1_batch_l1_events_dal()
+                .insert_l1_batch_l1_events(&l1_batch_events);
+        }
+        // The deadlines of the priority operations are L1 timestamps rather than block numbers.
+        if let Some(l1_block_timestamp) = self.client.block_timestamp(to_block).await? {
+            Self::record_expired_priority_ops(
+                storage,
+                L1BlockNumber(to_block as u32),
+                l1_block_timestamp,
+            );
+        }
         Ok(())
     }
 
+    /// Records the priority operations which weren't executed on L1 until their deadline.
+    /// The operations are still executed by the state keeper in order, since L1 doesn't allow to skip them.
+    fn record_expired_priority_ops(
+        storage: &mut StorageProcessor<'_>,
+        l1_block: L1BlockNumber,
+        l1_block_timestamp: u64,
+    ) {
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_block_executed_on_eth();
+        let expired_ops = storage
+            .expired_priority_ops_dal()
+            .mark_expired_priority_ops(l1_block, l1_block_timestamp, last_executed_l1_batch);
+        if expired_ops > 0 {
+            vlog::warn!(
+                "{} priority operations weren't executed on L1 until their deadline (L1 block {})",
+                expired_ops,
+                l1_block
+            );
+            metrics::counter!(
+                "server.eth_watcher.expired_priority_ops",
+                expired_ops as u64
+            );
+        }
+    }
+
     async fn get_new_priority_ops(
         &self,
         from_block: u64,
@@ -9,15 +9,20 @@ use db_test_macro::db_test;
 use zksync_dal::StorageProcessor;
 use zksync_types::web3::types::{Address, BlockNumber};
 use zksync_types::{
+    api::{L1BatchL1Event, L1BatchL1EventKind},
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
-    Execute, L1TxCommonData, Nonce, PriorityOpId, Transaction, H256, U256,
+    Execute, L1BatchNumber, L1TxCommonData, Nonce, PriorityOpId, Transaction, H256, U256,
 };
 
 use super::client::Error;
 use crate::eth_watch::{client::EthClient, EthWatch};
 
+/// Interval between the blocks of the fake L1, in seconds.
+const L1_BLOCK_TIME: u64 = 12;
+
 struct FakeEthClientData {
     transactions: HashMap<u64, Vec<L1Tx>>,
+    l1_batch_events: Vec<L1BatchL1Event>,
     last_block_number: u64,
 }
 
@@ -25,6 +30,7 @@ impl FakeEthClientData {
     fn new() -> Self {
         Self {
             transactions: Default::default(),
+            l1_batch_events: vec![],
             last_block_number: 0,
         }
     }
@@ -39,6 +45,14 @@ impl FakeEthClientData {
                 .push(transaction.clone());
         }
     }
+
+    fn add_l1_batch_events(&mut self, events: &[L1BatchL1Event]) {
+        for event in events {
+            self.last_block_number = max(event.l1_block_number, self.last_block_number);
+        }
+        self.l1_batch_events.extend_from_slice(events);
+    }
+
     fn set_last_block_number(&mut self, number: u64) {
         self.last_block_number = number;
     }
@@ -60,6 +74,10 @@ impl FakeEthClient {
         self.inner.write().await.add_transactions(transactions);
     }
 
+    async fn add_l1_batch_events(&mut self, events: &[L1BatchL1Event]) {
+        self.inner.write().await.add_l1_batch_events(events);
+    }
+
     async fn set_last_block_number(&mut self, number: u64) {
         self.inner.write().await.set_last_block_number(number);
     }
@@ -93,10 +111,33 @@ impl EthClient for FakeEthClient {
         Ok(transactions)
     }
 
+    async fn get_l1_batch_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        _retries_left: usize,
+    ) -> Result<Vec<L1BatchL1Event>, Error> {
+        let from = self.block_to_number(from).await;
+        let to = self.block_to_number(to).await;
+        Ok(self
+            .inner
+            .read()
+            .await
+            .l1_batch_events
+            .iter()
+            .filter(|event| (from..=to).contains(&event.l1_block_number))
+            .cloned()
+            .collect())
+    }
+
     async fn block_number(&self) -> Result<u64, Error> {
         Ok(self.block_to_number(BlockNumber::Latest).await)
     }
 
+    async fn block_timestamp(&self, block: u64) -> Result<Option<u64>, Error> {
+        Ok(Some(block * L1_BLOCK_TIME))
+    }
+
     async fn get_auth_fact(&self, _address: Address, _nonce: Nonce) -> Result<Vec<u8>, Error> {
         unreachable!()
     }
@@ -270,6 +311,58 @@ async fn test_overlapping_batches(connection_pool: ConnectionPool) {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+#[db_test]
+async fn test_l1_batch_events(connection_pool: ConnectionPool) {
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        client.clone(),
+        &connection_pool,
+        5,
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.access_test_storage().await;
+    let event = |number, kind, l1_block_number| L1BatchL1Event {
+        l1_batch_number: L1BatchNumber(number),
+        kind,
+        l1_tx_hash: H256::from_low_u64_be(l1_block_number),
+        l1_block_number,
+        block_hash: (kind != L1BatchL1EventKind::Prove).then(|| H256::repeat_byte(number as u8)),
+        commitment: (kind != L1BatchL1EventKind::Prove).then(|| H256::repeat_byte(0xff)),
+    };
+    client
+        .add_l1_batch_events(&[
+            event(1, L1BatchL1EventKind::Commit, 10),
+            event(1, L1BatchL1EventKind::Prove, 12),
+            event(1, L1BatchL1EventKind::Execute, 18),
+        ])
+        .await;
+    client.set_last_block_number(20).await;
+    // the execute event will not be processed, as it has less than 5 confirmations
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let events = storage
+        .l1_batch_l1_events_dal()
+        .get_l1_batch_l1_events(L1BatchNumber(1))
+        .unwrap();
+    assert_eq!(
+        events,
+        [
+            event(1, L1BatchL1EventKind::Commit, 10),
+            event(1, L1BatchL1EventKind::Prove, 12)
+        ]
+    );
+
+    client.set_last_block_number(25).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    let events = storage
+        .l1_batch_l1_events_dal()
+        .get_l1_batch_l1_events(L1BatchNumber(1))
+        .unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[2], event(1, L1BatchL1EventKind::Execute, 18));
+}
+
 fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool();
     storage
@@ -0,0 +1,394 @@
+//! This module allows to evaluate fee model changes (fee parameters or the gas schedule of the bootloader)
+//! on the historical transactions before enabling them.
+//!
+//! A sample of the executed L2 transactions is replayed in the API sandbox on top of the state of the miniblock
+//! preceding the one each transaction was included in. Every transaction is replayed twice: with the fee input
+//! of its miniblock and the current base system contracts (the baseline), and with the proposed parameters.
+//! The fees are compared between the replays rather than with the fee actually paid, so that the inaccuracy
+//! of the replay itself (e.g. the transactions executed earlier in the same miniblock aren't applied)
+//! doesn't show up as a delta.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use vm::vm_with_bootloader::{
+    derive_base_fee_and_gas_per_pubdata, push_transaction_to_bootloader_memory, TxExecutionMode,
+};
+use zksync_contracts::BaseSystemContracts;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api, AccountTreeId, MiniblockNumber, Transaction, CONTRACT_DEPLOYER_ADDRESS, U256,
+};
+
+use crate::api_server::execution_sandbox::{
+    apply_vm_in_sandbox, SandboxExecutionError, SandboxFeeInput,
+};
+
+/// Selector of the ERC-20 `transfer(address,uint256)` function.
+const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+/// Kind of the transaction, used to group the fee deltas in the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TxCategory {
+    /// Calls without calldata, i.e. plain ETH transfers.
+    Transfer,
+    Erc20Transfer,
+    Deployment,
+    ContractCall,
+}
+
+impl TxCategory {
+    pub fn of(tx: &Transaction) -> Self {
+        let execute = &tx.execute;
+        if execute.contract_address == CONTRACT_DEPLOYER_ADDRESS {
+            Self::Deployment
+        } else if execute.calldata.is_empty() {
+            Self::Transfer
+        } else if execute.calldata.starts_with(&ERC20_TRANSFER_SELECTOR) {
+            Self::Erc20Transfer
+        } else {
+            Self::ContractCall
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Transfer => "transfer",
+            Self::Erc20Transfer => "erc20_transfer",
+            Self::Deployment => "deployment",
+            Self::ContractCall => "contract_call",
+        }
+    }
+}
+
+/// Fee model to be evaluated.
+#[derive(Debug, Clone)]
+pub struct FeeModelProposal {
+    /// Fair L2 gas price; the historical one is used if not set.
+    pub fair_l2_gas_price: Option<u64>,
+    /// Multiplier applied to the historical L1 gas price, same as `internal_l1_pricing_multiplier`
+    /// of the gas adjuster.
+    pub l1_gas_price_scale_factor: f64,
+    /// Base system contracts with the proposed gas schedule.
+    pub base_system_contracts: BaseSystemContracts,
+}
+
+/// Fees of the replayed transactions of a single category.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryReport {
+    pub tx_count: usize,
+    /// Transactions that can't be executed under the proposed fee model, e.g. because the pubdata price
+    /// exceeds their `gas_per_pubdata` limit. They aren't included into the fee totals.
+    pub failed_tx_count: usize,
+    /// Fee actually paid for the transactions.
+    pub paid_fee: U256,
+    pub baseline_fee: U256,
+    pub proposed_fee: U256,
+}
+
+impl CategoryReport {
+    /// Relative change of the fee under the proposed fee model, in percent.
+    pub fn fee_delta_percent(&self) -> Option<f64> {
+        if self.baseline_fee.is_zero() {
+            return None;
+        }
+        let baseline_fee = u256_to_f64(self.baseline_fee);
+        let proposed_fee = u256_to_f64(self.proposed_fee);
+        Some((proposed_fee - baseline_fee) / baseline_fee * 100.0)
+    }
+}
+
+/// Lossy conversion that, unlike `U256::as_u128()`, doesn't panic on the fee totals that overflow `u128`.
+fn u256_to_f64(value: U256) -> f64 {
+    value
+        .0
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &limb| acc * 2.0_f64.powi(64) + limb as f64)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepricingReport {
+    pub categories: BTreeMap<TxCategory, CategoryReport>,
+    /// Transactions that couldn't be replayed even with the baseline fee model, or whose replay couldn't
+    /// fetch the forked state. They are skipped entirely.
+    pub skipped_tx_count: usize,
+}
+
+impl RepricingReport {
+    fn record(
+        &mut self,
+        category: TxCategory,
+        paid_fee: U256,
+        baseline_fee: U256,
+        proposed_fee: Option<U256>,
+    ) {
+        let report = self.categories.entry(category).or_default();
+        report.tx_count += 1;
+        match proposed_fee {
+            Some(proposed_fee) => {
+                report.paid_fee += paid_fee;
+                report.baseline_fee += baseline_fee;
+                report.proposed_fee += proposed_fee;
+            }
+            None => report.failed_tx_count += 1,
+        }
+    }
+}
+
+impl fmt::Display for RepricingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<16}{:>8}{:>8}{:>28}{:>28}{:>28}{:>10}",
+            "category", "txs", "failed", "paid fee", "baseline fee", "proposed fee", "delta %"
+        )?;
+        for (category, report) in &self.categories {
+            let delta = report
+                .fee_delta_percent()
+                .map_or_else(|| "-".to_owned(), |delta| format!("{:.2}", delta));
+            writeln!(
+                f,
+                "{:<16}{:>8}{:>8}{:>28}{:>28}{:>28}{:>10}",
+                category.as_str(),
+                report.tx_count,
+                report.failed_tx_count,
+                report.paid_fee,
+                report.baseline_fee,
+                report.proposed_fee,
+                delta
+            )?;
+        }
+        write!(f, "skipped transactions: {}", self.skipped_tx_count)
+    }
+}
+
+#[derive(Debug)]
+pub struct FeeRepricer {
+    connection_pool: ConnectionPool,
+    baseline_contracts: BaseSystemContracts,
+    proposal: FeeModelProposal,
+    validation_computational_gas_limit: u32,
+}
+
+impl FeeRepricer {
+    pub fn new(
+        connection_pool: ConnectionPool,
+        proposal: FeeModelProposal,
+        validation_computational_gas_limit: u32,
+    ) -> Self {
+        Self {
+            connection_pool,
+            baseline_contracts: BaseSystemContracts::load_from_disk(),
+            proposal,
+            validation_computational_gas_limit,
+        }
+    }
+
+    /// Replays up to `sample_size` transactions from the given miniblock range (inclusive).
+    pub fn run(
+        &self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+        sample_size: usize,
+    ) -> RepricingReport {
+        // The state before the genesis miniblock isn't available.
+        let from_miniblock = from_miniblock.max(MiniblockNumber(1));
+        let txs = self
+            .connection_pool
+            .access_storage_blocking()
+            .transactions_dal()
+            .get_executed_l2_txs_sample(from_miniblock, to_miniblock, sample_size);
+
+        let mut report = RepricingReport::default();
+        for (miniblock_number, tx, paid_fee) in txs {
+            let (l1_gas_price, fair_l2_gas_price) = self
+                .connection_pool
+                .access_storage_blocking()
+                .blocks_web3_dal()
+                .get_miniblock_fee_input(miniblock_number)
+                .unwrap()
+                .expect("miniblock of the executed transaction must be present");
+
+            let baseline_fee = match self.replay(
+                &tx,
+                miniblock_number,
+                l1_gas_price,
+                fair_l2_gas_price,
+                &self.baseline_contracts,
+            ) {
+                Ok(fee) => fee,
+                Err(err) => {
+                    vlog::warn!("Failed to replay transaction {:?}: {}", tx.hash(), err);
+                    report.skipped_tx_count += 1;
+                    continue;
+                }
+            };
+
+            let proposed_l1_gas_price =
+                (l1_gas_price as f64 * self.proposal.l1_gas_price_scale_factor) as u64;
+            let proposed_fair_l2_gas_price =
+                self.proposal.fair_l2_gas_price.unwrap_or(fair_l2_gas_price);
+            let proposed_fee = match self.replay(
+                &tx,
+                miniblock_number,
+                proposed_l1_gas_price,
+                proposed_fair_l2_gas_price,
+                &self.proposal.base_system_contracts,
+            ) {
+                Ok(fee) => Some(fee),
+                // Not a failure caused by the proposal, so the transaction can't be evaluated.
+                Err(SandboxExecutionError::ForkSourceUnavailable(err)) => {
+                    vlog::warn!("Failed to replay transaction {:?}: {}", tx.hash(), err);
+                    report.skipped_tx_count += 1;
+                    continue;
+                }
+                Err(err) => {
+                    vlog::info!(
+                        "Transaction {:?} fails under the proposed fee model: {}",
+                        tx.hash(),
+                        err
+                    );
+                    None
+                }
+            };
+
+            report.record(TxCategory::of(&tx), paid_fee, baseline_fee, proposed_fee);
+        }
+        report
+    }
+
+    /// Executes the transaction on top of the state preceding `miniblock_number` and returns the charged fee.
+    fn replay(
+        &self,
+        tx: &Transaction,
+        miniblock_number: MiniblockNumber,
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+        base_system_contracts: &BaseSystemContracts,
+    ) -> Result<U256, SandboxExecutionError> {
+        let state_miniblock_number = MiniblockNumber(miniblock_number.0 - 1);
+        let mut connection = self.connection_pool.access_storage_blocking();
+        let block_timestamp_s = connection
+            .blocks_web3_dal()
+            .get_block_timestamp(state_miniblock_number)
+            .unwrap();
+        let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
+        let validation_computational_gas_limit = self.validation_computational_gas_limit;
+
+        let result = apply_vm_in_sandbox(
+            connection,
+            tx.clone(),
+            TxExecutionMode::VerifyExecute,
+            base_system_contracts,
+            AccountTreeId::default(),
+            api::BlockId::Number(api::BlockNumber::Number(state_miniblock_number.0.into())),
+            state_miniblock_number,
+            block_timestamp_s,
+            tx.nonce(),
+            U256::zero(),
+            SandboxFeeInput {
+                l1_gas_price,
+                fair_l2_gas_price,
+                enforced_base_fee: None,
+                enforce_for_historical_blocks: true,
+            },
+            |vm, tx| {
+                push_transaction_to_bootloader_memory(
+                    vm,
+                    &tx,
+                    TxExecutionMode::VerifyExecute,
+                    None,
+                );
+                vm.execute_next_tx(validation_computational_gas_limit)
+            },
+        )??;
+        let charged_gas = tx
+            .gas_limit()
+            .saturating_sub(U256::from(result.gas_refunded));
+        Ok(charged_gas * U256::from(base_fee))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::{Address, Execute};
+
+    fn mock_tx(contract_address: Address, calldata: Vec<u8>) -> Transaction {
+        Transaction {
+            common_data: zksync_types::ExecuteTransactionCommon::L2(Default::default()),
+            execute: Execute {
+                contract_address,
+                calldata,
+                value: U256::zero(),
+                factory_deps: None,
+            },
+            received_timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn tx_categories() {
+        let address = Address::repeat_byte(1);
+        assert_eq!(
+            TxCategory::of(&mock_tx(address, vec![])),
+            TxCategory::Transfer
+        );
+        let mut calldata = ERC20_TRANSFER_SELECTOR.to_vec();
+        calldata.extend_from_slice(&[0; 64]);
+        assert_eq!(
+            TxCategory::of(&mock_tx(address, calldata)),
+            TxCategory::Erc20Transfer
+        );
+        assert_eq!(
+            TxCategory::of(&mock_tx(address, vec![1, 2, 3, 4])),
+            TxCategory::ContractCall
+        );
+        assert_eq!(
+            TxCategory::of(&mock_tx(CONTRACT_DEPLOYER_ADDRESS, vec![1, 2, 3, 4])),
+            TxCategory::Deployment
+        );
+    }
+
+    #[test]
+    fn report_aggregation() {
+        let mut report = RepricingReport::default();
+        report.record(
+            TxCategory::Transfer,
+            100.into(),
+            100.into(),
+            Some(150.into()),
+        );
+        report.record(
+            TxCategory::Transfer,
+            100.into(),
+            100.into(),
+            Some(50.into()),
+        );
+        report.record(TxCategory::Transfer, 100.into(), 100.into(), None);
+        report.record(TxCategory::ContractCall, 0.into(), 0.into(), Some(0.into()));
+
+        let transfers = &report.categories[&TxCategory::Transfer];
+        assert_eq!(transfers.tx_count, 3);
+        assert_eq!(transfers.failed_tx_count, 1);
+        assert_eq!(transfers.baseline_fee, 200.into());
+        assert_eq!(transfers.proposed_fee, 200.into());
+        assert_eq!(transfers.fee_delta_percent(), Some(0.0));
+        assert_eq!(
+            report.categories[&TxCategory::ContractCall].fee_delta_percent(),
+            None
+        );
+    }
+
+    #[test]
+    fn fee_delta_for_large_fees() {
+        let report = CategoryReport {
+            baseline_fee: U256::MAX / 2,
+            proposed_fee: U256::MAX,
+            ..CategoryReport::default()
+        };
+        let delta = report.fee_delta_percent().unwrap();
+        assert!((delta - 100.0).abs() < 1e-9, "{}", delta);
+    }
+}
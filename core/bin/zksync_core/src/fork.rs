@@ -0,0 +1,297 @@
+//! Fork mode of a dev node.
+//!
+//! In fork mode, the node runs its own chain on top of the state of a remote node at a certain L1 batch.
+//! The storage slots and bytecodes which were never written locally are fetched from the remote node
+//! on the first access and cached in memory, while the local writes always take precedence over the remote state.
+//!
+//! Only the VM executions (both in the state keeper and in the API sandbox) see the forked state,
+//! the API methods reading the storage directly (e.g. `eth_getBalance`) return the local state.
+//!
+//! The remote state can't be fetched from within the VM storage interface, which is infallible, so
+//! [`ForkStorage`] remembers the first failure in [`ForkErrors`], and the code running the VM checks it
+//! once the execution is done instead of using the results obtained on top of the incomplete state.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+
+use anyhow::Context;
+use once_cell::sync::OnceCell;
+use thiserror::Error;
+use tokio::runtime::Handle;
+
+use zksync_config::configs::chain::ChainForkConfig;
+use zksync_state::secondary_storage::SecondaryStateStorage;
+use zksync_types::{
+    api::{BlockIdVariant, BlockNumber},
+    Address, L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, ZkSyncReadStorage, H256,
+};
+use zksync_utils::h256_to_u256;
+use zksync_web3_decl::{
+    jsonrpsee::{
+        core::Error as ClientError,
+        http_client::{HttpClient, HttpClientBuilder},
+    },
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+};
+
+static FORK_SOURCE: OnceCell<Arc<ForkSource>> = OnceCell::new();
+
+/// Returns the source of the forked state if the node runs in fork mode.
+pub fn fork_source() -> Option<Arc<ForkSource>> {
+    FORK_SOURCE.get().cloned()
+}
+
+/// Enables fork mode if it's configured.
+/// Must be called before starting the components which access the state.
+pub async fn init_fork_source(config: &ChainForkConfig) -> anyhow::Result<()> {
+    let url = match &config.url {
+        Some(url) => url,
+        None => return Ok(()),
+    };
+    let source = ForkSource::new(url, config.l1_batch_number.map(L1BatchNumber)).await?;
+    vlog::info!(
+        "Running in fork mode on top of L1 batch #{} (miniblock #{}) of {}",
+        source.l1_batch_number,
+        source.miniblock_number,
+        url
+    );
+    if FORK_SOURCE.set(Arc::new(source)).is_err() {
+        anyhow::bail!("Fork source is already initialized");
+    }
+    Ok(())
+}
+
+/// Failure to fetch the forked state from the remote node.
+#[derive(Debug, Error)]
+#[error("Failed to fetch {subject} from the fork source: {source}")]
+pub struct ForkError {
+    subject: String,
+    source: ClientError,
+}
+
+/// Remote node the state is forked from, along with the cache of the already fetched data.
+#[derive(Debug)]
+pub struct ForkSource {
+    client: HttpClient,
+    l1_batch_number: L1BatchNumber,
+    /// Last miniblock of the forked L1 batch, all the remote state is read at this miniblock.
+    miniblock_number: MiniblockNumber,
+    runtime: Handle,
+    storage: RwLock<HashMap<StorageKey, StorageValue>>,
+    factory_deps: RwLock<HashMap<H256, Option<Vec<u8>>>>,
+}
+
+impl ForkSource {
+    async fn new(url: &str, l1_batch_number: Option<L1BatchNumber>) -> anyhow::Result<Self> {
+        let client = HttpClientBuilder::default()
+            .build(url)
+            .context("Unable to create a fork source client")?;
+        let l1_batch_number = match l1_batch_number {
+            Some(number) => number,
+            None => {
+                let number = client
+                    .get_l1_batch_number()
+                    .await
+                    .context("Failed to get the last L1 batch of the fork source")?;
+                L1BatchNumber(number.as_u32())
+            }
+        };
+        let (_, last_miniblock) = client
+            .get_miniblock_range(l1_batch_number)
+            .await
+            .context("Failed to get the miniblocks of the forked L1 batch")?
+            .with_context(|| {
+                format!(
+                    "L1 batch #{} is not sealed on the fork source",
+                    l1_batch_number
+                )
+            })?;
+
+        Ok(Self {
+            client,
+            l1_batch_number,
+            miniblock_number: MiniblockNumber(last_miniblock.as_u32()),
+            runtime: Handle::current(),
+            storage: RwLock::default(),
+            factory_deps: RwLock::default(),
+        })
+    }
+
+    fn block_id(&self) -> Option<BlockIdVariant> {
+        Some(BlockIdVariant::BlockNumber(BlockNumber::Number(
+            self.miniblock_number.0.into(),
+        )))
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        // The state is also accessed from the threads not managed by Tokio (e.g., by the batch executor),
+        // while the client requires the runtime context.
+        let _guard = self.runtime.enter();
+        async_std::task::block_on(future)
+    }
+
+    /// Returns the value of the slot at the fork point.
+    pub fn read_value(&self, key: &StorageKey) -> Result<StorageValue, ForkError> {
+        if let Some(value) = self.storage.read().unwrap().get(key) {
+            return Ok(*value);
+        }
+
+        metrics::counter!("server.fork.remote_requests", 1, "kind" => "storage");
+        let value = self
+            .block_on(self.client.get_storage_at(
+                *key.address(),
+                h256_to_u256(*key.key()),
+                self.block_id(),
+            ))
+            .map_err(|source| ForkError {
+                subject: format!("slot {:?}", key),
+                source,
+            })?;
+        self.storage.write().unwrap().insert(*key, value);
+        Ok(value)
+    }
+
+    /// Returns the code deployed to the address at the fork point.
+    pub fn load_contract(&self, address: Address) -> Result<Option<Vec<u8>>, ForkError> {
+        metrics::counter!("server.fork.remote_requests", 1, "kind" => "contract");
+        let code = self
+            .block_on(self.client.get_code(address, self.block_id()))
+            .map_err(|source| ForkError {
+                subject: format!("the code of {:?}", address),
+                source,
+            })?;
+        Ok((!code.0.is_empty()).then_some(code.0))
+    }
+
+    /// Returns the factory dependency known to the fork source.
+    pub fn load_factory_dep(&self, hash: H256) -> Result<Option<Vec<u8>>, ForkError> {
+        if let Some(bytecode) = self.factory_deps.read().unwrap().get(&hash) {
+            return Ok(bytecode.clone());
+        }
+
+        metrics::counter!("server.fork.remote_requests", 1, "kind" => "factory_dep");
+        let bytecode = self
+            .block_on(self.client.get_bytecode_by_hash(hash))
+            .map_err(|source| ForkError {
+                subject: format!("bytecode {:?}", hash),
+                source,
+            })?;
+        self.factory_deps
+            .write()
+            .unwrap()
+            .insert(hash, bytecode.clone());
+        Ok(bytecode)
+    }
+}
+
+/// Storage which can tell apart the slots that were never written locally from the zeroed ones.
+pub trait LocalStorage: ZkSyncReadStorage {
+    /// Returns the locally written value of the slot, or `None` if the slot was never written locally.
+    fn read_local_value(&mut self, key: &StorageKey) -> Option<StorageValue>;
+}
+
+impl LocalStorage for &SecondaryStateStorage {
+    fn read_local_value(&mut self, key: &StorageKey) -> Option<StorageValue> {
+        self.read_value_opt(key)
+    }
+}
+
+/// First error met by a [`ForkStorage`] while fetching the forked state.
+/// Shared with the code running the VM on top of the storage, which can't get it from the VM.
+#[derive(Debug, Clone, Default)]
+pub struct ForkErrors(Arc<Mutex<Option<ForkError>>>);
+
+impl ForkErrors {
+    /// Returns the error met since the last call, if any.
+    pub fn take(&self) -> Option<ForkError> {
+        self.0.lock().unwrap().take()
+    }
+
+    fn record(&self, err: ForkError) {
+        vlog::warn!("{}", err);
+        metrics::counter!("server.fork.remote_errors", 1);
+        self.0.lock().unwrap().get_or_insert(err);
+    }
+}
+
+/// Storage layering the local state on top of the forked one.
+/// Transparently proxies the calls to the wrapped storage if the node doesn't run in fork mode.
+///
+/// If the forked state can't be fetched, the storage behaves as if the slot or the bytecode was missing
+/// on the fork source, and records the error to [`ForkErrors`].
+#[derive(Debug)]
+pub struct ForkStorage<S> {
+    inner: S,
+    fork: Option<Arc<ForkSource>>,
+    errors: ForkErrors,
+}
+
+impl<S: LocalStorage> ForkStorage<S> {
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            fork: fork_source(),
+            errors: ForkErrors::default(),
+        }
+    }
+
+    /// Returns the handle to the errors met by this storage, which stays valid once the storage
+    /// is moved into the VM.
+    pub fn errors(&self) -> ForkErrors {
+        self.errors.clone()
+    }
+
+    fn read_forked_value(&self, fork: &ForkSource, key: &StorageKey) -> StorageValue {
+        fork.read_value(key).unwrap_or_else(|err| {
+            self.errors.record(err);
+            StorageValue::zero()
+        })
+    }
+
+    fn or_record<T>(&self, result: Result<Option<T>, ForkError>) -> Option<T> {
+        result.unwrap_or_else(|err| {
+            self.errors.record(err);
+            None
+        })
+    }
+}
+
+impl<S: LocalStorage> ZkSyncReadStorage for ForkStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        match self.fork.clone() {
+            Some(fork) => match self.inner.read_local_value(key) {
+                Some(value) => value,
+                None => self.read_forked_value(&fork, key),
+            },
+            None => self.inner.read_value(key),
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        // The slots existing at the fork point were already written, even if it didn't happen locally.
+        if !self.inner.is_write_initial(key) {
+            return false;
+        }
+        match self.fork.clone() {
+            Some(fork) => self.read_forked_value(&fork, key).is_zero(),
+            None => true,
+        }
+    }
+
+    fn load_contract(&mut self, address: Address) -> Option<Vec<u8>> {
+        if let Some(code) = self.inner.load_contract(address) {
+            return Some(code);
+        }
+        let fork = self.fork.clone()?;
+        self.or_record(fork.load_contract(address))
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(bytecode) = self.inner.load_factory_dep(hash) {
+            return Some(bytecode);
+        }
+        let fork = self.fork.clone()?;
+        self.or_record(fork.load_factory_dep(hash))
+    }
+}
@@ -2,13 +2,19 @@
 
 // Built-in deps
 use std::collections::VecDeque;
-use std::sync::{Arc, RwLock};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
 use tokio::sync::watch::Receiver;
 use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
 
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::{clients::http_client::Error, EthInterface};
 use zksync_mempool::L2TxFilter;
+use zksync_types::web3::types::BlockNumber;
+
+use crate::api_server::execution_sandbox::adjust_l1_gas_price_for_tx;
 
 #[cfg(test)]
 mod tests;
@@ -19,6 +25,8 @@ mod tests;
 pub struct GasAdjuster<E> {
     pub(super) statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
+    // Timestamp of the last processed L1 block, 0 if it's not known yet.
+    l1_block_timestamp: AtomicU64,
     eth_client: E,
 }
 
@@ -35,8 +43,13 @@ impl<E: EthInterface> GasAdjuster<E> {
         let history = eth_client
             .base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
             .await?;
+        let l1_block_timestamp = eth_client
+            .block_timestamp(BlockNumber::Number(current_block.into()), "gas_adjuster")
+            .await?
+            .unwrap_or(0);
         Ok(Self {
             statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
+            l1_block_timestamp: AtomicU64::new(l1_block_timestamp),
             eth_client,
             config,
         })
@@ -54,6 +67,28 @@ impl<E: EthInterface> GasAdjuster<E> {
         (self.config.internal_l1_pricing_multiplier * effective_gas_price as f64) as u64
     }
 
+    /// Returns the L1 gas price used to price L1->L2 transactions. If the smoothing window is configured,
+    /// the price is based on the minimum base fee over the window rather than on the current fee statistics,
+    /// so that short L1 fee spikes don't affect the deposits.
+    pub fn estimate_l1_to_l2_gas_price(&self) -> u64 {
+        let window = match self.config.l1_to_l2_fee_smoothing_window {
+            Some(window) if self.config.internal_enforced_l1_gas_price.is_none() => window,
+            _ => return self.estimate_effective_gas_price(),
+        };
+
+        let base_fee = self.statistics.min_of_last(window);
+        let effective_gas_price = base_fee + self.get_priority_fee();
+        let gas_price =
+            (self.config.internal_l1_pricing_multiplier * effective_gas_price as f64) as u64;
+        metrics::gauge!("server.gas_adjuster.l1_to_l2_gas_price", gas_price as f64);
+        gas_price
+    }
+
+    /// Maximum gas per pubdata byte charged to L1->L2 transactions, if it's configured.
+    pub fn l1_to_l2_max_gas_per_pubdata(&self) -> Option<u64> {
+        self.config.l1_to_l2_max_gas_per_pubdata
+    }
+
     // This is the method where we decide how much we are ready to pay for the
     // base_fee based on the number of L1 blocks the transaction has been in the mempool.
     // This is done in order to avoid base_fee spikes (e.g. during NFT drops) and
@@ -126,20 +161,45 @@ impl<E: EthInterface> GasAdjuster<E> {
             );
 
             self.statistics.add_samples(&history);
+
+            let timestamp = self
+                .eth_client
+                .block_timestamp(BlockNumber::Number(current_block.into()), "gas_adjuster")
+                .await?;
+            if let Some(timestamp) = timestamp {
+                self.l1_block_timestamp.store(timestamp, Ordering::Relaxed);
+            }
         }
         Ok(())
     }
 
+    /// Returns the timestamp of the last processed L1 block in seconds, if it's known.
+    pub fn l1_block_timestamp(&self) -> Option<u64> {
+        let timestamp = self.l1_block_timestamp.load(Ordering::Relaxed);
+        (timestamp != 0).then_some(timestamp)
+    }
+
+    /// Returns the number of the last processed L1 block.
+    pub fn l1_block_number(&self) -> u64 {
+        self.statistics.last_processed_block() as u64
+    }
+
     pub fn l2_tx_filter(&self, fair_l2_gas_price: u64) -> L2TxFilter {
-        let effective_gas_price = self.estimate_effective_gas_price();
-
-        let (base_fee, gas_per_pubdata) =
-            derive_base_fee_and_gas_per_pubdata(effective_gas_price, fair_l2_gas_price);
-        L2TxFilter {
-            l1_gas_price: effective_gas_price,
-            fee_per_gas: base_fee,
-            gas_per_pubdata: gas_per_pubdata as u32,
+        tx_filter(self.estimate_effective_gas_price(), fair_l2_gas_price)
+    }
+
+    /// Same as [`Self::l2_tx_filter`], but with the L1->L2 transactions pricing: the L1 gas price is smoothed
+    /// over the configured window and lowered further if the gas per pubdata byte exceeds the configured cap.
+    pub fn l1_to_l2_tx_filter(&self, fair_l2_gas_price: u64) -> L2TxFilter {
+        let mut l1_gas_price = self.estimate_l1_to_l2_gas_price();
+        if let Some(max_gas_per_pubdata) = self.config.l1_to_l2_max_gas_per_pubdata {
+            l1_gas_price = adjust_l1_gas_price_for_tx(
+                l1_gas_price,
+                fair_l2_gas_price,
+                max_gas_per_pubdata.into(),
+            );
         }
+        tx_filter(l1_gas_price, fair_l2_gas_price)
     }
 
     pub async fn run(self: Arc<Self>, stop_receiver: Receiver<bool>) {
@@ -158,6 +218,16 @@ impl<E: EthInterface> GasAdjuster<E> {
     }
 }
 
+fn tx_filter(l1_gas_price: u64, fair_l2_gas_price: u64) -> L2TxFilter {
+    let (base_fee, gas_per_pubdata) =
+        derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
+    L2TxFilter {
+        l1_gas_price,
+        fee_per_gas: base_fee,
+        gas_per_pubdata: gas_per_pubdata as u32,
+    }
+}
+
 /// Helper structure responsible for collecting the data about recent transactions,
 /// calculating the median base fee.
 #[derive(Debug, Clone, Default)]
@@ -193,6 +263,17 @@ impl GasStatisticsInner {
         self.samples.back().copied().unwrap_or(self.median_cached)
     }
 
+    /// Returns the minimum of the last `count` samples.
+    fn min_of_last(&self, count: usize) -> u64 {
+        self.samples
+            .iter()
+            .rev()
+            .take(count)
+            .min()
+            .copied()
+            .unwrap_or(self.median_cached)
+    }
+
     fn add_samples(&mut self, fees: &[u64]) {
         self.samples.extend(fees);
         self.last_processed_block += fees.len();
@@ -227,6 +308,10 @@ impl GasStatistics {
         self.0.read().unwrap().last_added_value()
     }
 
+    pub fn min_of_last(&self, count: usize) -> u64 {
+        self.0.read().unwrap().min_of_last(count)
+    }
+
     pub fn add_samples(&self, fees: &[u64]) {
         self.0.write().unwrap().add_samples(fees)
     }
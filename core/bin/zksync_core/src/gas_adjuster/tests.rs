@@ -29,6 +29,21 @@ fn samples_queue() {
     assert_eq!(stats.samples, VecDeque::from([4, 5, 18, 18, 18]));
 }
 
+/// Check that short fee spikes are filtered out by the L1->L2 smoothing window
+#[test]
+fn min_of_last_samples() {
+    let stats = GasStatisticsInner::new(5, 5, &[6, 4, 7, 8, 4, 5]);
+
+    assert_eq!(stats.min_of_last(2), 4);
+    assert_eq!(stats.min_of_last(1), 5);
+    // The window exceeding the number of samples covers all of them.
+    assert_eq!(stats.min_of_last(10), 4);
+
+    let stats = GasStatisticsInner::new(5, 5, &[5, 5, 5, 50, 60]);
+    assert_eq!(stats.min_of_last(3), 5);
+    assert_eq!(stats.min_of_last(2), 50);
+}
+
 /// Check that we properly fetch base fees as block are mined
 #[tokio::test]
 async fn kept_updated() {
@@ -46,6 +61,8 @@ async fn kept_updated() {
             internal_l1_pricing_multiplier: 0.8,
             internal_enforced_l1_gas_price: None,
             poll_period: 5,
+            l1_to_l2_fee_smoothing_window: None,
+            l1_to_l2_max_gas_per_pubdata: None,
         },
     )
     .await
@@ -60,3 +77,39 @@ async fn kept_updated() {
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
 }
+
+/// Check that the gas per pubdata byte of L1->L2 transactions is capped, while L2 transactions are priced as usual
+#[tokio::test]
+async fn l1_to_l2_gas_per_pubdata_is_capped() {
+    const FAIR_L2_GAS_PRICE: u64 = 250_000_000;
+
+    let eth_client =
+        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    eth_client.advance_block_number(5);
+
+    let adjuster = GasAdjuster::new(
+        Arc::clone(&eth_client),
+        GasAdjusterConfig {
+            default_priority_fee_per_gas: 5,
+            max_base_fee_samples: 5,
+            pricing_formula_parameter_a: 1.5,
+            pricing_formula_parameter_b: 1.0005,
+            internal_l1_pricing_multiplier: 0.8,
+            internal_enforced_l1_gas_price: Some(100_000_000_000),
+            poll_period: 5,
+            l1_to_l2_fee_smoothing_window: None,
+            l1_to_l2_max_gas_per_pubdata: Some(800),
+        },
+    )
+    .await
+    .unwrap();
+
+    let filter = adjuster.l2_tx_filter(FAIR_L2_GAS_PRICE);
+    assert_eq!(filter.l1_gas_price, 100_000_000_000);
+    assert_eq!(filter.gas_per_pubdata, 6800);
+
+    let filter = adjuster.l1_to_l2_tx_filter(FAIR_L2_GAS_PRICE);
+    assert!(filter.l1_gas_price < 100_000_000_000);
+    assert!(filter.gas_per_pubdata <= 800, "{:?}", filter);
+    assert_eq!(filter.fee_per_gas, FAIR_L2_GAS_PRICE);
+}
@@ -246,6 +246,8 @@ pub(crate) async fn chain_schema_genesis<'a>(
         l1_gas_price: 0,
         l2_fair_gas_price: 0,
         base_system_contracts_hashes: base_system_contracts.hashes(),
+        logs_bloom: Default::default(),
+        fee_account_address: first_validator_address,
     };
 
     let mut transaction = storage.start_transaction().await;
@@ -0,0 +1,62 @@
+use zksync_dal::ConnectionPool;
+use zksync_types::MiniblockNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Max number of miniblocks aggregated into a single checkpoint,
+/// so that catching up with the chain doesn't result in long-running queries.
+const MAX_MINIBLOCKS_PER_CHECKPOINT: u32 = 1000;
+
+/// Incrementally aggregates chain-wide statistics served by `zks_getChainStats`.
+/// Each run adds a checkpoint with the cumulative stats up to the last sealed miniblock.
+#[derive(Debug, Default)]
+pub struct ChainStatsAggregator;
+
+impl ChainStatsAggregator {
+    fn aggregate(&self, connection_pool: ConnectionPool) {
+        let mut conn = connection_pool.access_storage_blocking();
+        let last_checkpoint = conn.chain_stats_dal().get_last_chain_stats_checkpoint();
+        let from = match &last_checkpoint {
+            Some(checkpoint) => checkpoint.miniblock_number + 1,
+            None => MiniblockNumber(0),
+        };
+        let sealed_miniblock_number = conn.blocks_dal().get_sealed_miniblock_number();
+        if sealed_miniblock_number < from {
+            return;
+        }
+        let to = std::cmp::min(
+            sealed_miniblock_number,
+            from + (MAX_MINIBLOCKS_PER_CHECKPOINT - 1),
+        );
+        let header = match conn.blocks_dal().get_miniblock_header(to) {
+            Some(header) => header,
+            // The genesis miniblock is not created yet.
+            None => return,
+        };
+
+        let delta = conn.chain_stats_dal().get_chain_stats_delta(from, to);
+        let checkpoint = last_checkpoint
+            .unwrap_or_default()
+            .advance(to, header.timestamp, &delta);
+        conn.chain_stats_dal()
+            .insert_chain_stats_checkpoint(&checkpoint);
+
+        metrics::gauge!(
+            "server.chain_stats.total_transactions",
+            checkpoint.total_transactions as f64
+        );
+        metrics::gauge!(
+            "server.chain_stats.total_accounts",
+            checkpoint.total_accounts as f64
+        );
+    }
+}
+
+impl PeriodicJob for ChainStatsAggregator {
+    const SERVICE_NAME: &'static str = "ChainStatsAggregator";
+    const POLLING_INTERVAL_MS: u64 = 10000;
+
+    fn run_routine_task(&mut self, connection_pool: ConnectionPool) {
+        self.aggregate(connection_pool);
+    }
+}
@@ -0,0 +1,47 @@
+use zksync_dal::ConnectionPool;
+use zksync_utils::time::seconds_since_epoch;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+use crate::state_keeper::MempoolGuard;
+
+/// Removes the pending L2 transactions whose `valid_until` timestamp has passed, both from the database
+/// and from the in-memory mempool. The state keeper rejects such transactions on its own, so this only
+/// keeps them from piling up in the mempool. Has to run in the same process as the state keeper.
+#[derive(Debug)]
+pub struct ExpiredTxsCleaner {
+    mempool: MempoolGuard,
+}
+
+impl ExpiredTxsCleaner {
+    pub fn new(mempool: MempoolGuard) -> Self {
+        Self { mempool }
+    }
+
+    fn cleanup(&mut self, connection_pool: ConnectionPool) {
+        let now = seconds_since_epoch();
+        let mut storage = connection_pool.access_storage_blocking();
+        let mut transaction = storage.start_transaction_blocking();
+        let removed_txs = transaction.transactions_dal().remove_expired_txs(now);
+        if !removed_txs.is_empty() {
+            transaction
+                .tx_lifecycle_dal()
+                .insert_tx_rejected_events(&removed_txs, "transaction expired");
+            vlog::info!("Removed {} expired transactions", removed_txs.len());
+        }
+        transaction.commit_blocking();
+        // The transactions are removed from the mempool only after they're gone from the database,
+        // so that the mempool fetcher doesn't load them again.
+        let evicted_txs = self.mempool.remove_expired(now);
+        metrics::counter!("server.expired_txs.removed", removed_txs.len() as u64);
+        metrics::counter!("server.expired_txs.evicted", evicted_txs as u64);
+    }
+}
+
+impl PeriodicJob for ExpiredTxsCleaner {
+    const SERVICE_NAME: &'static str = "ExpiredTxsCleaner";
+    const POLLING_INTERVAL_MS: u64 = 10000;
+
+    fn run_routine_task(&mut self, connection_pool: ConnectionPool) {
+        self.cleanup(connection_pool);
+    }
+}
@@ -21,7 +21,7 @@ fn handle_remove_result(result: Result<(), ObjectStoreError>) {
             // in this scenario the retry of removal from GCS would fail as the object is already removed.
             // Hence we ignore the KeyNotFound error below
             ObjectStoreError::KeyNotFound(_) => {}
-            ObjectStoreError::Other(err) => {
+            ObjectStoreError::Corrupted(err) | ObjectStoreError::Other(err) => {
                 panic!("{:?}", err)
             }
         }
@@ -1,7 +1,12 @@
 pub mod blocks_state_reporter;
+pub mod chain_stats_aggregator;
+pub mod expired_txs_cleaner;
 pub mod gcs_blob_cleaner;
 pub mod gpu_prover_queue_monitor;
 pub mod periodic_job;
 pub mod prover_queue_monitor;
+pub mod transfers_backfiller;
+pub mod tx_execute_columns_backfiller;
+pub mod tx_lifecycle_events_cleaner;
 pub mod witness_generator_misc_reporter;
 pub mod witness_generator_queue_monitor;
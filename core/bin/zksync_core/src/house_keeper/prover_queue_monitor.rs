@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use zksync_config::configs::ProverGroupConfig;
 use zksync_dal::ConnectionPool;
-use zksync_prover_utils::circuit_name_to_numeric_index;
+use zksync_prover_utils::circuit_type::CircuitType;
 use zksync_types::proofs::JobCountStatistics;
 
 use crate::house_keeper::periodic_job::PeriodicJob;
@@ -32,7 +32,9 @@ impl PeriodicJob for ProverStatsReporter {
             .map(|(key, value)| {
                 (
                     prover_group_config
-                        .get_group_id_for_circuit_id(circuit_name_to_numeric_index(&key).unwrap())
+                        .get_group_id_for_circuit_id(
+                            key.parse::<CircuitType>().unwrap().numeric_index(),
+                        )
                         .unwrap(),
                     value,
                 )
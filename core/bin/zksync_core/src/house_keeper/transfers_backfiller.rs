@@ -0,0 +1,38 @@
+use zksync_dal::ConnectionPool;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Max number of miniblocks processed by a single run, so that a run doesn't scan too many events at once.
+const BACKFILL_BATCH_SIZE: usize = 1_000;
+
+/// Extracts the transfers of the miniblocks sealed before the `transfers` table was added, going from
+/// the newest miniblock to the genesis. Once the backfill is complete, each run is a lookup in an empty table.
+#[derive(Debug, Default)]
+pub struct TransfersBackfiller;
+
+impl TransfersBackfiller {
+    fn backfill(&self, connection_pool: ConnectionPool) {
+        let backfilled_miniblocks = connection_pool
+            .access_storage_blocking()
+            .transfers_dal()
+            .backfill_transfers(BACKFILL_BATCH_SIZE);
+        if backfilled_miniblocks > 0 {
+            vlog::info!(
+                "Backfilled transfers of {} miniblocks",
+                backfilled_miniblocks
+            );
+        }
+        metrics::counter!(
+            "server.transfers.backfilled_miniblocks",
+            backfilled_miniblocks as u64
+        );
+    }
+}
+
+impl PeriodicJob for TransfersBackfiller {
+    const SERVICE_NAME: &'static str = "TransfersBackfiller";
+
+    fn run_routine_task(&mut self, connection_pool: ConnectionPool) {
+        self.backfill(connection_pool);
+    }
+}
@@ -0,0 +1,39 @@
+use zksync_dal::ConnectionPool;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Max number of transactions updated by a single run, so that the backfill doesn't hold the row locks for long.
+const BACKFILL_BATCH_SIZE: usize = 10_000;
+
+/// Fills the columns extracted from the transaction data (`calldata_length`, `factory_dep_hashes`, etc.)
+/// for the transactions inserted before the columns were added. Once the backfill is complete,
+/// each run is a lookup in an empty index.
+#[derive(Debug, Default)]
+pub struct TxExecuteColumnsBackfiller;
+
+impl TxExecuteColumnsBackfiller {
+    fn backfill(&self, connection_pool: ConnectionPool) {
+        let backfilled_txs = connection_pool
+            .access_storage_blocking()
+            .transactions_dal()
+            .backfill_execute_columns(BACKFILL_BATCH_SIZE);
+        if backfilled_txs > 0 {
+            vlog::info!(
+                "Backfilled execute columns of {} transactions",
+                backfilled_txs
+            );
+        }
+        metrics::counter!(
+            "server.tx_execute_columns.backfilled",
+            backfilled_txs as u64
+        );
+    }
+}
+
+impl PeriodicJob for TxExecuteColumnsBackfiller {
+    const SERVICE_NAME: &'static str = "TxExecuteColumnsBackfiller";
+
+    fn run_routine_task(&mut self, connection_pool: ConnectionPool) {
+        self.backfill(connection_pool);
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use zksync_dal::ConnectionPool;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Transaction lifecycle events older than this are removed.
+const TX_LIFECYCLE_EVENTS_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Enforces the retention of the transaction lifecycle log served by `debug_getTransactionLifecycle`.
+#[derive(Debug, Default)]
+pub struct TxLifecycleEventsCleaner;
+
+impl TxLifecycleEventsCleaner {
+    fn cleanup(&self, connection_pool: ConnectionPool) {
+        let removed_events = connection_pool
+            .access_storage_blocking()
+            .tx_lifecycle_dal()
+            .delete_tx_lifecycle_events_older_than(TX_LIFECYCLE_EVENTS_RETENTION);
+        if removed_events > 0 {
+            vlog::info!("Removed {} expired tx lifecycle events", removed_events);
+        }
+        metrics::counter!("server.tx_lifecycle_events.removed", removed_events);
+    }
+}
+
+impl PeriodicJob for TxLifecycleEventsCleaner {
+    const SERVICE_NAME: &'static str = "TxLifecycleEventsCleaner";
+    const POLLING_INTERVAL_MS: u64 = 60000;
+
+    fn run_routine_task(&mut self, connection_pool: ConnectionPool) {
+        self.cleanup(connection_pool);
+    }
+}
@@ -8,7 +8,7 @@ use futures::future;
 use std::time::Instant;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
-use zksync_config::configs::WitnessGeneratorConfig;
+use zksync_config::configs::{DBMaintenanceConfig, EncryptedMempoolConfig, WitnessGeneratorConfig};
 
 use house_keeper::periodic_job::PeriodicJob;
 use prometheus_exporter::run_prometheus_exporter;
@@ -24,22 +24,40 @@ use zksync_mempool::MempoolStore;
 use zksync_object_store::object_store::create_object_store_from_env;
 use zksync_queued_job_processor::JobProcessor;
 
+use crate::api_server::web3::audit_log::ApiAuditLogMiddleware;
+use crate::api_server::web3::extensions::ApiExtensionsMiddleware;
+use crate::api_server::web3::pools::{ApiConnectionPools, ApiWorkload};
+use crate::api_server::web3::usage_metering::ApiUsageMeteringMiddleware;
+use crate::api_server::web3::ApiMiddleware;
+use crate::chain_watchdog::ChainWatchdog;
+use crate::db_maintenance::DBMaintenance;
+use crate::encrypted_mempool::{EncryptedTxDecryptor, EncryptedTxShareProvider};
 use crate::eth_sender::{Aggregator, EthTxManager};
 use crate::fee_monitor::FeeMonitor;
+use crate::fork::init_fork_source;
 use crate::house_keeper::blocks_state_reporter::L1BatchMetricsReporter;
+use crate::house_keeper::chain_stats_aggregator::ChainStatsAggregator;
+use crate::house_keeper::expired_txs_cleaner::ExpiredTxsCleaner;
 use crate::house_keeper::gcs_blob_cleaner::GcsBlobCleaner;
 use crate::house_keeper::gpu_prover_queue_monitor::GpuProverQueueMonitor;
 use crate::house_keeper::{
-    prover_queue_monitor::ProverStatsReporter,
+    prover_queue_monitor::ProverStatsReporter, transfers_backfiller::TransfersBackfiller,
+    tx_execute_columns_backfiller::TxExecuteColumnsBackfiller,
+    tx_lifecycle_events_cleaner::TxLifecycleEventsCleaner,
     witness_generator_misc_reporter::WitnessGeneratorMetricsReporter,
     witness_generator_queue_monitor::WitnessGeneratorStatsReporter,
 };
 use crate::metadata_calculator::{MetadataCalculator, MetadataCalculatorMode};
+use crate::rocksdb_backup::{
+    restore_missing_instances, BackupTarget, RocksdbBackuper, StateSnapshotDiffCreator,
+};
+use crate::schema_compatibility::{check_schema_compatibility, InstanceHeartbeater};
 use crate::state_keeper::mempool_actor::MempoolFetcher;
+use crate::state_keeper::sealed_miniblocks::SealedMiniblocksSender;
 use crate::state_keeper::MempoolGuard;
 use crate::witness_generator::WitnessGenerator;
 use crate::{
-    api_server::{explorer, web3},
+    api_server::{explorer, prover_gateway, web3},
     data_fetchers::run_data_fetchers,
     eth_sender::EthTxAggregator,
     eth_watch::start_eth_watch,
@@ -47,17 +65,25 @@ use crate::{
 };
 
 pub mod api_server;
+pub mod block_reverter;
+pub mod chain_watchdog;
 pub mod data_fetchers;
+pub mod db_maintenance;
 pub mod db_storage_provider;
+pub mod encrypted_mempool;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod fee_monitor;
+pub mod fee_repricing;
 pub mod fee_ticker;
+pub mod fork;
 pub mod gas_adjuster;
 pub mod gas_tracker;
 pub mod genesis;
 pub mod house_keeper;
 pub mod metadata_calculator;
+pub mod rocksdb_backup;
+pub mod schema_compatibility;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod witness_generator;
@@ -100,6 +126,8 @@ pub enum Component {
     WsApi,
     // REST API for explorer.
     ExplorerApi,
+    // REST API for external provers.
+    ProverGateway,
     // Metadata Calculator.
     Tree,
     TreeLightweight,
@@ -117,6 +145,19 @@ pub enum Component {
     WitnessGenerator(Option<usize>),
     // Component for housekeeping task such as cleaning blobs from GCS, reporting metrics etc.
     Housekeeper,
+    // Watchdog alerting when the chain stops making progress.
+    ChainWatchdog,
+    // Monitor of the table bloat, optionally vacuuming the bloated tables.
+    DbMaintenance,
+    // Periodic backups of the RocksDB instances to the object store.
+    RocksdbBackup,
+    // Incremental state snapshots on top of the state keeper cache backups, used by the external nodes to catch up.
+    StateSnapshotDiffs,
+    // Decryptor of the experimental encrypted mempool, combining the decryption shares of the committee members.
+    EncryptedTxDecryptor,
+    // Publisher of the decryption shares of an encrypted mempool committee member. The only component provided
+    // with a key share.
+    EncryptedTxShareProvider,
 }
 
 #[derive(Debug)]
@@ -135,12 +176,21 @@ impl FromStr for Components {
             "http_api" => Ok(Components(vec![Component::HttpApi])),
             "ws_api" => Ok(Components(vec![Component::WsApi])),
             "explorer_api" => Ok(Components(vec![Component::ExplorerApi])),
+            "prover_gateway" => Ok(Components(vec![Component::ProverGateway])),
             "tree" => Ok(Components(vec![Component::Tree])),
             "tree_lightweight" => Ok(Components(vec![Component::TreeLightweight])),
             "tree_backup" => Ok(Components(vec![Component::TreeBackup])),
             "data_fetcher" => Ok(Components(vec![Component::DataFetcher])),
             "state_keeper" => Ok(Components(vec![Component::StateKeeper])),
             "housekeeper" => Ok(Components(vec![Component::Housekeeper])),
+            "chain_watchdog" => Ok(Components(vec![Component::ChainWatchdog])),
+            "db_maintenance" => Ok(Components(vec![Component::DbMaintenance])),
+            "rocksdb_backup" => Ok(Components(vec![Component::RocksdbBackup])),
+            "state_snapshot_diffs" => Ok(Components(vec![Component::StateSnapshotDiffs])),
+            "encrypted_tx_decryptor" => Ok(Components(vec![Component::EncryptedTxDecryptor])),
+            "encrypted_tx_decryption_share" => {
+                Ok(Components(vec![Component::EncryptedTxShareProvider]))
+            }
             "witness_generator" => Ok(Components(vec![Component::WitnessGenerator(None)])),
             "one_shot_witness_generator" => {
                 Ok(Components(vec![Component::WitnessGenerator(Some(1))]))
@@ -178,23 +228,67 @@ pub async fn initialize_components(
     circuit_breaker_checker.check().await.unwrap_or_else(|err| {
         panic!("Circuit breaker triggered: {}", err);
     });
+    check_required_indices(&connection_pool, config.db.enforce_required_indices).await?;
+    check_schema_compatibility(&connection_pool, config.db.enforce_schema_compatibility).await?;
+    init_fork_source(&config.chain.fork).await?;
+    if config.db.restore_from_object_store {
+        restore_rocksdb_instances(config, &components).await;
+    }
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let (cb_sender, cb_receiver) = oneshot::channel();
+    let sealed_miniblocks = SealedMiniblocksSender::new();
     // Prometheus exporter and circuit breaker checker should run for every component configuration.
     let mut task_futures: Vec<JoinHandle<()>> = vec![
         run_prometheus_exporter(config.api.prometheus.clone(), use_prometheus_pushgateway),
         tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
+        tokio::spawn(
+            InstanceHeartbeater::new(
+                ConnectionPool::new(Some(1), true),
+                format!("{:?}", components),
+            )
+            .run(stop_receiver.clone()),
+        ),
     ];
 
+    let mut api_workloads = vec![];
+    if components.contains(&Component::HttpApi) {
+        api_workloads.push(ApiWorkload::Http);
+    }
+    if components.contains(&Component::WsApi) {
+        api_workloads.extend([ApiWorkload::Ws, ApiWorkload::PubSub]);
+    }
+    if !api_workloads.is_empty() {
+        api_workloads.push(ApiWorkload::TxSender);
+    }
+    let api_pools = ApiConnectionPools::new(&config.api.web3_json_rpc, &api_workloads);
+    let (api_audit_log, api_audit_log_writer) =
+        ApiAuditLogMiddleware::new(&config.api.web3_json_rpc);
+    let (api_usage_metering, api_usage_exporter) =
+        ApiUsageMeteringMiddleware::new(&config.api.web3_json_rpc);
+    let api_middleware: ApiMiddleware =
+        (api_audit_log, api_usage_metering, ApiExtensionsMiddleware);
+    if !api_workloads.is_empty() {
+        task_futures.push(tokio::spawn(
+            api_pools.watcher().run(ConnectionPool::new(Some(1), false)),
+        ));
+        if let Some(writer) = api_audit_log_writer {
+            task_futures.push(tokio::spawn(writer.run(stop_receiver.clone())));
+        }
+        if let Some(exporter) = api_usage_exporter {
+            task_futures.push(tokio::spawn(exporter.run(stop_receiver.clone())));
+        }
+    }
+
     if components.contains(&Component::HttpApi) {
         let started_at = Instant::now();
         vlog::info!("initializing HTTP API");
         task_futures.extend(
             run_http_api(
                 config,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
+                api_pools.get(ApiWorkload::TxSender),
+                api_pools.get(ApiWorkload::Http),
+                api_middleware.clone(),
                 stop_receiver.clone(),
             )
             .await,
@@ -209,8 +303,14 @@ pub async fn initialize_components(
         task_futures.extend(
             run_ws_api(
                 config,
-                connection_pool.clone(),
-                replica_connection_pool.clone(),
+                api_pools.get(ApiWorkload::TxSender),
+                api_pools.get(ApiWorkload::Ws),
+                api_pools.get(ApiWorkload::PubSub),
+                api_middleware,
+                // The notifiers are driven by the state keeper only if it runs in the same process.
+                components
+                    .contains(&Component::StateKeeper)
+                    .then(|| sealed_miniblocks.clone()),
                 stop_receiver.clone(),
             )
             .await,
@@ -235,6 +335,18 @@ pub async fn initialize_components(
         metrics::gauge!("server.init.latency", started_at.elapsed().as_secs() as f64, "stage" => "explorer_api");
     }
 
+    if components.contains(&Component::ProverGateway) {
+        let started_at = Instant::now();
+        vlog::info!("initializing prover gateway");
+        task_futures.push(prover_gateway::start_server_thread_detached(
+            config.api.prover_gateway.clone(),
+            connection_pool.clone(),
+            stop_receiver.clone(),
+        ));
+        vlog::info!("initialized prover gateway in {:?}", started_at.elapsed());
+        metrics::gauge!("server.init.latency", started_at.elapsed().as_secs() as f64, "stage" => "prover_gateway");
+    }
+
     if components.contains(&Component::StateKeeper) {
         let started_at = Instant::now();
         vlog::info!("initializing State Keeper");
@@ -263,6 +375,7 @@ pub async fn initialize_components(
             &state_keeper_pool,
             mempool.clone(),
             gas_adjuster.clone(),
+            sealed_miniblocks.clone(),
             stop_receiver.clone(),
         );
 
@@ -270,6 +383,11 @@ pub async fn initialize_components(
             state_keeper_actor.run()
         }));
 
+        let expired_txs_cleaner = ExpiredTxsCleaner::new(mempool.clone());
+        task_futures.push(tokio::spawn(
+            expired_txs_cleaner.run(ConnectionPool::new(Some(1), true)),
+        ));
+
         let mempool_fetcher_pool = ConnectionPool::new(Some(1), true);
         let mempool_fetcher_actor = MempoolFetcher::new(mempool, gas_adjuster, config);
         task_futures.push(tokio::spawn(mempool_fetcher_actor.run(
@@ -410,6 +528,7 @@ pub async fn initialize_components(
             );
             let config = WitnessGeneratorConfig::from_env();
             let witness_generator = WitnessGenerator::new(config);
+            witness_generator.prepare_initial_setup_keys().await;
             task_futures.push(tokio::spawn(witness_generator.run(
                 connection_pool.clone(),
                 stop_receiver.clone(),
@@ -440,14 +559,93 @@ pub async fn initialize_components(
             tokio::spawn(gcs_blob_cleaner.run(ConnectionPool::new(Some(1), true))),
             tokio::spawn(L1BatchMetricsReporter::default().run(ConnectionPool::new(Some(1), true))),
             tokio::spawn(ProverStatsReporter::default().run(ConnectionPool::new(Some(1), true))),
+            tokio::spawn(ChainStatsAggregator::default().run(ConnectionPool::new(Some(1), true))),
+            tokio::spawn(
+                TxLifecycleEventsCleaner::default().run(ConnectionPool::new(Some(1), true)),
+            ),
+            tokio::spawn(
+                TxExecuteColumnsBackfiller::default().run(ConnectionPool::new(Some(1), true)),
+            ),
+            tokio::spawn(TransfersBackfiller::default().run(ConnectionPool::new(Some(1), true))),
         ];
 
         task_futures.extend(witness_generator_metrics);
     }
 
+    if components.contains(&Component::ChainWatchdog) {
+        let watchdog = ChainWatchdog::new(
+            config.chain.watchdog.clone(),
+            ConnectionPool::new(Some(1), false),
+        );
+        task_futures.push(tokio::spawn(watchdog.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::DbMaintenance) {
+        // Vacuum has to be run on the master database.
+        let db_maintenance = DBMaintenance::new(
+            DBMaintenanceConfig::from_env(),
+            ConnectionPool::new(Some(1), true),
+        )?;
+        task_futures.push(tokio::spawn(db_maintenance.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::RocksdbBackup) {
+        let backuper = RocksdbBackuper::new(
+            config.db.clone(),
+            create_object_store_from_env(),
+            ConnectionPool::new(Some(1), true),
+        );
+        task_futures.push(tokio::spawn(backuper.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::StateSnapshotDiffs) {
+        let creator = StateSnapshotDiffCreator::new(
+            config.db.clone(),
+            create_object_store_from_env(),
+            ConnectionPool::new(Some(1), true),
+        );
+        task_futures.push(tokio::spawn(creator.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::EncryptedTxDecryptor) {
+        task_futures.extend(run_encrypted_tx_decryptor(config, stop_receiver.clone()).await);
+    }
+
+    if components.contains(&Component::EncryptedTxShareProvider) {
+        let share_provider = EncryptedTxShareProvider::new(
+            EncryptedMempoolConfig::from_env(),
+            ConnectionPool::new(Some(1), true),
+        )?;
+        task_futures.push(tokio::spawn(share_provider.run(stop_receiver.clone())));
+    }
+
     Ok((task_futures, stop_sender, cb_receiver))
 }
 
+/// Restores the RocksDB instances used by the components from the object store, if they are missing.
+async fn restore_rocksdb_instances(config: &ZkSyncConfig, components: &[Component]) {
+    let targets: Vec<_> = components
+        .iter()
+        .filter_map(|component| match component {
+            Component::Tree => Some(BackupTarget::Tree),
+            Component::TreeLightweight => Some(BackupTarget::LightweightTree),
+            Component::StateKeeper => Some(BackupTarget::StateKeeperCache),
+            _ => None,
+        })
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+
+    let db_config = config.db.clone();
+    tokio::task::spawn_blocking(move || {
+        let pool = ConnectionPool::new(Some(1), true);
+        restore_missing_instances(&db_config, &targets, &create_object_store_from_env(), &pool);
+    })
+    .await
+    .expect("RocksDB restore panicked");
+}
+
 fn run_tree(
     config: &ZkSyncConfig,
     stop_receiver: watch::Receiver<bool>,
@@ -462,6 +660,7 @@ async fn run_http_api(
     config: &ZkSyncConfig,
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
+    middleware: ApiMiddleware,
     stop_receiver: watch::Receiver<bool>,
 ) -> Vec<JoinHandle<()>> {
     let eth_gateway = EthereumClient::from_config(config);
@@ -477,15 +676,47 @@ async fn run_http_api(
             config,
             stop_receiver.clone(),
             gas_adjuster.clone(),
+            middleware,
         ),
         tokio::spawn(gas_adjuster.run(stop_receiver)),
     ]
 }
 
+async fn run_encrypted_tx_decryptor(
+    config: &ZkSyncConfig,
+    stop_receiver: watch::Receiver<bool>,
+) -> Vec<JoinHandle<()>> {
+    let eth_gateway = EthereumClient::from_config(config);
+    let gas_adjuster = Arc::new(
+        GasAdjuster::new(eth_gateway, config.eth_sender.gas_adjuster)
+            .await
+            .unwrap(),
+    );
+    // Decrypted transactions are validated in the same way as the ones submitted via the API.
+    let tx_sender = web3::build_tx_sender(
+        config,
+        ConnectionPool::new(Some(1), true),
+        ConnectionPool::new(Some(1), false),
+        gas_adjuster.clone(),
+    );
+    let decryptor = EncryptedTxDecryptor::new(
+        EncryptedMempoolConfig::from_env(),
+        ConnectionPool::new(Some(1), true),
+        tx_sender,
+    );
+    vec![
+        tokio::spawn(decryptor.run(stop_receiver.clone())),
+        tokio::spawn(gas_adjuster.run(stop_receiver)),
+    ]
+}
+
 async fn run_ws_api(
     config: &ZkSyncConfig,
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
+    pubsub_connection_pool: ConnectionPool,
+    middleware: ApiMiddleware,
+    sealed_miniblocks: Option<SealedMiniblocksSender>,
     stop_receiver: watch::Receiver<bool>,
 ) -> Vec<JoinHandle<()>> {
     let eth_gateway = EthereumClient::from_config(config);
@@ -497,12 +728,37 @@ async fn run_ws_api(
     web3::start_ws_rpc_server_old(
         master_connection_pool,
         replica_connection_pool,
+        pubsub_connection_pool,
         config,
         stop_receiver,
         gas_adjuster,
+        middleware,
+        sealed_miniblocks,
     )
 }
 
+/// Verifies that the indices the hot DAL queries rely on are present, so that a migration drift
+/// is detected at startup rather than by the degraded API latency.
+async fn check_required_indices(pool: &ConnectionPool, enforce: bool) -> anyhow::Result<()> {
+    let missing_indices = pool
+        .access_storage()
+        .await
+        .indices_dal()
+        .get_missing_required_indices();
+    for index in &missing_indices {
+        vlog::warn!(
+            "No index on `{}` ({}) required by the {} queries",
+            index.table,
+            index.columns.join(", "),
+            index.query_family
+        );
+    }
+    if enforce && !missing_indices.is_empty() {
+        anyhow::bail!("{} required indices are missing", missing_indices.len());
+    }
+    Ok(())
+}
+
 fn circuit_breakers_for_components(
     components: &[Component],
     config: &ZkSyncConfig,
@@ -72,7 +72,10 @@ impl MetadataCalculator {
             Self::rocksdb_path(&config.db, mode),
             true,
         );
-        let tree = ZkSyncTree::new_with_mode(db, mode.into());
+        let mut tree = ZkSyncTree::new_with_mode(db, mode.into());
+        if config.db.merkle_tree_history_enabled {
+            tree = tree.with_history();
+        }
         Self {
             delay_interval: config.chain.operations_manager.delay_interval(),
             tree,
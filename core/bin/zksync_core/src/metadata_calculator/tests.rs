@@ -211,6 +211,8 @@ async fn reset_db_state(pool: ConnectionPool, num_blocks: usize) {
             l1_gas_price: 0,
             l2_fair_gas_price: 0,
             base_system_contracts_hashes: base_system_contracts.hashes(),
+            logs_bloom: Default::default(),
+            fee_account_address: header.fee_account_address,
         };
 
         storage
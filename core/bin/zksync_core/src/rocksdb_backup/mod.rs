@@ -0,0 +1,342 @@
+//! Backups of the RocksDB instances (Merkle tree and state keeper cache) stored in the object store.
+//!
+//! RocksDB instances are derived from Postgres, so a backup is only useful together with the L1 batch
+//! it corresponds to. Each backup is recorded in Postgres along with such a watermark: the number of the
+//! next L1 batch to be processed by the instance. A backup is a RocksDB checkpoint of the instance, and
+//! the watermark is read from the checkpoint itself, so that it matches the backed up state exactly.
+//!
+//! On restore, the latest backup not ahead of Postgres is downloaded, and the restored instance is checked
+//! against Postgres before it's used, so that the node catches up from the watermark instead of rebuilding
+//! the instance from scratch. The node restores the missing instances this way on startup if
+//! `DATABASE_RESTORE_FROM_OBJECT_STORE` is set (see [`restore_missing_instances()`]).
+//!
+//! On top of the state keeper cache backups, incremental state snapshots are created (see [`StateSnapshotDiffCreator`]),
+//! so that the external nodes can catch up with the main node without replaying every miniblock.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context as _;
+use tempfile::TempDir;
+use tokio::sync::watch;
+
+use zksync_config::DBConfig;
+use zksync_dal::ConnectionPool;
+use zksync_merkle_tree::ZkSyncTree;
+use zksync_object_store::object_store::{DynamicObjectStore, ROCKSDB_BACKUPS_BUCKET_PATH};
+use zksync_state::secondary_storage::SecondaryStateStorage;
+use zksync_storage::db::Database;
+use zksync_storage::RocksDB;
+use zksync_types::{L1BatchNumber, H256};
+
+pub use self::snapshot_diff::{StateSnapshotDiffCreator, StateSnapshotDiffData};
+
+mod snapshot_diff;
+
+/// RocksDB instance which can be backed up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupTarget {
+    Tree,
+    LightweightTree,
+    StateKeeperCache,
+}
+
+impl BackupTarget {
+    pub const ALL: [Self; 3] = [Self::Tree, Self::LightweightTree, Self::StateKeeperCache];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tree => "tree",
+            Self::LightweightTree => "tree_lightweight",
+            Self::StateKeeperCache => "state_keeper_cache",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|target| target.name() == name)
+    }
+
+    fn database(self) -> Database {
+        match self {
+            Self::Tree | Self::LightweightTree => Database::MerkleTree,
+            Self::StateKeeperCache => Database::StateKeeper,
+        }
+    }
+
+    fn db_path(self, config: &DBConfig) -> &str {
+        match self {
+            Self::Tree => config.path(),
+            Self::LightweightTree => config.merkle_tree_fast_ssd_path(),
+            Self::StateKeeperCache => config.state_keeper_db_path(),
+        }
+    }
+
+    /// Returns the number of the next L1 batch to be processed by the instance.
+    fn read_l1_batch_number(self, db: RocksDB) -> L1BatchNumber {
+        match self {
+            Self::Tree | Self::LightweightTree => L1BatchNumber(ZkSyncTree::new(db).block_number()),
+            Self::StateKeeperCache => SecondaryStateStorage::new(db).get_l1_batch_number(),
+        }
+    }
+}
+
+/// Checks whether the directory holds a RocksDB instance.
+fn db_exists(path: &Path) -> bool {
+    path.join("CURRENT").exists()
+}
+
+/// Lists the files in the directory recursively, as paths relative to it.
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_files(root, &path, files)?;
+        } else {
+            files.push(path.strip_prefix(root).unwrap().to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+/// Periodically backs up the RocksDB instances present on this machine to the object store.
+#[derive(Debug)]
+pub struct RocksdbBackuper {
+    config: DBConfig,
+    object_store: DynamicObjectStore,
+    pool: ConnectionPool,
+}
+
+impl RocksdbBackuper {
+    pub fn new(config: DBConfig, object_store: DynamicObjectStore, pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            object_store,
+            pool,
+        }
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) {
+        let mut timer =
+            tokio::time::interval(Duration::from_millis(self.config.backup_interval_ms));
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, RocksDB backuper is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            // Backups are heavy on disk IO, so they're performed outside of the async runtime.
+            self = tokio::task::spawn_blocking(move || {
+                for target in BackupTarget::ALL {
+                    if let Err(err) = self.backup(target) {
+                        vlog::error!("Failed to back up {}: {:#}", target.name(), err);
+                        metrics::counter!("server.rocksdb_backup.failures", 1, "target" => target.name());
+                    }
+                }
+                self
+            })
+            .await
+            .expect("RocksDB backup task panicked");
+        }
+    }
+
+    fn backup(&mut self, target: BackupTarget) -> anyhow::Result<()> {
+        let db_path = Path::new(target.db_path(&self.config));
+        if !db_exists(db_path) {
+            return Ok(());
+        }
+
+        let started_at = Instant::now();
+        fs::create_dir_all(self.config.merkle_tree_backup_path())?;
+        let backup_dir = TempDir::new_in(self.config.merkle_tree_backup_path())?;
+        let checkpoint_path = backup_dir.path().join("checkpoint");
+        // The instance is opened once, and the watermark is read from the checkpoint rather than
+        // from the live instance, which may move forward in the meantime.
+        let db = RocksDB::new_read_only(target.database(), db_path);
+        db.create_checkpoint(&checkpoint_path)?;
+        drop(db);
+        let l1_batch_number = target
+            .read_l1_batch_number(RocksDB::new_read_only(target.database(), &checkpoint_path));
+
+        let mut files = vec![];
+        list_files(&checkpoint_path, &checkpoint_path, &mut files)?;
+        let unix_millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let prefix = format!("{}/{}_{}", target.name(), l1_batch_number.0, unix_millis);
+        let mut object_keys = Vec::with_capacity(files.len());
+        for file in files {
+            let key = format!("{}/{}", prefix, file.to_str().unwrap());
+            let bytes = fs::read(checkpoint_path.join(&file))?;
+            self.object_store
+                .put(ROCKSDB_BACKUPS_BUCKET_PATH, key.clone(), bytes)?;
+            object_keys.push(key);
+        }
+
+        let mut storage = self.pool.access_storage_blocking();
+        storage
+            .rocksdb_backups_dal()
+            .insert_backup(target.name(), l1_batch_number, &object_keys);
+        vlog::info!(
+            "Backed up {} at L1 batch #{} in {:?}",
+            target.name(),
+            l1_batch_number,
+            started_at.elapsed()
+        );
+        metrics::histogram!("server.rocksdb_backup.latency", started_at.elapsed(), "target" => target.name());
+        metrics::gauge!("server.rocksdb_backup.l1_batch_number", l1_batch_number.0 as f64, "target" => target.name());
+
+        let outdated_backups = storage
+            .rocksdb_backups_dal()
+            .get_outdated_backups(target.name(), self.config.backup_count());
+        for backup in outdated_backups {
+            for key in backup.object_keys {
+                if let Err(err) = self.object_store.remove(ROCKSDB_BACKUPS_BUCKET_PATH, key) {
+                    vlog::warn!("Failed to remove outdated backup file: {}", err);
+                }
+            }
+            storage.rocksdb_backups_dal().delete_backup(backup.id);
+        }
+        Ok(())
+    }
+}
+
+/// Restores the instance from the latest backup in the object store which is consistent with Postgres.
+/// Returns the number of the next L1 batch to be processed by the restored instance.
+pub fn restore_from_object_store(
+    config: &DBConfig,
+    target: BackupTarget,
+    object_store: &DynamicObjectStore,
+    pool: &ConnectionPool,
+) -> anyhow::Result<L1BatchNumber> {
+    let db_path = Path::new(target.db_path(config));
+    anyhow::ensure!(
+        !db_exists(db_path),
+        "{} already holds a RocksDB instance, remove it before restoring",
+        db_path.display()
+    );
+
+    let mut storage = pool.access_storage_blocking();
+    let next_l1_batch_number = storage.blocks_dal().get_sealed_block_number() + 1;
+    let backup = storage
+        .rocksdb_backups_dal()
+        .get_latest_backup(target.name(), next_l1_batch_number)
+        .with_context(|| {
+            format!(
+                "no backups of {} at or before L1 batch #{}",
+                target.name(),
+                next_l1_batch_number
+            )
+        })?;
+    vlog::info!(
+        "Restoring {} from the backup taken at L1 batch #{} on {}",
+        target.name(),
+        backup.l1_batch_number,
+        backup.created_at
+    );
+
+    // The checkpoint is downloaded next to the instance files, so that they can be moved in place
+    // once the checkpoint is validated.
+    fs::create_dir_all(db_path)?;
+    let checkpoint_dir = TempDir::new_in(db_path)?;
+    for key in &backup.object_keys {
+        // Keys have the `{target}/{backup}/{relative path}` form.
+        let relative_path = key
+            .splitn(3, '/')
+            .nth(2)
+            .with_context(|| format!("malformed backup key {}", key))?;
+        let path = checkpoint_dir.path().join(relative_path);
+        fs::create_dir_all(path.parent().unwrap())?;
+        let bytes = object_store.get(ROCKSDB_BACKUPS_BUCKET_PATH, key.clone())?;
+        fs::write(path, bytes)?;
+    }
+
+    let restored = RocksDB::new_read_only(target.database(), checkpoint_dir.path());
+    let l1_batch_number = match target {
+        BackupTarget::Tree | BackupTarget::LightweightTree => {
+            let tree = ZkSyncTree::new(restored);
+            let l1_batch_number = L1BatchNumber(tree.block_number());
+            let root_hash = H256::from_slice(&tree.root_hash());
+            validate_restored_instance(
+                &mut storage,
+                next_l1_batch_number,
+                l1_batch_number,
+                Some(root_hash),
+            )?
+        }
+        BackupTarget::StateKeeperCache => {
+            let l1_batch_number = SecondaryStateStorage::new(restored).get_l1_batch_number();
+            validate_restored_instance(&mut storage, next_l1_batch_number, l1_batch_number, None)?
+        }
+    };
+
+    // `CURRENT` is moved last, so that an interrupted restore doesn't leave an openable instance behind.
+    let mut files = vec![];
+    list_files(checkpoint_dir.path(), checkpoint_dir.path(), &mut files)?;
+    files.sort_by_key(|file| file == Path::new("CURRENT"));
+    for file in files {
+        fs::rename(checkpoint_dir.path().join(&file), db_path.join(&file))?;
+    }
+    Ok(l1_batch_number)
+}
+
+/// Restores the missing instances of the targets from the object store before the components using them
+/// are started. Instances without a suitable backup are left to be rebuilt from Postgres.
+pub fn restore_missing_instances(
+    config: &DBConfig,
+    targets: &[BackupTarget],
+    object_store: &DynamicObjectStore,
+    pool: &ConnectionPool,
+) {
+    for &target in targets {
+        if db_exists(Path::new(target.db_path(config))) {
+            continue;
+        }
+        match restore_from_object_store(config, target, object_store, pool) {
+            Ok(l1_batch_number) => vlog::info!(
+                "Restored {} from the object store, next L1 batch to process is #{}",
+                target.name(),
+                l1_batch_number
+            ),
+            Err(err) => vlog::warn!(
+                "Failed to restore {} from the object store, it will be rebuilt from Postgres: {:#}",
+                target.name(),
+                err
+            ),
+        }
+    }
+}
+
+fn validate_restored_instance(
+    storage: &mut zksync_dal::StorageProcessor<'_>,
+    next_l1_batch_number: L1BatchNumber,
+    l1_batch_number: L1BatchNumber,
+    root_hash: Option<H256>,
+) -> anyhow::Result<L1BatchNumber> {
+    anyhow::ensure!(
+        l1_batch_number <= next_l1_batch_number,
+        "restored instance is at L1 batch #{}, which is ahead of Postgres (#{})",
+        l1_batch_number,
+        next_l1_batch_number
+    );
+    if let (Some(root_hash), Some(last_processed)) = (root_hash, l1_batch_number.0.checked_sub(1)) {
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_block_state_root(L1BatchNumber(last_processed));
+        if let Some(expected_root_hash) = expected_root_hash {
+            anyhow::ensure!(
+                root_hash == expected_root_hash,
+                "root hash of the restored tree {:?} doesn't match the state root of L1 batch #{} {:?}",
+                root_hash,
+                last_processed,
+                expected_root_hash
+            );
+        }
+    }
+    Ok(l1_batch_number)
+}
@@ -0,0 +1,376 @@
+//! Incremental state snapshots for the external nodes.
+//!
+//! The backup of the state keeper cache serves as a full snapshot of the state. Snapshot diffs form a chain on top
+//! of it: each diff contains the final values of the storage slots changed since the end of the previous diff
+//! (or since the backup watermark for the first diff of the chain), together with the contracts and factory deps
+//! published in the meantime. A node whose state is at any L1 batch covered by a diff may apply it, so it catches up
+//! by applying the diffs one by one instead of replaying all the miniblocks in between via the fetcher.
+//!
+//! Each diff records the root hash of its last L1 batch, which the node checks against the main node. Diffs are
+//! kept for `DATABASE_SNAPSHOT_DIFF_RETENTION_SEC` after they are created, so that they aren't removed while
+//! the nodes are downloading them.
+
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use zksync_config::DBConfig;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_object_store::object_store::{DynamicObjectStore, STATE_SNAPSHOT_DIFFS_BUCKET_PATH};
+use zksync_state::secondary_storage::SecondaryStateStorage;
+use zksync_types::{
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, StorageLog, H256,
+};
+
+use super::BackupTarget;
+
+/// Number of the changed storage slots loaded from Postgres at a time.
+const STORAGE_SLOTS_PAGE_SIZE: usize = 10_000;
+
+/// Contents of a state snapshot diff file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshotDiffData {
+    pub base_l1_batch_number: L1BatchNumber,
+    /// Number of the next L1 batch to be processed after the diff is applied.
+    pub l1_batch_number: L1BatchNumber,
+    /// First miniblock of `l1_batch_number`.
+    pub first_miniblock_number: MiniblockNumber,
+    /// Root hash of the last L1 batch of the diff (`l1_batch_number - 1`).
+    pub root_hash: H256,
+    /// Final values of the slots changed by the L1 batches of the diff.
+    pub storage_slots: Vec<(StorageKey, H256)>,
+    pub contracts: Vec<(Address, Vec<u8>)>,
+    pub factory_deps: Vec<(H256, Vec<u8>)>,
+}
+
+impl StateSnapshotDiffData {
+    fn load(
+        storage: &mut StorageProcessor<'_>,
+        base_l1_batch_number: L1BatchNumber,
+        l1_batch_number: L1BatchNumber,
+        root_hash: H256,
+        miniblocks: RangeInclusive<MiniblockNumber>,
+    ) -> anyhow::Result<Self> {
+        let mut storage_slots = vec![];
+        let mut after = None;
+        loop {
+            let page = storage.storage_web3_dal().get_state_diff(
+                miniblocks.clone(),
+                after,
+                STORAGE_SLOTS_PAGE_SIZE,
+            )?;
+            let is_last_page = page.len() < STORAGE_SLOTS_PAGE_SIZE;
+            after = page.last().map(|slot| slot.hashed_key);
+            storage_slots.extend(page.into_iter().map(|slot| {
+                let key = StorageKey::new(AccountTreeId::new(slot.address), slot.key);
+                (key, slot.new_value)
+            }));
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(Self {
+            base_l1_batch_number,
+            l1_batch_number,
+            first_miniblock_number: *miniblocks.end() + 1,
+            root_hash,
+            storage_slots,
+            contracts: storage
+                .storage_dal()
+                .get_deployed_contracts_for_miniblocks(miniblocks.clone()),
+            factory_deps: storage
+                .storage_dal()
+                .get_factory_deps_for_miniblocks(miniblocks),
+        })
+    }
+
+    /// Applies the diff to the state stored in Postgres, i.e. the current values of the storage slots and
+    /// the factory deps, so that the node serves the state it has caught up to. The diff doesn't contain
+    /// the miniblocks themselves, so the factory deps are attributed to the latest miniblock stored by the node.
+    ///
+    /// Applying the diff is idempotent, so it's applied to Postgres before the state keeper cache:
+    /// if the node stops in between, the diff is applied again on the next start.
+    pub fn apply_to_postgres(&self, storage: &mut StorageProcessor<'_>) {
+        let mut transaction = storage.start_transaction_blocking();
+        let logs = self
+            .storage_slots
+            .iter()
+            .map(|&(key, value)| StorageLog::new_write_log(key, value))
+            .collect();
+        // Slots are not attributed to the transactions that changed them.
+        transaction
+            .storage_dal()
+            .apply_storage_logs(&[(H256::zero(), logs)]);
+        let last_miniblock_number = transaction.blocks_dal().get_sealed_miniblock_number();
+        transaction.storage_dal().insert_factory_deps(
+            last_miniblock_number,
+            self.factory_deps.iter().cloned().collect(),
+        );
+        transaction.commit_blocking();
+    }
+
+    /// Checks that the state keeper cache is at an L1 batch covered by the diff.
+    pub fn check_applicable(&self, state: &SecondaryStateStorage) -> anyhow::Result<()> {
+        let l1_batch_number = state.get_l1_batch_number();
+        anyhow::ensure!(
+            self.base_l1_batch_number <= l1_batch_number && l1_batch_number <= self.l1_batch_number,
+            "state keeper cache is at L1 batch #{}, which is not covered by the diff for L1 batches #{}..#{}",
+            l1_batch_number,
+            self.base_l1_batch_number,
+            self.l1_batch_number
+        );
+        Ok(())
+    }
+
+    /// Applies the diff to the state keeper cache, which must be at an L1 batch covered by the diff.
+    pub fn apply(self, state: &mut SecondaryStateStorage) -> anyhow::Result<()> {
+        self.check_applicable(state)?;
+
+        let logs: Vec<_> = self
+            .storage_slots
+            .into_iter()
+            .map(|(key, value)| StorageLog::new_write_log(key, value))
+            .collect();
+        state.process_transaction_logs(&logs);
+        for (address, bytecode) in self.contracts {
+            state.store_contract(address, bytecode);
+        }
+        for (hash, bytecode) in self.factory_deps {
+            state.store_factory_dep(hash, bytecode);
+        }
+        state.save(self.l1_batch_number);
+        Ok(())
+    }
+}
+
+/// Periodically uploads the state snapshot diff since the previous diff to the object store.
+#[derive(Debug)]
+pub struct StateSnapshotDiffCreator {
+    config: DBConfig,
+    object_store: DynamicObjectStore,
+    pool: ConnectionPool,
+}
+
+impl StateSnapshotDiffCreator {
+    pub fn new(config: DBConfig, object_store: DynamicObjectStore, pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            object_store,
+            pool,
+        }
+    }
+
+    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) {
+        let mut timer =
+            tokio::time::interval(Duration::from_millis(self.config.snapshot_diff_interval_ms));
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, state snapshot diff creator is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            self = tokio::task::spawn_blocking(move || {
+                if let Err(err) = self.create_diff() {
+                    vlog::error!("Failed to create state snapshot diff: {:#}", err);
+                    metrics::counter!("server.state_snapshot_diff.failures", 1);
+                }
+                self
+            })
+            .await
+            .expect("state snapshot diff task panicked");
+        }
+    }
+
+    fn create_diff(&mut self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_blocking();
+        // Diffs only cover the L1 batches with the known root hash, so that the nodes can verify them.
+        let last_l1_batch = storage.blocks_dal().get_last_block_number_with_metadata();
+        let l1_batch_number = last_l1_batch + 1;
+        let Some(backup) = storage
+            .rocksdb_backups_dal()
+            .get_latest_backup(BackupTarget::StateKeeperCache.name(), l1_batch_number)
+        else {
+            // There's no full snapshot to build the chain of diffs on yet.
+            return Ok(());
+        };
+        // The new diff is chained on the latest one, unless the chain ended before the latest backup
+        // (e.g., if no diffs were created for a while), in which case a new chain starts from the backup.
+        let base_l1_batch_number = match storage.state_snapshot_diffs_dal().get_latest_diff() {
+            Some(diff) if diff.l1_batch_number >= backup.l1_batch_number => diff.l1_batch_number,
+            _ => backup.l1_batch_number,
+        };
+        if base_l1_batch_number >= l1_batch_number {
+            return Ok(());
+        }
+
+        let started_at = Instant::now();
+        let root_hash = storage
+            .blocks_dal()
+            .get_block_state_root(last_l1_batch)
+            .with_context(|| format!("L1 batch #{} has no root hash", last_l1_batch))?;
+        let (first_miniblock, _) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(base_l1_batch_number)
+            .with_context(|| format!("L1 batch #{} has no miniblocks", base_l1_batch_number))?;
+        let (_, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch)
+            .with_context(|| format!("L1 batch #{} has no miniblocks", last_l1_batch))?;
+        let diff = StateSnapshotDiffData::load(
+            &mut storage,
+            base_l1_batch_number,
+            l1_batch_number,
+            root_hash,
+            first_miniblock..=last_miniblock,
+        )?;
+        let slot_count = diff.storage_slots.len();
+
+        let object_key = format!("{}_{}.bin", base_l1_batch_number.0, l1_batch_number.0);
+        let bytes = bincode::serialize(&diff)?;
+        let size = bytes.len();
+        self.object_store
+            .put(STATE_SNAPSHOT_DIFFS_BUCKET_PATH, object_key.clone(), bytes)?;
+        storage.state_snapshot_diffs_dal().insert_diff(
+            base_l1_batch_number,
+            l1_batch_number,
+            diff.first_miniblock_number,
+            root_hash,
+            &object_key,
+        );
+        vlog::info!(
+            "Created state snapshot diff for L1 batches #{}..#{} ({} slots, {} bytes) in {:?}",
+            base_l1_batch_number,
+            l1_batch_number,
+            slot_count,
+            size,
+            started_at.elapsed()
+        );
+        metrics::histogram!("server.state_snapshot_diff.latency", started_at.elapsed());
+        metrics::histogram!("server.state_snapshot_diff.size", size as f64);
+
+        let outdated_diffs = storage
+            .state_snapshot_diffs_dal()
+            .get_outdated_diffs(self.config.snapshot_diff_retention());
+        for diff in outdated_diffs {
+            if let Err(err) = self
+                .object_store
+                .remove(STATE_SNAPSHOT_DIFFS_BUCKET_PATH, diff.object_key)
+            {
+                vlog::warn!("Failed to remove outdated state snapshot diff: {}", err);
+            }
+            storage.state_snapshot_diffs_dal().delete_diff(diff.id);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db_test_macro::db_test;
+    use tempfile::TempDir;
+    use zksync_storage::{db::Database, RocksDB};
+    use zksync_types::block::MiniblockHeader;
+
+    fn storage_key(index: u64) -> StorageKey {
+        StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(1)),
+            H256::from_low_u64_be(index),
+        )
+    }
+
+    fn diff(base_l1_batch_number: u32, l1_batch_number: u32) -> StateSnapshotDiffData {
+        StateSnapshotDiffData {
+            base_l1_batch_number: L1BatchNumber(base_l1_batch_number),
+            l1_batch_number: L1BatchNumber(l1_batch_number),
+            first_miniblock_number: MiniblockNumber(10),
+            root_hash: H256::repeat_byte(0xdd),
+            storage_slots: vec![
+                (storage_key(1), H256::repeat_byte(0xaa)),
+                (storage_key(2), H256::zero()),
+            ],
+            contracts: vec![],
+            factory_deps: vec![(H256::repeat_byte(0xbb), vec![1, 2, 3])],
+        }
+    }
+
+    #[test]
+    fn applying_snapshot_diff() {
+        let temp_dir = TempDir::new().expect("failed to get temporary directory for RocksDB");
+        let db = RocksDB::new(Database::StateKeeper, temp_dir.as_ref(), false);
+        let mut state = SecondaryStateStorage::new(db);
+        state.process_transaction_logs(&[StorageLog::new_write_log(
+            storage_key(2),
+            H256::repeat_byte(0xcc),
+        )]);
+        state.save(L1BatchNumber(3));
+
+        // The base of the diff is ahead of the state.
+        assert!(diff(4, 6).apply(&mut state).is_err());
+        assert_eq!(state.get_l1_batch_number(), L1BatchNumber(3));
+
+        diff(2, 6).apply(&mut state).unwrap();
+        assert_eq!(state.get_l1_batch_number(), L1BatchNumber(6));
+        assert_eq!(
+            state.read_value_opt(&storage_key(1)),
+            Some(H256::repeat_byte(0xaa))
+        );
+        assert_eq!(state.read_value_opt(&storage_key(2)), Some(H256::zero()));
+        assert_eq!(
+            state.load_factory_dependency(H256::repeat_byte(0xbb)),
+            Some(vec![1, 2, 3])
+        );
+
+        // The state is already past the diff.
+        assert!(diff(2, 5).apply(&mut state).is_err());
+    }
+
+    #[db_test]
+    async fn applying_snapshot_diff_to_postgres(connection_pool: ConnectionPool) {
+        let mut storage = connection_pool.access_test_storage().await;
+        storage.blocks_dal().insert_miniblock(MiniblockHeader {
+            number: MiniblockNumber(0),
+            timestamp: 0,
+            hash: H256::zero(),
+            l1_tx_count: 0,
+            l2_tx_count: 0,
+            base_fee_per_gas: 0,
+            l1_gas_price: 0,
+            l2_fair_gas_price: 0,
+            base_system_contracts_hashes: Default::default(),
+            logs_bloom: Default::default(),
+            fee_account_address: Address::zero(),
+        });
+        storage.storage_dal().apply_storage_logs(&[(
+            H256::zero(),
+            vec![StorageLog::new_write_log(
+                storage_key(2),
+                H256::repeat_byte(0xcc),
+            )],
+        )]);
+
+        let diff = diff(0, 6);
+        diff.apply_to_postgres(&mut storage);
+        // Applying the diff again doesn't change the state.
+        diff.apply_to_postgres(&mut storage);
+        assert_eq!(
+            storage.storage_dal().get_by_key(&storage_key(1)),
+            Some(H256::repeat_byte(0xaa))
+        );
+        assert_eq!(
+            storage.storage_dal().get_by_key(&storage_key(2)),
+            Some(H256::zero())
+        );
+        assert_eq!(
+            storage
+                .storage_dal()
+                .get_factory_dep(H256::repeat_byte(0xbb)),
+            Some(vec![1, 2, 3])
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Gate for the rolling upgrades of the server instances sharing a database.
+//!
+//! Each binary knows the DB schema version it's built for (the version of its latest migration), and the oldest
+//! schema version whose binaries can still run against its schema. Running instances advertise both versions via
+//! heartbeats, so that a starting instance can check that it's compatible both with the migrations applied
+//! to the database and with all the instances which are still running.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::sync::watch;
+
+use zksync_dal::instance_heartbeats_dal::{binary_schema_version, COMPATIBLE_SINCE_SCHEMA_VERSION};
+use zksync_dal::ConnectionPool;
+
+/// Interval between the heartbeats of the running instance.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Instances which haven't sent a heartbeat for this long are considered stopped.
+pub(crate) const HEARTBEAT_TTL: Duration = Duration::from_secs(60);
+
+/// Checks that the binary can run against the database and alongside the other running instances.
+/// If `enforce` is not set, incompatibilities are only reported in logs.
+pub async fn check_schema_compatibility(
+    pool: &ConnectionPool,
+    enforce: bool,
+) -> anyhow::Result<()> {
+    let schema_version = binary_schema_version();
+    let mut storage = pool.access_storage().await;
+    let db_schema_version = storage.instance_heartbeats_dal().get_schema_version();
+    let live_instances = storage
+        .instance_heartbeats_dal()
+        .get_live_instances(HEARTBEAT_TTL);
+    drop(storage);
+
+    let mut incompatibilities = vec![];
+    match db_schema_version {
+        Some(db_schema_version) if db_schema_version < schema_version => {
+            incompatibilities.push(format!(
+                "DB schema version {} is older than version {} the binary is built for, migrations must be applied first",
+                db_schema_version, schema_version
+            ));
+        }
+        Some(db_schema_version)
+            if db_schema_version > schema_version
+                && !live_instances
+                    .iter()
+                    .any(|instance| instance.schema_version >= db_schema_version) =>
+        {
+            // The compatibility is only advertised by the running instances of the newer binaries.
+            vlog::warn!(
+                "DB schema version {} is newer than version {} the binary is built for, \
+                 and no running instances advertise its compatibility",
+                db_schema_version,
+                schema_version
+            );
+        }
+        Some(_) => { /* Compatibility with the newer schema is checked below */ }
+        None => vlog::warn!("Cannot determine the DB schema version, no migrations are recorded"),
+    }
+
+    for instance in &live_instances {
+        if !instance.is_compatible_with(schema_version, COMPATIBLE_SINCE_SCHEMA_VERSION) {
+            incompatibilities.push(format!(
+                "running instance {} ({}) has DB schema version {} compatible since {}, \
+                 while the binary has version {} compatible since {}",
+                instance.instance_id,
+                instance.components,
+                instance.schema_version,
+                instance.compatible_since_schema_version,
+                schema_version,
+                COMPATIBLE_SINCE_SCHEMA_VERSION
+            ));
+        }
+    }
+
+    for incompatibility in &incompatibilities {
+        vlog::warn!("Incompatible DB schema: {}", incompatibility);
+    }
+    if enforce && !incompatibilities.is_empty() {
+        anyhow::bail!(
+            "DB schema version {} is incompatible with the database or the running instances",
+            schema_version
+        );
+    }
+    Ok(())
+}
+
+/// Advertises the DB schema versions of the running instance.
+#[derive(Debug)]
+pub struct InstanceHeartbeater {
+    pool: ConnectionPool,
+    instance_id: String,
+    components: String,
+}
+
+impl InstanceHeartbeater {
+    pub fn new(pool: ConnectionPool, components: String) -> Self {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "unknown".to_owned());
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        Self {
+            pool,
+            instance_id: format!("{}-{}-{}", host, std::process::id(), started_at),
+            components,
+        }
+    }
+
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) {
+        let schema_version = binary_schema_version();
+        let mut timer = tokio::time::interval(HEARTBEAT_INTERVAL);
+        loop {
+            if *stop_receiver.borrow() {
+                vlog::info!("Stop signal received, instance heartbeater is shutting down");
+                break;
+            }
+
+            timer.tick().await;
+            self.pool
+                .access_storage()
+                .await
+                .instance_heartbeats_dal()
+                .upsert_heartbeat(
+                    &self.instance_id,
+                    &self.components,
+                    schema_version,
+                    COMPATIBLE_SINCE_SCHEMA_VERSION,
+                );
+        }
+
+        self.pool
+            .access_storage()
+            .await
+            .instance_heartbeats_dal()
+            .remove_heartbeat(&self.instance_id);
+    }
+}
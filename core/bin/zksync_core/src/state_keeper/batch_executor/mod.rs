@@ -9,17 +9,24 @@ use vm::{
     },
     TxRevertReason, VmBlockResult, VmInstance,
 };
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::ConnectionPool;
 use zksync_state::{secondary_storage::SecondaryStateStorage, storage_view::StorageView};
 use zksync_storage::{db::Database, RocksDB};
 use zksync_types::{tx::ExecutionMetrics, Transaction, U256};
 use zksync_utils::bytecode::{hash_bytecode, CompressedBytecodeInfo};
 
+use crate::fork::ForkStorage;
 use crate::gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics};
 use crate::state_keeper::io::L1BatchParams;
 
 use crate::state_keeper::types::ExecutionMetricsForCriteria;
 
+use self::parallel::ShadowParallelExecutor;
+use self::shadow_vm::{execute_tx_in_shadow, finish_batch_in_shadow};
+
+mod parallel;
+mod shadow_vm;
 #[cfg(test)]
 mod tests;
 
@@ -118,6 +125,8 @@ pub(crate) struct MainBatchExecutorBuilder {
     reexecute_each_tx: bool,
     max_allowed_tx_gas_limit: U256,
     validation_computational_gas_limit: u32,
+    parallel_execution_shadow_mode: bool,
+    shadow_base_system_contracts: Option<BaseSystemContracts>,
 }
 
 impl MainBatchExecutorBuilder {
@@ -127,13 +136,22 @@ impl MainBatchExecutorBuilder {
         reexecute_each_tx: bool,
         max_allowed_tx_gas_limit: U256,
         validation_computational_gas_limit: u32,
+        parallel_execution_shadow_mode: bool,
+        shadow_base_system_contracts_hashes: Option<BaseSystemContractsHashes>,
     ) -> Self {
+        let shadow_base_system_contracts = shadow_base_system_contracts_hashes.map(|hashes| {
+            pool.access_storage_blocking()
+                .storage_dal()
+                .get_base_system_contracts(hashes.bootloader, hashes.default_aa)
+        });
         Self {
             state_keeper_db_path,
             pool,
             reexecute_each_tx,
             max_allowed_tx_gas_limit,
             validation_computational_gas_limit,
+            parallel_execution_shadow_mode,
+            shadow_base_system_contracts,
         }
     }
 }
@@ -166,6 +184,8 @@ impl L1BatchExecutorBuilder for MainBatchExecutorBuilder {
             self.reexecute_each_tx,
             self.max_allowed_tx_gas_limit,
             self.validation_computational_gas_limit,
+            self.parallel_execution_shadow_mode,
+            self.shadow_base_system_contracts.clone(),
             secondary_storage,
             l1_batch_params,
             None,
@@ -183,10 +203,13 @@ pub struct BatchExecutorHandle {
 }
 
 impl BatchExecutorHandle {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         reexecute_each_tx: bool,
         max_allowed_tx_gas_limit: U256,
         validation_computational_gas_limit: u32,
+        parallel_execution_shadow_mode: bool,
+        shadow_base_system_contracts: Option<BaseSystemContracts>,
         secondary_storage: SecondaryStateStorage,
         l1_batch_params: L1BatchParams,
         vm_gas_limit: Option<u32>,
@@ -196,6 +219,8 @@ impl BatchExecutorHandle {
             reexecute_each_tx,
             max_allowed_tx_gas_limit,
             validation_computational_gas_limit,
+            parallel_execution_shadow_mode,
+            shadow_base_system_contracts,
             commands: commands_receiver,
             vm_gas_limit,
         };
@@ -241,6 +266,12 @@ impl BatchExecutorHandle {
         metrics::histogram!("state_keeper.batch_executor.command_response_time", start.elapsed(), "command" => "rollback_last_tx");
     }
 
+    /// Notifies the executor that the transactions of the current miniblock were sealed.
+    /// Unlike other commands, doesn't wait for a confirmation.
+    pub(super) fn start_next_miniblock(&self) {
+        self.commands.send(Command::StartNextMiniblock).unwrap();
+    }
+
     pub(super) fn finish_batch(self) -> VmBlockResult {
         let (response_sender, response_receiver) = mpsc::sync_channel(0);
         self.commands
@@ -265,6 +296,9 @@ pub(super) struct BatchExecutor {
     reexecute_each_tx: bool,
     max_allowed_tx_gas_limit: U256,
     validation_computational_gas_limit: u32,
+    parallel_execution_shadow_mode: bool,
+    /// Base system contracts to execute the batch with in the shadow VM, see `shadow_vm` module.
+    shadow_base_system_contracts: Option<BaseSystemContracts>,
     commands: mpsc::Receiver<Command>,
     vm_gas_limit: Option<u32>,
 }
@@ -273,6 +307,7 @@ pub(super) struct BatchExecutor {
 pub(crate) enum Command {
     ExecuteTx(Transaction, mpsc::SyncSender<TxExecutionResult>),
     RollbackLastTx(mpsc::SyncSender<()>),
+    StartNextMiniblock,
     FinishBatch(mpsc::SyncSender<VmBlockResult>),
 }
 
@@ -291,7 +326,30 @@ impl BatchExecutor {
                 .block_number
         );
 
-        let mut storage_view = StorageView::new(&secondary_storage);
+        let mut parallel_shadow_executor = self.parallel_execution_shadow_mode.then(|| {
+            ShadowParallelExecutor::new(
+                l1_batch_params.clone(),
+                self.validation_computational_gas_limit,
+            )
+        });
+
+        // The shadow VM has its own storage view, so that its changes don't affect the main VM.
+        let mut shadow_storage_view = StorageView::new(ForkStorage::new(&secondary_storage));
+        let mut shadow_oracle_tools =
+            vm::OracleTools::new(&mut shadow_storage_view as &mut dyn Storage);
+        let mut shadow_vm = self.shadow_base_system_contracts.as_ref().map(|contracts| {
+            init_vm(
+                &mut shadow_oracle_tools,
+                l1_batch_params.context_mode.clone(),
+                &l1_batch_params.properties,
+                TxExecutionMode::VerifyExecute,
+                contracts,
+            )
+        });
+
+        let fork_storage = ForkStorage::new(&secondary_storage);
+        let fork_errors = fork_storage.errors();
+        let mut storage_view = StorageView::new(fork_storage);
         let mut oracle_tools = vm::OracleTools::new(&mut storage_view as &mut dyn Storage);
 
         let mut vm = match self.vm_gas_limit {
@@ -316,14 +374,54 @@ impl BatchExecutor {
             match cmd {
                 Command::ExecuteTx(tx, resp) => {
                     let result = self.execute_tx(&tx, &mut vm);
+                    if let Some(err) = fork_errors.take() {
+                        // The transaction was executed on top of an incomplete state, so neither its result
+                        // nor the batch can be sealed. Stop the node rather than seal a batch built on it.
+                        panic!(
+                            "Transaction {:?} was executed without the forked state: {}",
+                            tx.hash(),
+                            err
+                        );
+                    }
+                    if let Some(parallel_shadow_executor) = &mut parallel_shadow_executor {
+                        parallel_shadow_executor.on_tx_executed(&tx, &result.tx_result);
+                    }
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        execute_tx_in_shadow(
+                            shadow_vm,
+                            &tx,
+                            &result,
+                            self.validation_computational_gas_limit,
+                        );
+                    }
                     resp.send(result).unwrap();
                 }
                 Command::RollbackLastTx(resp) => {
                     self.rollback_last_tx(&mut vm);
+                    if let Some(parallel_shadow_executor) = &mut parallel_shadow_executor {
+                        parallel_shadow_executor.on_tx_rolled_back();
+                    }
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        shadow_vm.rollback_to_latest_snapshot_popping();
+                    }
                     resp.send(()).unwrap();
                 }
+                Command::StartNextMiniblock => {
+                    if let Some(parallel_shadow_executor) = &mut parallel_shadow_executor {
+                        let storage = vm.state.storage.storage.get_ptr();
+                        let batch_changes = storage.borrow();
+                        parallel_shadow_executor.finish_miniblock(
+                            &secondary_storage,
+                            batch_changes.get_modified_storage_keys(),
+                        );
+                    }
+                }
                 Command::FinishBatch(resp) => {
-                    resp.send(self.finish_batch(&mut vm)).unwrap();
+                    let result = self.finish_batch(&mut vm);
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        finish_batch_in_shadow(shadow_vm, &result);
+                    }
+                    resp.send(result).unwrap();
                     return;
                 }
             }
@@ -0,0 +1,340 @@
+//! Prototype of the parallel execution of the miniblock transactions (Block-STM style).
+//!
+//! A batch is proven as a single sequential run of the bootloader, so the results of the parallel execution
+//! can't be used to seal it. Instead, the prototype runs in the shadow mode: once a miniblock is finished,
+//! its transactions are speculatively executed in parallel on top of the state at the miniblock start. The
+//! speculative results are then validated in the execution order: a result is valid if the transaction didn't
+//! access any slot written by the preceding transactions of the miniblock, otherwise the transaction has to be
+//! re-executed (the sequential result is used for it). Valid speculative results are cross-checked against
+//! the sequential execution, and the outcome is reported via metrics.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use vm::{
+    storage::Storage,
+    vm::VmTxExecutionResult,
+    vm_with_bootloader::{init_vm, push_transaction_to_bootloader_memory, TxExecutionMode},
+    TxRevertReason,
+};
+use zksync_state::{secondary_storage::SecondaryStateStorage, storage_view::StorageView};
+use zksync_types::{
+    tx::TxAccessSet, utils::storage_key_for_eth_balance, Address, StorageKey, StorageLog,
+    StorageValue, Transaction, ZkSyncReadStorage, BOOTLOADER_ADDRESS, H256, SYSTEM_CONTEXT_ADDRESS,
+};
+
+use crate::state_keeper::io::L1BatchParams;
+
+/// Storage with the state at the start of the miniblock: the state of the previous batch
+/// with the changes made by the preceding miniblocks of the current batch.
+#[derive(Debug)]
+struct MiniblockStartStorage<'a> {
+    base: &'a SecondaryStateStorage,
+    batch_changes: &'a HashMap<StorageKey, StorageValue>,
+}
+
+impl ZkSyncReadStorage for MiniblockStartStorage<'_> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        match self.batch_changes.get(key) {
+            Some(value) => *value,
+            None => self.base.read_value(key),
+        }
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        // Same as for the sequential execution, the writes are initial relative to the previous batch.
+        self.base.is_write_initial(key)
+    }
+
+    fn load_contract(&mut self, address: Address) -> Option<Vec<u8>> {
+        self.base.load_contract(address)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.base.load_factory_dep(hash)
+    }
+}
+
+/// Runs the parallel execution of each miniblock in the shadow mode.
+#[derive(Debug)]
+pub(super) struct ShadowParallelExecutor {
+    l1_batch_params: L1BatchParams,
+    validation_computational_gas_limit: u32,
+    /// Storage changes made by the batch before the current miniblock.
+    miniblock_start_changes: HashMap<StorageKey, StorageValue>,
+    /// Transactions executed in the current miniblock with their sequential results.
+    /// Rejected transactions are kept as well, so that the rollbacks can be matched.
+    txs: Vec<(Transaction, Option<VmTxExecutionResult>)>,
+}
+
+impl ShadowParallelExecutor {
+    pub(super) fn new(
+        l1_batch_params: L1BatchParams,
+        validation_computational_gas_limit: u32,
+    ) -> Self {
+        Self {
+            l1_batch_params,
+            validation_computational_gas_limit,
+            miniblock_start_changes: HashMap::new(),
+            txs: vec![],
+        }
+    }
+
+    pub(super) fn on_tx_executed(
+        &mut self,
+        tx: &Transaction,
+        result: &Result<VmTxExecutionResult, TxRevertReason>,
+    ) {
+        self.txs.push((tx.clone(), result.as_ref().ok().cloned()));
+    }
+
+    pub(super) fn on_tx_rolled_back(&mut self) {
+        self.txs.pop();
+    }
+
+    /// Executes the transactions of the finished miniblock in parallel and cross-checks the results.
+    /// `batch_changes` are the storage changes made by the batch including the finished miniblock.
+    pub(super) fn finish_miniblock(
+        &mut self,
+        secondary_storage: &SecondaryStateStorage,
+        batch_changes: &HashMap<StorageKey, StorageValue>,
+    ) {
+        let txs: Vec<_> = std::mem::take(&mut self.txs)
+            .into_iter()
+            .filter_map(|(tx, result)| Some((tx, result?)))
+            .collect();
+        if !txs.is_empty() {
+            self.check_miniblock(secondary_storage, &txs);
+        }
+        self.miniblock_start_changes = batch_changes.clone();
+    }
+
+    fn check_miniblock(
+        &self,
+        secondary_storage: &SecondaryStateStorage,
+        txs: &[(Transaction, VmTxExecutionResult)],
+    ) {
+        let started_at = Instant::now();
+        let speculative_results = self.execute_speculatively(secondary_storage, txs);
+        metrics::histogram!(
+            "server.state_keeper.parallel_execution.speculation_time",
+            started_at.elapsed()
+        );
+
+        let sequential_access_sets: Vec<_> = txs
+            .iter()
+            .enumerate()
+            .map(|(index, (tx, result))| access_set(tx.hash(), index, result))
+            .collect();
+        let speculative_access_sets: Vec<_> = txs
+            .iter()
+            .zip(&speculative_results)
+            .enumerate()
+            .map(|(index, ((tx, _), result))| {
+                result
+                    .as_ref()
+                    .ok()
+                    .map(|result| access_set(tx.hash(), index, result))
+            })
+            .collect();
+        let validity =
+            validate_speculative_execution(&speculative_access_sets, &sequential_access_sets);
+
+        let (mut valid_count, mut reexecuted_count, mut mismatch_count) = (0_u64, 0_u64, 0_u64);
+        for (index, is_valid) in validity.into_iter().enumerate() {
+            if !is_valid {
+                reexecuted_count += 1;
+                continue;
+            }
+            valid_count += 1;
+
+            // The speculative result is valid, so it must match the sequential execution. The written values
+            // aren't compared, since the refunds depend on the pubdata already published by the batch,
+            // which the speculative VM is unaware of.
+            let (tx, sequential_result) = &txs[index];
+            let speculative_result = speculative_results[index].as_ref().unwrap();
+            let matches = speculative_result.status == sequential_result.status
+                && speculative_access_sets[index].as_ref() == Some(&sequential_access_sets[index])
+                && speculative_result.result.logs.l2_to_l1_logs
+                    == sequential_result.result.logs.l2_to_l1_logs;
+            if !matches {
+                mismatch_count += 1;
+                vlog::error!(
+                    "Parallel execution of tx {:?} doesn't match the sequential one. \
+                     Speculative status: {:?}, sequential status: {:?}",
+                    tx.hash(),
+                    speculative_result.status,
+                    sequential_result.status
+                );
+            }
+        }
+
+        metrics::counter!("server.state_keeper.parallel_execution.txs", valid_count, "outcome" => "valid");
+        metrics::counter!("server.state_keeper.parallel_execution.txs", reexecuted_count, "outcome" => "reexecuted");
+        metrics::counter!("server.state_keeper.parallel_execution.txs", mismatch_count, "outcome" => "mismatch");
+        vlog::debug!(
+            "Parallel execution of {} txs: {} valid, {} to reexecute, {} mismatched",
+            txs.len(),
+            valid_count,
+            reexecuted_count,
+            mismatch_count
+        );
+    }
+
+    /// Executes each transaction in its own VM on top of the state at the miniblock start.
+    fn execute_speculatively(
+        &self,
+        secondary_storage: &SecondaryStateStorage,
+        txs: &[(Transaction, VmTxExecutionResult)],
+    ) -> Vec<Result<VmTxExecutionResult, TxRevertReason>> {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = (txs.len() + threads - 1) / threads;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = txs
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(tx, _)| self.execute_tx(secondary_storage, tx))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    }
+
+    fn execute_tx(
+        &self,
+        secondary_storage: &SecondaryStateStorage,
+        tx: &Transaction,
+    ) -> Result<VmTxExecutionResult, TxRevertReason> {
+        let mut storage_view = StorageView::new(MiniblockStartStorage {
+            base: secondary_storage,
+            batch_changes: &self.miniblock_start_changes,
+        });
+        let mut oracle_tools = vm::OracleTools::new(&mut storage_view as &mut dyn Storage);
+        let mut vm = init_vm(
+            &mut oracle_tools,
+            self.l1_batch_params.context_mode.clone(),
+            &self.l1_batch_params.properties,
+            TxExecutionMode::VerifyExecute,
+            &self.l1_batch_params.base_system_contracts,
+        );
+        push_transaction_to_bootloader_memory(&mut vm, tx, TxExecutionMode::VerifyExecute, None);
+        vm.execute_next_tx(self.validation_computational_gas_limit)
+    }
+}
+
+/// Slots updated by every transaction as a part of the batch processing: the system context
+/// and the balance of the bootloader collecting the fees. These updates commute, so they don't
+/// create dependencies between the transactions.
+fn is_batch_level_key(key: &StorageKey) -> bool {
+    *key.address() == SYSTEM_CONTEXT_ADDRESS
+        || *key == storage_key_for_eth_balance(&BOOTLOADER_ADDRESS)
+}
+
+fn access_set(tx_hash: H256, index: usize, result: &VmTxExecutionResult) -> TxAccessSet {
+    let logs: Vec<_> = result
+        .result
+        .logs
+        .storage_logs
+        .iter()
+        .map(StorageLog::from_log_query)
+        .filter(|log| !is_batch_level_key(&log.key))
+        .collect();
+    TxAccessSet::new(tx_hash, index as u32, &logs)
+}
+
+/// Validates the speculative results in the execution order. A speculative result is valid if the transaction
+/// didn't access the slots written by the preceding transactions; `None` stands for a transaction rejected
+/// during the speculative execution. Invalid transactions are considered re-executed, so their sequential
+/// writes are used to validate the following transactions.
+fn validate_speculative_execution(
+    speculative: &[Option<TxAccessSet>],
+    sequential: &[TxAccessSet],
+) -> Vec<bool> {
+    let mut written_keys = HashSet::new();
+    speculative
+        .iter()
+        .zip(sequential)
+        .map(|(speculative, sequential)| {
+            let valid_set = speculative.as_ref().filter(|set| {
+                set.read_keys
+                    .iter()
+                    .chain(&set.written_keys)
+                    .all(|key| !written_keys.contains(key))
+            });
+            let final_set = valid_set.unwrap_or(sequential);
+            written_keys.extend(final_set.written_keys.iter().copied());
+            valid_set.is_some()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_set(read_keys: &[u64], written_keys: &[u64]) -> TxAccessSet {
+        TxAccessSet {
+            tx_hash: H256::zero(),
+            tx_index_in_miniblock: 0,
+            read_keys: read_keys
+                .iter()
+                .map(|&key| H256::from_low_u64_be(key))
+                .collect(),
+            written_keys: written_keys
+                .iter()
+                .map(|&key| H256::from_low_u64_be(key))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn speculative_execution_validation() {
+        let sequential = vec![
+            access_set(&[1], &[2]),
+            access_set(&[3], &[4]),
+            access_set(&[2], &[5]),
+            access_set(&[6], &[5]),
+            access_set(&[7], &[8]),
+        ];
+        let speculative = vec![
+            Some(sequential[0].clone()),
+            Some(sequential[1].clone()),
+            // Reads the slot written by the first tx.
+            Some(sequential[2].clone()),
+            // Overwrites the slot written by the re-executed tx.
+            Some(sequential[3].clone()),
+            // Rejected in the speculative execution.
+            None,
+        ];
+
+        let validity = validate_speculative_execution(&speculative, &sequential);
+        assert_eq!(validity, [true, true, false, false, false]);
+    }
+
+    #[test]
+    fn reexecuted_tx_writes_are_taken_from_sequential_execution() {
+        let sequential = vec![
+            access_set(&[], &[1]),
+            access_set(&[2], &[3]),
+            access_set(&[4], &[]),
+        ];
+        // The speculative execution of the second tx depends on the first one and reads another slot,
+        // so only the sequential writes of the second tx are relevant for the third one.
+        let speculative = vec![
+            Some(sequential[0].clone()),
+            Some(access_set(&[1], &[4])),
+            Some(sequential[2].clone()),
+        ];
+
+        let validity = validate_speculative_execution(&speculative, &sequential);
+        assert_eq!(validity, [true, false, true]);
+    }
+}
@@ -0,0 +1,165 @@
+//! Shadow execution of the batches with the new base system contracts (bootloader and default account).
+//!
+//! Before a new version of the base system contracts is activated, the batch executor can run every batch
+//! with it in a second VM. The shadow VM receives the same transactions and rollbacks as the main one, and
+//! the divergences in the outcome, storage logs and gas are logged and reported via metrics. Nothing from
+//! the shadow VM is persisted.
+
+use vm::{
+    vm::VmTxExecutionResult,
+    vm_with_bootloader::{
+        push_transaction_to_bootloader_memory, BootloaderJobType, TxExecutionMode,
+    },
+    TxRevertReason, VmBlockResult, VmInstance,
+};
+use zksync_types::{StorageLog, StorageLogQuery, Transaction};
+
+use super::TxExecutionResult;
+
+/// Part of the execution outcome which diverged between the main and the shadow VMs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Divergence {
+    Outcome,
+    StorageLogs,
+    Gas,
+}
+
+impl Divergence {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Outcome => "outcome",
+            Self::StorageLogs => "storage_logs",
+            Self::Gas => "gas",
+        }
+    }
+}
+
+/// Executes the transaction in the shadow VM and reports the divergences from the main execution.
+pub(super) fn execute_tx_in_shadow(
+    shadow_vm: &mut VmInstance,
+    tx: &Transaction,
+    main_result: &TxExecutionResult,
+    validation_computational_gas_limit: u32,
+) {
+    // The snapshot is saved for every transaction to mirror the rollbacks of the main VM.
+    shadow_vm.save_current_vm_as_snapshot();
+    if matches!(main_result.tx_result, Err(TxRevertReason::TooBigGasLimit)) {
+        // The transaction wasn't executed by the main VM either.
+        return;
+    }
+
+    let gas_consumed_before = shadow_vm.gas_consumed();
+    push_transaction_to_bootloader_memory(
+        shadow_vm,
+        tx,
+        TxExecutionMode::VerifyExecute,
+        Some(main_result.compressed_bytecodes.clone()),
+    );
+    let shadow_result = shadow_vm.execute_next_tx(validation_computational_gas_limit);
+    let shadow_gas_used = shadow_vm.gas_consumed().saturating_sub(gas_consumed_before);
+
+    let main_gas_used = main_result
+        .tx_metrics
+        .as_ref()
+        .map(|metrics| metrics.execution_metrics.gas_used);
+    let divergences = tx_divergences(
+        &main_result.tx_result,
+        main_gas_used,
+        &shadow_result,
+        shadow_gas_used as usize,
+    );
+    report_divergences(&divergences, "tx");
+    if !divergences.is_empty() {
+        vlog::warn!(
+            "Shadow VM diverged on tx {:?}: {:?}. Main result: {:?}, shadow result: {:?}",
+            tx.hash(),
+            divergences,
+            main_result.tx_result,
+            shadow_result
+        );
+    }
+}
+
+/// Finishes the batch in the shadow VM and reports the divergences from the main execution.
+pub(super) fn finish_batch_in_shadow(shadow_vm: &mut VmInstance, main_result: &VmBlockResult) {
+    let shadow_result = shadow_vm.execute_till_block_end(BootloaderJobType::BlockPostprocessing);
+
+    let mut divergences = vec![];
+    if storage_logs(&main_result.full_result.storage_log_queries)
+        != storage_logs(&shadow_result.full_result.storage_log_queries)
+    {
+        divergences.push(Divergence::StorageLogs);
+    }
+    if main_result.full_result.gas_used != shadow_result.full_result.gas_used {
+        divergences.push(Divergence::Gas);
+    }
+    report_divergences(&divergences, "batch");
+    if !divergences.is_empty() {
+        vlog::warn!(
+            "Shadow VM diverged on batch postprocessing: {:?}. Gas used by the main VM: {}, by the shadow VM: {}",
+            divergences,
+            main_result.full_result.gas_used,
+            shadow_result.full_result.gas_used
+        );
+    }
+}
+
+fn report_divergences(divergences: &[Divergence], stage: &'static str) {
+    metrics::increment_counter!("server.state_keeper.shadow_vm.executions", "stage" => stage);
+    for divergence in divergences {
+        metrics::increment_counter!(
+            "server.state_keeper.shadow_vm.divergences",
+            "stage" => stage,
+            "kind" => divergence.as_str()
+        );
+    }
+}
+
+/// VM timestamps differ between the VMs if the bootloaders differ, so they are dropped from the comparison.
+fn storage_logs(log_queries: &[StorageLogQuery]) -> Vec<StorageLog> {
+    log_queries.iter().map(StorageLog::from_log_query).collect()
+}
+
+fn tx_divergences(
+    main_result: &Result<VmTxExecutionResult, TxRevertReason>,
+    main_gas_used: Option<usize>,
+    shadow_result: &Result<VmTxExecutionResult, TxRevertReason>,
+    shadow_gas_used: usize,
+) -> Vec<Divergence> {
+    let (main, shadow) = match (main_result, shadow_result) {
+        (Ok(main), Ok(shadow)) => (main, shadow),
+        (Err(main_err), Err(shadow_err)) if main_err == shadow_err => return vec![],
+        _ => return vec![Divergence::Outcome],
+    };
+
+    let mut divergences = vec![];
+    if main.status != shadow.status {
+        divergences.push(Divergence::Outcome);
+    }
+    if storage_logs(&main.result.logs.storage_logs)
+        != storage_logs(&shadow.result.logs.storage_logs)
+    {
+        divergences.push(Divergence::StorageLogs);
+    }
+    if main_gas_used.map_or(false, |gas_used| gas_used != shadow_gas_used) {
+        divergences.push(Divergence::Gas);
+    }
+    divergences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejections_are_compared_by_reason() {
+        let rejected = Err(TxRevertReason::TooBigGasLimit);
+        assert!(tx_divergences(&rejected, None, &rejected, 0).is_empty());
+
+        let rejected_otherwise = Err(TxRevertReason::BootloaderOutOfGas);
+        assert_eq!(
+            tx_divergences(&rejected, None, &rejected_otherwise, 0),
+            [Divergence::Outcome]
+        );
+    }
+}
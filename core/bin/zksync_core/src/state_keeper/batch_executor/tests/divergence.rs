@@ -0,0 +1,116 @@
+//! Randomized test checking that the API sandbox and the state keeper execute transactions identically.
+//! Any difference in the environment of the VM (fee input, block context, storage view) makes the fee
+//! estimations diverge from the actual execution, so the outcomes of both paths are compared for the
+//! random transactions and fee inputs. The sandbox is set up as for the fee estimation (the estimation
+//! bootloader in the `EstimateFee` mode), and the batch executor as in the state keeper.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::tester::{is_block_info_log, Account, Tester};
+use db_test_macro::db_test;
+use vm::{vm::VmTxExecutionResult, TxRevertReason};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    event::VmEvent, fee::RefundBreakdown, l2_to_l1_log::L2ToL1Log,
+    tx::tx_execution_info::TxExecutionStatus, PriorityOpId, StorageLog, Transaction,
+    SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+};
+
+/// Number of the transactions executed by the test.
+const TXS_COUNT: usize = 20;
+/// Environment variable to reproduce the failed run with.
+const SEED_ENV_VAR: &str = "DIVERGENCE_TEST_SEED";
+
+/// Part of the execution result that must not depend on the way the VM was set up.
+#[derive(Debug, PartialEq)]
+struct ComparableOutcome {
+    status: TxExecutionStatus,
+    storage_logs: Vec<StorageLog>,
+    events: Vec<VmEvent>,
+    l2_to_l1_logs: Vec<L2ToL1Log>,
+    gas_refunded: u32,
+    operator_suggested_refund: u32,
+    refund_breakdown: RefundBreakdown,
+}
+
+impl ComparableOutcome {
+    fn new(result: Result<VmTxExecutionResult, TxRevertReason>) -> Result<Self, TxRevertReason> {
+        let result = result?;
+        let logs = result.result.logs;
+        // VM timestamps are dropped by the conversion to `StorageLog`.
+        let storage_logs = logs
+            .storage_logs
+            .iter()
+            .map(StorageLog::from_log_query)
+            .filter(|log| !is_block_info_log(log))
+            .collect();
+
+        Ok(Self {
+            status: result.status,
+            storage_logs,
+            events: logs.events,
+            l2_to_l1_logs: logs.l2_to_l1_logs,
+            gas_refunded: result.gas_refunded,
+            operator_suggested_refund: result.operator_suggested_refund,
+            refund_breakdown: result.refund_breakdown,
+        })
+    }
+}
+
+/// Generates a random transaction from a fresh account, so that the transactions don't depend on each other.
+fn random_tx(rng: &mut StdRng, account: &mut Account, serial_id: PriorityOpId) -> Transaction {
+    match rng.gen_range(0..3) {
+        0 => account.execute_with_gas_limit(rng.gen_range(100_000..=2_000_000)),
+        1 => account.l1_execute(serial_id),
+        _ => account.deploy_loadnext_tx().0,
+    }
+}
+
+/// Picks a random fee input. The gas price per pubdata byte must fit into the default limit of the test
+/// transactions, so the L1 gas price is bounded relative to the L2 one.
+fn random_fee_input(rng: &mut StdRng) -> (u64, u64) {
+    let fair_l2_gas_price = rng.gen_range(1..=SYSTEM_CONTEXT_MINIMAL_BASE_FEE);
+    let l1_gas_price = rng.gen_range(1..=fair_l2_gas_price * 5);
+    (l1_gas_price, fair_l2_gas_price)
+}
+
+/// Executes random transactions both in the sandbox and in the batch executor, each under its own random
+/// fee input, and checks that the outcomes are identical. The fee input is fixed for the batch, so each
+/// transaction is executed in a new batch that is discarded afterwards, since the sandbox always executes
+/// on top of the sealed state.
+#[db_test]
+async fn sandbox_and_state_keeper_do_not_diverge(connection_pool: ConnectionPool) {
+    let seed = std::env::var(SEED_ENV_VAR)
+        .map(|seed| seed.parse().expect("invalid seed"))
+        .unwrap_or_else(|_| rand::thread_rng().gen());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut accounts: Vec<_> = (0..TXS_COUNT).map(|_| Account::random()).collect();
+    let addresses: Vec<_> = accounts.iter().map(Account::address).collect();
+
+    let tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    // The state keeper storage is loaded when the executor is created, so all accounts are funded in advance.
+    tester.fund(&addresses);
+
+    for (i, account) in accounts.iter_mut().enumerate() {
+        let tx = random_tx(&mut rng, account, PriorityOpId(i as u64 + 1));
+        let tx_hash = tx.hash();
+        let (l1_gas_price, fair_l2_gas_price) = random_fee_input(&mut rng);
+        let executor = tester.create_batch_executor_with_fee_input(l1_gas_price, fair_l2_gas_price);
+
+        let sandbox_outcome = ComparableOutcome::new(tester.execute_in_sandbox(
+            tx.clone(),
+            l1_gas_price,
+            fair_l2_gas_price,
+        ));
+        let state_keeper_outcome = ComparableOutcome::new(executor.execute_tx(tx).tx_result);
+        assert_eq!(
+            sandbox_outcome, state_keeper_outcome,
+            "Execution of tx {:?} diverged (seed: {}, l1_gas_price: {}, fair_l2_gas_price: {}); \
+             rerun with {}={} to reproduce",
+            tx_hash, seed, l1_gas_price, fair_l2_gas_price, SEED_ENV_VAR, seed
+        );
+        executor.finish_batch();
+    }
+}
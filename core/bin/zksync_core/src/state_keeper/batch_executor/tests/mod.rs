@@ -1,4 +1,5 @@
 use crate::state_keeper::batch_executor::tests::tester::TestConfig;
+use crate::state_keeper::tests::BASE_SYSTEM_CONTRACTS;
 
 use self::tester::{Account, Tester};
 use assert_matches::assert_matches;
@@ -6,6 +7,8 @@ use db_test_macro::db_test;
 use vm::TxRevertReason;
 use zksync_types::{tx::tx_execution_info::TxExecutionStatus, PriorityOpId};
 
+mod divergence;
+mod replay;
 mod tester;
 
 use super::TxExecutionResult;
@@ -112,6 +115,71 @@ async fn rollback(connection_pool: ConnectionPool) {
     executor.finish_batch();
 }
 
+/// Checks that the parallel execution in the shadow mode doesn't affect the sequential one,
+/// including the rollbacks and the dependent transactions in the same miniblock.
+#[db_test]
+async fn parallel_execution_shadow_mode(connection_pool: ConnectionPool) {
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+
+    let tester = Tester::with_config(
+        connection_pool,
+        TestConfig {
+            parallel_execution_shadow_mode: true,
+            ..TestConfig::new()
+        },
+    );
+    tester.genesis().await;
+    tester.fund(&[alice.address(), bob.address()]);
+    let executor = tester.create_batch_executor();
+
+    assert_executed(executor.execute_tx(alice.execute()));
+    assert_executed(executor.execute_tx(bob.execute()));
+    // Depends on the previous transaction of Alice.
+    assert_executed(executor.execute_tx(alice.execute()));
+    executor.start_next_miniblock();
+
+    let tx = bob.execute();
+    assert_executed(executor.execute_tx(tx.clone()));
+    executor.rollback_last_tx();
+    assert_executed(executor.execute_tx(tx));
+    executor.start_next_miniblock();
+
+    executor.finish_batch();
+}
+
+/// Checks that the shadow VM mirrors the transactions and the rollbacks of the main one
+/// without affecting the execution results.
+#[db_test]
+async fn shadow_vm(connection_pool: ConnectionPool) {
+    let mut alice = Account::random();
+
+    let tester = Tester::with_config(
+        connection_pool,
+        TestConfig {
+            shadow_base_system_contracts: Some(BASE_SYSTEM_CONTRACTS.clone()),
+            ..TestConfig::new()
+        },
+    );
+    tester.genesis().await;
+    tester.fund(&[alice.address()]);
+    let executor = tester.create_batch_executor();
+
+    let tx = alice.execute();
+    let res_old = executor.execute_tx(tx.clone());
+    assert_executed(res_old.clone());
+    executor.rollback_last_tx();
+
+    let res_new = executor.execute_tx(tx);
+    assert_eq!(res_old, res_new);
+    assert_rejected(executor.execute_tx(alice.execute_with_gas_limit(u32::MAX)));
+    executor.rollback_last_tx();
+    alice.nonce -= 1; // Reset the nonce.
+    assert_executed(executor.execute_tx(alice.execute()));
+
+    executor.finish_batch();
+}
+
 /// Checks that incorrect transactions are marked as rejected.
 #[db_test]
 async fn reject_tx(connection_pool: ConnectionPool) {
@@ -282,6 +350,8 @@ async fn bootloader_out_of_gas_for_any_tx(connection_pool: ConnectionPool) {
             vm_gas_limit: Some(10),
             max_allowed_tx_gas_limit: u32::MAX,
             validation_computational_gas_limit: u32::MAX,
+            parallel_execution_shadow_mode: false,
+            shadow_base_system_contracts: None,
         },
     );
 
@@ -321,6 +391,8 @@ async fn bootloader_tip_out_of_gas(connection_pool: ConnectionPool) {
         vm_gas_limit: Some(vm_block_res.full_result.gas_used - 10),
         max_allowed_tx_gas_limit: u32::MAX,
         validation_computational_gas_limit: u32::MAX,
+        parallel_execution_shadow_mode: false,
+        shadow_base_system_contracts: None,
     });
 
     let second_executor = tester.create_batch_executor();
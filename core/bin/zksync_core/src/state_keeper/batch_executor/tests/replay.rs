@@ -0,0 +1,125 @@
+//! Test checking that the transactions replayed by the `debug` namespace of the API are executed the same way
+//! as by the state keeper. The replay re-executes the whole miniblock on top of the state preceding it,
+//! so each transaction must observe the changes made by the previous ones.
+
+use super::tester::{is_block_info_log, Account, Tester};
+use crate::api_server::tracers::{DebugTracer, StructLogLimit, StructLogTracer};
+use db_test_macro::db_test;
+use vm::{oracles::tracer::TracerPlugin, vm::VmTxExecutionResult, VmExecutionResult};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    event::VmEvent, l2_to_l1_log::L2ToL1Log, tx::tx_execution_info::TxExecutionStatus,
+    vm_trace::DebugTraceResult, StorageLog, SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+};
+
+/// Part of the execution result reported by the replay.
+#[derive(Debug, PartialEq)]
+struct ReplayedOutcome {
+    storage_logs: Vec<StorageLog>,
+    events: Vec<VmEvent>,
+    l2_to_l1_logs: Vec<L2ToL1Log>,
+}
+
+impl ReplayedOutcome {
+    fn from_execution(result: VmTxExecutionResult) -> Self {
+        assert_eq!(result.status, TxExecutionStatus::Success);
+        let logs = result.result.logs;
+        Self {
+            storage_logs: logs
+                .storage_logs
+                .iter()
+                .map(StorageLog::from_log_query)
+                .filter(|log| !is_block_info_log(log))
+                .collect(),
+            events: logs.events,
+            l2_to_l1_logs: logs.l2_to_l1_logs,
+        }
+    }
+
+    fn from_replay(result: &VmExecutionResult) -> Self {
+        Self {
+            storage_logs: result
+                .storage_log_queries
+                .iter()
+                .map(StorageLog::from_log_query)
+                .filter(|log| !is_block_info_log(log))
+                .collect(),
+            events: result.events.clone(),
+            l2_to_l1_logs: result.l2_to_l1_logs.clone(),
+        }
+    }
+}
+
+/// Replays a miniblock of the dependent transactions with the provided fee input and checks that the outcomes
+/// and the traces match the execution by the batch executor.
+async fn test_replay(connection_pool: ConnectionPool, l1_gas_price: u64, fair_l2_gas_price: u64) {
+    let mut alice = Account::random();
+
+    let tester = Tester::new(connection_pool);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]);
+    // The transactions are sent by the same account, so each one depends on the previous ones.
+    let txs = vec![
+        alice.execute(),
+        alice.deploy_loadnext_tx().0,
+        alice.execute(),
+    ];
+
+    let executor = tester.create_batch_executor_with_fee_input(l1_gas_price, fair_l2_gas_price);
+    let expected_outcomes: Vec<_> = txs
+        .iter()
+        .map(|tx| {
+            let result = executor.execute_tx(tx.clone()).tx_result;
+            ReplayedOutcome::from_execution(result.expect("transaction was rejected"))
+        })
+        .collect();
+    executor.finish_batch();
+
+    // Trace all the transactions but the first one.
+    let mut tracers: Vec<_> = txs[1..]
+        .iter()
+        .map(|tx| StructLogTracer::new(tx, StructLogLimit::default()))
+        .collect();
+    let mut plugins: Vec<_> = tracers
+        .iter_mut()
+        .map(|tracer| tracer as &mut dyn TracerPlugin)
+        .collect();
+    let results = tester.replay_in_sandbox(txs, l1_gas_price, fair_l2_gas_price, &mut plugins);
+    drop(plugins);
+
+    assert_eq!(results.len(), 2);
+    for ((result, expected_outcome), tracer) in results
+        .into_iter()
+        .zip(&expected_outcomes[1..])
+        .zip(tracers)
+    {
+        let result = result.expect("replayed transaction was rejected");
+        assert_eq!(ReplayedOutcome::from_replay(&result), *expected_outcome);
+
+        let trace = Box::new(tracer).into_trace(Ok(result)).unwrap();
+        match trace {
+            DebugTraceResult::StructLogTrace(trace) => {
+                assert!(!trace.failed);
+                assert!(!trace.struct_logs.is_empty());
+                assert_eq!(trace.struct_logs[0].depth, 1);
+            }
+            other => panic!("unexpected trace: {:?}", other),
+        }
+    }
+}
+
+#[db_test]
+async fn replayed_transactions_match_execution(connection_pool: ConnectionPool) {
+    let fair_l2_gas_price = SYSTEM_CONTEXT_MINIMAL_BASE_FEE;
+    test_replay(connection_pool, fair_l2_gas_price * 2, fair_l2_gas_price).await;
+}
+
+/// The gas price per pubdata byte is derived from the L1 gas price, so a different ratio of the prices
+/// checks that it's propagated to the replay as well.
+#[db_test]
+async fn replayed_transactions_match_execution_with_low_l2_gas_price(
+    connection_pool: ConnectionPool,
+) {
+    let fair_l2_gas_price = SYSTEM_CONTEXT_MINIMAL_BASE_FEE / 10;
+    test_replay(connection_pool, fair_l2_gas_price * 5, fair_l2_gas_price).await;
+}
@@ -1,29 +1,42 @@
 //! Testing harness for the batch executor.
 //! Contains helper functionality to initialize test context and perform tests without too much boilerplate.
 
+use crate::api_server::execution_sandbox::{
+    apply_vm_in_sandbox, replay_miniblock_transactions, SandboxExecutionError, SandboxFeeInput,
+};
 use crate::genesis::chain_schema_genesis;
 use crate::state_keeper::{
     batch_executor::BatchExecutorHandle,
-    io::L1BatchParams,
+    io::{common::l1_batch_params, L1BatchParams},
     tests::{default_block_properties, BASE_SYSTEM_CONTRACTS},
 };
 use tempfile::TempDir;
 use vm::{
+    oracles::tracer::TracerPlugin,
     test_utils::{
         get_create_zksync_address, get_deploy_tx, mock_loadnext_gas_burn_call,
         mock_loadnext_test_call,
     },
-    vm_with_bootloader::{BlockContext, BlockContextMode, DerivedBlockContext},
+    vm::VmTxExecutionResult,
+    vm_with_bootloader::{
+        derive_base_fee_and_gas_per_pubdata, push_transaction_to_bootloader_memory, BlockContext,
+        BlockContextMode, DerivedBlockContext, TxExecutionMode,
+    },
     zk_evm::{
         block_properties::BlockProperties,
         zkevm_opcode_defs::system_params::INITIAL_STORAGE_WRITE_PUBDATA_BYTES,
     },
+    TxRevertReason, VmExecutionResult,
 };
 use zksync_config::ZkSyncConfig;
-use zksync_contracts::{get_loadnext_contract, TestContract};
+use zksync_contracts::{
+    get_loadnext_contract, BaseSystemContracts, TestContract, ESTIMATE_FEE_BLOCK_CODE,
+};
 use zksync_dal::ConnectionPool;
 use zksync_storage::{db::Database, RocksDB};
 use zksync_types::{
+    api,
+    block::MiniblockHeader,
     ethabi::{encode, Token},
     fee::Fee,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
@@ -31,9 +44,13 @@ use zksync_types::{
     utils::storage_key_for_standard_token_balance,
     AccountTreeId, Address, Execute, L1BatchNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
     Nonce, PackedEthSignature, PriorityOpId, StorageLog, Transaction, H256, L2_ETH_TOKEN_ADDRESS,
-    SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
+    SYSTEM_CONTEXT_ADDRESS, SYSTEM_CONTEXT_BLOCK_INFO_POSITION, SYSTEM_CONTEXT_MINIMAL_BASE_FEE,
+    U256,
+};
+use zksync_utils::{
+    h256_to_u256, test_utils::LoadnextContractExecutionParams, time::seconds_since_epoch,
+    u256_to_h256,
 };
-use zksync_utils::{test_utils::LoadnextContractExecutionParams, u256_to_h256};
 
 const DEFAULT_GAS_PER_PUBDATA: u32 = 100;
 const CHAIN_ID: L2ChainId = L2ChainId(270);
@@ -46,6 +63,8 @@ pub(super) struct TestConfig {
     pub(super) vm_gas_limit: Option<u32>,
     pub(super) max_allowed_tx_gas_limit: u32,
     pub(super) validation_computational_gas_limit: u32,
+    pub(super) parallel_execution_shadow_mode: bool,
+    pub(super) shadow_base_system_contracts: Option<BaseSystemContracts>,
 }
 
 impl TestConfig {
@@ -61,6 +80,8 @@ impl TestConfig {
                 .chain
                 .state_keeper
                 .validation_computational_gas_limit,
+            parallel_execution_shadow_mode: false,
+            shadow_base_system_contracts: None,
         }
     }
 }
@@ -98,7 +119,35 @@ impl Tester {
     pub(super) fn create_batch_executor(&self) -> BatchExecutorHandle {
         // Not really important for the batch executor - it operates over a single batch.
         let (block_context, block_properties) = self.batch_params(L1BatchNumber(1), 100);
+        self.create_batch_executor_with_params(L1BatchParams {
+            context_mode: block_context,
+            properties: block_properties,
+            base_system_contracts: BASE_SYSTEM_CONTRACTS.clone(),
+        })
+    }
 
+    /// Creates a batch executor instance with the batch params derived the same way the state keeper does it
+    /// for the provided fee input.
+    pub(super) fn create_batch_executor_with_fee_input(
+        &self,
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+    ) -> BatchExecutorHandle {
+        self.create_batch_executor_with_params(l1_batch_params(
+            L1BatchNumber(1),
+            self.fee_account,
+            seconds_since_epoch(),
+            U256::zero(),
+            l1_gas_price,
+            fair_l2_gas_price,
+            BASE_SYSTEM_CONTRACTS.clone(),
+        ))
+    }
+
+    fn create_batch_executor_with_params(
+        &self,
+        l1_batch_params: L1BatchParams,
+    ) -> BatchExecutorHandle {
         let secondary_storage = self
             .pool
             .access_storage_blocking()
@@ -115,16 +164,89 @@ impl Tester {
             self.config.reexecute_each_tx,
             self.config.max_allowed_tx_gas_limit.into(),
             self.config.validation_computational_gas_limit,
+            self.config.parallel_execution_shadow_mode,
+            self.config.shadow_base_system_contracts.clone(),
             secondary_storage,
-            L1BatchParams {
-                context_mode: block_context,
-                properties: block_properties,
-                base_system_contracts: BASE_SYSTEM_CONTRACTS.clone(),
-            },
+            l1_batch_params,
             self.config.vm_gas_limit,
         )
     }
 
+    /// Executes the transaction in the API sandbox on top of the sealed state the same way the fee estimation
+    /// does it for the pending block with the provided fee input, i.e. with the fee estimation bootloader.
+    pub(super) fn execute_in_sandbox(
+        &self,
+        tx: Transaction,
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+    ) -> Result<VmTxExecutionResult, TxRevertReason> {
+        let validation_computational_gas_limit = self.config.validation_computational_gas_limit;
+        let mut base_system_contracts = BASE_SYSTEM_CONTRACTS.clone();
+        base_system_contracts.bootloader = ESTIMATE_FEE_BLOCK_CODE.clone();
+        apply_vm_in_sandbox(
+            self.pool.access_storage_blocking(),
+            tx,
+            TxExecutionMode::EstimateFee,
+            &base_system_contracts,
+            AccountTreeId::new(self.fee_account),
+            api::BlockId::Number(api::BlockNumber::Pending),
+            MiniblockNumber(0),
+            None,
+            None,
+            U256::zero(),
+            SandboxFeeInput {
+                l1_gas_price,
+                fair_l2_gas_price,
+                enforced_base_fee: None,
+                enforce_for_historical_blocks: false,
+            },
+            |vm, tx| {
+                push_transaction_to_bootloader_memory(vm, &tx, TxExecutionMode::EstimateFee, None);
+                vm.execute_next_tx(validation_computational_gas_limit)
+            },
+        )
+        .expect("the sandbox doesn't use a fork source in tests")
+    }
+
+    /// Replays the transactions as the miniblock following the genesis one with the provided fee input,
+    /// the same way the `debug` namespace of the API does it. The last transactions are traced by `tracers`.
+    pub(super) fn replay_in_sandbox(
+        &self,
+        txs: Vec<Transaction>,
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+        tracers: &mut [&mut dyn TracerPlugin],
+    ) -> Vec<Result<VmExecutionResult, SandboxExecutionError>> {
+        let miniblock_number = MiniblockNumber(1);
+        let (base_fee_per_gas, _) =
+            derive_base_fee_and_gas_per_pubdata(l1_gas_price, fair_l2_gas_price);
+        self.pool
+            .access_storage_blocking()
+            .blocks_dal()
+            .insert_miniblock(MiniblockHeader {
+                number: miniblock_number,
+                timestamp: seconds_since_epoch(),
+                hash: H256::zero(),
+                l1_tx_count: 0,
+                l2_tx_count: txs.len() as u16,
+                base_fee_per_gas,
+                l1_gas_price,
+                l2_fair_gas_price: fair_l2_gas_price,
+                base_system_contracts_hashes: BASE_SYSTEM_CONTRACTS.hashes(),
+                logs_bloom: Default::default(),
+                fee_account_address: self.fee_account,
+            });
+
+        replay_miniblock_transactions(
+            &self.pool,
+            miniblock_number,
+            txs,
+            self.config.validation_computational_gas_limit,
+            tracers,
+        )
+        .expect("failed to replay transactions")
+    }
+
     /// Creates test batch params that can be fed into the VM.
     fn batch_params(
         &self,
@@ -354,6 +476,16 @@ impl Account {
     }
 }
 
+/// Checks whether the log touches the current block info or the block hashes in the system context.
+/// These are initialized differently by design: the sandbox overrides the current block with the wall clock
+/// timestamp, while the state keeper starts a new one. The block hashes mapping is the only one placed
+/// after the block info, so its (hashed) slots are the only ones exceeding the block info position.
+/// The rest of the system context (e.g., the gas price and the tx origin) must be the same.
+pub(super) fn is_block_info_log(log: &StorageLog) -> bool {
+    *log.key.address() == SYSTEM_CONTEXT_ADDRESS
+        && h256_to_u256(*log.key.key()) >= h256_to_u256(SYSTEM_CONTEXT_BLOCK_INFO_POSITION)
+}
+
 fn fee(gas_limit: u32) -> Fee {
     Fee {
         gas_limit: U256::from(gas_limit),
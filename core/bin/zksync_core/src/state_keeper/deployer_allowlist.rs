@@ -0,0 +1,140 @@
+//! Permissioned deployment mode: only the transactions initiated by the allowlisted accounts are allowed
+//! to deploy contracts. The policy is enforced by the state keeper on the deployments the executed transaction
+//! has actually made (including the ones made by factory contracts or account abstractions), while the tx sender
+//! checks direct calls to the contract deployer, so that the users get the error immediately.
+
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::event::{VmEvent, DEPLOY_EVENT_SIGNATURE};
+use zksync_types::{Address, CONTRACT_DEPLOYER_ADDRESS};
+use zksync_utils::h256_to_account_address;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("account {0:?} is not allowed to deploy contracts")]
+pub struct DeploymentNotAllowed(pub Address);
+
+/// Allowlist of the deployers. The allowlist is reloaded from the database once it's older than the TTL,
+/// so that the changes made via the admin API are picked up without a restart.
+#[derive(Debug, Clone)]
+pub struct DeployerAllowlist {
+    pool: ConnectionPool,
+    cache: Arc<RwLock<Option<(Arc<HashSet<Address>>, Instant)>>>,
+}
+
+impl DeployerAllowlist {
+    const TTL: Duration = Duration::from_secs(5);
+
+    /// Returns `None` if the permissioned deployment mode is disabled.
+    pub fn new(config: &StateKeeperConfig, pool: ConnectionPool) -> Option<Self> {
+        config.deployer_allowlist_enabled.then(|| Self {
+            pool,
+            cache: Arc::default(),
+        })
+    }
+
+    /// Checks an L2 transaction. Calls to the contracts other than the contract deployer are always allowed.
+    pub fn check(
+        &self,
+        initiator: Address,
+        contract_address: Address,
+    ) -> Result<(), DeploymentNotAllowed> {
+        if contract_address != CONTRACT_DEPLOYER_ADDRESS || self.deployers().contains(&initiator) {
+            Ok(())
+        } else {
+            Err(DeploymentNotAllowed(initiator))
+        }
+    }
+
+    /// Checks the events emitted by an executed transaction: if the transaction has deployed any contracts,
+    /// its initiator must be allowlisted.
+    pub fn check_deployments(
+        &self,
+        initiator: Address,
+        events: &[VmEvent],
+    ) -> Result<(), DeploymentNotAllowed> {
+        if deployed_contracts(events).next().is_none() || self.deployers().contains(&initiator) {
+            Ok(())
+        } else {
+            Err(DeploymentNotAllowed(initiator))
+        }
+    }
+
+    fn deployers(&self) -> Arc<HashSet<Address>> {
+        if let Some((deployers, loaded_at)) = &*self.cache.read().unwrap() {
+            if loaded_at.elapsed() < Self::TTL {
+                return deployers.clone();
+            }
+        }
+
+        let deployers: Arc<HashSet<_>> = Arc::new(
+            self.pool
+                .access_storage_blocking()
+                .deployer_allowlist_dal()
+                .get_allowlisted_deployers()
+                .into_iter()
+                .collect(),
+        );
+        *self.cache.write().unwrap() = Some((deployers.clone(), Instant::now()));
+        deployers
+    }
+}
+
+/// Returns the addresses of the contracts deployed according to the events of the contract deployer.
+fn deployed_contracts(events: &[VmEvent]) -> impl Iterator<Item = Address> + '_ {
+    events
+        .iter()
+        .filter(|event| {
+            event.address == CONTRACT_DEPLOYER_ADDRESS
+                && event.indexed_topics.len() == 4
+                && event.indexed_topics[0] == *DEPLOY_EVENT_SIGNATURE
+        })
+        .map(|event| h256_to_account_address(&event.indexed_topics[3]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::H256;
+    use zksync_utils::address_to_h256;
+
+    fn deploy_event(deployer: Address, contract: Address) -> VmEvent {
+        VmEvent {
+            address: CONTRACT_DEPLOYER_ADDRESS,
+            indexed_topics: vec![
+                *DEPLOY_EVENT_SIGNATURE,
+                address_to_h256(&deployer),
+                H256::random(),
+                address_to_h256(&contract),
+            ],
+            ..VmEvent::default()
+        }
+    }
+
+    #[test]
+    fn deployments_made_by_factories_are_detected() {
+        let factory = Address::random();
+        let contract = Address::random();
+        let events = vec![
+            VmEvent {
+                address: factory,
+                indexed_topics: vec![H256::random()],
+                ..VmEvent::default()
+            },
+            deploy_event(factory, contract),
+        ];
+        assert_eq!(deployed_contracts(&events).collect::<Vec<_>>(), [contract]);
+
+        // Events with the same signature emitted by other contracts don't count.
+        let fake_event = VmEvent {
+            address: factory,
+            ..deploy_event(factory, contract)
+        };
+        assert_eq!(deployed_contracts(&[fake_event]).count(), 0);
+    }
+}
@@ -4,15 +4,22 @@ use itertools::Itertools;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use vm::vm_with_bootloader::{get_bootloader_memory, BlockContextMode, TxExecutionMode};
+use vm::vm_with_bootloader::{
+    get_bootloader_memory, BlockContextMode, BootloaderMemoryUsage, TxExecutionMode,
+    BOOTLOADER_TX_ENCODING_SPACE, COMPRESSED_BYTECODES_SLOTS,
+};
 use vm::zk_evm::aux_structures::LogQuery;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_config::constants::ERC20_TRANSFER_TOPIC;
 use zksync_dal::StorageProcessor;
+use zksync_types::api::BootloaderUsageReport;
+use zksync_types::api::Transfer;
 use zksync_types::block::DeployedContract;
-use zksync_types::tx::{IncludedTxLocation, TransactionExecutionResult};
+use zksync_types::tx::{IncludedTxLocation, TransactionExecutionResult, TxAccessSet};
 use zksync_types::{
     l2_to_l1_log::L2ToL1Log, log::StorageLogKind, AccountTreeId, Address, ExecuteTransactionCommon,
-    L1BatchNumber, StorageKey, StorageLog, StorageLogQuery, StorageValue, VmEvent,
-    ACCOUNT_CODE_STORAGE_ADDRESS, H256, U256,
+    L1BatchNumber, MiniblockNumber, StorageKey, StorageLog, StorageLogQuery, StorageValue, VmEvent,
+    ACCOUNT_CODE_STORAGE_ADDRESS, H256, MAX_PUBDATA_PER_L1_BATCH, U256,
 };
 use zksync_utils::{h256_to_account_address, h256_to_u256};
 
@@ -60,6 +67,26 @@ pub(crate) fn write_logs_from_storage_logs(storage_logs: StorageLogs) -> Storage
         .collect()
 }
 
+/// Read and write sets of the transactions in the miniblock, in the execution order.
+pub(crate) fn extract_tx_access_sets_this_block(
+    log_queries: &[StorageLogQuery],
+    updates_manager: &UpdatesManager,
+) -> Vec<TxAccessSet> {
+    log_queries
+        .iter()
+        .group_by(|log| log.log_query.tx_number_in_block)
+        .into_iter()
+        .map(|(tx_index, logs)| {
+            let storage_logs: Vec<_> = logs.map(StorageLog::from_log_query).collect();
+            TxAccessSet::new(
+                updates_manager.get_tx_by_index(tx_index as usize).hash(),
+                tx_index as u32 - updates_manager.l1_batch.executed_transactions.len() as u32,
+                &storage_logs,
+            )
+        })
+        .collect()
+}
+
 pub(crate) fn extract_events_this_block(
     vm_events: &[VmEvent],
     updates_manager: &UpdatesManager,
@@ -94,6 +121,34 @@ pub(crate) fn extract_events_this_block(
         .collect()
 }
 
+/// ETH and ERC-20 token transfers among the miniblock events, indexed the same way as the events are stored.
+/// ETH transfers are emitted by the L2 ETH token contract with the same `Transfer` signature as ERC-20 ones;
+/// ERC-721 transfers, which have the token ID as the last indexed topic, are skipped.
+pub(crate) fn extract_transfers_this_block(
+    miniblock_number: MiniblockNumber,
+    events: &[(IncludedTxLocation, Vec<VmEvent>)],
+) -> Vec<Transfer> {
+    events
+        .iter()
+        .flat_map(|(location, events)| events.iter().map(move |event| (location.tx_hash, event)))
+        .enumerate()
+        .filter(|(_, (_, event))| {
+            event.indexed_topics.len() == 3
+                && event.indexed_topics[0] == ERC20_TRANSFER_TOPIC
+                && event.value.len() == 32
+        })
+        .map(|(event_index, (tx_hash, event))| Transfer {
+            token_address: event.address,
+            from: h256_to_account_address(&event.indexed_topics[1]),
+            to: h256_to_account_address(&event.indexed_topics[2]),
+            amount: U256::from_big_endian(&event.value),
+            transaction_hash: tx_hash,
+            block_number: miniblock_number,
+            log_index: event_index as u32,
+        })
+        .collect()
+}
+
 pub(crate) fn extract_l2_to_l1_logs_this_block(
     l2_to_l1_logs: &[L2ToL1Log],
     updates_manager: &UpdatesManager,
@@ -171,6 +226,55 @@ pub(crate) fn get_initial_bootloader_memory(
     )
 }
 
+/// Limits the bootloader usage of an L1 batch is reported against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BootloaderUsageLimits {
+    pub tx_slots: usize,
+    pub gas_limit: u32,
+}
+
+impl BootloaderUsageLimits {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            tx_slots: config.transaction_slots,
+            gas_limit: config.block_gas_limit,
+        }
+    }
+}
+
+pub(crate) fn bootloader_usage_report(
+    l1_batch_number: L1BatchNumber,
+    updates_manager: &UpdatesManager,
+    initial_bootloader_memory: &[(usize, U256)],
+    limits: BootloaderUsageLimits,
+) -> BootloaderUsageReport {
+    let memory_usage = BootloaderMemoryUsage::from_memory(initial_bootloader_memory);
+    let executed_transactions = &updates_manager.l1_batch.executed_transactions;
+    let execution_metrics = updates_manager.pending_execution_metrics();
+    let pubdata_bytes =
+        execution_metrics.size() + updates_manager.storage_writes_deduplicator.metrics().size();
+    let max_tx_pubdata_bytes = executed_transactions
+        .iter()
+        .map(|tx| tx.execution_info.size())
+        .max()
+        .unwrap_or(0);
+
+    BootloaderUsageReport {
+        l1_batch_number,
+        tx_count: executed_transactions.len() as u32,
+        tx_slots: limits.tx_slots as u32,
+        txs_encoding_words: memory_usage.txs_encoding_words as u32,
+        txs_encoding_limit: BOOTLOADER_TX_ENCODING_SPACE,
+        compressed_bytecodes_words: memory_usage.compressed_bytecodes_words as u32,
+        compressed_bytecodes_limit: COMPRESSED_BYTECODES_SLOTS as u32 - 1,
+        gas_used: execution_metrics.gas_used as u64,
+        gas_limit: limits.gas_limit.into(),
+        pubdata_bytes: pubdata_bytes as u32,
+        max_tx_pubdata_bytes: max_tx_pubdata_bytes as u32,
+        pubdata_limit: MAX_PUBDATA_PER_L1_BATCH as u32,
+    }
+}
+
 pub(crate) fn storage_log_query_write_read_counts(logs: &[StorageLogQuery]) -> (usize, usize) {
     let (reads, writes): (Vec<&StorageLogQuery>, Vec<&StorageLogQuery>) =
         logs.iter().partition(|l| l.log_query.rw_flag);
@@ -250,3 +354,48 @@ pub(crate) fn wait_for_l1_batch_state_root_unchecked(
         std::thread::sleep(SAFE_STATE_ROOT_INTERVAL);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::event::TransferEventsFixture;
+
+    #[test]
+    fn transfers_are_extracted_with_miniblock_log_indices() {
+        let TransferEventsFixture {
+            from,
+            to,
+            amount,
+            transfer_event,
+            nft_transfer_event,
+            other_event,
+        } = TransferEventsFixture::default();
+        let tx_location = |index: u32| IncludedTxLocation {
+            tx_hash: H256::repeat_byte(index as u8 + 1),
+            tx_index_in_miniblock: index,
+            tx_initiator_address: from,
+        };
+        let events = [
+            (
+                tx_location(0),
+                vec![other_event, transfer_event.clone(), nft_transfer_event],
+            ),
+            (tx_location(1), vec![transfer_event.clone()]),
+        ];
+
+        let transfers = extract_transfers_this_block(MiniblockNumber(5), &events);
+        let expected_transfer = |tx_index: u32, log_index: u32| Transfer {
+            token_address: transfer_event.address,
+            from,
+            to,
+            amount,
+            transaction_hash: tx_location(tx_index).tx_hash,
+            block_number: MiniblockNumber(5),
+            log_index,
+        };
+        assert_eq!(
+            transfers,
+            [expected_transfer(0, 1), expected_transfer(1, 3)]
+        );
+    }
+}
@@ -4,22 +4,28 @@ use std::time::Instant;
 
 use vm::vm_with_bootloader::derive_base_fee_and_gas_per_pubdata;
 use vm::vm_with_bootloader::DerivedBlockContext;
-use vm::VmBlockResult;
+use vm::{VmBlockResult, VmTxExecutionResult};
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::EthInterface;
 use zksync_mempool::L2TxFilter;
-use zksync_types::{Address, L1BatchNumber, MiniblockNumber, Transaction};
+use zksync_types::{
+    api::TxLifecycleEvent, Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber,
+    L1TxCommonData, MiniblockNumber, Transaction, H256,
+};
 use zksync_utils::time::millis_since_epoch;
 
 use crate::gas_adjuster::GasAdjuster;
 use crate::state_keeper::{
-    extractors,
+    deployer_allowlist::DeployerAllowlist,
+    extractors::{self, BootloaderUsageLimits},
     io::{
         common::{l1_batch_params, poll_until, StateKeeperStats},
         seal_logic::{seal_l1_batch_impl, seal_miniblock_impl},
+        timestamps::TimestampController,
         L1BatchParams, PendingBatchData, StateKeeperIO,
     },
+    sealed_miniblocks::SealedMiniblocksSender,
     updates::UpdatesManager,
     MempoolGuard,
 };
@@ -46,6 +52,25 @@ pub(crate) struct MempoolIO<E> {
     gas_adjuster: Arc<GasAdjuster<E>>,
 
     base_system_contracts: BaseSystemContracts,
+
+    record_tx_access_sets: bool,
+
+    // Limits the bootloader usage reports are recorded against; reports aren't recorded if `None`.
+    bootloader_usage_limits: Option<BootloaderUsageLimits>,
+
+    // Rejects deployments from the non-allowlisted accounts in the permissioned deployment mode.
+    deployer_allowlist: Option<DeployerAllowlist>,
+
+    timestamp_controller: TimestampController,
+
+    // Hash of the last received priority operation if it's past its L1 deadline.
+    expired_priority_op: Option<H256>,
+
+    // Whether the current L1 batch is priced with the L1->L2 transactions pricing.
+    l1_to_l2_priced_batch: bool,
+
+    // Publishes the sealed miniblocks to the in-process subscribers (e.g., the WS API).
+    sealed_miniblocks: SealedMiniblocksSender,
 }
 
 impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO for MempoolIO<E> {
@@ -82,10 +107,16 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
                 .default_aa,
         );
 
+        // The pending batch must be re-executed with the fee account it was started with, even if the configured one
+        // has changed since. Miniblocks sealed before the fee account was recorded were executed with the configured one.
+        let fee_account = Some(pending_miniblock_header.fee_account_address)
+            .filter(|address| !address.is_zero())
+            .unwrap_or(self.fee_account);
+
         vlog::info!("previous_l1_batch_hash: {}", previous_l1_batch_hash);
         let params = l1_batch_params(
             self.current_l1_batch_number,
-            self.fee_account,
+            fee_account,
             pending_miniblock_header.timestamp,
             previous_l1_batch_hash,
             pending_miniblock_header.l1_gas_price,
@@ -116,7 +147,7 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
         poll_until(self.delay_interval, max_wait, || {
             // We create a new filter each time, since parameters may change and a previously
             // ignored transaction in the mempool may be scheduled for the execution.
-            self.filter = self.gas_adjuster.l2_tx_filter(self.fair_l2_gas_price);
+            (self.filter, self.l1_to_l2_priced_batch) = self.next_batch_filter();
             self.mempool.has_next(&self.filter).then(|| {
                 // We only need to get the root hash when we're certain that we have a new transaction.
                 vlog::info!("getting previous block hash");
@@ -135,10 +166,14 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
                     self.fair_l2_gas_price
                 );
 
+                let timestamp = self.timestamp_controller.next_l1_batch_timestamp(
+                    (millis_since_epoch() / 1000) as u64,
+                    self.gas_adjuster.l1_block_timestamp(),
+                );
                 l1_batch_params(
                     self.current_l1_batch_number,
                     self.fee_account,
-                    (millis_since_epoch() / 1000) as u64,
+                    timestamp,
                     previous_l1_batch_hash,
                     self.filter.l1_gas_price,
                     self.fair_l2_gas_price,
@@ -149,12 +184,15 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
     }
 
     fn wait_for_new_miniblock_params(&mut self, _max_wait: Duration) -> Option<u64> {
-        let new_miniblock_timestamp = (millis_since_epoch() / 1000) as u64;
+        let new_miniblock_timestamp = self.timestamp_controller.next_miniblock_timestamp(
+            (millis_since_epoch() / 1000) as u64,
+            self.gas_adjuster.l1_block_timestamp(),
+        );
         Some(new_miniblock_timestamp)
     }
 
     fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
-        poll_until(self.delay_interval, max_wait, || {
+        let tx = poll_until(self.delay_interval, max_wait, || {
             let started_at = Instant::now();
             let res = self.mempool.next_transaction(&self.filter);
             metrics::histogram!(
@@ -162,7 +200,52 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
                 started_at.elapsed(),
             );
             res
-        })
+        })?;
+        if let ExecuteTransactionCommon::L1(data) = &tx.common_data {
+            self.check_priority_op_deadline(tx.hash(), data);
+        }
+        if let ExecuteTransactionCommon::L2(data) = &tx.common_data {
+            let miniblock_timestamp = self.timestamp_controller.last_timestamp();
+            if data.is_expired_at(miniblock_timestamp) {
+                let err = format!(
+                    "transaction expired at {}, miniblock timestamp: {}",
+                    data.valid_until.unwrap(),
+                    miniblock_timestamp
+                );
+                self.reject(&tx, &err);
+                return None;
+            }
+        }
+        Some(tx)
+    }
+
+    fn check_executed_tx(
+        &mut self,
+        tx: &Transaction,
+        result: &VmTxExecutionResult,
+    ) -> Result<(), String> {
+        match &self.deployer_allowlist {
+            // Deployments can be made by the contracts called by the transaction, so the allowlist is checked
+            // against the deployments actually made rather than the transaction itself.
+            Some(allowlist) if !tx.is_l1() => allowlist
+                .check_deployments(tx.initiator_account(), &result.result.logs.events)
+                .map_err(|err| err.to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    fn should_seal_after_tx(&self, tx: &Transaction) -> bool {
+        self.expired_priority_op == Some(tx.hash())
+    }
+
+    fn should_seal_before_next_tx(&self) -> bool {
+        // A priority operation is charged with the parameters of the batch it's executed in. If the batch is priced
+        // above the L1->L2 pricing (e.g., it was opened during an L1 fee spike), the operation starts a new batch instead.
+        if self.l1_to_l2_priced_batch || !self.mempool.has_next_priority_op() {
+            return false;
+        }
+        let l1_to_l2_filter = self.gas_adjuster.l1_to_l2_tx_filter(self.fair_l2_gas_price);
+        l1_to_l2_filter.l1_gas_price < self.filter.l1_gas_price
     }
 
     fn rollback(&mut self, tx: &Transaction) {
@@ -193,20 +276,27 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
         storage
             .transactions_dal()
             .mark_tx_as_rejected(rejected.hash(), &format!("rejected: {}", error));
+        storage.tx_lifecycle_dal().insert_tx_lifecycle_events(&[(
+            rejected.hash(),
+            TxLifecycleEvent::rejected(error.to_owned()),
+        )]);
     }
 
     fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
         let pool = self.pool.clone();
         let mut storage = pool.access_storage_blocking();
-        seal_miniblock_impl(
+        let sealed_miniblock = seal_miniblock_impl(
             self.current_miniblock_number,
             self.current_l1_batch_number,
             &mut self.statistics,
             &mut storage,
             updates_manager,
             false,
+            self.record_tx_access_sets,
         );
         self.current_miniblock_number += 1;
+        drop(storage);
+        self.sealed_miniblocks.send(sealed_miniblock);
     }
 
     fn seal_l1_batch(
@@ -223,7 +313,7 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
         );
         let pool = self.pool.clone();
         let mut storage = pool.access_storage_blocking();
-        seal_l1_batch_impl(
+        let fictive_miniblock = seal_l1_batch_impl(
             self.current_miniblock_number,
             self.current_l1_batch_number,
             &mut self.statistics,
@@ -232,13 +322,16 @@ impl<E: 'static + EthInterface + std::fmt::Debug + Send + Sync> StateKeeperIO fo
             block_result,
             updates_manager,
             block_context,
+            self.bootloader_usage_limits,
         );
         self.current_miniblock_number += 1; // Due to fictive miniblock being sealed.
         self.current_l1_batch_number += 1;
+        self.sealed_miniblocks.send(fictive_miniblock);
     }
 }
 
 impl<E: EthInterface> MempoolIO<E> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         mempool: MempoolGuard,
         pool: ConnectionPool,
@@ -247,11 +340,20 @@ impl<E: EthInterface> MempoolIO<E> {
         delay_interval: Duration,
         gas_adjuster: Arc<GasAdjuster<E>>,
         base_system_contracts_hashes: BaseSystemContractsHashes,
+        record_tx_access_sets: bool,
+        bootloader_usage_limits: Option<BootloaderUsageLimits>,
+        deployer_allowlist: Option<DeployerAllowlist>,
+        max_timestamp_drift_from_l1: u64,
+        sealed_miniblocks: SealedMiniblocksSender,
     ) -> Self {
         let mut storage = pool.access_storage_blocking();
         let last_sealed_block_header = storage.blocks_dal().get_newest_block_header();
         let last_miniblock_number = storage.blocks_dal().get_sealed_miniblock_number();
         let num_contracts = storage.storage_load_dal().load_number_of_contracts();
+        let last_miniblock_timestamp = storage
+            .blocks_dal()
+            .get_last_sealed_miniblock_header()
+            .map_or(0, |header| header.timestamp);
         let filter = L2TxFilter::default(); // Will be initialized properly on the first newly opened batch.
 
         let base_system_contracts = storage.storage_dal().get_base_system_contracts(
@@ -272,9 +374,74 @@ impl<E: EthInterface> MempoolIO<E> {
             statistics: StateKeeperStats { num_contracts },
             gas_adjuster,
             base_system_contracts,
+            record_tx_access_sets,
+            bootloader_usage_limits,
+            deployer_allowlist,
+            timestamp_controller: TimestampController::new(
+                max_timestamp_drift_from_l1,
+                last_miniblock_timestamp,
+            ),
+            expired_priority_op: None,
+            l1_to_l2_priced_batch: false,
+            sealed_miniblocks,
+        }
+    }
+
+    /// Returns the fee parameters of the next L1 batch and whether it's priced with the L1->L2 pricing. If the batch
+    /// starts with a priority operation, it's priced the same way the fee of L1->L2 transactions is estimated by the API
+    /// (unless it's more expensive), so that the deposits estimated during an L1 fee spike don't fail. The L2 transactions
+    /// of the batch get the same discount, which is covered by the operator. Priority operations arriving while a batch
+    /// priced above that is open are executed in a new batch (see `should_seal_before_next_tx`).
+    fn next_batch_filter(&self) -> (L2TxFilter, bool) {
+        let filter = self.gas_adjuster.l2_tx_filter(self.fair_l2_gas_price);
+        if !self.mempool.has_next_priority_op() {
+            return (filter, false);
+        }
+        let l1_to_l2_filter = self.gas_adjuster.l1_to_l2_tx_filter(self.fair_l2_gas_price);
+        if l1_to_l2_filter.l1_gas_price < filter.l1_gas_price {
+            metrics::increment_counter!("server.state_keeper.l1_to_l2_priced_batches");
+            (l1_to_l2_filter, true)
+        } else {
+            (filter, false)
         }
     }
 
+    /// Priority operations past their L1 deadline can't be skipped, since L1 requires the priority queue
+    /// to be processed in order. Instead, such an operation is recorded and the L1 batch is sealed right after it,
+    /// so that the operation reaches L1 as soon as possible.
+    fn check_priority_op_deadline(&mut self, tx_hash: H256, common_data: &L1TxCommonData) {
+        self.expired_priority_op = None;
+        // The deadline is the timestamp of the L1 block until which the operation must be executed.
+        // It's 0 for the old operations which had it not persisted.
+        let Some(l1_block_timestamp) = self.gas_adjuster.l1_block_timestamp() else {
+            return;
+        };
+        if common_data.deadline_block == 0 || l1_block_timestamp <= common_data.deadline_block {
+            return;
+        }
+
+        let l1_block_number = self.gas_adjuster.l1_block_number();
+        vlog::warn!(
+            "Priority operation #{} ({:?}) is executed past its deadline: L1 block {} with timestamp {}, deadline {}",
+            common_data.serial_id,
+            tx_hash,
+            l1_block_number,
+            l1_block_timestamp,
+            common_data.deadline_block
+        );
+        metrics::increment_counter!("server.state_keeper.expired_priority_ops");
+        self.pool
+            .access_storage_blocking()
+            .expired_priority_ops_dal()
+            .insert_expired_priority_op(
+                common_data.serial_id,
+                tx_hash,
+                common_data.deadline_block,
+                L1BlockNumber(l1_block_number as u32),
+            );
+        self.expired_priority_op = Some(tx_hash);
+    }
+
     fn pending_miniblock_number(&self, storage: &mut StorageProcessor<'_>) -> MiniblockNumber {
         let (_, last_miniblock_number_included_in_l1_batch) = storage
             .blocks_dal()
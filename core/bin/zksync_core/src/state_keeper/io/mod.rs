@@ -3,7 +3,7 @@ use std::time::Duration;
 use vm::vm_with_bootloader::BlockContextMode;
 use vm::vm_with_bootloader::DerivedBlockContext;
 use vm::zk_evm::block_properties::BlockProperties;
-use vm::VmBlockResult;
+use vm::{VmBlockResult, VmTxExecutionResult};
 use zksync_types::{L1BatchNumber, MiniblockNumber, Transaction};
 
 use super::updates::UpdatesManager;
@@ -14,6 +14,7 @@ use zksync_contracts::BaseSystemContracts;
 pub(crate) mod common;
 pub(crate) mod mempool;
 pub(crate) mod seal_logic;
+pub(crate) mod timestamps;
 
 /// System parameters for L1 batch.
 /// It includes system params such as Basic System Contracts and zkPorter configuration
@@ -66,6 +67,25 @@ pub trait StateKeeperIO: 'static + std::fmt::Debug + Send {
     fn rollback(&mut self, tx: &Transaction);
     /// Marks the transaction as "rejected", e.g. one that is not correct and can't be executed.
     fn reject(&mut self, tx: &Transaction, error: &str);
+    /// Checks the effects of an executed transaction against the policies of the node.
+    /// Returns the reason to reject the transaction if it violates any of them.
+    fn check_executed_tx(
+        &mut self,
+        _tx: &Transaction,
+        _result: &VmTxExecutionResult,
+    ) -> Result<(), String> {
+        Ok(())
+    }
+    /// Returns `true` if the L1 batch must be sealed right after the transaction regardless of the seal criteria,
+    /// e.g. for a priority operation which is past its L1 deadline.
+    fn should_seal_after_tx(&self, _tx: &Transaction) -> bool {
+        false
+    }
+    /// Returns `true` if the L1 batch must be sealed before the next transaction regardless of the seal criteria,
+    /// e.g. for a priority operation that would be charged more in the current batch than in a new one.
+    fn should_seal_before_next_tx(&self) -> bool {
+        false
+    }
     /// Marks the miniblock (aka L2 block) as sealed.
     /// Returns the timestamp for the next miniblock.
     fn seal_miniblock(&mut self, updates_manager: &UpdatesManager);
@@ -9,18 +9,26 @@ use vm::VmBlockResult;
 use zksync_dal::StorageProcessor;
 use zksync_types::{
     block::L1BatchHeader,
-    block::MiniblockHeader,
+    block::{txs_rolling_hash, MiniblockHeader},
+    bloom::logs_bloom,
+    circuit::estimate_basic_circuits_count,
     event::{extract_added_tokens, extract_long_l2_to_l1_messages},
+    tx::execution_digest::{execution_outcomes_digest, EXECUTION_OUTCOMES_SERIALIZATION_VERSION},
     zkevm_test_harness::witness::sort_storage_access::sort_storage_access_queries,
     Address, L1BatchNumber, MiniblockNumber,
 };
 use zksync_utils::{miniblock_hash, time::millis_since_epoch};
 
-use crate::state_keeper::{extractors, io::common::StateKeeperStats, updates::UpdatesManager};
+use crate::state_keeper::{
+    extractors::{self, BootloaderUsageLimits},
+    io::common::StateKeeperStats,
+    sealed_miniblocks::SealedMiniblock,
+    updates::UpdatesManager,
+};
 
 /// Persists an L1 batch in the storage.
 /// This action includes a creation of an empty "fictive" miniblock that contains the events
-/// generated during the bootloader "tip phase".
+/// generated during the bootloader "tip phase". Returns the summary of the fictive miniblock.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn seal_l1_batch_impl(
     current_miniblock_number: MiniblockNumber,
@@ -31,7 +39,9 @@ pub(crate) fn seal_l1_batch_impl(
     block_result: VmBlockResult,
     mut updates_manager: UpdatesManager,
     block_context: DerivedBlockContext,
-) {
+    bootloader_usage_limits: Option<BootloaderUsageLimits>,
+) -> SealedMiniblock {
+    let _log_context = vlog::with_batch(current_l1_batch_number.0).enter();
     let started_at = Instant::now();
     let mut stage_started_at: Instant = Instant::now();
 
@@ -52,13 +62,15 @@ pub(crate) fn seal_l1_batch_impl(
 
     updates_manager.extend_from_fictive_transaction(block_tip_result.logs);
     // Seal fictive miniblock with last events and storage logs.
-    seal_miniblock_impl(
+    // It has no transactions, so there are no access sets to record.
+    let fictive_miniblock = seal_miniblock_impl(
         current_miniblock_number,
         current_l1_batch_number,
         statistics,
         &mut transaction,
         &updates_manager,
         true,
+        false,
     );
     track_l1_batch_execution_stage("fictive_miniblock", &mut stage_started_at);
 
@@ -89,6 +101,18 @@ pub(crate) fn seal_l1_batch_impl(
         current_l1_batch_number,
     );
     let block_context_properties = BlockContextMode::NewBlock(block_context, hash);
+    let initial_bootloader_contents = extractors::get_initial_bootloader_memory(
+        &updates_manager.l1_batch,
+        block_context_properties,
+    );
+    let bootloader_usage_report = bootloader_usage_limits.map(|limits| {
+        extractors::bootloader_usage_report(
+            current_l1_batch_number,
+            &updates_manager,
+            &initial_bootloader_contents,
+            limits,
+        )
+    });
 
     let l1_batch = L1BatchHeader {
         number: current_l1_batch_number,
@@ -100,11 +124,8 @@ pub(crate) fn seal_l1_batch_impl(
         l2_tx_count: l2_tx_count as u16,
         l2_to_l1_logs: full_result.l2_to_l1_logs,
         l2_to_l1_messages: extract_long_l2_to_l1_messages(&full_result.events),
-        bloom: Default::default(),
-        initial_bootloader_contents: extractors::get_initial_bootloader_memory(
-            &updates_manager.l1_batch,
-            block_context_properties,
-        ),
+        bloom: logs_bloom(&full_result.events),
+        initial_bootloader_contents,
         used_contract_hashes: full_result.used_contract_hashes,
         base_fee_per_gas: block_context.base_fee,
         l1_gas_price: updates_manager.l1_gas_price(),
@@ -117,6 +138,28 @@ pub(crate) fn seal_l1_batch_impl(
         .insert_l1_batch(l1_batch, updates_manager.l1_batch.l1_gas_count);
     track_l1_batch_execution_stage("insert_l1_batch_header", &mut stage_started_at);
 
+    if let Some(report) = bootloader_usage_report {
+        transaction.bootloader_usage_dal().insert_report(&report);
+        track_l1_batch_execution_stage("insert_bootloader_usage_report", &mut stage_started_at);
+    }
+
+    let estimated_basic_circuits =
+        estimate_basic_circuits_count(&updates_manager.l1_batch.block_execution_metrics);
+    transaction
+        .blocks_dal()
+        .set_estimated_basic_circuits(current_l1_batch_number, estimated_basic_circuits as u32);
+    metrics::histogram!(
+        "server.state_keeper.l1_batch.estimated_basic_circuits",
+        estimated_basic_circuits as f64
+    );
+    track_l1_batch_execution_stage("set_estimated_basic_circuits", &mut stage_started_at);
+
+    if let Some(seal_criterion) = updates_manager.l1_batch.seal_criterion {
+        transaction
+            .blocks_dal()
+            .set_l1_batch_seal_criterion(current_l1_batch_number, seal_criterion);
+    }
+
     transaction
         .blocks_dal()
         .mark_miniblocks_as_executed_in_l1_batch(current_l1_batch_number);
@@ -186,6 +229,7 @@ pub(crate) fn seal_l1_batch_impl(
         current_l1_batch_number,
         started_at.elapsed()
     );
+    fictive_miniblock
 }
 
 // Seal miniblock with the given number.
@@ -194,6 +238,9 @@ pub(crate) fn seal_l1_batch_impl(
 // in it. It is needed because there might be some storage logs/events that are created after the last processed tx in
 // l1 batch: after the last transaction is processed, bootloader enters the "tip" phase in which it can still generate
 // events (e.g. one for sending fees to the operator).
+//
+// Returns the summary of the sealed miniblock, which must be published only after the enclosing DB transaction
+// (if any) is committed.
 pub(crate) fn seal_miniblock_impl(
     current_miniblock_number: MiniblockNumber,
     current_l1_batch_number: L1BatchNumber,
@@ -201,9 +248,13 @@ pub(crate) fn seal_miniblock_impl(
     storage: &mut StorageProcessor<'_>,
     updates_manager: &UpdatesManager,
     is_fictive: bool,
-) {
+    record_tx_access_sets: bool,
+) -> SealedMiniblock {
     miniblock_assertions(updates_manager, is_fictive);
 
+    let _log_context = vlog::with_batch(current_l1_batch_number.0)
+        .with_miniblock(current_miniblock_number.0)
+        .enter();
     let started_at = Instant::now();
     let mut stage_started_at: Instant = Instant::now();
 
@@ -221,21 +272,25 @@ pub(crate) fn seal_miniblock_impl(
         );
 
     let mut transaction = storage.start_transaction_blocking();
-    let miniblock_header = MiniblockHeader {
-        number: current_miniblock_number,
-        timestamp: updates_manager.miniblock.timestamp,
-        hash: miniblock_hash(current_miniblock_number),
-        l1_tx_count: l1_tx_count as u16,
-        l2_tx_count: l2_tx_count as u16,
-        base_fee_per_gas: updates_manager.base_fee_per_gas(),
-        l1_gas_price: updates_manager.l1_gas_price(),
-        l2_fair_gas_price: updates_manager.fair_l2_gas_price(),
-        base_system_contracts_hashes: updates_manager.base_system_contract_hashes(),
-    };
-
-    transaction.blocks_dal().insert_miniblock(miniblock_header);
+    let sealed_miniblock = sealed_miniblock_summary(current_miniblock_number, updates_manager);
+    transaction
+        .blocks_dal()
+        .insert_miniblock_with_system_context(
+            sealed_miniblock.header.clone(),
+            updates_manager.gas_per_pubdata(),
+            txs_rolling_hash(sealed_miniblock.tx_hashes.iter().copied()),
+        );
     track_miniblock_execution_stage("insert_miniblock_header", &mut stage_started_at);
 
+    let execution_digest =
+        execution_outcomes_digest(&updates_manager.miniblock.executed_transactions);
+    transaction.blocks_dal().set_miniblock_execution_digest(
+        current_miniblock_number,
+        execution_digest,
+        EXECUTION_OUTCOMES_SERIALIZATION_VERSION,
+    );
+    track_miniblock_execution_stage("set_miniblock_execution_digest", &mut stage_started_at);
+
     transaction
         .transactions_dal()
         .mark_txs_as_executed_in_miniblock(
@@ -245,6 +300,22 @@ pub(crate) fn seal_miniblock_impl(
         );
     track_miniblock_execution_stage("mark_transactions_in_miniblock", &mut stage_started_at);
 
+    transaction
+        .tx_lifecycle_dal()
+        .insert_tx_executed_events(current_miniblock_number, &sealed_miniblock.tx_hashes);
+    track_miniblock_execution_stage("insert_tx_lifecycle_events", &mut stage_started_at);
+
+    if record_tx_access_sets && !is_fictive {
+        let access_sets = extractors::extract_tx_access_sets_this_block(
+            &updates_manager.miniblock.storage_logs,
+            updates_manager,
+        );
+        transaction
+            .tx_access_sets_dal()
+            .insert_access_sets(current_miniblock_number, &access_sets);
+        track_miniblock_execution_stage("insert_tx_access_sets", &mut stage_started_at);
+    }
+
     let storage_logs = extractors::log_queries_to_storage_logs(
         &updates_manager.miniblock.storage_logs,
         updates_manager,
@@ -286,11 +357,27 @@ pub(crate) fn seal_miniblock_impl(
         updates_manager,
         is_fictive,
     );
+    let tx_logs_blooms: Vec<_> = events_this_miniblock
+        .iter()
+        .map(|(location, events)| (location.tx_hash, logs_bloom(events)))
+        .collect();
+    let transfers =
+        extractors::extract_transfers_this_block(current_miniblock_number, &events_this_miniblock);
     transaction
         .events_dal()
         .save_events(current_miniblock_number, events_this_miniblock);
     track_miniblock_execution_stage("insert_events", &mut stage_started_at);
 
+    transaction
+        .transfers_dal()
+        .insert_transfers(current_miniblock_number, &transfers);
+    track_miniblock_execution_stage("insert_transfers", &mut stage_started_at);
+
+    transaction
+        .transactions_dal()
+        .set_logs_blooms(&tx_logs_blooms);
+    track_miniblock_execution_stage("set_transactions_logs_blooms", &mut stage_started_at);
+
     let l2_to_l1_logs_this_miniblock = extractors::extract_l2_to_l1_logs_this_block(
         &updates_manager.miniblock.l2_to_l1_logs,
         updates_manager,
@@ -337,6 +424,37 @@ pub(crate) fn seal_miniblock_impl(
         "apply_miniblock_updates_to_l1_batch_updates_accumulator",
         &mut stage_started_at,
     );
+
+    sealed_miniblock
+}
+
+/// Returns the summary of the miniblock with the given number executed by the state keeper.
+pub(crate) fn sealed_miniblock_summary(
+    miniblock_number: MiniblockNumber,
+    updates_manager: &UpdatesManager,
+) -> SealedMiniblock {
+    let (l1_tx_count, l2_tx_count) =
+        extractors::l1_l2_tx_count(&updates_manager.miniblock.executed_transactions);
+    let header = MiniblockHeader {
+        number: miniblock_number,
+        timestamp: updates_manager.miniblock.timestamp,
+        hash: miniblock_hash(miniblock_number),
+        l1_tx_count: l1_tx_count as u16,
+        l2_tx_count: l2_tx_count as u16,
+        base_fee_per_gas: updates_manager.base_fee_per_gas(),
+        l1_gas_price: updates_manager.l1_gas_price(),
+        l2_fair_gas_price: updates_manager.fair_l2_gas_price(),
+        base_system_contracts_hashes: updates_manager.base_system_contract_hashes(),
+        logs_bloom: logs_bloom(&updates_manager.miniblock.events),
+        fee_account_address: updates_manager.fee_account_address(),
+    };
+    let tx_hashes = updates_manager
+        .miniblock
+        .executed_transactions
+        .iter()
+        .map(|tx| tx.hash)
+        .collect();
+    SealedMiniblock { header, tx_hashes }
 }
 
 /// Performs several sanity checks to make sure that the miniblock is valid.
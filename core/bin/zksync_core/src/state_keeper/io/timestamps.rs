@@ -0,0 +1,134 @@
+//! Timestamps of the new L1 batches and miniblocks.
+//!
+//! The local clock is not trusted as is: the timestamps must be monotonic (the bootloader requires each batch
+//! to be strictly newer than the previous one), and the L1 contract rejects batches with timestamps too far
+//! ahead of the L1 block. If the clock jumps back (e.g. after an NTP correction), the last timestamp is reused
+//! until the clock catches up; if it runs ahead of L1, the timestamp is clamped to the allowed drift.
+
+/// Reason for the local clock value to be replaced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClampReason {
+    /// The clock is behind the last used timestamp.
+    BehindLastTimestamp,
+    /// The clock is ahead of the last L1 block by more than the allowed drift.
+    AheadOfL1,
+}
+
+impl ClampReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::BehindLastTimestamp => "behind_last_timestamp",
+            Self::AheadOfL1 => "ahead_of_l1",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TimestampController {
+    max_drift_from_l1: u64,
+    last_timestamp: u64,
+}
+
+impl TimestampController {
+    /// `last_timestamp` is the timestamp of the last sealed miniblock, 0 for the empty chain.
+    pub fn new(max_drift_from_l1: u64, last_timestamp: u64) -> Self {
+        Self {
+            max_drift_from_l1,
+            last_timestamp,
+        }
+    }
+
+    /// Returns the last timestamp handed out, i.e. the timestamp of the miniblock being currently processed.
+    pub fn last_timestamp(&self) -> u64 {
+        self.last_timestamp
+    }
+
+    /// Returns the timestamp for a new L1 batch, which must be strictly greater than all the previous timestamps.
+    pub fn next_l1_batch_timestamp(&mut self, now: u64, l1_block_timestamp: Option<u64>) -> u64 {
+        let timestamp = self.next_timestamp(self.last_timestamp + 1, now, l1_block_timestamp);
+        self.report(timestamp, now, "l1_batch");
+        timestamp
+    }
+
+    /// Returns the timestamp for a new miniblock, which may be equal to the previous one.
+    pub fn next_miniblock_timestamp(&mut self, now: u64, l1_block_timestamp: Option<u64>) -> u64 {
+        let timestamp = self.next_timestamp(self.last_timestamp, now, l1_block_timestamp);
+        self.report(timestamp, now, "miniblock");
+        timestamp
+    }
+
+    fn next_timestamp(
+        &mut self,
+        min_timestamp: u64,
+        now: u64,
+        l1_block_timestamp: Option<u64>,
+    ) -> u64 {
+        let max_timestamp =
+            l1_block_timestamp.map(|l1_timestamp| l1_timestamp + self.max_drift_from_l1);
+        let timestamp = match max_timestamp {
+            Some(max_timestamp) if now > max_timestamp => max_timestamp,
+            _ => now,
+        };
+        // Monotonicity takes precedence over the L1 bound: the bootloader would reject a batch otherwise,
+        // and the L1 bound is restored once the L1 block timestamp advances.
+        let timestamp = timestamp.max(min_timestamp);
+        self.last_timestamp = timestamp;
+        timestamp
+    }
+
+    fn report(&self, timestamp: u64, now: u64, block_kind: &'static str) {
+        metrics::gauge!(
+            "server.state_keeper.timestamp_drift",
+            timestamp as f64 - now as f64,
+            "block_kind" => block_kind
+        );
+        let reason = match timestamp.cmp(&now) {
+            std::cmp::Ordering::Equal => return,
+            std::cmp::Ordering::Greater => ClampReason::BehindLastTimestamp,
+            std::cmp::Ordering::Less => ClampReason::AheadOfL1,
+        };
+        metrics::increment_counter!(
+            "server.state_keeper.timestamp_clamped",
+            "block_kind" => block_kind,
+            "reason" => reason.as_str()
+        );
+        vlog::debug!(
+            "Clamped {} timestamp to {} ({}), local clock: {}",
+            block_kind,
+            timestamp,
+            reason.as_str(),
+            now
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_follow_clock() {
+        let mut controller = TimestampController::new(600, 100);
+        assert_eq!(controller.next_l1_batch_timestamp(110, Some(105)), 110);
+        assert_eq!(controller.next_miniblock_timestamp(110, Some(105)), 110);
+        assert_eq!(controller.next_miniblock_timestamp(111, None), 111);
+    }
+
+    #[test]
+    fn timestamps_are_monotonic_on_clock_jumps() {
+        let mut controller = TimestampController::new(600, 100);
+        assert_eq!(controller.next_miniblock_timestamp(90, None), 100);
+        assert_eq!(controller.next_l1_batch_timestamp(90, None), 101);
+        assert_eq!(controller.next_miniblock_timestamp(95, None), 101);
+        assert_eq!(controller.next_miniblock_timestamp(102, None), 102);
+    }
+
+    #[test]
+    fn timestamps_are_bounded_by_l1() {
+        let mut controller = TimestampController::new(600, 100);
+        assert_eq!(controller.next_miniblock_timestamp(1_000, Some(200)), 800);
+        assert_eq!(controller.next_miniblock_timestamp(1_001, Some(200)), 800);
+        // Monotonicity is preserved even if the L1 bound is violated.
+        assert_eq!(controller.next_l1_batch_timestamp(1_002, Some(200)), 801);
+    }
+}
@@ -14,6 +14,7 @@ use crate::state_keeper::{
     batch_executor::{BatchExecutorHandle, L1BatchExecutorBuilder, TxExecutionResult},
     io::{L1BatchParams, PendingBatchData, StateKeeperIO},
     seal_criteria::{SealManager, SealResolution},
+    tx_quarantine::TxQuarantine,
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
 };
@@ -41,6 +42,7 @@ pub struct ZkSyncStateKeeper {
     io: Box<dyn StateKeeperIO>,
     batch_executor_base: Box<dyn L1BatchExecutorBuilder>,
     sealer: SealManager,
+    tx_quarantine: Option<TxQuarantine>,
 }
 
 impl ZkSyncStateKeeper {
@@ -55,9 +57,16 @@ impl ZkSyncStateKeeper {
             io,
             batch_executor_base,
             sealer,
+            tx_quarantine: None,
         }
     }
 
+    /// Enables the detection of the transactions crashing the state keeper, see `TxQuarantine` for details.
+    pub(crate) fn with_tx_quarantine(mut self, tx_quarantine: TxQuarantine) -> Self {
+        self.tx_quarantine = Some(tx_quarantine);
+        self
+    }
+
     pub fn run(mut self) {
         match self.run_inner() {
             Ok(()) => {
@@ -123,6 +132,7 @@ impl ZkSyncStateKeeper {
             // Finish current batch.
             if !updates_manager.miniblock.executed_transactions.is_empty() {
                 self.io.seal_miniblock(&updates_manager);
+                batch_executor.start_next_miniblock();
                 // We've sealed the miniblock that we had, but we still need to setup the timestamp for the
                 // fictive miniblock.
                 let fictive_miniblock_timestamp = self.wait_for_new_miniblock_params()?;
@@ -172,6 +182,22 @@ impl ZkSyncStateKeeper {
         Ok(params)
     }
 
+    fn execute_tx(
+        &mut self,
+        batch_executor: &BatchExecutorHandle,
+        tx: &Transaction,
+        reexecuted: bool,
+    ) -> TxExecutionResult {
+        if let Some(tx_quarantine) = &self.tx_quarantine {
+            tx_quarantine.start_execution(tx, reexecuted);
+        }
+        let result = batch_executor.execute_tx(tx.clone());
+        if let Some(tx_quarantine) = &mut self.tx_quarantine {
+            tx_quarantine.finish_execution(tx);
+        }
+        result
+    }
+
     /// Applies the "pending state" on the `UpdatesManager`.
     /// Pending state means transactions that were executed before the server restart. Before we continue processing the
     /// batch, we need to restore the state. We must ensure that every transaction is executed successfully.
@@ -187,7 +213,7 @@ impl ZkSyncStateKeeper {
                 miniblock_number
             );
             for tx in txs {
-                let result = batch_executor.execute_tx(tx.clone());
+                let result = self.execute_tx(batch_executor, &tx, true);
 
                 if !result.success() {
                     let err = result.err().unwrap();
@@ -235,6 +261,7 @@ impl ZkSyncStateKeeper {
             // that will be used as a timestamp for the next sealed miniblock.
             // So, we should care about passing the correct timestamp for miniblock that comes after the pending batch.
             updates_manager.seal_miniblock((millis_since_epoch() / 1000) as u64);
+            batch_executor.start_next_miniblock();
         }
     }
 
@@ -245,14 +272,27 @@ impl ZkSyncStateKeeper {
     ) -> Result<(), Canceled> {
         loop {
             self.check_if_cancelled()?;
-            if self
+            if let Some(criterion) = self
                 .sealer
                 .should_seal_l1_batch_unconditionally(updates_manager)
             {
+                updates_manager.l1_batch.seal_criterion = Some(criterion);
+                return Ok(());
+            }
+            if updates_manager.pending_executed_transactions_len() > 0
+                && self.io.should_seal_before_next_tx()
+            {
+                metrics::counter!(
+                    "server.tx_aggregation.reason",
+                    1,
+                    "criterion" => "seal_before_tx"
+                );
+                updates_manager.l1_batch.seal_criterion = Some("seal_before_tx");
                 return Ok(());
             }
             if self.sealer.should_seal_miniblock(updates_manager) {
                 self.io.seal_miniblock(updates_manager);
+                batch_executor.start_next_miniblock();
                 let new_timestamp = self.wait_for_new_miniblock_params()?;
                 updates_manager.seal_miniblock(new_timestamp);
             }
@@ -261,8 +301,17 @@ impl ZkSyncStateKeeper {
                 continue;
             };
 
-            let (seal_resolution, exec_result) =
+            let (mut seal_resolution, mut seal_criterion, exec_result) =
                 self.process_one_tx(batch_executor, updates_manager, &tx);
+            if seal_resolution == SealResolution::NoSeal && self.io.should_seal_after_tx(&tx) {
+                metrics::counter!(
+                    "server.tx_aggregation.reason",
+                    1,
+                    "criterion" => "seal_after_tx"
+                );
+                seal_resolution = SealResolution::IncludeAndSeal;
+                seal_criterion = Some("seal_after_tx");
+            }
 
             match &seal_resolution {
                 SealResolution::NoSeal => {
@@ -304,6 +353,7 @@ impl ZkSyncStateKeeper {
             };
 
             if seal_resolution.should_seal() {
+                updates_manager.l1_batch.seal_criterion = seal_criterion;
                 return Ok(());
             }
         }
@@ -314,6 +364,7 @@ impl ZkSyncStateKeeper {
     /// 1. The VM entered an incorrect state (e.g. out of gas). In that case, we must revert the transaction and seal
     /// the block.
     /// 2. Seal manager decided that batch is ready to be sealed.
+    /// Returns the name of the criterion the batch should be sealed by along with the resolution.
     /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
     /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
     fn process_one_tx(
@@ -321,8 +372,8 @@ impl ZkSyncStateKeeper {
         batch_executor: &BatchExecutorHandle,
         updates_manager: &mut UpdatesManager,
         tx: &Transaction,
-    ) -> (SealResolution, TxExecutionResult) {
-        let exec_result = batch_executor.execute_tx(tx.clone());
+    ) -> (SealResolution, Option<&'static str>, TxExecutionResult) {
+        let exec_result = self.execute_tx(batch_executor, tx, false);
         let TxExecutionResult {
             tx_result,
             bootloader_dry_run_result,
@@ -338,13 +389,21 @@ impl ZkSyncStateKeeper {
                     "criterion" => "bootloader_tx_out_of_gas",
                     "seal_resolution" => "exclude_and_seal",
                 );
-                (SealResolution::ExcludeAndSeal, exec_result)
+                (
+                    SealResolution::ExcludeAndSeal,
+                    Some("bootloader_tx_out_of_gas"),
+                    exec_result,
+                )
             }
             Err(rejection) => (
                 SealResolution::Unexecutable(rejection.to_string()),
+                None,
                 exec_result,
             ),
             Ok(tx_execution_result) => {
+                if let Err(reason) = self.io.check_executed_tx(tx, &tx_execution_result) {
+                    return (SealResolution::Unexecutable(reason), None, exec_result);
+                }
                 let tx_execution_status = tx_execution_result.status;
                 let ExecutionMetricsForCriteria {
                     l1_gas: tx_l1_gas_this_tx,
@@ -379,7 +438,11 @@ impl ZkSyncStateKeeper {
                             "criterion" => "bootloader_block_tip_failed",
                             "seal_resolution" => "exclude_and_seal",
                         );
-                        return (SealResolution::ExcludeAndSeal, exec_result);
+                        return (
+                            SealResolution::ExcludeAndSeal,
+                            Some("bootloader_block_tip_failed"),
+                            exec_result,
+                        );
                     };
 
                 let ExecutionMetricsForCriteria {
@@ -406,7 +469,7 @@ impl ZkSyncStateKeeper {
                     StorageWritesDeduplicator::apply_on_empty_state(logs_to_apply_iter);
                 let tx_writes_l1_gas = gas_count_from_writes(&tx_writes_metrics);
 
-                let resolution = self.sealer.should_seal_l1_batch(
+                let (resolution, criterion) = self.sealer.should_seal_l1_batch(
                     self.io.current_l1_batch_number().0,
                     updates_manager.batch_timestamp() as u128 * 1000,
                     updates_manager.pending_executed_transactions_len() + 1,
@@ -425,7 +488,7 @@ impl ZkSyncStateKeeper {
                     tx_writes_metrics,
                 );
 
-                (resolution, exec_result)
+                (resolution, criterion, exec_result)
             }
         }
     }
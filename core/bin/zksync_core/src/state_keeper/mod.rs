@@ -2,27 +2,36 @@ use std::sync::Arc;
 
 use tokio::sync::watch::Receiver;
 
+use vm::utils::BLOCK_GAS_LIMIT;
 use zksync_config::constants::MAX_TXS_IN_BLOCK;
-use zksync_config::ZkSyncConfig;
+use zksync_config::{configs::chain::StateKeeperConfig, ZkSyncConfig};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_eth_client::EthInterface;
+use zksync_types::protocol_version::ProtocolVersion;
 
 use self::batch_executor::MainBatchExecutorBuilder;
+use self::deployer_allowlist::DeployerAllowlist;
+use self::extractors::BootloaderUsageLimits;
 use self::io::MempoolIO;
+use self::sealed_miniblocks::SealedMiniblocksSender;
+use self::tx_quarantine::TxQuarantine;
 use crate::gas_adjuster::GasAdjuster;
 use crate::state_keeper::seal_criteria::SealManager;
 
 pub use self::{keeper::ZkSyncStateKeeper, types::MempoolGuard};
 
 pub(crate) mod batch_executor;
+pub(crate) mod deployer_allowlist;
 mod extractors;
 pub(crate) mod io;
 mod keeper;
 pub(crate) mod mempool_actor;
 pub mod seal_criteria;
+pub mod sealed_miniblocks;
 #[cfg(test)]
 mod tests;
+mod tx_quarantine;
 pub(crate) mod types;
 pub(crate) mod updates;
 
@@ -31,6 +40,7 @@ pub(crate) fn start_state_keeper<E>(
     pool: &ConnectionPool,
     mempool: MempoolGuard,
     gas_adjuster: Arc<GasAdjuster<E>>,
+    sealed_miniblocks: SealedMiniblocksSender,
     stop_receiver: Receiver<bool>,
 ) -> ZkSyncStateKeeper
 where
@@ -40,13 +50,27 @@ where
         config.chain.state_keeper.transaction_slots <= MAX_TXS_IN_BLOCK,
         "Configured transaction_slots must be lower than the bootloader constant MAX_TXS_IN_BLOCK"
     );
+    assert!(
+        config.chain.state_keeper.block_gas_limit <= BLOCK_GAS_LIMIT,
+        "Configured block_gas_limit must not exceed the bootloader constant BLOCK_GAS_LIMIT"
+    );
+    register_protocol_version(&config.chain.state_keeper, pool);
 
     let batch_executor_base = MainBatchExecutorBuilder::new(
         config.db.state_keeper_db_path.clone(),
         pool.clone(),
         config.chain.state_keeper.reexecute_each_tx,
-        config.chain.state_keeper.max_allowed_l2_tx_gas_limit.into(),
+        config.chain.state_keeper.max_allowed_tx_gas_limit().into(),
         config.chain.state_keeper.validation_computational_gas_limit,
+        config.chain.state_keeper.parallel_execution_shadow_mode,
+        config
+            .chain
+            .state_keeper
+            .shadow_base_system_contracts_hashes()
+            .map(|(bootloader, default_aa)| BaseSystemContractsHashes {
+                bootloader,
+                default_aa,
+            }),
     );
     let io = MempoolIO::new(
         mempool,
@@ -59,7 +83,24 @@ where
             bootloader: config.chain.state_keeper.bootloader_hash,
             default_aa: config.chain.state_keeper.default_aa_hash,
         },
+        config.chain.state_keeper.record_tx_access_sets,
+        config
+            .chain
+            .state_keeper
+            .record_bootloader_usage_reports
+            .then(|| BootloaderUsageLimits::new(&config.chain.state_keeper)),
+        DeployerAllowlist::new(&config.chain.state_keeper, pool.clone()),
+        config.chain.state_keeper.max_timestamp_drift_from_l1_sec,
+        sealed_miniblocks,
+    );
+
+    // The transaction crashing the previous run must be quarantined before the mempool is loaded.
+    let mut tx_quarantine = TxQuarantine::new(
+        &config.db.state_keeper_db_path,
+        pool.clone(),
+        config.chain.state_keeper.tx_quarantine_crash_threshold(),
     );
+    tx_quarantine.check_previous_run();
 
     let sealer = SealManager::new(config.chain.state_keeper.clone());
     ZkSyncStateKeeper::new(
@@ -68,4 +109,44 @@ where
         Box::new(batch_executor_base),
         sealer,
     )
+    .with_tx_quarantine(tx_quarantine)
+}
+
+/// Records the gas limits of the configured protocol version, which is activated from the next miniblock
+/// if it's new. Panics if the version is older than the latest recorded one, or if the limits of
+/// an already recorded version were changed in the config.
+fn register_protocol_version(config: &StateKeeperConfig, pool: &ConnectionPool) {
+    let mut storage = pool.access_storage_blocking();
+    if let Some(latest_version) = storage
+        .protocol_versions_dal()
+        .get_latest_protocol_version()
+    {
+        assert!(
+            config.protocol_version >= latest_version.id,
+            "Configured protocol_version {} is older than the latest used version {}",
+            config.protocol_version,
+            latest_version.id
+        );
+    }
+
+    let next_miniblock = storage.blocks_dal().get_sealed_miniblock_number() + 1;
+    let configured_version = ProtocolVersion {
+        id: config.protocol_version,
+        activated_at_miniblock: next_miniblock,
+        block_gas_limit: config.block_gas_limit,
+        max_allowed_tx_gas_limit: config.max_allowed_tx_gas_limit(),
+    };
+    let stored_version = storage
+        .protocol_versions_dal()
+        .register_protocol_version(configured_version);
+    assert!(
+        stored_version.block_gas_limit == configured_version.block_gas_limit
+            && stored_version.max_allowed_tx_gas_limit
+                == configured_version.max_allowed_tx_gas_limit,
+        "Gas limits of protocol version {} were changed in the config: {:?} are recorded, while {:?} are configured; \
+         bump protocol_version to change them",
+        config.protocol_version,
+        stored_version,
+        configured_version
+    );
 }
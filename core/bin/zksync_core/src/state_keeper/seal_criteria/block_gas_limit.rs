@@ -0,0 +1,89 @@
+use super::{SealCriterion, SealResolution, StateKeeperConfig};
+use zksync_types::block::BlockGasCount;
+use zksync_types::tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics};
+
+/// Checks whether the L2 gas spent by the transactions of the block fits into the configured block gas limit.
+#[derive(Debug)]
+pub struct BlockGasLimitCriterion;
+
+impl SealCriterion for BlockGasLimitCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_execution_metrics: ExecutionMetrics,
+        tx_execution_metrics: ExecutionMetrics,
+        _block_gas_count: BlockGasCount,
+        _tx_gas_count: BlockGasCount,
+        _block_included_txs_size: usize,
+        _tx_size: usize,
+        _block_writes_metrics: DeduplicatedWritesMetrics,
+        _tx_writes_metrics: DeduplicatedWritesMetrics,
+    ) -> SealResolution {
+        let block_gas_limit = config.block_gas_limit as usize;
+        if tx_execution_metrics.gas_used > block_gas_limit {
+            SealResolution::Unexecutable("Transaction exceeds the block gas limit".into())
+        } else if block_execution_metrics.gas_used > block_gas_limit {
+            SealResolution::ExcludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "block_gas_limit"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockGasLimitCriterion, SealCriterion, SealResolution};
+    use zksync_config::configs::chain::StateKeeperConfig;
+    use zksync_types::tx::tx_execution_info::ExecutionMetrics;
+
+    fn check(
+        config: &StateKeeperConfig,
+        block_gas_used: usize,
+        tx_gas_used: usize,
+    ) -> SealResolution {
+        BlockGasLimitCriterion.should_seal(
+            config,
+            Default::default(),
+            Default::default(),
+            ExecutionMetrics {
+                gas_used: block_gas_used,
+                ..Default::default()
+            },
+            ExecutionMetrics {
+                gas_used: tx_gas_used,
+                ..Default::default()
+            },
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn test_block_gas_limit_seal_criterion() {
+        let config = StateKeeperConfig {
+            block_gas_limit: 1_000_000,
+            ..Default::default()
+        };
+
+        assert_eq!(check(&config, 500_000, 100_000), SealResolution::NoSeal);
+        assert_eq!(check(&config, 1_000_000, 100_000), SealResolution::NoSeal);
+        assert_eq!(
+            check(&config, 1_100_000, 200_000),
+            SealResolution::ExcludeAndSeal
+        );
+        assert_eq!(
+            check(&config, 1_100_000, 1_100_000),
+            SealResolution::Unexecutable("Transaction exceeds the block gas limit".into())
+        );
+    }
+}
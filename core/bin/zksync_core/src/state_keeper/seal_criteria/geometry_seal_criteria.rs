@@ -20,6 +20,10 @@ pub struct RepeatedWritesCriterion;
 pub struct InitialWritesCriterion;
 #[derive(Debug, Default)]
 pub struct MaxCyclesCriterion;
+/// The L2 to L1 logs of the block must also fit into the logs Merkle tree, otherwise the block commitment
+/// can't be generated. Their size is limited by `PubDataBytesCriterion` along with the L2 to L1 messages.
+#[derive(Debug, Default)]
+pub struct L2ToL1LogsCriterion;
 
 trait MetricExtractor {
     const PROM_METRIC_CRITERION_NAME: &'static str;
@@ -117,6 +121,18 @@ impl MetricExtractor for MaxCyclesCriterion {
     }
 }
 
+impl MetricExtractor for L2ToL1LogsCriterion {
+    const PROM_METRIC_CRITERION_NAME: &'static str = "l2_l1_logs";
+
+    fn limit_per_block() -> usize {
+        GEOMETRY_CONFIG.limit_for_l1_messages_merklizer as usize
+    }
+
+    fn extract(metrics: &ExecutionMetrics, _writes: &DeduplicatedWritesMetrics) -> usize {
+        metrics.l2_l1_logs
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_config::configs::chain::StateKeeperConfig;
@@ -127,7 +143,8 @@ mod tests {
     use crate::state_keeper::seal_criteria::{SealCriterion, SealResolution};
 
     use super::{
-        BytecodeHashesCriterion, InitialWritesCriterion, MetricExtractor, RepeatedWritesCriterion,
+        BytecodeHashesCriterion, InitialWritesCriterion, L2ToL1LogsCriterion, MetricExtractor,
+        RepeatedWritesCriterion,
     };
 
     fn get_config() -> StateKeeperConfig {
@@ -337,4 +354,9 @@ mod tests {
     fn initial_max_cycles_seal_criterion() {
         test_scenario_execution_metrics!(MaxCyclesCriterion, cycles_used, u32);
     }
+
+    #[test]
+    fn l2_to_l1_logs_seal_criterion() {
+        test_scenario_execution_metrics!(L2ToL1LogsCriterion, l2_l1_logs, usize);
+    }
 }
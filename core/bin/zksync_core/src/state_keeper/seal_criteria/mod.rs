@@ -19,6 +19,7 @@ use zksync_utils::time::{millis_since, millis_since_epoch};
 
 use super::updates::UpdatesManager;
 
+mod block_gas_limit;
 pub(crate) mod function;
 pub(crate) mod gas;
 mod geometry_seal_criteria;
@@ -100,13 +101,15 @@ pub trait SealCriterion: Debug + Send + 'static {
 
 /// Sealer function that returns a boolean.
 pub type SealerFn = dyn Fn(&UpdatesManager) -> bool + Send;
+/// Batch sealer function that returns the name of the criterion the batch should be sealed by, if any.
+pub type BatchSealerFn = dyn Fn(&UpdatesManager) -> Option<&'static str> + Send;
 
 pub struct SealManager {
     config: StateKeeperConfig,
     /// Primary sealers set that is used to check if batch should be sealed after executing a transaction.
     sealers: Vec<Box<dyn SealCriterion>>,
     /// Unconditional batch sealer, i.e. one that can be used if we should seal the batch *without* executing a tx.
-    unconditional_sealer: Box<SealerFn>,
+    unconditional_sealer: Box<BatchSealerFn>,
     /// Miniblock sealer function used to determine if we should seal the miniblock.
     miniblock_sealer: Box<SealerFn>,
 }
@@ -141,7 +144,7 @@ impl SealManager {
     pub fn custom(
         config: StateKeeperConfig,
         sealers: Vec<Box<dyn SealCriterion>>,
-        unconditional_sealer: Box<SealerFn>,
+        unconditional_sealer: Box<BatchSealerFn>,
         miniblock_sealer: Box<SealerFn>,
     ) -> Self {
         Self {
@@ -156,7 +159,7 @@ impl SealManager {
     pub(crate) fn timeout_and_code_hash_batch_sealer(
         block_commit_deadline_ms: u64,
         base_system_contracts_hashes: BaseSystemContractsHashes,
-    ) -> Box<SealerFn> {
+    ) -> Box<BatchSealerFn> {
         Box::new(move |manager| {
             // Verify timestamp
             let should_seal_timeout =
@@ -192,7 +195,13 @@ impl SealManager {
                 );
             }
 
-            should_seal_timeout || should_seal_code_hashes
+            if should_seal_code_hashes {
+                Some("different_code_hashes")
+            } else if should_seal_timeout {
+                Some("no_txs_timeout")
+            } else {
+                None
+            }
         })
     }
 
@@ -205,6 +214,7 @@ impl SealManager {
         })
     }
 
+    /// Returns the resolution for the batch along with the name of the criterion that has determined it.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn should_seal_l1_batch(
         &self,
@@ -219,8 +229,9 @@ impl SealManager {
         tx_size: usize,
         block_writes_metrics: DeduplicatedWritesMetrics,
         tx_writes_metrics: DeduplicatedWritesMetrics,
-    ) -> SealResolution {
+    ) -> (SealResolution, Option<&'static str>) {
         let mut final_seal_resolution = SealResolution::NoSeal;
+        let mut final_criterion = None;
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
                 &self.config,
@@ -281,17 +292,24 @@ impl SealManager {
                 _ => {}
             }
 
-            final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
+            let stricter_resolution = final_seal_resolution.clone().stricter(seal_resolution);
+            if stricter_resolution != final_seal_resolution {
+                final_criterion = Some(sealer.prom_criterion_name());
+            }
+            final_seal_resolution = stricter_resolution;
         }
-        final_seal_resolution
+        (final_seal_resolution, final_criterion)
     }
 
+    /// Returns the name of the criterion the batch should be sealed by without executing a tx, if any.
     pub(crate) fn should_seal_l1_batch_unconditionally(
         &self,
         updates_manager: &UpdatesManager,
-    ) -> bool {
-        updates_manager.pending_executed_transactions_len() != 0
-            && (self.unconditional_sealer)(updates_manager)
+    ) -> Option<&'static str> {
+        if updates_manager.pending_executed_transactions_len() == 0 {
+            return None;
+        }
+        (self.unconditional_sealer)(updates_manager)
     }
 
     pub(crate) fn should_seal_miniblock(&self, updates_manager: &UpdatesManager) -> bool {
@@ -307,11 +325,13 @@ impl SealManager {
         let sealers: Vec<Box<dyn SealCriterion>> = vec![
             Box::new(slots::SlotsCriterion),
             Box::new(gas::GasCriterion),
+            Box::new(block_gas_limit::BlockGasLimitCriterion),
             Box::new(pubdata_bytes::PubDataBytesCriterion),
             Box::new(geometry_seal_criteria::BytecodeHashesCriterion),
             Box::new(geometry_seal_criteria::InitialWritesCriterion),
             Box::new(geometry_seal_criteria::RepeatedWritesCriterion),
             Box::new(geometry_seal_criteria::MaxCyclesCriterion),
+            Box::new(geometry_seal_criteria::L2ToL1LogsCriterion),
             Box::new(tx_encoding_size::TxEncodingSizeCriterion),
         ];
         sealers
@@ -374,6 +394,7 @@ mod tests {
                 },
                 gas_refunded: 0,
                 operator_suggested_refund: 0,
+                refund_breakdown: Default::default(),
             },
             Default::default(),
             Default::default(),
@@ -408,4 +429,77 @@ mod tests {
             "Non-empty miniblock with too recent timestamp shouldn't be sealed"
         );
     }
+
+    #[derive(Debug)]
+    struct ExcludeAfterCriterion(usize);
+
+    impl SealCriterion for ExcludeAfterCriterion {
+        fn should_seal(
+            &self,
+            _config: &StateKeeperConfig,
+            _block_open_timestamp_ms: u128,
+            tx_count: usize,
+            _block_execution_metrics: ExecutionMetrics,
+            _tx_execution_metrics: ExecutionMetrics,
+            _block_gas_count: BlockGasCount,
+            _tx_gas_count: BlockGasCount,
+            _block_included_txs_size: usize,
+            _tx_size: usize,
+            _block_writes_metrics: DeduplicatedWritesMetrics,
+            _tx_writes_metrics: DeduplicatedWritesMetrics,
+        ) -> SealResolution {
+            if tx_count > self.0 {
+                SealResolution::ExcludeAndSeal
+            } else {
+                SealResolution::NoSeal
+            }
+        }
+
+        fn prom_criterion_name(&self) -> &'static str {
+            "exclude_after"
+        }
+    }
+
+    /// The batch is attributed to the criterion that has determined the final resolution.
+    #[test]
+    fn seal_criterion_of_the_stricter_resolution() {
+        let config = StateKeeperConfig {
+            transaction_slots: 2,
+            ..Default::default()
+        };
+        let sealer = SealManager::custom(
+            config,
+            vec![
+                Box::new(slots::SlotsCriterion),
+                Box::new(ExcludeAfterCriterion(2)),
+            ],
+            Box::new(|_| None),
+            Box::new(|_| false),
+        );
+        let should_seal = |tx_count| {
+            sealer.should_seal_l1_batch(
+                1,
+                0,
+                tx_count,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                0,
+                0,
+                Default::default(),
+                Default::default(),
+            )
+        };
+
+        assert_eq!(should_seal(1), (SealResolution::NoSeal, None));
+        assert_eq!(
+            should_seal(2),
+            (SealResolution::IncludeAndSeal, Some("slots"))
+        );
+        assert_eq!(
+            should_seal(3),
+            (SealResolution::ExcludeAndSeal, Some("exclude_after"))
+        );
+    }
 }
@@ -0,0 +1,53 @@
+//! In-process stream of the miniblocks sealed by the state keeper.
+//!
+//! The WS API running in the same process as the state keeper subscribes to the stream, so that `newHeads`
+//! subscribers are notified as soon as a miniblock is sealed, without polling Postgres for new miniblocks.
+//! Miniblocks are published after the DB transaction persisting them is committed; note that the API
+//! may still read from a replica lagging behind the master.
+//!
+//! On the external node, the miniblocks fetched from the main node are published by the external IO
+//! once the state keeper has re-executed them.
+
+use tokio::sync::broadcast;
+
+use zksync_types::block::MiniblockHeader;
+use zksync_types::H256;
+
+/// Number of miniblocks buffered for a subscriber. A subscriber lagging behind by more miniblocks
+/// misses them and has to catch up from the DB.
+const CHANNEL_CAPACITY: usize = 128;
+
+/// Summary of a sealed miniblock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealedMiniblock {
+    pub header: MiniblockHeader,
+    /// Hashes of the transactions executed in the miniblock, in the execution order.
+    pub tx_hashes: Vec<H256>,
+}
+
+pub type SealedMiniblocksReceiver = broadcast::Receiver<SealedMiniblock>;
+
+/// Publishes the sealed miniblocks to the subscribers.
+#[derive(Debug, Clone)]
+pub struct SealedMiniblocksSender(broadcast::Sender<SealedMiniblock>);
+
+impl Default for SealedMiniblocksSender {
+    fn default() -> Self {
+        Self(broadcast::channel(CHANNEL_CAPACITY).0)
+    }
+}
+
+impl SealedMiniblocksSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self) -> SealedMiniblocksReceiver {
+        self.0.subscribe()
+    }
+
+    pub(crate) fn send(&self, miniblock: SealedMiniblock) {
+        // It's fine if there are no subscribers, e.g. if the WS API isn't run in the same process.
+        let _ = self.0.send(miniblock);
+    }
+}
@@ -51,7 +51,7 @@ fn sealed_by_number_of_txs() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -77,7 +77,7 @@ fn sealed_by_gas() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(GasCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -129,7 +129,7 @@ fn sealed_by_gas_then_by_num_tx() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(GasCriterion), Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -167,7 +167,7 @@ fn batch_sealed_before_miniblock_does() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 3),
     );
 
@@ -197,7 +197,7 @@ fn basic_flow() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -219,7 +219,7 @@ fn rejected_tx() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -244,7 +244,7 @@ fn bootloader_tip_out_of_gas_flow() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -328,7 +328,7 @@ fn pending_batch_is_applied() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -375,7 +375,11 @@ fn unconditional_sealing() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(move |_| batch_seal_trigger_checker.load(Ordering::Relaxed)),
+        Box::new(move |_| {
+            batch_seal_trigger_checker
+                .load(Ordering::Relaxed)
+                .then_some("trigger")
+        }),
         Box::new(move |upd_manager| {
             if upd_manager.pending_executed_transactions_len() != 0
                 && start.elapsed() >= seal_miniblock_after
@@ -407,7 +411,7 @@ fn miniblock_timestamp_after_pending_batch() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -449,7 +453,7 @@ fn time_is_monotonic() {
     let sealer = SealManager::custom(
         config,
         vec![Box::new(SlotsCriterion)],
-        Box::new(|_| false),
+        Box::new(|_| None),
         Box::new(|updates| updates.miniblock.executed_transactions.len() == 1),
     );
 
@@ -221,6 +221,7 @@ pub(crate) fn successful_exec() -> TxExecutionResult {
             result: partial_execution_result(),
             gas_refunded: 0,
             operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
         },
         vec![],
     )));
@@ -250,6 +251,7 @@ pub(crate) fn bootloader_tip_out_of_gas() -> TxExecutionResult {
             result: partial_execution_result(),
             gas_refunded: 0,
             operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
         },
         vec![],
     )));
@@ -471,6 +473,7 @@ impl TestBatchExecutor {
                     // It's OK to not update `last_executed_tx`, since state keeper never should rollback more than 1
                     // tx in a row, and it's going to cause a panic anyway.
                 }
+                Command::StartNextMiniblock => {}
                 Command::FinishBatch(resp) => {
                     // Blanket result, it doesn't really matter.
                     let result = VmBlockResult {
@@ -0,0 +1,235 @@
+//! Detection of the transactions crashing the state keeper ("poison" transactions).
+//!
+//! The state keeper keeps the transaction it's executing in memory, so the transactions aren't written anywhere
+//! in the normal operation. If the state keeper panics while a transaction is executed (including the panics
+//! of the batch executor, which are propagated to the state keeper), the panic hook writes its hash into
+//! the in-flight marker file next to the RocksDB cache. The panics outside of the transaction execution aren't
+//! attributed to any transaction. If the marker is present on startup, the previous run has crashed while
+//! executing the transaction, and the crash is recorded in the DB.
+//! Once the same transaction crashes the state keeper the configured number of times in a row, it's quarantined:
+//! an L2 transaction that isn't included into a miniblock yet is excluded from the mempool until it's released
+//! via the `admin` API namespace. L1 transactions and the transactions of the pending L1 batch cannot be skipped
+//! without breaking the protocol, so for them the crash loop is only reported.
+//!
+//! Witness generation is not covered: its jobs are tied to L1 batches rather than to separate transactions,
+//! and a failing job is already given up after `max_attempts`.
+
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+
+use serde::{Deserialize, Serialize};
+
+use zksync_dal::ConnectionPool;
+use zksync_types::{Transaction, H256};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct InFlightTx {
+    hash: H256,
+    is_l1: bool,
+    /// Whether the transaction was re-executed from the pending L1 batch.
+    reexecuted: bool,
+}
+
+impl InFlightTx {
+    fn can_be_excluded(&self) -> bool {
+        !self.is_l1 && !self.reexecuted
+    }
+}
+
+#[derive(Debug)]
+struct InFlightMarker {
+    path: PathBuf,
+}
+
+impl InFlightMarker {
+    fn new(state_keeper_db_path: &str) -> Self {
+        Self {
+            path: Path::new(state_keeper_db_path).with_extension("in_flight_tx"),
+        }
+    }
+
+    fn write(&self, tx: &InFlightTx) -> io::Result<()> {
+        fs::write(&self.path, serde_json::to_vec(tx).unwrap())
+    }
+
+    fn take(&self) -> io::Result<Option<InFlightTx>> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        fs::remove_file(&self.path)?;
+        // A marker torn by the crash is ignored: the crash cannot be attributed to a transaction.
+        Ok(serde_json::from_slice(&bytes).ok())
+    }
+}
+
+/// Transaction executed by the state keeper, together with the thread of the state keeper.
+#[derive(Debug)]
+struct ExecutingTx {
+    tx: InFlightTx,
+    thread_id: ThreadId,
+}
+
+/// Tracks the transaction executed by the state keeper, so that the crash loops caused by it are detected.
+#[derive(Debug)]
+pub(crate) struct TxQuarantine {
+    marker: Arc<InFlightMarker>,
+    executing: Arc<Mutex<Option<ExecutingTx>>>,
+    pool: ConnectionPool,
+    crash_threshold: u32,
+    /// Transaction which has crashed the previous run. Its crashes are forgotten once it's executed successfully.
+    suspect: Option<H256>,
+}
+
+impl TxQuarantine {
+    /// Creates the quarantine and installs the panic hook writing the in-flight marker. The hook is chained
+    /// with the previously installed one, so it must be called after the logger is initialized.
+    pub fn new(state_keeper_db_path: &str, pool: ConnectionPool, crash_threshold: u32) -> Self {
+        let marker = Arc::new(InFlightMarker::new(state_keeper_db_path));
+        let executing = Arc::new(Mutex::new(None));
+        Self::install_panic_hook(marker.clone(), executing.clone());
+        Self {
+            marker,
+            executing,
+            pool,
+            crash_threshold,
+            suspect: None,
+        }
+    }
+
+    fn install_panic_hook(marker: Arc<InFlightMarker>, executing: Arc<Mutex<Option<ExecutingTx>>>) {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic_info| {
+            // `try_lock()` is used so that the hook never blocks; the lock is only held for an assignment.
+            if let Ok(executing) = executing.try_lock() {
+                match executing.as_ref() {
+                    Some(executing) if executing.thread_id == thread::current().id() => {
+                        if let Err(err) = marker.write(&executing.tx) {
+                            vlog::warn!(
+                                "Failed to write the in-flight transaction marker: {}",
+                                err
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            previous_hook(panic_info);
+        }));
+    }
+
+    /// Checks whether the previous run has crashed during the transaction execution, and quarantines the transaction
+    /// if it has crashed the state keeper too many times. Must be called before the mempool is loaded.
+    pub fn check_previous_run(&mut self) {
+        let in_flight_tx = match self.marker.take() {
+            Ok(Some(tx)) => tx,
+            Ok(None) => return,
+            Err(err) => {
+                vlog::warn!("Failed to read the in-flight transaction marker: {}", err);
+                return;
+            }
+        };
+
+        let mut storage = self.pool.access_storage_blocking();
+        let mut quarantine_dal = storage.quarantined_transactions_dal();
+        let crash_count = quarantine_dal.record_crash(&in_flight_tx.hash);
+        metrics::increment_counter!("server.state_keeper.tx_crashes");
+        vlog::warn!(
+            "State keeper has crashed while executing transaction {:?} ({} time(s) in a row)",
+            in_flight_tx.hash,
+            crash_count
+        );
+
+        if crash_count < self.crash_threshold {
+            self.suspect = Some(in_flight_tx.hash);
+        } else if in_flight_tx.can_be_excluded() {
+            quarantine_dal.quarantine(&in_flight_tx.hash);
+            metrics::increment_counter!("server.state_keeper.poison_transactions", "action" => "quarantined");
+            vlog::error!(
+                "Transaction {:?} has crashed the state keeper {} times and is quarantined until reviewed",
+                in_flight_tx.hash,
+                crash_count
+            );
+        } else {
+            self.suspect = Some(in_flight_tx.hash);
+            metrics::increment_counter!("server.state_keeper.poison_transactions", "action" => "reported");
+            vlog::error!(
+                "Transaction {:?} has crashed the state keeper {} times, but it cannot be skipped \
+                 (is L1: {}, from the pending L1 batch: {}); manual intervention is required",
+                in_flight_tx.hash,
+                crash_count,
+                in_flight_tx.is_l1,
+                in_flight_tx.reexecuted
+            );
+        }
+    }
+
+    /// Must be called right before the transaction is passed to the batch executor.
+    pub fn start_execution(&self, tx: &Transaction, reexecuted: bool) {
+        let in_flight_tx = InFlightTx {
+            hash: tx.hash(),
+            is_l1: tx.is_l1(),
+            reexecuted,
+        };
+        *self.executing.lock().unwrap() = Some(ExecutingTx {
+            tx: in_flight_tx,
+            thread_id: thread::current().id(),
+        });
+    }
+
+    /// Must be called once the batch executor has returned the execution result, whatever it is.
+    pub fn finish_execution(&mut self, tx: &Transaction) {
+        *self.executing.lock().unwrap() = None;
+        if self.suspect == Some(tx.hash()) {
+            self.suspect = None;
+            self.pool
+                .access_storage_blocking()
+                .quarantined_transactions_dal()
+                .clear_crashes(&tx.hash());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_flight_marker_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db_path = dir.path().join("state_keeper");
+        let marker = InFlightMarker::new(db_path.to_str().unwrap());
+        assert_eq!(marker.take().unwrap(), None);
+
+        let tx = InFlightTx {
+            hash: H256::repeat_byte(1),
+            is_l1: false,
+            reexecuted: false,
+        };
+        marker.write(&tx).unwrap();
+        assert_eq!(marker.take().unwrap(), Some(tx.clone()));
+        // The marker is consumed by the startup check.
+        assert_eq!(marker.take().unwrap(), None);
+
+        fs::write(&marker.path, b"{\"hash\":").unwrap();
+        assert_eq!(marker.take().unwrap(), None);
+        assert!(!marker.path.exists());
+    }
+
+    #[test]
+    fn only_new_l2_transactions_can_be_excluded() {
+        let tx = |is_l1, reexecuted| InFlightTx {
+            hash: H256::zero(),
+            is_l1,
+            reexecuted,
+        };
+        assert!(tx(false, false).can_be_excluded());
+        assert!(!tx(true, false).can_be_excluded());
+        assert!(!tx(false, true).can_be_excluded());
+    }
+}
@@ -21,6 +21,13 @@ impl MempoolGuard {
             .has_next(filter)
     }
 
+    pub fn has_next_priority_op(&self) -> bool {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .has_next_priority_op()
+    }
+
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
         self.0
             .lock()
@@ -35,6 +42,13 @@ impl MempoolGuard {
             .rollback(rejected);
     }
 
+    pub fn remove_expired(&mut self, timestamp: u64) -> usize {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .remove_expired(timestamp)
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         self.0
             .lock()
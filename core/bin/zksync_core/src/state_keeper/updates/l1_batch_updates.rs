@@ -13,6 +13,8 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    /// Name of the criterion the batch is sealed by, set by the state keeper once it decides to seal the batch.
+    pub seal_criterion: Option<&'static str>,
 }
 
 impl L1BatchUpdates {
@@ -23,6 +25,7 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            seal_criterion: None,
         }
     }
 
@@ -80,6 +83,7 @@ mod tests {
                 },
                 gas_refunded: 0,
                 operator_suggested_refund: 0,
+                refund_breakdown: Default::default(),
             },
             Default::default(),
             Default::default(),
@@ -90,6 +90,7 @@ impl MiniblockUpdates {
             execution_status: tx_execution_result.status,
             refunded_gas: tx_execution_result.gas_refunded,
             operator_suggested_refund: tx_execution_result.operator_suggested_refund,
+            refund_breakdown: tx_execution_result.refund_breakdown,
             compressed_bytecodes,
         });
 
@@ -143,6 +144,7 @@ mod tests {
                 },
                 gas_refunded: 0,
                 operator_suggested_refund: 0,
+                refund_breakdown: Default::default(),
             },
             Default::default(),
             Default::default(),
@@ -1,10 +1,13 @@
-use vm::{vm::VmTxExecutionResult, vm_with_bootloader::BlockContextMode};
+use vm::{
+    vm::VmTxExecutionResult,
+    vm_with_bootloader::{derive_base_fee_and_gas_per_pubdata, BlockContextMode},
+};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
     block::BlockGasCount,
     storage_writes_deduplicator::StorageWritesDeduplicator,
     tx::tx_execution_info::{ExecutionMetrics, VmExecutionLogs},
-    Transaction,
+    Address, Transaction,
 };
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -25,6 +28,8 @@ pub struct UpdatesManager {
     l1_gas_price: u64,
     fair_l2_gas_price: u64,
     base_fee_per_gas: u64,
+    gas_per_pubdata: u64,
+    fee_account_address: Address,
     base_system_contract_hashes: BaseSystemContractsHashes,
     pub l1_batch: L1BatchUpdates,
     pub miniblock: MiniblockUpdates,
@@ -38,11 +43,16 @@ impl UpdatesManager {
     ) -> Self {
         let batch_timestamp = block_context.timestamp();
         let context = block_context.inner_block_context().context;
+        // The VM derives the gas per pubdata byte from the prices the same way when it's initialized.
+        let (_, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(context.l1_gas_price, context.fair_l2_gas_price);
         Self {
             batch_timestamp,
             l1_gas_price: context.l1_gas_price,
             fair_l2_gas_price: context.fair_l2_gas_price,
             base_fee_per_gas: block_context.inner_block_context().base_fee,
+            gas_per_pubdata,
+            fee_account_address: context.operator_address,
             base_system_contract_hashes,
             l1_batch: L1BatchUpdates::new(),
             miniblock: MiniblockUpdates::new(batch_timestamp),
@@ -70,6 +80,14 @@ impl UpdatesManager {
         self.base_fee_per_gas
     }
 
+    pub(crate) fn gas_per_pubdata(&self) -> u64 {
+        self.gas_per_pubdata
+    }
+
+    pub(crate) fn fee_account_address(&self) -> Address {
+        self.fee_account_address
+    }
+
     pub(crate) fn extend_from_executed_transaction(
         &mut self,
         tx: &Transaction,
@@ -184,6 +202,7 @@ mod tests {
                 },
                 gas_refunded: 0,
                 operator_suggested_refund: 0,
+                refund_breakdown: Default::default(),
             },
             vec![],
             new_block_gas_count(),
@@ -1,14 +1,19 @@
 use std::time::Duration;
 
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
-use zksync_types::{Address, L1BatchNumber, MiniblockNumber, Transaction};
+use zksync_types::{
+    tx::execution_digest::{execution_outcomes_digest, EXECUTION_OUTCOMES_SERIALIZATION_VERSION},
+    Address, L1BatchNumber, MiniblockNumber, Transaction, H256,
+};
 
 use crate::state_keeper::{
     io::{
         common::{l1_batch_params, poll_until},
+        seal_logic::sealed_miniblock_summary,
         L1BatchParams, PendingBatchData, StateKeeperIO,
     },
-    seal_criteria::SealerFn,
+    seal_criteria::{BatchSealerFn, SealerFn},
+    sealed_miniblocks::SealedMiniblocksSender,
     updates::UpdatesManager,
 };
 
@@ -32,7 +37,10 @@ impl ExternalNodeSealer {
     }
 
     fn should_seal_miniblock(&self) -> bool {
-        let res = matches!(self.actions.peek_action(), Some(SyncAction::SealMiniblock));
+        let res = matches!(
+            self.actions.peek_action(),
+            Some(SyncAction::SealMiniblock { .. })
+        );
         vlog::info!("Asked if should seal the miniblock. The answer is {res}");
         res
     }
@@ -43,8 +51,9 @@ impl ExternalNodeSealer {
         res
     }
 
-    pub fn into_unconditional_batch_seal_criterion(self) -> Box<SealerFn> {
-        Box::new(move |_| self.should_seal_batch())
+    /// The batch is sealed as the main node has sealed it, so the actual criterion is not known.
+    pub fn into_unconditional_batch_seal_criterion(self) -> Box<BatchSealerFn> {
+        Box::new(move |_| self.should_seal_batch().then_some("main_node"))
     }
 
     pub fn into_miniblock_seal_criterion(self) -> Box<SealerFn> {
@@ -65,15 +74,58 @@ pub struct ExternalIO {
     current_l1_batch_number: L1BatchNumber,
     current_miniblock_number: MiniblockNumber,
     actions: ActionQueue,
+
+    // Publishes the miniblocks replicated from the main node to the in-process subscribers (e.g., the WS API).
+    sealed_miniblocks: SealedMiniblocksSender,
 }
 
 impl ExternalIO {
-    pub fn new(fee_account: Address, actions: ActionQueue) -> Self {
+    pub fn new(
+        fee_account: Address,
+        current_l1_batch_number: L1BatchNumber,
+        current_miniblock_number: MiniblockNumber,
+        actions: ActionQueue,
+        sealed_miniblocks: SealedMiniblocksSender,
+    ) -> Self {
         Self {
             fee_account,
-            current_l1_batch_number: L1BatchNumber(1),
-            current_miniblock_number: MiniblockNumber(1),
+            current_l1_batch_number,
+            current_miniblock_number,
             actions,
+            sealed_miniblocks,
+        }
+    }
+
+    /// Compares the digest of the local execution outcomes with the one reported by the main node.
+    /// Digests serialized with a different version (or with an unknown one) are not comparable,
+    /// so the check is skipped for them rather than reporting a divergence.
+    fn check_execution_digest(
+        &self,
+        updates_manager: &UpdatesManager,
+        expected_digest: H256,
+        digest_version: Option<u8>,
+    ) {
+        if digest_version != Some(EXECUTION_OUTCOMES_SERIALIZATION_VERSION) {
+            metrics::counter!("external_node.execution_digest_version_mismatch", 1);
+            vlog::warn!(
+                "Main node reported the execution digest of miniblock {} with serialization version {:?}, \
+                 while the local version is {}; skipping the comparison",
+                self.current_miniblock_number,
+                digest_version,
+                EXECUTION_OUTCOMES_SERIALIZATION_VERSION
+            );
+            return;
+        }
+
+        let local_digest =
+            execution_outcomes_digest(&updates_manager.miniblock.executed_transactions);
+        if local_digest != expected_digest {
+            metrics::counter!("external_node.execution_digest_mismatch", 1);
+            panic!(
+                "Execution of miniblock {} diverged from the main node: digest of the local outcomes is {:?}, \
+                 while the main node reported {:?}",
+                self.current_miniblock_number, local_digest, expected_digest
+            );
         }
     }
 }
@@ -101,6 +153,7 @@ impl StateKeeperIO for ExternalIO {
                     l1_gas_price,
                     l2_fair_gas_price,
                     base_system_contracts_hashes,
+                    operator_address,
                 } => {
                     assert_eq!(
                         number, self.current_l1_batch_number,
@@ -108,7 +161,7 @@ impl StateKeeperIO for ExternalIO {
                     );
                     Some(l1_batch_params(
                         number,
-                        self.fee_account,
+                        operator_address.unwrap_or(self.fee_account),
                         timestamp,
                         Default::default(),
                         l1_gas_price,
@@ -184,14 +237,24 @@ impl StateKeeperIO for ExternalIO {
         );
     }
 
-    fn seal_miniblock(&mut self, _updates_manager: &UpdatesManager) {
-        match self.actions.pop_action() {
-            Some(SyncAction::SealMiniblock) => {}
+    fn seal_miniblock(&mut self, updates_manager: &UpdatesManager) {
+        let (expected_digest, digest_version) = match self.actions.pop_action() {
+            Some(SyncAction::SealMiniblock {
+                execution_digest,
+                execution_digest_version,
+            }) => (execution_digest, execution_digest_version),
             other => panic!(
                 "State keeper requested to seal miniblock, but the next action is {:?}",
                 other
             ),
         };
+        if let Some(expected_digest) = expected_digest {
+            self.check_execution_digest(updates_manager, expected_digest, digest_version);
+        }
+        self.sealed_miniblocks.send(sealed_miniblock_summary(
+            self.current_miniblock_number,
+            updates_manager,
+        ));
         self.current_miniblock_number += 1;
         vlog::info!("Miniblock {} is sealed", self.current_miniblock_number);
     }
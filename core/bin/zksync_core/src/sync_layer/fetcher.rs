@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use zksync_types::{explorer_api::BlockDetails, L1BatchNumber, MiniblockNumber};
 use zksync_web3_decl::{
@@ -9,7 +9,10 @@ use zksync_web3_decl::{
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
-use crate::sync_layer::sync_action::{BatchStatusChange, SyncAction};
+use crate::sync_layer::{
+    handshake::{SyncSession, HANDSHAKE_INTERVAL},
+    sync_action::{BatchStatusChange, SyncAction},
+};
 
 use super::sync_action::ActionQueue;
 
@@ -19,8 +22,11 @@ const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
 /// Structure responsible for fetching batches and miniblock data from the main node.
 #[derive(Debug)]
 pub struct MainNodeFetcher {
-    main_node_url: String,
     client: HttpClient,
+    /// Outcome of the last handshake with the main node, including the negotiated sync protocol version.
+    session: SyncSession,
+    /// Time of the last handshake, or `None` if it must be repeated, e.g. after a reconnection.
+    last_handshake_at: Option<Instant>,
     current_l1_batch: L1BatchNumber,
     current_miniblock: MiniblockNumber,
 
@@ -33,7 +39,7 @@ pub struct MainNodeFetcher {
 
 impl MainNodeFetcher {
     pub fn new(
-        main_node_url: &str,
+        session: SyncSession,
         current_l1_batch: L1BatchNumber,
         current_miniblock: MiniblockNumber,
         last_executed_l1_batch: L1BatchNumber,
@@ -41,11 +47,12 @@ impl MainNodeFetcher {
         last_committed_l1_batch: L1BatchNumber,
         actions: ActionQueue,
     ) -> Self {
-        let client = Self::build_client(main_node_url);
+        let client = Self::build_client(&session.main_node_url);
 
         Self {
-            main_node_url: main_node_url.into(),
             client,
+            session,
+            last_handshake_at: Some(Instant::now()),
             current_l1_batch,
             current_miniblock,
 
@@ -65,9 +72,14 @@ impl MainNodeFetcher {
 
     pub async fn run(mut self) {
         vlog::info!(
-            "Starting the fetcher routine. Initial miniblock: {}, initial l1 batch: {}",
+            "Starting the fetcher routine. Initial miniblock: {}, initial l1 batch: {}, sync protocol version: {}",
             self.current_miniblock,
-            self.current_l1_batch
+            self.current_l1_batch,
+            self.session.protocol_version
+        );
+        metrics::gauge!(
+            "external_node.sync_protocol_version",
+            self.session.protocol_version as f64
         );
         // Run the main routine and reconnect upon the network errors.
         loop {
@@ -87,9 +99,11 @@ impl MainNodeFetcher {
 
     async fn reconnect(&mut self) {
         loop {
-            self.client = Self::build_client(&self.main_node_url);
+            self.client = Self::build_client(&self.session.main_node_url);
             if self.client.chain_id().await.is_ok() {
                 vlog::info!("Reconnected");
+                // The main node may have been upgraded in the meantime.
+                self.last_handshake_at = None;
                 break;
             }
             vlog::warn!(
@@ -99,8 +113,40 @@ impl MainNodeFetcher {
         }
     }
 
+    /// Repeats the handshake with the main node, so that the sync protocol version is renegotiated
+    /// after the main node is upgraded.
+    async fn repeat_handshake(&mut self) -> RpcResult<()> {
+        let session = SyncSession::establish(
+            &self.client,
+            &self.session.main_node_url,
+            self.session.local.clone(),
+        )
+        .await?;
+        if session.protocol_version != self.session.protocol_version {
+            vlog::info!(
+                "Sync protocol version changed from {} to {}",
+                self.session.protocol_version,
+                session.protocol_version
+            );
+            metrics::gauge!(
+                "external_node.sync_protocol_version",
+                session.protocol_version as f64
+            );
+        }
+        self.session = session;
+        self.last_handshake_at = Some(Instant::now());
+        Ok(())
+    }
+
     async fn run_inner(&mut self) -> RpcResult<()> {
         loop {
+            let handshake_due = self
+                .last_handshake_at
+                .map_or(true, |at| at.elapsed() >= HANDSHAKE_INTERVAL);
+            if handshake_due {
+                self.repeat_handshake().await?;
+            }
+
             let mut progressed = false;
 
             if self.actions.has_action_capacity() {
@@ -149,6 +195,7 @@ impl MainNodeFetcher {
                 l1_gas_price: miniblock_header.l1_gas_price,
                 l2_fair_gas_price: miniblock_header.l2_fair_gas_price,
                 base_system_contracts_hashes: miniblock_header.base_system_contracts_hashes,
+                operator_address: miniblock_header.operator_address,
             });
 
             self.current_l1_batch += 1;
@@ -168,7 +215,10 @@ impl MainNodeFetcher {
             .into_iter()
             .map(|tx| SyncAction::Tx(Box::new(tx)));
         new_actions.extend(miniblock_txs);
-        new_actions.push(SyncAction::SealMiniblock);
+        new_actions.push(SyncAction::SealMiniblock {
+            execution_digest: miniblock_header.execution_digest,
+            execution_digest_version: miniblock_header.execution_digest_version,
+        });
 
         // Check if this was the last miniblock in the batch.
         // If we will receive `None` here, it would mean that it's the currently open batch and it was not sealed
@@ -0,0 +1,267 @@
+//! Handshake between an external node and the main node. Nodes exchange their identities, and the external node
+//! refuses to sync from a main node of another chain or with no sync protocol version in common, rather than
+//! failing on the data it can't process in the middle of the sync.
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+use zksync_dal::StorageProcessor;
+use zksync_types::{api::NodeIdentity, L1BatchNumber, L2ChainId, H256};
+use zksync_web3_decl::{
+    jsonrpsee::{
+        core::{Error as RpcError, RpcResult},
+        http_client::{HttpClient, HttpClientBuilder},
+        types::error::{CallError, ErrorCode},
+    },
+    namespaces::EnNamespaceClient,
+};
+
+/// Version of the protocol the external nodes sync with the main node by. Must be bumped on any change
+/// of the sync-related API that the older nodes can't handle.
+pub const SYNC_PROTOCOL_VERSION: u16 = 1;
+/// Oldest sync protocol version the node is still able to serve and to sync with.
+pub const MIN_SUPPORTED_SYNC_PROTOCOL_VERSION: u16 = 1;
+/// Sync protocol version of the main nodes predating the handshake.
+const INITIAL_SYNC_PROTOCOL_VERSION: u16 = 1;
+/// Interval after which the external node repeats the handshake to notice the upgrades of the main node.
+pub const HANDSHAKE_INTERVAL: Duration = Duration::from_secs(300);
+/// Max length of the peer software version reported in the metrics.
+const MAX_REPORTED_VERSION_LEN: usize = 32;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum HandshakeError {
+    #[error("chain ID mismatch: local {local}, peer {peer}")]
+    ChainIdMismatch { local: L2ChainId, peer: L2ChainId },
+    #[error("genesis root hash mismatch: local {local:?}, peer {peer:?}")]
+    GenesisMismatch { local: H256, peer: H256 },
+    #[error(
+        "no common sync protocol version: local node supports {}..={}, peer supports {}..={}",
+        .local.0, .local.1, .peer.0, .peer.1
+    )]
+    NoCommonProtocolVersion { local: (u16, u16), peer: (u16, u16) },
+}
+
+/// Returns the identity of this node. The genesis L1 batch must already be in the database.
+pub fn local_identity(storage: &mut StorageProcessor<'_>, chain_id: L2ChainId) -> NodeIdentity {
+    let genesis_root_hash = storage
+        .blocks_dal()
+        .get_block_state_root(L1BatchNumber(0))
+        .expect("genesis L1 batch doesn't have a root hash");
+    NodeIdentity {
+        chain_id,
+        genesis_root_hash,
+        min_protocol_version: MIN_SUPPORTED_SYNC_PROTOCOL_VERSION,
+        max_protocol_version: SYNC_PROTOCOL_VERSION,
+        software_version: env!("CARGO_PKG_VERSION").to_owned(),
+    }
+}
+
+/// Checks whether the nodes can sync with each other and returns the highest sync protocol version
+/// supported by both of them.
+pub fn check_compatibility(
+    local: &NodeIdentity,
+    peer: &NodeIdentity,
+) -> Result<u16, HandshakeError> {
+    if local.chain_id != peer.chain_id {
+        return Err(HandshakeError::ChainIdMismatch {
+            local: local.chain_id,
+            peer: peer.chain_id,
+        });
+    }
+    if local.genesis_root_hash != peer.genesis_root_hash {
+        return Err(HandshakeError::GenesisMismatch {
+            local: local.genesis_root_hash,
+            peer: peer.genesis_root_hash,
+        });
+    }
+
+    let min_version = local.min_protocol_version.max(peer.min_protocol_version);
+    let max_version = local.max_protocol_version.min(peer.max_protocol_version);
+    if min_version > max_version {
+        return Err(HandshakeError::NoCommonProtocolVersion {
+            local: (local.min_protocol_version, local.max_protocol_version),
+            peer: (peer.min_protocol_version, peer.max_protocol_version),
+        });
+    }
+    Ok(max_version)
+}
+
+/// Software version of the peer to be used as a metric label. The version is provided by the peer,
+/// so it's truncated and stripped of unexpected characters to keep the labels reasonably sized.
+pub(crate) fn reported_software_version(peer: &NodeIdentity) -> String {
+    peer.software_version
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '+'))
+        .take(MAX_REPORTED_VERSION_LEN)
+        .collect()
+}
+
+/// Outcome of the handshake with the main node, kept by the external node for the sync session.
+#[derive(Debug, Clone)]
+pub struct SyncSession {
+    pub main_node_url: String,
+    pub local: NodeIdentity,
+    /// Identity of the main node, or `None` if the main node doesn't support the handshake yet.
+    pub main_node: Option<NodeIdentity>,
+    /// Highest sync protocol version supported by both nodes, which the node syncs by.
+    pub protocol_version: u16,
+}
+
+impl SyncSession {
+    /// Exchanges the identities with the main node. A main node that doesn't support the handshake yet
+    /// is assumed to support only the initial sync protocol version.
+    /// Panics if the main node is incompatible with this node.
+    pub async fn establish(
+        client: &HttpClient,
+        main_node_url: &str,
+        local: NodeIdentity,
+    ) -> RpcResult<Self> {
+        let main_node = match client.handshake(local.clone()).await {
+            Ok(main_node) => Some(main_node),
+            Err(RpcError::Call(CallError::Custom(err)))
+                if err.code() == ErrorCode::MethodNotFound.code() =>
+            {
+                vlog::warn!(
+                    "Main node at {} doesn't support the handshake, assuming that it supports \
+                     sync protocol version {} only",
+                    main_node_url,
+                    INITIAL_SYNC_PROTOCOL_VERSION
+                );
+                None
+            }
+            Err(err) => return Err(err),
+        };
+
+        let result = match &main_node {
+            Some(main_node) => check_compatibility(&local, main_node),
+            None => check_compatibility_with_initial_version(&local),
+        };
+        metrics::gauge!(
+            "external_node.main_node_version",
+            1.0,
+            "software_version" => main_node
+                .as_ref()
+                .map_or_else(|| "unknown".to_owned(), reported_software_version),
+            "max_protocol_version" => main_node
+                .as_ref()
+                .map_or(INITIAL_SYNC_PROTOCOL_VERSION, |main_node| main_node.max_protocol_version)
+                .to_string(),
+            "compatible" => result.is_ok().to_string()
+        );
+        match result {
+            Ok(protocol_version) => {
+                vlog::info!(
+                    "Handshake with the main node (software version {}) succeeded, sync protocol version: {}",
+                    main_node
+                        .as_ref()
+                        .map_or("unknown", |main_node| &main_node.software_version),
+                    protocol_version
+                );
+                Ok(Self {
+                    main_node_url: main_node_url.to_owned(),
+                    local,
+                    main_node,
+                    protocol_version,
+                })
+            }
+            Err(err) => panic!(
+                "Main node at {} is incompatible with this node: {}",
+                main_node_url, err
+            ),
+        }
+    }
+}
+
+fn check_compatibility_with_initial_version(local: &NodeIdentity) -> Result<u16, HandshakeError> {
+    if local.min_protocol_version > INITIAL_SYNC_PROTOCOL_VERSION {
+        return Err(HandshakeError::NoCommonProtocolVersion {
+            local: (local.min_protocol_version, local.max_protocol_version),
+            peer: (INITIAL_SYNC_PROTOCOL_VERSION, INITIAL_SYNC_PROTOCOL_VERSION),
+        });
+    }
+    Ok(INITIAL_SYNC_PROTOCOL_VERSION)
+}
+
+/// Exchanges the identities with the main node and returns the negotiated sync session.
+/// Panics if the main node is unreachable or incompatible with this node.
+pub async fn perform_handshake(main_node_url: &str, local: &NodeIdentity) -> SyncSession {
+    let client = HttpClientBuilder::default()
+        .build(main_node_url)
+        .expect("Unable to create a main node client");
+    SyncSession::establish(&client, main_node_url, local.clone())
+        .await
+        .expect("couldn't perform the handshake with the main node")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(min_protocol_version: u16, max_protocol_version: u16) -> NodeIdentity {
+        NodeIdentity {
+            chain_id: L2ChainId(270),
+            genesis_root_hash: H256::repeat_byte(1),
+            min_protocol_version,
+            max_protocol_version,
+            software_version: "1.0.0".to_owned(),
+        }
+    }
+
+    #[test]
+    fn compatibility() {
+        assert_eq!(check_compatibility(&identity(1, 3), &identity(2, 5)), Ok(3));
+        assert_eq!(check_compatibility(&identity(2, 5), &identity(1, 3)), Ok(3));
+        assert_eq!(
+            check_compatibility(&identity(1, 1), &identity(2, 3)),
+            Err(HandshakeError::NoCommonProtocolVersion {
+                local: (1, 1),
+                peer: (2, 3)
+            })
+        );
+
+        let mut other_chain = identity(1, 1);
+        other_chain.chain_id = L2ChainId(280);
+        assert_eq!(
+            check_compatibility(&identity(1, 1), &other_chain),
+            Err(HandshakeError::ChainIdMismatch {
+                local: L2ChainId(270),
+                peer: L2ChainId(280)
+            })
+        );
+
+        let mut other_genesis = identity(1, 1);
+        other_genesis.genesis_root_hash = H256::repeat_byte(2);
+        assert!(matches!(
+            check_compatibility(&identity(1, 1), &other_genesis),
+            Err(HandshakeError::GenesisMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn compatibility_with_main_node_without_handshake() {
+        assert_eq!(
+            check_compatibility_with_initial_version(&identity(1, 3)),
+            Ok(1)
+        );
+        assert_eq!(
+            check_compatibility_with_initial_version(&identity(2, 3)),
+            Err(HandshakeError::NoCommonProtocolVersion {
+                local: (2, 3),
+                peer: (1, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn reported_version_is_sanitized() {
+        let mut peer = identity(1, 1);
+        peer.software_version = "1.2.3-rc.1+build\n\"}".to_owned();
+        assert_eq!(reported_software_version(&peer), "1.2.3-rc.1+build");
+        peer.software_version = "9".repeat(100);
+        assert_eq!(
+            reported_software_version(&peer).len(),
+            MAX_REPORTED_VERSION_LEN
+        );
+    }
+}
@@ -36,6 +36,7 @@ impl L1BatchExecutorBuilder for MockBatchExecutorBuilder {
                 Command::RollbackLastTx(_resp) => {
                     panic!("Rollback should never happen");
                 }
+                Command::StartNextMiniblock => {}
                 Command::FinishBatch(resp) => {
                     // Blanket result, it doesn't really matter.
                     let result = VmBlockResult {
@@ -87,6 +88,7 @@ pub(crate) fn successful_exec() -> TxExecutionResult {
             result: partial_execution_result(),
             gas_refunded: 0,
             operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
         },
         vec![],
     )));
@@ -2,10 +2,13 @@ pub mod batch_status_updater;
 pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
+pub mod handshake;
 pub mod mock_batch_executor;
+pub mod snapshot_diff;
 pub(crate) mod sync_action;
 
 pub use self::{
     external_io::{ExternalIO, ExternalNodeSealer},
+    snapshot_diff::SyncStart,
     sync_action::ActionQueue,
 };
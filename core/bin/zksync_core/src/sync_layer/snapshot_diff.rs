@@ -0,0 +1,163 @@
+//! Catching up with the main node using the state snapshot diffs, so that a node which was offline for a while
+//! doesn't need to replay every miniblock it has missed via the fetcher.
+
+use std::time::Instant;
+
+use anyhow::Context as _;
+
+use zksync_dal::ConnectionPool;
+use zksync_object_store::object_store::{DynamicObjectStore, STATE_SNAPSHOT_DIFFS_BUCKET_PATH};
+use zksync_state::secondary_storage::SecondaryStateStorage;
+use zksync_storage::{db::Database, RocksDB};
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_web3_decl::{jsonrpsee::http_client::HttpClient, namespaces::ZksNamespaceClient};
+
+use crate::rocksdb_backup::StateSnapshotDiffData;
+
+/// Point from which the external node starts syncing with the main node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStart {
+    /// Number of the next L1 batch to be processed.
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of the next miniblock to be fetched.
+    pub miniblock_number: MiniblockNumber,
+}
+
+impl SyncStart {
+    pub fn genesis() -> Self {
+        Self {
+            l1_batch_number: L1BatchNumber(1),
+            miniblock_number: MiniblockNumber(1),
+        }
+    }
+
+    /// Returns the point to resume syncing from for a node that has processed the L1 batches before
+    /// `l1_batch_number`, replaying the following miniblocks via the fetcher.
+    pub async fn resume(
+        main_node_client: &HttpClient,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        if l1_batch_number.0 <= 1 {
+            return Ok(Self::genesis());
+        }
+        let last_l1_batch_number = l1_batch_number - 1;
+        let (_, last_miniblock_number) = main_node_client
+            .get_miniblock_range(last_l1_batch_number)
+            .await?
+            .with_context(|| {
+                format!(
+                    "main node returned no miniblocks for L1 batch #{}",
+                    last_l1_batch_number
+                )
+            })?;
+        Ok(Self {
+            l1_batch_number,
+            miniblock_number: MiniblockNumber(last_miniblock_number.as_u32() + 1),
+        })
+    }
+
+    /// Last L1 batch processed before the start.
+    pub fn last_l1_batch_number(&self) -> L1BatchNumber {
+        self.l1_batch_number - 1
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CatchUpError {
+    /// The main node has no diffs applicable to the state of the node, so the missed miniblocks
+    /// have to be replayed via the fetcher.
+    #[error("no state snapshot diffs are applicable to L1 batch #{0}")]
+    NoDiffs(L1BatchNumber),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Applies the state snapshot diffs published by the main node one by one to Postgres and the state keeper cache,
+/// for as long as there are diffs applicable to the state keeper cache. Returns the point to continue syncing from,
+/// or [`CatchUpError::NoDiffs`] if no diffs are applicable.
+pub async fn catch_up_via_snapshot_diffs(
+    main_node_client: &HttpClient,
+    mut object_store: DynamicObjectStore,
+    pool: &ConnectionPool,
+    state_keeper_db_path: &str,
+) -> Result<SyncStart, CatchUpError> {
+    let db = RocksDB::new(Database::StateKeeper, state_keeper_db_path, true);
+    let mut state = SecondaryStateStorage::new(db);
+    let started_at = Instant::now();
+    let start_l1_batch_number = state.get_l1_batch_number();
+    let mut sync_start = None;
+    loop {
+        let l1_batch_number = state.get_l1_batch_number();
+        let Some(diff_info) = main_node_client
+            .get_state_snapshot_diff(l1_batch_number)
+            .await
+            .context("failed to fetch state snapshot diff")?
+        else {
+            break;
+        };
+        let last_l1_batch_number = diff_info.l1_batch_number - 1;
+        let root_hash = main_node_client
+            .get_l1_batch_details(last_l1_batch_number)
+            .await
+            .context("failed to fetch L1 batch details")?
+            .and_then(|details| details.root_hash)
+            .with_context(|| {
+                format!(
+                    "main node returned no root hash for L1 batch #{}",
+                    last_l1_batch_number
+                )
+            })?;
+        if diff_info.root_hash != root_hash {
+            return Err(anyhow::anyhow!(
+                "state snapshot diff {} is built for root hash {:?} of L1 batch #{}, while the main node reports {:?}",
+                diff_info.object_key,
+                diff_info.root_hash,
+                last_l1_batch_number,
+                root_hash
+            )
+            .into());
+        }
+
+        sync_start = Some(SyncStart {
+            l1_batch_number: diff_info.l1_batch_number,
+            miniblock_number: diff_info.first_miniblock_number,
+        });
+        let pool = pool.clone();
+        (state, object_store) = tokio::task::spawn_blocking(move || {
+            let bytes =
+                object_store.get(STATE_SNAPSHOT_DIFFS_BUCKET_PATH, diff_info.object_key.clone())?;
+            let diff: StateSnapshotDiffData = bincode::deserialize(&bytes)?;
+            anyhow::ensure!(
+                diff.base_l1_batch_number == diff_info.base_l1_batch_number
+                    && diff.l1_batch_number == diff_info.l1_batch_number
+                    && diff.first_miniblock_number == diff_info.first_miniblock_number
+                    && diff.root_hash == diff_info.root_hash,
+                "contents of the state snapshot diff {} don't match the metadata reported by the main node",
+                diff_info.object_key
+            );
+            diff.check_applicable(&state)?;
+            diff.apply_to_postgres(&mut pool.access_storage_blocking());
+            diff.apply(&mut state)?;
+
+            vlog::info!(
+                "Applied state snapshot diff {} moving the state to L1 batch #{}",
+                diff_info.object_key,
+                diff_info.l1_batch_number
+            );
+            metrics::increment_counter!("external_node.snapshot_diffs_applied");
+            anyhow::Ok((state, object_store))
+        })
+        .await
+        .context("state snapshot diff task panicked")??;
+    }
+
+    let sync_start = sync_start.ok_or(CatchUpError::NoDiffs(start_l1_batch_number))?;
+    vlog::info!(
+        "Caught up from L1 batch #{} to L1 batch #{} using the state snapshot diffs in {:?}",
+        start_l1_batch_number,
+        sync_start.l1_batch_number,
+        started_at.elapsed()
+    );
+    metrics::histogram!("external_node.snapshot_diff_catch_up", started_at.elapsed());
+    Ok(sync_start)
+}
@@ -5,7 +5,7 @@ use std::{
 
 use chrono::{DateTime, Utc};
 use zksync_contracts::BaseSystemContractsHashes;
-use zksync_types::{L1BatchNumber, MiniblockNumber, Transaction, H256};
+use zksync_types::{Address, L1BatchNumber, MiniblockNumber, Transaction, H256};
 
 /// Action queue is used to communicate between the fetcher and the rest of the external node
 /// by collecting the fetched data in memory until it gets processed by the different entities.
@@ -126,7 +126,7 @@ impl ActionQueue {
                         return Err(format!("Unexpected Tx: {:?}", actions));
                     }
                 }
-                SyncAction::SealMiniblock => {
+                SyncAction::SealMiniblock { .. } => {
                     if !opened || miniblock_sealed {
                         return Err(format!("Unexpected SealMiniblock: {:?}", actions));
                     }
@@ -171,6 +171,8 @@ pub(crate) enum SyncAction {
         l1_gas_price: u64,
         l2_fair_gas_price: u64,
         base_system_contracts_hashes: BaseSystemContractsHashes,
+        /// Fee account the batch was executed with on the main node, if provided.
+        operator_address: Option<Address>,
     },
     Miniblock {
         number: MiniblockNumber,
@@ -181,7 +183,14 @@ pub(crate) enum SyncAction {
     /// that they are sealed, but at the same time the next miniblock may not exist yet.
     /// By having a dedicated action for that we prevent a situation where the miniblock is kept open on the EN until
     /// the next one is sealed on the main node.
-    SealMiniblock,
+    SealMiniblock {
+        /// Digest of the execution outcomes computed by the main node, if provided.
+        /// Used to detect the execution divergence right after the miniblock is executed locally.
+        execution_digest: Option<H256>,
+        /// Serialization version of the `execution_digest`. Not provided by the main nodes
+        /// that don't expose it.
+        execution_digest_version: Option<u8>,
+    },
     /// Similarly to `SealMiniblock` we must be able to seal the batch even if there is no next miniblock yet.
     SealBatch,
 }
@@ -208,6 +217,7 @@ mod tests {
             l1_gas_price: 1,
             l2_fair_gas_price: 1,
             base_system_contracts_hashes: BaseSystemContractsHashes::default(),
+            operator_address: None,
         }
     }
 
@@ -235,7 +245,10 @@ mod tests {
     }
 
     fn seal_miniblock() -> SyncAction {
-        SyncAction::SealMiniblock
+        SyncAction::SealMiniblock {
+            execution_digest: None,
+            execution_digest_version: None,
+        }
     }
 
     fn seal_batch() -> SyncAction {
@@ -16,7 +16,7 @@ use zksync_object_store::gcs_utils::{
 };
 use zksync_object_store::object_store::create_object_store_from_env;
 use zksync_object_store::object_store::{
-    DynamicObjectStore, LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+    DynamicObjectStore, ObjectStoreError, LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
     SCHEDULER_WITNESS_JOBS_BUCKET_PATH, WITNESS_INPUT_BUCKET_PATH,
 };
 use zksync_state::storage_view::StorageView;
@@ -40,7 +40,9 @@ use crate::db_storage_provider::DbStorageProvider;
 use crate::witness_generator;
 use crate::witness_generator::precalculated_merkle_paths_provider::PrecalculatedMerklePathsProvider;
 use crate::witness_generator::track_witness_generation_stage;
-use crate::witness_generator::utils::{expand_bootloader_contents, save_prover_input_artifacts};
+use crate::witness_generator::utils::{
+    deserialize_artifact, expand_bootloader_contents, save_prover_input_artifacts,
+};
 
 pub struct BasicCircuitArtifacts {
     pub basic_circuits: BlockBasicCircuits<Bn256>,
@@ -119,25 +121,22 @@ pub fn update_database(
 pub async fn get_artifacts(
     block_number: L1BatchNumber,
     object_store: &DynamicObjectStore,
-) -> WitnessGeneratorJob {
-    let merkle_tree_paths = object_store
-        .get(
-            WITNESS_INPUT_BUCKET_PATH,
-            merkle_tree_paths_blob_url(block_number),
-        )
-        .unwrap();
+) -> Result<WitnessGeneratorJob, ObjectStoreError> {
+    let merkle_tree_paths = object_store.get(
+        WITNESS_INPUT_BUCKET_PATH,
+        merkle_tree_paths_blob_url(block_number),
+    )?;
 
     let (merkle_paths, next_enumeration_index) =
-        bincode::deserialize::<(Vec<StorageLogMetadata>, u64)>(&merkle_tree_paths)
-            .expect("witness deserialization failed");
+        deserialize_artifact::<(Vec<StorageLogMetadata>, u64)>("witness", &merkle_tree_paths)?;
 
-    WitnessGeneratorJob {
+    Ok(WitnessGeneratorJob {
         block_number,
         job: WitnessGeneratorJobInput::BasicCircuits(Box::new(PrepareBasicCircuitsJob {
             merkle_paths,
             next_enumeration_index,
         })),
-    }
+    })
 }
 
 pub async fn save_artifacts(
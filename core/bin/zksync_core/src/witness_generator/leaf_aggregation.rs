@@ -7,7 +7,7 @@ use zksync_object_store::gcs_utils::{
     leaf_layer_subqueues_blob_url,
 };
 use zksync_object_store::object_store::{
-    DynamicObjectStore, LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+    DynamicObjectStore, ObjectStoreError, LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
     NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
 };
 use zksync_types::{
@@ -36,7 +36,7 @@ use zksync_verification_key_server::{
 
 use crate::witness_generator;
 use crate::witness_generator::track_witness_generation_stage;
-use crate::witness_generator::utils::save_prover_input_artifacts;
+use crate::witness_generator::utils::{deserialize_artifact, save_prover_input_artifacts};
 
 pub struct LeafAggregationArtifacts {
     pub leaf_layer_subqueues: Vec<QueueSimulator<Bn256, RecursionRequest<Bn256>, 2, 2>>,
@@ -154,29 +154,26 @@ pub fn update_database(
 pub async fn get_artifacts(
     metadata: WitnessGeneratorJobMetadata,
     object_store: &DynamicObjectStore,
-) -> WitnessGeneratorJob {
-    let basic_circuits_serialized = object_store
-        .get(
-            LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
-            basic_circuits_blob_url(metadata.block_number),
-        )
-        .unwrap();
-    let basic_circuits =
-        bincode::deserialize::<BlockBasicCircuits<Bn256>>(&basic_circuits_serialized)
-            .expect("basic_circuits deserialization failed");
-
-    let basic_circuits_inputs_serialized = object_store
-        .get(
-            LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
-            basic_circuits_inputs_blob_url(metadata.block_number),
-        )
-        .unwrap();
-    let basic_circuits_inputs = bincode::deserialize::<BlockBasicCircuitsPublicInputs<Bn256>>(
+) -> Result<WitnessGeneratorJob, ObjectStoreError> {
+    let basic_circuits_serialized = object_store.get(
+        LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+        basic_circuits_blob_url(metadata.block_number),
+    )?;
+    let basic_circuits = deserialize_artifact::<BlockBasicCircuits<Bn256>>(
+        "basic_circuits",
+        &basic_circuits_serialized,
+    )?;
+
+    let basic_circuits_inputs_serialized = object_store.get(
+        LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+        basic_circuits_inputs_blob_url(metadata.block_number),
+    )?;
+    let basic_circuits_inputs = deserialize_artifact::<BlockBasicCircuitsPublicInputs<Bn256>>(
+        "basic_circuits_inputs",
         &basic_circuits_inputs_serialized,
-    )
-    .expect("basic_circuits_inputs deserialization failed");
+    )?;
 
-    WitnessGeneratorJob {
+    Ok(WitnessGeneratorJob {
         block_number: metadata.block_number,
         job: WitnessGeneratorJobInput::LeafAggregation(Box::new(
             PrepareLeafAggregationCircuitsJob {
@@ -185,7 +182,7 @@ pub async fn get_artifacts(
                 basic_circuits,
             },
         )),
-    }
+    })
 }
 
 pub async fn save_artifacts(
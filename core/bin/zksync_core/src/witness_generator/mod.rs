@@ -1,3 +1,4 @@
+use std::env;
 use std::fmt::Debug;
 use std::time::Instant;
 
@@ -6,10 +7,17 @@ use rand::Rng;
 
 use zksync_config::configs::WitnessGeneratorConfig;
 use zksync_dal::ConnectionPool;
-use zksync_object_store::object_store::create_object_store_from_env;
-use zksync_queued_job_processor::JobProcessor;
+use zksync_object_store::object_store::{create_object_store_from_env, ObjectStoreError};
+use zksync_prover_utils::{
+    ensure_initial_setup_keys_present_async,
+    key_store::{create_setup_key_store, SetupKeyStoreMode},
+    DownloadRetryPolicy,
+};
+use zksync_queued_job_processor::{worker_id, JobProcessor};
 use zksync_types::{
-    proofs::{AggregationRound, WitnessGeneratorJob, WitnessGeneratorJobInput},
+    proofs::{
+        AggregationRound, WitnessGeneratorJob, WitnessGeneratorJobInput, WitnessMemoryBudget,
+    },
     zkevm_test_harness::{
         abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit, bellman::bn256::Bn256,
         witness::oracle::VmWitnessOracle,
@@ -90,6 +98,61 @@ impl WitnessGenerator {
         Self { config }
     }
 
+    /// Ensures that the initial setup keys used by the node aggregation round are present and intact,
+    /// and points the aggregation to them. Verifying the checksum of the keys takes a while, so it's done
+    /// once at startup rather than for every job.
+    pub async fn prepare_initial_setup_keys(&self) {
+        let started_at = Instant::now();
+        let store_mode =
+            SetupKeyStoreMode::from_config(self.config.setup_key_store_mode.as_deref());
+        let store = create_setup_key_store(store_mode).await;
+        ensure_initial_setup_keys_present_async(
+            store.as_ref(),
+            &self.config.initial_setup_key_path,
+            &self.config.key_download_url,
+            self.config.initial_setup_key_checksum.as_deref(),
+            DownloadRetryPolicy::default(),
+        )
+        .await;
+        env::set_var("CRS_FILE", &self.config.initial_setup_key_path);
+        vlog::info!("Initial setup keys loaded in {:?}", started_at.elapsed());
+    }
+
+    /// Returns the job if its input artifacts were loaded. Jobs with corrupted artifacts are moved
+    /// to the `artifact_corrupted` status, since retrying them would fail the same way.
+    fn job_or_mark_corrupted(
+        connection_pool: &ConnectionPool,
+        block_number: L1BatchNumber,
+        aggregation_round: AggregationRound,
+        job: Result<WitnessGeneratorJob, ObjectStoreError>,
+    ) -> Option<((L1BatchNumber, AggregationRound), WitnessGeneratorJob)> {
+        match job {
+            Ok(job) => Some(((block_number, aggregation_round), job)),
+            Err(ObjectStoreError::Corrupted(error)) => {
+                vlog::error!(
+                    "Artifacts of {:?} witness job for block {} are corrupted: {}",
+                    aggregation_round,
+                    block_number,
+                    error
+                );
+                metrics::counter!(
+                    "server.witness_generator.corrupted_artifacts",
+                    1,
+                    "aggregation_round" => format!("{:?}", aggregation_round)
+                );
+                connection_pool
+                    .access_storage_blocking()
+                    .witness_generator_dal()
+                    .mark_witness_job_as_artifact_corrupted(block_number, aggregation_round, error);
+                None
+            }
+            Err(err) => panic!(
+                "Failed to load artifacts of {:?} witness job for block {}: {}",
+                aggregation_round, block_number, err
+            ),
+        }
+    }
+
     fn process_job_sync(
         connection_pool: ConnectionPool,
         job: WitnessGeneratorJob,
@@ -97,6 +160,7 @@ impl WitnessGenerator {
     ) -> Option<WitnessGeneratorArtifacts> {
         let config: WitnessGeneratorConfig = WitnessGeneratorConfig::from_env();
         let WitnessGeneratorJob { block_number, job } = job;
+        let _log_context = vlog::with_batch(block_number.0).enter();
 
         if let (Some(blocks_proving_percentage), &WitnessGeneratorJobInput::BasicCircuits(_)) =
             (config.blocks_proving_percentage, &job)
@@ -148,12 +212,7 @@ impl WitnessGenerator {
             }
             WitnessGeneratorJobInput::NodeAggregation(job) => {
                 Some(WitnessGeneratorArtifacts::NodeAggregation(Box::new(
-                    node_aggregation::process_node_aggregation_job(
-                        config,
-                        started_at,
-                        block_number,
-                        *job,
-                    ),
+                    node_aggregation::process_node_aggregation_job(started_at, block_number, *job),
                 )))
             }
 
@@ -181,12 +240,14 @@ impl JobProcessor for WitnessGenerator {
         let mut connection = connection_pool.access_storage().await;
         let object_store = create_object_store_from_env();
         let last_l1_batch_to_process = self.config.last_l1_batch_to_process();
+        let picked_by = worker_id();
         let optional_metadata = connection
             .witness_generator_dal()
             .get_next_scheduler_witness_job(
                 self.config.witness_generation_timeout(),
                 self.config.max_attempts,
                 last_l1_batch_to_process,
+                &picked_by,
             );
 
         if let Some(metadata) = optional_metadata {
@@ -198,6 +259,7 @@ impl JobProcessor for WitnessGenerator {
                 .map_or([0u8; 32], |e| e.metadata.aux_data_hash.0);
             let previous_meta_hash =
                 prev_metadata.map_or([0u8; 32], |e| e.metadata.meta_parameters_hash.0);
+            let block_number = metadata.block_number;
             let job = scheduler::get_artifacts(
                 metadata,
                 previous_aux_hash,
@@ -205,7 +267,12 @@ impl JobProcessor for WitnessGenerator {
                 &object_store,
             )
             .await;
-            return Some(((job.block_number, job.job.aggregation_round()), job));
+            return Self::job_or_mark_corrupted(
+                &connection_pool,
+                block_number,
+                AggregationRound::Scheduler,
+                job,
+            );
         }
 
         let optional_metadata = connection
@@ -214,11 +281,18 @@ impl JobProcessor for WitnessGenerator {
                 self.config.witness_generation_timeout(),
                 self.config.max_attempts,
                 last_l1_batch_to_process,
+                &picked_by,
             );
 
         if let Some(metadata) = optional_metadata {
+            let block_number = metadata.block_number;
             let job = node_aggregation::get_artifacts(metadata, &object_store).await;
-            return Some(((job.block_number, job.job.aggregation_round()), job));
+            return Self::job_or_mark_corrupted(
+                &connection_pool,
+                block_number,
+                AggregationRound::NodeAggregation,
+                job,
+            );
         }
 
         let optional_metadata = connection
@@ -227,11 +301,18 @@ impl JobProcessor for WitnessGenerator {
                 self.config.witness_generation_timeout(),
                 self.config.max_attempts,
                 last_l1_batch_to_process,
+                &picked_by,
             );
 
         if let Some(metadata) = optional_metadata {
+            let block_number = metadata.block_number;
             let job = leaf_aggregation::get_artifacts(metadata, &object_store).await;
-            return Some(((job.block_number, job.job.aggregation_round()), job));
+            return Self::job_or_mark_corrupted(
+                &connection_pool,
+                block_number,
+                AggregationRound::LeafAggregation,
+                job,
+            );
         }
 
         let optional_metadata = connection
@@ -240,11 +321,20 @@ impl JobProcessor for WitnessGenerator {
                 self.config.witness_generation_timeout(),
                 self.config.max_attempts,
                 last_l1_batch_to_process,
+                self.config
+                    .memory_budget_bytes()
+                    .map(WitnessMemoryBudget::new),
+                &picked_by,
             );
 
         if let Some(metadata) = optional_metadata {
             let job = basic_circuits::get_artifacts(metadata.block_number, &object_store).await;
-            return Some(((job.block_number, job.job.aggregation_round()), job));
+            return Self::job_or_mark_corrupted(
+                &connection_pool,
+                metadata.block_number,
+                AggregationRound::BasicCircuits,
+                job,
+            );
         }
 
         None
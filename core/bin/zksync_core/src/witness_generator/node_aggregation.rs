@@ -1,14 +1,12 @@
 use std::collections::HashMap;
-use std::env;
 use std::time::Instant;
 
-use zksync_config::configs::WitnessGeneratorConfig;
 use zksync_dal::ConnectionPool;
 use zksync_object_store::gcs_utils::{
     aggregation_outputs_blob_url, final_node_aggregations_blob_url, leaf_layer_subqueues_blob_url,
 };
 use zksync_object_store::object_store::{
-    DynamicObjectStore, NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+    DynamicObjectStore, ObjectStoreError, NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
     SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
 };
 use zksync_types::{
@@ -39,27 +37,22 @@ use zksync_verification_key_server::{
 
 use crate::witness_generator;
 use crate::witness_generator::track_witness_generation_stage;
-use crate::witness_generator::utils::save_prover_input_artifacts;
+use crate::witness_generator::utils::{deserialize_artifact, save_prover_input_artifacts};
 
 pub struct NodeAggregationArtifacts {
     pub final_node_aggregation: NodeAggregationOutputDataWitness<Bn256>,
     pub serialized_circuits: Vec<(String, Vec<u8>)>,
 }
 
+/// Requires the initial setup keys prepared by [`WitnessGenerator::prepare_initial_setup_keys()`].
+///
+/// [`WitnessGenerator::prepare_initial_setup_keys()`]: crate::witness_generator::WitnessGenerator::prepare_initial_setup_keys
 pub fn process_node_aggregation_job(
-    config: WitnessGeneratorConfig,
     started_at: Instant,
     block_number: L1BatchNumber,
     job: PrepareNodeAggregationCircuitJob,
 ) -> NodeAggregationArtifacts {
     let stage_started_at = Instant::now();
-    zksync_prover_utils::ensure_initial_setup_keys_present(
-        &config.initial_setup_key_path,
-        &config.key_download_url,
-    );
-    env::set_var("CRS_FILE", config.initial_setup_key_path);
-    vlog::info!("Keys loaded in {:?}", stage_started_at.elapsed());
-    let stage_started_at = Instant::now();
 
     let verification_keys: HashMap<
         u8,
@@ -190,16 +183,12 @@ pub fn update_database(
 pub async fn get_artifacts(
     metadata: WitnessGeneratorJobMetadata,
     object_store: &DynamicObjectStore,
-) -> WitnessGeneratorJob {
-    let leaf_layer_subqueues_serialized = object_store
-        .get(
-            NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
-            leaf_layer_subqueues_blob_url(metadata.block_number),
-        )
-        .expect(
-            "leaf_layer_subqueues is not found in a `queued` `node_aggregation_witness_jobs` job",
-        );
-    let leaf_layer_subqueues = bincode::deserialize::<
+) -> Result<WitnessGeneratorJob, ObjectStoreError> {
+    let leaf_layer_subqueues_serialized = object_store.get(
+        NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+        leaf_layer_subqueues_blob_url(metadata.block_number),
+    )?;
+    let leaf_layer_subqueues = deserialize_artifact::<
         Vec<
             zksync_types::zkevm_test_harness::encodings::QueueSimulator<
                 Bn256,
@@ -210,23 +199,18 @@ pub async fn get_artifacts(
                 2,
             >,
         >,
-    >(&leaf_layer_subqueues_serialized)
-    .expect("leaf_layer_subqueues deserialization failed");
-
-    let aggregation_outputs_serialized = object_store
-        .get(
-            NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
-            aggregation_outputs_blob_url(metadata.block_number),
-        )
-        .expect(
-            "aggregation_outputs is not found in a `queued` `node_aggregation_witness_jobs` job",
-        );
-    let aggregation_outputs = bincode::deserialize::<Vec<LeafAggregationOutputDataWitness<Bn256>>>(
+    >("leaf_layer_subqueues", &leaf_layer_subqueues_serialized)?;
+
+    let aggregation_outputs_serialized = object_store.get(
+        NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+        aggregation_outputs_blob_url(metadata.block_number),
+    )?;
+    let aggregation_outputs = deserialize_artifact::<Vec<LeafAggregationOutputDataWitness<Bn256>>>(
+        "aggregation_outputs",
         &aggregation_outputs_serialized,
-    )
-    .expect("aggregation_outputs deserialization failed");
+    )?;
 
-    WitnessGeneratorJob {
+    Ok(WitnessGeneratorJob {
         block_number: metadata.block_number,
         job: WitnessGeneratorJobInput::NodeAggregation(Box::new(
             PrepareNodeAggregationCircuitJob {
@@ -235,7 +219,7 @@ pub async fn get_artifacts(
                 previous_sequence: leaf_layer_subqueues,
             },
         )),
-    }
+    })
 }
 
 pub async fn save_artifacts(
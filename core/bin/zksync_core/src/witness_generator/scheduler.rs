@@ -5,7 +5,9 @@ use zksync_dal::ConnectionPool;
 use zksync_object_store::gcs_utils::{
     final_node_aggregations_blob_url, scheduler_witness_blob_url,
 };
-use zksync_object_store::object_store::{DynamicObjectStore, SCHEDULER_WITNESS_JOBS_BUCKET_PATH};
+use zksync_object_store::object_store::{
+    DynamicObjectStore, ObjectStoreError, SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
+};
 use zksync_types::{
     circuit::{
         LEAF_CIRCUIT_INDEX, LEAF_SPLITTING_FACTOR, NODE_CIRCUIT_INDEX, NODE_SPLITTING_FACTOR,
@@ -29,7 +31,7 @@ use zksync_verification_key_server::{
 
 use crate::witness_generator;
 use crate::witness_generator::track_witness_generation_stage;
-use crate::witness_generator::utils::save_prover_input_artifacts;
+use crate::witness_generator::utils::{deserialize_artifact, save_prover_input_artifacts};
 
 pub struct SchedulerArtifacts {
     pub final_aggregation_result: BlockApplicationWitness<Bn256>,
@@ -199,30 +201,26 @@ pub async fn get_artifacts(
     previous_aux_hash: [u8; 32],
     previous_meta_hash: [u8; 32],
     object_store: &DynamicObjectStore,
-) -> WitnessGeneratorJob {
-    let scheduler_witness_serialized = object_store
-        .get(
-            SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
-            scheduler_witness_blob_url(metadata.block_number),
-        )
-        .unwrap();
-    let scheduler_witness = bincode::deserialize::<SchedulerCircuitInstanceWitness<Bn256>>(
+) -> Result<WitnessGeneratorJob, ObjectStoreError> {
+    let scheduler_witness_serialized = object_store.get(
+        SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
+        scheduler_witness_blob_url(metadata.block_number),
+    )?;
+    let scheduler_witness = deserialize_artifact::<SchedulerCircuitInstanceWitness<Bn256>>(
+        "scheduler_witness",
         &scheduler_witness_serialized,
-    )
-    .expect("scheduler_witness deserialization failed");
-
-    let final_node_aggregations_serialized = object_store
-        .get(
-            SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
-            final_node_aggregations_blob_url(metadata.block_number),
-        )
-        .expect("final_node_aggregations is not found in a `queued` `scheduler_witness_jobs` job");
-    let final_node_aggregations = bincode::deserialize::<NodeAggregationOutputDataWitness<Bn256>>(
+    )?;
+
+    let final_node_aggregations_serialized = object_store.get(
+        SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
+        final_node_aggregations_blob_url(metadata.block_number),
+    )?;
+    let final_node_aggregations = deserialize_artifact::<NodeAggregationOutputDataWitness<Bn256>>(
+        "final_node_aggregations",
         &final_node_aggregations_serialized,
-    )
-    .expect("final_node_aggregations deserialization failed");
+    )?;
 
-    WitnessGeneratorJob {
+    Ok(WitnessGeneratorJob {
         block_number: metadata.block_number,
         job: WitnessGeneratorJobInput::Scheduler(Box::new(PrepareSchedulerCircuitJob {
             incomplete_scheduler_witness: scheduler_witness,
@@ -231,5 +229,5 @@ pub async fn get_artifacts(
             previous_aux_hash,
             previous_meta_hash,
         })),
-    }
+    })
 }
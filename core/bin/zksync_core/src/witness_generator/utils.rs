@@ -1,7 +1,10 @@
+use serde::de::DeserializeOwned;
 use vm::zk_evm::abstractions::MAX_MEMORY_BYTES;
 use vm::zk_evm::ethereum_types::U256;
 use zksync_object_store::gcs_utils::prover_circuit_input_blob_url;
-use zksync_object_store::object_store::{DynamicObjectStore, PROVER_JOBS_BUCKET_PATH};
+use zksync_object_store::object_store::{
+    DynamicObjectStore, ObjectStoreError, PROVER_JOBS_BUCKET_PATH,
+};
 use zksync_types::{proofs::AggregationRound, L1BatchNumber};
 
 pub fn expand_bootloader_contents(packed: Vec<(usize, U256)>) -> Vec<u8> {
@@ -15,6 +18,17 @@ pub fn expand_bootloader_contents(packed: Vec<(usize, U256)>) -> Vec<u8> {
     result.to_vec()
 }
 
+/// Deserializes an artifact fetched from the object store. Artifacts that can't be deserialized
+/// are reported as corrupted, the same as the ones not matching their content hash.
+pub fn deserialize_artifact<T: DeserializeOwned>(
+    name: &str,
+    serialized: &[u8],
+) -> Result<T, ObjectStoreError> {
+    bincode::deserialize(serialized).map_err(|err| {
+        ObjectStoreError::Corrupted(format!("{} deserialization failed: {}", name, err))
+    })
+}
+
 pub async fn save_prover_input_artifacts(
     block_number: L1BatchNumber,
     serialized_circuits: Vec<(String, Vec<u8>)>,
@@ -1,12 +1,13 @@
 /// External uses
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 /// Built-in uses
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::time::Duration;
 // Local uses
 pub use crate::configs::utils::Prometheus;
 use crate::envy_load;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 /// API configuration.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -17,14 +18,22 @@ pub struct ApiConfig {
     pub explorer: Explorer,
     /// Configuration options for the Prometheus exporter.
     pub prometheus: Prometheus,
+    /// Configuration options for the prover gateway used by the external provers.
+    pub prover_gateway: ProverGateway,
 }
 
 impl ApiConfig {
     pub fn from_env() -> Self {
+        let web3_json_rpc: Web3JsonRpc = envy_load!("web3_json_rpc", "API_WEB3_JSON_RPC_");
+        // A zero threshold would let the state-changing `admin` calls through without any signature.
+        if web3_json_rpc.admin_signatures_threshold == Some(0) {
+            panic!("Invalid admin signatures threshold: at least 1 signature must be required");
+        }
         Self {
-            web3_json_rpc: envy_load!("web3_json_rpc", "API_WEB3_JSON_RPC_"),
+            web3_json_rpc,
             explorer: envy_load!("explorer", "API_EXPLORER_"),
             prometheus: envy_load!("prometheus", "API_PROMETHEUS_"),
+            prover_gateway: envy_load!("prover_gateway", "API_PROVER_GATEWAY_"),
         }
     }
 }
@@ -39,14 +48,27 @@ pub struct Web3JsonRpc {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
-    /// Max possible limit of entities to be requested once.
+    /// Max possible limit of entities to be requested once. Also caps the number of transactions
+    /// returned by `zks_getRawBlockTransactions`, so it must exceed the number of transactions
+    /// in any miniblock for the external nodes to be able to sync.
     pub req_entities_limit: Option<u32>,
+    /// Max number of miniblocks in the block range of `eth_getLogs` and `zks_getLogsPaginated` requests.
+    /// Not limited if not set.
+    pub logs_block_range_limit: Option<u32>,
+    /// Max number of logs returned by `eth_getLogs`, `eth_getFilterChanges` and a page of `zks_getLogsPaginated`.
+    /// Defaults to `req_entities_limit`.
+    pub logs_result_limit: Option<u32>,
     /// Max possible limit of filters to be in the state at once.
     pub filters_limit: Option<u32>,
+    /// Time (in seconds) after which a filter that hasn't been polled is uninstalled. Defaults to 300.
+    pub filters_ttl: Option<u64>,
     /// Max possible limit of subscriptions to be in the state at once.
     pub subscriptions_limit: Option<u32>,
     /// Interval between polling db for pubsub (in ms).
     pub pubsub_polling_interval: Option<u64>,
+    /// Number of the latest miniblocks whose `newHeads` and `logs` notifications can be replayed
+    /// to the resumed subscriptions. Defaults to 1000.
+    pub pubsub_resume_window: Option<u32>,
     /// number of threads per server
     pub threads_per_server: u32,
     /// Tx nonce: how far ahead from the committed nonce can it be.
@@ -67,7 +89,117 @@ pub struct Web3JsonRpc {
     ///  Max possible size of an ABI encoded tx (in bytes).
     pub max_tx_size: usize,
     /// Main node URL - used only by external node to proxy transactions to.
+    /// For an `https` URL, the certificate of the main node is verified against the system root certificates.
     pub main_node_url: Option<String>,
+    /// Set of the historical data served by the node. Checked against the database on startup
+    /// and advertised via `zks_getNodeCapabilities`. Defaults to `archive`.
+    pub node_personality: Option<NodePersonality>,
+    /// Whether `zks_sendEncryptedTransaction` is accepted. Experimental, disabled by default.
+    pub encrypted_mempool_enabled: Option<bool>,
+    /// Max number of the L2 to L1 log trees of L1 batches cached in memory to serve log proofs.
+    pub l2_to_l1_log_trees_cache_size: Option<usize>,
+    /// Whether the L2 to L1 log trees are also saved to the object store, so that they are shared
+    /// between the API servers and survive restarts. Disabled by default.
+    pub l2_to_l1_log_trees_in_object_store: Option<bool>,
+    /// Sizes of the DB connection pools used by the HTTP API, the WS API, the subscriptions notifier
+    /// and the transaction sender respectively, so that one workload can't starve the others.
+    /// Default to 30, 30, 5 and 10. Can be changed at runtime via the `connection_pool_sizes`
+    /// table (`api_http`, `api_ws`, `api_pubsub` and `api_tx_sender` pools).
+    pub http_pool_size: Option<u32>,
+    pub ws_pool_size: Option<u32>,
+    pub pubsub_pool_size: Option<u32>,
+    pub tx_sender_pool_size: Option<u32>,
+    /// Numbers of the worker threads of the HTTP and WS servers respectively. Default to `threads_per_server`.
+    pub http_threads: Option<u32>,
+    pub ws_threads: Option<u32>,
+    /// Deadline of the heavy DB queries (logs, account transactions, transfers and the mempool listings)
+    /// made to serve an HTTP or WS API request, counted from the start of the request, in ms. The queries
+    /// still running at the deadline are cancelled by Postgres, and the request fails with the request
+    /// timeout error. Not limited if not set.
+    pub request_deadline_ms: Option<u64>,
+    /// Overrides of the request deadline per method, in the `method=ms` form.
+    pub request_deadline_overrides: Option<Vec<String>>,
+    /// Share of the API requests recorded into the `api_audit_log` table, from 0 to 1.
+    /// The audit log is disabled if not set or set to 0.
+    pub audit_log_sample_rate: Option<f64>,
+    /// Number of days the audit log records are kept for. Defaults to 7.
+    pub audit_log_retention_days: Option<u32>,
+    /// Number of the trusted reverse proxies in front of the API server, each appending the address of its peer
+    /// to `X-Forwarded-For`. The client address recorded into the audit log is the entry this many positions
+    /// from the end of the header, since the preceding entries can be set by the client. Defaults to 1;
+    /// `X-Forwarded-For` is ignored if set to 0.
+    pub trusted_proxy_hops: Option<usize>,
+    /// Whether the `admin` namespace is served. Disabled by default; the servers exposing it
+    /// must not be publicly accessible.
+    pub admin_namespace_enabled: Option<bool>,
+    /// Addresses of the keys authorizing the state-changing `admin` namespace calls. Such calls are refused
+    /// if the list is empty.
+    pub admin_signers: Option<Vec<Address>>,
+    /// Number of distinct admin signatures required to authorize a state-changing `admin` call. Defaults to 1;
+    /// 0 is rejected when the config is loaded.
+    pub admin_signatures_threshold: Option<usize>,
+    /// Identifier of this node included into the messages signed by the admin keys, so that an authorization
+    /// for one node can't be replayed against another node of the same chain. State-changing `admin` calls
+    /// are refused if not set.
+    pub admin_node_id: Option<String>,
+    /// Whether the `txpool` namespace exposing the pending transactions of all accounts is served.
+    /// Disabled by default.
+    pub txpool_namespace_enabled: Option<bool>,
+    /// Whether the `debug` namespace (transaction tracing and lifecycle) is served. Tracing re-executes
+    /// transactions in the VM, so it's disabled by default.
+    pub debug_namespace_enabled: Option<bool>,
+    /// Whether `zks_getPendingTransactions` listing the pending transactions of all accounts is served.
+    /// Disabled by default.
+    pub pending_transactions_api_enabled: Option<bool>,
+    /// Fields of the transactions returned by `zks_getPendingTransactions` that are replaced with `null`.
+    /// Defaults to `calldata`.
+    pub pending_transactions_redacted_fields: Option<Vec<PendingTxField>>,
+    /// Whether the requests made with an API key (the `x-api-key` header) are metered per key.
+    /// Disabled by default.
+    pub usage_metering_enabled: Option<bool>,
+    /// API keys of the tenants whose usage is metered. Requests made with other keys aren't metered,
+    /// so that the arbitrary keys sent by the clients don't create the usage records.
+    pub usage_api_keys: Option<Vec<String>>,
+    /// Interval between the exports of the metered usage, in ms. Defaults to 1 minute.
+    pub usage_export_interval_ms: Option<u64>,
+    /// If set, usage records are posted to this URL instead of being written to the `api_usage_records` table.
+    pub usage_webhook_url: Option<String>,
+    /// Overrides of the compute units charged per method call, in the `method=units` form.
+    pub usage_compute_units: Option<Vec<String>>,
+}
+
+/// Personality of the node, defining which queries it is able to serve.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodePersonality {
+    /// Keeps the whole history starting from the genesis block.
+    #[default]
+    Archive,
+    /// Keeps the history starting from some block; older blocks are pruned.
+    FullWithPruning,
+    /// Serves only the queries for the latest state and accepts transactions.
+    MinimalApi,
+}
+
+impl NodePersonality {
+    /// Whether the node serves queries for blocks other than the latest one.
+    pub fn serves_historical_queries(self) -> bool {
+        !matches!(self, Self::MinimalApi)
+    }
+
+    /// Whether the node is required to have all the blocks since genesis.
+    pub fn requires_full_history(self) -> bool {
+        matches!(self, Self::Archive)
+    }
+}
+
+/// Field of a pending transaction that can be redacted in the public mempool API.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingTxField {
+    To,
+    Value,
+    Calldata,
 }
 
 impl Web3JsonRpc {
@@ -83,10 +215,23 @@ impl Web3JsonRpc {
         self.req_entities_limit.unwrap_or_else(|| 2u32.pow(10)) as usize
     }
 
+    pub fn logs_block_range_limit(&self) -> Option<u32> {
+        self.logs_block_range_limit
+    }
+
+    pub fn logs_result_limit(&self) -> usize {
+        self.logs_result_limit
+            .map_or_else(|| self.req_entities_limit(), |limit| limit as usize)
+    }
+
     pub fn filters_limit(&self) -> usize {
         self.filters_limit.unwrap_or(10000) as usize
     }
 
+    pub fn filters_ttl(&self) -> Duration {
+        Duration::from_secs(self.filters_ttl.unwrap_or(300))
+    }
+
     pub fn subscriptions_limit(&self) -> usize {
         self.subscriptions_limit.unwrap_or(10000) as usize
     }
@@ -95,6 +240,10 @@ impl Web3JsonRpc {
         Duration::from_millis(self.pubsub_polling_interval.unwrap_or(200))
     }
 
+    pub fn pubsub_resume_window(&self) -> u32 {
+        self.pubsub_resume_window.unwrap_or(1000)
+    }
+
     pub fn request_timeout(&self) -> Duration {
         Duration::from_secs(self.request_timeout.unwrap_or(10))
     }
@@ -102,6 +251,134 @@ impl Web3JsonRpc {
     pub fn account_pks(&self) -> Vec<H256> {
         self.account_pks.clone().unwrap_or_default()
     }
+
+    pub fn node_personality(&self) -> NodePersonality {
+        self.node_personality.unwrap_or_default()
+    }
+
+    pub fn encrypted_mempool_enabled(&self) -> bool {
+        self.encrypted_mempool_enabled.unwrap_or(false)
+    }
+
+    pub fn l2_to_l1_log_trees_cache_size(&self) -> usize {
+        self.l2_to_l1_log_trees_cache_size.unwrap_or(64)
+    }
+
+    pub fn l2_to_l1_log_trees_in_object_store(&self) -> bool {
+        self.l2_to_l1_log_trees_in_object_store.unwrap_or(false)
+    }
+
+    pub fn http_pool_size(&self) -> u32 {
+        self.http_pool_size.unwrap_or(30)
+    }
+
+    pub fn ws_pool_size(&self) -> u32 {
+        self.ws_pool_size.unwrap_or(30)
+    }
+
+    pub fn pubsub_pool_size(&self) -> u32 {
+        self.pubsub_pool_size.unwrap_or(5)
+    }
+
+    pub fn tx_sender_pool_size(&self) -> u32 {
+        self.tx_sender_pool_size.unwrap_or(10)
+    }
+
+    pub fn http_threads(&self) -> usize {
+        self.http_threads.unwrap_or(self.threads_per_server) as usize
+    }
+
+    pub fn ws_threads(&self) -> usize {
+        self.ws_threads.unwrap_or(self.threads_per_server) as usize
+    }
+
+    pub fn request_deadline(&self) -> Option<Duration> {
+        self.request_deadline_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the overridden request deadlines per method.
+    pub fn request_deadline_overrides(&self) -> HashMap<String, Duration> {
+        self.request_deadline_overrides
+            .iter()
+            .flatten()
+            .map(|entry| {
+                let (method, deadline_ms) = entry
+                    .split_once('=')
+                    .and_then(|(method, ms)| Some((method.trim(), ms.trim().parse().ok()?)))
+                    .unwrap_or_else(|| panic!("Invalid request deadline override: {}", entry));
+                (method.to_owned(), Duration::from_millis(deadline_ms))
+            })
+            .collect()
+    }
+
+    pub fn audit_log_sample_rate(&self) -> f64 {
+        self.audit_log_sample_rate.unwrap_or(0.0).clamp(0.0, 1.0)
+    }
+
+    pub fn audit_log_retention_days(&self) -> u32 {
+        self.audit_log_retention_days.unwrap_or(7)
+    }
+
+    pub fn trusted_proxy_hops(&self) -> usize {
+        self.trusted_proxy_hops.unwrap_or(1)
+    }
+
+    pub fn admin_namespace_enabled(&self) -> bool {
+        self.admin_namespace_enabled.unwrap_or(false)
+    }
+
+    pub fn admin_signers(&self) -> Vec<Address> {
+        self.admin_signers.clone().unwrap_or_default()
+    }
+
+    pub fn admin_signatures_threshold(&self) -> usize {
+        self.admin_signatures_threshold.unwrap_or(1)
+    }
+
+    pub fn admin_node_id(&self) -> Option<&str> {
+        self.admin_node_id.as_deref()
+    }
+
+    pub fn txpool_namespace_enabled(&self) -> bool {
+        self.txpool_namespace_enabled.unwrap_or(false)
+    }
+
+    pub fn debug_namespace_enabled(&self) -> bool {
+        self.debug_namespace_enabled.unwrap_or(false)
+    }
+
+    pub fn pending_transactions_api_enabled(&self) -> bool {
+        self.pending_transactions_api_enabled.unwrap_or(false)
+    }
+
+    pub fn pending_transactions_redacted_fields(&self) -> Vec<PendingTxField> {
+        self.pending_transactions_redacted_fields
+            .clone()
+            .unwrap_or_else(|| vec![PendingTxField::Calldata])
+    }
+
+    pub fn usage_metering_enabled(&self) -> bool {
+        self.usage_metering_enabled.unwrap_or(false)
+    }
+
+    pub fn usage_export_interval(&self) -> Duration {
+        Duration::from_millis(self.usage_export_interval_ms.unwrap_or(60_000))
+    }
+
+    /// Returns the overridden compute units per method.
+    pub fn usage_compute_units(&self) -> HashMap<String, u32> {
+        self.usage_compute_units
+            .iter()
+            .flatten()
+            .map(|entry| {
+                let (method, units) = entry
+                    .split_once('=')
+                    .and_then(|(method, units)| Some((method.trim(), units.trim().parse().ok()?)))
+                    .unwrap_or_else(|| panic!("Invalid compute units override: {}", entry));
+                (method.to_owned(), units)
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -138,6 +415,79 @@ impl Explorer {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ProverGateway {
+    /// Port to which the prover gateway is listening.
+    pub port: u16,
+    /// Keys accepted in the `Authorization: Bearer` header. If empty, all requests are rejected.
+    pub api_keys: Option<Vec<String>>,
+    /// Path to the PEM-encoded certificate chain. TLS is enabled if set, in which case the key must be set as well.
+    pub tls_cert_path: Option<String>,
+    /// Path to the PEM-encoded private key of the certificate.
+    pub tls_key_path: Option<String>,
+    /// Path to the PEM-encoded CA certificates the client certificates must be signed with.
+    /// If set, only the clients presenting a valid certificate are accepted.
+    pub tls_client_ca_path: Option<String>,
+}
+
+impl ProverGateway {
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
+    }
+
+    /// Checks the API key against all the configured keys in constant time, so that the response time
+    /// doesn't reveal how much of a key was guessed.
+    pub fn is_authorized(&self, api_key: &str) -> bool {
+        let is_known = self
+            .api_keys
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .fold(false, |is_known, key| {
+                is_known | constant_time_eq(key.as_bytes(), api_key.as_bytes())
+            });
+        !api_key.is_empty() && is_known
+    }
+
+    /// Returns the TLS settings, or `None` if the gateway is served over plain HTTP.
+    /// Panics if the settings are incomplete, so that the gateway never falls back to plain HTTP by mistake.
+    pub fn tls_config(&self) -> Option<ServerTlsConfig> {
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => Some(ServerTlsConfig {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                client_ca_path: self.tls_client_ca_path.clone(),
+            }),
+            (None, None) => {
+                assert!(
+                    self.tls_client_ca_path.is_none(),
+                    "Invalid TLS configuration of prover gateway: client CA is set without the server certificate"
+                );
+                None
+            }
+            _ => panic!(
+                "Invalid TLS configuration of prover gateway: both the certificate and the key must be set"
+            ),
+        }
+    }
+}
+
+fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    lhs.len() == rhs.len() && lhs.iter().zip(rhs).fold(0, |acc, (l, r)| acc | (l ^ r)) == 0
+}
+
+/// TLS settings of an internal API server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerTlsConfig {
+    /// Path to the PEM-encoded certificate chain of the server.
+    pub cert_path: String,
+    /// Path to the PEM-encoded private key of the server.
+    pub key_path: String,
+    /// Path to the PEM-encoded CA certificates to verify the client certificates with.
+    /// Client authentication is disabled if not set.
+    pub client_ca_path: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,9 +503,13 @@ mod tests {
                 ws_port: 3051,
                 ws_url: "ws://127.0.0.1:3051".into(),
                 req_entities_limit: Some(10000),
+                logs_block_range_limit: Some(10000),
+                logs_result_limit: Some(5000),
                 filters_limit: Some(10000),
+                filters_ttl: Some(600),
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
+                pubsub_resume_window: Some(500),
                 threads_per_server: 128,
                 max_nonce_ahead: 5,
                 transactions_per_sec_limit: Some(1000),
@@ -175,6 +529,40 @@ mod tests {
                 estimate_gas_acceptable_overestimation: 1000,
                 max_tx_size: 1000000,
                 main_node_url: None,
+                node_personality: Some(NodePersonality::FullWithPruning),
+                encrypted_mempool_enabled: Some(true),
+                l2_to_l1_log_trees_cache_size: Some(128),
+                l2_to_l1_log_trees_in_object_store: Some(true),
+                http_pool_size: Some(50),
+                ws_pool_size: Some(30),
+                pubsub_pool_size: Some(5),
+                tx_sender_pool_size: Some(10),
+                http_threads: Some(64),
+                ws_threads: None,
+                request_deadline_ms: Some(5000),
+                request_deadline_overrides: Some(vec!["eth_getLogs=20000".into()]),
+                audit_log_sample_rate: Some(0.01),
+                audit_log_retention_days: Some(14),
+                trusted_proxy_hops: Some(2),
+                admin_namespace_enabled: Some(true),
+                admin_signers: Some(vec![
+                    Address::from_str("0x0000000000000000000000000000000000000001").unwrap(),
+                    Address::from_str("0x0000000000000000000000000000000000000002").unwrap(),
+                ]),
+                admin_signatures_threshold: Some(2),
+                admin_node_id: Some("main-node-0".into()),
+                txpool_namespace_enabled: Some(true),
+                debug_namespace_enabled: Some(true),
+                pending_transactions_api_enabled: Some(false),
+                pending_transactions_redacted_fields: Some(vec![
+                    PendingTxField::Calldata,
+                    PendingTxField::Value,
+                ]),
+                usage_metering_enabled: Some(true),
+                usage_api_keys: Some(vec!["tenant1".into(), "tenant2".into()]),
+                usage_export_interval_ms: Some(30000),
+                usage_webhook_url: Some("http://127.0.0.1:8080/usage".into()),
+                usage_compute_units: Some(vec!["eth_call=50".into(), "eth_getLogs=100".into()]),
             },
             explorer: Explorer {
                 port: 3070,
@@ -189,6 +577,13 @@ mod tests {
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
             },
+            prover_gateway: ProverGateway {
+                port: 3080,
+                api_keys: Some(vec!["key1".into(), "key2".into()]),
+                tls_cert_path: Some("/etc/zksync/tls/gateway.crt".into()),
+                tls_key_path: Some("/etc/zksync/tls/gateway.key".into()),
+                tls_client_ca_path: Some("/etc/zksync/tls/provers_ca.crt".into()),
+            },
         }
     }
 
@@ -200,9 +595,13 @@ API_WEB3_JSON_RPC_HTTP_URL="http://127.0.0.1:3050"
 API_WEB3_JSON_RPC_WS_PORT="3051"
 API_WEB3_JSON_RPC_WS_URL="ws://127.0.0.1:3051"
 API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
+API_WEB3_JSON_RPC_LOGS_BLOCK_RANGE_LIMIT=10000
+API_WEB3_JSON_RPC_LOGS_RESULT_LIMIT=5000
 API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
+API_WEB3_JSON_RPC_FILTERS_TTL=600
 API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
 API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
+API_WEB3_JSON_RPC_PUBSUB_RESUME_WINDOW=500
 API_WEB3_JSON_RPC_THREADS_PER_SERVER=128
 API_WEB3_JSON_RPC_MAX_NONCE_AHEAD=5
 API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
@@ -212,6 +611,33 @@ API_WEB3_JSON_RPC_ACCOUNT_PKS=0x000000000000000000000000000000000000000000000000
 API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
 API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
 API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
+API_WEB3_JSON_RPC_NODE_PERSONALITY="full_with_pruning"
+API_WEB3_JSON_RPC_ENCRYPTED_MEMPOOL_ENABLED="true"
+API_WEB3_JSON_RPC_L2_TO_L1_LOG_TREES_CACHE_SIZE=128
+API_WEB3_JSON_RPC_L2_TO_L1_LOG_TREES_IN_OBJECT_STORE="true"
+API_WEB3_JSON_RPC_HTTP_POOL_SIZE=50
+API_WEB3_JSON_RPC_WS_POOL_SIZE=30
+API_WEB3_JSON_RPC_PUBSUB_POOL_SIZE=5
+API_WEB3_JSON_RPC_TX_SENDER_POOL_SIZE=10
+API_WEB3_JSON_RPC_HTTP_THREADS=64
+API_WEB3_JSON_RPC_REQUEST_DEADLINE_MS=5000
+API_WEB3_JSON_RPC_REQUEST_DEADLINE_OVERRIDES=eth_getLogs=20000
+API_WEB3_JSON_RPC_AUDIT_LOG_SAMPLE_RATE=0.01
+API_WEB3_JSON_RPC_AUDIT_LOG_RETENTION_DAYS=14
+API_WEB3_JSON_RPC_TRUSTED_PROXY_HOPS=2
+API_WEB3_JSON_RPC_ADMIN_NAMESPACE_ENABLED=true
+API_WEB3_JSON_RPC_ADMIN_SIGNERS=0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002
+API_WEB3_JSON_RPC_ADMIN_SIGNATURES_THRESHOLD=2
+API_WEB3_JSON_RPC_ADMIN_NODE_ID="main-node-0"
+API_WEB3_JSON_RPC_TXPOOL_NAMESPACE_ENABLED=true
+API_WEB3_JSON_RPC_DEBUG_NAMESPACE_ENABLED=true
+API_WEB3_JSON_RPC_PENDING_TRANSACTIONS_API_ENABLED=false
+API_WEB3_JSON_RPC_PENDING_TRANSACTIONS_REDACTED_FIELDS=calldata,value
+API_WEB3_JSON_RPC_USAGE_METERING_ENABLED=true
+API_WEB3_JSON_RPC_USAGE_API_KEYS=tenant1,tenant2
+API_WEB3_JSON_RPC_USAGE_EXPORT_INTERVAL_MS=30000
+API_WEB3_JSON_RPC_USAGE_WEBHOOK_URL="http://127.0.0.1:8080/usage"
+API_WEB3_JSON_RPC_USAGE_COMPUTE_UNITS=eth_call=50,eth_getLogs=100
 API_EXPLORER_PORT="3070"
 API_EXPLORER_URL="http://127.0.0.1:3070"
 API_EXPLORER_NETWORK_STATS_POLLING_INTERVAL="1000"
@@ -221,6 +647,11 @@ API_EXPLORER_THREADS_PER_SERVER=128
 API_PROMETHEUS_LISTENER_PORT="3312"
 API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
 API_PROMETHEUS_PUSH_INTERVAL_MS=100
+API_PROVER_GATEWAY_PORT="3080"
+API_PROVER_GATEWAY_API_KEYS=key1,key2
+API_PROVER_GATEWAY_TLS_CERT_PATH="/etc/zksync/tls/gateway.crt"
+API_PROVER_GATEWAY_TLS_KEY_PATH="/etc/zksync/tls/gateway.key"
+API_PROVER_GATEWAY_TLS_CLIENT_CA_PATH="/etc/zksync/tls/provers_ca.crt"
         "#;
         set_env(config);
 
@@ -242,5 +673,61 @@ API_PROMETHEUS_PUSH_INTERVAL_MS=100
             config.explorer.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.explorer.port)
         );
+        assert_eq!(
+            config.web3_json_rpc.usage_compute_units(),
+            HashMap::from([("eth_call".to_owned(), 50), ("eth_getLogs".to_owned(), 100)])
+        );
+        assert_eq!(
+            config.web3_json_rpc.request_deadline_overrides(),
+            HashMap::from([("eth_getLogs".to_owned(), Duration::from_secs(20))])
+        );
+        assert!(config.prover_gateway.is_authorized("key2"));
+        assert!(!config.prover_gateway.is_authorized("key3"));
+        assert_eq!(config.web3_json_rpc.http_threads(), 64);
+        assert_eq!(config.web3_json_rpc.ws_threads(), 128);
+    }
+
+    #[test]
+    fn api_pool_sizes_have_separate_defaults() {
+        let mut config = expected_config().web3_json_rpc;
+        assert_eq!(config.ws_pool_size(), 30);
+        config.http_pool_size = None;
+        config.ws_pool_size = None;
+        config.pubsub_pool_size = None;
+        config.tx_sender_pool_size = None;
+        assert_eq!(config.http_pool_size(), 30);
+        assert_eq!(config.ws_pool_size(), 30);
+        assert_eq!(config.pubsub_pool_size(), 5);
+        assert_eq!(config.tx_sender_pool_size(), 10);
+    }
+
+    #[test]
+    fn prover_gateway_tls_config() {
+        let mut config = expected_config().prover_gateway;
+        assert_eq!(
+            config.tls_config().unwrap().client_ca_path.as_deref(),
+            Some("/etc/zksync/tls/provers_ca.crt")
+        );
+        config.tls_cert_path = None;
+        config.tls_key_path = None;
+        config.tls_client_ca_path = None;
+        assert_eq!(config.tls_config(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "both the certificate and the key must be set")]
+    fn prover_gateway_tls_config_without_key() {
+        let mut config = expected_config().prover_gateway;
+        config.tls_key_path = None;
+        config.tls_config();
+    }
+
+    #[test]
+    #[should_panic(expected = "client CA is set without the server certificate")]
+    fn prover_gateway_tls_config_with_only_client_ca() {
+        let mut config = expected_config().prover_gateway;
+        config.tls_cert_path = None;
+        config.tls_key_path = None;
+        config.tls_config();
     }
 }
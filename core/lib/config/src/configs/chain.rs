@@ -20,6 +20,10 @@ pub struct ChainConfig {
     pub mempool: MempoolConfig,
     /// circuit breaker configuration
     pub circuit_breaker: CircuitBreakerConfig,
+    /// stalled chain watchdog configuration
+    pub watchdog: ChainWatchdogConfig,
+    /// fork mode configuration
+    pub fork: ChainForkConfig,
 }
 
 impl ChainConfig {
@@ -30,6 +34,8 @@ impl ChainConfig {
             operations_manager: envy_load!("operations_manager", "CHAIN_OPERATIONS_MANAGER_"),
             mempool: envy_load!("mempool", "CHAIN_MEMPOOL_"),
             circuit_breaker: envy_load!("circuit_breaker", "CHAIN_CIRCUIT_BREAKER_"),
+            watchdog: envy_load!("watchdog", "CHAIN_WATCHDOG_"),
+            fork: envy_load!("fork", "CHAIN_FORK_"),
         }
     }
 }
@@ -59,7 +65,14 @@ pub struct StateKeeperConfig {
     /// The max number of gas to spend on an L1 tx before its batch should be sealed by the gas sealer.
     pub max_single_tx_gas: u32,
 
+    /// Max gas limit of a single L2 transaction accepted by the API. Capped by `block_gas_limit`.
     pub max_allowed_l2_tx_gas_limit: u32,
+    /// Max amount of L2 gas that can be spent by the transactions of a single L1 batch.
+    /// Must not exceed the limit of the bootloader used by the current protocol version.
+    pub block_gas_limit: u32,
+    /// Protocol version of the sealed miniblocks. The gas limits are recorded for each version when
+    /// the state keeper starts, and can't be changed without bumping the version.
+    pub protocol_version: u16,
 
     /// Configuration option for tx to be rejected in case
     /// it takes more percentage of the block capacity than this value.
@@ -89,6 +102,58 @@ pub struct StateKeeperConfig {
 
     /// Max number of computational gas that validation step is allowed to take.
     pub validation_computational_gas_limit: u32,
+
+    /// Whether the storage slots read and written by each transaction are saved when a miniblock is sealed.
+    /// Used to build the dependency graphs of the blocks.
+    pub record_tx_access_sets: bool,
+
+    /// Whether the utilization of the bootloader resources (transaction slots, bootloader memory, gas and pubdata)
+    /// is saved for each sealed L1 batch. Reports are returned by `admin_getBootloaderUsageReport`.
+    pub record_bootloader_usage_reports: bool,
+
+    /// Experimental: executes the transactions of each miniblock in parallel in the shadow mode and cross-checks
+    /// the results against the sequential execution. Doesn't affect the sealed blocks, but slows down the state keeper.
+    pub parallel_execution_shadow_mode: bool,
+
+    /// Bootloader to execute every batch with in the shadow VM, before it's activated.
+    /// If only one of the shadow hashes is set, the other contract is the same as in the main VM.
+    pub shadow_bootloader_hash: Option<H256>,
+    /// Default account code to execute every batch with in the shadow VM, before it's activated.
+    pub shadow_default_aa_hash: Option<H256>,
+
+    /// Permissioned deployment mode: only the L2 transactions from the accounts in the deployer allowlist
+    /// are allowed to call the contract deployer. The allowlist is managed via the `admin` API namespace.
+    pub deployer_allowlist_enabled: bool,
+
+    /// Max number of seconds the miniblock timestamps are allowed to run ahead of the latest L1 block.
+    /// Must be lower than the commit timestamp approximation delta enforced by the L1 contract.
+    pub max_timestamp_drift_from_l1_sec: u64,
+
+    /// Number of consecutive state keeper crashes during the execution of the same transaction after which
+    /// the transaction is quarantined, i.e. excluded from the mempool until released by the operator.
+    pub tx_quarantine_crash_threshold: Option<u32>,
+}
+
+impl StateKeeperConfig {
+    /// Returns the max gas limit of a single L2 transaction, taking the block gas limit into account.
+    pub fn max_allowed_tx_gas_limit(&self) -> u32 {
+        self.max_allowed_l2_tx_gas_limit.min(self.block_gas_limit)
+    }
+
+    /// Returns the bootloader and default account hashes for the shadow VM, if it's enabled.
+    pub fn shadow_base_system_contracts_hashes(&self) -> Option<(H256, H256)> {
+        if self.shadow_bootloader_hash.is_none() && self.shadow_default_aa_hash.is_none() {
+            return None;
+        }
+        Some((
+            self.shadow_bootloader_hash.unwrap_or(self.bootloader_hash),
+            self.shadow_default_aa_hash.unwrap_or(self.default_aa_hash),
+        ))
+    }
+
+    pub fn tx_quarantine_crash_threshold(&self) -> u32 {
+        self.tx_quarantine_crash_threshold.unwrap_or(3)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -114,6 +179,42 @@ impl CircuitBreakerConfig {
     }
 }
 
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ChainWatchdogConfig {
+    /// Interval between the checks of the chain progress.
+    pub check_interval_ms: u64,
+    /// Max age of the last sealed miniblock before the chain is considered stalled, in seconds.
+    pub miniblock_stall_threshold_sec: u64,
+    /// Max time since the last L1 batch commit confirmation before the chain is considered stalled, in seconds.
+    pub commit_stall_threshold_sec: u64,
+    /// Max time since the last L1 batch execution confirmation before the chain is considered stalled, in seconds.
+    pub execute_stall_threshold_sec: u64,
+    /// URL to `POST` the alert to once a stage gets stalled or recovers.
+    pub webhook_url: Option<String>,
+}
+
+impl ChainWatchdogConfig {
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+}
+
+/// Fork mode of a dev node: the storage slots and bytecodes which were never written locally
+/// are lazily fetched from a remote node and cached, so that the local chain runs on top of its state.
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+pub struct ChainForkConfig {
+    /// JSON-RPC URL of the node to fork the state from. Fork mode is disabled if not set.
+    pub url: Option<String>,
+    /// L1 batch to fork the state at. The last L1 batch sealed on the remote node is used if not set.
+    pub l1_batch_number: Option<u32>,
+}
+
+impl ChainForkConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.url.is_some()
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct MempoolConfig {
     pub sync_interval_ms: u64,
@@ -151,6 +252,8 @@ mod tests {
                 miniblock_commit_deadline_ms: 1000,
                 max_single_tx_gas: 1_000_000,
                 max_allowed_l2_tx_gas_limit: 2_000_000_000,
+                block_gas_limit: 3_000_000_000,
+                protocol_version: 2,
                 close_block_at_eth_params_percentage: 0.2,
                 close_block_at_gas_percentage: 0.8,
                 close_block_at_geometry_percentage: 0.5,
@@ -163,6 +266,14 @@ mod tests {
                 bootloader_hash: H256::from(&[254; 32]),
                 default_aa_hash: H256::from(&[254; 32]),
                 validation_computational_gas_limit: 10_000_000,
+                record_tx_access_sets: true,
+                record_bootloader_usage_reports: true,
+                parallel_execution_shadow_mode: true,
+                shadow_bootloader_hash: Some(H256::from(&[253; 32])),
+                shadow_default_aa_hash: None,
+                deployer_allowlist_enabled: true,
+                max_timestamp_drift_from_l1_sec: 600,
+                tx_quarantine_crash_threshold: Some(5),
             },
             operations_manager: OperationsManager {
                 delay_interval: 100,
@@ -177,6 +288,17 @@ mod tests {
             circuit_breaker: CircuitBreakerConfig {
                 sync_interval_ms: 1000,
             },
+            watchdog: ChainWatchdogConfig {
+                check_interval_ms: 10000,
+                miniblock_stall_threshold_sec: 60,
+                commit_stall_threshold_sec: 3600,
+                execute_stall_threshold_sec: 86400,
+                webhook_url: Some("http://127.0.0.1:8080/alerts".to_string()),
+            },
+            fork: ChainForkConfig {
+                url: Some("https://mainnet.era.zksync.io".to_string()),
+                l1_batch_number: Some(1000),
+            },
         }
     }
 
@@ -190,6 +312,8 @@ CHAIN_STATE_KEEPER_TRANSACTION_SLOTS="50"
 CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
 CHAIN_STATE_KEEPER_MAX_SINGLE_TX_GAS="1000000"
 CHAIN_STATE_KEEPER_MAX_ALLOWED_L2_TX_GAS_LIMIT="2000000000"
+CHAIN_STATE_KEEPER_BLOCK_GAS_LIMIT="3000000000"
+CHAIN_STATE_KEEPER_PROTOCOL_VERSION="2"
 CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GEOMETRY_PERCENTAGE="0.5"
 CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_GAS_PERCENTAGE="0.8"
 CHAIN_STATE_KEEPER_CLOSE_BLOCK_AT_ETH_PARAMS_PERCENTAGE="0.2"
@@ -203,6 +327,13 @@ CHAIN_STATE_KEEPER_FAIR_L2_GAS_PRICE="250000000"
 CHAIN_STATE_KEEPER_BOOTLOADER_HASH="0xfefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefe"
 CHAIN_STATE_KEEPER_DEFAULT_AA_HASH="0xfefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefefe"
 CHAIN_STATE_KEEPER_VALIDATION_COMPUTATIONAL_GAS_LIMIT="10000000"
+CHAIN_STATE_KEEPER_RECORD_TX_ACCESS_SETS="true"
+CHAIN_STATE_KEEPER_RECORD_BOOTLOADER_USAGE_REPORTS="true"
+CHAIN_STATE_KEEPER_PARALLEL_EXECUTION_SHADOW_MODE="true"
+CHAIN_STATE_KEEPER_SHADOW_BOOTLOADER_HASH="0xfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfdfd"
+CHAIN_STATE_KEEPER_DEPLOYER_ALLOWLIST_ENABLED="true"
+CHAIN_STATE_KEEPER_MAX_TIMESTAMP_DRIFT_FROM_L1_SEC="600"
+CHAIN_STATE_KEEPER_TX_QUARANTINE_CRASH_THRESHOLD="5"
 CHAIN_OPERATIONS_MANAGER_DELAY_INTERVAL="100"
 CHAIN_MEMPOOL_SYNC_INTERVAL_MS="10"
 CHAIN_MEMPOOL_SYNC_BATCH_SIZE="1000"
@@ -210,6 +341,13 @@ CHAIN_MEMPOOL_STUCK_TX_TIMEOUT="10"
 CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
 CHAIN_MEMPOOL_CAPACITY="1000000"
 CHAIN_CIRCUIT_BREAKER_SYNC_INTERVAL_MS="1000"
+CHAIN_WATCHDOG_CHECK_INTERVAL_MS="10000"
+CHAIN_WATCHDOG_MINIBLOCK_STALL_THRESHOLD_SEC="60"
+CHAIN_WATCHDOG_COMMIT_STALL_THRESHOLD_SEC="3600"
+CHAIN_WATCHDOG_EXECUTE_STALL_THRESHOLD_SEC="86400"
+CHAIN_WATCHDOG_WEBHOOK_URL="http://127.0.0.1:8080/alerts"
+CHAIN_FORK_URL="https://mainnet.era.zksync.io"
+CHAIN_FORK_L1_BATCH_NUMBER="1000"
         "#;
         set_env(config);
 
@@ -13,12 +13,28 @@ pub struct DBConfig {
     pub merkle_tree_backup_path: String,
     /// Fast ssd path
     pub merkle_tree_fast_ssd_path: String,
+    /// Whether the Merkle tree keeps the history of its recent versions, so that `zks_getProof` can serve
+    /// the proofs for the recent L1 batches rather than only for the latest one. Slows down the tree updates.
+    pub merkle_tree_history_enabled: bool,
     /// Number of backups to keep
     pub backup_count: usize,
     /// Time interval between performing backups
     pub backup_interval_ms: u64,
+    /// Time interval between creating incremental state snapshots (diffs since the last state keeper cache backup)
+    pub snapshot_diff_interval_ms: u64,
+    /// Minimum time the incremental state snapshots are kept for, so that the external nodes can download them
+    pub snapshot_diff_retention_sec: u64,
+    /// Whether the missing RocksDB instances are restored from the backups in the object store on startup,
+    /// rather than rebuilt from Postgres.
+    pub restore_from_object_store: bool,
     /// Maximum number of blocks to be processed by the full tree at a time
     pub max_block_batch: usize,
+    /// Whether the server fails to start if indices required by the hot queries are missing.
+    /// If disabled, missing indices are only reported in logs.
+    pub enforce_required_indices: bool,
+    /// Whether the server refuses to start if its DB schema version is incompatible with the database
+    /// or with the other running instances. If disabled, incompatibilities are only reported in logs.
+    pub enforce_schema_compatibility: bool,
 }
 
 impl Default for DBConfig {
@@ -28,9 +44,15 @@ impl Default for DBConfig {
             state_keeper_db_path: "./db/state_keeper".to_owned(),
             merkle_tree_backup_path: "./db/backups".to_owned(),
             merkle_tree_fast_ssd_path: "./db/lightweight".to_owned(),
+            merkle_tree_history_enabled: false,
             backup_count: 5,
             backup_interval_ms: 60_000,
+            snapshot_diff_interval_ms: 10_000,
+            snapshot_diff_retention_sec: 3_600,
+            restore_from_object_store: false,
             max_block_batch: 100,
+            enforce_required_indices: false,
+            enforce_schema_compatibility: true,
         }
     }
 }
@@ -50,15 +72,39 @@ impl DBConfig {
         if let Ok(path) = env::var("DATABASE_MERKLE_TREE_FAST_SSD_PATH") {
             config.merkle_tree_fast_ssd_path = path;
         }
+        if let Ok(Ok(enabled)) = env::var("DATABASE_MERKLE_TREE_HISTORY_ENABLED").map(|s| s.parse())
+        {
+            config.merkle_tree_history_enabled = enabled;
+        }
         if let Ok(Ok(count)) = env::var("DATABASE_BACKUP_COUNT").map(|s| s.parse()) {
             config.backup_count = count;
         }
         if let Ok(Ok(interval)) = env::var("DATABASE_BACKUP_INTERVAL_MS").map(|s| s.parse()) {
             config.backup_interval_ms = interval;
         }
+        if let Ok(Ok(interval)) = env::var("DATABASE_SNAPSHOT_DIFF_INTERVAL_MS").map(|s| s.parse())
+        {
+            config.snapshot_diff_interval_ms = interval;
+        }
+        if let Ok(Ok(retention)) =
+            env::var("DATABASE_SNAPSHOT_DIFF_RETENTION_SEC").map(|s| s.parse())
+        {
+            config.snapshot_diff_retention_sec = retention;
+        }
+        if let Ok(Ok(restore)) = env::var("DATABASE_RESTORE_FROM_OBJECT_STORE").map(|s| s.parse()) {
+            config.restore_from_object_store = restore;
+        }
         if let Ok(Ok(size)) = env::var("DATABASE_MAX_BLOCK_BATCH").map(|s| s.parse()) {
             config.max_block_batch = size;
         }
+        if let Ok(Ok(enforce)) = env::var("DATABASE_ENFORCE_REQUIRED_INDICES").map(|s| s.parse()) {
+            config.enforce_required_indices = enforce;
+        }
+        if let Ok(Ok(enforce)) =
+            env::var("DATABASE_ENFORCE_SCHEMA_COMPATIBILITY").map(|s| s.parse())
+        {
+            config.enforce_schema_compatibility = enforce;
+        }
         config
     }
 
@@ -90,6 +136,14 @@ impl DBConfig {
         Duration::from_millis(self.backup_interval_ms)
     }
 
+    pub fn snapshot_diff_interval(&self) -> Duration {
+        Duration::from_millis(self.snapshot_diff_interval_ms)
+    }
+
+    pub fn snapshot_diff_retention(&self) -> Duration {
+        Duration::from_secs(self.snapshot_diff_retention_sec)
+    }
+
     pub fn max_block_batch(&self) -> usize {
         self.max_block_batch
     }
@@ -106,9 +160,15 @@ mod tests {
             state_keeper_db_path: "./db/state_keeper".to_owned(),
             merkle_tree_backup_path: "./db/backups".to_owned(),
             merkle_tree_fast_ssd_path: "./db/lightweight".to_owned(),
+            merkle_tree_history_enabled: true,
             backup_count: 5,
             backup_interval_ms: 60_000,
+            snapshot_diff_interval_ms: 10_000,
+            snapshot_diff_retention_sec: 3_600,
+            restore_from_object_store: true,
             max_block_batch: 100,
+            enforce_required_indices: true,
+            enforce_schema_compatibility: false,
         }
     }
 
@@ -119,9 +179,15 @@ DATABASE_PATH="./db"
 DATABASE_STATE_KEEPER_DB_PATH="./db/state_keeper"
 DATABASE_MERKLE_TREE_BACKUP_PATH="./db/backups"
 DATABASE_MERKLE_TREE_FAST_SSD_PATH="./db/lightweight"
+DATABASE_MERKLE_TREE_HISTORY_ENABLED=true
 DATABASE_BACKUP_COUNT=5
 DATABASE_BACKUP_INTERVAL_MS=60000
+DATABASE_SNAPSHOT_DIFF_INTERVAL_MS=10000
+DATABASE_SNAPSHOT_DIFF_RETENTION_SEC=3600
+DATABASE_RESTORE_FROM_OBJECT_STORE=true
 DATABASE_MAX_BLOCK_BATCH=100
+DATABASE_ENFORCE_REQUIRED_INDICES=true
+DATABASE_ENFORCE_SCHEMA_COMPATIBILITY=false
         "#;
         set_env(config);
 
@@ -0,0 +1,133 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use crate::envy_load;
+
+/// Configuration of the DB maintenance component monitoring the table bloat.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct DBMaintenanceConfig {
+    /// Interval between the table bloat checks (in ms).
+    pub check_interval_ms: u64,
+    /// Monitored tables with their thresholds, formatted as `<table>:<max dead tuples ratio>`.
+    pub tables: Vec<String>,
+    /// Whether `VACUUM (ANALYZE)` is run for the tables exceeding the thresholds.
+    pub vacuum_enabled: bool,
+    /// Start of the low-traffic window in which vacuuming is allowed (UTC hour, inclusive).
+    pub vacuum_window_start_hour: u32,
+    /// End of the low-traffic window in which vacuuming is allowed (UTC hour, exclusive).
+    pub vacuum_window_end_hour: u32,
+}
+
+impl DBMaintenanceConfig {
+    pub fn from_env() -> Self {
+        envy_load!("db_maintenance", "DB_MAINTENANCE_")
+    }
+
+    pub fn check_interval(&self) -> Duration {
+        Duration::from_millis(self.check_interval_ms)
+    }
+
+    /// Returns the monitored tables with the max allowed ratio of dead tuples to all the tuples,
+    /// or an error describing the first malformed entry.
+    pub fn table_thresholds(&self) -> Result<Vec<(String, f64)>, String> {
+        self.tables
+            .iter()
+            .map(|entry| {
+                let (table, threshold) = entry
+                    .split_once(':')
+                    .ok_or_else(|| format!("Table entry `{}` has no threshold", entry))?;
+                if table.is_empty() {
+                    return Err(format!("Table entry `{}` has no table name", entry));
+                }
+                let threshold: f64 = threshold
+                    .parse()
+                    .map_err(|err| format!("Invalid threshold in `{}`: {}", entry, err))?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(format!(
+                        "Threshold in `{}` must be a ratio between 0 and 1",
+                        entry
+                    ));
+                }
+                Ok((table.to_owned(), threshold))
+            })
+            .collect()
+    }
+
+    /// Checks whether the hour falls into the vacuum window. The window may wrap around midnight.
+    pub fn is_in_vacuum_window(&self, utc_hour: u32) -> bool {
+        let (start, end) = (self.vacuum_window_start_hour, self.vacuum_window_end_hour);
+        if start <= end {
+            (start..end).contains(&utc_hour)
+        } else {
+            utc_hour >= start || utc_hour < end
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    fn expected_config() -> DBMaintenanceConfig {
+        DBMaintenanceConfig {
+            check_interval_ms: 300000,
+            tables: vec!["transactions:0.1".to_owned(), "events:0.2".to_owned()],
+            vacuum_enabled: true,
+            vacuum_window_start_hour: 22,
+            vacuum_window_end_hour: 4,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            DB_MAINTENANCE_CHECK_INTERVAL_MS=300000
+            DB_MAINTENANCE_TABLES="transactions:0.1,events:0.2"
+            DB_MAINTENANCE_VACUUM_ENABLED=true
+            DB_MAINTENANCE_VACUUM_WINDOW_START_HOUR=22
+            DB_MAINTENANCE_VACUUM_WINDOW_END_HOUR=4
+        "#;
+        set_env(config);
+
+        let actual = DBMaintenanceConfig::from_env();
+        assert_eq!(actual, expected_config());
+    }
+
+    #[test]
+    fn methods() {
+        let config = expected_config();
+        assert_eq!(
+            config.table_thresholds().unwrap(),
+            [("transactions".to_owned(), 0.1), ("events".to_owned(), 0.2)]
+        );
+        for malformed in [
+            "transactions",
+            ":0.1",
+            "transactions:high",
+            "transactions:1.5",
+        ] {
+            let config = DBMaintenanceConfig {
+                tables: vec![malformed.to_owned()],
+                ..config.clone()
+            };
+            assert!(config.table_thresholds().is_err(), "{}", malformed);
+        }
+
+        assert!(config.is_in_vacuum_window(23));
+        assert!(config.is_in_vacuum_window(0));
+        assert!(!config.is_in_vacuum_window(4));
+        assert!(!config.is_in_vacuum_window(12));
+
+        let config = DBMaintenanceConfig {
+            vacuum_window_start_hour: 2,
+            vacuum_window_end_hour: 5,
+            ..config
+        };
+        assert!(config.is_in_vacuum_window(2));
+        assert!(!config.is_in_vacuum_window(5));
+        assert!(!config.is_in_vacuum_window(1));
+    }
+}
@@ -0,0 +1,68 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use serde::Deserialize;
+// Local uses
+use zksync_basic_types::H256;
+
+use crate::envy_load;
+
+/// Configuration of the experimental encrypted mempool. It's deliberately not a part of `ZkSyncConfig`:
+/// the key share of a committee member is only provided to the host of that member, not to the sequencer
+/// or the API servers.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct EncryptedMempoolConfig {
+    /// Number of the committee decryption shares required to decrypt a transaction.
+    pub decryption_threshold: usize,
+    /// Interval between the decryption rounds (in ms). Transactions received within the interval are queued
+    /// together, so it's expected to be on par with the miniblock commit deadline.
+    pub decryption_interval_ms: u64,
+    /// Index of the committee member (starting from 1). Only set for the hosts of the committee members.
+    pub member_index: Option<u32>,
+    /// Share of the committee secret key of the member. Only set for the hosts of the committee members.
+    pub member_key_share: Option<H256>,
+}
+
+impl EncryptedMempoolConfig {
+    pub fn from_env() -> Self {
+        envy_load!("encrypted_mempool", "ENCRYPTED_MEMPOOL_")
+    }
+
+    pub fn decryption_interval(&self) -> Duration {
+        Duration::from_millis(self.decryption_interval_ms)
+    }
+
+    /// Returns the index and the key share of the committee member run by this host, if both are set.
+    pub fn member(&self) -> Option<(u32, H256)> {
+        Some((self.member_index?, self.member_key_share?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configs::test_utils::set_env;
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            ENCRYPTED_MEMPOOL_DECRYPTION_THRESHOLD=3
+            ENCRYPTED_MEMPOOL_DECRYPTION_INTERVAL_MS=1000
+            ENCRYPTED_MEMPOOL_MEMBER_INDEX=2
+            ENCRYPTED_MEMPOOL_MEMBER_KEY_SHARE="0x1111111111111111111111111111111111111111111111111111111111111111"
+        "#;
+        set_env(config);
+
+        let actual = EncryptedMempoolConfig::from_env();
+        assert_eq!(
+            actual,
+            EncryptedMempoolConfig {
+                decryption_threshold: 3,
+                decryption_interval_ms: 1000,
+                member_index: Some(2),
+                member_key_share: Some(H256::from(&[17; 32])),
+            }
+        );
+        assert_eq!(actual.member(), Some((2, H256::from(&[17; 32]))));
+    }
+}
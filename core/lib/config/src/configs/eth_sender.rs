@@ -63,6 +63,10 @@ pub struct SenderConfig {
     /// Note that this number must be slightly higher than the one set on the contract,
     /// because the contract uses block.timestamp which lags behind the clock time.
     pub l1_batch_min_age_before_execute_seconds: Option<u64>,
+
+    /// If set, eth txs are not broadcast: instead, they are simulated via `eth_call` against the configured L1
+    /// (which may be a fork) and the results are logged. Used to validate the contract deployments and upgrades.
+    pub simulation_mode: bool,
 }
 
 impl SenderConfig {
@@ -92,6 +96,13 @@ pub struct GasAdjusterConfig {
     pub internal_enforced_l1_gas_price: Option<u64>,
     /// Node polling period in seconds
     pub poll_period: u64,
+    /// Number of the last L1 blocks whose minimum base fee is used to price L1->L2 transactions,
+    /// so that fee spikes shorter than the window don't affect deposits. If not set, L1->L2 transactions
+    /// are priced the same way as L2 ones.
+    pub l1_to_l2_fee_smoothing_window: Option<usize>,
+    /// Maximum gas per pubdata byte charged to L1->L2 transactions. The operator subsidizes the pubdata
+    /// costs exceeding the cap.
+    pub l1_to_l2_max_gas_per_pubdata: Option<u64>,
 }
 
 impl GasAdjusterConfig {
@@ -129,6 +140,7 @@ mod tests {
                 operator_commit_eth_addr: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
                 proof_sending_mode: ProofSendingMode::SkipEveryProof,
                 l1_batch_min_age_before_execute_seconds: Some(1000),
+                simulation_mode: true,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -138,6 +150,8 @@ mod tests {
                 internal_l1_pricing_multiplier: 0.8,
                 internal_enforced_l1_gas_price: None,
                 poll_period: 15,
+                l1_to_l2_fee_smoothing_window: Some(20),
+                l1_to_l2_max_gas_per_pubdata: Some(800),
             },
         }
     }
@@ -158,6 +172,8 @@ ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_A="1.5"
 ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_B="1.0005"
 ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
 ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
+ETH_SENDER_GAS_ADJUSTER_L1_TO_L2_FEE_SMOOTHING_WINDOW="20"
+ETH_SENDER_GAS_ADJUSTER_L1_TO_L2_MAX_GAS_PER_PUBDATA="800"
 ETH_SENDER_WAIT_FOR_PROOFS="false"
 ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
 ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -169,6 +185,7 @@ ETH_SENDER_SENDER_TIMESTAMP_CRITERIA_MAX_ALLOWED_LAG="30"
 ETH_SENDER_SENDER_MAX_AGGREGATED_TX_GAS="4000000"
 ETH_SENDER_SENDER_MAX_ETH_TX_DATA_SIZE="120000"
 ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
+ETH_SENDER_SENDER_SIMULATION_MODE="true"
         "#;
         set_env(config);
 
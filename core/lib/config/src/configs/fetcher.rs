@@ -41,12 +41,32 @@ impl<TYPE> SingleFetcherConfig<TYPE> {
     }
 }
 
+/// Configuration for the fetcher reading the metadata of the L2 tokens from their contracts.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct TokenMetadataFetcherConfig {
+    /// Interval for looking for the new tokens in seconds.
+    pub fetching_interval: u64,
+    /// Interval after which the metadata of a token is read again, in seconds.
+    pub refresh_interval: u64,
+}
+
+impl TokenMetadataFetcherConfig {
+    pub fn fetching_interval(&self) -> Duration {
+        Duration::from_secs(self.fetching_interval)
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_interval)
+    }
+}
+
 /// Configuration for the third-party API data fetcher.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct FetcherConfig {
     pub token_list: SingleFetcherConfig<TokenListSource>,
     pub token_price: SingleFetcherConfig<TokenPriceSource>,
     pub token_trading_volume: SingleFetcherConfig<TokenTradingVolumeSource>,
+    pub token_metadata: TokenMetadataFetcherConfig,
 }
 
 impl FetcherConfig {
@@ -58,6 +78,7 @@ impl FetcherConfig {
                 "token_trading_volume",
                 "FETCHER_TOKEN_TRADING_VOLUME_"
             ),
+            token_metadata: envy_load!("token_metadata", "FETCHER_TOKEN_METADATA_"),
         }
     }
 }
@@ -84,6 +105,10 @@ mod tests {
                 url: "http://127.0.0.1:9975/graphql".to_string(),
                 fetching_interval: 5,
             },
+            token_metadata: TokenMetadataFetcherConfig {
+                fetching_interval: 3,
+                refresh_interval: 86400,
+            },
         }
     }
 
@@ -99,6 +124,8 @@ FETCHER_TOKEN_PRICE_FETCHING_INTERVAL="7"
 FETCHER_TOKEN_TRADING_VOLUME_SOURCE="Uniswap"
 FETCHER_TOKEN_TRADING_VOLUME_URL="http://127.0.0.1:9975/graphql"
 FETCHER_TOKEN_TRADING_VOLUME_FETCHING_INTERVAL="5"
+FETCHER_TOKEN_METADATA_FETCHING_INTERVAL="3"
+FETCHER_TOKEN_METADATA_REFRESH_INTERVAL="86400"
         "#;
         set_env(config);
 
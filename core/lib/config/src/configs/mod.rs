@@ -2,6 +2,7 @@
 pub use self::{
     api::ApiConfig, chain::ChainConfig, circuit_synthesizer::CircuitSynthesizerConfig,
     contract_verifier::ContractVerifierConfig, contracts::ContractsConfig, database::DBConfig,
+    db_maintenance::DBMaintenanceConfig, encrypted_mempool::EncryptedMempoolConfig,
     eth_client::ETHClientConfig, eth_sender::ETHSenderConfig, eth_sender::GasAdjusterConfig,
     eth_watch::ETHWatchConfig, fetcher::FetcherConfig, nfs::NfsConfig,
     object_store::ObjectStoreConfig, prover::ProverConfig, prover::ProverConfigs,
@@ -14,6 +15,8 @@ pub mod circuit_synthesizer;
 pub mod contract_verifier;
 pub mod contracts;
 pub mod database;
+pub mod db_maintenance;
+pub mod encrypted_mempool;
 pub mod eth_client;
 pub mod eth_sender;
 pub mod eth_watch;
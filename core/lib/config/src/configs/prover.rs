@@ -16,6 +16,20 @@ pub struct ProverConfig {
     pub initial_setup_key_path: String,
     /// https://storage.googleapis.com/universal-setup/setup_2\^26.key
     pub key_download_url: String,
+    /// Expected checksum of the initial setup key, `sha256:<hex>` or `blake3:<hex>`. If not set, the checksum
+    /// is fetched from the `.sha256` sidecar of the download URL if available.
+    pub initial_setup_key_checksum: Option<String>,
+    /// Store the setup keys are fetched from: `Http` (default), `GCS`, `S3` or `LocalDir`. Depending on it,
+    /// `key_download_url` and `setup_keys_download_url` are URLs, `bucket/object` paths or local paths.
+    pub setup_key_store_mode: Option<String>,
+    /// Number of retries of a failed key download. 5 by default.
+    pub key_download_max_retries: Option<usize>,
+    /// Backoff before the first retry of a key download, doubled after each failed retry. 1 second by default.
+    pub key_download_initial_backoff_in_millis: Option<u64>,
+    /// Upper bound of the backoff between the key download retries. 30 seconds by default.
+    pub key_download_max_backoff_in_millis: Option<u64>,
+    /// Time after which a key download attempt without any received data is aborted. 120 seconds by default.
+    pub key_download_timeout_in_secs: Option<u64>,
     /// Max time for proof to be generated
     pub generation_timeout_in_secs: u16,
     /// Number of threads to be used concurrent proof generation.
@@ -26,6 +40,19 @@ pub struct ProverConfig {
     pub polling_duration_in_millis: u64,
     // Path to setup keys for individual circuit.
     pub setup_keys_path: String,
+    /// Base URL to download the missing setup keys for the circuits of the prover group from, if set.
+    pub setup_keys_download_url: Option<String>,
+    /// Protocol version of the setup keys. If set, `setup_keys_path` must be the `v<version>` subdirectory
+    /// of the directory with the keys of all versions, and the keys of obsolete versions are removed from it
+    /// on startup.
+    pub setup_keys_protocol_version: Option<u16>,
+    /// Protocol versions preceding `setup_keys_protocol_version` that still have jobs to be proven.
+    /// Their keys are kept by the cleanup.
+    pub setup_keys_active_protocol_versions: Option<Vec<u16>>,
+    /// Directory the keys of obsolete protocol versions are moved to instead of being removed, if set.
+    pub setup_keys_archive_path: Option<String>,
+    /// Whether the keys of obsolete protocol versions are only reported rather than removed. Disabled by default.
+    pub setup_keys_cleanup_dry_run: Option<bool>,
     // Group id for this prover, provers running the same circuit types shall have same group id.
     pub specialized_prover_group_id: u8,
     // Number of setup-keys kept in memory without swapping
@@ -101,11 +128,22 @@ mod tests {
                 prometheus_port: 3313,
                 initial_setup_key_path: "key".to_owned(),
                 key_download_url: "value".to_owned(),
+                initial_setup_key_checksum: None,
+                setup_key_store_mode: None,
+                key_download_max_retries: None,
+                key_download_initial_backoff_in_millis: None,
+                key_download_max_backoff_in_millis: None,
+                key_download_timeout_in_secs: None,
                 generation_timeout_in_secs: 2700u16,
                 number_of_threads: 2,
                 max_attempts: 4,
                 polling_duration_in_millis: 5,
                 setup_keys_path: "/usr/src/setup-keys".to_string(),
+                setup_keys_download_url: None,
+                setup_keys_protocol_version: None,
+                setup_keys_active_protocol_versions: None,
+                setup_keys_archive_path: None,
+                setup_keys_cleanup_dry_run: None,
                 specialized_prover_group_id: 0,
                 number_of_setup_slots: 2,
                 assembly_receiver_port: 17791,
@@ -116,11 +154,22 @@ mod tests {
                 prometheus_port: 3313,
                 initial_setup_key_path: "key".to_owned(),
                 key_download_url: "value".to_owned(),
+                initial_setup_key_checksum: None,
+                setup_key_store_mode: None,
+                key_download_max_retries: None,
+                key_download_initial_backoff_in_millis: None,
+                key_download_max_backoff_in_millis: None,
+                key_download_timeout_in_secs: None,
                 generation_timeout_in_secs: 2700u16,
                 number_of_threads: 2,
                 max_attempts: 4,
                 polling_duration_in_millis: 5,
                 setup_keys_path: "/usr/src/setup-keys".to_string(),
+                setup_keys_download_url: None,
+                setup_keys_protocol_version: None,
+                setup_keys_active_protocol_versions: None,
+                setup_keys_archive_path: None,
+                setup_keys_cleanup_dry_run: None,
                 specialized_prover_group_id: 1,
                 number_of_setup_slots: 5,
                 assembly_receiver_port: 17791,
@@ -131,11 +180,22 @@ mod tests {
                 prometheus_port: 3313,
                 initial_setup_key_path: "key".to_owned(),
                 key_download_url: "value".to_owned(),
+                initial_setup_key_checksum: None,
+                setup_key_store_mode: None,
+                key_download_max_retries: None,
+                key_download_initial_backoff_in_millis: None,
+                key_download_max_backoff_in_millis: None,
+                key_download_timeout_in_secs: None,
                 generation_timeout_in_secs: 2700u16,
                 number_of_threads: 4,
                 max_attempts: 4,
                 polling_duration_in_millis: 5,
                 setup_keys_path: "/usr/src/setup-keys".to_string(),
+                setup_keys_download_url: None,
+                setup_keys_protocol_version: None,
+                setup_keys_active_protocol_versions: None,
+                setup_keys_archive_path: None,
+                setup_keys_cleanup_dry_run: None,
                 specialized_prover_group_id: 2,
                 number_of_setup_slots: 5,
                 assembly_receiver_port: 17791,
@@ -146,11 +206,22 @@ mod tests {
                 prometheus_port: 3313,
                 initial_setup_key_path: "key".to_owned(),
                 key_download_url: "value".to_owned(),
+                initial_setup_key_checksum: None,
+                setup_key_store_mode: None,
+                key_download_max_retries: None,
+                key_download_initial_backoff_in_millis: None,
+                key_download_max_backoff_in_millis: None,
+                key_download_timeout_in_secs: None,
                 generation_timeout_in_secs: 2700u16,
                 number_of_threads: 9,
                 max_attempts: 4,
                 polling_duration_in_millis: 5,
                 setup_keys_path: "/usr/src/setup-keys".to_string(),
+                setup_keys_download_url: None,
+                setup_keys_protocol_version: None,
+                setup_keys_active_protocol_versions: None,
+                setup_keys_archive_path: None,
+                setup_keys_cleanup_dry_run: None,
                 specialized_prover_group_id: 3,
                 number_of_setup_slots: 9,
                 assembly_receiver_port: 17791,
@@ -161,11 +232,22 @@ mod tests {
                 prometheus_port: 3313,
                 initial_setup_key_path: "key".to_owned(),
                 key_download_url: "value".to_owned(),
+                initial_setup_key_checksum: None,
+                setup_key_store_mode: None,
+                key_download_max_retries: None,
+                key_download_initial_backoff_in_millis: None,
+                key_download_max_backoff_in_millis: None,
+                key_download_timeout_in_secs: None,
                 generation_timeout_in_secs: 2700u16,
                 number_of_threads: 18,
                 max_attempts: 4,
                 polling_duration_in_millis: 5,
                 setup_keys_path: "/usr/src/setup-keys".to_string(),
+                setup_keys_download_url: None,
+                setup_keys_protocol_version: None,
+                setup_keys_active_protocol_versions: None,
+                setup_keys_archive_path: None,
+                setup_keys_cleanup_dry_run: None,
                 specialized_prover_group_id: 4,
                 number_of_setup_slots: 18,
                 assembly_receiver_port: 17791,
@@ -271,11 +353,22 @@ PROVER_FOUR_GPU_EIGHTY_GB_MEM_SPECIALIZED_PROVER_GROUP_ID=4
             prometheus_port: 3313,
             initial_setup_key_path: "key".to_owned(),
             key_download_url: "value".to_owned(),
+            initial_setup_key_checksum: Some("sha256:00".to_owned()),
+            setup_key_store_mode: Some("GCS".to_owned()),
+            key_download_max_retries: Some(3),
+            key_download_initial_backoff_in_millis: Some(500),
+            key_download_max_backoff_in_millis: Some(10_000),
+            key_download_timeout_in_secs: Some(60),
             generation_timeout_in_secs: 2700u16,
             number_of_threads: 2,
             max_attempts: 4,
             polling_duration_in_millis: 5,
             setup_keys_path: "/usr/src/setup-keys".to_string(),
+            setup_keys_download_url: Some("https://example.com/setup-keys".to_owned()),
+            setup_keys_protocol_version: Some(5),
+            setup_keys_active_protocol_versions: Some(vec![3, 4]),
+            setup_keys_archive_path: Some("/usr/src/setup-keys-archive".to_owned()),
+            setup_keys_cleanup_dry_run: Some(true),
             specialized_prover_group_id: 0,
             number_of_setup_slots: 11,
             assembly_receiver_port: 17791,
@@ -287,11 +380,28 @@ PROVER_FOUR_GPU_EIGHTY_GB_MEM_SPECIALIZED_PROVER_GROUP_ID=4
             ("PROVER_PROMETHEUS_PORT", "3313"),
             ("PROVER_INITIAL_SETUP_KEY_PATH", "key"),
             ("PROVER_KEY_DOWNLOAD_URL", "value"),
+            ("PROVER_INITIAL_SETUP_KEY_CHECKSUM", "sha256:00"),
+            ("PROVER_SETUP_KEY_STORE_MODE", "GCS"),
+            ("PROVER_KEY_DOWNLOAD_MAX_RETRIES", "3"),
+            ("PROVER_KEY_DOWNLOAD_INITIAL_BACKOFF_IN_MILLIS", "500"),
+            ("PROVER_KEY_DOWNLOAD_MAX_BACKOFF_IN_MILLIS", "10000"),
+            ("PROVER_KEY_DOWNLOAD_TIMEOUT_IN_SECS", "60"),
             ("PROVER_GENERATION_TIMEOUT_IN_SECS", "2700"),
             ("PROVER_NUMBER_OF_THREADS", "2"),
             ("PROVER_MAX_ATTEMPTS", "4"),
             ("PROVER_POLLING_DURATION_IN_MILLIS", "5"),
             ("PROVER_SETUP_KEYS_PATH", "/usr/src/setup-keys"),
+            (
+                "PROVER_SETUP_KEYS_DOWNLOAD_URL",
+                "https://example.com/setup-keys",
+            ),
+            ("PROVER_SETUP_KEYS_PROTOCOL_VERSION", "5"),
+            ("PROVER_SETUP_KEYS_ACTIVE_PROTOCOL_VERSIONS", "3,4"),
+            (
+                "PROVER_SETUP_KEYS_ARCHIVE_PATH",
+                "/usr/src/setup-keys-archive",
+            ),
+            ("PROVER_SETUP_KEYS_CLEANUP_DRY_RUN", "true"),
             ("PROVER_NUMBER_OF_SETUP_SLOTS", "11"),
             ("PROVER_ASSEMBLY_RECEIVER_PORT", "17791"),
             ("PROVER_ASSEMBLY_RECEIVER_POLL_TIME_IN_MILLIS", "250"),
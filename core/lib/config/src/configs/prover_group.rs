@@ -17,6 +17,9 @@ pub struct ProverGroupConfig {
     pub group_8_circuit_ids: Vec<u8>,
     pub group_9_circuit_ids: Vec<u8>,
     pub region_read_url: String,
+    /// Cloud provider of the node: `GCP`, `AWS` or `Azure`. If not set, the provider is detected
+    /// by probing the metadata services.
+    pub cloud_provider: Option<String>,
 }
 
 impl ProverGroupConfig {
@@ -84,6 +87,7 @@ mod tests {
             group_8_circuit_ids: vec![16, 17],
             group_9_circuit_ids: vec![3],
             region_read_url: "http://metadata.google.internal/computeMetadata/v1/instance/attributes/cluster-location".to_string(),
+            cloud_provider: Some("AWS".to_string()),
         }
     }
 
@@ -99,6 +103,7 @@ mod tests {
         PROVER_GROUP_GROUP_8_CIRCUIT_IDS="16,17"
         PROVER_GROUP_GROUP_9_CIRCUIT_IDS="3"
         PROVER_GROUP_REGION_READ_URL="http://metadata.google.internal/computeMetadata/v1/instance/attributes/cluster-location"
+        PROVER_GROUP_CLOUD_PROVIDER="AWS"
     "#;
 
     #[test]
@@ -16,6 +16,10 @@ pub struct WitnessGeneratorConfig {
     pub initial_setup_key_path: String,
     /// https://storage.googleapis.com/universal-setup/setup_2\^26.key
     pub key_download_url: String,
+    /// Expected checksum of the initial setup key, see `ProverConfig::initial_setup_key_checksum`.
+    pub initial_setup_key_checksum: Option<String>,
+    /// Store the initial setup key is fetched from, see `ProverConfig::setup_key_store_mode`.
+    pub setup_key_store_mode: Option<String>,
     /// Max attempts for generating witness
     pub max_attempts: u32,
     // Percentage of the blocks that gets proven in the range [0.0, 1.0]
@@ -26,6 +30,9 @@ pub struct WitnessGeneratorConfig {
     // This parameter is used in case of performing circuit upgrades(VK/Setup keys),
     // to not let witness-generator pick new job and finish all the existing jobs with old circuit.
     pub last_l1_batch_to_process: Option<u32>,
+    // Optional amount of memory (in MB) available to a witness generator instance.
+    // Basic circuit jobs whose estimated memory footprint exceeds it are left for other instances.
+    pub memory_budget_mb: Option<u64>,
 }
 
 impl WitnessGeneratorConfig {
@@ -40,6 +47,10 @@ impl WitnessGeneratorConfig {
     pub fn last_l1_batch_to_process(&self) -> u32 {
         self.last_l1_batch_to_process.unwrap_or(u32::MAX)
     }
+
+    pub fn memory_budget_bytes(&self) -> Option<u64> {
+        self.memory_budget_mb.map(|mb| mb * 1024 * 1024)
+    }
 }
 
 #[cfg(test)]
@@ -53,10 +64,13 @@ mod tests {
             generation_timeout_in_secs: 900u16,
             initial_setup_key_path: "key".to_owned(),
             key_download_url: "value".to_owned(),
+            initial_setup_key_checksum: Some("sha256:00".to_owned()),
+            setup_key_store_mode: Some("LocalDir".to_owned()),
             max_attempts: 4,
             blocks_proving_percentage: Some(30),
             dump_arguments_for_blocks: vec![2, 3],
             last_l1_batch_to_process: None,
+            memory_budget_mb: Some(65536),
         }
     }
 
@@ -66,9 +80,12 @@ mod tests {
         WITNESS_GENERATION_TIMEOUT_IN_SECS=900
         WITNESS_INITIAL_SETUP_KEY_PATH="key"
         WITNESS_KEY_DOWNLOAD_URL="value"
+        WITNESS_INITIAL_SETUP_KEY_CHECKSUM="sha256:00"
+        WITNESS_SETUP_KEY_STORE_MODE="LocalDir"
         WITNESS_MAX_ATTEMPTS=4
         WITNESS_DUMP_ARGUMENTS_FOR_BLOCKS="2,3"
         WITNESS_BLOCKS_PROVING_PERCENTAGE="30"
+        WITNESS_MEMORY_BUDGET_MB=65536
         "#;
         set_env(config);
         let actual = WitnessGeneratorConfig::from_env();
@@ -0,0 +1,21 @@
+-- Indices on the large tables that must not be locked while the index is being built.
+-- `CREATE INDEX CONCURRENTLY` can't run inside the transaction each migration is applied in, so these statements
+-- are executed one by one by `zk db migrate` after the migrations. Each statement must be idempotent.
+-- If a build is interrupted, the resulting invalid index must be dropped before the statement is rerun.
+
+-- Miniblock timestamps are non-decreasing, so the index allows to find the miniblock
+-- sealed at a given time with a single index lookup.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS miniblocks_timestamp_idx ON miniblocks (timestamp, number);
+
+-- Serves the pagination over the mempool transactions in the order they were received.
+-- The index is small, since it only covers the transactions not yet included into miniblocks.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS transactions_pending_received_at_idx ON transactions (received_at, hash)
+    WHERE miniblock_number IS NULL AND error IS NULL AND is_priority = FALSE;
+
+-- Used to list the transactions sent by / to an account ordered by their position in the chain.
+-- The composite index on `contract_address` supersedes the single-column one.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS transactions_initiator_address_miniblock_number_idx
+    ON transactions (initiator_address, miniblock_number, index_in_block);
+CREATE INDEX CONCURRENTLY IF NOT EXISTS transactions_contract_address_miniblock_number_idx
+    ON transactions (contract_address, miniblock_number, index_in_block);
+DROP INDEX CONCURRENTLY IF EXISTS transactions_contract_address_idx;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS feature_flags
+(
+    name               TEXT PRIMARY KEY,
+    enabled            BOOLEAN NOT NULL DEFAULT FALSE,
+    rollout_percentage SMALLINT NOT NULL DEFAULT 100 CHECK (rollout_percentage BETWEEN 0 AND 100),
+    api_keys           TEXT[] NOT NULL DEFAULT '{}',
+
+    created_at         TIMESTAMP NOT NULL,
+    updated_at         TIMESTAMP NOT NULL
+);
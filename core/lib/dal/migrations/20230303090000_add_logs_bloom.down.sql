@@ -0,0 +1,2 @@
+ALTER TABLE transactions DROP COLUMN IF EXISTS logs_bloom;
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS logs_bloom;
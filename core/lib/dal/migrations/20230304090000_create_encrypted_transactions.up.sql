@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS encrypted_transactions
+(
+    hash        BYTEA PRIMARY KEY,
+    payload     BYTEA NOT NULL,
+    status      TEXT NOT NULL DEFAULT 'pending',
+    tx_hash     BYTEA,
+    error       TEXT,
+
+    received_at TIMESTAMP NOT NULL,
+    created_at  TIMESTAMP NOT NULL,
+    updated_at  TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS encrypted_transactions_pending_idx
+    ON encrypted_transactions (received_at) WHERE status = 'pending';
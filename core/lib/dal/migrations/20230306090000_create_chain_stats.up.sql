@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS chain_stats
+(
+    miniblock_number   BIGINT PRIMARY KEY REFERENCES miniblocks (number) ON DELETE CASCADE,
+    timestamp          BIGINT NOT NULL,
+    total_transactions BIGINT NOT NULL,
+    total_accounts     BIGINT NOT NULL,
+    contracts_deployed BIGINT NOT NULL,
+    total_fees         NUMERIC(80) NOT NULL,
+
+    created_at         TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS chain_stats_timestamp_idx ON chain_stats (timestamp);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS tx_lifecycle_events
+(
+    id               BIGSERIAL PRIMARY KEY,
+    tx_hash          BYTEA NOT NULL,
+    event            VARCHAR NOT NULL,
+    miniblock_number BIGINT,
+    details          VARCHAR,
+
+    created_at       TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS tx_lifecycle_events_tx_hash_idx ON tx_lifecycle_events (tx_hash);
+CREATE INDEX IF NOT EXISTS tx_lifecycle_events_created_at_idx ON tx_lifecycle_events (created_at);
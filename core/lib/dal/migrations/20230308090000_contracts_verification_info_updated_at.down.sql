@@ -0,0 +1 @@
+DROP INDEX IF EXISTS contracts_verification_info_updated_at_idx;
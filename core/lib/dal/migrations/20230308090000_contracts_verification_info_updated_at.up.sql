@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS contracts_verification_info_updated_at_idx ON contracts_verification_info (updated_at);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS connection_pool_sizes
+(
+    name       TEXT PRIMARY KEY,
+    max_size   INT NOT NULL,
+
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+ALTER TABLE transactions DROP COLUMN IF EXISTS transfer_to;
+ALTER TABLE transactions DROP COLUMN IF EXISTS factory_dep_hashes;
+ALTER TABLE transactions DROP COLUMN IF EXISTS calldata_length;
@@ -0,0 +1,4 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS calldata_length INT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS factory_dep_hashes BYTEA[];
+-- Recipient of the legacy transfers, which stored it in `data->'to'`. Not set for the new transactions.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS transfer_to BYTEA;
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS transactions_execute_columns_backfill_idx;
@@ -0,0 +1,5 @@
+-- The columns of the transactions inserted before they were added are filled in batches by the housekeeper,
+-- so that the backfill doesn't rewrite the whole table in a single transaction. The index keeps finding
+-- the next batch cheap; it's empty once the backfill is complete.
+CREATE INDEX IF NOT EXISTS transactions_execute_columns_backfill_idx
+    ON transactions (hash) WHERE calldata_length IS NULL;
@@ -0,0 +1,12 @@
+-- Partitioned by day, partitions are created and dropped by the API server according to the retention.
+CREATE TABLE IF NOT EXISTS api_audit_log (
+    method TEXT NOT NULL,
+    params_hash BYTEA NOT NULL,
+    caller_key TEXT,
+    latency_ms INT NOT NULL,
+    result_code BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+) PARTITION BY RANGE (created_at);
+
+CREATE INDEX IF NOT EXISTS api_audit_log_method_created_at_idx ON api_audit_log (method, created_at);
+CREATE INDEX IF NOT EXISTS api_audit_log_caller_key_created_at_idx ON api_audit_log (caller_key, created_at);
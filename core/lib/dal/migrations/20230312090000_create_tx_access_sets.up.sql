@@ -0,0 +1,10 @@
+-- Storage slots accessed by the transactions, recorded at seal time if enabled in the state keeper config.
+-- The keys are hashed storage keys concatenated into a single byte array (32 bytes per key).
+CREATE TABLE IF NOT EXISTS tx_access_sets (
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    index_in_block INT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    read_keys BYTEA NOT NULL,
+    written_keys BYTEA NOT NULL,
+    PRIMARY KEY (miniblock_number, index_in_block)
+);
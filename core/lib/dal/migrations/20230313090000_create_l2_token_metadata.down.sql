@@ -0,0 +1 @@
+DROP TABLE IF EXISTS l2_token_metadata;
@@ -0,0 +1,16 @@
+-- Metadata of the L2 tokens read from the token contracts by the token metadata fetcher.
+-- Tokens are observed by their ERC20 `Transfer` events; the metadata columns are NULL
+-- if the corresponding method reverted or the token wasn't processed yet.
+CREATE TABLE IF NOT EXISTS l2_token_metadata (
+    l2_address BYTEA PRIMARY KEY,
+    first_seen_miniblock BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    name VARCHAR,
+    symbol VARCHAR,
+    decimals INT,
+    refreshed_at TIMESTAMP,
+
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS l2_token_metadata_refreshed_at_idx ON l2_token_metadata (refreshed_at NULLS FIRST);
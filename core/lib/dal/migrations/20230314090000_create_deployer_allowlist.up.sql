@@ -0,0 +1,5 @@
+-- Accounts allowed to deploy contracts if the permissioned deployment mode is enabled in the state keeper config.
+CREATE TABLE IF NOT EXISTS deployer_allowlist (
+    address BYTEA PRIMARY KEY,
+    created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS expired_priority_ops;
+ALTER TABLE transactions DROP COLUMN IF EXISTS l1_deadline_timestamp;
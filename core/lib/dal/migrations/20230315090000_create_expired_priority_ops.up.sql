@@ -0,0 +1,11 @@
+-- Timestamp of the L1 block until which the priority operation must be processed. Before, the deadline wasn't persisted.
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS l1_deadline_timestamp BIGINT;
+
+-- Priority operations which weren't executed on L1 until their deadline block.
+CREATE TABLE IF NOT EXISTS expired_priority_ops (
+    priority_op_id BIGINT PRIMARY KEY,
+    tx_hash BYTEA NOT NULL,
+    deadline_block BIGINT NOT NULL,
+    detected_at_block BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,12 @@
+-- `BlockCommit`, `BlocksVerification` and `BlockExecution` events of the main contract indexed by the Ethereum watcher.
+-- A batch may have several events of the same kind if it was reverted on L1 and committed again.
+CREATE TABLE IF NOT EXISTS l1_batch_l1_events (
+    l1_batch_number BIGINT NOT NULL,
+    event VARCHAR NOT NULL,
+    l1_tx_hash BYTEA NOT NULL,
+    l1_block_number BIGINT NOT NULL,
+    block_hash BYTEA,
+    commitment BYTEA,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, event, l1_tx_hash)
+);
@@ -0,0 +1 @@
+-- The backfilled values are correct, so they are not reverted.
@@ -0,0 +1,7 @@
+-- Miniblocks created before the fee input columns were added have zeros there. All the miniblocks of a batch
+-- are executed with the fee input of the batch, so it's copied from the batch.
+UPDATE miniblocks
+SET l1_gas_price = l1_batches.l1_gas_price, l2_fair_gas_price = l1_batches.l2_fair_gas_price
+FROM l1_batches
+WHERE miniblocks.l1_batch_number = l1_batches.number
+    AND (miniblocks.l1_gas_price = 0 OR miniblocks.l2_fair_gas_price = 0);
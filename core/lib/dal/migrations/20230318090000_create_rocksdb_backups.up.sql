@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS rocksdb_backups
+(
+    id              BIGSERIAL PRIMARY KEY,
+    target          TEXT      NOT NULL,
+    l1_batch_number BIGINT    NOT NULL,
+    object_keys     TEXT[]    NOT NULL,
+    created_at      TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS rocksdb_backups_target_l1_batch_number_idx
+    ON rocksdb_backups (target, l1_batch_number);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS reorged_miniblocks;
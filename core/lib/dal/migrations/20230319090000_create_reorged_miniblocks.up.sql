@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS reorged_miniblocks
+(
+    hash       BYTEA     PRIMARY KEY,
+    number     BIGINT    NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS instance_heartbeats
+(
+    instance_id                     TEXT      PRIMARY KEY,
+    components                      TEXT      NOT NULL,
+    schema_version                  BIGINT    NOT NULL,
+    compatible_since_schema_version BIGINT    NOT NULL,
+    started_at                      TIMESTAMP NOT NULL,
+    heartbeat_at                    TIMESTAMP NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Usage of the API metered per API key, aggregated over the export periods.
+CREATE TABLE IF NOT EXISTS api_usage_records (
+    id BIGSERIAL PRIMARY KEY,
+    api_key_hash TEXT NOT NULL,
+    method TEXT NOT NULL,
+    requests BIGINT NOT NULL,
+    compute_units BIGINT NOT NULL,
+    period_start TIMESTAMP NOT NULL,
+    period_end TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS api_usage_records_api_key_hash_period_start_idx ON api_usage_records (api_key_hash, period_start);
@@ -0,0 +1,9 @@
+-- Transactions which were being executed when the state keeper crashed. Once a transaction crashes the state keeper
+-- the configured number of times in a row, it's quarantined: excluded from the mempool until released by the operator.
+CREATE TABLE IF NOT EXISTS quarantined_transactions (
+    hash BYTEA PRIMARY KEY,
+    crash_count INT NOT NULL,
+    last_crashed_at TIMESTAMP NOT NULL,
+    quarantined_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL
+);
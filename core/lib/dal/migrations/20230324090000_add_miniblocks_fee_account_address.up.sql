@@ -0,0 +1,6 @@
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS fee_account_address BYTEA;
+
+-- The fee account used to be recorded only per L1 batch, so the miniblocks of the pending batch are left unset.
+UPDATE miniblocks SET fee_account_address = l1_batches.fee_account_address
+FROM l1_batches
+WHERE miniblocks.l1_batch_number = l1_batches.number AND miniblocks.fee_account_address IS NULL;
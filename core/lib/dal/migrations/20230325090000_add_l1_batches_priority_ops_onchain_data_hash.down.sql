@@ -0,0 +1 @@
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS priority_ops_onchain_data_hash;
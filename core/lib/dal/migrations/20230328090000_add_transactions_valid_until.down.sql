@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS transactions_valid_until_idx;
+ALTER TABLE transactions DROP COLUMN IF EXISTS valid_until;
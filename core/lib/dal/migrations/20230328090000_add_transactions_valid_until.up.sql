@@ -0,0 +1,4 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS valid_until BIGINT;
+-- Used by the housekeeper to find expired transactions that are still pending.
+CREATE INDEX IF NOT EXISTS transactions_valid_until_idx ON transactions (valid_until)
+    WHERE miniblock_number IS NULL AND valid_until IS NOT NULL;
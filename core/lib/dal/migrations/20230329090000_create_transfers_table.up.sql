@@ -0,0 +1,16 @@
+-- ETH and ERC-20 token transfers, extracted from the `Transfer` events when a miniblock is sealed.
+-- `event_index_in_block` matches the index of the source event in the `events` table.
+CREATE TABLE IF NOT EXISTS transfers (
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    event_index_in_block INT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    token_address BYTEA NOT NULL,
+    from_address BYTEA NOT NULL,
+    to_address BYTEA NOT NULL,
+    amount NUMERIC(80) NOT NULL,
+    PRIMARY KEY (miniblock_number, event_index_in_block)
+);
+CREATE INDEX IF NOT EXISTS transfers_from_address_idx
+    ON transfers (from_address, miniblock_number, event_index_in_block);
+CREATE INDEX IF NOT EXISTS transfers_to_address_idx
+    ON transfers (to_address, miniblock_number, event_index_in_block);
@@ -0,0 +1,17 @@
+-- Utilization of the bootloader resources limiting the number of transactions in an L1 batch, compared to their limits.
+-- Recorded by the state keeper when the batch is sealed, if enabled.
+CREATE TABLE IF NOT EXISTS l1_batch_bootloader_usage (
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    tx_count INT NOT NULL,
+    tx_slots INT NOT NULL,
+    txs_encoding_words INT NOT NULL,
+    txs_encoding_limit INT NOT NULL,
+    compressed_bytecodes_words INT NOT NULL,
+    compressed_bytecodes_limit INT NOT NULL,
+    gas_used BIGINT NOT NULL,
+    gas_limit BIGINT NOT NULL,
+    pubdata_bytes INT NOT NULL,
+    max_tx_pubdata_bytes INT NOT NULL,
+    pubdata_limit INT NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
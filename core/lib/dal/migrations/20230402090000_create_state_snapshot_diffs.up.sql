@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS state_snapshot_diffs
+(
+    id                     BIGSERIAL PRIMARY KEY,
+    base_l1_batch_number   BIGINT    NOT NULL,
+    l1_batch_number        BIGINT    NOT NULL,
+    first_miniblock_number BIGINT    NOT NULL,
+    object_key             TEXT      NOT NULL,
+    created_at             TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS state_snapshot_diffs_l1_batch_number_idx
+    ON state_snapshot_diffs (l1_batch_number);
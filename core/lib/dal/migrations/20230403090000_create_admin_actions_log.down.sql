@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS admin_actions_log;
+DROP FUNCTION IF EXISTS admin_actions_log_append_only;
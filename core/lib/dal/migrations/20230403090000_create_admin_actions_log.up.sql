@@ -0,0 +1,21 @@
+-- Append-only audit trail of the state-changing `admin` API calls, together with the admin keys authorizing them.
+-- The hash of the signed message is unique, so that an authorization can't be replayed.
+CREATE TABLE IF NOT EXISTS admin_actions_log (
+    id BIGSERIAL PRIMARY KEY,
+    action TEXT NOT NULL,
+    params JSONB NOT NULL,
+    signers BYTEA[] NOT NULL,
+    message_hash BYTEA NOT NULL UNIQUE,
+    applied BOOLEAN NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION admin_actions_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'admin_actions_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER admin_actions_log_append_only
+    BEFORE UPDATE OR DELETE ON admin_actions_log
+    FOR EACH ROW EXECUTE FUNCTION admin_actions_log_append_only();
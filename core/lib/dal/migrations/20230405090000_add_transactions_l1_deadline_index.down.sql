@@ -0,0 +1 @@
+DROP INDEX IF EXISTS transactions_l1_deadline_timestamp_idx;
@@ -0,0 +1,5 @@
+-- The index is used by the Ethereum watcher to find the operations past their deadline. It's keyed by the L1 batch
+-- number first, so that only the operations not yet executed on L1 are scanned rather than the whole history.
+CREATE INDEX IF NOT EXISTS transactions_l1_deadline_timestamp_idx
+    ON transactions (l1_batch_number, l1_deadline_timestamp)
+    WHERE is_priority = TRUE;
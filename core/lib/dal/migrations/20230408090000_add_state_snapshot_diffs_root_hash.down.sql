@@ -0,0 +1 @@
+ALTER TABLE state_snapshot_diffs DROP COLUMN IF EXISTS root_hash;
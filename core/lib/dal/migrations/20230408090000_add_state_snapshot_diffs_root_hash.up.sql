@@ -0,0 +1 @@
+ALTER TABLE state_snapshot_diffs ADD COLUMN IF NOT EXISTS root_hash BYTEA;
@@ -0,0 +1,2 @@
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS gas_per_pubdata;
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS tx_rolling_hash;
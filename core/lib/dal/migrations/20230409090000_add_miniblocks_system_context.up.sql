@@ -0,0 +1,2 @@
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS gas_per_pubdata BIGINT;
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS tx_rolling_hash BYTEA;
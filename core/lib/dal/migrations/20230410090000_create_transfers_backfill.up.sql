@@ -0,0 +1,15 @@
+-- Progress of the backfill of the `transfers` table for the miniblocks sealed before the table was added.
+-- The miniblocks are processed from `next_miniblock_number` down to the genesis; the row is removed once
+-- the backfill is complete. Miniblocks sealed since the table was added are either above the first saved
+-- transfer or have no transfers, so reprocessing them is a no-op.
+CREATE TABLE IF NOT EXISTS transfers_backfill (
+    next_miniblock_number BIGINT NOT NULL
+);
+INSERT INTO transfers_backfill (next_miniblock_number)
+SELECT number FROM (
+    SELECT COALESCE(
+        (SELECT MIN(miniblock_number) - 1 FROM transfers),
+        (SELECT MAX(number) FROM miniblocks)
+    ) AS number
+) AS start
+WHERE number >= 0;
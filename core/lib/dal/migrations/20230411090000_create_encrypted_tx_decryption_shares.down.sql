@@ -0,0 +1 @@
+DROP TABLE IF EXISTS encrypted_transaction_decryption_shares;
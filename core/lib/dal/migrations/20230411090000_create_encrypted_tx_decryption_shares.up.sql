@@ -0,0 +1,11 @@
+-- Decryption shares published by the members of the encrypted mempool committee. An encrypted transaction
+-- is decrypted once the shares of the threshold number of members are available.
+CREATE TABLE IF NOT EXISTS encrypted_transaction_decryption_shares
+(
+    encrypted_tx_hash BYTEA NOT NULL REFERENCES encrypted_transactions (hash) ON DELETE CASCADE,
+    member_index      INT NOT NULL,
+    share             BYTEA NOT NULL,
+
+    created_at        TIMESTAMP NOT NULL,
+    PRIMARY KEY (encrypted_tx_hash, member_index)
+);
@@ -0,0 +1 @@
+ALTER TABLE miniblocks DROP COLUMN IF EXISTS execution_digest_version;
@@ -0,0 +1,2 @@
+ALTER TABLE gpu_prover_queue
+    ADD COLUMN IF NOT EXISTS zone TEXT;
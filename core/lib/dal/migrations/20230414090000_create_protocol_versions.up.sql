@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS protocol_versions
+(
+    id                       INT PRIMARY KEY,
+    activated_at_miniblock   BIGINT NOT NULL,
+    block_gas_limit          BIGINT NOT NULL,
+    max_allowed_tx_gas_limit BIGINT NOT NULL,
+
+    created_at               TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS protocol_versions_activated_at_miniblock_idx
+    ON protocol_versions (activated_at_miniblock);
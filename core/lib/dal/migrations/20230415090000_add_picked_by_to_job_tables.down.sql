@@ -0,0 +1,6 @@
+ALTER TABLE witness_inputs DROP COLUMN IF EXISTS picked_by;
+ALTER TABLE leaf_aggregation_witness_jobs DROP COLUMN IF EXISTS picked_by;
+ALTER TABLE node_aggregation_witness_jobs DROP COLUMN IF EXISTS picked_by;
+ALTER TABLE scheduler_witness_jobs DROP COLUMN IF EXISTS picked_by;
+ALTER TABLE prover_jobs DROP COLUMN IF EXISTS picked_by;
+ALTER TABLE contract_verification_requests DROP COLUMN IF EXISTS picked_by;
@@ -0,0 +1,6 @@
+ALTER TABLE witness_inputs ADD COLUMN IF NOT EXISTS picked_by TEXT;
+ALTER TABLE leaf_aggregation_witness_jobs ADD COLUMN IF NOT EXISTS picked_by TEXT;
+ALTER TABLE node_aggregation_witness_jobs ADD COLUMN IF NOT EXISTS picked_by TEXT;
+ALTER TABLE scheduler_witness_jobs ADD COLUMN IF NOT EXISTS picked_by TEXT;
+ALTER TABLE prover_jobs ADD COLUMN IF NOT EXISTS picked_by TEXT;
+ALTER TABLE contract_verification_requests ADD COLUMN IF NOT EXISTS picked_by TEXT;
@@ -0,0 +1,101 @@
+use sqlx::types::chrono::NaiveDateTime;
+use zksync_types::{Address, H256};
+
+use crate::StorageProcessor;
+
+/// State-changing `admin` API call recorded into the audit trail.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminActionRecord {
+    pub action: String,
+    pub params: serde_json::Value,
+    /// Admin keys whose signatures authorized the call.
+    pub signers: Vec<Address>,
+    /// Hash of the message signed by the admin keys.
+    pub message_hash: H256,
+    /// Whether the call has changed anything.
+    pub applied: bool,
+    pub created_at: NaiveDateTime,
+}
+
+/// Append-only audit trail of the state-changing `admin` API calls. Updates and deletions
+/// are rejected by the database.
+#[derive(Debug)]
+pub struct AdminActionsLogDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl AdminActionsLogDal<'_, '_> {
+    /// Returns `false` if an action authorized by the same signed message is already recorded.
+    pub fn insert_action(&mut self, record: &AdminActionRecord) -> bool {
+        async_std::task::block_on(async {
+            let signers: Vec<_> = record
+                .signers
+                .iter()
+                .map(|address| address.as_bytes().to_vec())
+                .collect();
+            let result = sqlx::query!(
+                "
+                INSERT INTO admin_actions_log (action, params, signers, message_hash, applied, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (message_hash) DO NOTHING
+                ",
+                record.action,
+                record.params,
+                &signers,
+                record.message_hash.as_bytes(),
+                record.applied,
+                record.created_at
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            result.rows_affected() > 0
+        })
+    }
+
+    /// Checks whether an action authorized by the signed message is already recorded.
+    pub fn contains_action(&mut self, message_hash: H256) -> bool {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"SELECT EXISTS(SELECT 1 FROM admin_actions_log WHERE message_hash = $1) as "exists!""#,
+                message_hash.as_bytes()
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .exists
+        })
+    }
+
+    /// Returns the latest recorded actions, the most recent ones first.
+    pub fn get_recent_actions(&mut self, limit: usize) -> Vec<AdminActionRecord> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT action, params, signers, message_hash, applied, created_at
+                FROM admin_actions_log
+                ORDER BY id DESC
+                LIMIT $1
+                ",
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| AdminActionRecord {
+                action: row.action,
+                params: row.params,
+                signers: row
+                    .signers
+                    .iter()
+                    .map(|address| Address::from_slice(address))
+                    .collect(),
+                message_hash: H256::from_slice(&row.message_hash),
+                applied: row.applied,
+                created_at: row.created_at,
+            })
+            .collect()
+        })
+    }
+}
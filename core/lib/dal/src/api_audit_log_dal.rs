@@ -0,0 +1,122 @@
+use sqlx::types::chrono::{NaiveDate, NaiveDateTime};
+
+use crate::{SqlxError, StorageProcessor};
+
+/// Sampled API request recorded into the audit log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiAuditRecord {
+    pub method: String,
+    pub params_hash: Vec<u8>,
+    pub caller_key: Option<String>,
+    pub latency_ms: u32,
+    /// 0 for the successful requests, JSON-RPC error code otherwise.
+    pub result_code: i64,
+    pub created_at: NaiveDateTime,
+}
+
+/// Audit log of the API requests. The table is partitioned by day, the partitions have to be
+/// created before the records of the day are inserted.
+#[derive(Debug)]
+pub struct ApiAuditLogDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ApiAuditLogDal<'_, '_> {
+    const PARTITION_PREFIX: &'static str = "api_audit_log_";
+    const PARTITION_DATE_FORMAT: &'static str = "%Y%m%d";
+
+    fn partition_name(day: NaiveDate) -> String {
+        format!(
+            "{}{}",
+            Self::PARTITION_PREFIX,
+            day.format(Self::PARTITION_DATE_FORMAT)
+        )
+    }
+
+    /// Creates the partition for the records of the given day if it doesn't exist.
+    pub fn create_partition(&mut self, day: NaiveDate) -> Result<(), SqlxError> {
+        async_std::task::block_on(async {
+            let next_day = day.succ_opt().expect("date out of range");
+            // Partition DDL doesn't support bind parameters; the values are generated by us.
+            let query = format!(
+                "CREATE TABLE IF NOT EXISTS {} PARTITION OF api_audit_log FOR VALUES FROM ('{}') TO ('{}')",
+                Self::partition_name(day),
+                day,
+                next_day
+            );
+            sqlx::query(&query).execute(self.storage.conn()).await?;
+            Ok(())
+        })
+    }
+
+    /// Drops the partitions with the records older than the given day. Returns the names of the dropped partitions.
+    pub fn drop_partitions_before(&mut self, day: NaiveDate) -> Result<Vec<String>, SqlxError> {
+        async_std::task::block_on(async {
+            let partitions = sqlx::query!(
+                r#"
+                SELECT child.relname as "name!"
+                FROM pg_inherits
+                JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
+                JOIN pg_class child ON pg_inherits.inhrelid = child.oid
+                WHERE parent.relname = 'api_audit_log'
+                "#
+            )
+            .fetch_all(self.storage.conn())
+            .await?;
+
+            let mut dropped = vec![];
+            for partition in partitions {
+                let partition_day = partition
+                    .name
+                    .strip_prefix(Self::PARTITION_PREFIX)
+                    .and_then(|date| {
+                        NaiveDate::parse_from_str(date, Self::PARTITION_DATE_FORMAT).ok()
+                    });
+                if partition_day.map_or(false, |partition_day| partition_day < day) {
+                    let query = format!("DROP TABLE IF EXISTS {}", partition.name);
+                    sqlx::query(&query).execute(self.storage.conn()).await?;
+                    dropped.push(partition.name);
+                }
+            }
+            Ok(dropped)
+        })
+    }
+
+    pub fn insert_records(&mut self, records: &[ApiAuditRecord]) {
+        async_std::task::block_on(async {
+            let mut methods = Vec::with_capacity(records.len());
+            let mut params_hashes = Vec::with_capacity(records.len());
+            let mut caller_keys = Vec::with_capacity(records.len());
+            let mut latencies = Vec::with_capacity(records.len());
+            let mut result_codes = Vec::with_capacity(records.len());
+            let mut created_at = Vec::with_capacity(records.len());
+            for record in records {
+                methods.push(record.method.clone());
+                params_hashes.push(record.params_hash.clone());
+                // Arrays with NULLs can't be passed, so the missing keys are sent as empty strings.
+                caller_keys.push(record.caller_key.clone().unwrap_or_default());
+                latencies.push(record.latency_ms as i32);
+                result_codes.push(record.result_code);
+                created_at.push(record.created_at);
+            }
+
+            sqlx::query!(
+                r#"
+                INSERT INTO api_audit_log (method, params_hash, caller_key, latency_ms, result_code, created_at)
+                SELECT method, params_hash, NULLIF(caller_key, ''), latency_ms, result_code, created_at
+                FROM UNNEST($1::text[], $2::bytea[], $3::text[], $4::int[], $5::bigint[], $6::timestamp[])
+                    AS t(method, params_hash, caller_key, latency_ms, result_code, created_at)
+                "#,
+                &methods,
+                &params_hashes,
+                &caller_keys,
+                &latencies,
+                &result_codes,
+                &created_at
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use sqlx::types::chrono::NaiveDateTime;
+
+use crate::StorageProcessor;
+
+/// API usage of a single key for a single method, aggregated over the export period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiUsageRecord {
+    /// Hash of the API key, as recorded into the audit log.
+    pub api_key_hash: String,
+    pub method: String,
+    pub requests: u64,
+    pub compute_units: u64,
+    pub period_start: NaiveDateTime,
+    pub period_end: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct ApiUsageDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ApiUsageDal<'_, '_> {
+    pub fn insert_records(&mut self, records: &[ApiUsageRecord]) {
+        async_std::task::block_on(async {
+            let mut api_key_hashes = Vec::with_capacity(records.len());
+            let mut methods = Vec::with_capacity(records.len());
+            let mut requests = Vec::with_capacity(records.len());
+            let mut compute_units = Vec::with_capacity(records.len());
+            let mut period_starts = Vec::with_capacity(records.len());
+            let mut period_ends = Vec::with_capacity(records.len());
+            for record in records {
+                api_key_hashes.push(record.api_key_hash.clone());
+                methods.push(record.method.clone());
+                requests.push(record.requests as i64);
+                compute_units.push(record.compute_units as i64);
+                period_starts.push(record.period_start);
+                period_ends.push(record.period_end);
+            }
+
+            sqlx::query!(
+                r#"
+                INSERT INTO api_usage_records (api_key_hash, method, requests, compute_units, period_start, period_end)
+                SELECT * FROM UNNEST($1::text[], $2::text[], $3::bigint[], $4::bigint[], $5::timestamp[], $6::timestamp[])
+                "#,
+                &api_key_hashes,
+                &methods,
+                &requests,
+                &compute_units,
+                &period_starts,
+                &period_ends
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the usage of the key per method, summed over the periods started at or after `since`.
+    pub fn get_usage(&mut self, api_key_hash: &str, since: NaiveDateTime) -> Vec<ApiUsageRecord> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"
+                SELECT method, SUM(requests)::bigint as "requests!", SUM(compute_units)::bigint as "compute_units!",
+                    MIN(period_start) as "period_start!", MAX(period_end) as "period_end!"
+                FROM api_usage_records
+                WHERE api_key_hash = $1 AND period_start >= $2
+                GROUP BY method
+                ORDER BY method
+                "#,
+                api_key_hash,
+                since
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| ApiUsageRecord {
+                api_key_hash: api_key_hash.to_owned(),
+                method: row.method,
+                requests: row.requests as u64,
+                compute_units: row.compute_units as u64,
+                period_start: row.period_start,
+                period_end: row.period_end,
+            })
+            .collect()
+        })
+    }
+}
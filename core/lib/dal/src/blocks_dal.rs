@@ -18,7 +18,7 @@ use zksync_types::{
 };
 
 use crate::{
-    models::storage_block::{StorageBlock, StorageMiniblockHeader},
+    models::storage_block::{execution_digest_version, StorageBlock, StorageMiniblockHeader},
     StorageProcessor,
 };
 
@@ -117,6 +117,22 @@ impl BlocksDal<'_, '_> {
         self.get_storage_block(number).map(Into::into)
     }
 
+    /// Returns the rolling hash of the priority operations recorded when the L1 batch was sealed.
+    /// Absent for the batches sealed before it started to be recorded.
+    pub fn get_l1_batch_priority_ops_hash(&mut self, number: L1BatchNumber) -> Option<H256> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "SELECT priority_ops_onchain_data_hash FROM l1_batches WHERE number = $1",
+                number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .and_then(|row| row.priority_ops_onchain_data_hash)
+            .map(|hash| H256::from_slice(&hash))
+        })
+    }
+
     pub fn set_eth_tx_id(
         &mut self,
         first_block: L1BatchNumber,
@@ -191,15 +207,16 @@ impl BlocksDal<'_, '_> {
 
             let base_fee_per_gas = BigDecimal::from_u64(block.base_fee_per_gas)
                 .expect("block.base_fee_per_gas should fit in u64");
+            let priority_ops_onchain_data_hash = block.priority_ops_onchain_data_hash();
 
             sqlx::query!(
             "INSERT INTO l1_batches (number, l1_tx_count, l2_tx_count,
             timestamp, is_finished, fee_account_address, l2_to_l1_logs, l2_to_l1_messages, bloom, priority_ops_onchain_data,
             predicted_commit_gas_cost, predicted_prove_gas_cost, predicted_execute_gas_cost,
             initial_bootloader_heap_content, used_contract_hashes, base_fee_per_gas, l1_gas_price, l2_fair_gas_price,
-            bootloader_code_hash, default_aa_code_hash,
+            bootloader_code_hash, default_aa_code_hash, priority_ops_onchain_data_hash,
                 created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, now(), now())
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, now(), now())
             ",
             block.number.0 as i64,
             block.l1_tx_count as i32,
@@ -226,7 +243,8 @@ impl BlocksDal<'_, '_> {
             block
                 .base_system_contracts_hashes
                 .default_aa
-                .as_bytes()
+                .as_bytes(),
+            priority_ops_onchain_data_hash.as_bytes()
             )
                 .execute(self.storage.conn())
                 .await
@@ -235,6 +253,30 @@ impl BlocksDal<'_, '_> {
     }
 
     pub fn insert_miniblock(&mut self, miniblock_header: MiniblockHeader) {
+        self.insert_miniblock_inner(miniblock_header, None, None);
+    }
+
+    /// Saves the miniblock along with the system context values it was executed with that aren't a part
+    /// of its header, which are returned by `zks_getSystemContext`.
+    pub fn insert_miniblock_with_system_context(
+        &mut self,
+        miniblock_header: MiniblockHeader,
+        gas_per_pubdata: u64,
+        tx_rolling_hash: H256,
+    ) {
+        self.insert_miniblock_inner(
+            miniblock_header,
+            Some(gas_per_pubdata),
+            Some(tx_rolling_hash),
+        );
+    }
+
+    fn insert_miniblock_inner(
+        &mut self,
+        miniblock_header: MiniblockHeader,
+        gas_per_pubdata: Option<u64>,
+        tx_rolling_hash: Option<H256>,
+    ) {
         let base_fee_per_gas = BigDecimal::from_u64(miniblock_header.base_fee_per_gas)
             .expect("base_fee_per_gas should fit in u64");
         async_std::task::block_on(async {
@@ -243,10 +285,10 @@ impl BlocksDal<'_, '_> {
                     INSERT INTO miniblocks (
                         number, timestamp, hash, l1_tx_count, l2_tx_count,
                         base_fee_per_gas, l1_gas_price, l2_fair_gas_price, gas_per_pubdata_limit, 
-                        bootloader_code_hash, default_aa_code_hash,
-                        created_at, updated_at
+                        bootloader_code_hash, default_aa_code_hash, logs_bloom, fee_account_address,
+                        gas_per_pubdata, tx_rolling_hash, created_at, updated_at
                     )
-                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, now(), now())
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, now(), now())
                 ",
                 miniblock_header.number.0 as i64,
                 miniblock_header.timestamp as i64,
@@ -265,6 +307,10 @@ impl BlocksDal<'_, '_> {
                     .base_system_contracts_hashes
                     .default_aa
                     .as_bytes(),
+                miniblock_header.logs_bloom.as_bytes(),
+                miniblock_header.fee_account_address.as_bytes(),
+                gas_per_pubdata.map(|gas_per_pubdata| gas_per_pubdata as i64),
+                tx_rolling_hash.as_ref().map(H256::as_bytes),
             )
             .execute(self.storage.conn())
             .await
@@ -279,7 +325,7 @@ impl BlocksDal<'_, '_> {
                 "
                     SELECT number, timestamp, hash, l1_tx_count, l2_tx_count,
                         base_fee_per_gas, l1_gas_price, l2_fair_gas_price,
-                        bootloader_code_hash, default_aa_code_hash
+                        bootloader_code_hash, default_aa_code_hash, logs_bloom, fee_account_address
                     FROM miniblocks
                     ORDER BY number DESC 
                     LIMIT 1
@@ -302,7 +348,7 @@ impl BlocksDal<'_, '_> {
                 "
                     SELECT number, timestamp, hash, l1_tx_count, l2_tx_count,
                         base_fee_per_gas, l1_gas_price, l2_fair_gas_price,
-                        bootloader_code_hash, default_aa_code_hash
+                        bootloader_code_hash, default_aa_code_hash, logs_bloom, fee_account_address
                     FROM miniblocks
                     WHERE number = $1
                 ",
@@ -315,6 +361,49 @@ impl BlocksDal<'_, '_> {
         })
     }
 
+    /// Saves the digest of the canonically serialized execution outcomes of the miniblock transactions,
+    /// which is used by the external nodes to cross-validate their execution, along with the version
+    /// of the serialization.
+    pub fn set_miniblock_execution_digest(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        execution_digest: H256,
+        serialization_version: u8,
+    ) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "UPDATE miniblocks SET execution_digest = $2, execution_digest_version = $3 WHERE number = $1",
+                miniblock_number.0 as i64,
+                execution_digest.as_bytes(),
+                serialization_version as i32,
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the execution digest of the miniblock along with its serialization version.
+    pub fn get_miniblock_execution_digest(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Option<(H256, u8)> {
+        async_std::task::block_on(async {
+            let row = sqlx::query!(
+                "SELECT execution_digest, execution_digest_version FROM miniblocks WHERE number = $1",
+                miniblock_number.0 as i64,
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()?;
+            let digest = H256::from_slice(&row.execution_digest?);
+            Some((
+                digest,
+                execution_digest_version(row.execution_digest_version),
+            ))
+        })
+    }
+
     pub fn mark_miniblocks_as_executed_in_l1_batch(&mut self, l1_batch_number: L1BatchNumber) {
         async_std::task::block_on(async {
             sqlx::query!(
@@ -503,7 +592,7 @@ impl BlocksDal<'_, '_> {
             let l1_batches = sqlx::query_as!(
                 StorageBlock,
                 "
-                SELECT number, timestamp, is_finished, l1_tx_count, l2_tx_count, fee_account_address, bloom, priority_ops_onchain_data, hash, parent_hash, commitment, compressed_write_logs, compressed_contracts, eth_prove_tx_id, eth_commit_tx_id, eth_execute_tx_id, created_at, updated_at, merkle_root_hash, l2_to_l1_logs, l2_to_l1_messages, predicted_commit_gas_cost, predicted_prove_gas_cost, predicted_execute_gas_cost, initial_bootloader_heap_content, used_contract_hashes, compressed_initial_writes, compressed_repeated_writes, l2_l1_compressed_messages, l2_l1_merkle_root, l1_gas_price, l2_fair_gas_price, rollup_last_leaf_index, zkporter_is_available, bootloader_code_hash, default_aa_code_hash, base_fee_per_gas, aux_data_hash, pass_through_data_hash, meta_parameters_hash, skip_proof, gas_per_pubdata_byte_in_block, gas_per_pubdata_limit
+                SELECT number, timestamp, is_finished, l1_tx_count, l2_tx_count, fee_account_address, bloom, priority_ops_onchain_data, hash, parent_hash, commitment, compressed_write_logs, compressed_contracts, eth_prove_tx_id, eth_commit_tx_id, eth_execute_tx_id, created_at, updated_at, merkle_root_hash, l2_to_l1_logs, l2_to_l1_messages, predicted_commit_gas_cost, predicted_prove_gas_cost, predicted_execute_gas_cost, initial_bootloader_heap_content, used_contract_hashes, compressed_initial_writes, compressed_repeated_writes, l2_l1_compressed_messages, l2_l1_merkle_root, l1_gas_price, l2_fair_gas_price, rollup_last_leaf_index, zkporter_is_available, bootloader_code_hash, default_aa_code_hash, base_fee_per_gas, aux_data_hash, pass_through_data_hash, meta_parameters_hash, skip_proof, gas_per_pubdata_byte_in_block, gas_per_pubdata_limit, estimated_basic_circuits
                 FROM
                 (SELECT l1_batches.*, row_number() over (order by number ASC) as row_number
                  FROM l1_batches
@@ -587,7 +676,7 @@ impl BlocksDal<'_, '_> {
             let l1_batches = sqlx::query_as!(
                 StorageBlock,
                 "
-                SELECT number, timestamp, is_finished, l1_tx_count, l2_tx_count, fee_account_address, bloom, priority_ops_onchain_data, hash, parent_hash, commitment, compressed_write_logs, compressed_contracts, eth_prove_tx_id, eth_commit_tx_id, eth_execute_tx_id, created_at, updated_at, merkle_root_hash, l2_to_l1_logs, l2_to_l1_messages, predicted_commit_gas_cost, predicted_prove_gas_cost, predicted_execute_gas_cost, initial_bootloader_heap_content, used_contract_hashes, compressed_initial_writes, compressed_repeated_writes, l2_l1_compressed_messages, l2_l1_merkle_root, l1_gas_price, l2_fair_gas_price, rollup_last_leaf_index, zkporter_is_available, bootloader_code_hash, default_aa_code_hash, base_fee_per_gas, aux_data_hash, pass_through_data_hash, meta_parameters_hash, skip_proof, gas_per_pubdata_byte_in_block, gas_per_pubdata_limit
+                SELECT number, timestamp, is_finished, l1_tx_count, l2_tx_count, fee_account_address, bloom, priority_ops_onchain_data, hash, parent_hash, commitment, compressed_write_logs, compressed_contracts, eth_prove_tx_id, eth_commit_tx_id, eth_execute_tx_id, created_at, updated_at, merkle_root_hash, l2_to_l1_logs, l2_to_l1_messages, predicted_commit_gas_cost, predicted_prove_gas_cost, predicted_execute_gas_cost, initial_bootloader_heap_content, used_contract_hashes, compressed_initial_writes, compressed_repeated_writes, l2_l1_compressed_messages, l2_l1_merkle_root, l1_gas_price, l2_fair_gas_price, rollup_last_leaf_index, zkporter_is_available, bootloader_code_hash, default_aa_code_hash, base_fee_per_gas, aux_data_hash, pass_through_data_hash, meta_parameters_hash, skip_proof, gas_per_pubdata_byte_in_block, gas_per_pubdata_limit, estimated_basic_circuits
                 FROM
                 (SELECT l1_batches.*, row_number() over (order by number ASC) as row_number
                     FROM l1_batches
@@ -802,8 +891,22 @@ impl BlocksDal<'_, '_> {
         })
     }
 
+    /// Deletes the miniblocks after the specified one. The hashes of the deleted miniblocks are recorded,
+    /// so that the API can tell the reorged miniblocks apart from the unknown ones.
     pub fn delete_miniblocks(&mut self, block_number: MiniblockNumber) {
         async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO reorged_miniblocks (hash, number, created_at)
+                SELECT hash, number, now() FROM miniblocks WHERE number > $1
+                ON CONFLICT (hash) DO UPDATE
+                SET number = excluded.number, created_at = excluded.created_at
+                ",
+                block_number.0 as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
             sqlx::query!(
                 "DELETE FROM miniblocks WHERE number > $1",
                 block_number.0 as i64
@@ -866,6 +969,52 @@ impl BlocksDal<'_, '_> {
         })
     }
 
+    /// Stores the number of basic circuits the L1 batch is expected to produce.
+    /// Used by the witness generator to estimate the memory a job needs before picking it.
+    pub fn set_estimated_basic_circuits(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        estimated_basic_circuits: u32,
+    ) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                    UPDATE l1_batches
+                    SET estimated_basic_circuits = $2, updated_at = now()
+                    WHERE number = $1
+                ",
+                l1_batch_number.0 as i64,
+                estimated_basic_circuits as i32
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Stores the name of the criterion the L1 batch has been sealed by. Served with the blocks
+    /// of the batch to the API clients that have requested the zkSync extensions.
+    pub fn set_l1_batch_seal_criterion(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        seal_criterion: &str,
+    ) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                    UPDATE l1_batches
+                    SET seal_criterion = $2, updated_at = now()
+                    WHERE number = $1
+                ",
+                l1_batch_number.0 as i64,
+                seal_criterion
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
     pub fn get_miniblock_range_of_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
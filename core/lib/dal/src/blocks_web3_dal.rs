@@ -9,16 +9,78 @@ use sqlx::postgres::PgArguments;
 use sqlx::query::Query;
 use sqlx::{Postgres, Row};
 use std::time::Instant;
-use vm::utils::BLOCK_GAS_LIMIT;
 use zksync_config::constants::EMPTY_UNCLES_HASH;
 
-use zksync_types::api::{self, Block, BlockId, TransactionVariant};
+use zksync_types::api::{
+    self, Block, BlockExtensions, BlockId, L1BatchFinalityWatermarks, TransactionVariant,
+};
 use zksync_types::l2_to_l1_log::L2ToL1Log;
 use zksync_types::web3::types::{BlockHeader, U64};
-use zksync_types::{L1BatchNumber, L2ChainId, MiniblockNumber, H160, H256, U256};
+use zksync_types::{Address, L1BatchNumber, L2ChainId, MiniblockNumber, H160, H2048, H256, U256};
 use zksync_utils::{bigdecimal_to_u256, miniblock_hash};
 use zksync_web3_decl::error::Web3Error;
 
+/// Returns the header of the miniblock as reported by the `newHeads` subscription.
+pub fn web3_block_header(
+    number: MiniblockNumber,
+    timestamp: u64,
+    hash: H256,
+    logs_bloom: H2048,
+) -> BlockHeader {
+    BlockHeader {
+        hash: Some(hash),
+        parent_hash: H256::zero(),
+        uncles_hash: EMPTY_UNCLES_HASH,
+        author: H160::zero(),
+        state_root: H256::zero(),
+        transactions_root: H256::zero(),
+        receipts_root: H256::zero(),
+        number: Some(U64::from(number.0)),
+        gas_used: Default::default(),
+        gas_limit: Default::default(),
+        base_fee_per_gas: Default::default(),
+        extra_data: Default::default(),
+        logs_bloom: logs_bloom.into(),
+        timestamp: U256::from(timestamp),
+        difficulty: Default::default(),
+        mix_hash: None,
+        nonce: None,
+    }
+}
+
+/// Fee statistics of a miniblock reported by `eth_feeHistory`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MiniblockFeeStats {
+    pub number: MiniblockNumber,
+    pub base_fee_per_gas: U256,
+    pub gas_used: U256,
+    /// Priority fees at the requested percentiles.
+    pub rewards: Vec<U256>,
+}
+
+/// Returns the priority fees at `percentiles` (given as fractions in `[0, 1]`) of the gas used by the transactions,
+/// i.e. the fee of the first transaction at which the cumulative gas used reaches the percentile of the total.
+/// `rewards` are the priority fees and the gas used by the transactions sorted by the fees.
+fn gas_weighted_rewards(rewards: &[(U256, u64)], percentiles: &[f64]) -> Vec<U256> {
+    if rewards.is_empty() {
+        return vec![];
+    }
+    let total_gas_used: u64 = rewards.iter().map(|&(_, gas_used)| gas_used).sum();
+    let mut tx_index = 0;
+    let mut cumulative_gas_used = rewards[0].1;
+    percentiles
+        .iter()
+        .map(|&percentile| {
+            let threshold = (total_gas_used as f64 * percentile) as u64;
+            while cumulative_gas_used < threshold && tx_index < rewards.len() - 1 {
+                tx_index += 1;
+                cumulative_gas_used += rewards[tx_index].1;
+            }
+            rewards[tx_index].0
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct BlocksWeb3Dal<'a, 'c> {
     pub storage: &'a mut StorageProcessor<'c>,
@@ -38,6 +100,19 @@ impl BlocksWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns the number of the earliest miniblock stored in the database, or `None` if there are no miniblocks.
+    pub fn get_earliest_miniblock_number(&mut self) -> Result<Option<MiniblockNumber>, SqlxError> {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let number = sqlx::query!(r#"SELECT MIN(number) as "number" FROM miniblocks"#)
+                .fetch_one(self.storage.conn())
+                .await?
+                .number;
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_earliest_miniblock_number");
+            Ok(number.map(|number| MiniblockNumber(number as u32)))
+        })
+    }
+
     pub fn get_sealed_l1_batch_number(&mut self) -> Result<L1BatchNumber, SqlxError> {
         async_std::task::block_on(async {
             let started_at = Instant::now();
@@ -51,11 +126,154 @@ impl BlocksWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns the numbers of the last L1 batches which commit, prove and execute operations are confirmed on L1.
+    pub fn get_l1_batch_finality_watermarks(
+        &mut self,
+    ) -> Result<L1BatchFinalityWatermarks, SqlxError> {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let row = sqlx::query!(
+                r#"
+                SELECT
+                    (
+                        SELECT COALESCE(MAX(number), 0) FROM l1_batches
+                        JOIN eth_txs_history ON l1_batches.eth_commit_tx_id = eth_txs_history.eth_tx_id
+                        WHERE eth_txs_history.confirmed_at IS NOT NULL
+                    ) as "last_committed!",
+                    (
+                        SELECT COALESCE(MAX(number), 0) FROM l1_batches
+                        JOIN eth_txs_history ON l1_batches.eth_prove_tx_id = eth_txs_history.eth_tx_id
+                        WHERE eth_txs_history.confirmed_at IS NOT NULL
+                    ) as "last_proven!",
+                    (
+                        SELECT COALESCE(MAX(number), 0) FROM l1_batches
+                        JOIN eth_txs_history ON l1_batches.eth_execute_tx_id = eth_txs_history.eth_tx_id
+                        WHERE eth_txs_history.confirmed_at IS NOT NULL
+                    ) as "last_executed!"
+                "#
+            )
+            .fetch_one(self.storage.conn())
+            .await?;
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_l1_batch_finality_watermarks");
+            Ok(L1BatchFinalityWatermarks {
+                last_committed: L1BatchNumber(row.last_committed as u32),
+                last_proven: L1BatchNumber(row.last_proven as u32),
+                last_executed: L1BatchNumber(row.last_executed as u32),
+            })
+        })
+    }
+
+    /// Returns the median of the priority fees actually paid by the L2 transactions included into
+    /// the last `miniblocks_count` miniblocks, or `None` if there are no such transactions.
+    pub fn get_median_priority_fee(
+        &mut self,
+        miniblocks_count: u32,
+    ) -> Result<Option<U256>, SqlxError> {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let priority_fee = sqlx::query!(
+                r#"
+                SELECT percentile_disc(0.5) WITHIN GROUP (
+                    ORDER BY GREATEST(transactions.effective_gas_price - miniblocks.base_fee_per_gas, 0)
+                ) as "priority_fee"
+                FROM transactions
+                JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+                WHERE transactions.miniblock_number > (SELECT MAX(number) FROM miniblocks) - $1
+                    AND transactions.is_priority = FALSE
+                    AND transactions.effective_gas_price IS NOT NULL
+                "#,
+                miniblocks_count as i64
+            )
+            .fetch_one(self.storage.conn())
+            .await?
+            .priority_fee;
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_median_priority_fee");
+            Ok(priority_fee.map(bigdecimal_to_u256))
+        })
+    }
+
+    /// Returns the fee statistics of the miniblocks in the `[from_block, to_block]` range ordered by number.
+    /// Rewards are the priority fees paid by the L2 transactions at each of `reward_percentiles`
+    /// (given as fractions in `[0, 1]`) of the gas used by these transactions, like in Ethereum clients;
+    /// they are empty for the miniblocks without L2 transactions.
+    pub fn get_fee_history(
+        &mut self,
+        from_block: MiniblockNumber,
+        to_block: MiniblockNumber,
+        reward_percentiles: &[f64],
+    ) -> Result<Vec<MiniblockFeeStats>, SqlxError> {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            // Both arrays are sorted by the priority fees, with the ties broken by the transaction hashes.
+            let rows = sqlx::query!(
+                r#"
+                SELECT
+                    miniblocks.number,
+                    miniblocks.base_fee_per_gas,
+                    COALESCE(SUM(transactions.gas_limit - transactions.refunded_gas), 0) as "gas_used!",
+                    ARRAY_AGG(
+                        GREATEST(transactions.effective_gas_price - miniblocks.base_fee_per_gas, 0)
+                        ORDER BY
+                            GREATEST(transactions.effective_gas_price - miniblocks.base_fee_per_gas, 0),
+                            transactions.hash
+                    ) FILTER (
+                        WHERE transactions.is_priority = FALSE
+                            AND transactions.effective_gas_price IS NOT NULL
+                    ) as "rewards",
+                    ARRAY_AGG(
+                        transactions.gas_limit - transactions.refunded_gas
+                        ORDER BY
+                            GREATEST(transactions.effective_gas_price - miniblocks.base_fee_per_gas, 0),
+                            transactions.hash
+                    ) FILTER (
+                        WHERE transactions.is_priority = FALSE
+                            AND transactions.effective_gas_price IS NOT NULL
+                    ) as "rewards_gas_used"
+                FROM miniblocks
+                LEFT JOIN transactions ON transactions.miniblock_number = miniblocks.number
+                WHERE miniblocks.number BETWEEN $1 AND $2
+                GROUP BY miniblocks.number
+                ORDER BY miniblocks.number
+                "#,
+                from_block.0 as i64,
+                to_block.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?;
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_fee_history");
+
+            Ok(rows
+                .into_iter()
+                .map(|row| {
+                    let rewards: Vec<_> = row
+                        .rewards
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(bigdecimal_to_u256)
+                        .zip(
+                            row.rewards_gas_used
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|gas_used| bigdecimal_to_u256(gas_used).low_u64()),
+                        )
+                        .collect();
+                    MiniblockFeeStats {
+                        number: MiniblockNumber(row.number as u32),
+                        base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
+                        gas_used: bigdecimal_to_u256(row.gas_used),
+                        rewards: gas_weighted_rewards(&rewards, reward_percentiles),
+                    }
+                })
+                .collect())
+        })
+    }
+
     pub fn get_block_by_web3_block_id(
         &mut self,
         block_id: BlockId,
         include_full_transactions: bool,
         chain_id: L2ChainId,
+        default_block_gas_limit: u32,
     ) -> Result<Option<Block<TransactionVariant>>, SqlxError> {
         async_std::task::block_on(async {
             let transactions_sql = if include_full_transactions {
@@ -72,13 +290,23 @@ impl BlocksWeb3Dal<'_, '_> {
                     miniblocks.l1_batch_number,
                     miniblocks.timestamp,
                     miniblocks.base_fee_per_gas,
+                    miniblocks.logs_bloom,
+                    miniblocks.fee_account_address,
                     l1_batches.timestamp as l1_batch_timestamp,
+                    l1_batches.seal_criterion,
+                    protocol_versions.block_gas_limit,
                     transactions.gas_limit as gas_limit,
                     transactions.refunded_gas as refunded_gas,
                     {}
                 FROM miniblocks
                 LEFT JOIN l1_batches
                     ON l1_batches.number = miniblocks.l1_batch_number
+                LEFT JOIN LATERAL (
+                    SELECT block_gas_limit FROM protocol_versions
+                    WHERE activated_at_miniblock <= miniblocks.number
+                    ORDER BY activated_at_miniblock DESC
+                    LIMIT 1
+                ) protocol_versions ON TRUE
                 LEFT JOIN transactions
                     ON transactions.miniblock_number = miniblocks.number
                 WHERE {}
@@ -105,14 +333,21 @@ impl BlocksWeb3Dal<'_, '_> {
                                 .map(H256::from_slice)
                                 .unwrap_or_else(|_| H256::zero());
                             let number = U64::from(db_row.get::<i64, &str>("number"));
-                            let l1_batch_number = db_row
-                                .try_get::<i64, &str>("l1_batch_number")
-                                .map(U64::from)
-                                .ok();
-                            let l1_batch_timestamp = db_row
-                                .try_get::<i64, &str>("l1_batch_timestamp")
-                                .map(U256::from)
-                                .ok();
+                            let extensions = BlockExtensions {
+                                l1_batch_number: db_row
+                                    .try_get::<i64, &str>("l1_batch_number")
+                                    .map(U64::from)
+                                    .ok(),
+                                l1_batch_timestamp: db_row
+                                    .try_get::<i64, &str>("l1_batch_timestamp")
+                                    .map(U256::from)
+                                    .ok(),
+                                seal_criterion: db_row
+                                    .get::<Option<String>, &str>("seal_criterion"),
+                                operator_address: db_row
+                                    .get::<Option<Vec<u8>>, &str>("fee_account_address")
+                                    .map(|address| Address::from_slice(&address)),
+                            };
                             let parent_hash = match number.as_u32() {
                                 0 => H256::zero(),
                                 number => miniblock_hash(MiniblockNumber(number - 1)),
@@ -127,17 +362,23 @@ impl BlocksWeb3Dal<'_, '_> {
                                 transactions_root: H256::zero(),
                                 receipts_root: H256::zero(),
                                 number,
-                                l1_batch_number,
                                 gas_used: Default::default(),
-                                gas_limit: BLOCK_GAS_LIMIT.into(),
+                                // Blocks sealed before the protocol versions were recorded
+                                // are reported with the configured limit.
+                                gas_limit: db_row
+                                    .get::<Option<i64>, &str>("block_gas_limit")
+                                    .map_or(default_block_gas_limit.into(), |limit| {
+                                        (limit as u64).into()
+                                    }),
                                 base_fee_per_gas: bigdecimal_to_u256(
                                     db_row.get::<BigDecimal, &str>("base_fee_per_gas"),
                                 ),
                                 extra_data: Default::default(),
-                                // todo logs
-                                logs_bloom: Default::default(),
+                                logs_bloom: db_row
+                                    .get::<Option<Vec<u8>>, &str>("logs_bloom")
+                                    .map(|bloom| H2048::from_slice(&bloom))
+                                    .unwrap_or_default(),
                                 timestamp: U256::from(db_row.get::<i64, &str>("timestamp")),
-                                l1_batch_timestamp,
                                 difficulty: Default::default(),
                                 total_difficulty: Default::default(),
                                 seal_fields: vec![],
@@ -146,6 +387,7 @@ impl BlocksWeb3Dal<'_, '_> {
                                 size: Default::default(),
                                 mix_hash: Default::default(),
                                 nonce: Default::default(),
+                                extensions: Some(extensions),
                             }
                         });
                         if db_row.try_get::<&[u8], &str>("tx_hash").is_ok() {
@@ -228,7 +470,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 SELECT
                     hash,
                     number,
-                    timestamp
+                    timestamp,
+                    logs_bloom
                 FROM miniblocks
                 WHERE number > $1
                 ORDER BY number ASC
@@ -239,25 +482,16 @@ impl BlocksWeb3Dal<'_, '_> {
             .await?;
             let blocks: Vec<BlockHeader> = records
                 .into_iter()
-                .map(|db_row| BlockHeader {
-                    hash: Some(H256::from_slice(&db_row.hash)),
-                    parent_hash: H256::zero(),
-                    uncles_hash: EMPTY_UNCLES_HASH,
-                    author: H160::zero(),
-                    state_root: H256::zero(),
-                    transactions_root: H256::zero(),
-                    receipts_root: H256::zero(),
-                    number: Some(U64::from(db_row.number)),
-                    gas_used: Default::default(),
-                    gas_limit: Default::default(),
-                    base_fee_per_gas: Default::default(),
-                    extra_data: Default::default(),
-                    // todo logs
-                    logs_bloom: Default::default(),
-                    timestamp: U256::from(db_row.timestamp),
-                    difficulty: Default::default(),
-                    mix_hash: None,
-                    nonce: None,
+                .map(|db_row| {
+                    web3_block_header(
+                        MiniblockNumber(db_row.number as u32),
+                        db_row.timestamp as u64,
+                        H256::from_slice(&db_row.hash),
+                        db_row
+                            .logs_bloom
+                            .map(|bloom| H2048::from_slice(&bloom))
+                            .unwrap_or_default(),
+                    )
                 })
                 .collect();
             Ok(blocks)
@@ -268,7 +502,7 @@ impl BlocksWeb3Dal<'_, '_> {
         &mut self,
         block_id: api::BlockId,
     ) -> Result<Result<MiniblockNumber, Web3Error>, SqlxError> {
-        async_std::task::block_on(async {
+        let block_number = async_std::task::block_on(async {
             let query_string = match block_id {
                 api::BlockId::Hash(_) => {
                     "SELECT number FROM miniblocks WHERE hash = $1".to_string()
@@ -284,12 +518,55 @@ impl BlocksWeb3Dal<'_, '_> {
 
             let block_number = row
                 .and_then(|row| row.get::<Option<i64>, &str>("number"))
-                .map(|n| MiniblockNumber(n as u32))
-                .ok_or(Web3Error::NoBlock);
-            Ok(block_number)
+                .map(|n| MiniblockNumber(n as u32));
+            Ok::<_, SqlxError>(block_number)
+        })?;
+
+        let block_number = match (block_number, block_id) {
+            (Some(number), _) => Ok(number),
+            (None, api::BlockId::Hash(hash)) => Err(self.missing_block_error(hash)?),
+            (None, _) => Err(Web3Error::NoBlock),
+        };
+        Ok(block_number)
+    }
+
+    /// Returns the number of the reorged miniblock with the specified hash, along with the hash
+    /// of the miniblock superseding it (if the miniblock with the same number is sealed again).
+    pub fn get_reorged_miniblock(
+        &mut self,
+        hash: H256,
+    ) -> Result<Option<(MiniblockNumber, Option<H256>)>, SqlxError> {
+        async_std::task::block_on(async {
+            let row = sqlx::query!(
+                r#"
+                SELECT reorged_miniblocks.number, miniblocks.hash as "superseded_by?"
+                FROM reorged_miniblocks
+                LEFT JOIN miniblocks ON miniblocks.number = reorged_miniblocks.number
+                WHERE reorged_miniblocks.hash = $1
+                "#,
+                hash.as_bytes()
+            )
+            .fetch_optional(self.storage.conn())
+            .await?;
+            Ok(row.map(|row| {
+                (
+                    MiniblockNumber(row.number as u32),
+                    row.superseded_by.map(|hash| H256::from_slice(&hash)),
+                )
+            }))
         })
     }
 
+    /// Returns the error for the miniblock hash which isn't found: either the miniblock was reorged,
+    /// or it never existed.
+    pub fn missing_block_error(&mut self, hash: H256) -> Result<Web3Error, SqlxError> {
+        let error = match self.get_reorged_miniblock(hash)? {
+            Some((number, superseded_by)) => Web3Error::BlockReorged(hash, number, superseded_by),
+            None => Web3Error::NoBlock,
+        };
+        Ok(error)
+    }
+
     pub fn get_block_timestamp(
         &mut self,
         block_number: MiniblockNumber,
@@ -306,6 +583,127 @@ impl BlocksWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns the number and the timestamp of the last miniblock sealed at or before `timestamp`.
+    /// Returns `None` if the timestamp precedes all the stored miniblocks. Timestamps not fitting into `i64`
+    /// are clamped, since they follow all the stored miniblocks anyway.
+    pub fn get_miniblock_by_timestamp(
+        &mut self,
+        timestamp: u64,
+    ) -> Result<Option<(MiniblockNumber, u64)>, SqlxError> {
+        async_std::task::block_on(async {
+            let miniblock = sqlx::query!(
+                r#"
+                    SELECT number, timestamp FROM miniblocks
+                    WHERE timestamp <= $1
+                    ORDER BY timestamp DESC, number DESC
+                    LIMIT 1
+                "#,
+                timestamp.min(i64::MAX as u64) as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| (MiniblockNumber(row.number as u32), row.timestamp as u64));
+            Ok(miniblock)
+        })
+    }
+
+    /// Returns the fee input `(l1_gas_price, l2_fair_gas_price)` the miniblock was executed with.
+    /// Returns `None` if the miniblock doesn't exist or has no fee input recorded.
+    pub fn get_miniblock_fee_input(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<(u64, u64)>, SqlxError> {
+        async_std::task::block_on(async {
+            let fee_input = sqlx::query!(
+                "SELECT l1_gas_price, l2_fair_gas_price FROM miniblocks WHERE number = $1",
+                block_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .filter(|row| row.l1_gas_price != 0 && row.l2_fair_gas_price != 0)
+            .map(|row| (row.l1_gas_price as u64, row.l2_fair_gas_price as u64));
+            Ok(fee_input)
+        })
+    }
+
+    /// Returns the fee account the miniblock was executed with.
+    /// Returns `None` if the miniblock doesn't exist or has no fee account recorded.
+    pub fn get_miniblock_fee_account_address(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<Address>, SqlxError> {
+        async_std::task::block_on(async {
+            let fee_account_address = sqlx::query!(
+                "SELECT fee_account_address FROM miniblocks WHERE number = $1",
+                block_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .and_then(|row| row.fee_account_address)
+            .map(|address| Address::from_slice(&address));
+            Ok(fee_account_address)
+        })
+    }
+
+    /// Returns the system context values the VM executed the miniblock with. The values are taken from the L1 batch
+    /// of the miniblock, or from the miniblock itself if the batch isn't sealed yet; the gas per pubdata byte
+    /// and the transactions rolling hash are recorded per miniblock when it's sealed.
+    /// Returns `None` if the miniblock doesn't exist.
+    pub fn get_miniblock_system_context(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<Option<api::SystemContext>, SqlxError> {
+        async_std::task::block_on(async {
+            let row = sqlx::query!(
+                r#"
+                    SELECT miniblocks.number, miniblocks.timestamp, miniblocks.hash,
+                        COALESCE(
+                            miniblocks.l1_batch_number,
+                            (SELECT MAX(number) + 1 FROM l1_batches)
+                        ) as "l1_batch_number!",
+                        COALESCE(
+                            l1_batches.timestamp,
+                            (SELECT MIN(timestamp) FROM miniblocks WHERE l1_batch_number IS NULL)
+                        ) as "l1_batch_timestamp!",
+                        l1_batches.number IS NOT NULL as "l1_batch_sealed!",
+                        COALESCE(l1_batches.fee_account_address, miniblocks.fee_account_address) as fee_account_address,
+                        COALESCE(l1_batches.l1_gas_price, miniblocks.l1_gas_price) as "l1_gas_price!",
+                        COALESCE(l1_batches.l2_fair_gas_price, miniblocks.l2_fair_gas_price) as "l2_fair_gas_price!",
+                        COALESCE(l1_batches.base_fee_per_gas, miniblocks.base_fee_per_gas) as "base_fee_per_gas!",
+                        COALESCE(l1_batches.bootloader_code_hash, miniblocks.bootloader_code_hash) as bootloader_code_hash,
+                        COALESCE(l1_batches.default_aa_code_hash, miniblocks.default_aa_code_hash) as default_aa_code_hash,
+                        miniblocks.gas_per_pubdata, miniblocks.tx_rolling_hash
+                    FROM miniblocks
+                    LEFT JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
+                    WHERE miniblocks.number = $1
+                "#,
+                block_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?;
+
+            Ok(row.map(|row| api::SystemContext {
+                miniblock_number: MiniblockNumber(row.number as u32),
+                miniblock_timestamp: row.timestamp as u64,
+                miniblock_hash: H256::from_slice(&row.hash),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                l1_batch_timestamp: row.l1_batch_timestamp as u64,
+                l1_batch_sealed: row.l1_batch_sealed,
+                operator_address: row
+                    .fee_account_address
+                    .map(|address| Address::from_slice(&address))
+                    .filter(|address| !address.is_zero()),
+                l1_gas_price: row.l1_gas_price as u64,
+                fair_l2_gas_price: row.l2_fair_gas_price as u64,
+                base_fee_per_gas: bigdecimal_to_u256(row.base_fee_per_gas),
+                gas_per_pubdata: row.gas_per_pubdata.map(|gas| gas as u64),
+                bootloader_code_hash: row.bootloader_code_hash.map(|hash| H256::from_slice(&hash)),
+                default_aa_code_hash: row.default_aa_code_hash.map(|hash| H256::from_slice(&hash)),
+                tx_rolling_hash: row.tx_rolling_hash.map(|hash| H256::from_slice(&hash)),
+            }))
+        })
+    }
+
     pub fn get_l2_to_l1_logs(
         &mut self,
         block_number: L1BatchNumber,
@@ -324,6 +722,44 @@ impl BlocksWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns the root hash of the L2 to L1 logs tree of the L1 batch, or `None` if the batch
+    /// doesn't exist or its metadata isn't calculated yet.
+    pub fn get_l2_to_l1_logs_root(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> Result<Option<H256>, SqlxError> {
+        async_std::task::block_on(async {
+            let root = sqlx::query!(
+                "SELECT l2_l1_merkle_root FROM l1_batches WHERE number = $1",
+                block_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .and_then(|row| row.l2_l1_merkle_root)
+            .map(|root| H256::from_slice(&root));
+            Ok(root)
+        })
+    }
+
+    /// Returns the root hash of the state tree after the L1 batch, or `None` if the batch
+    /// doesn't exist or its metadata isn't calculated yet.
+    pub fn get_l1_batch_root_hash(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<H256>, SqlxError> {
+        async_std::task::block_on(async {
+            let root_hash = sqlx::query!(
+                "SELECT hash FROM l1_batches WHERE number = $1",
+                l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await?
+            .and_then(|row| row.hash)
+            .map(|hash| H256::from_slice(&hash));
+            Ok(root_hash)
+        })
+    }
+
     pub fn get_l1_batch_number_of_miniblock(
         &mut self,
         miniblock_number: MiniblockNumber,
@@ -0,0 +1,68 @@
+use zksync_types::{api::BootloaderUsageReport, L1BatchNumber};
+
+use crate::StorageProcessor;
+
+/// Bootloader resources used by the sealed L1 batches.
+#[derive(Debug)]
+pub struct BootloaderUsageDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl BootloaderUsageDal<'_, '_> {
+    pub fn insert_report(&mut self, report: &BootloaderUsageReport) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO l1_batch_bootloader_usage (
+                    l1_batch_number, tx_count, tx_slots, txs_encoding_words, txs_encoding_limit,
+                    compressed_bytecodes_words, compressed_bytecodes_limit, gas_used, gas_limit,
+                    pubdata_bytes, max_tx_pubdata_bytes, pubdata_limit, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, now())
+                ",
+                report.l1_batch_number.0 as i64,
+                report.tx_count as i32,
+                report.tx_slots as i32,
+                report.txs_encoding_words as i32,
+                report.txs_encoding_limit as i32,
+                report.compressed_bytecodes_words as i32,
+                report.compressed_bytecodes_limit as i32,
+                report.gas_used as i64,
+                report.gas_limit as i64,
+                report.pubdata_bytes as i32,
+                report.max_tx_pubdata_bytes as i32,
+                report.pubdata_limit as i32
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns `None` if the L1 batch isn't sealed yet or the report wasn't recorded for it.
+    pub fn get_report(&mut self, l1_batch_number: L1BatchNumber) -> Option<BootloaderUsageReport> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "SELECT * FROM l1_batch_bootloader_usage WHERE l1_batch_number = $1",
+                l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| BootloaderUsageReport {
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                tx_count: row.tx_count as u32,
+                tx_slots: row.tx_slots as u32,
+                txs_encoding_words: row.txs_encoding_words as u32,
+                txs_encoding_limit: row.txs_encoding_limit as u32,
+                compressed_bytecodes_words: row.compressed_bytecodes_words as u32,
+                compressed_bytecodes_limit: row.compressed_bytecodes_limit as u32,
+                gas_used: row.gas_used as u64,
+                gas_limit: row.gas_limit as u64,
+                pubdata_bytes: row.pubdata_bytes as u32,
+                max_tx_pubdata_bytes: row.max_tx_pubdata_bytes as u32,
+                pubdata_limit: row.pubdata_limit as u32,
+            })
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use zksync_types::chain_stats::{ChainStatsCheckpoint, ChainStatsDelta};
+use zksync_types::event::DEPLOY_EVENT_SIGNATURE;
+use zksync_types::{MiniblockNumber, CONTRACT_DEPLOYER_ADDRESS};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::models::storage_chain_stats::StorageChainStatsCheckpoint;
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct ChainStatsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ChainStatsDal<'_, '_> {
+    pub fn get_last_chain_stats_checkpoint(&mut self) -> Option<ChainStatsCheckpoint> {
+        async_std::task::block_on(async {
+            sqlx::query_as!(
+                StorageChainStatsCheckpoint,
+                "
+                SELECT miniblock_number, timestamp, total_transactions, total_accounts, contracts_deployed, total_fees
+                FROM chain_stats
+                ORDER BY miniblock_number DESC
+                LIMIT 1
+                "
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(Into::into)
+        })
+    }
+
+    /// Returns the last checkpoint created at or before `timestamp`,
+    /// or the first checkpoint if all of them are newer.
+    pub fn get_chain_stats_checkpoint_at(
+        &mut self,
+        timestamp: u64,
+    ) -> Option<ChainStatsCheckpoint> {
+        async_std::task::block_on(async {
+            let checkpoint = sqlx::query_as!(
+                StorageChainStatsCheckpoint,
+                "
+                SELECT miniblock_number, timestamp, total_transactions, total_accounts, contracts_deployed, total_fees
+                FROM chain_stats
+                WHERE timestamp <= $1
+                ORDER BY timestamp DESC
+                LIMIT 1
+                ",
+                timestamp as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap();
+            if checkpoint.is_some() {
+                return checkpoint.map(Into::into);
+            }
+
+            sqlx::query_as!(
+                StorageChainStatsCheckpoint,
+                "
+                SELECT miniblock_number, timestamp, total_transactions, total_accounts, contracts_deployed, total_fees
+                FROM chain_stats
+                ORDER BY miniblock_number
+                LIMIT 1
+                "
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(Into::into)
+        })
+    }
+
+    /// Aggregates the stats of the miniblocks in the `from..=to` range.
+    pub fn get_chain_stats_delta(
+        &mut self,
+        from: MiniblockNumber,
+        to: MiniblockNumber,
+    ) -> ChainStatsDelta {
+        async_std::task::block_on(async {
+            // New accounts are counted by the L2 transactions with zero nonce,
+            // so that the unique initiators don't have to be recomputed over the whole history.
+            let row = sqlx::query!(
+                r#"
+                SELECT
+                    (
+                        SELECT COALESCE(SUM(l1_tx_count + l2_tx_count), 0) FROM miniblocks
+                        WHERE number BETWEEN $1 AND $2
+                    ) AS "transactions!",
+                    (
+                        SELECT COUNT(*) FROM transactions
+                        WHERE miniblock_number BETWEEN $1 AND $2 AND is_priority = FALSE AND nonce = 0
+                    ) AS "new_accounts!",
+                    (
+                        SELECT COUNT(*) FROM events
+                        WHERE miniblock_number BETWEEN $1 AND $2 AND address = $3 AND topic1 = $4
+                    ) AS "contracts_deployed!",
+                    (
+                        SELECT COALESCE(SUM((gas_limit - refunded_gas) * effective_gas_price), 0) FROM transactions
+                        WHERE miniblock_number BETWEEN $1 AND $2 AND is_priority = FALSE
+                    ) AS "fees!"
+                "#,
+                from.0 as i64,
+                to.0 as i64,
+                CONTRACT_DEPLOYER_ADDRESS.as_bytes(),
+                DEPLOY_EVENT_SIGNATURE.as_bytes()
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap();
+
+            ChainStatsDelta {
+                transactions: row.transactions as u64,
+                new_accounts: row.new_accounts as u64,
+                contracts_deployed: row.contracts_deployed as u64,
+                fees: bigdecimal_to_u256(row.fees),
+            }
+        })
+    }
+
+    pub fn insert_chain_stats_checkpoint(&mut self, checkpoint: &ChainStatsCheckpoint) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO chain_stats (miniblock_number, timestamp, total_transactions, total_accounts, contracts_deployed, total_fees, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, now())
+                ",
+                checkpoint.miniblock_number.0 as i64,
+                checkpoint.timestamp as i64,
+                checkpoint.total_transactions as i64,
+                checkpoint.total_accounts as i64,
+                checkpoint.contracts_deployed as i64,
+                u256_to_big_decimal(checkpoint.total_fees)
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+}
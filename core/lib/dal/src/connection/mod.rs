@@ -1,9 +1,15 @@
 // Built-in deps
+use std::collections::{hash_map::Entry, HashMap};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 // External imports
 use async_std::task::{block_on, sleep};
 use sqlx::pool::PoolConnection;
 use sqlx::postgres::{PgPool, PgPoolOptions, Postgres};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::{Connection, Error as SqlxError, PgConnection, Row};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 // Local imports
 use crate::{get_master_database_url, get_replica_database_url, StorageProcessor};
 use zksync_utils::parse_env;
@@ -15,25 +21,260 @@ pub mod test_pool;
 
 #[derive(Clone, Debug)]
 pub enum ConnectionPool {
-    Real(PgPool),
+    Real(RealConnectionPool),
     Test(TestPool),
 }
 
+/// Pool of the connections to the database. Pools created with [`ConnectionPool::new_resizable()`]
+/// can be resized at runtime; clones of the pool share the size, so resizing affects all of them.
+#[derive(Clone, Debug)]
+pub struct RealConnectionPool {
+    database_url: Arc<String>,
+    pool: PgPool,
+    max_size: u32,
+    limit: Option<Arc<ConnectionLimit>>,
+}
+
+/// Max size of a resizable pool. The underlying `PgPool` is created with this size, and the number of
+/// the connections acquired at a time is limited by [`ConnectionLimit`] instead.
+pub const MAX_RESIZABLE_POOL_SIZE: u32 = 500;
+
+/// Limit on the number of the connections acquired from a resizable pool at a time. Unlike the size
+/// of a `PgPool`, it can be changed without reconnecting: once the limit is decreased, the idle connections
+/// above it are closed by the pool after the idle timeout.
+#[derive(Debug)]
+struct ConnectionLimit {
+    semaphore: Arc<Semaphore>,
+    max_size: AtomicU32,
+    /// Number of the permits to be forgotten once released, left after the limit was decreased
+    /// while the connections were in use.
+    excess_permits: Arc<AtomicU32>,
+}
+
+impl ConnectionLimit {
+    fn new(max_size: u32) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_size as usize)),
+            max_size: AtomicU32::new(max_size),
+            excess_permits: Arc::default(),
+        }
+    }
+
+    fn max_size(&self) -> u32 {
+        self.max_size.load(Ordering::SeqCst)
+    }
+
+    async fn acquire(&self) -> ConnectionPermit {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("connection limit semaphore is never closed");
+        ConnectionPermit {
+            permit: Some(permit),
+            excess_permits: self.excess_permits.clone(),
+        }
+    }
+
+    fn resize(&self, max_size: u32) -> bool {
+        let prev_max_size = self.max_size.swap(max_size, Ordering::SeqCst);
+        if max_size > prev_max_size {
+            let added = max_size - prev_max_size;
+            // The permits still to be forgotten are kept instead of adding new ones.
+            let prev_excess = self
+                .excess_permits
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |excess| {
+                    Some(excess.saturating_sub(added))
+                })
+                .unwrap();
+            self.semaphore
+                .add_permits((added - prev_excess.min(added)) as usize);
+        } else {
+            for _ in max_size..prev_max_size {
+                match self.semaphore.try_acquire() {
+                    Ok(permit) => permit.forget(),
+                    Err(_) => {
+                        self.excess_permits.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            }
+        }
+        max_size != prev_max_size
+    }
+}
+
+/// Permit to hold a connection of a resizable pool, released together with the connection.
+#[derive(Debug)]
+pub(crate) struct ConnectionPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    excess_permits: Arc<AtomicU32>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        let permit = self.permit.take().unwrap();
+        let is_excess = self
+            .excess_permits
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |excess| {
+                excess.checked_sub(1)
+            })
+            .is_ok();
+        if is_excess {
+            permit.forget();
+        }
+    }
+}
+
+/// Transaction of a Postgres backend running the queries of a cancellable storage processor.
+/// The transaction is identified by its start time, so that the queries of the later transactions
+/// run over the same connection aren't cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendTransaction {
+    pub pid: i32,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Cancels the queries run by the connections of the pools, e.g. the queries of the API requests whose
+/// clients have disconnected. The cancellations are sent over the connections established outside the pools,
+/// so that they aren't blocked by the saturation of the pools; a connection is kept per database
+/// and is reused for the subsequent cancellations.
+#[derive(Debug, Default)]
+pub struct QueryCanceller {
+    connections: HashMap<Arc<String>, PgConnection>,
+}
+
+impl QueryCanceller {
+    /// Cancels the query currently run by the Postgres backend of the pool, if the backend is still
+    /// in the same transaction. Does nothing for the test pools.
+    pub async fn cancel_query(
+        &mut self,
+        pool: &ConnectionPool,
+        backend_transaction: BackendTransaction,
+    ) -> Result<(), SqlxError> {
+        let ConnectionPool::Real(real_pool) = pool else {
+            return Ok(());
+        };
+        let connection = match self.connections.entry(real_pool.database_url.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                entry.insert(PgConnection::connect(&real_pool.database_url).await?)
+            }
+        };
+        let result = sqlx::query(
+            "SELECT pg_cancel_backend(pid) FROM pg_stat_activity WHERE pid = $1 AND xact_start = $2",
+        )
+        .bind(backend_transaction.pid)
+        .bind(backend_transaction.started_at)
+        .execute(connection)
+        .await;
+        if result.is_err() {
+            // The connection may be broken, so it's re-established for the next cancellation.
+            self.connections.remove(&real_pool.database_url);
+        }
+        result.map(drop)
+    }
+}
+
+/// Usage of the connection pool, used to report its saturation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionPoolStats {
+    pub max_size: u32,
+    /// Number of the open connections, both idle and in use.
+    pub size: u32,
+    pub idle: usize,
+}
+
+/// Postgres error code of the queries cancelled due to `statement_timeout` or by a cancel request.
+const QUERY_CANCELED_CODE: &str = "57014";
+
+/// Checks whether the query was cancelled by Postgres, e.g. because it exceeded the statement timeout
+/// set by [`ConnectionPool::access_cancellable_storage`] or was cancelled with
+/// [`QueryCanceller::cancel_query`].
+pub fn is_statement_timeout(err: &SqlxError) -> bool {
+    err.as_database_error()
+        .and_then(|err| err.code())
+        .map_or(false, |code| code == QUERY_CANCELED_CODE)
+}
+
+impl RealConnectionPool {
+    async fn acquire_permit(&self) -> Option<ConnectionPermit> {
+        match &self.limit {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        }
+    }
+}
+
+impl ConnectionPoolStats {
+    pub fn in_use(&self) -> u32 {
+        self.size.saturating_sub(self.idle as u32)
+    }
+}
+
 impl ConnectionPool {
     /// Establishes a pool of the connections to the database and
     /// creates a new `ConnectionPool` object.
     /// pool_max_size - number of connections in pool, if not set env variable "DATABASE_POOL_SIZE" is going to be used.
     pub fn new(pool_max_size: Option<u32>, connect_to_master: bool) -> Self {
+        let max_connections = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
+        Self::connect(max_connections, None, connect_to_master)
+    }
+
+    /// Same as [`Self::new()`], but the pool can be resized at runtime with [`Self::resize()`]
+    /// up to [`MAX_RESIZABLE_POOL_SIZE`].
+    pub fn new_resizable(pool_max_size: u32, connect_to_master: bool) -> Self {
+        let limit = ConnectionLimit::new(pool_max_size.min(MAX_RESIZABLE_POOL_SIZE));
+        Self::connect(MAX_RESIZABLE_POOL_SIZE, Some(limit), connect_to_master)
+    }
+
+    fn connect(
+        max_connections: u32,
+        limit: Option<ConnectionLimit>,
+        connect_to_master: bool,
+    ) -> Self {
         let database_url = if connect_to_master {
             get_master_database_url()
         } else {
             get_replica_database_url()
         };
-        let max_connections = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
-
         let options = PgPoolOptions::new().max_connections(max_connections);
         let pool = block_on(options.connect(&database_url)).unwrap();
-        Self::Real(pool)
+        Self::Real(RealConnectionPool {
+            database_url: Arc::new(database_url),
+            pool,
+            max_size: max_connections,
+            limit: limit.map(Arc::new),
+        })
+    }
+
+    /// Changes the max number of connections acquired from the pool at a time, capped by
+    /// [`MAX_RESIZABLE_POOL_SIZE`]. The connections in use are not affected; if the size is decreased,
+    /// the new connections are only handed out once enough connections are released.
+    /// Returns `true` if the size has changed. Only the pools created with [`Self::new_resizable()`]
+    /// can be resized.
+    pub fn resize(&self, max_size: u32) -> bool {
+        match self {
+            ConnectionPool::Real(RealConnectionPool {
+                limit: Some(limit), ..
+            }) => limit.resize(max_size.min(MAX_RESIZABLE_POOL_SIZE)),
+            _ => false,
+        }
+    }
+
+    /// Returns the current usage of the pool, `None` for the test pools.
+    pub fn stats(&self) -> Option<ConnectionPoolStats> {
+        match self {
+            ConnectionPool::Real(real_pool) => Some(ConnectionPoolStats {
+                max_size: real_pool
+                    .limit
+                    .as_ref()
+                    .map_or(real_pool.max_size, |limit| limit.max_size()),
+                size: real_pool.pool.size(),
+                idle: real_pool.pool.num_idle(),
+            }),
+            ConnectionPool::Test(_) => None,
+        }
     }
 
     /// Creates a `StorageProcessor` entity over a recoverable connection.
@@ -48,14 +289,60 @@ impl ConnectionPool {
         match self {
             ConnectionPool::Real(real_pool) => {
                 let start = Instant::now();
-                let conn = Self::acquire_connection_retried(real_pool).await;
+                let permit = real_pool.acquire_permit().await;
+                let conn = Self::acquire_connection_retried(&real_pool.pool).await;
                 metrics::histogram!("sql.connection_acquire", start.elapsed());
-                StorageProcessor::from_pool(conn)
+                StorageProcessor::from_pool(conn).with_permit(permit)
             }
             ConnectionPool::Test(test) => test.access_storage().await,
         }
     }
 
+    /// Same as `access_storage`, but the returned `StorageProcessor` is inside a transaction, and
+    /// its queries can be cancelled with [`QueryCanceller::cancel_query`] using the returned
+    /// [`BackendTransaction`]. If `statement_timeout` is set, Postgres also cancels the statements
+    /// running for longer than it. Cancelled queries fail with an error recognized by [`is_statement_timeout`],
+    /// so the processor must only be used for the queries whose errors are returned to the caller rather
+    /// than unwrapped. The transaction is rolled back once the processor is dropped.
+    pub async fn access_cancellable_storage(
+        &self,
+        statement_timeout: Option<Duration>,
+    ) -> Result<(StorageProcessor<'static>, BackendTransaction), SqlxError> {
+        let mut storage = match self {
+            ConnectionPool::Real(real_pool) => {
+                let start = Instant::now();
+                let permit = real_pool.acquire_permit().await;
+                let transaction = real_pool.pool.begin().await?;
+                metrics::histogram!("sql.connection_acquire", start.elapsed());
+                StorageProcessor::from_transaction(transaction).with_permit(permit)
+            }
+            ConnectionPool::Test(test) => test.access_storage().await,
+        };
+        let row = match statement_timeout {
+            // `set_config` with `is_local = true` is the same as `SET LOCAL`, but accepts parameters.
+            // Postgres treats 0 as no timeout, so the timeout is at least 1 ms.
+            Some(timeout) => {
+                sqlx::query(
+                    "SELECT pg_backend_pid() AS pid, now() AS started_at, \
+                     set_config('statement_timeout', $1, true)",
+                )
+                .bind(timeout.as_millis().max(1).to_string())
+                .fetch_one(storage.conn())
+                .await?
+            }
+            None => {
+                sqlx::query("SELECT pg_backend_pid() AS pid, now() AS started_at")
+                    .fetch_one(storage.conn())
+                    .await?
+            }
+        };
+        let backend_transaction = BackendTransaction {
+            pid: row.get("pid"),
+            started_at: row.get("started_at"),
+        };
+        Ok((storage, backend_transaction))
+    }
+
     pub async fn acquire_connection_retried(pool: &PgPool) -> PoolConnection<Postgres> {
         const DB_CONNECTION_RETRIES: u32 = 3;
 
@@ -93,3 +380,33 @@ impl ConnectionPool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resizing_connection_limit() {
+        let limit = ConnectionLimit::new(2);
+        let first = block_on(limit.acquire());
+        let second = block_on(limit.acquire());
+        assert_eq!(limit.semaphore.available_permits(), 0);
+
+        // The permits in use are forgotten once released.
+        assert!(limit.resize(1));
+        assert!(!limit.resize(1));
+        assert_eq!(limit.max_size(), 1);
+        drop(first);
+        assert_eq!(limit.semaphore.available_permits(), 0);
+        drop(second);
+        assert_eq!(limit.semaphore.available_permits(), 1);
+
+        let first = block_on(limit.acquire());
+        assert!(limit.resize(0));
+        // Increasing the limit cancels forgetting the permit in use rather than adding a new one.
+        assert!(limit.resize(3));
+        assert_eq!(limit.semaphore.available_permits(), 2);
+        drop(first);
+        assert_eq!(limit.semaphore.available_permits(), 3);
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use crate::StorageProcessor;
+
+/// Access to the connection pool sizes overridden by the operator. Services that support
+/// resizing their pools at runtime pick up the overrides without a restart.
+#[derive(Debug)]
+pub struct ConnectionPoolSizesDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ConnectionPoolSizesDal<'_, '_> {
+    /// Returns the overridden pool sizes keyed by the pool name.
+    pub fn get_pool_sizes(&mut self) -> HashMap<String, u32> {
+        async_std::task::block_on(async {
+            sqlx::query!("SELECT name, max_size FROM connection_pool_sizes")
+                .fetch_all(self.storage.conn())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.name, row.max_size as u32))
+                .collect()
+        })
+    }
+
+    pub fn set_pool_size(&mut self, name: &str, max_size: u32) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO connection_pool_sizes (name, max_size, updated_at)
+                VALUES ($1, $2, now())
+                ON CONFLICT (name) DO UPDATE
+                SET max_size = $2, updated_at = now()
+                ",
+                name,
+                max_size as i32
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Removes the override, so that the pool returns to its configured size.
+    pub fn remove_pool_size(&mut self, name: &str) {
+        async_std::task::block_on(async {
+            sqlx::query!("DELETE FROM connection_pool_sizes WHERE name = $1", name)
+                .execute(self.storage.conn())
+                .await
+                .unwrap();
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use sqlx::types::chrono::{DateTime, Utc};
+
+use crate::StorageProcessor;
+
+/// Bloat-related statistics of a table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableStats {
+    pub table_name: String,
+    pub live_tuples: u64,
+    pub dead_tuples: u64,
+    pub table_size_bytes: u64,
+    pub indexes_size_bytes: u64,
+    /// Time of the last manual or automatic vacuum.
+    pub last_vacuum_at: Option<DateTime<Utc>>,
+}
+
+impl TableStats {
+    /// Ratio of the dead tuples to all the tuples of the table.
+    pub fn dead_tuples_ratio(&self) -> f64 {
+        let total_tuples = self.live_tuples + self.dead_tuples;
+        if total_tuples == 0 {
+            0.0
+        } else {
+            self.dead_tuples as f64 / total_tuples as f64
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DBMaintenanceDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl DBMaintenanceDal<'_, '_> {
+    pub fn get_table_stats(&mut self, tables: &[String]) -> Vec<TableStats> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"
+                SELECT
+                    relname::text AS "table_name!",
+                    n_live_tup AS "live_tuples!",
+                    n_dead_tup AS "dead_tuples!",
+                    pg_table_size(relid) AS "table_size!",
+                    pg_indexes_size(relid) AS "indexes_size!",
+                    GREATEST(last_vacuum, last_autovacuum) AS last_vacuum_at
+                FROM pg_stat_user_tables
+                WHERE relname = ANY($1::text[])
+                "#,
+                tables
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| TableStats {
+                table_name: row.table_name,
+                live_tuples: row.live_tuples as u64,
+                dead_tuples: row.dead_tuples as u64,
+                table_size_bytes: row.table_size as u64,
+                indexes_size_bytes: row.indexes_size as u64,
+                last_vacuum_at: row.last_vacuum_at,
+            })
+            .collect()
+        })
+    }
+
+    /// Runs `VACUUM (ANALYZE)` for the table. Must not be called within a transaction.
+    pub fn vacuum_analyze_table(&mut self, table: &str) {
+        async_std::task::block_on(async {
+            // Identifiers can't be passed as query parameters.
+            let sql_query_str = format!("VACUUM (ANALYZE) \"{}\"", table.replace('"', "\"\""));
+            sqlx::query(&sql_query_str)
+                .execute(self.storage.conn())
+                .await
+                .unwrap();
+        })
+    }
+}
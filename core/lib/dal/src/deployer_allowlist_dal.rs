@@ -0,0 +1,54 @@
+use zksync_types::Address;
+
+use crate::StorageProcessor;
+
+/// Accounts allowed to deploy contracts in the permissioned deployment mode.
+#[derive(Debug)]
+pub struct DeployerAllowlistDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl DeployerAllowlistDal<'_, '_> {
+    pub fn get_allowlisted_deployers(&mut self) -> Vec<Address> {
+        async_std::task::block_on(async {
+            sqlx::query!("SELECT address FROM deployer_allowlist ORDER BY address")
+                .fetch_all(self.storage.conn())
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| Address::from_slice(&row.address))
+                .collect()
+        })
+    }
+
+    /// Returns `false` if the account is already in the allowlist.
+    pub fn add_deployer(&mut self, address: &Address) -> bool {
+        async_std::task::block_on(async {
+            let result = sqlx::query!(
+                "
+                INSERT INTO deployer_allowlist (address, created_at) VALUES ($1, now())
+                ON CONFLICT (address) DO NOTHING
+                ",
+                address.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            result.rows_affected() > 0
+        })
+    }
+
+    /// Returns `false` if the account wasn't in the allowlist.
+    pub fn remove_deployer(&mut self, address: &Address) -> bool {
+        async_std::task::block_on(async {
+            let result = sqlx::query!(
+                "DELETE FROM deployer_allowlist WHERE address = $1",
+                address.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            result.rows_affected() > 0
+        })
+    }
+}
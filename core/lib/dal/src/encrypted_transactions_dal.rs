@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use zksync_types::encrypted_tx::{DecryptionShare, EncryptedTransaction};
+use zksync_types::H256;
+
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct EncryptedTransactionsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl EncryptedTransactionsDal<'_, '_> {
+    /// Returns `false` if the same payload has already been submitted.
+    pub fn insert_encrypted_transaction(&mut self, hash: H256, payload: Vec<u8>) -> bool {
+        async_std::task::block_on(async {
+            let rows_affected = sqlx::query!(
+                "
+                INSERT INTO encrypted_transactions (hash, payload, status, received_at, created_at, updated_at)
+                VALUES ($1, $2, 'pending', now(), now(), now())
+                ON CONFLICT (hash) DO NOTHING
+                ",
+                hash.as_bytes(),
+                payload
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap()
+            .rows_affected();
+            rows_affected > 0
+        })
+    }
+
+    /// Returns the pending encrypted transactions in the order they have been received.
+    pub fn get_pending_encrypted_transactions(
+        &mut self,
+        limit: usize,
+    ) -> Vec<EncryptedTransaction> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT hash, payload FROM encrypted_transactions
+                WHERE status = 'pending'
+                ORDER BY received_at, hash
+                LIMIT $1
+                ",
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| EncryptedTransaction {
+                hash: H256::from_slice(&row.hash),
+                payload: row.payload,
+            })
+            .collect()
+        })
+    }
+
+    pub fn mark_encrypted_transaction_as_decrypted(&mut self, hash: H256, tx_hash: H256) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                UPDATE encrypted_transactions
+                SET status = 'decrypted', tx_hash = $2, updated_at = now()
+                WHERE hash = $1
+                ",
+                hash.as_bytes(),
+                tx_hash.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    pub fn mark_encrypted_transaction_as_rejected(&mut self, hash: H256, error: &str) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                UPDATE encrypted_transactions
+                SET status = 'rejected', error = $2, updated_at = now()
+                WHERE hash = $1
+                ",
+                hash.as_bytes(),
+                error
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the pending encrypted transactions the committee member hasn't published a decryption share for,
+    /// in the order they have been received.
+    pub fn get_pending_encrypted_transactions_without_share(
+        &mut self,
+        member_index: u32,
+        limit: usize,
+    ) -> Vec<EncryptedTransaction> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT hash, payload FROM encrypted_transactions
+                WHERE status = 'pending' AND NOT EXISTS (
+                    SELECT 1 FROM encrypted_transaction_decryption_shares
+                    WHERE encrypted_tx_hash = encrypted_transactions.hash AND member_index = $1
+                )
+                ORDER BY received_at, hash
+                LIMIT $2
+                ",
+                member_index as i32,
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| EncryptedTransaction {
+                hash: H256::from_slice(&row.hash),
+                payload: row.payload,
+            })
+            .collect()
+        })
+    }
+
+    pub fn insert_decryption_share(&mut self, hash: H256, share: &DecryptionShare) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO encrypted_transaction_decryption_shares (encrypted_tx_hash, member_index, share, created_at)
+                VALUES ($1, $2, $3, now())
+                ON CONFLICT (encrypted_tx_hash, member_index) DO NOTHING
+                ",
+                hash.as_bytes(),
+                share.member_index as i32,
+                &share.point
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the published decryption shares of the encrypted transactions ordered by the member index.
+    pub fn get_decryption_shares(
+        &mut self,
+        hashes: &[H256],
+    ) -> HashMap<H256, Vec<DecryptionShare>> {
+        async_std::task::block_on(async {
+            let hashes: Vec<_> = hashes.iter().map(|hash| hash.as_bytes().to_vec()).collect();
+            let rows = sqlx::query!(
+                "
+                SELECT encrypted_tx_hash, member_index, share FROM encrypted_transaction_decryption_shares
+                WHERE encrypted_tx_hash = ANY($1)
+                ORDER BY encrypted_tx_hash, member_index
+                ",
+                &hashes
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+
+            let mut shares: HashMap<_, Vec<_>> = HashMap::new();
+            for row in rows {
+                shares
+                    .entry(H256::from_slice(&row.encrypted_tx_hash))
+                    .or_default()
+                    .push(DecryptionShare {
+                        member_index: row.member_index as u32,
+                        point: row.share,
+                    });
+            }
+            shares
+        })
+    }
+}
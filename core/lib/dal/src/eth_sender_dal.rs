@@ -75,6 +75,25 @@ impl EthSenderDal<'_, '_> {
         })
     }
 
+    /// Returns the Ethereum transaction any of the attempts to send which has the given hash.
+    pub fn get_eth_tx_by_hash(&mut self, tx_hash: H256) -> Option<EthTx> {
+        async_std::task::block_on(async {
+            let tx_hash = format!("{:#x}", tx_hash);
+            sqlx::query_as!(
+                StorageEthTx,
+                "SELECT eth_txs.* FROM eth_txs
+                JOIN eth_txs_history ON eth_txs.id = eth_txs_history.eth_tx_id
+                WHERE eth_txs_history.tx_hash = $1
+                LIMIT 1",
+                tx_hash
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(Into::into)
+        })
+    }
+
     pub fn get_new_eth_txs(&mut self, limit: u64) -> Vec<EthTx> {
         async_std::task::block_on(async {
             let txs = sqlx::query_as!(
@@ -93,6 +112,21 @@ impl EthSenderDal<'_, '_> {
         })
     }
 
+    pub fn get_eth_txs_after(&mut self, eth_tx_id: u32, limit: u64) -> Vec<EthTx> {
+        async_std::task::block_on(async {
+            let txs = sqlx::query_as!(
+                StorageEthTx,
+                "SELECT * FROM eth_txs WHERE id > $1 ORDER BY id LIMIT $2",
+                eth_tx_id as i32,
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+            txs.into_iter().map(|tx| tx.into()).collect()
+        })
+    }
+
     pub fn get_unsent_txs(&mut self) -> Vec<TxHistoryToSend> {
         async_std::task::block_on(async {
             let txs = sqlx::query_as!(
@@ -276,6 +310,27 @@ impl EthSenderDal<'_, '_> {
         })
     }
 
+    /// Returns the UNIX timestamp (in seconds) of the latest confirmation
+    /// of an Ethereum transaction of the given type.
+    pub fn get_last_confirmation_timestamp(
+        &mut self,
+        tx_type: AggregatedActionType,
+    ) -> Option<u64> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"SELECT MAX(eth_txs_history.confirmed_at) as "confirmed_at?" FROM eth_txs
+                JOIN eth_txs_history ON eth_txs.confirmed_eth_tx_history_id = eth_txs_history.id
+                WHERE eth_txs.tx_type = $1"#,
+                tx_type.to_string()
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .confirmed_at
+            .map(|confirmed_at| confirmed_at.timestamp() as u64)
+        })
+    }
+
     pub fn get_next_nonce(&mut self) -> Option<u64> {
         async_std::task::block_on(async {
             sqlx::query!(r#"SELECT MAX(nonce) as "max_nonce?" FROM eth_txs"#,)
@@ -310,6 +365,70 @@ impl EthSenderDal<'_, '_> {
         })
     }
 
+    /// Returns the number of eth txs removed and the number of L1 batches unlinked from their eth txs
+    /// by `revert_eth_txs()`.
+    pub fn get_eth_txs_revert_stats(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> (usize, usize) {
+        async_std::task::block_on(async {
+            let eth_txs_to_remove = sqlx::query!(
+                "SELECT COUNT(*) FROM eth_txs WHERE id >= (
+                    SELECT MIN(eth_txs.id) FROM eth_txs
+                    JOIN l1_batches ON eth_txs.id IN
+                        (l1_batches.eth_commit_tx_id, l1_batches.eth_prove_tx_id, l1_batches.eth_execute_tx_id)
+                    WHERE l1_batches.number > $1 AND eth_txs.confirmed_eth_tx_history_id IS NULL
+                )",
+                last_l1_batch_to_keep.0 as i64
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .count
+            .unwrap();
+            let l1_batches_to_unlink = sqlx::query!(
+                "SELECT COUNT(*) FROM l1_batches WHERE number > $1 AND
+                    (eth_commit_tx_id IS NOT NULL OR eth_prove_tx_id IS NOT NULL OR eth_execute_tx_id IS NOT NULL)",
+                last_l1_batch_to_keep.0 as i64
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .count
+            .unwrap();
+            (eth_txs_to_remove as usize, l1_batches_to_unlink as usize)
+        })
+    }
+
+    /// Reverts the eth sender state after the L1 batches after `last_l1_batch_to_keep` were reverted on L1.
+    /// Inflight txs for such batches are removed together with all txs created after them, so that
+    /// the nonces stay contiguous; the kept L1 batches losing their txs are picked up by the aggregator again.
+    pub fn revert_eth_txs(&mut self, last_l1_batch_to_keep: L1BatchNumber) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "DELETE FROM eth_txs WHERE id >= (
+                    SELECT MIN(eth_txs.id) FROM eth_txs
+                    JOIN l1_batches ON eth_txs.id IN
+                        (l1_batches.eth_commit_tx_id, l1_batches.eth_prove_tx_id, l1_batches.eth_execute_tx_id)
+                    WHERE l1_batches.number > $1 AND eth_txs.confirmed_eth_tx_history_id IS NULL
+                )",
+                last_l1_batch_to_keep.0 as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            sqlx::query!(
+                "UPDATE l1_batches
+                    SET eth_commit_tx_id = NULL, eth_prove_tx_id = NULL, eth_execute_tx_id = NULL
+                    WHERE number > $1",
+                last_l1_batch_to_keep.0 as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
     pub fn clear_failed_transactions(&mut self) {
         async_std::task::block_on(async {
             sqlx::query!(
@@ -7,10 +7,18 @@ use crate::models::storage_event::StorageWeb3Log;
 use crate::SqlxError;
 use crate::StorageProcessor;
 use zksync_types::{
-    api::{self, GetLogsFilter, Log},
+    api::{self, GetLogsFilter, Log, LogCursor},
+    bloom::{bloom_bit_indices, bloom_byte_and_mask},
     MiniblockNumber,
 };
 
+/// Max number of miniblocks, starting from the beginning of the filter range, whose logs blooms are checked
+/// for a query. Checking a bloom costs up to 3 `get_bit` calls per filter address / topic for every miniblock
+/// in the range, so the blooms of the miniblocks past this window aren't checked, and the miniblocks are scanned
+/// as if they had no bloom. This bounds the cost of the bloom check for the filters with unbounded ranges,
+/// e.g. the ones polled via `eth_getFilterChanges`.
+const BLOOM_CHECK_MINIBLOCKS_LIMIT: u32 = 10_000;
+
 #[derive(Debug)]
 pub struct EventsWeb3Dal<'a, 'c> {
     pub storage: &'a mut StorageProcessor<'c>,
@@ -19,108 +27,210 @@ pub struct EventsWeb3Dal<'a, 'c> {
 impl EventsWeb3Dal<'_, '_> {
     /// Returns miniblock number of log for given filter and offset.
     /// Used to determine if there is more than `offset` logs that satisfies filter.
-    pub fn get_log_block_number(
+    pub async fn get_log_block_number(
         &mut self,
         filter: GetLogsFilter,
         offset: usize,
     ) -> Result<Option<MiniblockNumber>, SqlxError> {
-        async_std::task::block_on(async {
-            let started_at = Instant::now();
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+        let started_at = Instant::now();
+        let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter, None);
 
-            let query = format!(
-                r#"
-                    SELECT miniblock_number
-                    FROM events
-                    WHERE {}
-                    ORDER BY miniblock_number ASC, event_index_in_block ASC
-                    LIMIT 1 OFFSET ${}
-                "#,
-                where_sql, arg_index
-            );
+        let query = format!(
+            r#"
+                SELECT miniblock_number
+                FROM events
+                WHERE {}
+                ORDER BY miniblock_number ASC, event_index_in_block ASC
+                LIMIT 1 OFFSET ${}
+            "#,
+            where_sql, arg_index
+        );
+
+        let mut query = sqlx::query(&query);
+        query = query.bind(filter.from_block.0 as i64);
 
-            let mut query = sqlx::query(&query);
-            query = query.bind(filter.from_block.0 as i64);
-
-            if let Some(api::BlockNumber::Number(number)) = filter.to_block {
-                query = query.bind(number.as_u64() as i64);
-            }
-            if !filter.addresses.is_empty() {
-                let addresses: Vec<_> = filter
-                    .addresses
-                    .into_iter()
-                    .map(|address| address.0.to_vec())
+        if let Some(api::BlockNumber::Number(number)) = filter.to_block {
+            query = query.bind(number.as_u64() as i64);
+        }
+        if !filter.addresses.is_empty() {
+            let addresses: Vec<_> = filter
+                .addresses
+                .into_iter()
+                .map(|address| address.0.to_vec())
+                .collect();
+            query = query.bind(addresses);
+        }
+        for (_, topics) in filter.topics {
+            let topics: Vec<_> = topics.into_iter().map(|topic| topic.0.to_vec()).collect();
+            query = query.bind(topics);
+        }
+        query = query.bind(offset as i32);
+        let log = query.fetch_optional(self.storage.conn()).await?;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_log_block_number");
+
+        Ok(log.map(|row| MiniblockNumber(row.get::<i64, &str>("miniblock_number") as u32)))
+    }
+
+    /// Checks miniblock logs blooms to determine whether there may be logs satisfying the filter.
+    /// `false` means that there are definitely no such logs, so the `events` table doesn't need to be scanned.
+    /// Miniblocks without a stored bloom are assumed to contain matching logs.
+    pub async fn may_contain_logs(&mut self, filter: &GetLogsFilter) -> Result<bool, SqlxError> {
+        if filter.addresses.is_empty() && filter.topics.is_empty() {
+            return Ok(true);
+        }
+
+        let started_at = Instant::now();
+
+        let (block_sql, arg_index) =
+            web3_block_number_to_sql(api::BlockNumber::Number(filter.from_block.0.into()), 1);
+        let mut where_sql = format!("(number >= {})", block_sql);
+        if let Some(to_block) = filter.to_block {
+            let (block_sql, _) = web3_block_number_to_sql(to_block, arg_index);
+            where_sql += &format!(" AND (number <= {})", block_sql);
+        }
+
+        // Miniblocks past the bloom check window are considered to contain matching logs.
+        let query = format!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM miniblocks
+                    WHERE {} AND (number >= {} + {} OR logs_bloom IS NULL OR ({}))
+                ) AS "exists"
+            "#,
+            where_sql,
+            block_sql,
+            BLOOM_CHECK_MINIBLOCKS_LIMIT,
+            Self::bloom_filter_sql(filter)
+        );
+
+        let mut query = sqlx::query(&query);
+        query = query.bind(filter.from_block.0 as i64);
+        if let Some(api::BlockNumber::Number(number)) = filter.to_block {
+            query = query.bind(number.as_u64() as i64);
+        }
+        let row = query.fetch_one(self.storage.conn()).await?;
+
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "may_contain_logs");
+        Ok(row.get::<bool, &str>("exists"))
+    }
+
+    /// Builds an SQL condition checking that the `logs_bloom` may contain logs matching the filter.
+    fn bloom_filter_sql(filter: &GetLogsFilter) -> String {
+        let mut bloom_sql = vec![];
+        if !filter.addresses.is_empty() {
+            bloom_sql.push(Self::bloom_contains_any_sql(
+                filter.addresses.iter().map(|address| address.as_bytes()),
+            ));
+        }
+        for (_, topics) in &filter.topics {
+            bloom_sql.push(Self::bloom_contains_any_sql(
+                topics.iter().map(|topic| topic.as_bytes()),
+            ));
+        }
+        bloom_sql.join(" AND ")
+    }
+
+    /// Builds an SQL condition checking that the `logs_bloom` may contain any of the inputs.
+    /// Bit positions are computed on the server side, so the query contains only integer literals.
+    fn bloom_contains_any_sql<'a>(inputs: impl Iterator<Item = &'a [u8]>) -> String {
+        let conditions: Vec<_> = inputs
+            .map(|input| {
+                let bits: Vec<_> = bloom_bit_indices(input)
+                    .iter()
+                    .map(|&bit_index| {
+                        // `get_bit` counts bits from the least significant bit of the first byte.
+                        let (byte, mask) = bloom_byte_and_mask(bit_index);
+                        let position = byte * 8 + mask.trailing_zeros() as usize;
+                        format!("get_bit(logs_bloom, {}) = 1", position)
+                    })
                     .collect();
-                query = query.bind(addresses);
-            }
-            for (_, topics) in filter.topics {
-                let topics: Vec<_> = topics.into_iter().map(|topic| topic.0.to_vec()).collect();
-                query = query.bind(topics);
-            }
-            query = query.bind(offset as i32);
-            let log = query.fetch_optional(self.storage.conn()).await?;
-
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_log_block_number");
-
-            Ok(log.map(|row| MiniblockNumber(row.get::<i64, &str>("miniblock_number") as u32)))
-        })
+                format!("({})", bits.join(" AND "))
+            })
+            .collect();
+        if conditions.is_empty() {
+            // Consistent with `ANY` of an empty array, which matches nothing.
+            return "FALSE".to_owned();
+        }
+        format!("({})", conditions.join(" OR "))
     }
 
     /// Returns logs for given filter.
+    pub async fn get_logs(
+        &mut self,
+        filter: GetLogsFilter,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        self.get_logs_after(filter, None, limit).await
+    }
+
+    /// Returns up to `limit` logs for given filter, starting from the log following `after`.
     #[allow(clippy::type_complexity)]
-    pub fn get_logs(&mut self, filter: GetLogsFilter, limit: usize) -> Result<Vec<Log>, SqlxError> {
-        async_std::task::block_on(async {
-            let started_at = Instant::now();
-            let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter);
+    pub async fn get_logs_after(
+        &mut self,
+        filter: GetLogsFilter,
+        after: Option<LogCursor>,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        let started_at = Instant::now();
+        let (where_sql, arg_index) = self.build_get_logs_where_clause(&filter, after);
 
-            let query = format!(
-                r#"
-                WITH events_select AS (
-                    SELECT
-                        address, topic1, topic2, topic3, topic4, value,
-                        miniblock_number, tx_hash, tx_index_in_block,
-                        event_index_in_block, event_index_in_tx
-                    FROM events
-                    WHERE {}
-                    ORDER BY miniblock_number ASC, event_index_in_block ASC
-                    LIMIT ${}
-                )
-                SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number", events_select.*
-                FROM events_select
-                LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+        let query = format!(
+            r#"
+            WITH events_select AS (
+                SELECT
+                    address, topic1, topic2, topic3, topic4, value,
+                    miniblock_number, tx_hash, tx_index_in_block,
+                    event_index_in_block, event_index_in_tx
+                FROM events
+                WHERE {}
                 ORDER BY miniblock_number ASC, event_index_in_block ASC
-                "#,
-                where_sql, arg_index
-            );
+                LIMIT ${}
+            )
+            SELECT miniblocks.hash as "block_hash", miniblocks.l1_batch_number as "l1_batch_number", events_select.*
+            FROM events_select
+            LEFT JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            ORDER BY miniblock_number ASC, event_index_in_block ASC
+            "#,
+            where_sql, arg_index
+        );
 
-            let mut query = sqlx::query_as(&query);
-            query = query.bind(filter.from_block.0 as i64);
-
-            if let Some(api::BlockNumber::Number(number)) = filter.to_block {
-                query = query.bind(number.as_u64() as i64);
-            }
-            if !filter.addresses.is_empty() {
-                let addresses: Vec<_> = filter
-                    .addresses
-                    .into_iter()
-                    .map(|address| address.0.to_vec())
-                    .collect();
-                query = query.bind(addresses);
-            }
-            for (_, topics) in filter.topics {
-                let topics: Vec<_> = topics.into_iter().map(|topic| topic.0.to_vec()).collect();
-                query = query.bind(topics);
-            }
-            query = query.bind(limit as i32);
-
-            let db_logs: Vec<StorageWeb3Log> = query.fetch_all(self.storage.conn()).await?;
-            let logs = db_logs.into_iter().map(Into::into).collect();
-            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_logs");
-            Ok(logs)
-        })
+        let mut query = sqlx::query_as(&query);
+        query = query.bind(filter.from_block.0 as i64);
+
+        if let Some(api::BlockNumber::Number(number)) = filter.to_block {
+            query = query.bind(number.as_u64() as i64);
+        }
+        if !filter.addresses.is_empty() {
+            let addresses: Vec<_> = filter
+                .addresses
+                .into_iter()
+                .map(|address| address.0.to_vec())
+                .collect();
+            query = query.bind(addresses);
+        }
+        for (_, topics) in filter.topics {
+            let topics: Vec<_> = topics.into_iter().map(|topic| topic.0.to_vec()).collect();
+            query = query.bind(topics);
+        }
+        if let Some(after) = after {
+            query = query
+                .bind(after.block_number.0 as i64)
+                .bind(after.log_index as i32);
+        }
+        query = query.bind(limit as i32);
+
+        let db_logs: Vec<StorageWeb3Log> = query.fetch_all(self.storage.conn()).await?;
+        let logs = db_logs.into_iter().map(Into::into).collect();
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_logs");
+        Ok(logs)
     }
 
-    fn build_get_logs_where_clause(&self, filter: &GetLogsFilter) -> (String, u8) {
+    fn build_get_logs_where_clause(
+        &self,
+        filter: &GetLogsFilter,
+        after: Option<LogCursor>,
+    ) -> (String, u8) {
         let mut arg_index = 1;
 
         let (block_sql, new_arg_index) = web3_block_number_to_sql(
@@ -128,13 +238,30 @@ impl EventsWeb3Dal<'_, '_> {
             arg_index,
         );
         let mut where_sql = format!("(miniblock_number >= {})", block_sql);
+        let mut miniblocks_where_sql = format!("(number >= {})", block_sql);
+        let bloom_check_end_sql = format!("{} + {}", block_sql, BLOOM_CHECK_MINIBLOCKS_LIMIT);
         arg_index = new_arg_index;
 
         if let Some(to_block) = filter.to_block {
             let (block_sql, new_arg_index) = web3_block_number_to_sql(to_block, arg_index);
             where_sql += &format!(" AND (miniblock_number <= {})", block_sql);
+            miniblocks_where_sql += &format!(" AND (number <= {})", block_sql);
             arg_index = new_arg_index;
         }
+        // Only the miniblocks whose logs bloom may match the filter are scanned, which is much cheaper
+        // for the filters by rare addresses / topics. The block range arguments are reused by the subquery.
+        // Blooms are only checked within the window at the start of the range, see `BLOOM_CHECK_MINIBLOCKS_LIMIT`.
+        if !filter.addresses.is_empty() || !filter.topics.is_empty() {
+            where_sql += &format!(
+                " AND (miniblock_number >= {end} OR miniblock_number IN (
+                    SELECT number FROM miniblocks
+                    WHERE {} AND number < {end} AND (logs_bloom IS NULL OR ({}))
+                ))",
+                miniblocks_where_sql,
+                Self::bloom_filter_sql(filter),
+                end = bloom_check_end_sql
+            );
+        }
         if !filter.addresses.is_empty() {
             where_sql += &format!(" AND (address = ANY(${}))", arg_index);
             arg_index += 1;
@@ -143,6 +270,14 @@ impl EventsWeb3Dal<'_, '_> {
             where_sql += &format!(" AND (topic{} = ANY(${}))", topic_index, arg_index);
             arg_index += 1;
         }
+        if after.is_some() {
+            where_sql += &format!(
+                " AND ((miniblock_number, event_index_in_block) > (${}, ${}))",
+                arg_index,
+                arg_index + 1
+            );
+            arg_index += 2;
+        }
 
         (where_sql, arg_index)
     }
@@ -0,0 +1,119 @@
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::Error as SqlxError;
+
+use zksync_types::{
+    api::ExpiredPriorityOp, L1BatchNumber, L1BlockNumber, MiniblockNumber, PriorityOpId, H256,
+};
+
+use crate::StorageProcessor;
+
+/// Priority operations which weren't executed on L1 until their deadline.
+#[derive(Debug)]
+pub struct ExpiredPriorityOpsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ExpiredPriorityOpsDal<'_, '_> {
+    /// Records all the priority operations with the deadline before `current_l1_timestamp` which are not
+    /// included into the batches executed on L1 up to `last_executed_l1_batch`. The deadline of a priority
+    /// operation is the timestamp of the L1 block until which it must be executed. Only the operations not yet
+    /// executed on L1 are scanned, since the index on the deadline is keyed by the L1 batch number.
+    /// Returns the number of the newly recorded operations.
+    pub fn mark_expired_priority_ops(
+        &mut self,
+        current_l1_block: L1BlockNumber,
+        current_l1_timestamp: u64,
+        last_executed_l1_batch: Option<L1BatchNumber>,
+    ) -> usize {
+        async_std::task::block_on(async {
+            let last_executed_l1_batch =
+                last_executed_l1_batch.map_or(-1, |number| number.0 as i64);
+            let result = sqlx::query!(
+                "
+                INSERT INTO expired_priority_ops
+                    (priority_op_id, tx_hash, deadline_block, detected_at_block, created_at)
+                SELECT priority_op_id, hash, l1_deadline_timestamp, $1, now()
+                FROM transactions
+                WHERE is_priority = TRUE
+                    AND (l1_batch_number IS NULL OR l1_batch_number > $3)
+                    AND l1_deadline_timestamp < $2
+                ON CONFLICT (priority_op_id) DO NOTHING
+                ",
+                current_l1_block.0 as i64,
+                current_l1_timestamp as i64,
+                last_executed_l1_batch
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            result.rows_affected() as usize
+        })
+    }
+
+    /// Records a single expired operation. Does nothing if the operation is already recorded.
+    pub fn insert_expired_priority_op(
+        &mut self,
+        serial_id: PriorityOpId,
+        tx_hash: H256,
+        deadline_block: u64,
+        detected_at_block: L1BlockNumber,
+    ) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO expired_priority_ops
+                    (priority_op_id, tx_hash, deadline_block, detected_at_block, created_at)
+                VALUES ($1, $2, $3, $4, now())
+                ON CONFLICT (priority_op_id) DO NOTHING
+                ",
+                serial_id.0 as i64,
+                tx_hash.as_bytes(),
+                deadline_block as i64,
+                detected_at_block.0 as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the expired operations with serial IDs starting from `from`, ordered by the serial ID.
+    pub fn get_expired_priority_ops(
+        &mut self,
+        from: PriorityOpId,
+        limit: usize,
+    ) -> Result<Vec<ExpiredPriorityOp>, SqlxError> {
+        async_std::task::block_on(async {
+            let ops = sqlx::query!(
+                "
+                SELECT expired_priority_ops.*, transactions.miniblock_number, transactions.l1_batch_number
+                FROM expired_priority_ops
+                LEFT JOIN transactions ON transactions.hash = expired_priority_ops.tx_hash
+                WHERE expired_priority_ops.priority_op_id >= $1
+                ORDER BY expired_priority_ops.priority_op_id
+                LIMIT $2
+                ",
+                from.0 as i64,
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| ExpiredPriorityOp {
+                serial_id: row.priority_op_id as u64,
+                tx_hash: H256::from_slice(&row.tx_hash),
+                deadline_block: row.deadline_block as u64,
+                detected_at_block: row.detected_at_block as u64,
+                detected_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+                miniblock_number: row
+                    .miniblock_number
+                    .map(|number| MiniblockNumber(number as u32)),
+                l1_batch_number: row
+                    .l1_batch_number
+                    .map(|number| L1BatchNumber(number as u32)),
+            })
+            .collect();
+            Ok(ops)
+        })
+    }
+}
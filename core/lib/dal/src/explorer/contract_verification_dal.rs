@@ -8,8 +8,9 @@ use zksync_types::{
     get_code_key, Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
 };
 
-use sqlx::postgres::types::PgInterval;
+use sqlx::types::chrono::NaiveDateTime;
 
+use crate::job_queue_dal::ContractVerificationQueue;
 use crate::SqlxError;
 use crate::StorageProcessor;
 
@@ -79,31 +80,30 @@ impl ContractVerificationDal<'_, '_> {
     pub fn get_next_queued_verification_request(
         &mut self,
         processing_timeout: Duration,
+        picked_by: &str,
     ) -> Result<Option<VerificationRequest>, SqlxError> {
         async_std::task::block_on(async {
-            let processing_timeout = PgInterval {
-                months: 0,
-                days: 0,
-                microseconds: processing_timeout.as_micros() as i64,
+            let mut transaction = self.storage.start_transaction().await;
+            let mut queue = transaction.job_queue_dal(ContractVerificationQueue);
+            queue.requeue_expired(processing_timeout);
+            let id = queue.lock_next_request();
+            if let Some(id) = id {
+                queue.lease(id, picked_by);
+            }
+
+            let row = match id {
+                Some(id) => Some(
+                    sqlx::query!(
+                        "SELECT * FROM contract_verification_requests WHERE id = $1",
+                        id as i64
+                    )
+                    .fetch_one(transaction.conn())
+                    .await?,
+                ),
+                None => None,
             };
-            let result = sqlx::query!(
-                "UPDATE contract_verification_requests
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE id = (
-                    SELECT id FROM contract_verification_requests
-                    WHERE status = 'queued' OR (status = 'in_progress' AND processing_started_at < now() - $1::interval)
-                    ORDER BY created_at
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING contract_verification_requests.*",
-                &processing_timeout
-            )
-            .fetch_optional(self.storage.conn())
-            .await?
-            .map(|row| VerificationRequest {
+            transaction.commit().await;
+            Ok(row.map(|row| VerificationRequest {
                 id: row.id as usize,
                 req: VerificationIncomingRequest {
                     contract_address: Address::from_slice(&row.contract_address),
@@ -115,8 +115,7 @@ impl ContractVerificationDal<'_, '_> {
                     constructor_arguments: row.constructor_arguments.into(),
                     is_system: row.is_system,
                 },
-            });
-            Ok(result)
+            }))
         })
     }
 
@@ -145,10 +144,10 @@ impl ContractVerificationDal<'_, '_> {
             sqlx::query!(
                 "
                     INSERT INTO contracts_verification_info
-                    (address, verification_info)
-                    VALUES ($1, $2)
+                    (address, verification_info, created_at, updated_at)
+                    VALUES ($1, $2, now(), now())
                     ON CONFLICT (address)
-                    DO UPDATE SET verification_info = $2
+                    DO UPDATE SET verification_info = $2, updated_at = now()
                 ",
                 address.as_bytes(),
                 &verification_info_json
@@ -286,6 +285,49 @@ impl ContractVerificationDal<'_, '_> {
         })
     }
 
+    /// Returns ABIs of the contracts verified or re-verified after `since - overlap` (or all of them if `since`
+    /// is `None`), together with the database time before the query to be passed as `since` to the next call.
+    ///
+    /// Verification time is the start time of the verifier transaction, so the verifications committed after
+    /// the previous call may be timestamped before it; `overlap` should exceed the duration of these transactions,
+    /// so that such verifications are not missed. The contracts verified within the overlap are returned again.
+    pub fn get_verified_abis_updated_after(
+        &mut self,
+        since: Option<NaiveDateTime>,
+        overlap: Duration,
+    ) -> (Vec<(Address, serde_json::Value)>, NaiveDateTime) {
+        async_std::task::block_on(async {
+            let synced_until = sqlx::query!(r#"SELECT now()::timestamp AS "now!""#)
+                .fetch_one(self.storage.conn())
+                .await
+                .unwrap()
+                .now;
+            let since = since.map(|since| {
+                since
+                    - sqlx::types::chrono::Duration::from_std(overlap)
+                        .expect("overlap is too large")
+            });
+            let rows = sqlx::query!(
+                r#"
+                    SELECT address, verification_info->'artifacts'->'abi' AS "abi!"
+                    FROM contracts_verification_info
+                    WHERE ($1::timestamp IS NULL OR updated_at > $1)
+                        AND verification_info->'artifacts'->'abi' IS NOT NULL
+                "#,
+                since
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+
+            let abis = rows
+                .into_iter()
+                .map(|row| (Address::from_slice(&row.address), row.abi))
+                .collect();
+            (abis, synced_until)
+        })
+    }
+
     pub fn get_zksolc_versions(&mut self) -> Result<Vec<String>, SqlxError> {
         async_std::task::block_on(async {
             let versions: Vec<_> = sqlx::query!(
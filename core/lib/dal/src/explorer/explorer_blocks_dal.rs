@@ -6,6 +6,7 @@ use zksync_types::explorer_api::{
 };
 use zksync_types::{L1BatchNumber, MiniblockNumber};
 
+use super::cursor_filters;
 use crate::models::storage_block::{
     block_page_item_from_storage, l1_batch_page_item_from_storage, StorageBlockDetails,
     StorageL1BatchDetails,
@@ -29,8 +30,20 @@ impl ExplorerBlocksDal<'_, '_> {
                 PaginationDirection::Older => ("<", "DESC"),
                 PaginationDirection::Newer => (">", "ASC"),
             };
-            let cmp_str = if query.from.is_some() {
-                format!("WHERE miniblocks.number {} $3", cmp_sign)
+            let mut filters = Vec::new();
+            if query.from.is_some() {
+                filters.push(format!("miniblocks.number {} $3", cmp_sign));
+            }
+            if let Some(cursor) = query.pagination.cursor {
+                filters.extend(cursor_filters(
+                    &cursor,
+                    query.pagination.direction,
+                    "miniblocks.number",
+                    None,
+                ));
+            }
+            let cmp_str = if !filters.is_empty() {
+                format!("WHERE {}", filters.join(" AND "))
             } else {
                 "".to_string()
             };
@@ -84,7 +97,10 @@ impl ExplorerBlocksDal<'_, '_> {
                         miniblocks.l1_gas_price,
                         miniblocks.l2_fair_gas_price,
                         miniblocks.bootloader_code_hash,
-                        miniblocks.default_aa_code_hash
+                        miniblocks.default_aa_code_hash,
+                        miniblocks.execution_digest,
+                        miniblocks.execution_digest_version,
+                        miniblocks.fee_account_address as "operator_address?"
                     FROM miniblocks
                     LEFT JOIN l1_batches ON miniblocks.l1_batch_number = l1_batches.number
                     LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
@@ -111,8 +127,20 @@ impl ExplorerBlocksDal<'_, '_> {
                 PaginationDirection::Older => ("<", "DESC"),
                 PaginationDirection::Newer => (">", "ASC"),
             };
-            let cmp_str = if query.from.is_some() {
-                format!("WHERE l1_batches.number {} $3", cmp_sign)
+            let mut filters = Vec::new();
+            if query.from.is_some() {
+                filters.push(format!("l1_batches.number {} $3", cmp_sign));
+            }
+            if let Some(cursor) = query.pagination.cursor {
+                filters.extend(cursor_filters(
+                    &cursor,
+                    query.pagination.direction,
+                    "l1_batches.number",
+                    None,
+                ));
+            }
+            let cmp_str = if !filters.is_empty() {
+                format!("WHERE {}", filters.join(" AND "))
             } else {
                 "".to_string()
             };
@@ -165,7 +193,8 @@ impl ExplorerBlocksDal<'_, '_> {
                         l1_batches.l1_gas_price,
                         l1_batches.l2_fair_gas_price,
                         l1_batches.bootloader_code_hash,
-                        l1_batches.default_aa_code_hash
+                        l1_batches.default_aa_code_hash,
+                        l1_batches.priority_ops_onchain_data_hash as "priority_ops_hash?"
                     FROM l1_batches
                     LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
                     LEFT JOIN eth_txs_history as prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL)
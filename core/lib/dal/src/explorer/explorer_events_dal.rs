@@ -3,6 +3,7 @@ use zksync_types::explorer_api::{EventsQuery, EventsResponse, PaginationDirectio
 
 use sqlx::Row;
 
+use super::cursor_filters;
 use crate::models::storage_event::StorageWeb3Log;
 use crate::{SqlxError, StorageProcessor};
 
@@ -36,11 +37,25 @@ impl ExplorerEventsDal<'_, '_> {
                 filters.push(format!("(events.address = ${})", bind_index));
                 bind_index += 1;
             }
-            let filters: String = if !filters.is_empty() {
-                format!("WHERE {}", filters.join(" AND "))
-            } else {
-                "".to_string()
+            // The cursor only selects the page, so it doesn't affect `total`.
+            let mut page_filters = filters.clone();
+            if let Some(cursor) = query.pagination.cursor {
+                page_filters.extend(cursor_filters(
+                    &cursor,
+                    query.pagination.direction,
+                    "events.miniblock_number",
+                    Some("events.event_index_in_block"),
+                ));
+            }
+            let where_clause = |filters: Vec<String>| {
+                if !filters.is_empty() {
+                    format!("WHERE {}", filters.join(" AND "))
+                } else {
+                    "".to_string()
+                }
             };
+            let filters = where_clause(filters);
+            let page_filters = where_clause(page_filters);
 
             let ordering = format!(
                 "events.miniblock_number {0}, events.event_index_in_block {0}",
@@ -62,7 +77,7 @@ impl ExplorerEventsDal<'_, '_> {
                 JOIN miniblocks ON events.miniblock_number = miniblocks.number
                 ORDER BY {1}
                 "#,
-                filters,
+                page_filters,
                 ordering,
                 bind_index,
                 bind_index + 1
@@ -81,7 +96,17 @@ impl ExplorerEventsDal<'_, '_> {
 
             let storage_web3_logs: Vec<StorageWeb3Log> =
                 sql_query.fetch_all(self.storage.conn()).await?;
-            let logs = storage_web3_logs.into_iter().map(Log::from).collect();
+            let logs: Vec<Log> = storage_web3_logs.into_iter().map(Log::from).collect();
+            let last_log_key = logs
+                .last()
+                .and_then(|log| Some((log.block_number?.as_u32(), Some(log.log_index?.as_u32()))));
+            let next_cursor = query.pagination.next_cursor(logs.len(), last_log_key, || {
+                Ok(self
+                    .storage
+                    .blocks_web3_dal()
+                    .get_sealed_miniblock_number()?
+                    .0)
+            })?;
 
             let sql_count_query_str = format!(
                 r#"
@@ -111,6 +136,7 @@ impl ExplorerEventsDal<'_, '_> {
             Ok(EventsResponse {
                 list: logs,
                 total: total as usize,
+                next_cursor,
             })
         })
     }
@@ -15,7 +15,9 @@ use zksync_types::{
     tokens::ETHEREUM_ADDRESS, tx::Execute, Address, L1BatchNumber, MiniblockNumber, H256,
     L2_ETH_TOKEN_ADDRESS, U256, U64,
 };
+use zksync_utils::bigdecimal_to_u256;
 
+use super::cursor_filters;
 use crate::models::storage_event::StorageWeb3Log;
 use crate::models::storage_transaction::{
     transaction_details_from_storage, StorageTransactionDetails,
@@ -149,11 +151,18 @@ impl ExplorerTransactionsDal<'_, '_> {
             if let Some(number) = l1_batch_number {
                 filters.push(format!("transactions.l1_batch_number = {}", number.0));
             }
-            let filters: String = if !filters.is_empty() {
-                format!("WHERE {}", filters.join(" AND "))
-            } else {
-                "".to_string()
-            };
+            // The cursor only selects the page, so it doesn't affect `total`.
+            let mut page_filters = filters.clone();
+            if let Some(cursor) = pagination.cursor {
+                page_filters.extend(cursor_filters(
+                    &cursor,
+                    pagination.direction,
+                    "transactions.miniblock_number",
+                    Some("transactions.index_in_block"),
+                ));
+            }
+            let filters = format!("WHERE {}", filters.join(" AND "));
+            let page_filters = format!("WHERE {}", page_filters.join(" AND "));
             let ordering = format!(
                 "transactions.miniblock_number {0}, transactions.index_in_block {0}",
                 order_str
@@ -179,7 +188,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                 LEFT JOIN eth_txs_history as execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)
                 ORDER BY {1}
                 "#,
-                filters, ordering, pagination.limit, pagination.offset
+                page_filters, ordering, pagination.limit, pagination.offset
             );
             let storage_txs: Vec<StorageTransactionDetails> = sqlx::query_as(&sql_query_list_str)
                 .fetch_all(self.storage.conn())
@@ -195,14 +204,28 @@ impl ExplorerTransactionsDal<'_, '_> {
                     LIMIT {}
                 ) as c
                 "#,
-                filters, max_total
+                filters,
+                max_total + 1
             );
             let total = sqlx::query(&sql_query_total_str)
                 .fetch_one(self.storage.conn())
                 .await?
                 .get::<i64, &str>("count") as usize;
-
-            Ok(TransactionsResponse { list, total })
+            let next_cursor =
+                pagination.next_cursor(list.len(), Self::last_tx_key(&list), || {
+                    Ok(self
+                        .storage
+                        .blocks_web3_dal()
+                        .get_sealed_miniblock_number()?
+                        .0)
+                })?;
+
+            Ok(TransactionsResponse {
+                list,
+                total: total.min(max_total),
+                truncated: total > max_total,
+                next_cursor,
+            })
         })
     }
 
@@ -252,8 +275,21 @@ impl ExplorerTransactionsDal<'_, '_> {
                 sql_query.fetch_all(self.storage.conn()).await?;
             let list =
                 self.storage_tx_list_to_tx_details_list(storage_txs, l2_erc20_bridge_addr)?;
-
-            Ok(TransactionsResponse { list, total })
+            let next_cursor =
+                pagination.next_cursor(list.len(), Self::last_tx_key(&list), || {
+                    Ok(self
+                        .storage
+                        .blocks_web3_dal()
+                        .get_sealed_miniblock_number()?
+                        .0)
+                })?;
+
+            Ok(TransactionsResponse {
+                list,
+                total: total.min(max_total),
+                truncated: total > max_total,
+                next_cursor,
+            })
         })
     }
 
@@ -289,6 +325,18 @@ impl ExplorerTransactionsDal<'_, '_> {
                     .as_str();
                 }
             }
+            // The cursor only selects the page, so it doesn't affect `total`.
+            let mut page_filters = optional_filters.clone();
+            if let Some(cursor) = pagination.cursor {
+                for filter in cursor_filters(
+                    &cursor,
+                    pagination.direction,
+                    "miniblock_number",
+                    Some("tx_index_in_block"),
+                ) {
+                    page_filters += format!("AND {}\n", filter).as_str();
+                }
+            }
 
             let mut padded_address = [0u8; 12].to_vec();
             padded_address.extend_from_slice(account_address.as_bytes());
@@ -318,7 +366,7 @@ impl ExplorerTransactionsDal<'_, '_> {
                     ORDER BY miniblock_number {0}, tx_index_in_block {0}
                     LIMIT {2} OFFSET {3}
                 ",
-                order_str, optional_filters, pagination.limit, pagination.offset
+                order_str, page_filters, pagination.limit, pagination.offset
             );
             let sql_query = sqlx::query(&sql_query_str)
                 .bind(padded_address.clone())
@@ -359,7 +407,9 @@ impl ExplorerTransactionsDal<'_, '_> {
                     LIMIT {2}
                 ) AS c
                 "#,
-                order_str, optional_filters, max_total
+                order_str,
+                optional_filters,
+                max_total + 1
             );
             let sql_count_query = sqlx::query(&sql_count_query_str)
                 .bind(padded_address)
@@ -374,6 +424,11 @@ impl ExplorerTransactionsDal<'_, '_> {
         })
     }
 
+    fn last_tx_key(list: &[TransactionDetails]) -> Option<(u32, Option<u32>)> {
+        let last_tx = list.last()?;
+        Some((last_tx.block_number?.0, last_tx.index_in_block))
+    }
+
     fn get_erc20_transfers(
         &mut self,
         hashes: Vec<Vec<u8>>,
@@ -723,12 +778,11 @@ impl ExplorerTransactionsDal<'_, '_> {
                 .expect("Info about ETH should be present in DB");
             let eth_transfers_iter = txs.iter().filter_map(|tx| {
                 let hash = H256::from_slice(&tx.hash);
-                let execute = serde_json::from_value::<Execute>(tx.data.clone()).unwrap();
                 // All transactions with an empty calldata are considered to be called "transfers".
-                if execute.calldata().is_empty() {
+                if tx.calldata_length == Some(0) {
                     let from = Address::from_slice(&tx.initiator_address);
-                    let to = execute.contract_address;
-                    let amount = execute.value;
+                    let to = Address::from_slice(tx.contract_address.as_ref()?);
+                    let amount = bigdecimal_to_u256(tx.value.clone());
 
                     Some((
                         hash,
@@ -1,4 +1,6 @@
 use crate::StorageProcessor;
+use zksync_types::explorer_api::{PaginationCursor, PaginationDirection};
+
 use contract_verification_dal::ContractVerificationDal;
 use explorer_accounts_dal::ExplorerAccountsDal;
 use explorer_blocks_dal::ExplorerBlocksDal;
@@ -56,3 +58,28 @@ impl<'a, 'c> ExplorerIntermediator<'a, 'c> {
         }
     }
 }
+
+/// Builds SQL conditions that restrict a page to the items following the cursor position
+/// and not newer than the cursor watermark.
+pub(crate) fn cursor_filters(
+    cursor: &PaginationCursor,
+    direction: PaginationDirection,
+    number_column: &str,
+    index_column: Option<&str>,
+) -> Vec<String> {
+    let cmp_sign = match direction {
+        PaginationDirection::Older => "<",
+        PaginationDirection::Newer => ">",
+    };
+    let mut filters = vec![format!("{} <= {}", number_column, cursor.watermark)];
+    if let Some(key) = cursor.last_key {
+        match (index_column, key.index) {
+            (Some(index_column), Some(index)) => filters.push(format!(
+                "({}, {}) {} ({}, {})",
+                number_column, index_column, cmp_sign, key.number, index
+            )),
+            _ => filters.push(format!("{} {} {}", number_column, cmp_sign, key.number)),
+        }
+    }
+    filters
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use zksync_types::feature_flags::FeatureFlag;
+
+use crate::connection::ConnectionPool;
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct FeatureFlagsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl FeatureFlagsDal<'_, '_> {
+    pub fn get_feature_flags(&mut self) -> Vec<FeatureFlag> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "SELECT name, enabled, rollout_percentage, api_keys FROM feature_flags ORDER BY name"
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| FeatureFlag {
+                name: row.name,
+                enabled: row.enabled,
+                rollout_percentage: row.rollout_percentage as u8,
+                api_keys: row.api_keys,
+            })
+            .collect()
+        })
+    }
+
+    pub fn upsert_feature_flag(&mut self, flag: &FeatureFlag) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO feature_flags (name, enabled, rollout_percentage, api_keys, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, now(), now())
+                ON CONFLICT (name) DO UPDATE
+                SET enabled = $2, rollout_percentage = $3, api_keys = $4, updated_at = now()
+                ",
+                flag.name,
+                flag.enabled,
+                flag.rollout_percentage.min(100) as i16,
+                &flag.api_keys
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    pub fn remove_feature_flag(&mut self, name: &str) {
+        async_std::task::block_on(async {
+            sqlx::query!("DELETE FROM feature_flags WHERE name = $1", name)
+                .execute(self.storage.conn())
+                .await
+                .unwrap();
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct CachedFlags {
+    flags: HashMap<String, FeatureFlag>,
+    fetched_at: Option<Instant>,
+}
+
+/// Reader of the feature flags that keeps them in memory, so that checking a flag is cheap enough
+/// to be done on every API request. Once the flags become older than the configured TTL, they are
+/// reloaded in a background task, while the readers keep getting the cached values; the readers never
+/// access the database themselves. Until the flags are loaded for the first time, all of them are disabled.
+#[derive(Debug, Clone)]
+pub struct FeatureFlagsReader {
+    pool: ConnectionPool,
+    ttl: Duration,
+    cache: Arc<RwLock<CachedFlags>>,
+    is_refreshing: Arc<AtomicBool>,
+}
+
+impl FeatureFlagsReader {
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(10);
+
+    pub fn new(pool: ConnectionPool, ttl: Duration) -> Self {
+        let reader = Self {
+            pool,
+            ttl,
+            cache: Arc::default(),
+            is_refreshing: Arc::default(),
+        };
+        reader.refresh_if_stale();
+        reader
+    }
+
+    /// Checks whether the feature is enabled for the given API key and/or subject.
+    /// Unknown flags are considered disabled.
+    pub fn is_enabled(&self, name: &str, api_key: Option<&str>, subject: Option<&[u8]>) -> bool {
+        self.with_flags(|flags| {
+            flags
+                .get(name)
+                .map(|flag| flag.is_enabled_for(api_key, subject))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Returns the current state of all the flags.
+    pub fn snapshot(&self) -> Vec<FeatureFlag> {
+        let mut flags: Vec<_> = self.with_flags(|flags| flags.values().cloned().collect());
+        flags.sort_by(|a, b| a.name.cmp(&b.name));
+        flags
+    }
+
+    fn with_flags<T>(&self, f: impl FnOnce(&HashMap<String, FeatureFlag>) -> T) -> T {
+        self.refresh_if_stale();
+        f(&self.cache.read().unwrap().flags)
+    }
+
+    fn refresh_if_stale(&self) {
+        let is_stale = match self.cache.read().unwrap().fetched_at {
+            Some(fetched_at) => fetched_at.elapsed() >= self.ttl,
+            None => true,
+        };
+        if !is_stale || self.is_refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let pool = self.pool.clone();
+        let cache = self.cache.clone();
+        let refresh_guard = RefreshGuard(self.is_refreshing.clone());
+        async_std::task::spawn(async move {
+            // Resets the flag even if the refresh panics, so that the next reader retries it.
+            let _refresh_guard = refresh_guard;
+            let flags = pool
+                .access_storage()
+                .await
+                .feature_flags_dal()
+                .get_feature_flags()
+                .into_iter()
+                .map(|flag| (flag.name.clone(), flag))
+                .collect();
+            *cache.write().unwrap() = CachedFlags {
+                flags,
+                fetched_at: Some(Instant::now()),
+            };
+        });
+    }
+}
+
+/// Marks the refresh of the feature flags as finished when dropped.
+#[derive(Debug)]
+struct RefreshGuard(Arc<AtomicBool>);
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
@@ -147,7 +147,7 @@ impl FeeMonitorDal<'_, '_> {
             let mut events_web3_dal = EventsWeb3Dal {
                 storage: self.storage,
             };
-            events_web3_dal.get_logs(
+            async_std::task::block_on(events_web3_dal.get_logs(
                 GetLogsFilter {
                     from_block: miniblocks_range.0,
                     to_block: Some(api::BlockNumber::Number(miniblocks_range.1 .0.into())),
@@ -155,7 +155,7 @@ impl FeeMonitorDal<'_, '_> {
                     topics,
                 },
                 MAX_LOGS_PER_BLOCK,
-            )?
+            ))?
         };
 
         // Now collect the transfer amounts from retrieved logs.
@@ -28,11 +28,13 @@ pub enum GpuProverInstanceStatus {
 }
 
 impl GpuProverQueueDal<'_, '_> {
+    /// Reserves a free prover instance in the region, preferring the instances in the same zone.
     pub fn get_free_prover_instance(
         &mut self,
         processing_timeout: Duration,
         specialized_prover_group_id: u8,
         region: String,
+        zone: Option<String>,
     ) -> Option<SocketAddress> {
         async_std::task::block_on(async {
             let processing_timeout = pg_interval_from_duration(processing_timeout);
@@ -51,7 +53,7 @@ impl GpuProverQueueDal<'_, '_> {
                         instance_status = 'available'
                         OR (instance_status = 'reserved' AND  processing_started_at < now() - $1::interval)
                     )
-                    ORDER BY updated_at ASC
+                    ORDER BY zone IS NOT DISTINCT FROM $4 DESC, updated_at ASC
                     LIMIT 1
                     FOR UPDATE
                     SKIP LOCKED
@@ -60,7 +62,8 @@ impl GpuProverQueueDal<'_, '_> {
                 ",
                 &processing_timeout,
                 specialized_prover_group_id as i16,
-                region
+                region,
+                zone
             )
                 .fetch_optional(self.storage.conn())
                 .await
@@ -80,19 +83,21 @@ impl GpuProverQueueDal<'_, '_> {
         queue_capacity: usize,
         specialized_prover_group_id: u8,
         region: String,
+        zone: Option<String>,
     ) {
         async_std::task::block_on(async {
             sqlx::query!(
                     "
-                    INSERT INTO gpu_prover_queue (instance_host, instance_port, queue_capacity, queue_free_slots, instance_status, specialized_prover_group_id, region, created_at, updated_at)
-                    VALUES (cast($1::text as inet), $2, $3, $3, 'available', $4, $5, now(), now())
+                    INSERT INTO gpu_prover_queue (instance_host, instance_port, queue_capacity, queue_free_slots, instance_status, specialized_prover_group_id, region, zone, created_at, updated_at)
+                    VALUES (cast($1::text as inet), $2, $3, $3, 'available', $4, $5, $6, now(), now())
                     ON CONFLICT(instance_host, instance_port, region)
-                    DO UPDATE SET instance_status='available', queue_capacity=$3, queue_free_slots=$3, specialized_prover_group_id=$4, region=$5, updated_at=now()",
+                    DO UPDATE SET instance_status='available', queue_capacity=$3, queue_free_slots=$3, specialized_prover_group_id=$4, region=$5, zone=$6, updated_at=now()",
                     format!("{}",address.host),
                 address.port as i32,
                 queue_capacity as i32,
                 specialized_prover_group_id as i16,
-                region)
+                region,
+                zone)
                 .execute(self.storage.conn())
                 .await
                 .unwrap();
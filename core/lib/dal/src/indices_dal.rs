@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use crate::StorageProcessor;
+
+/// Index that the hot queries of a DAL query family rely on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequiredIndex {
+    /// Query family the index serves, e.g. `nonce` or `events`.
+    pub query_family: &'static str,
+    pub table: &'static str,
+    /// Leading key columns of the index. Any index starting with these columns satisfies the requirement,
+    /// so the requirement doesn't depend on the index name or the access method.
+    pub columns: &'static [&'static str],
+}
+
+impl RequiredIndex {
+    const fn new(
+        query_family: &'static str,
+        table: &'static str,
+        columns: &'static [&'static str],
+    ) -> Self {
+        Self {
+            query_family,
+            table,
+            columns,
+        }
+    }
+
+    fn is_satisfied_by(&self, index_columns: &[String]) -> bool {
+        index_columns.len() >= self.columns.len()
+            && self
+                .columns
+                .iter()
+                .zip(index_columns)
+                .all(|(required, actual)| required == actual)
+    }
+}
+
+/// Indices that must be present for the hot queries to be performant.
+pub const REQUIRED_INDICES: &[RequiredIndex] = &[
+    // `TransactionsWeb3Dal::next_nonce_by_initiator_account`
+    RequiredIndex::new("nonce", "transactions", &["initiator_address", "nonce"]),
+    // `StorageWeb3Dal::get_address_historical_nonce`
+    RequiredIndex::new("nonce", "storage_logs", &["hashed_key", "miniblock_number"]),
+    // `EventsWeb3Dal::get_logs` and `EventsWeb3Dal::get_log_block_number`
+    RequiredIndex::new("events", "events", &["miniblock_number"]),
+    RequiredIndex::new("events", "events", &["address"]),
+    RequiredIndex::new("events", "events", &["topic1"]),
+    RequiredIndex::new("events", "events", &["topic2"]),
+    RequiredIndex::new("events", "events", &["topic3"]),
+    RequiredIndex::new("events", "events", &["topic4"]),
+    // Logs of the transaction receipts.
+    RequiredIndex::new("events", "events", &["tx_hash"]),
+    // `TransactionsWeb3Dal::get_pending_transactions`
+    RequiredIndex::new(
+        "pending_transactions",
+        "transactions",
+        &["received_at", "hash"],
+    ),
+    // `TransfersDal::get_transfers`
+    RequiredIndex::new(
+        "transfers",
+        "transfers",
+        &["from_address", "miniblock_number"],
+    ),
+    RequiredIndex::new(
+        "transfers",
+        "transfers",
+        &["to_address", "miniblock_number"],
+    ),
+    // `BlocksWeb3Dal::get_miniblock_by_timestamp`
+    RequiredIndex::new("block_by_timestamp", "miniblocks", &["timestamp"]),
+];
+
+#[derive(Debug)]
+pub struct IndicesDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl IndicesDal<'_, '_> {
+    /// Returns the key columns of the existing indices for each of the tables.
+    pub fn get_index_columns(&mut self, tables: &[String]) -> HashMap<String, Vec<Vec<String>>> {
+        async_std::task::block_on(async {
+            let rows = sqlx::query!(
+                r#"
+                SELECT tablename::text AS "table_name!", indexdef AS "index_definition!"
+                FROM pg_indexes
+                WHERE schemaname = current_schema() AND tablename = ANY($1::text[])
+                "#,
+                tables
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap();
+
+            let mut index_columns: HashMap<_, Vec<_>> = HashMap::new();
+            for row in rows {
+                index_columns
+                    .entry(row.table_name)
+                    .or_default()
+                    .push(parse_index_columns(&row.index_definition));
+            }
+            index_columns
+        })
+    }
+
+    /// Returns the required indices missing in the database.
+    pub fn get_missing_required_indices(&mut self) -> Vec<RequiredIndex> {
+        let mut tables: Vec<_> = REQUIRED_INDICES
+            .iter()
+            .map(|index| index.table.to_owned())
+            .collect();
+        tables.sort_unstable();
+        tables.dedup();
+        let index_columns = self.get_index_columns(&tables);
+
+        REQUIRED_INDICES
+            .iter()
+            .filter(|required| {
+                let table_indices = index_columns.get(required.table);
+                !table_indices.map_or(false, |indices| {
+                    indices
+                        .iter()
+                        .any(|columns| required.is_satisfied_by(columns))
+                })
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// Extracts the key columns from the index definition returned by `pg_indexes`, e.g.
+/// `CREATE INDEX events_address_idx ON public.events USING btree (address)`.
+/// Expression columns are returned as is, and the ordering options are dropped.
+fn parse_index_columns(index_definition: &str) -> Vec<String> {
+    let start = match index_definition.find('(') {
+        Some(start) => start,
+        None => return vec![],
+    };
+    let mut depth = 0;
+    let mut columns = vec![];
+    let mut column = String::new();
+    for ch in index_definition[start + 1..].chars() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                columns.push(std::mem::take(&mut column));
+                continue;
+            }
+            _ => {}
+        }
+        column.push(ch);
+    }
+    columns.push(column);
+
+    columns
+        .iter()
+        .map(|column| {
+            let column = column.trim();
+            let name = column.split_whitespace().next().unwrap_or(column);
+            name.trim_matches('"').to_owned()
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::Row;
+
+use crate::time_utils::pg_interval_from_duration;
+use crate::StorageProcessor;
+
+/// Version of the oldest DB schema whose binaries can run against the schema of this binary.
+/// Must be bumped to the version of the new migration if it breaks the queries of the older binaries
+/// (e.g., drops or renames a column), so that such binaries aren't rolled together.
+pub const COMPATIBLE_SINCE_SCHEMA_VERSION: i64 = 20230320090000;
+
+/// Returns the DB schema version this binary is built for, i.e. the version of its latest migration.
+pub fn binary_schema_version() -> i64 {
+    sqlx::migrate!()
+        .migrations
+        .iter()
+        .map(|migration| migration.version)
+        .max()
+        .expect("no migrations")
+}
+
+/// Heartbeat of a running server instance, advertising the DB schema versions it's compatible with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceHeartbeat {
+    pub instance_id: String,
+    pub components: String,
+    pub schema_version: i64,
+    pub compatible_since_schema_version: i64,
+    pub started_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+impl InstanceHeartbeat {
+    /// Checks whether the instances can share the database, i.e. each of them can run
+    /// against the schema of the other one.
+    pub fn is_compatible_with(&self, schema_version: i64, compatible_since: i64) -> bool {
+        self.schema_version >= compatible_since
+            && schema_version >= self.compatible_since_schema_version
+    }
+}
+
+#[derive(Debug)]
+pub struct InstanceHeartbeatsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl InstanceHeartbeatsDal<'_, '_> {
+    /// Returns the version of the latest migration applied to the database.
+    pub fn get_schema_version(&mut self) -> Option<i64> {
+        async_std::task::block_on(async {
+            // Migrations table is managed by `sqlx-cli`, so it's not known to the query macros.
+            sqlx::query("SELECT MAX(version) AS version FROM _sqlx_migrations WHERE success")
+                .fetch_one(self.storage.conn())
+                .await
+                .unwrap()
+                .get::<Option<i64>, _>("version")
+        })
+    }
+
+    pub fn upsert_heartbeat(
+        &mut self,
+        instance_id: &str,
+        components: &str,
+        schema_version: i64,
+        compatible_since_schema_version: i64,
+    ) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO instance_heartbeats
+                    (instance_id, components, schema_version, compatible_since_schema_version, started_at, heartbeat_at)
+                VALUES ($1, $2, $3, $4, now(), now())
+                ON CONFLICT (instance_id) DO UPDATE SET heartbeat_at = now()
+                ",
+                instance_id,
+                components,
+                schema_version,
+                compatible_since_schema_version
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the instances which sent a heartbeat within `ttl`.
+    pub fn get_live_instances(&mut self, ttl: Duration) -> Vec<InstanceHeartbeat> {
+        async_std::task::block_on(async {
+            let ttl = pg_interval_from_duration(ttl);
+            sqlx::query!(
+                "
+                SELECT * FROM instance_heartbeats
+                WHERE heartbeat_at > now() - $1::interval
+                ORDER BY started_at
+                ",
+                &ttl
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| InstanceHeartbeat {
+                instance_id: row.instance_id,
+                components: row.components,
+                schema_version: row.schema_version,
+                compatible_since_schema_version: row.compatible_since_schema_version,
+                started_at: DateTime::<Utc>::from_utc(row.started_at, Utc),
+                heartbeat_at: DateTime::<Utc>::from_utc(row.heartbeat_at, Utc),
+            })
+            .collect()
+        })
+    }
+
+    pub fn remove_heartbeat(&mut self, instance_id: &str) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "DELETE FROM instance_heartbeats WHERE instance_id = $1",
+                instance_id
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+}
@@ -0,0 +1,180 @@
+use std::convert::TryFrom;
+
+use zksync_types::proofs::AggregationRound;
+use zksync_types::L1BatchNumber;
+
+use super::{ContractVerificationQueue, JobQueueDal, ProverJobQueue, WitnessJobQueue};
+
+impl JobQueueDal<'_, '_, WitnessJobQueue> {
+    /// Locks up to `limit` eligible jobs for L1 batches up to `last_l1_batch_to_process`, in the order
+    /// of the L1 batches. A job is eligible if it's queued, or failed with attempts left. Must be called
+    /// in a transaction; the jobs locked by other workers are skipped.
+    pub fn lock_next_jobs(
+        &mut self,
+        max_attempts: u32,
+        last_l1_batch_to_process: u32,
+        limit: usize,
+    ) -> Vec<L1BatchNumber> {
+        async_std::task::block_on(async {
+            let max_attempts = i32::try_from(max_attempts).unwrap_or(i32::MAX);
+            let last_l1_batch_to_process = last_l1_batch_to_process as i64;
+            let limit = limit as i64;
+            let conn = self.storage.conn();
+            let numbers: Vec<i64> = match self.queue.0 {
+                AggregationRound::BasicCircuits => sqlx::query!(
+                    "
+                    SELECT l1_batch_number FROM witness_inputs
+                    WHERE l1_batch_number <= $2 AND (status = 'queued' OR (status = 'failed' AND attempts < $1))
+                    ORDER BY l1_batch_number ASC
+                    LIMIT $3
+                    FOR UPDATE
+                    SKIP LOCKED
+                    ",
+                    max_attempts,
+                    last_l1_batch_to_process,
+                    limit
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| row.l1_batch_number)
+                .collect(),
+                AggregationRound::LeafAggregation => sqlx::query!(
+                    "
+                    SELECT l1_batch_number FROM leaf_aggregation_witness_jobs
+                    WHERE l1_batch_number <= $2 AND (status = 'queued' OR (status = 'failed' AND attempts < $1))
+                    ORDER BY l1_batch_number ASC
+                    LIMIT $3
+                    FOR UPDATE
+                    SKIP LOCKED
+                    ",
+                    max_attempts,
+                    last_l1_batch_to_process,
+                    limit
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| row.l1_batch_number)
+                .collect(),
+                AggregationRound::NodeAggregation => sqlx::query!(
+                    "
+                    SELECT l1_batch_number FROM node_aggregation_witness_jobs
+                    WHERE l1_batch_number <= $2 AND (status = 'queued' OR (status = 'failed' AND attempts < $1))
+                    ORDER BY l1_batch_number ASC
+                    LIMIT $3
+                    FOR UPDATE
+                    SKIP LOCKED
+                    ",
+                    max_attempts,
+                    last_l1_batch_to_process,
+                    limit
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| row.l1_batch_number)
+                .collect(),
+                AggregationRound::Scheduler => sqlx::query!(
+                    "
+                    SELECT l1_batch_number FROM scheduler_witness_jobs
+                    WHERE l1_batch_number <= $2 AND (status = 'queued' OR (status = 'failed' AND attempts < $1))
+                    ORDER BY l1_batch_number ASC
+                    LIMIT $3
+                    FOR UPDATE
+                    SKIP LOCKED
+                    ",
+                    max_attempts,
+                    last_l1_batch_to_process,
+                    limit
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| row.l1_batch_number)
+                .collect(),
+            };
+            numbers
+                .into_iter()
+                .map(|number| L1BatchNumber(number as u32))
+                .collect()
+        })
+    }
+}
+
+impl JobQueueDal<'_, '_, ProverJobQueue> {
+    /// Locks the next eligible prover job, optionally only among the `circuit_types`. The jobs of the later
+    /// aggregation rounds go first, since they unblock the proofs of their L1 batches. A job is eligible
+    /// if it's queued, or failed with attempts left. Must be called in a transaction.
+    pub fn lock_next_job(
+        &mut self,
+        max_attempts: u32,
+        circuit_types: Option<&[String]>,
+    ) -> Option<u32> {
+        async_std::task::block_on(async {
+            let max_attempts = i32::try_from(max_attempts).unwrap_or(i32::MAX);
+            let conn = self.storage.conn();
+            let id = match circuit_types {
+                None => sqlx::query!(
+                    "
+                    SELECT id FROM prover_jobs
+                    WHERE status = 'queued' OR (status = 'failed' AND attempts < $1)
+                    ORDER BY aggregation_round DESC, l1_batch_number ASC, id ASC
+                    LIMIT 1
+                    FOR UPDATE
+                    SKIP LOCKED
+                    ",
+                    max_attempts
+                )
+                .fetch_optional(conn)
+                .await
+                .unwrap()
+                .map(|row| row.id),
+                Some(circuit_types) => sqlx::query!(
+                    "
+                    SELECT id FROM prover_jobs
+                    WHERE circuit_type = ANY($2)
+                        AND (status = 'queued' OR (status = 'failed' AND attempts < $1))
+                    ORDER BY aggregation_round DESC, l1_batch_number ASC, id ASC
+                    LIMIT 1
+                    FOR UPDATE
+                    SKIP LOCKED
+                    ",
+                    max_attempts,
+                    circuit_types
+                )
+                .fetch_optional(conn)
+                .await
+                .unwrap()
+                .map(|row| row.id),
+            };
+            id.map(|id| id as u32)
+        })
+    }
+}
+
+impl JobQueueDal<'_, '_, ContractVerificationQueue> {
+    /// Locks the oldest queued verification request. Must be called in a transaction.
+    pub fn lock_next_request(&mut self) -> Option<usize> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT id FROM contract_verification_requests
+                WHERE status = 'queued'
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE
+                SKIP LOCKED
+                "
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| row.id as usize)
+        })
+    }
+}
@@ -0,0 +1,207 @@
+//! Status management shared by the job tables: the witness generator tables for each aggregation round,
+//! `prover_jobs` and `contract_verification_requests`.
+//!
+//! All of them follow the same lifecycle: a job is `queued`, leased by a worker which moves it to `in_progress`,
+//! increments `attempts` and records itself in `picked_by`, and finally becomes `successful` or `failed`. A job whose lease has expired
+//! is always returned to the queue, since the expiry is caused by the worker rather than the job, while
+//! a failed proving job is only retried until it runs out of attempts.
+//! The table-specific DALs only load the details of the leased jobs, since they differ between the tables.
+//!
+//! Each table has its own static queries, so that they are checked against the schema at compile time.
+
+use std::fmt;
+use std::str::FromStr;
+
+use zksync_types::proofs::AggregationRound;
+use zksync_types::L1BatchNumber;
+
+use crate::StorageProcessor;
+
+mod locks;
+mod proving;
+mod transitions;
+
+/// Status of a job in one of the job tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JobStatus {
+    Queued,
+    InProgress,
+    /// The prover job is being proven by a GPU prover; it's leased the same way as `InProgress`.
+    InGpuProof,
+    Successful,
+    Failed,
+    Skipped,
+    WaitingForArtifacts,
+    WaitingForProofs,
+    /// The input artifacts of the job failed the integrity check, so the job isn't retried.
+    ArtifactCorrupted,
+}
+
+impl JobStatus {
+    pub const ALL: [Self; 9] = [
+        Self::Queued,
+        Self::InProgress,
+        Self::InGpuProof,
+        Self::Successful,
+        Self::Failed,
+        Self::Skipped,
+        Self::WaitingForArtifacts,
+        Self::WaitingForProofs,
+        Self::ArtifactCorrupted,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::InProgress => "in_progress",
+            Self::InGpuProof => "in_gpu_proof",
+            Self::Successful => "successful",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+            Self::WaitingForArtifacts => "waiting_for_artifacts",
+            Self::WaitingForProofs => "waiting_for_proofs",
+            Self::ArtifactCorrupted => "artifact_corrupted",
+        }
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JobStatus {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|status| status.as_str() == input)
+            .ok_or_else(|| format!("Unknown job status: {}", input))
+    }
+}
+
+/// Table holding the jobs of a single pipeline stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobTable {
+    WitnessInputs,
+    LeafAggregationWitnessJobs,
+    NodeAggregationWitnessJobs,
+    SchedulerWitnessJobs,
+    ProverJobs,
+    ContractVerificationRequests,
+}
+
+impl JobTable {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::WitnessInputs => "witness_inputs",
+            Self::LeafAggregationWitnessJobs => "leaf_aggregation_witness_jobs",
+            Self::NodeAggregationWitnessJobs => "node_aggregation_witness_jobs",
+            Self::SchedulerWitnessJobs => "scheduler_witness_jobs",
+            Self::ProverJobs => "prover_jobs",
+            Self::ContractVerificationRequests => "contract_verification_requests",
+        }
+    }
+}
+
+/// Queue of the jobs stored in a [`JobTable`].
+pub trait JobQueue: fmt::Debug + Copy {
+    /// Identifier of a job in the table.
+    type Id: fmt::Debug + Copy;
+
+    fn table(&self) -> JobTable;
+
+    fn bind_id(id: Self::Id) -> i64;
+}
+
+/// Queue of the proving pipeline. Its jobs belong to an L1 batch, whose proof is skipped once a job
+/// runs out of attempts, and track the time taken to process them.
+pub trait ProvingJobQueue: JobQueue {}
+
+/// Witness generator jobs of an aggregation round, identified by the L1 batch number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessJobQueue(pub AggregationRound);
+
+impl JobQueue for WitnessJobQueue {
+    type Id = L1BatchNumber;
+
+    fn table(&self) -> JobTable {
+        match self.0 {
+            AggregationRound::BasicCircuits => JobTable::WitnessInputs,
+            AggregationRound::LeafAggregation => JobTable::LeafAggregationWitnessJobs,
+            AggregationRound::NodeAggregation => JobTable::NodeAggregationWitnessJobs,
+            AggregationRound::Scheduler => JobTable::SchedulerWitnessJobs,
+        }
+    }
+
+    fn bind_id(id: L1BatchNumber) -> i64 {
+        id.0 as i64
+    }
+}
+
+impl ProvingJobQueue for WitnessJobQueue {}
+
+/// Prover jobs of all circuits and aggregation rounds. Jobs in `in_gpu_proof` are leased the same way
+/// as the ones `in_progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProverJobQueue;
+
+impl JobQueue for ProverJobQueue {
+    type Id = u32;
+
+    fn table(&self) -> JobTable {
+        JobTable::ProverJobs
+    }
+
+    fn bind_id(id: u32) -> i64 {
+        id as i64
+    }
+}
+
+impl ProvingJobQueue for ProverJobQueue {}
+
+/// Contract verification requests. A failed request is final, since its failure is reported to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractVerificationQueue;
+
+impl JobQueue for ContractVerificationQueue {
+    type Id = usize;
+
+    fn table(&self) -> JobTable {
+        JobTable::ContractVerificationRequests
+    }
+
+    fn bind_id(id: usize) -> i64 {
+        id as i64
+    }
+}
+
+/// Proving job marked as failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailedJob {
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of attempts made for the job.
+    pub attempts: u32,
+}
+
+#[derive(Debug)]
+pub struct JobQueueDal<'a, 'c, J> {
+    pub storage: &'a mut StorageProcessor<'c>,
+    pub queue: J,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn job_status_roundtrip() {
+        for status in JobStatus::ALL {
+            assert_eq!(status.as_str().parse::<JobStatus>().unwrap(), status);
+        }
+        assert!("ignored".parse::<JobStatus>().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use std::time::{Duration, Instant};
+
+use zksync_types::L1BatchNumber;
+
+use super::{FailedJob, JobQueueDal, JobStatus, JobTable, ProvingJobQueue};
+use crate::time_utils::duration_to_naive_time;
+
+impl<J: ProvingJobQueue> JobQueueDal<'_, '_, J> {
+    pub fn mark_successful(&mut self, id: J::Id, time_taken: Duration) {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let id = J::bind_id(id);
+            let time_taken = duration_to_naive_time(time_taken);
+            let conn = self.storage.conn();
+            match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    "
+                    UPDATE witness_inputs SET status = 'successful', updated_at = now(), time_taken = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    time_taken,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE leaf_aggregation_witness_jobs SET status = 'successful', updated_at = now(), time_taken = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    time_taken,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE node_aggregation_witness_jobs SET status = 'successful', updated_at = now(), time_taken = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    time_taken,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE scheduler_witness_jobs SET status = 'successful', updated_at = now(), time_taken = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    time_taken,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ProverJobs => sqlx::query!(
+                    "
+                    UPDATE prover_jobs SET status = 'successful', updated_at = now(), time_taken = $1
+                    WHERE id = $2
+                    ",
+                    time_taken,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ContractVerificationRequests => {
+                    unreachable!("contract verification requests are not a proving job queue")
+                }
+            };
+            self.report_transition(JobStatus::Successful, started_at, "mark_job_successful");
+        })
+    }
+
+    /// Marks the job as failed. The caller decides whether the job is retried based on the returned attempts.
+    pub fn mark_failed(
+        &mut self,
+        id: J::Id,
+        error: &str,
+        time_taken: Option<Duration>,
+    ) -> FailedJob {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let id = J::bind_id(id);
+            let time_taken = time_taken.map(duration_to_naive_time);
+            let conn = self.storage.conn();
+            let (l1_batch_number, attempts) = match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    "
+                    UPDATE witness_inputs SET status = 'failed', updated_at = now(), error = $1,
+                        time_taken = COALESCE($2, time_taken)
+                    WHERE l1_batch_number = $3
+                    RETURNING l1_batch_number, attempts
+                    ",
+                    error,
+                    time_taken,
+                    id
+                )
+                .fetch_one(conn)
+                .await
+                .map(|row| (row.l1_batch_number, row.attempts)),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE leaf_aggregation_witness_jobs SET status = 'failed', updated_at = now(), error = $1,
+                        time_taken = COALESCE($2, time_taken)
+                    WHERE l1_batch_number = $3
+                    RETURNING l1_batch_number, attempts
+                    ",
+                    error,
+                    time_taken,
+                    id
+                )
+                .fetch_one(conn)
+                .await
+                .map(|row| (row.l1_batch_number, row.attempts)),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE node_aggregation_witness_jobs SET status = 'failed', updated_at = now(), error = $1,
+                        time_taken = COALESCE($2, time_taken)
+                    WHERE l1_batch_number = $3
+                    RETURNING l1_batch_number, attempts
+                    ",
+                    error,
+                    time_taken,
+                    id
+                )
+                .fetch_one(conn)
+                .await
+                .map(|row| (row.l1_batch_number, row.attempts)),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE scheduler_witness_jobs SET status = 'failed', updated_at = now(), error = $1,
+                        time_taken = COALESCE($2, time_taken)
+                    WHERE l1_batch_number = $3
+                    RETURNING l1_batch_number, attempts
+                    ",
+                    error,
+                    time_taken,
+                    id
+                )
+                .fetch_one(conn)
+                .await
+                .map(|row| (row.l1_batch_number, row.attempts)),
+                JobTable::ProverJobs => sqlx::query!(
+                    "
+                    UPDATE prover_jobs SET status = 'failed', updated_at = now(), error = $1,
+                        time_taken = COALESCE($2, time_taken)
+                    WHERE id = $3
+                    RETURNING l1_batch_number, attempts
+                    ",
+                    error,
+                    time_taken,
+                    id
+                )
+                .fetch_one(conn)
+                .await
+                .map(|row| (row.l1_batch_number, row.attempts)),
+                JobTable::ContractVerificationRequests => {
+                    unreachable!("contract verification requests are not a proving job queue")
+                }
+            }
+            .unwrap();
+            self.report_transition(JobStatus::Failed, started_at, "mark_job_failed");
+            FailedJob {
+                l1_batch_number: L1BatchNumber(l1_batch_number as u32),
+                attempts: attempts as u32,
+            }
+        })
+    }
+
+    pub fn mark_artifact_corrupted(&mut self, id: J::Id, error: &str) {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let id = J::bind_id(id);
+            let conn = self.storage.conn();
+            match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    "
+                    UPDATE witness_inputs SET status = 'artifact_corrupted', updated_at = now(), error = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    error,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE leaf_aggregation_witness_jobs SET status = 'artifact_corrupted', updated_at = now(), error = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    error,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE node_aggregation_witness_jobs SET status = 'artifact_corrupted', updated_at = now(), error = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    error,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE scheduler_witness_jobs SET status = 'artifact_corrupted', updated_at = now(), error = $1
+                    WHERE l1_batch_number = $2
+                    ",
+                    error,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ProverJobs => sqlx::query!(
+                    "
+                    UPDATE prover_jobs SET status = 'artifact_corrupted', updated_at = now(), error = $1
+                    WHERE id = $2
+                    ",
+                    error,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ContractVerificationRequests => {
+                    unreachable!("contract verification requests are not a proving job queue")
+                }
+            };
+            self.report_transition(
+                JobStatus::ArtifactCorrupted,
+                started_at,
+                "mark_job_artifact_corrupted",
+            );
+        })
+    }
+}
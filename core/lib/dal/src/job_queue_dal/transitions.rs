@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use zksync_types::proofs::JobCountStatistics;
+
+use super::{JobQueue, JobQueueDal, JobStatus, JobTable};
+use crate::time_utils::pg_interval_from_duration;
+
+impl<J: JobQueue> JobQueueDal<'_, '_, J> {
+    pub fn set_status(&mut self, id: J::Id, status: JobStatus) {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let id = J::bind_id(id);
+            let status_str = status.as_str();
+            let conn = self.storage.conn();
+            match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    "UPDATE witness_inputs SET status = $1, updated_at = now() WHERE l1_batch_number = $2",
+                    status_str,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    "UPDATE leaf_aggregation_witness_jobs SET status = $1, updated_at = now() WHERE l1_batch_number = $2",
+                    status_str,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    "UPDATE node_aggregation_witness_jobs SET status = $1, updated_at = now() WHERE l1_batch_number = $2",
+                    status_str,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    "UPDATE scheduler_witness_jobs SET status = $1, updated_at = now() WHERE l1_batch_number = $2",
+                    status_str,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ProverJobs => sqlx::query!(
+                    "UPDATE prover_jobs SET status = $1, updated_at = now() WHERE id = $2",
+                    status_str,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ContractVerificationRequests => sqlx::query!(
+                    "UPDATE contract_verification_requests SET status = $1, updated_at = now() WHERE id = $2",
+                    status_str,
+                    id
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+            };
+            self.report_transition(status, started_at, "set_job_status");
+        })
+    }
+
+    /// Leases a job returned by one of the `lock_next_*` methods to the worker identified by `picked_by`.
+    /// Must be called in the same transaction as the lock.
+    pub fn lease(&mut self, id: J::Id, picked_by: &str) {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let id = J::bind_id(id);
+            let conn = self.storage.conn();
+            match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    "
+                    UPDATE witness_inputs
+                    SET status = 'in_progress', attempts = attempts + 1,
+                        updated_at = now(), processing_started_at = now(), picked_by = $2
+                    WHERE l1_batch_number = $1
+                    ",
+                    id,
+                    picked_by
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE leaf_aggregation_witness_jobs
+                    SET status = 'in_progress', attempts = attempts + 1,
+                        updated_at = now(), processing_started_at = now(), picked_by = $2
+                    WHERE l1_batch_number = $1
+                    ",
+                    id,
+                    picked_by
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE node_aggregation_witness_jobs
+                    SET status = 'in_progress', attempts = attempts + 1,
+                        updated_at = now(), processing_started_at = now(), picked_by = $2
+                    WHERE l1_batch_number = $1
+                    ",
+                    id,
+                    picked_by
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE scheduler_witness_jobs
+                    SET status = 'in_progress', attempts = attempts + 1,
+                        updated_at = now(), processing_started_at = now(), picked_by = $2
+                    WHERE l1_batch_number = $1
+                    ",
+                    id,
+                    picked_by
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ProverJobs => sqlx::query!(
+                    "
+                    UPDATE prover_jobs
+                    SET status = 'in_progress', attempts = attempts + 1,
+                        updated_at = now(), processing_started_at = now(), picked_by = $2
+                    WHERE id = $1
+                    ",
+                    id,
+                    picked_by
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ContractVerificationRequests => sqlx::query!(
+                    "
+                    UPDATE contract_verification_requests
+                    SET status = 'in_progress', attempts = attempts + 1,
+                        updated_at = now(), processing_started_at = now(), picked_by = $2
+                    WHERE id = $1
+                    ",
+                    id,
+                    picked_by
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+            };
+            self.report_transition(JobStatus::InProgress, started_at, "lease_job");
+        })
+    }
+
+    /// Returns the jobs with expired leases to the queue. Their attempts aren't checked, so a job
+    /// is retried until a worker finishes it; only the jobs failed by the workers run out of attempts.
+    /// Returns the number of requeued jobs.
+    pub fn requeue_expired(&mut self, processing_timeout: Duration) -> usize {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let processing_timeout = pg_interval_from_duration(processing_timeout);
+            let conn = self.storage.conn();
+            let requeued = match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    "
+                    UPDATE witness_inputs
+                    SET status = 'queued', updated_at = now()
+                    WHERE status = 'in_progress' AND processing_started_at < now() - $1::interval
+                    ",
+                    &processing_timeout
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE leaf_aggregation_witness_jobs
+                    SET status = 'queued', updated_at = now()
+                    WHERE status = 'in_progress' AND processing_started_at < now() - $1::interval
+                    ",
+                    &processing_timeout
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE node_aggregation_witness_jobs
+                    SET status = 'queued', updated_at = now()
+                    WHERE status = 'in_progress' AND processing_started_at < now() - $1::interval
+                    ",
+                    &processing_timeout
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    "
+                    UPDATE scheduler_witness_jobs
+                    SET status = 'queued', updated_at = now()
+                    WHERE status = 'in_progress' AND processing_started_at < now() - $1::interval
+                    ",
+                    &processing_timeout
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ProverJobs => sqlx::query!(
+                    "
+                    UPDATE prover_jobs
+                    SET status = 'queued', updated_at = now()
+                    WHERE status IN ('in_progress', 'in_gpu_proof')
+                        AND processing_started_at < now() - $1::interval
+                    ",
+                    &processing_timeout
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+                JobTable::ContractVerificationRequests => sqlx::query!(
+                    "
+                    UPDATE contract_verification_requests
+                    SET status = 'queued', updated_at = now()
+                    WHERE status = 'in_progress' AND processing_started_at < now() - $1::interval
+                    ",
+                    &processing_timeout
+                )
+                .execute(conn)
+                .await
+                .unwrap(),
+            }
+            .rows_affected();
+            metrics::counter!(
+                "dal.job_queue.transitions", requeued,
+                "table" => self.queue.table().name(),
+                "status" => JobStatus::Queued.as_str()
+            );
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "requeue_expired_jobs");
+            requeued as usize
+        })
+    }
+
+    /// Returns the number of jobs in each status. Statuses without jobs and the ones unknown to [`JobStatus`]
+    /// are omitted.
+    pub fn count_by_status(&mut self) -> HashMap<JobStatus, usize> {
+        async_std::task::block_on(async {
+            let conn = self.storage.conn();
+            let counts: Vec<(String, i64)> = match self.queue.table() {
+                JobTable::WitnessInputs => sqlx::query!(
+                    r#"SELECT status, COUNT(*) as "count!" FROM witness_inputs GROUP BY status"#
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.status, row.count))
+                .collect(),
+                JobTable::LeafAggregationWitnessJobs => sqlx::query!(
+                    r#"SELECT status, COUNT(*) as "count!" FROM leaf_aggregation_witness_jobs GROUP BY status"#
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.status, row.count))
+                .collect(),
+                JobTable::NodeAggregationWitnessJobs => sqlx::query!(
+                    r#"SELECT status, COUNT(*) as "count!" FROM node_aggregation_witness_jobs GROUP BY status"#
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.status, row.count))
+                .collect(),
+                JobTable::SchedulerWitnessJobs => sqlx::query!(
+                    r#"SELECT status, COUNT(*) as "count!" FROM scheduler_witness_jobs GROUP BY status"#
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.status, row.count))
+                .collect(),
+                JobTable::ProverJobs => sqlx::query!(
+                    r#"SELECT status, COUNT(*) as "count!" FROM prover_jobs GROUP BY status"#
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.status, row.count))
+                .collect(),
+                JobTable::ContractVerificationRequests => sqlx::query!(
+                    r#"SELECT status, COUNT(*) as "count!" FROM contract_verification_requests GROUP BY status"#
+                )
+                .fetch_all(conn)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|row| (row.status, row.count))
+                .collect(),
+            };
+            counts
+                .into_iter()
+                .filter_map(|(status, count)| Some((status.parse().ok()?, count as usize)))
+                .collect()
+        })
+    }
+
+    pub fn stats(&mut self) -> JobCountStatistics {
+        let counts = self.count_by_status();
+        let count = |status| counts.get(&status).copied().unwrap_or(0);
+        JobCountStatistics {
+            queued: count(JobStatus::Queued),
+            in_progress: count(JobStatus::InProgress),
+            failed: count(JobStatus::Failed),
+            successful: count(JobStatus::Successful),
+        }
+    }
+
+    pub(super) fn report_transition(
+        &self,
+        status: JobStatus,
+        started_at: Instant,
+        method: &'static str,
+    ) {
+        metrics::counter!(
+            "dal.job_queue.transitions", 1,
+            "table" => self.queue.table().name(),
+            "status" => status.as_str()
+        );
+        metrics::histogram!("dal.request", started_at.elapsed(), "method" => method);
+    }
+}
@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use sqlx::Error as SqlxError;
+
+use zksync_types::api::{L1BatchL1Event, L1BatchL1EventKind};
+use zksync_types::{L1BatchNumber, H256};
+
+use crate::StorageProcessor;
+
+/// Commit, prove and execute events of the L1 batches emitted by the main L1 contract.
+#[derive(Debug)]
+pub struct L1BatchL1EventsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl L1BatchL1EventsDal<'_, '_> {
+    /// Inserts the events, skipping the already indexed ones.
+    pub fn insert_l1_batch_l1_events(&mut self, events: &[L1BatchL1Event]) {
+        async_std::task::block_on(async {
+            for event in events {
+                sqlx::query!(
+                    "
+                    INSERT INTO l1_batch_l1_events
+                        (l1_batch_number, event, l1_tx_hash, l1_block_number, block_hash, commitment, created_at)
+                    VALUES ($1, $2, $3, $4, $5, $6, now())
+                    ON CONFLICT (l1_batch_number, event, l1_tx_hash) DO NOTHING
+                    ",
+                    event.l1_batch_number.0 as i64,
+                    event.kind.as_str(),
+                    event.l1_tx_hash.as_bytes(),
+                    event.l1_block_number as i64,
+                    event.block_hash.as_ref().map(H256::as_bytes),
+                    event.commitment.as_ref().map(H256::as_bytes)
+                )
+                .execute(self.storage.conn())
+                .await
+                .unwrap();
+            }
+        })
+    }
+
+    /// Returns the events of the L1 batch in the order they were emitted on L1.
+    pub fn get_l1_batch_l1_events(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Vec<L1BatchL1Event>, SqlxError> {
+        async_std::task::block_on(async {
+            let events = sqlx::query!(
+                "
+                SELECT * FROM l1_batch_l1_events
+                WHERE l1_batch_number = $1
+                ORDER BY l1_block_number, created_at
+                ",
+                l1_batch_number.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| L1BatchL1Event {
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                kind: L1BatchL1EventKind::from_str(&row.event).unwrap(),
+                l1_tx_hash: H256::from_slice(&row.l1_tx_hash),
+                l1_block_number: row.l1_block_number as u64,
+                block_hash: row.block_hash.map(|hash| H256::from_slice(&hash)),
+                commitment: row.commitment.map(|hash| H256::from_slice(&hash)),
+            })
+            .collect();
+            Ok(events)
+        })
+    }
+}
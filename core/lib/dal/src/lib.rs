@@ -11,17 +11,37 @@ use sqlx::pool::PoolConnection;
 pub use sqlx::types::BigDecimal;
 
 // Local imports
+use crate::admin_actions_log_dal::AdminActionsLogDal;
+use crate::api_audit_log_dal::ApiAuditLogDal;
+use crate::api_usage_dal::ApiUsageDal;
 use crate::blocks_dal::BlocksDal;
 use crate::blocks_web3_dal::BlocksWeb3Dal;
+use crate::bootloader_usage_dal::BootloaderUsageDal;
+use crate::chain_stats_dal::ChainStatsDal;
 use crate::connection::holder::ConnectionHolder;
+use crate::connection::ConnectionPermit;
 pub use crate::connection::ConnectionPool;
+use crate::connection_pool_sizes_dal::ConnectionPoolSizesDal;
+use crate::db_maintenance_dal::DBMaintenanceDal;
+use crate::deployer_allowlist_dal::DeployerAllowlistDal;
+use crate::encrypted_transactions_dal::EncryptedTransactionsDal;
 use crate::eth_sender_dal::EthSenderDal;
 use crate::events_dal::EventsDal;
 use crate::events_web3_dal::EventsWeb3Dal;
+use crate::expired_priority_ops_dal::ExpiredPriorityOpsDal;
 use crate::explorer::ExplorerIntermediator;
+use crate::feature_flags_dal::FeatureFlagsDal;
 use crate::fee_monitor_dal::FeeMonitorDal;
 use crate::gpu_prover_queue_dal::GpuProverQueueDal;
+use crate::indices_dal::IndicesDal;
+use crate::instance_heartbeats_dal::InstanceHeartbeatsDal;
+use crate::job_queue_dal::{JobQueue, JobQueueDal};
+use crate::l1_batch_l1_events_dal::L1BatchL1EventsDal;
+use crate::protocol_versions_dal::ProtocolVersionsDal;
 use crate::prover_dal::ProverDal;
+use crate::quarantined_transactions_dal::QuarantinedTransactionsDal;
+use crate::rocksdb_backups_dal::RocksdbBackupsDal;
+use crate::state_snapshot_diffs_dal::StateSnapshotDiffsDal;
 use crate::storage_dal::StorageDal;
 use crate::storage_load_dal::StorageLoadDal;
 use crate::storage_logs_dal::StorageLogsDal;
@@ -31,19 +51,42 @@ use crate::tokens_dal::TokensDal;
 use crate::tokens_web3_dal::TokensWeb3Dal;
 use crate::transactions_dal::TransactionsDal;
 use crate::transactions_web3_dal::TransactionsWeb3Dal;
+use crate::transfers_dal::TransfersDal;
+use crate::transfers_web3_dal::TransfersWeb3Dal;
+use crate::tx_access_sets_dal::TxAccessSetsDal;
+use crate::tx_lifecycle_dal::TxLifecycleDal;
 use crate::witness_generator_dal::WitnessGeneratorDal;
 
+pub mod admin_actions_log_dal;
+pub mod api_audit_log_dal;
+pub mod api_usage_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod bootloader_usage_dal;
+pub mod chain_stats_dal;
 pub mod connection;
+pub mod connection_pool_sizes_dal;
+pub mod db_maintenance_dal;
+pub mod deployer_allowlist_dal;
+pub mod encrypted_transactions_dal;
 pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
+pub mod expired_priority_ops_dal;
 pub mod explorer;
+pub mod feature_flags_dal;
 pub mod fee_monitor_dal;
 pub mod gpu_prover_queue_dal;
+pub mod indices_dal;
+pub mod instance_heartbeats_dal;
+pub mod job_queue_dal;
+pub mod l1_batch_l1_events_dal;
 mod models;
+pub mod protocol_versions_dal;
 pub mod prover_dal;
+pub mod quarantined_transactions_dal;
+pub mod rocksdb_backups_dal;
+pub mod state_snapshot_diffs_dal;
 pub mod storage_dal;
 pub mod storage_load_dal;
 pub mod storage_logs_dal;
@@ -54,6 +97,10 @@ pub mod tokens_dal;
 pub mod tokens_web3_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
+pub mod transfers_dal;
+pub mod transfers_web3_dal;
+pub mod tx_access_sets_dal;
+pub mod tx_lifecycle_dal;
 pub mod witness_generator_dal;
 
 #[cfg(test)]
@@ -81,6 +128,8 @@ pub fn get_test_database_url() -> String {
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Permit of the resizable pool the connection is acquired from, released together with the connection.
+    _permit: Option<ConnectionPermit>,
 }
 
 impl<'a> StorageProcessor<'a> {
@@ -95,6 +144,7 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Direct(connection),
             in_transaction: false,
+            _permit: None,
         }
     }
 
@@ -120,6 +170,7 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            _permit: None,
         }
     }
 
@@ -129,6 +180,7 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::TestTransaction(conn),
             in_transaction: true,
+            _permit: None,
         }
     }
 
@@ -151,9 +203,15 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            _permit: None,
         }
     }
 
+    fn with_permit(mut self, permit: Option<ConnectionPermit>) -> Self {
+        self._permit = permit;
+        self
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
@@ -231,6 +289,34 @@ impl<'a> StorageProcessor<'a> {
         ExplorerIntermediator { storage: self }
     }
 
+    pub fn encrypted_transactions_dal(&mut self) -> EncryptedTransactionsDal<'_, 'a> {
+        EncryptedTransactionsDal { storage: self }
+    }
+
+    pub fn feature_flags_dal(&mut self) -> FeatureFlagsDal<'_, 'a> {
+        FeatureFlagsDal { storage: self }
+    }
+
+    pub fn connection_pool_sizes_dal(&mut self) -> ConnectionPoolSizesDal<'_, 'a> {
+        ConnectionPoolSizesDal { storage: self }
+    }
+
+    pub fn api_audit_log_dal(&mut self) -> ApiAuditLogDal<'_, 'a> {
+        ApiAuditLogDal { storage: self }
+    }
+
+    pub fn admin_actions_log_dal(&mut self) -> AdminActionsLogDal<'_, 'a> {
+        AdminActionsLogDal { storage: self }
+    }
+
+    pub fn api_usage_dal(&mut self) -> ApiUsageDal<'_, 'a> {
+        ApiUsageDal { storage: self }
+    }
+
+    pub fn quarantined_transactions_dal(&mut self) -> QuarantinedTransactionsDal<'_, 'a> {
+        QuarantinedTransactionsDal { storage: self }
+    }
+
     pub fn fee_monitor_dal(&mut self) -> FeeMonitorDal<'_, 'a> {
         FeeMonitorDal { storage: self }
     }
@@ -238,4 +324,71 @@ impl<'a> StorageProcessor<'a> {
     pub fn gpu_prover_queue_dal(&mut self) -> GpuProverQueueDal<'_, 'a> {
         GpuProverQueueDal { storage: self }
     }
+
+    pub fn chain_stats_dal(&mut self) -> ChainStatsDal<'_, 'a> {
+        ChainStatsDal { storage: self }
+    }
+
+    pub fn tx_lifecycle_dal(&mut self) -> TxLifecycleDal<'_, 'a> {
+        TxLifecycleDal { storage: self }
+    }
+
+    pub fn tx_access_sets_dal(&mut self) -> TxAccessSetsDal<'_, 'a> {
+        TxAccessSetsDal { storage: self }
+    }
+
+    pub fn transfers_dal(&mut self) -> TransfersDal<'_, 'a> {
+        TransfersDal { storage: self }
+    }
+
+    pub fn transfers_web3_dal(&mut self) -> TransfersWeb3Dal<'_, 'a> {
+        TransfersWeb3Dal { storage: self }
+    }
+
+    pub fn deployer_allowlist_dal(&mut self) -> DeployerAllowlistDal<'_, 'a> {
+        DeployerAllowlistDal { storage: self }
+    }
+
+    pub fn expired_priority_ops_dal(&mut self) -> ExpiredPriorityOpsDal<'_, 'a> {
+        ExpiredPriorityOpsDal { storage: self }
+    }
+
+    pub fn protocol_versions_dal(&mut self) -> ProtocolVersionsDal<'_, 'a> {
+        ProtocolVersionsDal { storage: self }
+    }
+
+    pub fn rocksdb_backups_dal(&mut self) -> RocksdbBackupsDal<'_, 'a> {
+        RocksdbBackupsDal { storage: self }
+    }
+
+    pub fn state_snapshot_diffs_dal(&mut self) -> StateSnapshotDiffsDal<'_, 'a> {
+        StateSnapshotDiffsDal { storage: self }
+    }
+
+    pub fn l1_batch_l1_events_dal(&mut self) -> L1BatchL1EventsDal<'_, 'a> {
+        L1BatchL1EventsDal { storage: self }
+    }
+
+    pub fn bootloader_usage_dal(&mut self) -> BootloaderUsageDal<'_, 'a> {
+        BootloaderUsageDal { storage: self }
+    }
+
+    pub fn db_maintenance_dal(&mut self) -> DBMaintenanceDal<'_, 'a> {
+        DBMaintenanceDal { storage: self }
+    }
+
+    pub fn instance_heartbeats_dal(&mut self) -> InstanceHeartbeatsDal<'_, 'a> {
+        InstanceHeartbeatsDal { storage: self }
+    }
+
+    pub fn indices_dal(&mut self) -> IndicesDal<'_, 'a> {
+        IndicesDal { storage: self }
+    }
+
+    pub fn job_queue_dal<J: JobQueue>(&mut self, queue: J) -> JobQueueDal<'_, 'a, J> {
+        JobQueueDal {
+            storage: self,
+            queue,
+        }
+    }
 }
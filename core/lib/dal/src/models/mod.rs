@@ -1,9 +1,11 @@
 pub mod storage_block;
+pub mod storage_chain_stats;
 pub mod storage_contract;
 pub mod storage_eth_tx;
 pub mod storage_event;
 pub mod storage_fee_monitor;
 pub mod storage_log;
+pub mod storage_protocol_version;
 pub mod storage_prover_job_info;
 pub mod storage_state_record;
 pub mod storage_token;
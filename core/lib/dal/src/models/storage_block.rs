@@ -36,6 +36,7 @@ pub struct StorageBlock {
     pub bloom: Vec<u8>,
     pub l2_to_l1_logs: Vec<Vec<u8>>,
     pub priority_ops_onchain_data: Vec<Vec<u8>>,
+    pub priority_ops_onchain_data_hash: Option<Vec<u8>>,
 
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -82,6 +83,8 @@ pub struct StorageBlock {
     pub gas_per_pubdata_limit: i64,
 
     pub skip_proof: bool,
+    pub estimated_basic_circuits: Option<i32>,
+    pub seal_criterion: Option<String>,
 }
 
 impl From<StorageBlock> for L1BatchHeader {
@@ -234,6 +237,12 @@ pub struct StorageL1BatchPageItem {
     pub timestamp: i64,
 }
 
+/// Returns the serialization version of a stored execution digest. Digests saved before the version
+/// was recorded were all serialized with the version 1.
+pub fn execution_digest_version(stored_version: Option<i32>) -> u8 {
+    stored_version.map_or(1, |version| version as u8)
+}
+
 pub fn block_page_item_from_storage(
     storage: StorageBlockPageItem,
     last_verified: MiniblockNumber,
@@ -352,6 +361,9 @@ pub struct StorageBlockDetails {
     pub l2_fair_gas_price: i64, // L2 gas price assumed in the corresponding batch
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
+    pub execution_digest: Option<Vec<u8>>,
+    pub execution_digest_version: Option<i32>,
+    pub operator_address: Option<Vec<u8>>,
 }
 
 impl From<StorageBlockDetails> for BlockDetails {
@@ -363,6 +375,16 @@ impl From<StorageBlockDetails> for BlockDetails {
         } else {
             BlockStatus::Sealed
         };
+        let finality = if storage_block_details.number == 0 {
+            api::Finality::Executed
+        } else {
+            api::Finality::new(
+                true,
+                storage_block_details.commit_tx_hash.is_some(),
+                storage_block_details.prove_tx_hash.is_some(),
+                storage_block_details.execute_tx_hash.is_some(),
+            )
+        };
         BlockDetails {
             number: MiniblockNumber(storage_block_details.number as u32),
             l1_batch_number: L1BatchNumber(storage_block_details.l1_batch_number as u32),
@@ -370,6 +392,7 @@ impl From<StorageBlockDetails> for BlockDetails {
             l1_tx_count: storage_block_details.l1_tx_count as usize,
             l2_tx_count: storage_block_details.l2_tx_count as usize,
             status,
+            finality,
             root_hash: storage_block_details
                 .root_hash
                 .as_deref()
@@ -407,6 +430,18 @@ impl From<StorageBlockDetails> for BlockDetails {
                     .map(|default_aa_code_hash| H256::from_slice(&default_aa_code_hash))
                     .expect("Should be not none"),
             },
+            execution_digest: storage_block_details
+                .execution_digest
+                .as_deref()
+                .map(H256::from_slice),
+            execution_digest_version: storage_block_details
+                .execution_digest
+                .as_ref()
+                .map(|_| execution_digest_version(storage_block_details.execution_digest_version)),
+            operator_address: storage_block_details
+                .operator_address
+                .as_deref()
+                .map(Address::from_slice),
         }
     }
 }
@@ -428,6 +463,7 @@ pub struct StorageL1BatchDetails {
     pub l2_fair_gas_price: i64,
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
+    pub priority_ops_hash: Option<Vec<u8>>,
 }
 
 impl From<StorageL1BatchDetails> for L1BatchDetails {
@@ -482,6 +518,10 @@ impl From<StorageL1BatchDetails> for L1BatchDetails {
                     .map(|default_aa_code_hash| H256::from_slice(&default_aa_code_hash))
                     .expect("Should be not none"),
             },
+            priority_ops_hash: storage_l1_batch_details
+                .priority_ops_hash
+                .as_deref()
+                .map(H256::from_slice),
         }
     }
 }
@@ -497,6 +537,8 @@ pub struct StorageMiniblockHeader {
     pub l2_fair_gas_price: i64, // L2 gas price assumed in the corresponding batch
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
+    pub logs_bloom: Option<Vec<u8>>,
+    pub fee_account_address: Option<Vec<u8>>,
 }
 
 impl From<StorageMiniblockHeader> for MiniblockHeader {
@@ -520,6 +562,14 @@ impl From<StorageMiniblockHeader> for MiniblockHeader {
                     .map(|default_aa_code_hash| H256::from_slice(&default_aa_code_hash))
                     .expect("Should be not none"),
             },
+            logs_bloom: row
+                .logs_bloom
+                .map(|bloom| H2048::from_slice(&bloom))
+                .unwrap_or_default(),
+            fee_account_address: row
+                .fee_account_address
+                .map(|address| Address::from_slice(&address))
+                .unwrap_or_default(),
         }
     }
 }
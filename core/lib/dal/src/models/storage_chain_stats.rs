@@ -0,0 +1,27 @@
+use sqlx::types::BigDecimal;
+
+use zksync_types::{chain_stats::ChainStatsCheckpoint, MiniblockNumber};
+use zksync_utils::bigdecimal_to_u256;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageChainStatsCheckpoint {
+    pub miniblock_number: i64,
+    pub timestamp: i64,
+    pub total_transactions: i64,
+    pub total_accounts: i64,
+    pub contracts_deployed: i64,
+    pub total_fees: BigDecimal,
+}
+
+impl From<StorageChainStatsCheckpoint> for ChainStatsCheckpoint {
+    fn from(checkpoint: StorageChainStatsCheckpoint) -> Self {
+        Self {
+            miniblock_number: MiniblockNumber(checkpoint.miniblock_number as u32),
+            timestamp: checkpoint.timestamp as u64,
+            total_transactions: checkpoint.total_transactions as u64,
+            total_accounts: checkpoint.total_accounts as u64,
+            contracts_deployed: checkpoint.contracts_deployed as u64,
+            total_fees: bigdecimal_to_u256(checkpoint.total_fees),
+        }
+    }
+}
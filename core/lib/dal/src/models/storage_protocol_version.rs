@@ -0,0 +1,20 @@
+use zksync_types::{protocol_version::ProtocolVersion, MiniblockNumber};
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageProtocolVersion {
+    pub id: i32,
+    pub activated_at_miniblock: i64,
+    pub block_gas_limit: i64,
+    pub max_allowed_tx_gas_limit: i64,
+}
+
+impl From<StorageProtocolVersion> for ProtocolVersion {
+    fn from(version: StorageProtocolVersion) -> Self {
+        Self {
+            id: version.id as u16,
+            activated_at_miniblock: MiniblockNumber(version.activated_at_miniblock as u32),
+            block_gas_limit: version.block_gas_limit as u32,
+            max_allowed_tx_gas_limit: version.max_allowed_tx_gas_limit as u32,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use sqlx::types::chrono::{DateTime, Utc};
 use sqlx::types::{chrono::NaiveDateTime, BigDecimal};
-use zksync_types::tokens::{TokenMarketVolume, TokenMetadata, TokenPrice};
+use zksync_types::api::L2TokenInfo;
+use zksync_types::tokens::{TokenInfo, TokenMarketVolume, TokenMetadata, TokenPrice};
+use zksync_types::Address;
 use zksync_utils::big_decimal_to_ratio;
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -20,6 +22,52 @@ impl From<StorageTokenMetadata> for TokenMetadata {
     }
 }
 
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageTokenInfo {
+    pub l1_address: Vec<u8>,
+    pub l2_address: Vec<u8>,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: i32,
+}
+
+impl From<StorageTokenInfo> for TokenInfo {
+    fn from(token: StorageTokenInfo) -> TokenInfo {
+        TokenInfo {
+            l1_address: Address::from_slice(&token.l1_address),
+            l2_address: Address::from_slice(&token.l2_address),
+            metadata: TokenMetadata {
+                name: token.name,
+                symbol: token.symbol,
+                decimals: token.decimals as u8,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageL2TokenInfo {
+    pub l2_address: Vec<u8>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<i32>,
+    pub refreshed_at: Option<NaiveDateTime>,
+}
+
+impl From<StorageL2TokenInfo> for L2TokenInfo {
+    fn from(token: StorageL2TokenInfo) -> L2TokenInfo {
+        L2TokenInfo {
+            l2_address: Address::from_slice(&token.l2_address),
+            name: token.name,
+            symbol: token.symbol,
+            decimals: token.decimals.map(|decimals| decimals as u8),
+            refreshed_at: token
+                .refreshed_at
+                .map(|refreshed_at| DateTime::<Utc>::from_utc(refreshed_at, Utc)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StorageTokenPrice {
     pub usd_price: Option<BigDecimal>,
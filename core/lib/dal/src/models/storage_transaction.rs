@@ -16,10 +16,10 @@ use zksync_types::{
     l1::{OpProcessingType, PriorityQueueType},
     Address, Execute, L1TxCommonData, L2ChainId, L2TxCommonData, Nonce, PackedEthSignature,
     PriorityOpId, Transaction, BOOTLOADER_ADDRESS, EIP_1559_TX_TYPE, EIP_2930_TX_TYPE,
-    EIP_712_TX_TYPE, H160, H256, U256,
+    EIP_712_TX_TYPE, H160, H2048, H256, U256,
 };
 use zksync_types::{ExecuteTransactionCommon, L1BatchNumber, MiniblockNumber};
-use zksync_utils::bigdecimal_to_u256;
+use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StorageTransaction {
@@ -62,6 +62,16 @@ pub struct StorageTransaction {
     pub l1_tx_mint: Option<BigDecimal>,
     pub l1_tx_refund_recipient: Option<Vec<u8>>,
 
+    pub logs_bloom: Option<Vec<u8>>,
+    pub refund_breakdown: Option<serde_json::Value>,
+
+    pub calldata_length: Option<i32>,
+    pub factory_dep_hashes: Option<Vec<Vec<u8>>>,
+    pub transfer_to: Option<Vec<u8>>,
+
+    pub l1_deadline_timestamp: Option<i64>,
+    pub valid_until: Option<i64>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -107,6 +117,16 @@ pub struct StorageTransactionDetails {
 
     pub execution_info: serde_json::Value,
 
+    pub logs_bloom: Option<Vec<u8>>,
+    pub refund_breakdown: Option<serde_json::Value>,
+
+    pub calldata_length: Option<i32>,
+    pub factory_dep_hashes: Option<Vec<Vec<u8>>>,
+    pub transfer_to: Option<Vec<u8>>,
+
+    pub l1_deadline_timestamp: Option<i64>,
+    pub valid_until: Option<i64>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 
@@ -127,11 +147,21 @@ impl StorageTransactionDetails {
             TransactionStatus::Pending
         }
     }
+
+    fn get_finality(&self) -> api::Finality {
+        api::Finality::new(
+            self.miniblock_number.is_some(),
+            self.eth_commit_tx_hash.is_some(),
+            self.eth_prove_tx_hash.is_some(),
+            self.eth_execute_tx_hash.is_some(),
+        )
+    }
 }
 
 impl From<StorageTransactionDetails> for api::TransactionDetails {
     fn from(tx_details: StorageTransactionDetails) -> Self {
         let status = tx_details.get_transaction_status();
+        let finality = tx_details.get_finality();
 
         let fee = if tx_details.is_priority {
             let full_fee_string = tx_details
@@ -166,16 +196,105 @@ impl From<StorageTransactionDetails> for api::TransactionDetails {
         let eth_execute_tx_hash = tx_details
             .eth_execute_tx_hash
             .map(|hash| H256::from_str(&hash).unwrap());
+        let refund_breakdown = tx_details
+            .refund_breakdown
+            .map(|breakdown| serde_json::from_value(breakdown).unwrap());
 
         api::TransactionDetails {
             is_l1_originated: tx_details.is_priority,
             status,
+            finality,
             fee,
             initiator_address,
             received_at,
             eth_commit_tx_hash,
             eth_prove_tx_hash,
             eth_execute_tx_hash,
+            refunded_gas: tx_details.refunded_gas.into(),
+            refund_breakdown,
+        }
+    }
+}
+
+/// Transaction receipt without the logs, which are loaded separately.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StorageTransactionReceipt {
+    pub tx_hash: Vec<u8>,
+    pub index_in_block: Option<i32>,
+    pub l1_batch_tx_index: Option<i32>,
+    pub block_number: Option<i64>,
+    pub error: Option<String>,
+    pub effective_gas_price: Option<BigDecimal>,
+    pub initiator_address: Vec<u8>,
+    /// Recipient of the transaction; the legacy transfers store it separately from the contract address.
+    pub execute_contract_address: Option<Vec<u8>>,
+    pub tx_format: Option<i32>,
+    pub refunded_gas: i64,
+    pub gas_limit: Option<BigDecimal>,
+    pub logs_bloom: Option<Vec<u8>>,
+    pub block_hash: Option<Vec<u8>>,
+    pub l1_batch_number: Option<i64>,
+    /// Address of the contract deployed by the transaction, as the key of its `AccountCodeStorage` slot.
+    pub contract_address: Option<Vec<u8>>,
+}
+
+impl From<StorageTransactionReceipt> for api::TransactionReceipt {
+    fn from(db_row: StorageTransactionReceipt) -> Self {
+        let status = match (db_row.block_number, &db_row.error) {
+            (_, Some(_)) => Some(U64::from(0)),
+            (Some(_), None) => Some(U64::from(1)),
+            // tx not executed yet
+            _ => None,
+        };
+        let tx_type = db_row.tx_format.map(U64::from).unwrap_or_default();
+        let transaction_index = db_row.index_in_block.map(U64::from).unwrap_or_default();
+
+        api::TransactionReceipt {
+            transaction_hash: H256::from_slice(&db_row.tx_hash),
+            transaction_index,
+            block_hash: db_row
+                .block_hash
+                .clone()
+                .map(|bytes| H256::from_slice(&bytes)),
+            block_number: db_row.block_number.map(U64::from),
+            l1_batch_tx_index: db_row.l1_batch_tx_index.map(U64::from),
+            l1_batch_number: db_row.l1_batch_number.map(U64::from),
+            from: H160::from_slice(&db_row.initiator_address),
+            to: db_row
+                .execute_contract_address
+                .map(|addr| Address::from_slice(&addr))
+                // For better compatibility with various clients, we never return null.
+                .or_else(|| Some(Address::default())),
+            cumulative_gas_used: Default::default(),
+            gas_used: {
+                let refunded_gas: U256 = db_row.refunded_gas.into();
+                db_row.gas_limit.map(|val| {
+                    let gas_limit = bigdecimal_to_u256(val);
+                    gas_limit - refunded_gas
+                })
+            },
+            effective_gas_price: Some(
+                db_row
+                    .effective_gas_price
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+            ),
+            contract_address: db_row
+                .contract_address
+                .map(|addr| h256_to_account_address(&H256::from_slice(&addr))),
+            logs: vec![],
+            l2_to_l1_logs: vec![],
+            status,
+            root: db_row.block_hash.map(|bytes| H256::from_slice(&bytes)),
+            logs_bloom: db_row
+                .logs_bloom
+                .map(|bloom| H2048::from_slice(&bloom))
+                .unwrap_or_default(),
+            // Even though the Rust SDK recommends us to supply "None" for legacy transactions
+            // we always supply some number anyway to have the same behaviour as most popular RPCs
+            transaction_type: Some(tx_type),
+            // Filled in by the API server from the cached L1 batch statuses.
+            finality: None,
         }
     }
 }
@@ -196,7 +315,7 @@ pub fn web3_transaction_select_sql() -> &'static str {
          transactions.effective_gas_price as effective_gas_price,
          transactions.l1_batch_number as l1_batch_number_tx,
          transactions.l1_batch_tx_index as l1_batch_tx_index,
-         transactions.data->'contractAddress' as "execute_contract_address",
+         transactions.contract_address as "execute_contract_address",
          transactions.data->'calldata' as "calldata",
          miniblocks.hash as "block_hash"
     "#
@@ -223,8 +342,10 @@ pub fn extract_web3_transaction(
             .map(U64::from),
         from: Some(H160::from_slice(db_row.get("initiator_address"))),
         to: Some(
-            serde_json::from_value::<Address>(db_row.get("execute_contract_address"))
-                .expect("incorrect address value in the database"),
+            db_row
+                .get::<Option<Vec<u8>>, &str>("execute_contract_address")
+                .map(|address| Address::from_slice(&address))
+                .unwrap_or_default(),
         ),
         value: bigdecimal_to_u256(db_row.get::<BigDecimal, &str>("value")),
         // `gas_price`, `max_fee_per_gas`, `max_priority_fee_per_gas` will be zero for the priority transactions.
@@ -341,7 +462,8 @@ impl From<StorageTransaction> for Transaction {
                     .gas_per_pubdata_limit
                     .map(bigdecimal_to_u256)
                     .unwrap_or_else(|| U256::from(1u32)),
-                deadline_block: 0,
+                // 0 (i.e., no known deadline) for old transactions that did not have the deadline stored
+                deadline_block: tx.l1_deadline_timestamp.unwrap_or_default() as u64,
                 eth_hash: Default::default(),
                 eth_block: tx.l1_block_number.unwrap_or_default() as u64,
                 canonical_tx_hash,
@@ -425,6 +547,7 @@ impl From<StorageTransaction> for Transaction {
                 input,
                 data,
                 received_at,
+                valid_until,
                 ..
             } = tx;
 
@@ -433,7 +556,7 @@ impl From<StorageTransaction> for Transaction {
                 paymaster_input,
             };
 
-            let tx_common_data = L2TxCommonData::new(
+            let mut tx_common_data = L2TxCommonData::new(
                 nonce,
                 fee,
                 Address::from_slice(&initiator_address),
@@ -445,6 +568,7 @@ impl From<StorageTransaction> for Transaction {
                 H256::from_slice(&hash),
                 paymaster_params,
             );
+            tx_common_data.valid_until = valid_until.map(|timestamp| timestamp as u64);
 
             let inner = serde_json::from_value::<Execute>(data)
                 .unwrap_or_else(|_| panic!("invalid json in database for tx {:?}", hash));
@@ -465,6 +589,7 @@ pub fn transaction_details_from_storage(
     mut deposits: Vec<BalanceChangeInfo>,
 ) -> explorer_api::TransactionDetails {
     let status = tx_details.get_transaction_status();
+    let finality = tx_details.get_finality();
 
     // Dirty fix to avoid inconsistency.
     // Info about the transactions is built using several DB requests.
@@ -533,6 +658,13 @@ pub fn transaction_details_from_storage(
         l1_tx_refund_recipient: tx_details.l1_tx_refund_recipient,
         refunded_gas: tx_details.refunded_gas,
         execution_info: tx_details.execution_info,
+        logs_bloom: tx_details.logs_bloom,
+        refund_breakdown: tx_details.refund_breakdown,
+        calldata_length: tx_details.calldata_length,
+        factory_dep_hashes: tx_details.factory_dep_hashes,
+        transfer_to: tx_details.transfer_to,
+        l1_deadline_timestamp: tx_details.l1_deadline_timestamp,
+        valid_until: tx_details.valid_until,
         created_at: tx_details.created_at,
         updated_at: tx_details.updated_at,
     };
@@ -633,6 +765,7 @@ pub fn transaction_details_from_storage(
         data,
         is_l1_originated,
         status,
+        finality,
         fee,
         nonce,
         block_number,
@@ -0,0 +1,104 @@
+use zksync_types::{protocol_version::ProtocolVersion, MiniblockNumber};
+
+use crate::models::storage_protocol_version::StorageProtocolVersion;
+use crate::StorageProcessor;
+
+/// Protocol versions the state keeper has sealed miniblocks with, along with their gas limits.
+#[derive(Debug)]
+pub struct ProtocolVersionsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ProtocolVersionsDal<'_, '_> {
+    /// Records the protocol version unless it's already known. Returns the version as it's stored,
+    /// which may differ from `version` if the version was recorded earlier.
+    pub fn register_protocol_version(&mut self, version: ProtocolVersion) -> ProtocolVersion {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO protocol_versions
+                    (id, activated_at_miniblock, block_gas_limit, max_allowed_tx_gas_limit, created_at)
+                VALUES ($1, $2, $3, $4, now())
+                ON CONFLICT (id) DO NOTHING
+                ",
+                version.id as i32,
+                version.activated_at_miniblock.0 as i64,
+                version.block_gas_limit as i64,
+                version.max_allowed_tx_gas_limit as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        });
+        self.get_protocol_version(version.id)
+            .expect("protocol version must be present after it's inserted")
+    }
+
+    pub fn get_protocol_version(&mut self, id: u16) -> Option<ProtocolVersion> {
+        async_std::task::block_on(async {
+            sqlx::query_as!(
+                StorageProtocolVersion,
+                "
+                SELECT id, activated_at_miniblock, block_gas_limit, max_allowed_tx_gas_limit
+                FROM protocol_versions
+                WHERE id = $1
+                ",
+                id as i32
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(Into::into)
+        })
+    }
+
+    pub fn get_latest_protocol_version(&mut self) -> Option<ProtocolVersion> {
+        async_std::task::block_on(async {
+            sqlx::query_as!(
+                StorageProtocolVersion,
+                "
+                SELECT id, activated_at_miniblock, block_gas_limit, max_allowed_tx_gas_limit
+                FROM protocol_versions
+                ORDER BY id DESC
+                LIMIT 1
+                "
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(Into::into)
+        })
+    }
+
+    /// Returns the protocol versions the miniblocks in the `from_miniblock..=to_miniblock` range were sealed with,
+    /// ordered by the activation miniblock. Miniblocks sealed before the first recorded version are not covered.
+    pub fn get_protocol_versions_for_miniblocks(
+        &mut self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> Vec<ProtocolVersion> {
+        async_std::task::block_on(async {
+            sqlx::query_as!(
+                StorageProtocolVersion,
+                "
+                SELECT id, activated_at_miniblock, block_gas_limit, max_allowed_tx_gas_limit
+                FROM protocol_versions
+                WHERE activated_at_miniblock <= $2
+                    AND activated_at_miniblock >= COALESCE(
+                        (SELECT MAX(activated_at_miniblock) FROM protocol_versions WHERE activated_at_miniblock <= $1),
+                        0
+                    )
+                ORDER BY activated_at_miniblock
+                ",
+                from_miniblock.0 as i64,
+                to_miniblock.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect()
+        })
+    }
+}
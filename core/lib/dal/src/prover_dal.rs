@@ -5,14 +5,16 @@ use std::time::{Duration, Instant};
 use zksync_object_store::gcs_utils::prover_circuit_input_blob_url;
 use zksync_types::aggregated_operations::BlockProofForL1;
 use zksync_types::proofs::{
-    AggregationRound, JobCountStatistics, JobExtendedStatistics, ProverJobInfo, ProverJobMetadata,
+    AggregationRound, ExternalProverJob, JobCountStatistics, JobExtendedStatistics, ProverJobInfo,
+    ProverJobMetadata,
 };
 use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::ZkSyncProof;
 use zksync_types::zkevm_test_harness::bellman::bn256::Bn256;
 use zksync_types::L1BatchNumber;
 
+use crate::job_queue_dal::{JobStatus, ProverJobQueue};
 use crate::models::storage_prover_job_info::StorageProverJobInfo;
-use crate::time_utils::{duration_to_naive_time, pg_interval_from_duration};
+use crate::time_utils::duration_to_naive_time;
 use crate::StorageProcessor;
 
 #[derive(Debug)]
@@ -23,45 +25,11 @@ pub struct ProverDal<'a, 'c> {
 impl ProverDal<'_, '_> {
     pub fn get_next_prover_job(
         &mut self,
-        _processing_timeout: Duration,
+        processing_timeout: Duration,
         max_attempts: u32,
+        picked_by: &str,
     ) -> Option<ProverJobMetadata> {
-        async_std::task::block_on(async {
-            let processing_timeout = pg_interval_from_duration(_processing_timeout);
-            let result: Option<ProverJobMetadata> = sqlx::query!(
-                "
-                UPDATE prover_jobs
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE id = (
-                    SELECT id
-                    FROM prover_jobs
-                    WHERE status = 'queued' 
-                    OR (status = 'in_progress' AND  processing_started_at < now() - $1::interval)
-                    OR (status = 'in_gpu_proof' AND  processing_started_at < now() - $1::interval)
-                    OR (status = 'failed' AND attempts < $2)
-                    ORDER BY aggregation_round DESC, l1_batch_number ASC, id ASC
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING prover_jobs.*
-                ",
-                &processing_timeout,
-                max_attempts as i32
-            )
-            .fetch_optional(self.storage.conn())
-            .await
-            .unwrap()
-            .map(|row| ProverJobMetadata {
-                id: row.id as u32,
-                block_number: L1BatchNumber(row.l1_batch_number as u32),
-                circuit_type: row.circuit_type.clone(),
-                aggregation_round: AggregationRound::try_from(row.aggregation_round).unwrap(),
-                sequence_number: row.sequence_number as usize,
-            });
-            result
-        })
+        self.lease_next_prover_job(processing_timeout, max_attempts, None, picked_by)
     }
 
     pub fn get_proven_l1_batches(&mut self) -> Vec<(L1BatchNumber, AggregationRound)> {
@@ -91,46 +59,35 @@ impl ProverDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
         circuit_types: Vec<String>,
+        picked_by: &str,
     ) -> Option<ProverJobMetadata> {
-        async_std::task::block_on(async {
-            let processing_timeout = pg_interval_from_duration(processing_timeout);
-            let result: Option<ProverJobMetadata> = sqlx::query!(
-                "
-                UPDATE prover_jobs
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE id = (
-                    SELECT id
-                    FROM prover_jobs
-                    WHERE circuit_type = ANY($3)
-                    AND
-                    (   status = 'queued'
-                        OR (status = 'in_progress' AND  processing_started_at < now() - $1::interval)
-                        OR (status = 'in_gpu_proof' AND  processing_started_at < now() - $1::interval)
-                        OR (status = 'failed' AND attempts < $2)
-                    )
-                    ORDER BY aggregation_round DESC, l1_batch_number ASC, id ASC
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING prover_jobs.*
-                ",
-                &processing_timeout,
-                max_attempts as i32,
-                &circuit_types[..],
-            )
-                .fetch_optional(self.storage.conn())
-                .await
-                .unwrap()
-                .map(|row| ProverJobMetadata {
-                    id: row.id as u32,
-                    block_number: L1BatchNumber(row.l1_batch_number as u32),
-                    circuit_type: row.circuit_type,
-                    aggregation_round: AggregationRound::try_from(row.aggregation_round).unwrap(),
-                    sequence_number: row.sequence_number as usize,
-                });
+        self.lease_next_prover_job(
+            processing_timeout,
+            max_attempts,
+            Some(&circuit_types[..]),
+            picked_by,
+        )
+    }
 
+    fn lease_next_prover_job(
+        &mut self,
+        processing_timeout: Duration,
+        max_attempts: u32,
+        circuit_types: Option<&[String]>,
+        picked_by: &str,
+    ) -> Option<ProverJobMetadata> {
+        async_std::task::block_on(async {
+            let mut transaction = self.storage.start_transaction().await;
+            let mut queue = transaction.job_queue_dal(ProverJobQueue);
+            queue.requeue_expired(processing_timeout);
+            let result = match queue.lock_next_job(max_attempts, circuit_types) {
+                Some(id) => {
+                    queue.lease(id, picked_by);
+                    transaction.prover_dal().get_prover_job_by_id(id)
+                }
+                None => None,
+            };
+            transaction.commit().await;
             result
         })
     }
@@ -213,30 +170,27 @@ impl ProverDal<'_, '_> {
         async_std::task::block_on(async {
             let mut transaction = self.storage.start_transaction().await;
 
-            let row = sqlx::query!(
-                "
-                UPDATE prover_jobs
-                SET status = 'failed', error = $1, updated_at = now()
-                WHERE id = $2
-                RETURNING l1_batch_number, attempts
-                ",
-                error,
-                id as i64,
-            )
-            .fetch_one(transaction.conn())
-            .await
-            .unwrap();
-
-            if row.attempts as u32 >= max_attempts {
+            let job = transaction
+                .job_queue_dal(ProverJobQueue)
+                .mark_failed(id, &error, None);
+            if job.attempts >= max_attempts {
                 transaction
                     .blocks_dal()
-                    .set_skip_proof_for_l1_batch(L1BatchNumber(row.l1_batch_number as u32));
+                    .set_skip_proof_for_l1_batch(job.l1_batch_number);
             }
 
             transaction.commit().await;
         })
     }
 
+    /// Marks the job as `artifact_corrupted`: its circuit input failed the integrity check,
+    /// so the job isn't retried.
+    pub fn mark_prover_job_as_artifact_corrupted(&mut self, id: u32, error: String) {
+        self.storage
+            .job_queue_dal(ProverJobQueue)
+            .mark_artifact_corrupted(id, &error);
+    }
+
     // For each block in the provided range it returns a tuple:
     // (aggregation_coords; scheduler_proof)
     pub fn get_final_proofs_for_blocks(
@@ -314,27 +268,7 @@ impl ProverDal<'_, '_> {
     }
 
     pub fn get_prover_jobs_stats(&mut self) -> JobCountStatistics {
-        async_std::task::block_on(async {
-            let mut results: HashMap<String, usize> = sqlx::query!(
-                r#"
-                SELECT COUNT(*) as "count!", status as "status!"
-                FROM prover_jobs
-                GROUP BY status
-                "#
-            )
-            .fetch_all(self.storage.conn())
-            .await
-            .unwrap()
-            .into_iter()
-            .map(|row| (row.status, row.count as usize))
-            .collect::<HashMap<String, usize>>();
-            JobCountStatistics {
-                queued: results.remove("queued").unwrap_or(0usize),
-                in_progress: results.remove("in_progress").unwrap_or(0usize),
-                failed: results.remove("failed").unwrap_or(0usize),
-                successful: results.remove("successful").unwrap_or(0usize),
-            }
-        })
+        self.storage.job_queue_dal(ProverJobQueue).stats()
     }
 
     pub fn successful_proofs_count(
@@ -523,6 +457,65 @@ impl ProverDal<'_, '_> {
         })
     }
 
+    /// Returns all the prover jobs of the L1 batch, ordered by the aggregation round.
+    pub fn get_external_prover_jobs_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Vec<ExternalProverJob> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT id, l1_batch_number, circuit_type, aggregation_round, sequence_number, status,
+                    CASE WHEN is_blob_cleaned THEN NULL ELSE circuit_input_blob_url END AS circuit_input_blob_url
+                FROM prover_jobs
+                WHERE l1_batch_number = $1
+                ORDER BY aggregation_round, sequence_number
+                ",
+                l1_batch_number.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| ExternalProverJob {
+                id: row.id as u32,
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                circuit_type: row.circuit_type,
+                aggregation_round: AggregationRound::try_from(row.aggregation_round).unwrap(),
+                sequence_number: row.sequence_number as usize,
+                status: row.status,
+                circuit_input_blob_url: row.circuit_input_blob_url,
+            })
+            .collect()
+        })
+    }
+
+    pub fn get_external_prover_job(&mut self, job_id: u32) -> Option<ExternalProverJob> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT id, l1_batch_number, circuit_type, aggregation_round, sequence_number, status,
+                    CASE WHEN is_blob_cleaned THEN NULL ELSE circuit_input_blob_url END AS circuit_input_blob_url
+                FROM prover_jobs
+                WHERE id = $1
+                ",
+                job_id as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| ExternalProverJob {
+                id: row.id as u32,
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                circuit_type: row.circuit_type,
+                aggregation_round: AggregationRound::try_from(row.aggregation_round).unwrap(),
+                sequence_number: row.sequence_number as usize,
+                status: row.status,
+                circuit_input_blob_url: row.circuit_input_blob_url,
+            })
+        })
+    }
+
     pub fn get_circuit_input_blob_urls_to_be_cleaned(&mut self, limit: u8) -> Vec<(i64, String)> {
         async_std::task::block_on(async {
             let job_ids = sqlx::query!(
@@ -561,21 +554,10 @@ impl ProverDal<'_, '_> {
         })
     }
 
-    pub fn update_status(&mut self, id: u32, status: &str) {
-        async_std::task::block_on(async {
-            sqlx::query!(
-                r#"
-                UPDATE prover_jobs
-                SET status = $1, updated_at = now()
-                WHERE id = $2
-                "#,
-                status,
-                id as i64,
-            )
-            .execute(self.storage.conn())
-            .await
-            .unwrap();
-        })
+    pub fn update_status(&mut self, id: u32, status: JobStatus) {
+        self.storage
+            .job_queue_dal(ProverJobQueue)
+            .set_status(id, status);
     }
 }
 
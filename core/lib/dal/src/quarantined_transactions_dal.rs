@@ -0,0 +1,112 @@
+use sqlx::types::chrono::NaiveDateTime;
+
+use zksync_types::H256;
+
+use crate::StorageProcessor;
+
+/// Transaction excluded from the mempool after repeatedly crashing the state keeper.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuarantinedTransaction {
+    pub hash: H256,
+    pub crash_count: u32,
+    pub last_crashed_at: NaiveDateTime,
+    pub quarantined_at: NaiveDateTime,
+}
+
+/// Crashes of the state keeper attributed to the transactions, and the transactions quarantined because of them.
+#[derive(Debug)]
+pub struct QuarantinedTransactionsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl QuarantinedTransactionsDal<'_, '_> {
+    /// Records a state keeper crash during the execution of the transaction.
+    /// Returns the number of crashes recorded for the transaction, including this one.
+    pub fn record_crash(&mut self, hash: &H256) -> u32 {
+        async_std::task::block_on(async {
+            let row = sqlx::query!(
+                "
+                INSERT INTO quarantined_transactions (hash, crash_count, last_crashed_at, created_at)
+                VALUES ($1, 1, now(), now())
+                ON CONFLICT (hash) DO UPDATE
+                SET crash_count = quarantined_transactions.crash_count + 1, last_crashed_at = now()
+                RETURNING crash_count
+                ",
+                hash.as_bytes()
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap();
+            row.crash_count as u32
+        })
+    }
+
+    /// Forgets the crashes recorded for the transaction, unless it's already quarantined.
+    /// Called once the transaction is executed without crashing the state keeper.
+    pub fn clear_crashes(&mut self, hash: &H256) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "DELETE FROM quarantined_transactions WHERE hash = $1 AND quarantined_at IS NULL",
+                hash.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Quarantines the transaction, so that it's no longer loaded into the mempool.
+    pub fn quarantine(&mut self, hash: &H256) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                UPDATE quarantined_transactions SET quarantined_at = now()
+                WHERE hash = $1 AND quarantined_at IS NULL
+                ",
+                hash.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    pub fn get_quarantined_transactions(&mut self) -> Vec<QuarantinedTransaction> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"
+                SELECT hash, crash_count, last_crashed_at, quarantined_at as "quarantined_at!"
+                FROM quarantined_transactions
+                WHERE quarantined_at IS NOT NULL
+                ORDER BY quarantined_at
+                "#
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| QuarantinedTransaction {
+                hash: H256::from_slice(&row.hash),
+                crash_count: row.crash_count as u32,
+                last_crashed_at: row.last_crashed_at,
+                quarantined_at: row.quarantined_at,
+            })
+            .collect()
+        })
+    }
+
+    /// Releases the transaction after the manual review, returning it to the mempool.
+    /// Returns `false` if the transaction wasn't quarantined.
+    pub fn release(&mut self, hash: &H256) -> bool {
+        async_std::task::block_on(async {
+            let result = sqlx::query!(
+                "DELETE FROM quarantined_transactions WHERE hash = $1 AND quarantined_at IS NOT NULL",
+                hash.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+            result.rows_affected() > 0
+        })
+    }
+}
@@ -0,0 +1,139 @@
+use sqlx::types::chrono::{DateTime, Utc};
+
+use zksync_types::L1BatchNumber;
+
+use crate::StorageProcessor;
+
+/// Backup of a RocksDB instance uploaded to the object store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RocksdbBackup {
+    pub id: i64,
+    /// Name of the backed up instance, e.g. `tree` or `state_keeper_cache`.
+    pub target: String,
+    /// Number of the next L1 batch to be processed by the instance at the moment of the backup.
+    pub l1_batch_number: L1BatchNumber,
+    /// Keys of the backup files in the object store.
+    pub object_keys: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct RocksdbBackupsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl RocksdbBackupsDal<'_, '_> {
+    pub fn insert_backup(
+        &mut self,
+        target: &str,
+        l1_batch_number: L1BatchNumber,
+        object_keys: &[String],
+    ) -> i64 {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO rocksdb_backups (target, l1_batch_number, object_keys, created_at)
+                VALUES ($1, $2, $3, now())
+                RETURNING id
+                ",
+                target,
+                l1_batch_number.0 as i64,
+                object_keys
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .id
+        })
+    }
+
+    /// Returns the latest backup of the instance taken at the L1 batch not exceeding `max_l1_batch_number`.
+    pub fn get_latest_backup(
+        &mut self,
+        target: &str,
+        max_l1_batch_number: L1BatchNumber,
+    ) -> Option<RocksdbBackup> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT * FROM rocksdb_backups
+                WHERE target = $1 AND l1_batch_number <= $2
+                ORDER BY l1_batch_number DESC, id DESC
+                LIMIT 1
+                ",
+                target,
+                max_l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| RocksdbBackup {
+                id: row.id,
+                target: row.target,
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                object_keys: row.object_keys,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+        })
+    }
+
+    /// Returns the backups of the instance except for the `keep_count` most recent ones.
+    pub fn get_outdated_backups(&mut self, target: &str, keep_count: usize) -> Vec<RocksdbBackup> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT * FROM rocksdb_backups
+                WHERE target = $1
+                ORDER BY id DESC
+                OFFSET $2
+                ",
+                target,
+                keep_count as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| RocksdbBackup {
+                id: row.id,
+                target: row.target,
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                object_keys: row.object_keys,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+            .collect()
+        })
+    }
+
+    /// Returns the backups of all instances taken after `l1_batch_number` was processed, i.e. the ones
+    /// which would get ahead of Postgres if the L1 batches after it were reverted.
+    pub fn get_backups_after(&mut self, l1_batch_number: L1BatchNumber) -> Vec<RocksdbBackup> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "SELECT * FROM rocksdb_backups WHERE l1_batch_number > $1 ORDER BY id",
+                l1_batch_number.0 as i64 + 1
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| RocksdbBackup {
+                id: row.id,
+                target: row.target,
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                object_keys: row.object_keys,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+            .collect()
+        })
+    }
+
+    pub fn delete_backup(&mut self, id: i64) {
+        async_std::task::block_on(async {
+            sqlx::query!("DELETE FROM rocksdb_backups WHERE id = $1", id)
+                .execute(self.storage.conn())
+                .await
+                .unwrap();
+        })
+    }
+}
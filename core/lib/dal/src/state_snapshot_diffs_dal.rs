@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use sqlx::types::chrono::{DateTime, Utc};
+
+use zksync_types::{L1BatchNumber, MiniblockNumber, H256};
+
+use crate::time_utils::pg_interval_from_duration;
+use crate::StorageProcessor;
+
+/// Incremental state snapshot uploaded to the object store. It contains the state changes made by the L1 batches
+/// `base_l1_batch_number..l1_batch_number`, where `base_l1_batch_number` is either the end of the previous diff,
+/// or the watermark of the full backup of the state keeper cache the chain of diffs starts from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSnapshotDiff {
+    pub id: i64,
+    pub base_l1_batch_number: L1BatchNumber,
+    /// Number of the next L1 batch to be processed after the diff is applied.
+    pub l1_batch_number: L1BatchNumber,
+    /// First miniblock of `l1_batch_number`.
+    pub first_miniblock_number: MiniblockNumber,
+    /// Root hash of the last L1 batch of the diff. Absent for the diffs created before it started to be recorded.
+    pub root_hash: Option<H256>,
+    /// Key of the diff file in the object store.
+    pub object_key: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct StateSnapshotDiffsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl StateSnapshotDiffsDal<'_, '_> {
+    pub fn insert_diff(
+        &mut self,
+        base_l1_batch_number: L1BatchNumber,
+        l1_batch_number: L1BatchNumber,
+        first_miniblock_number: MiniblockNumber,
+        root_hash: H256,
+        object_key: &str,
+    ) -> i64 {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                INSERT INTO state_snapshot_diffs
+                    (base_l1_batch_number, l1_batch_number, first_miniblock_number, root_hash, object_key, created_at)
+                VALUES ($1, $2, $3, $4, $5, now())
+                RETURNING id
+                ",
+                base_l1_batch_number.0 as i64,
+                l1_batch_number.0 as i64,
+                first_miniblock_number.0 as i64,
+                root_hash.as_bytes(),
+                object_key
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .id
+        })
+    }
+
+    /// Returns the most recent diff, regardless of its base.
+    pub fn get_latest_diff(&mut self) -> Option<StateSnapshotDiff> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT * FROM state_snapshot_diffs
+                ORDER BY l1_batch_number DESC, id DESC
+                LIMIT 1
+                "
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| StateSnapshotDiff {
+                id: row.id,
+                base_l1_batch_number: L1BatchNumber(row.base_l1_batch_number as u32),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                first_miniblock_number: MiniblockNumber(row.first_miniblock_number as u32),
+                root_hash: row.root_hash.map(|hash| H256::from_slice(&hash)),
+                object_key: row.object_key,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+        })
+    }
+
+    /// Returns the diff moving the state the furthest from `l1_batch_number` (the next L1 batch to be processed
+    /// by the node), not going past `max_l1_batch_number`. A diff contains the final values of the slots changed
+    /// by all its L1 batches, so it can be applied to any state between its base and its end. Diffs without
+    /// the root hash can't be verified by the node and are never returned.
+    pub fn get_diff_to_apply(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        max_l1_batch_number: L1BatchNumber,
+    ) -> Option<StateSnapshotDiff> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT * FROM state_snapshot_diffs
+                WHERE base_l1_batch_number <= $1 AND l1_batch_number > $1 AND l1_batch_number <= $2
+                    AND root_hash IS NOT NULL
+                ORDER BY l1_batch_number DESC, id DESC
+                LIMIT 1
+                ",
+                l1_batch_number.0 as i64,
+                max_l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()
+            .map(|row| StateSnapshotDiff {
+                id: row.id,
+                base_l1_batch_number: L1BatchNumber(row.base_l1_batch_number as u32),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                first_miniblock_number: MiniblockNumber(row.first_miniblock_number as u32),
+                root_hash: row.root_hash.map(|hash| H256::from_slice(&hash)),
+                object_key: row.object_key,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+        })
+    }
+
+    /// Returns the diffs created more than `retention` ago, except for the latest diff, which the next diff
+    /// is chained on. Diffs are created in the order of their L1 batches, so the remaining diffs
+    /// still form a contiguous chain.
+    pub fn get_outdated_diffs(&mut self, retention: Duration) -> Vec<StateSnapshotDiff> {
+        async_std::task::block_on(async {
+            let retention = pg_interval_from_duration(retention);
+            sqlx::query!(
+                "
+                SELECT * FROM state_snapshot_diffs
+                WHERE created_at < now() - $1::interval
+                    AND l1_batch_number < (SELECT MAX(l1_batch_number) FROM state_snapshot_diffs)
+                ORDER BY id
+                ",
+                &retention
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| StateSnapshotDiff {
+                id: row.id,
+                base_l1_batch_number: L1BatchNumber(row.base_l1_batch_number as u32),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                first_miniblock_number: MiniblockNumber(row.first_miniblock_number as u32),
+                root_hash: row.root_hash.map(|hash| H256::from_slice(&hash)),
+                object_key: row.object_key,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+            .collect()
+        })
+    }
+
+    /// Returns the diffs covering L1 batches after `last_l1_batch_to_keep`, which become invalid once
+    /// the L1 batches are reverted.
+    pub fn get_diffs_after(
+        &mut self,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> Vec<StateSnapshotDiff> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT * FROM state_snapshot_diffs
+                WHERE l1_batch_number > $1
+                ORDER BY id
+                ",
+                last_l1_batch_to_keep.0 as i64 + 1
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| StateSnapshotDiff {
+                id: row.id,
+                base_l1_batch_number: L1BatchNumber(row.base_l1_batch_number as u32),
+                l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                first_miniblock_number: MiniblockNumber(row.first_miniblock_number as u32),
+                root_hash: row.root_hash.map(|hash| H256::from_slice(&hash)),
+                object_key: row.object_key,
+                created_at: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+            .collect()
+        })
+    }
+
+    pub fn delete_diff(&mut self, id: i64) {
+        async_std::task::block_on(async {
+            sqlx::query!("DELETE FROM state_snapshot_diffs WHERE id = $1", id)
+                .execute(self.storage.conn())
+                .await
+                .unwrap();
+        })
+    }
+}
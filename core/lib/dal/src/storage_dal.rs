@@ -1,6 +1,7 @@
 use crate::models::storage_contract::StorageContractSource;
 use crate::StorageProcessor;
 use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
 use std::time::Instant;
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
 use zksync_types::{
@@ -152,6 +153,63 @@ impl StorageDal<'_, '_> {
         })
     }
 
+    /// Returns the factory deps published in the miniblocks.
+    pub fn get_factory_deps_for_miniblocks(
+        &mut self,
+        miniblocks: RangeInclusive<MiniblockNumber>,
+    ) -> Vec<(H256, Vec<u8>)> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "SELECT bytecode_hash, bytecode FROM factory_deps
+                WHERE miniblock_number BETWEEN $1 AND $2",
+                miniblocks.start().0 as i64,
+                miniblocks.end().0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
+            .collect()
+        })
+    }
+
+    /// Returns the contracts successfully deployed in the miniblocks together with their bytecodes.
+    pub fn get_deployed_contracts_for_miniblocks(
+        &mut self,
+        miniblocks: RangeInclusive<MiniblockNumber>,
+    ) -> Vec<(Address, Vec<u8>)> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT storage_logs.key, factory_deps.bytecode
+                FROM storage_logs
+                JOIN factory_deps ON storage_logs.value = factory_deps.bytecode_hash
+                WHERE
+                    storage_logs.address = $1 AND
+                    storage_logs.miniblock_number BETWEEN $3 AND $4 AND
+                    NOT EXISTS (
+                        SELECT 1 FROM storage_logs as s
+                        WHERE
+                            s.hashed_key = storage_logs.hashed_key AND
+                            (s.miniblock_number, s.operation_number) >= (storage_logs.miniblock_number, storage_logs.operation_number) AND
+                            s.value = $2
+                    )
+                ",
+                ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+                FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
+                miniblocks.start().0 as i64,
+                miniblocks.end().0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| (h256_to_account_address(&H256::from_slice(&row.key)), row.bytecode))
+            .collect()
+        })
+    }
+
     pub fn set_contract_source(&mut self, address: Address, source: ContractSourceDebugInfo) {
         async_std::task::block_on(async {
             sqlx::query!(
@@ -1,10 +1,13 @@
 use crate::{SqlxError, StorageProcessor};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::time::Instant;
 use zksync_types::{
-    api::BlockId,
+    api::{BlockId, StorageSlotDiff},
     get_code_key, get_nonce_key,
     utils::{decompose_full_nonce, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, StorageKey, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
+    AccountTreeId, Address, MiniblockNumber, StorageKey, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
+    H256, U256,
 };
 use zksync_utils::h256_to_u256;
 use zksync_web3_decl::error::Web3Error;
@@ -62,11 +65,22 @@ impl StorageWeb3Dal<'_, '_> {
         key: &StorageKey,
         block_number: zksync_types::MiniblockNumber,
     ) -> Result<H256, SqlxError> {
+        // We need to proper distinguish if the value is zero or None
+        // for the VM to correctly determine initial writes.
+        // So, we accept that the value is None if it's zero and it wasn't initially written at the moment.
+        self.get_historical_value_opt(key, block_number)
+            .map(|value| value.unwrap_or_else(H256::zero))
+    }
+
+    /// Same as `get_historical_value_unchecked`, but returns `None` if the slot
+    /// wasn't written up to the block (inclusive).
+    pub fn get_historical_value_opt(
+        &mut self,
+        key: &StorageKey,
+        block_number: zksync_types::MiniblockNumber,
+    ) -> Result<Option<H256>, SqlxError> {
         async_std::task::block_on(async {
             let started_at = Instant::now();
-            // We need to proper distinguish if the value is zero or None
-            // for the VM to correctly determine initial writes.
-            // So, we accept that the value is None if it's zero and it wasn't initially written at the moment.
             let result = sqlx::query!(
                 r#"
                 SELECT value
@@ -80,17 +94,69 @@ impl StorageWeb3Dal<'_, '_> {
             )
             .fetch_optional(self.storage.conn())
             .await
-            .map(|option_row| {
-                option_row
-                    .map(|row| H256::from_slice(&row.value))
-                    .unwrap_or_else(H256::zero)
-            });
+            .map(|option_row| option_row.map(|row| H256::from_slice(&row.value)));
             metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_historical_value_unchecked");
 
             result
         })
     }
 
+    /// Batched version of `get_historical_value`: returns the values of the slots in the order of `keys`.
+    pub fn get_historical_values(
+        &mut self,
+        keys: &[StorageKey],
+        block_id: BlockId,
+    ) -> Result<Result<Vec<H256>, Web3Error>, SqlxError> {
+        let block_number = self.storage.blocks_web3_dal().resolve_block_id(block_id)?;
+        match block_number {
+            Ok(block_number) => {
+                let values = self.get_historical_values_unchecked(keys, block_number)?;
+                Ok(Ok(values))
+            }
+            Err(err) => Ok(Err(err)),
+        }
+    }
+
+    /// Batched version of `get_historical_value_unchecked` loading all the slots in a single query.
+    /// The values are returned in the order of `keys`; the slots that weren't written are zero.
+    pub fn get_historical_values_unchecked(
+        &mut self,
+        keys: &[StorageKey],
+        block_number: zksync_types::MiniblockNumber,
+    ) -> Result<Vec<H256>, SqlxError> {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let hashed_keys: Vec<_> = keys.iter().map(|key| key.hashed_key().0.to_vec()).collect();
+            let rows = sqlx::query!(
+                r#"
+                SELECT DISTINCT ON (hashed_key) hashed_key, value
+                FROM storage_logs
+                WHERE storage_logs.hashed_key = ANY($1) AND storage_logs.miniblock_number <= $2
+                ORDER BY hashed_key, storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
+                "#,
+                &hashed_keys,
+                block_number.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?;
+            let values: HashMap<_, _> = rows
+                .into_iter()
+                .map(|row| {
+                    (
+                        H256::from_slice(&row.hashed_key),
+                        H256::from_slice(&row.value),
+                    )
+                })
+                .collect();
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_historical_values_unchecked");
+
+            Ok(keys
+                .iter()
+                .map(|key| values.get(&key.hashed_key()).copied().unwrap_or_default())
+                .collect())
+        })
+    }
+
     pub fn is_write_initial(
         &mut self,
         key: &StorageKey,
@@ -131,6 +197,65 @@ impl StorageWeb3Dal<'_, '_> {
         })
     }
 
+    /// Returns the slots changed in the miniblocks, ordered by the hashed key and starting after the `after` key.
+    /// Slots overwritten with the same value are skipped.
+    pub fn get_state_diff(
+        &mut self,
+        miniblocks: RangeInclusive<MiniblockNumber>,
+        after: Option<H256>,
+        limit: usize,
+    ) -> Result<Vec<StorageSlotDiff>, SqlxError> {
+        async_std::task::block_on(async {
+            let started_at = Instant::now();
+            let after = after.map_or_else(Vec::new, |hashed_key| hashed_key.as_bytes().to_vec());
+            let rows = sqlx::query!(
+                r#"
+                SELECT new_logs.hashed_key, new_logs.address, new_logs.key, new_logs.value,
+                    prev_logs.value as "old_value?"
+                FROM (
+                    SELECT DISTINCT ON (hashed_key) hashed_key, address, key, value
+                    FROM storage_logs
+                    WHERE miniblock_number BETWEEN $1 AND $2 AND hashed_key > $3
+                    ORDER BY hashed_key, miniblock_number DESC, operation_number DESC
+                ) new_logs
+                LEFT JOIN LATERAL (
+                    SELECT value FROM storage_logs
+                    WHERE storage_logs.hashed_key = new_logs.hashed_key AND storage_logs.miniblock_number < $1
+                    ORDER BY storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
+                    LIMIT 1
+                ) prev_logs ON TRUE
+                WHERE new_logs.value != COALESCE(prev_logs.value, $4)
+                ORDER BY new_logs.hashed_key
+                LIMIT $5
+                "#,
+                miniblocks.start().0 as i64,
+                miniblocks.end().0 as i64,
+                &after,
+                H256::zero().as_bytes(),
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?;
+            metrics::histogram!("dal.request", started_at.elapsed(), "method" => "get_state_diff");
+
+            let diffs = rows
+                .into_iter()
+                .map(|row| StorageSlotDiff {
+                    address: Address::from_slice(&row.address),
+                    key: H256::from_slice(&row.key),
+                    hashed_key: H256::from_slice(&row.hashed_key),
+                    old_value: row
+                        .old_value
+                        .as_deref()
+                        .map_or_else(H256::zero, H256::from_slice),
+                    new_value: H256::from_slice(&row.value),
+                    initial_write: row.old_value.is_none(),
+                })
+                .collect();
+            Ok(diffs)
+        })
+    }
+
     pub fn get_contract_code(
         &mut self,
         address: Address,
@@ -1,24 +1,80 @@
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use db_test_macro::db_test;
+use sqlx::Row;
+use zksync_config::constants::ERC20_TRANSFER_TOPIC;
 use zksync_types::block::{L1BatchHeader, MiniblockHeader};
-use zksync_types::proofs::AggregationRound;
+use zksync_types::encrypted_tx::DecryptionShare;
+use zksync_types::proofs::{AggregationRound, WitnessMemoryBudget};
+use zksync_types::tx::execution_digest::{
+    execution_outcomes_digest, EXECUTION_OUTCOMES_SERIALIZATION_VERSION,
+};
 use zksync_types::MAX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
+    api::{self, TxLifecycleEvent, TxLifecycleEventKind},
+    bloom::logs_bloom,
+    event::TransferEventsFixture,
+    explorer_api::{
+        CompilationArtifacts, SourceCodeData, VerificationIncomingRequest, VerificationInfo,
+        VerificationRequest,
+    },
     fee::{Fee, TransactionExecutionMetrics},
     helpers::unix_timestamp_ms,
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     l2::L2Tx,
-    tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
-    Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, H160, H256, U256,
+    protocol_version::ProtocolVersion,
+    tokens::{TokenInfo, TokenMetadata},
+    tx::{
+        tx_execution_info::TxExecutionStatus, IncludedTxLocation, TransactionExecutionResult,
+        TxAccessSet,
+    },
+    AccountTreeId, Address, Execute, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber,
+    L1TxCommonData, L2ChainId, MiniblockNumber, PriorityOpId, StorageKey, StorageLog, VmEvent,
+    H160, H256, U256,
 };
+use zksync_utils::bytecode::hash_bytecode;
+use zksync_web3_decl::error::Web3Error;
 
+use crate::admin_actions_log_dal::{AdminActionRecord, AdminActionsLogDal};
+use crate::api_audit_log_dal::{ApiAuditLogDal, ApiAuditRecord};
+use crate::api_usage_dal::{ApiUsageDal, ApiUsageRecord};
 use crate::blocks_dal::BlocksDal;
+use crate::blocks_web3_dal::BlocksWeb3Dal;
+use crate::bootloader_usage_dal::BootloaderUsageDal;
+use crate::connection::is_statement_timeout;
+use crate::connection_pool_sizes_dal::ConnectionPoolSizesDal;
+use crate::db_maintenance_dal::DBMaintenanceDal;
+use crate::deployer_allowlist_dal::DeployerAllowlistDal;
+use crate::encrypted_transactions_dal::EncryptedTransactionsDal;
+use crate::events_dal::EventsDal;
+use crate::events_web3_dal::EventsWeb3Dal;
+use crate::expired_priority_ops_dal::ExpiredPriorityOpsDal;
+use crate::explorer::contract_verification_dal::ContractVerificationDal;
+use crate::indices_dal::IndicesDal;
+use crate::instance_heartbeats_dal::{
+    binary_schema_version, InstanceHeartbeatsDal, COMPATIBLE_SINCE_SCHEMA_VERSION,
+};
+use crate::job_queue_dal::{ContractVerificationQueue, JobStatus, ProverJobQueue};
+use crate::protocol_versions_dal::ProtocolVersionsDal;
 use crate::prover_dal::{GetProverJobsParams, ProverDal};
+use crate::quarantined_transactions_dal::QuarantinedTransactionsDal;
+use crate::rocksdb_backups_dal::RocksdbBackupsDal;
+use crate::state_snapshot_diffs_dal::StateSnapshotDiffsDal;
+use crate::storage_dal::StorageDal;
+use crate::storage_logs_dal::StorageLogsDal;
+use crate::storage_web3_dal::StorageWeb3Dal;
+use crate::tokens_dal::TokensDal;
+use crate::tokens_web3_dal::TokensWeb3Dal;
 use crate::transactions_dal::L2TxSubmissionResult;
 use crate::transactions_dal::TransactionsDal;
 use crate::transactions_web3_dal::TransactionsWeb3Dal;
+use crate::transfers_dal::TransfersDal;
+use crate::transfers_web3_dal::TransfersWeb3Dal;
+use crate::tx_access_sets_dal::TxAccessSetsDal;
+use crate::tx_lifecycle_dal::TxLifecycleDal;
+use crate::SqlxError;
+use crate::StorageProcessor;
 
 fn mock_tx_execution_metrics() -> TransactionExecutionMetrics {
     TransactionExecutionMetrics::default()
@@ -84,6 +140,24 @@ fn mock_l1_execute() -> L1Tx {
     }
 }
 
+/// Creates an empty miniblock header with zero fee input and the hash derived from the number.
+/// Tests override the fields they need with the struct update syntax.
+fn create_miniblock_header(number: u32) -> MiniblockHeader {
+    MiniblockHeader {
+        number: MiniblockNumber(number),
+        timestamp: 0,
+        hash: H256::from_low_u64_be(number.into()),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        base_fee_per_gas: 0,
+        l1_gas_price: 0,
+        l2_fair_gas_price: 0,
+        base_system_contracts_hashes: Default::default(),
+        logs_bloom: Default::default(),
+        fee_account_address: Address::default(),
+    }
+}
+
 #[db_test(dal_crate)]
 async fn workflow_with_submit_tx_equal_hashes(connection_pool: ConnectionPool) {
     let storage = &mut connection_pool.access_test_storage().await;
@@ -152,17 +226,7 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
     let txs = transactions_dal.sync_mempool(vec![], vec![], 0, 0, 1000).0;
     assert_eq!(txs.len(), 4);
 
-    blocks_dal.insert_miniblock(MiniblockHeader {
-        number: MiniblockNumber(1),
-        timestamp: 0,
-        hash: Default::default(),
-        l1_tx_count: 0,
-        l2_tx_count: 0,
-        base_fee_per_gas: Default::default(),
-        l1_gas_price: 0,
-        l2_fair_gas_price: 0,
-        base_system_contracts_hashes: Default::default(),
-    });
+    blocks_dal.insert_miniblock(create_miniblock_header(1));
     transactions_dal.mark_txs_as_executed_in_miniblock(
         MiniblockNumber(1),
         &[TransactionExecutionResult {
@@ -172,6 +236,7 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
             execution_status: TxExecutionStatus::Success,
             refunded_gas: 0,
             operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
             compressed_bytecodes: vec![],
         }],
         U256::from(1),
@@ -194,10 +259,42 @@ async fn remove_stuck_txs(connection_pool: ConnectionPool) {
     let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
     transactions_web3_dal
         .get_transaction_receipt(executed_tx.hash())
+        .await
         .unwrap()
         .unwrap();
 }
 
+#[db_test(dal_crate)]
+async fn remove_expired_txs(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let mut expired_tx = mock_l2_transaction();
+    expired_tx.common_data.valid_until = Some(100);
+    transactions_dal.insert_transaction_l2(expired_tx.clone(), mock_tx_execution_metrics());
+    let mut valid_tx = mock_l2_transaction();
+    valid_tx.common_data.valid_until = Some(200);
+    transactions_dal.insert_transaction_l2(valid_tx.clone(), mock_tx_execution_metrics());
+    // Tx without expiry
+    let tx = mock_l2_transaction();
+    transactions_dal.insert_transaction_l2(tx, mock_tx_execution_metrics());
+
+    let txs = transactions_dal.sync_mempool(vec![], vec![], 0, 0, 1000).0;
+    assert_eq!(txs.len(), 3);
+    let stored_valid_tx = txs.iter().find(|tx| tx.hash() == valid_tx.hash()).unwrap();
+    match &stored_valid_tx.common_data {
+        ExecuteTransactionCommon::L2(data) => assert_eq!(data.valid_until, Some(200)),
+        _ => panic!("unexpected L1 transaction"),
+    }
+
+    let removed_txs = transactions_dal.remove_expired_txs(150);
+    assert_eq!(removed_txs, [expired_tx.hash()]);
+    transactions_dal.reset_mempool();
+    let txs = transactions_dal.sync_mempool(vec![], vec![], 0, 0, 1000).0;
+    assert_eq!(txs.len(), 2);
+    assert!(transactions_dal.remove_expired_txs(200).is_empty());
+}
+
 #[db_test(dal_crate)]
 async fn test_duplicate_insert_prover_jobs(connection_pool: ConnectionPool) {
     let storage = &mut connection_pool.access_test_storage().await;
@@ -246,3 +343,2282 @@ async fn test_duplicate_insert_prover_jobs(connection_pool: ConnectionPool) {
     let jobs = prover_dal.get_jobs(prover_jobs_params).unwrap();
     assert_eq!(circuits.len(), jobs.len());
 }
+
+#[db_test(dal_crate)]
+async fn basic_circuit_witness_jobs_over_memory_budget(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    // Estimated circuits per L1 batch; `None` is a batch sealed before the estimation was introduced.
+    let estimations = [Some(100), None, Some(10)];
+    for (i, estimation) in estimations.iter().enumerate() {
+        let l1_batch_number = L1BatchNumber(i as u32 + 1);
+        let header = L1BatchHeader::new(l1_batch_number, 0, Default::default(), Default::default());
+        storage
+            .blocks_dal()
+            .insert_l1_batch(header, Default::default());
+        if let Some(estimation) = estimation {
+            storage
+                .blocks_dal()
+                .set_estimated_basic_circuits(l1_batch_number, *estimation);
+        }
+        storage
+            .witness_generator_dal()
+            .save_witness_inputs(l1_batch_number);
+    }
+
+    let budget = Some(WitnessMemoryBudget::new(8 << 30));
+    let next_job = |storage: &mut StorageProcessor<'_>| {
+        storage
+            .witness_generator_dal()
+            .get_next_basic_circuit_witness_job(Duration::from_secs(60), 3, 10, budget, "worker")
+            .map(|job| job.block_number)
+    };
+    assert_eq!(next_job(storage), Some(L1BatchNumber(2)));
+    assert_eq!(next_job(storage), Some(L1BatchNumber(3)));
+    assert_eq!(next_job(storage), None);
+
+    // Without a budget, the batch is picked regardless of its estimation.
+    let job = storage
+        .witness_generator_dal()
+        .get_next_basic_circuit_witness_job(Duration::from_secs(60), 3, 10, None, "worker")
+        .unwrap();
+    assert_eq!(job.block_number, L1BatchNumber(1));
+}
+
+#[db_test(dal_crate)]
+async fn prover_job_queue(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let l1_batch_number = L1BatchNumber(1);
+    let header = L1BatchHeader::new(l1_batch_number, 0, Default::default(), Default::default());
+    storage
+        .blocks_dal()
+        .insert_l1_batch(header, Default::default());
+    storage.prover_dal().insert_prover_jobs(
+        l1_batch_number,
+        vec!["Main VM".to_string()],
+        AggregationRound::BasicCircuits,
+    );
+
+    let processing_timeout = Duration::from_secs(60);
+    let max_attempts = 2;
+    let expire_leases = |storage: &mut StorageProcessor<'_>| {
+        async_std::task::block_on(
+            sqlx::query("UPDATE prover_jobs SET processing_started_at = now() - interval '1 hour'")
+                .execute(storage.conn()),
+        )
+        .unwrap();
+    };
+
+    let job = storage
+        .prover_dal()
+        .get_next_prover_job(processing_timeout, max_attempts, "worker-1")
+        .unwrap();
+    let picked_by = |storage: &mut StorageProcessor<'_>| {
+        async_std::task::block_on(
+            sqlx::query("SELECT picked_by FROM prover_jobs WHERE id = $1")
+                .bind(job.id as i64)
+                .fetch_one(storage.conn()),
+        )
+        .unwrap()
+        .get::<Option<String>, _>("picked_by")
+    };
+    assert_eq!(picked_by(storage).as_deref(), Some("worker-1"));
+    let mut queue = storage.job_queue_dal(ProverJobQueue);
+    assert_eq!(queue.count_by_status()[&JobStatus::InProgress], 1);
+    assert_eq!(queue.requeue_expired(processing_timeout), 0);
+    expire_leases(queue.storage);
+    assert_eq!(queue.requeue_expired(processing_timeout), 1);
+    assert_eq!(queue.stats().queued, 1);
+
+    // Expired leases don't count towards the attempts, so the job is requeued even once it's out of them.
+    storage
+        .prover_dal()
+        .get_next_prover_job(processing_timeout, max_attempts, "worker-2")
+        .unwrap();
+    assert_eq!(picked_by(storage).as_deref(), Some("worker-2"));
+    storage
+        .prover_dal()
+        .update_status(job.id, JobStatus::InGpuProof);
+    expire_leases(storage);
+    let mut queue = storage.job_queue_dal(ProverJobQueue);
+    assert_eq!(queue.requeue_expired(processing_timeout), 1);
+    assert_eq!(queue.stats().queued, 1);
+    storage
+        .prover_dal()
+        .get_next_prover_job(processing_timeout, max_attempts, "worker-1")
+        .unwrap();
+
+    // A job failed by the worker isn't retried once it's out of attempts.
+    let mut queue = storage.job_queue_dal(ProverJobQueue);
+    let failed_job = queue.mark_failed(job.id, "error", None);
+    assert_eq!(failed_job.l1_batch_number, l1_batch_number);
+    assert_eq!(failed_job.attempts, 3);
+    assert!(storage
+        .prover_dal()
+        .get_next_prover_job(processing_timeout, max_attempts, "worker-1")
+        .is_none());
+
+    let mut queue = storage.job_queue_dal(ProverJobQueue);
+    queue.mark_successful(job.id, Duration::from_secs(1));
+    let stats = queue.stats();
+    assert_eq!((stats.failed, stats.successful), (0, 1));
+}
+
+#[db_test(dal_crate)]
+async fn contract_verification_queue(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let request = VerificationIncomingRequest {
+        contract_address: Address::repeat_byte(0x11),
+        source_code_data: SourceCodeData::SingleFile("contract Test {}".to_owned()),
+        contract_name: "Test".to_owned(),
+        compiler_zksolc_version: "v1.3.5".to_owned(),
+        compiler_solc_version: "0.8.16".to_owned(),
+        optimization_used: true,
+        constructor_arguments: Default::default(),
+        is_system: false,
+    };
+    let id = storage
+        .explorer()
+        .contract_verification_dal()
+        .add_contract_verification_request(request)
+        .unwrap();
+
+    let processing_timeout = Duration::from_secs(60);
+    let next_request = |storage: &mut StorageProcessor<'_>| {
+        storage
+            .explorer()
+            .contract_verification_dal()
+            .get_next_queued_verification_request(processing_timeout, "worker")
+            .unwrap()
+            .map(|request| request.id)
+    };
+    assert_eq!(next_request(storage), Some(id));
+    assert_eq!(next_request(storage), None);
+
+    // An expired lease returns the request to the queue, regardless of the attempts made.
+    for _ in 0..3 {
+        async_std::task::block_on(
+            sqlx::query(
+                "UPDATE contract_verification_requests SET processing_started_at = now() - interval '1 hour'",
+            )
+            .execute(storage.conn()),
+        )
+        .unwrap();
+        assert_eq!(next_request(storage), Some(id));
+    }
+    let stats = storage.job_queue_dal(ContractVerificationQueue).stats();
+    assert_eq!((stats.queued, stats.in_progress), (0, 1));
+}
+
+#[db_test(dal_crate)]
+async fn tx_lifecycle_events(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut tx_lifecycle_dal = TxLifecycleDal { storage };
+
+    let tx_hash = H256::repeat_byte(1);
+    let events = vec![
+        TxLifecycleEvent::new(TxLifecycleEventKind::Received),
+        TxLifecycleEvent::new(TxLifecycleEventKind::Validated),
+        TxLifecycleEvent::new(TxLifecycleEventKind::InsertedToMempool),
+    ];
+    let events: Vec<_> = events.into_iter().map(|event| (tx_hash, event)).collect();
+    tx_lifecycle_dal.insert_tx_lifecycle_events(&events);
+    tx_lifecycle_dal.insert_tx_executed_events(MiniblockNumber(5), &[tx_hash]);
+    tx_lifecycle_dal.insert_tx_lifecycle_events(&[(
+        H256::repeat_byte(2),
+        TxLifecycleEvent::rejected("nonce too low".to_owned()),
+    )]);
+
+    let recorded_events = tx_lifecycle_dal.get_tx_lifecycle_events(tx_hash);
+    let kinds: Vec<_> = recorded_events.iter().map(|event| event.event).collect();
+    assert_eq!(
+        kinds,
+        [
+            TxLifecycleEventKind::Received,
+            TxLifecycleEventKind::Validated,
+            TxLifecycleEventKind::InsertedToMempool,
+            TxLifecycleEventKind::Executed,
+        ]
+    );
+    assert_eq!(recorded_events[0].miniblock_number, None);
+    assert_eq!(recorded_events[0].details, None);
+    assert_eq!(
+        recorded_events[3].miniblock_number,
+        Some(MiniblockNumber(5))
+    );
+
+    let rejected_events = tx_lifecycle_dal.get_tx_lifecycle_events(H256::repeat_byte(2));
+    assert_eq!(rejected_events.len(), 1);
+    assert_eq!(rejected_events[0].details.as_deref(), Some("nonce too low"));
+
+    assert_eq!(
+        tx_lifecycle_dal.delete_tx_lifecycle_events_older_than(Duration::from_secs(3600)),
+        0
+    );
+}
+
+#[db_test(dal_crate)]
+async fn table_stats(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut db_maintenance_dal = DBMaintenanceDal { storage };
+
+    let tables = ["transactions".to_owned(), "non_existing_table".to_owned()];
+    let stats = db_maintenance_dal.get_table_stats(&tables);
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].table_name, "transactions");
+    assert!(stats[0].dead_tuples_ratio() >= 0.0 && stats[0].dead_tuples_ratio() <= 1.0);
+}
+
+#[db_test(dal_crate)]
+async fn storage_logs_and_events_are_not_partitioned(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    // Point lookups by the hashed key and the tx hash can't be pruned to a single miniblock range partition.
+    for table in ["storage_logs", "events"] {
+        let row = sqlx::query("SELECT relkind::text AS kind FROM pg_class WHERE relname = $1")
+            .bind(table)
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("kind"), "r", "{}", table);
+    }
+}
+
+#[db_test(dal_crate)]
+async fn required_indices_are_present(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut indices_dal = IndicesDal { storage };
+
+    let missing_indices = indices_dal.get_missing_required_indices();
+    assert!(missing_indices.is_empty(), "{:?}", missing_indices);
+
+    let tables = ["events".to_owned()];
+    let index_columns = indices_dal.get_index_columns(&tables);
+    assert!(index_columns["events"].contains(&vec![
+        "miniblock_number".to_owned(),
+        "event_index_in_block".to_owned()
+    ]));
+}
+
+#[db_test(dal_crate)]
+async fn verified_abis(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut contract_verification_dal = ContractVerificationDal { storage };
+
+    let contract_address = Address::repeat_byte(0x11);
+    let abi = serde_json::json!([{ "type": "fallback", "stateMutability": "payable" }]);
+    let verification_info = VerificationInfo {
+        request: VerificationRequest {
+            id: 1,
+            req: VerificationIncomingRequest {
+                contract_address,
+                source_code_data: SourceCodeData::SingleFile("contract Test {}".to_owned()),
+                contract_name: "Test".to_owned(),
+                compiler_zksolc_version: "v1.3.5".to_owned(),
+                compiler_solc_version: "0.8.16".to_owned(),
+                optimization_used: true,
+                constructor_arguments: Default::default(),
+                is_system: false,
+            },
+        },
+        artifacts: CompilationArtifacts {
+            bytecode: vec![0; 32],
+            abi: abi.clone(),
+        },
+        verified_at: sqlx::types::chrono::Utc::now(),
+    };
+    contract_verification_dal
+        .save_verification_info(verification_info)
+        .unwrap();
+
+    let (abis, synced_until) =
+        contract_verification_dal.get_verified_abis_updated_after(None, Duration::ZERO);
+    assert_eq!(abis, [(contract_address, abi.clone())]);
+
+    // The test transaction has a single timestamp, so the contract is verified at `synced_until`.
+    let (abis, new_synced_until) = contract_verification_dal
+        .get_verified_abis_updated_after(Some(synced_until), Duration::ZERO);
+    assert!(abis.is_empty());
+    assert_eq!(new_synced_until, synced_until);
+
+    let (abis, _) = contract_verification_dal
+        .get_verified_abis_updated_after(Some(synced_until), Duration::from_secs(60));
+    assert_eq!(abis, [(contract_address, abi)]);
+}
+
+#[db_test(dal_crate)]
+async fn account_activity(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_dal = TransactionsDal { storage };
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut blocks_dal = BlocksDal { storage };
+
+    let executed_tx = mock_l2_transaction();
+    let initiator_address = executed_tx.common_data.initiator_address;
+    transactions_dal.insert_transaction_l2(executed_tx.clone(), mock_tx_execution_metrics());
+    let mut pending_tx = mock_l2_transaction();
+    pending_tx.common_data.initiator_address = initiator_address;
+    pending_tx.common_data.nonce = zksync_types::Nonce(1);
+    transactions_dal.insert_transaction_l2(pending_tx, mock_tx_execution_metrics());
+
+    blocks_dal.insert_miniblock(MiniblockHeader {
+        timestamp: 42,
+        l2_tx_count: 1,
+        ..create_miniblock_header(1)
+    });
+    transactions_dal.mark_txs_as_executed_in_miniblock(
+        MiniblockNumber(1),
+        &[TransactionExecutionResult {
+            transaction: executed_tx.clone().into(),
+            hash: executed_tx.hash(),
+            execution_info: Default::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
+            compressed_bytecodes: vec![],
+        }],
+        U256::from(1),
+    );
+
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let pending_count = transactions_web3_dal
+        .get_pending_txs_count_by_initiator_account(initiator_address)
+        .await
+        .unwrap();
+    assert_eq!(pending_count, 1);
+    let last_activity = transactions_web3_dal
+        .get_last_activity_timestamp_by_initiator_account(initiator_address)
+        .await
+        .unwrap();
+    assert_eq!(last_activity, Some(42));
+
+    let unknown_account = Address::repeat_byte(0x22);
+    let last_activity = transactions_web3_dal
+        .get_last_activity_timestamp_by_initiator_account(unknown_account)
+        .await
+        .unwrap();
+    assert_eq!(last_activity, None);
+}
+
+#[db_test(dal_crate)]
+async fn execute_columns(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_dal = TransactionsDal { storage };
+
+    let mut tx = mock_l2_transaction();
+    tx.execute.calldata = vec![1, 2, 3];
+    tx.execute.factory_deps = Some(vec![vec![0; 32], vec![1; 64]]);
+    transactions_dal.insert_transaction_l2(tx.clone(), mock_tx_execution_metrics());
+    let storage = &mut connection_pool.access_test_storage().await;
+    BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+        l2_tx_count: 1,
+        ..create_miniblock_header(1)
+    });
+    transactions_dal.mark_txs_as_executed_in_miniblock(
+        MiniblockNumber(1),
+        &[TransactionExecutionResult {
+            transaction: tx.clone().into(),
+            hash: tx.hash(),
+            execution_info: Default::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
+            compressed_bytecodes: vec![],
+        }],
+        U256::from(1),
+    );
+
+    let storage = &mut connection_pool.access_test_storage().await;
+    let row = sqlx::query(
+        "SELECT contract_address, calldata_length, factory_dep_hashes FROM transactions WHERE hash = $1",
+    )
+    .bind(tx.hash().as_bytes())
+    .fetch_one(storage.conn())
+    .await
+    .unwrap();
+    let contract_address: Vec<u8> = row.get("contract_address");
+    assert_eq!(contract_address, tx.execute.contract_address.as_bytes());
+    assert_eq!(row.get::<i32, _>("calldata_length"), 3);
+    let expected_hashes: Vec<Vec<u8>> = tx
+        .execute
+        .factory_deps
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|bytecode| hash_bytecode(bytecode).0.to_vec())
+        .collect();
+    assert_eq!(
+        row.get::<Vec<Vec<u8>>, _>("factory_dep_hashes"),
+        expected_hashes
+    );
+
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let receipt = transactions_web3_dal
+        .get_transaction_receipt(tx.hash())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipt.to, Some(tx.execute.contract_address));
+
+    // Dependencies missing from `factory_deps` are read from the transaction data.
+    let raw_txs = transactions_web3_dal
+        .get_raw_miniblock_transactions(MiniblockNumber(1), Some(10))
+        .await
+        .unwrap();
+    assert_eq!(raw_txs.len(), 1);
+    assert_eq!(raw_txs[0].execute.factory_deps, tx.execute.factory_deps);
+
+    let storage = &mut connection_pool.access_test_storage().await;
+    let factory_deps = tx
+        .execute
+        .factory_deps
+        .clone()
+        .unwrap()
+        .into_iter()
+        .map(|bytecode| (hash_bytecode(&bytecode), bytecode))
+        .collect();
+    StorageDal { storage }.insert_factory_deps(MiniblockNumber(1), factory_deps);
+    let storage = &mut connection_pool.access_test_storage().await;
+    let raw_txs = TransactionsWeb3Dal { storage }
+        .get_raw_miniblock_transactions(MiniblockNumber(1), None)
+        .await
+        .unwrap();
+    assert_eq!(raw_txs[0].execute.factory_deps, tx.execute.factory_deps);
+    assert_eq!(raw_txs[0].execute.calldata, tx.execute.calldata);
+
+    // Legacy transactions are backfilled in batches; legacy transfers keep their recipient in the receipts.
+    let recipient = Address::repeat_byte(0x42);
+    let storage = &mut connection_pool.access_test_storage().await;
+    sqlx::query(
+        "UPDATE transactions SET calldata_length = NULL, factory_dep_hashes = NULL, \
+         data = data || jsonb_build_object('to', $2::text) WHERE hash = $1",
+    )
+    .bind(tx.hash().as_bytes())
+    .bind(format!("{:?}", recipient))
+    .execute(storage.conn())
+    .await
+    .unwrap();
+    let mut transactions_dal = TransactionsDal { storage };
+    assert_eq!(transactions_dal.backfill_execute_columns(10), 1);
+    assert_eq!(transactions_dal.backfill_execute_columns(10), 0);
+
+    let storage = &mut connection_pool.access_test_storage().await;
+    let row =
+        sqlx::query("SELECT calldata_length, factory_dep_hashes FROM transactions WHERE hash = $1")
+            .bind(tx.hash().as_bytes())
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+    assert_eq!(row.get::<i32, _>("calldata_length"), 3);
+    assert_eq!(
+        row.get::<Vec<Vec<u8>>, _>("factory_dep_hashes"),
+        expected_hashes
+    );
+    let receipt = TransactionsWeb3Dal { storage }
+        .get_transaction_receipt(tx.hash())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipt.to, Some(recipient));
+}
+
+#[db_test(dal_crate)]
+async fn api_audit_log_partitions(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut api_audit_log_dal = ApiAuditLogDal { storage };
+
+    let day = sqlx::types::chrono::NaiveDate::from_ymd_opt(2023, 3, 11).unwrap();
+    let next_day = day.succ_opt().unwrap();
+    api_audit_log_dal.create_partition(day).unwrap();
+    api_audit_log_dal.create_partition(next_day).unwrap();
+    // Creating the same partition twice is a no-op.
+    api_audit_log_dal.create_partition(day).unwrap();
+
+    let record = ApiAuditRecord {
+        method: "eth_call".to_string(),
+        params_hash: vec![1; 32],
+        caller_key: None,
+        latency_ms: 5,
+        result_code: 0,
+        created_at: day.and_hms_opt(23, 59, 59).unwrap(),
+    };
+    let next_day_record = ApiAuditRecord {
+        caller_key: Some("ip:127.0.0.1".to_string()),
+        result_code: -32602,
+        created_at: next_day.and_hms_opt(0, 0, 0).unwrap(),
+        ..record.clone()
+    };
+    api_audit_log_dal.insert_records(&[record, next_day_record]);
+
+    let dropped = api_audit_log_dal.drop_partitions_before(next_day).unwrap();
+    assert_eq!(dropped, ["api_audit_log_20230311"]);
+
+    let rows = sqlx::query("SELECT caller_key, result_code FROM api_audit_log")
+        .fetch_all(api_audit_log_dal.storage.conn())
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(
+        rows[0].get::<Option<String>, _>("caller_key").as_deref(),
+        Some("ip:127.0.0.1")
+    );
+    assert_eq!(rows[0].get::<i64, _>("result_code"), -32602);
+}
+
+#[db_test(dal_crate)]
+async fn admin_actions_log(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut admin_actions_log_dal = AdminActionsLogDal { storage };
+
+    let created_at = sqlx::types::chrono::NaiveDate::from_ymd_opt(2023, 4, 3)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    let record = AdminActionRecord {
+        action: "removeDeployerFromAllowlist".to_string(),
+        params: serde_json::json!([Address::repeat_byte(1)]),
+        signers: vec![Address::repeat_byte(0xaa), Address::repeat_byte(0xbb)],
+        message_hash: H256::repeat_byte(1),
+        applied: true,
+        created_at,
+    };
+    assert!(admin_actions_log_dal.insert_action(&record));
+    // The same authorization can't be recorded twice.
+    let replayed_record = AdminActionRecord {
+        applied: false,
+        ..record.clone()
+    };
+    assert!(!admin_actions_log_dal.insert_action(&replayed_record));
+    let next_record = AdminActionRecord {
+        action: "releaseQuarantinedTransaction".to_string(),
+        params: serde_json::json!([H256::repeat_byte(2)]),
+        signers: vec![Address::repeat_byte(0xaa)],
+        message_hash: H256::repeat_byte(2),
+        applied: false,
+        created_at,
+    };
+    assert!(admin_actions_log_dal.insert_action(&next_record));
+
+    let actions = admin_actions_log_dal.get_recent_actions(10);
+    assert_eq!(actions, [next_record.clone(), record]);
+    assert_eq!(admin_actions_log_dal.get_recent_actions(1), [next_record]);
+
+    // The log is append-only.
+    let update_result = sqlx::query("UPDATE admin_actions_log SET applied = TRUE")
+        .execute(admin_actions_log_dal.storage.conn())
+        .await;
+    assert!(update_result.is_err());
+}
+
+#[db_test(dal_crate)]
+async fn api_usage_records(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut api_usage_dal = ApiUsageDal { storage };
+
+    let day = sqlx::types::chrono::NaiveDate::from_ymd_opt(2023, 3, 22).unwrap();
+    let record = ApiUsageRecord {
+        api_key_hash: "0102030405060708".to_string(),
+        method: "eth_call".to_string(),
+        requests: 2,
+        compute_units: 100,
+        period_start: day.and_hms_opt(0, 0, 0).unwrap(),
+        period_end: day.and_hms_opt(0, 1, 0).unwrap(),
+    };
+    let next_period_record = ApiUsageRecord {
+        requests: 1,
+        compute_units: 50,
+        period_start: day.and_hms_opt(0, 1, 0).unwrap(),
+        period_end: day.and_hms_opt(0, 2, 0).unwrap(),
+        ..record.clone()
+    };
+    let other_key_record = ApiUsageRecord {
+        api_key_hash: "0807060504030201".to_string(),
+        ..record.clone()
+    };
+    api_usage_dal.insert_records(&[record.clone(), next_period_record, other_key_record]);
+
+    let usage = api_usage_dal.get_usage(&record.api_key_hash, record.period_start);
+    assert_eq!(
+        usage,
+        [ApiUsageRecord {
+            requests: 3,
+            compute_units: 150,
+            period_end: day.and_hms_opt(0, 2, 0).unwrap(),
+            ..record.clone()
+        }]
+    );
+    let usage = api_usage_dal.get_usage(&record.api_key_hash, day.and_hms_opt(0, 1, 0).unwrap());
+    assert_eq!(usage.len(), 1);
+    assert_eq!(usage[0].requests, 1);
+}
+
+#[db_test(dal_crate)]
+async fn tx_access_sets(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+        l2_tx_count: 2,
+        ..create_miniblock_header(1)
+    });
+
+    let access_sets = [
+        TxAccessSet {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            read_keys: vec![H256::repeat_byte(0x10), H256::repeat_byte(0x11)],
+            written_keys: vec![H256::repeat_byte(0x20)],
+        },
+        TxAccessSet {
+            tx_hash: H256::repeat_byte(2),
+            tx_index_in_miniblock: 1,
+            read_keys: vec![],
+            written_keys: vec![H256::repeat_byte(0x10)],
+        },
+    ];
+    let mut tx_access_sets_dal = TxAccessSetsDal { storage };
+    tx_access_sets_dal.insert_access_sets(MiniblockNumber(1), &access_sets);
+
+    assert_eq!(
+        tx_access_sets_dal.get_access_sets(MiniblockNumber(1)),
+        access_sets
+    );
+    assert!(tx_access_sets_dal
+        .get_access_sets(MiniblockNumber(2))
+        .is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn bridged_tokens_registry(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let token = |byte: u8, symbol: &str| TokenInfo {
+        l1_address: Address::repeat_byte(byte),
+        l2_address: Address::repeat_byte(byte + 0x10),
+        metadata: TokenMetadata {
+            name: format!("{} token", symbol),
+            symbol: symbol.to_owned(),
+            decimals: 18,
+        },
+    };
+    let (first_token, second_token) = (token(1, "FIRST"), token(2, "SECOND"));
+    TokensDal { storage }.add_tokens(vec![second_token.clone(), first_token.clone()]);
+
+    // Metadata from the token list takes precedence.
+    let well_known_metadata = TokenMetadata {
+        name: "Well-known token".to_owned(),
+        symbol: "WK".to_owned(),
+        decimals: 6,
+    };
+    TokensDal { storage }
+        .update_well_known_l1_token(&second_token.l1_address, well_known_metadata.clone());
+    let second_token = TokenInfo {
+        metadata: well_known_metadata,
+        ..second_token
+    };
+
+    let mut tokens_web3_dal = TokensWeb3Dal { storage };
+    assert_eq!(
+        tokens_web3_dal
+            .get_token_by_l1_address(&first_token.l1_address)
+            .unwrap(),
+        Some(first_token.clone())
+    );
+    assert_eq!(
+        tokens_web3_dal
+            .get_token_by_l2_address(&second_token.l2_address)
+            .unwrap(),
+        Some(second_token.clone())
+    );
+    // Addresses are not mixed up between the layers.
+    assert_eq!(
+        tokens_web3_dal
+            .get_token_by_l2_address(&first_token.l1_address)
+            .unwrap(),
+        None
+    );
+
+    assert_eq!(
+        tokens_web3_dal.get_bridged_tokens(0, 10, None).unwrap(),
+        [first_token.clone(), second_token.clone()]
+    );
+    assert_eq!(
+        tokens_web3_dal.get_bridged_tokens(1, 10, None).unwrap(),
+        [second_token.clone()]
+    );
+    assert_eq!(
+        tokens_web3_dal
+            .get_bridged_tokens(0, 10, Some(first_token.l1_address))
+            .unwrap(),
+        [second_token.clone()]
+    );
+    assert!(tokens_web3_dal
+        .get_bridged_tokens(0, 10, Some(second_token.l1_address))
+        .unwrap()
+        .is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn l2_token_metadata(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+        l2_tx_count: 1,
+        ..create_miniblock_header(1)
+    });
+
+    let (token, nft) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    // The ERC721 `Transfer` event has the token ID as the 4th topic and must be ignored.
+    let mut transfer_topics = vec![ERC20_TRANSFER_TOPIC, H256::zero(), H256::zero()];
+    let erc20_transfer = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: token,
+        indexed_topics: transfer_topics.clone(),
+        value: vec![],
+    };
+    transfer_topics.push(H256::zero());
+    let erc721_transfer = VmEvent {
+        address: nft,
+        indexed_topics: transfer_topics,
+        ..erc20_transfer.clone()
+    };
+    let tx_location = IncludedTxLocation {
+        tx_hash: H256::repeat_byte(1),
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: Address::repeat_byte(3),
+    };
+    EventsDal { storage }.save_events(
+        MiniblockNumber(1),
+        vec![(tx_location, vec![erc20_transfer, erc721_transfer])],
+    );
+
+    let mut tokens_dal = TokensDal { storage };
+    assert_eq!(tokens_dal.get_last_l2_token_observed_miniblock(), None);
+    assert_eq!(
+        tokens_dal.observe_l2_tokens(MiniblockNumber(0), MiniblockNumber(1)),
+        1
+    );
+    // Tokens are observed only once.
+    assert_eq!(
+        tokens_dal.observe_l2_tokens(MiniblockNumber(1), MiniblockNumber(1)),
+        0
+    );
+    assert_eq!(
+        tokens_dal.get_last_l2_token_observed_miniblock(),
+        Some(MiniblockNumber(1))
+    );
+
+    let refresh_interval = Duration::from_secs(3600);
+    assert_eq!(
+        tokens_dal.get_l2_tokens_for_metadata_refresh(refresh_interval, 10),
+        [token]
+    );
+    let token_info = TokensWeb3Dal { storage }
+        .get_l2_token_info(&token)
+        .unwrap()
+        .unwrap();
+    assert_eq!(token_info.name, None);
+    assert_eq!(token_info.refreshed_at, None);
+
+    let mut tokens_dal = TokensDal { storage };
+    tokens_dal.set_l2_token_metadata(&token, Some("Test token"), Some("TEST"), None);
+    assert!(tokens_dal
+        .get_l2_tokens_for_metadata_refresh(refresh_interval, 10)
+        .is_empty());
+
+    let mut tokens_web3_dal = TokensWeb3Dal { storage };
+    let token_info = tokens_web3_dal.get_l2_token_info(&token).unwrap().unwrap();
+    assert_eq!(token_info.name.as_deref(), Some("Test token"));
+    assert_eq!(token_info.symbol.as_deref(), Some("TEST"));
+    assert_eq!(token_info.decimals, None);
+    assert!(token_info.refreshed_at.is_some());
+    assert_eq!(tokens_web3_dal.get_l2_token_info(&nft).unwrap(), None);
+}
+
+#[db_test(dal_crate)]
+async fn deployer_allowlist(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut allowlist_dal = DeployerAllowlistDal { storage };
+    let (first, second) = (Address::repeat_byte(2), Address::repeat_byte(1));
+    assert!(allowlist_dal.get_allowlisted_deployers().is_empty());
+
+    assert!(allowlist_dal.add_deployer(&first));
+    assert!(allowlist_dal.add_deployer(&second));
+    assert!(!allowlist_dal.add_deployer(&first));
+    assert_eq!(allowlist_dal.get_allowlisted_deployers(), [second, first]);
+
+    assert!(allowlist_dal.remove_deployer(&first));
+    assert!(!allowlist_dal.remove_deployer(&first));
+    assert_eq!(allowlist_dal.get_allowlisted_deployers(), [second]);
+}
+
+#[db_test(dal_crate)]
+async fn quarantined_transactions(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let (poison_tx, other_tx) = (mock_l2_transaction(), mock_l2_transaction());
+    let mut transactions_dal = TransactionsDal { storage };
+    transactions_dal.insert_transaction_l2(poison_tx.clone(), mock_tx_execution_metrics());
+    transactions_dal.insert_transaction_l2(other_tx.clone(), mock_tx_execution_metrics());
+
+    let mut quarantine_dal = QuarantinedTransactionsDal { storage };
+    assert_eq!(quarantine_dal.record_crash(&other_tx.hash()), 1);
+    quarantine_dal.clear_crashes(&other_tx.hash());
+    assert_eq!(quarantine_dal.record_crash(&other_tx.hash()), 1);
+    assert_eq!(quarantine_dal.record_crash(&poison_tx.hash()), 1);
+    assert_eq!(quarantine_dal.record_crash(&poison_tx.hash()), 2);
+    assert!(quarantine_dal.get_quarantined_transactions().is_empty());
+
+    quarantine_dal.quarantine(&poison_tx.hash());
+    // Crashes of the quarantined transaction are not forgotten.
+    quarantine_dal.clear_crashes(&poison_tx.hash());
+    let quarantined = quarantine_dal.get_quarantined_transactions();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].hash, poison_tx.hash());
+    assert_eq!(quarantined[0].crash_count, 2);
+
+    let mut transactions_dal = TransactionsDal { storage };
+    transactions_dal.reset_mempool();
+    let txs = transactions_dal.sync_mempool(vec![], vec![], 0, 0, 1000).0;
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].hash(), other_tx.hash());
+
+    let mut quarantine_dal = QuarantinedTransactionsDal { storage };
+    assert!(quarantine_dal.release(&poison_tx.hash()));
+    assert!(!quarantine_dal.release(&poison_tx.hash()));
+    assert!(quarantine_dal.get_quarantined_transactions().is_empty());
+
+    let mut transactions_dal = TransactionsDal { storage };
+    let txs = transactions_dal.sync_mempool(vec![], vec![], 0, 0, 1000).0;
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].hash(), poison_tx.hash());
+}
+
+#[db_test(dal_crate)]
+async fn mempool_transactions(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let first_tx = mock_l2_transaction();
+    let initiator_address = first_tx.common_data.initiator_address;
+    let mut gapped_tx = mock_l2_transaction();
+    gapped_tx.common_data.initiator_address = initiator_address;
+    gapped_tx.common_data.nonce = zksync_types::Nonce(2);
+    let poison_tx = mock_l2_transaction();
+    let mut transactions_dal = TransactionsDal { storage };
+    transactions_dal.insert_transaction_l2(gapped_tx.clone(), mock_tx_execution_metrics());
+    transactions_dal.insert_transaction_l2(first_tx.clone(), mock_tx_execution_metrics());
+    transactions_dal.insert_transaction_l2(poison_tx.clone(), mock_tx_execution_metrics());
+    let mut quarantine_dal = QuarantinedTransactionsDal { storage };
+    quarantine_dal.record_crash(&poison_tx.hash());
+    quarantine_dal.quarantine(&poison_tx.hash());
+
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let content = transactions_web3_dal
+        .get_mempool_transactions(None)
+        .await
+        .unwrap();
+    assert!(!content.is_truncated);
+    let (txs, nonces) = (content.transactions, content.nonces);
+    assert_eq!(txs.len(), 3);
+    let account_txs: Vec<_> = txs
+        .iter()
+        .filter(|tx| tx.initiator_address == initiator_address)
+        .map(|tx| (tx.hash, tx.nonce))
+        .collect();
+    assert_eq!(account_txs, [(first_tx.hash(), 0), (gapped_tx.hash(), 2)]);
+    let poison = txs.iter().find(|tx| tx.hash == poison_tx.hash()).unwrap();
+    assert!(poison.is_quarantined);
+    assert_eq!(poison.max_fee_per_gas, U256::from(250_000_000u32));
+    // None of the accounts has executed transactions.
+    assert_eq!(nonces[&initiator_address], 0);
+    assert_eq!(nonces.len(), 2);
+
+    // The transactions of an account are never split by the limit.
+    let content = transactions_web3_dal
+        .get_mempool_transactions(Some(1))
+        .await
+        .unwrap();
+    assert!(content.is_truncated);
+    let initiators: HashSet<_> = content
+        .transactions
+        .iter()
+        .map(|tx| tx.initiator_address)
+        .collect();
+    assert_eq!(initiators.len(), 1);
+    let expected_len = if initiators.contains(&initiator_address) {
+        2
+    } else {
+        1
+    };
+    assert_eq!(content.transactions.len(), expected_len);
+    let content = transactions_web3_dal
+        .get_mempool_transactions(Some(3))
+        .await
+        .unwrap();
+    assert!(!content.is_truncated);
+    assert_eq!(content.transactions.len(), 3);
+
+    // The first transaction of the account is pending, and the gapped one is queued, as is the quarantined one.
+    let status = transactions_web3_dal
+        .get_mempool_status(0, 0)
+        .await
+        .unwrap();
+    assert_eq!(
+        status,
+        api::TxPoolStatus {
+            pending: 1,
+            queued: 2
+        }
+    );
+
+    let idle_account = Address::repeat_byte(0x42);
+    let (txs, nonces) = transactions_web3_dal
+        .get_mempool_transactions_by_initiators(&[initiator_address, idle_account])
+        .await
+        .unwrap();
+    let account_txs: Vec<_> = txs.iter().map(|tx| (tx.hash, tx.nonce)).collect();
+    assert_eq!(account_txs, [(first_tx.hash(), 0), (gapped_tx.hash(), 2)]);
+    // The nonces are returned for the accounts without mempool transactions as well.
+    assert_eq!(nonces.len(), 2);
+    assert_eq!(nonces[&idle_account], 0);
+}
+
+#[db_test(dal_crate)]
+async fn expired_priority_ops(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut tx = mock_l1_execute();
+    // The deadline is an L1 timestamp, so it must never be compared with the L1 block numbers.
+    let deadline_block = 1_680_000_000;
+    tx.common_data.deadline_block = deadline_block;
+    let (serial_id, tx_hash) = (tx.serial_id(), tx.hash());
+    TransactionsDal { storage }.insert_transaction_l1(tx, L1BlockNumber(16_000_000));
+
+    let mut expired_ops_dal = ExpiredPriorityOpsDal { storage };
+    let l1_block = L1BlockNumber(16_000_010);
+    assert_eq!(
+        expired_ops_dal.mark_expired_priority_ops(l1_block, deadline_block, None),
+        0
+    );
+    let after_deadline = l1_block + 1;
+    assert_eq!(
+        expired_ops_dal.mark_expired_priority_ops(after_deadline, deadline_block + 12, None),
+        1
+    );
+    assert_eq!(
+        expired_ops_dal.mark_expired_priority_ops(after_deadline + 1, deadline_block + 24, None),
+        0
+    );
+    // The state keeper may record the same operation once again.
+    expired_ops_dal.insert_expired_priority_op(
+        serial_id,
+        tx_hash,
+        deadline_block,
+        after_deadline + 1,
+    );
+
+    let ops = expired_ops_dal
+        .get_expired_priority_ops(PriorityOpId(0), 10)
+        .unwrap();
+    assert_eq!(ops.len(), 1);
+    assert_eq!(ops[0].serial_id, serial_id.0);
+    assert_eq!(ops[0].tx_hash, tx_hash);
+    assert_eq!(ops[0].deadline_block, deadline_block);
+    assert_eq!(ops[0].detected_at_block, after_deadline.0 as u64);
+    assert_eq!(ops[0].miniblock_number, None);
+
+    let ops = expired_ops_dal
+        .get_expired_priority_ops(serial_id.next(), 10)
+        .unwrap();
+    assert!(ops.is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn miniblock_fee_input(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let miniblock = |number, l1_gas_price, l2_fair_gas_price| MiniblockHeader {
+        l1_gas_price,
+        l2_fair_gas_price,
+        ..create_miniblock_header(number)
+    };
+    let mut blocks_dal = BlocksDal { storage };
+    blocks_dal.insert_miniblock(miniblock(1, 0, 0));
+    blocks_dal.insert_miniblock(miniblock(2, 1_000, 250));
+
+    let mut blocks_web3_dal = BlocksWeb3Dal { storage };
+    // Miniblocks without the recorded fee input are treated as unknown.
+    let fee_input = blocks_web3_dal.get_miniblock_fee_input(MiniblockNumber(1));
+    assert_eq!(fee_input.unwrap(), None);
+    let fee_input = blocks_web3_dal.get_miniblock_fee_input(MiniblockNumber(2));
+    assert_eq!(fee_input.unwrap(), Some((1_000, 250)));
+    let fee_input = blocks_web3_dal.get_miniblock_fee_input(MiniblockNumber(3));
+    assert_eq!(fee_input.unwrap(), None);
+}
+
+#[db_test(dal_crate)]
+async fn miniblock_fee_account_address(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let fee_account = Address::repeat_byte(0x11);
+    let mut blocks_dal = BlocksDal { storage };
+    blocks_dal.insert_miniblock(MiniblockHeader {
+        fee_account_address: fee_account,
+        ..create_miniblock_header(1)
+    });
+    let header = blocks_dal.get_miniblock_header(MiniblockNumber(1)).unwrap();
+    assert_eq!(header.fee_account_address, fee_account);
+
+    let mut blocks_web3_dal = BlocksWeb3Dal { storage };
+    let address = blocks_web3_dal.get_miniblock_fee_account_address(MiniblockNumber(1));
+    assert_eq!(address.unwrap(), Some(fee_account));
+    let address = blocks_web3_dal.get_miniblock_fee_account_address(MiniblockNumber(2));
+    assert_eq!(address.unwrap(), None);
+}
+
+#[db_test(dal_crate)]
+async fn miniblock_system_context(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let fee_account = Address::repeat_byte(0x11);
+    let miniblock = |number, timestamp, l1_gas_price, fee_account_address| MiniblockHeader {
+        timestamp,
+        base_fee_per_gas: 100,
+        l1_gas_price,
+        l2_fair_gas_price: 250,
+        fee_account_address,
+        ..create_miniblock_header(number)
+    };
+    let mut blocks_dal = BlocksDal { storage };
+    blocks_dal.insert_miniblock(miniblock(1, 10, 1_000, fee_account));
+    let tx_rolling_hash = H256::repeat_byte(0x22);
+    blocks_dal.insert_miniblock_with_system_context(
+        miniblock(2, 11, 1_000, fee_account),
+        800,
+        tx_rolling_hash,
+    );
+    let mut header = L1BatchHeader::new(L1BatchNumber(1), 10, fee_account, Default::default());
+    header.l1_gas_price = 1_000;
+    header.l2_fair_gas_price = 250;
+    header.base_fee_per_gas = 100;
+    blocks_dal.insert_l1_batch(header, Default::default());
+    blocks_dal.mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1));
+    // Miniblocks of the pending batch sealed before the fee account was recorded.
+    blocks_dal.insert_miniblock(miniblock(3, 20, 2_000, Address::zero()));
+    blocks_dal.insert_miniblock(miniblock(4, 21, 2_000, Address::zero()));
+
+    let mut blocks_web3_dal = BlocksWeb3Dal { storage };
+    let context = blocks_web3_dal
+        .get_miniblock_system_context(MiniblockNumber(2))
+        .unwrap()
+        .unwrap();
+    assert_eq!(context.miniblock_timestamp, 11);
+    assert_eq!(context.l1_batch_number, L1BatchNumber(1));
+    assert_eq!(context.l1_batch_timestamp, 10);
+    assert!(context.l1_batch_sealed);
+    assert_eq!(context.operator_address, Some(fee_account));
+    assert_eq!(context.l1_gas_price, 1_000);
+    assert_eq!(context.base_fee_per_gas, U256::from(100));
+    assert_eq!(context.gas_per_pubdata, Some(800));
+    assert_eq!(context.tx_rolling_hash, Some(tx_rolling_hash));
+
+    let context = blocks_web3_dal
+        .get_miniblock_system_context(MiniblockNumber(4))
+        .unwrap()
+        .unwrap();
+    assert_eq!(context.miniblock_timestamp, 21);
+    assert_eq!(context.l1_batch_number, L1BatchNumber(2));
+    assert_eq!(context.l1_batch_timestamp, 20);
+    assert!(!context.l1_batch_sealed);
+    assert_eq!(context.operator_address, None);
+    assert_eq!(context.l1_gas_price, 2_000);
+    // The values that aren't recorded for the miniblock aren't recomputed.
+    assert_eq!(context.gas_per_pubdata, None);
+    assert_eq!(context.tx_rolling_hash, None);
+
+    let context = blocks_web3_dal.get_miniblock_system_context(MiniblockNumber(5));
+    assert_eq!(context.unwrap(), None);
+}
+
+#[db_test(dal_crate)]
+async fn state_diff(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    for number in 1..=2 {
+        BlocksDal { storage }.insert_miniblock(create_miniblock_header(number));
+    }
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    let keys = [1, 2, 3].map(|byte| StorageKey::new(account, H256::repeat_byte(byte)));
+    let write =
+        |key: usize, value: u8| StorageLog::new_write_log(keys[key], H256::repeat_byte(value));
+    let mut storage_logs_dal = StorageLogsDal { storage };
+    storage_logs_dal.insert_storage_logs(
+        MiniblockNumber(1),
+        &[(H256::zero(), vec![write(0, 1), write(1, 2)])],
+    );
+    // The second slot is overwritten with the same value, and the third one is written twice.
+    storage_logs_dal.insert_storage_logs(
+        MiniblockNumber(2),
+        &[(
+            H256::zero(),
+            vec![write(0, 3), write(1, 2), write(2, 5), write(2, 6)],
+        )],
+    );
+
+    let mut storage_web3_dal = StorageWeb3Dal { storage };
+    let mut diffs = storage_web3_dal
+        .get_state_diff(MiniblockNumber(2)..=MiniblockNumber(2), None, 10)
+        .unwrap();
+    assert_eq!(diffs.len(), 2);
+    diffs.sort_by_key(|diff| diff.key);
+    assert_eq!(diffs[0].address, Address::repeat_byte(1));
+    assert_eq!(diffs[0].hashed_key, keys[0].hashed_key());
+    assert_eq!(diffs[0].old_value, H256::repeat_byte(1));
+    assert_eq!(diffs[0].new_value, H256::repeat_byte(3));
+    assert!(!diffs[0].initial_write);
+    assert_eq!(diffs[1].key, *keys[2].key());
+    assert_eq!(diffs[1].old_value, H256::zero());
+    assert_eq!(diffs[1].new_value, H256::repeat_byte(6));
+    assert!(diffs[1].initial_write);
+
+    let diffs = storage_web3_dal
+        .get_state_diff(MiniblockNumber(1)..=MiniblockNumber(2), None, 10)
+        .unwrap();
+    assert_eq!(diffs.len(), 3);
+    assert!(diffs.iter().all(|diff| diff.initial_write));
+    let hashed_keys: Vec<_> = diffs.iter().map(|diff| diff.hashed_key).collect();
+    let mut sorted_keys = hashed_keys.clone();
+    sorted_keys.sort();
+    assert_eq!(hashed_keys, sorted_keys);
+
+    let first_page = storage_web3_dal
+        .get_state_diff(MiniblockNumber(1)..=MiniblockNumber(2), None, 2)
+        .unwrap();
+    assert_eq!(first_page, diffs[..2]);
+    let last_page = storage_web3_dal
+        .get_state_diff(
+            MiniblockNumber(1)..=MiniblockNumber(2),
+            Some(first_page[1].hashed_key),
+            2,
+        )
+        .unwrap();
+    assert_eq!(last_page, diffs[2..]);
+}
+
+#[db_test(dal_crate)]
+async fn historical_values(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    for number in 1..=2 {
+        BlocksDal { storage }.insert_miniblock(create_miniblock_header(number));
+    }
+    let account = AccountTreeId::new(Address::repeat_byte(1));
+    let keys = [1, 2, 3].map(|byte| StorageKey::new(account, H256::repeat_byte(byte)));
+    let write =
+        |key: usize, value: u8| StorageLog::new_write_log(keys[key], H256::repeat_byte(value));
+    let mut storage_logs_dal = StorageLogsDal { storage };
+    storage_logs_dal.insert_storage_logs(
+        MiniblockNumber(1),
+        &[(H256::zero(), vec![write(0, 1), write(1, 2), write(1, 3)])],
+    );
+    storage_logs_dal.insert_storage_logs(MiniblockNumber(2), &[(H256::zero(), vec![write(0, 4)])]);
+
+    let mut storage_web3_dal = StorageWeb3Dal { storage };
+    // The values are returned in the order of the keys, including the duplicate and unwritten ones.
+    let requested_keys = [keys[2], keys[1], keys[0], keys[1]];
+    let values = storage_web3_dal
+        .get_historical_values_unchecked(&requested_keys, MiniblockNumber(1))
+        .unwrap();
+    let expected = [0, 3, 1, 3].map(H256::repeat_byte);
+    assert_eq!(values, expected);
+    for (key, value) in requested_keys.iter().zip(&values) {
+        let single_value = storage_web3_dal
+            .get_historical_value_unchecked(key, MiniblockNumber(1))
+            .unwrap();
+        assert_eq!(single_value, *value);
+    }
+
+    let values = storage_web3_dal
+        .get_historical_values_unchecked(&keys, MiniblockNumber(2))
+        .unwrap();
+    assert_eq!(values, [4, 3, 0].map(H256::repeat_byte));
+    let values = storage_web3_dal
+        .get_historical_values_unchecked(&[], MiniblockNumber(2))
+        .unwrap();
+    assert!(values.is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn l1_batch_priority_ops(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let tx = mock_l1_execute();
+    let (serial_id, tx_hash) = (tx.serial_id(), tx.hash());
+    TransactionsDal { storage }.insert_transaction_l1(tx.clone(), L1BlockNumber(1));
+
+    let mut header =
+        L1BatchHeader::new(L1BatchNumber(1), 0, Default::default(), Default::default());
+    header.l1_tx_count = 1;
+    header.priority_ops_onchain_data = vec![tx.common_data.onchain_data()];
+    let expected_hash = header.priority_ops_onchain_data_hash();
+    storage
+        .blocks_dal()
+        .insert_l1_batch(header, Default::default());
+    let mut transactions_dal = TransactionsDal { storage };
+    transactions_dal.mark_txs_as_executed_in_l1_batch(
+        L1BatchNumber(1),
+        &[TransactionExecutionResult {
+            transaction: tx.into(),
+            hash: tx_hash,
+            execution_info: Default::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
+            compressed_bytecodes: vec![],
+        }],
+    );
+
+    assert_eq!(
+        transactions_dal.get_l1_batch_priority_ops(L1BatchNumber(1)),
+        [(serial_id, tx_hash)]
+    );
+    assert!(transactions_dal
+        .get_l1_batch_priority_ops(L1BatchNumber(2))
+        .is_empty());
+    assert_eq!(
+        transactions_dal.last_priority_id_before_l1_batch(L1BatchNumber(2)),
+        Some(serial_id)
+    );
+    assert_eq!(
+        transactions_dal.last_priority_id_before_l1_batch(L1BatchNumber(1)),
+        None
+    );
+
+    let mut blocks_dal = BlocksDal { storage };
+    assert_eq!(
+        blocks_dal.get_l1_batch_priority_ops_hash(L1BatchNumber(1)),
+        Some(expected_hash)
+    );
+    assert_eq!(
+        blocks_dal.get_l1_batch_priority_ops_hash(L1BatchNumber(2)),
+        None
+    );
+}
+
+#[db_test(dal_crate)]
+async fn rocksdb_backups(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut backups_dal = RocksdbBackupsDal { storage };
+    let keys = |id: &str| {
+        vec![
+            format!("tree/{}/meta/1", id),
+            format!("tree/{}/private/1/CURRENT", id),
+        ]
+    };
+    backups_dal.insert_backup("tree", L1BatchNumber(5), &keys("a"));
+    let latest_id = backups_dal.insert_backup("tree", L1BatchNumber(10), &keys("b"));
+    backups_dal.insert_backup("state_keeper_cache", L1BatchNumber(12), &[]);
+
+    let backup = backups_dal
+        .get_latest_backup("tree", L1BatchNumber(100))
+        .unwrap();
+    assert_eq!(backup.id, latest_id);
+    assert_eq!(backup.l1_batch_number, L1BatchNumber(10));
+    assert_eq!(backup.object_keys, keys("b"));
+    // Backups ahead of the Postgres state must not be selected.
+    let backup = backups_dal
+        .get_latest_backup("tree", L1BatchNumber(7))
+        .unwrap();
+    assert_eq!(backup.l1_batch_number, L1BatchNumber(5));
+    assert!(backups_dal
+        .get_latest_backup("tree", L1BatchNumber(4))
+        .is_none());
+
+    // Reverting to L1 batch #9 keeps the backup taken right after it was processed.
+    let reverted: Vec<_> = backups_dal
+        .get_backups_after(L1BatchNumber(9))
+        .into_iter()
+        .map(|backup| backup.l1_batch_number)
+        .collect();
+    assert_eq!(reverted, [L1BatchNumber(12)]);
+
+    let outdated = backups_dal.get_outdated_backups("tree", 1);
+    assert_eq!(outdated.len(), 1);
+    assert_eq!(outdated[0].l1_batch_number, L1BatchNumber(5));
+    backups_dal.delete_backup(outdated[0].id);
+    assert!(backups_dal.get_outdated_backups("tree", 1).is_empty());
+    assert!(backups_dal
+        .get_latest_backup("tree", L1BatchNumber(7))
+        .is_none());
+}
+
+#[db_test(dal_crate)]
+async fn state_snapshot_diffs(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut diffs_dal = StateSnapshotDiffsDal { storage };
+    assert!(diffs_dal.get_latest_diff().is_none());
+
+    diffs_dal.insert_diff(
+        L1BatchNumber(5),
+        L1BatchNumber(7),
+        MiniblockNumber(20),
+        H256::repeat_byte(7),
+        "5_7",
+    );
+    diffs_dal.insert_diff(
+        L1BatchNumber(7),
+        L1BatchNumber(9),
+        MiniblockNumber(30),
+        H256::repeat_byte(9),
+        "7_9",
+    );
+    let latest_id = diffs_dal.insert_diff(
+        L1BatchNumber(9),
+        L1BatchNumber(12),
+        MiniblockNumber(45),
+        H256::repeat_byte(12),
+        "9_12",
+    );
+
+    let latest = diffs_dal.get_latest_diff().unwrap();
+    assert_eq!(latest.id, latest_id);
+    assert_eq!(latest.first_miniblock_number, MiniblockNumber(45));
+    assert_eq!(latest.root_hash, Some(H256::repeat_byte(12)));
+
+    // The diffs are applied one by one along the chain.
+    let diff = diffs_dal
+        .get_diff_to_apply(L1BatchNumber(6), L1BatchNumber(100))
+        .unwrap();
+    assert_eq!(diff.object_key, "5_7");
+    let diff = diffs_dal
+        .get_diff_to_apply(L1BatchNumber(7), L1BatchNumber(100))
+        .unwrap();
+    assert_eq!(diff.object_key, "7_9");
+    let diff = diffs_dal
+        .get_diff_to_apply(L1BatchNumber(10), L1BatchNumber(100))
+        .unwrap();
+    assert_eq!(diff.object_key, "9_12");
+    // Diffs with the base ahead of the node state, or leading past `max_l1_batch_number` must not be selected.
+    assert!(diffs_dal
+        .get_diff_to_apply(L1BatchNumber(4), L1BatchNumber(100))
+        .is_none());
+    assert!(diffs_dal
+        .get_diff_to_apply(L1BatchNumber(9), L1BatchNumber(11))
+        .is_none());
+    assert!(diffs_dal
+        .get_diff_to_apply(L1BatchNumber(12), L1BatchNumber(100))
+        .is_none());
+
+    // Reverting the L1 batches after #8 invalidates the diffs covering them.
+    let reverted_keys: Vec<_> = diffs_dal
+        .get_diffs_after(L1BatchNumber(8))
+        .into_iter()
+        .map(|diff| diff.object_key)
+        .collect();
+    assert_eq!(reverted_keys, ["7_9", "9_12"]);
+    assert!(diffs_dal.get_diffs_after(L1BatchNumber(11)).is_empty());
+
+    // Diffs are kept for the retention period.
+    let retention = Duration::from_secs(3_600);
+    assert!(diffs_dal.get_outdated_diffs(retention).is_empty());
+    async_std::task::block_on(
+        sqlx::query("UPDATE state_snapshot_diffs SET created_at = now() - interval '2 hours'")
+            .execute(diffs_dal.storage.conn()),
+    )
+    .unwrap();
+    // The latest diff is kept regardless, so that the next diff is chained on it.
+    let outdated = diffs_dal.get_outdated_diffs(retention);
+    let outdated_keys: Vec<_> = outdated
+        .iter()
+        .map(|diff| diff.object_key.as_str())
+        .collect();
+    assert_eq!(outdated_keys, ["5_7", "7_9"]);
+    for diff in outdated {
+        diffs_dal.delete_diff(diff.id);
+    }
+    assert!(diffs_dal.get_outdated_diffs(retention).is_empty());
+    assert!(diffs_dal
+        .get_diff_to_apply(L1BatchNumber(6), L1BatchNumber(100))
+        .is_none());
+    assert_eq!(diffs_dal.get_latest_diff().unwrap().id, latest_id);
+}
+
+#[db_test(dal_crate)]
+async fn reorged_miniblocks(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let miniblock = |number, hash| MiniblockHeader {
+        hash: H256::from_low_u64_be(hash),
+        ..create_miniblock_header(number)
+    };
+    let mut blocks_dal = BlocksDal { storage };
+    blocks_dal.insert_miniblock(miniblock(1, 1));
+    blocks_dal.insert_miniblock(miniblock(2, 2));
+    blocks_dal.insert_miniblock(miniblock(3, 3));
+    blocks_dal.delete_miniblocks(MiniblockNumber(1));
+    blocks_dal.insert_miniblock(miniblock(2, 20));
+
+    let mut blocks_web3_dal = BlocksWeb3Dal { storage };
+    let reorged = blocks_web3_dal.get_reorged_miniblock(H256::from_low_u64_be(2));
+    assert_eq!(
+        reorged.unwrap(),
+        Some((MiniblockNumber(2), Some(H256::from_low_u64_be(20))))
+    );
+    // The miniblock #3 isn't sealed again yet.
+    let reorged = blocks_web3_dal.get_reorged_miniblock(H256::from_low_u64_be(3));
+    assert_eq!(reorged.unwrap(), Some((MiniblockNumber(3), None)));
+    let reorged = blocks_web3_dal.get_reorged_miniblock(H256::from_low_u64_be(1));
+    assert_eq!(reorged.unwrap(), None);
+
+    let block_id = api::BlockId::Hash(H256::from_low_u64_be(3));
+    let err = blocks_web3_dal
+        .resolve_block_id(block_id)
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Web3Error::BlockReorged(_, MiniblockNumber(3), None)
+    ));
+}
+
+#[db_test(dal_crate)]
+async fn instance_heartbeats(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut heartbeats_dal = InstanceHeartbeatsDal { storage };
+    let schema_version = binary_schema_version();
+    assert!(schema_version >= COMPATIBLE_SINCE_SCHEMA_VERSION);
+
+    heartbeats_dal.upsert_heartbeat("old", "[Tree]", 20230101000000, 20221201000000);
+    heartbeats_dal.upsert_heartbeat("new", "[Api]", schema_version, schema_version);
+    heartbeats_dal.upsert_heartbeat("new", "[Api]", schema_version, schema_version);
+
+    let instances = heartbeats_dal.get_live_instances(Duration::from_secs(60));
+    assert_eq!(instances.len(), 2);
+    let old_instance = instances.iter().find(|i| i.instance_id == "old").unwrap();
+    assert!(!old_instance.is_compatible_with(schema_version, COMPATIBLE_SINCE_SCHEMA_VERSION));
+    let new_instance = instances.iter().find(|i| i.instance_id == "new").unwrap();
+    assert!(new_instance.is_compatible_with(schema_version, COMPATIBLE_SINCE_SCHEMA_VERSION));
+    // An older binary is incompatible with the newer instance which requires a newer schema.
+    assert!(!new_instance.is_compatible_with(20230101000000, 20221201000000));
+
+    heartbeats_dal.remove_heartbeat("old");
+    let instances = heartbeats_dal.get_live_instances(Duration::from_secs(60));
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].instance_id, "new");
+}
+
+#[db_test(dal_crate)]
+async fn miniblock_execution_digest(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut blocks_dal = BlocksDal { storage };
+    blocks_dal.insert_miniblock(create_miniblock_header(1));
+    assert_eq!(
+        blocks_dal.get_miniblock_execution_digest(MiniblockNumber(1)),
+        None
+    );
+
+    let digest = execution_outcomes_digest(&[]);
+    blocks_dal.set_miniblock_execution_digest(
+        MiniblockNumber(1),
+        digest,
+        EXECUTION_OUTCOMES_SERIALIZATION_VERSION,
+    );
+    assert_eq!(
+        blocks_dal.get_miniblock_execution_digest(MiniblockNumber(1)),
+        Some((digest, EXECUTION_OUTCOMES_SERIALIZATION_VERSION))
+    );
+}
+
+#[db_test(dal_crate)]
+async fn fee_history(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut transactions_dal = TransactionsDal { storage };
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut blocks_dal = BlocksDal { storage };
+
+    let base_fee_per_gas = 100_000_000;
+    let mut executed_txs = vec![];
+    for (priority_fee, refunded_gas) in [(1_000u32, 0), (3_000, 400_000)] {
+        let mut tx = mock_l2_transaction();
+        tx.common_data.fee.max_priority_fee_per_gas = priority_fee.into();
+        transactions_dal.insert_transaction_l2(tx.clone(), mock_tx_execution_metrics());
+        executed_txs.push(TransactionExecutionResult {
+            hash: tx.hash(),
+            transaction: tx.into(),
+            execution_info: Default::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas,
+            operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
+            compressed_bytecodes: vec![],
+        });
+    }
+    for number in 1..=2 {
+        blocks_dal.insert_miniblock(MiniblockHeader {
+            timestamp: number as u64,
+            l2_tx_count: if number == 1 { 2 } else { 0 },
+            base_fee_per_gas,
+            ..create_miniblock_header(number)
+        });
+    }
+    transactions_dal.mark_txs_as_executed_in_miniblock(
+        MiniblockNumber(1),
+        &executed_txs,
+        base_fee_per_gas.into(),
+    );
+
+    let mut blocks_web3_dal = BlocksWeb3Dal { storage };
+    let history = blocks_web3_dal
+        .get_fee_history(MiniblockNumber(1), MiniblockNumber(5), &[0.5, 0.55, 1.0])
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].number, MiniblockNumber(1));
+    assert_eq!(history[0].base_fee_per_gas, base_fee_per_gas.into());
+    assert_eq!(history[0].gas_used, U256::from(1_600_000u32));
+    // Percentiles are weighted by the gas used: the first transaction uses 1_000_000 gas of 1_600_000.
+    assert_eq!(
+        history[0].rewards,
+        [
+            U256::from(1_000u32),
+            U256::from(1_000u32),
+            U256::from(3_000u32)
+        ]
+    );
+    assert_eq!(history[1].gas_used, U256::zero());
+    assert!(history[1].rewards.is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn paginated_logs(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let token = Address::repeat_byte(1);
+    let event = |address| VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address,
+        indexed_topics: vec![ERC20_TRANSFER_TOPIC],
+        value: vec![],
+    };
+    for number in 1..=2 {
+        let events = vec![event(token), event(Address::repeat_byte(2)), event(token)];
+        BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+            l2_tx_count: 1,
+            logs_bloom: logs_bloom(&events),
+            ..create_miniblock_header(number)
+        });
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::random(),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::random(),
+        };
+        EventsDal { storage }.save_events(MiniblockNumber(number), vec![(tx_location, events)]);
+    }
+
+    let filter = api::GetLogsFilter {
+        from_block: MiniblockNumber(1),
+        to_block: Some(api::BlockNumber::Number(2.into())),
+        addresses: vec![token],
+        topics: vec![(1, vec![ERC20_TRANSFER_TOPIC])],
+    };
+    let mut events_web3_dal = EventsWeb3Dal { storage };
+    let mut after = None;
+    let mut positions = vec![];
+    loop {
+        let logs = events_web3_dal
+            .get_logs_after(filter.clone(), after, 3)
+            .await
+            .unwrap();
+        positions.extend(logs.iter().map(|log| {
+            (
+                log.block_number.unwrap().as_u32(),
+                log.log_index.unwrap().as_u32(),
+            )
+        }));
+        match logs.last() {
+            Some(log) if logs.len() == 3 => {
+                after = Some(api::LogCursor {
+                    block_number: MiniblockNumber(log.block_number.unwrap().as_u32()),
+                    log_index: log.log_index.unwrap().as_u32(),
+                    watermark: None,
+                });
+            }
+            _ => break,
+        }
+    }
+    assert_eq!(positions, [(1, 0), (1, 2), (2, 0), (2, 2)]);
+}
+
+#[db_test(dal_crate)]
+async fn logs_bloom_filtering(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let (token, other_token) = (Address::repeat_byte(1), Address::repeat_byte(2));
+    let event = |address| VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address,
+        indexed_topics: vec![ERC20_TRANSFER_TOPIC],
+        value: vec![],
+    };
+    for number in 1..=3 {
+        let events = vec![event(token)];
+        // The bloom of the 2nd miniblock excludes its log, so that skipping the miniblock is observable.
+        let bloom_events = if number == 2 {
+            vec![event(other_token)]
+        } else {
+            events.clone()
+        };
+        BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+            l2_tx_count: 1,
+            logs_bloom: logs_bloom(&bloom_events),
+            ..create_miniblock_header(number)
+        });
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::random(),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::random(),
+        };
+        EventsDal { storage }.save_events(MiniblockNumber(number), vec![(tx_location, events)]);
+    }
+
+    let filter = api::GetLogsFilter {
+        from_block: MiniblockNumber(1),
+        to_block: Some(api::BlockNumber::Number(3.into())),
+        addresses: vec![token],
+        topics: vec![(1, vec![ERC20_TRANSFER_TOPIC])],
+    };
+    let mut events_web3_dal = EventsWeb3Dal { storage };
+    let log_blocks = |logs: Vec<api::Log>| -> Vec<_> {
+        logs.iter()
+            .map(|log| log.block_number.unwrap().as_u32())
+            .collect()
+    };
+    let logs = events_web3_dal.get_logs(filter.clone(), 10).await.unwrap();
+    assert_eq!(log_blocks(logs), [1, 3]);
+    let second_block_filter = api::GetLogsFilter {
+        from_block: MiniblockNumber(2),
+        to_block: Some(api::BlockNumber::Number(2.into())),
+        ..filter.clone()
+    };
+    assert!(!events_web3_dal
+        .may_contain_logs(&second_block_filter)
+        .await
+        .unwrap());
+    // Filters without addresses and topics don't use the blooms.
+    let unfiltered = api::GetLogsFilter {
+        addresses: vec![],
+        topics: vec![],
+        ..filter.clone()
+    };
+    let logs = events_web3_dal.get_logs(unfiltered, 10).await.unwrap();
+    assert_eq!(log_blocks(logs), [1, 2, 3]);
+    // An empty list of topics at a position matches nothing, same as in the `events` query.
+    let empty_topics = api::GetLogsFilter {
+        topics: vec![(1, vec![])],
+        ..filter.clone()
+    };
+    assert!(!events_web3_dal
+        .may_contain_logs(&empty_topics)
+        .await
+        .unwrap());
+    assert!(events_web3_dal
+        .get_logs(empty_topics, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Miniblocks sealed before the blooms were introduced are always scanned.
+    sqlx::query("UPDATE miniblocks SET logs_bloom = NULL WHERE number = 2")
+        .execute(events_web3_dal.storage.conn())
+        .await
+        .unwrap();
+    let logs = events_web3_dal.get_logs(filter, 10).await.unwrap();
+    assert_eq!(log_blocks(logs), [1, 2, 3]);
+    assert!(events_web3_dal
+        .may_contain_logs(&second_block_filter)
+        .await
+        .unwrap());
+}
+
+#[db_test(dal_crate)]
+async fn miniblock_transaction_receipts(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let txs = [mock_l2_transaction(), mock_l2_transaction()];
+    for tx in &txs {
+        TransactionsDal { storage }.insert_transaction_l2(tx.clone(), mock_tx_execution_metrics());
+    }
+    BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+        hash: H256::repeat_byte(1),
+        l2_tx_count: 2,
+        ..create_miniblock_header(1)
+    });
+    let execution_results: Vec<_> = txs
+        .iter()
+        .map(|tx| TransactionExecutionResult {
+            transaction: tx.clone().into(),
+            hash: tx.hash(),
+            execution_info: Default::default(),
+            execution_status: TxExecutionStatus::Success,
+            refunded_gas: 0,
+            operator_suggested_refund: 0,
+            refund_breakdown: Default::default(),
+            compressed_bytecodes: vec![],
+        })
+        .collect();
+    TransactionsDal { storage }.mark_txs_as_executed_in_miniblock(
+        MiniblockNumber(1),
+        &execution_results,
+        U256::from(1),
+    );
+
+    let tx_location = |index: usize| IncludedTxLocation {
+        tx_hash: txs[index].hash(),
+        tx_index_in_miniblock: index as u32,
+        tx_initiator_address: txs[index].initiator_account(),
+    };
+    let event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: Address::repeat_byte(2),
+        indexed_topics: vec![ERC20_TRANSFER_TOPIC],
+        value: vec![],
+    };
+    EventsDal { storage }.save_events(
+        MiniblockNumber(1),
+        vec![(tx_location(0), vec![event.clone(), event])],
+    );
+    let l2_to_l1_log = zksync_types::l2_to_l1_log::L2ToL1Log {
+        shard_id: 0,
+        is_service: false,
+        tx_number_in_block: 1,
+        sender: Address::repeat_byte(3),
+        key: H256::repeat_byte(4),
+        value: H256::repeat_byte(5),
+    };
+    EventsDal { storage }.save_l2_to_l1_logs(
+        MiniblockNumber(1),
+        vec![(tx_location(1), vec![l2_to_l1_log])],
+    );
+
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let receipts = transactions_web3_dal
+        .get_transaction_receipts_for_miniblock(MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(receipts.len(), 2);
+    assert_eq!(receipts[0].logs.len(), 2);
+    assert!(receipts[0].l2_to_l1_logs.is_empty());
+    assert!(receipts[1].logs.is_empty());
+    assert_eq!(receipts[1].l2_to_l1_logs.len(), 1);
+    // The batched receipts are the same as the ones fetched one by one.
+    for (receipt, tx) in receipts.iter().zip(&txs) {
+        let expected = transactions_web3_dal
+            .get_transaction_receipt(tx.hash())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(*receipt, expected);
+    }
+
+    let receipts = transactions_web3_dal
+        .get_transaction_receipts_for_miniblock(MiniblockNumber(2))
+        .await
+        .unwrap();
+    assert!(receipts.is_empty());
+}
+
+#[db_test(dal_crate)]
+async fn bootloader_usage_reports(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let header = L1BatchHeader::new(L1BatchNumber(1), 0, Default::default(), Default::default());
+    storage
+        .blocks_dal()
+        .insert_l1_batch(header, Default::default());
+
+    let report = api::BootloaderUsageReport {
+        l1_batch_number: L1BatchNumber(1),
+        tx_count: 120,
+        tx_slots: 250,
+        txs_encoding_words: 40_000,
+        txs_encoding_limit: 470_000,
+        compressed_bytecodes_words: 1_000,
+        compressed_bytecodes_limit: 32_767,
+        gas_used: 3_500_000_000,
+        gas_limit: 4_000_000_000,
+        pubdata_bytes: 90_000,
+        max_tx_pubdata_bytes: 24_000,
+        pubdata_limit: 120_000,
+    };
+    let mut usage_dal = BootloaderUsageDal { storage };
+    usage_dal.insert_report(&report);
+    assert_eq!(usage_dal.get_report(L1BatchNumber(1)), Some(report));
+    assert_eq!(usage_dal.get_report(L1BatchNumber(2)), None);
+
+    // Reports are removed together with the reverted L1 batches.
+    storage.blocks_dal().delete_l1_batches(L1BatchNumber(0));
+    assert_eq!(
+        BootloaderUsageDal { storage }.get_report(L1BatchNumber(1)),
+        None
+    );
+}
+
+#[db_test(dal_crate)]
+async fn pending_transaction_by_hash(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let tx = mock_l2_transaction();
+    let tx_hash = tx.hash();
+    TransactionsDal { storage }.insert_transaction_l2(tx, mock_tx_execution_metrics());
+
+    let chain_id = L2ChainId(270);
+    let pending_tx = TransactionsWeb3Dal { storage }
+        .get_transaction(api::TransactionId::Hash(tx_hash), chain_id)
+        .await
+        .unwrap()
+        .expect("pending transaction is not returned");
+    assert_eq!(pending_tx.hash, tx_hash);
+    assert_eq!(pending_tx.block_hash, None);
+    assert_eq!(pending_tx.block_number, None);
+    assert_eq!(pending_tx.transaction_index, None);
+    assert_eq!(pending_tx.l1_batch_number, None);
+    // Pending transactions report the max fee as their gas price.
+    assert_eq!(pending_tx.gas_price, Some(U256::from(250_000_000u32)));
+
+    TransactionsDal { storage }.mark_tx_as_rejected(tx_hash, "rejected: nonce too low");
+    let rejected_tx = TransactionsWeb3Dal { storage }
+        .get_transaction(api::TransactionId::Hash(tx_hash), chain_id)
+        .await
+        .unwrap();
+    assert!(rejected_tx.is_none());
+}
+
+#[db_test(dal_crate)]
+async fn raw_transaction_bytes(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let tx = mock_l2_transaction();
+    let tx_hash = tx.hash();
+    let input = tx.common_data.input_data().unwrap();
+    TransactionsDal { storage }.insert_transaction_l2(tx, mock_tx_execution_metrics());
+
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let raw_tx = transactions_web3_dal
+        .get_raw_transaction_bytes(tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(raw_tx, Some(input));
+    let unknown_tx = transactions_web3_dal
+        .get_raw_transaction_bytes(H256::repeat_byte(1))
+        .await
+        .unwrap();
+    assert_eq!(unknown_tx, None);
+}
+
+#[db_test(dal_crate)]
+async fn transactions_by_account(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let sent_tx = mock_l2_transaction();
+    let account = sent_tx.initiator_account();
+    let mut received_tx = mock_l2_transaction();
+    received_tx.execute.contract_address = account;
+    let other_tx = mock_l2_transaction();
+    let pending_tx = {
+        let mut tx = mock_l2_transaction();
+        tx.execute.contract_address = account;
+        tx
+    };
+    let miniblocks = [vec![sent_tx.clone()], vec![other_tx, received_tx.clone()]];
+    for (number, txs) in (1..).zip(&miniblocks) {
+        BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+            l2_tx_count: txs.len() as u16,
+            ..create_miniblock_header(number)
+        });
+        let execution_results: Vec<_> = txs
+            .iter()
+            .map(|tx| {
+                TransactionsDal { storage }
+                    .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics());
+                TransactionExecutionResult {
+                    transaction: tx.clone().into(),
+                    hash: tx.hash(),
+                    execution_info: Default::default(),
+                    execution_status: TxExecutionStatus::Success,
+                    refunded_gas: 0,
+                    operator_suggested_refund: 0,
+                    refund_breakdown: Default::default(),
+                    compressed_bytecodes: vec![],
+                }
+            })
+            .collect();
+        TransactionsDal { storage }.mark_txs_as_executed_in_miniblock(
+            MiniblockNumber(number),
+            &execution_results,
+            U256::from(1),
+        );
+    }
+    TransactionsDal { storage }.insert_transaction_l2(pending_tx, mock_tx_execution_metrics());
+
+    async fn get_hashes(
+        dal: &mut TransactionsWeb3Dal<'_, '_>,
+        account: Address,
+        direction: api::AccountTxDirection,
+        before: Option<api::TxCursor>,
+        limit: usize,
+    ) -> Vec<H256> {
+        let txs = dal
+            .get_transactions_by_account(account, direction, before, limit, L2ChainId(270))
+            .await
+            .unwrap();
+        txs.into_iter().map(|tx| tx.hash).collect()
+    }
+
+    let dal = &mut TransactionsWeb3Dal { storage };
+    // The pending transaction isn't returned.
+    assert_eq!(
+        get_hashes(dal, account, api::AccountTxDirection::All, None, 10).await,
+        [received_tx.hash(), sent_tx.hash()]
+    );
+    assert_eq!(
+        get_hashes(dal, account, api::AccountTxDirection::Sent, None, 10).await,
+        [sent_tx.hash()]
+    );
+    assert_eq!(
+        get_hashes(dal, account, api::AccountTxDirection::Received, None, 10).await,
+        [received_tx.hash()]
+    );
+    assert_eq!(
+        get_hashes(dal, account, api::AccountTxDirection::All, None, 1).await,
+        [received_tx.hash()]
+    );
+    let cursor = api::TxCursor {
+        block_number: MiniblockNumber(2),
+        index_in_block: 1,
+    };
+    assert_eq!(
+        get_hashes(dal, account, api::AccountTxDirection::All, Some(cursor), 1).await,
+        [sent_tx.hash()]
+    );
+}
+
+#[db_test(dal_crate)]
+async fn pending_transactions_pagination(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut txs: Vec<_> = (0..3).map(|_| mock_l2_transaction()).collect();
+    for (i, tx) in txs.iter_mut().enumerate() {
+        tx.received_timestamp_ms = 1_000_000 + i as u64;
+    }
+    let rejected_tx = mock_l2_transaction();
+    let mut transactions_dal = TransactionsDal { storage };
+    for tx in txs.iter().chain([&rejected_tx]) {
+        transactions_dal.insert_transaction_l2(tx.clone(), mock_tx_execution_metrics());
+    }
+    transactions_dal.mark_tx_as_rejected(rejected_tx.hash(), "rejected: nonce too low");
+
+    let mut transactions_web3_dal = TransactionsWeb3Dal { storage };
+    let first_page = transactions_web3_dal
+        .get_pending_transactions(None, 2)
+        .await
+        .unwrap();
+    let hashes: Vec<_> = first_page.iter().map(|tx| tx.hash).collect();
+    assert_eq!(hashes, [txs[0].hash(), txs[1].hash()]);
+    assert_eq!(first_page[0].from, txs[0].initiator_account());
+    assert_eq!(
+        first_page[0].input,
+        Some(txs[0].execute.calldata.clone().into())
+    );
+
+    let last_tx = first_page.last().unwrap();
+    let cursor = api::PendingTxCursor {
+        received_at: last_tx.received_at,
+        hash: last_tx.hash,
+    };
+    let second_page = transactions_web3_dal
+        .get_pending_transactions(Some(cursor), 2)
+        .await
+        .unwrap();
+    let hashes: Vec<_> = second_page.iter().map(|tx| tx.hash).collect();
+    assert_eq!(hashes, [txs[2].hash()]);
+}
+
+#[db_test(dal_crate)]
+async fn transfers(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let account = Address::repeat_byte(1);
+    let other = Address::repeat_byte(2);
+    let transfer = |number: u32, log_index: u32, from: Address, to: Address| api::Transfer {
+        token_address: Address::repeat_byte(0xee),
+        from,
+        to,
+        amount: U256::from(number * 100 + log_index),
+        transaction_hash: H256::from_low_u64_be(number as u64),
+        block_number: MiniblockNumber(number),
+        log_index,
+    };
+    let miniblocks = [
+        vec![transfer(1, 0, account, other), transfer(1, 3, other, other)],
+        vec![
+            transfer(2, 1, other, account),
+            transfer(2, 2, account, account),
+        ],
+    ];
+    for (number, transfers) in (1..).zip(&miniblocks) {
+        BlocksDal { storage }.insert_miniblock(create_miniblock_header(number));
+        TransfersDal { storage }.insert_transfers(MiniblockNumber(number), transfers);
+    }
+
+    let dal = &mut TransfersWeb3Dal { storage };
+    // The self-transfer is returned once.
+    let account_transfers = dal.get_transfers(account, None, 10).await.unwrap();
+    assert_eq!(
+        account_transfers,
+        [
+            miniblocks[1][1].clone(),
+            miniblocks[1][0].clone(),
+            miniblocks[0][0].clone()
+        ]
+    );
+    let cursor = api::TransferCursor {
+        block_number: MiniblockNumber(2),
+        log_index: 1,
+    };
+    let page = dal.get_transfers(account, Some(cursor), 10).await.unwrap();
+    assert_eq!(page, [miniblocks[0][0].clone()]);
+    let page = dal.get_transfers(other, None, 1).await.unwrap();
+    assert_eq!(page, [miniblocks[1][0].clone()]);
+
+    BlocksDal { storage }.delete_miniblocks(MiniblockNumber(1));
+    let dal = &mut TransfersWeb3Dal { storage };
+    let account_transfers = dal.get_transfers(account, None, 10).await.unwrap();
+    assert_eq!(account_transfers, [miniblocks[0][0].clone()]);
+}
+
+#[db_test(dal_crate)]
+async fn transfers_backfill(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let TransferEventsFixture {
+        from,
+        to,
+        amount,
+        transfer_event,
+        nft_transfer_event,
+        other_event,
+    } = TransferEventsFixture::default();
+    let tx_location = |number: u32| IncludedTxLocation {
+        tx_hash: H256::from_low_u64_be(number as u64),
+        tx_index_in_miniblock: 0,
+        tx_initiator_address: from,
+    };
+    let expected_transfer = |number: u32, log_index: u32| api::Transfer {
+        token_address: transfer_event.address,
+        from,
+        to,
+        amount,
+        transaction_hash: tx_location(number).tx_hash,
+        block_number: MiniblockNumber(number),
+        log_index,
+    };
+
+    for number in 1..=2 {
+        BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+            l2_tx_count: 1,
+            ..create_miniblock_header(number)
+        });
+        EventsDal { storage }.save_events(
+            MiniblockNumber(number),
+            vec![(
+                tx_location(number),
+                vec![
+                    other_event.clone(),
+                    nft_transfer_event.clone(),
+                    transfer_event.clone(),
+                ],
+            )],
+        );
+    }
+    // The transfers of the 2nd miniblock were extracted at seal time, and the backfill must skip them.
+    TransfersDal { storage }.insert_transfers(MiniblockNumber(2), &[expected_transfer(2, 2)]);
+    sqlx::query("INSERT INTO transfers_backfill (next_miniblock_number) VALUES (2)")
+        .execute(storage.conn())
+        .await
+        .unwrap();
+
+    let mut transfers_dal = TransfersDal { storage };
+    assert_eq!(transfers_dal.backfill_transfers(2), 2);
+    assert_eq!(transfers_dal.backfill_transfers(2), 1);
+    assert_eq!(transfers_dal.backfill_transfers(2), 0);
+
+    let transfers = TransfersWeb3Dal { storage }
+        .get_transfers(from, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(
+        transfers,
+        [expected_transfer(2, 2), expected_transfer(1, 2)]
+    );
+}
+
+#[db_test(dal_crate)]
+async fn statement_timeout(connection_pool: ConnectionPool) {
+    // The timeout is local to the test transaction, so it doesn't leak to other tests.
+    let (mut storage, backend_transaction) = connection_pool
+        .access_cancellable_storage(Some(Duration::from_millis(10)))
+        .await
+        .unwrap();
+    assert!(backend_transaction.pid > 0);
+    let err = sqlx::query("SELECT pg_sleep(1)")
+        .execute(storage.conn())
+        .await
+        .unwrap_err();
+    assert!(is_statement_timeout(&err));
+    assert!(!is_statement_timeout(&SqlxError::RowNotFound));
+}
+
+#[db_test(dal_crate)]
+async fn miniblock_by_timestamp(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    for (number, timestamp) in [(1, 10), (2, 20), (3, 20), (4, 30)] {
+        BlocksDal { storage }.insert_miniblock(MiniblockHeader {
+            timestamp,
+            ..create_miniblock_header(number)
+        });
+    }
+
+    let dal = &mut BlocksWeb3Dal { storage };
+    assert_eq!(dal.get_miniblock_by_timestamp(5).unwrap(), None);
+    assert_eq!(
+        dal.get_miniblock_by_timestamp(10).unwrap(),
+        Some((MiniblockNumber(1), 10))
+    );
+    // The last one of the miniblocks with the same timestamp is returned.
+    assert_eq!(
+        dal.get_miniblock_by_timestamp(25).unwrap(),
+        Some((MiniblockNumber(3), 20))
+    );
+    assert_eq!(
+        dal.get_miniblock_by_timestamp(100).unwrap(),
+        Some((MiniblockNumber(4), 30))
+    );
+}
+
+#[db_test(dal_crate)]
+async fn encrypted_transactions(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut dal = EncryptedTransactionsDal { storage };
+    for i in [3, 1, 2, 4] {
+        assert!(dal.insert_encrypted_transaction(H256::repeat_byte(i), vec![i; 10]));
+    }
+    // The same payload can't be submitted twice.
+    assert!(!dal.insert_encrypted_transaction(H256::repeat_byte(1), vec![1; 10]));
+
+    // The transactions are inserted within the same DB transaction, so they share `received_at`
+    // and are ordered by the hash.
+    let pending = dal.get_pending_encrypted_transactions(3);
+    let pending_hashes: Vec<_> = pending.iter().map(|tx| tx.hash).collect();
+    assert_eq!(pending_hashes, [1, 2, 3].map(H256::repeat_byte));
+    assert_eq!(pending[0].payload, vec![1; 10]);
+
+    dal.mark_encrypted_transaction_as_decrypted(H256::repeat_byte(1), H256::repeat_byte(0xff));
+    dal.mark_encrypted_transaction_as_rejected(H256::repeat_byte(2), "failed to decrypt");
+    let pending_hashes: Vec<_> = dal
+        .get_pending_encrypted_transactions(10)
+        .into_iter()
+        .map(|tx| tx.hash)
+        .collect();
+    assert_eq!(pending_hashes, [3, 4].map(H256::repeat_byte));
+
+    let row =
+        sqlx::query("SELECT status, tx_hash, error FROM encrypted_transactions WHERE hash = $1")
+            .bind(H256::repeat_byte(1).as_bytes())
+            .fetch_one(dal.storage.conn())
+            .await
+            .unwrap();
+    assert_eq!(row.get::<String, _>("status"), "decrypted");
+    assert_eq!(
+        row.get::<Option<Vec<u8>>, _>("tx_hash"),
+        Some(vec![0xff; 32])
+    );
+    let row =
+        sqlx::query("SELECT status, tx_hash, error FROM encrypted_transactions WHERE hash = $1")
+            .bind(H256::repeat_byte(2).as_bytes())
+            .fetch_one(dal.storage.conn())
+            .await
+            .unwrap();
+    assert_eq!(row.get::<String, _>("status"), "rejected");
+    assert_eq!(
+        row.get::<Option<String>, _>("error").as_deref(),
+        Some("failed to decrypt")
+    );
+
+    // Decryption shares are only requested from the members that haven't published them yet.
+    let share = |member_index: u32| DecryptionShare {
+        member_index,
+        point: vec![member_index as u8; 64],
+    };
+    dal.insert_decryption_share(H256::repeat_byte(3), &share(2));
+    dal.insert_decryption_share(H256::repeat_byte(3), &share(1));
+    // Publishing the same share twice is a no-op.
+    dal.insert_decryption_share(H256::repeat_byte(3), &share(1));
+    let without_share: Vec<_> = dal
+        .get_pending_encrypted_transactions_without_share(1, 10)
+        .into_iter()
+        .map(|tx| tx.hash)
+        .collect();
+    assert_eq!(without_share, [H256::repeat_byte(4)]);
+    let without_share = dal.get_pending_encrypted_transactions_without_share(3, 10);
+    assert_eq!(without_share.len(), 2);
+
+    let shares = dal.get_decryption_shares(&[3, 4].map(H256::repeat_byte));
+    assert_eq!(shares.len(), 1);
+    assert_eq!(shares[&H256::repeat_byte(3)], [share(1), share(2)]);
+}
+
+#[db_test(dal_crate)]
+async fn overriding_connection_pool_sizes(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut pool_sizes_dal = ConnectionPoolSizesDal { storage };
+    assert!(pool_sizes_dal.get_pool_sizes().is_empty());
+
+    pool_sizes_dal.set_pool_size("api_http", 20);
+    pool_sizes_dal.set_pool_size("api_ws", 10);
+    pool_sizes_dal.set_pool_size("api_http", 40);
+    assert_eq!(
+        pool_sizes_dal.get_pool_sizes(),
+        HashMap::from([("api_http".to_owned(), 40), ("api_ws".to_owned(), 10)])
+    );
+
+    pool_sizes_dal.remove_pool_size("api_http");
+    assert_eq!(
+        pool_sizes_dal.get_pool_sizes(),
+        HashMap::from([("api_ws".to_owned(), 10)])
+    );
+}
+
+#[db_test(dal_crate)]
+async fn block_gas_limits_are_keyed_by_protocol_version(connection_pool: ConnectionPool) {
+    let storage = &mut connection_pool.access_test_storage().await;
+    let mut blocks_dal = BlocksDal { storage };
+    for number in 1..=4 {
+        blocks_dal.insert_miniblock(create_miniblock_header(number));
+    }
+
+    let version = |id, activated_at_miniblock, block_gas_limit| ProtocolVersion {
+        id,
+        activated_at_miniblock: MiniblockNumber(activated_at_miniblock),
+        block_gas_limit,
+        max_allowed_tx_gas_limit: block_gas_limit / 2,
+    };
+    let mut versions_dal = ProtocolVersionsDal { storage };
+    assert_eq!(versions_dal.get_latest_protocol_version(), None);
+    versions_dal.register_protocol_version(version(1, 2, 1_000));
+    versions_dal.register_protocol_version(version(2, 4, 2_000));
+    // Limits of the recorded version are not overwritten.
+    let stored = versions_dal.register_protocol_version(version(2, 5, 3_000));
+    assert_eq!(stored, version(2, 4, 2_000));
+    assert_eq!(versions_dal.get_latest_protocol_version(), Some(stored));
+
+    let versions =
+        versions_dal.get_protocol_versions_for_miniblocks(MiniblockNumber(1), MiniblockNumber(4));
+    assert_eq!(versions, [version(1, 2, 1_000), version(2, 4, 2_000)]);
+    let versions =
+        versions_dal.get_protocol_versions_for_miniblocks(MiniblockNumber(3), MiniblockNumber(3));
+    assert_eq!(versions, [version(1, 2, 1_000)]);
+    assert_eq!(
+        ProtocolVersion::of_miniblock(&versions, MiniblockNumber(3)),
+        Some(&version(1, 2, 1_000))
+    );
+
+    let mut blocks_web3_dal = BlocksWeb3Dal { storage };
+    let mut block_gas_limit = |number: u32| {
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(number.into()));
+        blocks_web3_dal
+            .get_block_by_web3_block_id(block_id, false, L2ChainId(270), 500)
+            .unwrap()
+            .unwrap()
+            .gas_limit
+    };
+    assert_eq!(block_gas_limit(1), U256::from(500));
+    assert_eq!(block_gas_limit(3), U256::from(1_000));
+    assert_eq!(block_gas_limit(4), U256::from(2_000));
+}
@@ -1,7 +1,10 @@
 use crate::models::storage_token::StorageTokenMarketVolume;
 use crate::StorageProcessor;
 use num::{rational::Ratio, BigUint};
+use sqlx::postgres::types::PgInterval;
 use sqlx::types::chrono::Utc;
+use std::time::Duration;
+use zksync_config::constants::ERC20_TRANSFER_TOPIC;
 use zksync_types::{
     tokens::{TokenInfo, TokenMarketVolume, TokenMetadata, TokenPrice},
     Address, MiniblockNumber, ACCOUNT_CODE_STORAGE_ADDRESS,
@@ -198,4 +201,101 @@ impl TokensDal<'_, '_> {
             .unwrap();
         })
     }
+
+    /// Registers the contracts that emitted an ERC20 `Transfer` event in the given range of miniblocks
+    /// as L2 tokens, so that their metadata is read by the token metadata fetcher. Returns the number
+    /// of the newly observed tokens.
+    pub fn observe_l2_tokens(
+        &mut self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+    ) -> usize {
+        async_std::task::block_on(async {
+            // ERC721 `Transfer` event has the same signature, but the token ID is indexed.
+            sqlx::query!(
+                "
+                INSERT INTO l2_token_metadata (l2_address, first_seen_miniblock, created_at, updated_at)
+                SELECT address, MIN(miniblock_number), now(), now() FROM events
+                WHERE miniblock_number BETWEEN $1 AND $2 AND topic1 = $3 AND topic4 = ''
+                GROUP BY address
+                ON CONFLICT (l2_address) DO NOTHING
+                ",
+                from_miniblock.0 as i64,
+                to_miniblock.0 as i64,
+                ERC20_TRANSFER_TOPIC.as_bytes()
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap()
+            .rows_affected() as usize
+        })
+    }
+
+    /// Returns the number of the last miniblock in which a new L2 token was observed.
+    pub fn get_last_l2_token_observed_miniblock(&mut self) -> Option<MiniblockNumber> {
+        async_std::task::block_on(async {
+            sqlx::query!("SELECT MAX(first_seen_miniblock) as \"number\" FROM l2_token_metadata")
+                .fetch_one(self.storage.conn())
+                .await
+                .unwrap()
+                .number
+                .map(|number| MiniblockNumber(number as u32))
+        })
+    }
+
+    /// Returns the L2 tokens that weren't processed yet or were processed earlier than `refresh_interval` ago.
+    pub fn get_l2_tokens_for_metadata_refresh(
+        &mut self,
+        refresh_interval: Duration,
+        limit: usize,
+    ) -> Vec<Address> {
+        async_std::task::block_on(async {
+            let refresh_pg_interval = PgInterval {
+                months: 0,
+                days: 0,
+                microseconds: refresh_interval.as_micros() as i64,
+            };
+            sqlx::query!(
+                "
+                SELECT l2_address FROM l2_token_metadata
+                WHERE refreshed_at IS NULL OR refreshed_at < now() - $1::interval
+                ORDER BY refreshed_at NULLS FIRST
+                LIMIT $2
+                ",
+                refresh_pg_interval,
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| Address::from_slice(&row.l2_address))
+            .collect()
+        })
+    }
+
+    pub fn set_l2_token_metadata(
+        &mut self,
+        l2_address: &Address,
+        name: Option<&str>,
+        symbol: Option<&str>,
+        decimals: Option<u8>,
+    ) {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                UPDATE l2_token_metadata
+                SET name = $2, symbol = $3, decimals = $4, refreshed_at = now(), updated_at = now()
+                WHERE l2_address = $1
+                ",
+                l2_address.as_bytes(),
+                name,
+                symbol,
+                decimals.map(i32::from)
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
 }
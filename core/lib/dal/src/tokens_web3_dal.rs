@@ -1,9 +1,12 @@
-use crate::models::storage_token::{StorageTokenMetadata, StorageTokenPrice};
+use crate::models::storage_token::{
+    StorageL2TokenInfo, StorageTokenInfo, StorageTokenMetadata, StorageTokenPrice,
+};
 use crate::SqlxError;
 use crate::StorageProcessor;
 use num::{rational::Ratio, BigUint};
 use sqlx::postgres::types::PgInterval;
 use zksync_types::{
+    api::L2TokenInfo,
     tokens::{TokenInfo, TokenMetadata, TokenPrice},
     Address,
 };
@@ -121,4 +124,108 @@ impl TokensWeb3Dal<'_, '_> {
             Ok(storage_token_metadata.map(Into::into))
         })
     }
+
+    /// Returns the token bridged from the given L1 address. The metadata from the token list takes precedence
+    /// over the one provided by the token contract.
+    pub fn get_token_by_l1_address(
+        &mut self,
+        l1_address: &Address,
+    ) -> Result<Option<TokenInfo>, SqlxError> {
+        async_std::task::block_on(async {
+            let token = sqlx::query_as!(
+                StorageTokenInfo,
+                r#"
+                SELECT
+                    l1_address, l2_address,
+                    COALESCE(token_list_name, name) as "name!",
+                    COALESCE(token_list_symbol, symbol) as "symbol!",
+                    COALESCE(token_list_decimals, decimals) as "decimals!"
+                FROM tokens WHERE l1_address = $1
+                "#,
+                l1_address.as_bytes(),
+            )
+            .fetch_optional(self.storage.conn())
+            .await?;
+
+            Ok(token.map(Into::into))
+        })
+    }
+
+    /// Returns the token deployed by the bridge to the given L2 address.
+    pub fn get_token_by_l2_address(
+        &mut self,
+        l2_address: &Address,
+    ) -> Result<Option<TokenInfo>, SqlxError> {
+        async_std::task::block_on(async {
+            let token = sqlx::query_as!(
+                StorageTokenInfo,
+                r#"
+                SELECT
+                    l1_address, l2_address,
+                    COALESCE(token_list_name, name) as "name!",
+                    COALESCE(token_list_symbol, symbol) as "symbol!",
+                    COALESCE(token_list_decimals, decimals) as "decimals!"
+                FROM tokens WHERE l2_address = $1
+                "#,
+                l2_address.as_bytes(),
+            )
+            .fetch_optional(self.storage.conn())
+            .await?;
+
+            Ok(token.map(Into::into))
+        })
+    }
+
+    /// Returns all the bridged tokens, including the ones that aren't well-known, ordered by the L1 address.
+    /// If `after` is specified, only the tokens with the greater L1 address are returned.
+    pub fn get_bridged_tokens(
+        &mut self,
+        offset: u32,
+        limit: u32,
+        after: Option<Address>,
+    ) -> Result<Vec<TokenInfo>, SqlxError> {
+        async_std::task::block_on(async {
+            let tokens = sqlx::query_as!(
+                StorageTokenInfo,
+                r#"
+                SELECT
+                    l1_address, l2_address,
+                    COALESCE(token_list_name, name) as "name!",
+                    COALESCE(token_list_symbol, symbol) as "symbol!",
+                    COALESCE(token_list_decimals, decimals) as "decimals!"
+                FROM tokens
+                WHERE l1_address > $1
+                ORDER BY l1_address
+                OFFSET $2 LIMIT $3
+                "#,
+                // Empty byte string precedes all the addresses.
+                after.map_or_else(Vec::new, |address| address.as_bytes().to_vec()),
+                offset as i64,
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await?;
+
+            Ok(tokens.into_iter().map(Into::into).collect())
+        })
+    }
+
+    /// Returns the metadata read from the token contract by the token metadata fetcher.
+    pub fn get_l2_token_info(
+        &mut self,
+        l2_address: &Address,
+    ) -> Result<Option<L2TokenInfo>, SqlxError> {
+        async_std::task::block_on(async {
+            let token = sqlx::query_as!(
+                StorageL2TokenInfo,
+                "SELECT l2_address, name, symbol, decimals, refreshed_at FROM l2_token_metadata
+                WHERE l2_address = $1",
+                l2_address.as_bytes(),
+            )
+            .fetch_optional(self.storage.conn())
+            .await?;
+
+            Ok(token.map(Into::into))
+        })
+    }
 }
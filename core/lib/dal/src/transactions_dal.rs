@@ -13,10 +13,10 @@ use zksync_types::tx::tx_execution_info::TxExecutionStatus;
 use zksync_types::{get_nonce_key, U256};
 use zksync_types::{
     l1::L1Tx, l2::L2Tx, tx::TransactionExecutionResult, vm_trace::VmExecutionTrace, Address,
-    ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce, PriorityOpId,
-    Transaction, H256,
+    Execute, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce,
+    PriorityOpId, Transaction, H2048, H256,
 };
-use zksync_utils::{h256_to_u32, u256_to_big_decimal};
+use zksync_utils::{bigdecimal_to_u256, bytecode::hash_bytecode, h256_to_u32, u256_to_big_decimal};
 
 use crate::models::storage_transaction::StorageTransaction;
 use crate::time_utils::pg_interval_from_duration;
@@ -58,6 +58,9 @@ impl TransactionsDal<'_, '_> {
             let gas_per_pubdata_limit = u256_to_big_decimal(tx.common_data.gas_per_pubdata_limit);
             let value = u256_to_big_decimal(tx.execute.value);
             let tx_format = tx.common_data.tx_format() as i32;
+            let calldata_length = tx.execute.calldata.len() as i32;
+            let factory_dep_hashes = factory_dep_hashes(&tx.execute);
+            let deadline_block = tx.common_data.deadline_block as i64;
 
             let to_mint = u256_to_big_decimal(tx.common_data.to_mint);
             let refund_recipient = tx.common_data.refund_recipient.as_bytes().to_vec();
@@ -93,6 +96,10 @@ impl TransactionsDal<'_, '_> {
                     l1_tx_mint,
                     l1_tx_refund_recipient,
 
+                    calldata_length,
+                    factory_dep_hashes,
+                    l1_deadline_timestamp,
+
                     received_at,
                     created_at,
                     updated_at
@@ -100,7 +107,7 @@ impl TransactionsDal<'_, '_> {
                 VALUES
                     (
                         $1, TRUE, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12,
-                        $13, $14, $15, $16, $17, $18, now(), now()
+                        $13, $14, $15, $16, $17, $18, $19, $20, $21, now(), now()
                     )
                 ",
                 tx_hash,
@@ -120,6 +127,9 @@ impl TransactionsDal<'_, '_> {
                 tx_format,
                 to_mint,
                 refund_recipient,
+                calldata_length,
+                factory_dep_hashes.as_deref(),
+                deadline_block,
                 received_at,
             )
             .fetch_optional(self.storage.conn())
@@ -155,11 +165,14 @@ impl TransactionsDal<'_, '_> {
                 .expect("Data is mandatory")
                 .data;
             let value = u256_to_big_decimal(tx.execute.value);
+            let calldata_length = tx.execute.calldata.len() as i32;
+            let factory_dep_hashes = factory_dep_hashes(&tx.execute);
             let paymaster = tx.common_data.paymaster_params.paymaster.0.to_vec();
             let paymaster_input = tx.common_data.paymaster_params.paymaster_input.clone();
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
             let received_at = NaiveDateTime::from_timestamp_opt(secs, nanosecs).unwrap();
+            let valid_until = tx.common_data.valid_until.map(|timestamp| timestamp as i64);
             // Besides just adding or updating(on conflict) the record, we want to extract some info
             // from the query below, to indicate what actually happened:
             // 1) transaction is added
@@ -192,6 +205,9 @@ impl TransactionsDal<'_, '_> {
                     paymaster_input,
                     execution_info,
                     received_at,
+                    calldata_length,
+                    factory_dep_hashes,
+                    valid_until,
                     created_at,
                     updated_at
                 )
@@ -199,7 +215,7 @@ impl TransactionsDal<'_, '_> {
                     (
                         $1, FALSE, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15,
                         jsonb_build_object('gas_used', $16::bigint, 'storage_writes', $17::int, 'contracts_used', $18::int),
-                        $19, now(), now()
+                        $19, $20, $21, $22, now(), now()
                     )
                 ON CONFLICT
                     (initiator_address, nonce)
@@ -220,6 +236,9 @@ impl TransactionsDal<'_, '_> {
                         execution_info=jsonb_build_object('gas_used', $16::bigint, 'storage_writes', $17::int, 'contracts_used', $18::int),
                         in_mempool=FALSE,
                         received_at=$19,
+                        calldata_length=$20,
+                        factory_dep_hashes=$21,
+                        valid_until=$22,
                         created_at=now(),
                         updated_at=now(),
                         error = NULL
@@ -244,7 +263,10 @@ impl TransactionsDal<'_, '_> {
                 exec_info.gas_used as i64,
                 (exec_info.initial_storage_writes + exec_info.repeated_storage_writes) as i32,
                 exec_info.contracts_used as i32,
-                received_at
+                received_at,
+                calldata_length,
+                factory_dep_hashes.as_deref(),
+                valid_until,
             )
                 .fetch_optional(self.storage.conn())
                 .await
@@ -334,6 +356,7 @@ impl TransactionsDal<'_, '_> {
             let mut l1_errors = Vec::with_capacity(transactions.len());
             let mut l1_execution_infos = Vec::with_capacity(transactions.len());
             let mut l1_refunded_gas = Vec::with_capacity(transactions.len());
+            let mut l1_refund_breakdowns = Vec::with_capacity(transactions.len());
 
             let mut l2_hashes = Vec::with_capacity(transactions.len());
             let mut l2_values = Vec::with_capacity(transactions.len());
@@ -355,6 +378,9 @@ impl TransactionsDal<'_, '_> {
             let mut l2_max_priority_fees_per_gas = Vec::with_capacity(transactions.len());
             let mut l2_gas_per_pubdata_limit = Vec::with_capacity(transactions.len());
             let mut l2_refunded_gas = Vec::with_capacity(transactions.len());
+            let mut l2_refund_breakdowns = Vec::with_capacity(transactions.len());
+            let mut l2_calldata_lengths = Vec::with_capacity(transactions.len());
+            let mut l2_factory_dep_hashes = Vec::with_capacity(transactions.len());
 
             transactions
                 .iter()
@@ -366,8 +392,10 @@ impl TransactionsDal<'_, '_> {
                         transaction,
                         execution_status,
                         refunded_gas,
+                        refund_breakdown,
                         ..
                     } = tx_res;
+                    let refund_breakdown = serde_json::to_value(refund_breakdown).unwrap();
 
                     // Bootloader currently doesn't return detailed errors.
                     let error = match execution_status {
@@ -385,6 +413,7 @@ impl TransactionsDal<'_, '_> {
                             l1_errors.push(error.unwrap_or_default());
                             l1_execution_infos.push(serde_json::to_value(execution_info).unwrap());
                             l1_refunded_gas.push(*refunded_gas as i64);
+                            l1_refund_breakdowns.push(refund_breakdown);
                         }
                         ExecuteTransactionCommon::L2(common_data) => {
                             let data = serde_json::to_value(&transaction.execute).unwrap();
@@ -420,6 +449,15 @@ impl TransactionsDal<'_, '_> {
                             l2_gas_per_pubdata_limit
                                 .push(u256_to_big_decimal(common_data.fee.gas_per_pubdata_limit));
                             l2_refunded_gas.push(*refunded_gas as i64);
+                            l2_refund_breakdowns.push(refund_breakdown);
+                            l2_calldata_lengths.push(transaction.execute.calldata.len() as i32);
+                            // Multidimensional arrays can't be unnested row by row, so the hashes
+                            // are passed concatenated and split back in the query.
+                            l2_factory_dep_hashes.push(
+                                factory_dep_hashes(&transaction.execute)
+                                    .unwrap_or_default()
+                                    .concat(),
+                            );
                         }
                     }
                 });
@@ -449,6 +487,16 @@ impl TransactionsDal<'_, '_> {
                                 effective_gas_price = data_table.effective_gas_price,
                                 execution_info = data_table.new_execution_info,
                                 refunded_gas = data_table.refunded_gas,
+                                refund_breakdown = data_table.refund_breakdown,
+                                calldata_length = data_table.calldata_length,
+                                factory_dep_hashes = NULLIF(
+                                    ARRAY(
+                                        SELECT substring(data_table.factory_dep_hashes FROM i FOR 32)
+                                        FROM generate_series(1, length(data_table.factory_dep_hashes), 32) AS i
+                                        ORDER BY i
+                                    ),
+                                    '{}'
+                                ),
                                 value = data_table.value,
                                 contract_address = data_table.contract_address,
                                 paymaster = data_table.paymaster,
@@ -477,7 +525,10 @@ impl TransactionsDal<'_, '_> {
                                     UNNEST($17::numeric[]) as value,
                                     UNNEST($18::bytea[]) as contract_address,
                                     UNNEST($19::bytea[]) as paymaster,
-                                    UNNEST($20::bytea[]) as paymaster_input
+                                    UNNEST($20::bytea[]) as paymaster_input,
+                                    UNNEST($22::jsonb[]) as refund_breakdown,
+                                    UNNEST($23::int[]) as calldata_length,
+                                    UNNEST($24::bytea[]) as factory_dep_hashes
                             ) AS data_table
                         WHERE transactions.initiator_address=data_table.initiator_address 
                         AND transactions.nonce=data_table.nonce
@@ -503,6 +554,9 @@ impl TransactionsDal<'_, '_> {
                     &l2_paymaster,
                     &l2_paymaster_input,
                     miniblock_number.0 as i32,
+                    &l2_refund_breakdowns,
+                    &l2_calldata_lengths,
+                    &l2_factory_dep_hashes,
                 )
                 .execute(self.storage.conn())
                 .await
@@ -521,6 +575,7 @@ impl TransactionsDal<'_, '_> {
                                 in_mempool=FALSE,
                                 execution_info = execution_info || data_table.new_execution_info,
                                 refunded_gas = data_table.refunded_gas,
+                                refund_breakdown = data_table.refund_breakdown,
                                 updated_at = now()
                         FROM
                             (
@@ -529,7 +584,8 @@ impl TransactionsDal<'_, '_> {
                                     UNNEST($3::integer[]) AS index_in_block,
                                     UNNEST($4::varchar[]) AS error,
                                     UNNEST($5::jsonb[]) AS new_execution_info,
-                                    UNNEST($6::bigint[]) as refunded_gas
+                                    UNNEST($6::bigint[]) as refunded_gas,
+                                    UNNEST($7::jsonb[]) as refund_breakdown
                             ) AS data_table
                         WHERE transactions.hash = data_table.hash
                     "#,
@@ -538,7 +594,8 @@ impl TransactionsDal<'_, '_> {
                     &l1_indices_in_block,
                     &l1_errors,
                     &l1_execution_infos,
-                    &l1_refunded_gas
+                    &l1_refunded_gas,
+                    &l1_refund_breakdowns
                 )
                 .execute(self.storage.conn())
                 .await
@@ -547,6 +604,33 @@ impl TransactionsDal<'_, '_> {
         })
     }
 
+    pub fn set_logs_blooms(&mut self, logs_blooms: &[(H256, H2048)]) {
+        async_std::task::block_on(async {
+            let (hashes, blooms): (Vec<_>, Vec<_>) = logs_blooms
+                .iter()
+                .map(|(hash, bloom)| (hash.as_bytes().to_vec(), bloom.as_bytes().to_vec()))
+                .unzip();
+            sqlx::query!(
+                "
+                    UPDATE transactions
+                    SET logs_bloom = data_table.logs_bloom, updated_at = now()
+                    FROM
+                        (
+                            SELECT
+                                UNNEST($1::bytea[]) AS hash,
+                                UNNEST($2::bytea[]) AS logs_bloom
+                        ) AS data_table
+                    WHERE transactions.hash = data_table.hash
+                ",
+                &hashes,
+                &blooms
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
     pub fn mark_tx_as_rejected(&mut self, transaction_hash: H256, error: &str) {
         async_std::task::block_on(async {
             // If the rejected tx has been replaced, it means that this tx hash does not exist in the database
@@ -565,11 +649,26 @@ impl TransactionsDal<'_, '_> {
         })
     }
 
+    /// Returns the number of transactions included into the miniblocks after `miniblock_number`.
+    pub fn get_tx_count_after_miniblock(&mut self, miniblock_number: MiniblockNumber) -> usize {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "SELECT COUNT(*) FROM transactions WHERE miniblock_number > $1",
+                miniblock_number.0 as i64
+            )
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap()
+            .count
+            .unwrap() as usize
+        })
+    }
+
     pub fn reset_transactions_state(&mut self, miniblock_number: MiniblockNumber) {
         async_std::task::block_on(async {
             sqlx::query!(
                 "UPDATE transactions
-                    SET l1_batch_number = NULL, miniblock_number = NULL, error = NULL, index_in_block = NULL, execution_info = '{}'
+                    SET l1_batch_number = NULL, miniblock_number = NULL, error = NULL, index_in_block = NULL, execution_info = '{}', refund_breakdown = NULL
                     WHERE miniblock_number > $1",
                 miniblock_number.0 as i64
             )
@@ -596,6 +695,26 @@ impl TransactionsDal<'_, '_> {
         })
     }
 
+    /// Removes the pending L2 transactions that expired before `timestamp` (in seconds),
+    /// returning their hashes.
+    pub fn remove_expired_txs(&mut self, timestamp: u64) -> Vec<H256> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "DELETE FROM transactions \
+                 WHERE miniblock_number IS NULL AND valid_until IS NOT NULL AND valid_until < $1 \
+                 AND is_priority = FALSE \
+                 RETURNING hash",
+                timestamp as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect()
+        })
+    }
+
     /// Fetches new updates for mempool
     /// Returns new transactions and current nonces for related accounts
     /// Latter is only used to bootstrap mempool for given account
@@ -640,6 +759,11 @@ impl TransactionsDal<'_, '_> {
                     FROM transactions
                     WHERE miniblock_number IS NULL AND in_mempool = FALSE AND error IS NULL
                         AND (is_priority = TRUE OR (max_fee_per_gas >= $2 and gas_per_pubdata_limit >= $3))
+                        AND NOT EXISTS (
+                            SELECT 1 FROM quarantined_transactions
+                            WHERE quarantined_transactions.hash = transactions.hash
+                                AND quarantined_transactions.quarantined_at IS NOT NULL
+                        )
                     ORDER BY is_priority DESC, priority_op_id, received_at
                     LIMIT $1
                 ) as subquery
@@ -723,6 +847,53 @@ impl TransactionsDal<'_, '_> {
         })
     }
 
+    /// Returns the serial IDs and hashes of the priority operations executed in the L1 batch,
+    /// in the execution order.
+    pub fn get_l1_batch_priority_ops(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Vec<(PriorityOpId, H256)> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"
+                    SELECT priority_op_id as "priority_op_id!", hash FROM transactions
+                    WHERE l1_batch_number = $1 AND is_priority = true
+                    ORDER BY l1_batch_tx_index
+                "#,
+                l1_batch_number.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| {
+                (
+                    PriorityOpId(row.priority_op_id as u64),
+                    H256::from_slice(&row.hash),
+                )
+            })
+            .collect()
+        })
+    }
+
+    /// Returns the serial ID of the last priority operation executed before the L1 batch.
+    pub fn last_priority_id_before_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<PriorityOpId> {
+        async_std::task::block_on(async {
+            let op_id = sqlx::query!(
+                r#"SELECT MAX(priority_op_id) as "op_id" from transactions where is_priority = true AND l1_batch_number < $1"#,
+                l1_batch_number.0 as i64
+            )
+            .fetch_optional(self.storage.conn())
+            .await
+            .unwrap()?
+            .op_id?;
+            Some(PriorityOpId(op_id as u64))
+        })
+    }
+
     pub fn next_priority_id(&mut self) -> PriorityOpId {
         async_std::task::block_on(async {
             sqlx::query!(
@@ -798,6 +969,45 @@ impl TransactionsDal<'_, '_> {
         })
     }
 
+    /// Returns up to `limit` L2 transactions executed in the given miniblock range (inclusive) together with
+    /// the miniblock number and the fee paid. The sample is ordered by the transaction hash, so it is pseudo-random,
+    /// yet stable between the calls.
+    pub fn get_executed_l2_txs_sample(
+        &mut self,
+        from_miniblock: MiniblockNumber,
+        to_miniblock: MiniblockNumber,
+        limit: usize,
+    ) -> Vec<(MiniblockNumber, Transaction, U256)> {
+        async_std::task::block_on(async {
+            sqlx::query_as!(
+                StorageTransaction,
+                "
+                    SELECT * FROM transactions
+                    WHERE miniblock_number BETWEEN $1 AND $2 AND is_priority = FALSE
+                    ORDER BY hash
+                    LIMIT $3
+                ",
+                from_miniblock.0 as i64,
+                to_miniblock.0 as i64,
+                limit as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tx| {
+                let miniblock_number = MiniblockNumber(tx.miniblock_number.unwrap() as u32);
+                let gas_limit = bigdecimal_to_u256(tx.gas_limit.clone().unwrap_or_default());
+                let effective_gas_price =
+                    bigdecimal_to_u256(tx.effective_gas_price.clone().unwrap_or_default());
+                let paid_fee = gas_limit.saturating_sub(U256::from(tx.refunded_gas as u64))
+                    * effective_gas_price;
+                (miniblock_number, tx.into(), paid_fee)
+            })
+            .collect()
+        })
+    }
+
     pub fn get_tx_locations(&mut self, l1_batch_number: L1BatchNumber) -> TxLocations {
         async_std::task::block_on(async {
             sqlx::query!(
@@ -826,4 +1036,70 @@ impl TransactionsDal<'_, '_> {
                 .collect()
         })
     }
+
+    /// Fills the columns extracted from the `data` JSONB for up to `limit` transactions inserted before
+    /// the columns were added. Returns the number of updated transactions, so 0 means that the backfill
+    /// is complete.
+    ///
+    /// Factory dependency hashes are computed the same way as `hash_bytecode`: SHA-256 of the bytecode
+    /// with the first 4 bytes replaced by the version byte, a zero byte and the length of the bytecode in words.
+    pub fn backfill_execute_columns(&mut self, limit: usize) -> usize {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                r#"
+                UPDATE transactions
+                SET contract_address = COALESCE(
+                        contract_address,
+                        decode(substring(data->>'contractAddress' FROM 3), 'hex')
+                    ),
+                    transfer_to = decode(substring(data->>'to' FROM 3), 'hex'),
+                    calldata_length = COALESCE((length(data->>'calldata') - 2) / 2, 0),
+                    factory_dep_hashes = (
+                        SELECT NULLIF(
+                            ARRAY(
+                                SELECT overlay(
+                                    sha256(bytecode) PLACING
+                                    ('\x0100'::bytea || int2send((length(bytecode) / 32)::smallint)) FROM 1 FOR 4
+                                )
+                                FROM (
+                                    SELECT dep.index, decode(string_agg(lpad(to_hex(byte.value::int), 2, '0'), '' ORDER BY byte.index), 'hex') AS bytecode
+                                    FROM jsonb_array_elements(data->'factoryDeps') WITH ORDINALITY AS dep(value, index),
+                                        jsonb_array_elements_text(dep.value) WITH ORDINALITY AS byte(value, index)
+                                    GROUP BY dep.index
+                                ) AS deps
+                                ORDER BY deps.index
+                            ),
+                            '{}'
+                        )
+                        WHERE jsonb_typeof(data->'factoryDeps') = 'array'
+                    )
+                WHERE hash IN (
+                    SELECT hash FROM transactions
+                    WHERE calldata_length IS NULL
+                    LIMIT $1
+                )
+                "#,
+                limit as i64
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap()
+            .rows_affected() as usize
+        })
+    }
+}
+
+/// Hashes of the factory dependencies stored in the `factory_dep_hashes` column,
+/// so that the read paths don't need to parse the bytecodes from the `data` JSONB.
+fn factory_dep_hashes(execute: &Execute) -> Option<Vec<Vec<u8>>> {
+    let factory_deps = execute.factory_deps.as_ref()?;
+    if factory_deps.is_empty() {
+        return None;
+    }
+    Some(
+        factory_deps
+            .iter()
+            .map(|bytecode| hash_bytecode(bytecode).0.to_vec())
+            .collect(),
+    )
 }
@@ -1,374 +1,920 @@
-use sqlx::types::chrono::NaiveDateTime;
+use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
+
+use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, L2ToL1Log, Log, Transaction, TransactionDetails, TransactionId,
-        TransactionReceipt,
+        AccountTxDirection, BlockId, BlockNumber, L2ToL1Log, Log, PendingTransaction,
+        PendingTxCursor, Transaction, TransactionDetails, TransactionId, TransactionReceipt,
+        TxCursor, TxPoolStatus,
     },
-    Address, L2ChainId, MiniblockNumber, ACCOUNT_CODE_STORAGE_ADDRESS,
-    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H160, H256, U256, U64,
+    bloom::accrue_bloom,
+    get_nonce_key, Address, L2ChainId, MiniblockNumber, ACCOUNT_CODE_STORAGE_ADDRESS,
+    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
-use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
+use zksync_utils::{bigdecimal_to_u256, h256_to_u32};
 
 use crate::models::{
     storage_block::{bind_block_where_sql_params, web3_block_where_sql},
     storage_event::{StorageL2ToL1Log, StorageWeb3Log},
     storage_transaction::{
         extract_web3_transaction, web3_transaction_select_sql, StorageTransaction,
-        StorageTransactionDetails,
+        StorageTransactionDetails, StorageTransactionReceipt,
     },
 };
 use crate::SqlxError;
 use crate::StorageProcessor;
 
+/// L2 transaction waiting in the mempool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolTransaction {
+    pub hash: H256,
+    pub initiator_address: Address,
+    pub nonce: u32,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub gas_per_pubdata_limit: U256,
+    pub received_at: DateTime<Utc>,
+    pub is_quarantined: bool,
+}
+
+/// Mempool transactions returned by [`TransactionsWeb3Dal::get_mempool_transactions()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MempoolContent {
+    /// Transactions ordered by initiator and nonce.
+    pub transactions: Vec<MempoolTransaction>,
+    /// Nonces of the initiators in the latest sealed state.
+    pub nonces: HashMap<Address, u32>,
+    /// Whether the transactions of some accounts were left out because of the limit.
+    pub is_truncated: bool,
+}
+
 pub struct TransactionsWeb3Dal<'a, 'c> {
     pub storage: &'a mut StorageProcessor<'c>,
 }
 
 impl TransactionsWeb3Dal<'_, '_> {
-    pub fn get_transaction_receipt(
+    pub async fn get_transaction_receipt(
         &mut self,
         hash: H256,
     ) -> Result<Option<TransactionReceipt>, SqlxError> {
-        async_std::task::block_on(async {
-            let receipt: Option<TransactionReceipt> = sqlx::query!(
-                r#"
-                WITH sl AS (
-                    SELECT * FROM storage_logs
-                    WHERE storage_logs.address = $1 AND storage_logs.tx_hash = $2
-                    ORDER BY storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
-                    LIMIT 1
-                )
-                SELECT
-                     transactions.hash as tx_hash,
-                     transactions.index_in_block as index_in_block,
-                     transactions.l1_batch_tx_index as l1_batch_tx_index,
-                     transactions.miniblock_number as block_number,
-                     transactions.error as error,
-                     transactions.effective_gas_price as effective_gas_price,
-                     transactions.initiator_address as initiator_address,
-                     transactions.data->'to' as "transfer_to?",
-                     transactions.data->'contractAddress' as "execute_contract_address?",
-                     transactions.tx_format as "tx_format?",
-                     transactions.refunded_gas as refunded_gas,
-                     transactions.gas_limit as gas_limit,
-                     miniblocks.hash as "block_hash?",
-                     miniblocks.l1_batch_number as "l1_batch_number?",
-                     sl.key as "contract_address?"
-                FROM transactions
-                LEFT JOIN miniblocks
-                    ON miniblocks.number = transactions.miniblock_number
-                LEFT JOIN sl
-                    ON sl.value != $3
-                WHERE transactions.hash = $2
-                "#,
-                ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
-                hash.0.to_vec(),
-                FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
+        let receipt = sqlx::query_as!(
+            StorageTransactionReceipt,
+            r#"
+            WITH sl AS (
+                SELECT * FROM storage_logs
+                WHERE storage_logs.address = $1 AND storage_logs.tx_hash = $2
+                ORDER BY storage_logs.miniblock_number DESC, storage_logs.operation_number DESC
+                LIMIT 1
             )
-            .fetch_optional(self.storage.conn())
-            .await?
-            .map(|db_row| {
-                let status = match (db_row.block_number, db_row.error) {
-                    (_, Some(_)) => Some(U64::from(0)),
-                    (Some(_), None) => Some(U64::from(1)),
-                    // tx not executed yet
-                    _ => None,
-                };
-                let tx_type = db_row.tx_format.map(U64::from).unwrap_or_default();
-                let transaction_index = db_row.index_in_block.map(U64::from).unwrap_or_default();
-
-                TransactionReceipt {
-                    transaction_hash: H256::from_slice(&db_row.tx_hash),
-                    transaction_index,
-                    block_hash: db_row
-                        .block_hash
-                        .clone()
-                        .map(|bytes| H256::from_slice(&bytes)),
-                    block_number: db_row.block_number.map(U64::from),
-                    l1_batch_tx_index: db_row.l1_batch_tx_index.map(U64::from),
-                    l1_batch_number: db_row.l1_batch_number.map(U64::from),
-                    from: H160::from_slice(&db_row.initiator_address),
-                    to: db_row
-                        .transfer_to
-                        .or(db_row.execute_contract_address)
-                        .map(|addr| {
-                            serde_json::from_value::<Address>(addr)
-                                .expect("invalid address value in the database")
-                        })
-                        // For better compatibility with various clients, we never return null.
-                        .or_else(|| Some(Address::default())),
-                    cumulative_gas_used: Default::default(),
-                    gas_used: {
-                        let refunded_gas: U256 = db_row.refunded_gas.into();
-                        db_row.gas_limit.map(|val| {
-                            let gas_limit = bigdecimal_to_u256(val);
-                            gas_limit - refunded_gas
-                        })
-                    },
-                    effective_gas_price: Some(
-                        db_row
-                            .effective_gas_price
-                            .map(bigdecimal_to_u256)
-                            .unwrap_or_default(),
-                    ),
-                    contract_address: db_row
-                        .contract_address
-                        .map(|addr| h256_to_account_address(&H256::from_slice(&addr))),
-                    logs: vec![],
-                    l2_to_l1_logs: vec![],
-                    status,
-                    root: db_row.block_hash.map(|bytes| H256::from_slice(&bytes)),
-                    logs_bloom: Default::default(),
-                    // Even though the Rust SDK recommends us to supply "None" for legacy transactions
-                    // we always supply some number anyway to have the same behaviour as most popular RPCs
-                    transaction_type: Some(tx_type),
-                }
-            });
-            match receipt {
-                Some(mut receipt) => {
-                    let logs: Vec<Log> = sqlx::query_as!(
-                        StorageWeb3Log,
-                        r#"
+            SELECT
+                 transactions.hash as tx_hash,
+                 transactions.index_in_block as index_in_block,
+                 transactions.l1_batch_tx_index as l1_batch_tx_index,
+                 transactions.miniblock_number as block_number,
+                 transactions.error as error,
+                 transactions.effective_gas_price as effective_gas_price,
+                 transactions.initiator_address as initiator_address,
+                 COALESCE(transactions.transfer_to, transactions.contract_address) as "execute_contract_address?",
+                 transactions.tx_format as "tx_format?",
+                 transactions.refunded_gas as refunded_gas,
+                 transactions.gas_limit as gas_limit,
+                 transactions.logs_bloom as logs_bloom,
+                 miniblocks.hash as "block_hash?",
+                 miniblocks.l1_batch_number as "l1_batch_number?",
+                 sl.key as "contract_address?"
+            FROM transactions
+            LEFT JOIN miniblocks
+                ON miniblocks.number = transactions.miniblock_number
+            LEFT JOIN sl
+                ON sl.value != $3
+            WHERE transactions.hash = $2
+            "#,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            hash.0.to_vec(),
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(TransactionReceipt::from);
+        match receipt {
+            Some(mut receipt) => {
+                let logs: Vec<Log> = sqlx::query_as!(
+                    StorageWeb3Log,
+                    r#"
+                    SELECT
+                        address, topic1, topic2, topic3, topic4, value,
+                        Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
+                        miniblock_number, tx_hash, tx_index_in_block,
+                        event_index_in_block, event_index_in_tx
+                    FROM events
+                    WHERE tx_hash = $1
+                    ORDER BY miniblock_number ASC, event_index_in_block ASC
+                    "#,
+                    hash.as_bytes()
+                )
+                .fetch_all(self.storage.conn())
+                .await?
+                .into_iter()
+                .map(Log::from)
+                .collect();
+
+                let l2_to_l1_logs: Vec<L2ToL1Log> = sqlx::query_as!(
+                    StorageL2ToL1Log,
+                    r#"
                         SELECT
-                            address, topic1, topic2, topic3, topic4, value,
+                            miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash,
                             Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
-                            miniblock_number, tx_hash, tx_index_in_block,
-                            event_index_in_block, event_index_in_tx
-                        FROM events
+                            shard_id, is_service, tx_index_in_miniblock, tx_index_in_l1_batch, sender, key, value
+                        FROM l2_to_l1_logs
                         WHERE tx_hash = $1
-                        ORDER BY miniblock_number ASC, event_index_in_block ASC
-                        "#,
-                        hash.as_bytes()
-                    )
-                    .fetch_all(self.storage.conn())
-                    .await?
-                    .into_iter()
-                    .map(|storage_log: StorageWeb3Log| {
-                        let mut log = Log::from(storage_log);
-                        log.block_hash = receipt.block_hash;
-                        log.l1_batch_number = receipt.l1_batch_number;
-                        log
-                    })
-                    .collect();
-                    receipt.logs = logs;
-
-                    let l2_to_l1_logs: Vec<L2ToL1Log> = sqlx::query_as!(
-                        StorageL2ToL1Log,
-                        r#"
-                            SELECT
-                                miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash,
-                                Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
-                                shard_id, is_service, tx_index_in_miniblock, tx_index_in_l1_batch, sender, key, value
-                            FROM l2_to_l1_logs
-                            WHERE tx_hash = $1
-                            ORDER BY log_index_in_tx ASC
-                        "#,
-                        hash.as_bytes()
-                    )
-                    .fetch_all(self.storage.conn())
-                    .await?
-                    .into_iter()
-                    .map(|storage_l2_to_l1_log: StorageL2ToL1Log| {
-                        let mut l2_to_l1_log = L2ToL1Log::from(storage_l2_to_l1_log);
-                        l2_to_l1_log.block_hash = receipt.block_hash;
-                        l2_to_l1_log.l1_batch_number = receipt.l1_batch_number;
-                        l2_to_l1_log
-                    })
-                    .collect();
-                    receipt.l2_to_l1_logs = l2_to_l1_logs;
+                        ORDER BY log_index_in_tx ASC
+                    "#,
+                    hash.as_bytes()
+                )
+                .fetch_all(self.storage.conn())
+                .await?
+                .into_iter()
+                .map(L2ToL1Log::from)
+                .collect();
 
-                    Ok(Some(receipt))
-                }
-                None => Ok(None),
+                set_receipt_logs(&mut receipt, logs, l2_to_l1_logs);
+                Ok(Some(receipt))
             }
-        })
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the receipts of all transactions in the miniblock, ordered by their index in the miniblock.
+    /// Unlike fetching the receipts one by one, the receipts, events and L2-to-L1 logs are loaded
+    /// with a single query each.
+    pub async fn get_transaction_receipts_for_miniblock(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> Result<Vec<TransactionReceipt>, SqlxError> {
+        let receipts: Vec<TransactionReceipt> = sqlx::query_as!(
+            StorageTransactionReceipt,
+            r#"
+            SELECT
+                 transactions.hash as tx_hash,
+                 transactions.index_in_block as index_in_block,
+                 transactions.l1_batch_tx_index as l1_batch_tx_index,
+                 transactions.miniblock_number as block_number,
+                 transactions.error as error,
+                 transactions.effective_gas_price as effective_gas_price,
+                 transactions.initiator_address as initiator_address,
+                 COALESCE(transactions.transfer_to, transactions.contract_address) as "execute_contract_address?",
+                 transactions.tx_format as "tx_format?",
+                 transactions.refunded_gas as refunded_gas,
+                 transactions.gas_limit as gas_limit,
+                 transactions.logs_bloom as logs_bloom,
+                 miniblocks.hash as "block_hash?",
+                 miniblocks.l1_batch_number as "l1_batch_number?",
+                 sl.key as "contract_address?"
+            FROM transactions
+            LEFT JOIN miniblocks
+                ON miniblocks.number = transactions.miniblock_number
+            LEFT JOIN LATERAL (
+                SELECT storage_logs.key, storage_logs.value FROM storage_logs
+                WHERE storage_logs.address = $1 AND storage_logs.tx_hash = transactions.hash
+                    AND storage_logs.miniblock_number = transactions.miniblock_number
+                ORDER BY storage_logs.operation_number DESC
+                LIMIT 1
+            ) sl
+                ON sl.value != $3
+            WHERE transactions.miniblock_number = $2
+            ORDER BY transactions.index_in_block ASC
+            "#,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            miniblock_number.0 as i64,
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(TransactionReceipt::from)
+        .collect();
+        if receipts.is_empty() {
+            return Ok(receipts);
+        }
+
+        let mut logs: HashMap<H256, Vec<Log>> = HashMap::new();
+        sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            SELECT
+                address, topic1, topic2, topic3, topic4, value,
+                Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
+                miniblock_number, tx_hash, tx_index_in_block,
+                event_index_in_block, event_index_in_tx
+            FROM events
+            WHERE miniblock_number = $1
+            ORDER BY event_index_in_block ASC
+            "#,
+            miniblock_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(Log::from)
+        .for_each(|log| {
+            let tx_hash = log.transaction_hash.unwrap_or_default();
+            logs.entry(tx_hash).or_default().push(log);
+        });
+
+        let mut l2_to_l1_logs: HashMap<H256, Vec<L2ToL1Log>> = HashMap::new();
+        sqlx::query_as!(
+            StorageL2ToL1Log,
+            r#"
+            SELECT
+                miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash,
+                Null::bytea as "block_hash", Null::bigint as "l1_batch_number?",
+                shard_id, is_service, tx_index_in_miniblock, tx_index_in_l1_batch, sender, key, value
+            FROM l2_to_l1_logs
+            WHERE miniblock_number = $1
+            ORDER BY log_index_in_miniblock ASC
+            "#,
+            miniblock_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(L2ToL1Log::from)
+        .for_each(|log| {
+            l2_to_l1_logs
+                .entry(log.transaction_hash)
+                .or_default()
+                .push(log);
+        });
+
+        let receipts = receipts
+            .into_iter()
+            .map(|mut receipt| {
+                let hash = receipt.transaction_hash;
+                set_receipt_logs(
+                    &mut receipt,
+                    logs.remove(&hash).unwrap_or_default(),
+                    l2_to_l1_logs.remove(&hash).unwrap_or_default(),
+                );
+                receipt
+            })
+            .collect();
+        Ok(receipts)
     }
 
-    pub fn get_transaction(
+    /// Looking up by hash also returns pending (not yet included in a miniblock) transactions from the mempool,
+    /// with `null` block fields, like Geth does. Transactions rejected by the state keeper aren't returned.
+    pub async fn get_transaction(
         &mut self,
         transaction_id: TransactionId,
         chain_id: L2ChainId,
     ) -> Result<Option<Transaction>, SqlxError> {
-        async_std::task::block_on(async {
-            let where_sql = match transaction_id {
-                TransactionId::Hash(_) => "transactions.hash = $1".to_owned(),
-                TransactionId::Block(block_id, _) => {
-                    format!(
-                        "transactions.index_in_block = $1 AND {}",
-                        web3_block_where_sql(block_id, 2)
-                    )
-                }
-            };
-            let query = format!(
-                r#"
-                SELECT
-                    {}
-                FROM transactions
-                LEFT JOIN miniblocks
-                    ON miniblocks.number = transactions.miniblock_number
-                WHERE {}
-                "#,
-                web3_transaction_select_sql(),
-                where_sql
-            );
-            let query = sqlx::query(&query);
-
-            let query = match transaction_id {
-                TransactionId::Hash(tx_hash) => query.bind(tx_hash.0.to_vec()),
-                TransactionId::Block(block_id, tx_index) => {
-                    let tx_index = if tx_index.as_u64() > i32::MAX as u64 {
-                        return Ok(None);
-                    } else {
-                        tx_index.as_u64() as i32
-                    };
-                    bind_block_where_sql_params(block_id, query.bind(tx_index))
-                }
-            };
-
-            let tx = query
-                .fetch_optional(self.storage.conn())
-                .await?
-                .map(|row| extract_web3_transaction(row, chain_id));
-            Ok(tx)
-        })
+        let where_sql = match transaction_id {
+            TransactionId::Hash(_) => "transactions.hash = $1 \
+                AND (transactions.miniblock_number IS NOT NULL OR transactions.error IS NULL)"
+                .to_owned(),
+            TransactionId::Block(block_id, _) => {
+                format!(
+                    "transactions.index_in_block = $1 AND {}",
+                    web3_block_where_sql(block_id, 2)
+                )
+            }
+        };
+        let query = format!(
+            r#"
+            SELECT
+                {}
+            FROM transactions
+            LEFT JOIN miniblocks
+                ON miniblocks.number = transactions.miniblock_number
+            WHERE {}
+            "#,
+            web3_transaction_select_sql(),
+            where_sql
+        );
+        let query = sqlx::query(&query);
+
+        let query = match transaction_id {
+            TransactionId::Hash(tx_hash) => query.bind(tx_hash.0.to_vec()),
+            TransactionId::Block(block_id, tx_index) => {
+                let tx_index = if tx_index.as_u64() > i32::MAX as u64 {
+                    return Ok(None);
+                } else {
+                    tx_index.as_u64() as i32
+                };
+                bind_block_where_sql_params(block_id, query.bind(tx_index))
+            }
+        };
+
+        let tx = query
+            .fetch_optional(self.storage.conn())
+            .await?
+            .map(|row| extract_web3_transaction(row, chain_id));
+        Ok(tx)
+    }
+
+    /// Returns up to `limit` transactions sent by and / or to the account, newest first. Only the transactions
+    /// included in miniblocks are returned; `before` excludes the transactions at and after the given position.
+    pub async fn get_transactions_by_account(
+        &mut self,
+        address: Address,
+        direction: AccountTxDirection,
+        before: Option<TxCursor>,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> Result<Vec<Transaction>, SqlxError> {
+        // Each branch is served by an index on `(address column, miniblock_number, index_in_block)`.
+        // An address is bound as `NULL` to skip its branch.
+        let query = format!(
+            r#"
+            SELECT
+                {}
+            FROM transactions
+            LEFT JOIN miniblocks
+                ON miniblocks.number = transactions.miniblock_number
+            WHERE transactions.hash IN (
+                (
+                    SELECT hash FROM transactions
+                    WHERE initiator_address = $1
+                        AND (miniblock_number, index_in_block) < ($3, $4)
+                    ORDER BY miniblock_number DESC, index_in_block DESC
+                    LIMIT $5
+                )
+                UNION ALL
+                (
+                    SELECT hash FROM transactions
+                    WHERE contract_address = $2
+                        AND (miniblock_number, index_in_block) < ($3, $4)
+                    ORDER BY miniblock_number DESC, index_in_block DESC
+                    LIMIT $5
+                )
+            )
+            ORDER BY transactions.miniblock_number DESC, transactions.index_in_block DESC
+            LIMIT $5
+            "#,
+            web3_transaction_select_sql()
+        );
+        let address = address.as_bytes().to_vec();
+        let (sent_address, received_address) = match direction {
+            AccountTxDirection::Sent => (Some(address), None),
+            AccountTxDirection::Received => (None, Some(address)),
+            AccountTxDirection::All => (Some(address.clone()), Some(address)),
+        };
+        let (before_block, before_index) = before.map_or((i64::MAX, i32::MAX), |cursor| {
+            (cursor.block_number.0 as i64, cursor.index_in_block as i32)
+        });
+
+        let transactions = sqlx::query(&query)
+            .bind(sent_address)
+            .bind(received_address)
+            .bind(before_block)
+            .bind(before_index)
+            .bind(limit as i64)
+            .fetch_all(self.storage.conn())
+            .await?
+            .into_iter()
+            .map(|row| extract_web3_transaction(row, chain_id))
+            .collect();
+        Ok(transactions)
     }
 
-    pub fn get_transaction_details(
+    pub async fn get_transaction_details(
         &mut self,
         hash: H256,
     ) -> Result<Option<TransactionDetails>, SqlxError> {
-        async_std::task::block_on(async {
-            let storage_tx_details: Option<StorageTransactionDetails> = sqlx::query_as!(
-                StorageTransactionDetails,
-                r#"
-                    SELECT transactions.*,
-                        miniblocks.hash as "block_hash?",
-                        commit_tx.tx_hash as "eth_commit_tx_hash?",
-                        prove_tx.tx_hash as "eth_prove_tx_hash?",
-                        execute_tx.tx_hash as "eth_execute_tx_hash?"
-                    FROM transactions
-                    LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
-                    LEFT JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
-                    LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
-                    LEFT JOIN eth_txs_history as prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL)
-                    LEFT JOIN eth_txs_history as execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)
-                    WHERE transactions.hash = $1
-                "#,
-                hash.as_bytes()
-            )
-            .fetch_optional(self.storage.conn())
-            .await?;
+        // The transaction data and input aren't a part of the details, so they aren't loaded.
+        let storage_tx_details: Option<StorageTransactionDetails> = sqlx::query_as!(
+            StorageTransactionDetails,
+            r#"
+                SELECT transactions.priority_op_id, transactions.hash, transactions.is_priority,
+                    transactions.full_fee, transactions.layer_2_tip_fee, transactions.initiator_address,
+                    transactions.nonce, transactions.signature, transactions.gas_limit,
+                    transactions.max_fee_per_gas, transactions.max_priority_fee_per_gas,
+                    transactions.gas_per_storage_limit, transactions.gas_per_pubdata_limit,
+                    NULL::bytea as "input", transactions.tx_format, 'null'::jsonb as "data!",
+                    transactions.received_at, transactions.in_mempool, transactions.l1_block_number,
+                    transactions.l1_batch_tx_index, transactions.l1_batch_number,
+                    transactions.miniblock_number, transactions.index_in_block, transactions.error,
+                    transactions.effective_gas_price, transactions.contract_address, transactions.value,
+                    transactions.paymaster, transactions.paymaster_input, transactions.l1_tx_mint,
+                    transactions.l1_tx_refund_recipient, transactions.refunded_gas,
+                    transactions.execution_info, transactions.logs_bloom, transactions.refund_breakdown,
+                    transactions.calldata_length, transactions.factory_dep_hashes, transactions.transfer_to,
+                    transactions.l1_deadline_timestamp, transactions.valid_until,
+                    transactions.created_at, transactions.updated_at,
+                    miniblocks.hash as "block_hash?",
+                    commit_tx.tx_hash as "eth_commit_tx_hash?",
+                    prove_tx.tx_hash as "eth_prove_tx_hash?",
+                    execute_tx.tx_hash as "eth_execute_tx_hash?"
+                FROM transactions
+                LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN l1_batches ON l1_batches.number = miniblocks.l1_batch_number
+                LEFT JOIN eth_txs_history as commit_tx ON (l1_batches.eth_commit_tx_id = commit_tx.eth_tx_id AND commit_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id AND prove_tx.confirmed_at IS NOT NULL)
+                LEFT JOIN eth_txs_history as execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id AND execute_tx.confirmed_at IS NOT NULL)
+                WHERE transactions.hash = $1
+            "#,
+            hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
 
-            let tx = storage_tx_details.map(|tx_details| tx_details.into());
+        let tx = storage_tx_details.map(|tx_details| tx_details.into());
 
-            Ok(tx)
-        })
+        Ok(tx)
+    }
+
+    /// Returns the bytes of an L2 transaction as they were submitted to the API (RLP-encoded or EIP-712).
+    /// L1 transactions have no such encoding, so `None` is returned for them.
+    pub async fn get_raw_transaction_bytes(
+        &mut self,
+        hash: H256,
+    ) -> Result<Option<Vec<u8>>, SqlxError> {
+        let row = sqlx::query!(
+            "SELECT input FROM transactions WHERE hash = $1",
+            hash.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row.and_then(|row| row.input))
     }
 
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
-    pub fn get_pending_txs_hashes_after(
+    pub async fn get_pending_txs_hashes_after(
         &mut self,
         from_timestamp: NaiveDateTime,
         limit: Option<usize>,
     ) -> Result<(Vec<H256>, Option<NaiveDateTime>), SqlxError> {
-        async_std::task::block_on(async {
-            let records = sqlx::query!(
-                "
-                SELECT transactions.hash, transactions.received_at
-                FROM transactions
-                LEFT JOIN miniblocks ON miniblocks.number = miniblock_number
-                WHERE received_at > $1
-                ORDER BY received_at ASC
-                LIMIT $2
-                ",
-                from_timestamp,
-                limit.map(|l| l as i64)
-            )
-            .fetch_all(self.storage.conn())
-            .await?;
-            let last_loc = records.last().map(|record| record.received_at);
-            let hashes = records
-                .into_iter()
-                .map(|record| H256::from_slice(&record.hash))
-                .collect();
-            Ok((hashes, last_loc))
-        })
+        let records = sqlx::query!(
+            "
+            SELECT transactions.hash, transactions.received_at
+            FROM transactions
+            LEFT JOIN miniblocks ON miniblocks.number = miniblock_number
+            WHERE received_at > $1
+            ORDER BY received_at ASC
+            LIMIT $2
+            ",
+            from_timestamp,
+            limit.map(|l| l as i64)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        let last_loc = records.last().map(|record| record.received_at);
+        let hashes = records
+            .into_iter()
+            .map(|record| H256::from_slice(&record.hash))
+            .collect();
+        Ok((hashes, last_loc))
     }
 
-    pub fn next_nonce_by_initiator_account(
+    pub async fn next_nonce_by_initiator_account(
         &mut self,
         initiator_address: Address,
     ) -> Result<U256, SqlxError> {
-        async_std::task::block_on(async {
-            let latest_nonce = self
-                .storage
-                .storage_web3_dal()
-                .get_address_historical_nonce(
-                    initiator_address,
-                    BlockId::Number(BlockNumber::Latest),
-                )?
-                .expect("Failed to get `latest` nonce")
-                .as_u64();
-
-            // Get nonces of non-rejected transactions, starting from the 'latest' nonce.
-            // `latest` nonce is used, because it is guaranteed that there are no gaps before it.
-            // `(miniblock_number IS NOT NULL OR error IS NULL)` is the condition that filters non-rejected transactions.
-            // Query is fast because we have an index on (`initiator_address`, `nonce`)
-            // and it cannot return more than `max_nonce_ahead` nonces.
-            let non_rejected_nonces: Vec<u64> = sqlx::query!(
-                r#"
-                    SELECT nonce as "nonce!" FROM transactions
-                    WHERE initiator_address = $1 AND nonce >= $2
-                        AND is_priority = FALSE
-                        AND (miniblock_number IS NOT NULL OR error IS NULL)
-                    ORDER BY nonce
-                "#,
-                initiator_address.0.to_vec(),
-                latest_nonce as i64
-            )
-            .fetch_all(self.storage.conn())
+        let latest_nonce = self
+            .storage
+            .storage_web3_dal()
+            .get_address_historical_nonce(initiator_address, BlockId::Number(BlockNumber::Latest))?
+            .expect("Failed to get `latest` nonce")
+            .as_u64();
+
+        // Get nonces of non-rejected transactions, starting from the 'latest' nonce.
+        // `latest` nonce is used, because it is guaranteed that there are no gaps before it.
+        // `(miniblock_number IS NOT NULL OR error IS NULL)` is the condition that filters non-rejected transactions.
+        // Query is fast because we have an index on (`initiator_address`, `nonce`)
+        // and it cannot return more than `max_nonce_ahead` nonces.
+        let non_rejected_nonces: Vec<u64> = sqlx::query!(
+            r#"
+                SELECT nonce as "nonce!" FROM transactions
+                WHERE initiator_address = $1 AND nonce >= $2
+                    AND is_priority = FALSE
+                    AND (miniblock_number IS NOT NULL OR error IS NULL)
+                ORDER BY nonce
+            "#,
+            initiator_address.0.to_vec(),
+            latest_nonce as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.nonce as u64)
+        .collect();
+
+        // Find pending nonce as the first "gap" in nonces.
+        let mut pending_nonce = latest_nonce;
+        for nonce in non_rejected_nonces {
+            if pending_nonce == nonce {
+                pending_nonce += 1;
+            } else {
+                break;
+            }
+        }
+
+        Ok(U256::from(pending_nonce))
+    }
+
+    /// Returns the number of transactions of the account that are waiting in the mempool.
+    pub async fn get_pending_txs_count_by_initiator_account(
+        &mut self,
+        initiator_address: Address,
+    ) -> Result<usize, SqlxError> {
+        let count = sqlx::query!(
+            r#"
+                SELECT COUNT(*) as "count!" FROM transactions
+                WHERE initiator_address = $1
+                    AND miniblock_number IS NULL
+                    AND error IS NULL
+            "#,
+            initiator_address.0.to_vec()
+        )
+        .fetch_one(self.storage.conn())
+        .await?
+        .count;
+        Ok(count as usize)
+    }
+
+    /// Returns up to `limit` L2 transactions waiting in the mempool in the order they were received,
+    /// starting after the `after` cursor.
+    pub async fn get_pending_transactions(
+        &mut self,
+        after: Option<PendingTxCursor>,
+        limit: usize,
+    ) -> Result<Vec<PendingTransaction>, SqlxError> {
+        // Served by the partial index on `(received_at, hash)` covering the mempool transactions.
+        let (after_received_at, after_hash) = after.map_or_else(
+            || (NaiveDateTime::from_timestamp_opt(0, 0).unwrap(), vec![]),
+            |cursor| (cursor.received_at.naive_utc(), cursor.hash.0.to_vec()),
+        );
+        let transactions = sqlx::query!(
+            r#"
+                SELECT hash, initiator_address, contract_address, nonce as "nonce!", value,
+                    data->'calldata' as "calldata!", gas_limit as "gas_limit!",
+                    max_fee_per_gas as "max_fee_per_gas!",
+                    max_priority_fee_per_gas as "max_priority_fee_per_gas!",
+                    gas_per_pubdata_limit as "gas_per_pubdata_limit!", received_at
+                FROM transactions
+                WHERE miniblock_number IS NULL AND error IS NULL AND is_priority = FALSE
+                    AND (received_at, hash) > ($1, $2)
+                ORDER BY received_at, hash
+                LIMIT $3
+            "#,
+            after_received_at,
+            after_hash,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| PendingTransaction {
+            hash: H256::from_slice(&row.hash),
+            from: Address::from_slice(&row.initiator_address),
+            to: row
+                .contract_address
+                .map(|address| Address::from_slice(&address)),
+            nonce: row.nonce as u32,
+            value: Some(bigdecimal_to_u256(row.value)),
+            input: Some(
+                serde_json::from_value(row.calldata)
+                    .expect("Incorrect calldata value in the database"),
+            ),
+            gas: bigdecimal_to_u256(row.gas_limit),
+            max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+            max_priority_fee_per_gas: bigdecimal_to_u256(row.max_priority_fee_per_gas),
+            gas_per_pubdata_limit: bigdecimal_to_u256(row.gas_per_pubdata_limit),
+            received_at: DateTime::<Utc>::from_utc(row.received_at, Utc),
+        })
+        .collect();
+        Ok(transactions)
+    }
+
+    /// Returns the L2 transactions waiting in the mempool ordered by initiator and nonce, together with the nonces
+    /// of their initiators in the latest sealed state. The transactions of an account are either all returned or
+    /// left out, so that the gaps in the returned nonces are real. Accounts are returned while their transactions
+    /// fit into `limit`; the first account is returned even if it doesn't fit.
+    pub async fn get_mempool_transactions(
+        &mut self,
+        limit: Option<usize>,
+    ) -> Result<MempoolContent, SqlxError> {
+        let (transactions, total_count) = self.load_mempool_transactions(limit, None).await?;
+
+        let initiators: Vec<_> = transactions.iter().map(|tx| tx.initiator_address).collect();
+        let nonces = self.get_sealed_nonces(&initiators).await?;
+        Ok(MempoolContent {
+            is_truncated: transactions.len() < total_count,
+            transactions,
+            nonces,
+        })
+    }
+
+    /// Returns the number of the L2 transactions in the mempool that can be executed in the order of nonces
+    /// (pending), and the ones that are stuck or follow a stuck transaction of the same account (queued).
+    /// Transactions with the nonces already used in the latest sealed state are not counted.
+    pub async fn get_mempool_status(
+        &mut self,
+        min_fee_per_gas: u64,
+        min_gas_per_pubdata: u64,
+    ) -> Result<TxPoolStatus, SqlxError> {
+        let initiators: Vec<_> = sqlx::query!(
+            "
+                SELECT DISTINCT initiator_address FROM transactions
+                WHERE miniblock_number IS NULL AND error IS NULL AND is_priority = FALSE
+            "
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| Address::from_slice(&row.initiator_address))
+        .collect();
+        let (initiator_bytes, nonces): (Vec<_>, Vec<_>) = self
+            .get_sealed_nonces(&initiators)
             .await?
             .into_iter()
-            .map(|row| row.nonce as u64)
-            .collect();
+            .map(|(address, nonce)| (address.0.to_vec(), nonce as i64))
+            .unzip();
 
-            // Find pending nonce as the first "gap" in nonces.
-            let mut pending_nonce = latest_nonce;
-            for nonce in non_rejected_nonces {
-                if pending_nonce == nonce {
-                    pending_nonce += 1;
-                } else {
-                    break;
-                }
+        let row = sqlx::query!(
+            r#"
+                WITH sealed_nonces AS (
+                    SELECT * FROM UNNEST($1::bytea[], $2::bigint[]) AS t (initiator_address, nonce)
+                ),
+                txs AS (
+                    SELECT transactions.initiator_address, transactions.nonce,
+                        transactions.nonce - sealed_nonces.nonce + 1 - ROW_NUMBER() OVER (
+                            PARTITION BY transactions.initiator_address ORDER BY transactions.nonce
+                        ) AS nonce_gap,
+                        transactions.max_fee_per_gas < $3::bigint
+                            OR transactions.gas_per_pubdata_limit < $4::bigint
+                            OR EXISTS (
+                                SELECT 1 FROM quarantined_transactions
+                                WHERE quarantined_transactions.hash = transactions.hash
+                                    AND quarantined_transactions.quarantined_at IS NOT NULL
+                            ) AS is_stuck
+                    FROM transactions
+                    INNER JOIN sealed_nonces ON sealed_nonces.initiator_address = transactions.initiator_address
+                    WHERE miniblock_number IS NULL AND error IS NULL AND is_priority = FALSE
+                        AND transactions.nonce >= sealed_nonces.nonce
+                ),
+                queued AS (
+                    SELECT nonce_gap > 0
+                        OR BOOL_OR(is_stuck) OVER (PARTITION BY initiator_address ORDER BY nonce) AS is_queued
+                    FROM txs
+                )
+                SELECT COUNT(*) FILTER (WHERE NOT is_queued) as "pending!",
+                    COUNT(*) FILTER (WHERE is_queued) as "queued!"
+                FROM queued
+            "#,
+            &initiator_bytes,
+            &nonces,
+            min_fee_per_gas as i64,
+            min_gas_per_pubdata as i64
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(TxPoolStatus {
+            pending: row.pending as usize,
+            queued: row.queued as usize,
+        })
+    }
+
+    /// Returns the L2 transactions of the specified initiators waiting in the mempool ordered by initiator and nonce,
+    /// together with the nonces of all the specified initiators in the latest sealed state.
+    pub async fn get_mempool_transactions_by_initiators(
+        &mut self,
+        initiators: &[Address],
+    ) -> Result<(Vec<MempoolTransaction>, HashMap<Address, u32>), SqlxError> {
+        let (transactions, _) = self
+            .load_mempool_transactions(None, Some(initiators))
+            .await?;
+        let nonces = self.get_sealed_nonces(initiators).await?;
+        Ok((transactions, nonces))
+    }
+
+    /// Loads the mempool transactions of the specified initiators (or all of them) ordered by initiator and nonce,
+    /// applying the per-account `limit` described in [`Self::get_mempool_transactions()`]. Also returns
+    /// the total number of the matching transactions before the limit is applied.
+    async fn load_mempool_transactions(
+        &mut self,
+        limit: Option<usize>,
+        initiators: Option<&[Address]>,
+    ) -> Result<(Vec<MempoolTransaction>, usize), SqlxError> {
+        let initiator_bytes: Option<Vec<_>> = initiators.map(|initiators| {
+            initiators
+                .iter()
+                .map(|address| address.0.to_vec())
+                .collect()
+        });
+        let mut total_count = 0;
+        let transactions: Vec<_> = sqlx::query!(
+            r#"
+                SELECT hash, initiator_address, nonce as "nonce!", max_fee_per_gas as "max_fee_per_gas!",
+                    max_priority_fee_per_gas as "max_priority_fee_per_gas!",
+                    gas_per_pubdata_limit as "gas_per_pubdata_limit!", received_at,
+                    EXISTS (
+                        SELECT 1 FROM quarantined_transactions
+                        WHERE quarantined_transactions.hash = txs.hash
+                            AND quarantined_transactions.quarantined_at IS NOT NULL
+                    ) as "is_quarantined!",
+                    total_count as "total_count!"
+                FROM (
+                    SELECT hash, initiator_address, nonce, max_fee_per_gas, max_priority_fee_per_gas,
+                        gas_per_pubdata_limit, received_at,
+                        COUNT(*) OVER (ORDER BY initiator_address) AS running_count,
+                        COUNT(*) OVER (ORDER BY initiator_address)
+                            - COUNT(*) OVER (PARTITION BY initiator_address) AS preceding_count,
+                        COUNT(*) OVER () AS total_count
+                    FROM transactions
+                    WHERE miniblock_number IS NULL AND error IS NULL AND is_priority = FALSE
+                        AND ($2::bytea[] IS NULL OR initiator_address = ANY($2))
+                ) txs
+                WHERE $1::bigint IS NULL OR running_count <= $1 OR preceding_count = 0
+                ORDER BY initiator_address, nonce
+            "#,
+            limit.map(|limit| limit as i64),
+            initiator_bytes.as_ref()
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            total_count = row.total_count as usize;
+            MempoolTransaction {
+                hash: H256::from_slice(&row.hash),
+                initiator_address: Address::from_slice(&row.initiator_address),
+                nonce: row.nonce as u32,
+                max_fee_per_gas: bigdecimal_to_u256(row.max_fee_per_gas),
+                max_priority_fee_per_gas: bigdecimal_to_u256(row.max_priority_fee_per_gas),
+                gas_per_pubdata_limit: bigdecimal_to_u256(row.gas_per_pubdata_limit),
+                received_at: DateTime::<Utc>::from_utc(row.received_at, Utc),
+                is_quarantined: row.is_quarantined,
             }
+        })
+        .collect();
+        Ok((transactions, total_count))
+    }
 
-            Ok(U256::from(pending_nonce))
+    /// Returns the nonces of the accounts in the latest sealed state.
+    async fn get_sealed_nonces(
+        &mut self,
+        accounts: &[Address],
+    ) -> Result<HashMap<Address, u32>, SqlxError> {
+        let nonce_keys: HashMap<_, _> = accounts
+            .iter()
+            .map(|&address| (get_nonce_key(&address).hashed_key(), address))
+            .collect();
+        let storage_keys: Vec<_> = nonce_keys.keys().map(|key| key.0.to_vec()).collect();
+        let mut nonces: HashMap<_, _> = sqlx::query!(
+            r#"SELECT hashed_key, value as "value!" FROM storage WHERE hashed_key = ANY($1)"#,
+            &storage_keys,
+        )
+        .fetch_all(self.storage.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            let nonce_key = H256::from_slice(&row.hashed_key);
+            let nonce = h256_to_u32(H256::from_slice(&row.value));
+            (nonce_keys[&nonce_key], nonce)
         })
+        .collect();
+        // Accounts without the nonce in the storage haven't executed any transactions yet.
+        for address in nonce_keys.into_values() {
+            nonces.entry(address).or_insert(0);
+        }
+        Ok(nonces)
+    }
+
+    /// Returns the timestamp of the latest miniblock with a transaction initiated by the account.
+    pub async fn get_last_activity_timestamp_by_initiator_account(
+        &mut self,
+        initiator_address: Address,
+    ) -> Result<Option<u64>, SqlxError> {
+        let timestamp = sqlx::query!(
+            r#"
+                SELECT miniblocks.timestamp FROM transactions
+                INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                WHERE transactions.initiator_address = $1
+                ORDER BY transactions.miniblock_number DESC
+                LIMIT 1
+            "#,
+            initiator_address.0.to_vec()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        .map(|row| row.timestamp as u64);
+        Ok(timestamp)
     }
 
     /// Returns the server transactions (not API ones) from a certain miniblock.
     /// Returns an empty list if the miniblock doesn't exist.
-    pub fn get_raw_miniblock_transactions(
+    /// Returns at most `limit` first transactions of the miniblock, or all of them if `limit` is `None`.
+    pub async fn get_raw_miniblock_transactions(
         &mut self,
         miniblock: MiniblockNumber,
+        limit: Option<usize>,
     ) -> Result<Vec<zksync_types::Transaction>, SqlxError> {
-        async_std::task::block_on(async {
-            let txs = sqlx::query_as!(
-                StorageTransaction,
-                "
-                    SELECT * FROM transactions
-                    WHERE miniblock_number = $1
-                    ORDER BY index_in_block
-                ",
-                miniblock.0 as i64
+        // Factory dependencies are the bulk of the transaction data, so they are loaded by their hashes
+        // from `factory_deps` rather than parsed from the data JSONB.
+        let storage_txs = sqlx::query_as!(
+            StorageTransaction,
+            r#"
+                SELECT priority_op_id, hash, is_priority, full_fee, layer_2_tip_fee, initiator_address,
+                    nonce, signature, gas_limit, max_fee_per_gas, max_priority_fee_per_gas,
+                    gas_per_storage_limit, gas_per_pubdata_limit, input, tx_format,
+                    CASE WHEN factory_dep_hashes IS NULL THEN data ELSE data - 'factoryDeps' END as "data!",
+                    received_at, in_mempool, l1_block_number, l1_batch_number, l1_batch_tx_index,
+                    miniblock_number, index_in_block, error, effective_gas_price, contract_address,
+                    value, paymaster, paymaster_input, refunded_gas, execution_info, l1_tx_mint,
+                    l1_tx_refund_recipient, logs_bloom, refund_breakdown, calldata_length,
+                    factory_dep_hashes, transfer_to, l1_deadline_timestamp, valid_until,
+                    created_at, updated_at
+                FROM transactions
+                WHERE miniblock_number = $1
+                ORDER BY index_in_block
+                LIMIT $2
+            "#,
+            miniblock.0 as i64,
+            limit.map(|limit| limit as i64)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let dep_hashes: Vec<Vec<u8>> = storage_txs
+            .iter()
+            .flat_map(|tx| tx.factory_dep_hashes.iter().flatten().cloned())
+            .collect();
+        let mut bytecodes = HashMap::new();
+        if !dep_hashes.is_empty() {
+            bytecodes = sqlx::query!(
+                "SELECT bytecode_hash, bytecode FROM factory_deps WHERE bytecode_hash = ANY($1)",
+                &dep_hashes
             )
             .fetch_all(self.storage.conn())
             .await?
             .into_iter()
-            .map(zksync_types::Transaction::from)
+            .map(|row| (row.bytecode_hash, row.bytecode))
             .collect();
-            Ok(txs)
-        })
+        }
+
+        let mut txs = Vec::with_capacity(storage_txs.len());
+        for storage_tx in storage_txs {
+            let dep_hashes = storage_tx.factory_dep_hashes.clone();
+            let mut tx = zksync_types::Transaction::from(storage_tx);
+            if let Some(dep_hashes) = dep_hashes {
+                let factory_deps: Option<Vec<Vec<u8>>> = dep_hashes
+                    .iter()
+                    .map(|hash| bytecodes.get(hash).cloned())
+                    .collect();
+                tx.execute.factory_deps = match factory_deps {
+                    Some(factory_deps) => Some(factory_deps),
+                    // A dependency that wasn't published by the VM is missing from `factory_deps`,
+                    // so the dependencies are read from the transaction data instead.
+                    None => self.get_factory_deps_from_data(tx.hash()).await?,
+                };
+            }
+            txs.push(tx);
+        }
+        Ok(txs)
+    }
+
+    async fn get_factory_deps_from_data(
+        &mut self,
+        hash: H256,
+    ) -> Result<Option<Vec<Vec<u8>>>, SqlxError> {
+        let factory_deps = sqlx::query_scalar!(
+            "SELECT data->'factoryDeps' FROM transactions WHERE hash = $1",
+            hash.as_bytes()
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(factory_deps.and_then(|factory_deps| {
+            serde_json::from_value(factory_deps).expect("invalid factory deps in the database")
+        }))
+    }
+}
+
+/// Fills in the logs of the receipt, setting the block hash and the L1 batch number of the logs from the receipt.
+fn set_receipt_logs(
+    receipt: &mut TransactionReceipt,
+    mut logs: Vec<Log>,
+    mut l2_to_l1_logs: Vec<L2ToL1Log>,
+) {
+    for log in &mut logs {
+        log.block_hash = receipt.block_hash;
+        log.l1_batch_number = receipt.l1_batch_number;
+    }
+    // Blooms are not stored for the transactions executed before they were introduced.
+    if receipt.logs_bloom.is_zero() {
+        for log in &logs {
+            accrue_bloom(&mut receipt.logs_bloom, log.address.as_bytes());
+            for topic in &log.topics {
+                accrue_bloom(&mut receipt.logs_bloom, topic.as_bytes());
+            }
+        }
+    }
+    receipt.logs = logs;
+
+    for l2_to_l1_log in &mut l2_to_l1_logs {
+        l2_to_l1_log.block_hash = receipt.block_hash;
+        l2_to_l1_log.l1_batch_number = receipt.l1_batch_number;
     }
+    receipt.l2_to_l1_logs = l2_to_l1_logs;
 }
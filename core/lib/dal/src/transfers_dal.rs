@@ -0,0 +1,126 @@
+use zksync_config::constants::ERC20_TRANSFER_TOPIC;
+use zksync_types::api::Transfer;
+use zksync_types::MiniblockNumber;
+use zksync_utils::u256_to_big_decimal;
+
+use crate::StorageProcessor;
+
+/// ETH and ERC-20 token transfers extracted from the miniblock events at seal time.
+#[derive(Debug)]
+pub struct TransfersDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TransfersDal<'_, '_> {
+    pub fn insert_transfers(&mut self, miniblock_number: MiniblockNumber, transfers: &[Transfer]) {
+        async_std::task::block_on(async {
+            let event_indices: Vec<_> = transfers
+                .iter()
+                .map(|transfer| transfer.log_index as i32)
+                .collect();
+            let tx_hashes: Vec<_> = transfers
+                .iter()
+                .map(|transfer| transfer.transaction_hash.as_bytes().to_vec())
+                .collect();
+            let token_addresses: Vec<_> = transfers
+                .iter()
+                .map(|transfer| transfer.token_address.as_bytes().to_vec())
+                .collect();
+            let from_addresses: Vec<_> = transfers
+                .iter()
+                .map(|transfer| transfer.from.as_bytes().to_vec())
+                .collect();
+            let to_addresses: Vec<_> = transfers
+                .iter()
+                .map(|transfer| transfer.to.as_bytes().to_vec())
+                .collect();
+            let amounts: Vec<_> = transfers
+                .iter()
+                .map(|transfer| u256_to_big_decimal(transfer.amount))
+                .collect();
+
+            sqlx::query!(
+                "
+                INSERT INTO transfers
+                    (miniblock_number, event_index_in_block, tx_hash, token_address, from_address, to_address, amount)
+                SELECT $1, u.event_index_in_block, u.tx_hash, u.token_address, u.from_address, u.to_address, u.amount
+                FROM UNNEST($2::int[], $3::bytea[], $4::bytea[], $5::bytea[], $6::bytea[], $7::numeric[])
+                    AS u(event_index_in_block, tx_hash, token_address, from_address, to_address, amount)
+                ",
+                miniblock_number.0 as i64,
+                &event_indices,
+                &tx_hashes,
+                &token_addresses,
+                &from_addresses,
+                &to_addresses,
+                &amounts
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Extracts the transfers of up to `limit` miniblocks sealed before the `transfers` table was added,
+    /// going from the newest to the oldest miniblock. Returns the number of processed miniblocks,
+    /// so 0 means that the backfill is complete.
+    ///
+    /// The events are filtered the same way as in `extract_transfers_this_block` of the state keeper:
+    /// `Transfer` events with exactly 3 indexed topics and a 32-byte amount.
+    pub fn backfill_transfers(&mut self, limit: usize) -> usize {
+        async_std::task::block_on(async {
+            let mut transaction = self.storage.start_transaction().await;
+            let next_miniblock_number =
+                sqlx::query!("SELECT next_miniblock_number FROM transfers_backfill FOR UPDATE")
+                    .fetch_optional(transaction.conn())
+                    .await
+                    .unwrap()
+                    .map(|row| row.next_miniblock_number);
+            let to_miniblock = match next_miniblock_number {
+                Some(number) => number,
+                None => return 0,
+            };
+            let from_miniblock = (to_miniblock - limit as i64 + 1).max(0);
+
+            sqlx::query!(
+                r#"
+                INSERT INTO transfers
+                    (miniblock_number, event_index_in_block, tx_hash, token_address, from_address, to_address, amount)
+                SELECT miniblock_number, event_index_in_block, tx_hash, address,
+                    substring(topic2 FROM 13), substring(topic3 FROM 13),
+                    (
+                        SELECT SUM(get_byte(value, i)::numeric * 256::numeric ^ (31 - i))
+                        FROM generate_series(0, 31) AS i
+                    )::numeric(80)
+                FROM events
+                WHERE miniblock_number BETWEEN $1 AND $2
+                    AND topic1 = $3 AND length(topic3) = 32 AND topic4 = '' AND length(value) = 32
+                ON CONFLICT (miniblock_number, event_index_in_block) DO NOTHING
+                "#,
+                from_miniblock,
+                to_miniblock,
+                ERC20_TRANSFER_TOPIC.as_bytes()
+            )
+            .execute(transaction.conn())
+            .await
+            .unwrap();
+
+            if from_miniblock == 0 {
+                sqlx::query!("DELETE FROM transfers_backfill")
+                    .execute(transaction.conn())
+                    .await
+                    .unwrap();
+            } else {
+                sqlx::query!(
+                    "UPDATE transfers_backfill SET next_miniblock_number = $1",
+                    from_miniblock - 1
+                )
+                .execute(transaction.conn())
+                .await
+                .unwrap();
+            }
+            transaction.commit().await;
+            (to_miniblock - from_miniblock + 1) as usize
+        })
+    }
+}
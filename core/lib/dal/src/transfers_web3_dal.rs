@@ -0,0 +1,72 @@
+use zksync_types::api::{Transfer, TransferCursor};
+use zksync_types::{Address, MiniblockNumber, H256};
+use zksync_utils::bigdecimal_to_u256;
+
+use crate::SqlxError;
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct TransfersWeb3Dal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TransfersWeb3Dal<'_, '_> {
+    /// Returns up to `limit` transfers from and / or to the account, newest first;
+    /// `before` excludes the transfers at and after the given position.
+    pub async fn get_transfers(
+        &mut self,
+        address: Address,
+        before: Option<TransferCursor>,
+        limit: usize,
+    ) -> Result<Vec<Transfer>, SqlxError> {
+        let (before_block, before_index) = before.map_or((i64::MAX, i32::MAX), |cursor| {
+            (cursor.block_number.0 as i64, cursor.log_index as i32)
+        });
+        // Each branch is served by an index on `(address column, miniblock_number, event_index_in_block)`.
+        let rows = sqlx::query!(
+            "
+            SELECT miniblock_number, event_index_in_block, tx_hash, token_address, from_address, to_address, amount
+            FROM transfers
+            WHERE (miniblock_number, event_index_in_block) IN (
+                (
+                    SELECT miniblock_number, event_index_in_block FROM transfers
+                    WHERE from_address = $1
+                        AND (miniblock_number, event_index_in_block) < ($2, $3)
+                    ORDER BY miniblock_number DESC, event_index_in_block DESC
+                    LIMIT $4
+                )
+                UNION ALL
+                (
+                    SELECT miniblock_number, event_index_in_block FROM transfers
+                    WHERE to_address = $1
+                        AND (miniblock_number, event_index_in_block) < ($2, $3)
+                    ORDER BY miniblock_number DESC, event_index_in_block DESC
+                    LIMIT $4
+                )
+            )
+            ORDER BY miniblock_number DESC, event_index_in_block DESC
+            LIMIT $4
+            ",
+            address.as_bytes(),
+            before_block,
+            before_index,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let transfers = rows
+            .into_iter()
+            .map(|row| Transfer {
+                token_address: Address::from_slice(&row.token_address),
+                from: Address::from_slice(&row.from_address),
+                to: Address::from_slice(&row.to_address),
+                amount: bigdecimal_to_u256(row.amount),
+                transaction_hash: H256::from_slice(&row.tx_hash),
+                block_number: MiniblockNumber(row.miniblock_number as u32),
+                log_index: row.event_index_in_block as u32,
+            })
+            .collect();
+        Ok(transfers)
+    }
+}
@@ -0,0 +1,91 @@
+use zksync_types::tx::TxAccessSet;
+use zksync_types::{MiniblockNumber, H256};
+
+use crate::StorageProcessor;
+
+/// Read and write sets of the executed transactions. The keys of a set are stored
+/// as a single byte array to keep the rows compact.
+#[derive(Debug)]
+pub struct TxAccessSetsDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TxAccessSetsDal<'_, '_> {
+    fn concat_keys(keys: &[H256]) -> Vec<u8> {
+        keys.iter()
+            .flat_map(|key| key.as_bytes())
+            .copied()
+            .collect()
+    }
+
+    fn split_keys(bytes: &[u8]) -> Vec<H256> {
+        bytes.chunks(32).map(H256::from_slice).collect()
+    }
+
+    pub fn insert_access_sets(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        access_sets: &[TxAccessSet],
+    ) {
+        async_std::task::block_on(async {
+            let indices: Vec<_> = access_sets
+                .iter()
+                .map(|set| set.tx_index_in_miniblock as i32)
+                .collect();
+            let tx_hashes: Vec<_> = access_sets
+                .iter()
+                .map(|set| set.tx_hash.as_bytes().to_vec())
+                .collect();
+            let read_keys: Vec<_> = access_sets
+                .iter()
+                .map(|set| Self::concat_keys(&set.read_keys))
+                .collect();
+            let written_keys: Vec<_> = access_sets
+                .iter()
+                .map(|set| Self::concat_keys(&set.written_keys))
+                .collect();
+
+            sqlx::query!(
+                "
+                INSERT INTO tx_access_sets (miniblock_number, index_in_block, tx_hash, read_keys, written_keys)
+                SELECT $1, u.index_in_block, u.tx_hash, u.read_keys, u.written_keys
+                FROM UNNEST($2::int[], $3::bytea[], $4::bytea[], $5::bytea[])
+                    AS u(index_in_block, tx_hash, read_keys, written_keys)
+                ",
+                miniblock_number.0 as i64,
+                &indices,
+                &tx_hashes,
+                &read_keys,
+                &written_keys
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the access sets of the miniblock transactions in the execution order.
+    pub fn get_access_sets(&mut self, miniblock_number: MiniblockNumber) -> Vec<TxAccessSet> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT index_in_block, tx_hash, read_keys, written_keys FROM tx_access_sets
+                WHERE miniblock_number = $1
+                ORDER BY index_in_block
+                ",
+                miniblock_number.0 as i64
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| TxAccessSet {
+                tx_hash: H256::from_slice(&row.tx_hash),
+                tx_index_in_miniblock: row.index_in_block as u32,
+                read_keys: Self::split_keys(&row.read_keys),
+                written_keys: Self::split_keys(&row.written_keys),
+            })
+            .collect()
+        })
+    }
+}
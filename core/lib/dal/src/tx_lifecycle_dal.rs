@@ -0,0 +1,157 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use sqlx::types::chrono::{DateTime, Utc};
+use zksync_types::api::{TxLifecycleEvent, TxLifecycleEventKind};
+use zksync_types::{MiniblockNumber, H256};
+
+use crate::time_utils::pg_interval_from_duration;
+use crate::StorageProcessor;
+
+#[derive(Debug)]
+pub struct TxLifecycleDal<'a, 'c> {
+    pub storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TxLifecycleDal<'_, '_> {
+    /// Inserts the lifecycle events of (possibly) several transactions at once.
+    pub fn insert_tx_lifecycle_events(&mut self, events: &[(H256, TxLifecycleEvent)]) {
+        async_std::task::block_on(async {
+            let hashes: Vec<_> = events
+                .iter()
+                .map(|(tx_hash, _)| tx_hash.as_bytes().to_vec())
+                .collect();
+            let kinds: Vec<_> = events
+                .iter()
+                .map(|(_, event)| event.event.as_str().to_owned())
+                .collect();
+            let miniblock_numbers: Vec<_> = events
+                .iter()
+                .map(|(_, event)| event.miniblock_number.map_or(-1, |number| number.0 as i64))
+                .collect();
+            let details: Vec<_> = events
+                .iter()
+                .map(|(_, event)| event.details.clone().unwrap_or_default())
+                .collect();
+            let timestamps: Vec<_> = events
+                .iter()
+                .map(|(_, event)| event.timestamp.naive_utc())
+                .collect();
+
+            // Absent miniblock numbers and details are passed as `-1` and empty strings respectively,
+            // since the nullable arrays are not supported.
+            sqlx::query!(
+                "
+                INSERT INTO tx_lifecycle_events (tx_hash, event, miniblock_number, details, created_at)
+                SELECT u.tx_hash, u.event, NULLIF(u.miniblock_number, -1), NULLIF(u.details, ''), u.created_at
+                FROM UNNEST($1::bytea[], $2::varchar[], $3::bigint[], $4::varchar[], $5::timestamp[])
+                    AS u(tx_hash, event, miniblock_number, details, created_at)
+                ",
+                &hashes,
+                &kinds,
+                &miniblock_numbers,
+                &details,
+                &timestamps
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    pub fn insert_tx_executed_events(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+        tx_hashes: &[H256],
+    ) {
+        async_std::task::block_on(async {
+            let hashes: Vec<_> = tx_hashes
+                .iter()
+                .map(|hash| hash.as_bytes().to_vec())
+                .collect();
+            // `now()` returns the start time of the DB transaction, so the timestamp is taken
+            // the same way as for the events recorded by the API to keep them ordered.
+            let executed_at = Utc::now().naive_utc();
+            sqlx::query!(
+                "
+                INSERT INTO tx_lifecycle_events (tx_hash, event, miniblock_number, created_at)
+                SELECT u.tx_hash, 'executed', $2, $3
+                FROM UNNEST($1::bytea[]) AS u(tx_hash)
+                ",
+                &hashes,
+                miniblock_number.0 as i64,
+                executed_at
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    pub fn insert_tx_rejected_events(&mut self, tx_hashes: &[H256], reason: &str) {
+        async_std::task::block_on(async {
+            let hashes: Vec<_> = tx_hashes
+                .iter()
+                .map(|hash| hash.as_bytes().to_vec())
+                .collect();
+            let rejected_at = Utc::now().naive_utc();
+            sqlx::query!(
+                "
+                INSERT INTO tx_lifecycle_events (tx_hash, event, details, created_at)
+                SELECT u.tx_hash, 'rejected', $2, $3
+                FROM UNNEST($1::bytea[]) AS u(tx_hash)
+                ",
+                &hashes,
+                reason,
+                rejected_at
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap();
+        })
+    }
+
+    /// Returns the lifecycle events of the transaction in the order they have been recorded.
+    pub fn get_tx_lifecycle_events(&mut self, tx_hash: H256) -> Vec<TxLifecycleEvent> {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                SELECT event, miniblock_number, details, created_at FROM tx_lifecycle_events
+                WHERE tx_hash = $1
+                ORDER BY created_at, id
+                ",
+                tx_hash.as_bytes()
+            )
+            .fetch_all(self.storage.conn())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|row| TxLifecycleEvent {
+                event: TxLifecycleEventKind::from_str(&row.event).unwrap(),
+                miniblock_number: row
+                    .miniblock_number
+                    .map(|number| MiniblockNumber(number as u32)),
+                details: row.details,
+                timestamp: DateTime::<Utc>::from_utc(row.created_at, Utc),
+            })
+            .collect()
+        })
+    }
+
+    /// Removes the events recorded more than `retention` ago, returning the number of removed events.
+    pub fn delete_tx_lifecycle_events_older_than(&mut self, retention: Duration) -> u64 {
+        async_std::task::block_on(async {
+            sqlx::query!(
+                "
+                DELETE FROM tx_lifecycle_events
+                WHERE created_at < now() - $1::interval
+                ",
+                pg_interval_from_duration(retention)
+            )
+            .execute(self.storage.conn())
+            .await
+            .unwrap()
+            .rows_affected()
+        })
+    }
+}
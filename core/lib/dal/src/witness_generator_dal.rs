@@ -1,10 +1,10 @@
-use std::collections::HashMap;
 use std::ops::Range;
 use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use sqlx::Row;
 
+use crate::job_queue_dal::{JobQueue, JobStatus, WitnessJobQueue};
 use crate::models::storage_witness_job_info::StorageWitnessJobInfo;
 use zksync_object_store::gcs_utils::merkle_tree_paths_blob_url;
 use zksync_object_store::gcs_utils::{
@@ -13,6 +13,7 @@ use zksync_object_store::gcs_utils::{
 };
 use zksync_types::proofs::{
     AggregationRound, JobCountStatistics, WitnessGeneratorJobMetadata, WitnessJobInfo,
+    WitnessMemoryBudget,
 };
 use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::ZkSyncCircuit;
 use zksync_types::zkevm_test_harness::abstract_zksync_circuit::concrete_circuits::ZkSyncProof;
@@ -21,56 +22,91 @@ use zksync_types::zkevm_test_harness::bellman::plonk::better_better_cs::proof::P
 use zksync_types::zkevm_test_harness::witness::oracle::VmWitnessOracle;
 use zksync_types::L1BatchNumber;
 
-use crate::time_utils::{duration_to_naive_time, pg_interval_from_duration};
 use crate::StorageProcessor;
 
+/// Number of the eligible basic circuits witness jobs checked against the memory budget per pick.
+const MEMORY_BUDGET_CANDIDATES_LIMIT: usize = 100;
+
 #[derive(Debug)]
 pub struct WitnessGeneratorDal<'a, 'c> {
     pub storage: &'a mut StorageProcessor<'c>,
 }
 
 impl WitnessGeneratorDal<'_, '_> {
+    /// Picks the first eligible basic circuits witness job fitting into `memory_budget`.
+    /// Up to [`MEMORY_BUDGET_CANDIDATES_LIMIT`] eligible jobs are considered; the ones that
+    /// don't fit are left queued for instances with more memory.
     pub fn get_next_basic_circuit_witness_job(
         &mut self,
         processing_timeout: Duration,
         max_attempts: u32,
         last_l1_batch_to_process: u32,
+        memory_budget: Option<WitnessMemoryBudget>,
+        picked_by: &str,
     ) -> Option<WitnessGeneratorJobMetadata> {
         async_std::task::block_on(async {
-            let processing_timeout = pg_interval_from_duration(processing_timeout);
-            let result: Option<WitnessGeneratorJobMetadata> = sqlx::query!(
-                "
-                UPDATE witness_inputs
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE l1_batch_number = (
-                    SELECT l1_batch_number
-                    FROM witness_inputs
-                    WHERE l1_batch_number <= $3
-                    AND
-                    (   status = 'queued'
-                        OR (status = 'in_progress' AND processing_started_at < now() - $1::interval)
-                        OR (status = 'failed' AND attempts < $2)
-                    )
-                    ORDER BY l1_batch_number ASC
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING witness_inputs.*
-               ",
-                &processing_timeout,
-                max_attempts as i32,
-                last_l1_batch_to_process as i64
+            let candidates_limit = if memory_budget.is_some() {
+                MEMORY_BUDGET_CANDIDATES_LIMIT
+            } else {
+                1
+            };
+            let mut transaction = self.storage.start_transaction().await;
+            let mut queue =
+                transaction.job_queue_dal(WitnessJobQueue(AggregationRound::BasicCircuits));
+            queue.requeue_expired(processing_timeout);
+            let candidates: Vec<_> = queue
+                .lock_next_jobs(max_attempts, last_l1_batch_to_process, candidates_limit)
+                .into_iter()
+                .map(|l1_batch_number| l1_batch_number.0 as i64)
+                .collect();
+            let candidates = sqlx::query!(
+                r#"
+                SELECT number, (l1_tx_count + l2_tx_count) as "tx_count!", estimated_basic_circuits
+                FROM l1_batches
+                WHERE number = ANY($1)
+                ORDER BY number ASC
+                "#,
+                &candidates
             )
-            .fetch_optional(self.storage.conn())
+            .fetch_all(transaction.conn())
             .await
-            .unwrap()
-            .map(|row| WitnessGeneratorJobMetadata {
-                block_number: L1BatchNumber(row.l1_batch_number as u32),
-                proofs: vec![],
+            .unwrap();
+
+            // Batches sealed before the circuit estimation was introduced have no estimation
+            // and are always considered to fit.
+            let picked = candidates.into_iter().find(|candidate| {
+                let (budget, basic_circuits) =
+                    match (memory_budget, candidate.estimated_basic_circuits) {
+                        (Some(budget), Some(basic_circuits)) => (budget, basic_circuits),
+                        _ => return true,
+                    };
+                let tx_count = candidate.tx_count as usize;
+                if budget.fits(tx_count, basic_circuits as usize) {
+                    return true;
+                }
+                vlog::info!(
+                    "Skipping witness generation for L1 batch #{}: estimated {} bytes ({} txs, {} basic circuits) exceed the budget of {} bytes",
+                    candidate.number,
+                    budget.estimate_bytes(tx_count, basic_circuits as usize),
+                    tx_count,
+                    basic_circuits,
+                    budget.available_bytes
+                );
+                metrics::counter!("server.witness_generator.skipped_over_budget", 1);
+                false
             });
 
+            let result = picked.map(|candidate| {
+                let block_number = L1BatchNumber(candidate.number as u32);
+                transaction
+                    .job_queue_dal(WitnessJobQueue(AggregationRound::BasicCircuits))
+                    .lease(block_number, picked_by);
+                WitnessGeneratorJobMetadata {
+                    block_number,
+                    proofs: vec![],
+                }
+            });
+            transaction.commit().await;
             result
         })
     }
@@ -115,63 +151,15 @@ impl WitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
         last_l1_batch_to_process: u32,
+        picked_by: &str,
     ) -> Option<WitnessGeneratorJobMetadata> {
-        async_std::task::block_on(async {
-            let processing_timeout = pg_interval_from_duration(processing_timeout);
-            sqlx::query!(
-                "
-                UPDATE leaf_aggregation_witness_jobs
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE l1_batch_number = (
-                    SELECT l1_batch_number
-                    FROM leaf_aggregation_witness_jobs
-                    WHERE l1_batch_number <= $3
-                    AND
-                    (   status = 'queued'
-                        OR (status = 'in_progress' AND processing_started_at < now() - $1::interval)
-                        OR (status = 'failed' AND attempts < $2)
-                    )
-                    ORDER BY l1_batch_number ASC
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING leaf_aggregation_witness_jobs.*
-                ", &processing_timeout,
-                max_attempts as i32,
-                last_l1_batch_to_process as i64
-            )
-                .fetch_optional(self.storage.conn())
-                .await
-                .unwrap()
-                .map(|row| {
-                    let l1_batch_number = L1BatchNumber(row.l1_batch_number as u32);
-                    let number_of_basic_circuits = row.number_of_basic_circuits;
-
-                    // Now that we have a job in `queued` status, we need to enrich it with the computed proofs.
-                    // We select `aggregation_round = 0` to only get basic circuits.
-                    // Note that at this point there cannot be any other circuits anyway,
-                    // but we keep the check for explicitness
-                    let basic_circuits_proofs: Vec<
-                        Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
-                    > = self.load_proofs_for_block(l1_batch_number, AggregationRound::BasicCircuits);
-
-                    assert_eq!(
-                        basic_circuits_proofs.len(),
-                        number_of_basic_circuits as usize,
-                        "leaf_aggregation_witness_job for l1 batch {} is in status `queued`, but there are only {} computed basic proofs, which is different from expected {}",
-                        l1_batch_number,
-                        basic_circuits_proofs.len(),
-                        number_of_basic_circuits
-                    );
-
-                    WitnessGeneratorJobMetadata {
-                        block_number: l1_batch_number,
-                        proofs: basic_circuits_proofs
-                    }
-                })
-        })
+        self.get_next_aggregation_witness_job(
+            AggregationRound::LeafAggregation,
+            processing_timeout,
+            max_attempts,
+            last_l1_batch_to_process,
+            picked_by,
+        )
     }
 
     pub fn get_next_node_aggregation_witness_job(
@@ -179,60 +167,15 @@ impl WitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
         last_l1_batch_to_process: u32,
+        picked_by: &str,
     ) -> Option<WitnessGeneratorJobMetadata> {
-        async_std::task::block_on(async {
-            let processing_timeout = pg_interval_from_duration(processing_timeout);
-            sqlx::query!(
-                "
-                UPDATE node_aggregation_witness_jobs
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE l1_batch_number = (
-                    SELECT l1_batch_number
-                    FROM node_aggregation_witness_jobs
-                    WHERE l1_batch_number <= $3
-                    AND
-                    (   status = 'queued'
-                        OR (status = 'in_progress' AND processing_started_at < now() - $1::interval)
-                        OR (status = 'failed' AND attempts < $2)
-                    )
-                    ORDER BY l1_batch_number ASC
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING node_aggregation_witness_jobs.*
-            ", &processing_timeout, 
-                max_attempts as i32,
-                last_l1_batch_to_process as i64,
-            )
-                .fetch_optional(self.storage.conn())
-                .await
-                .unwrap()
-                .map(|row| {
-                    let l1_batch_number = L1BatchNumber(row.l1_batch_number as u32);
-                    let number_of_leaf_circuits = row.number_of_leaf_circuits.expect("number_of_leaf_circuits is not found in a `queued` `node_aggregation_witness_jobs` job");
-
-                    // Now that we have a job in `queued` status, we need to enrich it with the computed proofs.
-                    // We select `aggregation_round = 1` to only get leaf aggregation circuits
-                    let leaf_circuits_proofs: Vec<
-                        Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
-                    > = self.load_proofs_for_block(l1_batch_number, AggregationRound::LeafAggregation);
-
-                    assert_eq!(
-                        leaf_circuits_proofs.len(),
-                        number_of_leaf_circuits as usize,
-                        "node_aggregation_witness_job for l1 batch {} is in status `queued`, but there are only {} computed leaf proofs, which is different from expected {}",
-                        l1_batch_number,
-                        leaf_circuits_proofs.len(),
-                        number_of_leaf_circuits
-                    );
-                    WitnessGeneratorJobMetadata {
-                        block_number: l1_batch_number,
-                        proofs: leaf_circuits_proofs
-                    }
-                })
-        })
+        self.get_next_aggregation_witness_job(
+            AggregationRound::NodeAggregation,
+            processing_timeout,
+            max_attempts,
+            last_l1_batch_to_process,
+            picked_by,
+        )
     }
 
     pub fn get_next_scheduler_witness_job(
@@ -240,57 +183,63 @@ impl WitnessGeneratorDal<'_, '_> {
         processing_timeout: Duration,
         max_attempts: u32,
         last_l1_batch_to_process: u32,
+        picked_by: &str,
     ) -> Option<WitnessGeneratorJobMetadata> {
-        async_std::task::block_on(async {
-            let processing_timeout = pg_interval_from_duration(processing_timeout);
-            sqlx::query!(
-                "
-                UPDATE scheduler_witness_jobs
-                SET status = 'in_progress', attempts = attempts + 1,
-                    updated_at = now(), processing_started_at = now()
-                WHERE l1_batch_number = (
-                    SELECT l1_batch_number
-                    FROM scheduler_witness_jobs
-                    WHERE l1_batch_number <= $3
-                    AND
-                    (   status = 'queued'
-                        OR (status = 'in_progress' AND processing_started_at < now() - $1::interval)
-                        OR (status = 'failed' AND attempts < $2)
-                    )
-                    ORDER BY l1_batch_number ASC
-                    LIMIT 1
-                    FOR UPDATE
-                    SKIP LOCKED
-                )
-                RETURNING scheduler_witness_jobs.*
-                ", &processing_timeout, 
-                max_attempts as i32,
-                last_l1_batch_to_process as i64
-            )
-                .fetch_optional(self.storage.conn())
-                .await
-                .unwrap()
-                .map(|row| {
-                    let l1_batch_number = L1BatchNumber(row.l1_batch_number as u32);
-                    // Now that we have a job in `queued` status, we need to enrich it with the computed proof.
-                    // We select `aggregation_round = 2` to only get node aggregation circuits
-                    let leaf_circuits_proofs: Vec<
-                        Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
-                    > = self.load_proofs_for_block(l1_batch_number, AggregationRound::NodeAggregation);
-
-                    assert_eq!(
-                        leaf_circuits_proofs.len(),
-                        1usize,
-                        "scheduler_job for l1 batch {} is in status `queued`, but there is {} computed node proofs. We expect exactly one node proof.",
-                        l1_batch_number.0,
-                        leaf_circuits_proofs.len()
-                    );
-
-                    WitnessGeneratorJobMetadata {
-                        block_number: l1_batch_number,
-                        proofs: leaf_circuits_proofs
-                    }
-                })
+        self.get_next_aggregation_witness_job(
+            AggregationRound::Scheduler,
+            processing_timeout,
+            max_attempts,
+            last_l1_batch_to_process,
+            picked_by,
+        )
+    }
+
+    /// Leases the next job of the aggregation round and enriches it with the proofs computed
+    /// in the previous round.
+    fn get_next_aggregation_witness_job(
+        &mut self,
+        aggregation_round: AggregationRound,
+        processing_timeout: Duration,
+        max_attempts: u32,
+        last_l1_batch_to_process: u32,
+        picked_by: &str,
+    ) -> Option<WitnessGeneratorJobMetadata> {
+        let l1_batch_number = async_std::task::block_on(async {
+            let mut transaction = self.storage.start_transaction().await;
+            let mut queue = transaction.job_queue_dal(WitnessJobQueue(aggregation_round));
+            queue.requeue_expired(processing_timeout);
+            let l1_batch_number = queue
+                .lock_next_jobs(max_attempts, last_l1_batch_to_process, 1)
+                .into_iter()
+                .next();
+            if let Some(l1_batch_number) = l1_batch_number {
+                queue.lease(l1_batch_number, picked_by);
+            }
+            transaction.commit().await;
+            l1_batch_number
+        })?;
+
+        let proofs_round = match aggregation_round {
+            AggregationRound::BasicCircuits => unreachable!(),
+            AggregationRound::LeafAggregation => AggregationRound::BasicCircuits,
+            AggregationRound::NodeAggregation => AggregationRound::LeafAggregation,
+            AggregationRound::Scheduler => AggregationRound::NodeAggregation,
+        };
+        let required_proofs = self.required_proofs_count(l1_batch_number, aggregation_round);
+        let proofs = self.load_proofs_for_block(l1_batch_number, proofs_round);
+        assert_eq!(
+            proofs.len(),
+            required_proofs,
+            "{:?} witness job for L1 batch {} is leased, but there are {} computed {:?} proofs, which is different from expected {}",
+            aggregation_round,
+            l1_batch_number,
+            proofs.len(),
+            proofs_round,
+            required_proofs
+        );
+        Some(WitnessGeneratorJobMetadata {
+            block_number: l1_batch_number,
+            proofs,
         })
     }
 
@@ -330,20 +279,9 @@ impl WitnessGeneratorDal<'_, '_> {
         aggregation_round: AggregationRound,
         time_taken: Duration,
     ) {
-        async_std::task::block_on(async {
-            let table_name = Self::input_table_name_for(aggregation_round);
-            let sql = format!(
-                "UPDATE {}
-                     SET status = 'successful', updated_at = now(), time_taken = $1
-                     WHERE l1_batch_number = $2",
-                table_name
-            );
-            let mut query = sqlx::query(&sql);
-            query = query.bind(duration_to_naive_time(time_taken));
-            query = query.bind(block_number.0 as i64);
-
-            query.execute(self.storage.conn()).await.unwrap();
-        });
+        self.storage
+            .job_queue_dal(WitnessJobQueue(aggregation_round))
+            .mark_successful(block_number, time_taken);
     }
 
     /// Is invoked by the prover when all the required proofs are computed
@@ -352,19 +290,9 @@ impl WitnessGeneratorDal<'_, '_> {
         block_number: L1BatchNumber,
         aggregation_round: AggregationRound,
     ) {
-        async_std::task::block_on(async {
-            let table_name = Self::input_table_name_for(aggregation_round);
-            let sql = format!(
-                "UPDATE {}
-                     SET status = 'queued', updated_at = now()
-                     WHERE l1_batch_number = $1",
-                table_name
-            );
-            let mut query = sqlx::query(&sql);
-            query = query.bind(block_number.0 as i64);
-
-            query.execute(self.storage.conn()).await.unwrap();
-        });
+        self.storage
+            .job_queue_dal(WitnessJobQueue(aggregation_round))
+            .set_status(block_number, JobStatus::Queued);
     }
 
     pub fn mark_witness_job_as_skipped(
@@ -373,19 +301,10 @@ impl WitnessGeneratorDal<'_, '_> {
         aggregation_round: AggregationRound,
     ) {
         async_std::task::block_on(async {
-            let table_name = Self::input_table_name_for(aggregation_round);
-            let sql = format!(
-                "UPDATE {}
-                     SET status = 'skipped', updated_at = now()
-                     WHERE l1_batch_number = $1",
-                table_name
-            );
-            let mut query = sqlx::query(&sql);
-            query = query.bind(block_number.0 as i64);
-
             let mut transaction = self.storage.start_transaction().await;
-            query.execute(transaction.conn()).await.unwrap();
-
+            transaction
+                .job_queue_dal(WitnessJobQueue(aggregation_round))
+                .set_status(block_number, JobStatus::Skipped);
             transaction
                 .blocks_dal()
                 .set_skip_proof_for_l1_batch(block_number);
@@ -399,19 +318,9 @@ impl WitnessGeneratorDal<'_, '_> {
         block_number: L1BatchNumber,
         aggregation_round: AggregationRound,
     ) {
-        async_std::task::block_on(async {
-            let table_name = Self::input_table_name_for(aggregation_round);
-            let sql = format!(
-                "UPDATE {}
-                     SET status = 'waiting_for_proofs', updated_at = now()
-                     WHERE l1_batch_number = $1",
-                table_name
-            );
-            let mut query = sqlx::query(&sql);
-            query = query.bind(block_number.0 as i64);
-
-            query.execute(self.storage.conn()).await.unwrap();
-        });
+        self.storage
+            .job_queue_dal(WitnessJobQueue(aggregation_round))
+            .set_status(block_number, JobStatus::WaitingForProofs);
     }
 
     pub fn mark_witness_job_as_failed(
@@ -423,35 +332,32 @@ impl WitnessGeneratorDal<'_, '_> {
         max_attempts: u32,
     ) {
         async_std::task::block_on(async {
-            let table_name = Self::input_table_name_for(aggregation_round);
-            let sql = format!(
-                "UPDATE {}
-                    SET status = 'failed', updated_at = now(), time_taken = $1, error = $2
-                    WHERE l1_batch_number = $3
-                RETURNING attempts
-                ",
-                table_name
-            );
-            let mut query = sqlx::query(&sql);
-            query = query.bind(duration_to_naive_time(time_taken));
-            query = query.bind(error);
-            query = query.bind(block_number.0 as i64);
-
             let mut transaction = self.storage.start_transaction().await;
-            let attempts = query
-                .fetch_one(transaction.conn())
-                .await
-                .unwrap()
-                .get::<i32, &str>("attempts");
-            if attempts as u32 >= max_attempts {
+            let job = transaction
+                .job_queue_dal(WitnessJobQueue(aggregation_round))
+                .mark_failed(block_number, &error, Some(time_taken));
+            if job.attempts >= max_attempts {
                 transaction
                     .blocks_dal()
-                    .set_skip_proof_for_l1_batch(block_number);
+                    .set_skip_proof_for_l1_batch(job.l1_batch_number);
             }
             transaction.commit().await;
         })
     }
 
+    /// Marks the job as `artifact_corrupted`: its input artifacts failed the integrity check,
+    /// so the job isn't retried.
+    pub fn mark_witness_job_as_artifact_corrupted(
+        &mut self,
+        block_number: L1BatchNumber,
+        aggregation_round: AggregationRound,
+        error: String,
+    ) {
+        self.storage
+            .job_queue_dal(WitnessJobQueue(aggregation_round))
+            .mark_artifact_corrupted(block_number, &error);
+    }
+
     /// Creates a leaf_aggregation_job in `waiting_for_proofs` status,
     /// and also a node_aggregation_job and scheduler_job in `waiting_for_artifacts` status.
     /// The jobs will be advanced to `waiting_for_proofs` by the `Witness Generator` when the corresponding artifacts are computed,
@@ -602,31 +508,9 @@ impl WitnessGeneratorDal<'_, '_> {
         &mut self,
         aggregation_round: AggregationRound,
     ) -> JobCountStatistics {
-        async_std::task::block_on(async {
-            let table_name = Self::input_table_name_for(aggregation_round);
-            let sql = format!(
-                r#"
-                SELECT COUNT(*) as "count", status as "status"
-                FROM {}
-                GROUP BY status
-                "#,
-                table_name
-            );
-            let mut results: HashMap<String, i64> = sqlx::query(&sql)
-                .fetch_all(self.storage.conn())
-                .await
-                .unwrap()
-                .into_iter()
-                .map(|row| (row.get("status"), row.get::<i64, &str>("count")))
-                .collect::<HashMap<String, i64>>();
-
-            JobCountStatistics {
-                queued: results.remove("queued").unwrap_or(0i64) as usize,
-                in_progress: results.remove("in_progress").unwrap_or(0i64) as usize,
-                failed: results.remove("failed").unwrap_or(0i64) as usize,
-                successful: results.remove("successful").unwrap_or(0i64) as usize,
-            }
-        })
+        self.storage
+            .job_queue_dal(WitnessJobQueue(aggregation_round))
+            .stats()
     }
 
     pub fn required_proofs_count(
@@ -663,12 +547,7 @@ impl WitnessGeneratorDal<'_, '_> {
     }
 
     fn input_table_name_for(aggregation_round: AggregationRound) -> &'static str {
-        match aggregation_round {
-            AggregationRound::BasicCircuits => "witness_inputs",
-            AggregationRound::LeafAggregation => "leaf_aggregation_witness_jobs",
-            AggregationRound::NodeAggregation => "node_aggregation_witness_jobs",
-            AggregationRound::Scheduler => "scheduler_witness_jobs",
-        }
+        WitnessJobQueue(aggregation_round).table().name()
     }
 
     pub fn get_jobs(
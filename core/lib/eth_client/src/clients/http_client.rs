@@ -1,5 +1,6 @@
 // Built-in deps
 use std::cmp::min;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::{fmt, time::Instant};
 
@@ -20,7 +21,7 @@ use zksync_types::web3::{
         Address, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
         TransactionReceipt, H160, H256, U256, U64,
     },
-    Web3,
+    Transport, Web3,
 };
 use zksync_types::{L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE};
 
@@ -78,6 +79,17 @@ pub struct FailureInfo {
     pub gas_limit: U256,
 }
 
+/// Outcome of the transaction executed via `eth_call` without broadcasting it.
+#[derive(Debug, Clone)]
+pub struct SimulatedTxResult {
+    /// Revert reason, `None` if the call succeeded.
+    pub revert_reason: Option<String>,
+    /// Estimated gas usage of the successful call.
+    pub estimated_gas: Option<U256>,
+    /// `callTracer` trace of the reverted call, if `debug_traceCall` is supported by the node.
+    pub trace: Option<jsonrpc_core::Value>,
+}
+
 #[async_trait]
 pub trait EthInterface {
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error>;
@@ -95,6 +107,12 @@ pub trait EthInterface {
     ) -> Result<Vec<u64>, Error>;
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error>;
     async fn block_number(&self, component: &'static str) -> Result<U64, Error>;
+    /// Returns the timestamp of the block in seconds, or `None` if the block is not known to the node.
+    async fn block_timestamp(
+        &self,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<Option<u64>, Error>;
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error>;
     async fn sign_prepared_tx_for_addr(
         &self,
@@ -109,6 +127,14 @@ pub trait EthInterface {
         component: &'static str,
     ) -> Result<Option<ExecutedTxStatus>, Error>;
     async fn failure_reason(&self, tx_hash: H256) -> Result<Option<FailureInfo>, Error>;
+    /// Executes the call from the operator account on top of the latest L1 block.
+    async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas_limit: U256,
+        component: &'static str,
+    ) -> Result<SimulatedTxResult, Error>;
 }
 
 struct ETHDirectClientInner<S: EthereumSigner> {
@@ -161,6 +187,16 @@ impl<S: EthereumSigner> EthInterface for ETHDirectClient<S> {
         Ok(block_number)
     }
 
+    async fn block_timestamp(
+        &self,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<Option<u64>, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "block_timestamp");
+        let block = self.block(BlockId::Number(block)).await?;
+        Ok(block.map(|block| block.timestamp.as_u64()))
+    }
+
     async fn get_gas_price(&self, component: &'static str) -> Result<U256, Error> {
         metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "get_gas_price");
         let start = Instant::now();
@@ -384,6 +420,74 @@ impl<S: EthereumSigner> EthInterface for ETHDirectClient<S> {
             _ => Ok(None),
         }
     }
+
+    async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas_limit: U256,
+        component: &'static str,
+    ) -> Result<SimulatedTxResult, Error> {
+        metrics::counter!("server.ethereum_gateway.call", 1, "component" => component, "method" => "simulate_tx");
+        let start = Instant::now();
+        let call_request = web3::types::CallRequest {
+            from: Some(self.inner.sender_account),
+            to: Some(contract_addr),
+            gas: Some(gas_limit),
+            gas_price: None,
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            value: None,
+            data: Some(Bytes(data)),
+            transaction_type: None,
+            access_list: None,
+        };
+
+        let call_result = self.inner.web3.eth().call(call_request.clone(), None).await;
+        let result = match call_result {
+            Ok(_) => {
+                let estimated_gas = self
+                    .inner
+                    .web3
+                    .eth()
+                    .estimate_gas(call_request, None)
+                    .await?;
+                SimulatedTxResult {
+                    revert_reason: None,
+                    estimated_gas: Some(estimated_gas),
+                    trace: None,
+                }
+            }
+            Err(web3::Error::Rpc(rpc_error)) => {
+                // Not all nodes support tracing, so the trace is best-effort.
+                let mut trace_options = HashMap::new();
+                trace_options.insert("tracer", "callTracer");
+                let trace = self
+                    .inner
+                    .web3
+                    .transport()
+                    .execute(
+                        "debug_traceCall",
+                        vec![
+                            web3::helpers::serialize(&call_request),
+                            web3::helpers::serialize(&BlockNumber::Latest),
+                            web3::helpers::serialize(&trace_options),
+                        ],
+                    )
+                    .await
+                    .ok();
+                SimulatedTxResult {
+                    revert_reason: Some(rpc_error.message),
+                    estimated_gas: None,
+                    trace,
+                }
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        metrics::histogram!("eth_client.direct.simulate_tx", start.elapsed());
+        Ok(result)
+    }
 }
 
 impl<S: EthereumSigner> ETHDirectClient<S> {
@@ -14,7 +14,9 @@ use zksync_types::web3::{
 
 use zksync_types::{web3::types::TransactionReceipt, H160, H256, U256};
 
-use super::http_client::{Error, EthInterface, ExecutedTxStatus, FailureInfo, SignedCallResult};
+use super::http_client::{
+    Error, EthInterface, ExecutedTxStatus, FailureInfo, SignedCallResult, SimulatedTxResult,
+};
 
 #[derive(Debug, Clone, Default, Copy)]
 pub struct MockTx {
@@ -50,6 +52,7 @@ impl From<Vec<u8>> for MockTx {
 #[derive(Debug)]
 pub struct MockEthereum {
     pub block_number: AtomicU64,
+    pub block_timestamp: AtomicU64,
     pub max_fee_per_gas: U256,
     pub base_fee_history: RwLock<Vec<u64>>,
     pub max_priority_fee_per_gas: U256,
@@ -66,6 +69,7 @@ impl Default for MockEthereum {
             max_fee_per_gas: 100.into(),
             max_priority_fee_per_gas: 10.into(),
             block_number: Default::default(),
+            block_timestamp: Default::default(),
             base_fee_history: Default::default(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
@@ -178,6 +182,10 @@ impl EthInterface for MockEthereum {
         Ok(self.block_number.load(Ordering::SeqCst).into())
     }
 
+    async fn block_timestamp(&self, _: BlockNumber, _: &'static str) -> Result<Option<u64>, Error> {
+        Ok(Some(self.block_timestamp.load(Ordering::SeqCst)))
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         let mock_tx = MockTx::from(tx);
 
@@ -257,6 +265,20 @@ impl EthInterface for MockEthereum {
             gas_limit: U256::zero(),
         }))
     }
+
+    async fn simulate_tx(
+        &self,
+        _data: Vec<u8>,
+        _contract_addr: H160,
+        gas_limit: U256,
+        _: &'static str,
+    ) -> Result<SimulatedTxResult, Error> {
+        Ok(SimulatedTxResult {
+            revert_reason: None,
+            estimated_gas: Some(gas_limit),
+            trace: None,
+        })
+    }
 }
 
 #[async_trait]
@@ -292,6 +314,14 @@ impl<T: AsRef<MockEthereum> + Sync> EthInterface for T {
         self.as_ref().block_number(component).await
     }
 
+    async fn block_timestamp(
+        &self,
+        block: BlockNumber,
+        component: &'static str,
+    ) -> Result<Option<u64>, Error> {
+        self.as_ref().block_timestamp(block, component).await
+    }
+
     async fn send_raw_tx(&self, tx: Vec<u8>) -> Result<H256, Error> {
         self.as_ref().send_raw_tx(tx).await
     }
@@ -319,4 +349,16 @@ impl<T: AsRef<MockEthereum> + Sync> EthInterface for T {
     ) -> Result<Option<ExecutedTxStatus>, Error> {
         self.as_ref().get_tx_status(hash, component).await
     }
+
+    async fn simulate_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        gas_limit: U256,
+        component: &'static str,
+    ) -> Result<SimulatedTxResult, Error> {
+        self.as_ref()
+            .simulate_tx(data, contract_addr, gas_limit, component)
+            .await
+    }
 }
@@ -113,7 +113,7 @@ impl MempoolStore {
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
-        self.l1_transactions.get(&self.next_priority_id).is_some()
+        self.has_next_priority_op()
             || self
                 .l2_priority_queue
                 .iter()
@@ -121,6 +121,12 @@ impl MempoolStore {
                 .is_some()
     }
 
+    /// Returns `true` if the next priority operation is in the mempool, so it will be executed before
+    /// any L2 transaction.
+    pub fn has_next_priority_op(&self) -> bool {
+        self.l1_transactions.contains_key(&self.next_priority_id)
+    }
+
     /// Returns next transaction for execution from mempool
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
         if let Some(transaction) = self.l1_transactions.remove(&self.next_priority_id) {
@@ -192,6 +198,25 @@ impl MempoolStore {
         }
     }
 
+    /// Removes the L2 transactions that can't be included in a block with the given timestamp anymore.
+    /// Returns the number of removed transactions.
+    pub fn remove_expired(&mut self, timestamp: u64) -> usize {
+        let mut removed = 0;
+        for transactions in self.l2_transactions_per_account.values_mut() {
+            let (removed_for_account, next_tx_score) = transactions.remove_expired(timestamp);
+            if let Some(score) = next_tx_score {
+                self.l2_priority_queue.remove(&score);
+            }
+            removed += removed_for_account;
+        }
+        self.size = self
+            .size
+            .checked_sub(removed as u64)
+            .expect("mempool size can't be negative");
+        self.collect_stats();
+        removed
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
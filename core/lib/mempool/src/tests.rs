@@ -52,7 +52,7 @@ fn basic_flow() {
         view(mempool.next_transaction(&L2TxFilter::default())),
         (account1, 1)
     );
-    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+    assert!(mempool.next_transaction(&L2TxFilter::default()).is_none());
 }
 
 #[test]
@@ -359,6 +359,40 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn expired_txs() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let mut expiring_tx = L2Tx::new(
+        Address::default(),
+        Vec::new(),
+        Nonce(0),
+        Fee::default(),
+        account0,
+        U256::zero(),
+        None,
+        Default::default(),
+    );
+    expiring_tx.common_data.valid_until = Some(100);
+    let transactions = vec![
+        expiring_tx.into(),
+        gen_l2_tx(account0, Nonce(1)),
+        gen_l2_tx_with_timestamp(account1, Nonce(0), unix_timestamp_ms() + 1),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    assert_eq!(mempool.remove_expired(100), 0);
+    assert_eq!(mempool.remove_expired(150), 1);
+    assert_eq!(mempool.size(), 2);
+    // The successor of the expired transaction waits for its replacement.
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account1, 0)
+    );
+    assert!(mempool.next_transaction(&L2TxFilter::default()).is_none());
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -70,6 +70,20 @@ impl AccountTransactions {
             .map(Self::score_for_transaction)
     }
 
+    /// Removes the transactions that can't be included in a block with the given timestamp anymore.
+    /// Returns the number of removed transactions and the score of the removed next transaction, if any.
+    pub fn remove_expired(&mut self, timestamp: u64) -> (usize, Option<MempoolScore>) {
+        let next_tx_score = self
+            .transactions
+            .get(&self.nonce)
+            .filter(|tx| tx.common_data.is_expired_at(timestamp))
+            .map(Self::score_for_transaction);
+        let len = self.transactions.len();
+        self.transactions
+            .retain(|_, tx| !tx.common_data.is_expired_at(timestamp));
+        (len - self.transactions.len(), next_tx_score)
+    }
+
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
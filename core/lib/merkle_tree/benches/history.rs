@@ -0,0 +1,67 @@
+//! Benchmarks the overhead of keeping the node history of the tree on saves.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use tempfile::TempDir;
+use zksync_merkle_tree::ZkSyncTree;
+use zksync_storage::db::Database;
+use zksync_storage::RocksDB;
+use zksync_types::{AccountTreeId, Address, StorageKey, StorageLog, WitnessStorageLog, H256};
+
+/// Number of storage writes in each benchmarked block. Blocks are numbered from 1.
+const WRITES_PER_BLOCK: u64 = 500;
+/// Number of writes updating the existing leaves rather than inserting the new ones.
+const UPDATES_PER_BLOCK: u64 = WRITES_PER_BLOCK / 5;
+
+fn gen_block(block_index: u64) -> Vec<WitnessStorageLog> {
+    let account = AccountTreeId::new(Address::repeat_byte(0x01));
+    let first_new_slot = block_index * WRITES_PER_BLOCK;
+    // Updates the leaves inserted by the previous block.
+    let updated_slots = (0..UPDATES_PER_BLOCK).map(|i| first_new_slot - WRITES_PER_BLOCK + i);
+    let new_slots = first_new_slot..first_new_slot + WRITES_PER_BLOCK - UPDATES_PER_BLOCK;
+    updated_slots
+        .chain(new_slots)
+        .map(|slot| {
+            let key = StorageKey::new(account, H256::from_low_u64_be(slot));
+            let value = H256::from_low_u64_be(block_index + 1);
+            WitnessStorageLog {
+                storage_log: StorageLog::new_write_log(key, value),
+                previous_value: H256::zero(),
+            }
+        })
+        .collect()
+}
+
+fn bench_save(c: &mut Criterion, name: &str, history_enabled: bool) {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let db = RocksDB::new(Database::MerkleTree, temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new(db);
+    if history_enabled {
+        tree = tree.with_history();
+    }
+    let mut block_index = 0;
+
+    c.bench_function(name, |b| {
+        b.iter_batched(
+            || {
+                block_index += 1;
+                gen_block(block_index)
+            },
+            |logs| {
+                tree.process_block(&logs);
+                tree.save().unwrap();
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn save_without_history(c: &mut Criterion) {
+    bench_save(c, "save_without_history", false);
+}
+
+fn save_with_history(c: &mut Criterion) {
+    bench_save(c, "save_with_history", true);
+}
+
+criterion_group!(benches, save_without_history, save_with_history);
+criterion_main!(benches);
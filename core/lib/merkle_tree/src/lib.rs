@@ -17,7 +17,9 @@ mod utils;
 mod zksync_tree;
 
 use types::Bytes;
-pub use types::{InitialStorageWrite, RepeatedStorageWrite, TreeMetadata};
+pub use types::{
+    InitialStorageWrite, RepeatedStorageWrite, TreeEntryProof, TreeKey, TreeMetadata, TreeValue,
+};
 pub use zksync_tree::{TreeMode, ZkSyncTree};
 
 /// All kinds of Merkle Tree errors.
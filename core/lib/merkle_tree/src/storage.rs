@@ -8,12 +8,15 @@ use itertools::Itertools;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use zksync_storage::db::MerkleTreeColumnFamily;
-use zksync_storage::rocksdb::WriteBatch;
+use zksync_storage::rocksdb::{self, IteratorMode, WriteBatch};
 use zksync_storage::util::{deserialize_block_number, serialize_block_number, serialize_tree_leaf};
 use zksync_storage::RocksDB;
 
 const BLOCK_NUMBER_KEY: &[u8; 12] = b"block_number";
 const LEAF_INDEX_KEY: &[u8; 10] = b"leaf_index";
+/// Number of the latest tree versions (i.e., block numbers the tree was saved at) the node history
+/// is kept for, so that the tree can be read at any of these versions.
+pub(crate) const RETAINED_VERSIONS: u32 = 256;
 
 // Represents pending update that is yet to be flushed in RocksDB.
 #[derive(Default)]
@@ -26,21 +29,40 @@ impl Debug for PendingPatch {
 }
 
 /// Storage wrapper around RocksDB.
-/// Stores hashes of branch nodes in merkle tree and current block number
+/// Stores hashes of branch nodes in merkle tree and current block number.
+///
+/// If the history is enabled, the storage additionally keeps the history of the nodes for the last
+/// [`RETAINED_VERSIONS`] versions of the tree. Each save records the previous hashes of the changed nodes keyed by the node and the new
+/// version, so the hash of a node at a certain version is the first previous hash recorded after this version,
+/// or the current hash if the node hasn't changed since then. Without the history, the tree can only be read
+/// at its latest version.
 #[derive(Debug)]
 pub struct Storage {
     db: RocksDB,
+    history_enabled: bool,
     pending_patch: PendingPatch,
+    /// Nodes changed by the pending patch.
+    pending_nodes: Vec<LevelIndex>,
+    /// Next leaf index after the pending patch, if it assigns the indices.
+    pending_leaf_index: Option<u64>,
 }
 
 impl Storage {
     pub fn new(db: RocksDB) -> Self {
         Self {
             db,
+            history_enabled: false,
             pending_patch: PendingPatch(WriteBatch::default()),
+            pending_nodes: Vec::new(),
+            pending_leaf_index: None,
         }
     }
 
+    /// Enables recording the node history on the following saves.
+    pub fn enable_history(&mut self) {
+        self.history_enabled = true;
+    }
+
     /// Fetches hashes of merkle tree branches from db
     pub fn hashes<'a, I: 'a>(&'a self, keys: I) -> Vec<Option<Vec<u8>>>
     where
@@ -53,10 +75,28 @@ impl Storage {
             .unwrap()
     }
 
+    /// Fetches enumeration indices of the leaves. The index is 0 for the leaves that have never been written.
+    pub fn leaf_indices(&self, keys: &[TreeKey]) -> Vec<u64> {
+        Self::read_leaf_indices(&self.db, keys)
+    }
+
+    fn read_leaf_indices(db: &RocksDB, keys: &[TreeKey]) -> Vec<u64> {
+        let cf = db.cf_merkle_tree_handle(MerkleTreeColumnFamily::LeafIndices);
+        db.multi_get_cf(keys.iter().map(|key| (cf, serialize_tree_leaf(*key))))
+            .into_iter()
+            .map(|raw_data| {
+                raw_data
+                    .expect("failed to fetch leaf index")
+                    .map_or(0, |bytes| deserialize_leaf_index(&bytes))
+            })
+            .collect()
+    }
+
     /// Prepares db update
     pub fn pre_save(&mut self, branches: HashMap<LevelIndex, Vec<u8>>) {
         for (level_index, value) in branches {
             self.pending_patch.0.put(level_index.bin_key(), value);
+            self.pending_nodes.push(level_index);
         }
     }
 
@@ -67,6 +107,36 @@ impl Storage {
         }
         let mut write_batch =
             std::mem::replace(&mut self.pending_patch, PendingPatch(WriteBatch::default())).0;
+        let pending_nodes = std::mem::take(&mut self.pending_nodes);
+        let next_leaf_index = match self.pending_leaf_index.take() {
+            Some(leaf_index) => leaf_index,
+            None => Self::read_next_leaf_index(&self.db)?,
+        };
+        let previous_block_number = Self::read_block_number(&self.db)?;
+        if block_number > previous_block_number {
+            let retained_versions = if self.history_enabled {
+                self.record_history(&mut write_batch, block_number, &pending_nodes);
+                RETAINED_VERSIONS
+            } else {
+                // Prunes the history recorded while it was enabled, if any.
+                0
+            };
+            let min_retained_version = block_number.saturating_sub(retained_versions);
+            let pruned_from = previous_block_number.saturating_sub(RETAINED_VERSIONS);
+            self.prune_history(&mut write_batch, pruned_from, min_retained_version);
+        } else {
+            // The tree is reverted, so the history of the reverted versions is no longer valid.
+            // The previous hashes are not recorded: the tree returns to the state it had at this version.
+            self.remove_history_after(&mut write_batch, block_number);
+        }
+        let versions_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::Versions);
+        write_batch.put_cf(
+            versions_cf,
+            serialize_version(block_number),
+            serialize_leaf_index(next_leaf_index),
+        );
         write_batch.put(BLOCK_NUMBER_KEY, serialize_block_number(block_number));
 
         // Sync write is not used here intentionally. It somewhat improves write performance.
@@ -77,6 +147,78 @@ impl Storage {
             .map_err(TreeError::StorageIoError)
     }
 
+    /// Records the current hashes of the nodes changed at the new version.
+    fn record_history(&self, write_batch: &mut WriteBatch, version: u32, nodes: &[LevelIndex]) {
+        let history_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::NodeHistory);
+        let versions_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::Versions);
+        let previous_hashes = self.hashes(nodes);
+        for (node, previous_hash) in nodes.iter().zip(previous_hashes) {
+            let node_key = node.bin_key();
+            // Empty value denotes the absent node.
+            write_batch.put_cf(
+                history_cf,
+                history_key(&node_key, version),
+                previous_hash.unwrap_or_default(),
+            );
+            write_batch.put_cf(versions_cf, versioned_node_key(version, &node_key), b"");
+        }
+    }
+
+    /// Removes the history only needed to read the tree at the versions before `min_retained_version`.
+    /// The history before `pruned_from` is assumed to be removed already.
+    fn prune_history(
+        &self,
+        write_batch: &mut WriteBatch,
+        pruned_from: u32,
+        min_retained_version: u32,
+    ) {
+        let history_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::NodeHistory);
+        let versions_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::Versions);
+        let start = serialize_version(pruned_from);
+        let mode = IteratorMode::From(&start, rocksdb::Direction::Forward);
+        for (key, _) in self.db.iterator_cf(versions_cf, mode) {
+            let version = deserialize_version(&key);
+            if version > min_retained_version {
+                break;
+            }
+            if key.len() > VERSION_LEN {
+                // Previous hashes recorded at `min_retained_version` or before are only needed
+                // to read the tree at the earlier versions.
+                write_batch.delete_cf(history_cf, history_key(&key[VERSION_LEN..], version));
+                write_batch.delete_cf(versions_cf, &key);
+            } else if version < min_retained_version {
+                write_batch.delete_cf(versions_cf, &key);
+            }
+        }
+    }
+
+    /// Removes the history of the versions after `version`.
+    fn remove_history_after(&self, write_batch: &mut WriteBatch, version: u32) {
+        let history_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::NodeHistory);
+        let versions_cf = self
+            .db
+            .cf_merkle_tree_handle(MerkleTreeColumnFamily::Versions);
+        let start = serialize_version(version + 1);
+        let mode = IteratorMode::From(&start, rocksdb::Direction::Forward);
+        for (key, _) in self.db.iterator_cf(versions_cf, mode) {
+            if key.len() > VERSION_LEN {
+                let version = deserialize_version(&key);
+                write_batch.delete_cf(history_cf, history_key(&key[VERSION_LEN..], version));
+            }
+            write_batch.delete_cf(versions_cf, &key);
+        }
+    }
+
     /// Updates mapping between leaf index and its historical first occurrence and returns it
     ///
     /// note: for simplicity this column family update is done separately from the main one
@@ -174,6 +316,7 @@ impl Storage {
 
         write_batch.put_cf(cf, LEAF_INDEX_KEY, serialize_leaf_index(current_index));
         self.pending_patch = PendingPatch(write_batch);
+        self.pending_leaf_index = Some(current_index);
 
         Ok(result)
     }
@@ -183,13 +326,93 @@ impl Storage {
         // Fetch root hash. It is represented by level index (0, 0).
         let root_hash = self.hashes(vec![&(0, 0.into()).into()])[0].clone();
 
-        let block_number = self
-            .db
-            .get(BLOCK_NUMBER_KEY)
-            .expect("failed to fetch tree metadata")
+        let block_number =
+            Self::read_block_number(&self.db).expect("failed to fetch tree metadata");
+        (root_hash, block_number)
+    }
+
+    /// Reads the current block number of the tree stored in the database.
+    pub fn read_block_number(db: &RocksDB) -> Result<u32, rocksdb::Error> {
+        let block_number = db
+            .get(BLOCK_NUMBER_KEY)?
             .map(|bytes| deserialize_block_number(&bytes))
             .unwrap_or(0);
-        (root_hash, block_number)
+        Ok(block_number)
+    }
+
+    /// Reads the index the next new leaf will be assigned in the tree stored in the database.
+    fn read_next_leaf_index(db: &RocksDB) -> Result<u64, rocksdb::Error> {
+        let cf = db.cf_merkle_tree_handle(MerkleTreeColumnFamily::LeafIndices);
+        let leaf_index = db
+            .get_cf(cf, LEAF_INDEX_KEY)?
+            .map(|bytes| deserialize_leaf_index(&bytes))
+            .unwrap_or(1);
+        Ok(leaf_index)
+    }
+
+    /// Reads the index the next new leaf was assigned at the given version of the tree stored in the database.
+    /// Returns `None` if the tree can't be read at this version.
+    pub fn read_next_leaf_index_at(db: &RocksDB, version: u32) -> Option<u64> {
+        let cf = db.cf_merkle_tree_handle(MerkleTreeColumnFamily::Versions);
+        let leaf_index = db
+            .get_cf(cf, serialize_version(version))
+            .expect("failed to fetch tree version");
+        match leaf_index {
+            Some(bytes) => Some(deserialize_leaf_index(&bytes)),
+            // The latest version may be saved before the history was introduced.
+            None if Self::read_block_number(db).ok()? == version => {
+                Self::read_next_leaf_index(db).ok()
+            }
+            None => None,
+        }
+    }
+
+    /// Reads enumeration indices of the leaves at the given version of the tree stored in the database.
+    /// Returns `None` if the tree can't be read at this version.
+    pub fn read_leaf_indices_at(db: &RocksDB, version: u32, keys: &[TreeKey]) -> Option<Vec<u64>> {
+        let next_leaf_index = Self::read_next_leaf_index_at(db, version)?;
+        // Leaf indices are assigned sequentially, so the leaves with the greater indices
+        // were written after this version.
+        let leaf_indices = Self::read_leaf_indices(db, keys)
+            .into_iter()
+            .map(|index| if index < next_leaf_index { index } else { 0 })
+            .collect();
+        Some(leaf_indices)
+    }
+
+    /// Reads hashes of merkle tree branches at the given version of the tree stored in the database.
+    /// The version must be retained, see [`Self::read_next_leaf_index_at()`].
+    pub fn read_hashes_at<'a, I>(db: &RocksDB, version: u32, keys: I) -> Vec<Option<Vec<u8>>>
+    where
+        I: IntoIterator<Item = &'a LevelIndex> + Clone,
+    {
+        let current_hashes = db
+            .multi_get(keys.clone().into_iter().map(LevelIndex::bin_key))
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let history_cf = db.cf_merkle_tree_handle(MerkleTreeColumnFamily::NodeHistory);
+        let mut history = db.raw_iterator_cf(history_cf);
+
+        keys.into_iter()
+            .zip(current_hashes)
+            .map(|(node, current_hash)| {
+                let node_key = node.bin_key();
+                history.seek(history_key(&node_key, version + 1));
+                if !history.valid() {
+                    history.status().expect("failed to fetch node history");
+                }
+                match history.key() {
+                    // Node keys are prefix-free, so the found key is the history key of the same node.
+                    Some(key) if key.starts_with(&node_key) => {
+                        let previous_hash = history.value().unwrap();
+                        (!previous_hash.is_empty()).then(|| previous_hash.to_vec())
+                    }
+                    // The node hasn't changed after this version.
+                    _ => current_hash,
+                }
+            })
+            .collect()
     }
 }
 
@@ -203,6 +426,25 @@ pub(crate) fn serialize_leaf_index(leaf_index: u64) -> Vec<u8> {
     bytes
 }
 
+const VERSION_LEN: usize = 4;
+
+/// Versions are serialized as big-endian, so that the keys starting with them are ordered by version.
+fn serialize_version(version: u32) -> [u8; VERSION_LEN] {
+    version.to_be_bytes()
+}
+
+fn deserialize_version(bytes: &[u8]) -> u32 {
+    BigEndian::read_u32(&bytes[..VERSION_LEN])
+}
+
+fn history_key(node_key: &[u8], version: u32) -> Vec<u8> {
+    [node_key, &serialize_version(version)].concat()
+}
+
+fn versioned_node_key(version: u32, node_key: &[u8]) -> Vec<u8> {
+    [&serialize_version(version), node_key].concat()
+}
+
 fn deserialize_leaf_index(mut bytes: &[u8]) -> u64 {
     bytes
         .read_u64::<BigEndian>()
@@ -1,9 +1,10 @@
+use crate::storage::RETAINED_VERSIONS;
 use crate::tree_config::TreeConfig;
 use crate::types::{TreeKey, ZkHash, ZkHasher};
 use crate::ZkSyncTree;
 use std::str::FromStr;
 use tempfile::TempDir;
-use zksync_config::constants::ACCOUNT_CODE_STORAGE_ADDRESS;
+use zksync_config::constants::{ACCOUNT_CODE_STORAGE_ADDRESS, ROOT_TREE_DEPTH};
 use zksync_storage::db::Database;
 use zksync_storage::RocksDB;
 use zksync_types::proofs::StorageLogMetadata;
@@ -471,3 +472,161 @@ fn process_block_idempotency_check() {
         "repeated writes must be same on multiple calls to process_block to ensure idempotency"
     );
 }
+
+#[test]
+fn entry_proofs() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let db = RocksDB::new(Database::MerkleTree, temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new(db);
+    tree.process_block(&logs);
+    tree.save().unwrap();
+
+    let missing_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    let mut keys: Vec<_> = logs
+        .iter()
+        .map(|log| log.storage_log.key.hashed_key_u256())
+        .collect();
+    keys.push(missing_key.hashed_key_u256());
+    let proofs = tree.get_proofs(&keys);
+
+    for (i, (log, proof)) in logs.iter().zip(&proofs).enumerate() {
+        assert_eq!(proof.leaf_index, i as u64 + 1);
+        assert_eq!(proof.merkle_path.len(), ROOT_TREE_DEPTH);
+        let root_hash = proof.root_hash(keys[i], log.storage_log.value);
+        assert_eq!(root_hash, tree.root_hash());
+    }
+    let missing_key_proof = proofs.last().unwrap();
+    assert_eq!(missing_key_proof.leaf_index, 0);
+    assert_eq!(
+        missing_key_proof.root_hash(missing_key.hashed_key_u256(), H256::zero()),
+        tree.root_hash()
+    );
+    assert_ne!(
+        proofs[0].root_hash(keys[0], H256::repeat_byte(0xff)),
+        tree.root_hash()
+    );
+}
+
+#[test]
+fn historical_entry_proofs() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let keys: Vec<_> = logs
+        .iter()
+        .map(|log| log.storage_log.key.hashed_key_u256())
+        .collect();
+    let db = RocksDB::new(Database::MerkleTree, temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new(db).with_history();
+    let root_hashes: Vec<_> = logs
+        .chunks(20)
+        .map(|chunk| {
+            tree.process_block(chunk);
+            tree.save().unwrap();
+            tree.root_hash()
+        })
+        .collect();
+
+    let assert_proofs_at = |block_number: u32| {
+        let db = RocksDB::new_read_only(Database::MerkleTree, temp_dir.as_ref());
+        let (root_hash, proofs) = ZkSyncTree::read_proofs_at(&db, block_number, &keys).unwrap();
+        assert_eq!(root_hash, root_hashes[block_number as usize - 1]);
+        for (i, (log, proof)) in logs.iter().zip(&proofs).enumerate() {
+            let value = if i < block_number as usize * 20 {
+                assert_eq!(proof.leaf_index, i as u64 + 1);
+                log.storage_log.value
+            } else {
+                assert_eq!(proof.leaf_index, 0);
+                H256::zero()
+            };
+            assert_eq!(proof.root_hash(keys[i], value), root_hash);
+        }
+    };
+    for block_number in 1..=5 {
+        assert_proofs_at(block_number);
+    }
+    let db = RocksDB::new_read_only(Database::MerkleTree, temp_dir.as_ref());
+    assert!(ZkSyncTree::read_proofs_at(&db, 0, &keys).is_none());
+    assert!(ZkSyncTree::read_proofs_at(&db, 6, &keys).is_none());
+
+    // Revert the last 2 blocks; the tree can no longer be read at the reverted block numbers.
+    let logs_to_revert = keys[60..].iter().map(|&key| (key, None)).collect();
+    tree.revert_logs(L1BatchNumber(2), logs_to_revert);
+    tree.save().unwrap();
+    assert_eq!(tree.root_hash(), root_hashes[2]);
+    for block_number in 1..=3 {
+        assert_proofs_at(block_number);
+    }
+    let db = RocksDB::new_read_only(Database::MerkleTree, temp_dir.as_ref());
+    assert!(ZkSyncTree::read_proofs_at(&db, 4, &keys).is_none());
+
+    tree.process_block(&logs[60..80]);
+    tree.save().unwrap();
+    for block_number in 1..=4 {
+        assert_proofs_at(block_number);
+    }
+}
+
+#[test]
+fn historical_entry_proofs_are_pruned() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let keys = [logs[0].storage_log.key.hashed_key_u256()];
+    let db = RocksDB::new(Database::MerkleTree, temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new(db).with_history();
+    let root_hashes: Vec<_> = logs
+        .iter()
+        .cycle()
+        .take(RETAINED_VERSIONS as usize + 2)
+        .map(|log| {
+            tree.process_block(vec![log]);
+            tree.save().unwrap();
+            tree.root_hash()
+        })
+        .collect();
+
+    let db = RocksDB::new_read_only(Database::MerkleTree, temp_dir.as_ref());
+    assert!(ZkSyncTree::read_proofs_at(&db, 1, &keys).is_none());
+    for block_number in [2, 3, RETAINED_VERSIONS + 2] {
+        let (root_hash, _) = ZkSyncTree::read_proofs_at(&db, block_number, &keys).unwrap();
+        assert_eq!(root_hash, root_hashes[block_number as usize - 1]);
+    }
+}
+
+#[test]
+fn entry_proofs_without_history() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let keys: Vec<_> = logs
+        .iter()
+        .map(|log| log.storage_log.key.hashed_key_u256())
+        .collect();
+    let db = RocksDB::new(Database::MerkleTree, temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new(db).with_history();
+    for chunk in logs[..40].chunks(20) {
+        tree.process_block(chunk);
+        tree.save().unwrap();
+    }
+    drop(tree);
+
+    // The history recorded before it was disabled is pruned on the next save.
+    let db = RocksDB::new(Database::MerkleTree, temp_dir.as_ref(), false);
+    let mut tree = ZkSyncTree::new(db);
+    tree.process_block(&logs[40..60]);
+    tree.save().unwrap();
+
+    let db = RocksDB::new_read_only(Database::MerkleTree, temp_dir.as_ref());
+    for block_number in [1, 2] {
+        assert!(ZkSyncTree::read_proofs_at(&db, block_number, &keys).is_none());
+    }
+    let (root_hash, proofs) = ZkSyncTree::read_proofs_at(&db, 3, &keys).unwrap();
+    assert_eq!(root_hash, tree.root_hash());
+    for (i, (log, proof)) in logs.iter().zip(&proofs).enumerate() {
+        let value = if i < 60 {
+            log.storage_log.value
+        } else {
+            H256::zero()
+        };
+        assert_eq!(proof.root_hash(keys[i], value), root_hash);
+    }
+}
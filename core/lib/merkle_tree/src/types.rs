@@ -1,6 +1,7 @@
 //! Definitions of types used in Merkle Tree implementation.
 
-use crate::U256;
+use crate::storage::serialize_leaf_index;
+use crate::{Hasher, U256};
 use serde::Serialize;
 use std::collections::HashMap;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
@@ -80,6 +81,37 @@ pub struct TreeMetadata {
     pub repeated_writes: Vec<RepeatedStorageWrite>,
 }
 
+/// Proof of the value of a tree entry against the tree root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeEntryProof {
+    /// Enumeration index of the leaf, 0 if the entry has never been written.
+    pub leaf_index: u64,
+    /// Hashes of the sibling nodes on the path from the leaf to the root.
+    pub merkle_path: Vec<ZkHash>,
+}
+
+impl TreeEntryProof {
+    /// Computes the root hash of the tree containing the entry with the given key and value.
+    /// The proof is valid if the computed hash is equal to the actual root hash.
+    pub fn root_hash(&self, key: TreeKey, value: TreeValue) -> ZkHash {
+        let hasher = ZkHasher::default();
+        let leaf_hash = hasher.compress(
+            &serialize_leaf_index(self.leaf_index),
+            &value.to_fixed_bytes().to_vec(),
+        );
+        self.merkle_path
+            .iter()
+            .enumerate()
+            .fold(leaf_hash, |hash, (depth, sibling_hash)| {
+                if key.bit(depth) {
+                    hasher.compress(sibling_hash, &hash)
+                } else {
+                    hasher.compress(&hash, sibling_hash)
+                }
+            })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct LeafIndices {
     pub leaf_indices: HashMap<TreeKey, u64>,
@@ -2,8 +2,8 @@ use crate::patch::{TreePatch, Update, UpdatesBatch};
 use crate::storage::{serialize_leaf_index, Storage};
 use crate::tree_config::TreeConfig;
 use crate::types::{
-    LeafIndices, LevelIndex, NodeEntry, TreeKey, TreeMetadata, TreeOperation, TreeValue, ZkHash,
-    ZkHasher,
+    LeafIndices, LevelIndex, NodeEntry, TreeEntryProof, TreeKey, TreeMetadata, TreeOperation,
+    TreeValue, ZkHash, ZkHasher,
 };
 use crate::utils::children_idxs;
 use crate::{utils, TreeError};
@@ -16,7 +16,7 @@ use std::sync::Arc;
 use tokio::time::Instant;
 use zksync_config::constants::ROOT_TREE_DEPTH;
 use zksync_crypto::hasher::Hasher;
-use zksync_storage::RocksDB;
+use zksync_storage::{rocksdb, RocksDB};
 use zksync_types::proofs::StorageLogMetadata;
 use zksync_types::{L1BatchNumber, StorageLogKind, WitnessStorageLog, H256};
 
@@ -60,6 +60,13 @@ impl ZkSyncTree {
         Self::new_with_mode(db, TreeMode::Lightweight)
     }
 
+    /// Makes the tree keep the history of its recent versions, so that it can be read at any of them
+    /// with [`Self::read_proofs_at()`]. Recording the history slows down the saves, so it's disabled by default.
+    pub fn with_history(mut self) -> Self {
+        self.storage.enable_history();
+        self
+    }
+
     pub fn root_hash(&self) -> ZkHash {
         self.root_hash.clone()
     }
@@ -72,6 +79,12 @@ impl ZkSyncTree {
         self.block_number
     }
 
+    /// Reads the block number of the tree stored in the database without loading the tree, e.g. from
+    /// a secondary instance following the database used by the tree.
+    pub fn read_block_number(db: &RocksDB) -> Result<u32, rocksdb::Error> {
+        Storage::read_block_number(db)
+    }
+
     /// Returns current hasher.
     fn hasher(&self) -> &ZkHasher {
         self.config.hasher()
@@ -350,6 +363,66 @@ impl ZkSyncTree {
             .map(move |idx| utils::idx_to_merkle_path(idx).map(hash_by_lvl_idx.clone()))
     }
 
+    /// Returns the proofs of the current values of the entries with the given keys.
+    /// Note that the tree doesn't store the values themselves, so they have to be fetched separately
+    /// to verify the proofs.
+    pub fn get_proofs(&self, keys: &[TreeKey]) -> Vec<TreeEntryProof> {
+        let leaf_indices = self.storage.leaf_indices(keys);
+        self.get_leaves_paths(keys.iter().copied())
+            .zip(leaf_indices)
+            .map(|(path, leaf_index)| TreeEntryProof {
+                leaf_index,
+                // The path is ordered from the root to the leaf.
+                merkle_path: path.rev().map(|(_, hash)| hash).collect(),
+            })
+            .collect()
+    }
+
+    /// Reads the root hash and the proofs of the values of the entries with the given keys at the given block
+    /// number of the tree stored in the database, e.g. from a secondary instance following the database
+    /// used by the tree. Returns `None` if the tree can't be read at this block number: the tree can only be read
+    /// at one of the recent block numbers it was saved at if it keeps the history (see [`Self::with_history()`]),
+    /// and at the latest block number otherwise.
+    pub fn read_proofs_at(
+        db: &RocksDB,
+        block_number: u32,
+        keys: &[TreeKey],
+    ) -> Option<(ZkHash, Vec<TreeEntryProof>)> {
+        let leaf_indices = Storage::read_leaf_indices_at(db, block_number, keys)?;
+        let config = TreeConfig::new(ZkHasher::default());
+        let empty_tree = config.empty_tree();
+
+        let root: LevelIndex = (0, TreeKey::zero()).into();
+        let nodes: HashSet<_> = once(root.clone())
+            .chain(keys.iter().copied().flat_map(utils::idx_to_merkle_path))
+            .collect();
+        let nodes: Vec<_> = nodes.into_iter().collect();
+        let hashes: HashMap<_, _> = nodes
+            .iter()
+            .cloned()
+            .zip(Storage::read_hashes_at(db, block_number, &nodes))
+            .collect();
+        let hash_by_lvl_idx = |lvl_idx: LevelIndex| {
+            hashes[&lvl_idx]
+                .clone()
+                .unwrap_or_else(|| empty_tree[lvl_idx.0 .0 as usize].hash().to_vec())
+        };
+
+        let proofs = keys
+            .iter()
+            .zip(leaf_indices)
+            .map(|(&key, leaf_index)| TreeEntryProof {
+                leaf_index,
+                // The path is ordered from the root to the leaf.
+                merkle_path: utils::idx_to_merkle_path(key)
+                    .rev()
+                    .map(hash_by_lvl_idx)
+                    .collect(),
+            })
+            .collect();
+        Some((hash_by_lvl_idx(root), proofs))
+    }
+
     fn make_node(level: usize, key: TreeKey, node: NodeEntry) -> (LevelIndex, Vec<u8>) {
         (
             ((ROOT_TREE_DEPTH - level) as u16, key).into(),
@@ -5,6 +5,7 @@
 //! Resulted tree is left-leaning, meaning that all meaningful non-trivial paths will be stored in the left subtree.
 use once_cell::sync::OnceCell;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator, ParallelSlice};
+use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use std::collections::HashMap;
 use zksync_basic_types::H256;
@@ -17,27 +18,97 @@ const MAX_NUMBER_OF_LEAVES: u32 = 2_u32.pow(MINI_TREE_MAX_DEPTH as u32);
 type ZkHasher = KeccakHasher;
 type EmptyTree = Vec<Vec<u8>>;
 
+/// Merkle tree with all its levels kept in memory, so that the proofs for any of the leaves
+/// can be produced without rehashing the tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MiniMerkleTree {
+    /// Hashes of the tree levels from the leaves to the root. Empty subtrees on the right aren't stored.
+    levels: Vec<Vec<Vec<u8>>>,
+    leaf_size: usize,
+}
+
+impl MiniMerkleTree {
+    /// Builds the tree from the list of leaf values. The leaves could have different sizes, normally
+    /// it's 32 bytes. But it's not always an option, e.g. leaf for L2L1Message is 88 bytes.
+    pub fn new<V>(values: Vec<V>, leaf_size: usize, tree_size: usize) -> Self
+    where
+        V: IntoIterator<Item = u8> + Send,
+    {
+        assert!(
+            values.len() as u32 <= MAX_NUMBER_OF_LEAVES,
+            "number of leaves exceeds merkle tree capacity"
+        );
+
+        // pick merkle tree depth
+        let depth = max(tree_size.trailing_zeros() as usize, MINI_TREE_MIN_DEPTH);
+        let empty_tree = empty_tree(depth, leaf_size);
+
+        // compute leaf hashes
+        let hasher = ZkHasher::default();
+        let mut current_level: Vec<_> = values
+            .into_par_iter()
+            .map(|value| hasher.hash_bytes(value))
+            .collect();
+
+        // iterate tree level by level bottom-up, group neighbour nodes and emit their cumulative hash
+        let mut levels = Vec::with_capacity(depth + 1);
+        for level_idx in 1..=depth {
+            let default_value = &empty_tree[level_idx - 1];
+            let next_level = current_level
+                .par_chunks(2)
+                .map(|chunk| {
+                    let right = chunk.get(1).unwrap_or(default_value);
+                    hasher.compress(&chunk[0], right)
+                })
+                .collect();
+            levels.push(std::mem::replace(&mut current_level, next_level));
+        }
+        levels.push(current_level);
+
+        Self { levels, leaf_size }
+    }
+
+    pub fn leaves_count(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns the root hash of the tree, or zero hash if the tree has no leaves.
+    pub fn root_hash(&self) -> H256 {
+        match self.levels.last().unwrap().first() {
+            Some(root) => H256::from_slice(root),
+            None => H256::zero(),
+        }
+    }
+
+    /// Returns the hashes of the neighbour nodes on the path from the leaf to the root.
+    pub fn merkle_path(&self, mut idx: usize) -> Vec<H256> {
+        assert!(idx < self.leaves_count(), "invalid tree leaf index");
+        let depth = self.levels.len() - 1;
+        let empty_tree = empty_tree(depth, self.leaf_size);
+
+        let mut path = Vec::with_capacity(depth);
+        for (level_idx, level) in self.levels[..depth].iter().enumerate() {
+            let neighbour_hash = level.get(idx ^ 1).unwrap_or(&empty_tree[level_idx]);
+            path.push(H256::from_slice(neighbour_hash));
+            idx /= 2;
+        }
+        path
+    }
+}
+
 /// Computes root hash of merkle tree by given list of leaf values. The leaves could have different sizes, normally
 /// it's 32 bytes. But it's not always an option, e.g. leaf for L2L1Message is 88 bytes.
 pub fn mini_merkle_tree_root_hash<V>(values: Vec<V>, leaf_size: usize, tree_size: usize) -> H256
 where
     V: IntoIterator<Item = u8> + Send,
 {
-    if values.is_empty() {
-        return H256::zero();
-    }
-    H256::from_slice(
-        mini_merkle_tree_proof(values, 0, leaf_size, tree_size)
-            .pop()
-            .unwrap()
-            .as_slice(),
-    )
+    MiniMerkleTree::new(values, leaf_size, tree_size).root_hash()
 }
 
 /// Recalculates hashes in merkle tree and returns root hash and merkle proof for specified leaf
 pub fn mini_merkle_tree_proof<V>(
     values: Vec<V>,
-    mut idx: usize,
+    idx: usize,
     leaf_size: usize,
     tree_size: usize,
 ) -> Vec<Vec<u8>>
@@ -45,40 +116,13 @@ where
     V: IntoIterator<Item = u8> + Send,
 {
     assert!(idx < values.len(), "invalid tree leaf index");
-    assert!(
-        values.len() as u32 <= MAX_NUMBER_OF_LEAVES,
-        "number of leaves exceeds merkle tree capacity"
-    );
-
-    // pick merkle tree depth
-    let depth = max(tree_size.trailing_zeros() as usize, MINI_TREE_MIN_DEPTH);
-    let empty_tree = empty_tree(depth, leaf_size);
-
-    // compute leaf hashes
-    let hasher = ZkHasher::default();
-    let mut current_level: Vec<_> = values
-        .into_par_iter()
-        .map(|value| hasher.hash_bytes(value))
+    let tree = MiniMerkleTree::new(values, leaf_size, tree_size);
+    let mut proof: Vec<_> = tree
+        .merkle_path(idx)
+        .into_iter()
+        .map(|hash| hash.as_bytes().to_vec())
         .collect();
-
-    // iterate tree level by level bottom-up, group neighbour nodes and emit their cumulative hash
-    let mut proof = Vec::with_capacity(depth + 1);
-    for level_idx in 1..=depth {
-        let default_value = empty_tree[level_idx - 1].clone();
-        let neighbour_idx = idx ^ 1;
-        let neighbour_hash = current_level.get(neighbour_idx).unwrap_or(&default_value);
-        proof.push(neighbour_hash.clone());
-
-        current_level = current_level
-            .par_chunks(2)
-            .map(|chunk| {
-                let right = chunk.get(1).unwrap_or(&default_value);
-                hasher.compress(&chunk[0], right)
-            })
-            .collect();
-        idx /= 2;
-    }
-    proof.push(current_level[0].clone());
+    proof.push(tree.root_hash().as_bytes().to_vec());
     proof
 }
 
@@ -200,6 +244,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn merkle_paths_lead_to_root() {
+        let hasher = ZkHasher::default();
+        let values = gen_test_data(0, 100);
+        let tree = MiniMerkleTree::new(values.clone(), 4, 128);
+        assert_eq!(tree.leaves_count(), 100);
+        assert_eq!(
+            tree.root_hash(),
+            mini_merkle_tree_root_hash(values.clone(), 4, 128)
+        );
+
+        for idx in [0, 1, 42, 99] {
+            let path = tree.merkle_path(idx);
+            assert_eq!(path.len(), 7);
+            let mut hash = hasher.hash_bytes(values[idx].clone());
+            for (level_idx, neighbour_hash) in path.iter().enumerate() {
+                let neighbour_hash = neighbour_hash.as_bytes().to_vec();
+                hash = if (idx >> level_idx) % 2 == 0 {
+                    hasher.compress(&hash, &neighbour_hash)
+                } else {
+                    hasher.compress(&neighbour_hash, &hash)
+                };
+            }
+            assert_eq!(H256::from_slice(&hash), tree.root_hash());
+
+            let proof = mini_merkle_tree_proof(values.clone(), idx, 4, 128);
+            assert_eq!(proof.len(), path.len() + 1);
+            assert_eq!(
+                proof[..7],
+                path.iter()
+                    .map(|hash| hash.as_bytes().to_vec())
+                    .collect::<Vec<_>>()[..]
+            );
+        }
+    }
+
+    #[test]
+    fn empty_tree_root_hash() {
+        let tree = MiniMerkleTree::new(gen_test_data(0, 0), 32, 32);
+        assert_eq!(tree.root_hash(), H256::zero());
+    }
+
     fn gen_test_data(left: usize, right: usize) -> Vec<Vec<u8>> {
         (left..right)
             .map(|x| vec![(x % 256) as u8, (x / 256) as u8, 0, 0])
@@ -2,11 +2,13 @@ use std::fmt::Debug;
 use std::fs;
 use std::fs::File;
 use std::io::{ErrorKind, Read, Write};
+use std::path::Path;
 
 use crate::object_store::{
-    ObjectStore, ObjectStoreError, LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
-    NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH, PROVER_JOBS_BUCKET_PATH,
-    SCHEDULER_WITNESS_JOBS_BUCKET_PATH, WITNESS_INPUT_BUCKET_PATH,
+    ObjectStore, ObjectStoreError, L2_TO_L1_LOG_TREES_BUCKET_PATH,
+    LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH, NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
+    PROVER_JOBS_BUCKET_PATH, ROCKSDB_BACKUPS_BUCKET_PATH, SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
+    STATE_SNAPSHOT_DIFFS_BUCKET_PATH, WITNESS_INPUT_BUCKET_PATH,
 };
 
 impl From<std::io::Error> for ObjectStoreError {
@@ -31,6 +33,9 @@ impl FileBackedObjectStore {
             LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
             NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH,
             SCHEDULER_WITNESS_JOBS_BUCKET_PATH,
+            L2_TO_L1_LOG_TREES_BUCKET_PATH,
+            ROCKSDB_BACKUPS_BUCKET_PATH,
+            STATE_SNAPSHOT_DIFFS_BUCKET_PATH,
         ] {
             fs::create_dir_all(format!("{}/{}", base_dir, bucket)).expect("failed creating bucket");
         }
@@ -66,6 +71,10 @@ impl ObjectStore for FileBackedObjectStore {
         value: Self::Value,
     ) -> Result<(), ObjectStoreError> {
         let filename = self.filename(bucket, key);
+        // Keys may contain slashes, e.g. for the RocksDB backups.
+        if let Some(parent) = Path::new(&filename).parent() {
+            fs::create_dir_all(parent)?;
+        }
         let mut file = File::create(filename)?;
         file.write_all(&value)?;
         Ok(())
@@ -40,3 +40,7 @@ pub fn scheduler_witness_blob_url(block_number: L1BatchNumber) -> String {
 pub fn final_node_aggregations_blob_url(block_number: L1BatchNumber) -> String {
     format!("final_node_aggregations_{}.bin", block_number)
 }
+
+pub fn l2_to_l1_log_tree_blob_url(block_number: L1BatchNumber) -> String {
+    format!("l2_to_l1_log_tree_{}.bin", block_number)
+}
@@ -0,0 +1,77 @@
+use sha2::{Digest, Sha256};
+
+use crate::object_store::{DynamicObjectStore, ObjectStore, ObjectStoreError};
+
+/// Object store wrapper that stores the SHA256 of every blob next to it and checks the blob
+/// against the hash when it's fetched, so that truncated or otherwise corrupted artifacts
+/// are reported as `ObjectStoreError::Corrupted` instead of failing in the deserialization.
+#[derive(Debug)]
+pub struct IntegrityCheckedObjectStore {
+    inner: DynamicObjectStore,
+}
+
+impl IntegrityCheckedObjectStore {
+    pub fn new(inner: DynamicObjectStore) -> Self {
+        Self { inner }
+    }
+
+    fn hash_key(key: &str) -> String {
+        format!("{}.sha256", key)
+    }
+}
+
+impl ObjectStore for IntegrityCheckedObjectStore {
+    type Bucket = &'static str;
+    type Key = String;
+    type Value = Vec<u8>;
+
+    fn get_store_type(&self) -> &'static str {
+        self.inner.get_store_type()
+    }
+
+    fn get(&self, bucket: Self::Bucket, key: Self::Key) -> Result<Self::Value, ObjectStoreError> {
+        let value = self.inner.get(bucket, key.clone())?;
+        let expected_hash = match self.inner.get(bucket, Self::hash_key(&key)) {
+            Ok(hash) => hash,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                // Blobs written before the hashes were introduced can't be checked.
+                metrics::counter!("object_store.unverified_blobs", 1, "bucket" => bucket);
+                vlog::warn!("No content hash is stored for {}/{}", bucket, key);
+                return Ok(value);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let actual_hash = Sha256::digest(&value);
+        if actual_hash.as_slice() != expected_hash.as_slice() {
+            metrics::counter!("object_store.corrupted_blobs", 1, "bucket" => bucket);
+            return Err(ObjectStoreError::Corrupted(format!(
+                "content hash mismatch for {}/{}: expected 0x{}, got 0x{}",
+                bucket,
+                key,
+                hex::encode(expected_hash),
+                hex::encode(actual_hash)
+            )));
+        }
+        Ok(value)
+    }
+
+    fn put(
+        &mut self,
+        bucket: Self::Bucket,
+        key: Self::Key,
+        value: Self::Value,
+    ) -> Result<(), ObjectStoreError> {
+        let hash = Sha256::digest(&value).to_vec();
+        self.inner.put(bucket, key.clone(), value)?;
+        self.inner.put(bucket, Self::hash_key(&key), hash)
+    }
+
+    fn remove(&mut self, bucket: Self::Bucket, key: Self::Key) -> Result<(), ObjectStoreError> {
+        match self.inner.remove(bucket, Self::hash_key(&key)) {
+            Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => {}
+            Err(err) => return Err(err),
+        }
+        self.inner.remove(bucket, key)
+    }
+}
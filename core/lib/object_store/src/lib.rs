@@ -2,6 +2,7 @@ extern crate core;
 
 pub mod file_backed_object_store;
 pub mod gcs_object_store;
+pub mod integrity;
 pub mod object_store;
 
 pub mod gcs_utils;
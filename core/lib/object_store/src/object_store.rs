@@ -10,16 +10,22 @@ use zksync_config::ObjectStoreConfig;
 
 use crate::file_backed_object_store::FileBackedObjectStore;
 use crate::gcs_object_store::GoogleCloudStorage;
+use crate::integrity::IntegrityCheckedObjectStore;
 
 pub const PROVER_JOBS_BUCKET_PATH: &str = "prover_jobs";
 pub const WITNESS_INPUT_BUCKET_PATH: &str = "witness_inputs";
 pub const LEAF_AGGREGATION_WITNESS_JOBS_BUCKET_PATH: &str = "leaf_aggregation_witness_jobs";
 pub const NODE_AGGREGATION_WITNESS_JOBS_BUCKET_PATH: &str = "node_aggregation_witness_jobs";
 pub const SCHEDULER_WITNESS_JOBS_BUCKET_PATH: &str = "scheduler_witness_jobs";
+pub const L2_TO_L1_LOG_TREES_BUCKET_PATH: &str = "l2_to_l1_log_trees";
+pub const ROCKSDB_BACKUPS_BUCKET_PATH: &str = "rocksdb_backups";
+pub const STATE_SNAPSHOT_DIFFS_BUCKET_PATH: &str = "state_snapshot_diffs";
 
 #[derive(Debug)]
 pub enum ObjectStoreError {
     KeyNotFound(String),
+    /// The blob doesn't match the content hash stored with it.
+    Corrupted(String),
     Other(String),
 }
 
@@ -27,6 +33,7 @@ impl Display for ObjectStoreError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ObjectStoreError::KeyNotFound(e) => write!(f, "Key Notfound error: {}", e),
+            ObjectStoreError::Corrupted(s) => write!(f, "Corrupted artifact: {}", s),
             ObjectStoreError::Other(s) => write!(f, "Other error: {}", s),
         }
     }
@@ -82,7 +89,7 @@ pub fn create_object_store(
     mode: ObjectStoreMode,
     file_backed_base_path: String,
 ) -> DynamicObjectStore {
-    match mode {
+    let store: DynamicObjectStore = match mode {
         ObjectStoreMode::GCS => {
             vlog::trace!("Initialized GoogleCloudStorage Object store");
             let gcs_config = fetch_gcs_config();
@@ -92,7 +99,8 @@ pub fn create_object_store(
             vlog::trace!("Initialized FileBacked Object store");
             Box::new(FileBackedObjectStore::new(file_backed_base_path))
         }
-    }
+    };
+    Box::new(IntegrityCheckedObjectStore::new(store))
 }
 
 pub fn create_object_store_from_env() -> DynamicObjectStore {
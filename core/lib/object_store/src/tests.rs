@@ -1,5 +1,9 @@
-use crate::object_store::{create_object_store, ObjectStoreMode};
+use crate::file_backed_object_store::FileBackedObjectStore;
+use crate::object_store::{
+    create_object_store, ObjectStore, ObjectStoreError, ObjectStoreMode, PROVER_JOBS_BUCKET_PATH,
+};
 use std::env;
+use tempdir::TempDir;
 
 #[test]
 fn test_object_store_in_memory_creation() {
@@ -19,3 +23,43 @@ fn set_object_store_environment_variable() {
     env::set_var("OBJECT_STORE_MODE", "GCS");
     env::set_var("OBJECT_STORE_FILE_BACKED_BASE_PATH", "/base/url");
 }
+
+#[test]
+fn test_integrity_checked_object_store() {
+    let dir = TempDir::new("test-data").unwrap();
+    let path = dir.path().to_str().unwrap().to_string();
+    let mut object_store = create_object_store(ObjectStoreMode::FileBacked, path.clone());
+    let mut raw_store = FileBackedObjectStore::new(path);
+    let key = "test-key.bin".to_string();
+
+    object_store
+        .put(PROVER_JOBS_BUCKET_PATH, key.clone(), vec![1, 2, 3])
+        .unwrap();
+    let value = object_store
+        .get(PROVER_JOBS_BUCKET_PATH, key.clone())
+        .unwrap();
+    assert_eq!(value, [1, 2, 3]);
+
+    raw_store
+        .put(PROVER_JOBS_BUCKET_PATH, key.clone(), vec![1, 2])
+        .unwrap();
+    let err = object_store
+        .get(PROVER_JOBS_BUCKET_PATH, key.clone())
+        .unwrap_err();
+    assert!(matches!(err, ObjectStoreError::Corrupted(_)), "{}", err);
+
+    object_store
+        .remove(PROVER_JOBS_BUCKET_PATH, key.clone())
+        .unwrap();
+    let err = raw_store
+        .get(PROVER_JOBS_BUCKET_PATH, format!("{}.sha256", key))
+        .unwrap_err();
+    assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{}", err);
+
+    // Blobs without a stored hash are returned as is.
+    raw_store
+        .put(PROVER_JOBS_BUCKET_PATH, key.clone(), vec![4, 5])
+        .unwrap();
+    let value = object_store.get(PROVER_JOBS_BUCKET_PATH, key).unwrap();
+    assert_eq!(value, [4, 5]);
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+
+/// Expected checksum of the initial setup key.
+///
+/// Parsed from the `sha256:<hex>` or `blake3:<hex>` form; the hex digest without the prefix is treated as SHA256,
+/// which is also the format of the `.sha256` sidecar files (the file name following the digest is ignored).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyChecksum {
+    Sha256([u8; 32]),
+    Blake3([u8; 32]),
+}
+
+impl FromStr for KeyChecksum {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.split_whitespace().next().unwrap_or_default();
+        let (algorithm, digest) = s.split_once(':').unwrap_or(("sha256", s));
+        let mut bytes = [0_u8; 32];
+        hex::decode_to_slice(digest, &mut bytes)
+            .map_err(|err| format!("invalid checksum digest {:?}: {}", digest, err))?;
+        match algorithm {
+            "sha256" => Ok(Self::Sha256(bytes)),
+            "blake3" => Ok(Self::Blake3(bytes)),
+            _ => Err(format!("unsupported checksum algorithm {:?}", algorithm)),
+        }
+    }
+}
+
+impl fmt::Display for KeyChecksum {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sha256(digest) => write!(formatter, "sha256:{}", hex::encode(digest)),
+            Self::Blake3(digest) => write!(formatter, "blake3:{}", hex::encode(digest)),
+        }
+    }
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(bytes),
+            Self::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+        }
+    }
+}
+
+impl KeyChecksum {
+    fn hasher(&self) -> Hasher {
+        match self {
+            Self::Sha256(_) => Hasher::Sha256(Sha256::new()),
+            Self::Blake3(_) => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    /// Returns the checksum of the same algorithm computed for the hashed data.
+    fn finalize(&self, hasher: Hasher) -> Self {
+        match hasher {
+            Hasher::Sha256(hasher) => Self::Sha256(hasher.finalize().into()),
+            Hasher::Blake3(hasher) => Self::Blake3(*hasher.finalize().as_bytes()),
+        }
+    }
+
+    /// Computes the checksum of the same algorithm for `bytes`.
+    pub fn compute(&self, bytes: &[u8]) -> Self {
+        let mut hasher = self.hasher();
+        hasher.update(bytes);
+        self.finalize(hasher)
+    }
+
+    /// Computes the checksum of the same algorithm for the file contents, without loading the file into memory.
+    pub fn compute_for_file(&self, path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut hasher = self.hasher();
+        let mut buffer = vec![0_u8; 1 << 20];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+        Ok(self.finalize(hasher))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+    const EMPTY_BLAKE3: &str = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+
+    #[test]
+    fn parsing_checksums() {
+        let checksum: KeyChecksum = EMPTY_SHA256.parse().unwrap();
+        assert_eq!(checksum.to_string(), format!("sha256:{}", EMPTY_SHA256));
+        let sidecar = format!("{}  setup_2^22.key\n", EMPTY_SHA256);
+        assert_eq!(sidecar.parse::<KeyChecksum>().unwrap(), checksum);
+
+        let checksum: KeyChecksum = format!("blake3:{}", EMPTY_BLAKE3).parse().unwrap();
+        assert!(matches!(checksum, KeyChecksum::Blake3(_)));
+
+        assert!("md5:00".parse::<KeyChecksum>().is_err());
+        assert!("sha256:xyz".parse::<KeyChecksum>().is_err());
+    }
+
+    #[test]
+    fn computing_checksums() {
+        for checksum in [EMPTY_SHA256.to_owned(), format!("blake3:{}", EMPTY_BLAKE3)] {
+            let checksum: KeyChecksum = checksum.parse().unwrap();
+            assert_eq!(checksum.compute(&[]), checksum);
+            assert_ne!(checksum.compute(b"key"), checksum);
+        }
+    }
+}
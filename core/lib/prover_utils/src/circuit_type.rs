@@ -0,0 +1,155 @@
+//! Circuit types of the proof system, identified by the numeric index used by the circuits and setup keys,
+//! and by the name stored in the `prover_jobs` table.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum CircuitType {
+    Scheduler = 0,
+    NodeAggregation = 1,
+    LeafAggregation = 2,
+    MainVm = 3,
+    DecommitmentsSorter = 4,
+    CodeDecommitter = 5,
+    LogDemuxer = 6,
+    Keccak = 7,
+    Sha256 = 8,
+    EcRecover = 9,
+    RamPermutation = 10,
+    StorageSorter = 11,
+    StorageApplication = 12,
+    InitialWritesPubdataRehasher = 13,
+    RepeatedWritesPubdataRehasher = 14,
+    EventsSorter = 15,
+    L1MessagesSorter = 16,
+    L1MessagesRehasher = 17,
+    L1MessagesMerklizer = 18,
+}
+
+impl CircuitType {
+    /// All circuit types ordered by their numeric index.
+    pub const ALL: [Self; 19] = [
+        Self::Scheduler,
+        Self::NodeAggregation,
+        Self::LeafAggregation,
+        Self::MainVm,
+        Self::DecommitmentsSorter,
+        Self::CodeDecommitter,
+        Self::LogDemuxer,
+        Self::Keccak,
+        Self::Sha256,
+        Self::EcRecover,
+        Self::RamPermutation,
+        Self::StorageSorter,
+        Self::StorageApplication,
+        Self::InitialWritesPubdataRehasher,
+        Self::RepeatedWritesPubdataRehasher,
+        Self::EventsSorter,
+        Self::L1MessagesSorter,
+        Self::L1MessagesRehasher,
+        Self::L1MessagesMerklizer,
+    ];
+
+    pub fn numeric_index(self) -> u8 {
+        self as u8
+    }
+
+    /// Name of the circuit as stored in the database and reported in metrics.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Scheduler => "Scheduler",
+            Self::NodeAggregation => "Node aggregation",
+            Self::LeafAggregation => "Leaf aggregation",
+            Self::MainVm => "Main VM",
+            Self::DecommitmentsSorter => "Decommitts sorter",
+            Self::CodeDecommitter => "Code decommitter",
+            Self::LogDemuxer => "Log demuxer",
+            Self::Keccak => "Keccak",
+            Self::Sha256 => "SHA256",
+            Self::EcRecover => "ECRecover",
+            Self::RamPermutation => "RAM permutation",
+            Self::StorageSorter => "Storage sorter",
+            Self::StorageApplication => "Storage application",
+            Self::InitialWritesPubdataRehasher => "Initial writes pubdata rehasher",
+            Self::RepeatedWritesPubdataRehasher => "Repeated writes pubdata rehasher",
+            Self::EventsSorter => "Events sorter",
+            Self::L1MessagesSorter => "L1 messages sorter",
+            Self::L1MessagesRehasher => "L1 messages rehasher",
+            Self::L1MessagesMerklizer => "L1 messages merklizer",
+        }
+    }
+}
+
+impl fmt::Display for CircuitType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.name())
+    }
+}
+
+impl FromStr for CircuitType {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|circuit_type| circuit_type.name() == input)
+            .ok_or_else(|| format!("Unknown circuit type: {}", input))
+    }
+}
+
+impl TryFrom<u8> for CircuitType {
+    type Error = String;
+
+    fn try_from(index: u8) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| format!("Unknown circuit numeric index: {}", index))
+    }
+}
+
+impl From<CircuitType> for u8 {
+    fn from(circuit_type: CircuitType) -> Self {
+        circuit_type.numeric_index()
+    }
+}
+
+impl Serialize for CircuitType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for CircuitType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuit_type_conversions() {
+        for (index, &circuit_type) in CircuitType::ALL.iter().enumerate() {
+            assert_eq!(circuit_type.numeric_index() as usize, index);
+            assert_eq!(CircuitType::try_from(index as u8), Ok(circuit_type));
+            assert_eq!(circuit_type.name().parse::<CircuitType>(), Ok(circuit_type));
+        }
+        assert!(CircuitType::try_from(19).is_err());
+        assert!("Main vm".parse::<CircuitType>().is_err());
+
+        let json = serde_json::to_string(&CircuitType::MainVm).unwrap();
+        assert_eq!(json, r#""Main VM""#);
+        let circuit_type: CircuitType = serde_json::from_str(&json).unwrap();
+        assert_eq!(circuit_type, CircuitType::MainVm);
+    }
+}
@@ -0,0 +1,220 @@
+//! Removal of the setup keys of obsolete protocol versions.
+//!
+//! Keys of each protocol version are stored in the `v<version>` subdirectory of the keys directory.
+//! Other entries of the directory are never touched.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Options of [`cleanup_obsolete_setup_keys`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetupKeysCleanup {
+    /// Protocol versions preceding the current one whose keys are kept, since the jobs created
+    /// before the upgrade to the current version still need to be proven with them.
+    pub active_protocol_versions: Vec<u16>,
+    /// Directory the obsolete keys are moved to instead of being removed. Must be on the same filesystem
+    /// as the keys directory.
+    pub archive_path: Option<PathBuf>,
+    /// Only report the obsolete keys without removing them.
+    pub dry_run: bool,
+}
+
+/// Directory with the obsolete setup keys of a protocol version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObsoleteSetupKeys {
+    pub protocol_version: u16,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetupKeysCleanupReport {
+    pub obsolete_keys: Vec<ObsoleteSetupKeys>,
+    /// Bytes freed by the cleanup; zero for the dry runs and archived keys.
+    pub reclaimed_bytes: u64,
+}
+
+/// Returns the protocol version of the keys stored in the directory with the given name.
+fn parse_version_dir(name: &str) -> Option<u16> {
+    name.strip_prefix('v')?.parse().ok()
+}
+
+fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+/// Removes or archives the keys of the protocol versions preceding `current_protocol_version`, except for
+/// `options.active_protocol_versions`. Keys of the newer versions are kept, since they may be prepared
+/// for an upcoming upgrade.
+pub fn cleanup_obsolete_setup_keys(
+    keys_path: &Path,
+    current_protocol_version: u16,
+    options: &SetupKeysCleanup,
+) -> io::Result<SetupKeysCleanupReport> {
+    let is_obsolete = |version: u16| {
+        version < current_protocol_version && !options.active_protocol_versions.contains(&version)
+    };
+    let mut report = SetupKeysCleanupReport::default();
+    for entry in fs::read_dir(keys_path)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let protocol_version = match entry.file_name().to_str().and_then(parse_version_dir) {
+            Some(version) if is_obsolete(version) => version,
+            _ => continue,
+        };
+        let path = entry.path();
+        report.obsolete_keys.push(ObsoleteSetupKeys {
+            protocol_version,
+            size_bytes: dir_size(&path)?,
+            path,
+        });
+    }
+    report
+        .obsolete_keys
+        .sort_by_key(|keys| keys.protocol_version);
+
+    for keys in &report.obsolete_keys {
+        if options.dry_run {
+            vlog::info!(
+                "Setup keys for protocol version {} at {:?} are obsolete ({} bytes)",
+                keys.protocol_version,
+                keys.path,
+                keys.size_bytes
+            );
+            continue;
+        }
+
+        let is_archived = match &options.archive_path {
+            Some(archive_path) => archive_keys(keys, archive_path)?,
+            None => false,
+        };
+        if !is_archived {
+            fs::remove_dir_all(&keys.path)?;
+            report.reclaimed_bytes += keys.size_bytes;
+            metrics::counter!("server.prover.setup_keys_reclaimed_bytes", keys.size_bytes);
+            vlog::info!(
+                "Removed setup keys for protocol version {} ({} bytes)",
+                keys.protocol_version,
+                keys.size_bytes
+            );
+        }
+    }
+    Ok(report)
+}
+
+/// Moves the keys to the archive directory. Returns `false` without moving the keys if the archive
+/// already contains the keys of the same protocol version (e.g., archived before the keys were downloaded again);
+/// the keys of a version are the same, so the archived copy is kept and the obsolete keys can be removed.
+fn archive_keys(keys: &ObsoleteSetupKeys, archive_path: &Path) -> io::Result<bool> {
+    let target_path = archive_path.join(keys.path.file_name().unwrap());
+    if target_path.exists() {
+        vlog::info!(
+            "Setup keys for protocol version {} are already archived at {:?}",
+            keys.protocol_version,
+            target_path
+        );
+        return Ok(false);
+    }
+
+    fs::create_dir_all(archive_path)?;
+    fs::rename(&keys.path, &target_path).map_err(|err| {
+        io::Error::new(
+            err.kind(),
+            format!(
+                "failed moving {:?} to {:?}: {}",
+                keys.path, target_path, err
+            ),
+        )
+    })?;
+    vlog::info!(
+        "Archived setup keys for protocol version {} to {:?}",
+        keys.protocol_version,
+        archive_path
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_keys(keys_path: &Path, dir: &str) {
+        let dir = keys_path.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("setup_3_key.bin"), b"key").unwrap();
+    }
+
+    #[test]
+    fn cleaning_up_obsolete_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        for version_dir in ["v8", "v9", "v10", "v11", "v12", "archive"] {
+            create_keys(dir.path(), version_dir);
+        }
+        fs::write(dir.path().join("v7"), b"not a directory").unwrap();
+
+        // Versions still having jobs to be proven are kept even if they aren't the latest preceding ones.
+        let mut options = SetupKeysCleanup {
+            active_protocol_versions: vec![9],
+            dry_run: true,
+            ..SetupKeysCleanup::default()
+        };
+        let report = cleanup_obsolete_setup_keys(dir.path(), 11, &options).unwrap();
+        let versions: Vec<_> = report
+            .obsolete_keys
+            .iter()
+            .map(|keys| keys.protocol_version)
+            .collect();
+        assert_eq!(versions, [8, 10]);
+        assert_eq!(report.obsolete_keys[0].size_bytes, 3);
+        assert_eq!(report.reclaimed_bytes, 0);
+        assert!(dir.path().join("v8").exists());
+
+        options.dry_run = false;
+        let report = cleanup_obsolete_setup_keys(dir.path(), 11, &options).unwrap();
+        assert_eq!(report.reclaimed_bytes, 6);
+        let mut remaining: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, ["archive", "v11", "v12", "v7", "v9"]);
+    }
+
+    #[test]
+    fn archiving_obsolete_keys() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let keys_path = dir.path().join("keys");
+        create_keys(&keys_path, "v1");
+        create_keys(&keys_path, "v3");
+
+        let options = SetupKeysCleanup {
+            archive_path: Some(dir.path().join("archive")),
+            ..SetupKeysCleanup::default()
+        };
+        let report = cleanup_obsolete_setup_keys(&keys_path, 3, &options).unwrap();
+        assert_eq!(report.obsolete_keys.len(), 1);
+        assert_eq!(report.reclaimed_bytes, 0);
+        assert!(!keys_path.join("v1").exists());
+        assert!(dir.path().join("archive/v1/setup_3_key.bin").exists());
+
+        // Keys downloaded again after being archived are removed, keeping the archived copy.
+        create_keys(&keys_path, "v1");
+        let report = cleanup_obsolete_setup_keys(&keys_path, 3, &options).unwrap();
+        assert_eq!(report.reclaimed_bytes, 3);
+        assert!(!keys_path.join("v1").exists());
+        assert!(dir.path().join("archive/v1/setup_3_key.bin").exists());
+    }
+}
@@ -0,0 +1,667 @@
+//! Stores the setup keys are fetched from.
+//!
+//! The location of a key is interpreted by the store: it's a URL for [`SetupKeyStoreMode::Http`],
+//! a `bucket/object` path (optionally prefixed with `gs://` or `s3://`) for the cloud storages,
+//! and a filesystem path (e.g., on an NFS mount) for [`SetupKeyStoreMode::LocalDir`].
+//! The `.sha256` checksum sidecar of a key is looked up next to the key in the same store.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use aws_sdk_s3::types::ByteStream;
+use futures::TryStreamExt;
+use google_cloud_default::WithAuthExt;
+use google_cloud_storage::client::{Client as GcsClient, ClientConfig};
+use google_cloud_storage::http::objects::download::Range;
+use google_cloud_storage::http::objects::get::GetObjectRequest;
+use google_cloud_storage::http::Error as GcsError;
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+
+#[derive(Debug)]
+pub enum KeyStoreError {
+    KeyNotFound(String),
+    Other(String),
+}
+
+impl From<io::Error> for KeyStoreError {
+    fn from(err: io::Error) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+impl fmt::Display for KeyStoreError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KeyNotFound(err) => write!(formatter, "key not found: {}", err),
+            Self::Other(err) => write!(formatter, "{}", err),
+        }
+    }
+}
+
+/// Source of the setup keys.
+#[async_trait]
+pub trait SetupKeyStore: fmt::Debug + Send + Sync {
+    /// Fetches the whole contents of the file at `location`. Only used for the small files, such as
+    /// the checksum sidecars; the keys themselves are fetched with [`Self::fetch_from()`].
+    async fn fetch(&self, location: &str) -> Result<Vec<u8>, KeyStoreError>;
+
+    /// Appends the contents of the key at `location` following the downloaded part to `part`.
+    ///
+    /// The key is streamed to `part` as it's downloaded, so that it's never held in memory as a whole
+    /// and the downloaded part is kept if the download is interrupted. The downloaded part is discarded
+    /// if it's longer than the key, if the key has changed since the part was downloaded, or if the store
+    /// returns the whole key.
+    async fn fetch_from(&self, location: &str, part: &mut PartialKey) -> Result<(), KeyStoreError>;
+}
+
+/// Partially downloaded key, together with the version of the key it's downloaded from (e.g., its ETag).
+/// The version is persisted next to the part, so that the download isn't resumed from a different key
+/// after a restart.
+#[derive(Debug)]
+pub struct PartialKey {
+    writer: PartWriter,
+    version: Option<String>,
+    version_path: PathBuf,
+}
+
+impl PartialKey {
+    /// Opens the partially downloaded key at `path`, creating it if it doesn't exist.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let len = file.metadata()?.len();
+        let version_path = Self::version_path(path);
+        let version = match fs::read_to_string(&version_path) {
+            Ok(version) if len > 0 => Some(version),
+            Ok(_) => None,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+        Ok(Self {
+            writer: PartWriter {
+                file,
+                len: Arc::new(AtomicU64::new(len)),
+            },
+            version,
+            version_path,
+        })
+    }
+
+    fn version_path(path: &Path) -> PathBuf {
+        let mut version_path = path.as_os_str().to_owned();
+        version_path.push(".version");
+        PathBuf::from(version_path)
+    }
+
+    pub fn len(&self) -> u64 {
+        self.writer.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Version of the key the part is downloaded from, if it's known.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns the handle to track the progress of the download.
+    pub fn progress(&self) -> DownloadProgress {
+        DownloadProgress(self.writer.len.clone())
+    }
+
+    /// Appends the downloaded chunk of the key.
+    fn append(&mut self, chunk: &[u8]) -> io::Result<()> {
+        self.writer.write_all(chunk)
+    }
+
+    /// Discards the downloaded part, so that the key of the `version` is downloaded from scratch.
+    fn restart(&mut self, version: Option<String>) -> io::Result<()> {
+        self.writer.file.set_len(0)?;
+        self.writer.len.store(0, Ordering::Relaxed);
+        match &version {
+            Some(version) => fs::write(&self.version_path, version)?,
+            None => remove_if_exists(&self.version_path)?,
+        }
+        self.version = version;
+        Ok(())
+    }
+
+    /// Prepares the part to resume the download of the `version` of the key, which is `key_len` bytes long.
+    /// The part downloaded from another version of the key, or longer than the key, doesn't belong to it,
+    /// so it's discarded. Returns `true` if the key is downloaded already.
+    fn resume(&mut self, version: String, key_len: u64) -> io::Result<bool> {
+        if self.version() != Some(version.as_str()) || self.len() > key_len {
+            self.restart(Some(version))?;
+        }
+        Ok(self.len() == key_len)
+    }
+
+    /// Removes the version of the key once the part is moved to its path or discarded.
+    pub fn finish(self) -> io::Result<()> {
+        remove_if_exists(&self.version_path)
+    }
+}
+
+/// Appends the downloaded chunks to the part, tracking its length.
+#[derive(Debug)]
+struct PartWriter {
+    file: File,
+    /// Length of the downloaded part. Shared with [`DownloadProgress`].
+    len: Arc<AtomicU64>,
+}
+
+impl PartWriter {
+    fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            file: self.file.try_clone()?,
+            len: self.len.clone(),
+        })
+    }
+}
+
+impl Write for PartWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.len.fetch_add(written as u64, Ordering::Relaxed);
+        metrics::counter!("server.prover.downloaded_bytes", written as u64);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Number of bytes of [`PartialKey`] downloaded so far, which can be checked while the key is downloaded.
+#[derive(Debug, Clone)]
+pub struct DownloadProgress(Arc<AtomicU64>);
+
+impl DownloadProgress {
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupKeyStoreMode {
+    Http,
+    GCS,
+    S3,
+    LocalDir,
+}
+
+impl Default for SetupKeyStoreMode {
+    fn default() -> Self {
+        Self::Http
+    }
+}
+
+impl FromStr for SetupKeyStoreMode {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "Http" => Ok(Self::Http),
+            "GCS" => Ok(Self::GCS),
+            "S3" => Ok(Self::S3),
+            "LocalDir" => Ok(Self::LocalDir),
+            _ => Err(format!("Unknown SetupKeyStoreMode type: {}", input)),
+        }
+    }
+}
+
+impl SetupKeyStoreMode {
+    /// Parses the mode from the config value, defaulting to [`Self::Http`] if it's not set.
+    pub fn from_config(mode: Option<&str>) -> Self {
+        mode.map_or_else(Self::default, |mode| mode.parse().unwrap())
+    }
+}
+
+/// Creates the store for the mode. The cloud storages are authenticated with the default credentials
+/// of the environment.
+pub async fn create_setup_key_store(mode: SetupKeyStoreMode) -> Box<dyn SetupKeyStore> {
+    match mode {
+        SetupKeyStoreMode::Http => Box::new(HttpKeyStore::new()),
+        SetupKeyStoreMode::GCS => Box::new(GcsKeyStore::new().await),
+        SetupKeyStoreMode::S3 => Box::new(S3KeyStore::new().await),
+        SetupKeyStoreMode::LocalDir => Box::new(LocalDirKeyStore),
+    }
+}
+
+/// Splits the `bucket/object` location, stripping the `scheme` prefix if present.
+fn split_bucket_location<'a>(
+    location: &'a str,
+    scheme: &str,
+) -> Result<(&'a str, &'a str), KeyStoreError> {
+    let path = location.strip_prefix(scheme).unwrap_or(location);
+    match path.split_once('/') {
+        Some((bucket, object)) if !bucket.is_empty() && !object.is_empty() => Ok((bucket, object)),
+        _ => Err(KeyStoreError::Other(format!(
+            "invalid key location {:?}, expected `bucket/object`",
+            location
+        ))),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HttpKeyStore {
+    client: reqwest::Client,
+}
+
+impl HttpKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl From<reqwest::Error> for KeyStoreError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.status() == Some(reqwest::StatusCode::NOT_FOUND) {
+            Self::KeyNotFound(err.to_string())
+        } else {
+            Self::Other(err.to_string())
+        }
+    }
+}
+
+/// Returns the version of the key the response is for: its strong ETag, or its modification time if the ETag
+/// isn't available. Weak ETags can't be used to resume the download.
+fn http_key_version(response: &reqwest::Response) -> Option<String> {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    header(ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(LAST_MODIFIED))
+        .map(str::to_owned)
+}
+
+/// Parses the length of the key from the `Content-Range` header of the 416 response, i.e. `bytes */{len}`.
+fn unsatisfied_range_key_len(content_range: &str) -> Option<u64> {
+    content_range.strip_prefix("bytes */")?.parse().ok()
+}
+
+#[async_trait]
+impl SetupKeyStore for HttpKeyStore {
+    async fn fetch(&self, location: &str) -> Result<Vec<u8>, KeyStoreError> {
+        let response = self.client.get(location).send().await?.error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    async fn fetch_from(&self, location: &str, part: &mut PartialKey) -> Result<(), KeyStoreError> {
+        let mut request = self.client.get(location);
+        // The range is only served if the key hasn't changed since the part was downloaded,
+        // otherwise the server returns the whole key.
+        if let (false, Some(version)) = (part.is_empty(), part.version()) {
+            request = request
+                .header(RANGE, format!("bytes={}-", part.len()))
+                .header(IF_RANGE, version);
+        }
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The part isn't shorter than the key; it's only complete if it's as long as the key.
+            let key_len = response
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(unsatisfied_range_key_len);
+            if key_len == Some(part.len()) {
+                return Ok(());
+            }
+            part.restart(None)?;
+            return self.fetch_from(location, part).await;
+        }
+
+        let mut response = response.error_for_status()?;
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            part.restart(http_key_version(&response))?;
+        }
+        while let Some(chunk) = response.chunk().await? {
+            part.append(&chunk)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct GcsKeyStore {
+    client: GcsClient,
+}
+
+// `google_cloud_storage::client::Client` doesn't implement `Debug`.
+impl fmt::Debug for GcsKeyStore {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_struct("GcsKeyStore").finish()
+    }
+}
+
+impl GcsKeyStore {
+    pub async fn new() -> Self {
+        let config = ClientConfig::default()
+            .with_auth()
+            .await
+            .expect("Failed build GCS client config");
+        Self {
+            client: GcsClient::new(config),
+        }
+    }
+}
+
+impl From<GcsError> for KeyStoreError {
+    fn from(err: GcsError) -> Self {
+        match &err {
+            GcsError::HttpClient(reqwest_err)
+                if reqwest_err.status() == Some(reqwest::StatusCode::NOT_FOUND) =>
+            {
+                Self::KeyNotFound(err.to_string())
+            }
+            _ => Self::Other(err.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl SetupKeyStore for GcsKeyStore {
+    async fn fetch(&self, location: &str) -> Result<Vec<u8>, KeyStoreError> {
+        let request = gcs_request(location)?;
+        let bytes = self
+            .client
+            .download_object(&request, &Range::default(), None)
+            .await?;
+        Ok(bytes)
+    }
+
+    async fn fetch_from(&self, location: &str, part: &mut PartialKey) -> Result<(), KeyStoreError> {
+        let mut request = gcs_request(location)?;
+        let object = self.client.get_object(&request, None).await?;
+        if part.resume(object.generation.to_string(), object.size as u64)? {
+            return Ok(());
+        }
+
+        // The download is pinned to the generation the part is downloaded from.
+        request.generation = Some(object.generation);
+        let stream = self
+            .client
+            .download_streamed_object(&request, &Range(Some(part.len()), None), None)
+            .await?;
+        futures::pin_mut!(stream);
+        while let Some(chunk) = stream.try_next().await? {
+            part.append(&chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn gcs_request(location: &str) -> Result<GetObjectRequest, KeyStoreError> {
+    let (bucket, object) = split_bucket_location(location, "gs://")?;
+    Ok(GetObjectRequest {
+        bucket: bucket.to_owned(),
+        object: object.to_owned(),
+        ..Default::default()
+    })
+}
+
+#[derive(Debug)]
+pub struct S3KeyStore {
+    client: aws_sdk_s3::Client,
+}
+
+impl S3KeyStore {
+    pub async fn new() -> Self {
+        let config = aws_config::load_from_env().await;
+        Self {
+            client: aws_sdk_s3::Client::new(&config),
+        }
+    }
+}
+
+impl S3KeyStore {
+    /// Requests the `version` of the key starting from `offset`; the body of the response is streamed.
+    async fn get_object(
+        &self,
+        location: &str,
+        offset: u64,
+        version: Option<&str>,
+    ) -> Result<ByteStream, KeyStoreError> {
+        let (bucket, key) = split_bucket_location(location, "s3://")?;
+        let mut request = self.client.get_object().bucket(bucket).key(key);
+        if offset > 0 {
+            request = request.range(format!("bytes={}-", offset));
+        }
+        if let Some(version) = version {
+            request = request.if_match(version);
+        }
+        let output = request.send().await.map_err(|err| {
+            let message = err.to_string();
+            if err.into_service_error().is_no_such_key() {
+                KeyStoreError::KeyNotFound(message)
+            } else {
+                KeyStoreError::Other(message)
+            }
+        })?;
+        Ok(output.body)
+    }
+
+    /// Returns the ETag and the length of the key.
+    async fn head_object(&self, location: &str) -> Result<(String, u64), KeyStoreError> {
+        let (bucket, key) = split_bucket_location(location, "s3://")?;
+        let output = self
+            .client
+            .head_object()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|err| {
+                let message = err.to_string();
+                if err.into_service_error().is_not_found() {
+                    KeyStoreError::KeyNotFound(message)
+                } else {
+                    KeyStoreError::Other(message)
+                }
+            })?;
+        let etag = output.e_tag().unwrap_or_default().to_owned();
+        Ok((etag, output.content_length() as u64))
+    }
+}
+
+#[async_trait]
+impl SetupKeyStore for S3KeyStore {
+    async fn fetch(&self, location: &str) -> Result<Vec<u8>, KeyStoreError> {
+        let bytes = self
+            .get_object(location, 0, None)
+            .await?
+            .collect()
+            .await
+            .map_err(|err| KeyStoreError::Other(err.to_string()))?;
+        Ok(bytes.into_bytes().to_vec())
+    }
+
+    async fn fetch_from(&self, location: &str, part: &mut PartialKey) -> Result<(), KeyStoreError> {
+        // Ranges starting past the end of the key are rejected, so the length is checked beforehand.
+        let (etag, key_len) = self.head_object(location).await?;
+        if part.resume(etag, key_len)? {
+            return Ok(());
+        }
+
+        // The download fails if the key has changed since the `HEAD` request, and is restarted by the next attempt.
+        let mut body = self
+            .get_object(location, part.len(), part.version())
+            .await?;
+        while let Some(chunk) = body
+            .try_next()
+            .await
+            .map_err(|err| KeyStoreError::Other(err.to_string()))?
+        {
+            part.append(&chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Store reading the keys from the filesystem, e.g. from an NFS mount.
+#[derive(Debug)]
+pub struct LocalDirKeyStore;
+
+fn local_key_error(location: &str, err: io::Error) -> KeyStoreError {
+    if err.kind() == io::ErrorKind::NotFound {
+        KeyStoreError::KeyNotFound(format!("{}: {}", location, err))
+    } else {
+        KeyStoreError::Other(format!("{}: {}", location, err))
+    }
+}
+
+#[async_trait]
+impl SetupKeyStore for LocalDirKeyStore {
+    async fn fetch(&self, location: &str) -> Result<Vec<u8>, KeyStoreError> {
+        let path = PathBuf::from(location);
+        let bytes = tokio::task::spawn_blocking(move || std::fs::read(path))
+            .await
+            .expect("Failed reading the key");
+        bytes.map_err(|err| local_key_error(location, err))
+    }
+
+    async fn fetch_from(&self, location: &str, part: &mut PartialKey) -> Result<(), KeyStoreError> {
+        let mut key = File::open(location).map_err(|err| local_key_error(location, err))?;
+        let metadata = key.metadata()?;
+        // The modification time serves as the version of the key.
+        let modified_at = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        if part.resume(modified_at.as_nanos().to_string(), metadata.len())? {
+            return Ok(());
+        }
+
+        let offset = part.len();
+        let mut writer = part.writer.try_clone()?;
+        let copy = tokio::task::spawn_blocking(move || {
+            key.seek(SeekFrom::Start(offset))?;
+            io::copy(&mut key, &mut writer)
+        });
+        copy.await
+            .expect("Failed reading the key")
+            .map_err(|err| local_key_error(location, err))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_bucket_locations() {
+        let (bucket, object) =
+            split_bucket_location("gs://keys/setup/setup_2^22.key", "gs://").unwrap();
+        assert_eq!((bucket, object), ("keys", "setup/setup_2^22.key"));
+        let (bucket, object) = split_bucket_location("keys/setup_2^22.key", "s3://").unwrap();
+        assert_eq!((bucket, object), ("keys", "setup_2^22.key"));
+
+        assert!(split_bucket_location("keys", "s3://").is_err());
+        assert!(split_bucket_location("s3://keys/", "s3://").is_err());
+    }
+
+    #[test]
+    fn parsing_store_modes() {
+        assert_eq!(
+            SetupKeyStoreMode::from_config(None),
+            SetupKeyStoreMode::Http
+        );
+        assert_eq!(
+            SetupKeyStoreMode::from_config(Some("LocalDir")),
+            SetupKeyStoreMode::LocalDir
+        );
+        assert!("Nfs".parse::<SetupKeyStoreMode>().is_err());
+    }
+
+    #[tokio::test]
+    async fn fetching_keys_from_local_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let key_path = dir.path().join("setup_2^22.key");
+        std::fs::write(&key_path, b"key").unwrap();
+
+        let store = LocalDirKeyStore;
+        let bytes = store.fetch(key_path.to_str().unwrap()).await.unwrap();
+        assert_eq!(bytes, b"key");
+        let missing = dir.path().join("missing.key");
+        let err = store.fetch(missing.to_str().unwrap()).await.unwrap_err();
+        assert!(matches!(err, KeyStoreError::KeyNotFound(_)));
+    }
+
+    #[test]
+    fn parsing_unsatisfied_ranges() {
+        assert_eq!(unsatisfied_range_key_len("bytes */1024"), Some(1024));
+        assert_eq!(unsatisfied_range_key_len("bytes 0-1023/1024"), None);
+        assert_eq!(unsatisfied_range_key_len("bytes */*"), None);
+    }
+
+    #[tokio::test]
+    async fn resuming_partial_downloads() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let key_path = dir.path().join("setup_2^22.key");
+        std::fs::write(&key_path, b"setup key").unwrap();
+        let location = key_path.to_str().unwrap();
+        let part_path = dir.path().join("setup_2^22.key.part");
+        let store = LocalDirKeyStore;
+
+        let mut part = PartialKey::open(&part_path).unwrap();
+        store.fetch_from(location, &mut part).await.unwrap();
+        assert_eq!(part.len(), 9);
+        assert!(part.version().is_some());
+
+        // The part of the same version of the key is resumed.
+        OpenOptions::new()
+            .write(true)
+            .open(&part_path)
+            .unwrap()
+            .set_len(5)
+            .unwrap();
+        let mut part = PartialKey::open(&part_path).unwrap();
+        assert_eq!(part.len(), 5);
+        let progress = part.progress();
+        store.fetch_from(location, &mut part).await.unwrap();
+        assert_eq!(progress.get(), 9);
+        assert_eq!(std::fs::read(&part_path).unwrap(), b"setup key");
+
+        // A complete part is kept as is.
+        let mut part = PartialKey::open(&part_path).unwrap();
+        store.fetch_from(location, &mut part).await.unwrap();
+        assert_eq!(std::fs::read(&part_path).unwrap(), b"setup key");
+
+        // A part longer than the key is discarded.
+        std::fs::write(&part_path, b"setup key and garbage").unwrap();
+        let mut part = PartialKey::open(&part_path).unwrap();
+        store.fetch_from(location, &mut part).await.unwrap();
+        assert_eq!(std::fs::read(&part_path).unwrap(), b"setup key");
+
+        // A part of another version of the key is discarded.
+        std::fs::write(&part_path, b"other").unwrap();
+        std::fs::write(PartialKey::version_path(&part_path), "0").unwrap();
+        let mut part = PartialKey::open(&part_path).unwrap();
+        store.fetch_from(location, &mut part).await.unwrap();
+        assert_eq!(std::fs::read(&part_path).unwrap(), b"setup key");
+
+        part.finish().unwrap();
+        assert!(!PartialKey::version_path(&part_path).exists());
+    }
+}
@@ -1,116 +1,412 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
+use std::fmt;
 use std::fs::create_dir_all;
-use std::io::Cursor;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use std::time::Instant;
 
+use futures::stream::{self, StreamExt};
+
+use crate::checksum::KeyChecksum;
+use crate::circuit_type::CircuitType;
+use crate::key_store::{HttpKeyStore, KeyStoreError, PartialKey, SetupKeyStore};
+use zksync_config::ProverConfig;
+
+pub mod checksum;
+pub mod circuit_type;
+pub mod key_cleanup;
+pub mod key_store;
 pub mod region_fetcher;
 
-fn download_bytes(key_download_url: &str) -> reqwest::Result<Vec<u8>> {
-    vlog::info!("Downloading initial setup from {:?}", key_download_url);
+/// Retry policy for downloading the initial setup keys. The backoff between the attempts is doubled
+/// after each failed attempt, up to `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadRetryPolicy {
+    /// Number of retries after the first failed attempt.
+    pub max_retries: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Timeout of a single download attempt. For the keys, it's reset whenever the data is received,
+    /// so that the large keys are not restarted as long as they're being downloaded.
+    pub timeout: Duration,
+}
 
-    const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
-    let client = reqwest::blocking::Client::builder()
-        .timeout(DOWNLOAD_TIMEOUT)
-        .build()
-        .unwrap();
+impl Default for DownloadRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            timeout: Duration::from_secs(120),
+        }
+    }
+}
 
-    const DOWNLOAD_RETRIES: usize = 5;
-    let mut retry_count = 0;
+impl DownloadRetryPolicy {
+    /// Loads the policy from the prover config, using the defaults for the parameters that are not set.
+    pub fn from_config(config: &ProverConfig) -> Self {
+        let default = Self::default();
+        Self {
+            max_retries: config
+                .key_download_max_retries
+                .unwrap_or(default.max_retries),
+            initial_backoff: config
+                .key_download_initial_backoff_in_millis
+                .map_or(default.initial_backoff, Duration::from_millis),
+            max_backoff: config
+                .key_download_max_backoff_in_millis
+                .map_or(default.max_backoff, Duration::from_millis),
+            timeout: config
+                .key_download_timeout_in_secs
+                .map_or(default.timeout, Duration::from_secs),
+        }
+    }
+}
 
-    while retry_count < DOWNLOAD_RETRIES {
-        let bytes = client
-            .get(key_download_url)
-            .send()
-            .and_then(|response| response.bytes().map(|bytes| bytes.to_vec()));
-        match bytes {
-            Ok(bytes) => return Ok(bytes),
-            Err(_) => retry_count += 1,
+#[derive(Debug)]
+enum DownloadError {
+    Store(KeyStoreError),
+    ChecksumMismatch {
+        expected: KeyChecksum,
+        actual: KeyChecksum,
+    },
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Store(err) => write!(formatter, "{}", err),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                formatter,
+                "checksum mismatch: expected {}, got {}",
+                expected, actual
+            ),
         }
+    }
+}
+
+async fn fetch_with_timeout(
+    store: &dyn SetupKeyStore,
+    location: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, KeyStoreError> {
+    tokio::time::timeout(timeout, store.fetch(location))
+        .await
+        .unwrap_or_else(|_| {
+            Err(KeyStoreError::Other(format!(
+                "timed out after {:?}",
+                timeout
+            )))
+        })
+}
 
-        vlog::warn!("Failed to download keys. Backing off for 5 second");
-        std::thread::sleep(Duration::from_secs(5));
+/// Path of the partially downloaded key. The download is resumed from it by the next attempts,
+/// including the ones after a restart.
+fn part_path(key_path: &Path) -> PathBuf {
+    let mut path = key_path.as_os_str().to_owned();
+    path.push(".part");
+    PathBuf::from(path)
+}
+
+/// Fetches the missing part of the key to `part`. The fetch times out if no data is received for `timeout`.
+async fn fetch_part(
+    store: &dyn SetupKeyStore,
+    key_location: &str,
+    part: &mut PartialKey,
+    timeout: Duration,
+) -> Result<(), KeyStoreError> {
+    let progress = part.progress();
+    let fetch = store.fetch_from(key_location, part);
+    futures::pin_mut!(fetch);
+    let mut downloaded = progress.get();
+    loop {
+        match tokio::time::timeout(timeout, &mut fetch).await {
+            Ok(result) => return result,
+            Err(_) if progress.get() == downloaded => {
+                return Err(KeyStoreError::Other(format!(
+                    "no data received for {:?}",
+                    timeout
+                )));
+            }
+            Err(_) => downloaded = progress.get(),
+        }
     }
+}
 
-    client
-        .get(key_download_url)
-        .send()
-        .and_then(|response| response.bytes().map(|bytes| bytes.to_vec()))
+/// Computes the checksum of the key file on the blocking thread pool, so that hashing the large keys
+/// doesn't block the runtime (e.g., the concurrent downloads of the other keys).
+async fn compute_checksum_for_file(
+    checksum: &KeyChecksum,
+    path: &Path,
+) -> std::io::Result<KeyChecksum> {
+    let checksum = checksum.clone();
+    let path = path.to_owned();
+    tokio::task::spawn_blocking(move || checksum.compute_for_file(&path))
+        .await
+        .expect("checksum computation panicked")
 }
 
-pub fn ensure_initial_setup_keys_present(initial_setup_key_path: &str, key_download_url: &str) {
-    if Path::new(initial_setup_key_path).exists() {
+/// Downloads the missing part of the key to `part_path` and verifies the whole key against the checksum.
+async fn download_attempt(
+    store: &dyn SetupKeyStore,
+    key_location: &str,
+    part_path: &Path,
+    checksum: Option<&KeyChecksum>,
+    timeout: Duration,
+) -> Result<(), DownloadError> {
+    let mut part = PartialKey::open(part_path).map_err(|err| DownloadError::Store(err.into()))?;
+    if !part.is_empty() {
         vlog::info!(
-            "Initial setup already present at {:?}",
-            initial_setup_key_path
+            "Resuming download from {:?} at byte {}",
+            key_location,
+            part.len()
         );
-        return;
     }
-    let started_at = Instant::now();
+    fetch_part(store, key_location, &mut part, timeout)
+        .await
+        .map_err(DownloadError::Store)?;
+
+    // A corrupted download is retried the same way as a failed one, but can't be resumed.
+    if let Some(expected) = checksum {
+        let actual = compute_checksum_for_file(expected, part_path)
+            .await
+            .map_err(|err| DownloadError::Store(err.into()))?;
+        if actual != *expected {
+            metrics::counter!("server.prover.download_checksum_mismatches", 1);
+            std::fs::remove_file(part_path).map_err(|err| DownloadError::Store(err.into()))?;
+            part.finish()
+                .map_err(|err| DownloadError::Store(err.into()))?;
+            return Err(DownloadError::ChecksumMismatch {
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
+    part.finish()
+        .map_err(|err| DownloadError::Store(err.into()))?;
+    Ok(())
+}
 
-    let bytes = download_bytes(key_download_url).expect("Failed downloading initial setup");
-    let initial_setup_key_dir = Path::new(initial_setup_key_path).parent().unwrap();
-    create_dir_all(initial_setup_key_dir).unwrap_or_else(|_| {
-        panic!(
-            "Failed creating dirs recursively: {:?}",
-            initial_setup_key_dir
+/// Downloads the key to `part_path`, resuming the partial download left by the previous attempts if any.
+async fn download_bytes(
+    store: &dyn SetupKeyStore,
+    key_location: &str,
+    part_path: &Path,
+    checksum: Option<&KeyChecksum>,
+    retry_policy: DownloadRetryPolicy,
+) -> Result<(), DownloadError> {
+    vlog::info!("Downloading initial setup from {:?}", key_location);
+
+    let mut retry_count = 0;
+    let mut backoff = retry_policy.initial_backoff;
+    loop {
+        let result = download_attempt(
+            store,
+            key_location,
+            part_path,
+            checksum,
+            retry_policy.timeout,
         )
-    });
-    let mut file = std::fs::File::create(initial_setup_key_path)
-        .expect("Cannot create file for the initial setup");
-    let mut content = Cursor::new(bytes);
-    std::io::copy(&mut content, &mut file).expect("Cannot write the downloaded key to the file");
-    metrics::histogram!("server.prover.download_time", started_at.elapsed());
+        .await;
+        match result {
+            Ok(()) => return Ok(()),
+            // A missing key won't appear on retries.
+            Err(err @ DownloadError::Store(KeyStoreError::KeyNotFound(_))) => return Err(err),
+            Err(err) if retry_count >= retry_policy.max_retries => return Err(err),
+            Err(err) => {
+                retry_count += 1;
+                vlog::warn!(
+                    "Failed to download keys: {}. Backing off for {:?} (retry {}/{})",
+                    err,
+                    backoff,
+                    retry_count,
+                    retry_policy.max_retries
+                );
+                metrics::counter!("server.prover.download_retries", 1);
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(retry_policy.max_backoff);
+            }
+        }
+    }
 }
 
-pub fn numeric_index_to_circuit_name(circuit_numeric_index: u8) -> Option<&'static str> {
-    match circuit_numeric_index {
-        0 => Some("Scheduler"),
-        1 => Some("Node aggregation"),
-        2 => Some("Leaf aggregation"),
-        3 => Some("Main VM"),
-        4 => Some("Decommitts sorter"),
-        5 => Some("Code decommitter"),
-        6 => Some("Log demuxer"),
-        7 => Some("Keccak"),
-        8 => Some("SHA256"),
-        9 => Some("ECRecover"),
-        10 => Some("RAM permutation"),
-        11 => Some("Storage sorter"),
-        12 => Some("Storage application"),
-        13 => Some("Initial writes pubdata rehasher"),
-        14 => Some("Repeated writes pubdata rehasher"),
-        15 => Some("Events sorter"),
-        16 => Some("L1 messages sorter"),
-        17 => Some("L1 messages rehasher"),
-        18 => Some("L1 messages merklizer"),
-        _ => None,
+/// Resolves the expected checksum of the key: the configured one if provided, and the one
+/// from the `.sha256` sidecar of the key in the store otherwise.
+async fn resolve_checksum(
+    store: &dyn SetupKeyStore,
+    key_location: &str,
+    expected_checksum: Option<&str>,
+    timeout: Duration,
+) -> Option<KeyChecksum> {
+    if let Some(checksum) = expected_checksum {
+        let checksum = checksum
+            .parse()
+            .unwrap_or_else(|err| panic!("Invalid initial setup checksum: {}", err));
+        return Some(checksum);
+    }
+
+    let sidecar_location = format!("{}.sha256", key_location);
+    let sidecar = fetch_with_timeout(store, &sidecar_location, timeout)
+        .await
+        .map(|sidecar| {
+            String::from_utf8(sidecar)
+                .map_err(|err| err.to_string())
+                .and_then(|sidecar| sidecar.parse::<KeyChecksum>())
+        });
+    match sidecar {
+        Ok(Ok(checksum)) => Some(checksum),
+        Ok(Err(err)) => {
+            vlog::warn!(
+                "Invalid checksum sidecar at {:?}: {}",
+                sidecar_location,
+                err
+            );
+            None
+        }
+        Err(err) => {
+            vlog::warn!(
+                "No checksum is configured and the sidecar at {:?} is not available ({}), \
+                 initial setup won't be verified",
+                sidecar_location,
+                err
+            );
+            None
+        }
     }
 }
 
-pub fn circuit_name_to_numeric_index(circuit_name: &str) -> Option<u8> {
-    match circuit_name {
-        "Scheduler" => Some(0),
-        "Node aggregation" => Some(1),
-        "Leaf aggregation" => Some(2),
-        "Main VM" => Some(3),
-        "Decommitts sorter" => Some(4),
-        "Code decommitter" => Some(5),
-        "Log demuxer" => Some(6),
-        "Keccak" => Some(7),
-        "SHA256" => Some(8),
-        "ECRecover" => Some(9),
-        "RAM permutation" => Some(10),
-        "Storage sorter" => Some(11),
-        "Storage application" => Some(12),
-        "Initial writes pubdata rehasher" => Some(13),
-        "Repeated writes pubdata rehasher" => Some(14),
-        "Events sorter" => Some(15),
-        "L1 messages sorter" => Some(16),
-        "L1 messages rehasher" => Some(17),
-        "L1 messages merklizer" => Some(18),
-        _ => None,
+/// Downloads the key to `key_path` unless it's already present and matches the checksum (if it's known).
+async fn ensure_key_present(
+    store: &dyn SetupKeyStore,
+    key_path: &str,
+    key_location: &str,
+    expected_checksum: Option<&str>,
+    retry_policy: DownloadRetryPolicy,
+) {
+    let path = Path::new(key_path);
+    let checksum =
+        resolve_checksum(store, key_location, expected_checksum, retry_policy.timeout).await;
+
+    if path.exists() {
+        let Some(expected) = &checksum else {
+            vlog::info!("Key already present at {:?}", key_path);
+            return;
+        };
+        let actual = compute_checksum_for_file(expected, path)
+            .await
+            .expect("Cannot read the key");
+        if actual == *expected {
+            vlog::info!(
+                "Key already present at {:?} and matches checksum {}",
+                key_path,
+                expected
+            );
+            return;
+        }
+        vlog::warn!(
+            "Key at {:?} doesn't match checksum: expected {}, got {}; re-downloading it",
+            key_path,
+            expected,
+            actual
+        );
+        metrics::counter!("server.prover.corrupted_initial_setup", 1);
+        std::fs::remove_file(path).expect("Cannot remove the corrupted key");
     }
+    let started_at = Instant::now();
+
+    let key_dir = path.parent().unwrap();
+    create_dir_all(key_dir)
+        .unwrap_or_else(|_| panic!("Failed creating dirs recursively: {:?}", key_dir));
+    let part_path = part_path(path);
+    download_bytes(
+        store,
+        key_location,
+        &part_path,
+        checksum.as_ref(),
+        retry_policy,
+    )
+    .await
+    .unwrap_or_else(|err| panic!("Failed downloading key from {:?}: {}", key_location, err));
+    std::fs::rename(&part_path, path).expect("Cannot move the downloaded key to its path");
+    metrics::histogram!("server.prover.download_time", started_at.elapsed());
+}
+
+/// Downloads the initial setup keys from `key_location` in the store unless they are already present.
+/// If the checksum is known (either from `expected_checksum` or from the `.sha256` sidecar of the key),
+/// both the present and the downloaded keys are verified against it, and the present keys are re-downloaded
+/// on mismatch.
+pub async fn ensure_initial_setup_keys_present_async(
+    store: &dyn SetupKeyStore,
+    initial_setup_key_path: &str,
+    key_location: &str,
+    expected_checksum: Option<&str>,
+    retry_policy: DownloadRetryPolicy,
+) {
+    ensure_key_present(
+        store,
+        initial_setup_key_path,
+        key_location,
+        expected_checksum,
+        retry_policy,
+    )
+    .await;
+}
+
+/// Blocking version of [`ensure_initial_setup_keys_present_async()`] downloading the key over HTTP
+/// with the default retry policy. The checksum is fetched from the `.sha256` sidecar of the key if available.
+pub fn ensure_initial_setup_keys_present(initial_setup_key_path: &str, key_download_url: &str) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create runtime for downloading the initial setup");
+    runtime.block_on(ensure_initial_setup_keys_present_async(
+        &HttpKeyStore::new(),
+        initial_setup_key_path,
+        key_download_url,
+        None,
+        DownloadRetryPolicy::default(),
+    ));
+}
+
+/// Name of the file with the setup key for the circuit type, both in `setup_keys_path` and in the key store.
+pub fn setup_key_filename(circuit_type: u8) -> String {
+    format!("setup_{}_key.bin", circuit_type)
+}
+
+/// Downloads the setup keys for the circuit types to `setup_keys_path` unless they are already present.
+/// Keys are downloaded from `{setup_keys_location}/setup_{circuit_type}_key.bin` in the store concurrently,
+/// with at most `max_concurrent_downloads` downloads at a time, and verified against their `.sha256` sidecars
+/// if available.
+pub async fn ensure_setup_keys_present_for_circuits(
+    store: &dyn SetupKeyStore,
+    circuit_types: &[CircuitType],
+    setup_keys_path: &str,
+    setup_keys_location: &str,
+    max_concurrent_downloads: usize,
+    retry_policy: DownloadRetryPolicy,
+) {
+    let started_at = Instant::now();
+    let setup_keys_location = setup_keys_location.trim_end_matches('/');
+    let keys: Vec<_> = circuit_types
+        .iter()
+        .map(|&circuit_type| {
+            let filename = setup_key_filename(circuit_type.numeric_index());
+            (
+                format!("{}/{}", setup_keys_path, filename),
+                format!("{}/{}", setup_keys_location, filename),
+            )
+        })
+        .collect();
+
+    stream::iter(&keys)
+        .for_each_concurrent(max_concurrent_downloads.max(1), |(key_path, location)| {
+            ensure_key_present(store, key_path, location, None, retry_policy)
+        })
+        .await;
+    vlog::info!(
+        "Setup keys for circuits {:?} are present, took {:?}",
+        circuit_types,
+        started_at.elapsed()
+    );
 }
@@ -1,29 +1,259 @@
+//! Location of the node running a prover component, read from the metadata service of the cloud provider.
+//! Components pick prover instances in the same region, preferring the ones in the same zone, so the location
+//! must be reported consistently by all of them.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
 use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::Method;
+use reqwest::{Client, Method};
 
 use zksync_config::configs::ProverGroupConfig;
 use zksync_utils::http_with_retries::send_request_with_retries;
 
-pub async fn get_region() -> String {
-    let prover_group_config = ProverGroupConfig::from_env();
-    let mut headers = HeaderMap::new();
-    headers.insert("Metadata-Flavor", HeaderValue::from_static("Google"));
-    let response = send_request_with_retries(
-        &prover_group_config.region_read_url,
-        5,
-        Method::GET,
-        Some(headers),
-        None,
-    )
-    .await;
-    response
-        .unwrap_or_else(|_| {
-            panic!(
-                "Failed fetching response from url: {}",
-                prover_group_config.region_read_url
-            )
+const GCP_ZONE_URL: &str = "http://metadata.google.internal/computeMetadata/v1/instance/zone";
+const AWS_TOKEN_URL: &str = "http://169.254.169.254/latest/api/token";
+const AWS_REGION_URL: &str = "http://169.254.169.254/latest/meta-data/placement/region";
+const AWS_ZONE_URL: &str = "http://169.254.169.254/latest/meta-data/placement/availability-zone";
+const AZURE_REGION_URL: &str =
+    "http://169.254.169.254/metadata/instance/compute/location?api-version=2021-02-01&format=text";
+const AZURE_ZONE_URL: &str =
+    "http://169.254.169.254/metadata/instance/compute/zone?api-version=2021-02-01&format=text";
+
+/// Lifetime of the IMDSv2 session token; it's only used for a couple of requests.
+const AWS_TOKEN_TTL_SECONDS: &str = "60";
+/// Timeout of the requests probing the metadata services. Metadata services are link-local,
+/// so a probe of the service of another provider fails fast or times out.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_RETRIES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudProvider {
+    Gcp,
+    Aws,
+    Azure,
+}
+
+impl CloudProvider {
+    const ALL: [Self; 3] = [Self::Gcp, Self::Aws, Self::Azure];
+}
+
+impl fmt::Display for CloudProvider {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Gcp => "GCP",
+            Self::Aws => "AWS",
+            Self::Azure => "Azure",
+        })
+    }
+}
+
+impl FromStr for CloudProvider {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "GCP" => Ok(Self::Gcp),
+            "AWS" => Ok(Self::Aws),
+            "Azure" => Ok(Self::Azure),
+            _ => Err(format!("Unknown cloud provider: {}", input)),
+        }
+    }
+}
+
+/// Location of the node normalized across the cloud providers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeLocation {
+    pub provider: CloudProvider,
+    pub region: String,
+    /// Zone within the region, if the node is placed in a specific one.
+    pub zone: Option<String>,
+}
+
+/// Fetches the location of the node from the metadata service of the configured cloud provider,
+/// detecting the provider if it isn't configured.
+#[derive(Debug, Clone)]
+pub struct NodeLocationFetcher {
+    provider: Option<CloudProvider>,
+    gcp_region_url: String,
+}
+
+impl NodeLocationFetcher {
+    /// Creates the fetcher, failing if the configured cloud provider is unknown.
+    pub fn from_config(config: &ProverGroupConfig) -> Result<Self, String> {
+        let provider = config
+            .cloud_provider
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err| format!("Invalid PROVER_GROUP_CLOUD_PROVIDER: {}", err))?;
+        Ok(Self {
+            provider,
+            gcp_region_url: config.region_read_url.clone(),
         })
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_config(&ProverGroupConfig::from_env())
+    }
+
+    pub async fn fetch(&self) -> Result<NodeLocation, String> {
+        let provider = match self.provider {
+            Some(provider) => provider,
+            None => Self::detect_provider()
+                .await
+                .ok_or("Failed detecting cloud provider: no metadata service responded")?,
+        };
+        match provider {
+            CloudProvider::Gcp => self.fetch_gcp().await,
+            CloudProvider::Aws => Self::fetch_aws().await,
+            CloudProvider::Azure => Self::fetch_azure().await,
+        }
+    }
+
+    async fn detect_provider() -> Option<CloudProvider> {
+        let client = Client::builder().timeout(PROBE_TIMEOUT).build().ok()?;
+        for provider in CloudProvider::ALL {
+            let request = match provider {
+                CloudProvider::Gcp => client
+                    .get(GCP_ZONE_URL)
+                    .headers(metadata_headers(CloudProvider::Gcp, None)),
+                CloudProvider::Aws => client.put(AWS_TOKEN_URL).header(
+                    "X-aws-ec2-metadata-token-ttl-seconds",
+                    AWS_TOKEN_TTL_SECONDS,
+                ),
+                CloudProvider::Azure => client
+                    .get(AZURE_REGION_URL)
+                    .headers(metadata_headers(CloudProvider::Azure, None)),
+            };
+            let is_available = request
+                .send()
+                .await
+                .map_or(false, |response| response.status().is_success());
+            if is_available {
+                vlog::info!("Detected cloud provider: {}", provider);
+                return Some(provider);
+            }
+        }
+        None
+    }
+
+    async fn fetch_gcp(&self) -> Result<NodeLocation, String> {
+        let headers = metadata_headers(CloudProvider::Gcp, None);
+        let region = fetch_text(&self.gcp_region_url, Method::GET, headers.clone()).await?;
+        // The zone is returned as `projects/<project number>/zones/<zone>`. It's informational only,
+        // so the nodes with the region URL pointing to a custom service don't need to provide it.
+        let zone = fetch_text(GCP_ZONE_URL, Method::GET, headers).await.ok();
+        Ok(NodeLocation {
+            provider: CloudProvider::Gcp,
+            region,
+            zone: zone.and_then(|zone| zone.rsplit('/').next().map(str::to_owned)),
+        })
+    }
+
+    async fn fetch_aws() -> Result<NodeLocation, String> {
+        let mut token_headers = HeaderMap::new();
+        token_headers.insert(
+            "X-aws-ec2-metadata-token-ttl-seconds",
+            HeaderValue::from_static(AWS_TOKEN_TTL_SECONDS),
+        );
+        let token = fetch_text(AWS_TOKEN_URL, Method::PUT, token_headers).await?;
+        let headers = metadata_headers(CloudProvider::Aws, Some(&token));
+        Ok(NodeLocation {
+            provider: CloudProvider::Aws,
+            region: fetch_text(AWS_REGION_URL, Method::GET, headers.clone()).await?,
+            zone: Some(fetch_text(AWS_ZONE_URL, Method::GET, headers).await?),
+        })
+    }
+
+    async fn fetch_azure() -> Result<NodeLocation, String> {
+        let headers = metadata_headers(CloudProvider::Azure, None);
+        let region = fetch_text(AZURE_REGION_URL, Method::GET, headers.clone()).await?;
+        // Azure zones are numbered within the region; the zone is empty for the VMs not pinned to a zone.
+        let zone = fetch_text(AZURE_ZONE_URL, Method::GET, headers).await?;
+        Ok(NodeLocation {
+            provider: CloudProvider::Azure,
+            region,
+            zone: normalize_azure_zone(&zone),
+        })
+    }
+}
+
+fn metadata_headers(provider: CloudProvider, aws_token: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    match provider {
+        CloudProvider::Gcp => {
+            headers.insert("Metadata-Flavor", HeaderValue::from_static("Google"));
+        }
+        CloudProvider::Aws => {
+            let token = aws_token.expect("IMDSv2 requests require a session token");
+            headers.insert(
+                "X-aws-ec2-metadata-token",
+                HeaderValue::from_str(token).expect("invalid IMDSv2 token"),
+            );
+        }
+        CloudProvider::Azure => {
+            headers.insert("Metadata", HeaderValue::from_static("true"));
+        }
+    }
+    headers
+}
+
+fn normalize_azure_zone(zone: &str) -> Option<String> {
+    let zone = zone.trim();
+    (!zone.is_empty()).then(|| zone.to_owned())
+}
+
+async fn fetch_text(url: &str, method: Method, headers: HeaderMap) -> Result<String, String> {
+    let response = send_request_with_retries(url, MAX_RETRIES, method, Some(headers), None)
+        .await
+        .map_err(|err| format!("Failed fetching response from url {}: {}", url, err))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Metadata service at {} responded with {}",
+            url,
+            response.status()
+        ));
+    }
+    let text = response
         .text()
         .await
-        .expect("Failed to read response as text")
+        .map_err(|err| format!("Failed to read response from {} as text: {}", url, err))?;
+    Ok(text.trim().to_owned())
+}
+
+/// Returns the location of the node, panicking if it can't be fetched.
+pub async fn get_node_location() -> NodeLocation {
+    let fetcher = NodeLocationFetcher::from_env().unwrap_or_else(|err| panic!("{}", err));
+    let location = fetcher
+        .fetch()
+        .await
+        .unwrap_or_else(|err| panic!("{}", err));
+    vlog::info!(
+        "Node location: {} region {}, zone {:?}",
+        location.provider,
+        location.region,
+        location.zone
+    );
+    location
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_cloud_providers() {
+        for provider in CloudProvider::ALL {
+            assert_eq!(provider.to_string().parse::<CloudProvider>(), Ok(provider));
+        }
+        assert!("gcp".parse::<CloudProvider>().is_err());
+    }
+
+    #[test]
+    fn normalizing_azure_zones() {
+        assert_eq!(normalize_azure_zone("2\n"), Some("2".to_owned()));
+        assert_eq!(normalize_azure_zone(""), None);
+    }
 }
@@ -8,6 +8,11 @@ use zksync_utils::panic_extractor::try_extract_panic_message;
 
 pub use async_trait::async_trait;
 
+/// Identifies the worker in the `picked_by` column of the jobs it leases.
+pub fn worker_id() -> String {
+    std::env::var("POD_NAME").unwrap_or_else(|_| "Unknown".to_string())
+}
+
 #[async_trait]
 pub trait JobProcessor: Sync + Send {
     type Job: Send + 'static;
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::ops::Deref;
 use zksync_storage::db::StateKeeperColumnFamily;
-use zksync_storage::rocksdb::WriteBatch;
+use zksync_storage::rocksdb::{self, WriteBatch};
 use zksync_storage::util::{deserialize_block_number, serialize_block_number};
 use zksync_storage::RocksDB;
 use zksync_types::{
@@ -69,6 +69,11 @@ impl SecondaryStateStorage {
             .map(|value| H256::from_slice(&value))
     }
 
+    /// Returns the value of the slot, or `None` if the slot was never written.
+    pub fn read_value_opt(&self, key: &StorageKey) -> Option<StorageValue> {
+        self.read_value_inner(key)
+    }
+
     pub fn load_factory_dependency(&self, hash: H256) -> Option<Vec<u8>> {
         if let Some(value) = self.pending_patch.factory_deps.get(&hash) {
             return Some(value.clone());
@@ -183,16 +188,18 @@ impl SecondaryStateStorage {
 
     /// Returns the last processed l1 batch number + 1
     pub fn get_l1_batch_number(&self) -> L1BatchNumber {
-        let cf = self
-            .db
-            .cf_state_keeper_handle(StateKeeperColumnFamily::State);
-        let block_number = self
-            .db
-            .get_cf(cf, BLOCK_NUMBER_KEY)
-            .expect("failed to fetch block number")
+        Self::read_l1_batch_number(&self.db).expect("failed to fetch block number")
+    }
+
+    /// Same as [`Self::get_l1_batch_number()`], but reads the number from a state keeper cache instance
+    /// without taking ownership of it.
+    pub fn read_l1_batch_number(db: &RocksDB) -> Result<L1BatchNumber, rocksdb::Error> {
+        let cf = db.cf_state_keeper_handle(StateKeeperColumnFamily::State);
+        let block_number = db
+            .get_cf(cf, BLOCK_NUMBER_KEY)?
             .map(|bytes| deserialize_block_number(&bytes))
             .unwrap_or(0);
-        L1BatchNumber(block_number)
+        Ok(L1BatchNumber(block_number))
     }
 
     fn serialize_state_key(key: &StorageKey) -> Vec<u8> {
@@ -1,7 +1,7 @@
 use once_cell::sync::Lazy;
 use rocksdb::{
-    AsColumnFamilyRef, BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor, Options,
-    WriteBatch, DB,
+    checkpoint::Checkpoint, AsColumnFamilyRef, BlockBasedOptions, ColumnFamily,
+    ColumnFamilyDescriptor, DBIterator, DBRawIterator, IteratorMode, Options, WriteBatch, DB,
 };
 use std::path::Path;
 use std::sync::{Condvar, Mutex};
@@ -20,7 +20,7 @@ pub struct RocksDB {
     _registry_entry: RegistryEntry,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum Database {
     MerkleTree,
     StateKeeper,
@@ -30,6 +30,10 @@ pub enum Database {
 pub enum MerkleTreeColumnFamily {
     Tree,
     LeafIndices,
+    /// Previous hashes of the tree nodes, used to access the tree at one of the recent versions.
+    NodeHistory,
+    /// Recent versions of the tree along with the nodes changed in each of them.
+    Versions,
 }
 
 #[derive(Debug)]
@@ -41,7 +45,12 @@ pub enum StateKeeperColumnFamily {
 
 impl MerkleTreeColumnFamily {
     fn all() -> &'static [Self] {
-        &[Self::Tree, Self::LeafIndices]
+        &[
+            Self::Tree,
+            Self::LeafIndices,
+            Self::NodeHistory,
+            Self::Versions,
+        ]
     }
 }
 
@@ -56,6 +65,8 @@ impl std::fmt::Display for MerkleTreeColumnFamily {
         let value = match self {
             MerkleTreeColumnFamily::Tree => "default",
             MerkleTreeColumnFamily::LeafIndices => "leaf_indices",
+            MerkleTreeColumnFamily::NodeHistory => "node_history",
+            MerkleTreeColumnFamily::Versions => "versions",
         };
         write!(formatter, "{}", value)
     }
@@ -96,6 +107,71 @@ impl RocksDB {
         }
     }
 
+    /// Opens the database in the read-only mode, which can be done while it's used by another process.
+    /// The instance doesn't observe the changes made after it was opened.
+    pub fn new_read_only<P: AsRef<Path>>(database: Database, path: P) -> Self {
+        Self::try_new_read_only(database, path).expect("failed to open rocksdb in read-only mode")
+    }
+
+    /// Same as [`Self::new_read_only()`], but returns an error if the database can't be opened.
+    pub fn try_new_read_only<P: AsRef<Path>>(
+        database: Database,
+        path: P,
+    ) -> Result<Self, rocksdb::Error> {
+        let db = DB::open_cf_for_read_only(
+            &Options::default(),
+            path,
+            Self::column_family_names(database),
+            false,
+        )?;
+        Ok(Self {
+            db,
+            _registry_entry: RegistryEntry::new(),
+        })
+    }
+
+    /// Opens the database as a secondary instance following the primary instance at `primary_path`, which may be
+    /// used by another process. Unlike the read-only instances, the secondary instance observes the changes
+    /// made by the primary after [`Self::catch_up_with_primary()`]. `secondary_path` is the directory
+    /// the secondary instance keeps its own logs in.
+    pub fn new_secondary<P: AsRef<Path>, S: AsRef<Path>>(
+        database: Database,
+        primary_path: P,
+        secondary_path: S,
+    ) -> Result<Self, rocksdb::Error> {
+        let mut options = Options::default();
+        // Secondary instances must keep all the files open, since the primary may delete them at any time.
+        options.set_max_open_files(-1);
+        let db = DB::open_cf_as_secondary(
+            &options,
+            primary_path.as_ref(),
+            secondary_path.as_ref(),
+            Self::column_family_names(database),
+        )?;
+        Ok(Self {
+            db,
+            _registry_entry: RegistryEntry::new(),
+        })
+    }
+
+    /// Applies the changes made by the primary instance since the secondary instance was opened or last caught up.
+    pub fn catch_up_with_primary(&self) -> Result<(), rocksdb::Error> {
+        self.db.try_catch_up_with_primary()
+    }
+
+    fn column_family_names(database: Database) -> Vec<String> {
+        match database {
+            Database::MerkleTree => MerkleTreeColumnFamily::all()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            Database::StateKeeper => StateKeeperColumnFamily::all()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+
     fn rocksdb_options(tune_options: bool) -> Options {
         let mut options = Options::default();
         options.create_missing_column_families(true);
@@ -176,6 +252,27 @@ impl RocksDB {
         self.db.get_cf(cf, key)
     }
 
+    /// Iterates over the entries of the column family in the order of their keys, starting from `mode`.
+    pub fn iterator_cf<'a>(
+        &'a self,
+        cf: &impl AsColumnFamilyRef,
+        mode: IteratorMode,
+    ) -> DBIterator<'a> {
+        self.db.iterator_cf(cf, mode)
+    }
+
+    /// Returns the iterator over the column family, which can be moved to an arbitrary key with
+    /// [`DBRawIterator::seek()`]. Useful to make multiple lookups of the closest keys.
+    pub fn raw_iterator_cf<'a>(&'a self, cf: &impl AsColumnFamilyRef) -> DBRawIterator<'a> {
+        self.db.raw_iterator_cf(cf)
+    }
+
+    /// Creates a checkpoint of the database in the directory, which must not exist yet. The checkpoint
+    /// is a consistent copy of the database which can be opened on its own (SST files are hard-linked if possible).
+    pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<(), rocksdb::Error> {
+        Checkpoint::new(&self.db)?.create_checkpoint(path)
+    }
+
     /// awaits termination of all running rocksdb instances
     pub fn await_rocksdb_termination() {
         let (lock, cvar) = &*ROCKSDB_INSTANCE_COUNTER;
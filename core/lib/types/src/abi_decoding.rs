@@ -0,0 +1,277 @@
+//! Decoding of the L2 transaction calldata and the event logs using the ABI of a verified contract.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::api::Log;
+use crate::ethabi::{self, Contract, ParamType, RawLog, Token};
+use crate::{Address, Bytes, U256};
+
+#[derive(Debug, Error)]
+pub enum AbiDecodingError {
+    #[error("Calldata is shorter than a function selector")]
+    TooShort,
+    #[error("Unknown function selector: 0x{0}")]
+    UnknownSelector(String),
+    #[error("Failed to decode the calldata: {0}")]
+    Abi(#[from] ethabi::Error),
+}
+
+/// Decoded function argument or event parameter.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedParam {
+    pub name: String,
+    /// Solidity type of the parameter, e.g. `uint256` or `address[]`.
+    #[serde(rename = "type")]
+    pub param_type: String,
+    /// Integers are represented as decimal strings, and addresses and bytes as `0x`-prefixed hex strings.
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedEvent {
+    pub address: Address,
+    pub log_index: Option<U256>,
+    pub event_name: String,
+    pub event_signature: String,
+    pub arguments: Vec<DecodedParam>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedTransactionInput {
+    pub contract_address: Address,
+    pub function_name: String,
+    /// Canonical signature of the function, e.g. `transfer(address,uint256)`.
+    pub function_signature: String,
+    pub selector: Bytes,
+    pub arguments: Vec<DecodedParam>,
+    /// Logs of the transaction emitted by the verified contracts. Only present if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<DecodedEvent>>,
+}
+
+/// Decodes the calldata of the transaction calling the contract with the provided ABI.
+pub fn decode_transaction_input(
+    contract: &Contract,
+    contract_address: Address,
+    calldata: &[u8],
+) -> Result<DecodedTransactionInput, AbiDecodingError> {
+    if calldata.len() < 4 {
+        return Err(AbiDecodingError::TooShort);
+    }
+    let (selector, input) = calldata.split_at(4);
+    let function = contract
+        .functions()
+        .find(|function| function.short_signature() == selector)
+        .ok_or_else(|| AbiDecodingError::UnknownSelector(hex::encode(selector)))?;
+    let tokens = function.decode_input(input)?;
+
+    let arguments = function
+        .inputs
+        .iter()
+        .zip(tokens)
+        .map(|(param, token)| decoded_param(param.name.clone(), &param.kind, token))
+        .collect();
+    Ok(DecodedTransactionInput {
+        contract_address,
+        function_name: function.name.clone(),
+        function_signature: canonical_signature(
+            &function.name,
+            function.inputs.iter().map(|param| &param.kind),
+        ),
+        selector: Bytes(selector.to_vec()),
+        arguments,
+        events: None,
+    })
+}
+
+/// Decodes the log emitted by the contract with the provided ABI. Returns `None` if the log
+/// doesn't correspond to any non-anonymous event of the contract.
+pub fn decode_log(contract: &Contract, log: &Log) -> Option<DecodedEvent> {
+    let topic0 = *log.topics.first()?;
+    let event = contract
+        .events()
+        .find(|event| !event.anonymous && event.signature() == topic0)?;
+    let parsed = event
+        .parse_log(RawLog {
+            topics: log.topics.clone(),
+            data: log.data.0.clone(),
+        })
+        .ok()?;
+
+    let arguments = event
+        .inputs
+        .iter()
+        .zip(parsed.params)
+        .map(|(param, parsed_param)| {
+            decoded_param(parsed_param.name, &param.kind, parsed_param.value)
+        })
+        .collect();
+    Some(DecodedEvent {
+        address: log.address,
+        log_index: log.log_index,
+        event_name: event.name.clone(),
+        event_signature: canonical_signature(
+            &event.name,
+            event.inputs.iter().map(|param| &param.kind),
+        ),
+        arguments,
+    })
+}
+
+fn canonical_signature<'a>(name: &str, kinds: impl Iterator<Item = &'a ParamType>) -> String {
+    let kinds: Vec<_> = kinds.map(ToString::to_string).collect();
+    format!("{}({})", name, kinds.join(","))
+}
+
+fn decoded_param(name: String, kind: &ParamType, token: Token) -> DecodedParam {
+    DecodedParam {
+        name,
+        param_type: kind.to_string(),
+        value: token_to_json(token),
+    }
+}
+
+fn token_to_json(token: Token) -> serde_json::Value {
+    match token {
+        Token::Address(address) => format!("{:?}", address).into(),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => {
+            format!("0x{}", hex::encode(bytes)).into()
+        }
+        Token::Uint(value) => value.to_string().into(),
+        Token::Int(value) => {
+            // Signed integers are represented in two's complement.
+            if value.bit(255) {
+                format!("-{}", (!value).overflowing_add(U256::one()).0).into()
+            } else {
+                value.to_string().into()
+            }
+        }
+        Token::Bool(value) => value.into(),
+        Token::String(value) => value.into(),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            tokens.into_iter().map(token_to_json).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::H256;
+
+    const ERC20_ABI: &str = r#"[
+        {
+            "type": "function",
+            "name": "transfer",
+            "inputs": [
+                { "name": "to", "type": "address" },
+                { "name": "amount", "type": "uint256" }
+            ],
+            "outputs": [{ "name": "", "type": "bool" }],
+            "stateMutability": "nonpayable"
+        },
+        {
+            "type": "event",
+            "name": "Transfer",
+            "inputs": [
+                { "name": "from", "type": "address", "indexed": true },
+                { "name": "to", "type": "address", "indexed": true },
+                { "name": "value", "type": "int256", "indexed": false }
+            ],
+            "anonymous": false
+        }
+    ]"#;
+
+    fn erc20_contract() -> Contract {
+        serde_json::from_str(ERC20_ABI).unwrap()
+    }
+
+    #[test]
+    fn transaction_input_is_decoded() {
+        let contract = erc20_contract();
+        let to = Address::repeat_byte(0x11);
+        let calldata = contract
+            .function("transfer")
+            .unwrap()
+            .encode_input(&[Token::Address(to), Token::Uint(1_000.into())])
+            .unwrap();
+
+        let contract_address = Address::repeat_byte(0x22);
+        let decoded = decode_transaction_input(&contract, contract_address, &calldata).unwrap();
+        assert_eq!(decoded.contract_address, contract_address);
+        assert_eq!(decoded.function_name, "transfer");
+        assert_eq!(decoded.function_signature, "transfer(address,uint256)");
+        assert_eq!(decoded.selector.0, calldata[..4]);
+        assert_eq!(
+            decoded.arguments,
+            [
+                DecodedParam {
+                    name: "to".to_owned(),
+                    param_type: "address".to_owned(),
+                    value: format!("{:?}", to).into(),
+                },
+                DecodedParam {
+                    name: "amount".to_owned(),
+                    param_type: "uint256".to_owned(),
+                    value: "1000".into(),
+                },
+            ]
+        );
+
+        let err = decode_transaction_input(&contract, contract_address, &[1, 2, 3, 4]).unwrap_err();
+        assert!(
+            matches!(err, AbiDecodingError::UnknownSelector(_)),
+            "{}",
+            err
+        );
+        let err = decode_transaction_input(&contract, contract_address, &[1]).unwrap_err();
+        assert!(matches!(err, AbiDecodingError::TooShort), "{}", err);
+    }
+
+    #[test]
+    fn log_is_decoded() {
+        let contract = erc20_contract();
+        let event = contract.event("Transfer").unwrap();
+        let from = Address::repeat_byte(0x11);
+        let to = Address::repeat_byte(0x22);
+        let minus_five = (!U256::from(5)).overflowing_add(U256::one()).0;
+        let log = Log {
+            address: Address::repeat_byte(0x33),
+            topics: vec![event.signature(), H256::from(from), H256::from(to)],
+            data: Bytes(ethabi::encode(&[Token::Int(minus_five)])),
+            block_hash: None,
+            block_number: None,
+            l1_batch_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: Some(3.into()),
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        };
+
+        let decoded = decode_log(&contract, &log).unwrap();
+        assert_eq!(decoded.event_name, "Transfer");
+        assert_eq!(decoded.event_signature, "Transfer(address,address,int256)");
+        assert_eq!(decoded.log_index, Some(3.into()));
+        let values: Vec<_> = decoded.arguments.iter().map(|arg| &arg.value).collect();
+        assert_eq!(
+            values,
+            [
+                &serde_json::Value::from(format!("{:?}", from)),
+                &format!("{:?}", to).into(),
+                &"-5".into()
+            ]
+        );
+
+        let unknown_log = Log {
+            topics: vec![H256::repeat_byte(1)],
+            ..log
+        };
+        assert!(decode_log(&contract, &unknown_log).is_none());
+    }
+}
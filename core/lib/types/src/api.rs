@@ -1,16 +1,23 @@
+use std::collections::{BTreeMap, HashSet};
+use std::str::FromStr;
+
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::explorer_api::TransactionStatus;
+use crate::chain_stats::ChainStatsCheckpoint;
+use crate::explorer_api::{BalanceItem, TransactionStatus};
+use crate::fee::{Fee, RefundBreakdown};
 pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
+use crate::tx::TxAccessSet;
 use crate::web3::types::{AccessList, Index, H2048};
-use crate::{Address, MiniblockNumber};
+use crate::{Address, L1BatchNumber, L2ChainId, MiniblockNumber, PackedEthSignature};
 use chrono::{DateTime, Utc};
 pub use zksync_basic_types::web3::{
     self, ethabi,
     types::{Bytes, Work, H160, H256, H64, U256, U64},
 };
+pub use zksync_config::configs::api::NodePersonality;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -172,6 +179,30 @@ pub struct L2ToL1LogProof {
     pub root: H256,
 }
 
+/// Merkle proof of the value of a storage slot in the state tree after an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProof {
+    pub key: H256,
+    pub value: H256,
+    /// Enumeration index of the slot in the tree, 0 if the slot has never been written.
+    pub index: u64,
+    /// Hashes of the sibling nodes on the path from the leaf to the root.
+    pub proof: Vec<H256>,
+}
+
+/// Merkle proofs of the storage slots of an account, returned by `zks_getProof`.
+/// The leaf hash is `blake2s(index as u64 big-endian ++ value)`, and the path leads to the `root_hash` of the L1 batch,
+/// which is committed on L1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStorageProof {
+    pub address: Address,
+    pub l1_batch_number: L1BatchNumber,
+    pub root_hash: H256,
+    pub storage_proof: Vec<StorageProof>,
+}
+
 /// A struct with the two default bridge contracts.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -239,6 +270,9 @@ pub struct TransactionReceipt {
     /// Effective gas price
     #[serde(rename = "effectiveGasPrice")]
     pub effective_gas_price: Option<U256>,
+    /// zkSync extension: finality of the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finality: Option<Finality>,
 }
 
 /// The block type returned from RPC calls.
@@ -267,9 +301,6 @@ pub struct Block<TX> {
     pub receipts_root: H256,
     /// Block number
     pub number: U64,
-    /// L1 batch number the block is included in
-    #[serde(rename = "l1BatchNumber")]
-    pub l1_batch_number: Option<U64>,
     /// Gas Used
     #[serde(rename = "gasUsed")]
     pub gas_used: U256,
@@ -287,9 +318,6 @@ pub struct Block<TX> {
     pub logs_bloom: H2048,
     /// Timestamp
     pub timestamp: U256,
-    /// Timestamp of the l1 batch this miniblock was included within
-    #[serde(rename = "l1BatchTimestamp")]
-    pub l1_batch_timestamp: Option<U256>,
     /// Difficulty
     pub difficulty: U256,
     /// Total difficulty
@@ -309,6 +337,62 @@ pub struct Block<TX> {
     pub mix_hash: H256,
     /// Nonce
     pub nonce: H64,
+    /// zkSync extension fields, which are not a part of the Ethereum block format. They are returned
+    /// only to the clients that have requested them, so the block is strictly Ethereum-compatible by default.
+    #[serde(
+        flatten,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_block_extensions"
+    )]
+    pub extensions: Option<BlockExtensions>,
+}
+
+/// zkSync extension fields of the block. Once requested, all of them are returned, with the unknown ones
+/// (e.g. for a block not included in an L1 batch yet) set to `null`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockExtensions {
+    /// L1 batch number the block is included in.
+    #[serde(default)]
+    pub l1_batch_number: Option<U64>,
+    /// Timestamp of the L1 batch the block is included in.
+    #[serde(default)]
+    pub l1_batch_timestamp: Option<U256>,
+    /// Name of the criterion the L1 batch has been sealed by. Unknown for the batches sealed
+    /// by an external node or before the criterion has started to be persisted.
+    #[serde(default)]
+    pub seal_criterion: Option<String>,
+    /// Address of the operator which has sealed the block and receives its fees.
+    #[serde(default)]
+    pub operator_address: Option<Address>,
+}
+
+impl BlockExtensions {
+    const FIELDS: [&'static str; 4] = [
+        "l1BatchNumber",
+        "l1BatchTimestamp",
+        "sealCriterion",
+        "operatorAddress",
+    ];
+}
+
+/// The block is deemed to have the extensions if any of the extension fields is present, even as `null`.
+fn deserialize_block_extensions<'de, D>(
+    deserializer: D,
+) -> Result<Option<BlockExtensions>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let fields = serde_json::Map::deserialize(deserializer)?;
+    if !BlockExtensions::FIELDS
+        .iter()
+        .any(|&field| fields.contains_key(field))
+    {
+        return Ok(None);
+    }
+    serde_json::from_value(serde_json::Value::Object(fields))
+        .map(Some)
+        .map_err(de::Error::custom)
 }
 
 fn null_to_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -471,17 +555,812 @@ pub struct Transaction {
     pub l1_batch_tx_index: Option<U64>,
 }
 
+/// Finality of a transaction or a block. Only the operations confirmed on L1 are taken into account,
+/// so the finality can only go back if L1 itself is reorganized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Finality {
+    /// Not included into a miniblock yet.
+    #[default]
+    Pending,
+    /// Included into a sealed miniblock, but the L1 batch isn't committed yet.
+    L2Sealed,
+    Committed,
+    Proven,
+    Executed,
+}
+
+impl Finality {
+    pub fn new(is_sealed: bool, is_committed: bool, is_proven: bool, is_executed: bool) -> Self {
+        if is_executed {
+            Self::Executed
+        } else if is_proven {
+            Self::Proven
+        } else if is_committed {
+            Self::Committed
+        } else if is_sealed {
+            Self::L2Sealed
+        } else {
+            Self::Pending
+        }
+    }
+}
+
+/// Numbers of the last L1 batches which commit, prove and execute operations are confirmed on L1.
+/// The genesis L1 batch is considered to be executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct L1BatchFinalityWatermarks {
+    pub last_committed: L1BatchNumber,
+    pub last_proven: L1BatchNumber,
+    pub last_executed: L1BatchNumber,
+}
+
+impl L1BatchFinalityWatermarks {
+    /// Returns the finality of a miniblock included into the given L1 batch.
+    /// `l1_batch_number` is `None` if the miniblock is not sealed or not included into an L1 batch yet.
+    pub fn finality(&self, is_sealed: bool, l1_batch_number: Option<L1BatchNumber>) -> Finality {
+        let reached =
+            |watermark: L1BatchNumber| l1_batch_number.map_or(false, |number| number <= watermark);
+        Finality::new(
+            is_sealed,
+            reached(self.last_committed),
+            reached(self.last_proven),
+            reached(self.last_executed),
+        )
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionDetails {
     pub is_l1_originated: bool,
     pub status: TransactionStatus,
+    #[serde(default)]
+    pub finality: Finality,
     pub fee: U256,
     pub initiator_address: Address,
     pub received_at: DateTime<Utc>,
     pub eth_commit_tx_hash: Option<H256>,
     pub eth_prove_tx_hash: Option<H256>,
     pub eth_execute_tx_hash: Option<H256>,
+    /// Gas refunded to the initiator at the end of the transaction.
+    pub refunded_gas: U256,
+    /// Components of the refund proposed by the operator.
+    /// `None` for transactions that have not been executed yet.
+    pub refund_breakdown: Option<RefundBreakdown>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxLifecycleEventKind {
+    Received,
+    Validated,
+    InsertedToMempool,
+    Replaced,
+    Executed,
+    Rejected,
+}
+
+impl TxLifecycleEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Received => "received",
+            Self::Validated => "validated",
+            Self::InsertedToMempool => "inserted_to_mempool",
+            Self::Replaced => "replaced",
+            Self::Executed => "executed",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+impl FromStr for TxLifecycleEventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "received" => Ok(Self::Received),
+            "validated" => Ok(Self::Validated),
+            "inserted_to_mempool" => Ok(Self::InsertedToMempool),
+            "replaced" => Ok(Self::Replaced),
+            "executed" => Ok(Self::Executed),
+            "rejected" => Ok(Self::Rejected),
+            other => Err(format!("unknown tx lifecycle event: {}", other)),
+        }
+    }
+}
+
+/// Entry of the transaction lifecycle log, returned by `debug_getTransactionLifecycle`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxLifecycleEvent {
+    pub event: TxLifecycleEventKind,
+    /// Miniblock the transaction has been executed in, set for `executed` events.
+    pub miniblock_number: Option<MiniblockNumber>,
+    /// Rejection reason, set for `rejected` events.
+    pub details: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TxLifecycleEvent {
+    pub fn new(event: TxLifecycleEventKind) -> Self {
+        Self {
+            event,
+            miniblock_number: None,
+            details: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn rejected(reason: String) -> Self {
+        Self {
+            details: Some(reason),
+            ..Self::new(TxLifecycleEventKind::Rejected)
+        }
+    }
+}
+
+/// Average number of transactions per second over a time window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AverageTps {
+    pub window_sec: u64,
+    pub tps: f64,
+}
+
+/// Chain-wide statistics, returned by `zks_getChainStats`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainStats {
+    #[serde(flatten)]
+    pub totals: ChainStatsCheckpoint,
+    pub average_tps: Vec<AverageTps>,
+}
+
+/// State of an account needed by wallets on load, returned by `zks_getAccountSummary`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    pub address: Address,
+    /// Nonce of the account as of the latest sealed miniblock.
+    pub nonce: U256,
+    /// Nonce to be used for the next transaction, includes the transactions in the mempool.
+    pub pending_nonce: U256,
+    pub eth_balance: U256,
+    /// Non-zero balances of the well-known tokens except ETH, the most valuable ones first.
+    pub token_balances: Vec<BalanceItem>,
+    pub pending_transactions_count: usize,
+    /// Timestamp of the latest miniblock with a transaction sent by the account.
+    pub last_activity_timestamp: Option<u64>,
+}
+
+/// State of an account returned by `eth_getAccount`.
+/// Unlike in Ethereum, there's no per-account storage root, since all the slots are kept in a single tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    /// Nonce of the account, doesn't include the transactions in the mempool.
+    pub nonce: U256,
+    pub balance: U256,
+    /// Versioned hash of the bytecode deployed at the address, zero for the accounts without code.
+    pub code_hash: H256,
+}
+
+/// Last miniblock sealed at or before the requested time, returned by `zks_getBlockByTimestamp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockAtTimestamp {
+    pub number: MiniblockNumber,
+    pub timestamp: u64,
+}
+
+/// State of an account as of the requested time, returned by `zks_getAccountStateAtTimestamp`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateAtTimestamp {
+    pub address: Address,
+    /// Miniblock the state is taken from.
+    pub block: BlockAtTimestamp,
+    pub nonce: U256,
+    pub eth_balance: U256,
+}
+
+/// Metadata of an L2 token read from the token contract, returned by `zks_getTokenInfo`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L2TokenInfo {
+    pub l2_address: Address,
+    /// `None` if the contract doesn't implement `name()` or the call has reverted.
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// Time the metadata was read for the last time, `None` if the token wasn't processed yet.
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// Priority operation which wasn't executed on L1 until its deadline, returned by `zks_getExpiredPriorityOps`.
+/// Such operations are still executed in order, since L1 doesn't allow to skip them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpiredPriorityOp {
+    pub serial_id: u64,
+    pub tx_hash: H256,
+    /// Expiration timestamp (in seconds) of the operation on L1.
+    pub deadline_block: u64,
+    /// L1 block at which the operation was detected as expired.
+    pub detected_at_block: u64,
+    pub detected_at: DateTime<Utc>,
+    /// `None` if the operation isn't executed on L2 yet.
+    pub miniblock_number: Option<MiniblockNumber>,
+    pub l1_batch_number: Option<L1BatchNumber>,
+}
+
+/// Storage slot changed in a block or an L1 batch, returned by `zks_getStateDiff` and `zks_getL1BatchStateDiff`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSlotDiff {
+    pub address: Address,
+    pub key: H256,
+    pub hashed_key: H256,
+    /// Value of the slot before the block / batch; zero if the slot wasn't written before.
+    pub old_value: H256,
+    /// Value of the slot after the block / batch.
+    pub new_value: H256,
+    /// Whether the slot wasn't written before the block / batch.
+    pub initial_write: bool,
+}
+
+/// Page of the state diff of a block or an L1 batch. Slots are ordered by their hashed keys.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffPage {
+    pub diffs: Vec<StorageSlotDiff>,
+    /// Hashed key to be passed as `after` to request the next page; `None` if the page is the last one.
+    pub next_after: Option<H256>,
+}
+
+/// Incremental state snapshot in the object store, returned by `zks_getStateSnapshotDiff`. The diff contains
+/// the state changes made by the L1 batches `base_l1_batch_number..l1_batch_number`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateSnapshotDiffInfo {
+    pub base_l1_batch_number: L1BatchNumber,
+    /// Number of the next L1 batch to be processed after the diff is applied.
+    pub l1_batch_number: L1BatchNumber,
+    /// First miniblock of `l1_batch_number`.
+    pub first_miniblock_number: MiniblockNumber,
+    /// Root hash of the last L1 batch of the diff (`l1_batch_number - 1`).
+    pub root_hash: H256,
+    /// Key of the diff file in the `state_snapshot_diffs` bucket.
+    pub object_key: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchL1EventKind {
+    Commit,
+    Prove,
+    Execute,
+}
+
+impl L1BatchL1EventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Commit => "commit",
+            Self::Prove => "prove",
+            Self::Execute => "execute",
+        }
+    }
+}
+
+impl FromStr for L1BatchL1EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "commit" => Ok(Self::Commit),
+            "prove" => Ok(Self::Prove),
+            "execute" => Ok(Self::Execute),
+            other => Err(format!("unknown L1 batch event: {}", other)),
+        }
+    }
+}
+
+/// Event emitted by the main L1 contract for an L1 batch, returned by `zks_getL1BatchL1Events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchL1Event {
+    pub l1_batch_number: L1BatchNumber,
+    pub kind: L1BatchL1EventKind,
+    pub l1_tx_hash: H256,
+    pub l1_block_number: u64,
+    /// Batch hash reported by L1, `None` for `prove` events, which only report the range of the proven batches.
+    pub block_hash: Option<H256>,
+    pub commitment: Option<H256>,
+}
+
+/// Result of `zks_validateTransaction`: outcome of the account and paymaster validation of a signed
+/// transaction. The transaction is neither executed nor submitted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionValidationResult {
+    pub success: bool,
+    /// Revert reason returned by the account or the paymaster.
+    pub revert_reason: Option<String>,
+    /// Validation rule violated by the account, e.g. an access to a storage slot of another contract.
+    pub violated_rule: Option<String>,
+    /// Gas used by the bootloader up to the end of the validation step.
+    pub gas_used: U256,
+    pub computational_gas_used: U256,
+    pub computational_gas_limit: U256,
+    /// Context returned by the paymaster, `None` if the transaction doesn't use a paymaster.
+    pub paymaster_context: Option<Bytes>,
+}
+
+/// Result of `eth_createAccessList`: storage slots accessed by the call and the gas
+/// that the transaction is estimated to use. The list is informational: the node doesn't
+/// prefetch the storage slots of the transactions submitted with an access list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListWithGasUsed {
+    pub access_list: AccessList,
+    pub gas_used: U256,
+}
+
+/// Queries that the node is able to serve, returned by `zks_getNodeCapabilities`.
+/// Used by load balancers to route historical queries only to the nodes that have the data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeCapabilities {
+    pub personality: NodePersonality,
+    /// Earliest miniblock available on the node.
+    pub earliest_block: MiniblockNumber,
+    /// Latest sealed miniblock.
+    pub latest_block: MiniblockNumber,
+    /// Whether queries for blocks other than the latest one (state, logs, receipts) are served.
+    pub historical_queries: bool,
+}
+
+impl NodeCapabilities {
+    pub fn new(
+        personality: NodePersonality,
+        earliest_block: MiniblockNumber,
+        latest_block: MiniblockNumber,
+    ) -> Self {
+        Self {
+            personality,
+            earliest_block,
+            latest_block,
+            historical_queries: personality.serves_historical_queries(),
+        }
+    }
+}
+
+/// Utilization of the bootloader resources limiting the number of transactions in an L1 batch,
+/// returned by `admin_getBootloaderUsageReport`. Each value is accompanied by its limit, so that
+/// operators can tune the seal criteria.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootloaderUsageReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub tx_count: u32,
+    /// Transaction slots configured for the state keeper.
+    pub tx_slots: u32,
+    /// Words of the bootloader memory occupied by the transaction encodings.
+    pub txs_encoding_words: u32,
+    pub txs_encoding_limit: u32,
+    /// Words of the bootloader memory occupied by the compressed bytecodes.
+    pub compressed_bytecodes_words: u32,
+    pub compressed_bytecodes_limit: u32,
+    /// L2 gas spent by the transactions of the batch.
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    /// Pubdata published by the batch, including the deduplicated storage writes.
+    pub pubdata_bytes: u32,
+    /// Largest pubdata of a single transaction, excluding the storage writes, which are deduplicated
+    /// across the batch.
+    pub max_tx_pubdata_bytes: u32,
+    pub pubdata_limit: u32,
+}
+
+/// Signatures of the admin keys authorizing a state-changing `admin` call, passed as its last parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAuthorization {
+    /// UNIX timestamp (in seconds) after which the authorization is rejected.
+    pub deadline: u64,
+    /// `personal_sign` signatures of [`Self::message()`] by the distinct admin keys.
+    pub signatures: Vec<PackedEthSignature>,
+}
+
+impl AdminAuthorization {
+    /// Message signed by the admin keys. `action` is the method name without the namespace, e.g.
+    /// `addDeployerToAllowlist`, and `params` is the JSON array of the call parameters preceding the authorization.
+    /// The message is bound to the L2 chain and the node, so that an authorization can't be replayed elsewhere.
+    pub fn message(
+        chain_id: L2ChainId,
+        node_id: &str,
+        action: &str,
+        params: &serde_json::Value,
+        deadline: u64,
+    ) -> String {
+        format!(
+            "zkSync admin action: {}\nChain ID: {}\nNode: {}\nParams: {}\nDeadline: {}",
+            action, chain_id, node_id, params, deadline
+        )
+    }
+
+    /// Hash of the message the signers are recovered from, with the EIP-191 prefix added by `personal_sign`.
+    pub fn message_hash(message: &str) -> H256 {
+        let prefixed_message =
+            format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message);
+        PackedEthSignature::message_to_signed_bytes(prefixed_message.as_bytes())
+    }
+}
+
+/// Position of a transaction in the mempool, used as a pagination cursor by `zks_getPendingTransactions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTxCursor {
+    pub received_at: DateTime<Utc>,
+    pub hash: H256,
+}
+
+/// L2 transaction not yet included into a miniblock, returned by `zks_getPendingTransactions`.
+/// The fields redacted by the node operator are `null`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransaction {
+    pub hash: H256,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub nonce: u32,
+    pub value: Option<U256>,
+    pub input: Option<Bytes>,
+    pub gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub gas_per_pubdata_limit: U256,
+    pub received_at: DateTime<Utc>,
+}
+
+/// Page of the pending transactions in the order they were received, returned by `zks_getPendingTransactions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionsPage {
+    pub transactions: Vec<PendingTransaction>,
+    /// Cursor to be passed as `after` to request the next page; `None` if the page is the last one.
+    pub next_after: Option<PendingTxCursor>,
+}
+
+/// Identity of a node exchanged via `en_handshake` between the main node and an external node,
+/// so that the external node doesn't try to sync from an incompatible main node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeIdentity {
+    pub chain_id: L2ChainId,
+    /// Root hash of the genesis L1 batch.
+    pub genesis_root_hash: H256,
+    /// Range of the sync protocol versions supported by the node, both ends inclusive.
+    pub min_protocol_version: u16,
+    pub max_protocol_version: u16,
+    /// Version of the node software, only used for diagnostics.
+    pub software_version: String,
+}
+
+/// Data that would be reverted by rolling the node back to an L1 batch, returned by `admin_getBlockRevertPlan`.
+/// Zero counts (or RocksDB instances not ahead of the L1 batch) mean that the corresponding stage
+/// has nothing to revert, e.g. because it was completed by a previous interrupted revert.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRevertPlan {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    pub last_miniblock_to_keep: MiniblockNumber,
+    /// Eth txs removed from the eth sender. Includes the txs for the kept L1 batches created after
+    /// the first removed one, so that the eth sender nonces stay contiguous.
+    pub eth_txs_to_remove: usize,
+    /// L1 batches unlinked from their commit / prove / execute eth txs.
+    pub l1_batches_to_unlink: usize,
+    /// Next L1 batch to be processed by the full tree; `None` if the tree isn't present on the machine.
+    pub tree_l1_batch_number: Option<L1BatchNumber>,
+    pub lightweight_tree_l1_batch_number: Option<L1BatchNumber>,
+    pub state_keeper_cache_l1_batch_number: Option<L1BatchNumber>,
+    pub l1_batches_to_remove: u32,
+    pub miniblocks_to_remove: u32,
+    /// Transactions returned to the mempool.
+    pub transactions_to_reset: usize,
+    /// Backups of the RocksDB instances taken after the L1 batch.
+    pub rocksdb_backups_to_remove: usize,
+    /// State snapshot diffs covering the reverted L1 batches.
+    pub state_snapshot_diffs_to_remove: usize,
+}
+
+/// Reason a transaction waiting in the mempool can't be included into a block right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxPoolStuckReason {
+    /// A transaction of the same account with a lower nonce is missing.
+    NonceGap,
+    /// `max_fee_per_gas` is below the current base fee.
+    InsufficientFee,
+    /// `gas_per_pubdata_limit` is below the current price of pubdata.
+    InsufficientGasPerPubdata,
+    /// The transaction has crashed the state keeper and awaits a review by the operator.
+    Quarantined,
+}
+
+/// Transaction waiting in the mempool, returned by `txpool_content`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPoolTransaction {
+    pub hash: H256,
+    pub nonce: u32,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub gas_per_pubdata_limit: U256,
+    pub received_at: DateTime<Utc>,
+    /// Empty for the executable transactions and for the ones only waiting for the earlier transactions
+    /// of the account.
+    pub stuck_reasons: Vec<TxPoolStuckReason>,
+}
+
+/// Transactions of the mempool grouped by initiator and nonce, returned by `txpool_content`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPoolContent {
+    /// Transactions that can be executed in the order of nonces.
+    pub pending: BTreeMap<Address, BTreeMap<u32, TxPoolTransaction>>,
+    /// Transactions that are stuck or follow a stuck transaction of the same account.
+    pub queued: BTreeMap<Address, BTreeMap<u32, TxPoolTransaction>>,
+    /// Whether the transactions of some accounts are left out because of the response size limit.
+    /// The transactions of an account are never split.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Number of transactions in the mempool, returned by `txpool_status`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPoolStatus {
+    pub pending: usize,
+    pub queued: usize,
+}
+
+/// Components of the estimated gas limit. The sum of the components is equal to the gas limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeBreakdown {
+    /// Gas for the execution of the transaction, including the pubdata of the storage writes.
+    pub execution_gas: U256,
+    /// Gas for publishing the bytecodes of the factory dependencies.
+    pub pubdata_gas: U256,
+    /// Share of the batch overhead charged to the transaction.
+    pub overhead_gas: U256,
+}
+
+/// Result of `zks_estimateFee`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    /// Fields of the fee are kept at the top level for compatibility with the clients expecting `Fee`.
+    #[serde(flatten)]
+    pub fee: Fee,
+    pub breakdown: FeeBreakdown,
+}
+
+/// Result of `eth_feeHistory`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    /// Number of the first miniblock in the returned range.
+    pub oldest_block: U64,
+    /// Base fee per gas of each miniblock in the range, followed by the base fee of the next miniblock.
+    /// The base fee isn't derived from the previous miniblocks in zkSync, so the last known base fee
+    /// is reported for the next miniblock.
+    pub base_fee_per_gas: Vec<U256>,
+    /// Ratio of the gas used by each miniblock to the block gas limit.
+    pub gas_used_ratio: Vec<f64>,
+    /// Priority fees at the requested percentiles for each miniblock; omitted if no percentiles are requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+}
+
+/// Kind of the conflict between two transactions accessing the same storage slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyKind {
+    /// The transaction reads a slot written by the earlier one.
+    ReadAfterWrite,
+    /// The transaction overwrites a slot written by the earlier one.
+    WriteAfterWrite,
+    /// The transaction writes a slot read by the earlier one.
+    WriteAfterRead,
+}
+
+/// Edge of the dependency graph: the transaction can't be reordered with the earlier one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxDependency {
+    pub tx_index: u32,
+    pub depends_on: u32,
+    pub kinds: Vec<DependencyKind>,
+    /// Number of the storage slots the transactions conflict on.
+    pub conflicting_slots: usize,
+}
+
+/// Result of `zks_getBlockDependencyGraph`: the conflicts between the transactions of the miniblock
+/// derived from the storage slots they accessed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockDependencyGraph {
+    pub number: MiniblockNumber,
+    /// Hashes of the transactions in the execution order.
+    pub transactions: Vec<H256>,
+    pub dependencies: Vec<TxDependency>,
+}
+
+impl BlockDependencyGraph {
+    /// Builds the graph from the access sets of the transactions sorted in the execution order.
+    pub fn new(number: MiniblockNumber, access_sets: &[TxAccessSet]) -> Self {
+        let sets: Vec<_> = access_sets
+            .iter()
+            .map(|set| {
+                let reads: HashSet<_> = set.read_keys.iter().collect();
+                let writes: HashSet<_> = set.written_keys.iter().collect();
+                (reads, writes)
+            })
+            .collect();
+
+        let mut dependencies = vec![];
+        for (later, (later_reads, later_writes)) in sets.iter().enumerate() {
+            for (earlier, (earlier_reads, earlier_writes)) in sets[..later].iter().enumerate() {
+                let conflicts = [
+                    (DependencyKind::ReadAfterWrite, earlier_writes, later_reads),
+                    (
+                        DependencyKind::WriteAfterWrite,
+                        earlier_writes,
+                        later_writes,
+                    ),
+                    (DependencyKind::WriteAfterRead, earlier_reads, later_writes),
+                ];
+                let mut kinds = vec![];
+                let mut conflicting_slots = HashSet::new();
+                for (kind, earlier_keys, later_keys) in conflicts {
+                    let shared: Vec<_> = earlier_keys.intersection(later_keys).copied().collect();
+                    if !shared.is_empty() {
+                        kinds.push(kind);
+                        conflicting_slots.extend(shared);
+                    }
+                }
+
+                if !kinds.is_empty() {
+                    dependencies.push(TxDependency {
+                        tx_index: access_sets[later].tx_index_in_miniblock,
+                        depends_on: access_sets[earlier].tx_index_in_miniblock,
+                        kinds,
+                        conflicting_slots: conflicting_slots.len(),
+                    });
+                }
+            }
+        }
+
+        Self {
+            number,
+            transactions: access_sets.iter().map(|set| set.tx_hash).collect(),
+            dependencies,
+        }
+    }
+}
+
+/// Position of a log in the chain, used as a pagination cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogCursor {
+    pub block_number: MiniblockNumber,
+    /// Index of the log in the miniblock.
+    pub log_index: u32,
+    /// Last miniblock of the filter range resolved for the first page. Later pages don't go past it,
+    /// so the logs of the miniblocks sealed mid-pagination don't end up in the results if the range
+    /// is open-ended (e.g., `toBlock` is `latest`). Cursors without the watermark are still accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watermark: Option<MiniblockNumber>,
+}
+
+/// Page of the logs matching a filter, oldest first, returned by `zks_getLogsPaginated`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsPage {
+    pub logs: Vec<Log>,
+    /// Cursor to be passed as `after` to request the next page; `None` if the page is the last one.
+    pub next_after: Option<LogCursor>,
+}
+
+/// System context values the VM executed a miniblock with, returned by `zks_getSystemContext`.
+/// The context is set once per L1 batch, so contracts observe the batch number and timestamp
+/// as `block.number` and `block.timestamp` in every miniblock of the batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemContext {
+    pub miniblock_number: MiniblockNumber,
+    pub miniblock_timestamp: u64,
+    pub miniblock_hash: H256,
+    /// For a miniblock of the pending batch, the number the batch will be sealed with.
+    pub l1_batch_number: L1BatchNumber,
+    pub l1_batch_timestamp: u64,
+    /// Whether the L1 batch is sealed; the values of a pending batch come from its miniblocks.
+    pub l1_batch_sealed: bool,
+    /// `None` for the pending batch if its miniblocks were sealed before the fee account was recorded.
+    pub operator_address: Option<Address>,
+    pub l1_gas_price: u64,
+    pub fair_l2_gas_price: u64,
+    pub base_fee_per_gas: U256,
+    /// `None` for the miniblocks sealed before the value was recorded, as well as `tx_rolling_hash`.
+    pub gas_per_pubdata: Option<u64>,
+    pub bootloader_code_hash: Option<H256>,
+    pub default_aa_code_hash: Option<H256>,
+    /// Rolling hash of the transactions executed in the miniblock.
+    pub tx_rolling_hash: Option<H256>,
+}
+
+/// Relation of the transactions to the account requested in `zks_getTransactionsByAddress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountTxDirection {
+    /// Transactions initiated by the account.
+    Sent,
+    /// Transactions calling the account.
+    Received,
+    All,
+}
+
+/// Position of a transaction in the chain, used as a pagination cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxCursor {
+    pub block_number: MiniblockNumber,
+    pub index_in_block: u32,
+}
+
+/// Page of the transactions of an account, newest first, returned by `zks_getTransactionsByAddress`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTransactionsPage {
+    pub transactions: Vec<Transaction>,
+    /// Cursor to be passed as `before` to request the next page; `None` if the page is the last one.
+    pub next_before: Option<TxCursor>,
+}
+
+/// ETH or ERC-20 token transfer, returned by `zks_getTransfers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    /// Token contract; `L2_ETH_TOKEN_ADDRESS` for ETH transfers.
+    pub token_address: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+    /// Hash of the transaction that emitted the transfer; zero for the transfers emitted by the bootloader
+    /// after the last transaction of the L1 batch (e.g., the operator fee payment).
+    pub transaction_hash: H256,
+    pub block_number: MiniblockNumber,
+    /// Index of the `Transfer` event among the events of the miniblock.
+    pub log_index: u32,
+}
+
+/// Position of a transfer in the chain, used as a pagination cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferCursor {
+    pub block_number: MiniblockNumber,
+    pub log_index: u32,
+}
+
+/// Page of the transfers from or to an account, newest first, returned by `zks_getTransfers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransfersPage {
+    pub transfers: Vec<Transfer>,
+    /// Cursor to be passed as `before` to request the next page; `None` if the page is the last one.
+    pub next_before: Option<TransferCursor>,
 }
 
 #[derive(Debug, Clone)]
@@ -491,3 +1370,194 @@ pub struct GetLogsFilter {
     pub addresses: Vec<Address>,
     pub topics: Vec<(u32, Vec<H256>)>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finality_from_watermarks() {
+        let watermarks = L1BatchFinalityWatermarks {
+            last_committed: L1BatchNumber(5),
+            last_proven: L1BatchNumber(3),
+            last_executed: L1BatchNumber(1),
+        };
+        let finality = |is_sealed, number: Option<u32>| {
+            watermarks.finality(is_sealed, number.map(L1BatchNumber))
+        };
+
+        assert_eq!(finality(false, None), Finality::Pending);
+        assert_eq!(finality(true, None), Finality::L2Sealed);
+        assert_eq!(finality(true, Some(6)), Finality::L2Sealed);
+        assert_eq!(finality(true, Some(5)), Finality::Committed);
+        assert_eq!(finality(true, Some(3)), Finality::Proven);
+        assert_eq!(finality(true, Some(1)), Finality::Executed);
+        assert_eq!(
+            serde_json::to_value(Finality::L2Sealed).unwrap(),
+            serde_json::json!("l2-sealed")
+        );
+    }
+
+    #[test]
+    fn fee_estimate_is_compatible_with_fee() {
+        let estimate = FeeEstimate {
+            fee: Fee {
+                gas_limit: 1_000.into(),
+                max_fee_per_gas: 250_000_000.into(),
+                max_priority_fee_per_gas: 0.into(),
+                gas_per_pubdata_limit: 17_000.into(),
+            },
+            breakdown: FeeBreakdown {
+                execution_gas: 700.into(),
+                pubdata_gas: 200.into(),
+                overhead_gas: 100.into(),
+            },
+        };
+        let json = serde_json::to_value(&estimate).unwrap();
+        assert_eq!(json["gas_limit"], serde_json::json!("0x3e8"));
+        assert_eq!(json["breakdown"]["pubdataGas"], serde_json::json!("0xc8"));
+
+        let fee: Fee = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(fee, estimate.fee);
+        let parsed: FeeEstimate = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, estimate);
+    }
+
+    #[test]
+    fn dependency_graph_from_access_sets() {
+        let key = H256::repeat_byte;
+        let access_set = |index: u32, read_keys: Vec<H256>, written_keys: Vec<H256>| TxAccessSet {
+            tx_hash: H256::from_low_u64_be(index.into()),
+            tx_index_in_miniblock: index,
+            read_keys,
+            written_keys,
+        };
+        let access_sets = [
+            access_set(0, vec![key(1)], vec![key(2)]),
+            access_set(1, vec![key(2)], vec![key(3)]),
+            access_set(2, vec![key(4)], vec![key(1), key(3)]),
+            access_set(3, vec![key(5)], vec![key(6)]),
+        ];
+
+        let graph = BlockDependencyGraph::new(MiniblockNumber(1), &access_sets);
+        assert_eq!(graph.transactions.len(), 4);
+        assert_eq!(
+            graph.dependencies,
+            [
+                TxDependency {
+                    tx_index: 1,
+                    depends_on: 0,
+                    kinds: vec![DependencyKind::ReadAfterWrite],
+                    conflicting_slots: 1,
+                },
+                TxDependency {
+                    tx_index: 2,
+                    depends_on: 0,
+                    kinds: vec![DependencyKind::WriteAfterRead],
+                    conflicting_slots: 1,
+                },
+                TxDependency {
+                    tx_index: 2,
+                    depends_on: 1,
+                    kinds: vec![DependencyKind::WriteAfterWrite],
+                    conflicting_slots: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn admin_authorization_signers() {
+        let params = serde_json::json!([Address::repeat_byte(1)]);
+        let message = AdminAuthorization::message(
+            L2ChainId(270),
+            "main-node-0",
+            "addDeployerToAllowlist",
+            &params,
+            1_000,
+        );
+        assert_eq!(
+            message,
+            "zkSync admin action: addDeployerToAllowlist\n\
+             Chain ID: 270\n\
+             Node: main-node-0\n\
+             Params: [\"0x0101010101010101010101010101010101010101\"]\n\
+             Deadline: 1000"
+        );
+
+        let private_key = H256::repeat_byte(0x11);
+        let message_hash = AdminAuthorization::message_hash(&message);
+        let signature = PackedEthSignature::sign_raw(&private_key, &message_hash).unwrap();
+        let authorization = AdminAuthorization {
+            deadline: 1_000,
+            signatures: vec![signature],
+        };
+        let authorization: AdminAuthorization =
+            serde_json::from_value(serde_json::to_value(&authorization).unwrap()).unwrap();
+        let signer = authorization.signatures[0]
+            .signature_recover_signer(&message_hash)
+            .unwrap();
+        assert_eq!(
+            signer,
+            PackedEthSignature::address_from_private_key(&private_key).unwrap()
+        );
+        let other_hash = AdminAuthorization::message_hash(&message.replace("1000", "1001"));
+        let other_signer = authorization.signatures[0]
+            .signature_recover_signer(&other_hash)
+            .unwrap();
+        assert_ne!(other_signer, signer);
+
+        for other_message in [
+            message.replace("Chain ID: 270", "Chain ID: 280"),
+            message.replace("main-node-0", "main-node-1"),
+        ] {
+            let other_hash = AdminAuthorization::message_hash(&other_message);
+            let other_signer = authorization.signatures[0]
+                .signature_recover_signer(&other_hash)
+                .unwrap();
+            assert_ne!(other_signer, signer);
+        }
+    }
+
+    #[test]
+    fn block_extensions_serialization() {
+        let mut block = Block::<H256> {
+            number: 5.into(),
+            extensions: Some(BlockExtensions {
+                l1_batch_number: Some(2.into()),
+                l1_batch_timestamp: Some(100.into()),
+                seal_criterion: Some("slots".to_owned()),
+                operator_address: Some(Address::repeat_byte(0x01)),
+            }),
+            ..Block::default()
+        };
+        let value = serde_json::to_value(&block).unwrap();
+        assert_eq!(value["l1BatchNumber"], "0x2");
+        assert_eq!(value["l1BatchTimestamp"], "0x64");
+        assert_eq!(value["sealCriterion"], "slots");
+        assert_eq!(
+            value["operatorAddress"],
+            "0x0101010101010101010101010101010101010101"
+        );
+        let restored: Block<H256> = serde_json::from_value(value).unwrap();
+        assert_eq!(restored, block);
+
+        // Unknown extension fields are returned as `null`.
+        block.extensions = Some(BlockExtensions::default());
+        let value = serde_json::to_value(&block).unwrap();
+        for field in BlockExtensions::FIELDS {
+            assert_eq!(value[field], serde_json::Value::Null, "{}", field);
+        }
+        let restored: Block<H256> = serde_json::from_value(value).unwrap();
+        assert_eq!(restored, block);
+
+        block.extensions = None;
+        let value = serde_json::to_value(&block).unwrap();
+        let fields = value.as_object().unwrap();
+        for field in BlockExtensions::FIELDS {
+            assert!(!fields.contains_key(field), "{}", field);
+        }
+        let restored: Block<H256> = serde_json::from_value(value).unwrap();
+        assert_eq!(restored, block);
+    }
+}
@@ -75,6 +75,9 @@ pub struct MiniblockHeader {
     pub l1_gas_price: u64, // L1 gas price assumed in the corresponding batch
     pub l2_fair_gas_price: u64, // L2 gas price assumed in the corresponding batch
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
+    pub logs_bloom: H2048,
+    /// Fee account (aka operator address) the miniblock was executed with.
+    pub fee_account_address: Address,
 }
 
 impl L1BatchHeader {
@@ -106,16 +109,11 @@ impl L1BatchHeader {
 
     /// Creates a hash of the priority ops data.
     pub fn priority_ops_onchain_data_hash(&self) -> H256 {
-        let mut rolling_hash: H256 = keccak256(&[]).into();
-        for onchain_data in &self.priority_ops_onchain_data {
-            let mut preimage = Vec::new();
-            preimage.extend(rolling_hash.as_bytes());
-            preimage.extend(onchain_data.onchain_data_hash.as_bytes());
-
-            rolling_hash = keccak256(&preimage).into();
-        }
-
-        rolling_hash
+        priority_ops_rolling_hash(
+            self.priority_ops_onchain_data
+                .iter()
+                .map(|onchain_data| onchain_data.onchain_data_hash),
+        )
     }
 
     pub fn tx_count(&self) -> usize {
@@ -123,6 +121,36 @@ impl L1BatchHeader {
     }
 }
 
+/// Rolling hash of the priority operations with the given onchain data hashes (i.e., the L1 transaction hashes),
+/// in the same form as `priorityOperationsHash` maintained by the L1 contract.
+pub fn priority_ops_rolling_hash(onchain_data_hashes: impl IntoIterator<Item = H256>) -> H256 {
+    let mut rolling_hash: H256 = keccak256(&[]).into();
+    for onchain_data_hash in onchain_data_hashes {
+        let mut preimage = Vec::new();
+        preimage.extend(rolling_hash.as_bytes());
+        preimage.extend(onchain_data_hash.as_bytes());
+
+        rolling_hash = keccak256(&preimage).into();
+    }
+
+    rolling_hash
+}
+
+/// Rolling hash of the transactions executed in a miniblock, starting from zero for each miniblock
+/// (`txRollingHash` of the system context).
+pub fn txs_rolling_hash(tx_hashes: impl IntoIterator<Item = H256>) -> H256 {
+    let mut rolling_hash = H256::zero();
+    for tx_hash in tx_hashes {
+        let mut preimage = Vec::new();
+        preimage.extend(rolling_hash.as_bytes());
+        preimage.extend(tx_hash.as_bytes());
+
+        rolling_hash = keccak256(&preimage).into();
+    }
+
+    rolling_hash
+}
+
 /// Utility structure that holds the block header together with its logs required to generate the witness
 #[derive(Debug)]
 pub struct WitnessBlockWithLogs {
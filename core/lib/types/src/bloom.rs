@@ -0,0 +1,92 @@
+//! Logs bloom filters as defined in the Ethereum yellow paper (section 4.3.1).
+//! Each log contributes its emitter address and all of its topics to the bloom.
+
+use crate::{web3::signing::keccak256, VmEvent, H2048};
+
+/// Size of the bloom filter in bits.
+pub const BLOOM_BITS: usize = 2048;
+
+/// Returns the indices of the bits set by the input in a bloom filter.
+/// The indices are counted from the least significant bit of the big-endian 2048-bit value.
+pub fn bloom_bit_indices(input: &[u8]) -> [usize; 3] {
+    let hash = keccak256(input);
+    let mut indices = [0; 3];
+    for (i, index) in indices.iter_mut().enumerate() {
+        *index = ((hash[2 * i] as usize) << 8 | hash[2 * i + 1] as usize) % BLOOM_BITS;
+    }
+    indices
+}
+
+/// Returns the byte index in the big-endian representation of the bloom and the mask of the bit
+/// within this byte for the given bit index.
+pub fn bloom_byte_and_mask(bit_index: usize) -> (usize, u8) {
+    (BLOOM_BITS / 8 - 1 - bit_index / 8, 1 << (bit_index % 8))
+}
+
+/// Adds the input to the bloom filter.
+pub fn accrue_bloom(bloom: &mut H2048, input: &[u8]) {
+    for bit_index in bloom_bit_indices(input) {
+        let (byte, mask) = bloom_byte_and_mask(bit_index);
+        bloom.0[byte] |= mask;
+    }
+}
+
+/// Checks whether the input may have been added to the bloom filter.
+/// `false` means that the input was definitely not added.
+pub fn bloom_contains(bloom: &H2048, input: &[u8]) -> bool {
+    bloom_bit_indices(input).iter().all(|&bit_index| {
+        let (byte, mask) = bloom_byte_and_mask(bit_index);
+        bloom.0[byte] & mask != 0
+    })
+}
+
+/// Computes the bloom filter for the given events.
+pub fn logs_bloom<'a>(events: impl IntoIterator<Item = &'a VmEvent>) -> H2048 {
+    let mut bloom = H2048::zero();
+    for event in events {
+        accrue_bloom(&mut bloom, event.address.as_bytes());
+        for topic in &event.indexed_topics {
+            accrue_bloom(&mut bloom, topic.as_bytes());
+        }
+    }
+    bloom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Address, L1BatchNumber, H256};
+
+    #[test]
+    fn accrue_sets_at_most_three_bits() {
+        let address = Address::from_low_u64_be(1);
+        let mut bloom = H2048::zero();
+        accrue_bloom(&mut bloom, address.as_bytes());
+
+        let bits_set: u32 = bloom.0.iter().map(|byte| byte.count_ones()).sum();
+        assert!((1..=3).contains(&bits_set));
+        assert!(bloom_contains(&bloom, address.as_bytes()));
+    }
+
+    #[test]
+    fn logs_bloom_contains_addresses_and_topics() {
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(0x11),
+            indexed_topics: vec![H256::repeat_byte(0x22), H256::repeat_byte(0x33)],
+            value: vec![],
+        };
+        let bloom = logs_bloom([&event]);
+
+        assert!(bloom_contains(&bloom, event.address.as_bytes()));
+        for topic in &event.indexed_topics {
+            assert!(bloom_contains(&bloom, topic.as_bytes()));
+        }
+        assert!(!bloom_contains(
+            &bloom,
+            Address::repeat_byte(0x44).as_bytes()
+        ));
+        assert!(!bloom_contains(&bloom, H256::repeat_byte(0x55).as_bytes()));
+        assert!(!bloom_contains(&H2048::zero(), event.address.as_bytes()));
+    }
+}
@@ -0,0 +1,91 @@
+//! Chain-wide statistics aggregated incrementally by the house keeper.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{MiniblockNumber, U256};
+
+/// Cumulative statistics of the chain up to (and including) a miniblock.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainStatsCheckpoint {
+    pub miniblock_number: MiniblockNumber,
+    pub timestamp: u64,
+    pub total_transactions: u64,
+    /// Number of accounts that have sent at least one L2 transaction.
+    pub total_accounts: u64,
+    pub contracts_deployed: u64,
+    /// Total fees paid by the L2 transactions, in wei.
+    pub total_fees: U256,
+}
+
+/// Statistics of a range of miniblocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainStatsDelta {
+    pub transactions: u64,
+    pub new_accounts: u64,
+    pub contracts_deployed: u64,
+    pub fees: U256,
+}
+
+impl ChainStatsCheckpoint {
+    /// Returns the checkpoint obtained by applying the stats of the miniblocks after this checkpoint
+    /// up to `miniblock_number`.
+    pub fn advance(
+        &self,
+        miniblock_number: MiniblockNumber,
+        timestamp: u64,
+        delta: &ChainStatsDelta,
+    ) -> Self {
+        Self {
+            miniblock_number,
+            timestamp,
+            total_transactions: self.total_transactions + delta.transactions,
+            total_accounts: self.total_accounts + delta.new_accounts,
+            contracts_deployed: self.contracts_deployed + delta.contracts_deployed,
+            total_fees: self.total_fees + delta.fees,
+        }
+    }
+
+    /// Average number of transactions per second between an earlier checkpoint and this one.
+    pub fn tps_since(&self, earlier: &Self) -> f64 {
+        let elapsed_sec = self.timestamp.saturating_sub(earlier.timestamp);
+        if elapsed_sec == 0 {
+            return 0.0;
+        }
+        let transactions = self
+            .total_transactions
+            .saturating_sub(earlier.total_transactions);
+        transactions as f64 / elapsed_sec as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_is_advanced_by_delta() {
+        let checkpoint = ChainStatsCheckpoint {
+            miniblock_number: MiniblockNumber(10),
+            timestamp: 100,
+            total_transactions: 50,
+            total_accounts: 5,
+            contracts_deployed: 2,
+            total_fees: 1000.into(),
+        };
+        let delta = ChainStatsDelta {
+            transactions: 30,
+            new_accounts: 1,
+            contracts_deployed: 1,
+            fees: 500.into(),
+        };
+
+        let next = checkpoint.advance(MiniblockNumber(20), 110, &delta);
+        assert_eq!(next.miniblock_number, MiniblockNumber(20));
+        assert_eq!(next.total_transactions, 80);
+        assert_eq!(next.total_accounts, 6);
+        assert_eq!(next.contracts_deployed, 3);
+        assert_eq!(next.total_fees, 1500.into());
+        assert_eq!(next.tps_since(&checkpoint), 3.0);
+        assert_eq!(checkpoint.tps_since(&checkpoint), 0.0);
+    }
+}
@@ -1,6 +1,8 @@
 use zkevm_test_harness::geometry_config::get_geometry_config;
 use zkevm_test_harness::toolset::GeometryConfig;
 
+use crate::tx::ExecutionMetrics;
+
 pub const LEAF_SPLITTING_FACTOR: usize = 50;
 pub const NODE_SPLITTING_FACTOR: usize = 48;
 pub const SCHEDULER_UPPER_BOUND: u32 = (LEAF_SPLITTING_FACTOR * NODE_SPLITTING_FACTOR) as u32;
@@ -9,3 +11,28 @@ pub const LEAF_CIRCUIT_INDEX: u8 = 2;
 pub const NODE_CIRCUIT_INDEX: u8 = 1;
 
 pub const GEOMETRY_CONFIG: GeometryConfig = get_geometry_config();
+
+/// Rough estimation of the number of basic circuits an L1 batch with the given execution metrics
+/// will be split into. Only the circuits whose count grows with the batch are taken into account,
+/// so the result is meant for sizing witness generation jobs rather than for anything precise.
+pub fn estimate_basic_circuits_count(metrics: &ExecutionMetrics) -> usize {
+    let circuits_for = |count: usize, limit: u32| -> usize {
+        let limit = limit as usize;
+        ((count + limit - 1) / limit).max(1)
+    };
+
+    circuits_for(
+        metrics.cycles_used as usize,
+        GEOMETRY_CONFIG.cycles_per_vm_snapshot,
+    ) + circuits_for(
+        metrics.total_log_queries,
+        GEOMETRY_CONFIG.limit_for_log_demuxer,
+    ) + circuits_for(
+        metrics.storage_logs,
+        GEOMETRY_CONFIG.limit_for_storage_sorter,
+    ) + circuits_for(metrics.vm_events, GEOMETRY_CONFIG.limit_for_events_sorter)
+        + circuits_for(
+            metrics.contracts_used,
+            GEOMETRY_CONFIG.limit_for_code_decommitter_sorter,
+        )
+}
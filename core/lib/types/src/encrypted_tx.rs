@@ -0,0 +1,316 @@
+//! Encrypted transactions submitted to the experimental encrypted mempool.
+//!
+//! Users encrypt the raw transaction to the public key of the decryption committee with threshold ElGamal
+//! over secp256k1, so that the sequencer can't see the contents of the transaction before its position is fixed.
+//! The payload carries an ephemeral point `R = r * G`, and the raw transaction is encrypted with a keystream
+//! derived from the shared point `r * PK`.
+//!
+//! The committee secret key `x` is Shamir-shared between the committee members, so no single party holds it.
+//! Each member publishes its decryption share `x_i * R`, and any `threshold` shares are combined into
+//! `x * R = r * PK` with Lagrange interpolation in the exponent.
+
+use parity_crypto::publickey::{
+    ec_math_utils::{public_add, public_mul_secret},
+    Error as ParityCryptoError, Generator, Public, Random, Secret,
+};
+use thiserror::Error;
+use zksync_basic_types::H256;
+
+use crate::web3::signing::keccak256;
+
+/// Length of the serialized ephemeral point prefixing the payload.
+const POINT_LEN: usize = 64;
+/// Length of the authentication tag suffixing the payload.
+const MAC_LEN: usize = 32;
+/// Size of the payload in excess of the raw transaction.
+pub const ENCRYPTION_OVERHEAD: usize = POINT_LEN + MAC_LEN;
+
+/// Domain separators of the keystream and the authentication tag, so that payloads can't be reused
+/// in other protocols.
+const KEYSTREAM_DOMAIN: &[u8] = b"zksync-encrypted-tx-keystream";
+const MAC_DOMAIN: &[u8] = b"zksync-encrypted-tx-mac";
+
+#[derive(Debug, Error)]
+pub enum EncryptedTxError {
+    #[error("malformed payload")]
+    MalformedPayload,
+    #[error("{0} decryption shares provided, while {1} are required")]
+    NotEnoughShares(usize, usize),
+    #[error("decryption shares must have distinct non-zero member indices")]
+    InvalidShareIndices,
+    #[error("payload authentication failed")]
+    AuthenticationFailed,
+    #[error("{0}")]
+    Crypto(#[from] ParityCryptoError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedTransaction {
+    /// Keccak256 hash of the encrypted payload.
+    pub hash: H256,
+    pub payload: Vec<u8>,
+}
+
+/// Decryption share of a committee member for a certain payload, i.e. the ephemeral point of the payload
+/// multiplied by the key share of the member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecryptionShare {
+    /// Index of the member, starting from 1.
+    pub member_index: u32,
+    /// Serialized point (64 bytes).
+    pub point: Vec<u8>,
+}
+
+/// Hash identifying the encrypted transaction before it's decrypted.
+pub fn encrypted_tx_hash(payload: &[u8]) -> H256 {
+    keccak256(payload).into()
+}
+
+/// Encrypts the raw transaction to the committee public key.
+pub fn encrypt_transaction(
+    committee_public_key: &Public,
+    raw_tx: &[u8],
+) -> Result<Vec<u8>, EncryptedTxError> {
+    let ephemeral = Random.generate();
+    let mut shared_point = *committee_public_key;
+    public_mul_secret(&mut shared_point, ephemeral.secret())?;
+
+    let mut payload = ephemeral.public().as_bytes().to_vec();
+    payload.extend(apply_keystream(&shared_point, raw_tx));
+    let mac = payload_mac(&shared_point, &payload[POINT_LEN..]);
+    payload.extend_from_slice(&mac);
+    Ok(payload)
+}
+
+/// Checks that the payload is well-formed, so that the committee members can produce decryption shares for it.
+pub fn check_payload(payload: &[u8]) -> Result<(), EncryptedTxError> {
+    split_payload(payload).map(drop)
+}
+
+/// Produces the decryption share of the committee member for the payload.
+pub fn decryption_share(
+    member_index: u32,
+    key_share: &H256,
+    payload: &[u8],
+) -> Result<DecryptionShare, EncryptedTxError> {
+    let (mut point, _, _) = split_payload(payload)?;
+    public_mul_secret(&mut point, &Secret::from(*key_share))?;
+    Ok(DecryptionShare {
+        member_index,
+        point: point.as_bytes().to_vec(),
+    })
+}
+
+/// Combines the first `threshold` decryption shares and decrypts the payload, returning the raw transaction.
+pub fn combine_and_decrypt(
+    shares: &[DecryptionShare],
+    threshold: usize,
+    payload: &[u8],
+) -> Result<Vec<u8>, EncryptedTxError> {
+    if threshold == 0 || shares.len() < threshold {
+        return Err(EncryptedTxError::NotEnoughShares(
+            shares.len(),
+            threshold.max(1),
+        ));
+    }
+    let shares = &shares[..threshold];
+    let (_, ciphertext, mac) = split_payload(payload)?;
+
+    let shared_point = interpolate_at_zero(shares)?;
+    if payload_mac(&shared_point, ciphertext) != mac {
+        return Err(EncryptedTxError::AuthenticationFailed);
+    }
+    Ok(apply_keystream(&shared_point, ciphertext))
+}
+
+/// Splits the committee secret key into shares for `members` members (with indices starting from 1),
+/// any `threshold` of which can decrypt the payloads. The share of a member is the value of a random polynomial
+/// of degree `threshold - 1` with the secret key as the free coefficient at the member index.
+///
+/// # Panics
+///
+/// Panics if `threshold` is zero or exceeds `members`.
+pub fn split_committee_key(
+    secret_key: &H256,
+    threshold: usize,
+    members: usize,
+) -> Result<Vec<H256>, EncryptedTxError> {
+    assert!(
+        threshold > 0 && threshold <= members,
+        "Threshold must be within 1..={}",
+        members
+    );
+    let coefficients: Vec<_> = std::iter::once(Secret::from(*secret_key))
+        .chain((1..threshold).map(|_| Random.generate().secret().clone()))
+        .collect();
+
+    (1..=members as u32)
+        .map(|member_index| {
+            let x = scalar(member_index);
+            let mut coefficients = coefficients.iter().rev();
+            let mut value = coefficients.next().unwrap().clone();
+            for coefficient in coefficients {
+                value.mul(&x)?;
+                value.add(coefficient)?;
+            }
+            Ok(*value)
+        })
+        .collect()
+}
+
+fn split_payload(payload: &[u8]) -> Result<(Public, &[u8], &[u8]), EncryptedTxError> {
+    if payload.len() < ENCRYPTION_OVERHEAD {
+        return Err(EncryptedTxError::MalformedPayload);
+    }
+    let (point, rest) = payload.split_at(POINT_LEN);
+    let (ciphertext, mac) = rest.split_at(rest.len() - MAC_LEN);
+    let point = Public::from_slice(point);
+    // Multiplying by 1 checks that the point is on the curve.
+    let mut checked_point = point;
+    public_mul_secret(&mut checked_point, &scalar(1))
+        .map_err(|_| EncryptedTxError::MalformedPayload)?;
+    Ok((point, ciphertext, mac))
+}
+
+/// Computes `x * R` from the shares `x_i * R` as `sum(lambda_i * x_i * R)`, where `lambda_i` are
+/// the Lagrange coefficients of the share indices at zero.
+fn interpolate_at_zero(shares: &[DecryptionShare]) -> Result<Public, EncryptedTxError> {
+    let mut indices: Vec<_> = shares.iter().map(|share| share.member_index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    if indices.len() != shares.len() || indices[0] == 0 {
+        return Err(EncryptedTxError::InvalidShareIndices);
+    }
+
+    let mut combined: Option<Public> = None;
+    for share in shares {
+        if share.point.len() != POINT_LEN {
+            return Err(EncryptedTxError::MalformedPayload);
+        }
+        let mut term = Public::from_slice(&share.point);
+        public_mul_secret(
+            &mut term,
+            &lagrange_coefficient(share.member_index, &indices)?,
+        )?;
+        combined = Some(match combined {
+            None => term,
+            Some(mut sum) => {
+                public_add(&mut sum, &term)?;
+                sum
+            }
+        });
+    }
+    Ok(combined.expect("at least one share is required"))
+}
+
+/// Returns `lambda_i = prod(j / (j - i))` over the indices `j != i`.
+fn lagrange_coefficient(index: u32, indices: &[u32]) -> Result<Secret, ParityCryptoError> {
+    let mut numerator = scalar(1);
+    let mut denominator = scalar(1);
+    for &other in indices.iter().filter(|&&other| other != index) {
+        numerator.mul(&scalar(other))?;
+        let mut difference = scalar(other);
+        difference.sub(&scalar(index))?;
+        denominator.mul(&difference)?;
+    }
+    denominator.inv()?;
+    numerator.mul(&denominator)?;
+    Ok(numerator)
+}
+
+fn scalar(value: u32) -> Secret {
+    Secret::from(H256::from_low_u64_be(value as u64))
+}
+
+/// XORs the data with the keystream `keccak256(domain || shared point || block index)`.
+fn apply_keystream(shared_point: &Public, data: &[u8]) -> Vec<u8> {
+    data.chunks(32)
+        .enumerate()
+        .flat_map(|(block_index, chunk)| {
+            let block = keccak256(
+                &[
+                    KEYSTREAM_DOMAIN,
+                    shared_point.as_bytes(),
+                    &(block_index as u64).to_be_bytes()[..],
+                ]
+                .concat(),
+            );
+            chunk
+                .iter()
+                .zip(block)
+                .map(|(byte, key)| byte ^ key)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn payload_mac(shared_point: &Public, ciphertext: &[u8]) -> [u8; 32] {
+    keccak256(&[MAC_DOMAIN, shared_point.as_bytes(), ciphertext].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parity_crypto::publickey::KeyPair;
+
+    fn member_shares(key_shares: &[H256], members: &[u32], payload: &[u8]) -> Vec<DecryptionShare> {
+        members
+            .iter()
+            .map(|&member_index| {
+                let key_share = &key_shares[member_index as usize - 1];
+                decryption_share(member_index, key_share, payload).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn threshold_decryption_roundtrip() {
+        let committee_key = H256::repeat_byte(0x11);
+        let key_pair = KeyPair::from_secret(committee_key.into()).unwrap();
+        let key_shares = split_committee_key(&committee_key, 3, 5).unwrap();
+        let raw_tx = b"raw transaction bytes spanning more than a single keystream block".to_vec();
+
+        let payload = encrypt_transaction(key_pair.public(), &raw_tx).unwrap();
+        assert_eq!(payload.len(), raw_tx.len() + ENCRYPTION_OVERHEAD);
+        check_payload(&payload).unwrap();
+
+        // Any 3 out of 5 members can decrypt.
+        for members in [[1, 2, 3], [5, 3, 1], [2, 4, 5]] {
+            let shares = member_shares(&key_shares, &members, &payload);
+            assert_eq!(combine_and_decrypt(&shares, 3, &payload).unwrap(), raw_tx);
+        }
+
+        // 2 members can't.
+        let two_shares = member_shares(&key_shares, &[1, 2], &payload);
+        assert!(matches!(
+            combine_and_decrypt(&two_shares, 3, &payload),
+            Err(EncryptedTxError::NotEnoughShares(2, 3))
+        ));
+        assert!(matches!(
+            combine_and_decrypt(&two_shares, 2, &payload),
+            Err(EncryptedTxError::AuthenticationFailed)
+        ));
+
+        // A share produced with a wrong key share is detected.
+        let mut shares = member_shares(&key_shares, &[1, 2], &payload);
+        shares.push(decryption_share(3, &H256::repeat_byte(0x22), &payload).unwrap());
+        assert!(matches!(
+            combine_and_decrypt(&shares, 3, &payload),
+            Err(EncryptedTxError::AuthenticationFailed)
+        ));
+
+        // Duplicate members are rejected.
+        let duplicate_shares = member_shares(&key_shares, &[1, 1, 2], &payload);
+        assert!(matches!(
+            combine_and_decrypt(&duplicate_shares, 3, &payload),
+            Err(EncryptedTxError::InvalidShareIndices)
+        ));
+    }
+
+    #[test]
+    fn malformed_payloads_are_rejected() {
+        assert!(check_payload(&[0; ENCRYPTION_OVERHEAD - 1]).is_err());
+        // The ephemeral point is not on the curve.
+        assert!(check_payload(&[0; ENCRYPTION_OVERHEAD + 10]).is_err());
+    }
+}
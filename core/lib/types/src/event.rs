@@ -1,8 +1,8 @@
 use crate::{
     ethabi,
     tokens::{TokenInfo, TokenMetadata},
-    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H256, KNOWN_CODES_STORAGE_ADDRESS,
-    L1_MESSENGER_ADDRESS,
+    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, ERC20_TRANSFER_TOPIC, H256,
+    KNOWN_CODES_STORAGE_ADDRESS, L1_MESSENGER_ADDRESS, U256,
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -200,3 +200,48 @@ pub struct VmEventGroupKey {
     pub address: Address,
     pub topic: (u32, H256),
 }
+
+/// Events of a token contract used by the tests of the transfers extraction, both at the miniblock seal
+/// and in the backfill of the historical miniblocks.
+#[derive(Debug, Clone)]
+pub struct TransferEventsFixture {
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+    /// ERC20 `Transfer` event moving `amount` from `from` to `to`.
+    pub transfer_event: VmEvent,
+    /// ERC721 `Transfer` event, which has the token ID as the 4th topic and must be skipped.
+    pub nft_transfer_event: VmEvent,
+    /// Unrelated event emitted by the same contract.
+    pub other_event: VmEvent,
+}
+
+impl Default for TransferEventsFixture {
+    fn default() -> Self {
+        let (from, to) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let amount = U256::MAX - 1;
+        let mut value = vec![0; 32];
+        amount.to_big_endian(&mut value);
+        let transfer_event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(0xee),
+            indexed_topics: vec![ERC20_TRANSFER_TOPIC, H256::from(from), H256::from(to)],
+            value,
+        };
+        let mut nft_transfer_event = transfer_event.clone();
+        nft_transfer_event.indexed_topics.push(H256::zero());
+        nft_transfer_event.value = vec![];
+        let other_event = VmEvent {
+            indexed_topics: vec![H256::repeat_byte(3)],
+            ..transfer_event.clone()
+        };
+        Self {
+            from,
+            to,
+            amount,
+            transfer_event,
+            nft_transfer_event,
+            other_event,
+        }
+    }
+}
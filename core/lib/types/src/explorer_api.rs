@@ -1,12 +1,15 @@
 use serde::de::{Deserializer, Error, MapAccess, Unexpected, Visitor};
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, convert::TryInto, fmt};
 use zksync_contracts::BaseSystemContractsHashes;
 
 use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::{api::Log, Address, Bytes, Execute, L1BatchNumber, MiniblockNumber, Nonce, H256, U256};
+use crate::{
+    api::{Finality, Log},
+    Address, Bytes, Execute, L1BatchNumber, MiniblockNumber, Nonce, H256, U256,
+};
 
 use serde_with::rust::display_fromstr::deserialize as deserialize_fromstr;
 
@@ -19,6 +22,87 @@ pub enum PaginationDirection {
     Older,
 }
 
+/// Position of the last item returned on a page: number of the block (miniblock or L1 batch)
+/// the item belongs to and, for items more granular than blocks, its index within the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationKey {
+    pub number: u32,
+    pub index: Option<u32>,
+}
+
+/// Opaque cursor used to request the next page of a list instead of `offset`.
+///
+/// Besides the key of the last returned item, the cursor contains the watermark, i.e. the number
+/// of the last block that was sealed when the first page was requested. Items added after the
+/// watermark are not returned, so the pages stay consistent even if new rows land mid-pagination.
+/// The cursor is serialized as URL-safe Base64 without padding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginationCursor {
+    pub watermark: u32,
+    pub last_key: Option<PaginationKey>,
+}
+
+impl PaginationCursor {
+    /// Creates the cursor for the first page.
+    pub fn start(watermark: u32) -> Self {
+        Self {
+            watermark,
+            last_key: None,
+        }
+    }
+
+    /// Creates the cursor pointing after the given item.
+    pub fn after(&self, number: u32, index: Option<u32>) -> Self {
+        Self {
+            watermark: self.watermark,
+            last_key: Some(PaginationKey { number, index }),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut bytes = self.watermark.to_be_bytes().to_vec();
+        if let Some(key) = self.last_key {
+            bytes.extend_from_slice(&key.number.to_be_bytes());
+            if let Some(index) = key.index {
+                bytes.extend_from_slice(&index.to_be_bytes());
+            }
+        }
+        base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, String> {
+        let bytes = base64::decode_config(encoded, base64::URL_SAFE_NO_PAD)
+            .map_err(|err| format!("Invalid cursor: {}", err))?;
+        let words: Vec<u32> = match bytes.len() {
+            4 | 8 | 12 => bytes
+                .chunks(4)
+                .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+                .collect(),
+            _ => return Err("Invalid cursor: unexpected length".to_string()),
+        };
+        Ok(Self {
+            watermark: words[0],
+            last_key: words.get(1).map(|&number| PaginationKey {
+                number,
+                index: words.get(2).copied(),
+            }),
+        })
+    }
+}
+
+impl Serialize for PaginationCursor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.encode())
+    }
+}
+
+impl<'de> Deserialize<'de> for PaginationCursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Self::decode(&encoded).map_err(D::Error::custom)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginationQuery {
@@ -31,6 +115,37 @@ pub struct PaginationQuery {
     #[serde(deserialize_with = "deserialize_fromstr", default)]
     pub offset: usize,
     pub direction: PaginationDirection,
+    /// Cursor returned with the previous page. Can't be used together with `offset`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<PaginationCursor>,
+}
+
+impl PaginationQuery {
+    /// Returns the cursor for the next page if the page is full and the query doesn't use `offset`.
+    /// `last_key` is the key (block number and index within the block) of the last item on the page.
+    ///
+    /// For the first page, the cursor is created here, so `get_watermark` (returning the number of the last
+    /// sealed block) is only called if there actually is a next page.
+    pub fn next_cursor<E>(
+        &self,
+        page_len: usize,
+        last_key: Option<(u32, Option<u32>)>,
+        get_watermark: impl FnOnce() -> Result<u32, E>,
+    ) -> Result<Option<PaginationCursor>, E> {
+        if page_len == 0 || page_len < self.limit {
+            return Ok(None);
+        }
+        let (number, index) = match last_key {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        let cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None if self.offset == 0 => PaginationCursor::start(get_watermark()?),
+            None => return Ok(None),
+        };
+        Ok(Some(cursor.after(number, index)))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -82,7 +197,14 @@ impl TransactionsQuery {
 #[serde(rename_all = "camelCase")]
 pub struct TransactionsResponse {
     pub list: Vec<TransactionDetails>,
+    /// Total number of the matching transactions, capped at the configured offset limit.
     pub total: usize,
+    /// Whether `total` was capped, i.e. more transactions match the query. The rest of them
+    /// can be fetched using `next_cursor`.
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<PaginationCursor>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
@@ -99,6 +221,8 @@ pub struct EventsQuery {
 pub struct EventsResponse {
     pub list: Vec<Log>,
     pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<PaginationCursor>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -149,6 +273,8 @@ pub struct TransactionDetails {
     pub data: Execute,
     pub is_l1_originated: bool,
     pub status: TransactionStatus,
+    #[serde(default)]
+    pub finality: Finality,
     pub fee: U256,
     pub nonce: Option<Nonce>,
     pub block_number: Option<MiniblockNumber>,
@@ -276,6 +402,8 @@ pub struct BlockDetails {
     pub l2_tx_count: usize,
     pub root_hash: Option<H256>,
     pub status: BlockStatus,
+    #[serde(default)]
+    pub finality: Finality,
     pub commit_tx_hash: Option<H256>,
     pub committed_at: Option<DateTime<Utc>>,
     pub prove_tx_hash: Option<H256>,
@@ -285,6 +413,18 @@ pub struct BlockDetails {
     pub l1_gas_price: u64,
     pub l2_fair_gas_price: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
+    /// Digest of the canonically serialized execution outcomes of the block transactions,
+    /// see `zksync_types::tx::execution_digest`. Absent for the blocks sealed before it was introduced.
+    #[serde(default)]
+    pub execution_digest: Option<H256>,
+    /// Version of the outcome serialization the `execution_digest` was computed with. The digests
+    /// of different versions are not comparable.
+    #[serde(default)]
+    pub execution_digest_version: Option<u8>,
+    /// Fee account the block was executed with. Absent for the blocks of the L1 batch that was pending
+    /// when it started to be recorded per block.
+    #[serde(default)]
+    pub operator_address: Option<Address>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -305,6 +445,10 @@ pub struct L1BatchDetails {
     pub l1_gas_price: u64,
     pub l2_fair_gas_price: u64,
     pub base_system_contracts_hashes: BaseSystemContractsHashes,
+    /// Rolling hash of the priority operations processed in the batch (`priorityOperationsHash` on L1).
+    /// Absent for the batches sealed before it started to be recorded.
+    #[serde(default)]
+    pub priority_ops_hash: Option<H256>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -450,7 +594,83 @@ pub enum DeployContractCalldata {
 
 #[cfg(test)]
 mod tests {
-    use super::SourceCodeData;
+    use super::{
+        PaginationCursor, PaginationDirection, PaginationKey, PaginationQuery, SourceCodeData,
+    };
+
+    #[test]
+    fn pagination_cursor_roundtrip() {
+        let start = PaginationCursor::start(100);
+        let cursors = [start, start.after(42, None), start.after(42, Some(7))];
+        for cursor in cursors {
+            let encoded = cursor.encode();
+            assert!(!encoded.contains(['+', '/', '=']));
+            assert_eq!(PaginationCursor::decode(&encoded), Ok(cursor));
+        }
+        assert_eq!(
+            start.after(42, Some(7)).last_key,
+            Some(PaginationKey {
+                number: 42,
+                index: Some(7)
+            })
+        );
+        assert!(PaginationCursor::decode("not a cursor").is_err());
+        assert!(PaginationCursor::decode("AAAA").is_err());
+    }
+
+    #[test]
+    fn pagination_query_with_cursor_deserialization() {
+        let cursor = PaginationCursor::start(5).after(3, Some(1));
+        let query_str = format!(
+            r#"{{"limit": "10", "direction": "older", "cursor": "{}"}}"#,
+            cursor.encode()
+        );
+        let query: PaginationQuery = serde_json::from_str(&query_str).unwrap();
+        assert_eq!(query.cursor, Some(cursor));
+        assert_eq!(query.offset, 0);
+    }
+
+    #[test]
+    fn next_cursor_is_created_lazily() {
+        let query = PaginationQuery {
+            limit: 2,
+            offset: 0,
+            direction: PaginationDirection::Older,
+            cursor: None,
+        };
+        let no_watermark = || -> Result<u32, ()> { panic!("watermark must not be requested") };
+        assert_eq!(
+            query.next_cursor(1, Some((5, None)), no_watermark),
+            Ok(None)
+        );
+
+        let next_cursor = query.next_cursor(2, Some((5, Some(1))), || Ok::<_, ()>(10));
+        assert_eq!(
+            next_cursor,
+            Ok(Some(PaginationCursor::start(10).after(5, Some(1))))
+        );
+
+        // The watermark of an existing cursor is preserved.
+        let query = PaginationQuery {
+            cursor: next_cursor.unwrap(),
+            ..query
+        };
+        assert_eq!(
+            query.next_cursor(2, Some((3, None)), no_watermark),
+            Ok(Some(PaginationCursor::start(10).after(3, None)))
+        );
+
+        // Offset-based pagination doesn't switch to cursors.
+        let query = PaginationQuery {
+            offset: 2,
+            cursor: None,
+            ..query
+        };
+        assert_eq!(
+            query.next_cursor(2, Some((3, None)), no_watermark),
+            Ok(None)
+        );
+    }
 
     #[test]
     fn source_code_deserialization() {
@@ -0,0 +1,95 @@
+//! Feature flags allowing to gradually roll out API behaviors without redeploying the server.
+
+use serde::{Deserialize, Serialize};
+
+/// Feature flag names known to the server.
+pub mod names {
+    pub const NEW_FEE_MODEL: &str = "new_fee_model";
+    pub const STRICT_TX_VALIDATION: &str = "strict_tx_validation";
+    pub const LOGS_BLOOM_IN_RECEIPTS: &str = "logs_bloom_in_receipts";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFlag {
+    pub name: String,
+    /// Whether the flag is enabled at all. API keys listed in `api_keys` get the feature even if it is disabled.
+    pub enabled: bool,
+    /// Percentage of subjects (API keys, addresses, etc.) the feature is enabled for, in the range [0, 100].
+    pub rollout_percentage: u8,
+    /// API keys the feature is always enabled for.
+    pub api_keys: Vec<String>,
+}
+
+impl FeatureFlag {
+    /// Checks whether the feature is enabled for the given subject.
+    /// Subjects are assigned to the rollout buckets deterministically, so the same subject
+    /// consistently gets the same behavior on every server instance.
+    /// If no subject is provided, the feature is only considered enabled if it is fully rolled out.
+    pub fn is_enabled_for(&self, api_key: Option<&str>, subject: Option<&[u8]>) -> bool {
+        if let Some(api_key) = api_key {
+            if self.api_keys.iter().any(|key| key == api_key) {
+                return true;
+            }
+        }
+        if !self.enabled {
+            return false;
+        }
+        match subject.or_else(|| api_key.map(str::as_bytes)) {
+            Some(subject) => rollout_bucket(&self.name, subject) < self.rollout_percentage,
+            None => self.rollout_percentage >= 100,
+        }
+    }
+}
+
+/// Maps a subject to a bucket in the range [0, 100) using FNV-1a hash.
+/// Flag name is mixed in, so that different flags are rolled out to different subjects.
+fn rollout_bucket(flag_name: &str, subject: &[u8]) -> u8 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let hash = flag_name
+        .as_bytes()
+        .iter()
+        .chain(subject)
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        });
+    (hash % 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(enabled: bool, rollout_percentage: u8) -> FeatureFlag {
+        FeatureFlag {
+            name: names::STRICT_TX_VALIDATION.to_owned(),
+            enabled,
+            rollout_percentage,
+            api_keys: vec!["key".to_owned()],
+        }
+    }
+
+    #[test]
+    fn api_key_overrides_flag_state() {
+        assert!(flag(false, 0).is_enabled_for(Some("key"), None));
+        assert!(!flag(false, 100).is_enabled_for(Some("other"), None));
+    }
+
+    #[test]
+    fn rollout_percentage_is_respected() {
+        assert!(flag(true, 100).is_enabled_for(None, None));
+        assert!(!flag(true, 50).is_enabled_for(None, None));
+
+        let subjects: Vec<_> = (0u32..1000).map(u32::to_be_bytes).collect();
+        let enabled = subjects
+            .iter()
+            .filter(|subject| flag(true, 30).is_enabled_for(None, Some(&subject[..])))
+            .count();
+        assert!((200..400).contains(&enabled), "{}", enabled);
+        assert!(subjects
+            .iter()
+            .all(|subject| !flag(true, 0).is_enabled_for(None, Some(&subject[..]))));
+    }
+}
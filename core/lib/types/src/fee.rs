@@ -23,6 +23,37 @@ pub struct TransactionExecutionMetrics {
     pub cycles_used: u32,
 }
 
+/// Components of the gas refund proposed by the operator for a transaction.
+/// The sum of the components is equal to the operator suggested refund.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundBreakdown {
+    /// Gas left unused after the execution, as reported by the bootloader.
+    pub unused_gas: u32,
+    /// Gas returned because the computation and pubdata were charged at the fair prices,
+    /// which are lower than the gas price paid in advance.
+    pub pubdata_refund: u32,
+    /// Gas returned from the batch overhead paid in advance.
+    pub overhead_refund: u32,
+}
+
+impl RefundBreakdown {
+    pub fn new(bootloader_refund: u32, tx_body_refund: u32, overhead_refund: u32) -> Self {
+        // Tx body refund is expected to include the bootloader refund, but it's not guaranteed,
+        // so the unused gas is capped to keep the components consistent with the total.
+        let unused_gas = std::cmp::min(bootloader_refund, tx_body_refund);
+        Self {
+            unused_gas,
+            pubdata_refund: tx_body_refund - unused_gas,
+            overhead_refund,
+        }
+    }
+
+    pub fn operator_suggested_refund(&self) -> u32 {
+        self.unused_gas + self.pubdata_refund + self.overhead_refund
+    }
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Fee {
     /// The limit of gas that are to be spent on the actual transaction.
@@ -81,7 +81,8 @@ pub struct L1TxCommonData {
     pub sender: Address,
     /// Unique ID of the priority operation.
     pub serial_id: PriorityOpId,
-    /// Ethereum deadline block until which operation must be processed.
+    /// Expiration timestamp (in seconds) of the operation on L1: the operation must be processed
+    /// by an L1 block with the timestamp not greater than this one.
     pub deadline_block: u64,
     /// Additional payment to the operator as an incentive to perform the operation. The contract uses a value of 192 bits.
     pub layer_2_tip_fee: U256,
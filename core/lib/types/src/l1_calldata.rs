@@ -0,0 +1,457 @@
+//! Decoding of the calldata of the `commitBlocks`, `proveBlocks` and `executeBlocks` transactions
+//! sent to L1, i.e. the inverse of `aggregated_operations::*::get_eth_tx_args`, and export of the data
+//! committed to L1 for a batch.
+
+use std::convert::TryInto;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::aggregated_operations::BlocksCommitOperation;
+use crate::commitment::BlockWithMetadata;
+use crate::ethabi::{self, Contract, Function, Token};
+use crate::{Bytes, L1BatchNumber, H256, U256};
+use zksync_contracts::zksync_contract;
+use zksync_utils::bytecode::{hash_bytecode, validate_bytecode};
+
+#[derive(Debug, Error)]
+pub enum L1CalldataError {
+    #[error("Calldata is shorter than a function selector")]
+    TooShort,
+    #[error("Unknown function selector: 0x{0}")]
+    UnknownSelector(String),
+    #[error("Failed to decode the calldata: {0}")]
+    Abi(#[from] ethabi::Error),
+    #[error("Unexpected layout of {0}")]
+    UnexpectedLayout(&'static str),
+}
+
+/// Batch information as it is stored on L1.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBatchInfo {
+    pub batch_number: L1BatchNumber,
+    pub state_root: H256,
+    pub rollup_last_leaf_index: u64,
+    pub l1_tx_count: u64,
+    pub priority_operations_hash: H256,
+    pub l2_logs_tree_root: H256,
+    pub timestamp: u64,
+    pub commitment: H256,
+}
+
+/// Data of the batch published in the `commitBlocks` transaction.
+/// Factory dependencies are represented by their bytecode hashes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitBatchInfo {
+    pub batch_number: L1BatchNumber,
+    pub timestamp: u64,
+    pub rollup_last_leaf_index: u64,
+    pub new_state_root: H256,
+    pub l1_tx_count: u64,
+    pub l2_logs_tree_root: H256,
+    pub priority_operations_hash: H256,
+    pub initial_storage_changes: Bytes,
+    pub repeated_storage_changes: Bytes,
+    pub l2_logs: Bytes,
+    pub l2_arbitrary_length_messages: Vec<Bytes>,
+    pub factory_deps_hashes: Vec<H256>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofInput {
+    pub recursive_aggregation_input: Vec<U256>,
+    /// Empty if the proof verification is skipped.
+    pub serialized_proof: Vec<U256>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "function", rename_all = "camelCase")]
+pub enum DecodedL1Calldata {
+    #[serde(rename_all = "camelCase")]
+    CommitBlocks {
+        last_committed_batch: StoredBatchInfo,
+        new_batches: Vec<CommitBatchInfo>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ProveBlocks {
+        prev_batch: StoredBatchInfo,
+        committed_batches: Vec<StoredBatchInfo>,
+        proof: ProofInput,
+    },
+    #[serde(rename_all = "camelCase")]
+    ExecuteBlocks { batches: Vec<StoredBatchInfo> },
+}
+
+/// Format of the data committed to L1 for a batch. New formats are added together with the protocol
+/// upgrades changing the `commitBlocks` interface of the zkSync contract.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchCommitmentFormat {
+    /// Storage writes, L2 to L1 logs and factory dependencies are published in the `commitBlocks` calldata.
+    PreBoojum,
+}
+
+/// Data of the batch exactly as it's committed to L1, allowing to re-run the commitment checks of the L1
+/// contract off-chain.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchCommitmentData {
+    pub format: L1BatchCommitmentFormat,
+    pub last_committed_batch: StoredBatchInfo,
+    pub batch: CommitBatchInfo,
+    /// Calldata of the `commitBlocks` transaction committing only this batch.
+    pub calldata: Bytes,
+    /// The commitment is the hash of the pass-through data, meta parameters and auxiliary output hashes.
+    pub pass_through_data_hash: H256,
+    pub meta_parameters_hash: H256,
+    pub aux_data_hash: H256,
+    pub commitment: H256,
+}
+
+impl L1BatchCommitmentData {
+    /// Re-derives the commitment data of `batch` from the stored metadata of the batch and of the preceding one.
+    pub fn new(
+        last_committed_batch: BlockWithMetadata,
+        batch: BlockWithMetadata,
+    ) -> Result<Self, L1CalldataError> {
+        let pass_through_data_hash = batch.metadata.pass_through_data_hash;
+        let meta_parameters_hash = batch.metadata.meta_parameters_hash;
+        let aux_data_hash = batch.metadata.aux_data_hash;
+        let commitment = batch.metadata.commitment;
+        let operation = BlocksCommitOperation {
+            last_committed_block: last_committed_batch,
+            blocks: vec![batch],
+        };
+        let contract = zksync_contract();
+        let calldata =
+            get_function(&contract, "commitBlocks").encode_input(&operation.get_eth_tx_args())?;
+        let (last_committed_batch, mut new_batches) = match decode_l1_calldata(&calldata)? {
+            DecodedL1Calldata::CommitBlocks {
+                last_committed_batch,
+                new_batches,
+            } => (last_committed_batch, new_batches),
+            _ => return Err(L1CalldataError::UnexpectedLayout("commitBlocks input")),
+        };
+        let batch = new_batches
+            .pop()
+            .ok_or(L1CalldataError::UnexpectedLayout("new batches"))?;
+
+        Ok(Self {
+            format: L1BatchCommitmentFormat::PreBoojum,
+            last_committed_batch,
+            batch,
+            calldata: calldata.into(),
+            pass_through_data_hash,
+            meta_parameters_hash,
+            aux_data_hash,
+            commitment,
+        })
+    }
+}
+
+/// Decodes the calldata of the transaction sent to the zkSync contract on L1.
+pub fn decode_l1_calldata(calldata: &[u8]) -> Result<DecodedL1Calldata, L1CalldataError> {
+    if calldata.len() < 4 {
+        return Err(L1CalldataError::TooShort);
+    }
+    let (selector, input) = calldata.split_at(4);
+
+    let contract = zksync_contract();
+    let commit_blocks = get_function(&contract, "commitBlocks");
+    let prove_blocks = get_function(&contract, "proveBlocks");
+    let execute_blocks = get_function(&contract, "executeBlocks");
+
+    if selector == commit_blocks.short_signature() {
+        decode_commit_blocks(commit_blocks.decode_input(input)?)
+    } else if selector == prove_blocks.short_signature() {
+        decode_prove_blocks(prove_blocks.decode_input(input)?)
+    } else if selector == execute_blocks.short_signature() {
+        decode_execute_blocks(execute_blocks.decode_input(input)?)
+    } else {
+        Err(L1CalldataError::UnknownSelector(hex::encode(selector)))
+    }
+}
+
+fn get_function<'a>(contract: &'a Contract, name: &str) -> &'a Function {
+    contract
+        .functions
+        .get(name)
+        .and_then(|functions| functions.first())
+        .unwrap_or_else(|| panic!("{} function not found", name))
+}
+
+fn decode_commit_blocks(tokens: Vec<Token>) -> Result<DecodedL1Calldata, L1CalldataError> {
+    let [last_committed_batch, new_batches] = into_fixed_tuple(tokens, "commitBlocks input")?;
+    Ok(DecodedL1Calldata::CommitBlocks {
+        last_committed_batch: StoredBatchInfo::from_token(last_committed_batch)?,
+        new_batches: into_array(new_batches, "new batches")?
+            .into_iter()
+            .map(CommitBatchInfo::from_token)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn decode_prove_blocks(tokens: Vec<Token>) -> Result<DecodedL1Calldata, L1CalldataError> {
+    let [prev_batch, committed_batches, proof] = into_fixed_tuple(tokens, "proveBlocks input")?;
+    let [recursive_aggregation_input, serialized_proof] =
+        into_fixed_tuple(into_tuple(proof, "proof")?, "proof")?;
+    Ok(DecodedL1Calldata::ProveBlocks {
+        prev_batch: StoredBatchInfo::from_token(prev_batch)?,
+        committed_batches: into_array(committed_batches, "committed batches")?
+            .into_iter()
+            .map(StoredBatchInfo::from_token)
+            .collect::<Result<_, _>>()?,
+        proof: ProofInput {
+            recursive_aggregation_input: into_array(recursive_aggregation_input, "proof")?
+                .into_iter()
+                .map(|token| into_uint(token, "proof"))
+                .collect::<Result<_, _>>()?,
+            serialized_proof: into_array(serialized_proof, "proof")?
+                .into_iter()
+                .map(|token| into_uint(token, "proof"))
+                .collect::<Result<_, _>>()?,
+        },
+    })
+}
+
+fn decode_execute_blocks(tokens: Vec<Token>) -> Result<DecodedL1Calldata, L1CalldataError> {
+    let [batches] = into_fixed_tuple(tokens, "executeBlocks input")?;
+    Ok(DecodedL1Calldata::ExecuteBlocks {
+        batches: into_array(batches, "batches")?
+            .into_iter()
+            .map(StoredBatchInfo::from_token)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+impl StoredBatchInfo {
+    /// Parses the token produced by `BlockWithMetadata::l1_header_data`.
+    pub fn from_token(token: Token) -> Result<Self, L1CalldataError> {
+        const NAME: &str = "stored batch info";
+        let mut fields = into_fields(token, 8, NAME)?;
+        let mut next = || fields.next().unwrap();
+        Ok(Self {
+            batch_number: L1BatchNumber(into_u64(next(), NAME)? as u32),
+            state_root: into_h256(next(), NAME)?,
+            rollup_last_leaf_index: into_u64(next(), NAME)?,
+            l1_tx_count: into_u64(next(), NAME)?,
+            priority_operations_hash: into_h256(next(), NAME)?,
+            l2_logs_tree_root: into_h256(next(), NAME)?,
+            timestamp: into_u64(next(), NAME)?,
+            commitment: into_h256(next(), NAME)?,
+        })
+    }
+}
+
+impl CommitBatchInfo {
+    /// Parses the token produced by `BlockWithMetadata::l1_commit_data`.
+    pub fn from_token(token: Token) -> Result<Self, L1CalldataError> {
+        const NAME: &str = "commit batch info";
+        let mut fields = into_fields(token, 12, NAME)?;
+        let mut next = || fields.next().unwrap();
+        Ok(Self {
+            batch_number: L1BatchNumber(into_u64(next(), NAME)? as u32),
+            timestamp: into_u64(next(), NAME)?,
+            rollup_last_leaf_index: into_u64(next(), NAME)?,
+            new_state_root: into_h256(next(), NAME)?,
+            l1_tx_count: into_u64(next(), NAME)?,
+            l2_logs_tree_root: into_h256(next(), NAME)?,
+            priority_operations_hash: into_h256(next(), NAME)?,
+            initial_storage_changes: into_bytes(next(), NAME)?.into(),
+            repeated_storage_changes: into_bytes(next(), NAME)?.into(),
+            l2_logs: into_bytes(next(), NAME)?.into(),
+            l2_arbitrary_length_messages: into_array(next(), NAME)?
+                .into_iter()
+                .map(|token| into_bytes(token, NAME).map(Bytes::from))
+                .collect::<Result<_, _>>()?,
+            factory_deps_hashes: into_array(next(), NAME)?
+                .into_iter()
+                .map(into_bytecode_hash)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn into_fixed_tuple<const N: usize>(
+    tokens: Vec<Token>,
+    name: &'static str,
+) -> Result<[Token; N], L1CalldataError> {
+    tokens
+        .try_into()
+        .map_err(|_| L1CalldataError::UnexpectedLayout(name))
+}
+
+/// Returns the fields of the tuple, checking that their number is as expected.
+fn into_fields(
+    token: Token,
+    len: usize,
+    name: &'static str,
+) -> Result<std::vec::IntoIter<Token>, L1CalldataError> {
+    let tokens = into_tuple(token, name)?;
+    if tokens.len() != len {
+        return Err(L1CalldataError::UnexpectedLayout(name));
+    }
+    Ok(tokens.into_iter())
+}
+
+fn into_tuple(token: Token, name: &'static str) -> Result<Vec<Token>, L1CalldataError> {
+    match token {
+        Token::Tuple(tokens) => Ok(tokens),
+        _ => Err(L1CalldataError::UnexpectedLayout(name)),
+    }
+}
+
+fn into_array(token: Token, name: &'static str) -> Result<Vec<Token>, L1CalldataError> {
+    match token {
+        Token::Array(tokens) => Ok(tokens),
+        _ => Err(L1CalldataError::UnexpectedLayout(name)),
+    }
+}
+
+fn into_uint(token: Token, name: &'static str) -> Result<U256, L1CalldataError> {
+    match token {
+        Token::Uint(value) => Ok(value),
+        _ => Err(L1CalldataError::UnexpectedLayout(name)),
+    }
+}
+
+fn into_u64(token: Token, name: &'static str) -> Result<u64, L1CalldataError> {
+    let value = into_uint(token, name)?;
+    if value > U256::from(u64::MAX) {
+        return Err(L1CalldataError::UnexpectedLayout(name));
+    }
+    Ok(value.as_u64())
+}
+
+fn into_h256(token: Token, name: &'static str) -> Result<H256, L1CalldataError> {
+    match token {
+        Token::FixedBytes(bytes) if bytes.len() == 32 => Ok(H256::from_slice(&bytes)),
+        _ => Err(L1CalldataError::UnexpectedLayout(name)),
+    }
+}
+
+fn into_bytes(token: Token, name: &'static str) -> Result<Vec<u8>, L1CalldataError> {
+    match token {
+        Token::Bytes(bytes) => Ok(bytes),
+        _ => Err(L1CalldataError::UnexpectedLayout(name)),
+    }
+}
+
+fn into_bytecode_hash(token: Token) -> Result<H256, L1CalldataError> {
+    const NAME: &str = "factory dependency";
+    let bytecode = into_bytes(token, NAME)?;
+    validate_bytecode(&bytecode).map_err(|_| L1CalldataError::UnexpectedLayout(NAME))?;
+    Ok(hash_bytecode(&bytecode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::L1BatchHeader;
+    use crate::commitment::{BlockMetaParameters, BlockMetadata};
+    use crate::Address;
+    use std::collections::HashMap;
+    use zksync_contracts::BaseSystemContractsHashes;
+
+    fn stored_batch_info_token(number: u32) -> Token {
+        Token::Tuple(vec![
+            Token::Uint(number.into()),
+            Token::FixedBytes(H256::repeat_byte(1).as_bytes().to_vec()),
+            Token::Uint(42.into()),
+            Token::Uint(3.into()),
+            Token::FixedBytes(H256::repeat_byte(2).as_bytes().to_vec()),
+            Token::FixedBytes(H256::repeat_byte(3).as_bytes().to_vec()),
+            Token::Uint(1_000.into()),
+            Token::FixedBytes(H256::repeat_byte(4).as_bytes().to_vec()),
+        ])
+    }
+
+    #[test]
+    fn execute_blocks_input_is_decoded() {
+        let tokens = vec![Token::Array(vec![
+            stored_batch_info_token(5),
+            stored_batch_info_token(6),
+        ])];
+        let decoded = decode_execute_blocks(tokens).unwrap();
+
+        let batches = match decoded {
+            DecodedL1Calldata::ExecuteBlocks { batches } => batches,
+            other => panic!("Unexpected decoded calldata: {:?}", other),
+        };
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].batch_number, L1BatchNumber(5));
+        assert_eq!(batches[1].batch_number, L1BatchNumber(6));
+        assert_eq!(batches[0].state_root, H256::repeat_byte(1));
+        assert_eq!(batches[0].rollup_last_leaf_index, 42);
+        assert_eq!(batches[0].l1_tx_count, 3);
+        assert_eq!(batches[0].timestamp, 1_000);
+        assert_eq!(batches[0].commitment, H256::repeat_byte(4));
+    }
+
+    fn block_with_metadata(number: u32) -> BlockWithMetadata {
+        let header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            1_000 + number as u64,
+            Address::default(),
+            BaseSystemContractsHashes::default(),
+        );
+        let metadata = BlockMetadata {
+            root_hash: H256::repeat_byte(1),
+            rollup_last_leaf_index: 42 + number as u64,
+            merkle_root_hash: H256::repeat_byte(1),
+            initial_writes_compressed: vec![1; 4],
+            repeated_writes_compressed: vec![2; 4],
+            commitment: H256::repeat_byte(2),
+            l2_l1_messages_compressed: vec![3; 4],
+            l2_l1_merkle_root: H256::repeat_byte(3),
+            block_meta_params: BlockMetaParameters {
+                zkporter_is_available: false,
+                bootloader_code_hash: H256::zero(),
+                default_aa_code_hash: H256::zero(),
+            },
+            aux_data_hash: H256::repeat_byte(4),
+            meta_parameters_hash: H256::repeat_byte(5),
+            pass_through_data_hash: H256::repeat_byte(6),
+        };
+        BlockWithMetadata::new(header, metadata, HashMap::new())
+    }
+
+    #[test]
+    fn commitment_data_is_derived() {
+        let data =
+            L1BatchCommitmentData::new(block_with_metadata(4), block_with_metadata(5)).unwrap();
+        assert_eq!(data.format, L1BatchCommitmentFormat::PreBoojum);
+        assert_eq!(data.last_committed_batch.batch_number, L1BatchNumber(4));
+        assert_eq!(data.last_committed_batch.rollup_last_leaf_index, 46);
+        assert_eq!(data.batch.batch_number, L1BatchNumber(5));
+        assert_eq!(data.batch.timestamp, 1_005);
+        assert_eq!(data.batch.initial_storage_changes.0, [1; 4]);
+        assert_eq!(data.batch.l2_logs.0, [3; 4]);
+        assert_eq!(data.commitment, H256::repeat_byte(2));
+        assert_eq!(data.aux_data_hash, H256::repeat_byte(4));
+
+        // The calldata commits only the batch and is decoded into the same data.
+        match decode_l1_calldata(&data.calldata.0).unwrap() {
+            DecodedL1Calldata::CommitBlocks {
+                last_committed_batch,
+                new_batches,
+            } => {
+                assert_eq!(last_committed_batch, data.last_committed_batch);
+                assert_eq!(new_batches, [data.batch]);
+            }
+            other => panic!("Unexpected decoded calldata: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unexpected_layout_is_rejected() {
+        let tokens = vec![Token::Array(vec![Token::Uint(1.into())])];
+        assert!(matches!(
+            decode_execute_blocks(tokens),
+            Err(L1CalldataError::UnexpectedLayout(_))
+        ));
+    }
+}
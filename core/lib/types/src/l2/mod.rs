@@ -45,6 +45,9 @@ pub struct L2TxCommonData {
     pub input: Option<InputData>,
 
     pub paymaster_params: PaymasterParams,
+    /// Unix timestamp (in seconds) after which the transaction can no longer be included in a block.
+    #[serde(default)]
+    pub valid_until: Option<u64>,
 }
 
 impl L2TxCommonData {
@@ -68,6 +71,7 @@ impl L2TxCommonData {
             transaction_type,
             input,
             paymaster_params,
+            valid_until: None,
         }
     }
 
@@ -82,6 +86,11 @@ impl L2TxCommonData {
     pub fn set_input(&mut self, input: Vec<u8>, hash: H256) {
         self.input = Some(InputData { hash, data: input })
     }
+
+    /// Returns `true` if the transaction cannot be included in a block with the given timestamp.
+    pub fn is_expired_at(&self, timestamp: u64) -> bool {
+        matches!(self.valid_until, Some(valid_until) if valid_until < timestamp)
+    }
 }
 
 impl Default for L2TxCommonData {
@@ -94,6 +103,7 @@ impl Default for L2TxCommonData {
             transaction_type: TransactionType::EIP712Transaction,
             input: Default::default(),
             paymaster_params: Default::default(),
+            valid_until: None,
         }
     }
 }
@@ -132,6 +142,7 @@ impl L2Tx {
                 transaction_type: TransactionType::EIP712Transaction,
                 input: None,
                 paymaster_params,
+                valid_until: None,
             },
             received_timestamp_ms: unix_timestamp_ms(),
         }
@@ -308,6 +319,7 @@ impl From<L2Tx> for TransactionRequest {
                 factory_deps: tx.execute.factory_deps,
                 custom_signature: Some(tx.common_data.signature),
                 paymaster_params: Some(tx.common_data.paymaster_params),
+                valid_until: tx.common_data.valid_until,
             }),
             chain_id: None,
         }
@@ -24,17 +24,24 @@ pub use zk_evm;
 pub use zkevm_test_harness;
 pub use zksync_basic_types::*;
 
+pub mod abi_decoding;
 pub mod aggregated_operations;
 pub mod block;
+pub mod bloom;
+pub mod chain_stats;
 pub mod circuit;
 pub mod commitment;
+pub mod encrypted_tx;
 pub mod event;
 pub mod explorer_api;
+pub mod feature_flags;
 pub mod fee;
 pub mod l1;
+pub mod l1_calldata;
 pub mod l2;
 pub mod l2_to_l1_log;
 pub mod priority_op_onchain_data;
+pub mod protocol_version;
 pub mod pubdata_packing;
 pub mod storage;
 pub mod storage_writes_deduplicator;
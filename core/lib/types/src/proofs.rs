@@ -54,6 +54,42 @@ pub struct WitnessGeneratorJobMetadata {
     pub proofs: Vec<Proof<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>>,
 }
 
+/// Linear model of the peak memory needed to generate basic circuit witnesses for an L1 batch,
+/// paired with the amount of memory available to the witness generator instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessMemoryBudget {
+    pub available_bytes: u64,
+    pub base_bytes: u64,
+    pub bytes_per_tx: u64,
+    pub bytes_per_circuit: u64,
+}
+
+impl WitnessMemoryBudget {
+    /// Empirical coefficients observed for the current circuit geometry.
+    const BASE_BYTES: u64 = 4 << 30;
+    const BYTES_PER_TX: u64 = 4 << 20;
+    const BYTES_PER_CIRCUIT: u64 = 64 << 20;
+
+    pub fn new(available_bytes: u64) -> Self {
+        Self {
+            available_bytes,
+            base_bytes: Self::BASE_BYTES,
+            bytes_per_tx: Self::BYTES_PER_TX,
+            bytes_per_circuit: Self::BYTES_PER_CIRCUIT,
+        }
+    }
+
+    pub fn estimate_bytes(&self, tx_count: usize, basic_circuits: usize) -> u64 {
+        self.base_bytes
+            .saturating_add(self.bytes_per_tx.saturating_mul(tx_count as u64))
+            .saturating_add(self.bytes_per_circuit.saturating_mul(basic_circuits as u64))
+    }
+
+    pub fn fits(&self, tx_count: usize, basic_circuits: usize) -> bool {
+        self.estimate_bytes(tx_count, basic_circuits) <= self.available_bytes
+    }
+}
+
 #[derive(Clone)]
 pub struct WitnessGeneratorJob {
     pub block_number: L1BatchNumber,
@@ -62,7 +98,7 @@ pub struct WitnessGeneratorJob {
 
 /// Represents the sequential number of the proof aggregation round.
 /// Mostly used to be stored in `aggregation_round` column  in `prover_jobs` table
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AggregationRound {
     BasicCircuits = 0,
     LeafAggregation = 1,
@@ -177,6 +213,21 @@ pub struct ProverJobMetadata {
     pub sequence_number: usize,
 }
 
+/// Prover job exposed to the external provers by the prover gateway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalProverJob {
+    pub id: u32,
+    pub l1_batch_number: L1BatchNumber,
+    pub circuit_type: String,
+    pub aggregation_round: AggregationRound,
+    pub sequence_number: usize,
+    pub status: String,
+    /// Key of the circuit input in the `prover_jobs` bucket of the object store.
+    /// `None` if the input has already been cleaned up.
+    pub circuit_input_blob_url: Option<String>,
+}
+
 pub struct ProverJob {
     pub metadata: ProverJobMetadata,
     pub circuit_input: Vec<u8>,
@@ -246,6 +297,8 @@ pub enum ProverJobStatus {
     Skipped,
     #[strum(serialize = "ignored")]
     Ignored,
+    #[strum(serialize = "artifact_corrupted")]
+    ArtifactCorrupted,
 }
 
 #[derive(strum::Display, strum::EnumString, strum::AsRefStr)]
@@ -264,6 +317,8 @@ pub enum WitnessJobStatus {
     InProgress,
     #[strum(serialize = "queued")]
     Queued,
+    #[strum(serialize = "artifact_corrupted")]
+    ArtifactCorrupted,
 }
 
 pub struct WitnessJobInfo {
@@ -313,3 +368,24 @@ impl Add for JobCountStatistics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn witness_memory_budget() {
+        let budget = WitnessMemoryBudget::new(8 << 30);
+        assert_eq!(budget.estimate_bytes(0, 0), 4 << 30);
+        assert_eq!(
+            budget.estimate_bytes(10, 2),
+            (4 << 30) + (40 << 20) + (128 << 20)
+        );
+        assert!(budget.fits(0, 64));
+        assert!(!budget.fits(0, 65));
+        assert!(!budget.fits(1, 64));
+        // Huge batches don't overflow the estimate.
+        assert_eq!(budget.estimate_bytes(usize::MAX, usize::MAX), u64::MAX);
+        assert!(!budget.fits(usize::MAX, 0));
+    }
+}
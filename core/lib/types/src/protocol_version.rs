@@ -0,0 +1,24 @@
+//! Gas limits of the protocol versions the miniblocks are sealed with.
+
+use zksync_basic_types::MiniblockNumber;
+
+/// Protocol version recorded by the state keeper. The gas limits of a version never change once it's recorded,
+/// so that the values reported for the already sealed blocks stay the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolVersion {
+    pub id: u16,
+    /// The first miniblock sealed with this version.
+    pub activated_at_miniblock: MiniblockNumber,
+    pub block_gas_limit: u32,
+    pub max_allowed_tx_gas_limit: u32,
+}
+
+impl ProtocolVersion {
+    /// Returns the version the miniblock was sealed with among `versions` ordered by the activation miniblock.
+    pub fn of_miniblock(versions: &[Self], miniblock_number: MiniblockNumber) -> Option<&Self> {
+        versions
+            .iter()
+            .rev()
+            .find(|version| version.activated_at_miniblock <= miniblock_number)
+    }
+}
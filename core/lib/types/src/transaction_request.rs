@@ -267,6 +267,12 @@ pub struct Eip712Meta {
     pub factory_deps: Option<Vec<Vec<u8>>>,
     pub custom_signature: Option<Vec<u8>>,
     pub paymaster_params: Option<PaymasterParams>,
+    /// Unix timestamp (in seconds) after which the transaction must not be included in a block.
+    /// Note that this field is not a part of the signed EIP-712 structure, since the bootloader computes
+    /// the signed hash (and thus the transaction hash) without it. So it only bounds the lifetime
+    /// of the transaction on the operator side.
+    #[serde(default)]
+    pub valid_until: Option<u64>,
 }
 
 impl Eip712Meta {
@@ -290,6 +296,10 @@ impl Eip712Meta {
         } else {
             rlp.begin_list(0);
         }
+
+        if let Some(valid_until) = self.valid_until {
+            rlp.append(&valid_until);
+        }
     }
 }
 
@@ -590,7 +600,9 @@ impl TransactionRequest {
             }
             Some(&EIP_712_TX_TYPE) => {
                 rlp = Rlp::new(&bytes[1..]);
-                if rlp.item_count()? != 16 {
+                // The 17th item is the optional `valid_until` timestamp.
+                let item_count = rlp.item_count()?;
+                if !matches!(item_count, 16 | 17) {
                     return Err(SerializationTransactionError::DecodeRlpError(
                         DecoderError::RlpIncorrectListLen,
                     ));
@@ -613,6 +625,11 @@ impl TransactionRequest {
                         } else {
                             None
                         },
+                        valid_until: if item_count == 17 {
+                            Some(rlp.val_at(16)?)
+                        } else {
+                            None
+                        },
                     }),
                     chain_id: tx_chain_id,
                     transaction_type: Some(EIP_712_TX_TYPE.into()),
@@ -775,6 +792,7 @@ impl TryFrom<TransactionRequest> for L2Tx {
             Some(EIP_2930_TX_TYPE) => TransactionType::EIP2930Transaction,
             _ => TransactionType::LegacyTransaction,
         };
+        tx.common_data.valid_until = value.eip712_meta.as_ref().and_then(|meta| meta.valid_until);
         // For fee calculation we use the same structure, as a result, signature may not be provided
         tx.set_raw_signature(value.get_signature().unwrap_or_default());
         Ok(tx)
@@ -788,6 +806,7 @@ impl From<L2Tx> for CallRequest {
             factory_deps: None,
             custom_signature: Some(tx.common_data.signature.clone()),
             paymaster_params: Some(tx.common_data.paymaster_params.clone()),
+            valid_until: tx.common_data.valid_until,
         };
         meta.factory_deps = tx.execute.factory_deps.clone();
         let mut request = CallRequestBuilder::default()
@@ -1033,6 +1052,7 @@ mod tests {
                     paymaster: Default::default(),
                     paymaster_input: vec![],
                 }),
+                valid_until: None,
             }),
             chain_id: Some(270),
             ..Default::default()
@@ -1056,6 +1076,103 @@ mod tests {
         assert_eq!(tx, tx2);
     }
 
+    #[test]
+    fn decode_eip712_with_valid_until() {
+        let random_tx_max_size = 1_000_000; // bytes
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+
+        let mut tx = TransactionRequest {
+            nonce: U256::from(1u32),
+            to: Some(Address::random()),
+            from: Some(address),
+            value: U256::from(10u32),
+            gas_price: U256::from(11u32),
+            max_priority_fee_per_gas: Some(U256::from(0u32)),
+            gas: U256::from(12u32),
+            input: Bytes::from(vec![1, 2, 3]),
+            transaction_type: Some(U64::from(EIP_712_TX_TYPE)),
+            eip712_meta: Some(Eip712Meta {
+                gas_per_pubdata: U256::from(4u32),
+                factory_deps: None,
+                custom_signature: Some(vec![1, 2, 3]),
+                paymaster_params: None,
+                valid_until: None,
+            }),
+            chain_id: Some(270),
+            ..Default::default()
+        };
+
+        let msg =
+            PackedEthSignature::typed_data_to_signed_bytes(&Eip712Domain::new(L2ChainId(270)), &tx);
+        let signature = PackedEthSignature::sign_raw(&private_key, &msg).unwrap();
+        let encoded_tx = tx.get_signed_bytes(&signature, L2ChainId(270));
+        let (_, hash) =
+            TransactionRequest::from_bytes(&encoded_tx, 270, random_tx_max_size).unwrap();
+
+        tx.eip712_meta.as_mut().unwrap().valid_until = Some(1_700_000_000);
+        // `valid_until` is not signed, so it must not affect the signed message.
+        let signed_msg =
+            PackedEthSignature::typed_data_to_signed_bytes(&Eip712Domain::new(L2ChainId(270)), &tx);
+        assert_eq!(msg, signed_msg);
+        let encoded_tx = tx.get_signed_bytes(&signature, L2ChainId(270));
+        let (decoded_tx, decoded_hash) =
+            TransactionRequest::from_bytes(&encoded_tx, 270, random_tx_max_size).unwrap();
+
+        assert_eq!(
+            decoded_tx.eip712_meta.unwrap().valid_until,
+            Some(1_700_000_000)
+        );
+        // The transaction hash must match the one computed by the bootloader, which doesn't know `valid_until`.
+        assert_eq!(hash, decoded_hash);
+        assert_eq!(address, decoded_tx.from.unwrap());
+    }
+
+    #[test]
+    fn decode_eip712_with_malformed_valid_until() {
+        let random_tx_max_size = 1_000_000; // bytes
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+
+        let tx = TransactionRequest {
+            nonce: U256::from(1u32),
+            to: Some(Address::random()),
+            from: Some(address),
+            gas: U256::from(12u32),
+            transaction_type: Some(U64::from(EIP_712_TX_TYPE)),
+            eip712_meta: Some(Eip712Meta {
+                gas_per_pubdata: U256::from(4u32),
+                factory_deps: None,
+                custom_signature: Some(vec![1, 2, 3]),
+                paymaster_params: None,
+                valid_until: None,
+            }),
+            chain_id: Some(270),
+            ..Default::default()
+        };
+        let msg =
+            PackedEthSignature::typed_data_to_signed_bytes(&Eip712Domain::new(L2ChainId(270)), &tx);
+        let signature = PackedEthSignature::sign_raw(&private_key, &msg).unwrap();
+        let encoded_tx = tx.get_signed_bytes(&signature, L2ChainId(270));
+
+        // Append a list instead of an integer as the 17th item.
+        let mut rlp = RlpStream::new();
+        rlp.begin_unbounded_list();
+        for item in Rlp::new(&encoded_tx[1..]).iter() {
+            rlp.append_raw(item.as_raw(), 1);
+        }
+        rlp.begin_list(0);
+        rlp.finalize_unbounded_list();
+        let mut malformed_tx = vec![EIP_712_TX_TYPE];
+        malformed_tx.extend_from_slice(rlp.as_raw());
+
+        let err = TransactionRequest::from_bytes(&malformed_tx, 270, random_tx_max_size);
+        assert!(matches!(
+            err,
+            Err(SerializationTransactionError::DecodeRlpError(_))
+        ));
+    }
+
     #[test]
     fn check_recovered_public_key_eip712() {
         let random_tx_max_size = 1_000_000; // bytes
@@ -1077,6 +1194,7 @@ mod tests {
                 factory_deps: Some(vec![vec![2; 32]]),
                 custom_signature: Some(vec![]),
                 paymaster_params: None,
+                valid_until: None,
             }),
             chain_id: Some(270),
             ..Default::default()
@@ -1118,6 +1236,7 @@ mod tests {
                     paymaster: Default::default(),
                     paymaster_input: vec![],
                 }),
+                valid_until: None,
             }),
             chain_id: Some(270),
             ..Default::default()
@@ -1400,6 +1519,7 @@ mod tests {
                     paymaster: Default::default(),
                     paymaster_input: vec![],
                 }),
+                valid_until: None,
             }),
             chain_id: Some(270),
             ..Default::default()
@@ -0,0 +1,154 @@
+//! Canonical serialization of the transaction execution outcomes.
+//!
+//! External nodes re-execute the transactions of each miniblock and compare the digest of the outcomes
+//! they've computed with the one provided by the main node, so that an execution divergence is detected
+//! at the miniblock where it happens rather than at the L1 batch commitment.
+//!
+//! Since the digests are compared between the nodes running different versions, the serialization must not
+//! depend on the field order or on the serializer settings: outcomes are serialized to JSON with sorted keys,
+//! and the payload is prefixed with the serialization version.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::web3::signing::keccak256;
+use crate::H256;
+
+use super::tx_execution_info::{ExecutionMetrics, TxExecutionStatus};
+use super::TransactionExecutionResult;
+
+/// Version of the outcome serialization. Must be bumped whenever the set of serialized fields
+/// or their encoding changes, so that the nodes don't report divergence for the differently serialized outcomes.
+pub const EXECUTION_OUTCOMES_SERIALIZATION_VERSION: u8 = 1;
+
+#[derive(Debug, Serialize)]
+struct ExecutionOutcome<'a> {
+    tx_hash: H256,
+    status: &'static str,
+    refunded_gas: u32,
+    metrics: &'a ExecutionMetrics,
+}
+
+impl<'a> From<&'a TransactionExecutionResult> for ExecutionOutcome<'a> {
+    fn from(result: &'a TransactionExecutionResult) -> Self {
+        Self {
+            tx_hash: result.hash,
+            status: match result.execution_status {
+                TxExecutionStatus::Success => "success",
+                TxExecutionStatus::Failure => "failure",
+            },
+            refunded_gas: result.refunded_gas,
+            metrics: &result.execution_info,
+        }
+    }
+}
+
+fn serialize_outcomes<'a>(outcomes: impl Iterator<Item = ExecutionOutcome<'a>>) -> Vec<u8> {
+    let outcomes = outcomes
+        .map(|outcome| serde_json::to_value(outcome).expect("cannot serialize execution outcome"))
+        .collect();
+    let mut json = String::new();
+    write_canonical_json(&Value::Array(outcomes), &mut json);
+
+    let mut bytes = Vec::with_capacity(json.len() + 1);
+    bytes.push(EXECUTION_OUTCOMES_SERIALIZATION_VERSION);
+    bytes.extend_from_slice(json.as_bytes());
+    bytes
+}
+
+/// Writes JSON without whitespace and with the object keys sorted.
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key).unwrap());
+                out.push(':');
+                write_canonical_json(item, out);
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&serde_json::to_string(scalar).unwrap()),
+    }
+}
+
+/// Serializes the outcomes of the executed transactions (in the execution order) in the canonical form.
+pub fn serialize_execution_outcomes(results: &[TransactionExecutionResult]) -> Vec<u8> {
+    serialize_outcomes(results.iter().map(ExecutionOutcome::from))
+}
+
+/// Returns the digest of the canonically serialized outcomes of the executed transactions.
+pub fn execution_outcomes_digest(results: &[TransactionExecutionResult]) -> H256 {
+    H256(keccak256(&serialize_execution_outcomes(results)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_json_sorts_keys() {
+        let value = serde_json::json!({
+            "b": [{ "d": 1, "c": null }],
+            "a": "x",
+        });
+        let mut json = String::new();
+        write_canonical_json(&value, &mut json);
+        assert_eq!(json, r#"{"a":"x","b":[{"c":null,"d":1}]}"#);
+    }
+
+    #[test]
+    fn outcomes_serialization_is_stable() {
+        let metrics = ExecutionMetrics {
+            gas_used: 100,
+            cycles_used: 5,
+            ..ExecutionMetrics::default()
+        };
+        let outcome = ExecutionOutcome {
+            tx_hash: H256::repeat_byte(1),
+            status: "success",
+            refunded_gas: 10,
+            metrics: &metrics,
+        };
+        let bytes = serialize_outcomes(std::iter::once(outcome));
+
+        assert_eq!(bytes[0], EXECUTION_OUTCOMES_SERIALIZATION_VERSION);
+        let expected = format!(
+            concat!(
+                r#"[{{"metrics":{{"contracts_deployed":0,"contracts_used":0,"cycles_used":5,"gas_used":100,"#,
+                r#""l2_l1_logs":0,"l2_l1_long_messages":0,"published_bytecode_bytes":0,"storage_logs":0,"#,
+                r#""total_log_queries":0,"vm_events":0}},"refunded_gas":10,"status":"success","tx_hash":"{:?}"}}]"#
+            ),
+            H256::repeat_byte(1)
+        );
+        assert_eq!(std::str::from_utf8(&bytes[1..]).unwrap(), expected);
+    }
+
+    #[test]
+    fn empty_outcomes_digest() {
+        let digest = execution_outcomes_digest(&[]);
+        assert_eq!(
+            digest,
+            H256(keccak256(&[
+                EXECUTION_OUTCOMES_SERIALIZATION_VERSION,
+                b'[',
+                b']'
+            ]))
+        );
+    }
+}
@@ -4,15 +4,19 @@
 //! it makes more sense to define the contents of each transaction chain-agnostic, and extent this data
 //! with metadata (such as fees and/or signatures) for L1 and L2 separately.
 
+use std::collections::BTreeSet;
 use std::fmt::Debug;
 use zksync_basic_types::{Address, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 pub mod execute;
+pub mod execution_digest;
 pub mod primitives;
 pub mod tx_execution_info;
 
 pub use self::execute::Execute;
+use crate::fee::RefundBreakdown;
+use crate::log::{StorageLog, StorageLogKind};
 use crate::Transaction;
 pub use tx_execution_info::ExecutionMetrics;
 use tx_execution_info::TxExecutionStatus;
@@ -25,6 +29,7 @@ pub struct TransactionExecutionResult {
     pub execution_status: TxExecutionStatus,
     pub refunded_gas: u32,
     pub operator_suggested_refund: u32,
+    pub refund_breakdown: RefundBreakdown,
     pub compressed_bytecodes: Vec<CompressedBytecodeInfo>,
 }
 
@@ -34,3 +39,33 @@ pub struct IncludedTxLocation {
     pub tx_index_in_miniblock: u32,
     pub tx_initiator_address: Address,
 }
+
+/// Storage slots read and written by the transaction, identified by the hashed storage keys.
+/// The keys are sorted and deduplicated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxAccessSet {
+    pub tx_hash: H256,
+    pub tx_index_in_miniblock: u32,
+    pub read_keys: Vec<H256>,
+    pub written_keys: Vec<H256>,
+}
+
+impl TxAccessSet {
+    pub fn new(tx_hash: H256, tx_index_in_miniblock: u32, storage_logs: &[StorageLog]) -> Self {
+        let mut read_keys = BTreeSet::new();
+        let mut written_keys = BTreeSet::new();
+        for log in storage_logs {
+            match log.kind {
+                StorageLogKind::Read => read_keys.insert(log.key.hashed_key()),
+                StorageLogKind::Write => written_keys.insert(log.key.hashed_key()),
+            };
+        }
+
+        Self {
+            tx_hash,
+            tx_index_in_miniblock,
+            read_keys: read_keys.into_iter().collect(),
+            written_keys: written_keys.into_iter().collect(),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use crate::{Address, U256};
+use crate::{Address, Bytes, H256, U256};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
 pub struct VmExecutionTrace {
@@ -48,3 +49,254 @@ pub struct VmDebugTrace {
     pub steps: Vec<VmExecutionStep>,
     pub sources: HashMap<Address, Option<ContractSourceDebugInfo>>,
 }
+
+/// Tracers supported by the `debug` namespace. The struct logger is used if no tracer is specified
+/// for `debug_traceTransaction` and `debug_traceBlockByNumber`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SupportedTracer {
+    #[serde(rename = "structLogger")]
+    StructLogger,
+    #[serde(rename = "callTracer")]
+    CallTracer,
+    #[serde(rename = "prestateTracer")]
+    PrestateTracer,
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+    #[serde(rename = "securityTracer")]
+    SecurityTracer,
+}
+
+/// Options of the tracers. Options that are not applicable to the selected tracer are ignored.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TracerConfig {
+    /// Only the top-level call is returned by the `callTracer`.
+    pub only_top_call: bool,
+    /// Logs emitted by the calls are attached to the `callTracer` frames.
+    pub with_log: bool,
+    /// The `securityTracer` fails the call instead of returning the report if it has any findings.
+    pub fail_on_findings: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerOptions {
+    pub tracer: SupportedTracer,
+    #[serde(default)]
+    pub tracer_config: TracerConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CallType {
+    Call,
+    DelegateCall,
+    StaticCall,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CallLog {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+/// Call frame in the format of the geth `callTracer`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub call_type: CallType,
+    pub from: Address,
+    pub to: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    pub gas: U256,
+    pub gas_used: U256,
+    pub input: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<CallLog>,
+}
+
+/// State of the account before the execution in the format of the geth `prestateTracer`.
+/// Only the fields that were accessed during the execution are present.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct PrestateAccount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// Bytecode of the contract; absent for the accounts without code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// Kind of the state change made in a frame.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StateChangeKind {
+    StorageWrite,
+    Event,
+    L1Message,
+}
+
+/// State change attempted in the static call context.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticCallViolation {
+    /// Contract in the context of which the change is attempted.
+    pub address: Address,
+    pub kind: StateChangeKind,
+    /// Storage slot for the storage writes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<H256>,
+}
+
+/// Contract re-entered while its earlier frame is still being executed.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReentrancyCycle {
+    pub address: Address,
+    /// Type of the call re-entering the contract; static calls correspond to the read-only reentrancy.
+    pub call_type: CallType,
+    /// Contexts of the calls from the first frame of the contract to the re-entering one, system contracts excluded.
+    pub path: Vec<Address>,
+}
+
+/// Findings of the `securityTracer`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityReport {
+    pub static_call_violations: Vec<StaticCallViolation>,
+    pub reentrancy_cycles: Vec<ReentrancyCycle>,
+}
+
+impl SecurityReport {
+    pub fn is_empty(&self) -> bool {
+        self.static_call_violations.is_empty() && self.reentrancy_cycles.is_empty()
+    }
+}
+
+/// Executed opcode in the format of the geth struct logger. zkEVM is a register machine,
+/// so the stack and the memory are not reported.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u16,
+    /// Name of the opcode. The names are shared by the logs rather than allocated for each of them.
+    pub op: Cow<'static, str>,
+    /// Gas remaining in the frame before the opcode.
+    pub gas: u32,
+    pub gas_cost: u32,
+    /// Depth of the frame, starting with 1 for the top-level call.
+    pub depth: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Trace of the struct logger, the default tracer of geth.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogTrace {
+    pub gas: U256,
+    pub failed: bool,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// Result of the `debug` namespace tracing methods; its format depends on the selected tracer.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum DebugTraceResult {
+    CallTrace(CallFrame),
+    PrestateTrace(BTreeMap<Address, PrestateAccount>),
+    /// Number of calls for each `<selector>-<calldata size without selector>` pair.
+    FourByteTrace(BTreeMap<String, u64>),
+    SecurityTrace(SecurityReport),
+    StructLogTrace(StructLogTrace),
+}
+
+/// Trace of a transaction in the block, returned by `debug_traceBlockByNumber`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxTraceResult {
+    pub tx_hash: H256,
+    pub result: DebugTraceResult,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracer_options_deserialization() {
+        let options: TracerOptions = serde_json::from_str(
+            r#"{ "tracer": "callTracer", "tracerConfig": { "onlyTopCall": true } }"#,
+        )
+        .unwrap();
+        assert_eq!(options.tracer, SupportedTracer::CallTracer);
+        assert!(options.tracer_config.only_top_call);
+        assert!(!options.tracer_config.with_log);
+
+        let options: TracerOptions =
+            serde_json::from_str(r#"{ "tracer": "4byteTracer" }"#).unwrap();
+        assert_eq!(options.tracer, SupportedTracer::FourByteTracer);
+        assert_eq!(options.tracer_config, TracerConfig::default());
+
+        let options: TracerOptions = serde_json::from_str(
+            r#"{ "tracer": "securityTracer", "tracerConfig": { "failOnFindings": true } }"#,
+        )
+        .unwrap();
+        assert_eq!(options.tracer, SupportedTracer::SecurityTracer);
+        assert!(options.tracer_config.fail_on_findings);
+    }
+
+    #[test]
+    fn security_trace_serialization() {
+        let report = SecurityReport {
+            static_call_violations: vec![StaticCallViolation {
+                address: Address::repeat_byte(1),
+                kind: StateChangeKind::StorageWrite,
+                key: Some(H256::zero()),
+            }],
+            reentrancy_cycles: vec![],
+        };
+        let json = serde_json::to_value(DebugTraceResult::SecurityTrace(report.clone())).unwrap();
+        assert_eq!(json["staticCallViolations"][0]["kind"], "storageWrite");
+        let result: DebugTraceResult = serde_json::from_value(json).unwrap();
+        assert_eq!(result, DebugTraceResult::SecurityTrace(report));
+    }
+
+    #[test]
+    fn struct_log_trace_serialization() {
+        let trace = StructLogTrace {
+            gas: 21_000.into(),
+            failed: false,
+            return_value: Bytes(vec![]),
+            struct_logs: vec![StructLog {
+                pc: 0,
+                op: "Add".into(),
+                gas: 1_000,
+                gas_cost: 6,
+                depth: 1,
+                error: None,
+            }],
+        };
+        let json = serde_json::to_value(DebugTraceResult::StructLogTrace(trace.clone())).unwrap();
+        assert_eq!(json["returnValue"], "0x");
+        assert_eq!(json["structLogs"][0]["gasCost"], 6);
+        assert!(json["structLogs"][0].get("error").is_none());
+        let result: DebugTraceResult = serde_json::from_value(json).unwrap();
+        assert_eq!(result, DebugTraceResult::StructLogTrace(trace));
+    }
+}
@@ -0,0 +1,186 @@
+//! Scoped logging contexts.
+//!
+//! A context carries identifiers of the entity being processed (L1 batch, miniblock, circuit)
+//! and attaches them to every log line emitted while the context is active:
+//!
+//! ```ignore
+//! let _guard = vlog::with_batch(batch_number).with_circuit(circuit_id).enter();
+//! vlog::info!("Started processing"); // logged with `l1_batch` and `circuit` fields
+//! ```
+//!
+//! Contexts are backed by `tracing` spans, so they nest, survive `.await` points when used with
+//! [`LogContext::instrument`] and are exported to OpenTelemetry together with the rest of the span data.
+//! The currently active context can be retrieved with [`current`], e.g. to label metrics.
+
+use std::future::Future;
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{instrument::Instrumented, Instrument, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+const CONTEXT_SPAN_NAME: &str = "vlog_context";
+
+/// Identifiers attached to the log lines emitted within a scope.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LogContext {
+    pub l1_batch: Option<u32>,
+    pub miniblock: Option<u32>,
+    pub circuit: Option<u32>,
+}
+
+/// Starts a logging context for the given L1 batch.
+pub fn with_batch(l1_batch: u32) -> LogContext {
+    LogContext::default().with_batch(l1_batch)
+}
+
+/// Starts a logging context for the given miniblock.
+pub fn with_miniblock(miniblock: u32) -> LogContext {
+    LogContext::default().with_miniblock(miniblock)
+}
+
+/// Starts a logging context for the given circuit.
+pub fn with_circuit(circuit: u32) -> LogContext {
+    LogContext::default().with_circuit(circuit)
+}
+
+impl LogContext {
+    pub fn with_batch(mut self, l1_batch: u32) -> Self {
+        self.l1_batch = Some(l1_batch);
+        self
+    }
+
+    pub fn with_miniblock(mut self, miniblock: u32) -> Self {
+        self.miniblock = Some(miniblock);
+        self
+    }
+
+    pub fn with_circuit(mut self, circuit: u32) -> Self {
+        self.circuit = Some(circuit);
+        self
+    }
+
+    /// Creates a span carrying the context fields. Fields that are not set are left empty
+    /// and are not shown in the logs.
+    pub fn span(&self) -> tracing::Span {
+        let span = tracing::info_span!(
+            CONTEXT_SPAN_NAME,
+            l1_batch = tracing::field::Empty,
+            miniblock = tracing::field::Empty,
+            circuit = tracing::field::Empty,
+        );
+        if let Some(l1_batch) = self.l1_batch {
+            span.record("l1_batch", l1_batch);
+        }
+        if let Some(miniblock) = self.miniblock {
+            span.record("miniblock", miniblock);
+        }
+        if let Some(circuit) = self.circuit {
+            span.record("circuit", circuit);
+        }
+        span
+    }
+
+    /// Activates the context until the returned guard is dropped.
+    /// Must not be held across `.await` points; use [`Self::instrument`] for futures instead.
+    pub fn enter(&self) -> tracing::span::EnteredSpan {
+        self.span().entered()
+    }
+
+    /// Runs the closure with the context activated.
+    pub fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.span().in_scope(f)
+    }
+
+    /// Activates the context every time the future is polled.
+    pub fn instrument<F: Future>(&self, future: F) -> Instrumented<F> {
+        future.instrument(self.span())
+    }
+
+    /// Context fields as key-value pairs, suitable to be used as metric labels.
+    pub fn labels(&self) -> Vec<(&'static str, String)> {
+        [
+            ("l1_batch", self.l1_batch),
+            ("miniblock", self.miniblock),
+            ("circuit", self.circuit),
+        ]
+        .iter()
+        .filter_map(|(key, value)| value.map(|value| (*key, value.to_string())))
+        .collect()
+    }
+
+    /// Overrides the fields of `self` with the fields set in `inner`.
+    fn merge(self, inner: Self) -> Self {
+        Self {
+            l1_batch: inner.l1_batch.or(self.l1_batch),
+            miniblock: inner.miniblock.or(self.miniblock),
+            circuit: inner.circuit.or(self.circuit),
+        }
+    }
+}
+
+impl Visit for LogContext {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        let value = Some(value as u32);
+        match field.name() {
+            "l1_batch" => self.l1_batch = value,
+            "miniblock" => self.miniblock = value,
+            "circuit" => self.circuit = value,
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// Returns the context combined from all the contexts active at the moment.
+/// Returns an empty context if logging was not initialized with [`crate::init`].
+pub fn current() -> LogContext {
+    let mut context = LogContext::default();
+    tracing::Span::current().with_subscriber(|(id, dispatch)| {
+        let registry = match dispatch.downcast_ref::<tracing_subscriber::Registry>() {
+            Some(registry) => registry,
+            None => return,
+        };
+        if let Some(span) = registry.span(id) {
+            for span in span.scope().from_root() {
+                if let Some(span_context) = span.extensions().get::<LogContext>() {
+                    context = context.merge(*span_context);
+                }
+            }
+        }
+    });
+    context
+}
+
+/// Layer storing the context in the extensions of the context spans, so that it can be
+/// retrieved by [`current`].
+pub(crate) struct ContextLayer;
+
+impl<S> Layer<S> for ContextLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != CONTEXT_SPAN_NAME {
+            return;
+        }
+        let mut context = LogContext::default();
+        attrs.record(&mut context);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(context);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &tracing::span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if span.metadata().name() != CONTEXT_SPAN_NAME {
+                return;
+            }
+            if let Some(context) = span.extensions_mut().get_mut::<LogContext>() {
+                values.record(context);
+            }
+        }
+    }
+}
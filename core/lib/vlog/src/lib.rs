@@ -11,6 +11,9 @@
 //! Integration with sentry for catching errors and react on them immediately
 //! https://docs.sentry.io/platforms/rust/
 //!
+//! Log lines can be enriched with the identifiers of the processed entities (L1 batch, miniblock, circuit)
+//! by using scoped contexts, see the [`context`] module.
+//!
 
 use std::{borrow::Cow, str::FromStr};
 
@@ -29,6 +32,10 @@ pub use sentry as __sentry;
 pub use tracing as __tracing;
 pub use tracing::{debug, info, log, trace};
 
+pub mod context;
+
+pub use context::{with_batch, with_circuit, with_miniblock, LogContext};
+
 fn get_trace_id() -> TraceId {
     let span = tracing::span::Span::current();
     span.context().span().span_context().trace_id()
@@ -213,12 +220,14 @@ pub fn init() -> Option<ClientInitGuard> {
         "plain" => {
             if let Some(opentelemetry) = opentelemetry {
                 tracing_subscriber::registry()
+                    .with(context::ContextLayer)
                     .with(opentelemetry)
                     .with(fmt::Layer::default())
                     .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             } else {
                 tracing_subscriber::registry()
+                    .with(context::ContextLayer)
                     .with(fmt::Layer::default())
                     .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
@@ -230,12 +239,14 @@ pub fn init() -> Option<ClientInitGuard> {
             install_pretty_panic_hook();
             if let Some(opentelemetry) = opentelemetry {
                 tracing_subscriber::registry()
+                    .with(context::ContextLayer)
                     .with(opentelemetry)
                     .with(fmt::Layer::default().with_timer(timer).json())
                     .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
             } else {
                 tracing_subscriber::registry()
+                    .with(context::ContextLayer)
                     .with(fmt::Layer::default().with_timer(timer).json())
                     .with(tracing_subscriber::EnvFilter::from_default_env())
                     .init();
@@ -1,7 +1,7 @@
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display};
 
-use zksync_types::U256;
+use zksync_types::{ethabi, U256};
 
 use crate::TxRevertReason;
 
@@ -21,6 +21,10 @@ pub enum VmRevertReason {
     General {
         msg: String,
     },
+    /// Solidity `Panic(uint256)` raised by a failed assertion or a runtime check.
+    Panic {
+        code: U256,
+    },
     InnerTxError,
     VmError,
     Unknown {
@@ -31,6 +35,50 @@ pub enum VmRevertReason {
 
 impl VmRevertReason {
     const GENERAL_ERROR_SELECTOR: &'static [u8] = &[0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: &'static [u8] = &[0x4e, 0x48, 0x7b, 0x71];
+
+    /// Returns the revert data as returned by the contract, i.e. `Error(string)` for the general errors
+    /// and the selector followed by the data for the custom ones. Reasons not produced by the contract
+    /// have no data.
+    pub fn encoded_message(&self) -> Vec<u8> {
+        match self {
+            Self::General { msg } => {
+                let mut encoded = Self::GENERAL_ERROR_SELECTOR.to_vec();
+                encoded.extend(ethabi::encode(&[ethabi::Token::String(msg.clone())]));
+                encoded
+            }
+            Self::Panic { code } => {
+                let mut encoded = Self::PANIC_SELECTOR.to_vec();
+                encoded.extend(ethabi::encode(&[ethabi::Token::Uint(*code)]));
+                encoded
+            }
+            Self::Unknown {
+                function_selector,
+                data,
+            } => [function_selector.as_slice(), data].concat(),
+            Self::InnerTxError | Self::VmError => vec![],
+        }
+    }
+
+    /// Returns the description of the Solidity panic code, the same as reported by Geth.
+    fn panic_reason(code: U256) -> Option<&'static str> {
+        if code > U256::from(u8::MAX) {
+            return None;
+        }
+        Some(match code.low_u64() {
+            0x00 => "generic panic",
+            0x01 => "assert(false)",
+            0x11 => "arithmetic underflow or overflow",
+            0x12 => "division or modulo by zero",
+            0x21 => "enum overflow",
+            0x22 => "invalid encoded storage byte array accessed",
+            0x31 => "out-of-bounds array access; popping on an empty array",
+            0x32 => "out-of-bounds access of an array or bytesN",
+            0x41 => "out of memory",
+            0x51 => "uninitialized function",
+            _ => return None,
+        })
+    }
 
     fn parse_general_error(bytes: &[u8]) -> Result<Self, VmRevertReasonParsingError> {
         if bytes.len() < 32 {
@@ -94,6 +142,9 @@ impl TryFrom<&[u8]> for VmRevertReason {
         let error_data = &bytes[4..];
         match function_selector {
             VmRevertReason::GENERAL_ERROR_SELECTOR => Self::parse_general_error(error_data),
+            VmRevertReason::PANIC_SELECTOR if error_data.len() == 32 => Ok(Self::Panic {
+                code: U256::from_big_endian(error_data),
+            }),
             _ => {
                 let result = VmRevertReason::Unknown {
                     function_selector: function_selector.to_vec(),
@@ -108,10 +159,14 @@ impl TryFrom<&[u8]> for VmRevertReason {
 
 impl Display for VmRevertReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use VmRevertReason::{General, InnerTxError, Unknown, VmError};
+        use VmRevertReason::{General, InnerTxError, Panic, Unknown, VmError};
 
         match self {
             General { msg } => write!(f, "{}", msg),
+            Panic { code } => match Self::panic_reason(*code) {
+                Some(reason) => write!(f, "{}", reason),
+                None => write!(f, "unknown panic code: {:#x}", code),
+            },
             VmError => write!(f, "VM Error",),
             InnerTxError => write!(f, "Bootloader-based tx failed"),
             Unknown {
@@ -168,6 +223,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn encoding_revert_reasons() {
+        let reason = VmRevertReason::General {
+            msg: "ERC20: transfer amount exceeds balance".to_string(),
+        };
+        let encoded = reason.encoded_message();
+        assert_eq!(encoded[..4], [0x08, 0xc3, 0x79, 0xa0]);
+        assert_eq!(
+            VmRevertReason::try_from(encoded.as_slice()).unwrap(),
+            reason
+        );
+
+        let reason = VmRevertReason::Unknown {
+            function_selector: vec![1, 2, 3, 4],
+            data: vec![5],
+        };
+        assert_eq!(reason.encoded_message(), [1, 2, 3, 4, 5]);
+        assert!(VmRevertReason::VmError.encoded_message().is_empty());
+    }
+
+    #[test]
+    fn panic_revert_reasons() {
+        let reason = VmRevertReason::Panic { code: 0x11.into() };
+        let encoded = reason.encoded_message();
+        assert_eq!(encoded.len(), 36);
+        assert_eq!(
+            VmRevertReason::try_from(encoded.as_slice()).unwrap(),
+            reason
+        );
+        assert_eq!(reason.to_string(), "arithmetic underflow or overflow");
+
+        let reason = VmRevertReason::Panic { code: 0x99.into() };
+        assert_eq!(reason.to_string(), "unknown panic code: 0x99");
+    }
+
     #[test]
     fn revert_reason_with_wrong_function_selector() {
         let msg = vec![
@@ -4,14 +4,17 @@ use zk_evm::vm_state::VmLocalState;
 
 mod bootloader;
 mod one_tx;
+mod plugin;
 mod transaction_result;
 mod utils;
 mod validation;
 
 pub use bootloader::BootloaderTracer;
 pub use one_tx::OneTxTracer;
-pub use validation::{ValidationError, ValidationTracer, ValidationTracerParams};
+pub use plugin::{far_call_calldata, far_ret_returndata, TracerPlugin};
+pub use validation::{ValidationError, ValidationReport, ValidationTracer, ValidationTracerParams};
 
+pub(crate) use plugin::TracerWithPlugins;
 pub(crate) use transaction_result::TransactionResultTracer;
 
 pub trait ExecutionEndTracer: Tracer<SupportedMemory = SimpleMemory> {
@@ -0,0 +1,183 @@
+//! Plugin interface for the tracers that only observe the execution.
+//!
+//! Plugins are invoked alongside the tracer that drives the VM (e.g. the one that decides
+//! when the execution should stop), so new tracers can be added outside of the VM crate
+//! without affecting how the VM itself is run.
+
+use zk_evm::{
+    abstractions::{
+        AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
+    },
+    vm_state::VmLocalState,
+    witness_trace::VmWitnessTracer,
+    zkevm_opcode_defs::{decoding::VmEncodingMode, FarCallABI, FatPointer, Opcode},
+};
+
+use crate::memory::SimpleMemory;
+use crate::oracles::tracer::utils::{get_calldata_page_via_abi, read_pointer};
+use crate::oracles::tracer::{ExecutionEndTracer, PendingRefundTracer, PubdataSpentTracer};
+
+/// Observer of the VM execution. Plugins can't affect the execution in any way.
+pub trait TracerPlugin {
+    fn before_execution(
+        &mut self,
+        _state: &VmLocalStateData<'_>,
+        _data: &BeforeExecutionData,
+        _memory: &SimpleMemory,
+    ) {
+    }
+
+    fn after_execution(
+        &mut self,
+        _state: &VmLocalStateData<'_>,
+        _data: &AfterExecutionData,
+        _memory: &SimpleMemory,
+    ) {
+    }
+}
+
+/// Reads the calldata passed to the `far_call` opcode that is about to be executed.
+/// Must be called from `TracerPlugin::before_execution`.
+pub fn far_call_calldata(
+    state: &VmLocalStateData<'_>,
+    data: &BeforeExecutionData,
+    memory: &SimpleMemory,
+) -> Vec<u8> {
+    debug_assert!(matches!(data.opcode.variant.opcode, Opcode::FarCall(_)));
+
+    let far_call_abi = FarCallABI::from_u256(data.src0_value.value);
+    let calldata_page = get_calldata_page_via_abi(
+        &far_call_abi,
+        state.vm_local_state.callstack.current.base_memory_page,
+    );
+    let pointer = far_call_abi.memory_quasi_fat_pointer;
+    memory.read_unaligned_bytes(
+        calldata_page as usize,
+        pointer.start as usize,
+        pointer.length as usize,
+    )
+}
+
+/// Reads the returndata of the `ret` opcode that is about to be executed from a far frame.
+/// Must be called from `TracerPlugin::before_execution`.
+pub fn far_ret_returndata(
+    state: &VmLocalStateData<'_>,
+    data: &BeforeExecutionData,
+    memory: &SimpleMemory,
+) -> Vec<u8> {
+    debug_assert!(matches!(data.opcode.variant.opcode, Opcode::Ret(_)));
+
+    // The `ret` ABI shares the layout of the memory pointer and the forwarding mode with the `far_call` one.
+    let ret_abi = FarCallABI::from_u256(data.src0_value.value);
+    let page = get_calldata_page_via_abi(
+        &ret_abi,
+        state.vm_local_state.callstack.current.base_memory_page,
+    );
+    read_pointer(
+        memory,
+        FatPointer {
+            memory_page: page,
+            ..ret_abi.memory_quasi_fat_pointer
+        },
+    )
+}
+
+/// Runs the plugins together with the tracer that drives the execution.
+pub(crate) struct TracerWithPlugins<'a, 'p, T> {
+    pub(crate) inner: T,
+    plugins: &'a mut [&'p mut dyn TracerPlugin],
+}
+
+impl<'a, 'p, T> TracerWithPlugins<'a, 'p, T> {
+    pub(crate) fn new(inner: T, plugins: &'a mut [&'p mut dyn TracerPlugin]) -> Self {
+        Self { inner, plugins }
+    }
+}
+
+impl<T> std::fmt::Debug for TracerWithPlugins<'_, '_, T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TracerWithPlugins")
+            .field("inner", &self.inner)
+            .field("plugins", &self.plugins.len())
+            .finish()
+    }
+}
+
+impl<const N: usize, E: VmEncodingMode<N>, T> VmWitnessTracer<N, E>
+    for TracerWithPlugins<'_, '_, T>
+{
+}
+
+impl<T: Tracer<SupportedMemory = SimpleMemory>> Tracer for TracerWithPlugins<'_, '_, T> {
+    const CALL_BEFORE_DECODING: bool = T::CALL_BEFORE_DECODING;
+    const CALL_AFTER_DECODING: bool = T::CALL_AFTER_DECODING;
+    const CALL_BEFORE_EXECUTION: bool = true;
+    const CALL_AFTER_EXECUTION: bool = true;
+    type SupportedMemory = SimpleMemory;
+
+    fn before_decoding(&mut self, state: VmLocalStateData<'_>, memory: &Self::SupportedMemory) {
+        self.inner.before_decoding(state, memory);
+    }
+
+    fn after_decoding(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: AfterDecodingData,
+        memory: &Self::SupportedMemory,
+    ) {
+        self.inner.after_decoding(state, data, memory);
+    }
+
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        memory: &Self::SupportedMemory,
+    ) {
+        for plugin in self.plugins.iter_mut() {
+            plugin.before_execution(&state, &data, memory);
+        }
+        if T::CALL_BEFORE_EXECUTION {
+            self.inner.before_execution(state, data, memory);
+        }
+    }
+
+    fn after_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: AfterExecutionData,
+        memory: &Self::SupportedMemory,
+    ) {
+        for plugin in self.plugins.iter_mut() {
+            plugin.after_execution(&state, &data, memory);
+        }
+        if T::CALL_AFTER_EXECUTION {
+            self.inner.after_execution(state, data, memory);
+        }
+    }
+}
+
+impl<T: ExecutionEndTracer> ExecutionEndTracer for TracerWithPlugins<'_, '_, T> {
+    fn should_stop_execution(&self) -> bool {
+        self.inner.should_stop_execution()
+    }
+}
+
+impl<T: PendingRefundTracer> PendingRefundTracer for TracerWithPlugins<'_, '_, T> {
+    fn requested_refund(&self) -> Option<u32> {
+        self.inner.requested_refund()
+    }
+
+    fn set_refund_as_done(&mut self) {
+        self.inner.set_refund_as_done();
+    }
+}
+
+impl<T: PubdataSpentTracer> PubdataSpentTracer for TracerWithPlugins<'_, '_, T> {
+    fn gas_spent_on_pubdata(&self, vm_local_state: &VmLocalState) -> u32 {
+        self.inner.gas_spent_on_pubdata(vm_local_state)
+    }
+}
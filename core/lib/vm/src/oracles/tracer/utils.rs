@@ -1,11 +1,14 @@
 use crate::memory::SimpleMemory;
-use crate::utils::heap_page_from_base;
+use crate::utils::{aux_heap_page_from_base, heap_page_from_base};
 use crate::vm::{get_vm_hook_params, VM_HOOK_POSITION};
 use crate::vm_with_bootloader::BOOTLOADER_HEAP_PAGE;
 
 use zk_evm::{
     abstractions::{BeforeExecutionData, VmLocalStateData},
-    zkevm_opcode_defs::{FatPointer, LogOpcode, Opcode, UMAOpcode},
+    aux_structures::MemoryPage,
+    zkevm_opcode_defs::{
+        FarCallABI, FarCallForwardPageType, FatPointer, LogOpcode, Opcode, UMAOpcode,
+    },
 };
 use zksync_config::constants::{
     ECRECOVER_PRECOMPILE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
@@ -112,6 +115,17 @@ pub(crate) fn read_pointer(memory: &SimpleMemory, pointer: FatPointer) -> Vec<u8
     )
 }
 
+/// Returns the memory page the calldata of the far call is located in.
+pub(crate) fn get_calldata_page_via_abi(far_call_abi: &FarCallABI, base_page: MemoryPage) -> u32 {
+    match far_call_abi.forwarding_mode {
+        FarCallForwardPageType::ForwardFatPointer => {
+            far_call_abi.memory_quasi_fat_pointer.memory_page
+        }
+        FarCallForwardPageType::UseAuxHeap => aux_heap_page_from_base(base_page).0,
+        FarCallForwardPageType::UseHeap => heap_page_from_base(base_page).0,
+    }
+}
+
 /// Outputs the returndata for the latest call.
 /// This is usually used to output the revert reason.
 pub(crate) fn get_debug_returndata(memory: &SimpleMemory) -> String {
@@ -6,18 +6,18 @@ use crate::{
     errors::VmRevertReasonParsingResult,
     memory::SimpleMemory,
     oracles::tracer::{
-        utils::{computational_gas_price, print_debug_if_needed, VmHook},
+        utils::{
+            computational_gas_price, get_calldata_page_via_abi, print_debug_if_needed, VmHook,
+        },
         ExecutionEndTracer, PendingRefundTracer, PubdataSpentTracer,
     },
-    utils::{aux_heap_page_from_base, heap_page_from_base},
 };
 
 use zk_evm::{
     abstractions::{
         AfterDecodingData, AfterExecutionData, BeforeExecutionData, Tracer, VmLocalStateData,
     },
-    aux_structures::MemoryPage,
-    zkevm_opcode_defs::{ContextOpcode, FarCallABI, FarCallForwardPageType, LogOpcode, Opcode},
+    zkevm_opcode_defs::{ContextOpcode, FarCallABI, LogOpcode, Opcode},
 };
 
 use crate::storage::StoragePtr;
@@ -57,6 +57,18 @@ pub enum ValidationError {
     VioalatedRule(ViolatedValidationRule),
 }
 
+/// Outcome of the validation step together with the resources it has used.
+pub struct ValidationReport {
+    pub result: Result<(), ValidationError>,
+    /// Gas spent by the bootloader since the transaction was pushed to its memory, including
+    /// the account and paymaster validation.
+    pub gas_used: u32,
+    /// Computational gas spent by the account validation, limited by the validation tracer.
+    pub computational_gas_used: u32,
+    /// Context returned by the paymaster validation. Empty if there is no paymaster.
+    pub paymaster_context: Vec<u8>,
+}
+
 impl Display for ViolatedValidationRule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -207,6 +219,10 @@ impl<'a> ValidationTracer<'a> {
         }
     }
 
+    pub fn computational_gas_used(&self) -> u32 {
+        self.computational_gas_used
+    }
+
     fn process_validation_round_result(&mut self, result: ValidationRoundResult) {
         match result {
             Ok(NewTrustedValidationItems {
@@ -467,16 +483,6 @@ impl Tracer for ValidationTracer<'_> {
     }
 }
 
-fn get_calldata_page_via_abi(far_call_abi: &FarCallABI, base_page: MemoryPage) -> u32 {
-    match far_call_abi.forwarding_mode {
-        FarCallForwardPageType::ForwardFatPointer => {
-            far_call_abi.memory_quasi_fat_pointer.memory_page
-        }
-        FarCallForwardPageType::UseAuxHeap => aux_heap_page_from_base(base_page).0,
-        FarCallForwardPageType::UseHeap => heap_page_from_base(base_page).0,
-    }
-}
-
 impl ExecutionEndTracer for ValidationTracer<'_> {
     fn should_stop_execution(&self) -> bool {
         self.should_stop_execution || self.validation_error.is_some()
@@ -7,11 +7,12 @@ use zk_evm::witness_trace::DummyTracer;
 use zk_evm::zkevm_opcode_defs::decoding::{AllowedPcOrImm, EncodingModeProduction, VmEncodingMode};
 use zk_evm::zkevm_opcode_defs::definitions::RET_IMPLICIT_RETURNDATA_PARAMS_REGISTER;
 use zksync_config::constants::MAX_TXS_IN_BLOCK;
+use zksync_types::fee::RefundBreakdown;
 use zksync_types::l2_to_l1_log::L2ToL1Log;
 use zksync_types::tx::tx_execution_info::{TxExecutionStatus, VmExecutionLogs};
 use zksync_types::vm_trace::VmExecutionTrace;
 use zksync_types::{L1BatchNumber, StorageLogQuery, VmEvent, U256};
-use zksync_utils::bytes_to_be_words;
+use zksync_utils::{be_words_to_bytes, bytes_to_be_words};
 
 use crate::bootloader_state::BootloaderState;
 use crate::errors::{TxRevertReason, VmRevertReason, VmRevertReasonParsingResult};
@@ -23,7 +24,8 @@ use crate::oracles::precompile::PrecompilesProcessorWithHistory;
 use crate::oracles::storage::StorageOracle;
 use crate::oracles::tracer::{
     BootloaderTracer, ExecutionEndTracer, OneTxTracer, PendingRefundTracer, PubdataSpentTracer,
-    TransactionResultTracer, ValidationError, ValidationTracer, ValidationTracerParams,
+    TracerPlugin, TracerWithPlugins, TransactionResultTracer, ValidationError, ValidationReport,
+    ValidationTracer, ValidationTracerParams,
 };
 use crate::oracles::OracleWithHistory;
 use crate::utils::{
@@ -32,7 +34,7 @@ use crate::utils::{
 };
 use crate::vm_with_bootloader::{
     BootloaderJobType, DerivedBlockContext, TxExecutionMode, BOOTLOADER_HEAP_PAGE,
-    OPERATOR_REFUNDS_OFFSET,
+    OPERATOR_REFUNDS_OFFSET, PAYMASTER_CONTEXT_OFFSET, PAYMASTER_CONTEXT_SLOTS,
 };
 use crate::Word;
 
@@ -139,6 +141,8 @@ pub struct VmTxExecutionResult {
     // Gas proposed by the operator to be refunded, before the postOp call.
     // This value is needed to correctly recover memory of the bootloader.
     pub operator_suggested_refund: u32,
+    // Components of the refund proposed by the operator.
+    pub refund_breakdown: RefundBreakdown,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -493,14 +497,14 @@ impl<'a> VmInstance<'a> {
         }
     }
 
-    // Returns a tuple of `VmExecutionStopReason` and the size of the refund proposed by the operator
+    // Returns a tuple of `VmExecutionStopReason` and the components of the refund proposed by the operator
     fn execute_with_custom_tracer_and_refunds<
         T: ExecutionEndTracer + PendingRefundTracer + PubdataSpentTracer,
     >(
         &mut self,
         tracer: &mut T,
-    ) -> (VmExecutionStopReason, u32) {
-        let mut operator_refund = None;
+    ) -> (VmExecutionStopReason, RefundBreakdown) {
+        let mut operator_refund: Option<RefundBreakdown> = None;
         let timestamp_initial = Timestamp(self.state.local_state.timestamp);
         let gas_remaining_before = self.gas_remaining();
         let spent_pubdata_counter_before = self.state.local_state.spent_pubdata_counter;
@@ -543,12 +547,14 @@ impl<'a> VmInstance<'a> {
                     );
                 }
 
-                let refund_to_propose = tx_body_refund
-                    + self.block_overhead_refund(
-                        timestamp_initial,
-                        gas_remaining_before,
-                        gas_spent_on_pubdata,
-                    );
+                let overhead_refund = self.block_overhead_refund(
+                    timestamp_initial,
+                    gas_remaining_before,
+                    gas_spent_on_pubdata,
+                );
+                let refund_breakdown =
+                    RefundBreakdown::new(bootloader_refund, tx_body_refund, overhead_refund);
+                let refund_to_propose = refund_breakdown.operator_suggested_refund();
 
                 let current_tx_index = self.bootloader_state.tx_to_execute() - 1;
                 let refund_slot = OPERATOR_REFUNDS_OFFSET + current_tx_index;
@@ -563,7 +569,7 @@ impl<'a> VmInstance<'a> {
                     }),
                     Timestamp(timestamp_before_cycle),
                 );
-                operator_refund = Some(refund_to_propose);
+                operator_refund = Some(refund_breakdown);
                 tracer.set_refund_as_done();
 
                 let tx_gas_limit = self.get_tx_gas_limit(current_tx_index);
@@ -618,15 +624,29 @@ impl<'a> VmInstance<'a> {
     pub fn execute_next_tx(
         &mut self,
         validation_computational_gas_limit: u32,
+    ) -> Result<VmTxExecutionResult, TxRevertReason> {
+        self.execute_next_tx_with_plugins(validation_computational_gas_limit, &mut [])
+    }
+
+    /// Same as `execute_next_tx`, but additionally runs the provided tracer plugins.
+    /// The plugins only observe the execution of the next transaction.
+    pub fn execute_next_tx_with_plugins(
+        &mut self,
+        validation_computational_gas_limit: u32,
+        plugins: &mut [&mut dyn TracerPlugin],
     ) -> Result<VmTxExecutionResult, TxRevertReason> {
         let tx_index = self.bootloader_state.next_unexecuted_tx() as u32;
-        let mut tx_tracer = OneTxTracer::new(validation_computational_gas_limit);
+        let mut tracer = TracerWithPlugins::new(
+            OneTxTracer::new(validation_computational_gas_limit),
+            plugins,
+        );
 
         let timestamp_initial = Timestamp(self.state.local_state.timestamp);
         let cycles_initial = self.state.local_state.monotonic_cycle_counter;
 
-        let (stop_reason, operator_suggested_refund) =
-            self.execute_with_custom_tracer_and_refunds(&mut tx_tracer);
+        let (stop_reason, refund_breakdown) =
+            self.execute_with_custom_tracer_and_refunds(&mut tracer);
+        let tx_tracer = tracer.inner;
         match stop_reason {
             VmExecutionStopReason::VmFinished => {
                 // Bootloader resulted in panic or revert, this means either the transaction is rejected
@@ -653,7 +673,8 @@ impl<'a> VmInstance<'a> {
 
                     Ok(VmTxExecutionResult {
                         gas_refunded: tx_tracer.refund_gas,
-                        operator_suggested_refund,
+                        operator_suggested_refund: refund_breakdown.operator_suggested_refund(),
+                        refund_breakdown,
                         status: tx_execution_status,
                         result: VmPartialExecutionResult {
                             logs: vm_execution_logs,
@@ -691,12 +712,22 @@ impl<'a> VmInstance<'a> {
 
     /// Returns full VM result and partial result produced within the current execution.
     pub fn execute_till_block_end(&mut self, job_type: BootloaderJobType) -> VmBlockResult {
+        self.execute_till_block_end_with_plugins(job_type, &mut [])
+    }
+
+    /// Same as `execute_till_block_end`, but additionally runs the provided tracer plugins.
+    pub fn execute_till_block_end_with_plugins(
+        &mut self,
+        job_type: BootloaderJobType,
+        plugins: &mut [&mut dyn TracerPlugin],
+    ) -> VmBlockResult {
         let timestamp_initial = Timestamp(self.state.local_state.timestamp);
         let cycles_initial = self.state.local_state.monotonic_cycle_counter;
         let gas_before = self.gas_remaining();
 
-        let mut tx_result_tracer = TransactionResultTracer::default();
-        let stop_reason = self.execute_with_custom_tracer(&mut tx_result_tracer);
+        let mut tracer = TracerWithPlugins::new(TransactionResultTracer::default(), plugins);
+        let stop_reason = self.execute_with_custom_tracer(&mut tracer);
+        let tx_result_tracer = tracer.inner;
         match stop_reason {
             VmExecutionStopReason::VmFinished => {
                 let mut full_result = vm_may_have_ended(self, gas_before).unwrap();
@@ -800,14 +831,25 @@ impl<'a> VmInstance<'a> {
         &mut self,
         validation_params: ValidationTracerParams,
     ) -> Result<(), ValidationError> {
+        self.execute_validation_with_report(validation_params)
+            .result
+    }
+
+    /// Same as `execute_validation`, but also reports the gas used by the validation and the paymaster context.
+    pub fn execute_validation_with_report(
+        &mut self,
+        validation_params: ValidationTracerParams,
+    ) -> ValidationReport {
+        let gas_consumed_before = self.gas_consumed();
         let mut validation_tracer = ValidationTracer::new(
             self.state.storage.storage.inner().get_ptr(),
             validation_params,
         );
 
         let stop_reason = self.execute_with_custom_tracer(&mut validation_tracer);
+        let computational_gas_used = validation_tracer.computational_gas_used();
 
-        match (stop_reason, validation_tracer.validation_error) {
+        let result = match (stop_reason, validation_tracer.validation_error) {
             (VmExecutionStopReason::VmFinished, _) => {
                 // The tx should only end in case of a revert, so it is safe to unwrap here
                 Err(ValidationError::FailedTx(self.revert_reason().unwrap()))
@@ -816,9 +858,30 @@ impl<'a> VmInstance<'a> {
                 Err(ValidationError::VioalatedRule(err))
             }
             (VmExecutionStopReason::TracerRequestedStop, None) => Ok(()),
+        };
+
+        ValidationReport {
+            result,
+            gas_used: self.gas_consumed().saturating_sub(gas_consumed_before),
+            computational_gas_used,
+            paymaster_context: self.paymaster_context(),
         }
     }
 
+    // Reads the context stored by the bootloader after the paymaster validation: the first slot
+    // holds the length of the context, and the next slots hold the context itself.
+    fn paymaster_context(&self) -> Vec<u8> {
+        let offset = PAYMASTER_CONTEXT_OFFSET as u32;
+        let words = self.state.memory.dump_page_content_as_u256_words(
+            BOOTLOADER_HEAP_PAGE,
+            offset..offset + PAYMASTER_CONTEXT_SLOTS as u32,
+        );
+        let mut context = be_words_to_bytes(&words[1..]);
+        let len = std::cmp::min(words[0], context.len().into()).as_usize();
+        context.truncate(len);
+        context
+    }
+
     // returns Some only when there is just one frame in execution trace.
     fn get_final_log_queries(&self) -> Vec<StorageLogQuery> {
         assert_eq!(
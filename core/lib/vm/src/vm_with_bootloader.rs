@@ -110,6 +110,7 @@ pub const DEBUG_SLOTS_OFFSET: usize = 8;
 pub const DEBUG_FIRST_SLOTS: usize = 32;
 // The next 33 slots are reserved for dealing with the paymaster context (1 slot for storing length + 32 slots for storing the actual context).
 pub const PAYMASTER_CONTEXT_SLOTS: usize = 32 + 1;
+pub(crate) const PAYMASTER_CONTEXT_OFFSET: usize = DEBUG_SLOTS_OFFSET + DEBUG_FIRST_SLOTS;
 // The next PAYMASTER_CONTEXT_SLOTS + 7 slots free slots are needed before each tx, so that the
 // postOp operation could be encoded correctly.
 pub const MAX_POSTOP_SLOTS: usize = PAYMASTER_CONTEXT_SLOTS + 7;
@@ -398,6 +399,38 @@ pub fn get_bootloader_memory(
     memory
 }
 
+/// Words occupied in the regions of the bootloader memory whose size limits the number of transactions in a batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BootloaderMemoryUsage {
+    pub txs_encoding_words: usize,
+    pub compressed_bytecodes_words: usize,
+}
+
+impl BootloaderMemoryUsage {
+    /// Computes the usage from the memory returned by [`get_bootloader_memory()`].
+    pub fn from_memory(memory: &[(usize, U256)]) -> Self {
+        let used_words = |start: usize, len: usize| {
+            memory
+                .iter()
+                .filter(|(slot, _)| (start..start + len).contains(slot))
+                .map(|(slot, _)| slot - start + 1)
+                .max()
+                .unwrap_or(0)
+        };
+        Self {
+            txs_encoding_words: used_words(
+                TX_DESCRIPTION_OFFSET,
+                BOOTLOADER_TX_ENCODING_SPACE as usize,
+            ),
+            // The first slot of the region is reserved, see `get_bootloader_memory_for_encoded_tx()`.
+            compressed_bytecodes_words: used_words(
+                COMPRESSED_BYTECODES_OFFSET + 1,
+                COMPRESSED_BYTECODES_SLOTS - 1,
+            ),
+        }
+    }
+}
+
 pub fn push_transaction_to_bootloader_memory(
     vm: &mut VmInstance,
     tx: &Transaction,
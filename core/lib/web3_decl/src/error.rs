@@ -1,12 +1,17 @@
 //! Definition of errors that can occur in the zkSync Web3 API.
 
 use thiserror::Error;
-use zksync_types::api::SerializationTransactionError;
+use zksync_types::{api::SerializationTransactionError, MiniblockNumber, H256, U256};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
     #[error("Block with such an ID doesn't exist yet")]
     NoBlock,
+    #[error(
+        "Block {0:?} was reorged{}",
+        .2.map(|hash| format!(", superseded by {:?}", hash)).unwrap_or_default()
+    )]
+    BlockReorged(H256, MiniblockNumber, Option<H256>),
     #[error("Request timeout")]
     RequestTimeout,
     #[error("Internal error")]
@@ -19,6 +24,14 @@ pub enum Web3Error {
     InvalidTransactionData(#[from] zksync_types::ethabi::Error),
     #[error("Failed to submit transaction: {0}")]
     SubmitTransactionError(String),
+    /// Revert of the executed call with the reason and the revert data returned by the contract.
+    #[error(
+        "execution reverted{}",
+        if .0.is_empty() { String::new() } else { format!(": {}", .0) }
+    )]
+    ExecutionReverted(String, Vec<u8>),
+    #[error("Failed to submit transaction: gas limit {0} exceeds the max allowed transaction gas limit {1}")]
+    TxGasLimitExceedsCap(U256, u32),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
@@ -33,4 +46,15 @@ pub enum Web3Error {
     NotImplemented,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
+    #[error("Query block range exceeds the limit of {0} blocks")]
+    LogsBlockRangeExceeded(u32),
+    #[error("Query returned more than {0} results")]
+    ResultLimitExceeded(usize),
+    #[error("Admin action is not authorized: {0}")]
+    Unauthorized(String),
+    #[error("Block revert is rejected: {0}")]
+    BlockRevertRejected(String),
+    /// Findings of the security tracer for the calls traced with `failOnFindings`.
+    #[error("Security check failed: {0}")]
+    SecurityCheckFailed(String),
 }
@@ -0,0 +1,66 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{AdminAuthorization, BlockRevertPlan, BootloaderUsageReport},
+    Address, L1BatchNumber, H256,
+};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    #[method(name = "getDeployerAllowlist")]
+    fn get_deployer_allowlist(&self) -> RpcResult<Vec<Address>>;
+
+    #[method(name = "addDeployerToAllowlist")]
+    fn add_deployer_to_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<bool>;
+
+    #[method(name = "removeDeployerFromAllowlist")]
+    fn remove_deployer_from_allowlist(
+        &self,
+        address: Address,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<bool>;
+
+    #[method(name = "getQuarantinedTransactions")]
+    fn get_quarantined_transactions(&self) -> RpcResult<Vec<H256>>;
+
+    #[method(name = "releaseQuarantinedTransaction")]
+    fn release_quarantined_transaction(
+        &self,
+        hash: H256,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<bool>;
+
+    #[method(name = "getBlockRevertPlan")]
+    fn get_block_revert_plan(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BlockRevertPlan>>;
+
+    #[method(name = "revertBlocks")]
+    async fn revert_blocks(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        stages: Vec<String>,
+        authorization: AdminAuthorization,
+    ) -> RpcResult<BlockRevertPlan>;
+
+    #[method(name = "getBootloaderUsageReport")]
+    fn get_bootloader_usage_report(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<BootloaderUsageReport>>;
+}
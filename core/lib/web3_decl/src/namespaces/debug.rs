@@ -0,0 +1,46 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{BlockIdVariant, BlockNumber, TxLifecycleEvent},
+    transaction_request::CallRequest,
+    vm_trace::{DebugTraceResult, TracerOptions, TxTraceResult},
+    H256,
+};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "debug")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "debug")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "debug")
+)]
+pub trait DebugNamespace {
+    #[method(name = "traceCall")]
+    fn trace_call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        options: TracerOptions,
+    ) -> RpcResult<DebugTraceResult>;
+
+    #[method(name = "getTransactionLifecycle")]
+    fn get_transaction_lifecycle(&self, hash: H256) -> RpcResult<Vec<TxLifecycleEvent>>;
+
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(
+        &self,
+        hash: H256,
+        options: Option<TracerOptions>,
+    ) -> RpcResult<Option<DebugTraceResult>>;
+
+    #[method(name = "traceBlockByNumber")]
+    async fn trace_block_by_number(
+        &self,
+        block: BlockNumber,
+        options: Option<TracerOptions>,
+    ) -> RpcResult<Vec<TxTraceResult>>;
+}
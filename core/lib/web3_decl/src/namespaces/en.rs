@@ -0,0 +1,19 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::api::NodeIdentity;
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "en")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "en")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "en")
+)]
+pub trait EnNamespace {
+    #[method(name = "handshake")]
+    fn handshake(&self, peer: NodeIdentity) -> RpcResult<NodeIdentity>;
+}
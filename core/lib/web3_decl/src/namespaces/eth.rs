@@ -8,7 +8,10 @@ use crate::types::{
 
 use zksync_types::{
     api::Transaction,
-    api::{BlockIdVariant, BlockNumber, TransactionVariant},
+    api::{
+        AccessListWithGasUsed, AccountInfo, BlockIdVariant, BlockNumber, FeeHistory,
+        TransactionVariant,
+    },
     transaction_request::CallRequest,
     Address, H256,
 };
@@ -40,9 +43,27 @@ pub trait EthNamespace {
     #[method(name = "estimateGas")]
     fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> RpcResult<U256>;
 
+    #[method(name = "createAccessList")]
+    fn create_access_list(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccessListWithGasUsed>;
+
     #[method(name = "gasPrice")]
     fn gas_price(&self) -> RpcResult<U256>;
 
+    #[method(name = "maxPriorityFeePerGas")]
+    fn max_priority_fee_per_gas(&self) -> RpcResult<U256>;
+
+    #[method(name = "feeHistory")]
+    fn fee_history(
+        &self,
+        block_count: U64,
+        newest_block: BlockNumber,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> RpcResult<FeeHistory>;
+
     #[method(name = "newFilter")]
     fn new_filter(&self, filter: Filter) -> RpcResult<U256>;
 
@@ -56,13 +77,13 @@ pub trait EthNamespace {
     fn new_pending_transaction_filter(&self) -> RpcResult<U256>;
 
     #[method(name = "getLogs")]
-    fn get_logs(&self, filter: Filter) -> RpcResult<Vec<Log>>;
+    async fn get_logs(&self, filter: Filter) -> RpcResult<Vec<Log>>;
 
     #[method(name = "getFilterLogs")]
-    fn get_filter_logs(&self, filter_index: U256) -> RpcResult<FilterChanges>;
+    async fn get_filter_logs(&self, filter_index: U256) -> RpcResult<FilterChanges>;
 
     #[method(name = "getFilterChanges")]
-    fn get_filter_changes(&self, filter_index: U256) -> RpcResult<FilterChanges>;
+    async fn get_filter_changes(&self, filter_index: U256) -> RpcResult<FilterChanges>;
 
     #[method(name = "getBalance")]
     fn get_balance(&self, address: Address, block: Option<BlockIdVariant>) -> RpcResult<U256>;
@@ -101,32 +122,48 @@ pub trait EthNamespace {
         block: Option<BlockIdVariant>,
     ) -> RpcResult<H256>;
 
+    #[method(name = "getAccount")]
+    fn get_account(
+        &self,
+        address: Address,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccountInfo>;
+
     #[method(name = "getTransactionCount")]
-    fn get_transaction_count(
+    async fn get_transaction_count(
         &self,
         address: Address,
         block: Option<BlockIdVariant>,
     ) -> RpcResult<U256>;
 
     #[method(name = "getTransactionByHash")]
-    fn get_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Transaction>>;
+    async fn get_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Transaction>>;
+
+    #[method(name = "getRawTransactionByHash")]
+    async fn get_raw_transaction_by_hash(&self, hash: H256) -> RpcResult<Option<Bytes>>;
 
     #[method(name = "getTransactionByBlockHashAndIndex")]
-    fn get_transaction_by_block_hash_and_index(
+    async fn get_transaction_by_block_hash_and_index(
         &self,
         block_hash: H256,
         index: Index,
     ) -> RpcResult<Option<Transaction>>;
 
     #[method(name = "getTransactionByBlockNumberAndIndex")]
-    fn get_transaction_by_block_number_and_index(
+    async fn get_transaction_by_block_number_and_index(
         &self,
         block_number: BlockNumber,
         index: Index,
     ) -> RpcResult<Option<Transaction>>;
 
     #[method(name = "getTransactionReceipt")]
-    fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>>;
+    async fn get_transaction_receipt(&self, hash: H256) -> RpcResult<Option<TransactionReceipt>>;
+
+    #[method(name = "getBlockReceipts")]
+    async fn get_block_receipts(
+        &self,
+        block: BlockIdVariant,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>>;
 
     #[method(name = "protocolVersion")]
     fn protocol_version(&self) -> RpcResult<String>;
@@ -1,19 +1,25 @@
+pub mod admin;
+pub mod debug;
+pub mod en;
 pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
 
 // Server trait re-exports.
 #[cfg(feature = "server")]
 pub use self::{
-    eth::EthNamespaceServer, net::NetNamespaceServer, web3::Web3NamespaceServer,
-    zks::ZksNamespaceServer,
+    admin::AdminNamespaceServer, debug::DebugNamespaceServer, en::EnNamespaceServer,
+    eth::EthNamespaceServer, net::NetNamespaceServer, txpool::TxPoolNamespaceServer,
+    web3::Web3NamespaceServer, zks::ZksNamespaceServer,
 };
 
 // Client trait re-exports.
 #[cfg(feature = "client")]
 pub use self::{
-    eth::EthNamespaceClient, net::NetNamespaceClient, web3::Web3NamespaceClient,
-    zks::ZksNamespaceClient,
+    admin::AdminNamespaceClient, debug::DebugNamespaceClient, en::EnNamespaceClient,
+    eth::EthNamespaceClient, net::NetNamespaceClient, txpool::TxPoolNamespaceClient,
+    web3::Web3NamespaceClient, zks::ZksNamespaceClient,
 };
@@ -0,0 +1,22 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::api::{TxPoolContent, TxPoolStatus};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "txpool")
+)]
+pub trait TxPoolNamespace {
+    #[method(name = "content")]
+    async fn content(&self) -> RpcResult<TxPoolContent>;
+
+    #[method(name = "status")]
+    async fn status(&self) -> RpcResult<TxPoolStatus>;
+}
@@ -1,15 +1,23 @@
-use crate::types::Token;
+use crate::types::{Filter, Token};
 use bigdecimal::BigDecimal;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use std::collections::HashMap;
-use zksync_types::api::{BridgeAddresses, L2ToL1LogProof, TransactionDetails};
+use zksync_types::api::{
+    AccountStateAtTimestamp, AccountStorageProof, AccountSummary, AccountTransactionsPage,
+    AccountTxDirection, BlockAtTimestamp, BlockDependencyGraph, BlockIdVariant, BridgeAddresses,
+    ChainStats, ExpiredPriorityOp, FeeEstimate, L1BatchL1Event, L2ToL1LogProof, L2TokenInfo,
+    LogCursor, LogsPage, NodeCapabilities, PendingTransactionsPage, PendingTxCursor, StateDiffPage,
+    StateSnapshotDiffInfo, SystemContext, TransactionDetails, TransactionReceipt,
+    TransactionValidationResult, TransferCursor, TransfersPage, TxCursor,
+};
 use zksync_types::transaction_request::CallRequest;
 use zksync_types::{
+    abi_decoding::DecodedTransactionInput,
     api::U64,
     explorer_api::{BlockDetails, L1BatchDetails},
-    fee::Fee,
+    l1_calldata::{DecodedL1Calldata, L1BatchCommitmentData},
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace},
-    Address, H256, U256,
+    Address, Bytes, H256, U256,
 };
 use zksync_types::{L1BatchNumber, MiniblockNumber};
 
@@ -27,7 +35,7 @@ use zksync_types::{L1BatchNumber, MiniblockNumber};
 )]
 pub trait ZksNamespace {
     #[method(name = "estimateFee")]
-    fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
+    fn estimate_fee(&self, req: CallRequest) -> RpcResult<FeeEstimate>;
 
     #[method(name = "zks_estimateGasL1ToL2")]
     fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
@@ -46,6 +54,24 @@ pub trait ZksNamespace {
 
     #[method(name = "getConfirmedTokens")]
     fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>>;
+
+    #[method(name = "getBridgedTokens")]
+    fn get_bridged_tokens(
+        &self,
+        from: u32,
+        limit: u8,
+        after: Option<Address>,
+    ) -> RpcResult<Vec<Token>>;
+
+    #[method(name = "getBridgedTokenByL1Address")]
+    fn get_bridged_token_by_l1_address(&self, l1_address: Address) -> RpcResult<Option<Token>>;
+
+    #[method(name = "getBridgedTokenByL2Address")]
+    fn get_bridged_token_by_l2_address(&self, l2_address: Address) -> RpcResult<Option<Token>>;
+
+    #[method(name = "getTokenInfo")]
+    fn get_token_info(&self, l2_address: Address) -> RpcResult<Option<L2TokenInfo>>;
+
     #[method(name = "getTokenPrice")]
     fn get_token_price(&self, token_address: Address) -> RpcResult<BigDecimal>;
 
@@ -84,6 +110,22 @@ pub trait ZksNamespace {
         index: Option<usize>,
     ) -> RpcResult<Option<L2ToL1LogProof>>;
 
+    #[method(name = "getStorageValues")]
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<Vec<H256>>;
+
+    #[method(name = "getProof")]
+    fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<AccountStorageProof>>;
+
     #[method(name = "L1BatchNumber")]
     fn get_l1_batch_number(&self) -> RpcResult<U64>;
 
@@ -94,14 +136,141 @@ pub trait ZksNamespace {
     fn get_block_details(&self, block_number: MiniblockNumber) -> RpcResult<Option<BlockDetails>>;
 
     #[method(name = "getTransactionDetails")]
-    fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
+    async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
+
+    #[method(name = "getRawTransaction")]
+    async fn get_raw_transaction(&self, hash: H256) -> RpcResult<Option<Bytes>>;
 
     #[method(name = "getRawBlockTransactions")]
-    fn get_raw_block_transactions(
+    async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
     ) -> RpcResult<Vec<zksync_types::Transaction>>;
 
+    #[method(name = "getBlockReceipts")]
+    async fn get_block_receipts(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Vec<TransactionReceipt>>;
+
     #[method(name = "getL1BatchDetails")]
     fn get_l1_batch_details(&self, batch: L1BatchNumber) -> RpcResult<Option<L1BatchDetails>>;
+
+    #[method(name = "getNodeCapabilities")]
+    fn get_node_capabilities(&self) -> RpcResult<NodeCapabilities>;
+
+    #[method(name = "decodeL1Calldata")]
+    fn decode_l1_calldata(&self, tx_hash: H256) -> RpcResult<Option<DecodedL1Calldata>>;
+
+    #[method(name = "getL1BatchCommitmentData")]
+    fn get_l1_batch_commitment_data(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<L1BatchCommitmentData>>;
+
+    #[method(name = "sendEncryptedTransaction")]
+    fn send_encrypted_transaction(&self, payload: Bytes) -> RpcResult<H256>;
+
+    #[method(name = "getChainStats")]
+    fn get_chain_stats(&self) -> RpcResult<Option<ChainStats>>;
+
+    #[method(name = "decodeTransactionInput")]
+    async fn decode_transaction_input(
+        &self,
+        tx_hash: H256,
+        include_events: Option<bool>,
+    ) -> RpcResult<Option<DecodedTransactionInput>>;
+
+    #[method(name = "getAccountSummary")]
+    async fn get_account_summary(
+        &self,
+        address: Address,
+        tokens_limit: Option<u8>,
+    ) -> RpcResult<AccountSummary>;
+
+    #[method(name = "getBlockDependencyGraph")]
+    fn get_block_dependency_graph(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Option<BlockDependencyGraph>>;
+
+    #[method(name = "validateTransaction")]
+    fn validate_transaction(&self, tx_bytes: Bytes) -> RpcResult<TransactionValidationResult>;
+
+    #[method(name = "getExpiredPriorityOps")]
+    fn get_expired_priority_ops(&self, from: u64, limit: u8) -> RpcResult<Vec<ExpiredPriorityOp>>;
+
+    #[method(name = "getL1BatchL1Events")]
+    fn get_l1_batch_l1_events(&self, batch: L1BatchNumber) -> RpcResult<Vec<L1BatchL1Event>>;
+
+    #[method(name = "getBytecodeByHash")]
+    fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
+
+    #[method(name = "getStateDiff")]
+    fn get_state_diff(
+        &self,
+        block_number: MiniblockNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> RpcResult<Option<StateDiffPage>>;
+
+    #[method(name = "getL1BatchStateDiff")]
+    fn get_l1_batch_state_diff(
+        &self,
+        batch: L1BatchNumber,
+        after: Option<H256>,
+        limit: Option<u16>,
+    ) -> RpcResult<Option<StateDiffPage>>;
+
+    #[method(name = "getStateSnapshotDiff")]
+    fn get_state_snapshot_diff(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<StateSnapshotDiffInfo>>;
+
+    #[method(name = "getLogsPaginated")]
+    async fn get_logs_paginated(
+        &self,
+        filter: Filter,
+        after: Option<LogCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<LogsPage>;
+
+    #[method(name = "getSystemContext")]
+    fn get_system_context(&self, block_number: MiniblockNumber)
+        -> RpcResult<Option<SystemContext>>;
+
+    #[method(name = "getTransactionsByAddress")]
+    async fn get_transactions_by_address(
+        &self,
+        address: Address,
+        direction: Option<AccountTxDirection>,
+        before: Option<TxCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<AccountTransactionsPage>;
+
+    #[method(name = "getPendingTransactions")]
+    async fn get_pending_transactions(
+        &self,
+        after: Option<PendingTxCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<PendingTransactionsPage>;
+
+    #[method(name = "getTransfers")]
+    async fn get_transfers(
+        &self,
+        address: Address,
+        before: Option<TransferCursor>,
+        limit: Option<u16>,
+    ) -> RpcResult<TransfersPage>;
+
+    #[method(name = "getBlockByTimestamp")]
+    fn get_block_by_timestamp(&self, timestamp: u64) -> RpcResult<Option<BlockAtTimestamp>>;
+
+    #[method(name = "getAccountStateAtTimestamp")]
+    fn get_account_state_at_timestamp(
+        &self,
+        address: Address,
+        timestamp: u64,
+    ) -> RpcResult<Option<AccountStateAtTimestamp>>;
 }
@@ -8,12 +8,15 @@
 use core::convert::{TryFrom, TryInto};
 use core::fmt;
 use core::marker::PhantomData;
+use core::str::FromStr;
 
 use chrono::NaiveDateTime;
 use itertools::unfold;
 use rlp::Rlp;
 use serde::{de, Deserialize, Serialize, Serializer};
 
+use zksync_types::{bloom::bloom_contains, MiniblockNumber, H2048};
+
 pub use zksync_types::{
     api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
@@ -278,6 +281,32 @@ impl PubSubFilter {
         }
         true
     }
+
+    /// Checks whether the logs with the given bloom may match the filter.
+    /// `false` means that none of the logs match the filter.
+    pub fn may_match_bloom(&self, bloom: &H2048) -> bool {
+        if let Some(addresses) = &self.address {
+            if !addresses
+                .0
+                .iter()
+                .any(|address| bloom_contains(bloom, address.as_bytes()))
+            {
+                return false;
+            }
+        }
+        if let Some(all_topics) = &self.topics {
+            for expected_topics in all_topics.iter().flatten() {
+                if !expected_topics
+                    .0
+                    .iter()
+                    .any(|topic| bloom_contains(bloom, topic.as_bytes()))
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
 }
 
 #[derive(Default, Clone)]
@@ -345,6 +374,125 @@ pub enum PubSubResult {
     Log(Log),
     TxHash(H256),
     Syncing(bool),
+    Resumable(ResumableNotification),
+}
+
+/// Position of the last notification delivered to a resumable `newHeads` or `logs` subscription.
+/// Serialized as `"<miniblock number>"` for the headers and as `"<miniblock number>:<log index in block>"`
+/// for the logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ResumeToken {
+    pub miniblock_number: MiniblockNumber,
+    pub log_index: Option<u32>,
+}
+
+impl fmt::Display for ResumeToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.log_index {
+            Some(log_index) => write!(f, "{}:{}", self.miniblock_number.0, log_index),
+            None => write!(f, "{}", self.miniblock_number.0),
+        }
+    }
+}
+
+impl FromStr for ResumeToken {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (miniblock_number, log_index) = match s.split_once(':') {
+            Some((miniblock_number, log_index)) => (miniblock_number, Some(log_index.parse()?)),
+            None => (s, None),
+        };
+        Ok(Self {
+            miniblock_number: MiniblockNumber(miniblock_number.parse()?),
+            log_index,
+        })
+    }
+}
+
+impl Serialize for ResumeToken {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ResumeToken {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let token = String::deserialize(deserializer)?;
+        token.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Parameters making a `newHeads` or `logs` subscription resumable.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeParams {
+    /// Whether the notifications are sent together with their resume tokens.
+    #[serde(default)]
+    pub resumable: bool,
+    /// Token of the last notification received before reconnecting. The notifications following it
+    /// are replayed before the new ones. Implies `resumable`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_token: Option<ResumeToken>,
+}
+
+impl ResumeParams {
+    pub fn is_resumable(&self) -> bool {
+        self.resumable || self.resume_token.is_some()
+    }
+}
+
+/// Parameters of the `logs` subscription.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogsSubscriptionParams {
+    #[serde(flatten)]
+    pub filter: PubSubFilter,
+    #[serde(flatten)]
+    pub resume: ResumeParams,
+}
+
+/// Notification of a resumable subscription.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumableNotification {
+    pub result: Box<PubSubResult>,
+    pub resume_token: ResumeToken,
+}
+
+/// Change in the mempool queue of an account notified to the `zks_subscribeAccountEvents` subscribers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AccountEvent {
+    /// The transactions starting from `first_queued_nonce` can't be executed until a transaction
+    /// with `expected_nonce` is submitted.
+    #[serde(rename_all = "camelCase")]
+    NonceGap {
+        account: Address,
+        expected_nonce: u32,
+        first_queued_nonce: u32,
+    },
+    /// The transaction that was blocked by a nonce gap can be executed now.
+    #[serde(rename_all = "camelCase")]
+    TxExecutable {
+        account: Address,
+        hash: H256,
+        nonce: u32,
+    },
+    /// The transaction was replaced in the mempool by a transaction with the same nonce.
+    #[serde(rename_all = "camelCase")]
+    TxReplaced {
+        account: Address,
+        hash: H256,
+        nonce: u32,
+        replaced_by: H256,
+    },
+    /// The transaction was removed from the mempool without being executed.
+    #[serde(rename_all = "camelCase")]
+    TxDropped {
+        account: Address,
+        hash: H256,
+        nonce: u32,
+    },
 }
 
 #[cfg(test)]
@@ -414,4 +562,85 @@ mod tests {
             assert_eq!(&actual_block_id, expected_block_id);
         }
     }
+
+    #[test]
+    fn matching_pubsub_filter_with_bloom() {
+        let address = H160::repeat_byte(1);
+        let topic = H256::repeat_byte(2);
+        let mut bloom = H2048::zero();
+        zksync_types::bloom::accrue_bloom(&mut bloom, address.as_bytes());
+        zksync_types::bloom::accrue_bloom(&mut bloom, topic.as_bytes());
+
+        assert!(PubSubFilter::default().may_match_bloom(&bloom));
+        assert!(PubSubFilter::default().may_match_bloom(&H2048::zero()));
+
+        let filter = PubSubFilterBuilder::default()
+            .set_address(vec![H160::repeat_byte(3), address])
+            .set_topics(None, Some(vec![topic]), None, None)
+            .build();
+        assert!(filter.may_match_bloom(&bloom));
+        assert!(!filter.may_match_bloom(&H2048::zero()));
+
+        let filter = PubSubFilterBuilder::default()
+            .set_address(vec![H160::repeat_byte(3)])
+            .build();
+        assert!(!filter.may_match_bloom(&bloom));
+        let filter = PubSubFilterBuilder::default()
+            .set_topics(
+                Some(vec![topic]),
+                Some(vec![H256::repeat_byte(4)]),
+                None,
+                None,
+            )
+            .build();
+        assert!(!filter.may_match_bloom(&bloom));
+    }
+
+    #[test]
+    fn resume_token_serde() {
+        let test_vector = &[
+            (
+                r#""42""#,
+                ResumeToken {
+                    miniblock_number: MiniblockNumber(42),
+                    log_index: None,
+                },
+            ),
+            (
+                r#""42:3""#,
+                ResumeToken {
+                    miniblock_number: MiniblockNumber(42),
+                    log_index: Some(3),
+                },
+            ),
+        ];
+
+        for (serialized_repr, deserialized_repr) in test_vector {
+            let serialized = serde_json::to_string(deserialized_repr).unwrap();
+            assert_eq!(&serialized, serialized_repr);
+
+            let deserialized: ResumeToken = serde_json::from_str(serialized_repr).unwrap();
+            assert_eq!(&deserialized, deserialized_repr);
+        }
+        assert!(serde_json::from_str::<ResumeToken>(r#""42:""#).is_err());
+    }
+
+    #[test]
+    fn logs_subscription_params_serde() {
+        let params: LogsSubscriptionParams = serde_json::from_str(
+            r#"{"address":"0x0303030303030303030303030303030303030303","resumeToken":"7:1"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            params.filter,
+            PubSubFilterBuilder::default()
+                .set_address(vec![H160::repeat_byte(3)])
+                .build()
+        );
+        assert!(params.resume.is_resumable());
+
+        let params: LogsSubscriptionParams = serde_json::from_str("{}").unwrap();
+        assert_eq!(params, LogsSubscriptionParams::default());
+        assert!(!params.resume.is_resumable());
+    }
 }
@@ -171,6 +171,7 @@ impl AccountLifespan {
                             limit: 100,
                             offset,
                             direction: PaginationDirection::Newer,
+                            cursor: None,
                         },
                     })
                     .await
@@ -224,6 +225,7 @@ impl AccountLifespan {
                             limit: 100,
                             offset,
                             direction: PaginationDirection::Newer,
+                            cursor: None,
                         },
                     })
                     .await
@@ -246,6 +248,7 @@ impl AccountLifespan {
                             limit: 100,
                             offset,
                             direction: PaginationDirection::Newer,
+                            cursor: None,
                         },
                     })
                     .await
@@ -274,7 +277,11 @@ impl AccountLifespan {
                 .await
                 .expect("static semaphore cannot be closed");
 
-            let request = ExplorerApiRequest::random(&mut self.wallet.rng).await;
+            let request = ExplorerApiRequest::random(
+                &mut self.wallet.rng,
+                &self.execution_config.explorer_api_config_weights,
+            )
+            .await;
 
             let start = Instant::now();
             let mut empty_success_txs = true;
@@ -6,7 +6,6 @@ use std::{
     time::{Duration, Instant},
 };
 use tokio::{sync::RwLock, time::sleep};
-use zksync_utils::test_utils::LoadnextContractExecutionParams;
 
 use zksync::{error::ClientError, operations::SyncTransactionHandle, HttpClient};
 use zksync_types::{
@@ -19,8 +18,9 @@ use crate::{
     account::{explorer_api_executor::ExplorerApiClient, tx_command_executor::SubmitResult},
     account_pool::{AddressPool, TestWallet},
     command::{ExpectedOutcome, IncorrectnessModifier, TxCommand, TxType},
-    config::LoadtestConfig,
+    config::{ExecutionConfig, LoadtestConfig},
     constants::POLLING_INTERVAL,
+    rate_limiter::TxRateLimiter,
     report::{Report, ReportBuilder, ReportLabel},
 };
 
@@ -57,7 +57,10 @@ pub struct AccountLifespan {
     /// Client for explorer api
     pub explorer_client: ExplorerApiClient,
     config: LoadtestConfig,
-    contract_execution_params: LoadnextContractExecutionParams,
+    /// Mix of the operations performed by the account.
+    execution_config: ExecutionConfig,
+    /// Limiter of the transactions rate shared by all the accounts, if the rate is limited.
+    tx_rate_limiter: Option<TxRateLimiter>,
     /// Pool of account addresses, used to generate commands.
     addresses: AddressPool,
     /// Successful transactions, required for requesting api
@@ -77,9 +80,11 @@ pub struct AccountLifespan {
 }
 
 impl AccountLifespan {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: &LoadtestConfig,
-        contract_execution_params: LoadnextContractExecutionParams,
+        execution_config: ExecutionConfig,
+        tx_rate_limiter: Option<TxRateLimiter>,
         addresses: AddressPool,
         test_account: TestWallet,
         report_sink: mpsc::Sender<Report>,
@@ -96,7 +101,8 @@ impl AccountLifespan {
             wallet: test_account,
             explorer_client,
             config: config.clone(),
-            contract_execution_params,
+            execution_config,
+            tx_rate_limiter,
             addresses,
             successfully_sent_txs: Default::default(),
             main_l1_token: config.main_token,
@@ -153,6 +159,9 @@ impl AccountLifespan {
                     timer.tick().await;
                     self.check_inflight_txs().await;
                 } else {
+                    if let Some(tx_rate_limiter) = &self.tx_rate_limiter {
+                        tx_rate_limiter.acquire().await;
+                    }
                     self.execute_command(command.clone()).await;
                     break;
                 }
@@ -379,6 +388,7 @@ impl AccountLifespan {
             &mut self.wallet.rng,
             self.wallet.wallet.address(),
             &self.addresses,
+            &self.execution_config.transaction_weights,
         )
     }
 }
@@ -1,9 +1,7 @@
-use crate::all::AllWeighted;
-use crate::config::ExplorerApiRequestWeights;
-use crate::rng::{LoadtestRng, WeightedRandom};
-use once_cell::sync::OnceCell;
+use rand::seq::SliceRandom;
 
-static WEIGHTS: OnceCell<[(ExplorerApiRequestType, f32); 9]> = OnceCell::new();
+use crate::config::ExplorerApiRequestWeights;
+use crate::rng::LoadtestRng;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ExplorerApiRequestType {
@@ -19,28 +17,26 @@ pub enum ExplorerApiRequestType {
 }
 
 impl ExplorerApiRequestType {
-    pub fn initialize_weights(weights: &ExplorerApiRequestWeights) {
-        WEIGHTS
-            .set([
-                (ExplorerApiRequestType::NetworkStats, weights.network_stats),
-                (ExplorerApiRequestType::Blocks, weights.blocks),
-                (ExplorerApiRequestType::Block, weights.block),
-                (ExplorerApiRequestType::Transaction, weights.transaction),
-                (ExplorerApiRequestType::Transactions, weights.transactions),
-                (
-                    ExplorerApiRequestType::AccountTransactions,
-                    weights.account_transactions,
-                ),
-                (ExplorerApiRequestType::Account, weights.account),
-                (ExplorerApiRequestType::Contract, weights.contract),
-                (ExplorerApiRequestType::Token, weights.token),
-            ])
-            .unwrap();
-    }
-}
-impl AllWeighted for ExplorerApiRequestType {
-    fn all_weighted() -> &'static [(Self, f32)] {
-        WEIGHTS.get().expect("Weights are not initialized")
+    /// Chooses a random request type according to the provided weights.
+    pub fn random(rng: &mut LoadtestRng, weights: &ExplorerApiRequestWeights) -> Self {
+        let weighted = [
+            (ExplorerApiRequestType::NetworkStats, weights.network_stats),
+            (ExplorerApiRequestType::Blocks, weights.blocks),
+            (ExplorerApiRequestType::Block, weights.block),
+            (ExplorerApiRequestType::Transaction, weights.transaction),
+            (ExplorerApiRequestType::Transactions, weights.transactions),
+            (
+                ExplorerApiRequestType::AccountTransactions,
+                weights.account_transactions,
+            ),
+            (ExplorerApiRequestType::Account, weights.account),
+            (ExplorerApiRequestType::Contract, weights.contract),
+            (ExplorerApiRequestType::Token, weights.token),
+        ];
+        weighted
+            .choose_weighted(rng, |item| item.1)
+            .expect("Invalid explorer API request weights")
+            .0
     }
 }
 
@@ -51,8 +47,8 @@ pub struct ExplorerApiRequest {
 }
 
 impl ExplorerApiRequest {
-    pub async fn random(rng: &mut LoadtestRng) -> Self {
-        let request_type = ExplorerApiRequestType::random(rng);
+    pub async fn random(rng: &mut LoadtestRng, weights: &ExplorerApiRequestWeights) -> Self {
+        let request_type = ExplorerApiRequestType::random(rng, weights);
         Self { request_type }
     }
 }
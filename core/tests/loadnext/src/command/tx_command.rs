@@ -1,5 +1,4 @@
-use once_cell::sync::OnceCell;
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 use static_assertions::const_assert;
 
 use zksync_types::{Address, U256};
@@ -11,8 +10,6 @@ use crate::{
     rng::{LoadtestRng, WeightedRandom},
 };
 
-static WEIGHTS: OnceCell<[(TxType, f32); 5]> = OnceCell::new();
-
 /// Type of transaction. It doesn't copy the zkSync operation list, because
 /// it divides some transactions in subcategories (e.g. to new account / to existing account; to self / to other; etc)/
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -20,25 +17,27 @@ pub enum TxType {
     Deposit,
     WithdrawToSelf,
     WithdrawToOther,
+    Transfer,
     DeployContract,
     L1Execute,
     L2Execute,
 }
 
 impl TxType {
-    pub fn initialize_weights(transaction_weights: &TransactionWeights) {
-        WEIGHTS
-            .set([
-                (TxType::Deposit, transaction_weights.deposit),
-                (TxType::L2Execute, transaction_weights.l2_transactions),
-                (TxType::L1Execute, transaction_weights.l1_transactions),
-                (TxType::WithdrawToSelf, transaction_weights.withdrawal / 2.0),
-                (
-                    TxType::WithdrawToOther,
-                    transaction_weights.withdrawal / 2.0,
-                ),
-            ])
-            .unwrap();
+    /// Chooses a random transaction type according to the provided weights.
+    pub fn random(rng: &mut LoadtestRng, weights: &TransactionWeights) -> Self {
+        let weighted = [
+            (TxType::Deposit, weights.deposit),
+            (TxType::L2Execute, weights.l2_transactions),
+            (TxType::L1Execute, weights.l1_transactions),
+            (TxType::WithdrawToSelf, weights.withdrawal / 2.0),
+            (TxType::WithdrawToOther, weights.withdrawal / 2.0),
+            (TxType::Transfer, weights.transfers),
+        ];
+        weighted
+            .choose_weighted(rng, |item| item.1)
+            .expect("Invalid transaction weights")
+            .0
     }
 }
 
@@ -48,18 +47,13 @@ impl All for TxType {
     }
 }
 
-impl AllWeighted for TxType {
-    fn all_weighted() -> &'static [(Self, f32)] {
-        WEIGHTS.get().expect("Weights are not initialized")
-    }
-}
-
 impl TxType {
     const fn const_all() -> &'static [Self] {
         &[
             Self::Deposit,
             Self::WithdrawToSelf,
             Self::WithdrawToOther,
+            Self::Transfer,
             Self::L1Execute,
             Self::L2Execute,
         ]
@@ -156,8 +150,13 @@ pub struct TxCommand {
 
 impl TxCommand {
     /// Generates a fully random transaction command.
-    pub fn random(rng: &mut LoadtestRng, own_address: Address, addresses: &AddressPool) -> Self {
-        let command_type = TxType::random(rng);
+    pub fn random(
+        rng: &mut LoadtestRng,
+        own_address: Address,
+        addresses: &AddressPool,
+        weights: &TransactionWeights,
+    ) -> Self {
+        let command_type = TxType::random(rng, weights);
 
         Self::new_with_type(rng, own_address, addresses, command_type)
     }
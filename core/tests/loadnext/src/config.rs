@@ -129,6 +129,11 @@ pub struct LoadtestConfig {
     /// that should be compared to the actual result.
     #[serde(default = "default_expected_tx_count")]
     pub expected_tx_count: Option<usize>,
+
+    /// Max number of transactions per second sent by all the accounts together.
+    /// Transactions are sent as fast as possible if not set.
+    #[serde(default = "default_max_tx_rate")]
+    pub max_tx_rate: Option<u32>,
 }
 
 fn default_max_inflight_txs() -> usize {
@@ -255,6 +260,12 @@ fn default_expected_tx_count() -> Option<usize> {
     result
 }
 
+fn default_max_tx_rate() -> Option<u32> {
+    let result = None;
+    vlog::info!("Using default MAX_TX_RATE: {:?}", result);
+    result
+}
+
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
         envy::from_env()
@@ -269,7 +280,7 @@ impl LoadtestConfig {
 ///
 /// This configuration is independent from the main config for preserving simplicity of the main config
 /// and do not break the backward compatibility
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct ExecutionConfig {
     pub transaction_weights: TransactionWeights,
     pub contract_execution_params: LoadnextContractExecutionParams,
@@ -335,6 +346,9 @@ pub struct TransactionWeights {
     pub withdrawal: f32,
     pub l1_transactions: f32,
     pub l2_transactions: f32,
+    /// ERC-20 transfers between the test accounts.
+    #[serde(default)]
+    pub transfers: f32,
 }
 
 impl TransactionWeights {
@@ -350,6 +364,7 @@ impl Default for TransactionWeights {
             withdrawal: 0.5,
             l1_transactions: 0.1,
             l2_transactions: 1.0,
+            transfers: 0.5,
         }
     }
 }
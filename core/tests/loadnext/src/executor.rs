@@ -20,7 +20,8 @@ use crate::{
     account_pool::AccountPool,
     config::{ExecutionConfig, LoadtestConfig},
     constants::*,
-    report_collector::{LoadtestResult, ReportCollector},
+    rate_limiter::TxRateLimiter,
+    report_collector::{LoadtestReport, LoadtestResult, ReportCollector},
 };
 
 pub const MAX_L1_TRANSACTIONS: u64 = 10;
@@ -71,15 +72,20 @@ impl Executor {
 
     /// Runs the loadtest until the completion.
     pub async fn start(&mut self) -> LoadtestResult {
+        self.run().await.result
+    }
+
+    /// Runs the loadtest until the completion and returns the report with latencies of the performed actions.
+    pub async fn run(&mut self) -> LoadtestReport {
         // If the error occurs during the main flow, we will consider it as a test failure.
         self.start_inner().await.unwrap_or_else(|err| {
             vlog::error!("Loadtest was interrupted by the following error: {}", err);
-            LoadtestResult::TestFailed
+            LoadtestReport::failed()
         })
     }
 
-    /// Inner representation of `start` function which returns a `Result`, so it can conveniently use `?`.
-    async fn start_inner(&mut self) -> anyhow::Result<LoadtestResult> {
+    /// Inner representation of `run` function which returns a `Result`, so it can conveniently use `?`.
+    async fn start_inner(&mut self) -> anyhow::Result<LoadtestReport> {
         vlog::info!("Initializing accounts");
         vlog::info!("Running for MASTER {:?}", self.pool.master_wallet.address());
         self.check_onchain_balance().await?;
@@ -94,9 +100,11 @@ impl Executor {
         let (executor_future, account_futures) = self.send_initial_transfers().await?;
         self.wait_account_routines(account_futures).await;
 
-        let final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
+        let final_report = executor_future
+            .await
+            .unwrap_or_else(|_| LoadtestReport::failed());
 
-        Ok(final_resultion)
+        Ok(final_report)
     }
 
     /// Verifies that onchain ETH balance for the main account is sufficient to run the loadtest.
@@ -463,7 +471,7 @@ impl Executor {
     /// - Collecting all the spawned tasks and returning them to the caller.
     async fn send_initial_transfers(
         &mut self,
-    ) -> anyhow::Result<(JoinHandle<LoadtestResult>, Vec<JoinHandle<()>>)> {
+    ) -> anyhow::Result<(JoinHandle<LoadtestReport>, Vec<JoinHandle<()>>)> {
         vlog::info!("Master Account: Sending initial transfers");
         // How many times we will resend a batch.
         const MAX_RETRIES: usize = 3;
@@ -485,6 +493,11 @@ impl Executor {
             .await?
             .expect("No testnet paymaster is set");
 
+        let tx_rate_limiter = config
+            .max_tx_rate
+            .filter(|&rate| rate > 0)
+            .map(TxRateLimiter::new);
+
         let mut retry_counter = 0;
         let mut accounts_processed = 0;
 
@@ -516,7 +529,7 @@ impl Executor {
             retry_counter = 0;
             accounts_processed += accounts_to_process;
 
-            let contract_execution_params = self.execution_config.contract_execution_params.clone();
+            let execution_config = self.execution_config.clone();
             // Spawn each account lifespan.
             let main_token = self.l2_main_token;
             let new_account_futures =
@@ -526,7 +539,8 @@ impl Executor {
                     .map(|wallet| {
                         let account = AccountLifespan::new(
                             config,
-                            contract_execution_params.clone(),
+                            execution_config.clone(),
+                            tx_rate_limiter.clone(),
                             addresses.clone(),
                             wallet,
                             report_sender.clone(),
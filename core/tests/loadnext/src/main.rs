@@ -5,7 +5,6 @@
 //! values to check the local zkSync deployment.
 
 use loadnext::{
-    command::{ExplorerApiRequestType, TxType},
     config::{ExecutionConfig, LoadtestConfig},
     executor::Executor,
     report_collector::LoadtestResult,
@@ -18,8 +17,6 @@ async fn main() -> anyhow::Result<()> {
     let config = LoadtestConfig::from_env()
         .expect("Config parameters should be loaded from env or from default values");
     let execution_config = ExecutionConfig::from_env();
-    TxType::initialize_weights(&execution_config.transaction_weights);
-    ExplorerApiRequestType::initialize_weights(&execution_config.explorer_api_config_weights);
 
     vlog::info!(
         "Run with tx weights: {:?}",
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// Limits the rate of the transactions sent by all the accounts together.
+///
+/// Each account acquires a slot before sending a transaction; slots are handed out
+/// evenly over time, so bursts after a pause are not allowed.
+#[derive(Debug, Clone)]
+pub struct TxRateLimiter {
+    interval: Arc<Mutex<Interval>>,
+}
+
+impl TxRateLimiter {
+    /// Creates a limiter allowing `max_tx_rate` transactions per second. Must be called within the Tokio runtime.
+    pub fn new(max_tx_rate: u32) -> Self {
+        assert!(max_tx_rate > 0, "Transaction rate must be positive");
+        let mut interval = tokio::time::interval(Duration::from_secs(1) / max_tx_rate);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            interval: Arc::new(Mutex::new(interval)),
+        }
+    }
+
+    /// Waits until the next transaction can be sent.
+    pub async fn acquire(&self) {
+        self.interval.lock().await.tick().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn rate_is_shared_between_clones() {
+        let limiter = TxRateLimiter::new(100);
+        let other = limiter.clone();
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+            other.acquire().await;
+        }
+        // The first slot is available immediately, the other 9 are spaced by 10ms.
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
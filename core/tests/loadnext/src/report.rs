@@ -116,6 +116,7 @@ impl ReportLabel {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TxActionType {
     Withdraw,
+    Transfer,
     Deposit,
     DeployContract,
     Execute(ExecutionType),
@@ -125,6 +126,7 @@ impl All for TxActionType {
     fn all() -> &'static [Self] {
         const ALL: &[TxActionType] = &[
             TxActionType::Withdraw,
+            TxActionType::Transfer,
             TxActionType::Deposit,
             TxActionType::DeployContract,
             TxActionType::Execute(ExecutionType::L2),
@@ -140,6 +142,7 @@ impl From<TxType> for TxActionType {
         match command {
             TxType::Deposit => Self::Deposit,
             TxType::WithdrawToSelf | TxType::WithdrawToOther => Self::Withdraw,
+            TxType::Transfer => Self::Transfer,
             TxType::L2Execute => Self::Execute(ExecutionType::L2),
             TxType::L1Execute => Self::Execute(ExecutionType::L1),
             TxType::DeployContract => Self::DeployContract,
@@ -106,6 +106,17 @@ impl TimeHistogram {
     }
 }
 
+/// Latencies of an action type observed during the loadtest.
+///
+/// Percentiles are represented by the lower bounds of the histogram windows they fall into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionLatencies {
+    pub count: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+}
+
 /// Collector for the execution time metrics.
 ///
 /// It builds a distribution histogram for each type of action, thus reported results are represented
@@ -137,6 +148,23 @@ impl MetricsCollector {
             .and_modify(|hist| hist.add_metric(time));
     }
 
+    /// Returns latencies of the action types that were performed at least once.
+    pub fn latencies(&self) -> HashMap<ActionType, ActionLatencies> {
+        self.action_stats
+            .iter()
+            .filter(|(_, histogram)| !histogram.is_empty())
+            .map(|(&action, histogram)| {
+                let latencies = ActionLatencies {
+                    count: histogram.total,
+                    p50: histogram.percentile(50).0,
+                    p90: histogram.percentile(90).0,
+                    p99: histogram.percentile(99).0,
+                };
+                (action, latencies)
+            })
+            .collect()
+    }
+
     pub fn report(&self) {
         vlog::info!("Action: [10 percentile, 50 percentile, 90 percentile]");
         for (action, histogram) in self.action_stats.iter() {
@@ -241,4 +269,25 @@ mod tests {
         assert_eq!(histogram.percentile(50), second_range);
         assert_eq!(histogram.percentile(100), third_range);
     }
+
+    #[test]
+    fn collected_latencies() {
+        let mut collector = MetricsCollector::new();
+        let action = ActionType::all()[0];
+        for millis in [50, 150, 250, 5_000] {
+            collector.add_metric(action, Duration::from_millis(millis));
+        }
+
+        let latencies = collector.latencies();
+        assert_eq!(latencies.len(), 1);
+        assert_eq!(
+            latencies[&action],
+            ActionLatencies {
+                count: 4,
+                p50: Duration::from_millis(100),
+                p90: Duration::from_millis(5_000),
+                p99: Duration::from_millis(5_000),
+            }
+        );
+    }
 }
@@ -1,11 +1,15 @@
+use std::collections::HashMap;
+
 use futures::{channel::mpsc::Receiver, StreamExt};
 use operation_results_collector::OperationResultsCollector;
 
 use crate::{
-    report::{Report, ReportLabel},
+    report::{ActionType, Report, ReportLabel},
     report_collector::metrics_collector::MetricsCollector,
 };
 
+pub use self::metrics_collector::ActionLatencies;
+
 mod metrics_collector;
 mod operation_results_collector;
 
@@ -16,6 +20,23 @@ pub enum LoadtestResult {
     TestFailed,
 }
 
+/// Final report of the loadtest: the decision on whether it passed and latencies of the performed actions.
+#[derive(Debug, Clone)]
+pub struct LoadtestReport {
+    pub result: LoadtestResult,
+    /// Latencies of the successfully performed actions. Contains only the action types that were performed.
+    pub latencies: HashMap<ActionType, ActionLatencies>,
+}
+
+impl LoadtestReport {
+    pub(crate) fn failed() -> Self {
+        Self {
+            result: LoadtestResult::TestFailed,
+            latencies: HashMap::new(),
+        }
+    }
+}
+
 /// ReportCollector is an entity capable of analyzing everything that happens in the loadtest.
 ///
 /// It is designed to be separated from the actual execution, so that logic of the execution does not
@@ -59,7 +80,7 @@ impl ReportCollector {
         }
     }
 
-    pub async fn run(mut self) -> LoadtestResult {
+    pub async fn run(mut self) -> LoadtestReport {
         while let Some(report) = self.reports_stream.next().await {
             vlog::trace!("Report: {:?}", &report);
 
@@ -83,7 +104,10 @@ impl ReportCollector {
         self.metrics_collector.report();
         self.operations_results_collector.report();
 
-        self.final_resolution()
+        LoadtestReport {
+            result: self.final_resolution(),
+            latencies: self.metrics_collector.latencies(),
+        }
     }
 
     fn final_resolution(&self) -> LoadtestResult {
@@ -130,7 +130,7 @@ describe('Block reverting test', function () {
 
         console.log('Rolling back DB..');
         await utils.spawn(
-            `cd $ZKSYNC_HOME && cargo run --bin block_reverter --release -- rollback-db --l1-batch-number ${lastL1BatchNumber} --rollback-postgres --rollback-tree --rollback-sk-cache`
+            `cd $ZKSYNC_HOME && cargo run --bin block_reverter --release -- rollback-db --l1-batch-number ${lastL1BatchNumber} --rollback-eth-sender --rollback-postgres --rollback-tree --rollback-sk-cache`
         );
 
         let blocksCommitted = await mainContract.getTotalBlocksCommitted();
@@ -23,11 +23,14 @@ describe('web3 API compatibility tests', () => {
     let testMaster: TestMaster;
     let alice: zksync.Wallet;
     let l2Token: string;
+    // Provider requesting the zkSync extension fields (e.g. `l1BatchNumber` of the blocks).
+    let extendedProvider: zksync.Provider;
 
     beforeAll(async () => {
         testMaster = TestMaster.getInstance(__filename);
         alice = testMaster.mainAccount();
         l2Token = testMaster.environment().erc20Token.l2Address;
+        extendedProvider = new zksync.Provider(`${testMaster.environment().l2NodeUrl}?zks_extensions=true`);
     });
 
     test('Should test block/transaction web3 methods', async () => {
@@ -158,8 +161,8 @@ describe('web3 API compatibility tests', () => {
             fromBlock: receipt.blockNumber,
             toBlock: receipt.blockNumber
         });
-        const block = await alice.provider.getBlock(receipt.blockNumber);
-        const blockWithTransactions = await alice.provider.getBlockWithTransactions(receipt.blockNumber);
+        const block = await extendedProvider.getBlock(receipt.blockNumber);
+        const blockWithTransactions = await extendedProvider.getBlockWithTransactions(receipt.blockNumber);
         const tx1 = await alice.provider.getTransaction(tx.transactionHash);
         expect(tx1.l1BatchNumber).toEqual(expect.anything()); // Can be anything except `null` or `undefined`.
         expect(tx1.l1BatchTxIndex).toEqual(expect.anything()); // Can be anything except `null` or `undefined`.
@@ -440,7 +443,7 @@ describe('web3 API compatibility tests', () => {
         const [from, to] = range!;
 
         for (let i = from; i <= to; i++) {
-            const block = await alice.provider.getBlockWithTransactions(i);
+            const block = await extendedProvider.getBlockWithTransactions(i);
             expect(block.l1BatchNumber).toEqual(l1BatchNumber);
             expect(block.l1BatchTimestamp).toEqual(expect.anything());
             expect(block.number).toEqual(i);
@@ -451,10 +454,10 @@ describe('web3 API compatibility tests', () => {
             }
         }
 
-        const prevBlock = await alice.provider.getBlockWithTransactions(from - 1);
+        const prevBlock = await extendedProvider.getBlockWithTransactions(from - 1);
         expect(prevBlock.l1BatchNumber).toEqual(l1BatchNumber - 1);
 
-        const nextBlock = await alice.provider.getBlock(to + 1);
+        const nextBlock = await extendedProvider.getBlock(to + 1);
         expect(nextBlock.l1BatchNumber).toEqual(l1BatchNumber + 1);
     });
 
@@ -563,6 +566,45 @@ describe('web3 API compatibility tests', () => {
         ).resolves.toHaveProperty('result', expect.stringMatching(HEX_VALUE_REGEX));
     });
 
+    test('Should return block extension fields only if requested', async () => {
+        const getBlock = (blockNumber: string, headers: Record<string, string>) => {
+            const req: RequestInit = {
+                method: 'POST',
+                headers: {
+                    'Content-Type': 'application/json',
+                    ...headers
+                },
+                body: JSON.stringify({
+                    jsonrpc: '2.0',
+                    method: 'eth_getBlockByNumber',
+                    params: [blockNumber, false],
+                    id: 1
+                })
+            };
+            return fetch(testMaster.environment().l2NodeUrl, req)
+                .then((response) => response.json())
+                .then((response) => response.result);
+        };
+        const extensionFields = ['l1BatchNumber', 'l1BatchTimestamp', 'sealCriterion', 'operatorAddress'];
+
+        const block = await getBlock('0x1', {});
+        for (const field of extensionFields) {
+            expect(block).not.toHaveProperty(field);
+        }
+
+        const extendedBlock = await getBlock('0x1', { 'x-zks-extensions': 'true' });
+        expect(extendedBlock.l1BatchNumber).toEqual(expect.stringMatching(HEX_VALUE_REGEX));
+        expect(extendedBlock.l1BatchTimestamp).toEqual(expect.stringMatching(HEX_VALUE_REGEX));
+        expect(extendedBlock.sealCriterion).toEqual(expect.any(String));
+        expect(extendedBlock.operatorAddress).toEqual(expect.stringMatching(HEX_VALUE_REGEX));
+
+        // Extension fields are present even if they are not known yet, e.g. for a block not included into a batch.
+        const latestBlock = await getBlock('latest', { 'x-zks-extensions': 'true' });
+        for (const field of extensionFields) {
+            expect(latestBlock).toHaveProperty(field);
+        }
+    });
+
     test('Should check API returns error when there are too many logs in eth_getLogs', async () => {
         const contract = await deployContract(alice, contracts.events, []);
         const maxLogsLimit = parseInt(process.env.API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT!);
@@ -0,0 +1,3 @@
+// calls a contract that reverts with an ABI-encoded Panic(uint) value
+>> {"jsonrpc":"2.0","id":1,"method":"eth_call","params":[{"from":"0x0000000000000000000000000000000000000000","input":"0x","to":"0x0000000000000000000000000000000000000aaa"},"latest"]}
+<< {"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted: assert(false)","data":"0x4e487b710000000000000000000000000000000000000000000000000000000000000001"}}
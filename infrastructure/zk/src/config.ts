@@ -9,6 +9,8 @@ const CONFIG_FILES = [
     'contract_verifier.toml',
     'contracts.toml',
     'database.toml',
+    'db_maintenance.toml',
+    'encrypted_mempool.toml',
     'eth_client.toml',
     'eth_sender.toml',
     'eth_watch.toml',
@@ -27,6 +27,13 @@ export async function migrate() {
     await utils.confirmAction();
     console.log('Running migrations...');
     await utils.spawn('cd core/lib/dal && cargo sqlx database create && cargo sqlx migrate run');
+    await createConcurrentIndices('core/lib/dal/concurrent_indices.sql');
+}
+
+// Indices on the large tables are built concurrently, which can't be done in the transaction of a migration.
+export async function createConcurrentIndices(path: string) {
+    console.log('Creating indices...');
+    await utils.spawn(`psql "${process.env.DATABASE_URL}" -v ON_ERROR_STOP=1 -q -f ${path}`);
 }
 
 export async function generateMigration(name: String) {
@@ -50,6 +57,7 @@ export async function setup() {
     }
     await utils.spawn('cargo sqlx database create');
     await utils.spawn('cargo sqlx migrate run');
+    await createConcurrentIndices('concurrent_indices.sql');
     if (process.env.DATABASE_URL == localDbUrl) {
         await utils.spawn('cargo sqlx prepare --check -- --tests || cargo sqlx prepare -- --tests');
     }
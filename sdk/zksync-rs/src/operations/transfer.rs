@@ -181,6 +181,7 @@ where
             .provider
             .estimate_fee(l2_tx.into())
             .await
+            .map(|estimate| estimate.fee)
             .map_err(Into::into)
     }
 }